@@ -1,28 +1,63 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
 fn main() {
     if std::env::var("CARGO_FEATURE_UI").is_ok() {
-        let ui_dir = std::path::Path::new("ui");
+        let ui_dir = Path::new("ui");
+
+        if bun_available() {
+            // bun install (only if node_modules is missing)
+            if !ui_dir.join("node_modules").exists() {
+                let status = Command::new("bun")
+                    .args(["install"])
+                    .current_dir(ui_dir)
+                    .status()
+                    .expect("failed to run bun install");
+                assert!(status.success(), "bun install failed");
+            }
 
-        // bun install (only if node_modules is missing)
-        if !ui_dir.join("node_modules").exists() {
-            let status = std::process::Command::new("bun")
-                .args(["install"])
+            // bun run build
+            let status = Command::new("bun")
+                .args(["run", "build"])
                 .current_dir(ui_dir)
                 .status()
-                .expect("failed to run bun install");
-            assert!(status.success(), "bun install failed");
+                .expect("failed to run bun run build");
+            assert!(status.success(), "bun run build failed");
+        } else {
+            println!("cargo:warning=bun not found, the UI is not rebuilt");
         }
 
-        // bun run build
-        let status = std::process::Command::new("bun")
-            .args(["run", "build"])
-            .current_dir(ui_dir)
-            .status()
-            .expect("failed to run bun run build");
-        assert!(status.success(), "bun run build failed");
-
         // Rerun if anything in ui/src/ changes
         println!("cargo:rerun-if-changed=ui/src");
         println!("cargo:rerun-if-changed=ui/vite.config.ts");
         println!("cargo:rerun-if-changed=ui/package.json");
+        println!("cargo:rerun-if-changed=ui/dist/client");
+
+        println!("cargo:rustc-env=GHQC_UI_DIST={}", ui_dist().display());
+    }
+}
+
+fn bun_available() -> bool {
+    Command::new("bun").arg("--version").output().is_ok()
+}
+
+/// Folder embedded as the UI: the built client, or a placeholder page when it was never built
+/// so the `ui` feature still compiles without bun
+fn ui_dist() -> PathBuf {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let client = manifest_dir.join("ui/dist/client");
+    if client.join("index.html").exists() {
+        return client;
     }
+
+    println!("cargo:warning=ui/dist/client is missing, embedding a placeholder page instead");
+    let placeholder = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("ui-placeholder");
+    std::fs::create_dir_all(&placeholder).expect("failed to create the UI placeholder");
+    std::fs::write(
+        placeholder.join("index.html"),
+        "<!doctype html>\n<html><body><p>The ghqc UI was not built. Install bun and rebuild with \
+         <code>--features ui</code>; the API is still served under <code>/api</code>.</p></body></html>\n",
+    )
+    .expect("failed to write the UI placeholder");
+    placeholder
 }
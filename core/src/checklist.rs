@@ -0,0 +1,427 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static CHECKLIST_LINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*-\s*\[([xX\s])\][ \t]*(.*)$").expect("Failed to compile checklist regex")
+});
+
+static LIST_ITEM_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*([-*+]|\d{1,9}[.)])(\s|$)").expect("Failed to compile list item regex")
+});
+
+/// Deepest indentation, in columns, of a counted checklist item: three levels of nesting. Anything
+/// indented further is more likely an example than an item to check off
+const MAX_ITEM_INDENT: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistSummary {
+    pub completed: usize,
+    pub total: usize,
+}
+
+impl ChecklistSummary {
+    pub fn new(completed: usize, total: usize) -> Self {
+        Self { completed, total }
+    }
+
+    pub fn completion_percentage(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            (self.completed as f64 / self.total as f64) * 100.0
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed == self.total && self.total > 0
+    }
+
+    pub fn sum<'a, I>(summaries: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let mut total_completed = 0;
+        let mut total_items = 0;
+
+        for summary in summaries {
+            total_completed += summary.completed;
+            total_items += summary.total;
+        }
+
+        Self::new(total_completed, total_items)
+    }
+}
+
+impl std::fmt::Display for ChecklistSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} ({:.1}%)",
+            self.completed,
+            self.total,
+            self.completion_percentage()
+        )
+    }
+}
+
+/// Analyze checklists within an issue's body
+/// Returns a vector of (checklist_name, summary) tuples, one per section with checklist items,
+/// named after the nearest preceding header
+pub fn analyze_issue_checklists(issue_body: Option<&str>) -> Vec<(String, ChecklistSummary)> {
+    let mut checklists: Vec<(usize, String, ChecklistSummary)> = Vec::new();
+
+    for (section, item) in scan_checklist_items(issue_body) {
+        let summary = match checklists.last_mut() {
+            Some((last, _, summary)) if *last == section => summary,
+            _ => {
+                checklists.push((section, item.checklist, ChecklistSummary::new(0, 0)));
+                &mut checklists.last_mut().expect("just pushed").2
+            }
+        };
+        summary.total += 1;
+        if item.checked {
+            summary.completed += 1;
+        }
+    }
+
+    checklists
+        .into_iter()
+        .map(|(_, name, summary)| (name, summary))
+        .collect()
+}
+
+/// Per-section completion as a single line, e.g. `Functionality 3/4, Testing 2/5`
+pub fn format_section_summaries(checklists: &[(String, ChecklistSummary)]) -> String {
+    checklists
+        .iter()
+        .map(|(name, summary)| format!("{name} {}/{}", summary.completed, summary.total))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Unchecked checklist items of an issue's body, as (checklist_name, item texts) tuples
+///
+/// Covers the same sections as [`analyze_issue_checklists`]; sections without unchecked items are
+/// left out.
+pub fn unchecked_checklist_items(issue_body: Option<&str>) -> Vec<(String, Vec<String>)> {
+    let mut unchecked: Vec<(usize, String, Vec<String>)> = Vec::new();
+
+    for (section, item) in scan_checklist_items(issue_body) {
+        if item.checked {
+            continue;
+        }
+        match unchecked.last_mut() {
+            Some((last, _, items)) if *last == section => items.push(item.text),
+            _ => unchecked.push((section, item.checklist, vec![item.text])),
+        }
+    }
+
+    unchecked
+        .into_iter()
+        .map(|(_, name, items)| (name, items))
+        .collect()
+}
+
+/// A single checklist item of an issue body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    /// 1-based position among all the items of the body
+    pub index: usize,
+    /// Header of the section holding the item
+    pub checklist: String,
+    pub text: String,
+    pub checked: bool,
+    /// 0-based line of the item within the body
+    pub line: usize,
+}
+
+/// Every checklist item of an issue's body, in order
+///
+/// Covers the same items as [`analyze_issue_checklists`], so they can be located and toggled
+/// safely with [`set_checklist_items`].
+pub fn checklist_items(issue_body: Option<&str>) -> Vec<ChecklistItem> {
+    scan_checklist_items(issue_body)
+        .into_iter()
+        .map(|(_, item)| item)
+        .collect()
+}
+
+/// The checklist items of the body with the index of the section holding them
+///
+/// Sections start at headers, from the first level 1 header on, so anything before it (like the
+/// Metadata section) is ignored. Checkboxes are only counted where markdown renders them as task
+/// items: not in fenced (```` ``` ```` or `~~~`) or indented code blocks, not in blockquotes, and not
+/// nested deeper than [`MAX_ITEM_INDENT`].
+fn scan_checklist_items(issue_body: Option<&str>) -> Vec<(usize, ChecklistItem)> {
+    let Some(body) = issue_body else {
+        return vec![];
+    };
+
+    let mut items = Vec::new();
+    let mut section = 0;
+    let mut current_header: Option<String> = None;
+    let mut fence: Option<&str> = None;
+    let mut in_list = false;
+    let mut in_indented_code = false;
+    let mut after_blank_line = true;
+
+    for (line_number, line) in body.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            after_blank_line = true;
+            continue;
+        }
+        let follows_blank_line = std::mem::replace(&mut after_blank_line, false);
+
+        // Outside of a list, a line indented by 4 columns after a blank line starts a code block
+        let indent = indentation(line);
+        if indent >= 4 && !in_list && (follows_blank_line || in_indented_code) {
+            in_indented_code = true;
+            continue;
+        }
+        in_indented_code = false;
+
+        if let Some(marker) = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker))
+        {
+            fence = Some(marker);
+            continue;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+
+        if let Some(header_text) = extract_header_text(line) {
+            in_list = false;
+            let is_level1_header = trimmed.starts_with("# ");
+            if current_header.is_some() || is_level1_header {
+                current_header = Some(header_text);
+                section += 1;
+            }
+            continue;
+        }
+
+        if LIST_ITEM_REGEX.is_match(line) {
+            in_list = true;
+        } else if indent < 2 && follows_blank_line {
+            // A paragraph after a blank line ends the list
+            in_list = false;
+        }
+
+        let Some(header) = current_header.as_ref() else {
+            continue;
+        };
+        if indent > MAX_ITEM_INDENT {
+            continue;
+        }
+        if let Some(capture) = CHECKLIST_LINE_REGEX.captures(line) {
+            let item = ChecklistItem {
+                index: items.len() + 1,
+                checklist: header.clone(),
+                text: capture[2].trim().to_string(),
+                checked: capture[1].trim().eq_ignore_ascii_case("x"),
+                line: line_number,
+            };
+            items.push((section, item));
+        }
+    }
+
+    items
+}
+
+/// Width of the leading whitespace of `line`, with tabs to the next multiple of 4 columns
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .fold(0, |width, c| match c {
+            '\t' => width + 4 - width % 4,
+            _ => width + 1,
+        })
+}
+
+/// Rewrite `body` with the checklist items on the given lines checked or unchecked
+///
+/// Only the character between the brackets of each targeted item changes; lines that are not
+/// checklist items are left untouched.
+pub fn set_checklist_items(body: &str, changes: &[(usize, bool)]) -> String {
+    let mut updated = String::with_capacity(body.len());
+
+    for (line_number, line) in body.split_inclusive('\n').enumerate() {
+        let change = changes.iter().find(|(line, _)| *line == line_number);
+        let marker = change.and_then(|_| {
+            CHECKLIST_LINE_REGEX
+                .captures(line.trim_end_matches(['\n', '\r']))
+                .and_then(|capture| capture.get(1))
+        });
+        match (change, marker) {
+            (Some((_, checked)), Some(marker)) => {
+                updated.push_str(&line[..marker.start()]);
+                updated.push(if *checked { 'x' } else { ' ' });
+                updated.push_str(&line[marker.end()..]);
+            }
+            _ => updated.push_str(line),
+        }
+    }
+
+    updated
+}
+
+/// Extract header text from a line if it's a markdown header (# to ######)
+/// Returns None if the line is not a valid header
+fn extract_header_text(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+
+    if !trimmed.starts_with('#') {
+        return None;
+    }
+
+    // Count the number of # symbols at the start
+    let hash_count = trimmed.chars().take_while(|&c| c == '#').count();
+
+    // Must be 1-6 # symbols followed by a space
+    if !(1..=6).contains(&hash_count) || trimmed.chars().nth(hash_count) != Some(' ') {
+        return None;
+    }
+
+    // Extract the text after the # symbols and space
+    let header_text = trimmed
+        .chars()
+        .skip(hash_count + 1)
+        .collect::<String>()
+        .trim()
+        .to_string();
+
+    if header_text.is_empty() {
+        None
+    } else {
+        Some(header_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_before_first_level1_header_are_ignored() {
+        let body = "## Metadata\n- [x] not a checklist\n# Code Review\n- [x] one\n- [ ] two\n## Output\n- [X] three\n## Notes\nnothing to check\n";
+
+        assert_eq!(
+            analyze_issue_checklists(Some(body)),
+            vec![
+                ("Code Review".to_string(), ChecklistSummary::new(1, 2)),
+                ("Output".to_string(), ChecklistSummary::new(1, 1)),
+            ]
+        );
+        assert!(analyze_issue_checklists(None).is_empty());
+    }
+
+    #[test]
+    fn test_unchecked_checklist_items() {
+        let body = "## Metadata\n- [ ] not a checklist\n# Code Review\n- [x] one\n- [ ] two\n- [ ]   three  \n## Output\n- [X] four\n";
+
+        assert_eq!(
+            unchecked_checklist_items(Some(body)),
+            vec![(
+                "Code Review".to_string(),
+                vec!["two".to_string(), "three".to_string()]
+            )]
+        );
+        assert!(unchecked_checklist_items(None).is_empty());
+    }
+
+    #[test]
+    fn test_checklist_items_skip_code_fences() {
+        let body = "## Metadata\n- [ ] not a checklist\n# Code Review\n- [x] one\n```md\n# Not a header\n- [ ] fenced\n```\n  - [ ] nested\n~~~\n- [x] also fenced\n~~~\n## Output\n- [ ] one\n";
+
+        let items = checklist_items(Some(body));
+
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| (
+                    item.index,
+                    item.checklist.as_str(),
+                    item.text.as_str(),
+                    item.checked,
+                    item.line
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "Code Review", "one", true, 3),
+                (2, "Code Review", "nested", false, 8),
+                (3, "Output", "one", false, 13),
+            ]
+        );
+        assert!(checklist_items(None).is_empty());
+    }
+
+    #[test]
+    fn test_checkboxes_outside_task_lists_are_ignored() {
+        let body = "# Code Review\n- [x] one\n\nAn example:\n\n    - [ ] indented code\n    - [x] still code\n\n\
+            > - [ ] quoted tip\n>\n> - [x] quoted too\n\n- [ ] two\n    - [x] nested under two\n\
+            \t- [ ] tab nested\n          - [ ] too deep\n";
+
+        assert_eq!(
+            analyze_issue_checklists(Some(body)),
+            vec![("Code Review".to_string(), ChecklistSummary::new(2, 4))]
+        );
+        assert_eq!(
+            unchecked_checklist_items(Some(body)),
+            vec![(
+                "Code Review".to_string(),
+                vec!["two".to_string(), "tab nested".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_sections_keyed_by_nearest_header() {
+        let body = "# Functionality\n- [x] a\n- [x] b\n- [x] c\n- [ ] d\n\
+            ## Testing\n```r\n# a comment, not a header\n- [ ] not an item\n```\n\
+            - [x] e\n- [x] f\n- [ ] g\n- [ ] h\n- [ ] i\n";
+
+        let checklists = analyze_issue_checklists(Some(body));
+        assert_eq!(
+            checklists,
+            vec![
+                ("Functionality".to_string(), ChecklistSummary::new(3, 4)),
+                ("Testing".to_string(), ChecklistSummary::new(2, 5)),
+            ]
+        );
+        assert_eq!(
+            format_section_summaries(&checklists),
+            "Functionality 3/4, Testing 2/5"
+        );
+    }
+
+    #[test]
+    fn test_set_checklist_items_only_flips_markers() {
+        let body = "# Code Review\r\n- [ ] one\r\n```\n- [ ] fenced\n```\n  - [X] nested *item*\nplain text\n- [ ] last";
+
+        let updated = set_checklist_items(body, &[(1, true), (5, false), (6, true), (7, true)]);
+
+        assert_eq!(
+            updated,
+            "# Code Review\r\n- [x] one\r\n```\n- [ ] fenced\n```\n  - [ ] nested *item*\nplain text\n- [x] last"
+        );
+    }
+
+    #[test]
+    fn test_checklist_summary() {
+        let total =
+            ChecklistSummary::sum(&[ChecklistSummary::new(1, 2), ChecklistSummary::new(2, 2)]);
+        assert_eq!(total, ChecklistSummary::new(3, 4));
+        assert!(!total.is_complete());
+        assert_eq!(total.to_string(), "3/4 (75.0%)");
+        assert_eq!(ChecklistSummary::new(0, 0).completion_percentage(), 100.0);
+    }
+}
@@ -0,0 +1,22 @@
+//! The pure pieces of ghqctoolkit: checklist analysis, parsing of the markers QC comments leave
+//! behind and the QC status state machine.
+//!
+//! Nothing here touches git, GitHub or an async runtime, so the crate also builds for
+//! `wasm32-unknown-unknown`. With the `wasm` feature, [`wasm`] exposes the parsers to the UI.
+
+mod checklist;
+mod markers;
+mod status;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use checklist::{
+    ChecklistItem, ChecklistSummary, analyze_issue_checklists, checklist_items,
+    format_section_summaries, set_checklist_items, unchecked_checklist_items,
+};
+pub use markers::{
+    CommitStatus, Obsoletion, parse_branch_from_body, parse_commit_from_pattern,
+    parse_inline_checklist, parse_markers, parse_obsoletion,
+};
+pub use status::{StatusCommit, ThreadStatus, determine_status};
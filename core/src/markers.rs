@@ -0,0 +1,329 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::LazyLock,
+};
+
+use regex::Regex;
+use serde::Serialize;
+
+static MARKDOWN_LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\([^)]+\)").unwrap());
+
+static HTML_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<a\s+[^>]*href\s*=\s*["']([^"']+)["'][^>]*>([^<]*)</a>"#).unwrap()
+});
+
+/// The role of a commit in a QC thread, as recorded by the QC comments
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitStatus {
+    Initial,
+    Notification,
+    Approved,
+    Reviewed,
+    /// The file was at this commit when an approval was withdrawn
+    Unapproved,
+}
+
+impl CommitStatus {
+    /// Whether the status records QC activity on the commit. An unapproval only records where
+    /// the file was when the approval was withdrawn, so it does not count.
+    pub fn is_activity(&self) -> bool {
+        !matches!(self, Self::Unapproved)
+    }
+}
+
+impl fmt::Display for CommitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let self_str = match self {
+            Self::Initial => "initial",
+            Self::Notification => "notification",
+            Self::Approved => "approved",
+            Self::Reviewed => "reviewed",
+            Self::Unapproved => "unapproved",
+        };
+        write!(f, "{self_str}")
+    }
+}
+
+/// Parse notification and approval commits from comment bodies, oldest first
+/// Returns a HashMap of commit strings to their accumulated status sets
+/// Uses accumulative approach - commits can hold multiple statuses simultaneously
+pub fn parse_markers<'a>(
+    bodies: impl IntoIterator<Item = &'a str>,
+) -> HashMap<&'a str, HashSet<CommitStatus>> {
+    let mut commit_statuses = HashMap::new();
+    let mut approved_commit = None;
+    let mut approval_comment_index = None;
+
+    // Parse all comments in order
+    for (index, body) in bodies.into_iter().enumerate() {
+        // Check for notification commit: "current commit: {hash}"
+        if let Some(commit) = parse_commit_from_pattern(body, "current commit: ") {
+            // Add notification status (accumulative approach)
+            let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+            statuses.insert(CommitStatus::Notification);
+        }
+
+        // Check for approval commit: "approved qc commit: {hash}"
+        if let Some(commit) = parse_commit_from_pattern(body, "approved qc commit: ") {
+            // Remove Approved status from all other commits (only one approval allowed)
+            for statuses in commit_statuses.values_mut() {
+                statuses.remove(&CommitStatus::Approved);
+            }
+
+            // Add approved status to this commit
+            let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+            statuses.insert(CommitStatus::Approved);
+            approved_commit = Some(commit);
+            approval_comment_index = Some(index);
+        }
+
+        // Check for review commit: "comparing commit: {hash}" in "# QC Review" comments
+        let reviewed_commit = Some(body)
+            .filter(|body| body.contains("# QC Review"))
+            .and_then(|body| parse_commit_from_pattern(body, "comparing commit: "));
+        if let Some(commit) = reviewed_commit {
+            // Add reviewed status (accumulative approach)
+            let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+            statuses.insert(CommitStatus::Reviewed);
+        }
+
+        // Check for unapproval: "# QC Un-Approval"
+        // Unapprovals record the commit the file was at since "unapproved at commit: {hash}";
+        // older ones don't
+        let is_unapproval = body.contains("# QC Un-Approval");
+        let unapproved_commit = Some(body)
+            .filter(|_| is_unapproval)
+            .and_then(|body| parse_commit_from_pattern(body, "unapproved at commit: "));
+        if let Some(commit) = unapproved_commit {
+            let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+            statuses.insert(CommitStatus::Unapproved);
+        }
+
+        // If this unapproval comes after an approval, remove the approval status
+        let withdraws_approval = is_unapproval
+            && approval_comment_index.is_some_and(|approval_index| index > approval_index);
+        if withdraws_approval {
+            if let Some(statuses) = approved_commit.and_then(|c| commit_statuses.get_mut(c)) {
+                statuses.remove(&CommitStatus::Approved);
+            }
+            approved_commit = None;
+            approval_comment_index = None;
+        }
+    }
+
+    commit_statuses
+}
+
+/// Why and when a QC thread was taken out of scope, as recorded by its latest
+/// "# QC Obsoleted" comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Obsoletion {
+    pub reason: String,
+    /// Login of whoever obsoleted the issue
+    pub actor: Option<String>,
+    /// Date the issue was obsoleted, as written in the comment (`YYYY-MM-DD`)
+    pub date: Option<String>,
+}
+
+/// Parse the standing obsoletion from comment bodies, oldest first
+///
+/// A "# QC Un-Obsoleted" comment clears any earlier obsoletion.
+pub fn parse_obsoletion<'a>(bodies: impl IntoIterator<Item = &'a str>) -> Option<Obsoletion> {
+    let mut obsoletion = None;
+
+    for body in bodies {
+        let header = body.lines().map(str::trim).find(|line| !line.is_empty());
+        match header {
+            Some("# QC Obsoleted") => {
+                obsoletion = Some(Obsoletion {
+                    reason: parse_metadata_line(body, "obsolete reason: ").unwrap_or_default(),
+                    actor: parse_metadata_line(body, "obsoleted by: "),
+                    date: parse_metadata_line(body, "obsoleted on: "),
+                });
+            }
+            Some("# QC Un-Obsoleted") => obsoletion = None,
+            _ => {}
+        }
+    }
+
+    obsoletion
+}
+
+/// Parse the file name of the one-off checklist an issue was created with from its body
+///
+/// Issues created with a configured checklist have no `inline checklist` metadata.
+pub fn parse_inline_checklist(body: &str) -> Option<String> {
+    parse_metadata_line(body, "inline checklist: ")
+}
+
+/// Parse the rest of the line following `pattern`, `None` when missing or blank
+fn parse_metadata_line(body: &str, pattern: &str) -> Option<String> {
+    let start = body.find(pattern)? + pattern.len();
+    let value = body[start..].lines().next()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Parse a commit from a body using the given pattern
+/// Supports both full and short SHAs with minimum 7 character length
+pub fn parse_commit_from_pattern<'a>(body: &'a str, pattern: &str) -> Option<&'a str> {
+    let start = body.find(pattern)?;
+    let commit_start = start + pattern.len();
+
+    let remaining = &body[commit_start..];
+    remaining.lines().next()?.split_whitespace().next()
+}
+
+/// Parse branch name from issue body
+/// Only looks for the "git branch: <branch-name>" pattern
+/// Branch name can be plain text, markdown link text, or HTML link text
+pub fn parse_branch_from_body(body: &str) -> Option<String> {
+    let pattern = "git branch: ";
+    let start = body.find(pattern)?;
+    let branch_start = start + pattern.len();
+    let remaining = &body[branch_start..];
+    let line = remaining.lines().next()?;
+
+    // The branch name is the text of a markdown link [name](url), of an HTML link
+    // <a href="url">text</a>, or the plain text
+    let link_texts = [
+        MARKDOWN_LINK_REGEX.captures(line).and_then(|c| c.get(1)),
+        HTML_LINK_REGEX.captures(line).and_then(|c| c.get(2)),
+    ];
+    link_texts
+        .into_iter()
+        .flatten()
+        .map(|link_text| link_text.as_str().trim())
+        .chain(std::iter::once(line.trim()))
+        .find(|branch_name| !branch_name.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markers_accumulates_statuses() {
+        let bodies = [
+            "# QC Notification\ncurrent commit: abc1234\nprevious commit: 0000000",
+            "# QC Review\ncomparing commit: abc1234",
+            "# QC Approval\napproved qc commit: def5678",
+        ];
+
+        let markers = parse_markers(bodies);
+
+        assert_eq!(
+            markers["abc1234"],
+            HashSet::from([CommitStatus::Notification, CommitStatus::Reviewed])
+        );
+        assert_eq!(markers["def5678"], HashSet::from([CommitStatus::Approved]));
+    }
+
+    #[test]
+    fn test_parse_markers_unapproval_withdraws_approval() {
+        let bodies = [
+            "# QC Approval\napproved qc commit: abc1234",
+            "# QC Un-Approval\n\nWrong commit\n\n## Metadata\n* issue: #1",
+        ];
+
+        let markers = parse_markers(bodies);
+
+        assert!(markers["abc1234"].is_empty());
+    }
+
+    #[test]
+    fn test_parse_markers_unapproval_records_commit() {
+        let bodies = [
+            "# QC Approval
+approved qc commit: abc1234",
+            "# QC Un-Approval
+
+Wrong commit
+
+## Metadata
+* issue: #1
+* unapproval reason: Wrong commit
+* unapproved at commit: def5678
+* previously approved commit: abc1234",
+        ];
+
+        let markers = parse_markers(bodies);
+
+        assert!(markers["abc1234"].is_empty());
+        assert_eq!(
+            markers["def5678"],
+            HashSet::from([CommitStatus::Unapproved])
+        );
+        // Only un-approval comments record the commit
+        assert!(parse_markers(["unapproved at commit: def5678"]).is_empty());
+    }
+
+    #[test]
+    fn test_review_marker_requires_review_header() {
+        let markers = parse_markers(["comparing commit: abc1234"]);
+        assert!(markers.is_empty());
+    }
+
+    #[test]
+    fn test_parse_obsoletion() {
+        let obsoleted = "# QC Obsoleted\n\nDropped from the analysis plan\n\n## Metadata\n* issue: #3\n* obsolete reason: Dropped from the analysis plan\n* obsoleted by: reviewer\n* obsoleted on: 2026-03-01";
+
+        assert_eq!(
+            parse_obsoletion(["# QC Notification\ncurrent commit: abc1234", obsoleted]),
+            Some(Obsoletion {
+                reason: "Dropped from the analysis plan".to_string(),
+                actor: Some("reviewer".to_string()),
+                date: Some("2026-03-01".to_string()),
+            })
+        );
+        assert_eq!(parse_obsoletion(["# QC Notification"]), None);
+    }
+
+    #[test]
+    fn test_parse_obsoletion_un_obsoleted() {
+        let obsoleted = "# QC Obsoleted\n\n## Metadata\n* obsolete reason: Out of scope";
+        let restored = "# QC Un-Obsoleted\n\nBack in scope\n\n## Metadata\n* issue: #3";
+
+        assert_eq!(parse_obsoletion([obsoleted, restored]), None);
+        assert_eq!(
+            parse_obsoletion([obsoleted, restored, obsoleted]).map(|o| o.reason),
+            Some("Out of scope".to_string())
+        );
+        // Quoting the header in another comment does not obsolete the issue
+        assert_eq!(parse_obsoletion(["See the # QC Obsoleted docs"]), None);
+    }
+
+    #[test]
+    fn test_parse_inline_checklist() {
+        let body = "## Metadata\n* initial qc commit: abc1234\n* inline checklist: bespoke_model_review.md\n* author: analyst";
+
+        assert_eq!(
+            parse_inline_checklist(body),
+            Some("bespoke_model_review.md".to_string())
+        );
+        assert_eq!(
+            parse_inline_checklist("## Metadata\n* initial qc commit: abc1234"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_branch_from_body_link_forms() {
+        assert_eq!(
+            parse_branch_from_body("git branch: [main](https://github.com/o/r/tree/main)"),
+            Some("main".to_string())
+        );
+        assert_eq!(
+            parse_branch_from_body("git branch: <a href=\"https://x/tree/dev\">dev</a>\n"),
+            Some("dev".to_string())
+        );
+        assert_eq!(
+            parse_branch_from_body("git branch: feature/x\nauthor: me"),
+            Some("feature/x".to_string())
+        );
+        assert_eq!(parse_branch_from_body("git branch: \n"), None);
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use crate::CommitStatus;
+
+/// A commit of a QC thread, as seen by [`determine_status`]
+pub trait StatusCommit {
+    /// How the commit is identified, e.g. a git object id or a hash string
+    type Hash: Clone;
+
+    fn hash(&self) -> &Self::Hash;
+    /// The commit changed the file under QC
+    fn file_changed(&self) -> bool;
+    /// The QC comments referencing the commit
+    fn statuses(&self) -> &HashSet<CommitStatus>;
+}
+
+/// Where a QC thread stands, carrying the commit hashes of type `H`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreadStatus<H> {
+    Approved,
+    ChangesAfterApproval(H),
+    // closed without approval
+    ApprovalRequired,
+    // latest comment = latest commit
+    AwaitingReview,
+    // latest comment = latest commit, but reviewed
+    ChangeRequested,
+    InProgress,
+    ChangesToComment(H),
+}
+
+/// Determine the status of a QC thread from its commits, newest first
+pub fn determine_status<C: StatusCommit>(commits: &[C], open: bool) -> ThreadStatus<C::Hash> {
+    let approved_index = commits
+        .iter()
+        .position(|commit| commit.statuses().contains(&CommitStatus::Approved));
+
+    if let Some(approved_index) = approved_index {
+        // Check if there are any commits after the approved commit that touch the file
+        // (commits are ordered chronologically, newer commits have lower indices)
+        let commits_after_approval = &commits[..approved_index];
+        let file_changes_after_approval = commits_after_approval
+            .iter()
+            .find(|commit| commit.file_changed());
+
+        return match file_changes_after_approval {
+            Some(latest_file_change) => {
+                ThreadStatus::ChangesAfterApproval(latest_file_change.hash().clone())
+            }
+            None => ThreadStatus::Approved,
+        };
+    }
+
+    // if not approved and closed
+    if !open {
+        return ThreadStatus::ApprovalRequired;
+    }
+
+    // Find the newest commit that carries any QC activity.
+    let latest_status_entry = commits
+        .iter()
+        .enumerate()
+        .find(|(_, c)| c.statuses().iter().any(CommitStatus::is_activity));
+    let status_commit_status = |status_commit: &C| {
+        if status_commit.statuses().contains(&CommitStatus::Reviewed) {
+            ThreadStatus::ChangeRequested
+        } else {
+            ThreadStatus::AwaitingReview
+        }
+    };
+
+    // Find the newest (lowest index) file-changing commit.
+    // Commits are stored newest-first, so lower index = more recent.
+    match commits.iter().enumerate().find(|(_, c)| c.file_changed()) {
+        Some((file_idx, latest_fc)) => match latest_status_entry {
+            // A status commit "covers" the file change if it is at the same
+            // position or newer (index ≤ file_idx).
+            Some((status_idx, status_commit)) if status_idx <= file_idx => {
+                status_commit_status(status_commit)
+            }
+            _ => ThreadStatus::ChangesToComment(latest_fc.hash().clone()),
+        },
+        // No file-changing commit found, but the issue has been posted
+        // (an Initial/Notification commit exists). Treat like a covered
+        // status commit: awaiting review unless already reviewed.
+        None => match latest_status_entry {
+            Some((_, status_commit)) => status_commit_status(status_commit),
+            None => ThreadStatus::InProgress,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Commit {
+        hash: &'static str,
+        file_changed: bool,
+        statuses: HashSet<CommitStatus>,
+    }
+
+    impl StatusCommit for Commit {
+        type Hash = &'static str;
+
+        fn hash(&self) -> &Self::Hash {
+            &self.hash
+        }
+
+        fn file_changed(&self) -> bool {
+            self.file_changed
+        }
+
+        fn statuses(&self) -> &HashSet<CommitStatus> {
+            &self.statuses
+        }
+    }
+
+    fn commit(hash: &'static str, file_changed: bool, statuses: &[CommitStatus]) -> Commit {
+        Commit {
+            hash,
+            file_changed,
+            statuses: statuses.iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn test_approved_thread() {
+        let commits = vec![
+            commit("c3", false, &[]),
+            commit("c2", true, &[CommitStatus::Approved]),
+            commit("c1", true, &[CommitStatus::Initial]),
+        ];
+        assert_eq!(determine_status(&commits, false), ThreadStatus::Approved);
+    }
+
+    #[test]
+    fn test_changes_after_approval_points_at_newest_change() {
+        let commits = vec![
+            commit("c4", true, &[]),
+            commit("c3", true, &[]),
+            commit("c2", true, &[CommitStatus::Approved]),
+            commit("c1", true, &[CommitStatus::Initial]),
+        ];
+        assert_eq!(
+            determine_status(&commits, true),
+            ThreadStatus::ChangesAfterApproval("c4")
+        );
+    }
+
+    #[test]
+    fn test_unapproved_threads() {
+        let posted = vec![commit("c1", true, &[CommitStatus::Initial])];
+        assert_eq!(
+            determine_status(&posted, false),
+            ThreadStatus::ApprovalRequired
+        );
+        assert_eq!(
+            determine_status(&posted, true),
+            ThreadStatus::AwaitingReview
+        );
+
+        let reviewed = vec![commit(
+            "c1",
+            true,
+            &[CommitStatus::Initial, CommitStatus::Reviewed],
+        )];
+        assert_eq!(
+            determine_status(&reviewed, true),
+            ThreadStatus::ChangeRequested
+        );
+
+        let changed = vec![
+            commit("c2", true, &[]),
+            commit("c1", true, &[CommitStatus::Initial]),
+        ];
+        assert_eq!(
+            determine_status(&changed, true),
+            ThreadStatus::ChangesToComment("c2")
+        );
+
+        let unposted = vec![commit("c1", false, &[])];
+        assert_eq!(determine_status(&unposted, true), ThreadStatus::InProgress);
+
+        // The commit of an unapproval is not QC activity covering the change
+        let unapproved = vec![
+            commit("c2", true, &[CommitStatus::Unapproved]),
+            commit("c1", true, &[CommitStatus::Initial]),
+        ];
+        assert_eq!(
+            determine_status(&unapproved, true),
+            ThreadStatus::ChangesToComment("c2")
+        );
+    }
+}
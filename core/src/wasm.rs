@@ -0,0 +1,58 @@
+//! wasm-bindgen interface for the UI, so checklist and status previews don't round-trip to the server
+//!
+//! Build with `cargo build-wasm`, then run `wasm-bindgen` on the output for the JS glue.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::CommitStatus;
+
+/// A checklist section of an issue body, as returned to JS
+#[derive(Serialize)]
+struct ChecklistSection<'a> {
+    name: &'a str,
+    completed: usize,
+    total: usize,
+}
+
+/// The part of a GitHub issue comment the markers are parsed from
+#[derive(Deserialize)]
+struct MarkerComment {
+    body: String,
+}
+
+fn to_js(value: &impl Serialize) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+}
+
+/// Checklist sections of an issue body: `[{ name, completed, total }]`
+#[wasm_bindgen]
+pub fn analyze_checklist(body: &str) -> JsValue {
+    let checklists = crate::analyze_issue_checklists(Some(body));
+    let sections = checklists
+        .iter()
+        .map(|(name, summary)| ChecklistSection {
+            name,
+            completed: summary.completed,
+            total: summary.total,
+        })
+        .collect::<Vec<_>>();
+
+    to_js(&sections).expect_throw("checklist sections serialize to JS")
+}
+
+/// Commit markers of an issue's comments, oldest first: `{ [commit]: ["notification", ...] }`
+///
+/// `comments_json` is a JSON array of comments with a `body`, such as the GitHub API returns.
+#[wasm_bindgen]
+pub fn parse_markers(comments_json: &str) -> Result<JsValue, JsError> {
+    let comments: Vec<MarkerComment> = serde_json::from_str(comments_json)?;
+    let markers = crate::parse_markers(comments.iter().map(|c| c.body.as_str()))
+        .into_iter()
+        .map(|(commit, statuses)| (commit, statuses.into_iter().collect::<BTreeSet<_>>()))
+        .collect::<BTreeMap<&str, BTreeSet<CommitStatus>>>();
+
+    Ok(to_js(&markers)?)
+}
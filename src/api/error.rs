@@ -16,15 +16,21 @@ pub enum ApiError {
     /// Validation error (400)
     #[error("Validation Error: {0}")]
     BadRequest(String),
+    /// Validation error with a machine readable code, e.g. `unknown_checklist` (400)
+    #[error("Validation Error: {message}")]
+    Invalid { code: &'static str, message: String },
     /// Conflict error, e.g., blocking QCs not approved (409)
     #[error("Request caused conflict: {0}")]
     Conflict(String),
     /// Conflict with structured data (409) - avoids double JSON encoding
     #[error("Request caused conflict")]
     ConflictDetails(serde_json::Value),
-    /// GitHub API error (502)
+    /// GitHub API error (502), or an exhausted GitHub rate limit (429)
     #[error("GitHub API Error: {0}")]
-    GitHubApi(String),
+    GitHubApi(crate::GitHubApiError),
+    /// The GitHub token cannot write (403)
+    #[error("Read-only token: {0}")]
+    ReadOnlyToken(String),
     /// Not implemented (501)
     #[error("Not implemented: {0}")]
     NotImplemented(String),
@@ -36,6 +42,9 @@ pub enum ApiError {
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
+    /// Machine readable error code, for errors the UI handles specially
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'static str>,
 }
 
 impl IntoResponse for ApiError {
@@ -45,17 +54,35 @@ impl IntoResponse for ApiError {
             ApiError::ConflictDetails(value) => (StatusCode::CONFLICT, Json(value)).into_response(),
             // All other errors wrap message in ErrorResponse
             _ => {
+                let code = match &self {
+                    ApiError::ReadOnlyToken(_) => Some("read_only_token"),
+                    ApiError::GitHubApi(err) if err.is_rate_limited() => Some("rate_limited"),
+                    ApiError::Invalid { code, .. } => Some(*code),
+                    _ => None,
+                };
                 let (status, message) = match self {
                     ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
                     ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+                    ApiError::Invalid { message, .. } => (StatusCode::BAD_REQUEST, message),
                     ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
-                    ApiError::GitHubApi(msg) => (StatusCode::BAD_GATEWAY, msg),
+                    ApiError::GitHubApi(err) if err.is_rate_limited() => {
+                        (StatusCode::TOO_MANY_REQUESTS, err.to_string())
+                    }
+                    ApiError::GitHubApi(err) => (StatusCode::BAD_GATEWAY, err.to_string()),
+                    ApiError::ReadOnlyToken(msg) => (StatusCode::FORBIDDEN, msg),
                     ApiError::NotImplemented(msg) => (StatusCode::NOT_IMPLEMENTED, msg),
                     ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
                     ApiError::ConflictDetails(_) => unreachable!(),
                 };
 
-                (status, Json(ErrorResponse { error: message })).into_response()
+                (
+                    status,
+                    Json(ErrorResponse {
+                        error: message,
+                        code,
+                    }),
+                )
+                    .into_response()
             }
         }
     }
@@ -63,7 +90,11 @@ impl IntoResponse for ApiError {
 
 impl From<crate::GitHubApiError> for ApiError {
     fn from(err: crate::GitHubApiError) -> Self {
-        ApiError::GitHubApi(err.to_string())
+        match err {
+            crate::GitHubApiError::ReadOnlyToken(_) => ApiError::ReadOnlyToken(err.to_string()),
+            crate::GitHubApiError::RemoteMismatch(_) => ApiError::Conflict(err.to_string()),
+            _ => ApiError::GitHubApi(err),
+        }
     }
 }
 
@@ -81,16 +112,18 @@ impl From<crate::QCStatusError> for ApiError {
 
 impl From<crate::IssueError> for ApiError {
     fn from(err: crate::IssueError) -> Self {
-        ApiError::Internal(err.to_string())
+        match err {
+            crate::IssueError::GitHubApiError(err) => ApiError::from(err),
+            _ => ApiError::Internal(err.to_string()),
+        }
     }
 }
 
 impl From<crate::ApprovalError> for ApiError {
     fn from(err: crate::ApprovalError) -> Self {
         match &err {
-            crate::ApprovalError::BlockingQCsNotApproved { .. } => {
-                ApiError::Conflict(err.to_string())
-            }
+            crate::ApprovalError::BlockingQCsNotApproved { .. }
+            | crate::ApprovalError::UnsignedApproval { .. } => ApiError::Conflict(err.to_string()),
             _ => ApiError::Internal(err.to_string()),
         }
     }
@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 use octocrab::models::issues::Issue;
 
 use crate::{
-    GitHubReader, GitProvider, IssueError, IssueThread,
+    CommitIndex, GitHubReader, GitProvider, IssueError, IssueThread,
     api::{AppState, types::IssueStatusResponse},
     get_issue_comments, parse_blocking_qcs,
 };
@@ -81,13 +81,15 @@ impl CreatedThreads {
                 .collect::<Vec<_>>();
         let comment_results = futures::future::join_all(comment_futures).await;
 
-        // Step 2: Build IssueThreads, sharing the disk cache for commit lookups.
+        // Step 2: Build IssueThreads, sharing the disk cache and the branch histories for
+        // commit lookups.
+        let index = CommitIndex::new();
         let mut thread_results: Vec<(&Issue, Result<IssueThread, IssueError>)> = Vec::new();
         for (issue, comments_result) in comment_results {
             let result = match comments_result {
-                Ok(comments) => {
-                    IssueThread::from_issue_comments(issue, &comments, git_info, disk_cache)
-                }
+                Ok(comments) => IssueThread::from_issue_comments_with_index(
+                    issue, &comments, git_info, disk_cache, &index,
+                ),
                 Err(e) => Err(IssueError::GitHubApiError(e)),
             };
             thread_results.push((issue, result));
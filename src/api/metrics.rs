@@ -0,0 +1,648 @@
+//! Request, GitHub API and cache metrics, rendered in the Prometheus text format.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use gix::ObjectId;
+use octocrab::models::{Milestone, issues::Issue};
+
+use crate::comment_system::CommentBody;
+use crate::{
+    DiskCache, FileLastCommit, FileReader, FileRenameEvent, FileStashOutcome, GitAuthor,
+    GitComment, GitCommit, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError,
+    GitHubReader, GitHubWriter, GitRepository, GitRepositoryError, GitState, GitStatusError,
+    GitStatusOps, MilestoneUpdate, PathAttributes, Permission, QCIssue, RepoUser, SignatureStatus,
+    SigningKeys,
+};
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations per bucket, not cumulative. Slower observations only count towards `+Inf`
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+        }
+        let count = self.count();
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {count}");
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {sum}");
+        let _ = writeln!(out, "{name}_count{{{labels}}} {count}");
+    }
+}
+
+#[derive(Debug, Default)]
+struct GitHubCallStats {
+    latency: Histogram,
+    errors: AtomicU64,
+}
+
+/// Counters of the API server, shared between the router and the [`MeteredGit`] provider.
+///
+/// Recording an observation only takes a read lock to find its series and updates atomics.
+#[derive(Debug, Default)]
+pub struct ApiMetrics {
+    /// Request latency keyed by method and matched route
+    requests: RwLock<BTreeMap<(String, String), Arc<Histogram>>>,
+    /// GitHub API call latency and errors keyed by provider method
+    github: RwLock<BTreeMap<&'static str, Arc<GitHubCallStats>>>,
+}
+
+impl ApiMetrics {
+    pub fn record_request(&self, method: &str, route: &str, elapsed: Duration) {
+        let key = (method.to_string(), route.to_string());
+        series(&self.requests, key).observe(elapsed);
+    }
+
+    pub fn record_github_call(&self, operation: &'static str, elapsed: Duration, ok: bool) {
+        let stats = series(&self.github, operation);
+        stats.latency.observe(elapsed);
+        if !ok {
+            stats.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of requests answered for `route`, as registered on the router
+    pub fn request_count(&self, method: &str, route: &str) -> u64 {
+        let requests = self.requests.read().unwrap_or_else(|e| e.into_inner());
+        requests
+            .get(&(method.to_string(), route.to_string()))
+            .map_or(0, |h| h.count())
+    }
+
+    /// Number of GitHub API calls made, across all operations
+    pub fn github_call_count(&self) -> u64 {
+        let github = self.github.read().unwrap_or_else(|e| e.into_inner());
+        github.values().map(|stats| stats.latency.count()).sum()
+    }
+
+    /// Render every metric in the Prometheus text exposition format
+    pub fn render(&self, disk_cache: Option<&DiskCache>) -> String {
+        let mut out = String::new();
+
+        let name = "ghqc_http_request_duration_seconds";
+        let _ = writeln!(out, "# HELP {name} Time to answer API requests, by route");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        let requests = self.requests.read().unwrap_or_else(|e| e.into_inner());
+        for ((method, route), histogram) in requests.iter() {
+            histogram.render(
+                &mut out,
+                name,
+                &format!("method=\"{method}\",route=\"{route}\""),
+            );
+        }
+        drop(requests);
+
+        let github = self.github.read().unwrap_or_else(|e| e.into_inner());
+        let name = "ghqc_github_request_duration_seconds";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Time spent in GitHub API calls, by operation"
+        );
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (operation, stats) in github.iter() {
+            stats
+                .latency
+                .render(&mut out, name, &format!("operation=\"{operation}\""));
+        }
+        let name = "ghqc_github_request_errors_total";
+        let _ = writeln!(out, "# HELP {name} Failed GitHub API calls, by operation");
+        let _ = writeln!(out, "# TYPE {name} counter");
+        for (operation, stats) in github.iter() {
+            let errors = stats.errors.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{name}{{operation=\"{operation}\"}} {errors}");
+        }
+        drop(github);
+
+        let (hits, misses) = disk_cache.map_or((0, 0), |cache| (cache.hits(), cache.misses()));
+        for (name, help, value) in [
+            (
+                "ghqc_disk_cache_hits_total",
+                "Reads answered by the disk cache",
+                hits,
+            ),
+            (
+                "ghqc_disk_cache_misses_total",
+                "Reads that found no valid disk cache entry",
+                misses,
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {value}");
+        }
+
+        out
+    }
+}
+
+/// Get the series stored under `key`, creating it on first use
+fn series<K: Ord, V: Default>(map: &RwLock<BTreeMap<K, Arc<V>>>, key: K) -> Arc<V> {
+    if let Some(series) = map.read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return series.clone();
+    }
+    map.write()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(key)
+        .or_default()
+        .clone()
+}
+
+/// Git provider that records the count and latency of every GitHub API call into
+/// [`ApiMetrics`]. Local git operations are passed through untouched.
+#[derive(Debug, Clone)]
+pub struct MeteredGit<G> {
+    inner: G,
+    metrics: Arc<ApiMetrics>,
+}
+
+impl<G> MeteredGit<G> {
+    pub fn new(inner: G, metrics: Arc<ApiMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    async fn timed<T>(
+        &self,
+        operation: &'static str,
+        call: impl Future<Output = Result<T, GitHubApiError>>,
+    ) -> Result<T, GitHubApiError> {
+        let start = Instant::now();
+        let result = call.await;
+        self.metrics
+            .record_github_call(operation, start.elapsed(), result.is_ok());
+        result
+    }
+}
+
+impl<G: GitHubReader + Sync> GitHubReader for MeteredGit<G> {
+    async fn get_milestones(&self) -> Result<Vec<Milestone>, GitHubApiError> {
+        self.timed("get_milestones", self.inner.get_milestones())
+            .await
+    }
+
+    async fn get_issues(&self, milestone: Option<u64>) -> Result<Vec<Issue>, GitHubApiError> {
+        self.timed("get_issues", self.inner.get_issues(milestone))
+            .await
+    }
+
+    async fn get_issue(&self, issue_number: u64) -> Result<Issue, GitHubApiError> {
+        self.timed("get_issue", self.inner.get_issue(issue_number))
+            .await
+    }
+
+    async fn get_assignees(&self) -> Result<Vec<String>, GitHubApiError> {
+        self.timed("get_assignees", self.inner.get_assignees())
+            .await
+    }
+
+    async fn get_user_details(&self, username: &str) -> Result<RepoUser, GitHubApiError> {
+        self.timed("get_user_details", self.inner.get_user_details(username))
+            .await
+    }
+
+    async fn get_user_permission(&self, login: &str) -> Result<Permission, GitHubApiError> {
+        self.timed("get_user_permission", self.inner.get_user_permission(login))
+            .await
+    }
+
+    async fn get_team_members(
+        &self,
+        org: &str,
+        team_slug: &str,
+    ) -> Result<Vec<String>, GitHubApiError> {
+        self.timed(
+            "get_team_members",
+            self.inner.get_team_members(org, team_slug),
+        )
+        .await
+    }
+
+    async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
+        self.timed("get_labels", self.inner.get_labels()).await
+    }
+
+    async fn get_issue_comments(&self, issue: &Issue) -> Result<Vec<GitComment>, GitHubApiError> {
+        self.timed("get_issue_comments", self.inner.get_issue_comments(issue))
+            .await
+    }
+
+    async fn get_issue_events(
+        &self,
+        issue: &Issue,
+    ) -> Result<Vec<serde_json::Value>, GitHubApiError> {
+        self.timed("get_issue_events", self.inner.get_issue_events(issue))
+            .await
+    }
+
+    async fn get_blocked_issues(&self, issue_number: u64) -> Result<Vec<Issue>, GitHubApiError> {
+        self.timed(
+            "get_blocked_issues",
+            self.inner.get_blocked_issues(issue_number),
+        )
+        .await
+    }
+
+    async fn get_current_user(&self) -> Result<Option<String>, GitHubApiError> {
+        self.timed("get_current_user", self.inner.get_current_user())
+            .await
+    }
+}
+
+impl<G: GitHubWriter + Sync> GitHubWriter for MeteredGit<G> {
+    async fn create_milestone(
+        &self,
+        milestone_name: &str,
+        description: &Option<String>,
+    ) -> Result<Milestone, GitHubApiError> {
+        self.timed(
+            "create_milestone",
+            self.inner.create_milestone(milestone_name, description),
+        )
+        .await
+    }
+
+    async fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> Result<Milestone, GitHubApiError> {
+        self.timed(
+            "update_milestone_title",
+            self.inner
+                .update_milestone_title(milestone_number, new_title),
+        )
+        .await
+    }
+
+    async fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &MilestoneUpdate,
+    ) -> Result<Milestone, GitHubApiError> {
+        self.timed(
+            "update_milestone",
+            self.inner.update_milestone(milestone_number, update),
+        )
+        .await
+    }
+
+    async fn close_milestone(&self, milestone_number: u64) -> Result<Milestone, GitHubApiError> {
+        self.timed(
+            "close_milestone",
+            self.inner.close_milestone(milestone_number),
+        )
+        .await
+    }
+
+    async fn post_issue(&self, issue: &QCIssue) -> Result<Issue, GitHubApiError> {
+        self.timed("post_issue", self.inner.post_issue(issue)).await
+    }
+
+    async fn post_comment<T: CommentBody + Sync + 'static>(
+        &self,
+        comment: &T,
+    ) -> Result<String, GitHubApiError> {
+        self.timed("post_comment", self.inner.post_comment(comment))
+            .await
+    }
+
+    fn guard_issue(&self, issue: &Issue) -> Result<(), GitHubApiError> {
+        self.inner.guard_issue(issue)
+    }
+
+    async fn close_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.timed("close_issue", self.inner.close_issue(issue_number))
+            .await
+    }
+
+    async fn open_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.timed("open_issue", self.inner.open_issue(issue_number))
+            .await
+    }
+
+    async fn create_label(&self, name: &str, color: &str) -> Result<(), GitHubApiError> {
+        self.timed("create_label", self.inner.create_label(name, color))
+            .await
+    }
+
+    async fn add_labels(&self, issue_number: u64, labels: &[String]) -> Result<(), GitHubApiError> {
+        self.timed("add_labels", self.inner.add_labels(issue_number, labels))
+            .await
+    }
+
+    async fn remove_label(&self, issue_number: u64, label: &str) -> Result<(), GitHubApiError> {
+        self.timed("remove_label", self.inner.remove_label(issue_number, label))
+            .await
+    }
+
+    async fn set_assignees(
+        &self,
+        issue_number: u64,
+        assignees: &[String],
+    ) -> Result<(), GitHubApiError> {
+        self.timed(
+            "set_assignees",
+            self.inner.set_assignees(issue_number, assignees),
+        )
+        .await
+    }
+
+    async fn block_issue(
+        &self,
+        blocked_issue_number: u64,
+        blocking_issue_id: u64,
+    ) -> Result<(), GitHubApiError> {
+        self.timed(
+            "block_issue",
+            self.inner
+                .block_issue(blocked_issue_number, blocking_issue_id),
+        )
+        .await
+    }
+
+    async fn update_issue(
+        &self,
+        issue_number: u64,
+        new_title: Option<String>,
+        new_body: Option<String>,
+    ) -> Result<(), GitHubApiError> {
+        self.timed(
+            "update_issue",
+            self.inner.update_issue(issue_number, new_title, new_body),
+        )
+        .await
+    }
+}
+
+impl<G: GitHelpers> GitHelpers for MeteredGit<G> {
+    fn file_content_url(&self, git_ref: &str, file: &Path) -> String {
+        self.inner.file_content_url(git_ref, file)
+    }
+
+    fn commit_comparison_url(
+        &self,
+        current_commit: &ObjectId,
+        previous_commit: &ObjectId,
+    ) -> String {
+        self.inner
+            .commit_comparison_url(current_commit, previous_commit)
+    }
+
+    fn issue_url(&self, issue_number: u64) -> String {
+        self.inner.issue_url(issue_number)
+    }
+}
+
+impl<G: GitRepository> GitRepository for MeteredGit<G> {
+    fn commit(&self) -> Result<String, GitRepositoryError> {
+        self.inner.commit()
+    }
+
+    fn branch(&self) -> Result<String, GitRepositoryError> {
+        self.inner.branch()
+    }
+
+    fn owner(&self) -> &str {
+        self.inner.owner()
+    }
+
+    fn repo(&self) -> &str {
+        self.inner.repo()
+    }
+
+    fn remote_name(&self) -> &str {
+        self.inner.remote_name()
+    }
+
+    fn path(&self) -> &Path {
+        self.inner.path()
+    }
+
+    fn fetch(&self) -> Result<bool, GitRepositoryError> {
+        self.inner.fetch()
+    }
+
+    fn stash_file(
+        &self,
+        file: &Path,
+        message: &str,
+    ) -> Result<FileStashOutcome, GitRepositoryError> {
+        self.inner.stash_file(file, message)
+    }
+
+    fn configured_author(&self) -> Option<GitAuthor> {
+        self.inner.configured_author()
+    }
+}
+
+impl<G: GitFileOps> GitFileOps for MeteredGit<G> {
+    fn authors(&self, file: &Path) -> Result<Vec<GitAuthor>, GitFileOpsError> {
+        self.inner.authors(file)
+    }
+
+    fn file_bytes_at_commit(
+        &self,
+        file: &Path,
+        commit: &ObjectId,
+    ) -> Result<Vec<u8>, GitFileOpsError> {
+        self.inner.file_bytes_at_commit(file, commit)
+    }
+
+    fn file_reader_at_commit(
+        &self,
+        file: &Path,
+        commit: &ObjectId,
+    ) -> Result<Option<FileReader>, GitFileOpsError> {
+        self.inner.file_reader_at_commit(file, commit)
+    }
+
+    fn list_tree_entries(&self, path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
+        self.inner.list_tree_entries(path)
+    }
+
+    fn path_attributes(&self, file: &Path) -> Result<PathAttributes, GitFileOpsError> {
+        self.inner.path_attributes(file)
+    }
+
+    fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+        self.inner.resolve_commit(commitish)
+    }
+}
+
+impl<G: GitCommitOps> GitCommitOps for MeteredGit<G> {
+    fn commits(
+        &self,
+        branch: &Option<String>,
+        stop_at: Option<ObjectId>,
+    ) -> Result<Vec<GitCommit>, GitFileOpsError> {
+        self.inner.commits(branch, stop_at)
+    }
+
+    fn branch_tip(&self, branch: &Option<String>) -> Result<ObjectId, GitFileOpsError> {
+        self.inner.branch_tip(branch)
+    }
+
+    fn file_touching_commits(
+        &self,
+        branch: Option<String>,
+        file: &Path,
+    ) -> Result<HashSet<String>, GitFileOpsError> {
+        self.inner.file_touching_commits(branch, file)
+    }
+
+    fn file_renames(
+        &self,
+        branch: Option<String>,
+        file: &Path,
+        since: Option<ObjectId>,
+    ) -> Result<Vec<FileRenameEvent>, GitFileOpsError> {
+        self.inner.file_renames(branch, file, since)
+    }
+
+    fn files_by_last_commit(
+        &self,
+        branch: &Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitFileOpsError> {
+        self.inner.files_by_last_commit(branch)
+    }
+
+    fn get_branches_containing_commit(
+        &self,
+        commit: &ObjectId,
+    ) -> Result<Vec<String>, GitFileOpsError> {
+        self.inner.get_branches_containing_commit(commit)
+    }
+
+    fn find_merged_into_branch(
+        &self,
+        target_commit: &ObjectId,
+    ) -> Result<Option<String>, GitFileOpsError> {
+        self.inner.find_merged_into_branch(target_commit)
+    }
+
+    fn find_merge_commit(
+        &self,
+        target_commit: &ObjectId,
+    ) -> Result<Option<ObjectId>, GitFileOpsError> {
+        self.inner.find_merge_commit(target_commit)
+    }
+
+    fn signature_status(
+        &self,
+        commit: &ObjectId,
+        keys: &SigningKeys,
+    ) -> Result<SignatureStatus, GitFileOpsError> {
+        self.inner.signature_status(commit, keys)
+    }
+
+    fn commit_time(&self, commit: &ObjectId) -> Result<DateTime<Utc>, GitFileOpsError> {
+        self.inner.commit_time(commit)
+    }
+
+    fn commit_author(&self, commit: &ObjectId) -> Result<GitAuthor, GitFileOpsError> {
+        self.inner.commit_author(commit)
+    }
+
+    fn is_shallow(&self) -> bool {
+        self.inner.is_shallow()
+    }
+}
+
+impl<G: GitStatusOps> GitStatusOps for MeteredGit<G> {
+    fn state(&self) -> Result<(ObjectId, GitState), GitStatusError> {
+        self.inner.state()
+    }
+
+    fn dirty(&self) -> Result<Vec<PathBuf>, GitStatusError> {
+        self.inner.dirty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = ApiMetrics::default();
+        metrics.record_request("GET", "/api/health", Duration::from_millis(20));
+        metrics.record_request("GET", "/api/health", Duration::from_secs(30));
+        metrics.record_github_call("get_issue", Duration::from_millis(300), true);
+        metrics.record_github_call("get_issue", Duration::from_millis(2), false);
+
+        assert_eq!(metrics.request_count("GET", "/api/health"), 2);
+        assert_eq!(metrics.request_count("POST", "/api/health"), 0);
+        assert_eq!(metrics.github_call_count(), 2);
+        insta::assert_snapshot!(metrics.render(None), @r#"
+        # HELP ghqc_http_request_duration_seconds Time to answer API requests, by route
+        # TYPE ghqc_http_request_duration_seconds histogram
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.005"} 0
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.01"} 0
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.025"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.05"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.1"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.25"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="0.5"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="1"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="2.5"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="10"} 1
+        ghqc_http_request_duration_seconds_bucket{method="GET",route="/api/health",le="+Inf"} 2
+        ghqc_http_request_duration_seconds_sum{method="GET",route="/api/health"} 30.02
+        ghqc_http_request_duration_seconds_count{method="GET",route="/api/health"} 2
+        # HELP ghqc_github_request_duration_seconds Time spent in GitHub API calls, by operation
+        # TYPE ghqc_github_request_duration_seconds histogram
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.005"} 1
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.01"} 1
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.025"} 1
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.05"} 1
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.1"} 1
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.25"} 1
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="0.5"} 2
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="1"} 2
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="2.5"} 2
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="10"} 2
+        ghqc_github_request_duration_seconds_bucket{operation="get_issue",le="+Inf"} 2
+        ghqc_github_request_duration_seconds_sum{operation="get_issue"} 0.302
+        ghqc_github_request_duration_seconds_count{operation="get_issue"} 2
+        # HELP ghqc_github_request_errors_total Failed GitHub API calls, by operation
+        # TYPE ghqc_github_request_errors_total counter
+        ghqc_github_request_errors_total{operation="get_issue"} 1
+        # HELP ghqc_disk_cache_hits_total Reads answered by the disk cache
+        # TYPE ghqc_disk_cache_hits_total counter
+        ghqc_disk_cache_hits_total 0
+        # HELP ghqc_disk_cache_misses_total Reads that found no valid disk cache entry
+        # TYPE ghqc_disk_cache_misses_total counter
+        ghqc_disk_cache_misses_total 0
+        "#);
+    }
+}
@@ -5,14 +5,17 @@
 
 mod error;
 mod fetch_helpers;
+mod metrics;
+mod record_jobs;
 mod routes;
 mod server;
 mod state;
 mod types;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
 pub use error::ApiError;
+pub use metrics::{ApiMetrics, MeteredGit};
 pub use server::{bind_local_server, bind_local_server_with_url, create_router, local_server_url};
 pub use state::AppState;
@@ -0,0 +1,295 @@
+//! Background record generation jobs and their progress events.
+
+use futures::Stream;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// How long a finished job's events stay available to late subscribers
+pub const RECORD_JOB_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Progress events buffered for subscribers which fall behind
+const EVENT_CAPACITY: usize = 256;
+
+/// Stage of a record generation, streamed to the UI as it happens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum RecordProgress {
+    FetchingIssues,
+    /// The comments and events of `issue` out of `total` issues have been fetched
+    FetchingComments {
+        issue: usize,
+        total: usize,
+    },
+    DownloadingImages,
+    RenderingPdf,
+    Complete {
+        output_path: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl RecordProgress {
+    /// Whether the job is over, so no event follows this one
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Complete { .. } | Self::Error { .. })
+    }
+}
+
+/// Events of one job: those sent so far, replayed to new subscribers, and the live channel
+struct JobEvents {
+    history: Vec<RecordProgress>,
+    sender: broadcast::Sender<RecordProgress>,
+}
+
+/// Reports the progress of a record pipeline to its job's subscribers
+///
+/// The default reporter belongs to no job and drops every event, for pipelines run within a
+/// request.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    job: Option<Arc<Mutex<JobEvents>>>,
+}
+
+impl ProgressReporter {
+    pub fn report(&self, progress: RecordProgress) {
+        let Some(job) = &self.job else {
+            return;
+        };
+        let mut events = job.lock().expect("record job lock poisoned");
+        events.history.push(progress.clone());
+        // No receivers is not an error: subscribers may connect later and replay the history
+        let _ = events.sender.send(progress);
+    }
+}
+
+/// Record generation jobs running in the background, by job id
+#[derive(Clone)]
+pub struct RecordJobs {
+    jobs: Arc<Mutex<HashMap<String, Arc<Mutex<JobEvents>>>>>,
+    ttl: Duration,
+}
+
+impl Default for RecordJobs {
+    fn default() -> Self {
+        Self::new(RECORD_JOB_TTL)
+    }
+}
+
+impl RecordJobs {
+    /// Jobs which are forgotten `ttl` after they finish
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    /// Run `pipeline` on a background task and return the job id right away
+    ///
+    /// The job ends with a [`RecordProgress::Complete`] event holding the path the pipeline
+    /// returns, or a [`RecordProgress::Error`] event.
+    pub fn start<F, Fut>(&self, pipeline: F) -> String
+    where
+        F: FnOnce(ProgressReporter) -> Fut,
+        Fut: Future<Output = Result<PathBuf, String>> + Send + 'static,
+    {
+        let id = uuid::Uuid::new_v4().to_string();
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        let job = Arc::new(Mutex::new(JobEvents {
+            history: Vec::new(),
+            sender,
+        }));
+        self.jobs
+            .lock()
+            .expect("record jobs lock poisoned")
+            .insert(id.clone(), job.clone());
+
+        let reporter = ProgressReporter { job: Some(job) };
+        let work = pipeline(reporter.clone());
+        let jobs = self.jobs.clone();
+        let ttl = self.ttl;
+        let job_id = id.clone();
+        tokio::spawn(async move {
+            let last = match work.await {
+                Ok(path) => RecordProgress::Complete {
+                    output_path: path.to_string_lossy().into_owned(),
+                },
+                Err(message) => RecordProgress::Error { message },
+            };
+            reporter.report(last);
+
+            tokio::time::sleep(ttl).await;
+            jobs.lock()
+                .expect("record jobs lock poisoned")
+                .remove(&job_id);
+        });
+
+        id
+    }
+
+    /// Events of job `id`, from its first one through its completion or error
+    ///
+    /// `None` when there is no such job, or it finished more than the TTL ago.
+    pub fn subscribe(&self, id: &str) -> Option<impl Stream<Item = RecordProgress> + use<>> {
+        let job = self
+            .jobs
+            .lock()
+            .expect("record jobs lock poisoned")
+            .get(id)
+            .cloned()?;
+        // Subscribing under the lock, so no event falls between the history and the channel
+        let (history, receiver) = {
+            let events = job.lock().expect("record job lock poisoned");
+            (events.history.clone(), events.sender.subscribe())
+        };
+
+        Some(futures::stream::unfold(
+            (history.into_iter(), receiver, false),
+            |(mut history, mut receiver, finished)| async move {
+                if finished {
+                    return None;
+                }
+                let progress = match history.next() {
+                    Some(progress) => progress,
+                    None => loop {
+                        match receiver.recv().await {
+                            Ok(progress) => break progress,
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                log::debug!("Record progress subscriber skipped {skipped} events");
+                            }
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        }
+                    },
+                };
+                let finished = progress.is_final();
+                Some((progress, (history, receiver, finished)))
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MilestoneSnapshot;
+    use crate::git::MockGitHubReader;
+    use crate::test_utils::{create_test_issue, create_test_milestone};
+    use futures::StreamExt;
+
+    const DELAY: Duration = Duration::from_millis(20);
+
+    /// GitHub reader whose every call takes a while
+    fn slow_reader() -> MockGitHubReader {
+        let mut reader = MockGitHubReader::new();
+        reader.expect_get_issues().returning(|_| {
+            Box::pin(async {
+                tokio::time::sleep(DELAY).await;
+                Ok((1..=2)
+                    .map(|n| create_test_issue("owner", "repo", n, "src/a.R", "", Some(1), "open"))
+                    .collect())
+            })
+        });
+        reader.expect_get_issue_comments().returning(|issue| {
+            let delay = DELAY * issue.number as u32;
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                Ok(Vec::new())
+            })
+        });
+        reader
+            .expect_get_issue_events()
+            .returning(|_| Box::pin(async { Ok(Vec::new()) }));
+        reader
+    }
+
+    /// The record pipeline's progress reporting, without downloading images or rendering
+    async fn pipeline(reporter: ProgressReporter, fail: bool) -> Result<PathBuf, String> {
+        reporter.report(RecordProgress::FetchingIssues);
+        let milestone = create_test_milestone("owner", "repo", 1, "v1.0", None, "open");
+        MilestoneSnapshot::build_with_progress(
+            &[&milestone],
+            &slow_reader(),
+            None,
+            |issue, total| reporter.report(RecordProgress::FetchingComments { issue, total }),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        reporter.report(RecordProgress::DownloadingImages);
+        if fail {
+            return Err("Typst compilation failed".to_string());
+        }
+        reporter.report(RecordProgress::RenderingPdf);
+        tokio::time::sleep(DELAY).await;
+        Ok(PathBuf::from("/tmp/record.pdf"))
+    }
+
+    #[tokio::test]
+    async fn test_progress_events_in_order() {
+        let jobs = RecordJobs::default();
+        let id = jobs.start(|reporter| pipeline(reporter, false));
+
+        let events = jobs.subscribe(&id).unwrap().collect::<Vec<_>>().await;
+        assert_eq!(
+            events,
+            [
+                RecordProgress::FetchingIssues,
+                RecordProgress::FetchingComments { issue: 1, total: 2 },
+                RecordProgress::FetchingComments { issue: 2, total: 2 },
+                RecordProgress::DownloadingImages,
+                RecordProgress::RenderingPdf,
+                RecordProgress::Complete {
+                    output_path: "/tmp/record.pdf".to_string()
+                },
+            ]
+        );
+
+        // A subscriber connecting after the job finished gets the whole history
+        let replayed = jobs.subscribe(&id).unwrap().collect::<Vec<_>>().await;
+        assert_eq!(replayed, events);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_ends_with_error() {
+        let jobs = RecordJobs::default();
+        let id = jobs.start(|reporter| pipeline(reporter, true));
+
+        let events = jobs.subscribe(&id).unwrap().collect::<Vec<_>>().await;
+        assert_eq!(events.first(), Some(&RecordProgress::FetchingIssues));
+        assert_eq!(
+            events.last(),
+            Some(&RecordProgress::Error {
+                message: "Typst compilation failed".to_string()
+            })
+        );
+        assert!(!events.contains(&RecordProgress::RenderingPdf));
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_evicted() {
+        let jobs = RecordJobs::new(DELAY);
+        let id = jobs.start(|_| async { Ok(PathBuf::from("/tmp/record.pdf")) });
+        assert!(jobs.subscribe("unknown").is_none());
+
+        let events = jobs.subscribe(&id).unwrap().collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 1);
+
+        tokio::time::sleep(DELAY * 5).await;
+        assert!(jobs.subscribe(&id).is_none());
+    }
+
+    #[test]
+    fn test_progress_json() {
+        let progress = RecordProgress::FetchingComments { issue: 3, total: 7 };
+        assert_eq!(
+            serde_json::to_string(&progress).unwrap(),
+            r#"{"stage":"fetching_comments","issue":3,"total":7}"#
+        );
+    }
+}
@@ -1,20 +1,38 @@
-//! Archive generation endpoint.
+//! Archive generation endpoints.
 
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::State,
+    http::{HeaderValue, StatusCode, header},
+    response::IntoResponse,
+};
 use gix::ObjectId;
-use std::path::{Component, PathBuf};
+use octocrab::models::Milestone;
+use std::{
+    collections::HashSet,
+    path::{Component, PathBuf},
+};
+use tokio::io::AsyncReadExt;
 
 use crate::{
-    GitProvider,
+    ApprovalWindow, GitProvider, IssueThread,
     api::{
         error::ApiError,
         state::AppState,
-        types::{ArchiveFileRequest, ArchiveGenerateRequest, ArchiveGenerateResponse},
+        types::{
+            ArchiveDownloadRequest, ArchiveFileRequest, ArchiveGenerateRequest,
+            ArchiveGenerateResponse,
+        },
     },
     archive::{ArchiveFile, ArchiveMetadata, ArchiveQC, archive},
+    generate_archive_name,
     utils::StdEnvProvider,
 };
 
+/// Size of the chunks a downloaded archive is streamed in
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
 /// POST /api/archive/generate
 ///
 /// Accepts an `ArchiveGenerateRequest` JSON body, builds an archive at
@@ -77,11 +95,18 @@ pub async fn generate_archive<G: GitProvider + 'static>(
     let flatten = request.flatten;
     let archive_files = build_archive_files(request.files, flatten)?;
 
+    let git_info = state.git_info().clone();
     let env = StdEnvProvider;
-    let metadata = ArchiveMetadata::new(archive_files, &env)
-        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let size_limits = state
+        .configuration
+        .read()
+        .await
+        .options
+        .archive_size_limits(None);
+    let metadata = ArchiveMetadata::new(archive_files, &git_info, &env)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+        .with_size_limits(size_limits);
 
-    let git_info = state.git_info().clone();
     let output_path_clone = output_path.clone();
     tokio::task::spawn_blocking(move || archive(metadata, &git_info, &output_path_clone))
         .await
@@ -93,6 +118,139 @@ pub async fn generate_archive<G: GitProvider + 'static>(
     }))
 }
 
+/// POST /api/archive
+///
+/// Accepts an `ArchiveDownloadRequest` JSON body, archives the files of the selected issues
+/// (plus any additional files) and streams the tarball back as an `application/gzip` download.
+/// The archive is written to a temporary file, which is removed once the response is sent.
+pub async fn download_archive<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    Json(request): Json<ArchiveDownloadRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if request.issues.is_empty() && request.additional_files.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one issue or additional file is required".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let issue_numbers: Vec<u64> = request
+        .issues
+        .iter()
+        .copied()
+        .filter(|number| seen.insert(*number))
+        .collect();
+
+    let mut threads = Vec::with_capacity(issue_numbers.len());
+    let mut milestones: Vec<Milestone> = Vec::new();
+    for number in issue_numbers {
+        let issue = match state.git_info().get_issue(number).await {
+            Ok(issue) => issue,
+            Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+                return Err(ApiError::NotFound(format!("Issue #{number} not found")));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(milestone) = &issue.milestone
+            && !milestones.iter().any(|m| m.number == milestone.number)
+        {
+            milestones.push(milestone.clone());
+        }
+        threads.push(IssueThread::from_issue(&issue, state.disk_cache(), state.git_info()).await?);
+    }
+
+    let window = ApprovalWindow::default();
+    let unapproved: Vec<String> = threads
+        .iter()
+        .filter(|thread| !window.includes(thread, request.include_unapproved))
+        .map(|thread| format!("#{}", thread.issue_number))
+        .collect();
+    if !unapproved.is_empty() {
+        return Err(ApiError::Conflict(format!(
+            "Issues not approved: {}; set include_unapproved to archive them at their latest commit",
+            unapproved.join(", ")
+        )));
+    }
+
+    let mut archive_files = threads
+        .iter()
+        .map(|thread| ArchiveFile::from_issue_thread(thread, request.flatten))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    archive_files.extend(build_archive_files(
+        request.additional_files,
+        request.flatten,
+    )?);
+
+    let git_info = state.git_info().clone();
+    let size_limits = state
+        .configuration
+        .read()
+        .await
+        .options
+        .archive_size_limits(None);
+    let metadata = ArchiveMetadata::new(archive_files, &git_info, &StdEnvProvider)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+        .with_size_limits(size_limits);
+    let file_name = generate_archive_name(&milestones.iter().collect::<Vec<_>>(), &git_info);
+
+    // The temp file is owned by the response stream, so it is removed once the download
+    // completes or is abandoned
+    let temp_file = tempfile::Builder::new()
+        .prefix("ghqc-archive-")
+        .suffix(".tar.gz")
+        .tempfile()
+        .map_err(|e| ApiError::Internal(format!("Failed to create temp archive: {e}")))?;
+    let temp_file = tokio::task::spawn_blocking(move || {
+        archive(metadata, &git_info, temp_file.path()).map(|_| temp_file)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("Archive task panicked: {e}")))?
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let file = temp_file
+        .reopen()
+        .map_err(|e| ApiError::Internal(format!("Failed to open temp archive: {e}")))?;
+    let content_length = file.metadata().map(|m| m.len()).ok();
+    let stream = futures::stream::try_unfold(
+        (tokio::fs::File::from_std(file), temp_file),
+        |(mut file, temp_file)| async move {
+            let mut buffer = vec![0; DOWNLOAD_CHUNK_SIZE];
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                return Ok::<_, std::io::Error>(None);
+            }
+            buffer.truncate(read);
+            Ok(Some((Bytes::from(buffer), (file, temp_file))))
+        },
+    );
+
+    let content_disposition = HeaderValue::from_str(&format!(
+        "attachment; filename=\"{}\"",
+        file_name.replace('"', "")
+    ))
+    .map_err(|e| ApiError::Internal(format!("Invalid archive file name: {e}")))?;
+    let mut response = (
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/gzip"),
+            ),
+            (header::CONTENT_DISPOSITION, content_disposition),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response();
+    if let Some(length) = content_length {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+    }
+
+    Ok(response)
+}
+
 fn build_archive_files(
     files: Vec<ArchiveFileRequest>,
     flatten: bool,
@@ -141,6 +299,9 @@ fn build_archive_files(
             (Some(milestone), Some(approved)) => Some(ArchiveQC {
                 milestone: milestone.clone(),
                 approved: *approved,
+                issue_number: None,
+                issue_url: None,
+                approved_by: None,
             }),
             (None, None) => None,
         };
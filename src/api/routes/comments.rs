@@ -1,7 +1,5 @@
 //! Comment, approve, unapprove, and review endpoints.
 
-use std::path::PathBuf;
-
 use crate::api::error::ApiError;
 use crate::api::fetch_helpers::{CreatedThreads, FetchedIssues};
 use crate::api::routes::issues::determine_blocking_qc_status;
@@ -11,15 +9,20 @@ use crate::api::types::{
     BlockingQCStatus, CommentResponse, CreateCommentRequest, ReviewRequest, ReviewResponse,
     UnapprovalResponse, UnapproveRequest,
 };
+use crate::utils::normalize_repo_path;
 use crate::{
-    GitProvider, QCApprove, QCComment, QCReview, QCUnapprove, parse_blocking_qcs, stash_review_file,
+    ApprovalError, GitProvider, IssueThread, NoDiff, QCApprove, QCComment, QCReview, QCScope,
+    QCUnapprove, check_checklist_completion, issue_url_matches_remote, parse_blocking_qcs,
+    stash_review_file,
 };
 use axum::{
     Json,
     extract::{Path, Query, State},
     http::StatusCode,
 };
+use chrono::Utc;
 use gix::ObjectId;
+use octocrab::models::issues::Issue;
 
 /// POST /api/issues/{number}/comment
 pub async fn create_comment<G: GitProvider + 'static>(
@@ -27,6 +30,8 @@ pub async fn create_comment<G: GitProvider + 'static>(
     Path(number): Path<u64>,
     Json(request): Json<CreateCommentRequest>,
 ) -> Result<(StatusCode, Json<CommentResponse>), ApiError> {
+    state.require_write_access()?;
+
     let previous_commit = request
         .previous_commit
         .as_deref()
@@ -37,15 +42,23 @@ pub async fn create_comment<G: GitProvider + 'static>(
     let issue = state.git_info().get_issue(number).await?;
 
     let comment = QCComment {
-        file: PathBuf::from(&issue.title),
+        file: normalize_repo_path(std::path::Path::new(&issue.title)),
         issue,
         current_commit,
         previous_commit,
         note: request.note,
-        no_diff: !request.include_diff,
+        no_diff: (!request.include_diff).then_some(NoDiff::Stats),
+        force_diff: false,
+        excel_diff: None,
+        renames: Vec::new(),
+        environment: Vec::new(),
     };
 
-    let comment_url = state.git_info().post_comment(&comment).await?;
+    let comment_url = state
+        .git_info()
+        .post_comment(&comment)
+        .await
+        .map_err(|e| state.write_error(e))?;
 
     Ok((StatusCode::CREATED, Json(CommentResponse { comment_url })))
 }
@@ -57,13 +70,37 @@ pub async fn approve_issue<G: GitProvider + 'static>(
     Query(query): Query<ApproveQuery>,
     Json(request): Json<ApproveRequest>,
 ) -> Result<(StatusCode, Json<ApprovalResponse>), ApiError> {
+    state.require_write_access()?;
+
     let issue = state.git_info().get_issue(number).await?;
+    let require_complete_checklist = !request.allow_incomplete_checklist
+        && state
+            .configuration
+            .read()
+            .await
+            .require_complete_checklist();
+    let incomplete_checklist = match check_checklist_completion(&issue, require_complete_checklist)
+    {
+        Ok(incomplete) => incomplete,
+        Err(ApprovalError::IncompleteChecklist(incomplete)) => {
+            return Err(ApiError::Invalid {
+                code: "incomplete_checklist",
+                message: format!(
+                    "Cannot approve: {incomplete}\n\nCheck the items off or set allow_incomplete_checklist to approve anyway"
+                ),
+            });
+        }
+        Err(e) => return Err(ApiError::Internal(e.to_string())),
+    };
+
     let blocking_qcs = issue
         .body
         .as_deref()
         .map(|b| {
             parse_blocking_qcs(b)
                 .iter()
+                // Issues of another repository cannot be looked up by number
+                .filter(|b| issue_url_matches_remote(&b.url, b.issue_number, state.git_info()))
                 .map(|b| b.issue_number)
                 .collect::<Vec<_>>()
         })
@@ -91,13 +128,23 @@ pub async fn approve_issue<G: GitProvider + 'static>(
     let commit = parse_str_as_commit(&request.commit)?;
 
     let approval = QCApprove {
-        file: PathBuf::from(&issue.title),
+        file: normalize_repo_path(std::path::Path::new(&issue.title)),
         commit,
         issue: issue.clone(),
         note: request.note,
+        effective_date: request.effective_date,
+        scope: approval_scope(&issue, &commit, &state).await,
+        incomplete_checklist,
     };
+    approval
+        .check_effective_date(state.git_info(), Utc::now().date_naive())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
 
-    let approval_url = state.git_info().post_comment(&approval).await?;
+    let approval_url = state
+        .git_info()
+        .post_comment(&approval)
+        .await
+        .map_err(|e| state.write_error(e))?;
     let closed = state.git_info().close_issue(issue.number).await.is_ok();
 
     Ok((
@@ -129,13 +176,24 @@ pub async fn unapprove_issue<G: GitProvider + 'static>(
     Path(number): Path<u64>,
     Json(request): Json<UnapproveRequest>,
 ) -> Result<(StatusCode, Json<UnapprovalResponse>), ApiError> {
+    state.require_write_access()?;
+
     let issue = state.git_info().get_issue(number).await?;
+    let issue_thread =
+        IssueThread::from_issue(&issue, state.disk_cache(), state.git_info()).await?;
     let unapprove = QCUnapprove {
-        issue,
-        reason: request.reason,
+        effective_date: request.effective_date,
+        ..QCUnapprove::new(issue, &issue_thread, request.reason)
     };
+    unapprove
+        .check_effective_date(Utc::now().date_naive())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
 
-    let unapproval_url = state.git_info().post_comment(&unapprove).await?;
+    let unapproval_url = state
+        .git_info()
+        .post_comment(&unapprove)
+        .await
+        .map_err(|e| state.write_error(e))?;
 
     let opened = state.git_info().open_issue(number).await.is_ok();
 
@@ -154,22 +212,30 @@ pub async fn review_issue<G: GitProvider + 'static>(
     Path(number): Path<u64>,
     Json(request): Json<ReviewRequest>,
 ) -> Result<(StatusCode, Json<ReviewResponse>), ApiError> {
+    state.require_write_access()?;
+
     let commit = parse_str_as_commit(&request.commit)?;
 
     let issue = state.git_info().get_issue(number).await?;
-    let review_file = PathBuf::from(&issue.title);
+    let review_file = normalize_repo_path(std::path::Path::new(&issue.title));
 
     let review = QCReview {
         file: review_file.clone(),
         issue,
         commit,
         note: request.note,
-        no_diff: !request.include_diff,
+        no_diff: (!request.include_diff).then_some(NoDiff::Stats),
+        force_diff: false,
         stash_after_review: request.auto_stash,
         working_dir: state.git_info().path().to_path_buf(),
+        excel_diff: None,
     };
 
-    let comment_url = state.git_info().post_comment(&review).await?;
+    let comment_url = state
+        .git_info()
+        .post_comment(&review)
+        .await
+        .map_err(|e| state.write_error(e))?;
 
     let stash = stash_review_file(state.git_info(), number, &review_file, request.auto_stash);
 
@@ -185,6 +251,25 @@ fn parse_str_as_commit(commit: &str) -> Result<ObjectId, ApiError> {
         .map_err(|e: gix::hash::decode::Error| ApiError::BadRequest(e.to_string()))
 }
 
+/// Scope of approving `commit` on the issue, left out of the approval when the issue's thread
+/// cannot be loaded
+pub(crate) async fn approval_scope<G: GitProvider>(
+    issue: &Issue,
+    commit: &ObjectId,
+    state: &AppState<G>,
+) -> Option<QCScope> {
+    match IssueThread::from_issue(issue, state.disk_cache(), state.git_info()).await {
+        Ok(thread) => QCScope::from_thread(&thread, commit),
+        Err(e) => {
+            log::debug!(
+                "Leaving the QC scope out of the approval of #{}: {e}",
+                issue.number
+            );
+            None
+        }
+    }
+}
+
 pub(crate) async fn get_blocking_qc_status<G: GitProvider>(
     blocking_qcs: &[u64],
     state: &AppState<G>,
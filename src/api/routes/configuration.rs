@@ -8,7 +8,7 @@ use crate::api::types::{
 };
 use crate::configuration::ConfigurationError;
 use crate::utils::StdEnvProvider;
-use crate::{Configuration, GitCli, GitProvider, setup_configuration};
+use crate::{Configuration, GitCli, GitProvider, set_diff_settings, setup_configuration};
 use axum::{Json, extract::State};
 
 /// GET /api/configuration
@@ -48,6 +48,7 @@ pub async fn get_configuration<G: GitProvider + 'static>(
         },
         checklists,
         config_repo_env,
+        warnings: config.warnings.iter().map(Into::into).collect(),
     };
 
     Ok(Json(response))
@@ -89,6 +90,7 @@ pub async fn setup_configuration_repo<G: GitProvider + 'static, C: GitCli + Send
 
     let mut new_configuration = Configuration::from_path(&config_dir);
     new_configuration.load_checklists();
+    set_diff_settings(new_configuration.diff_settings());
 
     {
         let mut config_lock = state.configuration.write().await;
@@ -20,6 +20,7 @@ use crate::api::state::AppState;
 use crate::api::types::{FileCollaboratorsResponse, FileTreeResponse, TreeEntry, TreeEntryKind};
 use crate::create::{collaborator_override_for_policy, resolve_issue_people};
 use crate::git::GitFileOpsError;
+use crate::utils::IgnoreRules;
 
 #[derive(Deserialize)]
 pub struct FileTreeQuery {
@@ -27,6 +28,15 @@ pub struct FileTreeQuery {
     path: String,
 }
 
+#[derive(Deserialize)]
+pub struct ListTreeQuery {
+    #[serde(default)]
+    path: String,
+    /// Include the entries matched by `.ghqcignore`
+    #[serde(default)]
+    no_ignore: bool,
+}
+
 #[derive(Deserialize)]
 pub struct FileRawQuery {
     path: String,
@@ -78,14 +88,21 @@ fn inline_content_disposition(path: &str) -> String {
     format!("inline; filename=\"{file_name}\"")
 }
 
-/// GET /api/files/tree?path=
+/// GET /api/files/tree?path=&no_ignore=
+///
+/// Entries matched by the repository's `.ghqcignore` are left out unless `no_ignore` is set.
 pub async fn list_tree<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
-    Query(query): Query<FileTreeQuery>,
+    Query(query): Query<ListTreeQuery>,
 ) -> Result<Json<FileTreeResponse>, ApiError> {
     // Sanitize: trim leading/trailing slashes, reject `.` and `..` segments
     let path = sanitize_repo_relative_path(&query.path)?;
 
+    let ignore = if query.no_ignore {
+        IgnoreRules::default()
+    } else {
+        IgnoreRules::load(state.git_info().path()).map_err(|e| ApiError::Internal(e.to_string()))?
+    };
     let git_info = state.git_info().clone();
     let path_for_task = path.clone();
 
@@ -103,9 +120,9 @@ pub async fn list_tree<G: GitProvider + 'static>(
         })?;
 
     let response = FileTreeResponse {
-        path,
         entries: entries
             .into_iter()
+            .filter(|(name, is_dir)| !ignore.is_ignored(&Path::new(&path).join(name), *is_dir))
             .map(|(name, is_dir)| TreeEntry {
                 name,
                 kind: if is_dir {
@@ -115,6 +132,7 @@ pub async fn list_tree<G: GitProvider + 'static>(
                 },
             })
             .collect(),
+        path,
     };
 
     Ok(Json(response))
@@ -1,12 +1,18 @@
 //! Health check endpoint.
 
+use crate::GitProvider;
+use crate::api::state::AppState;
 use crate::api::types::HealthResponse;
-use axum::Json;
+use axum::{Json, extract::State};
 
 /// GET /api/health
-pub async fn health_check() -> Json<HealthResponse> {
+pub async fn health_check<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+) -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
+        read_only: !state.write_enabled(),
+        cache: state.cache_health().clone(),
     })
 }
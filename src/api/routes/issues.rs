@@ -6,15 +6,18 @@ use crate::api::state::AppState;
 use crate::api::types::{
     BatchIssueStatusResponse, BlockedIssueStatus, BlockingQCError, BlockingQCItem,
     BlockingQCItemWithStatus, BlockingQCStatus, CreateIssueRequest, CreateIssueResponse, Issue,
-    IssueStatusError, IssueStatusErrorKind, IssueStatusResponse, QCStatusEnum,
+    IssueListResponse, IssueStatusError, IssueStatusErrorKind, IssueStatusResponse,
+    NewIssueRequest, NewIssueResponse, QCStatusEnum,
 };
 use crate::comment_system::CommentBody;
 use crate::create::QCIssueError;
-use crate::git::{GitFileOps, GitHelpers, GitHubApiError};
+use crate::git::{GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError};
+use crate::utils::same_repo_path;
 use crate::{
-    FileRenameEvent, GitProvider, QCEntry, batch_post_qc_entries, create_labels_if_needed,
-    file_history_section, get_repo_users, head_commit_hash, parse_file_history,
-    splice_file_history,
+    FileRenameEvent, GitProvider, QCEntry, QCRelationship, RelevantFileEntry,
+    batch_post_qc_entries, create_labels_if_needed, file_history_section, get_all_issues_cached,
+    get_milestone_issues_cached, get_milestones_cached, get_repo_users, head_commit_hash,
+    parse_file_history, splice_file_history,
 };
 use axum::{
     Json,
@@ -32,14 +35,198 @@ pub struct IssueStatusQuery {
     pub issues: String,
 }
 
+/// Query of `GET /api/issues`. Values are parsed by [`IssueListing::parse`] so invalid ones are
+/// reported with a structured error.
+#[derive(Debug, Default, Deserialize)]
+pub struct IssueListQuery {
+    pub page: Option<String>,
+    pub per_page: Option<String>,
+    /// `open`, `closed` or `all`
+    pub state: Option<String>,
+    /// Milestone number
+    pub milestone: Option<String>,
+    /// `updated`, `title` or `number`
+    pub sort: Option<String>,
+}
+
+const DEFAULT_PER_PAGE: usize = 30;
+const MAX_PER_PAGE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IssueStateFilter {
+    Open,
+    Closed,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IssueSort {
+    /// Most recently updated first
+    Updated,
+    Title,
+    Number,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IssueListing {
+    page: usize,
+    per_page: usize,
+    state: IssueStateFilter,
+    milestone: Option<u64>,
+    sort: IssueSort,
+}
+
+fn invalid_parameter(name: &str, value: &str, expected: &str) -> ApiError {
+    ApiError::Invalid {
+        code: "invalid_query_parameter",
+        message: format!("Invalid {name} '{value}': expected {expected}"),
+    }
+}
+
+impl IssueListing {
+    fn parse(query: &IssueListQuery) -> Result<Self, ApiError> {
+        let page = match query.page.as_deref() {
+            None => 1,
+            Some(page) => page
+                .parse()
+                .ok()
+                .filter(|page| *page >= 1)
+                .ok_or_else(|| invalid_parameter("page", page, "a positive integer"))?,
+        };
+        let per_page = match query.per_page.as_deref() {
+            None => DEFAULT_PER_PAGE,
+            Some(per_page) => per_page
+                .parse()
+                .ok()
+                .filter(|n| (1..=MAX_PER_PAGE).contains(n))
+                .ok_or_else(|| {
+                    invalid_parameter(
+                        "per_page",
+                        per_page,
+                        &format!("an integer from 1 to {MAX_PER_PAGE}"),
+                    )
+                })?,
+        };
+        let state = match query.state.as_deref() {
+            None | Some("all") => IssueStateFilter::All,
+            Some("open") => IssueStateFilter::Open,
+            Some("closed") => IssueStateFilter::Closed,
+            Some(state) => return Err(invalid_parameter("state", state, "open, closed or all")),
+        };
+        let milestone = query
+            .milestone
+            .as_deref()
+            .map(|milestone| {
+                milestone
+                    .parse()
+                    .map_err(|_| invalid_parameter("milestone", milestone, "a milestone number"))
+            })
+            .transpose()?;
+        let sort = match query.sort.as_deref() {
+            None | Some("updated") => IssueSort::Updated,
+            Some("title") => IssueSort::Title,
+            Some("number") => IssueSort::Number,
+            Some(sort) => return Err(invalid_parameter("sort", sort, "updated, title or number")),
+        };
+
+        Ok(Self {
+            page,
+            per_page,
+            state,
+            milestone,
+            sort,
+        })
+    }
+
+    /// Filter and sort `issues`, returning the number that match and those on the page.
+    /// A page past the last one is an error, except the first page of an empty listing
+    fn page_of(&self, issues: Vec<OctocrabIssue>) -> Result<(usize, Vec<OctocrabIssue>), ApiError> {
+        let mut issues = issues
+            .into_iter()
+            .filter(|issue| match self.state {
+                IssueStateFilter::All => true,
+                IssueStateFilter::Open => issue.state == octocrab::models::IssueState::Open,
+                IssueStateFilter::Closed => issue.state == octocrab::models::IssueState::Closed,
+            })
+            .collect::<Vec<_>>();
+        match self.sort {
+            IssueSort::Updated => issues.sort_by(|a, b| {
+                b.updated_at
+                    .cmp(&a.updated_at)
+                    .then(b.number.cmp(&a.number))
+            }),
+            IssueSort::Title => {
+                issues.sort_by(|a, b| a.title.cmp(&b.title).then(a.number.cmp(&b.number)))
+            }
+            IssueSort::Number => issues.sort_by_key(|issue| issue.number),
+        }
+
+        let total = issues.len();
+        let last_page = total.div_ceil(self.per_page).max(1);
+        if self.page > last_page {
+            return Err(ApiError::Invalid {
+                code: "page_out_of_range",
+                message: format!(
+                    "Page {} is out of range: {total} issue(s) fit on {last_page} page(s) of {}",
+                    self.page, self.per_page
+                ),
+            });
+        }
+
+        let page = issues
+            .into_iter()
+            .skip((self.page - 1) * self.per_page)
+            .take(self.per_page)
+            .collect();
+        Ok((total, page))
+    }
+}
+
+/// GET /api/issues?page=1&per_page=30&state=all&milestone=1&sort=updated
+///
+/// Filters, sorts and pages the issues before computing the status of those on the page.
+pub async fn list_issues<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    Query(query): Query<IssueListQuery>,
+) -> Result<(StatusCode, Json<IssueListResponse>), ApiError> {
+    let listing = IssueListing::parse(&query)?;
+    let issues = match listing.milestone {
+        Some(milestone) => {
+            get_milestone_issues_cached(state.disk_cache(), state.git_info(), milestone).await?
+        }
+        None => get_all_issues_cached(state.disk_cache(), state.git_info()).await?,
+    };
+    let (total, page) = listing.page_of(issues)?;
+
+    let issue_numbers = page.iter().map(|issue| issue.number).collect::<Vec<_>>();
+    let fetched_issues = FetchedIssues {
+        issues: page,
+        errors: Default::default(),
+    };
+    let (status, results, errors) = issue_statuses(&issue_numbers, fetched_issues, &state).await;
+
+    Ok((
+        status,
+        Json(IssueListResponse {
+            total,
+            page: listing.page,
+            per_page: listing.per_page,
+            results,
+            errors,
+        }),
+    ))
+}
+
 /// POST /api/milestones/{number}/issues
 pub async fn create_issues<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
     Path(milestone_number): Path<u64>,
     Json(requests): Json<Vec<CreateIssueRequest>>,
 ) -> Result<(StatusCode, Json<Vec<CreateIssueResponse>>), ApiError> {
+    state.require_write_access()?;
+
     // Validate milestone exists
-    let milestones = state.git_info().get_milestones().await?;
+    let milestones = get_milestones_cached(state.disk_cache(), state.git_info()).await?;
     if !milestones
         .iter()
         .any(|m| m.number == milestone_number as i64)
@@ -51,16 +238,8 @@ pub async fn create_issues<G: GitProvider + 'static>(
     }
 
     // Get existing issues in milestone
-    let milestone_issues = state
-        .git_info()
-        .get_issues(Some(milestone_number))
-        .await
-        .map_err(|e| {
-            ApiError::GitHubApi(format!(
-                "Failed to fetch existing issues in milestone {}: {}",
-                milestone_number, e
-            ))
-        })?;
+    let milestone_issues =
+        get_milestone_issues_cached(state.disk_cache(), state.git_info(), milestone_number).await?;
 
     let entries = requests
         .into_iter()
@@ -122,7 +301,7 @@ pub async fn create_issues<G: GitProvider + 'static>(
         .filter(|e| {
             milestone_issues
                 .iter()
-                .any(|i| PathBuf::from(&i.title) == e.title)
+                .any(|i| same_repo_path(std::path::Path::new(&i.title), &e.title))
         })
         .map(|e| e.title.to_string_lossy().to_string())
         .collect::<Vec<_>>();
@@ -145,12 +324,19 @@ pub async fn create_issues<G: GitProvider + 'static>(
     }
 
     let current_user = state.git_info().get_current_user().await.ok().flatten();
+    let template = state
+        .configuration
+        .read()
+        .await
+        .issue_template()
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
 
     let res = batch_post_qc_entries(
         &entries,
         state.git_info(),
         milestone_number,
         current_user.as_deref(),
+        template.as_ref(),
     )
     .await
     .map_err(|e| match e {
@@ -162,7 +348,7 @@ pub async fn create_issues<G: GitProvider + 'static>(
                 .collect::<Vec<_>>()
                 .join("\n  -")
         )),
-        QCIssueError::GitHubApiError(e) => ApiError::GitHubApi(e.to_string()),
+        QCIssueError::GitHubApiError(e) => state.write_error(e),
         _ => ApiError::Internal(e.to_string()),
     })?;
 
@@ -172,6 +358,178 @@ pub async fn create_issues<G: GitProvider + 'static>(
     ))
 }
 
+/// POST /api/issues
+///
+/// Create one issue with a configured checklist, creating its milestone when requested. The
+/// request is validated before the milestone is created, so a rejected request writes nothing.
+pub async fn create_issue<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    Json(request): Json<NewIssueRequest>,
+) -> Result<(StatusCode, Json<NewIssueResponse>), ApiError> {
+    state.require_write_access()?;
+    let git_info = state.git_info();
+    let file = PathBuf::from(&request.file);
+
+    let (checklist, include_collaborators, template) = {
+        let configuration = state.configuration.read().await;
+        let checklist = configuration
+            .checklists
+            .get(&request.checklist_name)
+            .cloned()
+            .ok_or_else(|| ApiError::Invalid {
+                code: "unknown_checklist",
+                message: format!("No checklist named '{}'", request.checklist_name),
+            })?;
+        let template = configuration
+            .issue_template()
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        (checklist, configuration.include_collaborators(), template)
+    };
+
+    if !is_tracked(git_info, &file)? {
+        return Err(ApiError::Invalid {
+            code: "file_not_tracked",
+            message: format!("{} is not tracked at HEAD", file.display()),
+        });
+    }
+
+    let repo_users = get_repo_users(state.disk_cache(), git_info)
+        .await?
+        .into_iter()
+        .map(|r| r.login)
+        .collect::<HashSet<_>>();
+    let unknown_assignees = request
+        .assignees
+        .iter()
+        .filter(|a| !repo_users.contains(*a))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unknown_assignees.is_empty() {
+        return Err(ApiError::Invalid {
+            code: "unknown_assignees",
+            message: format!("Unknown assignees: {}", unknown_assignees.join(", ")),
+        });
+    }
+
+    let mut relevant_files = Vec::new();
+    for (links, relation) in [
+        (&request.previous_qc, QCRelationship::PreviousQC),
+        (&request.gating_qc, QCRelationship::GatingQC),
+        (&request.relevant_qc, QCRelationship::RelevantQC),
+    ] {
+        for link in links {
+            let entry = link
+                .to_entry(relation, git_info)
+                .map_err(|message| ApiError::Invalid {
+                    code: "invalid_issue_url",
+                    message,
+                })?;
+            relevant_files.push(entry);
+        }
+    }
+    relevant_files.extend(
+        request
+            .relevant_files
+            .into_iter()
+            .map(|f| RelevantFileEntry::File {
+                file_path: PathBuf::from(f.file_path),
+                justification: f.justification,
+            }),
+    );
+
+    let milestones = get_milestones_cached(state.disk_cache(), git_info).await?;
+    let existing = milestones
+        .iter()
+        .find(|m| m.title == request.milestone.name);
+    let (milestone_number, milestone_created) = match existing {
+        Some(milestone) => {
+            let milestone_number = milestone.number as u64;
+            let issues =
+                get_milestone_issues_cached(state.disk_cache(), git_info, milestone_number).await?;
+            if issues
+                .iter()
+                .any(|i| same_repo_path(std::path::Path::new(&i.title), &file))
+            {
+                return Err(ApiError::Conflict(format!(
+                    "An issue already exists in milestone '{}' for {}",
+                    request.milestone.name,
+                    file.display()
+                )));
+            }
+            (milestone_number, false)
+        }
+        None if request.milestone.create => {
+            let milestone = git_info
+                .create_milestone(&request.milestone.name, &request.milestone.description)
+                .await
+                .map_err(|e| state.write_error(e))?;
+            (milestone.number as u64, true)
+        }
+        None => {
+            return Err(ApiError::Invalid {
+                code: "milestone_not_found",
+                message: format!(
+                    "Milestone '{}' not found. Set `create` to create it",
+                    request.milestone.name
+                ),
+            });
+        }
+    };
+
+    if let Err(e) = create_labels_if_needed(
+        state.disk_cache(),
+        git_info.branch().ok().as_deref(),
+        git_info,
+    )
+    .await
+    {
+        log::warn!("Failed to create issue labels: {e}. Continuing without...");
+    }
+
+    let entry = QCEntry {
+        title: file,
+        checklist,
+        assignees: request.assignees,
+        collaborators: (!include_collaborators).then(Vec::new),
+        relevant_files,
+    };
+    let current_user = git_info.get_current_user().await.ok().flatten();
+    let created = batch_post_qc_entries(
+        &[entry],
+        git_info,
+        milestone_number,
+        current_user.as_deref(),
+        template.as_ref(),
+    )
+    .await
+    .map_err(|e| match e {
+        QCIssueError::GitHubApiError(e) => state.write_error(e),
+        _ => ApiError::Internal(e.to_string()),
+    })?
+    .pop()
+    .ok_or_else(|| ApiError::Internal("No issue was created".to_string()))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(NewIssueResponse::new(created, milestone_created)),
+    ))
+}
+
+/// Whether `file` is tracked at HEAD, looking only at its directory
+fn is_tracked(git_info: &impl GitFileOps, file: &std::path::Path) -> Result<bool, ApiError> {
+    let (Some(dir), Some(name)) = (file.parent(), file.file_name()) else {
+        return Ok(false);
+    };
+    let dir = dir.to_string_lossy().replace('\\', "/");
+    match git_info.list_tree_entries(&dir) {
+        Ok(entries) => Ok(entries
+            .iter()
+            .any(|(entry, is_dir)| !is_dir && entry.as_str() == name)),
+        Err(GitFileOpsError::DirectoryNotFound(_)) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// GET /api/issues/status?issues=1,2,3
 pub async fn batch_get_issue_status<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
@@ -202,13 +560,24 @@ pub async fn batch_get_issue_status<G: GitProvider + 'static>(
         ));
     }
 
-    let mut fetched_issues = FetchedIssues::fetch_issues(&issue_numbers, state.git_info()).await;
-
     // Don't return early on fetch errors — accumulate them as FetchFailed entries.
+    let fetched_issues = FetchedIssues::fetch_issues(&issue_numbers, state.git_info()).await;
+    let (status, results, errors) = issue_statuses(&issue_numbers, fetched_issues, &state).await;
+
+    Ok((status, Json(BatchIssueStatusResponse { results, errors })))
+}
+
+/// Statuses of `issue_numbers`, in order, from their fetched issues. Issues which could not be
+/// fetched or processed are reported as errors, making the response partial.
+async fn issue_statuses<G: GitProvider + 'static>(
+    issue_numbers: &[u64],
+    mut fetched_issues: FetchedIssues,
+    state: &AppState<G>,
+) -> (StatusCode, Vec<IssueStatusResponse>, Vec<IssueStatusError>) {
     fetched_issues.fetch_blocking_qcs(state.git_info()).await;
 
     // Only create threads for successfully fetched issues.
-    let created_threads = CreatedThreads::create_threads(&fetched_issues.issues, &state).await;
+    let created_threads = CreatedThreads::create_threads(&fetched_issues.issues, state).await;
     fetched_issues.errors.extend(created_threads.thread_errors);
 
     let mut errors: Vec<IssueStatusError> = Vec::new();
@@ -224,7 +593,7 @@ pub async fn batch_get_issue_status<G: GitProvider + 'static>(
         .collect();
 
     // Preserve request ordering.
-    for issue_number in &issue_numbers {
+    for issue_number in issue_numbers {
         if let Some(response) = created_threads.responses.get(issue_number) {
             let mut response = response.clone();
             determine_blocking_qc_status(
@@ -273,13 +642,7 @@ pub async fn batch_get_issue_status<G: GitProvider + 'static>(
         (true, _) => StatusCode::INTERNAL_SERVER_ERROR,
     };
 
-    Ok((
-        status,
-        Json(BatchIssueStatusResponse {
-            results: responses,
-            errors,
-        }),
-    ))
+    (status, responses, errors)
 }
 
 /// Classify an `IssueError` into the API-facing `(kind, message, branch)` tuple.
@@ -443,6 +806,8 @@ pub async fn rename_issue<G: GitProvider + 'static>(
     Path(number): Path<u64>,
     Json(request): Json<RenameIssueRequest>,
 ) -> Result<StatusCode, ApiError> {
+    state.require_write_access()?;
+
     if request.new_path.trim().is_empty() {
         return Err(ApiError::BadRequest(
             "new_path must not be empty".to_string(),
@@ -455,6 +820,7 @@ pub async fn rename_issue<G: GitProvider + 'static>(
         .get_issue(number)
         .await
         .map_err(ApiError::from)?;
+    state.git_info().guard_issue(&raw_issue)?;
 
     let old_path = raw_issue.title.clone();
     let current_body = raw_issue.body.as_deref().unwrap_or("").to_string();
@@ -486,7 +852,7 @@ pub async fn rename_issue<G: GitProvider + 'static>(
         .git_info()
         .update_issue(number, Some(new_path.clone()), Some(new_body))
         .await
-        .map_err(ApiError::from)?;
+        .map_err(|e| state.write_error(e))?;
 
     log::info!(
         "Renamed issue #{number}: {:?} → {:?} (commit {})",
@@ -509,3 +875,124 @@ pub async fn rename_issue<G: GitProvider + 'static>(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_issue;
+
+    fn query(pairs: &[(&str, &str)]) -> IssueListQuery {
+        let get = |name: &str| {
+            pairs
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        IssueListQuery {
+            page: get("page"),
+            per_page: get("per_page"),
+            state: get("state"),
+            milestone: get("milestone"),
+            sort: get("sort"),
+        }
+    }
+
+    /// Five issues, #1-#4 open and #5 closed, updated one day apart with #3 the latest
+    fn issues() -> Vec<OctocrabIssue> {
+        let titles = ["d.R", "b.R", "e.R", "a.R", "c.R"];
+        let days = [1, 2, 5, 3, 4];
+        titles
+            .iter()
+            .zip(days)
+            .enumerate()
+            .map(|(i, (title, day))| {
+                let number = i as u64 + 1;
+                let state = if number == 5 { "closed" } else { "open" };
+                let mut issue =
+                    create_test_issue("owner", "repo", number, title, "", Some(1), state);
+                issue.updated_at = format!("2025-01-0{day}T00:00:00Z").parse().unwrap();
+                issue
+            })
+            .collect()
+    }
+
+    fn numbers(pairs: &[(&str, &str)]) -> (usize, Vec<u64>) {
+        let (total, page) = IssueListing::parse(&query(pairs))
+            .unwrap()
+            .page_of(issues())
+            .unwrap();
+        (total, page.iter().map(|issue| issue.number).collect())
+    }
+
+    #[test]
+    fn test_issue_listing_defaults() {
+        assert_eq!(
+            IssueListing::parse(&IssueListQuery::default()).unwrap(),
+            IssueListing {
+                page: 1,
+                per_page: DEFAULT_PER_PAGE,
+                state: IssueStateFilter::All,
+                milestone: None,
+                sort: IssueSort::Updated,
+            }
+        );
+        assert_eq!(numbers(&[]), (5, vec![3, 5, 4, 2, 1]));
+    }
+
+    #[test]
+    fn test_issue_listing_filters_sorts_and_pages() {
+        assert_eq!(
+            numbers(&[("state", "open"), ("sort", "title")]),
+            (4, vec![4, 2, 1, 3])
+        );
+        assert_eq!(numbers(&[("state", "closed")]), (1, vec![5]));
+        assert_eq!(
+            numbers(&[("sort", "number"), ("per_page", "2"), ("page", "3")]),
+            (5, vec![5])
+        );
+        assert_eq!(
+            numbers(&[("sort", "number"), ("per_page", "2"), ("page", "2")]),
+            (5, vec![3, 4])
+        );
+    }
+
+    #[test]
+    fn test_issue_listing_out_of_range_page() {
+        let listing = IssueListing::parse(&query(&[("per_page", "2"), ("page", "4")])).unwrap();
+        assert!(matches!(
+            listing.page_of(issues()),
+            Err(ApiError::Invalid {
+                code: "page_out_of_range",
+                ..
+            })
+        ));
+
+        // An empty listing still has a first page
+        let first = IssueListing::parse(&IssueListQuery::default()).unwrap();
+        assert_eq!(first.page_of(Vec::new()).unwrap().0, 0);
+    }
+
+    #[test]
+    fn test_issue_listing_invalid_parameters() {
+        for pairs in [
+            [("page", "0")],
+            [("page", "two")],
+            [("per_page", "0")],
+            [("per_page", "101")],
+            [("state", "merged")],
+            [("milestone", "v1.0")],
+            [("sort", "created")],
+        ] {
+            assert!(
+                matches!(
+                    IssueListing::parse(&query(&pairs)),
+                    Err(ApiError::Invalid {
+                        code: "invalid_query_parameter",
+                        ..
+                    })
+                ),
+                "{pairs:?} should be rejected"
+            );
+        }
+    }
+}
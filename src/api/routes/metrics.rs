@@ -0,0 +1,15 @@
+//! Prometheus metrics endpoint.
+
+use crate::GitProvider;
+use crate::api::state::AppState;
+use axum::{extract::State, http::header, response::IntoResponse};
+
+/// GET /api/metrics
+pub async fn metrics<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics().render(state.disk_cache()),
+    )
+}
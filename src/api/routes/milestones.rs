@@ -6,7 +6,8 @@ use crate::GitProvider;
 use crate::api::error::ApiError;
 use crate::api::state::AppState;
 use crate::api::types::{CreateMilestoneRequest, DetectedRename, Issue, Milestone};
-use crate::detect_renames;
+use crate::utils::normalize_repo_path;
+use crate::{detect_renames, get_milestone_issues_cached, get_milestones_cached};
 use axum::{
     Json,
     extract::{Path, State},
@@ -17,7 +18,7 @@ use axum::{
 pub async fn list_milestones<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
 ) -> Result<Json<Vec<Milestone>>, ApiError> {
-    let milestones = state.git_info().get_milestones().await?;
+    let milestones = get_milestones_cached(state.disk_cache(), state.git_info()).await?;
 
     let response: Vec<Milestone> = milestones.into_iter().map(Milestone::from).collect();
 
@@ -29,6 +30,8 @@ pub async fn create_milestone<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
     Json(request): Json<CreateMilestoneRequest>,
 ) -> Result<(StatusCode, Json<Milestone>), ApiError> {
+    state.require_write_access()?;
+
     if request.name.trim().is_empty() {
         return Err(ApiError::BadRequest(
             "Empty Milestone name not allowed".to_string(),
@@ -38,7 +41,8 @@ pub async fn create_milestone<G: GitProvider + 'static>(
         .git_info()
         .create_milestone(&request.name, &request.description)
         .await
-        .map(Milestone::from)?;
+        .map(Milestone::from)
+        .map_err(|e| state.write_error(e))?;
 
     Ok((StatusCode::CREATED, Json(milestone)))
 }
@@ -48,7 +52,7 @@ pub async fn list_milestone_issues<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
     Path(number): Path<u64>,
 ) -> Result<Json<Vec<Issue>>, ApiError> {
-    let issues = state.git_info().get_issues(Some(number)).await?;
+    let issues = get_milestone_issues_cached(state.disk_cache(), state.git_info(), number).await?;
 
     let response: Vec<Issue> = issues.into_iter().map(Issue::from).collect();
 
@@ -69,7 +73,12 @@ pub async fn list_milestone_renames<G: GitProvider + 'static>(
     let open_issue_paths: Vec<(u64, PathBuf)> = issues
         .into_iter()
         .filter(|i| matches!(i.state, octocrab::models::IssueState::Open))
-        .map(|i| (i.number as u64, PathBuf::from(&i.title)))
+        .map(|i| {
+            (
+                i.number as u64,
+                normalize_repo_path(std::path::Path::new(&i.title)),
+            )
+        })
         .collect();
 
     if open_issue_paths.is_empty() {
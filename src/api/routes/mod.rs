@@ -7,6 +7,7 @@ pub mod configuration;
 pub mod files;
 pub mod health;
 pub mod issues;
+pub mod metrics;
 pub mod milestones;
 pub mod preview;
 pub mod record;
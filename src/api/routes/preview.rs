@@ -8,6 +8,7 @@ use axum::{
 use gix::ObjectId;
 use std::{path::PathBuf, str::FromStr};
 
+use crate::api::routes::comments::approval_scope;
 use crate::api::state::AppState;
 use crate::api::types::{
     ApproveRequest, CreateIssueRequest, PreviousQCDiffPreviewRequest, RelevantIssueClass,
@@ -19,9 +20,11 @@ use crate::create::{
 };
 use crate::issue::IssueThread;
 use crate::relevant_files::{PreviousQCDiffComment, RelevantFile, RelevantFileClass};
+use crate::utils::normalize_repo_path;
 use crate::{CommentBody, api::error::ApiError};
 use crate::{
-    GitProvider, QCApprove, QCComment, QCReview, QCUnapprove, api::types::CreateCommentRequest,
+    GitProvider, IncompleteChecklist, NoDiff, QCApprove, QCComment, QCReview, QCUnapprove,
+    api::types::CreateCommentRequest,
 };
 
 /// POST /api/preview/issue
@@ -37,6 +40,12 @@ pub async fn preview_issue<G: GitProvider + 'static>(
     let configured_author = state.git_info().configured_author();
     let current_user = state.git_info().get_current_user().await.ok().flatten();
     let include_collaborators = state.configuration.read().await.include_collaborators();
+    let template = state
+        .configuration
+        .read()
+        .await
+        .issue_template()
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
     let collaborator_override = request
         .collaborators
         .as_ref()
@@ -72,11 +81,15 @@ pub async fn preview_issue<G: GitProvider + 'static>(
         Checklist {
             name: request.checklist_name.clone(),
             content: request.checklist_content.clone(),
+            inline_file: None,
         },
         relevant_files,
-    );
+    )
+    .with_template(template);
 
-    let markdown = qc_issue.body(state.git_info());
+    let markdown = qc_issue
+        .try_body(state.git_info())
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
     let html = markdown_to_html(&markdown);
 
     Ok(Html(html))
@@ -128,6 +141,7 @@ fn build_relevant_files(request: &CreateIssueRequest) -> Vec<RelevantFile> {
             file_name: PathBuf::from(&rf.file_path),
             class: RelevantFileClass::File {
                 justification: rf.justification.clone(),
+                commit: None,
             },
         });
     }
@@ -161,13 +175,15 @@ pub async fn preview_review<G: GitProvider + 'static>(
     let issue = state.git_info().get_issue(number).await?;
 
     let review = QCReview {
-        file: PathBuf::from(&issue.title),
+        file: normalize_repo_path(std::path::Path::new(&issue.title)),
         issue,
         commit,
         note: request.note,
-        no_diff: !request.include_diff,
+        no_diff: (!request.include_diff).then_some(NoDiff::Stats),
+        force_diff: false,
         stash_after_review: request.auto_stash,
         working_dir: state.git_info().path().to_path_buf(),
+        excel_diff: None,
     };
 
     let markdown = review.generate_body(state.git_info());
@@ -190,10 +206,15 @@ pub async fn preview_approve<G: GitProvider + 'static>(
     let issue = state.git_info().get_issue(number).await?;
 
     let approval = QCApprove {
-        file: PathBuf::from(&issue.title),
+        file: normalize_repo_path(std::path::Path::new(&issue.title)),
         commit,
+        scope: approval_scope(&issue, &commit, &state).await,
+        // Approvals refused for an incomplete checklist are never posted, so the unchecked items
+        // are shown as they would be recorded with `allow_incomplete_checklist`
+        incomplete_checklist: IncompleteChecklist::from_issue(&issue),
         issue,
         note: request.note,
+        effective_date: request.effective_date,
     };
 
     let markdown = approval.generate_body(state.git_info());
@@ -212,9 +233,11 @@ pub async fn preview_unapprove<G: GitProvider + 'static>(
 ) -> Result<Html<String>, ApiError> {
     let issue = state.git_info().get_issue(number).await?;
 
+    let issue_thread =
+        IssueThread::from_issue(&issue, state.disk_cache(), state.git_info()).await?;
     let unapprove = QCUnapprove {
-        issue,
-        reason: request.reason,
+        effective_date: request.effective_date,
+        ..QCUnapprove::new(issue, &issue_thread, request.reason)
     };
 
     let markdown = unapprove.generate_body(state.git_info());
@@ -252,12 +275,16 @@ pub async fn preview_comment<G: GitProvider + 'static>(
         .map_err(|e| ApiError::BadRequest(format!("Invalid previous commit format: {e}")))?;
 
     let qc_comment = QCComment {
-        file: PathBuf::from(&issue.title),
+        file: normalize_repo_path(std::path::Path::new(&issue.title)),
         issue,
         current_commit,
         previous_commit,
         note: request.note,
-        no_diff: !request.include_diff,
+        no_diff: (!request.include_diff).then_some(NoDiff::Stats),
+        force_diff: false,
+        excel_diff: None,
+        renames: Vec::new(),
+        environment: Vec::new(),
     };
 
     let markdown = qc_comment.generate_body(state.git_info());
@@ -288,7 +315,7 @@ pub async fn preview_previous_qc_diff<G: GitProvider + 'static>(
         issue: prev_issue,
         prev_file: PathBuf::from(&request.previous_file),
         current_file: PathBuf::from(&request.current_file),
-        prev_commit: thread.latest_commit().hash,
+        prev_commit: thread.latest_approved_or_activity().hash,
         current_commit,
         prev_issue_number: request.previous_issue_number,
     };
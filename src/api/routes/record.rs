@@ -4,20 +4,28 @@ use axum::extract::Multipart;
 use axum::{
     Json,
     body::Bytes,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{HeaderValue, StatusCode, header},
-    response::IntoResponse,
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
+use futures::{Stream, StreamExt};
 use serde::Deserialize;
 use std::path::PathBuf;
 
 use crate::{
-    ContextPosition, GitProvider, QCContext, UreqDownloader,
+    ContextPosition, Diagnostics, GitProvider, MilestoneSnapshot, QCContext, RecordFormat,
+    RecordOptions, TlsSettings, UreqDownloader,
+    api::record_jobs::{ProgressReporter, RecordProgress},
     api::types::{
-        RecordContextPosition, RecordPreviewResponse, RecordRequest, RecordUploadResponse,
+        RecordContextPosition, RecordJobResponse, RecordPreviewResponse, RecordRequest,
+        RecordUploadResponse,
     },
     api::{error::ApiError, state::AppState},
-    create_staging_dir, fetch_milestone_issues, get_milestone_issue_information, record, render,
+    create_staging_dir, get_milestone_issue_information, get_milestones_cached, qc_coverage,
+    record, render,
     utils::StdEnvProvider,
 };
 
@@ -84,16 +92,18 @@ pub async fn upload_context_file<G: GitProvider + 'static>(
     }))
 }
 
-/// Shared helper: run the full record pipeline, returning the output PDF path.
+/// Shared helper: run the full record pipeline, writing the PDF to `output_path`.
 async fn run_record_pipeline<G: GitProvider + 'static>(
     state: &AppState<G>,
     request: &RecordRequest,
     output_path: PathBuf,
+    progress: &ProgressReporter,
 ) -> Result<(), ApiError> {
     let git_info = state.git_info().clone();
+    progress.report(RecordProgress::FetchingIssues);
 
     // Fetch all milestones and filter to the requested ones
-    let all_milestones = git_info.get_milestones().await?;
+    let all_milestones = get_milestones_cached(state.disk_cache(), &git_info).await?;
     let selected_milestones: Vec<octocrab::models::Milestone> = all_milestones
         .into_iter()
         .filter(|m| request.milestone_numbers.contains(&(m.number as u64)))
@@ -105,26 +115,67 @@ async fn run_record_pipeline<G: GitProvider + 'static>(
         ));
     }
 
-    // Fetch issues for each selected milestone
-    let milestone_issues = fetch_milestone_issues(&selected_milestones, &git_info)
-        .await
+    // Fetch issues, comments and events for each selected milestone
+    let milestone_refs = selected_milestones.iter().collect::<Vec<_>>();
+    let snapshot = MilestoneSnapshot::build_with_progress(
+        &milestone_refs,
+        &git_info,
+        state.disk_cache(),
+        |issue, total| progress.report(RecordProgress::FetchingComments { issue, total }),
+    )
+    .await
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let threads = snapshot
+        .threads(&git_info, state.disk_cache())
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
     // Create staging directory (used for images, logo, template)
     let staging_dir = create_staging_dir().map_err(|e| ApiError::Internal(e.to_string()))?;
 
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.check_snapshot(&snapshot);
+
     // Download images and build detailed issue information
-    let http_downloader = UreqDownloader::new();
+    progress.report(RecordProgress::DownloadingImages);
+    // The settings were validated when the server's repository was opened
+    let tls = TlsSettings::from_env(&StdEnvProvider).unwrap_or_default();
+    let http_downloader = UreqDownloader::with_tls(&tls);
+    let signing_keys = state.configuration.read().await.signing_keys();
     let issue_information = get_milestone_issue_information(
-        &milestone_issues,
+        &threads,
         state.disk_cache(),
+        &signing_keys,
         &git_info,
         &http_downloader,
         &staging_dir,
+        &mut diagnostics,
     )
     .await
     .map_err(|e| ApiError::Internal(e.to_string()))?;
 
+    if request.strict {
+        diagnostics
+            .ensure_strict()
+            .map_err(|e| ApiError::Conflict(e.to_string()))?;
+    }
+
+    let coverage = match &request.coverage_paths {
+        Some(paths) => {
+            let scope = paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+            Some(
+                qc_coverage(&git_info, &scope, &threads)
+                    .map_err(|e| ApiError::BadRequest(e.to_string()))?,
+            )
+        }
+        None => None,
+    };
+    let options = RecordOptions {
+        only_tables: request.tables_only,
+        coverage,
+        format: RecordFormat::Pdf,
+        appendix: request.appendix,
+    };
+
     // Generate Typst markup
     let configuration = state.configuration.read().await;
     let env = StdEnvProvider;
@@ -134,7 +185,7 @@ async fn run_record_pipeline<G: GitProvider + 'static>(
         &configuration,
         &git_info,
         &env,
-        request.tables_only,
+        &options,
         &staging_dir,
     )
     .map_err(|e| ApiError::Internal(e.to_string()))?;
@@ -153,12 +204,14 @@ async fn run_record_pipeline<G: GitProvider + 'static>(
         .collect();
 
     // Render Typst to PDF (synchronous, potentially slow — runs in blocking task)
+    progress.report(RecordProgress::RenderingPdf);
     let http_for_render = http_downloader.clone();
     let staging_for_render = staging_dir.clone();
     tokio::task::spawn_blocking(move || {
         render(
             &record_str,
             &output_path,
+            RecordFormat::Pdf,
             &staging_for_render,
             &qc_contexts,
             None, // disk cache not needed for Typst packages in API context
@@ -182,7 +235,13 @@ pub async fn preview_record<G: GitProvider + 'static>(
     let key = uuid::Uuid::new_v4().to_string();
     let output_path = std::env::temp_dir().join(format!("ghqc-preview-{key}.pdf"));
 
-    run_record_pipeline(&state, &request, output_path.clone()).await?;
+    run_record_pipeline(
+        &state,
+        &request,
+        output_path.clone(),
+        &ProgressReporter::default(),
+    )
+    .await?;
 
     state.preview_store().await.insert(key.clone(), output_path);
 
@@ -229,13 +288,11 @@ pub async fn serve_preview_pdf<G: GitProvider + 'static>(
     Ok(response)
 }
 
-/// POST /api/record/generate
-///
-/// Runs the record pipeline and writes the PDF to `request.output_path`.
-pub async fn generate_record<G: GitProvider + 'static>(
-    State(state): State<AppState<G>>,
-    Json(request): Json<RecordRequest>,
-) -> Result<StatusCode, ApiError> {
+/// Resolve `request.output_path` for a generated record and create its parent directory
+async fn record_output_path<G: GitProvider + 'static>(
+    state: &AppState<G>,
+    request: &RecordRequest,
+) -> Result<PathBuf, ApiError> {
     if request.output_path.is_empty() {
         return Err(ApiError::BadRequest("output_path is required".to_string()));
     }
@@ -256,7 +313,59 @@ pub async fn generate_record<G: GitProvider + 'static>(
             .map_err(|e| ApiError::Internal(format!("Failed to create output directory: {e}")))?;
     }
 
-    run_record_pipeline(&state, &request, output_path).await?;
+    Ok(output_path)
+}
+
+/// POST /api/record/generate
+///
+/// Runs the record pipeline and writes the PDF to `request.output_path`.
+pub async fn generate_record<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    Json(request): Json<RecordRequest>,
+) -> Result<StatusCode, ApiError> {
+    let output_path = record_output_path(&state, &request).await?;
+
+    run_record_pipeline(&state, &request, output_path, &ProgressReporter::default()).await?;
 
     Ok(StatusCode::OK)
 }
+
+/// POST /api/record
+///
+/// Starts generating the record to `request.output_path` in the background and returns the job
+/// id, whose progress is streamed by `GET /api/record/progress/{job_id}`.
+pub async fn start_record<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    Json(request): Json<RecordRequest>,
+) -> Result<(StatusCode, Json<RecordJobResponse>), ApiError> {
+    let output_path = record_output_path(&state, &request).await?;
+
+    let job_state = state.clone();
+    let job_id = state.record_jobs().start(move |progress| async move {
+        run_record_pipeline(&job_state, &request, output_path.clone(), &progress)
+            .await
+            .map(|()| output_path)
+            .map_err(|e| e.to_string())
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(RecordJobResponse { job_id })))
+}
+
+/// GET /api/record/progress/{job_id}
+///
+/// Server-sent events with the stages of a record job, ending with its completion or error.
+/// Clients connecting late first receive the events sent so far.
+pub async fn record_progress<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    Path(job_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let events = state
+        .record_jobs()
+        .subscribe(&job_id)
+        .ok_or_else(|| ApiError::NotFound(format!("Record job not found: {job_id}")))?;
+
+    Ok(
+        Sse::new(events.map(|progress| Event::default().json_data(progress)))
+            .keep_alive(KeepAlive::default()),
+    )
+}
@@ -26,6 +26,6 @@ pub async fn list_assignees<G: GitProvider + 'static>(
 pub async fn repo_info<G: GitProvider + 'static>(
     State(state): State<AppState<G>>,
 ) -> Result<Json<RepoInfoResponse>, ApiError> {
-    let response = RepoInfoResponse::new(state.git_info()).await?;
+    let response = RepoInfoResponse::new(state.git_info(), !state.write_enabled()).await?;
     Ok(Json(response))
 }
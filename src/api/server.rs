@@ -1,19 +1,20 @@
 //! Axum server setup and router assembly.
 
 use crate::api::routes::{
-    archive, comments, commits, configuration, files, health, issues, milestones, preview, record,
-    status,
+    archive, comments, commits, configuration, files, health, issues, metrics, milestones, preview,
+    record, status,
 };
 use crate::api::state::AppState;
-use crate::{GitCli, GitProvider};
+use crate::{GitCli, GitProvider, count_writes};
 use axum::{
     Router,
-    extract::{DefaultBodyLimit, Request},
+    extract::{DefaultBodyLimit, MatchedPath, Request, State},
     middleware::{self, Next},
     response::Response,
     routing::{get, post},
 };
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Instant;
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
@@ -28,6 +29,54 @@ async fn log_request(req: Request, next: Next) -> Response {
     next.run(req).await
 }
 
+/// Record the latency of each request under the route it matched, keeping the label set bounded,
+/// and append write requests to the usage log
+async fn record_metrics<G: GitProvider + 'static>(
+    State(state): State<AppState<G>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", |path| path.as_str())
+        .to_string();
+    let start = Instant::now();
+    let Some(usage_log) = state.usage_log().filter(|_| method != "GET") else {
+        let response = next.run(req).await;
+        state
+            .metrics()
+            .record_request(&method, &route, start.elapsed());
+        invalidate_listings_after_write(&state, &method);
+        return response;
+    };
+
+    let (response, counts) = count_writes(next.run(req)).await;
+    invalidate_listings_after_write(&state, &method);
+    let elapsed = start.elapsed();
+    state.metrics().record_request(&method, &route, elapsed);
+    // Without the leading `/api/`, which would read as a path to redact
+    let route = route.trim_start_matches("/api/");
+    usage_log.record(
+        &format!("api {method} {route}"),
+        elapsed,
+        response.status().is_success(),
+        counts,
+    );
+    response
+}
+
+/// Drop the cached milestone and issue listings after a write request, which may have created
+/// issues or milestones or changed an issue's state
+fn invalidate_listings_after_write<G: GitProvider>(state: &AppState<G>, method: &str) {
+    if method != "GET"
+        && let Some(cache) = state.disk_cache()
+    {
+        cache.invalidate_listings();
+    }
+}
+
 /// Create the API router with all routes.
 pub fn create_router<G: GitProvider + 'static, C: GitCli + Send + Sync + 'static>(
     state: AppState<G>,
@@ -43,6 +92,7 @@ pub fn create_router<G: GitProvider + 'static, C: GitCli + Send + Sync + 'static
     Router::new()
         // Health
         .route("/api/health", get(health::health_check))
+        .route("/api/metrics", get(metrics::metrics))
         // Milestones
         .route("/api/milestones", get(milestones::list_milestones))
         .route("/api/milestones", post(milestones::create_milestone))
@@ -55,6 +105,10 @@ pub fn create_router<G: GitProvider + 'static, C: GitCli + Send + Sync + 'static
             get(milestones::list_milestone_renames),
         )
         // Issues
+        .route(
+            "/api/issues",
+            get(issues::list_issues).post(issues::create_issue),
+        )
         .route("/api/issues/status", get(issues::batch_get_issue_status))
         .route("/api/issues/{number}", get(issues::get_issue))
         .route(
@@ -118,7 +172,13 @@ pub fn create_router<G: GitProvider + 'static, C: GitCli + Send + Sync + 'static
         .route("/api/record/preview", post(record::preview_record))
         .route("/api/record/preview.pdf", get(record::serve_preview_pdf))
         .route("/api/record/generate", post(record::generate_record))
+        .route("/api/record", post(record::start_record))
+        .route(
+            "/api/record/progress/{job_id}",
+            get(record::record_progress),
+        )
         // Archive
+        .route("/api/archive", post(archive::download_archive))
         .route("/api/archive/generate", post(archive::generate_archive))
         // Configuration
         .route(
@@ -126,6 +186,10 @@ pub fn create_router<G: GitProvider + 'static, C: GitCli + Send + Sync + 'static
             get(configuration::get_configuration)
                 .post(configuration::setup_configuration_repo::<G, C>),
         )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            record_metrics::<G>,
+        ))
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .layer(middleware::from_fn(log_request))
@@ -1,9 +1,15 @@
 //! Application state for the API server.
 
-use crate::{Configuration, DiskCache, GitProvider};
+use crate::api::error::ApiError;
+use crate::api::metrics::ApiMetrics;
+use crate::api::record_jobs::RecordJobs;
+use crate::{
+    CacheHealth, Configuration, DiskCache, GitHubApiError, GitProvider, UsageLog, set_diff_settings,
+};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::{Mutex, RwLock};
 
 /// Application state shared across all request handlers.
@@ -20,12 +26,23 @@ pub struct AppState<G: GitProvider> {
     configuration_git_info: Arc<RwLock<Option<G>>>,
     /// Disk-based cache for GitHub API responses.
     disk_cache: Option<Arc<DiskCache>>,
+    /// Whether the disk cache could be opened, reported by the health check
+    cache_health: CacheHealth,
     /// Configuration git_info update
     pub config_git_info_creator: Arc<dyn Fn(&Path) -> Option<G> + Send + Sync + 'static>,
     /// Git Cli trait
     // git_cli: Arc<RwLock<Option<C>>>,
     /// Preview PDF store: UUID key → temp file path
     preview_store: Arc<Mutex<HashMap<String, PathBuf>>>,
+    /// Record generations running in the background, whose progress is streamed over SSE
+    record_jobs: RecordJobs,
+    /// Whether the GitHub token can write. Cleared at startup for read-only tokens, or on the
+    /// first write GitHub rejects as read-only.
+    write_enabled: Arc<AtomicBool>,
+    /// Request, GitHub and cache counters served at `/api/metrics`
+    metrics: Arc<ApiMetrics>,
+    /// Local usage log that write requests are appended to, when `usage_stats` is on
+    usage_log: Option<UsageLog>,
 }
 
 impl<G: GitProvider> AppState<G> {
@@ -36,16 +53,51 @@ impl<G: GitProvider> AppState<G> {
         configuration_git_info: Option<G>,
         disk_cache: Option<DiskCache>,
     ) -> Self {
+        set_diff_settings(configuration.diff_settings());
         Self {
             git_info: Arc::new(git_info),
             configuration: Arc::new(RwLock::new(configuration)),
             configuration_git_info: Arc::new(RwLock::new(configuration_git_info)),
+            cache_health: CacheHealth {
+                enabled: disk_cache.is_some(),
+                path: disk_cache.as_ref().map(|cache| cache.root.clone()),
+                source: None,
+                reason: disk_cache
+                    .is_none()
+                    .then(|| "No disk cache was opened".to_string()),
+            },
             disk_cache: disk_cache.map(Arc::new),
             config_git_info_creator: Arc::new(|_| None),
             preview_store: Arc::new(Mutex::new(HashMap::new())),
+            record_jobs: RecordJobs::default(),
+            write_enabled: Arc::new(AtomicBool::new(true)),
+            metrics: Arc::new(ApiMetrics::default()),
+            usage_log: None,
         }
     }
 
+    pub fn with_write_enabled(self, write_enabled: bool) -> Self {
+        self.write_enabled.store(write_enabled, Ordering::Relaxed);
+        self
+    }
+
+    pub fn with_cache_health(mut self, cache_health: CacheHealth) -> Self {
+        self.cache_health = cache_health;
+        self
+    }
+
+    /// Share `metrics` with the [`MeteredGit`](crate::api::MeteredGit) wrapping the git provider,
+    /// so GitHub calls show up alongside the request metrics
+    pub fn with_metrics(mut self, metrics: Arc<ApiMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_usage_log(mut self, usage_log: Option<UsageLog>) -> Self {
+        self.usage_log = usage_log;
+        self
+    }
+
     pub fn with_creator(
         mut self,
         creator: impl Fn(&Path) -> Option<G> + Send + Sync + 'static,
@@ -62,6 +114,18 @@ impl<G: GitProvider> AppState<G> {
         self.disk_cache.as_ref().map(|d| &**d)
     }
 
+    pub fn cache_health(&self) -> &CacheHealth {
+        &self.cache_health
+    }
+
+    pub fn metrics(&self) -> &ApiMetrics {
+        &self.metrics
+    }
+
+    pub fn usage_log(&self) -> Option<&UsageLog> {
+        self.usage_log.as_ref()
+    }
+
     pub async fn configuration_git_info(&self) -> Option<G> {
         self.configuration_git_info.read().await.clone()
     }
@@ -72,6 +136,35 @@ impl<G: GitProvider> AppState<G> {
         *config_git_info = git_info.clone();
     }
 
+    pub fn write_enabled(&self) -> bool {
+        self.write_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Reject a write request up front when the token is known to be read-only
+    pub fn require_write_access(&self) -> Result<(), ApiError> {
+        if self.write_enabled() {
+            Ok(())
+        } else {
+            Err(ApiError::ReadOnlyToken(
+                "The GitHub token is read-only. Authenticate with a token that has write access to make changes".to_string(),
+            ))
+        }
+    }
+
+    /// Convert an error from a GitHub write, disabling further writes if GitHub rejected it
+    /// because the token is read-only
+    pub fn write_error(&self, err: GitHubApiError) -> ApiError {
+        if err.is_read_only_token() {
+            log::warn!("Disabling write routes: {err}");
+            self.write_enabled.store(false, Ordering::Relaxed);
+        }
+        ApiError::from(err)
+    }
+
+    pub fn record_jobs(&self) -> &RecordJobs {
+        &self.record_jobs
+    }
+
     pub async fn preview_store(&self) -> tokio::sync::MutexGuard<'_, HashMap<String, PathBuf>> {
         self.preview_store.lock().await
     }
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use http::StatusCode;
+use http::{HeaderMap, StatusCode};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -34,10 +34,11 @@ impl<'a> ResponseAsserter<'a> {
         Self { expected }
     }
 
-    /// Validate the response status and body
+    /// Validate the response status, headers and body
     pub fn validate(
         &self,
         actual_status: StatusCode,
+        actual_headers: &HeaderMap,
         actual_body: Option<&Value>,
     ) -> Result<(), ValidationError> {
         let mut errors = Vec::new();
@@ -56,6 +57,19 @@ impl<'a> ResponseAsserter<'a> {
             ));
         }
 
+        // Check headers if specified
+        for (name, expected_value) in &self.expected.headers {
+            match actual_headers.get(name).map(|value| value.to_str()) {
+                Some(Ok(value)) if value == expected_value => {}
+                Some(Ok(value)) => errors.push(format!(
+                    "Header '{}' mismatch: expected '{}', got '{}'",
+                    name, expected_value, value
+                )),
+                Some(Err(_)) => errors.push(format!("Header '{}' is not valid text", name)),
+                None => errors.push(format!("Expected header '{}', but got none", name)),
+            }
+        }
+
         // Check body if specified
         if let Some(expected_body) = &self.expected.body {
             match actual_body {
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::api::tests::harness::types::UserSource;
-use crate::git::RepoUser;
+use crate::git::{GitComment, RepoUser};
 use crate::test_utils::{create_test_issue, create_test_milestone};
 
 use super::types::{BlockingRelationship, Fixtures, IssueSource, MilestoneSource};
@@ -27,6 +27,8 @@ pub struct LoadedFixtures {
     pub users: Vec<RepoUser>,
     /// Blocking relationships from YAML
     pub blocking: Vec<BlockingRelationship>,
+    /// Comments keyed by issue number
+    pub comments: HashMap<u64, Vec<GitComment>>,
 }
 
 impl FixtureLoader {
@@ -49,6 +51,7 @@ impl FixtureLoader {
         let mut issues = HashMap::new();
         let mut milestones = HashMap::new();
         let mut users = Vec::new();
+        let mut comments = HashMap::new();
 
         // Load or create issues
         for issue_source in &fixtures.issues {
@@ -60,15 +63,27 @@ impl FixtureLoader {
                     body,
                     state,
                     milestone,
-                } => create_test_issue(
-                    &git_state.owner,
-                    &git_state.repo,
-                    *number,
-                    title,
-                    body,
-                    *milestone,
-                    state,
-                ),
+                    comments: comment_bodies,
+                } => {
+                    if !comment_bodies.is_empty() {
+                        comments.insert(
+                            *number,
+                            comment_bodies
+                                .iter()
+                                .map(|body| test_comment(body))
+                                .collect(),
+                        );
+                    }
+                    create_test_issue(
+                        &git_state.owner,
+                        &git_state.repo,
+                        *number,
+                        title,
+                        body,
+                        *milestone,
+                        state,
+                    )
+                }
             };
             issues.insert(issue.number, issue);
         }
@@ -110,6 +125,7 @@ impl FixtureLoader {
             milestones,
             users,
             blocking: fixtures.blocking.clone(),
+            comments,
         })
     }
 
@@ -171,3 +187,13 @@ impl FixtureLoader {
         Ok(users)
     }
 }
+
+/// A comment by the default test user
+fn test_comment(body: &str) -> GitComment {
+    GitComment {
+        body: body.to_string(),
+        author_login: "test-user".to_string(),
+        created_at: chrono::DateTime::UNIX_EPOCH,
+        html: None,
+    }
+}
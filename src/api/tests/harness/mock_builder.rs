@@ -19,13 +19,19 @@ impl MockBuilder {
             .with_commit(&git_state.commit)
             .with_branch(&git_state.branch)
             .with_remote_commit(&git_state.remote_commit)
-            .with_current_user(git_state.current_user.clone());
+            .with_current_user(git_state.current_user.clone())
+            .with_read_only_token(git_state.read_only_token);
 
         // Add all issues
         for (number, issue) in &fixtures.issues {
             builder = builder.with_issue(*number, issue.clone());
         }
 
+        // Add issue comments
+        for (number, comments) in &fixtures.comments {
+            builder = builder.with_issue_comments(*number, comments.clone());
+        }
+
         // Add all milestones
         for (_number, milestone) in &fixtures.milestones {
             builder = builder.with_milestone(milestone.clone());
@@ -52,7 +52,8 @@ impl TestRunner {
 
         // Create AppState and router (clone mock to keep a reference for assertions)
         let config = Configuration::default();
-        let state = AppState::new(mock.clone(), config, Some(mock.clone()), None);
+        let state = AppState::new(mock.clone(), config, Some(mock.clone()), None)
+            .with_write_enabled(test_case.git_state.write_enabled);
         let app = create_router::<_, GitCommand>(state);
 
         // Build HTTP request
@@ -68,6 +69,7 @@ impl TestRunner {
 
         // Extract status and body
         let status = response.status();
+        let headers = response.headers().clone();
         let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
             .context("Failed to read response body")?;
@@ -88,7 +90,7 @@ impl TestRunner {
 
         // Validate response
         let asserter = ResponseAsserter::new(&test_case.response);
-        let validation = asserter.validate(status, body_json.as_ref());
+        let validation = asserter.validate(status, &headers, body_json.as_ref());
 
         // Validate write calls if specified
         let write_call_validation = if !test_case.assert_write_calls.is_empty() {
@@ -57,6 +57,9 @@ pub enum IssueSource {
         state: String,
         #[serde(default)]
         milestone: Option<i64>,
+        /// Bodies of the issue's comments, oldest first
+        #[serde(default)]
+        comments: Vec<String>,
     },
 }
 
@@ -130,6 +133,13 @@ pub struct GitState {
     /// Current authenticated user (defaults to "test-user", set to null to simulate unauthenticated)
     #[serde(default = "default_current_user")]
     pub current_user: Option<String>,
+    /// Whether the server starts with writes enabled (set to false to simulate a token detected
+    /// as read-only at startup)
+    #[serde(default = "default_write_enabled")]
+    pub write_enabled: bool,
+    /// Whether GitHub rejects every write as coming from a read-only token
+    #[serde(default)]
+    pub read_only_token: bool,
 }
 
 /// Git status specification for tests
@@ -160,6 +170,8 @@ impl Default for GitState {
             status: None,
             remote_commit: default_remote_commit(),
             current_user: default_current_user(),
+            write_enabled: default_write_enabled(),
+            read_only_token: false,
         }
     }
 }
@@ -188,6 +200,10 @@ fn default_current_user() -> Option<String> {
     Some("test-user".to_string())
 }
 
+fn default_write_enabled() -> bool {
+    true
+}
+
 /// HTTP request specification
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HttpRequest {
@@ -222,6 +238,9 @@ pub struct ExpectedResponse {
     /// Expected response body (optional)
     #[serde(default)]
     pub body: Option<ResponseBody>,
+    /// Expected response headers, e.g. `content-type` of a download (optional)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
 }
 
 /// Response body assertion configuration
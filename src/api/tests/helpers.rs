@@ -3,9 +3,12 @@
 use crate::CommentBody;
 use crate::git::{
     FileStashOutcome, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers, GitRepository,
-    GitRepositoryError, GitState, GitStatusError, GitStatusOps,
+    GitRepositoryError, GitState, GitStatusError, GitStatusOps, SignatureStatus, SigningKeys,
+};
+use crate::git::{GitComment, GitLabError};
+use crate::{
+    GitAuthor, GitCommit, GitHubApiError, GitHubReader, GitHubWriter, Permission, RequestTarget,
 };
-use crate::{GitAuthor, GitCommit, GitHubApiError, GitHubReader, GitHubWriter};
 use gix::ObjectId;
 use octocrab::models::issues::Issue;
 use std::collections::HashMap;
@@ -13,6 +16,14 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
+/// A 404 response, as the forge returns for a missing resource
+fn not_found(operation: &'static str, target: RequestTarget) -> GitHubApiError {
+    GitHubApiError::gitlab_with_ctx(operation, target)(GitLabError::Status {
+        status: http::StatusCode::NOT_FOUND,
+        message: "Not Found".to_string(),
+    })
+}
+
 /// Tracks write operation calls for test verification.
 #[derive(Debug, Clone, PartialEq)]
 pub enum WriteCall {
@@ -39,6 +50,14 @@ pub enum WriteCall {
         issue_number: u64,
         new_title: Option<String>,
     },
+    UpdateMilestoneTitle {
+        milestone_number: u64,
+        new_title: String,
+    },
+    UpdateMilestone {
+        milestone_number: u64,
+        update: crate::MilestoneUpdate,
+    },
 }
 
 /// Mock implementation of all git traits for testing.
@@ -53,6 +72,7 @@ pub struct MockGitInfo {
 
     // Mock data storage
     issues: Arc<Mutex<HashMap<u64, Issue>>>,
+    issue_comments: Arc<Mutex<HashMap<u64, Vec<GitComment>>>>,
     blocked_issues: Arc<Mutex<HashMap<u64, Vec<Issue>>>>,
     milestones: Arc<Mutex<Vec<octocrab::models::Milestone>>>,
     users: Arc<Mutex<Vec<crate::RepoUser>>>,
@@ -64,6 +84,7 @@ pub struct MockGitInfo {
 
     // Authentication
     current_user: Option<String>,
+    read_only_token: bool,
 
     // Call tracking (for assertions)
     calls: Arc<Mutex<Vec<String>>>,
@@ -80,6 +101,17 @@ impl MockGitInfo {
     pub fn write_calls(&self) -> Vec<WriteCall> {
         self.write_calls.lock().unwrap().clone()
     }
+
+    /// Reject a write with GitHub's 403 for read-only tokens, if configured
+    fn check_write_access(&self) -> Result<(), GitHubApiError> {
+        if self.read_only_token {
+            Err(GitHubApiError::ReadOnlyToken(
+                "Resource not accessible by personal access token".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 /// Builder for MockGitInfo.
@@ -90,12 +122,14 @@ pub struct MockGitInfoBuilder {
     branch: String,
     remote_commit: String,
     issues: HashMap<u64, Issue>,
+    issue_comments: HashMap<u64, Vec<GitComment>>,
     blocked_issues: HashMap<u64, Vec<Issue>>,
     milestones: Vec<octocrab::models::Milestone>,
     users: Vec<crate::RepoUser>,
     dirty_files: Vec<PathBuf>,
     git_state: GitState,
     current_user: Option<String>,
+    read_only_token: bool,
     stash_error: Option<String>,
 }
 
@@ -108,12 +142,14 @@ impl MockGitInfoBuilder {
             branch: "main".to_string(),
             remote_commit: "def4567890abcdef4567890abcdef4567890abc0".to_string(),
             issues: HashMap::new(),
+            issue_comments: HashMap::new(),
             blocked_issues: HashMap::new(),
             milestones: Vec::new(),
             users: Vec::new(),
             dirty_files: Vec::new(),
             git_state: GitState::Clean,
             current_user: Some("test-user".to_string()),
+            read_only_token: false,
             stash_error: None,
         }
     }
@@ -148,6 +184,11 @@ impl MockGitInfoBuilder {
         self
     }
 
+    pub fn with_issue_comments(mut self, number: u64, comments: Vec<GitComment>) -> Self {
+        self.issue_comments.insert(number, comments);
+        self
+    }
+
     pub fn with_blocked_issues(mut self, issue_number: u64, blocking: Vec<Issue>) -> Self {
         self.blocked_issues.insert(issue_number, blocking);
         self
@@ -178,6 +219,12 @@ impl MockGitInfoBuilder {
         self
     }
 
+    /// Reject every write the way GitHub does for a token without write scopes
+    pub fn with_read_only_token(mut self, read_only_token: bool) -> Self {
+        self.read_only_token = read_only_token;
+        self
+    }
+
     pub fn with_stash_error(mut self, error: impl Into<String>) -> Self {
         self.stash_error = Some(error.into());
         self
@@ -191,12 +238,14 @@ impl MockGitInfoBuilder {
             current_branch: self.branch,
             remote_commit: self.remote_commit,
             issues: Arc::new(Mutex::new(self.issues)),
+            issue_comments: Arc::new(Mutex::new(self.issue_comments)),
             blocked_issues: Arc::new(Mutex::new(self.blocked_issues)),
             milestones: Arc::new(Mutex::new(self.milestones)),
             users: Arc::new(Mutex::new(self.users)),
             dirty_files: Arc::new(Mutex::new(self.dirty_files)),
             git_state: self.git_state,
             current_user: self.current_user,
+            read_only_token: self.read_only_token,
             stash_error: self.stash_error,
             calls: Arc::new(Mutex::new(Vec::new())),
             write_calls: Arc::new(Mutex::new(Vec::new())),
@@ -339,6 +388,13 @@ impl GitCommitOps for MockGitInfo {
         Ok(std::iter::once(commit_hash.to_string()).collect())
     }
 
+    fn files_by_last_commit(
+        &self,
+        _branch: &Option<String>,
+    ) -> Result<Vec<crate::FileLastCommit>, GitFileOpsError> {
+        Ok(Vec::new())
+    }
+
     fn get_branches_containing_commit(
         &self,
         _commit: &ObjectId,
@@ -352,6 +408,39 @@ impl GitCommitOps for MockGitInfo {
     ) -> Result<Option<String>, GitFileOpsError> {
         Ok(None)
     }
+
+    fn find_merge_commit(
+        &self,
+        _target_commit: &ObjectId,
+    ) -> Result<Option<ObjectId>, GitFileOpsError> {
+        Ok(None)
+    }
+
+    fn signature_status(
+        &self,
+        _commit: &ObjectId,
+        _keys: &SigningKeys,
+    ) -> Result<SignatureStatus, GitFileOpsError> {
+        Ok(SignatureStatus::Unsigned)
+    }
+
+    fn commit_time(
+        &self,
+        _commit: &ObjectId,
+    ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+        Ok(chrono::DateTime::UNIX_EPOCH)
+    }
+
+    fn commit_author(&self, _commit: &ObjectId) -> Result<crate::GitAuthor, GitFileOpsError> {
+        Ok(crate::GitAuthor {
+            name: "Test Author".to_string(),
+            email: "test@example.com".to_string(),
+        })
+    }
+
+    fn is_shallow(&self) -> bool {
+        false
+    }
 }
 
 impl GitFileOps for MockGitInfo {
@@ -384,6 +473,18 @@ impl GitFileOps for MockGitInfo {
             _ => Err(GitFileOpsError::DirectoryNotFound(path.to_string())),
         }
     }
+
+    fn path_attributes(
+        &self,
+        _file: &std::path::Path,
+    ) -> Result<crate::git::PathAttributes, GitFileOpsError> {
+        Ok(Default::default())
+    }
+
+    fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+        ObjectId::from_str(commitish)
+            .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+    }
 }
 
 impl GitHubReader for MockGitInfo {
@@ -425,7 +526,7 @@ impl GitHubReader for MockGitInfo {
             .unwrap()
             .get(&issue_number)
             .cloned()
-            .ok_or_else(|| GitHubApiError::NoApi);
+            .ok_or_else(|| not_found("get_issue", RequestTarget::Issue(issue_number)));
 
         eprintln!(
             "MockGitInfo::get_issue({}) result: {:?}",
@@ -461,17 +562,32 @@ impl GitHubReader for MockGitInfo {
             }))
     }
 
-    async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
-        Ok(vec![])
+    async fn get_user_permission(&self, _login: &str) -> Result<Permission, GitHubApiError> {
+        Ok(Permission::Write)
     }
 
-    async fn get_issue_comments(
+    async fn get_team_members(
         &self,
-        _issue: &Issue,
-    ) -> Result<Vec<crate::GitComment>, GitHubApiError> {
+        _org: &str,
+        _team_slug: &str,
+    ) -> Result<Vec<String>, GitHubApiError> {
+        Ok(vec![])
+    }
+
+    async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
         Ok(vec![])
     }
 
+    async fn get_issue_comments(&self, issue: &Issue) -> Result<Vec<GitComment>, GitHubApiError> {
+        Ok(self
+            .issue_comments
+            .lock()
+            .unwrap()
+            .get(&issue.number)
+            .cloned()
+            .unwrap_or_default())
+    }
+
     async fn get_issue_events(
         &self,
         _issue: &Issue,
@@ -507,6 +623,8 @@ impl GitHubWriter for MockGitInfo {
         name: &str,
         desc: &Option<String>,
     ) -> Result<octocrab::models::Milestone, GitHubApiError> {
+        self.check_write_access()?;
+
         use chrono::Utc;
 
         // Track the call with parameters
@@ -553,7 +671,79 @@ impl GitHubWriter for MockGitInfo {
         })
     }
 
+    async fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> Result<octocrab::models::Milestone, GitHubApiError> {
+        self.check_write_access()?;
+
+        self.write_calls
+            .lock()
+            .unwrap()
+            .push(WriteCall::UpdateMilestoneTitle {
+                milestone_number,
+                new_title: new_title.to_string(),
+            });
+
+        let mut milestones = self.milestones.lock().unwrap();
+        let milestone = milestones
+            .iter_mut()
+            .find(|m| m.number as u64 == milestone_number)
+            .ok_or(GitHubApiError::NoApi)?;
+        milestone.title = new_title.to_string();
+        Ok(milestone.clone())
+    }
+
+    async fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &crate::MilestoneUpdate,
+    ) -> Result<octocrab::models::Milestone, GitHubApiError> {
+        self.check_write_access()?;
+
+        self.write_calls
+            .lock()
+            .unwrap()
+            .push(WriteCall::UpdateMilestone {
+                milestone_number,
+                update: update.clone(),
+            });
+
+        let mut milestones = self.milestones.lock().unwrap();
+        let milestone = milestones
+            .iter_mut()
+            .find(|m| m.number as u64 == milestone_number)
+            .ok_or(GitHubApiError::NoApi)?;
+        if let Some(title) = &update.title {
+            milestone.title = title.clone();
+        }
+        if let Some(description) = &update.description {
+            milestone.description = Some(description.clone());
+        }
+        if let Some(state) = update.state {
+            milestone.state = Some(state.as_str().to_string());
+        }
+        Ok(milestone.clone())
+    }
+
+    async fn close_milestone(
+        &self,
+        milestone_number: u64,
+    ) -> Result<octocrab::models::Milestone, GitHubApiError> {
+        self.update_milestone(
+            milestone_number,
+            &crate::MilestoneUpdate {
+                state: Some(crate::MilestoneState::Closed),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
     async fn post_issue(&self, issue: &crate::QCIssue) -> Result<Issue, GitHubApiError> {
+        self.check_write_access()?;
+
         // Track the call
         self.write_calls.lock().unwrap().push(WriteCall::PostIssue {
             title: issue.title().to_string(),
@@ -589,6 +779,8 @@ impl GitHubWriter for MockGitInfo {
         &self,
         comment: &T,
     ) -> Result<String, GitHubApiError> {
+        self.check_write_access()?;
+
         // Track the call
         self.write_calls
             .lock()
@@ -605,7 +797,13 @@ impl GitHubWriter for MockGitInfo {
         ))
     }
 
+    fn guard_issue(&self, issue: &Issue) -> Result<(), GitHubApiError> {
+        Ok(crate::guard_issue_change(issue, self, false)?)
+    }
+
     async fn close_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
         self.write_calls
             .lock()
             .unwrap()
@@ -614,6 +812,8 @@ impl GitHubWriter for MockGitInfo {
     }
 
     async fn open_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
         self.write_calls
             .lock()
             .unwrap()
@@ -627,6 +827,8 @@ impl GitHubWriter for MockGitInfo {
         new_title: Option<String>,
         new_body: Option<String>,
     ) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
         self.write_calls
             .lock()
             .unwrap()
@@ -647,10 +849,40 @@ impl GitHubWriter for MockGitInfo {
     }
 
     async fn create_label(&self, _name: &str, _color: &str) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
+        Err(GitHubApiError::NoApi)
+    }
+
+    async fn add_labels(
+        &self,
+        _issue_number: u64,
+        _labels: &[String],
+    ) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
+        Err(GitHubApiError::NoApi)
+    }
+
+    async fn remove_label(&self, _issue_number: u64, _label: &str) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
+        Err(GitHubApiError::NoApi)
+    }
+
+    async fn set_assignees(
+        &self,
+        _issue_number: u64,
+        _assignees: &[String],
+    ) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
         Err(GitHubApiError::NoApi)
     }
 
     async fn block_issue(&self, _blocked: u64, _blocking: u64) -> Result<(), GitHubApiError> {
+        self.check_write_access()?;
+
         Err(GitHubApiError::NoApi)
     }
 }
@@ -9,6 +9,7 @@ mod harness;
 mod test_runner {
     use std::fs;
     use std::path::{Path, PathBuf};
+    use std::sync::Arc;
 
     use axum::body::Body;
     use axum::extract::Request;
@@ -20,7 +21,8 @@ mod test_runner {
     use crate::api::tests::harness::runner::TestRunner;
     use crate::api::tests::harness::types::TestCase;
     use crate::api::tests::helpers::{MockGitInfo, load_test_issue, load_test_milestone};
-    use crate::api::{AppState, create_router};
+    use crate::api::{ApiMetrics, AppState, MeteredGit, create_router};
+    use crate::{UsageLog, read_usage_events};
 
     /// Discover all YAML test cases recursively
     fn discover_test_cases() -> Vec<PathBuf> {
@@ -220,4 +222,204 @@ mod test_runner {
         );
         assert_eq!(json1, json2, "Repeated requests should be stable");
     }
+
+    #[tokio::test]
+    async fn test_read_only_rejection_disables_writes() {
+        let mock = MockGitInfo::builder()
+            .with_issue(1, load_test_issue("test_file_issue"))
+            .with_read_only_token(true)
+            .build();
+
+        let state = AppState::new(mock.clone(), Configuration::default(), None, None);
+        let app = create_router::<_, GitCommand>(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/issues/1/comment")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "current_commit": "456def789abc012345678901234567890123cdef",
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "read_only_token");
+        assert!(!state.write_enabled());
+
+        // Later writes are rejected up front, without calling GitHub
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/milestones")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "name": "v2.0" }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(mock.write_calls().is_empty());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["read_only"], true);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_record_requests_by_route() {
+        let state = AppState::new(
+            MockGitInfo::builder().build(),
+            Configuration::default(),
+            None,
+            None,
+        );
+        let app = create_router::<_, GitCommand>(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.metrics().request_count("GET", "/api/health"), 1);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains(
+            "ghqc_http_request_duration_seconds_count{method=\"GET\",route=\"/api/health\"} 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_github_calls() {
+        let mock = MockGitInfo::builder()
+            .with_issue(1, load_test_issue("test_file_issue"))
+            .build();
+        let metrics = Arc::new(ApiMetrics::default());
+        let state = AppState::new(
+            MeteredGit::new(mock.clone(), metrics.clone()),
+            Configuration::default(),
+            None,
+            None,
+        )
+        .with_metrics(metrics.clone());
+        let app = create_router::<_, GitCommand>(state);
+
+        // Commenting fetches the issue and posts the comment
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/issues/1/comment")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "current_commit": "456def789abc012345678901234567890123cdef",
+                            "previous_commit": "123abc456def012345678901234567890123cdef",
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(mock.write_calls().len(), 1);
+        assert_eq!(metrics.github_call_count(), 2);
+        assert_eq!(
+            metrics.request_count("POST", "/api/issues/{number}/comment"),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_usage_log_records_write_requests() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("usage.jsonl");
+        let mock = MockGitInfo::builder()
+            .with_issue(1, load_test_issue("test_file_issue"))
+            .build();
+        let state = AppState::new(mock, Configuration::default(), None, None).with_usage_log(Some(
+            UsageLog::new(
+                log_path.clone(),
+                temp_dir.path().to_path_buf(),
+                Some("analyst".to_string()),
+            ),
+        ));
+        let app = create_router::<_, GitCommand>(state);
+
+        for request in [
+            Request::builder()
+                .uri("/api/health")
+                .body(Body::empty())
+                .unwrap(),
+            Request::builder()
+                .method("POST")
+                .uri("/api/issues/1/comment")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "current_commit": "456def789abc012345678901234567890123cdef",
+                        "previous_commit": "123abc456def012345678901234567890123cdef",
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        ] {
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        // Reads are not recorded
+        let events = read_usage_events(&log_path).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].command, "api POST issues/{number}/comment");
+        assert_eq!(events[0].user.as_deref(), Some("analyst"));
+        assert!(events[0].success);
+    }
 }
@@ -2,11 +2,12 @@
 
 use std::path::PathBuf;
 
+use chrono::NaiveDate;
 use serde::Deserialize;
 
 use crate::{
-    Checklist, QCEntry, QCRelationship, RelevantFile, RelevantFileClass, RelevantFileEntry,
-    create::normalize_collaborator_entries,
+    Checklist, GitHelpers, QCEntry, QCRelationship, RelevantFile, RelevantFileClass,
+    RelevantFileEntry, create::normalize_collaborator_entries,
 };
 
 /// Request to create a new milestone.
@@ -144,6 +145,7 @@ impl TryFrom<CreateIssueRequest> for QCEntry {
             checklist: Checklist {
                 name: request.checklist_name,
                 content: request.checklist_content,
+                inline_file: None,
             },
             assignees: request.assignees,
             collaborators,
@@ -152,6 +154,79 @@ impl TryFrom<CreateIssueRequest> for QCEntry {
     }
 }
 
+/// Milestone of a new issue, by name.
+#[derive(Debug, Deserialize)]
+pub struct NewIssueMilestone {
+    pub name: String,
+    /// Create the milestone when it does not exist
+    #[serde(default)]
+    pub create: bool,
+    /// Description of the milestone, if it is created
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Existing QC issue related to a new issue, by URL.
+#[derive(Debug, Deserialize)]
+pub struct RelatedIssueUrl {
+    pub url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub include_diff: bool,
+}
+
+impl RelatedIssueUrl {
+    /// Relate the issue at `url` as `relation`, if it is an issue of this repository
+    pub(crate) fn to_entry(
+        &self,
+        relation: QCRelationship,
+        git_info: &impl GitHelpers,
+    ) -> Result<RelevantFileEntry, String> {
+        let issue_number = self
+            .url
+            .rsplit_once("/issues/")
+            .and_then(|(_, number)| number.parse::<u64>().ok())
+            .ok_or_else(|| format!("Invalid issue URL '{}'", self.url))?;
+        let expected_url = git_info.issue_url(issue_number);
+        if self.url != expected_url {
+            return Err(format!(
+                "Issue URL '{}' does not match expected repository URL '{expected_url}'",
+                self.url
+            ));
+        }
+
+        let issue = RelevantIssue {
+            file_name: PathBuf::from(format!("issue #{issue_number}")),
+            issue_class: RelevantIssueClass::Exists {
+                issue_number,
+                issue_id: None,
+            },
+            description: self.description.clone(),
+            include_diff: self.include_diff,
+        };
+        Ok(issue.to_entry(relation))
+    }
+}
+
+/// Request to create a single QC issue with a configured checklist.
+#[derive(Debug, Deserialize)]
+pub struct NewIssueRequest {
+    pub milestone: NewIssueMilestone,
+    pub file: String,
+    pub checklist_name: String,
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    #[serde(default)]
+    pub previous_qc: Vec<RelatedIssueUrl>,
+    #[serde(default)]
+    pub gating_qc: Vec<RelatedIssueUrl>,
+    #[serde(default)]
+    pub relevant_qc: Vec<RelatedIssueUrl>,
+    #[serde(default)]
+    pub relevant_files: Vec<RelevantFileInput>,
+}
+
 /// Request to create a commit-to-commit comment.
 #[derive(Debug, Deserialize)]
 pub struct CreateCommentRequest {
@@ -170,6 +245,13 @@ pub struct ApproveRequest {
     pub commit: String,
     #[serde(default)]
     pub note: Option<String>,
+    /// When the approval actually happened, for approvals recorded after the fact.
+    #[serde(default)]
+    pub effective_date: Option<NaiveDate>,
+    /// Approve even if the issue's checklists have unchecked items, which are then recorded in
+    /// the approval.
+    #[serde(default)]
+    pub allow_incomplete_checklist: bool,
 }
 
 /// Query parameters for approve endpoint.
@@ -183,6 +265,9 @@ pub struct ApproveQuery {
 #[derive(Debug, Deserialize)]
 pub struct UnapproveRequest {
     pub reason: String,
+    /// When the unapproval actually happened, for unapprovals recorded after the fact.
+    #[serde(default)]
+    pub effective_date: Option<NaiveDate>,
 }
 
 /// Request to post a working directory review.
@@ -227,6 +312,21 @@ pub struct ArchiveGenerateRequest {
     pub files: Vec<ArchiveFileRequest>,
 }
 
+/// Request to download an archive of selected issues.
+#[derive(Debug, Deserialize)]
+pub struct ArchiveDownloadRequest {
+    /// Issues whose files are archived, across any milestones
+    pub issues: Vec<u64>,
+    #[serde(default)]
+    pub flatten: bool,
+    /// Archive unapproved and obsolete issues at their latest commit instead of rejecting them
+    #[serde(default)]
+    pub include_unapproved: bool,
+    /// Files archived alongside the issues' files
+    #[serde(default)]
+    pub additional_files: Vec<ArchiveFileRequest>,
+}
+
 #[derive(serde::Deserialize)]
 pub struct SetupConfigurationRequest {
     pub url: String,
@@ -259,4 +359,14 @@ pub struct RecordRequest {
     pub output_path: String,
     #[serde(default)]
     pub context_files: Vec<RecordContextFileRequest>,
+    /// Directories to report QC coverage of, all tracked files when empty.
+    /// The coverage section is omitted when not set.
+    #[serde(default)]
+    pub coverage_paths: Option<Vec<String>>,
+    /// Fail instead of rendering when gathering the record content raised any warning.
+    #[serde(default)]
+    pub strict: bool,
+    /// End the record with an appendix of the approved version of each file.
+    #[serde(default)]
+    pub appendix: bool,
 }
@@ -1,6 +1,6 @@
 //! API response types.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 
@@ -9,9 +9,10 @@ use octocrab::models::IssueState;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    FileRenameEvent, GitHubApiError, GitProvider, IssueThread, ReviewStashResult,
-    analyze_issue_checklists, api::ApiError, create::CreateResult, get_git_status,
-    parse_blocking_qcs, parse_file_history,
+    CacheHealth, CommitRetrievalInfo, FileRenameEvent, GitHubApiError, GitProvider, IssueThread,
+    QCRelationship, RelevantFileLink, ReviewStashResult, StaleApproval, analyze_issue_checklists,
+    api::ApiError, create::CreateResult, get_git_status, parse_blocking_qcs, parse_file_history,
+    parse_relevant_file_links, utils::contains_repo_path,
 };
 
 /// Health check response.
@@ -19,6 +20,10 @@ use crate::{
 pub struct HealthResponse {
     pub status: String,
     pub version: String,
+    /// Whether the GitHub token is read-only, in which case write routes are rejected
+    pub read_only: bool,
+    /// Whether GitHub responses are cached on disk, and why not
+    pub cache: CacheHealth,
 }
 
 /// Milestone information.
@@ -64,6 +69,10 @@ pub struct RelevantFileInfo {
     pub kind: RelevantFileKind,
     /// GitHub issue URL — present for BlockingQc and RelevantQc kinds, None for File
     pub issue_url: Option<String>,
+    /// Short hash of the commit a File entry was linked at, if any
+    pub commit: Option<String>,
+    /// URL of the file at `commit`, if any
+    pub file_url: Option<String>,
 }
 
 /// A detected rename of a file that has an open QC issue.
@@ -166,6 +175,7 @@ pub enum QCStatusEnum {
     InProgress,
     ApprovalRequired,
     ChangesToComment,
+    Obsoleted,
 }
 
 impl From<crate::QCStatus> for QCStatusEnum {
@@ -178,6 +188,7 @@ impl From<crate::QCStatus> for QCStatusEnum {
             crate::QCStatus::InProgress => QCStatusEnum::InProgress,
             crate::QCStatus::ApprovalRequired => QCStatusEnum::ApprovalRequired,
             crate::QCStatus::ChangesToComment(_) => QCStatusEnum::ChangesToComment,
+            crate::QCStatus::Obsoleted(_) => QCStatusEnum::Obsoleted,
         }
     }
 }
@@ -263,6 +274,7 @@ pub enum CommitStatusEnum {
     Notification,
     Approved,
     Reviewed,
+    Unapproved,
 }
 
 impl From<&crate::CommitStatus> for CommitStatusEnum {
@@ -272,6 +284,7 @@ impl From<&crate::CommitStatus> for CommitStatusEnum {
             crate::CommitStatus::Notification => CommitStatusEnum::Notification,
             crate::CommitStatus::Approved => CommitStatusEnum::Approved,
             crate::CommitStatus::Reviewed => CommitStatusEnum::Reviewed,
+            crate::CommitStatus::Unapproved => CommitStatusEnum::Unapproved,
         }
     }
 }
@@ -360,10 +373,17 @@ pub struct IssueStatusResponse {
     pub issue: Issue,
     pub qc_status: QCStatus,
     pub dirty: bool,
+    /// The file changed since the standing approval
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_approval: Option<StaleApproval>,
     pub branch: String,
     pub commits: Vec<IssueCommit>,
     pub checklist_summary: ChecklistSummary,
     pub blocking_qc_status: BlockingQCStatus,
+    /// How the commits were found, only present when resolved through a fallback or possibly
+    /// incomplete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_retrieval: Option<CommitRetrievalInfo>,
 }
 
 impl IssueStatusResponse {
@@ -373,7 +393,8 @@ impl IssueStatusResponse {
         dirty_files: &[PathBuf],
     ) -> Self {
         Self {
-            dirty: dirty_files.contains(&PathBuf::from(&issue.title)),
+            dirty: contains_repo_path(dirty_files, Path::new(&issue.title)),
+            stale_approval: StaleApproval::from_thread(issue_thread, dirty_files),
             issue: issue.clone().into(),
             qc_status: issue_thread.into(),
             branch: issue
@@ -384,6 +405,10 @@ impl IssueStatusResponse {
             commits: issue_thread.commits.iter().map(IssueCommit::from).collect(),
             checklist_summary: analyze_issue_checklists(issue.body.as_deref()).into(),
             blocking_qc_status: BlockingQCStatus::default(),
+            commit_retrieval: issue_thread
+                .commit_retrieval
+                .is_degraded()
+                .then(|| issue_thread.commit_retrieval.clone()),
         }
     }
 
@@ -430,6 +455,36 @@ impl From<CreateResult> for CreateIssueResponse {
     }
 }
 
+/// Response for creating a single issue with `POST /api/issues`.
+#[derive(Debug, Serialize)]
+pub struct NewIssueResponse {
+    pub issue_number: u64,
+    pub issue_url: String,
+    /// Body the issue was posted with
+    pub body: String,
+    /// Whether the milestone was created for this issue
+    pub milestone_created: bool,
+    pub blocking_created: Vec<u64>,
+    pub blocking_errors: Vec<BlockingQCError>,
+}
+
+impl NewIssueResponse {
+    pub fn new(res: CreateResult, milestone_created: bool) -> Self {
+        Self {
+            issue_number: res.issue_number,
+            issue_url: res.issue_url,
+            body: res.body,
+            milestone_created,
+            blocking_created: res.successful_blocking,
+            blocking_errors: res
+                .blocking_errors
+                .into_iter()
+                .map(BlockingQCError::from)
+                .collect(),
+        }
+    }
+}
+
 /// Error kind for batch issue status.
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -459,6 +514,17 @@ pub struct BatchIssueStatusResponse {
     pub errors: Vec<IssueStatusError>,
 }
 
+/// One page of the issue listing, with the status of each issue on it.
+#[derive(Debug, Serialize)]
+pub struct IssueListResponse {
+    /// Issues matching the filters, across all pages
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub results: Vec<IssueStatusResponse>,
+    pub errors: Vec<IssueStatusError>,
+}
+
 /// Response for comment creation.
 #[derive(Debug, Serialize)]
 pub struct CommentResponse {
@@ -573,6 +639,28 @@ pub struct ConfigurationStatusResponse {
     pub options: ConfigurationOptions,
     pub checklists: Vec<Checklist>,
     pub config_repo_env: Option<String>,
+    /// Problems found in the options file; defaults are used for the affected options
+    pub warnings: Vec<ConfigurationWarning>,
+}
+
+/// A problem found in the configuration options file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigurationWarning {
+    pub file: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl From<&crate::ConfigurationWarning> for ConfigurationWarning {
+    fn from(warning: &crate::ConfigurationWarning) -> Self {
+        Self {
+            file: warning.file.to_string_lossy().to_string(),
+            line: warning.line,
+            column: warning.column,
+            message: warning.message.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -589,6 +677,8 @@ pub struct RepoInfoResponse {
     pub git_status_detail: String,
     pub dirty_files: Vec<String>,
     pub current_user: Option<String>,
+    /// Whether the GitHub token is read-only, in which case write routes are rejected
+    pub read_only: bool,
 }
 
 /// Kind of a file tree entry.
@@ -639,6 +729,12 @@ pub struct RecordPreviewResponse {
     pub key: String,
 }
 
+/// Response for a record generation started in the background.
+#[derive(Debug, Serialize)]
+pub struct RecordJobResponse {
+    pub job_id: String,
+}
+
 /// Extract the checklist name from the first h1 heading (e.g. "# Code Review").
 fn parse_checklist_name(body: &str) -> Option<String> {
     body.lines()
@@ -665,66 +761,46 @@ fn parse_branch_from_body_simple(body: &str) -> Option<String> {
 
 /// Parse all entries from the "## Relevant Files" section.
 fn parse_relevant_file_infos(body: &str) -> Vec<RelevantFileInfo> {
-    use regex::Regex;
-    use std::sync::LazyLock;
-    static LINK: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap());
-    static BOLD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\*\*([^*]+)\*\*").unwrap());
-
-    let rf_start = match body.find("## Relevant Files") {
-        Some(p) => p,
-        None => return vec![],
-    };
-    let section = &body[rf_start..];
-    let end = section[17..]
-        .find("\n## ")
-        .map(|p| p + 17)
-        .unwrap_or(section.len());
-    let section = &section[..end];
-
-    let mut result = Vec::new();
-
-    for (sub, kind) in [
-        ("### Previous QC", RelevantFileKind::BlockingQc),
-        ("### Gating QC", RelevantFileKind::BlockingQc),
-        ("### Relevant QC", RelevantFileKind::RelevantQc),
-        ("### Relevant File", RelevantFileKind::File),
-    ] {
-        let sub_start = match section.find(sub) {
-            Some(p) => p,
-            None => continue,
-        };
-        let sub_section = &section[sub_start..];
-        let sub_end = sub_section[sub.len()..]
-            .find("\n### ")
-            .map(|p| p + sub.len())
-            .unwrap_or(sub_section.len());
-        let sub_section = &sub_section[..sub_end];
-
-        if kind == RelevantFileKind::File {
-            for cap in BOLD.captures_iter(sub_section) {
-                result.push(RelevantFileInfo {
-                    file_name: cap[1].to_string(),
-                    kind: RelevantFileKind::File,
-                    issue_url: None,
-                });
-            }
-        } else {
-            for cap in LINK.captures_iter(sub_section) {
-                result.push(RelevantFileInfo {
-                    file_name: cap[1].to_string(),
-                    kind: kind.clone(),
-                    issue_url: Some(cap[2].to_string()),
-                });
-            }
-        }
-    }
-    result
+    parse_relevant_file_links(body)
+        .into_iter()
+        .map(|link| match link {
+            RelevantFileLink::Issue {
+                file_name,
+                url,
+                relationship,
+                ..
+            } => RelevantFileInfo {
+                file_name: file_name.display().to_string(),
+                kind: match relationship {
+                    QCRelationship::PreviousQC | QCRelationship::GatingQC => {
+                        RelevantFileKind::BlockingQc
+                    }
+                    QCRelationship::RelevantQC => RelevantFileKind::RelevantQc,
+                },
+                issue_url: Some(url),
+                commit: None,
+                file_url: None,
+            },
+            RelevantFileLink::File {
+                file_name,
+                commit,
+                file_url,
+                ..
+            } => RelevantFileInfo {
+                file_name: file_name.display().to_string(),
+                kind: RelevantFileKind::File,
+                issue_url: None,
+                commit,
+                file_url,
+            },
+        })
+        .collect()
 }
 
 impl RepoInfoResponse {
     pub async fn new<G: GitProvider + Clone + Send + 'static>(
         git_info: &G,
+        read_only: bool,
     ) -> Result<Self, ApiError> {
         let owner = git_info.owner().to_string();
         let repo = git_info.repo().to_string();
@@ -771,6 +847,31 @@ impl RepoInfoResponse {
             git_status_detail,
             dirty_files,
             current_user,
+            read_only,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_relevant_files_with_and_without_commit() {
+        let body = "## Relevant Files\n\n### Relevant File\n\
+            - **data/config.yaml** ([abc1234](https://github.com/o/r/blob/abc1234def/data/config.yaml)) - Inputs\n\
+            - **R/utils.R** - Helpers\n\n## Detailed\n";
+
+        let files = parse_relevant_file_infos(body);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].file_name, "data/config.yaml");
+        assert_eq!(files[0].commit.as_deref(), Some("abc1234"));
+        assert_eq!(
+            files[0].file_url.as_deref(),
+            Some("https://github.com/o/r/blob/abc1234def/data/config.yaml")
+        );
+        assert_eq!(files[1].file_name, "R/utils.R");
+        assert_eq!(files[1].commit, None);
+        assert_eq!(files[1].file_url, None);
+    }
+}
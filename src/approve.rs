@@ -1,23 +1,53 @@
 use std::collections::HashMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, NaiveDate, Utc};
 use gix::ObjectId;
 use octocrab::models::issues::Issue;
-use serde::{Deserialize, Serialize};
 
 use crate::GitCommitOps;
 use crate::cache::DiskCache;
 use crate::comment_system::CommentBody;
-use crate::git::{GitFileOps, GitHelpers, GitHubApiError, GitHubReader, GitHubWriter};
-use crate::issue::{BlockingQC, parse_blocking_qcs};
-use crate::qc_status::get_blocking_qc_status;
+use crate::diff_utils::change_summary;
+use crate::git::{
+    GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError, GitHubReader, GitHubWriter,
+    SignatureStatus, SigningKeys, issue_url_matches_remote,
+};
+use crate::issue::{BlockingQC, CommitStatus, IssueThread, parse_blocking_qcs};
+use crate::qc_status::{
+    ChecklistSummary, analyze_issue_checklists, format_section_summaries, get_blocking_qc_status,
+    unchecked_checklist_items,
+};
 
 pub struct QCApprove {
     pub file: PathBuf,
     pub commit: ObjectId,
     pub issue: Issue,
     pub note: Option<String>,
+    /// When the approval actually happened, if it is recorded after the fact
+    pub effective_date: Option<NaiveDate>,
+    /// Range of QC the approval closes, summarized in the comment. `None` leaves it out
+    pub scope: Option<QCScope>,
+    /// Checklist items left unchecked when approving anyway, counted in the comment
+    pub incomplete_checklist: Option<IncompleteChecklist>,
+}
+
+impl QCApprove {
+    /// Refuse an effective date after `today`, or before the approved commit was made
+    pub fn check_effective_date(
+        &self,
+        git_info: &impl GitCommitOps,
+        today: NaiveDate,
+    ) -> Result<(), ApprovalError> {
+        let Some(effective_date) = self.effective_date else {
+            return Ok(());
+        };
+        let commit_time = git_info
+            .commit_time(&self.commit)
+            .map_err(ApprovalError::CommitTimeFailed)?;
+        validate_effective_date(effective_date, today, Some(commit_time))
+    }
 }
 
 impl CommentBody for QCApprove {
@@ -27,7 +57,7 @@ impl CommentBody for QCApprove {
 
     fn generate_body(&self, git_info: &(impl GitHelpers + GitFileOps)) -> String {
         let short_sha = &self.commit.to_string()[..7];
-        let metadata = vec![
+        let mut metadata = vec![
             "## Metadata".to_string(),
             format!("approved qc commit: {}", self.commit),
             format!(
@@ -35,6 +65,17 @@ impl CommentBody for QCApprove {
                 git_info.file_content_url(short_sha, &self.file)
             ),
         ];
+        if let Some(date) = self.effective_date {
+            metadata.push(format!("effective date: {date}"));
+        }
+        if let Some(incomplete) = &self.incomplete_checklist {
+            metadata.push(format!(
+                "incomplete checklist: {}/{} items checked, {} unchecked",
+                incomplete.summary.completed,
+                incomplete.summary.total,
+                incomplete.unchecked_count()
+            ));
+        }
 
         let mut body = vec!["# QC Approved".to_string()];
 
@@ -42,6 +83,10 @@ impl CommentBody for QCApprove {
             body.push(note.clone());
         }
 
+        if let Some(scope) = &self.scope {
+            body.push(scope.section(&self.commit, &self.file, git_info));
+        }
+
         body.push(metadata.join("\n* "));
         body.join("\n\n")
     }
@@ -51,10 +96,197 @@ impl CommentBody for QCApprove {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Checklist items of an issue that are not checked off
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncompleteChecklist {
+    pub summary: ChecklistSummary,
+    /// Completion of each checklist section, by header
+    pub sections: Vec<(String, ChecklistSummary)>,
+    /// Texts of the unchecked items by checklist, in issue order
+    pub unchecked: Vec<(String, Vec<String>)>,
+}
+
+impl IncompleteChecklist {
+    /// Unchecked items of the issue's checklists, `None` when all items are checked or the issue
+    /// has no checklist
+    pub fn from_issue(issue: &Issue) -> Option<Self> {
+        let body = issue.body.as_deref();
+        let checklists = analyze_issue_checklists(body);
+        let summary = ChecklistSummary::sum(checklists.iter().map(|(_, summary)| summary));
+        (summary.completed < summary.total).then(|| Self {
+            summary,
+            sections: checklists,
+            unchecked: unchecked_checklist_items(body),
+        })
+    }
+
+    pub fn unchecked_count(&self) -> usize {
+        self.summary.total - self.summary.completed
+    }
+
+    /// Completion per section, e.g. `Functionality 3/4, Testing 2/5`
+    pub fn section_summary(&self) -> String {
+        format_section_summaries(&self.sections)
+    }
+}
+
+impl fmt::Display for IncompleteChecklist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} checklist items are unchecked:",
+            self.unchecked_count(),
+            self.summary.total
+        )?;
+        for (checklist, items) in &self.unchecked {
+            write!(f, "\n  {checklist}:")?;
+            for item in items {
+                write!(f, "\n    - [ ] {item}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Refuse to approve an issue with unchecked checklist items when `require_complete` is set.
+/// Otherwise returns the unchecked items, if any, to record in the approval
+pub fn check_checklist_completion(
+    issue: &Issue,
+    require_complete: bool,
+) -> Result<Option<IncompleteChecklist>, ApprovalError> {
+    match IncompleteChecklist::from_issue(issue) {
+        Some(incomplete) if require_complete => Err(ApprovalError::IncompleteChecklist(incomplete)),
+        incomplete => Ok(incomplete),
+    }
+}
+
+/// The QC an approval closes: everything from the initial QC commit to the approved commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct QCScope {
+    pub initial_commit: ObjectId,
+    /// Commits changing the file after the initial QC commit, up to the approved commit
+    pub file_commits: usize,
+    /// Notified and reviewed commits between the initial and the approved commit, oldest first
+    pub intermediate_commits: Vec<ObjectId>,
+}
+
+impl QCScope {
+    /// Scope of approving `approved` on the issue, `None` when the commit is not on the issue's
+    /// branch or predates the initial QC commit
+    pub fn from_thread(thread: &IssueThread, approved: &ObjectId) -> Option<Self> {
+        // commits are ordered newest first
+        let approved_position = thread.commits.iter().position(|c| &c.hash == approved)?;
+        let initial_commit = *thread.initial_commit();
+        let initial_position = thread
+            .commits
+            .iter()
+            .position(|c| c.hash == initial_commit)?;
+        if approved_position > initial_position {
+            return None;
+        }
+
+        let range = &thread.commits[approved_position..initial_position];
+        Some(Self {
+            initial_commit,
+            file_commits: range.iter().filter(|c| c.file_changed).count(),
+            intermediate_commits: range
+                .iter()
+                .skip(1)
+                .rev()
+                .filter(|c| {
+                    c.statuses.contains(&CommitStatus::Notification)
+                        || c.statuses.contains(&CommitStatus::Reviewed)
+                })
+                .map(|c| c.hash)
+                .collect(),
+        })
+    }
+
+    /// "Scope of QC" section of the approval comment, with the combined change of the file
+    /// between the initial QC commit and `approved`
+    fn section(
+        &self,
+        approved: &ObjectId,
+        file: &Path,
+        git_info: &(impl GitHelpers + GitFileOps),
+    ) -> String {
+        let mut lines = vec![
+            "## Scope of QC".to_string(),
+            format!("initial qc commit: {}", self.initial_commit),
+        ];
+        if &self.initial_commit == approved {
+            lines.push("approved at the initial qc commit, no changes since".to_string());
+            return lines.join("\n* ");
+        }
+
+        lines.push(format!("commits changing the file: {}", self.file_commits));
+        match (
+            git_info.file_bytes_at_commit(file, &self.initial_commit),
+            git_info.file_bytes_at_commit(file, approved),
+        ) {
+            (Ok(from), Ok(to)) => {
+                lines.push(format!("changes: {}", change_summary(&from, &to, file)))
+            }
+            _ => log::debug!(
+                "Could not read {} to summarize the QC scope",
+                file.display()
+            ),
+        }
+        let intermediate = if self.intermediate_commits.is_empty() {
+            "none".to_string()
+        } else {
+            self.intermediate_commits
+                .iter()
+                .map(|c| c.to_string()[..7].to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        lines.push(format!("intermediate qc commits: {intermediate}"));
+        lines.push(format!(
+            "[changes since initial qc commit]({})",
+            git_info.commit_comparison_url(approved, &self.initial_commit)
+        ));
+        lines.join("\n* ")
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct QCUnapprove {
     pub issue: Issue,
     pub reason: String,
+    /// Commit the file is at when the approval is withdrawn
+    pub commit: ObjectId,
+    /// Commit of the approval being withdrawn, `None` when the issue has no standing approval
+    pub previously_approved: Option<ObjectId>,
+    /// When the unapproval actually happened, if it is recorded after the fact
+    pub effective_date: Option<NaiveDate>,
+}
+
+impl QCUnapprove {
+    /// Unapproval of the issue at the most recent commit of its file, withdrawing the thread's
+    /// standing approval
+    pub fn new(issue: Issue, issue_thread: &IssueThread, reason: String) -> Self {
+        Self {
+            issue,
+            reason,
+            // Same default as QC comments: the most recent commit which changed the file
+            commit: issue_thread
+                .file_commits()
+                .first()
+                .map(|commit| **commit)
+                .unwrap_or(issue_thread.commits[0].hash),
+            previously_approved: issue_thread.approved_commit().map(|commit| commit.hash),
+            effective_date: None,
+        }
+    }
+
+    /// Refuse an effective date after `today`
+    pub fn check_effective_date(&self, today: NaiveDate) -> Result<(), ApprovalError> {
+        match self.effective_date {
+            Some(effective_date) => validate_effective_date(effective_date, today, None),
+            None => Ok(()),
+        }
+    }
 }
 
 impl CommentBody for QCUnapprove {
@@ -64,11 +296,18 @@ impl CommentBody for QCUnapprove {
 
     fn generate_body(&self, _git_info: &(impl GitHelpers + GitFileOps)) -> String {
         // Enhanced QCUnapprove now uses GitHelpers for consistency
-        let metadata = vec![
+        let mut metadata = vec![
             "## Metadata".to_string(),
             format!("issue: #{}", self.issue.number),
             format!("unapproval reason: {}", self.reason),
+            format!("unapproved at commit: {}", self.commit),
         ];
+        if let Some(approved) = self.previously_approved {
+            metadata.push(format!("previously approved commit: {approved}"));
+        }
+        if let Some(date) = self.effective_date {
+            metadata.push(format!("effective date: {date}"));
+        }
 
         let mut body = vec!["# QC Un-Approval".to_string()];
         body.push(self.reason.clone());
@@ -92,6 +331,8 @@ pub struct BlockingQCCheckResult {
     pub unapproved: HashMap<u64, PathBuf>,
     /// Blocking QC issues where status could not be determined
     pub errors: HashMap<u64, String>,
+    /// Blocking QC issues of another repository, whose status cannot be verified
+    pub other_repository: Vec<BlockingQC>,
 }
 
 impl BlockingQCCheckResult {
@@ -133,12 +374,19 @@ impl fmt::Display for BlockingQCCheckResult {
             }
         }
 
+        if !self.other_repository.is_empty() {
+            writeln!(f, "\nBlocking QCs of another repository, not verified:")?;
+            for qc in &self.other_repository {
+                writeln!(f, "  {} - {}", qc.url, qc.file_name.display())?;
+            }
+        }
+
         Ok(())
     }
 }
 
 /// Result of an approval operation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ApprovalResult {
     /// URL of the approval comment
     pub approval_url: String,
@@ -146,6 +394,16 @@ pub struct ApprovalResult {
     pub skipped_unapproved: HashMap<u64, PathBuf>,
     /// Blocking QCs with fetch errors that were bypassed with --force
     pub skipped_errors: HashMap<u64, String>,
+    /// Blocking QCs of another repository, which could not be verified
+    #[serde(serialize_with = "serialize_blocking_qc_urls")]
+    pub unverified: Vec<BlockingQC>,
+}
+
+fn serialize_blocking_qc_urls<S: serde::Serializer>(
+    blocking_qcs: &[BlockingQC],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(blocking_qcs.iter().map(|qc| qc.url.as_str()))
 }
 
 impl fmt::Display for ApprovalResult {
@@ -180,6 +438,18 @@ impl fmt::Display for ApprovalResult {
             )?;
         }
 
+        if !self.unverified.is_empty() {
+            writeln!(
+                f,
+                "  ⚠️ Blocking QCs of another repository could not be verified: {}",
+                self.unverified
+                    .iter()
+                    .map(|qc| qc.url.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+
         write!(f, "\n{}", self.approval_url)
     }
 }
@@ -188,12 +458,26 @@ impl fmt::Display for ApprovalResult {
 ///
 /// This function works directly with a slice of `BlockingQC` without requiring an `IssueThread`,
 /// which allows approval to proceed even when IssueThread construction might fail.
+/// Blocking QCs linking to an issue of another repository are not looked up, since their number
+/// refers to an issue of that repository, and are returned as `other_repository` instead.
 pub async fn get_unapproved_blocking_qcs(
     blocking_qcs: &[BlockingQC],
-    git_info: &(impl GitHubReader + GitCommitOps),
+    git_info: &(impl GitHubReader + GitCommitOps + GitHelpers),
     cache: Option<&DiskCache>,
 ) -> BlockingQCCheckResult {
-    let status = get_blocking_qc_status(blocking_qcs, git_info, cache).await;
+    let (local, other_repository): (Vec<BlockingQC>, Vec<BlockingQC>) = blocking_qcs
+        .iter()
+        .cloned()
+        .partition(|qc| issue_url_matches_remote(&qc.url, qc.issue_number, git_info));
+    for qc in &other_repository {
+        log::warn!(
+            "Cannot verify blocking QC {} ({}) of another repository",
+            qc.url,
+            qc.file_name.display()
+        );
+    }
+
+    let status = get_blocking_qc_status(&local, git_info, cache).await;
 
     BlockingQCCheckResult {
         unapproved: status
@@ -202,20 +486,20 @@ pub async fn get_unapproved_blocking_qcs(
             .map(|(num, (path, _status))| (num, path))
             .collect(),
         errors: status.errors,
+        other_repository,
     }
 }
 
-/// Approve an issue with validation of blocking QCs
+/// Check the blocking QCs of an approval, without posting it
 ///
 /// Parses blocking QCs directly from the issue body for graceful degradation.
 /// If `force` is false and there are unapproved blocking QCs, returns an error.
-/// If `force` is true, proceeds with approval and records skipped issues in the result.
-pub async fn approve_with_validation(
+pub async fn check_approval_blocking_qcs(
     approval: &QCApprove,
-    git_info: &(impl GitHubWriter + GitHubReader + GitCommitOps),
+    git_info: &(impl GitHubReader + GitCommitOps + GitHelpers),
     cache: Option<&DiskCache>,
     force: bool,
-) -> Result<ApprovalResult, ApprovalError> {
+) -> Result<BlockingQCCheckResult, ApprovalError> {
     // Parse blocking QCs directly from the issue body
     // This avoids requiring full IssueThread construction which can fail if
     // the issue body is missing branch/commit metadata
@@ -234,10 +518,25 @@ pub async fn approve_with_validation(
         return Err(ApprovalError::BlockingQCsNotApproved {
             unapproved_count: check_result.unapproved.len(),
             error_count: check_result.errors.len(),
-            check_result,
+            check_result: Box::new(check_result),
         });
     }
 
+    Ok(check_result)
+}
+
+/// Approve an issue with validation of blocking QCs
+///
+/// See [`check_approval_blocking_qcs`] for the validation. If `force` is true, proceeds with
+/// approval and records skipped issues in the result.
+pub async fn approve_with_validation(
+    approval: &QCApprove,
+    git_info: &(impl GitHubWriter + GitHubReader + GitCommitOps + GitHelpers),
+    cache: Option<&DiskCache>,
+    force: bool,
+) -> Result<ApprovalResult, ApprovalError> {
+    let check_result = check_approval_blocking_qcs(approval, git_info, cache, force).await?;
+
     // Post the approval comment
     let approval_url = git_info.post_comment(approval).await?;
 
@@ -256,9 +555,59 @@ pub async fn approve_with_validation(
         } else {
             HashMap::new()
         },
+        unverified: check_result.other_repository,
     })
 }
 
+/// Refuse to approve a commit which is not signed by a trusted key
+///
+/// Unsigned commits are always refused. When trusted keys are configured, signatures which do not
+/// verify against them are refused as well; otherwise a signed commit is accepted as unverified.
+pub fn verify_signed_approval(
+    approval: &QCApprove,
+    git_info: &impl GitCommitOps,
+    keys: &SigningKeys,
+) -> Result<SignatureStatus, ApprovalError> {
+    let status = git_info.signature_status(&approval.commit, keys)?;
+
+    match status {
+        SignatureStatus::Signed => Ok(status),
+        SignatureStatus::Unverified if !keys.is_configured() => Ok(status),
+        _ => Err(ApprovalError::UnsignedApproval {
+            commit: approval.commit,
+            status,
+        }),
+    }
+}
+
+/// Check the effective date of a backfilled approval or unapproval
+///
+/// The date cannot be after `today`, nor before the day the approved commit was made.
+pub fn validate_effective_date(
+    effective_date: NaiveDate,
+    today: NaiveDate,
+    commit_time: Option<DateTime<Utc>>,
+) -> Result<(), ApprovalError> {
+    if effective_date > today {
+        return Err(ApprovalError::EffectiveDateInFuture {
+            effective_date,
+            today,
+        });
+    }
+
+    if let Some(commit_time) = commit_time {
+        let commit_date = commit_time.date_naive();
+        if effective_date < commit_date {
+            return Err(ApprovalError::EffectiveDateBeforeCommit {
+                effective_date,
+                commit_date,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 /// Error type for approval operations
 #[derive(Debug, thiserror::Error)]
 pub enum ApprovalError {
@@ -268,8 +617,35 @@ pub enum ApprovalError {
     BlockingQCsNotApproved {
         unapproved_count: usize,
         error_count: usize,
-        check_result: BlockingQCCheckResult,
+        check_result: Box<BlockingQCCheckResult>,
+    },
+    #[error(
+        "Cannot approve: commit {commit} is {status}. Approve a commit signed by a trusted key or omit --require-signed-approval"
+    )]
+    UnsignedApproval {
+        commit: ObjectId,
+        status: SignatureStatus,
+    },
+    #[error(
+        "Cannot approve: {0}\n\nCheck the items off or use --allow-incomplete-checklist to approve anyway"
+    )]
+    IncompleteChecklist(IncompleteChecklist),
+    #[error("Failed to check the signature of the approved commit: {0}")]
+    SignatureCheckFailed(#[from] GitFileOpsError),
+    #[error("Effective date {effective_date} is in the future (today is {today})")]
+    EffectiveDateInFuture {
+        effective_date: NaiveDate,
+        today: NaiveDate,
+    },
+    #[error(
+        "Effective date {effective_date} is before the approved commit was made on {commit_date}"
+    )]
+    EffectiveDateBeforeCommit {
+        effective_date: NaiveDate,
+        commit_date: NaiveDate,
     },
+    #[error("Failed to read the date of the approved commit: {0}")]
+    CommitTimeFailed(GitFileOpsError),
     #[error("GitHub API error: {0}")]
     GitHubApiError(#[from] GitHubApiError),
 }
@@ -281,7 +657,7 @@ pub enum ApprovalError {
 use crate::issue::BlockingRelationship;
 
 /// Result of an unapproval operation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct UnapprovalResult {
     /// URL of the unapproval comment
     pub unapproval_url: String,
@@ -314,7 +690,8 @@ impl fmt::Display for UnapprovalResult {
 }
 
 /// Represents impacted downstream issues
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "status", content = "issues", rename_all = "snake_case")]
 pub enum ImpactedIssues {
     /// No downstream issues found
     None,
@@ -325,7 +702,7 @@ pub enum ImpactedIssues {
 }
 
 /// A node in the impact tree
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ImpactNode {
     /// Issue number
     pub issue_number: u64,
@@ -540,7 +917,9 @@ mod tests {
     use super::*;
     use crate::comment_system::CommentBody;
     use crate::git::{GitAuthor, GitFileOps, GitFileOpsError, GitHelpers};
+    use crate::test_utils::FakeGit;
     use std::path::Path;
+    use std::str::FromStr;
 
     // Mock implementation for testing
     struct MockGitHelpers;
@@ -575,14 +954,26 @@ mod tests {
         fn file_bytes_at_commit(
             &self,
             _file: &Path,
-            _commit: &gix::ObjectId,
+            commit: &gix::ObjectId,
         ) -> Result<Vec<u8>, GitFileOpsError> {
-            Ok(Vec::new())
+            Ok(format!("header\n{}\n", commit).into_bytes())
         }
 
         fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
             Ok(Vec::new())
         }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<crate::git::PathAttributes, GitFileOpsError> {
+            Ok(Default::default())
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
     }
 
     fn load_issue(name: &str) -> Issue {
@@ -601,6 +992,9 @@ mod tests {
             commit,
             issue,
             note: Some("Everything looks good!".to_string()),
+            effective_date: None,
+            scope: None,
+            incomplete_checklist: None,
         };
 
         let git_helpers = MockGitHelpers;
@@ -619,6 +1013,9 @@ mod tests {
             commit,
             issue,
             note: None,
+            effective_date: None,
+            scope: None,
+            incomplete_checklist: None,
         };
 
         let git_helpers = MockGitHelpers;
@@ -627,6 +1024,269 @@ mod tests {
         insta::assert_snapshot!(body);
     }
 
+    /// Thread of `src/main.rs` with `a...` initial, three notified commits and a later
+    /// change `e...`; `c...` only touched other files
+    fn three_notification_thread() -> IssueThread {
+        let commits = [
+            ("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee", vec![], true),
+            (
+                "dddddddddddddddddddddddddddddddddddddddd",
+                vec![CommitStatus::Notification],
+                true,
+            ),
+            (
+                "cccccccccccccccccccccccccccccccccccccccc",
+                vec![CommitStatus::Notification],
+                false,
+            ),
+            (
+                "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                vec![CommitStatus::Notification],
+                true,
+            ),
+            (
+                "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                vec![CommitStatus::Initial],
+                true,
+            ),
+        ];
+
+        IssueThread {
+            file: PathBuf::from("src/main.rs"),
+            issue_number: 1,
+            issue_url: "https://github.com/owner/repo/issues/1".to_string(),
+            branch: "main".to_string(),
+            branch_deleted: false,
+            open: true,
+            commits: commits
+                .into_iter()
+                .map(|(hash, statuses, file_changed)| crate::IssueCommit {
+                    hash: ObjectId::from_str(hash).unwrap(),
+                    message: format!("Commit {}", &hash[..4]),
+                    statuses: statuses.into_iter().collect(),
+                    file_changed,
+                })
+                .collect(),
+            milestone: "v1.0".to_string(),
+            blocking_qcs: Vec::new(),
+            relevant_files: Vec::new(),
+            approved_at: None,
+            approval_recorded_at: None,
+            approved_by: None,
+            unapproval: None,
+            notified_at: None,
+            obsoleted: None,
+            inline_checklist: None,
+            rounds: Default::default(),
+            commit_retrieval: Default::default(),
+            renames: Vec::new(),
+        }
+    }
+
+    fn oid(c: char) -> ObjectId {
+        ObjectId::from_str(&c.to_string().repeat(40)).unwrap()
+    }
+
+    #[test]
+    fn test_qc_scope_with_three_notifications() {
+        let scope = QCScope::from_thread(&three_notification_thread(), &oid('e')).unwrap();
+
+        assert_eq!(
+            scope,
+            QCScope {
+                initial_commit: oid('a'),
+                file_commits: 3,
+                intermediate_commits: vec![oid('b'), oid('c'), oid('d')],
+            }
+        );
+
+        // Approving a notified commit leaves it out of the intermediate commits
+        let scope = QCScope::from_thread(&three_notification_thread(), &oid('d')).unwrap();
+        assert_eq!(scope.file_commits, 2);
+        assert_eq!(scope.intermediate_commits, vec![oid('b'), oid('c')]);
+    }
+
+    #[test]
+    fn test_qc_scope_approved_at_initial_commit() {
+        let thread = three_notification_thread();
+        let scope = QCScope::from_thread(&thread, &oid('a')).unwrap();
+
+        assert_eq!(scope.file_commits, 0);
+        assert!(scope.intermediate_commits.is_empty());
+        assert_eq!(
+            scope.section(&oid('a'), Path::new("src/main.rs"), &MockGitHelpers),
+            "## Scope of QC\n* initial qc commit: aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n* approved at the initial qc commit, no changes since"
+        );
+
+        // Commits that are not on the issue's branch have no scope
+        assert_eq!(QCScope::from_thread(&thread, &oid('f')), None);
+    }
+
+    #[test]
+    fn test_qc_approve_body_with_scope() {
+        let approve = QCApprove {
+            file: PathBuf::from("src/main.rs"),
+            commit: oid('e'),
+            issue: load_issue("main_file_issue"),
+            note: Some("Everything looks good!".to_string()),
+            effective_date: None,
+            scope: QCScope::from_thread(&three_notification_thread(), &oid('e')),
+            incomplete_checklist: None,
+        };
+
+        insta::assert_snapshot!(approve.generate_body(&MockGitHelpers));
+    }
+
+    #[test]
+    fn test_qc_approve_body_with_effective_date() {
+        let commit = gix::ObjectId::from_hex(b"abcdef1234567890abcdef1234567890abcdef12").unwrap();
+
+        let approve = QCApprove {
+            file: PathBuf::from("src/lib.rs"),
+            commit,
+            issue: load_issue("config_file_issue"),
+            note: None,
+            effective_date: NaiveDate::from_ymd_opt(2024, 5, 2),
+            scope: None,
+            incomplete_checklist: None,
+        };
+
+        let body = approve.generate_body(&MockGitHelpers);
+
+        assert!(body.ends_with("\n* effective date: 2024-05-02"));
+        assert_eq!(
+            crate::issue::parse_effective_date(&body),
+            NaiveDate::from_ymd_opt(2024, 5, 2)
+        );
+    }
+
+    fn issue_with_checklist(checklist: &str) -> Issue {
+        let mut issue = load_issue("main_file_issue");
+        issue.body = Some(format!(
+            "## Metadata\n* initial qc commit: abc\n\n# Code Review\n{checklist}\n## Notes\nnone\n"
+        ));
+        issue
+    }
+
+    #[test]
+    fn test_check_checklist_completion_refuses_unchecked_items() {
+        let none_checked = issue_with_checklist("- [ ] inputs\n- [ ] outputs");
+        let Err(ApprovalError::IncompleteChecklist(incomplete)) =
+            check_checklist_completion(&none_checked, true)
+        else {
+            panic!("expected an incomplete checklist error");
+        };
+        assert_eq!(incomplete.summary, ChecklistSummary::new(0, 2));
+
+        let partial = issue_with_checklist("- [x] inputs\n- [ ] outputs\n- [ ] plots");
+        let error = check_checklist_completion(&partial, true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot approve: 2 of 3 checklist items are unchecked:\n  Code Review:\n    - [ ] outputs\n    - [ ] plots\n\nCheck the items off or use --allow-incomplete-checklist to approve anyway"
+        );
+
+        let complete = issue_with_checklist("- [x] inputs\n- [X] outputs");
+        assert_eq!(check_checklist_completion(&complete, true).unwrap(), None);
+        assert_eq!(
+            check_checklist_completion(&load_issue("main_file_issue"), true).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_incomplete_checklist_override_is_recorded() {
+        let issue = issue_with_checklist("- [x] inputs\n- [ ] outputs\n- [ ] plots");
+        let incomplete_checklist = check_checklist_completion(&issue, false).unwrap();
+        assert_eq!(
+            incomplete_checklist.as_ref().map(|i| i.unchecked.clone()),
+            Some(vec![(
+                "Code Review".to_string(),
+                vec!["outputs".to_string(), "plots".to_string()]
+            )])
+        );
+
+        let approve = QCApprove {
+            file: PathBuf::from("src/main.rs"),
+            commit: gix::ObjectId::from_hex(b"1234567890abcdef1234567890abcdef12345678").unwrap(),
+            issue,
+            note: None,
+            effective_date: None,
+            scope: None,
+            incomplete_checklist,
+        };
+
+        assert!(
+            approve
+                .generate_body(&MockGitHelpers)
+                .ends_with("\n* incomplete checklist: 1/3 items checked, 2 unchecked")
+        );
+    }
+
+    fn signed_approval_result(
+        status: SignatureStatus,
+        keys: &SigningKeys,
+    ) -> Result<SignatureStatus, ApprovalError> {
+        let approval = QCApprove {
+            file: PathBuf::from("src/main.rs"),
+            commit: gix::ObjectId::from_hex(b"1234567890abcdef1234567890abcdef12345678").unwrap(),
+            issue: load_issue("main_file_issue"),
+            note: None,
+            effective_date: None,
+            scope: None,
+            incomplete_checklist: None,
+        };
+
+        let mut git_info = crate::git::MockGitCommitOps::new();
+        git_info
+            .expect_signature_status()
+            .withf(|commit, _| commit.to_string() == "1234567890abcdef1234567890abcdef12345678")
+            .returning(move |_, _| Ok(status));
+
+        verify_signed_approval(&approval, &git_info, keys)
+    }
+
+    fn trusted_keys() -> SigningKeys {
+        SigningKeys {
+            allowed_signers: Some(PathBuf::from("/config/allowed_signers")),
+            gpg_home: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_signed_approval_refuses_unsigned_commit() {
+        let result = signed_approval_result(SignatureStatus::Unsigned, &SigningKeys::default());
+        match result {
+            Err(ApprovalError::UnsignedApproval { status, .. }) => {
+                assert_eq!(status, SignatureStatus::Unsigned)
+            }
+            other => panic!("Expected UnsignedApproval error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_verify_signed_approval_refuses_unverified_with_trusted_keys() {
+        let result = signed_approval_result(SignatureStatus::Unverified, &trusted_keys());
+        assert!(matches!(
+            result,
+            Err(ApprovalError::UnsignedApproval {
+                status: SignatureStatus::Unverified,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_verify_signed_approval_accepts_signed_commit() {
+        let result = signed_approval_result(SignatureStatus::Signed, &trusted_keys());
+        assert_eq!(result.unwrap(), SignatureStatus::Signed);
+    }
+
+    #[test]
+    fn test_verify_signed_approval_accepts_unverified_without_trusted_keys() {
+        let result = signed_approval_result(SignatureStatus::Unverified, &SigningKeys::default());
+        assert_eq!(result.unwrap(), SignatureStatus::Unverified);
+    }
+
     #[test]
     fn test_qc_unapprove_body() {
         let issue = load_issue("test_file_issue");
@@ -634,6 +1294,11 @@ mod tests {
         let unapprove = QCUnapprove {
             issue,
             reason: "Found critical security vulnerability that needs to be addressed.".to_string(),
+            commit: ObjectId::from_str("456def789abc012345678901234567890123abcd").unwrap(),
+            previously_approved: Some(
+                ObjectId::from_str("123abc456def789012345678901234567890abcd").unwrap(),
+            ),
+            effective_date: None,
         };
 
         let git_helpers = MockGitHelpers;
@@ -642,6 +1307,81 @@ mod tests {
         insta::assert_snapshot!(body);
     }
 
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_validate_effective_date() {
+        let today = date(2024, 5, 6);
+        let commit_time = "2024-05-01T16:30:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert!(validate_effective_date(date(2024, 5, 2), today, Some(commit_time)).is_ok());
+        // The day of the commit and today are both allowed
+        assert!(validate_effective_date(date(2024, 5, 1), today, Some(commit_time)).is_ok());
+        assert!(validate_effective_date(today, today, Some(commit_time)).is_ok());
+        assert!(validate_effective_date(date(2024, 4, 1), today, None).is_ok());
+    }
+
+    #[test]
+    fn test_validate_effective_date_errors() {
+        let today = date(2024, 5, 6);
+        let commit_time = "2024-05-01T16:30:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        let err = validate_effective_date(date(2024, 5, 7), today, Some(commit_time)).unwrap_err();
+        assert!(matches!(err, ApprovalError::EffectiveDateInFuture { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Effective date 2024-05-07 is in the future (today is 2024-05-06)"
+        );
+
+        let err = validate_effective_date(date(2024, 4, 30), today, Some(commit_time)).unwrap_err();
+        assert!(matches!(
+            err,
+            ApprovalError::EffectiveDateBeforeCommit { .. }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Effective date 2024-04-30 is before the approved commit was made on 2024-05-01"
+        );
+    }
+
+    #[test]
+    fn test_check_effective_date_uses_approved_commit_time() {
+        let approval = QCApprove {
+            file: PathBuf::from("src/main.rs"),
+            commit: gix::ObjectId::from_hex(b"1234567890abcdef1234567890abcdef12345678").unwrap(),
+            issue: load_issue("main_file_issue"),
+            note: None,
+            effective_date: Some(date(2024, 5, 2)),
+            scope: None,
+            incomplete_checklist: None,
+        };
+
+        let mut git_info = crate::git::MockGitCommitOps::new();
+        git_info
+            .expect_commit_time()
+            .withf(|commit| commit.to_string() == "1234567890abcdef1234567890abcdef12345678")
+            .returning(|_| Ok("2024-05-03T09:00:00Z".parse().unwrap()));
+
+        assert!(matches!(
+            approval.check_effective_date(&git_info, date(2024, 5, 6)),
+            Err(ApprovalError::EffectiveDateBeforeCommit { .. })
+        ));
+
+        // Without an effective date the commit is not looked up
+        let approval = QCApprove {
+            effective_date: None,
+            ..approval
+        };
+        let git_info = crate::git::MockGitCommitOps::new();
+        assert!(
+            approval
+                .check_effective_date(&git_info, date(2024, 5, 6))
+                .is_ok()
+        );
+    }
+
     // Tests for BlockingQCCheckResult
 
     #[test]
@@ -704,6 +1444,7 @@ mod tests {
             approval_url: "https://github.com/owner/repo/issues/1#issuecomment-123".to_string(),
             skipped_unapproved: HashMap::new(),
             skipped_errors: HashMap::new(),
+            unverified: Vec::new(),
         };
 
         let display = format!("{}", result);
@@ -723,6 +1464,7 @@ mod tests {
             approval_url: "https://github.com/owner/repo/issues/1#issuecomment-123".to_string(),
             skipped_unapproved: skipped,
             skipped_errors: errors,
+            unverified: Vec::new(),
         };
 
         let display = format!("{}", result);
@@ -741,6 +1483,7 @@ mod tests {
             approval_url: "https://github.com/owner/repo/issues/1#issuecomment-123".to_string(),
             skipped_unapproved: skipped,
             skipped_errors: HashMap::new(),
+            unverified: Vec::new(),
         };
 
         let display = format!("{}", result);
@@ -759,6 +1502,7 @@ mod tests {
             approval_url: "https://github.com/owner/repo/issues/1#issuecomment-123".to_string(),
             skipped_unapproved: HashMap::new(),
             skipped_errors: errors,
+            unverified: Vec::new(),
         };
 
         let display = format!("{}", result);
@@ -1023,4 +1767,287 @@ mod tests {
             display
         );
     }
+
+    // Gating QC enforcement
+
+    const GATING_INITIAL_COMMIT: &str = "1234567890abcdef1234567890abcdef12345678";
+    const GATING_APPROVED_COMMIT: &str = "abcdef1234567890abcdef1234567890abcdef12";
+
+    /// Serves an issue gated by an open (#11), a closed and approved (#12) and a closed but
+    /// unapproved (#13) issue, plus one of another repository
+    fn gating_git() -> FakeGit {
+        [11, 12, 13]
+            .into_iter()
+            .filter_map(gating_issue)
+            .fold(FakeGit::new(), FakeGit::with_issue)
+            .with_issue_comments(
+                12,
+                vec![crate::GitComment {
+                    body: format!("# QC Approval\n\napproved qc commit: {GATING_APPROVED_COMMIT}"),
+                    author_login: "reviewer1".to_string(),
+                    created_at: Utc::now(),
+                    html: None,
+                }],
+            )
+            .with_commits(&[GATING_APPROVED_COMMIT, GATING_INITIAL_COMMIT])
+            .with_file_commits(
+                "src/data.R",
+                &[GATING_APPROVED_COMMIT, GATING_INITIAL_COMMIT],
+            )
+            .with_file_commits(
+                "src/model.R",
+                &[GATING_APPROVED_COMMIT, GATING_INITIAL_COMMIT],
+            )
+            .with_file_commits(
+                "src/plots.R",
+                &[GATING_APPROVED_COMMIT, GATING_INITIAL_COMMIT],
+            )
+    }
+
+    fn gated_issue() -> Issue {
+        crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            20,
+            "src/report.R",
+            include_str!("tests/gating_qc/gated_issue.md"),
+            Some(1),
+            "open",
+        )
+    }
+
+    fn gating_issue(number: u64) -> Option<Issue> {
+        let (file, state) = match number {
+            11 => ("src/data.R", "open"),
+            12 => ("src/model.R", "closed"),
+            13 => ("src/plots.R", "closed"),
+            _ => return None,
+        };
+        let body = format!("git branch: main\ninitial qc commit: {GATING_INITIAL_COMMIT}\n");
+        Some(crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            number,
+            file,
+            &body,
+            Some(1),
+            state,
+        ))
+    }
+
+    fn gated_approval() -> QCApprove {
+        QCApprove {
+            file: PathBuf::from("src/report.R"),
+            commit: ObjectId::from_str(GATING_APPROVED_COMMIT).unwrap(),
+            issue: gated_issue(),
+            note: None,
+            effective_date: None,
+            scope: None,
+            incomplete_checklist: None,
+        }
+    }
+
+    fn sorted_keys<V>(map: &HashMap<u64, V>) -> Vec<u64> {
+        let mut keys: Vec<u64> = map.keys().copied().collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_parse_gating_qc_urls() {
+        let blocking_qcs = parse_blocking_qcs(include_str!("tests/gating_qc/gated_issue.md"));
+
+        let urls: Vec<&str> = blocking_qcs.iter().map(|qc| qc.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://github.com/owner/repo/issues/11",
+                "https://github.com/owner/repo/issues/12",
+                "https://github.com/owner/repo/issues/13",
+                "https://github.com/other-org/shared/issues/14",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unapproved_gating_qcs_by_state() {
+        let git = gating_git();
+        let blocking_qcs = parse_blocking_qcs(include_str!("tests/gating_qc/gated_issue.md"));
+
+        let result = get_unapproved_blocking_qcs(&blocking_qcs, &git, None).await;
+
+        // Open and closed-but-unapproved issues block, the approved one does not
+        assert_eq!(sorted_keys(&result.unapproved), vec![11, 13]);
+        // #14 of the other repository is not looked up in this one
+        assert!(result.errors.is_empty());
+        assert_eq!(result.other_repository.len(), 1);
+        assert_eq!(
+            result.other_repository[0].url,
+            "https://github.com/other-org/shared/issues/14"
+        );
+        assert!(format!("{result}").contains("of another repository, not verified"));
+    }
+
+    #[tokio::test]
+    async fn test_approve_refused_with_unapproved_gating_qcs() {
+        let git = gating_git();
+
+        let err = approve_with_validation(&gated_approval(), &git, None, false)
+            .await
+            .unwrap_err();
+
+        match err {
+            ApprovalError::BlockingQCsNotApproved {
+                unapproved_count,
+                error_count,
+                check_result,
+            } => {
+                assert_eq!(unapproved_count, 2);
+                assert_eq!(error_count, 0);
+                assert_eq!(sorted_keys(&check_result.unapproved), vec![11, 13]);
+            }
+            other => panic!("Expected BlockingQCsNotApproved, got {other}"),
+        }
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_approve_forced_past_gating_qcs() {
+        let git = gating_git();
+
+        let result = approve_with_validation(&gated_approval(), &git, None, true)
+            .await
+            .unwrap();
+
+        assert_eq!(git.writes(), vec!["post_comment", "close_issue"]);
+        assert_eq!(sorted_keys(&result.skipped_unapproved), vec![11, 13]);
+        assert_eq!(result.unverified.len(), 1);
+        let display = format!("{result}");
+        assert!(display.contains("--force was used"));
+        assert!(
+            display
+                .contains("could not be verified: https://github.com/other-org/shared/issues/14")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_approval_result_json() {
+        let git = gating_git();
+
+        let result = approve_with_validation(&gated_approval(), &git, None, true)
+            .await
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+
+        assert_eq!(
+            json["approval_url"],
+            "https://github.com/owner/repo/issues/20#issuecomment-1"
+        );
+        let mut skipped: Vec<_> = json["skipped_unapproved"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        skipped.sort();
+        assert_eq!(skipped, vec!["11", "13"]);
+        assert_eq!(
+            json["unverified"],
+            serde_json::json!(["https://github.com/other-org/shared/issues/14"])
+        );
+    }
+
+    #[cfg(feature = "cli")]
+    #[tokio::test]
+    async fn test_posted_comment_json() {
+        let git = gating_git();
+        let comment = crate::QCComment {
+            file: PathBuf::from("src/report.R"),
+            issue: gated_issue(),
+            current_commit: ObjectId::from_str(GATING_APPROVED_COMMIT).unwrap(),
+            previous_commit: None,
+            note: Some("Addressed review".to_string()),
+            no_diff: None,
+            force_diff: false,
+            excel_diff: None,
+            renames: Vec::new(),
+            environment: Vec::new(),
+        };
+
+        let comment_url = git.post_comment(&comment).await.unwrap();
+        let posted = crate::cli::PostedComment::new(
+            "✅ Comment created!",
+            comment.issue.number,
+            comment_url,
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&posted).unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "issue_number": 20,
+                "comment_url": "https://github.com/owner/repo/issues/20#issuecomment-1",
+            })
+        );
+        assert_eq!(git.writes(), vec!["post_comment"]);
+    }
+
+    #[tokio::test]
+    async fn test_approval_draft_matches_posted_body() {
+        let git = gating_git();
+        let approval = QCApprove {
+            note: Some("Looks good".to_string()),
+            ..gated_approval()
+        };
+
+        check_approval_blocking_qcs(&approval, &git, None, true)
+            .await
+            .unwrap();
+        let draft = crate::comment_draft(&approval, &git, false).unwrap();
+        assert!(git.writes().is_empty());
+
+        approve_with_validation(&approval, &git, None, true)
+            .await
+            .unwrap();
+        assert_eq!(git.posted_comments(), vec![draft]);
+    }
+
+    #[tokio::test]
+    async fn test_approval_draft_refused_with_unapproved_gating_qcs() {
+        let git = gating_git();
+
+        let err = check_approval_blocking_qcs(&gated_approval(), &git, None, false)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ApprovalError::BlockingQCsNotApproved {
+                unapproved_count: 2,
+                ..
+            }
+        ));
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unapproval_draft_matches_posted_body() {
+        let git = gating_git();
+        let unapproval = QCUnapprove {
+            issue: gated_issue(),
+            reason: "Model inputs changed".to_string(),
+            commit: ObjectId::from_str(GATING_APPROVED_COMMIT).unwrap(),
+            previously_approved: Some(ObjectId::from_str(GATING_APPROVED_COMMIT).unwrap()),
+            effective_date: None,
+        };
+
+        let draft = crate::comment_draft(&unapproval, &git, false).unwrap();
+        assert!(git.writes().is_empty());
+
+        unapprove_with_impact(&unapproval, &git).await.unwrap();
+        assert_eq!(git.writes(), vec!["post_comment", "open_issue"]);
+        assert_eq!(git.posted_comments(), vec![draft]);
+    }
 }
@@ -1,21 +1,36 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt,
     fs::{self, File},
-    io,
+    io::{self, Read},
     path::{Path, PathBuf},
 };
 
+use chrono::{DateTime, Utc};
 use flate2::{Compression, write::GzEncoder};
 use gix::ObjectId;
+use octocrab::models::Milestone;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::{GitFileOps, GitFileOpsError, IssueError, IssueThread, utils::EnvProvider};
+use crate::{
+    FileReader, GitFileOps, GitFileOpsError, GitRepository, IssueError, IssueThread,
+    utils::{
+        EnvProvider, format_file_size, normalize_repo_path, repo_path_string, sanitize_file_name,
+    },
+};
 
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct ArchiveQC {
     pub milestone: String,
     pub approved: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub issue_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub issue_url: Option<String>,
+    /// Login of who posted the standing approval
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub approved_by: Option<String>,
 }
 
 fn display_as_string<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
@@ -49,74 +64,312 @@ pub struct ArchiveFile {
 }
 
 impl ArchiveFile {
+    /// Whether the file is archived at the root under its file name, away from its directory
+    fn is_flattened(&self) -> bool {
+        self.repository_file
+            .parent()
+            .is_some_and(|parent| !parent.as_os_str().is_empty() && parent != Path::new("/"))
+            && self.repository_file.file_name() == Some(self.archive_file.as_os_str())
+    }
+
     pub fn file_content(&self, git_info: &impl GitFileOps) -> Result<Vec<u8>, GitFileOpsError> {
         git_info.file_bytes_at_commit(&self.repository_file, &self.commit)
     }
 
+    /// Reader of the file's content, streamed when `git_info` can and read into memory otherwise
+    pub fn file_reader(&self, git_info: &impl GitFileOps) -> Result<FileReader, GitFileOpsError> {
+        match git_info.file_reader_at_commit(&self.repository_file, &self.commit)? {
+            Some(reader) => Ok(reader),
+            None => {
+                let content = self.file_content(git_info)?;
+                Ok(FileReader {
+                    size: content.len() as u64,
+                    reader: Box::new(io::Cursor::new(content)),
+                })
+            }
+        }
+    }
+
     pub fn from_issue_thread(
         issue_thread: &IssueThread,
         flatten: bool,
     ) -> Result<Self, ArchiveError> {
-        let (commit, approved) = if let Some(approved_commit) = issue_thread.approved_commit() {
-            (approved_commit.hash, true)
-        } else {
-            (issue_thread.latest_commit().hash.clone(), false)
-        };
-
-        let archive_file = if flatten {
-            issue_thread
-                .file
-                .file_name()
-                .map(PathBuf::from)
-                .expect("File to have file name")
-        } else {
-            issue_thread
-                .file
-                .strip_prefix("/")
-                .unwrap_or(&issue_thread.file)
-                .to_path_buf()
-        };
+        // Archive the signed-off version of the file when the approval still stands
+        let commit = issue_thread.latest_approved_or_activity().hash;
+        let approved = issue_thread.approved_commit().is_some();
 
         Ok(Self {
-            repository_file: issue_thread.file.clone(),
-            archive_file,
+            repository_file: normalize_repo_path(&issue_thread.file),
+            archive_file: archive_path(&issue_thread.file, flatten),
             commit,
             qc: Some(ArchiveQC {
                 milestone: issue_thread.milestone.to_string(),
                 approved,
+                issue_number: Some(issue_thread.issue_number),
+                issue_url: Some(issue_thread.issue_url.clone()),
+                approved_by: issue_thread.approved_by.clone().filter(|_| approved),
             }),
         })
     }
 
+    /// Files listed as relevant to an issue, archived under `relevant/<issue number>/` at the
+    /// commit the issue itself is archived at
+    ///
+    /// Relevant files which do not exist at that commit are skipped with a warning.
+    pub fn from_relevant_files(
+        issue_thread: &IssueThread,
+        git_info: &impl GitFileOps,
+    ) -> Vec<Self> {
+        let commit = issue_thread.latest_approved_or_activity().hash;
+        let relevant_dir = Path::new(RELEVANT_DIR).join(issue_thread.issue_number.to_string());
+        let mut seen = HashSet::new();
+
+        issue_thread
+            .relevant_files
+            .iter()
+            .filter(|relevant| seen.insert(&relevant.file))
+            .map(|relevant| Self {
+                repository_file: normalize_repo_path(&relevant.file),
+                archive_file: normalize_repo_path(
+                    &relevant_dir.join(archive_path(&relevant.file, false)),
+                ),
+                commit,
+                qc: None,
+            })
+            .filter(|archive_file| match archive_file.file_reader(git_info) {
+                Ok(_) => true,
+                Err(e) => {
+                    log::warn!(
+                        "Skipping relevant file {} of issue #{}: {e}",
+                        archive_file.repository_file.display(),
+                        issue_thread.issue_number
+                    );
+                    false
+                }
+            })
+            .collect()
+    }
+
     pub fn from_file(file: impl AsRef<Path>, commit: ObjectId, flatten: bool) -> Self {
         let file = file.as_ref();
-        let archive_file = if flatten {
-            file.file_name()
-                .map(PathBuf::from)
-                .expect("File to have file name")
-        } else {
-            file.strip_prefix("/").unwrap_or(file).to_path_buf()
-        };
         Self {
-            repository_file: file.to_path_buf(),
-            archive_file,
+            repository_file: normalize_repo_path(file),
+            archive_file: archive_path(file, flatten),
             commit,
             qc: None,
         }
     }
 }
 
+/// Path of the repository `file` in the archive, with `/` separators on every platform: its
+/// repository path, or only its file name when `flatten`ed
+fn archive_path(file: &Path, flatten: bool) -> PathBuf {
+    let file = repo_path_string(file);
+    let file = file.trim_start_matches('/');
+    if flatten {
+        PathBuf::from(file.rsplit('/').next().unwrap_or(file))
+    } else {
+        PathBuf::from(file)
+    }
+}
+
+/// Rename flattened files which share a file name with another file of the archive
+///
+/// Each colliding flattened file is archived under a name derived from its repository path, e.g.
+/// `scripts/run.R` and `other/run.R` become `scripts_run.R` and `other_run.R`, so the renaming
+/// does not depend on the order of the files. Files not flattened keep their path.
+fn disambiguate_flattened_files(files: &mut [ArchiveFile]) {
+    let mut by_archive_file: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        by_archive_file
+            .entry(file.archive_file.clone())
+            .or_default()
+            .push(index);
+    }
+
+    for indices in by_archive_file.into_values() {
+        let sources: HashSet<&PathBuf> =
+            indices.iter().map(|&i| &files[i].repository_file).collect();
+        if sources.len() < 2 {
+            continue;
+        }
+        for index in indices {
+            let file = &mut files[index];
+            if !file.is_flattened() {
+                continue;
+            }
+            let archive_file = path_derived_name(&file.repository_file);
+            log::warn!(
+                "Archiving {} as {} since another file is also named {}",
+                file.repository_file.display(),
+                archive_file.display(),
+                file.archive_file.display()
+            );
+            file.archive_file = archive_file;
+        }
+    }
+}
+
+/// File name made of every component of `path`, e.g. `scripts_run.R` for `scripts/run.R`
+fn path_derived_name(path: &Path) -> PathBuf {
+    let components: Vec<_> = path
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect();
+    PathBuf::from(components.join("_"))
+}
+
+/// Time window an issue's approval must fall in to be archived, e.g. for an interim delivery
+/// of everything approved before a data cut
+///
+/// `approved_after` is inclusive and `approved_before` exclusive.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, serde::Deserialize)]
+pub struct ApprovalWindow {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub approved_after: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub approved_before: Option<DateTime<Utc>>,
+}
+
+impl ApprovalWindow {
+    pub fn is_unbounded(&self) -> bool {
+        self.approved_after.is_none() && self.approved_before.is_none()
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.approved_after.is_none_or(|after| time >= after)
+            && self.approved_before.is_none_or(|before| time < before)
+    }
+
+    /// Whether the issue belongs in the archive
+    ///
+    /// Approved issues are kept when their approval falls in the window. Unapproved and obsolete
+    /// issues are only kept with `include_unapproved`, in which case the window applies to their
+    /// latest notification instead.
+    pub fn includes(&self, issue_thread: &IssueThread, include_unapproved: bool) -> bool {
+        if issue_thread.obsoleted.is_none() && issue_thread.approved_commit().is_some() {
+            return self.is_unbounded()
+                || issue_thread.approved_at.is_some_and(|t| self.contains(t));
+        }
+
+        include_unapproved
+            && (self.is_unbounded() || issue_thread.notified_at.is_some_and(|t| self.contains(t)))
+    }
+}
+
+/// Repository an archive was created from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, serde::Deserialize)]
+pub struct ArchiveRepository {
+    pub owner: String,
+    pub repo: String,
+    /// `None` when the archive was created from a detached HEAD
+    pub branch: Option<String>,
+}
+
+impl ArchiveRepository {
+    pub fn new(git_info: &impl GitRepository) -> Self {
+        Self {
+            owner: git_info.owner().to_string(),
+            repo: git_info.repo().to_string(),
+            branch: git_info.branch().ok(),
+        }
+    }
+}
+
+/// A file generated for the archive, such as the milestone record, rather than taken from the
+/// repository
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct GeneratedArchiveFile {
+    pub archive_file: PathBuf,
+    /// What the file is, e.g. `milestone record`
+    pub description: String,
+    #[serde(skip)]
+    pub content: Vec<u8>,
+}
+
+impl GeneratedArchiveFile {
+    pub fn new(
+        archive_file: impl Into<PathBuf>,
+        description: impl Into<String>,
+        content: Vec<u8>,
+    ) -> Self {
+        Self {
+            archive_file: archive_file.into(),
+            description: description.into(),
+            content,
+        }
+    }
+}
+
+/// Sizes of repository files at which archiving warns or stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveSizeLimits {
+    /// Files larger than this many bytes are archived with a warning
+    pub warn_bytes: u64,
+    /// Files larger than this many bytes stop the archive before anything is written
+    pub max_bytes: Option<u64>,
+}
+
+impl ArchiveSizeLimits {
+    pub const DEFAULT_WARN_BYTES: u64 = 1 << 30;
+}
+
+impl Default for ArchiveSizeLimits {
+    fn default() -> Self {
+        Self {
+            warn_bytes: Self::DEFAULT_WARN_BYTES,
+            max_bytes: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct ArchiveMetadata {
     creator: Option<String>,
     created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    repository: ArchiveRepository,
+    /// Approval window the archived issues were selected with
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    approval_window: Option<ApprovalWindow>,
     files: Vec<ArchiveFile>,
+    /// Files generated for the archive, archived after the repository files
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    generated: Vec<GeneratedArchiveFile>,
+    /// SHA-256 of each archived file's content, by archive path, recorded while archiving
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    checksums: BTreeMap<PathBuf, String>,
+    #[serde(skip)]
+    size_limits: ArchiveSizeLimits,
 }
 
 impl ArchiveMetadata {
-    pub fn new(files: Vec<ArchiveFile>, env: &impl EnvProvider) -> Result<Self, ArchiveError> {
-        // Check for duplicate archive paths and collect ALL conflicts
-        let mut path_to_sources = HashMap::new();
+    pub fn new(
+        mut files: Vec<ArchiveFile>,
+        git_info: &impl GitRepository,
+        env: &impl EnvProvider,
+    ) -> Result<Self, ArchiveError> {
+        disambiguate_flattened_files(&mut files);
+
+        // Check for duplicate archive paths and collect ALL conflicts, including files taking
+        // the place of the archive's own metadata files
+        let mut path_to_sources = HashMap::from([
+            (
+                PathBuf::from(METADATA_FILE),
+                vec![PathBuf::from("<archive metadata>")],
+            ),
+            (
+                PathBuf::from(MANIFEST_FILE),
+                vec![PathBuf::from("<archive manifest>")],
+            ),
+            (
+                PathBuf::from(CHECKSUMS_FILE),
+                vec![PathBuf::from("<archive checksums>")],
+            ),
+        ]);
 
         for file in &files {
             let archive_path = &file.archive_file;
@@ -160,22 +413,183 @@ impl ArchiveMetadata {
         Ok(Self {
             creator,
             created_at: chrono::Utc::now(),
+            repository: ArchiveRepository::new(git_info),
+            approval_window: None,
             files,
+            generated: Vec::new(),
+            checksums: BTreeMap::new(),
+            size_limits: ArchiveSizeLimits::default(),
         })
     }
+
+    /// The machine-readable listing of the archive's files
+    pub fn manifest(&self) -> ArchiveManifest {
+        ArchiveManifest {
+            owner: self.repository.owner.clone(),
+            repo: self.repository.repo.clone(),
+            branch: self.repository.branch.clone(),
+            created_at: self.created_at,
+            files: self
+                .files
+                .iter()
+                .map(|file| ArchiveManifestFile {
+                    repository_file: file.repository_file.clone(),
+                    archive_file: file.archive_file.clone(),
+                    commit: file.commit.to_string(),
+                    milestone: file.qc.as_ref().map(|qc| qc.milestone.clone()),
+                    issue_number: file.qc.as_ref().and_then(|qc| qc.issue_number),
+                    issue_url: file.qc.as_ref().and_then(|qc| qc.issue_url.clone()),
+                    approved: file.qc.as_ref().map(|qc| qc.approved),
+                    approved_by: file.qc.as_ref().and_then(|qc| qc.approved_by.clone()),
+                    sha256: self.checksums.get(&file.archive_file).cloned(),
+                })
+                .collect(),
+            generated: self
+                .generated
+                .iter()
+                .map(|file| ArchiveManifestGeneratedFile {
+                    archive_file: file.archive_file.clone(),
+                    description: file.description.clone(),
+                    sha256: self.checksums.get(&file.archive_file).cloned(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Add a generated file to the archive
+    ///
+    /// Repository files keep their archive paths: a generated file whose path is taken, e.g. by a
+    /// flattened repository file of the same name, is archived under a numbered name instead.
+    pub fn with_generated_file(mut self, mut file: GeneratedArchiveFile) -> Self {
+        let taken: HashSet<&Path> = [METADATA_FILE, MANIFEST_FILE, CHECKSUMS_FILE]
+            .into_iter()
+            .map(Path::new)
+            .chain(self.files.iter().map(|f| f.archive_file.as_path()))
+            .chain(self.generated.iter().map(|f| f.archive_file.as_path()))
+            .collect();
+
+        if taken.contains(file.archive_file.as_path()) {
+            let stem = file
+                .archive_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let extension = file.archive_file.extension().map(|e| e.to_os_string());
+            let archive_file = (1..)
+                .map(|n| {
+                    let mut name = file.archive_file.with_file_name(format!("{stem}_{n}"));
+                    if let Some(extension) = &extension {
+                        name.set_extension(extension);
+                    }
+                    name
+                })
+                .find(|name| !taken.contains(name.as_path()))
+                .expect("an unused name");
+            log::warn!(
+                "Archiving the {} as {} since {} is taken by another file",
+                file.description,
+                archive_file.display(),
+                file.archive_file.display()
+            );
+            file.archive_file = archive_file;
+        }
+
+        self.generated.push(file);
+        self
+    }
+
+    /// Record the approval window the milestone issues were selected with
+    pub fn with_approval_window(mut self, approval_window: ApprovalWindow) -> Self {
+        self.approval_window = (!approval_window.is_unbounded()).then_some(approval_window);
+        self
+    }
+
+    /// Set the file sizes at which archiving warns or stops
+    pub fn with_size_limits(mut self, size_limits: ArchiveSizeLimits) -> Self {
+        self.size_limits = size_limits;
+        self
+    }
+}
+
+/// Machine-readable listing of what an archive contains, written to `manifest.json` at the
+/// archive root
+///
+/// This is the stable format for tools validating a delivered archive.
+#[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
+pub struct ArchiveManifest {
+    pub owner: String,
+    pub repo: String,
+    /// `None` when the archive was created from a detached HEAD
+    pub branch: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<ArchiveManifestFile>,
+    /// Files generated for the archive, e.g. the milestone record
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub generated: Vec<ArchiveManifestGeneratedFile>,
 }
 
+/// A file of an [`ArchiveManifest`]
+///
+/// The milestone, issue and approval fields are `None` for files archived without a QC issue.
+#[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
+pub struct ArchiveManifestFile {
+    pub repository_file: PathBuf,
+    pub archive_file: PathBuf,
+    /// Full SHA of the commit the file was archived at
+    pub commit: String,
+    pub milestone: Option<String>,
+    pub issue_number: Option<u64>,
+    pub issue_url: Option<String>,
+    pub approved: Option<bool>,
+    /// Login of who posted the standing approval
+    pub approved_by: Option<String>,
+    /// SHA-256 of the archived content, `None` until the file is archived
+    pub sha256: Option<String>,
+}
+
+/// A generated file of an [`ArchiveManifest`]
+#[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
+pub struct ArchiveManifestGeneratedFile {
+    pub archive_file: PathBuf,
+    pub description: String,
+    /// SHA-256 of the archived content, `None` until the file is archived
+    pub sha256: Option<String>,
+}
+
+const METADATA_FILE: &str = "ghqc_archive_metadata.json";
+/// Directory of the archive holding the relevant files of the archived issues
+const RELEVANT_DIR: &str = "relevant";
+const MANIFEST_FILE: &str = "manifest.json";
+const CHECKSUMS_FILE: &str = "checksums.txt";
+
+/// Write the archive's files, its metadata, its manifest and the checksums of its files to a
+/// tar.gz at `path`
+///
+/// Repository files are streamed into the archive when `git_info` can, so files larger than
+/// memory can be archived. Their sizes are checked against the metadata's size limits before
+/// `path` is created. Returns the manifest written into the archive.
 pub fn archive(
-    archive_metadata: ArchiveMetadata,
+    mut archive_metadata: ArchiveMetadata,
     git_info: &impl GitFileOps,
     path: impl AsRef<Path>,
-) -> Result<(), ArchiveError> {
+) -> Result<ArchiveManifest, ArchiveError> {
     let path = path.as_ref();
     log::debug!(
         "Writing {} files to archive at {}",
         archive_metadata.files.len(),
         path.display()
     );
+    let contents = archive_metadata
+        .files
+        .iter()
+        .map(|archive_file| archive_file.file_reader(git_info))
+        .collect::<Result<Vec<_>, _>>()?;
+    check_file_sizes(
+        &archive_metadata.files,
+        &contents,
+        &archive_metadata.size_limits,
+    )?;
+
     if let Some(parent) = path.parent() {
         if !parent.is_dir() {
             fs::create_dir_all(parent)?;
@@ -186,28 +600,221 @@ pub fn archive(
     let encoder = GzEncoder::new(file, Compression::default());
     let mut tar = tar::Builder::new(encoder);
 
-    log::trace!("Writing metadata file to archive at ghqc_archive_metadata.json");
-    let metadata = serde_json::to_string_pretty(&archive_metadata)?;
-    write_content(&mut tar, "ghqc_archive_metadata.json", metadata.as_bytes())?;
-
-    for archive_file in archive_metadata.files {
+    for (archive_file, content) in archive_metadata.files.iter().zip(contents) {
         log::trace!(
             "Writing {} at {} to archive at {}",
             archive_file.repository_file.display(),
             archive_file.commit.to_string(),
             archive_file.archive_file.display()
         );
-        let content = archive_file.file_content(git_info)?;
-        write_content(&mut tar, &archive_file.archive_file, &content)?;
+        let checksum = write_reader(&mut tar, &archive_file.archive_file, content)?;
+        archive_metadata
+            .checksums
+            .insert(archive_file.archive_file.clone(), checksum);
+    }
+
+    for generated in &archive_metadata.generated {
+        log::trace!(
+            "Writing {} to archive at {}",
+            generated.description,
+            generated.archive_file.display()
+        );
+        write_content(&mut tar, &generated.archive_file, &generated.content)?;
+        archive_metadata.checksums.insert(
+            generated.archive_file.clone(),
+            sha256_hex(&generated.content),
+        );
     }
 
+    log::trace!("Writing metadata file to archive at {METADATA_FILE}");
+    let metadata = serde_json::to_string_pretty(&archive_metadata)?;
+    write_content(&mut tar, METADATA_FILE, metadata.as_bytes())?;
+
+    log::trace!("Writing manifest to archive at {MANIFEST_FILE}");
+    let manifest = archive_metadata.manifest();
+    write_content(
+        &mut tar,
+        MANIFEST_FILE,
+        serde_json::to_string_pretty(&manifest)?.as_bytes(),
+    )?;
+
+    log::trace!("Writing checksums to archive at {CHECKSUMS_FILE}");
+    write_content(
+        &mut tar,
+        CHECKSUMS_FILE,
+        checksums_file(&archive_metadata.checksums).as_bytes(),
+    )?;
+
     tar.finish()?;
     log::debug!(
         "Successfully created compressed archive at {}",
         path.display()
     );
 
-    Ok(())
+    Ok(manifest)
+}
+
+/// Warn about files over the warning size, and fail listing every file over the maximum size
+fn check_file_sizes(
+    files: &[ArchiveFile],
+    contents: &[FileReader],
+    size_limits: &ArchiveSizeLimits,
+) -> Result<(), ArchiveError> {
+    let mut too_large = Vec::new();
+    for (file, content) in files.iter().zip(contents) {
+        if size_limits.max_bytes.is_some_and(|max| content.size > max) {
+            too_large.push(format!(
+                "{} ({})",
+                file.repository_file.display(),
+                format_file_size(content.size)
+            ));
+        } else if content.size > size_limits.warn_bytes {
+            log::warn!(
+                "Archiving {} at {} is {}, over the {} warning size",
+                file.repository_file.display(),
+                &file.commit.to_string()[..7],
+                format_file_size(content.size),
+                format_file_size(size_limits.warn_bytes)
+            );
+        }
+    }
+
+    match size_limits.max_bytes {
+        Some(max) if !too_large.is_empty() => Err(ArchiveError::FileTooLarge {
+            max: format_file_size(max),
+            files: too_large.join(", "),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Checksums in `sha256sum` format, so the archive can also be checked with `sha256sum -c`
+fn checksums_file(checksums: &BTreeMap<PathBuf, String>) -> String {
+    checksums
+        .iter()
+        .map(|(path, digest)| format!("{digest}  {}\n", repo_path_string(path)))
+        .collect()
+}
+
+/// Outcome of checking the files of an archive against its recorded checksums
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerificationReport {
+    /// Files whose content matches their checksum
+    pub verified: Vec<PathBuf>,
+    /// Files whose content does not match their checksum
+    pub mismatched: Vec<ChecksumMismatch>,
+    /// Files with a checksum that are missing from the archive
+    pub missing: Vec<PathBuf>,
+    /// Files in the archive without a checksum
+    pub unlisted: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecksumMismatch {
+    pub path: PathBuf,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty() && self.unlisted.is_empty()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(
+                f,
+                "✅ All {} archived files match their checksums",
+                self.verified.len()
+            );
+        }
+
+        write!(
+            f,
+            "❌ Archive verification failed: {} verified, {} mismatched, {} missing, {} unlisted",
+            self.verified.len(),
+            self.mismatched.len(),
+            self.missing.len(),
+            self.unlisted.len()
+        )?;
+        for mismatch in &self.mismatched {
+            write!(
+                f,
+                "\n   {}: expected {}, found {}",
+                mismatch.path.display(),
+                mismatch.expected,
+                mismatch.actual
+            )?;
+        }
+        for path in &self.missing {
+            write!(f, "\n   {}: missing from the archive", path.display())?;
+        }
+        for path in &self.unlisted {
+            write!(f, "\n   {}: no recorded checksum", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Recompute the SHA-256 of every file in the archive at `path` and compare it to the archive's
+/// `checksums.txt`
+pub fn verify_archive(path: impl AsRef<Path>) -> Result<VerificationReport, ArchiveError> {
+    let file = File::open(path.as_ref())?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+
+    let mut checksums = None;
+    let mut digests = BTreeMap::new();
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = entry.path()?.to_path_buf();
+        let mut content = Vec::new();
+        io::Read::read_to_end(&mut entry, &mut content)?;
+
+        if entry_path == Path::new(CHECKSUMS_FILE) {
+            checksums = Some(parse_checksums_file(&String::from_utf8_lossy(&content))?);
+        } else if entry_path != Path::new(METADATA_FILE) && entry_path != Path::new(MANIFEST_FILE) {
+            digests.insert(entry_path, sha256_hex(&content));
+        }
+    }
+    let mut checksums = checksums.ok_or(ArchiveError::MissingChecksums)?;
+
+    let mut report = VerificationReport::default();
+    for (path, actual) in digests {
+        match checksums.remove(&path) {
+            Some(expected) if expected == actual => report.verified.push(path),
+            Some(expected) => report.mismatched.push(ChecksumMismatch {
+                path,
+                expected,
+                actual,
+            }),
+            None => report.unlisted.push(path),
+        }
+    }
+    report.missing = checksums.into_keys().collect();
+
+    Ok(report)
+}
+
+fn parse_checksums_file(content: &str) -> Result<BTreeMap<PathBuf, String>, ArchiveError> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split_once("  ")
+                .map(|(digest, path)| (PathBuf::from(path), digest.to_string()))
+                .ok_or_else(|| ArchiveError::InvalidChecksumLine(line.to_string()))
+        })
+        .collect()
 }
 
 fn write_content(
@@ -216,7 +823,7 @@ fn write_content(
     content: &[u8],
 ) -> io::Result<()> {
     let mut header = tar::Header::new_gnu();
-    header.set_path(path)?;
+    header.set_path(repo_path_string(path.as_ref()))?;
     header.set_size(content.len() as u64);
     header.set_mode(0o644);
     header.set_cksum();
@@ -224,6 +831,57 @@ fn write_content(
     tar.append(&header, content)
 }
 
+/// Append `content` to the tar under `path` as it is read, returning its SHA-256
+fn write_reader(
+    tar: &mut tar::Builder<GzEncoder<File>>,
+    path: impl AsRef<Path>,
+    content: FileReader,
+) -> Result<String, ArchiveError> {
+    let path = path.as_ref();
+    let mut header = tar::Header::new_gnu();
+    header.set_path(repo_path_string(path))?;
+    header.set_size(content.size);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    let mut reader = ChecksumReader {
+        inner: content.reader.take(content.size),
+        hasher: Sha256::new(),
+        read: 0,
+    };
+    tar.append(&header, &mut reader)?;
+    if reader.read != content.size {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "{} ended after {} of its {} bytes",
+                path.display(),
+                reader.read,
+                content.size
+            ),
+        )
+        .into());
+    }
+
+    Ok(format!("{:x}", reader.hasher.finalize()))
+}
+
+/// Hashes and counts the bytes read through it
+struct ChecksumReader<R> {
+    inner: R,
+    hasher: Sha256,
+    read: u64,
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.read += read as u64;
+        Ok(read)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ArchiveError {
     #[error("Failed to analyze issue due to: {0}")]
@@ -232,26 +890,65 @@ pub enum ArchiveError {
     GitFileOpsError(#[from] GitFileOpsError),
     #[error("Cannot create archive: multiple files have the same archive name '{0}'")]
     FileConflict(String),
+    #[error("Cannot create archive: files are larger than the maximum file size of {max}: {files}")]
+    FileTooLarge { max: String, files: String },
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
     #[error("Failed to determine commit for {0}")]
     CommitDetermination(PathBuf),
     #[error("Failed to serialize metadata: {0}")]
     Serde(#[from] serde_json::Error),
+    #[error("The archive has no {CHECKSUMS_FILE}; it was created before checksums were recorded")]
+    MissingChecksums,
+    #[error("Invalid line in {CHECKSUMS_FILE}: '{0}'")]
+    InvalidChecksumLine(String),
+}
+
+/// Generate archive name based on milestones and repository name
+pub fn generate_archive_name(milestones: &[&Milestone], git_info: &impl GitRepository) -> String {
+    // Get repository name from git_info
+    let repo_name = git_info.repo();
+
+    if milestones.is_empty() {
+        // No milestones: archive/<repo name>.tar.gz
+        format!("{}.tar.gz", repo_name)
+    } else {
+        // With milestones: archive/<repo name>-<milestone1-milestone2>.tar.gz
+        let milestone_names: Vec<String> = milestones
+            .iter()
+            .map(|m| sanitize_file_name(&m.title))
+            .collect();
+
+        format!("{}-{}.tar.gz", repo_name, milestone_names.join("-"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        IssueCommit, IssueThread, git::MockGitFileOps, issue::CommitStatus, utils::MockEnvProvider,
+        FileStashOutcome, GitAuthor, GitRepositoryError, IssueCommit, IssueThread,
+        git::MockGitFileOps,
+        issue::{CommitStatus, IssueRelevantFile},
+        test_utils::{RENOTIFIED_THREAD_COMMITS, create_renotified_after_approval_thread},
+        utils::{MockEnvProvider, parse_date_time},
     };
     use flate2::read::GzDecoder;
     use gix::ObjectId;
     use std::collections::HashMap;
+    use std::str::FromStr;
     use tar::Archive;
     use tempfile::TempDir;
 
+    /// A provider which cannot stream, so files are read with `file_bytes_at_commit`
+    fn in_memory_git() -> MockGitFileOps {
+        let mut mock_git = MockGitFileOps::new();
+        mock_git
+            .expect_file_reader_at_commit()
+            .returning(|_, _| Ok(None));
+        mock_git
+    }
+
     fn create_test_object_id(suffix: &str) -> ObjectId {
         // Create a valid 40-character hex string for SHA-1
         let hex_str = format!("{:0<40}", format!("deadbeef{}", suffix));
@@ -261,7 +958,10 @@ mod tests {
     fn create_test_issue_thread() -> IssueThread {
         IssueThread {
             file: PathBuf::from("src/test.rs"),
+            issue_number: 1,
+            issue_url: "https://github.com/owner/repo/issues/1".to_string(),
             branch: "main".to_string(),
+            branch_deleted: false,
             open: false,
             commits: vec![
                 IssueCommit {
@@ -288,6 +988,17 @@ mod tests {
             ],
             milestone: "v1.0".to_string(),
             blocking_qcs: vec![],
+            relevant_files: Vec::new(),
+            approved_at: None,
+            approval_recorded_at: None,
+            approved_by: None,
+            unapproval: None,
+            notified_at: None,
+            obsoleted: None,
+            inline_checklist: None,
+            rounds: Default::default(),
+            commit_retrieval: Default::default(),
+            renames: Vec::new(),
         }
     }
 
@@ -300,6 +1011,42 @@ mod tests {
         mock_env
     }
 
+    struct TestRepo;
+
+    impl GitRepository for TestRepo {
+        fn commit(&self) -> Result<String, GitRepositoryError> {
+            Ok(String::new())
+        }
+        fn branch(&self) -> Result<String, GitRepositoryError> {
+            Ok("main".to_string())
+        }
+        fn owner(&self) -> &str {
+            "owner"
+        }
+        fn repo(&self) -> &str {
+            "repo"
+        }
+        fn remote_name(&self) -> &str {
+            "origin"
+        }
+        fn path(&self) -> &Path {
+            Path::new(".")
+        }
+        fn fetch(&self) -> Result<bool, GitRepositoryError> {
+            Ok(false)
+        }
+        fn stash_file(
+            &self,
+            _file: &Path,
+            _message: &str,
+        ) -> Result<FileStashOutcome, GitRepositoryError> {
+            Ok(FileStashOutcome::NoChanges)
+        }
+        fn configured_author(&self) -> Option<GitAuthor> {
+            None
+        }
+    }
+
     fn setup_mock_env_no_user() -> MockEnvProvider {
         let mut mock_env = MockEnvProvider::new();
         mock_env
@@ -321,6 +1068,9 @@ mod tests {
                 qc: Some(ArchiveQC {
                     milestone: "v1.0".to_string(),
                     approved: true,
+                    issue_number: None,
+                    issue_url: None,
+                    approved_by: None,
                 }),
             },
             ArchiveFile {
@@ -330,11 +1080,14 @@ mod tests {
                 qc: Some(ArchiveQC {
                     milestone: "v1.0".to_string(),
                     approved: false,
+                    issue_number: None,
+                    issue_url: None,
+                    approved_by: None,
                 }),
             },
         ];
 
-        let result = ArchiveMetadata::new(files.clone(), &mock_env);
+        let result = ArchiveMetadata::new(files.clone(), &TestRepo, &mock_env);
         assert!(result.is_ok());
 
         let metadata = result.unwrap();
@@ -361,7 +1114,7 @@ mod tests {
             qc: None,
         }];
 
-        let result = ArchiveMetadata::new(files, &mock_env);
+        let result = ArchiveMetadata::new(files, &TestRepo, &mock_env);
         assert!(result.is_ok());
 
         let metadata = result.unwrap();
@@ -373,7 +1126,7 @@ mod tests {
     fn test_archive_metadata_new_duplicate_paths_error() {
         let mock_env = setup_mock_env_with_user();
 
-        // Create files that will conflict in the archive (same archive_file path)
+        // The same repository file archived twice, which renaming cannot resolve
         let files = vec![
             ArchiveFile {
                 repository_file: PathBuf::from("src/main.rs"),
@@ -382,20 +1135,20 @@ mod tests {
                 qc: None,
             },
             ArchiveFile {
-                repository_file: PathBuf::from("tests/main.rs"),
+                repository_file: PathBuf::from("src/main.rs"),
                 archive_file: PathBuf::from("main.rs"), // Same flattened path!
                 commit: create_test_object_id("456"),
                 qc: None,
             },
         ];
 
-        let result = ArchiveMetadata::new(files, &mock_env);
+        let result = ArchiveMetadata::new(files, &TestRepo, &mock_env);
         assert!(result.is_err());
 
         match result.unwrap_err() {
             ArchiveError::FileConflict(msg) => {
                 assert!(msg.contains("Conflicts detected"));
-                assert!(msg.contains("src/main.rs + tests/main.rs -> main.rs"));
+                assert!(msg.contains("src/main.rs + src/main.rs -> main.rs"));
             }
             _ => panic!("Expected FileConflict error"),
         }
@@ -409,17 +1162,17 @@ mod tests {
             // First conflict: main.rs
             ArchiveFile {
                 repository_file: PathBuf::from("src/main.rs"),
-                archive_file: PathBuf::from("main.rs"),
+                archive_file: PathBuf::from("src/main.rs"),
                 commit: create_test_object_id("123"),
                 qc: None,
             },
             ArchiveFile {
-                repository_file: PathBuf::from("tests/main.rs"),
-                archive_file: PathBuf::from("main.rs"),
+                repository_file: PathBuf::from("src/main.rs"),
+                archive_file: PathBuf::from("src/main.rs"),
                 commit: create_test_object_id("456"),
                 qc: None,
             },
-            // Second conflict: config.rs
+            // Second conflict: a flattened file renamed onto a file of that name
             ArchiveFile {
                 repository_file: PathBuf::from("src/config.rs"),
                 archive_file: PathBuf::from("config.rs"),
@@ -427,27 +1180,164 @@ mod tests {
                 qc: None,
             },
             ArchiveFile {
-                repository_file: PathBuf::from("lib/config.rs"),
+                repository_file: PathBuf::from("config.rs"),
                 archive_file: PathBuf::from("config.rs"),
                 commit: create_test_object_id("abc"),
                 qc: None,
             },
+            ArchiveFile {
+                repository_file: PathBuf::from("src_config.rs"),
+                archive_file: PathBuf::from("src_config.rs"),
+                commit: create_test_object_id("def"),
+                qc: None,
+            },
         ];
 
-        let result = ArchiveMetadata::new(files, &mock_env);
+        let result = ArchiveMetadata::new(files, &TestRepo, &mock_env);
         assert!(result.is_err());
 
         match result.unwrap_err() {
             ArchiveError::FileConflict(msg) => {
                 assert!(msg.contains("Conflicts detected"));
                 // Should contain both conflicts
-                assert!(msg.contains("main.rs"));
-                assert!(msg.contains("config.rs"));
+                assert!(msg.contains("src/main.rs + src/main.rs -> src/main.rs"));
+                assert!(msg.contains("src_config.rs"));
             }
             _ => panic!("Expected FileConflict error"),
         }
     }
 
+    /// Approved threads of `scripts/run.R` in v1.0 and `other/run.R` in v2.0
+    fn colliding_issue_threads() -> Vec<IssueThread> {
+        let mut scripts = create_test_issue_thread();
+        scripts.file = PathBuf::from("scripts/run.R");
+
+        let mut other = create_test_issue_thread();
+        other.file = PathBuf::from("other/run.R");
+        other.issue_number = 2;
+        other.issue_url = "https://github.com/owner/repo/issues/2".to_string();
+        other.milestone = "v2.0".to_string();
+
+        vec![scripts, other]
+    }
+
+    fn archive_metadata_for(threads: &[IssueThread], flatten: bool) -> ArchiveMetadata {
+        let files = threads
+            .iter()
+            .map(|thread| ArchiveFile::from_issue_thread(thread, flatten).unwrap())
+            .collect();
+        ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap()
+    }
+
+    fn manifest_paths(metadata: &ArchiveMetadata) -> Vec<(PathBuf, PathBuf)> {
+        metadata
+            .manifest()
+            .files
+            .into_iter()
+            .map(|f| (f.repository_file, f.archive_file))
+            .collect()
+    }
+
+    #[test]
+    fn test_archive_metadata_colliding_basenames_not_flattened() {
+        let metadata = archive_metadata_for(&colliding_issue_threads(), false);
+
+        assert_eq!(
+            manifest_paths(&metadata),
+            vec![
+                (
+                    PathBuf::from("scripts/run.R"),
+                    PathBuf::from("scripts/run.R")
+                ),
+                (PathBuf::from("other/run.R"), PathBuf::from("other/run.R")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_metadata_colliding_basenames_flattened() {
+        let metadata = archive_metadata_for(&colliding_issue_threads(), true);
+
+        assert_eq!(
+            manifest_paths(&metadata),
+            vec![
+                (
+                    PathBuf::from("scripts/run.R"),
+                    PathBuf::from("scripts_run.R")
+                ),
+                (PathBuf::from("other/run.R"), PathBuf::from("other_run.R")),
+            ]
+        );
+
+        // The names do not depend on the order of the files
+        let mut reversed = colliding_issue_threads();
+        reversed.reverse();
+        let reversed = archive_metadata_for(&reversed, true);
+        assert_eq!(
+            manifest_paths(&reversed),
+            vec![
+                (PathBuf::from("other/run.R"), PathBuf::from("other_run.R")),
+                (
+                    PathBuf::from("scripts/run.R"),
+                    PathBuf::from("scripts_run.R")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_flattened_collision_keeps_root_file_name() {
+        let files = vec![
+            ArchiveFile::from_file("run.R", create_test_object_id("123"), true),
+            ArchiveFile::from_file("scripts/run.R", create_test_object_id("456"), true),
+            ArchiveFile::from_file("scripts/plot.R", create_test_object_id("456"), true),
+        ];
+
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap();
+
+        assert_eq!(
+            manifest_paths(&metadata),
+            vec![
+                (PathBuf::from("run.R"), PathBuf::from("run.R")),
+                (
+                    PathBuf::from("scripts/run.R"),
+                    PathBuf::from("scripts_run.R")
+                ),
+                (PathBuf::from("scripts/plot.R"), PathBuf::from("plot.R")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_flattened_collision_writes_both_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("flattened.tar.gz");
+
+        let mut mock_git = in_memory_git();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(|file, _| Ok(file.to_string_lossy().into_owned().into_bytes()));
+
+        let metadata = archive_metadata_for(&colliding_issue_threads(), true);
+        archive(metadata, &mock_git, &archive_path).unwrap();
+
+        let file = std::fs::File::open(&archive_path).unwrap();
+        let mut archive = Archive::new(GzDecoder::new(file));
+        let mut entries: HashMap<String, String> = HashMap::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().to_string();
+            let mut contents = String::new();
+            std::io::Read::read_to_string(&mut entry, &mut contents).unwrap();
+            entries.insert(path, contents);
+        }
+
+        assert_eq!(entries["scripts_run.R"], "scripts/run.R");
+        assert_eq!(entries["other_run.R"], "other/run.R");
+        let metadata = &entries["ghqc_archive_metadata.json"];
+        assert!(metadata.contains("\"archive_file\": \"scripts_run.R\""));
+    }
+
     #[test]
     fn test_archive_file_from_issue_thread_approved() {
         let issue_thread = create_test_issue_thread();
@@ -516,6 +1406,58 @@ mod tests {
         assert!(!qc.approved);
     }
 
+    #[test]
+    fn test_archive_file_from_issue_thread_renotified_after_approval() {
+        let issue_thread = create_renotified_after_approval_thread();
+
+        let archive_file = ArchiveFile::from_issue_thread(&issue_thread, false).unwrap();
+
+        // A notification after the approval does not move the archived commit
+        assert_eq!(
+            archive_file.commit,
+            ObjectId::from_str(RENOTIFIED_THREAD_COMMITS[2]).unwrap()
+        );
+        assert!(archive_file.qc.unwrap().approved);
+    }
+
+    #[test]
+    fn test_archive_file_from_relevant_files() {
+        let mut issue_thread = create_test_issue_thread();
+        let relevant = |file: &str| IssueRelevantFile {
+            file: PathBuf::from(file),
+            kind: "Relevant File".to_string(),
+            justification: None,
+        };
+        issue_thread.relevant_files = vec![
+            relevant("data/config.yaml"),
+            relevant("src/missing.R"),
+            relevant("data/config.yaml"),
+        ];
+        let approved = create_test_object_id("456");
+        let mut mock_git = in_memory_git();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .withf(move |file, commit| file == Path::new("data/config.yaml") && *commit == approved)
+            .returning(|_, _| Ok(b"seed: 1".to_vec()));
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(|file, _| Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf())));
+
+        let archive_files = ArchiveFile::from_relevant_files(&issue_thread, &mock_git);
+
+        assert_eq!(archive_files.len(), 1);
+        assert_eq!(
+            archive_files[0].repository_file,
+            PathBuf::from("data/config.yaml")
+        );
+        assert_eq!(
+            archive_files[0].archive_file,
+            PathBuf::from("relevant/1/data/config.yaml")
+        );
+        assert_eq!(archive_files[0].commit, approved);
+        assert!(archive_files[0].qc.is_none());
+    }
+
     #[test]
     fn test_archive_file_from_file() {
         let file_path = PathBuf::from("src/example.rs");
@@ -541,6 +1483,25 @@ mod tests {
         assert_eq!(archive_file.commit, commit);
     }
 
+    #[test]
+    fn test_archive_file_from_file_windows_separators() {
+        let commit = create_test_object_id("123");
+
+        let archive_file =
+            ArchiveFile::from_file(Path::new(r"analysis\pk model\fit.R"), commit, false);
+        assert_eq!(
+            archive_file.repository_file,
+            PathBuf::from("analysis/pk model/fit.R")
+        );
+        assert_eq!(
+            archive_file.archive_file,
+            PathBuf::from("analysis/pk model/fit.R")
+        );
+
+        let flattened = ArchiveFile::from_file(Path::new(r"analysis/pk model\fit.R"), commit, true);
+        assert_eq!(flattened.archive_file, PathBuf::from("fit.R"));
+    }
+
     #[test]
     fn test_archive_file_content() {
         let mut mock_git = MockGitFileOps::new();
@@ -572,7 +1533,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let archive_path = temp_dir.path().join("test_archive.tar.gz");
 
-        let mut mock_git = MockGitFileOps::new();
+        let mut mock_git = in_memory_git();
         let file1_content = b"content of file1";
         let file2_content = b"content of file2";
 
@@ -602,6 +1563,9 @@ mod tests {
                 qc: Some(ArchiveQC {
                     milestone: "v1.0".to_string(),
                     approved: true,
+                    issue_number: None,
+                    issue_url: None,
+                    approved_by: None,
                 }),
             },
             ArchiveFile {
@@ -611,11 +1575,14 @@ mod tests {
                 qc: Some(ArchiveQC {
                     milestone: "v1.0".to_string(),
                     approved: false,
+                    issue_number: None,
+                    issue_url: None,
+                    approved_by: None,
                 }),
             },
         ];
 
-        let metadata = ArchiveMetadata::new(files, &mock_env).unwrap();
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &mock_env).unwrap();
         let result = archive(metadata, &mock_git, &archive_path);
 
         assert!(result.is_ok());
@@ -635,9 +1602,11 @@ mod tests {
             entries.insert(path, contents);
         }
 
-        // Should contain metadata file + 2 source files
-        assert_eq!(entries.len(), 3);
+        // Should contain metadata file + manifest + checksums + 2 source files
+        assert_eq!(entries.len(), 5);
         assert!(entries.contains_key("ghqc_archive_metadata.json"));
+        assert!(entries.contains_key("manifest.json"));
+        assert!(entries.contains_key("checksums.txt"));
         assert!(entries.contains_key("file1.rs"));
         assert!(entries.contains_key("file2.rs"));
 
@@ -662,7 +1631,7 @@ mod tests {
             .join("directory")
             .join("archive.tar.gz");
 
-        let mut mock_git = MockGitFileOps::new();
+        let mut mock_git = in_memory_git();
         let file_content = b"test content";
 
         mock_git
@@ -678,7 +1647,7 @@ mod tests {
             qc: None,
         }];
 
-        let metadata = ArchiveMetadata::new(files, &mock_env).unwrap();
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &mock_env).unwrap();
         let result = archive(metadata, &mock_git, &nested_path);
 
         assert!(result.is_ok());
@@ -691,7 +1660,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let archive_path = temp_dir.path().join("structured_archive.tar.gz");
 
-        let mut mock_git = MockGitFileOps::new();
+        let mut mock_git = in_memory_git();
         let file_content = b"content";
 
         mock_git
@@ -715,7 +1684,7 @@ mod tests {
             },
         ];
 
-        let metadata = ArchiveMetadata::new(files, &mock_env).unwrap();
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &mock_env).unwrap();
         let result = archive(metadata, &mock_git, &archive_path);
 
         assert!(result.is_ok());
@@ -735,4 +1704,566 @@ mod tests {
         assert!(paths.contains(&"tests/integration.rs".to_string()));
         assert!(paths.contains(&"ghqc_archive_metadata.json".to_string()));
     }
+
+    #[test]
+    fn test_archive_entries_use_forward_slashes() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("windows_archive.tar.gz");
+
+        let mut mock_git = in_memory_git();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(|_, _| Ok(b"content".to_vec()));
+
+        let mock_env = setup_mock_env_with_user();
+
+        let files = vec![ArchiveFile {
+            repository_file: PathBuf::from(r"scripts\data prep.R"),
+            archive_file: PathBuf::from(r"scripts\data prep.R"),
+            commit: create_test_object_id("123"),
+            qc: None,
+        }];
+
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &mock_env).unwrap();
+        archive(metadata, &mock_git, &archive_path).unwrap();
+
+        let file = std::fs::File::open(&archive_path).unwrap();
+        let mut archive = Archive::new(GzDecoder::new(file));
+        let paths: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert!(paths.contains(&"scripts/data prep.R".to_string()));
+        assert!(paths.iter().all(|p| !p.contains('\\')));
+    }
+
+    /// SHA-256 of `b"content"`
+    const CONTENT_SHA256: &str = "ed7002b439e9ac845f22357d822bac1444730fbdb6016d3ec9432297b9ec9f73";
+
+    #[test]
+    fn test_archive_manifest_round_trips() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+
+        let mut mock_git = in_memory_git();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(|_, _| Ok(b"content".to_vec()));
+
+        let mut issue_thread = create_test_issue_thread();
+        issue_thread.approved_by = Some("reviewer".to_string());
+        let files = vec![
+            ArchiveFile::from_issue_thread(&issue_thread, false).unwrap(),
+            ArchiveFile::from_file("data/input.csv", create_test_object_id("789"), false),
+        ];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap();
+        let manifest = archive(metadata, &mock_git, &archive_path).unwrap();
+
+        let file = std::fs::File::open(&archive_path).unwrap();
+        let mut archive = Archive::new(GzDecoder::new(file));
+        let entry = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap())
+            .find(|e| e.path().unwrap() == Path::new("manifest.json"))
+            .expect("manifest.json at the archive root");
+        let unpacked: ArchiveManifest = serde_json::from_reader(entry).unwrap();
+
+        assert_eq!(unpacked, manifest);
+        assert_eq!(unpacked.owner, "owner");
+        assert_eq!(unpacked.repo, "repo");
+        assert_eq!(unpacked.branch.as_deref(), Some("main"));
+        assert_eq!(
+            unpacked.files,
+            vec![
+                ArchiveManifestFile {
+                    repository_file: PathBuf::from("src/test.rs"),
+                    archive_file: PathBuf::from("src/test.rs"),
+                    commit: create_test_object_id("456").to_string(),
+                    milestone: Some("v1.0".to_string()),
+                    issue_number: Some(1),
+                    issue_url: Some("https://github.com/owner/repo/issues/1".to_string()),
+                    approved: Some(true),
+                    approved_by: Some("reviewer".to_string()),
+                    sha256: Some(CONTENT_SHA256.to_string()),
+                },
+                ArchiveManifestFile {
+                    repository_file: PathBuf::from("data/input.csv"),
+                    archive_file: PathBuf::from("data/input.csv"),
+                    commit: create_test_object_id("789").to_string(),
+                    milestone: None,
+                    issue_number: None,
+                    issue_url: None,
+                    approved: None,
+                    approved_by: None,
+                    sha256: Some(CONTENT_SHA256.to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_checksums_verify_empty_and_binary_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+        // Zip header followed by bytes that are not valid UTF-8, as in an xlsx
+        let binary = vec![0x50, 0x4b, 0x03, 0x04, 0x00, 0xff, 0xfe, 0x80];
+
+        let mut mock_git = in_memory_git();
+        let binary_content = binary.clone();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(move |file, _| {
+                Ok(match file.to_str().unwrap() {
+                    "data/empty.txt" => Vec::new(),
+                    "data/results.xlsx" => binary_content.clone(),
+                    _ => b"content".to_vec(),
+                })
+            });
+
+        let files = vec![
+            ArchiveFile::from_file("data/empty.txt", create_test_object_id("123"), false),
+            ArchiveFile::from_file("data/results.xlsx", create_test_object_id("456"), false),
+            ArchiveFile::from_file("src/test.rs", create_test_object_id("789"), false),
+        ];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap();
+        let manifest = archive(metadata, &mock_git, &archive_path).unwrap();
+
+        let checksums: Vec<_> = manifest
+            .files
+            .iter()
+            .map(|f| f.sha256.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            checksums,
+            vec![
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                sha256_hex(&binary).as_str(),
+                CONTENT_SHA256,
+            ]
+        );
+
+        let report = verify_archive(&archive_path).unwrap();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(
+            report.verified,
+            vec![
+                PathBuf::from("data/empty.txt"),
+                PathBuf::from("data/results.xlsx"),
+                PathBuf::from("src/test.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive_generated_file_is_written_and_verified() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+
+        let mut mock_git = in_memory_git();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(|_, _| Ok(b"content".to_vec()));
+
+        let files = vec![ArchiveFile::from_file(
+            "src/test.rs",
+            create_test_object_id("123"),
+            false,
+        )];
+        let record = b"%PDF-1.7 record".to_vec();
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user())
+            .unwrap()
+            .with_generated_file(GeneratedArchiveFile::new(
+                "QC_RECORD.pdf",
+                "milestone record",
+                record.clone(),
+            ));
+        let manifest = archive(metadata, &mock_git, &archive_path).unwrap();
+
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(
+            manifest.generated,
+            vec![ArchiveManifestGeneratedFile {
+                archive_file: PathBuf::from("QC_RECORD.pdf"),
+                description: "milestone record".to_string(),
+                sha256: Some(sha256_hex(&record)),
+            }]
+        );
+
+        let report = verify_archive(&archive_path).unwrap();
+        assert!(report.is_valid(), "{report}");
+        assert!(report.verified.contains(&PathBuf::from("QC_RECORD.pdf")));
+    }
+
+    #[test]
+    fn test_archive_generated_file_renamed_on_collision() {
+        // A flattened repository file takes the record's name at the archive root
+        let files = vec![
+            ArchiveFile::from_file("reports/QC_RECORD.pdf", create_test_object_id("123"), true),
+            ArchiveFile::from_file("QC_RECORD_1.pdf", create_test_object_id("456"), true),
+        ];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user())
+            .unwrap()
+            .with_generated_file(GeneratedArchiveFile::new(
+                "QC_RECORD.pdf",
+                "milestone record",
+                Vec::new(),
+            ));
+
+        let manifest = metadata.manifest();
+        assert_eq!(
+            manifest.files[0].archive_file,
+            PathBuf::from("QC_RECORD.pdf")
+        );
+        assert_eq!(
+            manifest.generated[0].archive_file,
+            PathBuf::from("QC_RECORD_2.pdf")
+        );
+    }
+
+    #[test]
+    fn test_verify_archive_reports_tampered_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+
+        let mut mock_git = in_memory_git();
+        mock_git
+            .expect_file_bytes_at_commit()
+            .returning(|_, _| Ok(b"content".to_vec()));
+        let files = vec![
+            ArchiveFile::from_file("a.R", create_test_object_id("123"), false),
+            ArchiveFile::from_file("b.R", create_test_object_id("123"), false),
+        ];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap();
+        archive(metadata, &mock_git, &archive_path).unwrap();
+
+        // Repack the archive with a.R edited, b.R removed and an extra file added
+        let tampered_path = temp_dir.path().join("tampered.tar.gz");
+        let mut tampered = tar::Builder::new(GzEncoder::new(
+            File::create(&tampered_path).unwrap(),
+            Compression::default(),
+        ));
+        let mut archive = Archive::new(GzDecoder::new(File::open(&archive_path).unwrap()));
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_path_buf();
+            let mut content = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut content).unwrap();
+            match path.to_str().unwrap() {
+                "a.R" => write_content(&mut tampered, &path, b"edited").unwrap(),
+                "b.R" => {}
+                _ => write_content(&mut tampered, &path, &content).unwrap(),
+            }
+        }
+        write_content(&mut tampered, "c.R", b"extra").unwrap();
+        tampered.into_inner().unwrap().finish().unwrap();
+
+        let report = verify_archive(&tampered_path).unwrap();
+        assert!(!report.is_valid());
+        assert!(report.verified.is_empty());
+        assert_eq!(
+            report.mismatched,
+            vec![ChecksumMismatch {
+                path: PathBuf::from("a.R"),
+                expected: CONTENT_SHA256.to_string(),
+                actual: sha256_hex(b"edited"),
+            }]
+        );
+        assert_eq!(report.missing, vec![PathBuf::from("b.R")]);
+        assert_eq!(report.unlisted, vec![PathBuf::from("c.R")]);
+    }
+
+    #[test]
+    fn test_archive_metadata_new_manifest_path_conflict() {
+        let files = vec![ArchiveFile::from_file(
+            "config/manifest.json",
+            create_test_object_id("123"),
+            true,
+        )];
+
+        let result = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user());
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("config/manifest.json"));
+        assert!(error.contains("-> manifest.json"));
+    }
+
+    fn thread_with_times(approved_at: Option<&str>, notified_at: Option<&str>) -> IssueThread {
+        let parse = |t: &str| parse_date_time(t).unwrap();
+        let mut thread = create_test_issue_thread();
+        if approved_at.is_none() {
+            for commit in &mut thread.commits {
+                commit.statuses.remove(&CommitStatus::Approved);
+            }
+        }
+        thread.approved_at = approved_at.map(parse);
+        thread.notified_at = notified_at.map(parse);
+        thread
+    }
+
+    #[test]
+    fn test_approval_window_straddling_boundary() {
+        // Everything approved before the June data cut, given in US Eastern time
+        let window = ApprovalWindow {
+            approved_after: None,
+            approved_before: Some(parse_date_time("2026-06-01T00:00:00-04:00").unwrap()),
+        };
+
+        let just_before = thread_with_times(Some("2026-06-01T03:59:59Z"), None);
+        let at_cut = thread_with_times(Some("2026-06-01T04:00:00Z"), None);
+        let after_cut = thread_with_times(Some("2026-06-01T10:00:00+02:00"), None);
+
+        assert!(window.includes(&just_before, false));
+        assert!(!window.includes(&at_cut, false));
+        // 08:00 UTC, after the cut even though the local date is still June 1st
+        assert!(!window.includes(&after_cut, false));
+    }
+
+    #[test]
+    fn test_approval_window_after_is_inclusive() {
+        let window = ApprovalWindow {
+            approved_after: Some(parse_date_time("2026-05-01").unwrap()),
+            approved_before: Some(parse_date_time("2026-06-01").unwrap()),
+        };
+
+        assert!(window.includes(
+            &thread_with_times(Some("2026-05-01T00:00:00Z"), None),
+            false
+        ));
+        assert!(!window.includes(
+            &thread_with_times(Some("2026-04-30T23:59:59Z"), None),
+            false
+        ));
+    }
+
+    #[test]
+    fn test_approval_window_unapproved_issues() {
+        let window = ApprovalWindow {
+            approved_after: None,
+            approved_before: Some(parse_date_time("2026-06-01").unwrap()),
+        };
+        let notified_before = thread_with_times(None, Some("2026-05-20T12:00:00Z"));
+        let notified_after = thread_with_times(None, Some("2026-06-02T12:00:00Z"));
+
+        // Unapproved issues are excluded regardless of the window
+        assert!(!window.includes(&notified_before, false));
+        // With include_unapproved, the window applies to the latest notification
+        assert!(window.includes(&notified_before, true));
+        assert!(!window.includes(&notified_after, true));
+
+        // An unbounded window keeps the existing behavior
+        let unbounded = ApprovalWindow::default();
+        assert!(!unbounded.includes(&notified_after, false));
+        assert!(unbounded.includes(&notified_after, true));
+        assert!(unbounded.includes(
+            &thread_with_times(Some("2026-06-02T12:00:00Z"), None),
+            false
+        ));
+    }
+
+    #[test]
+    fn test_approval_window_excludes_obsolete_issues() {
+        let mut obsolete = thread_with_times(Some("2026-05-20T12:00:00Z"), None);
+        obsolete.obsoleted = Some(crate::issue::Obsoletion {
+            reason: "Dropped from the analysis plan".to_string(),
+            actor: None,
+            date: None,
+        });
+
+        // A standing approval does not keep an obsolete issue in the archive
+        let unbounded = ApprovalWindow::default();
+        assert!(!unbounded.includes(&obsolete, false));
+        assert!(unbounded.includes(&obsolete, true));
+    }
+
+    #[test]
+    fn test_archive_metadata_records_approval_window() {
+        let mock_env = setup_mock_env_with_user();
+        let window = ApprovalWindow {
+            approved_after: None,
+            approved_before: Some(parse_date_time("2026-06-01").unwrap()),
+        };
+
+        let metadata = ArchiveMetadata::new(Vec::new(), &TestRepo, &mock_env)
+            .unwrap()
+            .with_approval_window(window.clone());
+        let json: serde_json::Value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(
+            json["approval_window"],
+            serde_json::json!({ "approved_before": "2026-06-01T00:00:00Z" })
+        );
+
+        let parsed: ArchiveMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.approval_window, Some(window));
+
+        // An unbounded window is left out of the metadata
+        let metadata = ArchiveMetadata::new(Vec::new(), &TestRepo, &mock_env)
+            .unwrap()
+            .with_approval_window(ApprovalWindow::default());
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert!(json.get("approval_window").is_none());
+    }
+
+    /// `len` bytes of a repeating pattern, generated as they are read
+    struct PatternReader {
+        position: u64,
+        len: u64,
+    }
+
+    impl PatternReader {
+        fn byte(position: u64) -> u8 {
+            (position % 251) as u8
+        }
+    }
+
+    impl Read for PatternReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = buf.len().min((self.len - self.position) as usize);
+            for byte in &mut buf[..read] {
+                *byte = Self::byte(self.position);
+                self.position += 1;
+            }
+            Ok(read)
+        }
+    }
+
+    /// A provider streaming every file as `len` bytes of the pattern
+    fn streaming_git(len: u64) -> MockGitFileOps {
+        let mut mock_git = MockGitFileOps::new();
+        mock_git
+            .expect_file_reader_at_commit()
+            .returning(move |_, _| {
+                Ok(Some(FileReader {
+                    size: len,
+                    reader: Box::new(PatternReader { position: 0, len }),
+                }))
+            });
+        mock_git.expect_file_bytes_at_commit().never();
+        mock_git
+    }
+
+    #[test]
+    fn test_archive_streams_large_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+        let len = 8 * 1024 * 1024 + 17;
+        let mock_git = streaming_git(len);
+
+        let files = vec![ArchiveFile::from_file(
+            "data/large.csv",
+            create_test_object_id("123"),
+            false,
+        )];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap();
+        let manifest = archive(metadata, &mock_git, &archive_path).unwrap();
+
+        let mut expected = ChecksumReader {
+            inner: PatternReader { position: 0, len },
+            hasher: Sha256::new(),
+            read: 0,
+        };
+        io::copy(&mut expected, &mut io::sink()).unwrap();
+        let expected = format!("{:x}", expected.hasher.finalize());
+        assert_eq!(manifest.files[0].sha256.as_deref(), Some(expected.as_str()));
+
+        // The entry holds the streamed bytes, in order
+        let file = std::fs::File::open(&archive_path).unwrap();
+        let mut archive = Archive::new(GzDecoder::new(file));
+        let mut entry = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.path().unwrap() == Path::new("data/large.csv"))
+            .unwrap();
+        assert_eq!(entry.header().size().unwrap(), len);
+        let mut position = 0;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let read = entry.read(&mut buf).unwrap();
+            if read == 0 {
+                break;
+            }
+            assert!(
+                buf[..read]
+                    .iter()
+                    .enumerate()
+                    .all(|(i, byte)| *byte == PatternReader::byte(position + i as u64))
+            );
+            position += read as u64;
+        }
+        assert_eq!(position, len);
+
+        assert!(verify_archive(&archive_path).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_archive_max_file_size_fails_before_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+        let mock_git = streaming_git(2 * 1024 * 1024);
+
+        let files = vec![
+            ArchiveFile::from_file("data/large.csv", create_test_object_id("123"), false),
+            ArchiveFile::from_file("data/other.csv", create_test_object_id("456"), false),
+        ];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user())
+            .unwrap()
+            .with_size_limits(ArchiveSizeLimits {
+                warn_bytes: 1024,
+                max_bytes: Some(1024 * 1024),
+            });
+        let err = archive(metadata, &mock_git, &archive_path).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Cannot create archive: files are larger than the maximum file size of 1.00 MB: \
+            data/large.csv (2.00 MB), data/other.csv (2.00 MB)"
+        );
+        assert!(!archive_path.exists());
+
+        // Files over the warning size are still archived
+        let files = vec![ArchiveFile::from_file(
+            "data/large.csv",
+            create_test_object_id("123"),
+            false,
+        )];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user())
+            .unwrap()
+            .with_size_limits(ArchiveSizeLimits {
+                warn_bytes: 1024,
+                max_bytes: Some(2 * 1024 * 1024),
+            });
+        assert!(archive(metadata, &mock_git, &archive_path).is_ok());
+    }
+
+    #[test]
+    fn test_archive_fails_on_truncated_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+        let mut mock_git = MockGitFileOps::new();
+        mock_git.expect_file_reader_at_commit().returning(|_, _| {
+            Ok(Some(FileReader {
+                size: 100,
+                reader: Box::new(PatternReader {
+                    position: 0,
+                    len: 10,
+                }),
+            }))
+        });
+
+        let files = vec![ArchiveFile::from_file(
+            "data/large.csv",
+            create_test_object_id("123"),
+            false,
+        )];
+        let metadata = ArchiveMetadata::new(files, &TestRepo, &setup_mock_env_with_user()).unwrap();
+        let err = archive(metadata, &mock_git, &archive_path).unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("data/large.csv ended after 10 of its 100 bytes")
+        );
+    }
 }
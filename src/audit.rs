@@ -0,0 +1,840 @@
+//! Local, append-only audit log of the changes ghqc makes on GitHub
+//!
+//! [`AuditedGit`] wraps a git provider and appends a JSON line per successful write to the
+//! repository's audit log, kept next to its cache directory so clearing the cache leaves it
+//! alone. Recording never fails the write itself: a log that cannot be written is warned about.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use gix::ObjectId;
+use octocrab::models::{Milestone, issues::Issue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use crate::cache::{CacheError, resolve_cache_root};
+use crate::comment_system::CommentBody;
+use crate::utils::EnvProvider;
+use crate::{
+    FileLastCommit, FileReader, FileRenameEvent, FileStashOutcome, GitAuthor, GitComment,
+    GitCommit, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError, GitHubReader,
+    GitHubWriter, GitRepository, GitRepositoryError, GitState, GitStatusError, GitStatusOps,
+    MilestoneState, MilestoneUpdate, PathAttributes, Permission, QCIssue, RepoUser,
+    SignatureStatus, SigningKeys,
+};
+
+/// A change made on GitHub
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOperation {
+    MilestoneCreated,
+    MilestoneRenamed,
+    MilestoneUpdated,
+    MilestoneClosed,
+    MilestoneReopened,
+    IssueCreated,
+    CommentPosted,
+    IssueClosed,
+    IssueReopened,
+    IssueUpdated,
+    IssueBlocked,
+    LabelCreated,
+    LabelsAdded,
+    LabelRemoved,
+    AssigneesChanged,
+}
+
+impl fmt::Display for AuditOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::MilestoneCreated => "milestone_created",
+            Self::MilestoneRenamed => "milestone_renamed",
+            Self::MilestoneUpdated => "milestone_updated",
+            Self::MilestoneClosed => "milestone_closed",
+            Self::MilestoneReopened => "milestone_reopened",
+            Self::IssueCreated => "issue_created",
+            Self::CommentPosted => "comment_posted",
+            Self::IssueClosed => "issue_closed",
+            Self::IssueReopened => "issue_reopened",
+            Self::IssueUpdated => "issue_updated",
+            Self::IssueBlocked => "issue_blocked",
+            Self::LabelCreated => "label_created",
+            Self::LabelsAdded => "labels_added",
+            Self::LabelRemoved => "label_removed",
+            Self::AssigneesChanged => "assignees_changed",
+        };
+        f.pad(name)
+    }
+}
+
+/// A line of the audit log. Fields the operation does not know about are null
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// GitHub login of the authenticated user
+    pub actor: Option<String>,
+    pub operation: AuditOperation,
+    pub issue: Option<u64>,
+    pub milestone: Option<String>,
+    /// File of the QC issue, i.e. its title
+    pub file: Option<String>,
+    /// URL GitHub returned for the created milestone, issue or comment
+    pub url: Option<String>,
+    /// Labels, assignees or fields changed by the operation
+    pub details: Option<String>,
+}
+
+impl AuditEntry {
+    fn new(operation: AuditOperation) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            actor: None,
+            operation,
+            issue: None,
+            milestone: None,
+            file: None,
+            url: None,
+            details: None,
+        }
+    }
+
+    fn issue(mut self, number: u64) -> Self {
+        self.issue = Some(number);
+        self
+    }
+
+    fn milestone(mut self, title: impl Into<String>) -> Self {
+        self.milestone = Some(title.into());
+        self
+    }
+
+    fn file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// Issue number, milestone and file of the GitHub issue
+    fn of_issue(self, issue: &Issue) -> Self {
+        let entry = self.issue(issue.number).file(issue.title.clone());
+        match &issue.milestone {
+            Some(milestone) => entry.milestone(milestone.title.clone()),
+            None => entry,
+        }
+    }
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {:<18} {}",
+            self.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.operation,
+            self.actor.as_deref().unwrap_or("unknown")
+        )?;
+        if let Some(issue) = self.issue {
+            write!(f, "  #{issue}")?;
+        }
+        if let Some(file) = &self.file {
+            write!(f, "  {file}")?;
+        }
+        if let Some(milestone) = &self.milestone {
+            write!(f, "  [{milestone}]")?;
+        }
+        if let Some(details) = &self.details {
+            write!(f, "  ({details})")?;
+        }
+        if let Some(url) = &self.url {
+            write!(f, "  {url}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Appends audit entries of one repository, attributed to the authenticated user
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    actor: Option<String>,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf, actor: Option<String>) -> Self {
+        Self { path, actor }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `entry`, warning instead of failing when the log cannot be written
+    fn record(&self, mut entry: AuditEntry) {
+        entry.actor = self.actor.clone();
+        if let Err(e) = self.append(&entry) {
+            log::warn!(
+                "⚠️  {} succeeded on GitHub but could not be recorded in the audit log {}: {e}",
+                entry.operation,
+                self.path.display()
+            );
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+/// Where the audit log of `owner/repo` lives: next to the repository's cache directory, so
+/// `ghqc cache rm` leaves it alone
+pub fn audit_log_path(
+    env: &impl EnvProvider,
+    configured_root: Option<&Path>,
+    owner: &str,
+    repo: &str,
+) -> Result<PathBuf, CacheError> {
+    let (root, _) = resolve_cache_root(env, configured_root)?;
+    Ok(root.join(owner).join(format!("{repo}.audit.log")))
+}
+
+/// Entries of the audit log at `path` on or after `since`, skipping lines that cannot be parsed
+///
+/// A log that does not exist yet has no entries.
+pub fn read_audit_entries(
+    path: &Path,
+    since: Option<NaiveDate>,
+) -> std::io::Result<Vec<AuditEntry>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            serde_json::from_str::<AuditEntry>(line)
+                .inspect_err(|e| log::debug!("Skipping malformed audit entry: {e}"))
+                .ok()
+        })
+        .filter(|entry| since.is_none_or(|since| entry.timestamp.date_naive() >= since))
+        .collect())
+}
+
+/// Git provider that records every successful GitHub write in an [`AuditLog`]
+///
+/// Reads and local git operations are passed through untouched. Without a log, writes are only
+/// passed through as well.
+#[derive(Debug, Clone)]
+pub struct AuditedGit<G> {
+    inner: G,
+    log: Option<AuditLog>,
+}
+
+impl<G> AuditedGit<G> {
+    pub fn new(inner: G, log: Option<AuditLog>) -> Self {
+        Self { inner, log }
+    }
+
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.log.as_ref()
+    }
+
+    fn record<T>(
+        &self,
+        result: Result<T, GitHubApiError>,
+        entry: impl FnOnce(&T) -> AuditEntry,
+    ) -> Result<T, GitHubApiError> {
+        if let (Ok(value), Some(log)) = (&result, &self.log) {
+            log.record(entry(value));
+        }
+        result
+    }
+}
+
+/// Functions taking the concrete provider, e.g. `&GitInfo`, keep working on the wrapped one
+impl<G> Deref for AuditedGit<G> {
+    type Target = G;
+
+    fn deref(&self) -> &G {
+        &self.inner
+    }
+}
+
+impl<G: GitHubWriter + Sync> GitHubWriter for AuditedGit<G> {
+    async fn create_milestone(
+        &self,
+        milestone_name: &str,
+        description: &Option<String>,
+    ) -> Result<Milestone, GitHubApiError> {
+        let result = self
+            .inner
+            .create_milestone(milestone_name, description)
+            .await;
+        self.record(result, |milestone| {
+            AuditEntry::new(AuditOperation::MilestoneCreated)
+                .milestone(milestone.title.clone())
+                .url(milestone.html_url.to_string())
+        })
+    }
+
+    async fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> Result<Milestone, GitHubApiError> {
+        let result = self
+            .inner
+            .update_milestone_title(milestone_number, new_title)
+            .await;
+        self.record(result, |milestone| {
+            AuditEntry::new(AuditOperation::MilestoneRenamed)
+                .milestone(milestone.title.clone())
+                .url(milestone.html_url.to_string())
+                .details(format!("milestone #{milestone_number}"))
+        })
+    }
+
+    async fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &MilestoneUpdate,
+    ) -> Result<Milestone, GitHubApiError> {
+        let result = self.inner.update_milestone(milestone_number, update).await;
+        let operation = match update.state {
+            Some(MilestoneState::Closed) => AuditOperation::MilestoneClosed,
+            Some(MilestoneState::Open) => AuditOperation::MilestoneReopened,
+            None => AuditOperation::MilestoneUpdated,
+        };
+        self.record(result, |milestone| {
+            AuditEntry::new(operation)
+                .milestone(milestone.title.clone())
+                .url(milestone.html_url.to_string())
+                .details(format!("milestone #{milestone_number}"))
+        })
+    }
+
+    async fn close_milestone(&self, milestone_number: u64) -> Result<Milestone, GitHubApiError> {
+        let result = self.inner.close_milestone(milestone_number).await;
+        self.record(result, |milestone| {
+            AuditEntry::new(AuditOperation::MilestoneClosed)
+                .milestone(milestone.title.clone())
+                .url(milestone.html_url.to_string())
+                .details(format!("milestone #{milestone_number}"))
+        })
+    }
+
+    async fn post_issue(&self, issue: &QCIssue) -> Result<Issue, GitHubApiError> {
+        let result = self.inner.post_issue(issue).await;
+        self.record(result, |posted| {
+            AuditEntry::new(AuditOperation::IssueCreated)
+                .of_issue(posted)
+                .url(posted.html_url.to_string())
+        })
+    }
+
+    async fn post_comment<T: CommentBody + Sync + 'static>(
+        &self,
+        comment: &T,
+    ) -> Result<String, GitHubApiError> {
+        let result = self.inner.post_comment(comment).await;
+        self.record(result, |url| {
+            AuditEntry::new(AuditOperation::CommentPosted)
+                .of_issue(comment.issue())
+                .url(url.clone())
+                .details(comment.title())
+        })
+    }
+
+    fn guard_issue(&self, issue: &Issue) -> Result<(), GitHubApiError> {
+        self.inner.guard_issue(issue)
+    }
+
+    async fn close_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        let result = self.inner.close_issue(issue_number).await;
+        self.record(result, |_| {
+            AuditEntry::new(AuditOperation::IssueClosed).issue(issue_number)
+        })
+    }
+
+    async fn open_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        let result = self.inner.open_issue(issue_number).await;
+        self.record(result, |_| {
+            AuditEntry::new(AuditOperation::IssueReopened).issue(issue_number)
+        })
+    }
+
+    async fn create_label(&self, name: &str, color: &str) -> Result<(), GitHubApiError> {
+        let result = self.inner.create_label(name, color).await;
+        self.record(result, |_| {
+            AuditEntry::new(AuditOperation::LabelCreated).details(name)
+        })
+    }
+
+    async fn add_labels(&self, issue_number: u64, labels: &[String]) -> Result<(), GitHubApiError> {
+        let result = self.inner.add_labels(issue_number, labels).await;
+        self.record(result, |_| {
+            AuditEntry::new(AuditOperation::LabelsAdded)
+                .issue(issue_number)
+                .details(labels.join(", "))
+        })
+    }
+
+    async fn remove_label(&self, issue_number: u64, label: &str) -> Result<(), GitHubApiError> {
+        let result = self.inner.remove_label(issue_number, label).await;
+        self.record(result, |_| {
+            AuditEntry::new(AuditOperation::LabelRemoved)
+                .issue(issue_number)
+                .details(label)
+        })
+    }
+
+    async fn set_assignees(
+        &self,
+        issue_number: u64,
+        assignees: &[String],
+    ) -> Result<(), GitHubApiError> {
+        let result = self.inner.set_assignees(issue_number, assignees).await;
+        self.record(result, |_| {
+            let assignees = if assignees.is_empty() {
+                "none".to_string()
+            } else {
+                assignees.join(", ")
+            };
+            AuditEntry::new(AuditOperation::AssigneesChanged)
+                .issue(issue_number)
+                .details(assignees)
+        })
+    }
+
+    async fn block_issue(
+        &self,
+        blocked_issue_number: u64,
+        blocking_issue_id: u64,
+    ) -> Result<(), GitHubApiError> {
+        let result = self
+            .inner
+            .block_issue(blocked_issue_number, blocking_issue_id)
+            .await;
+        self.record(result, |_| {
+            AuditEntry::new(AuditOperation::IssueBlocked)
+                .issue(blocked_issue_number)
+                .details(format!("blocked by issue id {blocking_issue_id}"))
+        })
+    }
+
+    async fn update_issue(
+        &self,
+        issue_number: u64,
+        new_title: Option<String>,
+        new_body: Option<String>,
+    ) -> Result<(), GitHubApiError> {
+        let fields = [("title", new_title.is_some()), ("body", new_body.is_some())]
+            .into_iter()
+            .filter_map(|(field, changed)| changed.then_some(field))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let file = new_title.clone();
+        let result = self
+            .inner
+            .update_issue(issue_number, new_title, new_body)
+            .await;
+        self.record(result, |_| {
+            let entry = AuditEntry::new(AuditOperation::IssueUpdated)
+                .issue(issue_number)
+                .details(fields);
+            match file {
+                Some(file) => entry.file(file),
+                None => entry,
+            }
+        })
+    }
+}
+
+impl<G: GitHubReader + Sync> GitHubReader for AuditedGit<G> {
+    async fn get_milestones(&self) -> Result<Vec<Milestone>, GitHubApiError> {
+        self.inner.get_milestones().await
+    }
+
+    async fn get_issues(&self, milestone: Option<u64>) -> Result<Vec<Issue>, GitHubApiError> {
+        self.inner.get_issues(milestone).await
+    }
+
+    async fn get_issue(&self, issue_number: u64) -> Result<Issue, GitHubApiError> {
+        self.inner.get_issue(issue_number).await
+    }
+
+    async fn get_assignees(&self) -> Result<Vec<String>, GitHubApiError> {
+        self.inner.get_assignees().await
+    }
+
+    async fn get_user_details(&self, username: &str) -> Result<RepoUser, GitHubApiError> {
+        self.inner.get_user_details(username).await
+    }
+
+    async fn get_user_permission(&self, login: &str) -> Result<Permission, GitHubApiError> {
+        self.inner.get_user_permission(login).await
+    }
+
+    async fn get_team_members(
+        &self,
+        org: &str,
+        team_slug: &str,
+    ) -> Result<Vec<String>, GitHubApiError> {
+        self.inner.get_team_members(org, team_slug).await
+    }
+
+    async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
+        self.inner.get_labels().await
+    }
+
+    async fn get_issue_comments(&self, issue: &Issue) -> Result<Vec<GitComment>, GitHubApiError> {
+        self.inner.get_issue_comments(issue).await
+    }
+
+    async fn get_issue_events(
+        &self,
+        issue: &Issue,
+    ) -> Result<Vec<serde_json::Value>, GitHubApiError> {
+        self.inner.get_issue_events(issue).await
+    }
+
+    async fn get_blocked_issues(&self, issue_number: u64) -> Result<Vec<Issue>, GitHubApiError> {
+        self.inner.get_blocked_issues(issue_number).await
+    }
+
+    async fn get_current_user(&self) -> Result<Option<String>, GitHubApiError> {
+        self.inner.get_current_user().await
+    }
+}
+
+impl<G: GitHelpers> GitHelpers for AuditedGit<G> {
+    fn file_content_url(&self, git_ref: &str, file: &Path) -> String {
+        self.inner.file_content_url(git_ref, file)
+    }
+
+    fn commit_comparison_url(
+        &self,
+        current_commit: &ObjectId,
+        previous_commit: &ObjectId,
+    ) -> String {
+        self.inner
+            .commit_comparison_url(current_commit, previous_commit)
+    }
+
+    fn issue_url(&self, issue_number: u64) -> String {
+        self.inner.issue_url(issue_number)
+    }
+}
+
+impl<G: GitRepository> GitRepository for AuditedGit<G> {
+    fn commit(&self) -> Result<String, GitRepositoryError> {
+        self.inner.commit()
+    }
+
+    fn branch(&self) -> Result<String, GitRepositoryError> {
+        self.inner.branch()
+    }
+
+    fn owner(&self) -> &str {
+        self.inner.owner()
+    }
+
+    fn repo(&self) -> &str {
+        self.inner.repo()
+    }
+
+    fn remote_name(&self) -> &str {
+        self.inner.remote_name()
+    }
+
+    fn path(&self) -> &Path {
+        self.inner.path()
+    }
+
+    fn fetch(&self) -> Result<bool, GitRepositoryError> {
+        self.inner.fetch()
+    }
+
+    fn stash_file(
+        &self,
+        file: &Path,
+        message: &str,
+    ) -> Result<FileStashOutcome, GitRepositoryError> {
+        self.inner.stash_file(file, message)
+    }
+
+    fn configured_author(&self) -> Option<GitAuthor> {
+        self.inner.configured_author()
+    }
+}
+
+impl<G: GitFileOps> GitFileOps for AuditedGit<G> {
+    fn authors(&self, file: &Path) -> Result<Vec<GitAuthor>, GitFileOpsError> {
+        self.inner.authors(file)
+    }
+
+    fn file_bytes_at_commit(
+        &self,
+        file: &Path,
+        commit: &ObjectId,
+    ) -> Result<Vec<u8>, GitFileOpsError> {
+        self.inner.file_bytes_at_commit(file, commit)
+    }
+
+    fn file_reader_at_commit(
+        &self,
+        file: &Path,
+        commit: &ObjectId,
+    ) -> Result<Option<FileReader>, GitFileOpsError> {
+        self.inner.file_reader_at_commit(file, commit)
+    }
+
+    fn list_tree_entries(&self, path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
+        self.inner.list_tree_entries(path)
+    }
+
+    fn path_attributes(&self, file: &Path) -> Result<PathAttributes, GitFileOpsError> {
+        self.inner.path_attributes(file)
+    }
+
+    fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+        self.inner.resolve_commit(commitish)
+    }
+}
+
+impl<G: GitCommitOps> GitCommitOps for AuditedGit<G> {
+    fn commits(
+        &self,
+        branch: &Option<String>,
+        stop_at: Option<ObjectId>,
+    ) -> Result<Vec<GitCommit>, GitFileOpsError> {
+        self.inner.commits(branch, stop_at)
+    }
+
+    fn branch_tip(&self, branch: &Option<String>) -> Result<ObjectId, GitFileOpsError> {
+        self.inner.branch_tip(branch)
+    }
+
+    fn file_touching_commits(
+        &self,
+        branch: Option<String>,
+        file: &Path,
+    ) -> Result<HashSet<String>, GitFileOpsError> {
+        self.inner.file_touching_commits(branch, file)
+    }
+
+    fn file_renames(
+        &self,
+        branch: Option<String>,
+        file: &Path,
+        since: Option<ObjectId>,
+    ) -> Result<Vec<FileRenameEvent>, GitFileOpsError> {
+        self.inner.file_renames(branch, file, since)
+    }
+
+    fn files_by_last_commit(
+        &self,
+        branch: &Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitFileOpsError> {
+        self.inner.files_by_last_commit(branch)
+    }
+
+    fn get_branches_containing_commit(
+        &self,
+        commit: &ObjectId,
+    ) -> Result<Vec<String>, GitFileOpsError> {
+        self.inner.get_branches_containing_commit(commit)
+    }
+
+    fn find_merged_into_branch(
+        &self,
+        target_commit: &ObjectId,
+    ) -> Result<Option<String>, GitFileOpsError> {
+        self.inner.find_merged_into_branch(target_commit)
+    }
+
+    fn find_merge_commit(
+        &self,
+        target_commit: &ObjectId,
+    ) -> Result<Option<ObjectId>, GitFileOpsError> {
+        self.inner.find_merge_commit(target_commit)
+    }
+
+    fn signature_status(
+        &self,
+        commit: &ObjectId,
+        keys: &SigningKeys,
+    ) -> Result<SignatureStatus, GitFileOpsError> {
+        self.inner.signature_status(commit, keys)
+    }
+
+    fn commit_time(&self, commit: &ObjectId) -> Result<DateTime<Utc>, GitFileOpsError> {
+        self.inner.commit_time(commit)
+    }
+
+    fn commit_author(&self, commit: &ObjectId) -> Result<GitAuthor, GitFileOpsError> {
+        self.inner.commit_author(commit)
+    }
+
+    fn is_shallow(&self) -> bool {
+        self.inner.is_shallow()
+    }
+}
+
+impl<G: GitStatusOps> GitStatusOps for AuditedGit<G> {
+    fn state(&self) -> Result<(ObjectId, GitState), GitStatusError> {
+        self.inner.state()
+    }
+
+    fn dirty(&self) -> Result<Vec<PathBuf>, GitStatusError> {
+        self.inner.dirty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::MockGitHubWriter;
+    use crate::reassign::{QCReassign, reassign_issue};
+    use crate::test_utils::create_test_issue;
+    use tempfile::TempDir;
+
+    const COMMENT_URL: &str = "https://github.com/owner/repo/issues/3#issuecomment-1";
+
+    fn reassign_writer() -> MockGitHubWriter {
+        let mut writer = MockGitHubWriter::new();
+        writer.expect_guard_issue().returning(|_| Ok(()));
+        writer
+            .expect_set_assignees()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        writer
+            .expect_post_comment::<QCReassign>()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(COMMENT_URL.to_string()) }));
+        writer
+    }
+
+    fn reassign() -> QCReassign {
+        let issue = create_test_issue("owner", "repo", 3, "src/analysis.R", "", Some(1), "open");
+        QCReassign::new(issue, &["bob".to_string()], &[], None)
+    }
+
+    fn audited<G>(inner: G, path: PathBuf) -> AuditedGit<G> {
+        AuditedGit::new(inner, Some(AuditLog::new(path, Some("alice".to_string()))))
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_recorded() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("owner").join("repo.audit.log");
+        let git = audited(reassign_writer(), path.clone());
+
+        reassign_issue(&reassign(), &git).await.unwrap();
+
+        let entries = read_audit_entries(&path, None).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].operation, AuditOperation::AssigneesChanged);
+        assert_eq!(entries[0].actor.as_deref(), Some("alice"));
+        assert_eq!(entries[0].issue, Some(3));
+        assert_eq!(entries[0].details.as_deref(), Some("bob"));
+        assert_eq!(entries[0].url, None);
+
+        assert_eq!(entries[1].operation, AuditOperation::CommentPosted);
+        assert_eq!(entries[1].issue, Some(3));
+        assert_eq!(entries[1].milestone.as_deref(), Some("v1.0"));
+        assert_eq!(entries[1].file.as_deref(), Some("src/analysis.R"));
+        assert_eq!(entries[1].url.as_deref(), Some(COMMENT_URL));
+
+        let lines = fs::read_to_string(&path).unwrap();
+        let line: serde_json::Value = serde_json::from_str(lines.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(line["operation"], "comment_posted");
+        assert_eq!(line["actor"], "alice");
+    }
+
+    #[tokio::test]
+    async fn test_failed_writes_are_not_recorded() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("repo.audit.log");
+        let mut writer = MockGitHubWriter::new();
+        writer
+            .expect_close_issue()
+            .returning(|_| Box::pin(async { Err(GitHubApiError::NoApi) }));
+        writer
+            .expect_open_issue()
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let git = audited(writer, path.clone());
+
+        assert!(git.close_issue(3).await.is_err());
+        git.open_issue(3).await.unwrap();
+
+        let entries = read_audit_entries(&path, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, AuditOperation::IssueReopened);
+    }
+
+    #[tokio::test]
+    async fn test_unwritable_log_does_not_fail_the_write() {
+        let dir = TempDir::new().unwrap();
+        // A directory cannot be opened for appending
+        let git = audited(reassign_writer(), dir.path().to_path_buf());
+
+        let url = reassign_issue(&reassign(), &git).await.unwrap();
+        assert_eq!(url, COMMENT_URL);
+    }
+
+    #[test]
+    fn test_read_audit_entries_since() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("repo.audit.log");
+        let log = AuditLog::new(path.clone(), None);
+        for (day, number) in [(1, 1), (15, 2)] {
+            let mut entry = AuditEntry::new(AuditOperation::IssueClosed).issue(number);
+            entry.timestamp = "2025-03-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+                + chrono::Duration::days(day - 1);
+            log.append(&entry).unwrap();
+        }
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        assert_eq!(read_audit_entries(&path, None).unwrap().len(), 2);
+        let since = NaiveDate::from_ymd_opt(2025, 3, 10);
+        let entries = read_audit_entries(&path, since).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].issue, Some(2));
+        assert_eq!(
+            entries[0].to_string(),
+            "2025-03-15 12:00:00 UTC  issue_closed       unknown  #2"
+        );
+
+        let missing = dir.path().join("missing.audit.log");
+        assert!(read_audit_entries(&missing, None).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,1415 @@
+use etcetera::BaseStrategy;
+use futures::StreamExt;
+use octocrab::models::Milestone;
+use octocrab::models::issues::Issue;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::git::{
+    GitComment, GitHubApiError, GitHubReader, GitHubWriter, GitRepository, Permission, RepoUser,
+};
+use crate::record::ImageCache;
+use crate::utils::{EnvProvider, StdEnvProvider};
+
+mod snapshot;
+mod users;
+
+pub use snapshot::{CACHE_SCHEMA_VERSION, CacheImportSummary, CacheManifest, CacheSnapshotError};
+use users::cached_user_details;
+pub use users::{MAX_CONCURRENT_USER_LOOKUPS, UserDirectory};
+
+/// Cache entry with optional TTL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry<T> {
+    pub data: T,
+    pub created_at: u64,
+    pub ttl_seconds: Option<u64>,
+}
+
+/// Cached comments with the issue's last updated timestamp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedComments {
+    pub comments: Vec<GitComment>,
+    pub issue_updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Cached events with the issue's last updated timestamp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedEvents {
+    pub events: Vec<serde_json::Value>,
+    pub issue_updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Cached issues of a milestone with the milestone's last updated timestamp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedIssues {
+    pub issues: Vec<Issue>,
+    pub milestone_updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Directory of the milestone and issue listings, removed by [`DiskCache::invalidate_listings`]
+const LISTINGS_DIR: &str = "listings";
+
+/// A single file-change record stored in the disk cache per commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChangeRecord {
+    /// Repo-relative file path (forward-slash separated).
+    pub file: String,
+    pub changed: bool,
+}
+
+/// Disk-serialisable representation of a commit.
+/// `file_changes` grows lazily as files are queried via `find_or_cache_file_changes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedCommit {
+    /// Full 40-char SHA hex string.
+    pub hash: String,
+    pub message: String,
+    pub file_changes: Vec<FileChangeRecord>,
+}
+
+impl<T> CacheEntry<T> {
+    pub fn new(data: T, ttl: Option<Duration>) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Self {
+            data,
+            created_at,
+            ttl_seconds: ttl.map(|d| d.as_secs()),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        if let Some(ttl) = self.ttl_seconds {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            now > self.created_at + ttl
+        } else {
+            false // No TTL means never expires
+        }
+    }
+}
+
+/// Simple disk-based cache for GitHub API responses
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    pub(crate) root: PathBuf,
+    owner: String,
+    repo: String,
+    ttl: Duration,
+    /// TTL of the milestone and issue listings, which change far more often than other entries
+    listing_ttl: Duration,
+    /// Never read entries, only write them, so every lookup fetches fresh data
+    refresh: bool,
+    /// Download issue images again instead of reusing the cached copies
+    refresh_images: bool,
+    /// Hit and miss counts of `read`, shared between clones
+    stats: Arc<CacheStats>,
+}
+
+#[derive(Debug, Default)]
+struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl DiskCache {
+    /// Open the cache of the repository, with its root resolved from `GHQC_CACHE_DIR` or the
+    /// system cache directory
+    pub fn from_git_info(git_info: &impl GitRepository) -> Result<Self, CacheError> {
+        Self::open(git_info, None, &StdEnvProvider)
+    }
+
+    /// Open the cache of the repository, with its root resolved by [`resolve_cache_root`]
+    ///
+    /// Fails when the root is not writable, or when an overridden root lies inside the
+    /// repository's working tree.
+    pub fn open(
+        git_info: &impl GitRepository,
+        configured_root: Option<&Path>,
+        env: &impl EnvProvider,
+    ) -> Result<Self, CacheError> {
+        let (root, source) = resolve_cache_root(env, configured_root)?;
+        validate_cache_root(&root, source, Some(git_info.path()))?;
+
+        Ok(Self {
+            root,
+            owner: git_info.owner().to_string(),
+            repo: git_info.repo().to_string(),
+            ttl: default_ttl(),
+            listing_ttl: default_listing_ttl(),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        })
+    }
+
+    /// Create a new DiskCache instance using the system cache directory, or `GHQC_CACHE_DIR`
+    pub fn new(owner: String, repo: String) -> Result<Self, CacheError> {
+        Ok(Self::at_root(cache_root()?, owner, repo))
+    }
+
+    /// Create a DiskCache for the repository under an already resolved cache root
+    pub(crate) fn at_root(root: PathBuf, owner: String, repo: String) -> Self {
+        Self {
+            root,
+            owner,
+            repo,
+            ttl: default_ttl(),
+            listing_ttl: default_listing_ttl(),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        }
+    }
+
+    /// Skip reading entries so data is fetched from GitHub again, while still writing what is
+    /// fetched to refresh the cache
+    pub fn refreshing(mut self) -> Self {
+        self.refresh = true;
+        self
+    }
+
+    /// Download issue images again for records, replacing the cached copies
+    pub fn refreshing_images(mut self) -> Self {
+        self.refresh_images = true;
+        self
+    }
+
+    /// Cache of the issue images downloaded for records, under `images/` of the repository's
+    /// cache directory
+    pub fn image_cache(&self) -> ImageCache {
+        let cache = ImageCache::new(self.repo_dir().join("images"));
+        if self.refresh || self.refresh_images {
+            cache.refreshing()
+        } else {
+            cache
+        }
+    }
+
+    /// Expire milestone and issue listings after `ttl` instead of `GHQC_LISTING_CACHE_TIMEOUT`
+    pub fn with_listing_ttl(mut self, ttl: Duration) -> Self {
+        self.listing_ttl = ttl;
+        self
+    }
+
+    /// Directory holding every cache entry for this repository
+    pub fn repo_dir(&self) -> PathBuf {
+        self.root.join(&self.owner).join(&self.repo)
+    }
+
+    /// Generate a path for a specific cache file based on the directory path and key
+    pub fn path(&self, path: &[&str], key: &str) -> PathBuf {
+        let mut full_path = self.repo_dir();
+
+        // Add directory parts
+        for part in path {
+            full_path = full_path.join(part);
+        }
+
+        // Add filename with .json extension
+        full_path.join(format!("{}.json", key))
+    }
+
+    /// Read and deserialize cached data if valid (not expired)
+    pub fn read<T>(&self, path: &[&str], key: &str) -> Option<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let data = self.read_entry(path, key);
+        let counter = if data.is_some() {
+            &self.stats.hits
+        } else {
+            &self.stats.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        data
+    }
+
+    fn read_entry<T>(&self, path: &[&str], key: &str) -> Option<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if self.refresh {
+            return None;
+        }
+        let file_path = self.path(path, key);
+        if !file_path.exists() {
+            return None;
+        }
+
+        let content = fs::read_to_string(&file_path).ok()?;
+        let entry: CacheEntry<T> = serde_json::from_str(&content).ok()?;
+
+        if entry.is_expired() {
+            // Clean up expired cache
+            let _ = fs::remove_file(&file_path);
+            return None;
+        }
+
+        Some(entry.data)
+    }
+
+    /// Number of reads answered from the cache since it was opened
+    pub fn hits(&self) -> u64 {
+        self.stats.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of reads that found no valid entry since the cache was opened
+    pub fn misses(&self) -> u64 {
+        self.stats.misses.load(Ordering::Relaxed)
+    }
+
+    /// Write and serialize data to cache with optional TTL
+    pub fn write<T>(
+        &self,
+        path: &[&str],
+        key: &str,
+        data: &T,
+        use_ttl: bool,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        T: Serialize,
+    {
+        let ttl = if use_ttl { Some(self.ttl) } else { None };
+
+        write_entry(&self.path(path, key), &CacheEntry::new(data, ttl))?;
+
+        Ok(())
+    }
+
+    /// Write and serialize data to cache, expiring after `ttl` instead of the cache's TTL
+    pub fn write_with_ttl<T>(
+        &self,
+        path: &[&str],
+        key: &str,
+        data: &T,
+        ttl: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        T: Serialize,
+    {
+        write_entry(&self.path(path, key), &CacheEntry::new(data, Some(ttl)))?;
+
+        Ok(())
+    }
+
+    /// Invalidate a specific cache entry by removing the file
+    ///
+    /// This is useful when we need to force a fresh fetch from the API,
+    /// such as when we need HTML content for JWT URL extraction.
+    pub fn invalidate(&self, path: &[&str], key: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let file_path = self.path(path, key);
+        if file_path.exists() {
+            std::fs::remove_file(&file_path)?;
+            log::debug!("Invalidated cache entry: {}", file_path.display());
+        } else {
+            log::debug!(
+                "Cache entry not found for invalidation: {}",
+                file_path.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl DiskCache {
+    /// Remove the cached milestone and issue listings, e.g. after creating an issue or changing
+    /// an issue's state, so the next lookup fetches them again
+    pub fn invalidate_listings(&self) {
+        let dir = self.repo_dir().join(LISTINGS_DIR);
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => log::debug!("Invalidated cached listings in {}", dir.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!(
+                "Failed to invalidate cached listings in {}: {e}",
+                dir.display()
+            ),
+        }
+    }
+}
+
+/// Invalidates the cached listings when dropped
+///
+/// Held for the duration of a command that writes to GitHub, so the listings are invalidated
+/// however the command ends, including on errors after a partial write.
+#[derive(Debug)]
+pub struct ListingInvalidation(Option<DiskCache>);
+
+impl ListingInvalidation {
+    pub fn new(cache: Option<DiskCache>) -> Self {
+        Self(cache)
+    }
+}
+
+impl Drop for ListingInvalidation {
+    fn drop(&mut self) {
+        if let Some(cache) = &self.0 {
+            cache.invalidate_listings();
+        }
+    }
+}
+
+/// Serialize `entry` to `file_path`, creating parent directories as needed
+fn write_entry<T: Serialize>(file_path: &Path, entry: &CacheEntry<T>) -> std::io::Result<()> {
+    // Ensure parent directory exists
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_json::to_string_pretty(entry)?;
+
+    // Write to a temp file then rename for atomicity — prevents readers
+    // from seeing a partially-written file if two writers race.
+    let tmp_path = file_path.with_extension("tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, file_path)
+}
+
+/// Why the disk cache cannot be used
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("Failed to determine the system cache directory: {0}")]
+    NoCacheDir(String),
+    #[error(
+        "Cache directory {} is inside the repository working tree {}",
+        .path.display(),
+        .repository.display()
+    )]
+    InsideRepository { path: PathBuf, repository: PathBuf },
+    #[error("Cache directory {} is not writable: {source}", .path.display())]
+    NotWritable {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Where the cache root was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheRootSource {
+    /// The `GHQC_CACHE_DIR` environment variable
+    Environment,
+    /// The `cache_directory` configuration option
+    Configuration,
+    /// `<system-cache-dir>/ghqc`
+    Default,
+}
+
+impl fmt::Display for CacheRootSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Environment => write!(f, "GHQC_CACHE_DIR"),
+            Self::Configuration => write!(f, "cache_directory option"),
+            Self::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// Path of the on-disk cache root: `GHQC_CACHE_DIR`, or `<system-cache-dir>/ghqc`
+pub fn cache_root() -> Result<PathBuf, CacheError> {
+    resolve_cache_root(&StdEnvProvider, None).map(|(root, _)| root)
+}
+
+/// Resolve the cache root from, in order, the `GHQC_CACHE_DIR` environment variable, the
+/// `cache_directory` configuration option and the system cache directory
+pub fn resolve_cache_root(
+    env: &impl EnvProvider,
+    configured_root: Option<&Path>,
+) -> Result<(PathBuf, CacheRootSource), CacheError> {
+    if let Some(root) = env
+        .var("GHQC_CACHE_DIR")
+        .ok()
+        .filter(|r| !r.trim().is_empty())
+    {
+        return Ok((PathBuf::from(root), CacheRootSource::Environment));
+    }
+    if let Some(root) = configured_root {
+        return Ok((root.to_path_buf(), CacheRootSource::Configuration));
+    }
+
+    let strategy =
+        etcetera::choose_base_strategy().map_err(|e| CacheError::NoCacheDir(e.to_string()))?;
+    Ok((strategy.cache_dir().join("ghqc"), CacheRootSource::Default))
+}
+
+/// Check the cache root can be written to, and that an overridden root is not inside the
+/// repository's working tree where cache files would show up as untracked changes
+fn validate_cache_root(
+    root: &Path,
+    source: CacheRootSource,
+    repository: Option<&Path>,
+) -> Result<(), CacheError> {
+    if let (CacheRootSource::Environment | CacheRootSource::Configuration, Some(repository)) =
+        (source, repository)
+    {
+        let repository = resolve_existing(repository);
+        if resolve_existing(root).starts_with(&repository) {
+            return Err(CacheError::InsideRepository {
+                path: root.to_path_buf(),
+                repository,
+            });
+        }
+    }
+
+    let not_writable = |source| CacheError::NotWritable {
+        path: root.to_path_buf(),
+        source,
+    };
+    fs::create_dir_all(root).map_err(not_writable)?;
+    let probe = root.join(".write-check");
+    fs::write(&probe, b"").map_err(not_writable)?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// `path` made absolute, with symlinks resolved in the part of it that already exists
+fn resolve_existing(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    absolute
+        .ancestors()
+        .find_map(|ancestor| {
+            let canonical = ancestor.canonicalize().ok()?;
+            Some(canonical.join(absolute.strip_prefix(ancestor).ok()?))
+        })
+        .unwrap_or(absolute)
+}
+
+static CACHELESS_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Use the opened cache, or warn why the command runs without one
+///
+/// The warning is logged once per process, however many times the cache is opened.
+pub fn cache_or_warn(cache: Result<DiskCache, CacheError>) -> Option<DiskCache> {
+    match cache {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            if !CACHELESS_WARNED.swap(true, Ordering::SeqCst) {
+                log::warn!(
+                    "Running without a disk cache, so GitHub data is fetched on every command: {e}. Set GHQC_CACHE_DIR to a writable directory to enable caching"
+                );
+            }
+            None
+        }
+    }
+}
+
+/// Whether the disk cache can be used, and where it lives
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheHealth {
+    pub enabled: bool,
+    /// The cache root, when it could be determined
+    pub path: Option<PathBuf>,
+    pub source: Option<CacheRootSource>,
+    /// Why the cache is disabled
+    pub reason: Option<String>,
+}
+
+impl CacheHealth {
+    /// Health of the cache root, resolved and checked as [`DiskCache::open`] does
+    pub fn check(
+        env: &impl EnvProvider,
+        configured_root: Option<&Path>,
+        repository: Option<&Path>,
+    ) -> Self {
+        let (root, source) = match resolve_cache_root(env, configured_root) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                return Self {
+                    enabled: false,
+                    path: None,
+                    source: None,
+                    reason: Some(e.to_string()),
+                };
+            }
+        };
+        let reason = validate_cache_root(&root, source, repository)
+            .err()
+            .map(|e| e.to_string());
+
+        Self {
+            enabled: reason.is_none(),
+            path: Some(root),
+            source: Some(source),
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for CacheHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.reason, &self.path, &self.source) {
+            (None, Some(path), Some(source)) => {
+                write!(f, "✅ cache enabled at {} ({source})", path.display())
+            }
+            (Some(reason), _, Some(source)) => {
+                write!(f, "⚠️ cache disabled ({source}): {reason}")
+            }
+            (reason, _, _) => write!(
+                f,
+                "⚠️ cache disabled: {}",
+                reason.as_deref().unwrap_or("unknown reason")
+            ),
+        }
+    }
+}
+
+/// Get the default cache TTL from environment or use 1 hour default
+fn default_ttl() -> Duration {
+    let ttl_seconds = std::env::var("GHQC_CACHE_TIMEOUT")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(3600); // 1 hour default
+    Duration::from_secs(ttl_seconds)
+}
+
+/// Get the listing TTL from environment or use a 60 second default
+fn default_listing_ttl() -> Duration {
+    let ttl_seconds = std::env::var("GHQC_LISTING_CACHE_TIMEOUT")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(60);
+    Duration::from_secs(ttl_seconds)
+}
+
+/// Get the repository's milestones, cached for the listing TTL
+pub async fn get_milestones_cached(
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<Milestone>, GitHubApiError> {
+    if let Some(milestones) = cache.and_then(|c| c.read(&[LISTINGS_DIR], "milestones")) {
+        log::debug!("Using cached milestones");
+        return Ok(milestones);
+    }
+
+    log::debug!("Milestones not found or expired in cache. Fetching...");
+    let milestones = git_info.get_milestones().await?;
+
+    if let Some(cache) = cache
+        && let Err(e) = cache.write_with_ttl(
+            &[LISTINGS_DIR],
+            "milestones",
+            &milestones,
+            cache.listing_ttl,
+        )
+    {
+        log::warn!("Failed to cache milestones: {}", e);
+    }
+
+    Ok(milestones)
+}
+
+/// Get the issues of a milestone, cached for the listing TTL and until the milestone's
+/// `updated_at` changes, e.g. when an issue is added, closed or reopened
+pub async fn get_milestone_issues_cached(
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+    milestone_number: u64,
+) -> Result<Vec<Issue>, GitHubApiError> {
+    let Some(cache) = cache else {
+        return git_info.get_issues(Some(milestone_number)).await;
+    };
+
+    let milestone_updated_at = get_milestones_cached(Some(cache), git_info)
+        .await?
+        .into_iter()
+        .find(|m| m.number as u64 == milestone_number)
+        .and_then(|m| m.updated_at);
+    let cache_key = format!("milestone_{milestone_number}");
+
+    if let Some(cached) = cache.read::<CachedIssues>(&[LISTINGS_DIR, "issues"], &cache_key) {
+        if cached.milestone_updated_at == milestone_updated_at {
+            log::debug!("Using cached issues for milestone {milestone_number}");
+            return Ok(cached.issues);
+        }
+        log::debug!(
+            "Cached issues for milestone {milestone_number} are stale (cache: {:?}, milestone: {:?})",
+            cached.milestone_updated_at,
+            milestone_updated_at
+        );
+    }
+
+    log::debug!("Fetching fresh issues for milestone {milestone_number}");
+    let issues = git_info.get_issues(Some(milestone_number)).await?;
+
+    let cached = CachedIssues {
+        issues,
+        milestone_updated_at,
+    };
+    if let Err(e) = cache.write_with_ttl(
+        &[LISTINGS_DIR, "issues"],
+        &cache_key,
+        &cached,
+        cache.listing_ttl,
+    ) {
+        log::warn!(
+            "Failed to cache issues for milestone {milestone_number}: {}",
+            e
+        );
+    }
+
+    Ok(cached.issues)
+}
+
+/// Get the ghqc issues of every milestone, cached for the listing TTL
+///
+/// Used to match files against the repository's issues, which may number in the hundreds.
+pub async fn get_all_issues_cached(
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<Issue>, GitHubApiError> {
+    if let Some(issues) = cache.and_then(|c| c.read(&[LISTINGS_DIR, "issues"], "all")) {
+        log::debug!("Using cached issues of all milestones");
+        return Ok(issues);
+    }
+
+    log::debug!("Issues of all milestones not found or expired in cache. Fetching...");
+    let issues = git_info.get_issues(None).await?;
+
+    if let Some(cache) = cache
+        && let Err(e) =
+            cache.write_with_ttl(&[LISTINGS_DIR, "issues"], "all", &issues, cache.listing_ttl)
+    {
+        log::warn!("Failed to cache issues of all milestones: {}", e);
+    }
+
+    Ok(issues)
+}
+
+/// Get repository users with caching for efficiency
+pub async fn get_repo_users(
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<RepoUser>, GitHubApiError> {
+    // Try to get assignees from cache first
+    let cached_assignees: Option<Vec<String>> = if let Some(cache) = cache {
+        cache.read::<Vec<String>>(&["users"], "assignees")
+    } else {
+        None
+    };
+
+    let assignee_logins = if let Some(logins) = cached_assignees {
+        log::debug!("Using cached assignees");
+        logins
+    } else {
+        log::debug!("Assignees not found or expired in cache. Fetching...");
+        let logins = git_info.get_assignees().await?;
+
+        // Cache the assignee list with TTL
+        if let Some(cache) = cache {
+            if let Err(e) = cache.write(&["users"], "assignees", &logins, true) {
+                log::warn!("Failed to cache assignees: {}", e);
+            }
+        }
+
+        logins
+    };
+
+    // Fetch user details concurrently, cached permanently
+    let results: Vec<Result<RepoUser, GitHubApiError>> = futures::stream::iter(assignee_logins)
+        .map(|username| async move { cached_user_details(cache, git_info, &username).await })
+        .buffered(MAX_CONCURRENT_USER_LOOKUPS)
+        .collect()
+        .await;
+
+    let users = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+    log::debug!(
+        "Successfully fetched {} assignees with user details",
+        users.len()
+    );
+
+    Ok(users)
+}
+
+/// Get repository permission levels for the given users, cached with the same TTL as the
+/// assignee list since permissions can change
+pub async fn get_user_permissions(
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+    logins: &[String],
+) -> Result<Vec<(String, Permission)>, GitHubApiError> {
+    let permission_futures: Vec<_> = logins
+        .iter()
+        .map(|login| async move {
+            if let Some(permission) =
+                cache.and_then(|c| c.read::<Permission>(&["users", "permissions"], login))
+            {
+                log::trace!("Using cached permission for: {}", login);
+                return Ok((login.clone(), permission));
+            }
+
+            log::debug!("Permission for {} not found in cache. Fetching...", login);
+            let permission = git_info.get_user_permission(login).await?;
+
+            if let Some(cache) = cache
+                && let Err(e) = cache.write(&["users", "permissions"], login, &permission, true)
+            {
+                log::warn!("Failed to cache permission for {}: {}", login, e);
+            }
+
+            Ok((login.clone(), permission))
+        })
+        .collect();
+
+    futures::future::join_all(permission_futures)
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Create required labels if they don't exist, with caching
+pub async fn create_labels_if_needed(
+    cache: Option<&DiskCache>,
+    branch: Option<&str>,
+    git_info: &(impl GitHubReader + GitHubWriter),
+) -> Result<(), GitHubApiError> {
+    // Try to get labels from cache first
+    let cached_labels: Option<Vec<String>> = if let Some(cache) = cache {
+        cache.read::<Vec<String>>(&["labels"], "names")
+    } else {
+        None
+    };
+
+    let label_names = if let Some(names) = cached_labels {
+        log::debug!("Using cached label names");
+        names
+    } else {
+        log::debug!("Label names not found or expired in cache. Fetching...");
+        let names = git_info.get_labels().await?;
+
+        // Cache the label names with TTL
+        if let Some(cache) = cache {
+            if let Err(e) = cache.write(&["labels"], "names", &names, true) {
+                log::warn!("Failed to cache label names: {}", e);
+            }
+        }
+
+        names
+    };
+
+    let original_count = label_names.len();
+    let mut updated_labels = label_names;
+
+    // Ensure "ghqc" label exists
+    if !updated_labels.iter().any(|name| name == "ghqc") {
+        log::debug!("ghqc label does not exist. Creating...");
+        git_info.create_label("ghqc", "FFCB05").await?;
+        updated_labels.push("ghqc".to_string());
+    }
+
+    // Ensure branch label exists
+    if let Some(branch) = branch {
+        if !updated_labels.iter().any(|name| name == branch) {
+            log::debug!("Branch label ({}) does not exist. Creating...", branch);
+            git_info.create_label(branch, "00274C").await?;
+            updated_labels.push(branch.to_string());
+        }
+    }
+
+    // Update cache with new labels if we created any
+    if updated_labels.len() != original_count {
+        if let Some(cache) = cache {
+            if let Err(e) = cache.write(&["labels"], "names", &updated_labels, true) {
+                log::warn!("Failed to update cached label names: {}", e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get issue comments with caching based on issue update timestamp
+pub async fn get_issue_comments(
+    issue: &Issue,
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<GitComment>, GitHubApiError> {
+    // Create cache key from issue number
+    let cache_key = format!("issue_{}", issue.number);
+
+    // Try to get cached comments first
+    let cached_comments: Option<CachedComments> = if let Some(cache) = cache {
+        cache.read::<CachedComments>(&["issues", "comments"], &cache_key)
+    } else {
+        None
+    };
+
+    // Check if cached comments are still valid by comparing timestamps
+    if let Some(cached) = cached_comments {
+        if cached.issue_updated_at >= issue.updated_at {
+            log::debug!(
+                "Using cached comments for issue #{} (cache timestamp: {}, issue timestamp: {})",
+                issue.number,
+                cached.issue_updated_at,
+                issue.updated_at
+            );
+            return Ok(cached.comments);
+        } else {
+            log::debug!(
+                "Cached comments for issue #{} are stale (cache: {}, issue: {})",
+                issue.number,
+                cached.issue_updated_at,
+                issue.updated_at
+            );
+        }
+    }
+
+    // Fetch fresh comments from API
+    log::debug!("Fetching fresh comments for issue #{}", issue.number);
+    let comments = git_info.get_issue_comments(issue).await?;
+
+    // Cache the comments with the current issue timestamp (permanently)
+    if let Some(cache) = cache {
+        let cached_comments = CachedComments {
+            comments: comments.clone(),
+            issue_updated_at: issue.updated_at,
+        };
+
+        if let Err(e) = cache.write(&["issues", "comments"], &cache_key, &cached_comments, false) {
+            log::warn!(
+                "Failed to cache comments for issue #{}: {}",
+                issue.number,
+                e
+            );
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Get issue events with caching based on issue update timestamp
+pub async fn get_issue_events(
+    issue: &Issue,
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<serde_json::Value>, GitHubApiError> {
+    // Create cache key from issue number
+    let cache_key = format!("issue_{}", issue.number);
+
+    // Try to get cached events first
+    let cached_events: Option<CachedEvents> = if let Some(cache) = cache {
+        cache.read::<CachedEvents>(&["issues", "events"], &cache_key)
+    } else {
+        None
+    };
+
+    // Check if cached events are still valid by comparing timestamps
+    if let Some(cached) = cached_events {
+        if cached.issue_updated_at >= issue.updated_at {
+            log::debug!(
+                "Using cached events for issue #{} (cache timestamp: {}, issue timestamp: {})",
+                issue.number,
+                cached.issue_updated_at,
+                issue.updated_at
+            );
+            return Ok(cached.events);
+        } else {
+            log::debug!(
+                "Cached events for issue #{} are stale (cache: {}, issue: {})",
+                issue.number,
+                cached.issue_updated_at,
+                issue.updated_at
+            );
+        }
+    }
+
+    // Fetch fresh events from API
+    log::debug!("Fetching fresh events for issue #{}", issue.number);
+    let events = git_info.get_issue_events(issue).await?;
+
+    // Cache the events with the current issue timestamp (permanently)
+    if let Some(cache) = cache {
+        let cached_events = CachedEvents {
+            events: events.clone(),
+            issue_updated_at: issue.updated_at,
+        };
+
+        if let Err(e) = cache.write(&["issues", "events"], &cache_key, &cached_events, false) {
+            log::warn!("Failed to cache events for issue #{}: {}", issue.number, e);
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::MockEnvProvider;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_path_generation() {
+        let cache = DiskCache {
+            root: PathBuf::from("/tmp/cache"),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            ttl: Duration::from_secs(3600),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        };
+
+        let path = cache.path(&[], "milestones");
+        assert_eq!(path, PathBuf::from("/tmp/cache/owner/repo/milestones.json"));
+    }
+
+    #[test]
+    fn test_path_with_complex_names() {
+        let cache = DiskCache {
+            root: PathBuf::from("/cache"),
+            owner: "my-org".to_string(),
+            repo: "my-repo_name".to_string(),
+            ttl: Duration::from_secs(1800),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        };
+
+        let path = cache.path(&["users"], "user_list");
+        assert_eq!(
+            path,
+            PathBuf::from("/cache/my-org/my-repo_name/users/user_list.json")
+        );
+    }
+
+    #[test]
+    fn test_new_creates_cache_in_system_dir() {
+        let cache = DiskCache::new("test-owner".to_string(), "test-repo".to_string()).unwrap();
+
+        // Just verify it doesn't panic and creates reasonable paths
+        assert!(cache.root.to_string_lossy().contains("ghqc"));
+
+        let path = cache.path(&[], "test");
+        assert!(path.to_string_lossy().contains("test-owner"));
+        assert!(path.to_string_lossy().contains("test-repo"));
+        assert!(path.to_string_lossy().ends_with("test.json"));
+    }
+
+    fn env_with_cache_dir(value: Option<&'static str>) -> MockEnvProvider {
+        let mut env = MockEnvProvider::new();
+        env.expect_var()
+            .with(mockall::predicate::eq("GHQC_CACHE_DIR"))
+            .returning(move |_| {
+                value
+                    .map(str::to_string)
+                    .ok_or(std::env::VarError::NotPresent)
+            });
+        env
+    }
+
+    #[test]
+    fn test_resolve_cache_root_order() {
+        let configured = Path::new("/configured/cache");
+
+        let (root, source) =
+            resolve_cache_root(&env_with_cache_dir(Some("/env/cache")), Some(configured)).unwrap();
+        assert_eq!(root, PathBuf::from("/env/cache"));
+        assert_eq!(source, CacheRootSource::Environment);
+
+        let (root, source) =
+            resolve_cache_root(&env_with_cache_dir(None), Some(configured)).unwrap();
+        assert_eq!(root, configured);
+        assert_eq!(source, CacheRootSource::Configuration);
+
+        // An empty variable is treated as unset
+        let (root, source) = resolve_cache_root(&env_with_cache_dir(Some(" ")), None).unwrap();
+        assert!(root.ends_with("ghqc"));
+        assert_eq!(source, CacheRootSource::Default);
+    }
+
+    #[test]
+    fn test_cache_health_rejects_override_inside_repository() {
+        let repository = tempfile::tempdir().unwrap();
+        let root = repository.path().join(".cache");
+        let env = env_with_cache_dir(None);
+
+        let health = CacheHealth::check(&env, Some(&root), Some(repository.path()));
+
+        assert!(!health.enabled);
+        assert_eq!(health.source, Some(CacheRootSource::Configuration));
+        assert!(
+            health
+                .reason
+                .unwrap()
+                .contains("is inside the repository working tree")
+        );
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_cache_health_enabled_for_writable_override() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let root = cache_dir.path().join("ghqc");
+        let env = env_with_cache_dir(None);
+
+        let health = CacheHealth::check(&env, Some(&root), None);
+
+        assert!(health.enabled);
+        assert_eq!(health.path, Some(root.clone()));
+        assert!(root.is_dir());
+        assert!(!root.join(".write-check").exists());
+    }
+
+    /// Captures warnings logged while the cache is opened
+    struct CapturingLogger(std::sync::Mutex<Vec<String>>);
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(std::sync::Mutex::new(Vec::new()));
+
+    #[test]
+    fn test_unwritable_cache_warns_once() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+
+        // A directory cannot be created below a file, whatever the permissions
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("not-a-directory");
+        fs::write(&file, "").unwrap();
+        let root = file.join("cache");
+
+        let health = CacheHealth::check(&env_with_cache_dir(None), Some(&root), None);
+        assert!(!health.enabled);
+        assert!(
+            health
+                .to_string()
+                .starts_with("⚠️ cache disabled (cache_directory option)")
+        );
+
+        for _ in 0..2 {
+            // Opened as `DiskCache::open` does for a repository elsewhere on disk
+            let cache = validate_cache_root(&root, CacheRootSource::Environment, None)
+                .map(|_| DiskCache::at_root(root.clone(), "owner".into(), "repo".into()));
+            assert!(cache_or_warn(cache).is_none());
+        }
+
+        let warnings = LOGGER
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|w| w.starts_with("Running without a disk cache"))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(&root.display().to_string()));
+        assert!(warnings[0].contains("is not writable"));
+    }
+
+    #[test]
+    fn test_cache_read_write_with_ttl() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let cache = DiskCache {
+            root: temp_dir.path().to_path_buf(),
+            owner: "test-owner".to_string(),
+            repo: "test-repo".to_string(),
+            ttl: Duration::from_secs(3600),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        };
+
+        let test_data = vec!["user1".to_string(), "user2".to_string()];
+
+        // Write data with TTL
+        cache.write(&[], "test_users", &test_data, true).unwrap();
+
+        // Read it back
+        let cached_data: Option<Vec<String>> = cache.read(&[], "test_users");
+        assert_eq!(cached_data, Some(test_data));
+
+        // Test non-existent key
+        let missing_data: Option<Vec<String>> = cache.read(&[], "missing");
+        assert_eq!(missing_data, None);
+    }
+
+    #[test]
+    fn test_cache_permanent_storage() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let cache = DiskCache {
+            root: temp_dir.path().to_path_buf(),
+            owner: "test-owner".to_string(),
+            repo: "test-repo".to_string(),
+            ttl: Duration::from_secs(7200),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        };
+
+        let user_data = ("test_user".to_string(), Some("Test User".to_string()));
+
+        // Write data without TTL (permanent)
+        cache.write(&[], "user_test", &user_data, false).unwrap();
+
+        // Read it back
+        let cached_user: Option<(String, Option<String>)> = cache.read(&[], "user_test");
+        assert_eq!(cached_user, Some(user_data));
+    }
+
+    #[test]
+    fn test_hierarchical_paths() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let cache = DiskCache {
+            root: temp_dir.path().to_path_buf(),
+            owner: "test-owner".to_string(),
+            repo: "test-repo".to_string(),
+            ttl: Duration::from_secs(3600),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        };
+
+        // Test nested path structure
+        let assignees = vec!["user1".to_string(), "user2".to_string()];
+        cache
+            .write(&["users"], "assignees", &assignees, true)
+            .unwrap();
+
+        let user_details = serde_json::json!({"login": "user1", "name": "User One"});
+        cache
+            .write(&["users", "details"], "user1", &user_details, false)
+            .unwrap();
+
+        // Verify paths are created correctly
+        let assignees_path = cache.path(&["users"], "assignees");
+        assert!(assignees_path.ends_with("assignees.json"));
+        assert!(assignees_path.parent().unwrap().ends_with("users"));
+
+        let user_path = cache.path(&["users", "details"], "user1");
+        assert!(user_path.ends_with("user1.json"));
+        assert!(user_path.parent().unwrap().ends_with("details"));
+        assert!(
+            user_path
+                .parent()
+                .unwrap()
+                .parent()
+                .unwrap()
+                .ends_with("users")
+        );
+
+        // Verify reading works
+        let cached_assignees: Option<Vec<String>> = cache.read(&["users"], "assignees");
+        assert_eq!(cached_assignees, Some(assignees));
+
+        let cached_user: Option<serde_json::Value> = cache.read(&["users", "details"], "user1");
+        assert_eq!(cached_user, Some(user_details));
+    }
+
+    #[test]
+    fn test_read_counts_hits_and_misses() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let cache = DiskCache::at_root(
+            temp_dir.path().to_path_buf(),
+            "owner".to_string(),
+            "repo".to_string(),
+        );
+        let clone = cache.clone();
+
+        assert_eq!(cache.read::<Vec<String>>(&["labels"], "names"), None);
+        cache
+            .write(&["labels"], "names", &vec!["ghqc".to_string()], false)
+            .unwrap();
+        assert!(clone.read::<Vec<String>>(&["labels"], "names").is_some());
+
+        // Clones share their counts
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        assert_eq!((clone.hits(), clone.misses()), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_get_user_permissions_uses_cache() {
+        use crate::git::MockGitHubReader;
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let cache = DiskCache {
+            root: temp_dir.path().to_path_buf(),
+            owner: "test-owner".to_string(),
+            repo: "test-repo".to_string(),
+            ttl: Duration::from_secs(3600),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        };
+
+        let mut reader = MockGitHubReader::new();
+        reader
+            .expect_get_user_permission()
+            .times(2)
+            .returning(|login| {
+                let permission = if login == "reader" {
+                    Permission::Read
+                } else {
+                    Permission::Write
+                };
+                Box::pin(async move { Ok(permission) })
+            });
+
+        let logins = vec!["reader".to_string(), "writer".to_string()];
+        let first = get_user_permissions(Some(&cache), &reader, &logins)
+            .await
+            .unwrap();
+        assert_eq!(
+            first,
+            vec![
+                ("reader".to_string(), Permission::Read),
+                ("writer".to_string(), Permission::Write),
+            ]
+        );
+
+        // Second lookup is served from the cache; the mock would panic on a third call
+        let second = get_user_permissions(Some(&cache), &reader, &logins)
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+
+        let cached: Option<Permission> = cache.read(&["users", "permissions"], "reader");
+        assert_eq!(cached, Some(Permission::Read));
+    }
+
+    fn listing_cache(dir: &tempfile::TempDir) -> DiskCache {
+        DiskCache::at_root(
+            dir.path().to_path_buf(),
+            "owner".to_string(),
+            "repo".to_string(),
+        )
+        .with_listing_ttl(Duration::from_secs(60))
+    }
+
+    #[tokio::test]
+    async fn test_get_milestones_cached_serves_second_call_from_disk() {
+        use crate::git::MockGitHubReader;
+        use crate::test_utils::create_test_milestone;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = listing_cache(&dir);
+        let mut reader = MockGitHubReader::new();
+        reader.expect_get_milestones().times(2).returning(|| {
+            Box::pin(async {
+                Ok(vec![create_test_milestone(
+                    "owner", "repo", 1, "v1.0", None, "open",
+                )])
+            })
+        });
+
+        let first = get_milestones_cached(Some(&cache), &reader).await.unwrap();
+        // Served from disk: the mock only allows one more call
+        let second = get_milestones_cached(Some(&cache), &reader).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(cache.hits(), 1);
+
+        // `--no-cache` fetches again
+        let refreshed = cache.clone().refreshing();
+        get_milestones_cached(Some(&refreshed), &reader)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_milestone_issues_cached_until_milestone_updated() {
+        use crate::git::MockGitHubReader;
+        use crate::test_utils::{create_test_issue, create_test_milestone};
+        use std::sync::atomic::AtomicUsize;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = listing_cache(&dir);
+        let milestone_calls = Arc::new(AtomicUsize::new(0));
+        let issue_calls = Arc::new(AtomicUsize::new(0));
+
+        let mut reader = MockGitHubReader::new();
+        let calls = milestone_calls.clone();
+        reader.expect_get_milestones().returning(move || {
+            // The milestone is updated between the first and second listing
+            let call = calls.fetch_add(1, Ordering::SeqCst) as i64;
+            let mut milestone = create_test_milestone("owner", "repo", 1, "v1.0", None, "open");
+            milestone.updated_at = chrono::DateTime::from_timestamp(1_700_000_000 + call, 0);
+            Box::pin(async move { Ok(vec![milestone]) })
+        });
+        let calls = issue_calls.clone();
+        reader
+            .expect_get_issues()
+            .withf(|milestone| *milestone == Some(1))
+            .returning(move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async {
+                    Ok(vec![create_test_issue(
+                        "owner",
+                        "repo",
+                        4,
+                        "src/a.R",
+                        "",
+                        Some(1),
+                        "open",
+                    )])
+                })
+            });
+
+        let first = get_milestone_issues_cached(Some(&cache), &reader, 1)
+            .await
+            .unwrap();
+        let second = get_milestone_issues_cached(Some(&cache), &reader, 1)
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].number, first[0].number);
+        assert_eq!(milestone_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(issue_calls.load(Ordering::SeqCst), 1);
+
+        // A fresh milestone listing with a newer `updated_at` makes the issue listing stale
+        cache.invalidate(&[LISTINGS_DIR], "milestones").unwrap();
+        get_milestone_issues_cached(Some(&cache), &reader, 1)
+            .await
+            .unwrap();
+        assert_eq!(milestone_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(issue_calls.load(Ordering::SeqCst), 2);
+
+        // Commands writing to GitHub drop every listing when they end
+        {
+            let _invalidation = ListingInvalidation::new(Some(cache.clone()));
+        }
+        assert!(!cache.repo_dir().join(LISTINGS_DIR).exists());
+        get_milestone_issues_cached(Some(&cache), &reader, 1)
+            .await
+            .unwrap();
+        assert_eq!(milestone_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(issue_calls.load(Ordering::SeqCst), 3);
+    }
+}
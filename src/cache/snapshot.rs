@@ -0,0 +1,674 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{CacheEntry, CachedCommit, DiskCache, write_entry};
+
+/// Version of the on-disk cache layout. Bump whenever cached data changes shape so that
+/// snapshots from incompatible versions are not imported
+pub const CACHE_SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const ENTRIES_DIR: &str = "cache";
+
+/// Describes the repository and cache layout a snapshot was exported from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheManifest {
+    pub owner: String,
+    pub repo: String,
+    pub schema_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub entries: usize,
+}
+
+/// What happened to each imported entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheImportSummary {
+    /// Entries with no local counterpart
+    pub added: usize,
+    /// Local entries replaced by newer imported entries
+    pub updated: usize,
+    /// Local entries kept, but extended with file-change records from the snapshot
+    pub merged: usize,
+    /// Local entries kept because they are at least as new as the imported ones
+    pub kept: usize,
+    /// Imported entries skipped because their TTL has expired
+    pub expired: usize,
+}
+
+impl std::fmt::Display for CacheImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} added, {} updated, {} merged, {} kept local, {} expired",
+            self.added, self.updated, self.merged, self.kept, self.expired
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MergeOutcome {
+    Added,
+    Updated,
+    Merged,
+    Kept,
+    Expired,
+}
+
+/// How entries in a cache namespace decide which copy is newer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Namespace {
+    /// Issue comments and events, valid for the issue's `updated_at` they were fetched at
+    IssueData,
+    /// Per-branch commit lists whose file-change records accumulate over time
+    Commits,
+    /// Everything else, compared by when the entry was written
+    Other,
+}
+
+impl Namespace {
+    fn of(relative_path: &Path) -> Self {
+        let parts: Vec<_> = relative_path
+            .components()
+            .filter_map(|c| c.as_os_str().to_str())
+            .collect();
+
+        match parts.as_slice() {
+            ["issues", "comments" | "events", ..] => Self::IssueData,
+            ["commits", ..] => Self::Commits,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl DiskCache {
+    /// Write every entry of this repository's cache to a zstd-compressed tarball at `path`
+    ///
+    /// The tarball starts with a manifest recording the repository, cache schema version and
+    /// export time, followed by the cache entries in their on-disk layout.
+    pub fn export_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<CacheManifest, CacheSnapshotError> {
+        let repo_dir = self.repo_dir();
+        let entries = entry_paths(&repo_dir)?;
+
+        let manifest = CacheManifest {
+            owner: self.owner.clone(),
+            repo: self.repo.clone(),
+            schema_version: CACHE_SCHEMA_VERSION,
+            exported_at: Utc::now(),
+            entries: entries.len(),
+        };
+
+        let file = fs::File::create(path.as_ref())?;
+        let mut tar = tar::Builder::new(zstd::Encoder::new(file, 0)?);
+
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, MANIFEST_NAME, manifest_bytes.as_slice())?;
+
+        for entry in &entries {
+            tar.append_path_with_name(repo_dir.join(entry), Path::new(ENTRIES_DIR).join(entry))?;
+        }
+
+        tar.into_inner()?.finish()?;
+
+        Ok(manifest)
+    }
+
+    /// Merge a snapshot written by [`DiskCache::export_snapshot`] into this cache
+    ///
+    /// Snapshots from another repository or cache schema version are refused unless `force`
+    /// is set. Local entries are never replaced by older imported ones: issue comments and
+    /// events compare the issue timestamp they were fetched at, commit lists compare when they
+    /// were written and keep the file-change records of both copies, and all other entries
+    /// compare when they were written.
+    pub fn import_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        force: bool,
+    ) -> Result<(CacheManifest, CacheImportSummary), CacheSnapshotError> {
+        let file = fs::File::open(path.as_ref())?;
+        let mut archive = tar::Archive::new(zstd::Decoder::new(file)?);
+        let mut entries = archive.entries()?;
+
+        let manifest: CacheManifest = match entries.next() {
+            Some(entry) => {
+                let entry = entry?;
+                if entry.path()? != Path::new(MANIFEST_NAME) {
+                    return Err(CacheSnapshotError::MissingManifest);
+                }
+                serde_json::from_reader(entry)?
+            }
+            None => return Err(CacheSnapshotError::MissingManifest),
+        };
+        self.check_manifest(&manifest, force)?;
+
+        let repo_dir = self.repo_dir();
+        let mut summary = CacheImportSummary::default();
+        for entry in entries {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let relative_path = snapshot_entry_path(&entry.path()?)?;
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            let imported: CacheEntry<serde_json::Value> = serde_json::from_str(&content)?;
+
+            match merge_into(&repo_dir.join(&relative_path), &relative_path, imported)? {
+                MergeOutcome::Added => summary.added += 1,
+                MergeOutcome::Updated => summary.updated += 1,
+                MergeOutcome::Merged => summary.merged += 1,
+                MergeOutcome::Kept => summary.kept += 1,
+                MergeOutcome::Expired => summary.expired += 1,
+            }
+        }
+
+        Ok((manifest, summary))
+    }
+
+    fn check_manifest(
+        &self,
+        manifest: &CacheManifest,
+        force: bool,
+    ) -> Result<(), CacheSnapshotError> {
+        if force {
+            return Ok(());
+        }
+
+        if manifest.owner != self.owner || manifest.repo != self.repo {
+            return Err(CacheSnapshotError::RepoMismatch {
+                expected: format!("{}/{}", self.owner, self.repo),
+                found: format!("{}/{}", manifest.owner, manifest.repo),
+            });
+        }
+
+        if manifest.schema_version != CACHE_SCHEMA_VERSION {
+            return Err(CacheSnapshotError::SchemaMismatch {
+                expected: CACHE_SCHEMA_VERSION,
+                found: manifest.schema_version,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Merge an imported entry into the local entry at `file_path`, writing only when the local
+/// entry changes
+fn merge_into(
+    file_path: &Path,
+    relative_path: &Path,
+    imported: CacheEntry<serde_json::Value>,
+) -> Result<MergeOutcome, CacheSnapshotError> {
+    if imported.is_expired() {
+        return Ok(MergeOutcome::Expired);
+    }
+
+    // An unreadable local entry would be discarded on read anyway, so the import replaces it
+    let local: Option<CacheEntry<serde_json::Value>> = fs::read_to_string(file_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .filter(|entry: &CacheEntry<serde_json::Value>| !entry.is_expired());
+
+    let Some(local) = local else {
+        write_entry(file_path, &imported)?;
+        return Ok(MergeOutcome::Added);
+    };
+
+    let namespace = Namespace::of(relative_path);
+    let imported_is_newer = match namespace {
+        Namespace::IssueData => match (issue_updated_at(&imported), issue_updated_at(&local)) {
+            (Some(imported_at), Some(local_at)) => imported_at > local_at,
+            _ => imported.created_at > local.created_at,
+        },
+        Namespace::Commits | Namespace::Other => imported.created_at > local.created_at,
+    };
+
+    if namespace == Namespace::Commits {
+        let (mut newer, older, outcome) = if imported_is_newer {
+            (imported, local, MergeOutcome::Updated)
+        } else {
+            (local, imported, MergeOutcome::Merged)
+        };
+
+        let added = merge_commit_file_changes(&mut newer, &older)?;
+        return match outcome {
+            MergeOutcome::Merged if !added => Ok(MergeOutcome::Kept),
+            outcome => {
+                write_entry(file_path, &newer)?;
+                Ok(outcome)
+            }
+        };
+    }
+
+    if imported_is_newer {
+        write_entry(file_path, &imported)?;
+        Ok(MergeOutcome::Updated)
+    } else {
+        Ok(MergeOutcome::Kept)
+    }
+}
+
+fn issue_updated_at(entry: &CacheEntry<serde_json::Value>) -> Option<DateTime<Utc>> {
+    entry
+        .data
+        .get("issue_updated_at")
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+/// Add the file-change records of `older` to the matching commits of `newer`
+///
+/// Whether a commit changed a file never changes, so records from either copy are valid.
+/// Returns whether any record was added.
+fn merge_commit_file_changes(
+    newer: &mut CacheEntry<serde_json::Value>,
+    older: &CacheEntry<serde_json::Value>,
+) -> Result<bool, CacheSnapshotError> {
+    let mut commits: Vec<CachedCommit> = serde_json::from_value(newer.data.clone())?;
+    let older_commits: Vec<CachedCommit> = serde_json::from_value(older.data.clone())?;
+
+    let mut added = false;
+    for commit in &mut commits {
+        let Some(older_commit) = older_commits.iter().find(|c| c.hash == commit.hash) else {
+            continue;
+        };
+        for record in &older_commit.file_changes {
+            if !commit.file_changes.iter().any(|r| r.file == record.file) {
+                commit.file_changes.push(record.clone());
+                added = true;
+            }
+        }
+    }
+
+    if added {
+        newer.data = serde_json::to_value(&commits)?;
+    }
+    Ok(added)
+}
+
+/// Relative paths of every cache entry under `repo_dir`, sorted for a reproducible snapshot
+fn entry_paths(repo_dir: &Path) -> Result<Vec<PathBuf>, CacheSnapshotError> {
+    let mut entries = Vec::new();
+    if !repo_dir.exists() {
+        return Ok(entries);
+    }
+
+    let mut stack = vec![repo_dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                let relative = path
+                    .strip_prefix(repo_dir)
+                    .expect("walked paths are under the cache directory");
+                entries.push(relative.to_path_buf());
+            }
+        }
+    }
+
+    entries.sort();
+    Ok(entries)
+}
+
+/// Path of a snapshot entry relative to the repository cache directory, rejecting anything
+/// that could escape it
+fn snapshot_entry_path(path: &Path) -> Result<PathBuf, CacheSnapshotError> {
+    let invalid = || CacheSnapshotError::InvalidEntry(path.display().to_string());
+
+    let relative = path.strip_prefix(ENTRIES_DIR).map_err(|_| invalid())?;
+    let is_safe = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !is_safe || relative.extension().is_none_or(|ext| ext != "json") {
+        return Err(invalid());
+    }
+
+    Ok(relative.to_path_buf())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheSnapshotError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid cache data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Cache snapshot is missing its manifest")]
+    MissingManifest,
+    #[error("Cache snapshot was exported for {found}, not {expected}")]
+    RepoMismatch { expected: String, found: String },
+    #[error("Cache snapshot has schema version {found}, but this version of ghqc uses {expected}")]
+    SchemaMismatch { expected: u32, found: u32 },
+    #[error("Cache snapshot contains an invalid entry: {0}")]
+    InvalidEntry(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FileChangeRecord, cache::CachedComments, get_issue_comments, git::MockGitHubReader,
+        test_utils::create_test_issue,
+    };
+    use std::time::Duration;
+
+    fn test_cache(root: &Path) -> DiskCache {
+        DiskCache {
+            root: root.to_path_buf(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            ttl: Duration::from_secs(3600),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Default::default(),
+        }
+    }
+
+    fn entry<T: Serialize>(data: T, created_at: u64) -> CacheEntry<serde_json::Value> {
+        CacheEntry {
+            data: serde_json::to_value(data).unwrap(),
+            created_at,
+            ttl_seconds: None,
+        }
+    }
+
+    fn write_raw(cache: &DiskCache, relative_path: &str, entry: &CacheEntry<serde_json::Value>) {
+        write_entry(&cache.repo_dir().join(relative_path), entry).unwrap();
+    }
+
+    fn read_raw(cache: &DiskCache, relative_path: &str) -> CacheEntry<serde_json::Value> {
+        let content = fs::read_to_string(cache.repo_dir().join(relative_path)).unwrap();
+        serde_json::from_str(&content).unwrap()
+    }
+
+    fn comments_at(body: &str, updated_at: &str) -> CachedComments {
+        CachedComments {
+            comments: vec![crate::GitComment {
+                body: body.to_string(),
+                author_login: "alice".to_string(),
+                created_at: Utc::now(),
+                html: None,
+            }],
+            issue_updated_at: updated_at.parse().unwrap(),
+        }
+    }
+
+    fn commits(file: &str, changed: bool) -> Vec<CachedCommit> {
+        vec![CachedCommit {
+            hash: "a".repeat(40),
+            message: "Initial commit".to_string(),
+            file_changes: vec![FileChangeRecord {
+                file: file.to_string(),
+                changed,
+            }],
+        }]
+    }
+
+    /// Export `source` and import the snapshot into `target`
+    fn round_trip(source: &DiskCache, target: &DiskCache) -> CacheImportSummary {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = dir.path().join("cache.tar.zst");
+        source.export_snapshot(&snapshot).unwrap();
+        target.import_snapshot(&snapshot, false).unwrap().1
+    }
+
+    #[test]
+    fn test_issue_data_merge_keeps_newer_issue_timestamp() {
+        let (source_dir, target_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (test_cache(source_dir.path()), test_cache(target_dir.path()));
+
+        // Newer local comments win even though the imported entry was written later
+        let local = comments_at("local", "2025-02-01T00:00:00Z");
+        write_raw(&target, "issues/comments/issue_1.json", &entry(&local, 100));
+        let imported = comments_at("imported", "2025-01-01T00:00:00Z");
+        write_raw(
+            &source,
+            "issues/comments/issue_1.json",
+            &entry(&imported, 200),
+        );
+
+        // Newer imported events replace older local events
+        write_raw(
+            &target,
+            "issues/events/issue_1.json",
+            &entry(
+                serde_json::json!({"events": [], "issue_updated_at": "2025-01-01T00:00:00Z"}),
+                200,
+            ),
+        );
+        write_raw(
+            &source,
+            "issues/events/issue_1.json",
+            &entry(
+                serde_json::json!({"events": [{"event": "closed"}], "issue_updated_at": "2025-02-01T00:00:00Z"}),
+                100,
+            ),
+        );
+
+        let summary = round_trip(&source, &target);
+
+        assert_eq!(summary.kept, 1);
+        assert_eq!(summary.updated, 1);
+        let comments: CachedComments =
+            serde_json::from_value(read_raw(&target, "issues/comments/issue_1.json").data).unwrap();
+        assert_eq!(comments.comments[0].body, "local");
+        let events = read_raw(&target, "issues/events/issue_1.json").data;
+        assert_eq!(events["events"][0]["event"], "closed");
+    }
+
+    #[test]
+    fn test_other_namespaces_merge_by_write_time() {
+        let (source_dir, target_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (test_cache(source_dir.path()), test_cache(target_dir.path()));
+
+        write_raw(&target, "labels/names.json", &entry(vec!["local"], 200));
+        write_raw(&source, "labels/names.json", &entry(vec!["imported"], 100));
+        write_raw(&target, "users/details/alice.json", &entry("local", 100));
+        write_raw(&source, "users/details/alice.json", &entry("imported", 200));
+        write_raw(&source, "users/details/bob.json", &entry("imported", 100));
+
+        let summary = round_trip(&source, &target);
+
+        assert_eq!(
+            summary,
+            CacheImportSummary {
+                added: 1,
+                updated: 1,
+                kept: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            read_raw(&target, "labels/names.json").data,
+            serde_json::json!(["local"])
+        );
+        assert_eq!(
+            read_raw(&target, "users/details/alice.json").data,
+            serde_json::json!("imported")
+        );
+        assert_eq!(
+            read_raw(&target, "users/details/bob.json").data,
+            serde_json::json!("imported")
+        );
+    }
+
+    #[test]
+    fn test_commit_merge_keeps_file_changes_from_both_copies() {
+        let (source_dir, target_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (test_cache(source_dir.path()), test_cache(target_dir.path()));
+
+        // The newer local list is kept, gaining the imported record for another file
+        write_raw(
+            &target,
+            "commits/main.json",
+            &entry(commits("a.R", true), 200),
+        );
+        write_raw(
+            &source,
+            "commits/main.json",
+            &entry(commits("b.R", false), 100),
+        );
+        // The newer imported list replaces the local one, keeping the local record
+        write_raw(
+            &target,
+            "commits/dev.json",
+            &entry(commits("a.R", true), 100),
+        );
+        write_raw(
+            &source,
+            "commits/dev.json",
+            &entry(commits("b.R", false), 200),
+        );
+        // Nothing to add, so the local list is left alone
+        write_raw(
+            &target,
+            "commits/HEAD.json",
+            &entry(commits("a.R", true), 200),
+        );
+        write_raw(
+            &source,
+            "commits/HEAD.json",
+            &entry(commits("a.R", false), 100),
+        );
+
+        let summary = round_trip(&source, &target);
+
+        assert_eq!(summary.merged, 1);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.kept, 1);
+
+        for branch in ["main", "dev"] {
+            let entry = read_raw(&target, &format!("commits/{branch}.json"));
+            let cached: Vec<CachedCommit> = serde_json::from_value(entry.data).unwrap();
+            let files: Vec<_> = cached[0]
+                .file_changes
+                .iter()
+                .map(|r| r.file.as_str())
+                .collect();
+            assert_eq!(files.len(), 2, "{branch} should have both records");
+            assert!(files.contains(&"a.R") && files.contains(&"b.R"));
+        }
+        let head: Vec<CachedCommit> =
+            serde_json::from_value(read_raw(&target, "commits/HEAD.json").data).unwrap();
+        assert!(head[0].file_changes[0].changed);
+    }
+
+    #[test]
+    fn test_expired_imported_entries_are_skipped() {
+        let (source_dir, target_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (test_cache(source_dir.path()), test_cache(target_dir.path()));
+
+        let mut expired = entry(vec!["alice"], 0);
+        expired.ttl_seconds = Some(60);
+        write_raw(&source, "users/assignees.json", &expired);
+
+        let summary = round_trip(&source, &target);
+
+        assert_eq!(summary.expired, 1);
+        assert!(!target.path(&["users"], "assignees").exists());
+    }
+
+    #[test]
+    fn test_import_refuses_mismatched_manifest_without_force() {
+        let (source_dir, target_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let source = test_cache(source_dir.path());
+        write_raw(&source, "labels/names.json", &entry(vec!["ghqc"], 100));
+        let snapshot = source_dir.path().join("cache.tar.zst");
+        let manifest = source.export_snapshot(&snapshot).unwrap();
+        assert_eq!(manifest.entries, 1);
+        assert_eq!(manifest.schema_version, CACHE_SCHEMA_VERSION);
+
+        let other_repo = DiskCache {
+            repo: "other".to_string(),
+            ..test_cache(target_dir.path())
+        };
+        assert!(matches!(
+            other_repo.import_snapshot(&snapshot, false),
+            Err(CacheSnapshotError::RepoMismatch { .. })
+        ));
+        assert!(!other_repo.path(&["labels"], "names").exists());
+
+        let (_, summary) = other_repo.import_snapshot(&snapshot, true).unwrap();
+        assert_eq!(summary.added, 1);
+
+        let old_schema = CacheManifest {
+            schema_version: CACHE_SCHEMA_VERSION + 1,
+            ..manifest
+        };
+        assert!(matches!(
+            source.check_manifest(&old_schema, false),
+            Err(CacheSnapshotError::SchemaMismatch { .. })
+        ));
+        assert!(source.check_manifest(&old_schema, true).is_ok());
+    }
+
+    #[test]
+    fn test_snapshot_entry_path_rejects_escapes() {
+        assert_eq!(
+            snapshot_entry_path(Path::new("cache/issues/comments/issue_1.json")).unwrap(),
+            PathBuf::from("issues/comments/issue_1.json")
+        );
+        for path in [
+            "cache/../escape.json",
+            "other/labels.json",
+            "cache/labels.tmp",
+        ] {
+            assert!(
+                matches!(
+                    snapshot_entry_path(Path::new(path)),
+                    Err(CacheSnapshotError::InvalidEntry(_))
+                ),
+                "{path} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_imported_cache_serves_comments_without_fetching() {
+        let (source_dir, target_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (test_cache(source_dir.path()), test_cache(target_dir.path()));
+        let issue = create_test_issue("owner", "repo", 1, "scripts/model.R", "body", None, "open");
+
+        let mut warming = MockGitHubReader::new();
+        warming.expect_get_issue_comments().times(1).returning(|_| {
+            Box::pin(async {
+                Ok(vec![crate::GitComment {
+                    body: "# QC Notification".to_string(),
+                    author_login: "alice".to_string(),
+                    created_at: Utc::now(),
+                    html: None,
+                }])
+            })
+        });
+        let fetched = get_issue_comments(&issue, Some(&source), &warming)
+            .await
+            .unwrap();
+
+        let summary = round_trip(&source, &target);
+        assert_eq!(summary.added, 1);
+
+        // Any comment fetch would panic: the imported cache must serve the comments
+        let mut cold = MockGitHubReader::new();
+        cold.expect_get_issue_comments().never();
+        let cached = get_issue_comments(&issue, Some(&target), &cold)
+            .await
+            .unwrap();
+
+        assert_eq!(cached.len(), fetched.len());
+        assert_eq!(cached[0].body, fetched[0].body);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use futures::StreamExt;
+
+use super::{DiskCache, get_repo_users};
+use crate::git::{GitHubApiError, GitHubReader, RepoUser};
+
+/// Maximum number of user lookups in flight at once
+pub const MAX_CONCURRENT_USER_LOOKUPS: usize = 4;
+
+/// Display names of GitHub users, keyed by login
+///
+/// Starts from the repository's assignable users; logins outside of them (former collaborators,
+/// outside commenters, organization owners) are looked up with
+/// [`GitHubReader::get_user_details`] by [`UserDirectory::resolve`]. Each login is looked up at
+/// most once, and the details are cached permanently in the disk cache.
+#[derive(Debug, Clone, Default)]
+pub struct UserDirectory {
+    /// `None` for logins whose lookup failed, so they are not retried
+    users: HashMap<String, Option<RepoUser>>,
+}
+
+impl UserDirectory {
+    pub fn new(repo_users: impl IntoIterator<Item = RepoUser>) -> Self {
+        Self {
+            users: repo_users
+                .into_iter()
+                .map(|user| (user.login.clone(), Some(user)))
+                .collect(),
+        }
+    }
+
+    /// Directory of the repository's assignable users
+    pub async fn load(
+        cache: Option<&DiskCache>,
+        git_info: &impl GitHubReader,
+    ) -> Result<Self, GitHubApiError> {
+        Ok(Self::new(get_repo_users(cache, git_info).await?))
+    }
+
+    /// Look up the details of the `logins` not in the directory yet, with at most
+    /// [`MAX_CONCURRENT_USER_LOOKUPS`] requests in flight
+    ///
+    /// A failed lookup is logged and leaves the login without a display name.
+    pub async fn resolve<'a>(
+        &mut self,
+        logins: impl IntoIterator<Item = &'a str>,
+        cache: Option<&DiskCache>,
+        git_info: &impl GitHubReader,
+    ) {
+        let mut unknown: Vec<String> = logins
+            .into_iter()
+            .filter(|login| !self.users.contains_key(*login))
+            .map(str::to_string)
+            .collect();
+        unknown.sort_unstable();
+        unknown.dedup();
+        if unknown.is_empty() {
+            return;
+        }
+
+        log::debug!("Looking up {} users outside the assignees", unknown.len());
+        let lookups: Vec<(String, Result<RepoUser, GitHubApiError>)> =
+            futures::stream::iter(unknown)
+                .map(|login| async move {
+                    let result = cached_user_details(cache, git_info, &login).await;
+                    (login, result)
+                })
+                .buffer_unordered(MAX_CONCURRENT_USER_LOOKUPS)
+                .collect()
+                .await;
+
+        for (login, result) in lookups {
+            let user = result
+                .inspect_err(|e| log::debug!("Failed to look up user {login}: {e}"))
+                .ok();
+            self.users.insert(login, user);
+        }
+    }
+
+    pub fn get(&self, login: &str) -> Option<&RepoUser> {
+        self.users.get(login).and_then(Option::as_ref)
+    }
+
+    /// `Name (login)` for a user with a display name
+    pub fn display_name(&self, login: &str) -> Option<String> {
+        self.get(login)
+            .and_then(|user| user.name.as_ref())
+            .map(|name| format!("{} ({})", name, login))
+    }
+}
+
+/// Details of a user, cached permanently since names rarely change
+pub(super) async fn cached_user_details(
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+    login: &str,
+) -> Result<RepoUser, GitHubApiError> {
+    if let Some(user) = cache.and_then(|c| c.read::<RepoUser>(&["users", "details"], login)) {
+        log::trace!("Using cached user details for: {}", login);
+        return Ok(user);
+    }
+
+    log::debug!("User details for {} not found in cache. Fetching...", login);
+    let user = git_info.get_user_details(login).await?;
+
+    if let Some(cache) = cache
+        && let Err(e) = cache.write(&["users", "details"], login, &user, false)
+    {
+        log::warn!("Failed to cache user details for {}: {}", login, e);
+    }
+
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::MockGitHubReader;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn user(login: &str, name: Option<&str>) -> RepoUser {
+        RepoUser {
+            login: login.to_string(),
+            name: name.map(str::to_string),
+        }
+    }
+
+    fn test_cache(root: &std::path::Path) -> DiskCache {
+        DiskCache {
+            root: root.to_path_buf(),
+            owner: "test-owner".to_string(),
+            repo: "test-repo".to_string(),
+            ttl: Duration::from_secs(3600),
+            listing_ttl: Duration::from_secs(60),
+            refresh: false,
+            refresh_images: false,
+            stats: Arc::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_directory_looks_up_unknown_logins_once() {
+        let mut reader = MockGitHubReader::new();
+        reader
+            .expect_get_user_details()
+            .times(2)
+            .returning(|login| {
+                let result = match login {
+                    "outsider" => Ok(user("outsider", Some("Olive Outsider"))),
+                    _ => Err(GitHubApiError::NoApi),
+                };
+                Box::pin(async move { result })
+            });
+
+        let mut directory = UserDirectory::new([user("octocat", Some("The Octocat"))]);
+        directory
+            .resolve(["octocat", "outsider", "ghost", "outsider"], None, &reader)
+            .await;
+        // Known and failed logins are not looked up again; the mock would panic on a third call
+        directory
+            .resolve(["outsider", "ghost", "octocat"], None, &reader)
+            .await;
+
+        assert_eq!(
+            directory.display_name("octocat").as_deref(),
+            Some("The Octocat (octocat)")
+        );
+        assert_eq!(
+            directory.display_name("outsider").as_deref(),
+            Some("Olive Outsider (outsider)")
+        );
+        assert_eq!(directory.display_name("ghost"), None);
+        assert!(directory.get("ghost").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_user_directory_uses_disk_cache() {
+        let temp_dir = tempdir().unwrap();
+        let cache = test_cache(temp_dir.path());
+
+        let mut reader = MockGitHubReader::new();
+        reader
+            .expect_get_user_details()
+            .times(1)
+            .returning(|login| {
+                let result = Ok(user(login, Some("Olive Outsider")));
+                Box::pin(async move { result })
+            });
+
+        let mut first = UserDirectory::default();
+        first.resolve(["outsider"], Some(&cache), &reader).await;
+
+        // A new directory, as in a later run, reads the details from the cache
+        let mut second = UserDirectory::default();
+        second.resolve(["outsider"], Some(&cache), &reader).await;
+
+        assert_eq!(
+            second.display_name("outsider").as_deref(),
+            Some("Olive Outsider (outsider)")
+        );
+        let cached: Option<RepoUser> = cache.read(&["users", "details"], "outsider");
+        assert_eq!(
+            cached.and_then(|u| u.name).as_deref(),
+            Some("Olive Outsider")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_user_directory_caps_concurrent_lookups() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let mut reader = MockGitHubReader::new();
+        let (current, max) = (in_flight.clone(), max_in_flight.clone());
+        reader
+            .expect_get_user_details()
+            .times(10)
+            .returning(move |login| {
+                let (current, max) = (current.clone(), max.clone());
+                let login = login.to_string();
+                Box::pin(async move {
+                    let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    current.fetch_sub(1, Ordering::SeqCst);
+                    Ok(user(&login, None))
+                })
+            });
+
+        let logins: Vec<String> = (0..10).map(|i| format!("user{i}")).collect();
+        let mut directory = UserDirectory::default();
+        directory
+            .resolve(logins.iter().map(String::as_str), None, &reader)
+            .await;
+
+        assert!(logins.iter().all(|login| directory.get(login).is_some()));
+        let max = max_in_flight.load(Ordering::SeqCst);
+        assert!(max > 1 && max <= MAX_CONCURRENT_USER_LOOKUPS, "{max}");
+    }
+}
@@ -4,8 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use anyhow::{Result, bail};
-use futures::future;
+use anyhow::{Context, Result, bail};
 use gix::ObjectId;
 use inquire::{
     Autocomplete, Confirm, CustomUserError, MultiSelect, Select, Text, list_option::ListOption,
@@ -13,20 +12,28 @@ use inquire::{
 };
 use octocrab::models::Milestone;
 
+use crate::cli::interactive::ask;
+use crate::message;
+use crate::utils::EnvProvider;
 use crate::{
-    DiskCache, GitCommitOps, GitHubReader, GitRepository, IssueThread, archive::ArchiveFile,
-    get_issue_comments, git::GitCommit,
+    ApprovalWindow, Configuration, Diagnostics, DiskCache, GeneratedArchiveFile, GitCommitOps,
+    GitFileOps, GitHelpers, GitHubReader, GitRepository, GitStatusOps, HttpDownloader, IssueThread,
+    MilestoneSnapshot, RecordOptions, archive::ArchiveFile, create_staging_dir,
+    generate_archive_name, get_milestone_issue_information, git::GitCommit, record,
 };
 
+/// Archive path of the milestone record added with `--include-record`
+pub const ARCHIVE_RECORD_FILE: &str = "QC_RECORD.pdf";
+
 pub async fn prompt_archive(
     milestones: &[Milestone],
     current_dir: &PathBuf,
     git_info: &(impl GitHubReader + GitCommitOps + GitRepository),
     cache: Option<&DiskCache>,
 ) -> Result<(Vec<ArchiveFile>, PathBuf)> {
-    println!("📦 Welcome to GHQC Milestone Archive Mode!");
+    message!("📦 Welcome to GHQC Milestone Archive Mode!");
 
-    let milestone_selection_method = Select::new(
+    let milestone_selection_method = ask(Select::new(
         "📦 How would you like to select milestones for the archive?",
         vec![
             "📋 Select All Milestones",
@@ -34,8 +41,7 @@ pub async fn prompt_archive(
             "🚫 Select No Milestones",
         ],
     )
-    .prompt()
-    .map_err(|e| anyhow::anyhow!("Selection cancelled: {e}"))?;
+    .prompt())?;
 
     let milestones = match milestone_selection_method {
         "📋 Select All Milestones" => {
@@ -63,19 +69,20 @@ pub async fn prompt_archive(
                 .map(|m| m.title.to_string())
                 .collect::<Vec<_>>();
 
-            let selected_strings =
-                MultiSelect::new("📦 Select milestones for the archive:", milestone_options)
-                    .with_validator(|selection: &[ListOption<&String>]| {
-                        if selection.is_empty() {
-                            Ok(inquire::validator::Validation::Invalid(
-                                "Please select at least one milestone".into(),
-                            ))
-                        } else {
-                            Ok(inquire::validator::Validation::Valid)
-                        }
-                    })
-                    .prompt()
-                    .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+            let selected_strings = ask(MultiSelect::new(
+                "📦 Select milestones for the archive:",
+                milestone_options,
+            )
+            .with_validator(|selection: &[ListOption<&String>]| {
+                if selection.is_empty() {
+                    Ok(inquire::validator::Validation::Invalid(
+                        "Please select at least one milestone".into(),
+                    ))
+                } else {
+                    Ok(inquire::validator::Validation::Valid)
+                }
+            })
+            .prompt())?;
 
             milestones
                 .iter()
@@ -89,20 +96,19 @@ pub async fn prompt_archive(
     let (issue_threads, select_additional_files) = if milestone_selection_method
         != "🚫 Select No Milestones"
     {
-        let approved_issues_only = Confirm::new("✅ Include approved issues only?")
+        let approved_issues_only = ask(Confirm::new("✅ Include approved issues only?")
             .with_default(true)
             .with_help_message(
                 "n = all issues within selected milestones, Y = only approved issues",
             )
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+            .prompt())?;
 
         let mut issue_threads = get_milestone_issue_threads(&milestones, git_info, cache).await?;
 
         if approved_issues_only {
             issue_threads = issue_threads
                 .into_iter()
-                .filter(|i| i.approved_commit().is_some())
+                .filter(|i| i.obsoleted.is_none() && i.approved_commit().is_some())
                 .collect()
         };
 
@@ -110,16 +116,15 @@ pub async fn prompt_archive(
             .iter()
             .any(|i| git_info.branch().map(|b| b == i.branch).unwrap_or(true))
         {
-            println!(
+            message!(
                 "⚠️ No issues in selected milestones match local branch. Selecting additional files may not have commits of interested"
             );
         }
 
-        let select_additional_files = Confirm::new("📄 Select additional files?")
+        let select_additional_files = ask(Confirm::new("📄 Select additional files?")
             .with_default(false)
             .with_help_message("N = milestone files only, y = select additional files and commits")
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+            .prompt())?;
 
         (issue_threads, select_additional_files)
     } else {
@@ -137,13 +142,12 @@ pub async fn prompt_archive(
         Vec::new()
     };
 
-    let flatten = Confirm::new("📁 Flatten archive directory structure?")
+    let flatten = ask(Confirm::new("📁 Flatten archive directory structure?")
         .with_default(false)
         .with_help_message(
             "N = retain repository structure, y = strip folder structure from selected files",
         )
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+        .prompt())?;
 
     let mut archive_files = Vec::new();
     for issue_thread in issue_threads {
@@ -158,11 +162,10 @@ pub async fn prompt_archive(
     let default_archive_path = PathBuf::from("archive").join(&default_archive_name);
 
     // Prompt user for archive path with default
-    let archive_path_input = Text::new("📁 Enter archive path:")
+    let archive_path_input = ask(Text::new("📁 Enter archive path:")
         .with_default(&default_archive_path.to_string_lossy())
         .with_help_message("Press Enter to use the default path shown above")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+        .prompt())?;
 
     let final_archive_path = PathBuf::from(archive_path_input.trim());
 
@@ -170,11 +173,10 @@ pub async fn prompt_archive(
 }
 
 fn prompt_open_milestones() -> Result<MilestoneSelectionFilter> {
-    let include_open_milestones = Confirm::new("📦 Include open milestones?")
+    let include_open_milestones = ask(Confirm::new("📦 Include open milestones?")
         .with_default(false)
         .with_help_message("N = include only closed milestones, y = include all milestones")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {e}"))?;
+        .prompt())?;
 
     if include_open_milestones {
         Ok(MilestoneSelectionFilter::All)
@@ -210,21 +212,153 @@ pub async fn get_milestone_issue_threads(
     git_info: &(impl GitHubReader + GitCommitOps + GitRepository),
     cache: Option<&DiskCache>,
 ) -> Result<Vec<IssueThread>> {
-    let futures = milestones
-        .iter()
-        .map(|&m| async move {
-            let issues = git_info.get_issues(Some(m.number as u64)).await?;
-            Ok::<_, anyhow::Error>(issues)
-        })
-        .collect::<Vec<_>>();
-    let milestone_results = future::try_join_all(futures).await?;
-
-    let mut seen_files: HashMap<String, Vec<String>> = HashMap::new();
-    for issue in milestone_results.iter().flatten() {
-        let entry = seen_files.entry(issue.title.to_string()).or_default();
-        if let Some(milestone) = &issue.milestone {
-            entry.push(milestone.title.to_string());
+    let snapshot = MilestoneSnapshot::build(milestones, git_info, cache).await?;
+    check_duplicate_files(&snapshot)?;
+
+    Ok(snapshot
+        .threads(git_info, cache)?
+        .into_iter()
+        .map(|t| t.thread)
+        .collect())
+}
+
+/// Generate the PDF record of `milestones` to place at the archive root
+///
+/// `render_pdf` renders the record's Typst source to the given path using the given staging
+/// directory, normally with [`crate::render`].
+pub async fn archive_record(
+    milestones: &[&Milestone],
+    configuration: &Configuration,
+    git_info: &(impl GitHubReader + GitCommitOps + GitStatusOps + GitRepository + GitHelpers),
+    env: &impl EnvProvider,
+    cache: Option<&DiskCache>,
+    http_downloader: &impl HttpDownloader,
+    render_pdf: impl FnOnce(&str, &Path, &Path) -> Result<()>,
+) -> Result<GeneratedArchiveFile> {
+    let snapshot = MilestoneSnapshot::build(milestones, git_info, cache).await?;
+    let threads = snapshot.threads(git_info, cache)?;
+
+    let staging_dir = create_staging_dir()?;
+    let issue_information = get_milestone_issue_information(
+        &threads,
+        cache,
+        &configuration.signing_keys(),
+        git_info,
+        http_downloader,
+        &staging_dir,
+        &mut Diagnostics::new(),
+    )
+    .await?;
+    let milestones = milestones.iter().map(|&m| m.clone()).collect::<Vec<_>>();
+    let record_str = record(
+        &milestones,
+        &issue_information,
+        configuration,
+        git_info,
+        env,
+        &RecordOptions::default(),
+        &staging_dir,
+    )?;
+
+    // Rendering removes the staging directory, so the pdf is written next to it
+    let output_dir = tempfile::tempdir()?;
+    let pdf_path = output_dir.path().join(ARCHIVE_RECORD_FILE);
+    render_pdf(&record_str, &pdf_path, &staging_dir)?;
+    let content = fs::read(&pdf_path).with_context(|| {
+        format!(
+            "Failed to read the rendered record at {}",
+            pdf_path.display()
+        )
+    })?;
+
+    Ok(GeneratedArchiveFile::new(
+        ARCHIVE_RECORD_FILE,
+        "milestone record",
+        content,
+    ))
+}
+
+/// The record from [`archive_record`], or `None` with a warning when it failed and is
+/// `optional`
+pub fn optional_record(
+    record: Result<GeneratedArchiveFile>,
+    optional: bool,
+) -> Result<Option<GeneratedArchiveFile>> {
+    match record {
+        Ok(record) => Ok(Some(record)),
+        Err(e) if optional => {
+            message!("⚠️  Archiving without the milestone record, which failed to generate: {e:#}");
+            Ok(None)
         }
+        Err(e) => Err(e.context(
+            "Failed to generate the milestone record for the archive (--record-optional archives without it)",
+        )),
+    }
+}
+
+/// Archive files for the issues of `milestones`, and the files listed as relevant to them when
+/// `include_relevant_files`
+pub async fn milestone_archive_files(
+    milestones: &[&Milestone],
+    include_unapproved: bool,
+    approval_window: &ApprovalWindow,
+    flatten: bool,
+    include_relevant_files: bool,
+    git_info: &(impl GitHubReader + GitCommitOps + GitRepository + GitFileOps),
+    cache: Option<&DiskCache>,
+) -> Result<Vec<ArchiveFile>> {
+    let issue_threads = get_milestone_issue_threads(milestones, git_info, cache).await?;
+    let mut archive_files =
+        archive_files_from_threads(&issue_threads, include_unapproved, approval_window, flatten)?;
+    if include_relevant_files {
+        archive_files.extend(relevant_archive_files(
+            &issue_threads,
+            include_unapproved,
+            approval_window,
+            git_info,
+        ));
+    }
+    Ok(archive_files)
+}
+
+/// Archive files for the relevant files of the issue threads archived by
+/// [`archive_files_from_threads`], see [`ArchiveFile::from_relevant_files`]
+pub fn relevant_archive_files<'a>(
+    issue_threads: impl IntoIterator<Item = &'a IssueThread>,
+    include_unapproved: bool,
+    approval_window: &ApprovalWindow,
+    git_info: &impl GitFileOps,
+) -> Vec<ArchiveFile> {
+    issue_threads
+        .into_iter()
+        .filter(|i| approval_window.includes(i, include_unapproved))
+        .flat_map(|i| ArchiveFile::from_relevant_files(i, git_info))
+        .collect()
+}
+
+/// Archive files for issue threads, skipping unapproved and obsolete issues unless
+/// `include_unapproved` and issues outside `approval_window`
+pub fn archive_files_from_threads<'a>(
+    issue_threads: impl IntoIterator<Item = &'a IssueThread>,
+    include_unapproved: bool,
+    approval_window: &ApprovalWindow,
+    flatten: bool,
+) -> Result<Vec<ArchiveFile>> {
+    issue_threads
+        .into_iter()
+        .filter(|i| approval_window.includes(i, include_unapproved))
+        .map(|i| ArchiveFile::from_issue_thread(i, flatten).map_err(Into::into))
+        .collect()
+}
+
+/// Fail if a file is QCed in more than one of the snapshot's milestones
+pub fn check_duplicate_files(snapshot: &MilestoneSnapshot) -> Result<()> {
+    let mut seen_files: HashMap<&str, Vec<&str>> = HashMap::new();
+    for issue in &snapshot.issues {
+        seen_files
+            .entry(issue.issue.title.as_str())
+            .or_default()
+            .push(issue.milestone.as_str());
     }
     let has_conflict = seen_files
         .iter()
@@ -242,23 +376,7 @@ pub async fn get_milestone_issue_threads(
         )
     }
 
-    // Fetch all comments in parallel first
-    let comment_futures = milestone_results
-        .iter()
-        .flatten()
-        .map(|issue| async move { (issue, get_issue_comments(issue, cache, git_info).await) })
-        .collect::<Vec<_>>();
-    let comment_results = future::join_all(comment_futures).await;
-
-    // Build IssueThreads
-    let mut issue_thread_results = Vec::new();
-    for (issue, comments_result) in comment_results {
-        let comments = comments_result?;
-        let issue_thread = IssueThread::from_issue_comments(issue, &comments, git_info, cache)?;
-        issue_thread_results.push(issue_thread);
-    }
-
-    Ok(issue_thread_results)
+    Ok(())
 }
 
 /// Interactive file selection for archive with conflict detection and commit selection
@@ -385,7 +503,7 @@ fn prompt_archive_files(
 
         let validator_dir = current_dir.clone();
         let validator_excluded = excluded_files.clone();
-        let input = Text::new(&prompt_text)
+        let input = ask(Text::new(&prompt_text)
             .with_autocomplete(file_completer)
             .with_validator(move |input: &str| {
                 let trimmed = input.trim();
@@ -418,8 +536,7 @@ fn prompt_archive_files(
                     }
                 }
             })
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+            .prompt())?;
 
         let trimmed_input = input.trim();
         if trimmed_input.is_empty() {
@@ -438,7 +555,7 @@ fn prompt_archive_files(
             .collect();
 
         if file_changing_commits.is_empty() {
-            println!(
+            message!(
                 "⚠️ No commits found that change file: {}",
                 file_path.display()
             );
@@ -465,12 +582,11 @@ fn prompt_archive_files(
             })
             .collect();
 
-        let commit_selection = Select::new(
+        let commit_selection = ask(Select::new(
             &format!("📝 Select commit for file {}:", file_path.display()),
             commit_options,
         )
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+        .prompt())?;
 
         // Extract the commit hash from the selection
         let selected_hash_str = commit_selection.split(" - ").next().unwrap_or("");
@@ -486,39 +602,3 @@ fn prompt_archive_files(
 
     Ok(selected_files)
 }
-
-/// Generate archive name based on milestones and repository name
-pub fn generate_archive_name(milestones: &[&Milestone], git_info: &impl GitRepository) -> String {
-    // Get repository name from git_info
-    let repo_name = git_info.repo();
-
-    let archive_name = if milestones.is_empty() {
-        // No milestones: archive/<repo name>.tar.gz
-        format!("{}.tar.gz", repo_name)
-    } else {
-        // With milestones: archive/<repo name>-<milestone1-milestone2>.tar.gz
-        let milestone_names: Vec<String> = milestones
-            .iter()
-            .map(|m| {
-                // Sanitize milestone names for filename usage
-                m.title
-                    .chars()
-                    .map(|c| match c {
-                        // Replace problematic characters with dashes
-                        '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | ' ' => '-',
-                        c => c,
-                    })
-                    .collect::<String>()
-                    // Remove consecutive dashes
-                    .split('-')
-                    .filter(|s| !s.is_empty())
-                    .collect::<Vec<_>>()
-                    .join("-")
-            })
-            .collect();
-
-        format!("{}-{}.tar.gz", repo_name, milestone_names.join("-"))
-    };
-
-    archive_name
-}
@@ -4,6 +4,7 @@ use owo_colors::OwoColorize;
 use std::path::Path;
 use std::process::{Command, Stdio};
 
+use super::interactive::ask;
 use super::section_header;
 use crate::AuthSources;
 use crate::auth::{AuthStore, canonicalize_base_url, extract_host_from_base_url, token_page_url};
@@ -68,12 +69,13 @@ pub fn gh_auth_login(
     let _ = open::that(&token_url);
     eprintln!("{} {}", "Token page:".bold(), token_url.underline().cyan());
 
-    let token = Password::new(&format!("{} ", "Paste personal access token".bold()))
-        .without_confirmation()
-        .with_display_toggle_enabled()
-        .with_help_message("Input is hidden by default. Press Ctrl+R to toggle visibility.")
-        .prompt()
-        .map_err(|e| anyhow!("Prompt cancelled: {e}"))?;
+    let token = ask(
+        Password::new(&format!("{} ", "Paste personal access token".bold()))
+            .without_confirmation()
+            .with_display_toggle_enabled()
+            .with_help_message("Input is hidden by default. Press Ctrl+R to toggle visibility.")
+            .prompt(),
+    )?;
 
     let token = require_nonempty_token(token)?;
     store.save_token(&host_name, &token)?;
@@ -0,0 +1,614 @@
+use anyhow::{Result, bail};
+use octocrab::models::{IssueState, Milestone};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::message;
+use crate::{
+    CodeOwners, Configuration, DiskCache, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers,
+    GitHubReader, GitHubWriter, GitRepository, QCIssue, RepoUser,
+    codeowners::pattern_regex,
+    create::{collaborator_override_for_policy, resolve_issue_people},
+    create_labels_if_needed, tracked_files,
+    utils::IgnoreRules,
+};
+
+use super::context::{ChecklistArg, validate_assignee_permissions, validate_owner_assignees};
+
+/// Settings of `issue create-batch`
+#[derive(Debug, Clone)]
+pub struct BatchCreateOptions {
+    /// Milestone the issues are created in, created when missing
+    pub milestone: String,
+    /// Description of the milestone, only used when it is created
+    pub description: Option<String>,
+    /// Files to create an issue for, in order
+    pub files: Vec<PathBuf>,
+    pub checklist: ChecklistArg,
+    pub assignees: Vec<String>,
+    pub require_write_access: bool,
+}
+
+/// What happened to one file of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchFileOutcome {
+    Created {
+        issue_number: u64,
+        issue_url: String,
+    },
+    /// The milestone already has an open issue for the file
+    Skipped { issue_number: u64 },
+    /// The file failed validation or its issue could not be posted
+    Failed(String),
+}
+
+/// The outcome of each file of an `issue create-batch` run
+#[derive(Debug, Clone)]
+pub struct BatchCreateReport {
+    pub milestone: String,
+    pub files: Vec<(PathBuf, BatchFileOutcome)>,
+}
+
+impl serde::Serialize for BatchCreateReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// A file of the batch with its outcome, flattened for JSON output
+        #[derive(serde::Serialize)]
+        struct FileOutcome<'a> {
+            file: &'a Path,
+            outcome: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            issue_number: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            issue_url: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<&'a str>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Report<'a> {
+            milestone: &'a str,
+            files: Vec<FileOutcome<'a>>,
+        }
+
+        let files = self
+            .files
+            .iter()
+            .map(|(file, outcome)| match outcome {
+                BatchFileOutcome::Created {
+                    issue_number,
+                    issue_url,
+                } => FileOutcome {
+                    file,
+                    outcome: "created",
+                    issue_number: Some(*issue_number),
+                    issue_url: Some(issue_url),
+                    error: None,
+                },
+                BatchFileOutcome::Skipped { issue_number } => FileOutcome {
+                    file,
+                    outcome: "skipped",
+                    issue_number: Some(*issue_number),
+                    issue_url: None,
+                    error: None,
+                },
+                BatchFileOutcome::Failed(e) => FileOutcome {
+                    file,
+                    outcome: "failed",
+                    issue_number: None,
+                    issue_url: None,
+                    error: Some(e),
+                },
+            })
+            .collect();
+        Report {
+            milestone: &self.milestone,
+            files,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl BatchCreateReport {
+    pub fn failures(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, BatchFileOutcome::Failed(_)))
+            .count()
+    }
+}
+
+/// The files given on the command line followed by the files tracked at HEAD matching `patterns`
+///
+/// Patterns follow the CODEOWNERS syntax, e.g. `scripts/*.R` or `models/`. Files are listed once,
+/// in the order they were first given or matched. Matched files in `ignore` are left out, files
+/// given by name are kept.
+pub fn expand_file_patterns(
+    files: &[PathBuf],
+    patterns: &[String],
+    ignore: &IgnoreRules,
+    git_info: &impl GitFileOps,
+) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for file in files {
+        if !expanded.contains(file) {
+            expanded.push(file.clone());
+        }
+    }
+
+    if patterns.is_empty() {
+        return Ok(expanded);
+    }
+
+    let mut tracked = tracked_files(git_info, &[])?;
+    tracked.retain(|file| !ignore.is_ignored(file, false));
+    for pattern in patterns {
+        let Some(regex) = pattern_regex(pattern) else {
+            bail!("Unsupported file pattern '{pattern}'");
+        };
+        let matched = tracked
+            .iter()
+            .filter(|file| regex.is_match(&file.to_string_lossy().replace('\\', "/")))
+            .collect::<Vec<_>>();
+        if matched.is_empty() {
+            message!("⚠️  No tracked files match '{pattern}'");
+        }
+        for file in matched {
+            if !expanded.contains(file) {
+                expanded.push(file.clone());
+            }
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Create one issue per file in the same milestone, with the same checklist and assignees
+///
+/// The milestone, labels, assignees and current user are looked up once for the whole batch.
+/// A file failing validation or posting is reported without stopping the batch, and files which
+/// already have an open issue in the milestone are skipped. The milestone is only created once
+/// a file passes validation.
+pub async fn create_issue_batch(
+    options: &BatchCreateOptions,
+    milestones: &[Milestone],
+    configuration: &Configuration,
+    repo_users: &[RepoUser],
+    cache: Option<&DiskCache>,
+    git_info: &(
+         impl GitHubReader + GitHubWriter + GitHelpers + GitRepository + GitFileOps + GitCommitOps
+     ),
+) -> Result<BatchCreateReport> {
+    let mut report = BatchCreateReport {
+        milestone: options.milestone.clone(),
+        files: Vec::new(),
+    };
+    if options.files.is_empty() {
+        return Ok(report);
+    }
+    let checklist = options.checklist.clone().resolve(configuration)?;
+    let template = configuration.issue_template()?;
+
+    let assignees = options
+        .assignees
+        .iter()
+        .filter(|a| {
+            if repo_users.iter().any(|r| &r.login == *a) {
+                true
+            } else {
+                log::warn!("Login {a} is not a valid assignee");
+                false
+            }
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    validate_assignee_permissions(&assignees, options.require_write_access, cache, git_info)
+        .await?;
+
+    let mut milestone_number = milestones
+        .iter()
+        .find(|m| m.title == options.milestone)
+        .map(|m| m.number as u64);
+    // Files with an open issue in the milestone
+    let open_issues: HashMap<String, u64> = match milestone_number {
+        Some(number) => git_info
+            .get_issues(Some(number))
+            .await?
+            .into_iter()
+            .filter(|issue| matches!(issue.state, IssueState::Open))
+            .map(|issue| (issue.title, issue.number))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    let codeowners = CodeOwners::from_head(git_info).unwrap_or_else(|e| {
+        log::warn!("Could not read CODEOWNERS: {e}");
+        None
+    });
+    let head = git_info.resolve_commit("HEAD")?;
+    let commit = git_info.commit()?;
+    let branch = git_info.branch()?;
+    let configured_author = git_info.configured_author();
+    let current_user = git_info.get_current_user().await.ok().flatten();
+    let collaborator_override =
+        collaborator_override_for_policy(configuration.include_collaborators(), None);
+    let mut labels_created = false;
+
+    for file in &options.files {
+        if let Some(&issue_number) = open_issues.get(&file.display().to_string()) {
+            message!(
+                "⚠️  {} already has open issue #{issue_number} in '{}'. Skipping",
+                file.display(),
+                options.milestone
+            );
+            report
+                .files
+                .push((file.clone(), BatchFileOutcome::Skipped { issue_number }));
+            continue;
+        }
+
+        if let Err(e) = validate_file(
+            file,
+            &head,
+            &assignees,
+            codeowners.as_ref(),
+            configuration,
+            git_info,
+        ) {
+            report
+                .files
+                .push((file.clone(), BatchFileOutcome::Failed(e.to_string())));
+            continue;
+        }
+
+        let number = match milestone_number {
+            Some(number) => number,
+            None => {
+                let milestone = git_info
+                    .create_milestone(&options.milestone, &options.description)
+                    .await?;
+                log::debug!(
+                    "Created milestone '{}' with ID: {}",
+                    options.milestone,
+                    milestone.number
+                );
+                *milestone_number.insert(milestone.number as u64)
+            }
+        };
+        if !labels_created {
+            create_labels_if_needed(cache, Some(&branch), git_info).await?;
+            labels_created = true;
+        }
+
+        let authors = match git_info.authors(file) {
+            Ok(authors) => authors,
+            Err(e) => {
+                report
+                    .files
+                    .push((file.clone(), BatchFileOutcome::Failed(e.to_string())));
+                continue;
+            }
+        };
+        let (author, collaborators) = resolve_issue_people(
+            configured_author.as_ref(),
+            current_user.as_deref(),
+            &authors,
+            collaborator_override.clone(),
+        );
+        let issue = QCIssue::new_without_git(
+            file,
+            number,
+            commit.clone(),
+            branch.clone(),
+            author,
+            collaborators,
+            assignees.clone(),
+            checklist.clone(),
+            Vec::new(),
+        )
+        .with_template(template.clone());
+
+        let outcome = match issue.post_with_blocking(git_info).await {
+            Ok(result) => BatchFileOutcome::Created {
+                issue_number: result.issue_number,
+                issue_url: result.issue_url,
+            },
+            Err(e) => BatchFileOutcome::Failed(e.to_string()),
+        };
+        report.files.push((file.clone(), outcome));
+    }
+
+    Ok(report)
+}
+
+/// Check that the file exists at HEAD and that the assignees may QC it
+fn validate_file(
+    file: &Path,
+    head: &gix::ObjectId,
+    assignees: &[String],
+    codeowners: Option<&CodeOwners>,
+    configuration: &Configuration,
+    git_info: &impl GitFileOps,
+) -> Result<()> {
+    match git_info.file_bytes_at_commit(file, head) {
+        Ok(_) => {}
+        Err(GitFileOpsError::FileNotFoundAtCommit(_)) => {
+            bail!("file is not tracked at HEAD")
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let owners = codeowners.map_or(&[][..], |codeowners| codeowners.owners(file));
+    validate_owner_assignees(
+        file,
+        assignees,
+        owners,
+        configuration.exclude_owners_from_qc(),
+    )
+}
+
+impl fmt::Display for BatchCreateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.files.is_empty() {
+            return write!(f, "No files to create issues for");
+        }
+
+        let created = self
+            .files
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, BatchFileOutcome::Created { .. }))
+            .count();
+        let skipped = self
+            .files
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, BatchFileOutcome::Skipped { .. }))
+            .count();
+        writeln!(
+            f,
+            "📋 Batch create in '{}': {created} created, {skipped} skipped, {} failed",
+            self.milestone,
+            self.failures()
+        )?;
+
+        for (file, outcome) in &self.files {
+            match outcome {
+                BatchFileOutcome::Created { issue_url, .. } => {
+                    write!(f, "\n  ✅ {}: {issue_url}", file.display())?
+                }
+                BatchFileOutcome::Skipped { issue_number } => write!(
+                    f,
+                    "\n  ⏭️  {}: already has open issue #{issue_number}",
+                    file.display()
+                )?,
+                BatchFileOutcome::Failed(error) => write!(f, "\n  ❌ {}: {error}", file.display())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        git::MockGitFileOps,
+        test_utils::{FakeGit, create_test_issue, create_test_milestone, test_configuration},
+    };
+
+    /// Serves the issues of milestone `v1.0`, accepting every write
+    fn batch_git() -> FakeGit {
+        FakeGit::new()
+            .with_milestones(milestones())
+            .with_issues(vec![
+                create_test_issue("owner", "repo", 7, "src/done.R", "", Some(1), "closed"),
+                create_test_issue("owner", "repo", 8, "src/open.R", "", Some(1), "open"),
+            ])
+            .with_missing_file("src/missing.R")
+    }
+
+    fn milestones() -> Vec<Milestone> {
+        vec![create_test_milestone(
+            "owner", "repo", 1, "v1.0", None, "open",
+        )]
+    }
+
+    fn options(milestone: &str, files: &[&str]) -> BatchCreateOptions {
+        BatchCreateOptions {
+            milestone: milestone.to_string(),
+            description: None,
+            files: files.iter().map(PathBuf::from).collect(),
+            checklist: ChecklistArg::Name("Simple Tasks".to_string()),
+            assignees: vec!["reviewer1".to_string()],
+            require_write_access: false,
+        }
+    }
+
+    async fn create(git: &FakeGit, milestone: &str, files: &[&str]) -> BatchCreateReport {
+        let repo_users = vec![RepoUser {
+            login: "reviewer1".to_string(),
+            name: None,
+        }];
+        create_issue_batch(
+            &options(milestone, files),
+            &milestones(),
+            &test_configuration(),
+            &repo_users,
+            None,
+            git,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn created(issue_number: u64, file: &str) -> (PathBuf, BatchFileOutcome) {
+        (
+            PathBuf::from(file),
+            BatchFileOutcome::Created {
+                issue_number,
+                issue_url: format!("https://github.com/owner/repo/issues/{issue_number}"),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_no_files_writes_nothing() {
+        let git = batch_git();
+
+        let report = create(&git, "v2.0", &[]).await;
+
+        assert!(report.files.is_empty());
+        assert_eq!(report.to_string(), "No files to create issues for");
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_file_in_existing_milestone() {
+        let git = batch_git();
+
+        let report = create(&git, "v1.0", &["src/model.R"]).await;
+
+        assert_eq!(report.files, vec![created(101, "src/model.R")]);
+        assert_eq!(git.posted_issues(), vec![("src/model.R".to_string(), 1)]);
+        assert!(!git.writes().contains(&"create_milestone"));
+    }
+
+    #[tokio::test]
+    async fn test_files_share_one_new_milestone() {
+        let git = batch_git();
+
+        let report = create(&git, "v2.0", &["src/a.R", "src/b.R", "src/c.R"]).await;
+
+        assert_eq!(
+            report.files,
+            vec![
+                created(101, "src/a.R"),
+                created(102, "src/b.R"),
+                created(103, "src/c.R"),
+            ]
+        );
+        assert_eq!(report.failures(), 0);
+        assert!(
+            git.posted_issues()
+                .iter()
+                .all(|(_, milestone)| *milestone == 2)
+        );
+        let writes = git.writes();
+        assert_eq!(
+            writes.iter().filter(|w| **w == "create_milestone").count(),
+            1
+        );
+        // `ghqc` and the branch label, created once for the batch
+        assert_eq!(writes.iter().filter(|w| **w == "create_label").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_and_skipped_files_do_not_stop_the_batch() {
+        let git = batch_git();
+
+        let report = create(
+            &git,
+            "v1.0",
+            &["src/a.R", "src/missing.R", "src/open.R", "src/done.R"],
+        )
+        .await;
+
+        assert_eq!(
+            report.files,
+            vec![
+                created(101, "src/a.R"),
+                (
+                    PathBuf::from("src/missing.R"),
+                    BatchFileOutcome::Failed("file is not tracked at HEAD".to_string())
+                ),
+                (
+                    PathBuf::from("src/open.R"),
+                    BatchFileOutcome::Skipped { issue_number: 8 }
+                ),
+                // Only open issues are skipped
+                created(102, "src/done.R"),
+            ]
+        );
+        assert_eq!(report.failures(), 1);
+        assert_eq!(
+            report.to_string(),
+            "📋 Batch create in 'v1.0': 2 created, 1 skipped, 1 failed\n\n  \
+            ✅ src/a.R: https://github.com/owner/repo/issues/101\n  \
+            ❌ src/missing.R: file is not tracked at HEAD\n  \
+            ⏭️  src/open.R: already has open issue #8\n  \
+            ✅ src/done.R: https://github.com/owner/repo/issues/102"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_only_invalid_files_leave_milestone_uncreated() {
+        let git = batch_git();
+
+        let report = create(&git, "v2.0", &["src/missing.R"]).await;
+
+        assert_eq!(report.failures(), 1);
+        assert!(git.writes().is_empty());
+    }
+
+    #[test]
+    fn test_expand_file_patterns() {
+        let mut git_info = MockGitFileOps::new();
+        git_info
+            .expect_list_tree_entries()
+            .returning(|path| match path {
+                "" => Ok(vec![
+                    ("scripts".to_string(), true),
+                    ("README.md".to_string(), false),
+                ]),
+                "scripts" => Ok(vec![
+                    ("model.R".to_string(), false),
+                    ("plots.R".to_string(), false),
+                    ("notes.md".to_string(), false),
+                ]),
+                other => Err(GitFileOpsError::DirectoryNotFound(other.to_string())),
+            });
+
+        let files = expand_file_patterns(
+            &[PathBuf::from("scripts/plots.R")],
+            &["scripts/*.R".to_string(), "*.txt".to_string()],
+            &IgnoreRules::default(),
+            &git_info,
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("scripts/plots.R"),
+                PathBuf::from("scripts/model.R")
+            ]
+        );
+
+        // Ignored files are only left out of pattern matches
+        let files = expand_file_patterns(
+            &[PathBuf::from("scripts/plots.R")],
+            &["scripts/".to_string()],
+            &IgnoreRules::parse("*.R\n!model.R\n"),
+            &git_info,
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("scripts/plots.R"),
+                PathBuf::from("scripts/model.R"),
+                PathBuf::from("scripts/notes.md")
+            ]
+        );
+
+        assert!(
+            expand_file_patterns(
+                &[],
+                &["!scripts".to_string()],
+                &IgnoreRules::default(),
+                &git_info
+            )
+            .is_err()
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{Result, anyhow, bail};
 use clap::{Subcommand, ValueEnum};
 
-use crate::cache::cache_root;
+use crate::cache::{CacheRootSource, CacheSnapshotError, DiskCache, resolve_cache_root};
 use crate::git::GitInfo;
 use crate::git::GitRepository;
 use crate::utils::StdEnvProvider;
@@ -33,6 +33,20 @@ pub enum CacheCommands {
     },
     /// Show cache locations, sizes, and TTL settings
     Status,
+    /// Write the current repo's cache to a compressed snapshot (.tar.zst)
+    Export {
+        /// Path of the snapshot to write
+        path: PathBuf,
+    },
+    /// Merge a snapshot written by `ghqc cache export` into the current repo's cache
+    Import {
+        /// Path of the snapshot to read
+        path: PathBuf,
+
+        /// Import even if the snapshot was exported for another repo or cache schema version
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -45,6 +59,8 @@ pub enum CacheElement {
     Users,
     /// Repo labels.
     Labels,
+    /// Milestone and issue listings.
+    Listings,
 }
 
 impl CacheElement {
@@ -54,25 +70,73 @@ impl CacheElement {
             CacheElement::Issues => "issues",
             CacheElement::Users => "users",
             CacheElement::Labels => "labels",
+            CacheElement::Listings => "listings",
         }
     }
 }
 
-pub fn handle_cache(cmd: CacheCommands, directory: &Path) -> Result<()> {
+/// Run a `ghqc cache` subcommand on the cache root, which can be overridden by
+/// `GHQC_CACHE_DIR` or the `cache_directory` option (`configured_root`)
+pub fn handle_cache(
+    cmd: CacheCommands,
+    directory: &Path,
+    configured_root: Option<&Path>,
+) -> Result<()> {
+    let (root, source) = resolve_cache_root(&StdEnvProvider, configured_root)
+        .map_err(|e| anyhow!("failed to resolve cache root: {e}"))?;
     match cmd {
-        CacheCommands::Remove { element, global } => clear(element, global, directory),
-        CacheCommands::Dir { global } => dir(global, directory),
-        CacheCommands::Status => status(directory),
+        CacheCommands::Remove { element, global } => clear(&root, element, global, directory),
+        CacheCommands::Dir { global } => dir(&root, global, directory),
+        CacheCommands::Status => status(&root, source, directory),
+        CacheCommands::Export { path } => export(&root, &path, directory),
+        CacheCommands::Import { path, force } => import(&root, &path, force, directory),
     }
 }
 
-fn status(directory: &Path) -> Result<()> {
-    let root = cache_root().map_err(|e| anyhow!("failed to resolve cache root: {e}"))?;
+fn repo_cache(root: &Path, directory: &Path) -> Result<DiskCache> {
+    let (owner, repo) = resolve_repo(directory)?;
+    Ok(DiskCache::at_root(root.to_path_buf(), owner, repo))
+}
+
+fn export(root: &Path, path: &Path, directory: &Path) -> Result<()> {
+    let cache = repo_cache(root, directory)?;
+    let manifest = cache.export_snapshot(path)?;
 
+    println!(
+        "exported {} cache entr{} for {}/{} to {}",
+        manifest.entries,
+        if manifest.entries == 1 { "y" } else { "ies" },
+        manifest.owner,
+        manifest.repo,
+        path.display()
+    );
+    Ok(())
+}
+
+fn import(root: &Path, path: &Path, force: bool, directory: &Path) -> Result<()> {
+    let cache = repo_cache(root, directory)?;
+    let (manifest, summary) = cache.import_snapshot(path, force).map_err(|e| match e {
+        CacheSnapshotError::RepoMismatch { .. } | CacheSnapshotError::SchemaMismatch { .. } => {
+            anyhow!("{e}. Re-run with --force to import anyway")
+        }
+        e => e.into(),
+    })?;
+
+    println!(
+        "imported snapshot of {}/{} exported {}",
+        manifest.owner,
+        manifest.repo,
+        manifest.exported_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("{summary}");
+    Ok(())
+}
+
+fn status(root: &Path, source: CacheRootSource, directory: &Path) -> Result<()> {
     println!("{}", super::section_header("Cache"));
-    println!("root:     {}", root.display());
+    println!("root:     {} ({source})", root.display());
     if root.exists() {
-        let (size, files) = dir_stats(&root)?;
+        let (size, files) = dir_stats(root)?;
         println!(
             "size:     {} ({} file{})",
             format_bytes(size),
@@ -83,6 +147,7 @@ fn status(directory: &Path) -> Result<()> {
         println!("size:     (cache root does not exist yet)");
     }
     println!("ttl:      {}", ttl_description());
+    println!("listings: {}", listing_ttl_description());
 
     println!("{}", super::section_header("Repository"));
     match resolve_repo(directory) {
@@ -101,6 +166,7 @@ fn status(directory: &Path) -> Result<()> {
                     CacheElement::Issues,
                     CacheElement::Users,
                     CacheElement::Labels,
+                    CacheElement::Listings,
                 ] {
                     let p = repo_dir.join(elem.dir_name());
                     let (size, files) = if p.exists() { dir_stats(&p)? } else { (0, 0) };
@@ -171,10 +237,16 @@ fn ttl_description() -> String {
     }
 }
 
-fn dir(global: bool, directory: &Path) -> Result<()> {
-    let root = cache_root().map_err(|e| anyhow!("failed to resolve cache root: {e}"))?;
+fn listing_ttl_description() -> String {
+    match std::env::var("GHQC_LISTING_CACHE_TIMEOUT") {
+        Ok(v) => format!("{}s (from GHQC_LISTING_CACHE_TIMEOUT)", v),
+        Err(_) => "60s (default; override with GHQC_LISTING_CACHE_TIMEOUT)".to_string(),
+    }
+}
+
+fn dir(root: &Path, global: bool, directory: &Path) -> Result<()> {
     let path = if global {
-        root
+        root.to_path_buf()
     } else {
         let (owner, repo) = resolve_repo(directory)?;
         root.join(owner).join(repo)
@@ -183,12 +255,10 @@ fn dir(global: bool, directory: &Path) -> Result<()> {
     Ok(())
 }
 
-fn clear(element: Option<CacheElement>, global: bool, directory: &Path) -> Result<()> {
-    let root = cache_root().map_err(|e| anyhow!("failed to resolve cache root: {e}"))?;
-
+fn clear(root: &Path, element: Option<CacheElement>, global: bool, directory: &Path) -> Result<()> {
     let removed = match (global, element) {
-        (true, None) => remove_dir(&root)?.into_iter().collect::<Vec<_>>(),
-        (true, Some(f)) => clear_feature_global(&root, f)?,
+        (true, None) => remove_dir(root)?.into_iter().collect::<Vec<_>>(),
+        (true, Some(f)) => clear_feature_global(root, f)?,
         (false, None) => {
             let (owner, repo) = resolve_repo(directory)?;
             remove_dir(&root.join(&owner).join(&repo))?
@@ -0,0 +1,379 @@
+use std::time::Duration;
+
+use clap::{Subcommand, ValueEnum};
+use octocrab::models::IssueState;
+
+use crate::{Configuration, DiskCache, GitHubApiError, GitHubReader};
+
+/// How long completion candidates are served from the cache before GitHub is asked again
+const COMPLETION_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Subcommand)]
+pub enum CompleteCommands {
+    /// Titles of the open milestones
+    Milestones,
+    /// File paths of the open QC issues in a milestone
+    Files {
+        /// Milestone whose issues to list
+        #[arg(long)]
+        milestone: String,
+    },
+    /// Names of the checklists in the configuration
+    Checklists,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl From<CompletionShell> for clap_complete::Shell {
+    fn from(shell: CompletionShell) -> Self {
+        match shell {
+            CompletionShell::Bash => clap_complete::Shell::Bash,
+            CompletionShell::Zsh => clap_complete::Shell::Zsh,
+            CompletionShell::Fish => clap_complete::Shell::Fish,
+        }
+    }
+}
+
+/// Titles of the open milestones, newest first
+///
+/// Completion must never get in the way of typing, so errors only leave the list empty.
+pub async fn milestone_candidates(
+    git_info: &impl GitHubReader,
+    cache: Option<&DiskCache>,
+) -> Vec<String> {
+    open_milestones(git_info, cache)
+        .await
+        .map(|milestones| milestones.into_iter().map(|(title, _)| title).collect())
+        .unwrap_or_else(|e| {
+            log::debug!("No milestones to complete: {e}");
+            Vec::new()
+        })
+}
+
+/// File paths of the open QC issues in the open milestone titled `milestone`
+pub async fn file_candidates(
+    git_info: &impl GitHubReader,
+    cache: Option<&DiskCache>,
+    milestone: &str,
+) -> Vec<String> {
+    open_issue_files(git_info, cache, milestone)
+        .await
+        .unwrap_or_else(|e| {
+            log::debug!("No files to complete: {e}");
+            Vec::new()
+        })
+}
+
+/// Names of the checklists in the configuration, sorted
+pub fn checklist_candidates(configuration: &Configuration) -> Vec<String> {
+    let mut names: Vec<String> = configuration.checklists.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Shell functions feeding `--milestone`, `--file` and `--checklist-name` from
+/// `ghqc __complete`, appended to the static script clap generates for `shell`
+pub fn dynamic_completions(shell: CompletionShell) -> &'static str {
+    match shell {
+        CompletionShell::Bash => BASH_COMPLETIONS,
+        CompletionShell::Zsh => ZSH_COMPLETIONS,
+        CompletionShell::Fish => FISH_COMPLETIONS,
+    }
+}
+
+/// Open milestones as `(title, number)`, newest first
+async fn open_milestones(
+    git_info: &impl GitHubReader,
+    cache: Option<&DiskCache>,
+) -> Result<Vec<(String, u64)>, GitHubApiError> {
+    if let Some(milestones) = cache.and_then(|c| c.read(&["completions"], "milestones")) {
+        return Ok(milestones);
+    }
+
+    let mut milestones: Vec<_> = git_info
+        .get_milestones()
+        .await?
+        .into_iter()
+        .filter(|m| m.state.as_deref() == Some("open"))
+        .map(|m| (m.title, m.number as u64))
+        .collect();
+    milestones.sort_by_key(|milestone| std::cmp::Reverse(milestone.1));
+
+    if let Some(cache) = cache
+        && let Err(e) =
+            cache.write_with_ttl(&["completions"], "milestones", &milestones, COMPLETION_TTL)
+    {
+        log::debug!("Failed to cache milestone completions: {e}");
+    }
+
+    Ok(milestones)
+}
+
+async fn open_issue_files(
+    git_info: &impl GitHubReader,
+    cache: Option<&DiskCache>,
+    milestone: &str,
+) -> Result<Vec<String>, GitHubApiError> {
+    let Some((_, number)) = open_milestones(git_info, cache)
+        .await?
+        .into_iter()
+        .find(|(title, _)| title == milestone)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let key = number.to_string();
+    if let Some(files) = cache.and_then(|c| c.read(&["completions", "files"], &key)) {
+        return Ok(files);
+    }
+
+    let mut files: Vec<String> = git_info
+        .get_issues(Some(number))
+        .await?
+        .into_iter()
+        .filter(|issue| matches!(issue.state, IssueState::Open))
+        .map(|issue| issue.title)
+        .collect();
+    files.sort();
+
+    if let Some(cache) = cache
+        && let Err(e) =
+            cache.write_with_ttl(&["completions", "files"], &key, &files, COMPLETION_TTL)
+    {
+        log::debug!("Failed to cache file completions: {e}");
+    }
+
+    Ok(files)
+}
+
+const BASH_COMPLETIONS: &str = r#"
+_ghqc_candidates() {
+    local cur="$1"
+    shift
+    local IFS=$'\n'
+    COMPREPLY=($(compgen -W "$(ghqc __complete "$@" 2>/dev/null)" -- "${cur}"))
+    COMPREPLY=("${COMPREPLY[@]// /\\ }")
+}
+
+_ghqc_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local milestone="" creating="" i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            --milestone|-m) milestone="${COMP_WORDS[i+1]}" ;;
+            create|create-batch) creating=1 ;;
+        esac
+    done
+    milestone="${milestone#[\"\']}"
+    milestone="${milestone%[\"\']}"
+
+    case "${prev}" in
+        --milestone|-m)
+            _ghqc_candidates "${cur}" milestones
+            return 0
+            ;;
+        --checklist-name)
+            _ghqc_candidates "${cur}" checklists
+            return 0
+            ;;
+        -c)
+            if [[ -n "${creating}" ]]; then
+                _ghqc_candidates "${cur}" checklists
+                return 0
+            fi
+            ;;
+        --file|-f)
+            if [[ -z "${creating}" && -n "${milestone}" ]]; then
+                _ghqc_candidates "${cur}" files --milestone "${milestone}"
+                return 0
+            fi
+            ;;
+    esac
+    _ghqc "$@"
+}
+
+complete -F _ghqc_dynamic -o bashdefault -o default ghqc
+"#;
+
+const ZSH_COMPLETIONS: &str = r#"
+_ghqc_dynamic() {
+    local prev="${words[CURRENT-1]}" milestone="" i
+    local -a candidates
+    for ((i = 2; i < CURRENT - 1; i++)); do
+        if [[ "${words[i]}" == (--milestone|-m) ]]; then
+            milestone="${(Q)words[i+1]}"
+        fi
+    done
+
+    case "${prev}" in
+        --milestone|-m)
+            candidates=(${(f)"$(ghqc __complete milestones 2>/dev/null)"})
+            ;;
+        --checklist-name)
+            candidates=(${(f)"$(ghqc __complete checklists 2>/dev/null)"})
+            ;;
+        -c)
+            if (( ${words[(I)create]} || ${words[(I)create-batch]} )); then
+                candidates=(${(f)"$(ghqc __complete checklists 2>/dev/null)"})
+            fi
+            ;;
+        --file|-f)
+            if [[ -n "${milestone}" ]] && (( ! ${words[(I)create]} && ! ${words[(I)create-batch]} )); then
+                candidates=(${(f)"$(ghqc __complete files --milestone "${milestone}" 2>/dev/null)"})
+            fi
+            ;;
+    esac
+
+    if (( ${#candidates} )); then
+        compadd -a candidates
+        return
+    fi
+    _ghqc "$@"
+}
+
+compdef _ghqc_dynamic ghqc
+"#;
+
+const FISH_COMPLETIONS: &str = r#"
+function __fish_ghqc_complete_files
+    set -l tokens (commandline -opc)
+    contains -- create $tokens; or contains -- create-batch $tokens; and return
+    set -l index (contains -i -- --milestone $tokens; or contains -i -- -m $tokens)
+    test -n "$index"; or return
+    set -l value_index (math $index + 1)
+    set -q tokens[$value_index]; or return
+    ghqc __complete files --milestone $tokens[$value_index] 2>/dev/null
+end
+
+function __fish_ghqc_creating
+    set -l tokens (commandline -opc)
+    contains -- create $tokens; or contains -- create-batch $tokens
+end
+
+complete -c ghqc -s m -l milestone -f -a '(ghqc __complete milestones 2>/dev/null)'
+complete -c ghqc -l checklist-name -f -a '(ghqc __complete checklists 2>/dev/null)'
+complete -c ghqc -n __fish_ghqc_creating -s c -f -a '(ghqc __complete checklists 2>/dev/null)'
+complete -c ghqc -s f -l file -a '(__fish_ghqc_complete_files)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Checklist,
+        git::MockGitHubReader,
+        test_utils::{create_test_issue, create_test_milestone},
+    };
+
+    fn cache(dir: &tempfile::TempDir) -> DiskCache {
+        DiskCache::at_root(
+            dir.path().to_path_buf(),
+            "owner".to_string(),
+            "repo".to_string(),
+        )
+    }
+
+    fn reader_with_milestones() -> MockGitHubReader {
+        let mut reader = MockGitHubReader::new();
+        reader.expect_get_milestones().times(1).returning(|| {
+            Box::pin(async {
+                Ok(vec![
+                    create_test_milestone("owner", "repo", 1, "v1.0", None, "open"),
+                    create_test_milestone("owner", "repo", 2, "v1.1", None, "closed"),
+                    create_test_milestone("owner", "repo", 3, "v2.0 release", None, "open"),
+                ])
+            })
+        });
+        reader
+    }
+
+    #[tokio::test]
+    async fn test_milestone_candidates_lists_open_milestones_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(&dir);
+        let reader = reader_with_milestones();
+
+        let first = milestone_candidates(&reader, Some(&cache)).await;
+        assert_eq!(first, vec!["v2.0 release", "v1.0"]);
+
+        // Served from the cache; the mock only expects one call
+        let second = milestone_candidates(&reader, Some(&cache)).await;
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_file_candidates_lists_open_issues_of_milestone() {
+        let mut reader = reader_with_milestones();
+        reader
+            .expect_get_issues()
+            .withf(|milestone| *milestone == Some(1))
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok(vec![
+                        create_test_issue("owner", "repo", 4, "src/b.R", "", Some(1), "open"),
+                        create_test_issue("owner", "repo", 5, "src/c.R", "", Some(1), "closed"),
+                        create_test_issue("owner", "repo", 6, "src/a.R", "", Some(1), "open"),
+                    ])
+                })
+            });
+
+        let files = file_candidates(&reader, None, "v1.0").await;
+        assert_eq!(files, vec!["src/a.R", "src/b.R"]);
+    }
+
+    #[tokio::test]
+    async fn test_candidates_are_empty_when_provider_fails() {
+        let mut reader = MockGitHubReader::new();
+        reader
+            .expect_get_milestones()
+            .returning(|| Box::pin(async { Err(GitHubApiError::NoApi) }));
+
+        assert!(milestone_candidates(&reader, None).await.is_empty());
+        assert!(file_candidates(&reader, None, "v1.0").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_candidates_for_unknown_milestone() {
+        let reader = reader_with_milestones();
+
+        assert!(file_candidates(&reader, None, "v9.9").await.is_empty());
+    }
+
+    #[test]
+    fn test_checklist_candidates_are_sorted() {
+        let mut configuration = Configuration::default();
+        configuration
+            .checklists
+            .insert("Analysis".to_string(), Checklist::default());
+
+        assert_eq!(
+            checklist_candidates(&configuration),
+            vec!["Analysis", "Custom"]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_completions_call_complete_helpers() {
+        for shell in [
+            CompletionShell::Bash,
+            CompletionShell::Zsh,
+            CompletionShell::Fish,
+        ] {
+            let script = dynamic_completions(shell);
+            assert!(script.contains("ghqc __complete"));
+            for helper in ["milestones", "checklists", "files --milestone"] {
+                assert!(
+                    script.contains(helper),
+                    "{shell:?} script does not complete {helper}"
+                );
+            }
+        }
+    }
+}
@@ -1,63 +1,130 @@
 use anyhow::{Result, anyhow, bail};
-use inquire::{Confirm, Text, validator::Validation};
 use octocrab::models::{Milestone, issues::Issue};
 
 use std::path::{Path, PathBuf};
 
+use crate::message;
 use crate::{
-    Configuration, DiskCache, GitFileOps, GitHelpers, GitHubReader, GitHubWriter, GitInfo,
-    GitRepository, QCApprove, QCIssue, QCReview, QCUnapprove, RepoUser,
+    ApproveRequest, AssigneePermissionError, BehindFileReport, Checklist, ChecklistItemRef,
+    ChecklistUpdate, CodeOwner, CodeOwners, CommentRequest, Configuration, CreateIssueRequest,
+    DiffTarget, DiskCache, GitCommitOps, GitFileOps, GitHelpers, GitHubReader, GitHubWriter,
+    GitInfo, GitRepository, GitStatusOps, IssueDiff, NoDiff, OwnerQCPolicy, QCApprove, QCIssue,
+    QCReassign, QCReview, QCScope, QCUnapprove, RepoUser, check_assignee_permissions,
+    check_checklist_completion, check_owner_assignees,
     cli::file_parser::{IssueUrlArg, RelevantFileArg},
-    cli::interactive::{
-        RelevantFileClassType, prompt_add_another_relevant_file, prompt_assignees,
-        prompt_checklist, prompt_collaborators, prompt_commits, prompt_existing_milestone,
-        prompt_file, prompt_include_previous_qc_diff, prompt_issue, prompt_milestone, prompt_note,
-        prompt_relevant_description, prompt_relevant_file_class, prompt_relevant_file_path,
-        prompt_relevant_file_source, prompt_single_commit, prompt_want_relevant_files,
-    },
+    cli::interactive::{MilestoneStatus, PromptCancelled, Prompter, RelevantFileClassType},
+    cli::status::file_behind_report,
     comment::QCComment,
     create::{
         collaborator_override_for_policy, normalize_collaborator_entries, resolve_issue_people,
     },
-    issue::IssueThread,
-    relevant_files::{RelevantFile, RelevantFileClass},
+    current_assignees, get_all_issues_cached, get_git_status, get_milestone_issues_cached,
+    get_repo_users, get_user_permissions,
+    issue::{IssueCommit, IssueThread},
+    relevant_files::{RelevantFile, RelevantFileClass, prior_qc_issues},
+    stash_review_file, suggested_qcers,
+    utils::{IgnoreRules, normalize_repo_path, repo_path_string, same_repo_path},
 };
 
+/// Checklist for a new issue: one from the configuration repo, or a one-off local file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecklistArg {
+    Name(String),
+    Inline(PathBuf),
+}
+
+impl ChecklistArg {
+    pub(super) fn resolve(self, configuration: &Configuration) -> Result<Checklist> {
+        match self {
+            Self::Name(name) => configuration
+                .checklists
+                .get(&name)
+                .cloned()
+                .ok_or(anyhow!("No checklist named {name}")),
+            Self::Inline(path) => Ok(Checklist::from_inline_file(
+                &path,
+                configuration.prepended_checklist_note(),
+            )?),
+        }
+    }
+}
+
+/// How a new issue is built, shared by the interactive and argument-driven flows
+#[derive(Debug, Clone, Copy)]
+pub struct CreateOptions<'a> {
+    pub configuration: &'a Configuration,
+    pub cache: Option<&'a DiskCache>,
+    /// Fail instead of warning about assignees with read-only access
+    pub require_write_access: bool,
+    /// Build the issue without creating a missing milestone or asking for confirmation
+    pub dry_run: bool,
+    /// Link earlier QCs of the file as previous QCs
+    pub auto_previous: bool,
+    /// Create the issue even when the file already has an issue in the milestone
+    pub allow_duplicate: bool,
+}
+
 impl QCIssue {
+    /// Build a new issue from a request
+    ///
+    /// The flags of `request` are not read, those of `options` apply instead.
     pub async fn from_args(
-        milestone_name: String,
-        file: PathBuf,
-        checklist_name: String,
-        assignees: Option<Vec<String>>,
-        add_collaborator: Vec<String>,
-        remove_collaborator: Vec<String>,
-        description: Option<String>,
-        previous_qc: Vec<IssueUrlArg>,
-        gating_qc: Vec<IssueUrlArg>,
-        relevant_qc: Vec<IssueUrlArg>,
-        relevant_file: Vec<RelevantFileArg>,
+        request: CreateIssueRequest,
         milestones: Vec<Milestone>,
         repo_users: &[RepoUser],
-        configuration: Configuration,
-        git_info: &GitInfo,
+        options: CreateOptions<'_>,
+        git_info: &(
+             impl GitHubReader + GitHubWriter + GitHelpers + GitCommitOps + GitFileOps + GitRepository
+         ),
     ) -> Result<Self> {
-        let milestone = if let Some(m) = milestones.into_iter().find(|m| m.title == milestone_name)
-        {
-            log::debug!("Found existing milestone {}", m.number);
-            m
-        } else {
-            git_info
-                .create_milestone(&milestone_name, &description)
-                .await?
-        };
+        let CreateIssueRequest {
+            milestone: milestone_name,
+            file,
+            checklist,
+            assignees,
+            add_collaborators,
+            remove_collaborators,
+            description,
+            previous_qc,
+            gating_qc,
+            relevant_qc,
+            relevant_files: relevant_file_args,
+            ..
+        } = request;
+        let CreateOptions {
+            configuration,
+            cache,
+            require_write_access,
+            dry_run,
+            auto_previous,
+            allow_duplicate,
+        } = options;
+
+        // Before anything is written, so a broken inline checklist does not leave a new milestone
+        let checklist = checklist.resolve(configuration)?;
+        let template = configuration.issue_template()?;
+
+        let milestone_number =
+            if let Some(m) = milestones.into_iter().find(|m| m.title == milestone_name) {
+                log::debug!("Found existing milestone {}", m.number);
+                Some(m.number as u64)
+            } else if dry_run {
+                message!("📝 Milestone '{milestone_name}' does not exist and would be created");
+                None
+            } else {
+                Some(
+                    git_info
+                        .create_milestone(&milestone_name, &description)
+                        .await?
+                        .number as u64,
+                )
+            };
 
-        let milestone_issues = git_info.get_issues(Some(milestone.number as u64)).await?;
-        if milestone_issues
-            .iter()
-            .any(|i| i.title == file.display().to_string())
-        {
-            bail!("File already has a corresponding issue within the milestone");
-        }
+        // A milestone which is yet to be created has no issues
+        let milestone_issues = match milestone_number {
+            Some(number) => git_info.get_issues(Some(number)).await?,
+            None => Vec::new(),
+        };
 
         let assignees = if let Some(assignees_vec) = assignees {
             assignees_vec
@@ -74,29 +141,49 @@ impl QCIssue {
         } else {
             Vec::new()
         };
-
-        let checklist = configuration
-            .checklists
-            .get(&checklist_name)
-            .ok_or(anyhow!("No checklist named {checklist_name}"))?
-            .clone();
+        validate_assignee_permissions(&assignees, require_write_access, cache, git_info).await?;
+        let owners = file_owners(&file, git_info);
+        validate_owner_assignees(
+            &file,
+            &assignees,
+            &owners,
+            configuration.exclude_owners_from_qc(),
+        )?;
 
         // Validate and convert issue URL arguments to RelevantFile structs
-        let relevant_files = validate_and_convert_relevant_files(
+        let mut relevant_files = validate_and_convert_relevant_files(
             previous_qc,
             gating_qc,
             relevant_qc,
-            relevant_file,
+            relevant_file_args,
             git_info,
         )?;
+        if auto_previous {
+            for prior in
+                detect_previous_qcs(&file, milestone_number, &relevant_files, cache, git_info).await
+            {
+                message!(
+                    "🔗 Linking {} as a previous QC (--no-auto-previous to skip)",
+                    describe_prior_qc(&prior)
+                );
+                relevant_files.push(RelevantFile::detected_previous_qc(&prior));
+            }
+        }
+        check_duplicate_issue(
+            &file,
+            milestone_number,
+            &milestone_issues,
+            &relevant_files,
+            allow_duplicate,
+        )?;
 
         let authors = git_info.authors(&file)?;
         let configured_author = git_info.configured_author();
         let current_user = git_info.get_current_user().await?;
         let collaborator_additions =
-            normalize_collaborator_entries(&add_collaborator).map_err(anyhow::Error::msg)?;
+            normalize_collaborator_entries(&add_collaborators).map_err(anyhow::Error::msg)?;
         let collaborator_removals =
-            normalize_collaborator_entries(&remove_collaborator).map_err(anyhow::Error::msg)?;
+            normalize_collaborator_entries(&remove_collaborators).map_err(anyhow::Error::msg)?;
         let should_include_collaborators = configuration.include_collaborators()
             || !collaborator_additions.is_empty()
             || !collaborator_removals.is_empty();
@@ -120,7 +207,7 @@ impl QCIssue {
 
         let issue = QCIssue::new_without_git(
             &file,
-            milestone.number as u64,
+            milestone_number.unwrap_or_default(),
             git_info.commit()?,
             git_info.branch()?,
             author,
@@ -128,29 +215,83 @@ impl QCIssue {
             assignees,
             checklist,
             relevant_files,
-        );
+        )
+        .with_template(template);
 
         Ok(issue)
     }
 
+    /// Interactively build a new issue
+    ///
+    /// Nothing is written to GitHub until the user confirms the summary. A new milestone is only
+    /// created after that confirmation, so cancelling at any prompt leaves the repository untouched.
+    /// With `dry_run`, there is no confirmation and a new milestone is never created.
     pub async fn from_interactive(
-        project_dir: &PathBuf,
+        prompter: &impl Prompter,
+        project_dir: &Path,
+        ignore: &IgnoreRules,
         milestones: Vec<Milestone>,
-        configuration: Configuration,
-        git_info: &GitInfo,
         repo_users: &[RepoUser],
+        options: CreateOptions<'_>,
+        git_info: &(impl GitHubReader + GitHubWriter + GitCommitOps + GitFileOps + GitRepository),
     ) -> Result<Self> {
-        println!("🚀 Welcome to GHQC Interactive Mode!");
+        let CreateOptions {
+            configuration,
+            cache,
+            require_write_access,
+            dry_run,
+            auto_previous,
+            allow_duplicate,
+        } = options;
+
+        message!("🚀 Welcome to GHQC Interactive Mode!");
+        let template = configuration.issue_template()?;
 
         // Interactive prompts
-        let milestone_status = prompt_milestone(milestones)?;
-
-        let milestone = milestone_status.determine_milestone(git_info).await?;
-        let milestone_issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+        let milestone_status = prompter.milestone(milestones)?.value()?;
+
+        // A new milestone has no issues yet and is not created until the issue is confirmed
+        let (milestone_number, milestone_issues) = match &milestone_status {
+            MilestoneStatus::Existing(milestone) => (
+                Some(milestone.number as u64),
+                get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?,
+            ),
+            MilestoneStatus::New(..) => (None, Vec::new()),
+        };
 
-        let file = prompt_file(project_dir, &milestone_issues)?;
-        let checklist = prompt_checklist(&configuration)?;
-        let assignees = prompt_assignees(&repo_users)?;
+        let mut files = git_info.files_by_last_commit(&git_info.branch().ok())?;
+        files.retain(|(file, _)| !ignore.is_ignored(file, false));
+        // Files with an issue in the milestone are left out, unless duplicates are allowed
+        let taken_files = if allow_duplicate {
+            &[][..]
+        } else {
+            &milestone_issues[..]
+        };
+        let file = prompter.file(&files, taken_files)?.value()?;
+        let checklist = prompter.checklist(configuration)?.value()?;
+        let logins: Vec<String> = repo_users.iter().map(|u| u.login.clone()).collect();
+        let permissions = get_user_permissions(cache, git_info, &logins)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Could not fetch assignee permissions: {e}");
+                Vec::new()
+            });
+        let owners = file_owners(&file, git_info);
+        let suggested = if configuration.suggest_team_members() {
+            suggested_qcers(&owners, git_info).await
+        } else {
+            Vec::new()
+        };
+        let assignees = prompter
+            .assignees(repo_users, &permissions, &suggested)?
+            .value()?;
+        validate_assignee_permissions(&assignees, require_write_access, cache, git_info).await?;
+        validate_owner_assignees(
+            &file,
+            &assignees,
+            &owners,
+            configuration.exclude_owners_from_qc(),
+        )?;
         let authors = git_info.authors(&file)?;
         let configured_author = git_info.configured_author();
         let current_user = git_info.get_current_user().await?;
@@ -161,7 +302,7 @@ impl QCIssue {
             collaborator_override_for_policy(configuration.include_collaborators(), None),
         );
         let collaborators = if configuration.include_collaborators() {
-            prompt_collaborators(&default_collaborators)?
+            prompter.collaborators(&default_collaborators)?.value()?
         } else {
             Vec::new()
         };
@@ -173,38 +314,49 @@ impl QCIssue {
         );
 
         // Prompt for relevant files
-        let relevant_files = if prompt_want_relevant_files()? {
+        let mut relevant_files = if prompter.want_relevant_files()?.value()? {
             // Fetch all issues (need for matching file paths to issues)
-            let all_issues = git_info.get_issues(None).await?;
+            let all_issues = get_all_issues_cached(cache, git_info).await?;
 
             let mut relevant_files = Vec::new();
             loop {
-                let relevant_file_path = prompt_relevant_file_path(project_dir, &all_issues)?;
+                let relevant_file_path = prompter
+                    .relevant_file_path(project_dir, &all_issues)?
+                    .value()?;
 
                 // Find matching issues (where issue.title == file_path)
-                let matching_issues: Vec<_> = all_issues
+                let matching_issues: Vec<Issue> = all_issues
                     .iter()
-                    .filter(|i| i.title == relevant_file_path.display().to_string())
+                    .filter(|i| same_repo_path(Path::new(&i.title), &relevant_file_path))
+                    .cloned()
                     .collect();
 
                 let relevant_file = if matching_issues.is_empty() {
                     // No matching issues - must be File type with justification
-                    let justification =
-                        prompt_relevant_description(true)?.expect("justification required");
+                    let justification = prompter
+                        .relevant_description(true)?
+                        .value()?
+                        .expect("justification required");
                     RelevantFile {
                         file_name: relevant_file_path,
-                        class: RelevantFileClass::File { justification },
+                        class: RelevantFileClass::File {
+                            justification,
+                            commit: None,
+                        },
                     }
                 } else {
                     // Has matching issues - let user choose
-                    match prompt_relevant_file_source(
-                        &relevant_file_path,
-                        &matching_issues,
-                        milestone.number as u64,
-                    )? {
+                    match prompter
+                        .relevant_file_source(
+                            &relevant_file_path,
+                            &matching_issues,
+                            milestone_number.unwrap_or_default(),
+                        )?
+                        .value()?
+                    {
                         Some(issue) => {
-                            let class_type = prompt_relevant_file_class()?;
-                            let description = prompt_relevant_description(false)?;
+                            let class_type = prompter.relevant_file_class()?.value()?;
+                            let description = prompter.relevant_description(false)?.value()?;
                             // Extract issue.id for blocking relationships
                             let issue_id = Some(issue.id.0);
                             RelevantFile {
@@ -218,7 +370,8 @@ impl QCIssue {
                                         }
                                     }
                                     RelevantFileClassType::PreviousQC => {
-                                        let include_diff = prompt_include_previous_qc_diff()?;
+                                        let include_diff =
+                                            prompter.include_previous_qc_diff()?.value()?;
                                         RelevantFileClass::PreviousQC {
                                             issue_number: issue.number,
                                             issue_id,
@@ -237,11 +390,16 @@ impl QCIssue {
                         }
                         None => {
                             // User chose File
-                            let justification =
-                                prompt_relevant_description(true)?.expect("justification required");
+                            let justification = prompter
+                                .relevant_description(true)?
+                                .value()?
+                                .expect("justification required");
                             RelevantFile {
                                 file_name: relevant_file_path,
-                                class: RelevantFileClass::File { justification },
+                                class: RelevantFileClass::File {
+                                    justification,
+                                    commit: None,
+                                },
                             }
                         }
                     }
@@ -249,7 +407,7 @@ impl QCIssue {
 
                 relevant_files.push(relevant_file);
 
-                if !prompt_add_another_relevant_file()? {
+                if !prompter.add_another_relevant_file()?.value()? {
                     break;
                 }
             }
@@ -258,26 +416,57 @@ impl QCIssue {
             Vec::new()
         };
 
+        if auto_previous {
+            for prior in
+                detect_previous_qcs(&file, milestone_number, &relevant_files, cache, git_info).await
+            {
+                let message = format!("Link {} as a previous QC?", describe_prior_qc(&prior));
+                if prompter.confirm(&message, true)?.value()? {
+                    relevant_files.push(RelevantFile::detected_previous_qc(&prior));
+                }
+            }
+        }
+
         // Display summary
-        println!("\n✨ Creating issue with:");
-        println!("   📊 Milestone: {}", milestone_status);
-        println!("   📁 File: {}", file.display());
-        println!("   📋 Checklist: {}", checklist.name);
+        message!("\n✨ Creating issue with:");
+        message!("   📊 Milestone: {}", milestone_status);
+        message!("   📁 File: {}", file.display());
+        message!("   📋 Checklist: {}", checklist.name);
         if !assignees.is_empty() {
-            println!("   👥 Assignees: {}", assignees.join(", "));
+            message!("   👥 Assignees: {}", assignees.join(", "));
         }
         if !collaborators.is_empty() {
-            println!("   🤝 Collaborators: {}", collaborators.join(", "));
+            message!("   🤝 Collaborators: {}", collaborators.join(", "));
         }
         if !relevant_files.is_empty() {
-            println!("   🔗 Relevant files: {}", relevant_files.len());
+            message!("   🔗 Relevant files: {}", relevant_files.len());
+        }
+        message!();
+
+        // The file prompt used the cached listing, so look for an open issue created since
+        if let Some(number) = milestone_number
+            && !allow_duplicate
+        {
+            let open_issues: Vec<Issue> = git_info
+                .get_issues(Some(number))
+                .await?
+                .into_iter()
+                .filter(|i| i.state == octocrab::models::IssueState::Open)
+                .collect();
+            check_duplicate_issue(&file, milestone_number, &open_issues, &[], false)?;
         }
-        println!();
+
+        let milestone_number = if dry_run {
+            milestone_number
+        } else {
+            confirm_submission(prompter, "Create this issue?")?;
+            Some(milestone_status.determine_milestone(git_info).await?.number as u64)
+        };
 
         // Create the QCIssue
         let issue = QCIssue::new_without_git(
             &file,
-            milestone.number as u64,
+            milestone_number.unwrap_or_default(),
             git_info.commit()?,
             git_info.branch()?,
             author,
@@ -285,12 +474,206 @@ impl QCIssue {
             assignees,
             checklist,
             relevant_files,
-        );
+        )
+        .with_template(template);
 
         Ok(issue)
     }
 }
 
+/// Issues of `file` in the milestone numbered `milestone_number`, none for a milestone yet to be
+/// created
+fn existing_issues<'a>(
+    file: &Path,
+    milestone_number: Option<u64>,
+    milestone_issues: &'a [Issue],
+) -> Vec<&'a Issue> {
+    let Some(number) = milestone_number else {
+        return Vec::new();
+    };
+    milestone_issues
+        .iter()
+        .filter(|i| same_repo_path(Path::new(&i.title), file))
+        .filter(|i| i.milestone.as_ref().map(|m| m.number as u64) == Some(number))
+        .collect()
+}
+
+/// Refuse to create a second issue for `file` in its milestone
+///
+/// An open issue of the file always blocks. A closed one blocks unless it is linked as a previous
+/// QC, so that a file is only QCed twice in a milestone on purpose. With `allow_duplicate`
+/// neither blocks, and the existing issues are named in a warning.
+fn check_duplicate_issue(
+    file: &Path,
+    milestone_number: Option<u64>,
+    milestone_issues: &[Issue],
+    relevant_files: &[RelevantFile],
+    allow_duplicate: bool,
+) -> Result<()> {
+    let existing = existing_issues(file, milestone_number, milestone_issues);
+    if existing.is_empty() {
+        return Ok(());
+    }
+
+    if allow_duplicate {
+        let numbers: Vec<String> = existing.iter().map(|i| format!("#{}", i.number)).collect();
+        message!(
+            "⚠️  {} already has {} in the milestone, creating another issue (--allow-duplicate)",
+            file.display(),
+            numbers.join(", ")
+        );
+        return Ok(());
+    }
+
+    if let Some(open) = existing
+        .iter()
+        .find(|i| i.state == octocrab::models::IssueState::Open)
+    {
+        bail!(
+            "File '{}' already has an open issue in the milestone: {}\nPass --allow-duplicate to create another one",
+            file.display(),
+            open.html_url
+        );
+    }
+
+    let unlinked = existing.iter().find(|issue| {
+        !relevant_files.iter().any(|r| {
+            matches!(r.class, RelevantFileClass::PreviousQC { issue_number, .. } if issue_number == issue.number)
+        })
+    });
+    if let Some(closed) = unlinked {
+        bail!(
+            "File '{}' already has a closed issue in the milestone: {}\nTo QC the file again, link it as a previous QC with --previous-qc {}, or pass --allow-duplicate",
+            file.display(),
+            closed.html_url,
+            closed.html_url
+        );
+    }
+
+    Ok(())
+}
+
+/// Earlier QCs of `file` to link as previous QCs, newest first, leaving out the issues already
+/// in `relevant_files`
+///
+/// The issues of every milestone are listed once and cached. Failing to list them only skips
+/// the detection.
+async fn detect_previous_qcs(
+    file: &Path,
+    milestone_number: Option<u64>,
+    relevant_files: &[RelevantFile],
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Vec<Issue> {
+    let issues = match get_all_issues_cached(cache, git_info).await {
+        Ok(issues) => issues,
+        Err(e) => {
+            log::warn!("Could not look for previous QCs of {}: {e}", file.display());
+            return Vec::new();
+        }
+    };
+
+    prior_qc_issues(file, &issues, milestone_number)
+        .into_iter()
+        .filter(|i| {
+            !relevant_files
+                .iter()
+                .any(|r| r.issue_number() == Some(i.number))
+        })
+        .cloned()
+        .collect()
+}
+
+/// `#12 (milestone v1.0, closed 2025-01-31)`
+fn describe_prior_qc(issue: &Issue) -> String {
+    let mut details = Vec::new();
+    if let Some(milestone) = &issue.milestone {
+        details.push(format!("milestone {}", milestone.title));
+    }
+    if let Some(closed_at) = issue.closed_at {
+        details.push(format!("closed {}", closed_at.format("%Y-%m-%d")));
+    }
+    if details.is_empty() {
+        format!("#{}", issue.number)
+    } else {
+        format!("#{} ({})", issue.number, details.join(", "))
+    }
+}
+
+/// Final confirmation of an interactive flow. Declining cancels the flow like Esc or Ctrl-C
+fn confirm_submission(prompter: &impl Prompter, message: &str) -> Result<()> {
+    if prompter.confirm(message, true)?.value()? {
+        Ok(())
+    } else {
+        Err(PromptCancelled.into())
+    }
+}
+
+/// Warn about, or reject when required, assignees who will not be able to complete the QC
+pub(super) async fn validate_assignee_permissions(
+    assignees: &[String],
+    require_write_access: bool,
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<()> {
+    match check_assignee_permissions(assignees, require_write_access, cache, git_info).await {
+        Ok(insufficient) => {
+            for assignee in insufficient {
+                message!("⚠️  {assignee}");
+            }
+            Ok(())
+        }
+        Err(AssigneePermissionError::GitHubApiError(e)) if !require_write_access => {
+            log::warn!("Could not verify assignee permissions: {e}");
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The owners of `file` in the repository's CODEOWNERS, shown to the user when there are any
+fn file_owners(file: &Path, git_info: &impl GitFileOps) -> Vec<CodeOwner> {
+    let codeowners = match CodeOwners::from_head(git_info) {
+        Ok(Some(codeowners)) => codeowners,
+        Ok(None) => return Vec::new(),
+        Err(e) => {
+            log::warn!("Could not read CODEOWNERS: {e}");
+            return Vec::new();
+        }
+    };
+
+    let owners = codeowners.owners(file).to_vec();
+    if !owners.is_empty() {
+        message!(
+            "📋 {} is owned by {} (from {})",
+            file.display(),
+            owners
+                .iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            codeowners.path.display()
+        );
+    }
+    owners
+}
+
+/// Warn about, or reject when the policy blocks them, assignees who own the file in CODEOWNERS
+pub(super) fn validate_owner_assignees(
+    file: &Path,
+    assignees: &[String],
+    owners: &[CodeOwner],
+    policy: OwnerQCPolicy,
+) -> Result<()> {
+    for assignee in check_owner_assignees(file, assignees, owners, policy)? {
+        message!(
+            "⚠️  {assignee} owns {} in CODEOWNERS and may not be the right person to QC it",
+            file.display()
+        );
+    }
+    Ok(())
+}
+
 fn apply_collaborator_overrides(
     defaults: Vec<String>,
     additions: Vec<String>,
@@ -320,7 +703,7 @@ fn validate_and_convert_relevant_files(
     gating_qc: Vec<IssueUrlArg>,
     relevant_qc: Vec<IssueUrlArg>,
     relevant_file: Vec<RelevantFileArg>,
-    git_info: &GitInfo,
+    git_info: &(impl GitHelpers + GitFileOps),
 ) -> Result<Vec<RelevantFile>> {
     let mut result = Vec::new();
     let mut errors = Vec::new();
@@ -379,20 +762,11 @@ fn validate_and_convert_relevant_files(
         process_issue_arg(arg, relevant, "--relevant-qc");
     }
 
-    // Process relevant files (validate file exists in repository)
+    // Process relevant files (validate the file exists at the given commit or HEAD)
     for arg in relevant_file {
-        if !arg.file.exists() {
-            errors.push(format!(
-                "--relevant-file: File '{}' does not exist",
-                arg.file.display()
-            ));
-        } else {
-            result.push(RelevantFile {
-                file_name: arg.file,
-                class: RelevantFileClass::File {
-                    justification: arg.justification,
-                },
-            });
+        match arg.resolve(git_info) {
+            Ok(relevant) => result.push(relevant),
+            Err(e) => errors.push(format!("--relevant-file: {e}")),
         }
     }
 
@@ -405,62 +779,64 @@ fn validate_and_convert_relevant_files(
 }
 
 impl QCComment {
+    /// Build the comment of a request. Its environment is left empty
     pub async fn from_args(
-        milestone_name: String,
-        file: PathBuf,
-        current_commit: Option<String>,
-        previous_commit: Option<String>,
-        note: Option<String>,
+        request: CommentRequest,
         milestones: &[Milestone],
         cache: Option<&DiskCache>,
-        git_info: &GitInfo,
-        no_diff: bool,
+        git_info: &(impl GitHubReader + GitCommitOps + GitFileOps + GitRepository + GitStatusOps),
     ) -> Result<Self> {
+        let CommentRequest {
+            milestone: milestone_name,
+            file,
+            current_commit,
+            previous_commit,
+            note,
+            no_diff,
+            force_diff,
+            strict,
+            allow_branch_mismatch,
+            ..
+        } = request;
         let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
 
         // Create IssueThread to get commits from the issue's specific branch
         let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        check_branch(git_info, &issue_thread, allow_branch_mismatch)?;
         let commits = &issue_thread.commits;
 
         if commits.is_empty() {
             return Err(anyhow!("No commits found for file: {}", file.display()));
         }
 
-        let final_current_commit =
-            match current_commit {
-                Some(commit_str) => commits
-                    .iter()
-                    .find(|c| c.hash.to_string().contains(&commit_str))
-                    .ok_or(anyhow!(
-                        "Provided commit does not correspond to any commits which edited this file"
-                    ))?
-                    .hash,
-                None => {
-                    // Default to most recent commit for this file (first in chronological order)
-                    commits[0].hash
-                }
-            };
-
-        let final_previous_commit = match previous_commit {
+        let final_current_commit = match current_commit {
             Some(commit_str) => {
-                // Parse the provided commit string into ObjectId
-                Some(
-                    commits
-                        .iter()
-                        .find(|c| c.hash.to_string().contains(&commit_str))
-                        .ok_or(anyhow!("Provided commit does not correspond to any commits which edited this file"))?
-                        .hash
-                )
+                let commit = resolve_issue_commit(commits, &issue_thread.branch, &commit_str)?;
+                check_commit_touches_file(commit, &file, strict)?;
+                commit.hash
             }
             None => {
-                // Default to second most recent commit if it exists
-                if commits.len() > 1 {
-                    Some(commits[1].hash)
-                } else {
-                    None // Only one commit exists for this file
-                }
+                // Default to most recent commit for this file (first in chronological order)
+                commits[0].hash
+            }
+        };
+
+        let final_previous_commit = match previous_commit {
+            Some(commit_str) => {
+                let commit = resolve_issue_commit(commits, &issue_thread.branch, &commit_str)?;
+                check_commit_touches_file(commit, &file, strict)?;
+                Some(commit.hash)
             }
+            // Default to the most recent QC activity before the current commit
+            None => issue_thread.previous_qc_commit(&final_current_commit),
         };
+        check_commit_on_branch(git_info, &final_current_commit, &issue_thread)?;
+
+        warn_unpulled_file_changes(git_info, &file);
+
+        let renames = final_previous_commit
+            .map(|previous| issue_thread.renames_between(&previous, &final_current_commit))
+            .unwrap_or_default();
 
         Ok(Self {
             issue: issue,
@@ -469,61 +845,76 @@ impl QCComment {
             previous_commit: final_previous_commit,
             note,
             no_diff,
+            force_diff,
+            excel_diff: None,
+            renames,
+            environment: Vec::new(),
         })
     }
 
     pub async fn from_interactive(
+        prompter: &impl Prompter,
         milestones: &[Milestone],
         cache: Option<&DiskCache>,
         git_info: &GitInfo,
     ) -> Result<Self> {
-        println!("💬 Welcome to GHQC Comment Mode!");
+        message!("💬 Welcome to GHQC Comment Mode!");
 
         // Select milestone (existing only)
-        let milestone = prompt_existing_milestone(milestones)?;
+        let milestone = prompter.existing_milestone(milestones)?.value()?;
 
         // Get issues for this milestone
-        let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
 
         // Select issue by title
-        let issue = prompt_issue(&issues)?;
+        let issue = prompter.issue(&issues)?.value()?;
 
         // Extract file path from issue - we need to determine which file this issue is about
-        let file_path = PathBuf::from(&issue.title);
+        let file_path = normalize_repo_path(Path::new(&issue.title));
+
+        // Make sure the user knows if their checkout is missing remote changes to the file
+        confirm_unpulled_file_changes(prompter, git_info, &file_path)?;
 
         // Create IssueThread to get commits from the issue's specific branch
         let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        confirm_branch(prompter, git_info, &issue_thread)?;
         // Select commits for comparison with status annotations
-        let (current_commit, previous_commit) = prompt_commits(&issue_thread)?;
+        let (current_commit, previous_commit) = prompter.commits(&issue_thread)?.value()?;
+        check_commit_on_branch(git_info, &current_commit, &issue_thread)?;
 
         // Prompt for optional note
-        let note = prompt_note()?;
+        let note = prompter.note()?.value()?;
 
         // Ask if user wants diff in comment (default is yes/include diff)
-        let include_diff = Confirm::new("📊 Include commit diff in comment?")
-            .with_default(true)
-            .prompt()
-            .map_err(|e| anyhow!("Prompt cancelled: {}", e))?;
+        let include_diff = prompter
+            .confirm("📊 Include commit diff in comment?", true)?
+            .value()?;
 
         // Display summary
-        println!("\n✨ Creating comment with:");
-        println!("   🎯 Milestone: {}", milestone.title);
-        println!("   🎫 Issue: #{} - {}", issue.number, issue.title);
-        println!("   📁 File: {}", file_path.display());
-        println!("   📝 Current commit: {}", current_commit);
+        message!("\n✨ Creating comment with:");
+        message!("   🎯 Milestone: {}", milestone.title);
+        message!("   🎫 Issue: #{} - {}", issue.number, issue.title);
+        message!("   📁 File: {}", file_path.display());
+        message!("   📝 Current commit: {}", current_commit);
         if let Some(prev) = &previous_commit {
-            println!("   📝 Previous commit: {}", prev);
+            message!("   📝 Previous commit: {}", prev);
         } else {
-            println!("   📝 Previous commit: None (first commit for this file)");
+            message!("   📝 Previous commit: None (first commit for this file)");
         }
         if let Some(ref n) = note {
-            println!("   💬 Note: {}", n);
+            message!("   💬 Note: {}", n);
         }
-        println!(
+        message!(
             "   📊 Include diff: {}",
             if include_diff { "Yes" } else { "No" }
         );
-        println!();
+        message!();
+
+        confirm_submission(prompter, "Post this comment?")?;
+
+        let renames = previous_commit
+            .map(|previous| issue_thread.renames_between(&previous, &current_commit))
+            .unwrap_or_default();
 
         Ok(Self {
             issue,
@@ -531,24 +922,30 @@ impl QCComment {
             current_commit,
             previous_commit,
             note,
-            no_diff: !include_diff,
+            no_diff: (!include_diff).then_some(NoDiff::Stats),
+            force_diff: false,
+            excel_diff: None,
+            renames,
+            environment: Vec::new(),
         })
     }
 }
 
 impl QCApprove {
     pub async fn from_interactive(
+        prompter: &impl Prompter,
         milestones: &[Milestone],
         cache: Option<&DiskCache>,
         git_info: &GitInfo,
+        require_complete_checklist: bool,
     ) -> Result<Self> {
-        println!("✅ Welcome to GHQC Approve Mode!");
+        message!("✅ Welcome to GHQC Approve Mode!");
 
         // Select milestone (existing only)
-        let milestone = prompt_existing_milestone(milestones)?;
+        let milestone = prompter.existing_milestone(milestones)?.value()?;
 
         // Get issues for this milestone
-        let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
 
         // Filter to only show open issues (since we can only approve open issues)
         let open_issues: Vec<_> = issues
@@ -564,13 +961,25 @@ impl QCApprove {
         }
 
         // Select issue by title
-        let issue = prompt_issue(&open_issues)?;
+        let issue = prompter.issue(&open_issues)?.value()?;
+
+        let incomplete_checklist = check_checklist_completion(&issue, require_complete_checklist)?;
+        if let Some(incomplete) = &incomplete_checklist {
+            message!("⚠️  {incomplete}");
+            let proceed = prompter
+                .confirm("Approve with unchecked checklist items?", false)?
+                .value()?;
+            if !proceed {
+                return Err(PromptCancelled.into());
+            }
+        }
 
         // Extract file path from issue - we need to determine which file this issue is about
-        let file_path = PathBuf::from(&issue.title);
+        let file_path = normalize_repo_path(Path::new(&issue.title));
 
         // Create IssueThread to get commits from the issue's specific branch
         let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        confirm_branch(prompter, git_info, &issue_thread)?;
         let commits = &issue_thread.commits;
 
         if commits.is_empty() {
@@ -586,49 +995,76 @@ impl QCApprove {
             .position(|c| c.hash == latest.hash)
             .unwrap_or(0);
 
-        let approved_commit = prompt_single_commit(
-            &issue_thread,
-            "📝 Select commit to approve (press Enter for latest):",
-            default_position,
-        )?;
+        let approved_commit = prompter
+            .single_commit(
+                &issue_thread,
+                "📝 Select commit to approve (press Enter for latest):",
+                default_position,
+            )?
+            .value()?;
+        check_commit_on_branch(git_info, &approved_commit, &issue_thread)?;
 
         // Prompt for optional note
-        let note = prompt_note()?;
+        let note = prompter.note()?.value()?;
 
         // Display summary
-        println!("\n✨ Creating approval with:");
-        println!("   🎯 Milestone: {}", milestone.title);
-        println!("   🎫 Issue: #{} - {}", issue.number, issue.title);
-        println!("   📁 File: {}", file_path.display());
-        println!("   📝 Commit: {}", approved_commit);
+        message!("\n✨ Creating approval with:");
+        message!("   🎯 Milestone: {}", milestone.title);
+        message!("   🎫 Issue: #{} - {}", issue.number, issue.title);
+        message!("   📁 File: {}", file_path.display());
+        message!("   📝 Commit: {}", approved_commit);
         if let Some(ref n) = note {
-            println!("   💬 Note: {}", n);
+            message!("   💬 Note: {}", n);
+        }
+        if let Some(ref incomplete) = incomplete_checklist {
+            message!(
+                "   ⚠️  Checklist: {} unchecked item(s) ({})",
+                incomplete.unchecked_count(),
+                incomplete.section_summary()
+            );
         }
-        println!();
+        message!();
+
+        confirm_submission(prompter, "Approve and close this issue?")?;
 
         Ok(Self {
             file: file_path,
             commit: approved_commit,
             issue,
             note,
+            effective_date: None,
+            scope: QCScope::from_thread(&issue_thread, &approved_commit),
+            incomplete_checklist,
         })
     }
 
+    /// Build the approval of a request, refusing an incomplete checklist when
+    /// `require_complete_checklist`
     pub async fn from_args(
-        milestone_name: String,
-        file: PathBuf,
-        approve_commit: Option<String>,
-        note: Option<String>,
+        request: ApproveRequest,
         milestones: &[Milestone],
+        require_complete_checklist: bool,
         cache: Option<&DiskCache>,
-        git_info: &GitInfo,
+        git_info: &(impl GitHubReader + GitCommitOps + GitFileOps + GitRepository),
     ) -> Result<Self> {
+        let ApproveRequest {
+            milestone: milestone_name,
+            file,
+            commit: approve_commit,
+            note,
+            effective_date,
+            no_scope_summary,
+            allow_branch_mismatch,
+            ..
+        } = request;
         let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
         if issue.state == octocrab::models::IssueState::Closed {
             bail!("")
         }
+        let incomplete_checklist = check_checklist_completion(&issue, require_complete_checklist)?;
 
         let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        check_branch(git_info, &issue_thread, allow_branch_mismatch)?;
         let commits = &issue_thread.commits;
 
         if commits.is_empty() {
@@ -649,127 +1085,371 @@ impl QCApprove {
                     .hash,
                 None => commits[0].hash,
             };
+        check_commit_on_branch(git_info, &approved_commit, &issue_thread)?;
+
+        let scope = if no_scope_summary {
+            None
+        } else {
+            QCScope::from_thread(&issue_thread, &approved_commit)
+        };
 
         Ok(Self {
             file,
             commit: approved_commit,
             issue,
             note,
+            effective_date,
+            scope,
+            incomplete_checklist,
         })
     }
 }
 
-impl QCUnapprove {
-    pub async fn from_interactive(milestones: &[Milestone], git_info: &GitInfo) -> Result<Self> {
-        println!("🚫 Welcome to GHQC Unapprove Mode!");
+impl QCReassign {
+    pub async fn from_interactive(
+        prompter: &impl Prompter,
+        milestones: &[Milestone],
+        require_write_access: bool,
+        cache: Option<&DiskCache>,
+        git_info: &impl GitHubReader,
+    ) -> Result<Self> {
+        message!("👥 Welcome to GHQC Reassign Mode!");
 
         // Select milestone (existing only)
-        let milestone = prompt_existing_milestone(milestones)?;
-
-        // Get issues for this milestone
-        let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
-        log::debug!(
-            "Found {} total issues in milestone '{}'",
-            issues.len(),
-            milestone.title
-        );
+        let milestone = prompter.existing_milestone(milestones)?.value()?;
 
-        // Filter to only show closed issues (since we can only unapprove closed issues)
-        let closed_issues: Vec<_> = issues
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+        let open_issues: Vec<_> = issues
             .into_iter()
-            .filter(|issue| {
-                let is_closed = matches!(issue.state, octocrab::models::IssueState::Closed);
-                log::debug!(
-                    "Issue #{}: '{}' (state: {:?}) -> closed: {}",
-                    issue.number,
-                    issue.title,
-                    issue.state,
-                    is_closed
-                );
-                is_closed
-            })
+            .filter(|issue| matches!(issue.state, octocrab::models::IssueState::Open))
             .collect();
 
-        log::debug!(
-            "Found {} closed issues after filtering",
-            closed_issues.len()
-        );
-
-        if closed_issues.is_empty() {
+        if open_issues.is_empty() {
             bail!(
-                "No closed issues found in milestone '{}' to unapprove",
+                "No open issues found in milestone '{}' to reassign",
                 milestone.title
             );
         }
 
-        // Select issue by title
-        let issue = prompt_issue(&closed_issues)?;
+        let issue = prompter.issue(&open_issues)?.value()?;
 
-        // Prompt for reason
-        let reason_input = Text::new("📝 Enter reason for unapproval:")
-            .with_validator(|input: &str| {
-                if input.trim().is_empty() {
-                    Ok(Validation::Invalid("Reason cannot be empty".into()))
-                } else {
-                    Ok(Validation::Valid)
-                }
-            })
-            .prompt()
-            .map_err(|e| anyhow!("Input cancelled: {}", e))?;
+        let repo_users = get_repo_users(cache, git_info).await?;
+        let old_assignees = current_assignees(&issue);
+        let new_assignees = prompter.reassign(&repo_users, &old_assignees)?.value()?;
+        let added: Vec<_> = new_assignees
+            .iter()
+            .filter(|login| !old_assignees.contains(login))
+            .cloned()
+            .collect();
+        validate_assignee_permissions(&added, require_write_access, cache, git_info).await?;
+
+        let note = prompter.note()?.value()?;
 
-        let reason = reason_input.trim().to_string();
+        let reassign = Self {
+            issue,
+            old_assignees,
+            new_assignees,
+            note,
+        };
 
         // Display summary
-        println!("\n✨ Creating unapproval with:");
-        println!("   🎯 Milestone: {}", milestone.title);
-        println!("   🎫 Issue: #{} - {}", issue.number, issue.title);
-        println!("   🚫 Reason: {}", reason);
-        println!();
+        message!("\n✨ Reassigning with:");
+        message!("   🎯 Milestone: {}", milestone.title);
+        message!(
+            "   🎫 Issue: #{} - {}",
+            reassign.issue.number,
+            reassign.issue.title
+        );
+        message!("   👥 Assignees: {}", reassign.new_assignees.join(", "));
+        if let Some(ref n) = reassign.note {
+            message!("   💬 Note: {}", n);
+        }
+        message!();
+
+        confirm_submission(prompter, "Reassign this issue?")?;
 
-        Ok(Self { issue, reason })
+        Ok(reassign)
     }
 
     pub async fn from_args(
-        milestone_name: String,
-        file: PathBuf,
-        reason: String,
+        settings: ReassignSettings,
         milestones: &[Milestone],
-        git_info: &GitInfo,
+        require_write_access: bool,
+        cache: Option<&DiskCache>,
+        git_info: &impl GitHubReader,
     ) -> Result<Self> {
-        let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
-        if issue.state == octocrab::models::IssueState::Closed {
-            bail!(
-                "No closed issue found for file '{}' in milestone '{milestone_name}'",
-                file.display()
-            )
-        }
+        let issue = find_issue(&settings.milestone, &settings.file, milestones, git_info).await?;
+        validate_assignee_permissions(
+            &settings.add_assignee,
+            require_write_access,
+            cache,
+            git_info,
+        )
+        .await?;
 
-        Ok(Self { issue, reason })
+        Ok(Self::new(
+            issue,
+            &settings.add_assignee,
+            &settings.remove_assignee,
+            settings.note,
+        ))
     }
 }
 
-impl QCReview {
+/// Reassignment of an issue given on the command line
+#[derive(Debug, Clone)]
+pub struct ReassignSettings {
+    pub milestone: String,
+    pub file: PathBuf,
+    pub add_assignee: Vec<String>,
+    pub remove_assignee: Vec<String>,
+    pub note: Option<String>,
+}
+
+impl ChecklistUpdate {
     pub async fn from_interactive(
-        milestones: Vec<Milestone>,
+        prompter: &impl Prompter,
+        milestones: &[Milestone],
         cache: Option<&DiskCache>,
-        git_info: &GitInfo,
+        git_info: &impl GitHubReader,
     ) -> Result<Self> {
-        println!("📝 Welcome to GHQC Review Mode!");
+        message!("☑️ Welcome to GHQC Checklist Mode!");
 
         // Select milestone (existing only)
-        let milestone = prompt_existing_milestone(&milestones)?;
+        let milestone = prompter.existing_milestone(milestones)?.value()?;
 
-        // Get issues for this milestone
-        let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+        let open_issues: Vec<_> = issues
+            .into_iter()
+            .filter(|issue| matches!(issue.state, octocrab::models::IssueState::Open))
+            .collect();
 
-        // Select issue by title
-        let issue = prompt_issue(&issues)?;
+        if open_issues.is_empty() {
+            bail!("No open issues found in milestone '{}'", milestone.title);
+        }
 
-        // Extract file path from issue - we need to determine which file this issue is about
-        let file_path = PathBuf::from(&issue.title);
+        let issue = prompter.issue(&open_issues)?.value()?;
+        let items = crate::issue_checklist_items(&issue)?;
+        let checked = prompter.checklist_items(&items)?.value()?;
+        let update = Self::from_selection(issue, &checked)?;
 
-        // Create IssueThread to get QC-tracked commits for status/metadata
-        let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        let changes = update.changes();
+        if changes.is_empty() {
+            bail!("No checklist items changed");
+        }
+
+        // Display summary
+        message!("\n✨ Updating checklist of #{}:", update.issue.number);
+        for (item, checked) in changes {
+            let mark = if checked { "x" } else { " " };
+            message!("   [{mark}] {}. {}", item.index, item.text);
+        }
+        message!();
+
+        confirm_submission(prompter, "Update this checklist?")?;
+
+        Ok(update)
+    }
+
+    pub async fn from_args(
+        milestone_name: String,
+        file: PathBuf,
+        check: Vec<ChecklistItemRef>,
+        uncheck: Vec<ChecklistItemRef>,
+        milestones: &[Milestone],
+        git_info: &impl GitHubReader,
+    ) -> Result<Self> {
+        let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
+        Ok(Self::new(issue, &check, &uncheck)?)
+    }
+}
+
+/// Ends of an `issue diff`, as full or short SHAs of the issue's commits
+#[derive(Debug, Clone, Default)]
+pub struct IssueDiffRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Diff to the uncommitted file instead of `to`
+    pub working_tree: bool,
+}
+
+impl IssueDiff {
+    /// Diff `file` between two commits of its issue
+    ///
+    /// `from` defaults to the approved commit or the latest QC activity, `to` to the latest
+    /// commit which modified the file, or the working tree when `working_tree` is set.
+    pub async fn from_args(
+        milestone_name: String,
+        file: PathBuf,
+        range: IssueDiffRange,
+        milestones: &[Milestone],
+        cache: Option<&DiskCache>,
+        git_info: &GitInfo,
+    ) -> Result<Self> {
+        let IssueDiffRange {
+            from,
+            to,
+            working_tree,
+        } = range;
+        let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
+        let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        let commits = &issue_thread.commits;
+
+        if commits.is_empty() {
+            return Err(anyhow!("No commits found for file: {}", file.display()));
+        }
+
+        let from = match from {
+            Some(commit_str) => {
+                resolve_issue_commit(commits, &issue_thread.branch, &commit_str)?.hash
+            }
+            None => issue_thread.latest_approved_or_activity().hash,
+        };
+
+        let to = match to {
+            _ if working_tree => DiffTarget::WorkingTree(git_info.path().to_path_buf()),
+            Some(commit_str) => DiffTarget::Commit(
+                resolve_issue_commit(commits, &issue_thread.branch, &commit_str)?.hash,
+            ),
+            None => DiffTarget::Commit(
+                issue_thread
+                    .file_commits()
+                    .first()
+                    .map(|commit| **commit)
+                    .unwrap_or(commits[0].hash),
+            ),
+        };
+
+        Ok(Self { file, from, to })
+    }
+}
+
+impl QCUnapprove {
+    pub async fn from_interactive(
+        prompter: &impl Prompter,
+        milestones: &[Milestone],
+        cache: Option<&DiskCache>,
+        git_info: &(impl GitHubReader + GitCommitOps),
+    ) -> Result<Self> {
+        message!("🚫 Welcome to GHQC Unapprove Mode!");
+
+        // Select milestone (existing only)
+        let milestone = prompter.existing_milestone(milestones)?.value()?;
+
+        // Get issues for this milestone
+        let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+        log::debug!(
+            "Found {} total issues in milestone '{}'",
+            issues.len(),
+            milestone.title
+        );
+
+        // Filter to only show closed issues (since we can only unapprove closed issues)
+        let closed_issues: Vec<_> = issues
+            .into_iter()
+            .filter(|issue| {
+                let is_closed = matches!(issue.state, octocrab::models::IssueState::Closed);
+                log::debug!(
+                    "Issue #{}: '{}' (state: {:?}) -> closed: {}",
+                    issue.number,
+                    issue.title,
+                    issue.state,
+                    is_closed
+                );
+                is_closed
+            })
+            .collect();
+
+        log::debug!(
+            "Found {} closed issues after filtering",
+            closed_issues.len()
+        );
+
+        if closed_issues.is_empty() {
+            bail!(
+                "No closed issues found in milestone '{}' to unapprove",
+                milestone.title
+            );
+        }
+
+        // Select issue by title
+        let issue = prompter.issue(&closed_issues)?.value()?;
+
+        // Prompt for reason
+        let reason = prompter.unapprove_reason()?.value()?;
+
+        // Record the commits the unapproval is made at
+        let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        let unapproval = Self::new(issue, &issue_thread, reason);
+
+        // Display summary
+        message!("\n✨ Creating unapproval with:");
+        message!("   🎯 Milestone: {}", milestone.title);
+        message!(
+            "   🎫 Issue: #{} - {}",
+            unapproval.issue.number,
+            unapproval.issue.title
+        );
+        message!("   🚫 Reason: {}", unapproval.reason);
+        message!("   📝 Unapproved at commit: {}", unapproval.commit);
+        if let Some(approved) = &unapproval.previously_approved {
+            message!("   ✅ Previously approved commit: {}", approved);
+        }
+        message!();
+
+        confirm_submission(prompter, "Reopen this issue?")?;
+
+        Ok(unapproval)
+    }
+
+    pub async fn from_args(
+        milestone_name: String,
+        file: PathBuf,
+        reason: String,
+        milestones: &[Milestone],
+        cache: Option<&DiskCache>,
+        git_info: &GitInfo,
+    ) -> Result<Self> {
+        let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
+        if issue.state == octocrab::models::IssueState::Closed {
+            bail!(
+                "No closed issue found for file '{}' in milestone '{milestone_name}'",
+                file.display()
+            )
+        }
+
+        let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        Ok(Self::new(issue, &issue_thread, reason))
+    }
+}
+
+impl QCReview {
+    pub async fn from_interactive(
+        prompter: &impl Prompter,
+        milestones: Vec<Milestone>,
+        cache: Option<&DiskCache>,
+        git_info: &GitInfo,
+    ) -> Result<Self> {
+        message!("📝 Welcome to GHQC Review Mode!");
+
+        // Select milestone (existing only)
+        let milestone = prompter.existing_milestone(&milestones)?.value()?;
+
+        // Get issues for this milestone
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+
+        // Select issue by title
+        let issue = prompter.issue(&issues)?.value()?;
+
+        // Extract file path from issue - we need to determine which file this issue is about
+        let file_path = normalize_repo_path(Path::new(&issue.title));
+
+        // Make sure the user knows if their checkout is missing remote changes to the file
+        confirm_unpulled_file_changes(prompter, git_info, &file_path)?;
+
+        // Create IssueThread to get QC-tracked commits for status/metadata
+        let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        confirm_branch(prompter, git_info, &issue_thread)?;
 
         if issue_thread.commits.is_empty() {
             return Err(anyhow!(
@@ -811,37 +1491,45 @@ impl QCReview {
             }
         };
 
-        let commit_hash = prompt_single_commit(
-            &issue_thread,
-            "📝 Select commit to compare against working directory:",
-            default_position,
-        )?;
-
-        let note = prompt_note()?;
-        let no_diff = !inquire::Confirm::new("Include diff between commit and working directory?")
-            .with_default(true)
-            .prompt()?;
-        let stash_after_review =
-            inquire::Confirm::new("Stash local changes for this file after posting review?")
-                .with_default(true)
-                .prompt()?;
-
-        println!();
-        println!("📝 QC Review Summary:");
-        println!("   📁 File: {}", file_path.display());
-        println!("   🏷️  Issue: #{} - {}", issue.number, issue.title);
-        println!("   📋 Milestone: {}", milestone.title);
-        println!("   🔗 Comparing against commit: {}", commit_hash);
+        let commit_hash = prompter
+            .single_commit(
+                &issue_thread,
+                "📝 Select commit to compare against working directory:",
+                default_position,
+            )?
+            .value()?;
+        check_commit_on_branch(git_info, &commit_hash, &issue_thread)?;
+
+        let note = prompter.note()?.value()?;
+        let no_diff = (!prompter
+            .confirm("Include diff between commit and working directory?", true)?
+            .value()?)
+        .then_some(NoDiff::Stats);
+        let stash_after_review = prompter
+            .confirm(
+                "Stash local changes for this file after posting review?",
+                true,
+            )?
+            .value()?;
+
+        message!();
+        message!("📝 QC Review Summary:");
+        message!("   📁 File: {}", file_path.display());
+        message!("   🏷️  Issue: #{} - {}", issue.number, issue.title);
+        message!("   📋 Milestone: {}", milestone.title);
+        message!("   🔗 Comparing against commit: {}", commit_hash);
         if let Some(note) = &note {
-            println!("   📝 Note: {}", note);
+            message!("   📝 Note: {}", note);
         }
-        if no_diff {
-            println!("   ⚠️  Diff generation disabled");
+        if no_diff.is_some() {
+            message!("   ⚠️  Diff generation disabled");
         }
         if !stash_after_review {
-            println!("   📦 Auto-stash disabled");
+            message!("   📦 Auto-stash disabled");
         }
-        println!();
+        message!();
+
+        confirm_submission(prompter, "Post this review?")?;
 
         Ok(Self {
             file: file_path,
@@ -849,43 +1537,53 @@ impl QCReview {
             commit: commit_hash,
             note,
             no_diff,
+            force_diff: false,
             stash_after_review,
             working_dir: git_info.repository_path.clone(),
+            excel_diff: None,
         })
     }
 
     pub async fn from_args(
         milestone_name: String,
         file: PathBuf,
-        commit: Option<String>,
-        note: Option<String>,
+        settings: &ReviewSettings,
         milestones: &[Milestone],
         cache: Option<&DiskCache>,
         git_info: &GitInfo,
-        no_diff: bool,
-        stash_after_review: bool,
     ) -> Result<Self> {
         let issue = find_issue(&milestone_name, &file, milestones, git_info).await?;
+        Self::for_issue(issue, file, settings, cache, git_info).await
+    }
 
+    /// Review `file` against its open `issue`
+    pub async fn for_issue(
+        issue: Issue,
+        file: PathBuf,
+        settings: &ReviewSettings,
+        cache: Option<&DiskCache>,
+        git_info: &(impl GitHubReader + GitCommitOps + GitRepository + GitStatusOps),
+    ) -> Result<Self> {
         // Create IssueThread to get commits from the issue's specific branch
         let issue_thread = IssueThread::from_issue(&issue, cache, git_info).await?;
+        check_branch(git_info, &issue_thread, settings.allow_branch_mismatch)?;
 
         if issue_thread.commits.is_empty() {
             return Err(anyhow!("No commits found for file: {}", file.display()));
         }
 
-        let final_commit = match commit {
+        let final_commit = match &settings.commit {
             Some(commit_str) => {
                 // Try to find the commit in the file's history first
                 issue_thread
                     .commits
                     .iter()
-                    .find(|c| c.hash.to_string().contains(&commit_str))
+                    .find(|c| c.hash.to_string().contains(commit_str.as_str()))
                     .map(|c| c.hash)
                     .unwrap_or_else(|| {
                         // If not found in file history, try to parse as ObjectId
                         use std::str::FromStr;
-                        gix::ObjectId::from_str(&commit_str).unwrap_or_else(|_| {
+                        gix::ObjectId::from_str(commit_str).unwrap_or_else(|_| {
                             log::warn!(
                                 "Could not parse commit '{}', using fallback logic",
                                 commit_str
@@ -901,14 +1599,19 @@ impl QCReview {
             }
         };
 
+        check_commit_on_branch(git_info, &final_commit, &issue_thread)?;
+        warn_unpulled_file_changes(git_info, &file);
+
         Ok(Self {
             file,
             issue,
             commit: final_commit,
-            note,
-            no_diff,
-            stash_after_review,
-            working_dir: git_info.repository_path.clone(),
+            note: settings.note.clone(),
+            no_diff: settings.no_diff,
+            force_diff: settings.force_diff,
+            stash_after_review: settings.stash_after_review,
+            working_dir: git_info.path().to_path_buf(),
+            excel_diff: None,
         })
     }
 
@@ -916,7 +1619,10 @@ impl QCReview {
     /// 1. HEAD commit from repository
     /// 2. Latest commit from issue thread
     /// 3. Most recent file commit (position 0)
-    fn get_default_commit(git_info: &GitInfo, issue_thread: &IssueThread) -> gix::ObjectId {
+    fn get_default_commit(
+        git_info: &impl GitRepository,
+        issue_thread: &IssueThread,
+    ) -> gix::ObjectId {
         // Try HEAD commit from repository
         if let Ok(head_str) = git_info.commit() {
             if let Ok(head_oid) = std::str::FromStr::from_str(&head_str) {
@@ -929,6 +1635,203 @@ impl QCReview {
     }
 }
 
+/// Remote commits which modify `file` but have not been pulled into the local checkout
+fn unpulled_file_changes(
+    git_info: &(impl GitCommitOps + GitRepository + GitStatusOps),
+    file: &Path,
+) -> Option<BehindFileReport> {
+    let git_status = match get_git_status(git_info) {
+        Ok(status) => status,
+        Err(e) => {
+            log::debug!("Skipping remote change check: {e}");
+            return None;
+        }
+    };
+    file_behind_report(git_info, &git_status, file).filter(|report| report.affects_file())
+}
+
+/// Minimum length of a short SHA given for a commit
+const MIN_SHORT_SHA_LEN: usize = 7;
+
+/// Find the commit of the issue's `branch` that `commit`, a full or short SHA, refers to
+fn resolve_issue_commit<'a>(
+    commits: &'a [IssueCommit],
+    branch: &str,
+    commit: &str,
+) -> Result<&'a IssueCommit> {
+    let commit = commit.trim().to_lowercase();
+    if commit.len() < MIN_SHORT_SHA_LEN || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!(
+            "'{commit}' is not a commit SHA: give the full SHA or at least its first {MIN_SHORT_SHA_LEN} characters"
+        );
+    }
+
+    let matches: Vec<&IssueCommit> = commits
+        .iter()
+        .filter(|c| c.hash.to_string().starts_with(&commit))
+        .collect();
+    match matches.as_slice() {
+        [found] => Ok(found),
+        [] => bail!(
+            "Commit {commit} was not found on branch '{branch}' since the start of QC for this issue"
+        ),
+        _ => bail!(
+            "Commit {commit} is ambiguous on branch '{branch}', it could be any of: {}",
+            matches
+                .iter()
+                .map(|c| c.hash.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Warn that `commit` did not modify `file`, so its diff may be empty, or fail when `strict`
+fn check_commit_touches_file(commit: &IssueCommit, file: &Path, strict: bool) -> Result<()> {
+    if commit.file_changed {
+        return Ok(());
+    }
+
+    let short = &commit.hash.to_string()[..MIN_SHORT_SHA_LEN];
+    if strict {
+        bail!("Commit {short} did not modify {}", file.display());
+    }
+    message!(
+        "⚠️  Commit {short} did not modify {}, so the diff may be empty or misleading",
+        file.display()
+    );
+    Ok(())
+}
+
+fn warn_unpulled_file_changes(
+    git_info: &(impl GitCommitOps + GitRepository + GitStatusOps),
+    file: &Path,
+) {
+    if let Some(report) = unpulled_file_changes(git_info, file) {
+        message!("⚠️  Local checkout is {report}");
+        message!("   Diffs against your working copy will not include these changes");
+    }
+}
+
+fn confirm_unpulled_file_changes(
+    prompter: &impl Prompter,
+    git_info: &GitInfo,
+    file: &Path,
+) -> Result<()> {
+    let Some(report) = unpulled_file_changes(git_info, file) else {
+        return Ok(());
+    };
+
+    message!("⚠️  Local checkout is {report}");
+    message!("   Diffs against your working copy will not include these changes");
+    let proceed = prompter
+        .confirm("Continue without pulling?", false)?
+        .value()?;
+    if !proceed {
+        bail!(
+            "Pull the remote changes to {} and try again",
+            file.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// The checked-out branch, when it is not the issue's QC branch
+///
+/// Issues whose branch was deleted, e.g. after being merged, cannot be checked out and are not
+/// compared.
+fn branch_mismatch(git_info: &impl GitRepository, issue_thread: &IssueThread) -> Option<String> {
+    if issue_thread.branch_deleted || issue_thread.branch.is_empty() {
+        return None;
+    }
+    let current = git_info.branch().ok()?;
+    (current != issue_thread.branch).then_some(current)
+}
+
+fn branch_mismatch_message(current: &str, issue_thread: &IssueThread) -> String {
+    format!(
+        "Checked-out branch '{current}' is not the QC branch '{}' of issue #{}",
+        issue_thread.branch, issue_thread.issue_number
+    )
+}
+
+/// Fail when the checked-out branch is not the issue's QC branch, unless `allow_mismatch`
+fn check_branch(
+    git_info: &impl GitRepository,
+    issue_thread: &IssueThread,
+    allow_mismatch: bool,
+) -> Result<()> {
+    let Some(current) = branch_mismatch(git_info, issue_thread) else {
+        return Ok(());
+    };
+    let message = branch_mismatch_message(&current, issue_thread);
+    if !allow_mismatch {
+        bail!(
+            "{message}. Check out '{}' or pass --allow-branch-mismatch",
+            issue_thread.branch
+        );
+    }
+    message!("⚠️  {message}");
+    Ok(())
+}
+
+fn confirm_branch(
+    prompter: &impl Prompter,
+    git_info: &impl GitRepository,
+    issue_thread: &IssueThread,
+) -> Result<()> {
+    let Some(current) = branch_mismatch(git_info, issue_thread) else {
+        return Ok(());
+    };
+
+    message!("⚠️  {}", branch_mismatch_message(&current, issue_thread));
+    let proceed = prompter
+        .confirm(&format!("Continue on branch '{current}'?"), false)?
+        .value()?;
+    if !proceed {
+        bail!("Check out '{}' and try again", issue_thread.branch);
+    }
+
+    Ok(())
+}
+
+/// Fail when `commit` is not reachable from the issue's QC branch, locally or on a remote
+///
+/// Commits whose branches cannot be determined are let through.
+fn check_commit_on_branch(
+    git_info: &impl GitCommitOps,
+    commit: &gix::ObjectId,
+    issue_thread: &IssueThread,
+) -> Result<()> {
+    if issue_thread.branch_deleted || issue_thread.branch.is_empty() {
+        return Ok(());
+    }
+    let branches = match git_info.get_branches_containing_commit(commit) {
+        Ok(branches) if !branches.is_empty() => branches,
+        Ok(_) => return Ok(()),
+        Err(e) => {
+            log::debug!("Skipping branch check of commit {commit}: {e}");
+            return Ok(());
+        }
+    };
+
+    let branch = &issue_thread.branch;
+    let remote_suffix = format!("/{branch}");
+    if branches
+        .iter()
+        .any(|b| b == branch || b.ends_with(&remote_suffix))
+    {
+        return Ok(());
+    }
+    bail!(
+        "Commit {} is not on the QC branch '{branch}' of issue #{}, only on: {}",
+        &commit.to_string()[..8],
+        issue_thread.issue_number,
+        branches.join(", ")
+    )
+}
+
 pub async fn find_issue(
     milestone_name: &str,
     file: impl AsRef<Path>,
@@ -943,14 +1846,1141 @@ pub async fn find_issue(
     let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
 
     let file_str = file.as_ref().to_string_lossy();
-    let issue = issues
-        .into_iter()
-        .find(|issue| {
-            issue.title.contains(file_str.as_ref())
-                && matches!(issue.state, octocrab::models::IssueState::Open)
-        })
+    let issue = open_issue_for_file(&issues, file.as_ref())
+        .cloned()
         .ok_or(anyhow!(
             "No open issue found for file '{file_str}' in milestone '{milestone_name}'"
         ))?;
     Ok(issue)
 }
+
+/// The open issue of `file` among `issues`
+///
+/// Titles are compared as normalized repository paths, so issues created on Windows with `\`
+/// separators match. Issues titled exactly `file` are preferred over those only containing it.
+/// Among several open issues, e.g. duplicates created with `--allow-duplicate`, the most recently
+/// updated one is used and all candidates are named in a warning.
+fn open_issue_for_file<'a>(issues: &'a [Issue], file: &Path) -> Option<&'a Issue> {
+    let file_str = repo_path_string(file);
+    let title_path = |issue: &Issue| repo_path_string(Path::new(&issue.title));
+    let mut candidates: Vec<&Issue> = issues
+        .iter()
+        .filter(|issue| {
+            title_path(issue).contains(&file_str)
+                && matches!(issue.state, octocrab::models::IssueState::Open)
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        (title_path(b) == file_str)
+            .cmp(&(title_path(a) == file_str))
+            .then(b.updated_at.cmp(&a.updated_at))
+            .then(b.number.cmp(&a.number))
+    });
+
+    let chosen = candidates.first().copied()?;
+    if candidates.len() > 1 {
+        let numbers: Vec<String> = candidates
+            .iter()
+            .map(|issue| format!("#{} ({})", issue.number, issue.title))
+            .collect();
+        message!(
+            "⚠️  {} open issues match {file_str}: {}. Using #{}",
+            candidates.len(),
+            numbers.join(", "),
+            chosen.number
+        );
+    }
+    Some(chosen)
+}
+
+/// Options shared by every review posted by one `issue review`
+#[derive(Debug, Clone, Default)]
+pub struct ReviewSettings {
+    /// Commit to compare against, defaulting to HEAD
+    pub commit: Option<String>,
+    pub note: Option<String>,
+    pub no_diff: Option<NoDiff>,
+    pub force_diff: bool,
+    pub stash_after_review: bool,
+    /// Review even when the checked-out branch is not the issue's QC branch
+    pub allow_branch_mismatch: bool,
+    /// Directory of the repository to write highlighted diff workbooks of Excel files to
+    pub excel_diff_dir: Option<PathBuf>,
+}
+
+/// Files to review in one invocation, split by whether they have an open issue in the milestone
+#[derive(Debug, Default)]
+pub struct ReviewTargets {
+    /// Files with an open issue, in the order they were given
+    pub matched: Vec<(PathBuf, Issue)>,
+    /// Files without an open issue, which are not reviewed
+    pub skipped: Vec<PathBuf>,
+}
+
+impl ReviewTargets {
+    pub async fn find(
+        milestone_name: &str,
+        files: Vec<PathBuf>,
+        milestones: &[Milestone],
+        cache: Option<&DiskCache>,
+        git_info: &impl GitHubReader,
+    ) -> Result<Self> {
+        let milestone = milestones
+            .iter()
+            .find(|m| m.title == milestone_name)
+            .ok_or(anyhow!("Milestone '{}' not found", milestone_name))?;
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+
+        let mut targets = Self::default();
+        for file in files {
+            if targets.matched.iter().any(|(f, _)| *f == file) || targets.skipped.contains(&file) {
+                continue;
+            }
+            match open_issue_for_file(&issues, &file) {
+                Some(issue) => targets.matched.push((file, issue.clone())),
+                None => targets.skipped.push(file),
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Build the review of every matched file with the same `settings`
+    pub async fn reviews(
+        self,
+        settings: &ReviewSettings,
+        cache: Option<&DiskCache>,
+        git_info: &(impl GitHubReader + GitCommitOps + GitRepository + GitStatusOps),
+    ) -> Vec<(PathBuf, Result<QCReview>)> {
+        let mut reviews = Vec::with_capacity(self.matched.len());
+        for (file, issue) in self.matched {
+            let review = QCReview::for_issue(issue, file.clone(), settings, cache, git_info).await;
+            reviews.push((file, review));
+        }
+        reviews
+    }
+
+    /// Post a review of every matched file and stash it, collecting failures instead of stopping
+    pub async fn post(
+        mut self,
+        settings: &ReviewSettings,
+        cache: Option<&DiskCache>,
+        git_info: &(
+             impl GitHubReader + GitHubWriter + GitCommitOps + GitRepository + GitStatusOps + GitFileOps
+         ),
+    ) -> ReviewBatchReport {
+        let mut report = ReviewBatchReport {
+            skipped: std::mem::take(&mut self.skipped),
+            ..Default::default()
+        };
+        for (file, review) in self.reviews(settings, cache, git_info).await {
+            let mut review = match review {
+                Ok(review) => review,
+                Err(e) => {
+                    report.failed.push((file, e));
+                    continue;
+                }
+            };
+            if let Some(dir) = &settings.excel_diff_dir
+                && let Err(e) = review.write_excel_diff(git_info, dir)
+            {
+                log::warn!(
+                    "Could not write the diff workbook of {}: {e}",
+                    file.display()
+                );
+            }
+            match git_info.post_comment(&review).await {
+                Ok(url) => {
+                    let stash = stash_review_file(
+                        git_info,
+                        review.issue.number,
+                        &review.file,
+                        review.stash_after_review,
+                    );
+                    report.posted.push(PostedReview {
+                        file,
+                        url,
+                        stash_message: stash.message,
+                    });
+                }
+                Err(e) => report.failed.push((file, e.into())),
+            }
+        }
+        report
+    }
+}
+
+/// A review comment posted as part of a multi-file review
+#[derive(Debug, serde::Serialize)]
+pub struct PostedReview {
+    pub file: PathBuf,
+    pub url: String,
+    pub stash_message: Option<String>,
+}
+
+/// Outcome of reviewing several files, where one failure does not stop the others
+#[derive(Debug, Default)]
+pub struct ReviewBatchReport {
+    pub posted: Vec<PostedReview>,
+    /// Files without an open issue in the milestone
+    pub skipped: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, anyhow::Error)>,
+}
+
+impl serde::Serialize for ReviewBatchReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ReviewBatchReport", 3)?;
+        state.serialize_field("posted", &self.posted)?;
+        state.serialize_field("skipped", &self.skipped)?;
+        state.serialize_field(
+            "failed",
+            &self
+                .failed
+                .iter()
+                .map(|(file, e)| (file, format!("{e:#}")))
+                .collect::<std::collections::BTreeMap<_, _>>(),
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Checklist, Permission,
+        cli::interactive::{MockPrompter, PromptOutcome},
+        test_utils::FakeGit,
+    };
+    use gix::ObjectId;
+    use std::str::FromStr;
+    use std::sync::Mutex;
+
+    /// Tracks `scripts/model.R` and fails every write
+    fn recording_git() -> FakeGit {
+        FakeGit::new()
+            .with_tracked_file("scripts/model.R")
+            .with_failing_writes()
+    }
+
+    fn load_milestone(name: &str) -> Milestone {
+        let text = std::fs::read_to_string(format!("src/tests/github_api/milestones/{name}.json"))
+            .unwrap_or_else(|_| panic!("Failed to load milestone fixture: {name}"));
+        serde_json::from_str(&text).expect("Failed to parse milestone fixture")
+    }
+
+    fn checklist() -> Checklist {
+        Checklist::new(
+            "Test Checklist".to_string(),
+            None,
+            "- [ ] Test item".to_string(),
+        )
+    }
+
+    /// Options of a test issue, with previous QCs detected and write access not required
+    fn create_options(
+        configuration: &Configuration,
+        dry_run: bool,
+        allow_duplicate: bool,
+    ) -> CreateOptions<'_> {
+        CreateOptions {
+            configuration,
+            cache: None,
+            require_write_access: false,
+            dry_run,
+            auto_previous: true,
+            allow_duplicate,
+        }
+    }
+
+    async fn create_issue(prompter: &MockPrompter, git: &FakeGit) -> Result<QCIssue> {
+        create_issue_with_configuration(prompter, git, Configuration::default()).await
+    }
+
+    async fn create_issue_with_configuration(
+        prompter: &MockPrompter,
+        git: &FakeGit,
+        configuration: Configuration,
+    ) -> Result<QCIssue> {
+        QCIssue::from_interactive(
+            prompter,
+            &PathBuf::from("."),
+            &IgnoreRules::default(),
+            vec![load_milestone("v1.0")],
+            &[],
+            create_options(&configuration, false, false),
+            git,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_issue_cancelled_at_first_prompt_writes_nothing() {
+        let mut prompter = MockPrompter::new();
+        prompter
+            .expect_milestone()
+            .returning(|_| Ok(PromptOutcome::Cancelled));
+        let git = recording_git();
+
+        let err = create_issue(&prompter, &git).await.unwrap_err();
+
+        assert!(err.is::<PromptCancelled>());
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_issue_cancelled_mid_flow_writes_nothing() {
+        let mut prompter = MockPrompter::new();
+        prompter.expect_milestone().returning(|_| {
+            Ok(PromptOutcome::Value(MilestoneStatus::Existing(
+                load_milestone("v1.0"),
+            )))
+        });
+        prompter
+            .expect_file()
+            .returning(|_, _| Ok(PromptOutcome::Value(PathBuf::from("scripts/model.R"))));
+        prompter
+            .expect_checklist()
+            .returning(|_| Ok(PromptOutcome::Cancelled));
+        prompter.expect_assignees().never();
+        let git = recording_git();
+
+        let err = create_issue(&prompter, &git).await.unwrap_err();
+
+        assert!(err.is::<PromptCancelled>());
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_issue_file_prompt_skips_ignored_files() {
+        let mut prompter = MockPrompter::new();
+        prompter.expect_milestone().returning(|_| {
+            Ok(PromptOutcome::Value(MilestoneStatus::Existing(
+                load_milestone("v1.0"),
+            )))
+        });
+        prompter
+            .expect_file()
+            .withf(|files, _| files.is_empty())
+            .times(1)
+            .returning(|_, _| Ok(PromptOutcome::Cancelled));
+        let git = recording_git();
+
+        let err = QCIssue::from_interactive(
+            &prompter,
+            &PathBuf::from("."),
+            &IgnoreRules::parse("scripts/\n"),
+            vec![load_milestone("v1.0")],
+            &[],
+            create_options(&Configuration::default(), false, false),
+            &git,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.is::<PromptCancelled>());
+    }
+
+    #[tokio::test]
+    async fn test_issue_declined_confirmation_does_not_create_milestone() {
+        let mut prompter = MockPrompter::new();
+        prompter.expect_milestone().returning(|_| {
+            Ok(PromptOutcome::Value(MilestoneStatus::New(
+                "v3.0".to_string(),
+                None,
+            )))
+        });
+        prompter
+            .expect_file()
+            .returning(|_, _| Ok(PromptOutcome::Value(PathBuf::from("scripts/model.R"))));
+        prompter
+            .expect_checklist()
+            .returning(|_| Ok(PromptOutcome::Value(checklist())));
+        prompter
+            .expect_assignees()
+            .returning(|_, _, _| Ok(PromptOutcome::Value(vec!["reviewer1".to_string()])));
+        prompter
+            .expect_collaborators()
+            .returning(|_| Ok(PromptOutcome::Value(Vec::new())));
+        prompter
+            .expect_want_relevant_files()
+            .returning(|| Ok(PromptOutcome::Value(false)));
+        prompter
+            .expect_confirm()
+            .withf(|message, _| message == "Create this issue?")
+            .times(1)
+            .returning(|_, _| Ok(PromptOutcome::Value(false)));
+        let git = recording_git();
+
+        let err = create_issue(&prompter, &git).await.unwrap_err();
+
+        assert!(err.is::<PromptCancelled>());
+        assert!(git.writes().is_empty());
+    }
+
+    /// A prompter creating an issue for `scripts/model.R` assigned to `reviewer1`
+    fn owner_prompter() -> MockPrompter {
+        let mut prompter = MockPrompter::new();
+        prompter.expect_milestone().returning(|_| {
+            Ok(PromptOutcome::Value(MilestoneStatus::Existing(
+                load_milestone("v1.0"),
+            )))
+        });
+        prompter
+            .expect_file()
+            .returning(|_, _| Ok(PromptOutcome::Value(PathBuf::from("scripts/model.R"))));
+        prompter
+            .expect_checklist()
+            .returning(|_| Ok(PromptOutcome::Value(checklist())));
+        prompter
+            .expect_assignees()
+            .returning(|_, _, _| Ok(PromptOutcome::Value(vec!["reviewer1".to_string()])));
+        prompter
+    }
+
+    fn owner_configuration(policy: OwnerQCPolicy) -> Configuration {
+        let mut configuration = Configuration::default();
+        configuration.options.exclude_owners_from_qc = policy;
+        configuration
+    }
+
+    #[tokio::test]
+    async fn test_issue_blocks_owner_assignees() {
+        let mut prompter = owner_prompter();
+        prompter.expect_confirm().never();
+        let git = recording_git().with_file(
+            crate::CODEOWNERS_PATHS[0],
+            "* @someone\n/scripts/ @Reviewer1",
+        );
+
+        let err = create_issue_with_configuration(
+            &prompter,
+            &git,
+            owner_configuration(OwnerQCPolicy::Block),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Assignees own scripts/model.R in CODEOWNERS and cannot QC it: reviewer1"
+        );
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_issue_warns_about_owner_assignees() {
+        let mut prompter = owner_prompter();
+        prompter
+            .expect_collaborators()
+            .returning(|_| Ok(PromptOutcome::Value(Vec::new())));
+        prompter
+            .expect_want_relevant_files()
+            .returning(|| Ok(PromptOutcome::Value(false)));
+        // Reaching the confirmation means the owner did not block the issue
+        prompter
+            .expect_confirm()
+            .times(1)
+            .returning(|_, _| Ok(PromptOutcome::Value(false)));
+        let git = recording_git().with_file(crate::CODEOWNERS_PATHS[0], "/scripts/ @reviewer1");
+
+        let err = create_issue_with_configuration(
+            &prompter,
+            &git,
+            owner_configuration(OwnerQCPolicy::Warn),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.is::<PromptCancelled>());
+    }
+
+    #[tokio::test]
+    async fn test_issue_suggests_codeowners_team_members() {
+        let mut prompter = MockPrompter::new();
+        prompter.expect_milestone().returning(|_| {
+            Ok(PromptOutcome::Value(MilestoneStatus::Existing(
+                load_milestone("v1.0"),
+            )))
+        });
+        prompter
+            .expect_file()
+            .returning(|_, _| Ok(PromptOutcome::Value(PathBuf::from("scripts/model.R"))));
+        prompter
+            .expect_checklist()
+            .returning(|_| Ok(PromptOutcome::Value(checklist())));
+        prompter
+            .expect_assignees()
+            .withf(|_, _, suggested| suggested.to_vec() == ["teammate"])
+            .times(1)
+            .returning(|_, _, _| Ok(PromptOutcome::Cancelled));
+        let git = recording_git()
+            .with_file(crate::CODEOWNERS_PATHS[0], "*.R @a2-ai/stats @author")
+            .with_team_members(&["author", "teammate"]);
+        let mut configuration = Configuration::default();
+        configuration.options.suggest_team_members = true;
+
+        let err = create_issue_with_configuration(&prompter, &git, configuration)
+            .await
+            .unwrap_err();
+
+        assert!(err.is::<PromptCancelled>());
+    }
+
+    /// A prompter creating an issue for `scripts/model.R` in `milestone`, confirming with `confirm`
+    fn dry_run_prompter(milestone: fn() -> MilestoneStatus, confirm: Option<bool>) -> MockPrompter {
+        let mut prompter = MockPrompter::new();
+        prompter
+            .expect_milestone()
+            .returning(move |_| Ok(PromptOutcome::Value(milestone())));
+        prompter
+            .expect_collaborators()
+            .returning(|_| Ok(PromptOutcome::Value(Vec::new())));
+        prompter
+            .expect_file()
+            .returning(|_, _| Ok(PromptOutcome::Value(PathBuf::from("scripts/model.R"))));
+        prompter
+            .expect_checklist()
+            .returning(|_| Ok(PromptOutcome::Value(checklist())));
+        prompter
+            .expect_assignees()
+            .returning(|_, _, _| Ok(PromptOutcome::Value(vec!["reviewer1".to_string()])));
+        prompter
+            .expect_want_relevant_files()
+            .returning(|| Ok(PromptOutcome::Value(false)));
+        match confirm {
+            Some(confirm) => {
+                prompter
+                    .expect_confirm()
+                    .times(1)
+                    .returning(move |_, _| Ok(PromptOutcome::Value(confirm)));
+            }
+            None => {
+                prompter.expect_confirm().never();
+            }
+        }
+        prompter
+    }
+
+    async fn dry_run_issue(prompter: &MockPrompter, git: &FakeGit) -> Result<QCIssue> {
+        QCIssue::from_interactive(
+            prompter,
+            &PathBuf::from("."),
+            &IgnoreRules::default(),
+            vec![load_milestone("v1.0")],
+            &[],
+            create_options(&Configuration::default(), true, false),
+            git,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_issue_dry_run_matches_confirmed_issue() {
+        let existing = || MilestoneStatus::Existing(load_milestone("v1.0"));
+        let git = recording_git();
+
+        let confirmed = create_issue(&dry_run_prompter(existing, Some(true)), &git)
+            .await
+            .unwrap();
+        let dry_run = dry_run_issue(&dry_run_prompter(existing, None), &git)
+            .await
+            .unwrap();
+
+        assert_eq!(format!("{dry_run:?}"), format!("{confirmed:?}"));
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_issue_links_confirmed_previous_qcs() {
+        let prior = |number, milestone, closed: i64| {
+            let mut issue = crate::test_utils::create_test_issue(
+                "owner",
+                "repo",
+                number,
+                "scripts/model.R",
+                "",
+                Some(milestone),
+                "closed",
+            );
+            issue.closed_at = chrono::DateTime::from_timestamp(1_700_000_000 + closed, 0);
+            issue
+        };
+        let mut prompter = MockPrompter::new();
+        prompter.expect_milestone().returning(|_| {
+            Ok(PromptOutcome::Value(MilestoneStatus::Existing(
+                load_milestone("v1.0"),
+            )))
+        });
+        prompter
+            .expect_collaborators()
+            .returning(|_| Ok(PromptOutcome::Value(Vec::new())));
+        prompter
+            .expect_file()
+            .returning(|_, _| Ok(PromptOutcome::Value(PathBuf::from("scripts/model.R"))));
+        prompter
+            .expect_checklist()
+            .returning(|_| Ok(PromptOutcome::Value(checklist())));
+        prompter
+            .expect_assignees()
+            .returning(|_, _, _| Ok(PromptOutcome::Value(Vec::new())));
+        prompter
+            .expect_want_relevant_files()
+            .returning(|| Ok(PromptOutcome::Value(false)));
+        let asked = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let record = asked.clone();
+        prompter
+            .expect_confirm()
+            .times(2)
+            .returning(move |message, default| {
+                record.lock().unwrap().push((message.to_string(), default));
+                Ok(PromptOutcome::Value(message.contains("#5")))
+            });
+        let open = crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            9,
+            "scripts/model.R",
+            "",
+            Some(4),
+            "open",
+        );
+        // #9 is still open and #1 is in the milestone of the new issue
+        let git = recording_git().with_issues(vec![
+            prior(3, 2, 10),
+            prior(5, 3, 20),
+            open,
+            prior(1, 1, 30),
+        ]);
+
+        let issue = dry_run_issue(&prompter, &git).await.unwrap();
+
+        let asked = asked.lock().unwrap().clone();
+        assert_eq!(asked.len(), 2);
+        assert!(asked[0].0.starts_with("Link #5 (milestone v3.0, closed "));
+        assert!(asked[1].0.starts_with("Link #3 (milestone v2.0, closed "));
+        assert!(asked.iter().all(|(_, default)| *default));
+        assert_eq!(issue.blocking_issues(), [(5, Some(5000))]);
+    }
+
+    #[tokio::test]
+    async fn test_issue_dry_run_does_not_create_milestone() {
+        let prompter = dry_run_prompter(|| MilestoneStatus::New("v3.0".to_string(), None), None);
+        let git = recording_git();
+
+        let issue = dry_run_issue(&prompter, &git).await.unwrap();
+
+        assert_eq!(issue.title, PathBuf::from("scripts/model.R"));
+        assert!(git.writes().is_empty());
+    }
+
+    fn model_issue(number: u64, milestone: i64, state: &str) -> Issue {
+        crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            number,
+            "scripts/model.R",
+            "",
+            Some(milestone),
+            state,
+        )
+    }
+
+    /// Create an issue for `scripts/model.R` in v1.0 from arguments
+    async fn create_issue_from_args(
+        git: &FakeGit,
+        previous_qc: Vec<IssueUrlArg>,
+        allow_duplicate: bool,
+    ) -> Result<QCIssue> {
+        let mut configuration = Configuration::default();
+        configuration
+            .checklists
+            .insert("Test Checklist".to_string(), checklist());
+        let request = CreateIssueRequest {
+            previous_qc,
+            ..CreateIssueRequest::new(
+                "v1.0",
+                "scripts/model.R",
+                ChecklistArg::Name("Test Checklist".to_string()),
+            )
+        };
+        QCIssue::from_args(
+            request,
+            vec![load_milestone("v1.0")],
+            &[],
+            CreateOptions {
+                auto_previous: false,
+                ..create_options(&configuration, true, allow_duplicate)
+            },
+            git,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_create_refuses_open_duplicate() {
+        let git = recording_git().with_issues(vec![model_issue(2, 1, "open")]);
+
+        let err = create_issue_from_args(&git, Vec::new(), false)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("already has an open issue in the milestone"));
+        assert!(err.contains("https://github.com/owner/repo/issues/2"));
+        assert!(err.contains("--allow-duplicate"));
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_suggests_linking_closed_issue_as_previous_qc() {
+        let git = recording_git().with_issues(vec![model_issue(2, 1, "closed")]);
+
+        let err = create_issue_from_args(&git, Vec::new(), false)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("already has a closed issue in the milestone"));
+        assert!(err.contains("--previous-qc https://github.com/owner/repo/issues/2"));
+
+        // Linked as a previous QC, the file is QCed again on purpose
+        let previous_qc = IssueUrlArg {
+            url: "https://github.com/owner/repo/issues/2".to_string(),
+            issue_number: 2,
+            description: None,
+            include_diff: true,
+        };
+        let issue = create_issue_from_args(&git, vec![previous_qc], false)
+            .await
+            .unwrap();
+        assert_eq!(
+            issue
+                .blocking_issues()
+                .into_iter()
+                .map(|(number, _)| number)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_allow_duplicate() {
+        let git = recording_git()
+            .with_issues(vec![model_issue(2, 1, "open"), model_issue(3, 1, "closed")]);
+
+        let issue = create_issue_from_args(&git, Vec::new(), true)
+            .await
+            .unwrap();
+
+        assert_eq!(issue.title, PathBuf::from("scripts/model.R"));
+    }
+
+    #[tokio::test]
+    async fn test_create_ignores_issues_in_other_milestones() {
+        let git = recording_git().with_issues(vec![model_issue(2, 2, "open")]);
+
+        assert!(
+            create_issue_from_args(&git, Vec::new(), false)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issue_refuses_open_issue_created_since_prompt() {
+        let existing = || MilestoneStatus::Existing(load_milestone("v1.0"));
+        // Another analyst created the issue after the cached listing was read
+        let git = recording_git().with_issues(vec![model_issue(2, 1, "open")]);
+
+        let err = dry_run_issue(&dry_run_prompter(existing, None), &git)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("already has an open issue in the milestone")
+        );
+
+        // Duplicates allowed, the issue is created anyway
+        let issue = QCIssue::from_interactive(
+            &dry_run_prompter(existing, None),
+            &PathBuf::from("."),
+            &IgnoreRules::default(),
+            vec![load_milestone("v1.0")],
+            &[],
+            create_options(&Configuration::default(), true, true),
+            &git,
+        )
+        .await
+        .unwrap();
+        assert_eq!(issue.title, PathBuf::from("scripts/model.R"));
+    }
+
+    #[tokio::test]
+    async fn test_find_issue_prefers_most_recently_updated_open_issue() {
+        let updated = |mut issue: Issue, day: u32| {
+            issue.updated_at = chrono::NaiveDate::from_ymd_opt(2026, 1, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc();
+            issue
+        };
+        let mut other_file = model_issue(7, 1, "open");
+        other_file.title = "old/scripts/model.R".to_string();
+        let git = recording_git().with_issues(vec![
+            updated(model_issue(1, 1, "open"), 1),
+            updated(model_issue(4, 1, "open"), 3),
+            updated(model_issue(6, 1, "closed"), 5),
+            updated(other_file, 6),
+        ]);
+
+        let issue = find_issue("v1.0", "scripts/model.R", &[load_milestone("v1.0")], &git)
+            .await
+            .unwrap();
+
+        assert_eq!(issue.number, 4);
+    }
+
+    #[tokio::test]
+    async fn test_reassign_require_write_access() {
+        let git = recording_git()
+            .with_issues(vec![model_issue(1, 1, "open")])
+            .with_permissions(&[("viewer", Permission::Read)]);
+        let settings = ReassignSettings {
+            milestone: "v1.0".to_string(),
+            file: PathBuf::from("scripts/model.R"),
+            add_assignee: vec!["viewer".to_string()],
+            remove_assignee: Vec::new(),
+            note: None,
+        };
+        let milestones = [load_milestone("v1.0")];
+
+        let reassign = QCReassign::from_args(settings.clone(), &milestones, false, None, &git)
+            .await
+            .unwrap();
+        assert_eq!(reassign.new_assignees, vec!["viewer".to_string()]);
+
+        let err = QCReassign::from_args(settings, &milestones, true, None, &git)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("viewer"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_unapprove_cancelled_at_reason_prompt() {
+        let closed = crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            1,
+            "scripts/model.R",
+            "body",
+            Some(1),
+            "closed",
+        );
+
+        let mut prompter = MockPrompter::new();
+        prompter
+            .expect_existing_milestone()
+            .returning(|_| Ok(PromptOutcome::Value(load_milestone("v1.0"))));
+        prompter
+            .expect_issue()
+            .returning(|issues| Ok(PromptOutcome::Value(issues[0].clone())));
+        prompter
+            .expect_unapprove_reason()
+            .returning(|| Ok(PromptOutcome::Cancelled));
+        prompter.expect_confirm().never();
+        let git = recording_git().with_issues(vec![closed]);
+
+        let err = QCUnapprove::from_interactive(&prompter, &[load_milestone("v1.0")], None, &git)
+            .await
+            .unwrap_err();
+
+        assert!(err.is::<PromptCancelled>());
+        assert!(git.writes().is_empty());
+    }
+
+    fn issue_commit(sha: &str, file_changed: bool) -> IssueCommit {
+        IssueCommit {
+            hash: ObjectId::from_str(sha).unwrap(),
+            message: "commit".to_string(),
+            statuses: std::collections::HashSet::new(),
+            file_changed,
+        }
+    }
+
+    fn branch_commits() -> Vec<IssueCommit> {
+        vec![
+            issue_commit("abcdef1234567890abcdef1234567890abcdef12", true),
+            issue_commit("abcdef1999999999999999999999999999999999", false),
+            issue_commit("0123456789abcdef0123456789abcdef01234567", true),
+        ]
+    }
+
+    #[test]
+    fn test_resolve_issue_commit_full_and_short_sha() {
+        let commits = branch_commits();
+
+        let found = resolve_issue_commit(&commits, "main", "0123456").unwrap();
+        assert_eq!(found.hash, commits[2].hash);
+        let found =
+            resolve_issue_commit(&commits, "main", "ABCDEF1234567890ABCDEF1234567890ABCDEF12")
+                .unwrap();
+        assert_eq!(found.hash, commits[0].hash);
+    }
+
+    #[test]
+    fn test_resolve_issue_commit_nonexistent_sha() {
+        let err = resolve_issue_commit(&branch_commits(), "main", "fedcba9").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Commit fedcba9 was not found on branch 'main' since the start of QC for this issue"
+        );
+
+        let err = resolve_issue_commit(&branch_commits(), "main", "abc12").unwrap_err();
+        assert!(err.to_string().contains("is not a commit SHA"));
+        let err = resolve_issue_commit(&branch_commits(), "main", "HEAD~1x").unwrap_err();
+        assert!(err.to_string().contains("is not a commit SHA"));
+    }
+
+    #[test]
+    fn test_resolve_issue_commit_ambiguous_short_sha() {
+        let err = resolve_issue_commit(&branch_commits(), "main", "abcdef1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Commit abcdef1 is ambiguous on branch 'main', it could be any of: \
+            abcdef1234567890abcdef1234567890abcdef12, abcdef1999999999999999999999999999999999"
+        );
+
+        // A longer prefix tells them apart
+        assert!(resolve_issue_commit(&branch_commits(), "main", "abcdef12").is_ok());
+    }
+
+    #[test]
+    fn test_resolve_issue_commit_on_another_branch() {
+        // Only commits of the issue's branch are candidates, so a commit of another branch
+        // is not found even when it exists in the repository
+        let feature_commits = vec![issue_commit(
+            "1111111111111111111111111111111111111111",
+            true,
+        )];
+        let err =
+            resolve_issue_commit(&feature_commits, "feature/qc", "0123456789abcdef").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("was not found on branch 'feature/qc'")
+        );
+    }
+
+    #[test]
+    fn test_check_commit_touches_file() {
+        let file = Path::new("scripts/run.R");
+        assert!(check_commit_touches_file(&branch_commits()[0], file, true).is_ok());
+        assert!(check_commit_touches_file(&branch_commits()[1], file, false).is_ok());
+
+        let err = check_commit_touches_file(&branch_commits()[1], file, true).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Commit abcdef1 did not modify scripts/run.R"
+        );
+    }
+
+    const REVIEW_COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    fn review_issue(number: u64, file: &str, state: &str) -> Issue {
+        crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            number,
+            file,
+            &format!("git branch: main\ninitial qc commit: {REVIEW_COMMIT}"),
+            Some(1),
+            state,
+        )
+    }
+
+    fn review_git(issues: Vec<Issue>) -> FakeGit {
+        recording_git()
+            .with_issues(issues)
+            .with_head(REVIEW_COMMIT)
+            .with_commits(&[REVIEW_COMMIT])
+    }
+
+    #[tokio::test]
+    async fn test_review_targets_skip_files_without_open_issue() {
+        let git = review_git(vec![
+            review_issue(1, "scripts/model.R", "open"),
+            review_issue(2, "scripts/data.R", "closed"),
+        ]);
+        let files = vec![
+            PathBuf::from("scripts/model.R"),
+            PathBuf::from("scripts/data.R"),
+            PathBuf::from("README.md"),
+            PathBuf::from("scripts/model.R"),
+        ];
+
+        let targets = ReviewTargets::find("v1.0", files, &[load_milestone("v1.0")], None, &git)
+            .await
+            .unwrap();
+
+        let matched: Vec<_> = targets.matched.iter().map(|(f, i)| (f, i.number)).collect();
+        assert_eq!(matched, vec![(&PathBuf::from("scripts/model.R"), 1)]);
+        assert_eq!(
+            targets.skipped,
+            vec![PathBuf::from("scripts/data.R"), PathBuf::from("README.md")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_review_targets_match_multiple_issues() {
+        let git = review_git(vec![
+            review_issue(1, "scripts/model.R", "open"),
+            review_issue(2, "scripts/data.R", "open"),
+        ]);
+        let files = vec![
+            PathBuf::from("scripts/data.R"),
+            PathBuf::from("scripts/model.R"),
+        ];
+
+        let targets = ReviewTargets::find("v1.0", files, &[load_milestone("v1.0")], None, &git)
+            .await
+            .unwrap();
+
+        let matched: Vec<_> = targets.matched.iter().map(|(_, i)| i.number).collect();
+        assert_eq!(matched, vec![2, 1]);
+        assert!(targets.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_review_settings_apply_to_every_file() {
+        let git = review_git(vec![
+            review_issue(1, "scripts/model.R", "open"),
+            review_issue(2, "scripts/data.R", "open"),
+        ]);
+        let files = vec![
+            PathBuf::from("scripts/model.R"),
+            PathBuf::from("scripts/data.R"),
+        ];
+        let targets = ReviewTargets::find("v1.0", files, &[load_milestone("v1.0")], None, &git)
+            .await
+            .unwrap();
+        let settings = ReviewSettings {
+            note: Some("Round two".to_string()),
+            no_diff: Some(NoDiff::Full),
+            stash_after_review: true,
+            ..Default::default()
+        };
+
+        let reviews = targets.reviews(&settings, None, &git).await;
+
+        assert_eq!(reviews.len(), 2);
+        for (file, review) in reviews {
+            let review = review.unwrap();
+            assert_eq!(review.file, file);
+            assert_eq!(review.no_diff, Some(NoDiff::Full));
+            assert_eq!(review.note.as_deref(), Some("Round two"));
+            assert_eq!(review.commit.to_string(), REVIEW_COMMIT);
+        }
+    }
+
+    fn review_settings(allow_branch_mismatch: bool) -> ReviewSettings {
+        ReviewSettings {
+            commit: Some(REVIEW_COMMIT.to_string()),
+            allow_branch_mismatch,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_review_on_issue_branch() {
+        let issue = review_issue(1, "scripts/model.R", "open");
+        let git =
+            review_git(vec![issue.clone()]).with_containing_branches(&["main", "origin/main"]);
+
+        let review = QCReview::for_issue(
+            issue,
+            PathBuf::from("scripts/model.R"),
+            &review_settings(false),
+            None,
+            &git,
+        )
+        .await
+        .unwrap();
+        assert_eq!(review.commit.to_string(), REVIEW_COMMIT);
+    }
+
+    #[tokio::test]
+    async fn test_review_branch_mismatch() {
+        let issue = review_issue(1, "scripts/model.R", "open");
+        let git = review_git(vec![issue.clone()])
+            .with_branch("feature/pk-analysis")
+            .with_containing_branches(&["origin/main"]);
+        let file = PathBuf::from("scripts/model.R");
+
+        let err = QCReview::for_issue(
+            issue.clone(),
+            file.clone(),
+            &review_settings(false),
+            None,
+            &git,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Checked-out branch 'feature/pk-analysis' is not the QC branch 'main' of issue #1. \
+             Check out 'main' or pass --allow-branch-mismatch"
+        );
+
+        let review = QCReview::for_issue(issue, file, &review_settings(true), None, &git)
+            .await
+            .unwrap();
+        assert_eq!(review.commit.to_string(), REVIEW_COMMIT);
+    }
+
+    #[tokio::test]
+    async fn test_review_commit_only_on_other_branch() {
+        let issue = review_issue(1, "scripts/model.R", "open");
+        let git = review_git(vec![issue.clone()])
+            .with_branch("feature/pk-analysis")
+            .with_containing_branches(&["feature/pk-analysis"]);
+
+        let err = QCReview::for_issue(
+            issue,
+            PathBuf::from("scripts/model.R"),
+            &review_settings(true),
+            None,
+            &git,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Commit 01234567 is not on the QC branch 'main' of issue #1, only on: feature/pk-analysis"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_review_batch_collects_failures() {
+        let git = review_git(vec![
+            review_issue(1, "scripts/model.R", "open"),
+            review_issue(2, "scripts/data.R", "open"),
+        ]);
+        let files = vec![
+            PathBuf::from("scripts/model.R"),
+            PathBuf::from("scripts/data.R"),
+            PathBuf::from("README.md"),
+        ];
+        let targets = ReviewTargets::find("v1.0", files, &[load_milestone("v1.0")], None, &git)
+            .await
+            .unwrap();
+
+        let report = targets.post(&ReviewSettings::default(), None, &git).await;
+
+        // The first failed post does not stop the second
+        assert_eq!(git.writes(), vec!["post_comment", "post_comment"]);
+        assert!(report.posted.is_empty());
+        assert_eq!(report.failed.len(), 2);
+        assert_eq!(report.skipped, vec![PathBuf::from("README.md")]);
+    }
+}
@@ -0,0 +1,750 @@
+use anyhow::{Result, anyhow, bail};
+use octocrab::models::{IssueState, Milestone, issues::Issue};
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use crate::utils::normalize_repo_path;
+use crate::{
+    Checklist, Configuration, DiskCache, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers,
+    GitHubReader, GitHubWriter, GitRepository, QCEntry, QCRelationship, RelevantFile,
+    RelevantFileClass, RelevantFileEntry, RelevantFileLink, batch_post_qc_entries,
+    create::CreateResult, create_labels_if_needed, find_checklist_start, parse_relevant_file_links,
+};
+
+static CHECKED_BOX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(\s*[-*+] )\[[xX]\]").unwrap());
+
+/// Settings of `milestone copy-plan`
+#[derive(Debug, Clone)]
+pub struct CopyPlanOptions {
+    /// Milestone whose QC issues are copied
+    pub from: String,
+    /// Milestone the copies are created in, created when missing
+    pub to: String,
+    /// Assign the copies to the QCers of the source issues
+    pub assignees_from_source: bool,
+    /// Only plan the copy, without writing anything to GitHub
+    pub dry_run: bool,
+}
+
+/// A relevant file listed in the body of a source issue
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceRelevantFile {
+    Issue {
+        file: PathBuf,
+        issue_number: u64,
+        relationship: QCRelationship,
+        description: Option<String>,
+    },
+    File {
+        file: PathBuf,
+        justification: String,
+    },
+}
+
+/// A QC issue of the source milestone, as it will be recreated in the target milestone
+#[derive(Debug, Clone)]
+pub struct PlannedIssue {
+    pub source_issue: u64,
+    pub file: PathBuf,
+    pub checklist: Checklist,
+    /// The checklist was found in the configuration rather than copied from the source issue
+    pub configured_checklist: bool,
+    pub assignees: Vec<String>,
+    pub relevant_files: Vec<SourceRelevantFile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    /// The file no longer exists at HEAD
+    FileMissing,
+    /// The target milestone already has an open issue for the file
+    AlreadyInTarget(u64),
+}
+
+/// A QC issue of the source milestone which is not copied
+#[derive(Debug, Clone)]
+pub struct SkippedIssue {
+    pub source_issue: u64,
+    pub file: PathBuf,
+    pub reason: SkipReason,
+}
+
+/// The QC issues of a milestone to recreate in another milestone
+#[derive(Debug, Clone)]
+pub struct CopyPlan {
+    pub source: Milestone,
+    pub target: String,
+    /// The target milestone, when it already exists
+    pub target_milestone: Option<Milestone>,
+    pub issues: Vec<PlannedIssue>,
+    pub skipped: Vec<SkippedIssue>,
+}
+
+/// The plan of a `milestone copy-plan` run and the issues it created
+pub struct CopyPlanOutcome {
+    pub plan: CopyPlan,
+    /// Empty for a dry run
+    pub created: Vec<CreateResult>,
+}
+
+/// Plan the copy of a milestone's QC issues and, unless it is a dry run, create the copies
+///
+/// The copies get fresh initial commits from the current branch state. Nothing is written to
+/// GitHub for a dry run, including the target milestone and the ghqc labels.
+pub async fn copy_milestone_plan(
+    options: &CopyPlanOptions,
+    milestones: &[Milestone],
+    configuration: &Configuration,
+    cache: Option<&DiskCache>,
+    git_info: &(
+         impl GitHubReader + GitHubWriter + GitHelpers + GitRepository + GitFileOps + GitCommitOps
+     ),
+) -> Result<CopyPlanOutcome> {
+    let plan = plan_milestone_copy(options, milestones, configuration, git_info).await?;
+    if options.dry_run || plan.issues.is_empty() {
+        return Ok(CopyPlanOutcome {
+            plan,
+            created: Vec::new(),
+        });
+    }
+
+    let milestone = match &plan.target_milestone {
+        Some(milestone) => milestone.clone(),
+        None => {
+            let milestone = git_info.create_milestone(&plan.target, &None).await?;
+            log::debug!(
+                "Created milestone '{}' with ID: {}",
+                plan.target,
+                milestone.number
+            );
+            milestone
+        }
+    };
+
+    let branch = git_info.branch()?;
+    create_labels_if_needed(cache, Some(&branch), git_info).await?;
+
+    let current_user = git_info.get_current_user().await.ok().flatten();
+    let created = batch_post_qc_entries(
+        &plan.entries(),
+        git_info,
+        milestone.number as u64,
+        current_user.as_deref(),
+        configuration.issue_template()?.as_ref(),
+    )
+    .await?;
+
+    Ok(CopyPlanOutcome { plan, created })
+}
+
+/// Read the source milestone's QC issues and decide which of them to copy
+pub async fn plan_milestone_copy(
+    options: &CopyPlanOptions,
+    milestones: &[Milestone],
+    configuration: &Configuration,
+    git_info: &(impl GitHubReader + GitFileOps),
+) -> Result<CopyPlan> {
+    if options.from == options.to {
+        bail!("Cannot copy milestone '{}' into itself", options.from);
+    }
+
+    let source = milestones
+        .iter()
+        .find(|m| m.title == options.from)
+        .ok_or(anyhow!("Milestone '{}' not found", options.from))?;
+    let target_milestone = milestones.iter().find(|m| m.title == options.to);
+
+    let mut source_issues = git_info.get_issues(Some(source.number as u64)).await?;
+    source_issues.sort_by_key(|issue| issue.number);
+
+    // Files with an open issue in the target milestone
+    let target_files: HashMap<String, u64> = match target_milestone {
+        Some(target) => git_info
+            .get_issues(Some(target.number as u64))
+            .await?
+            .into_iter()
+            .filter(|issue| matches!(issue.state, IssueState::Open))
+            .map(|issue| (issue.title, issue.number))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    let head = git_info.resolve_commit("HEAD")?;
+    let mut issues = Vec::new();
+    let mut skipped = Vec::new();
+
+    for issue in &source_issues {
+        let Some(mut planned) = planned_issue(issue, &configuration.checklists) else {
+            log::debug!("Issue #{} is not a QC issue. Skipping", issue.number);
+            continue;
+        };
+        if !options.assignees_from_source {
+            planned.assignees.clear();
+        }
+
+        let reason = if let Some(&number) = target_files.get(&issue.title) {
+            Some(SkipReason::AlreadyInTarget(number))
+        } else {
+            match git_info.file_bytes_at_commit(&planned.file, &head) {
+                Ok(_) => None,
+                Err(GitFileOpsError::FileNotFoundAtCommit(_)) => Some(SkipReason::FileMissing),
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        match reason {
+            Some(reason) => skipped.push(SkippedIssue {
+                source_issue: planned.source_issue,
+                file: planned.file,
+                reason,
+            }),
+            None => issues.push(planned),
+        }
+    }
+
+    Ok(CopyPlan {
+        source: source.clone(),
+        target: options.to.clone(),
+        target_milestone: target_milestone.cloned(),
+        issues,
+        skipped,
+    })
+}
+
+impl CopyPlan {
+    /// The issues to create, with references between copied issues pointing at the new issues
+    ///
+    /// References to skipped issues point at the target milestone's existing issue for the
+    /// file, or stay on the source issue when the file no longer exists.
+    pub fn entries(&self) -> Vec<QCEntry> {
+        let copied: HashMap<u64, &Path> = self
+            .issues
+            .iter()
+            .map(|issue| (issue.source_issue, issue.file.as_path()))
+            .collect();
+        let existing_copies: HashMap<u64, u64> = self
+            .skipped
+            .iter()
+            .filter_map(|skipped| match skipped.reason {
+                SkipReason::AlreadyInTarget(number) => Some((skipped.source_issue, number)),
+                SkipReason::FileMissing => None,
+            })
+            .collect();
+
+        self.issues
+            .iter()
+            .map(|issue| QCEntry {
+                title: issue.file.clone(),
+                checklist: issue.checklist.clone(),
+                assignees: issue.assignees.clone(),
+                collaborators: None,
+                relevant_files: issue
+                    .relevant_files
+                    .iter()
+                    .map(|relevant| match relevant {
+                        SourceRelevantFile::Issue {
+                            file,
+                            issue_number,
+                            relationship,
+                            description,
+                        } => match copied.get(issue_number) {
+                            Some(copied_file) => RelevantFileEntry::NewIssue {
+                                file_path: copied_file.to_path_buf(),
+                                relationship: *relationship,
+                                description: description.clone(),
+                                include_diff: false,
+                            },
+                            None => RelevantFileEntry::ExistingIssue(RelevantFile {
+                                file_name: file.clone(),
+                                class: issue_class(
+                                    *existing_copies.get(issue_number).unwrap_or(issue_number),
+                                    *relationship,
+                                    description.clone(),
+                                ),
+                            }),
+                        },
+                        SourceRelevantFile::File {
+                            file,
+                            justification,
+                        } => RelevantFileEntry::File {
+                            file_path: file.clone(),
+                            justification: justification.clone(),
+                        },
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+fn issue_class(
+    issue_number: u64,
+    relationship: QCRelationship,
+    description: Option<String>,
+) -> RelevantFileClass {
+    match relationship {
+        QCRelationship::PreviousQC => RelevantFileClass::PreviousQC {
+            issue_number,
+            issue_id: None,
+            description,
+            include_diff: false,
+        },
+        QCRelationship::GatingQC => RelevantFileClass::GatingQC {
+            issue_number,
+            issue_id: None,
+            description,
+        },
+        QCRelationship::RelevantQC => RelevantFileClass::RelevantQC {
+            issue_number,
+            description,
+        },
+    }
+}
+
+/// Extract what is needed to recreate a QC issue, or `None` when it is not a QC issue
+fn planned_issue(issue: &Issue, checklists: &HashMap<String, Checklist>) -> Option<PlannedIssue> {
+    let body = issue.body.as_deref()?;
+    if !body.contains("initial qc commit: ") {
+        return None;
+    }
+    let (checklist, configured_checklist) = source_checklist(body, checklists)?;
+
+    Some(PlannedIssue {
+        source_issue: issue.number,
+        file: normalize_repo_path(Path::new(&issue.title)),
+        checklist,
+        configured_checklist,
+        assignees: issue.assignees.iter().map(|a| a.login.clone()).collect(),
+        relevant_files: source_relevant_files(body),
+    })
+}
+
+/// The checklist named by the issue body's checklist heading
+///
+/// The configured checklist of that name is preferred. Otherwise the body's checklist is reused
+/// with its boxes unchecked.
+fn source_checklist(
+    body: &str,
+    checklists: &HashMap<String, Checklist>,
+) -> Option<(Checklist, bool)> {
+    let checklist = &body[find_checklist_start(body)?..];
+    let (heading, content) = checklist.split_once('\n').unwrap_or((checklist, ""));
+    let name = heading.trim_start_matches("# ").trim();
+
+    if let Some(configured) = checklists.get(name) {
+        return Some((configured.clone(), true));
+    }
+
+    let content = content
+        .find("\n## File History")
+        .map_or(content, |end| &content[..end]);
+    let content = CHECKED_BOX.replace_all(content.trim(), "$1[ ]");
+    Some((
+        Checklist::new(name.to_string(), None, content.into_owned()),
+        false,
+    ))
+}
+
+/// The entries of the issue body's "## Relevant Files" section
+fn source_relevant_files(body: &str) -> Vec<SourceRelevantFile> {
+    parse_relevant_file_links(body)
+        .into_iter()
+        .map(|link| match link {
+            RelevantFileLink::Issue {
+                file_name,
+                issue_number,
+                relationship,
+                description,
+                ..
+            } => SourceRelevantFile::Issue {
+                file: file_name,
+                issue_number,
+                relationship,
+                description,
+            },
+            RelevantFileLink::File {
+                file_name,
+                justification,
+                ..
+            } => SourceRelevantFile::File {
+                file: file_name,
+                justification: justification.unwrap_or_default(),
+            },
+        })
+        .collect()
+}
+
+fn relationship_name(relationship: QCRelationship) -> &'static str {
+    match relationship {
+        QCRelationship::PreviousQC => "previous QC",
+        QCRelationship::GatingQC => "gating QC",
+        QCRelationship::RelevantQC => "relevant QC",
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileMissing => write!(f, "file no longer exists"),
+            Self::AlreadyInTarget(number) => {
+                write!(
+                    f,
+                    "already has open issue #{number} in the target milestone"
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for CopyPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target_state = match &self.target_milestone {
+            Some(milestone) => format!("existing: #{}", milestone.number),
+            None => "new".to_string(),
+        };
+        writeln!(
+            f,
+            "📋 Copy plan: '{}' → '{}' ({target_state})",
+            self.source.title, self.target
+        )?;
+
+        writeln!(f, "\nIssues to create ({}):", self.issues.len())?;
+        for issue in &self.issues {
+            writeln!(
+                f,
+                "  {} (from #{})",
+                issue.file.display(),
+                issue.source_issue
+            )?;
+            let origin = if issue.configured_checklist {
+                "configured"
+            } else {
+                "copied from source issue"
+            };
+            writeln!(f, "    checklist: {} ({origin})", issue.checklist.name)?;
+            if !issue.assignees.is_empty() {
+                writeln!(f, "    assignees: {}", issue.assignees.join(", "))?;
+            }
+            for relevant in &issue.relevant_files {
+                match relevant {
+                    SourceRelevantFile::Issue {
+                        file,
+                        issue_number,
+                        relationship,
+                        ..
+                    } => writeln!(
+                        f,
+                        "    {}: {} (#{issue_number})",
+                        relationship_name(*relationship),
+                        file.display()
+                    )?,
+                    SourceRelevantFile::File { file, .. } => {
+                        writeln!(f, "    relevant file: {}", file.display())?
+                    }
+                }
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            writeln!(f, "\nSkipped ({}):", self.skipped.len())?;
+            for skipped in &self.skipped {
+                writeln!(
+                    f,
+                    "  {} (from #{}): {}",
+                    skipped.file.display(),
+                    skipped.source_issue,
+                    skipped.reason
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        FakeGit, create_test_issue, create_test_milestone, test_configuration,
+    };
+
+    const MODEL_BODY: &str = include_str!("../tests/copy_plan/model_issue.md");
+    const LEGACY_BODY: &str = include_str!("../tests/copy_plan/legacy_checklist_issue.md");
+
+    /// Serves the issues of milestones `v1.0` and `v2.0`, failing every write
+    fn fixture_git() -> FakeGit {
+        let mut model = issue(12, "src/model.R", MODEL_BODY, 1, "open");
+        model.assignees = load_issue("config_file_issue").assignees;
+
+        FakeGit::new()
+            .with_milestones(milestones())
+            .with_issues(vec![
+                issue(14, "src/old.R", LEGACY_BODY, 1, "closed"),
+                model,
+                issue(11, "src/data.R", LEGACY_BODY, 1, "closed"),
+                issue(13, "src/plots.R", LEGACY_BODY, 1, "open"),
+                issue(15, "Update the README", "Not a QC issue", 1, "open"),
+                issue(30, "src/data.R", LEGACY_BODY, 2, "closed"),
+                issue(31, "src/plots.R", LEGACY_BODY, 2, "open"),
+            ])
+            .with_missing_file("src/old.R")
+            .with_failing_writes()
+    }
+
+    fn issue(number: u64, title: &str, body: &str, milestone: i64, state: &str) -> Issue {
+        create_test_issue("owner", "repo", number, title, body, Some(milestone), state)
+    }
+
+    fn load_issue(name: &str) -> Issue {
+        let json =
+            std::fs::read_to_string(format!("src/tests/github_api/issues/{name}.json")).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn milestones() -> Vec<Milestone> {
+        vec![
+            create_test_milestone("owner", "repo", 1, "v1.0", None, "open"),
+            create_test_milestone("owner", "repo", 2, "v2.0", None, "open"),
+        ]
+    }
+
+    fn options(to: &str, dry_run: bool) -> CopyPlanOptions {
+        CopyPlanOptions {
+            from: "v1.0".to_string(),
+            to: to.to_string(),
+            assignees_from_source: true,
+            dry_run,
+        }
+    }
+
+    #[test]
+    fn test_planned_issue_from_fixture_bodies() {
+        let configuration = test_configuration();
+        let mut model = issue(12, "src/model.R", MODEL_BODY, 1, "open");
+        model.assignees = load_issue("config_file_issue").assignees;
+
+        let planned = planned_issue(&model, &configuration.checklists).unwrap();
+        assert_eq!(planned.file, PathBuf::from("src/model.R"));
+        assert_eq!(planned.assignees, ["reviewer1", "reviewer2"]);
+        assert!(planned.configured_checklist);
+        assert_eq!(planned.checklist.name, "Simple Tasks");
+        assert_eq!(
+            planned.checklist.content,
+            configuration.checklists["Simple Tasks"].content
+        );
+        assert_eq!(
+            planned.relevant_files,
+            vec![
+                SourceRelevantFile::Issue {
+                    file: PathBuf::from("src/model.R"),
+                    issue_number: 3,
+                    relationship: QCRelationship::PreviousQC,
+                    description: Some("QC of the first model run".to_string()),
+                },
+                SourceRelevantFile::Issue {
+                    file: PathBuf::from("src/data.R"),
+                    issue_number: 11,
+                    relationship: QCRelationship::GatingQC,
+                    description: Some("Builds the modeling dataset".to_string()),
+                },
+                SourceRelevantFile::Issue {
+                    file: PathBuf::from("src/plots.R"),
+                    issue_number: 13,
+                    relationship: QCRelationship::RelevantQC,
+                    description: None,
+                },
+                SourceRelevantFile::File {
+                    file: PathBuf::from("data/derived/pk.csv"),
+                    justification: "Generated by src/data.R".to_string(),
+                },
+                SourceRelevantFile::File {
+                    file: PathBuf::from("README.md"),
+                    justification: "Describes the model".to_string(),
+                },
+            ]
+        );
+
+        // A checklist missing from the configuration is copied from the body, unchecked
+        let legacy = issue(11, "src/data.R", LEGACY_BODY, 1, "closed");
+        let planned = planned_issue(&legacy, &configuration.checklists).unwrap();
+        assert!(!planned.configured_checklist);
+        assert!(planned.relevant_files.is_empty());
+        insta::assert_snapshot!(planned.checklist, @r"
+        # Legacy Review
+
+        Check the data against the specification.
+
+        - [ ] Columns match the specification
+        - [ ] Units are documented
+          - [ ] Concentrations
+        - [ ] Outliers are flagged
+        ");
+
+        let not_qc = issue(15, "Update the README", "Not a QC issue", 1, "open");
+        assert!(planned_issue(&not_qc, &configuration.checklists).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plan_skips_missing_and_already_open_files() {
+        let git_info = fixture_git();
+        let plan = plan_milestone_copy(
+            &options("v2.0", false),
+            &milestones(),
+            &test_configuration(),
+            &git_info,
+        )
+        .await
+        .unwrap();
+
+        let files: Vec<_> = plan.issues.iter().map(|i| i.file.clone()).collect();
+        // The closed src/data.R issue in v2.0 does not prevent a new copy
+        assert_eq!(
+            files,
+            [PathBuf::from("src/data.R"), PathBuf::from("src/model.R")]
+        );
+        let skipped: Vec<_> = plan
+            .skipped
+            .iter()
+            .map(|s| (s.source_issue, s.reason.clone()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (13, SkipReason::AlreadyInTarget(31)),
+                (14, SkipReason::FileMissing)
+            ]
+        );
+
+        let entries = plan.entries();
+        let model = entries
+            .iter()
+            .find(|e| e.title == Path::new("src/model.R"))
+            .unwrap();
+        assert_eq!(model.assignees, ["reviewer1", "reviewer2"]);
+        let references: Vec<String> = model
+            .relevant_files
+            .iter()
+            .map(|rf| match rf {
+                RelevantFileEntry::ExistingIssue(rf) => match &rf.class {
+                    RelevantFileClass::PreviousQC { issue_number, .. } => {
+                        format!("previous #{issue_number}")
+                    }
+                    RelevantFileClass::GatingQC { issue_number, .. } => {
+                        format!("gating #{issue_number}")
+                    }
+                    RelevantFileClass::RelevantQC { issue_number, .. } => {
+                        format!("relevant #{issue_number}")
+                    }
+                    RelevantFileClass::File { .. } => "file".to_string(),
+                },
+                RelevantFileEntry::NewIssue { file_path, .. } => {
+                    format!("new {}", file_path.display())
+                }
+                RelevantFileEntry::File { file_path, .. } => {
+                    format!("file {}", file_path.display())
+                }
+            })
+            .collect();
+        assert_eq!(
+            references,
+            [
+                "previous #3",
+                "new src/data.R",
+                "relevant #31",
+                "file data/derived/pk.csv",
+                "file README.md"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_fails_on_unreadable_files() {
+        let git_info = fixture_git().with_unreadable_file("src/model.R");
+
+        let result = plan_milestone_copy(
+            &options("v2.0", false),
+            &milestones(),
+            &test_configuration(),
+            &git_info,
+        )
+        .await;
+
+        // Only a file missing at HEAD is skipped
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("src/model.R"), "{error}");
+    }
+
+    #[test]
+    fn test_source_relevant_files_skips_invalid_issue_numbers() {
+        let body = MODEL_BODY.replace("issues/13)", "issues/99999999999999999999999)");
+
+        let relevant_files = source_relevant_files(&body);
+        assert_eq!(relevant_files.len(), 4);
+        assert!(!relevant_files.iter().any(|relevant| matches!(
+            relevant,
+            SourceRelevantFile::Issue { file, .. } if file == Path::new("src/plots.R")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_performs_no_writes() {
+        let git_info = fixture_git();
+        let mut options = options("v3.0", true);
+        options.assignees_from_source = false;
+
+        let outcome = copy_milestone_plan(
+            &options,
+            &milestones(),
+            &test_configuration(),
+            None,
+            &git_info,
+        )
+        .await
+        .unwrap();
+
+        assert!(git_info.writes().is_empty());
+        assert!(outcome.created.is_empty());
+        assert!(outcome.plan.target_milestone.is_none());
+        assert_eq!(outcome.plan.issues.len(), 3);
+        assert!(outcome.plan.issues.iter().all(|i| i.assignees.is_empty()));
+        insta::assert_snapshot!(outcome.plan.to_string(), @r"
+        📋 Copy plan: 'v1.0' → 'v3.0' (new)
+
+        Issues to create (3):
+          src/data.R (from #11)
+            checklist: Legacy Review (copied from source issue)
+          src/model.R (from #12)
+            checklist: Simple Tasks (configured)
+            previous QC: src/model.R (#3)
+            gating QC: src/data.R (#11)
+            relevant QC: src/plots.R (#13)
+            relevant file: data/derived/pk.csv
+            relevant file: README.md
+          src/plots.R (from #13)
+            checklist: Legacy Review (copied from source issue)
+
+        Skipped (1):
+          src/old.R (from #14): file no longer exists
+        ");
+
+        // Without --dry-run the missing target milestone is the first write
+        options.dry_run = false;
+        let result = copy_milestone_plan(
+            &options,
+            &milestones(),
+            &test_configuration(),
+            None,
+            &git_info,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(git_info.writes(), ["create_milestone"]);
+    }
+}
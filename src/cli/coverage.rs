@@ -0,0 +1,71 @@
+use anyhow::{Result, bail};
+use octocrab::models::Milestone;
+use std::path::PathBuf;
+
+use crate::{
+    DiskCache, GitCommitOps, GitFileOps, GitHubReader, MilestoneSnapshot, QCCoverage, qc_coverage,
+};
+
+use super::page;
+
+/// Print how many of the files tracked under `paths` have been through QC in `milestones`
+pub async fn milestone_coverage(
+    milestones: &[Milestone],
+    paths: &[PathBuf],
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitCommitOps + GitFileOps),
+    no_pager: bool,
+) -> Result<()> {
+    if milestones.is_empty() {
+        bail!("No milestones provided");
+    }
+
+    let milestone_refs = milestones.iter().collect::<Vec<_>>();
+    let snapshot = MilestoneSnapshot::build(&milestone_refs, git_info, cache).await?;
+    let threads = snapshot.threads(git_info, cache)?;
+    let coverage = qc_coverage(git_info, paths, &threads)?;
+
+    page(&format_coverage(milestones, &coverage), no_pager);
+
+    Ok(())
+}
+
+fn format_coverage(milestones: &[Milestone], coverage: &QCCoverage) -> String {
+    let milestone_names = milestones
+        .iter()
+        .map(|m| m.title.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("Milestones: {milestone_names}\n{coverage}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_milestone;
+
+    #[test]
+    fn test_format_coverage() {
+        let milestones = vec![
+            create_test_milestone("owner", "repo", 1, "v1.0", None, "closed"),
+            create_test_milestone("owner", "repo", 2, "v1.1", None, "open"),
+        ];
+        let coverage = QCCoverage {
+            scope: vec![PathBuf::from("scripts")],
+            files_in_scope: 3,
+            approved: 1,
+            in_progress: 1,
+            uncovered: vec![PathBuf::from("scripts/plots.R")],
+        };
+
+        insta::assert_snapshot!(format_coverage(&milestones, &coverage), @r"
+        Milestones: v1.0, v1.1
+        QC coverage of scripts/
+        - Files in scope:   3
+        - Approved QC:      1 (33.3%)
+        - QC in progress:   1
+        - Without an issue: 1
+          - scripts/plots.R
+        ");
+    }
+}
@@ -1,5 +1,7 @@
-use crate::GitCommit;
 use crate::archive::ArchiveFile;
+use crate::relevant_files::{RelevantFile, RelevantFileClass};
+use crate::utils::{closest_match, normalize_repo_path};
+use crate::{GitCommit, GitFileOps, GitFileOpsError, tracked_files};
 use anyhow::{Result, anyhow, bail};
 use clap::builder::TypedValueParser;
 use clap::{Arg, Command, error::ErrorKind};
@@ -122,6 +124,35 @@ impl TypedValueParser for FileCommitPairParser {
     }
 }
 
+/// A `previous..current` pair of commits, as given to `--range`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRange {
+    pub previous: String,
+    pub current: String,
+}
+
+impl FromStr for CommitRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((previous, current)) = s.split_once("..") else {
+            return Err(
+                "Format must be 'previous..current'. Example: def4567..abc1234".to_string(),
+            );
+        };
+        if previous.is_empty() || current.is_empty() || current.starts_with('.') {
+            return Err(
+                "Format must be 'previous..current'. Example: def4567..abc1234".to_string(),
+            );
+        }
+
+        Ok(CommitRange {
+            previous: previous.to_string(),
+            current: current.to_string(),
+        })
+    }
+}
+
 /// Represents a GitHub issue URL with an optional description and diff flag.
 /// Format: "<GITHUB_URL>/issues/<NUMBER>[::description][::no_diff]"
 /// Examples:
@@ -272,26 +303,82 @@ impl TypedValueParser for IssueUrlArgParser {
     }
 }
 
-/// Represents a file path with a required justification string
-/// Format: "file_path::justification"
-/// Example: "src/main.rs::This file contains the main entry point"
+/// Represents a file path with a required justification string and an optional commit hash
+/// Format: "file_path::justification[::commit]"
+/// Examples:
+///   "src/main.rs::This file contains the main entry point"
+///   "data/config.yaml::Settings used for the run::3f2a9c1"
 #[derive(Debug, Clone)]
 pub struct RelevantFileArg {
     pub file: PathBuf,
     pub justification: String,
+    /// The version of the file that is relevant, HEAD when omitted
+    pub commit: Option<String>,
+}
+
+impl RelevantFileArg {
+    /// Resolve the commit (HEAD when omitted) and check that the file exists at it.
+    /// Unknown files are reported with the closest tracked file as a suggestion.
+    pub fn resolve(self, git_info: &impl GitFileOps) -> Result<RelevantFile> {
+        let commitish = self.commit.as_deref().unwrap_or("HEAD");
+        let commit = git_info
+            .resolve_commit(commitish)
+            .map_err(|_| anyhow!("Commit '{commitish}' could not be resolved"))?;
+        let file = normalize_repo_path(&self.file);
+
+        match git_info.file_bytes_at_commit(&file, &commit) {
+            Ok(_) => {}
+            Err(GitFileOpsError::FileNotFoundAtCommit(_)) => {
+                let suggestion = tracked_files(git_info, &[])
+                    .ok()
+                    .and_then(|tracked| {
+                        let file = file.to_string_lossy();
+                        let tracked = tracked
+                            .iter()
+                            .map(|f| f.to_string_lossy().into_owned())
+                            .filter(|f| *f != file)
+                            .collect::<Vec<_>>();
+                        closest_match(&file, tracked.iter().map(String::as_str))
+                            .map(|closest| format!(". Did you mean '{closest}'?"))
+                    })
+                    .unwrap_or_default();
+                bail!(
+                    "File '{}' does not exist at {commitish}{suggestion}",
+                    file.display()
+                );
+            }
+            Err(e) => bail!("Could not read '{}' at {commitish}: {e}", file.display()),
+        }
+
+        Ok(RelevantFile {
+            file_name: file,
+            class: RelevantFileClass::File {
+                justification: self.justification,
+                commit: Some(commit),
+            },
+        })
+    }
 }
 
 impl FromStr for RelevantFileArg {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Split on "::" to separate file path from justification
-        let idx = s.find("::").ok_or_else(|| {
-            "Format must be 'file::justification'. The justification is required.".to_string()
+        // The file path ends at the first "::". The justification may itself contain "::"
+        // (e.g. "Calls dplyr::mutate"), so only a trailing hex segment is taken as the commit.
+        let (file_part, rest) = s.split_once("::").ok_or_else(|| {
+            "Format must be 'file::justification[::commit]'. The justification is required."
+                .to_string()
         })?;
-
-        let file_part = &s[..idx];
-        let justification = s[idx + 2..].trim();
+        let (justification, commit) = match rest.rsplit_once("::") {
+            Some((_, tail)) if tail.trim().is_empty() => {
+                return Err("Commit cannot be empty when given".to_string());
+            }
+            Some((justification, tail)) if is_commit_hash(tail.trim()) => {
+                (justification.trim(), Some(tail.trim()))
+            }
+            _ => (rest.trim(), None),
+        };
 
         if file_part.is_empty() {
             return Err("File path cannot be empty".to_string());
@@ -304,10 +391,16 @@ impl FromStr for RelevantFileArg {
         Ok(RelevantFileArg {
             file: PathBuf::from(file_part),
             justification: justification.to_string(),
+            commit: commit.map(str::to_string),
         })
     }
 }
 
+/// Whether `s` looks like an abbreviated or full commit hash
+fn is_commit_hash(s: &str) -> bool {
+    (4..=40).contains(&s.len()) && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// Custom parser for RelevantFileArg
 #[derive(Clone)]
 pub struct RelevantFileArgParser;
@@ -324,7 +417,7 @@ impl TypedValueParser for RelevantFileArgParser {
         let s = value.to_str().ok_or_else(|| {
             clap::Error::raw(
                 ErrorKind::InvalidUtf8,
-                "Invalid UTF-8 in file::justification specification",
+                "Invalid UTF-8 in file::justification[::commit] specification",
             )
         })?;
 
@@ -342,7 +435,7 @@ impl TypedValueParser for RelevantFileArgParser {
             );
             err.insert(
                 clap::error::ContextKind::ValidValue,
-                clap::error::ContextValue::String("file::justification".to_string()),
+                clap::error::ContextValue::String("file::justification[::commit]".to_string()),
             );
             err.insert(
                 clap::error::ContextKind::Usage,
@@ -356,6 +449,22 @@ impl TypedValueParser for RelevantFileArgParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::MockGitFileOps;
+
+    #[test]
+    fn test_commit_range_parsing() {
+        assert_eq!(
+            "def4567..abc1234".parse::<CommitRange>().unwrap(),
+            CommitRange {
+                previous: "def4567".to_string(),
+                current: "abc1234".to_string(),
+            }
+        );
+        assert!("abc1234".parse::<CommitRange>().is_err());
+        assert!("..abc1234".parse::<CommitRange>().is_err());
+        assert!("def4567..".parse::<CommitRange>().is_err());
+        assert!("def4567...abc1234".parse::<CommitRange>().is_err());
+    }
 
     #[test]
     fn test_issue_url_arg_parsing() {
@@ -458,4 +567,137 @@ mod tests {
         // Empty file path
         assert!("::some justification".parse::<RelevantFileArg>().is_err());
     }
+
+    #[test]
+    fn test_relevant_file_arg_arities() {
+        // file::justification, no commit
+        let arg: RelevantFileArg = "src/main.rs::Entry point".parse().unwrap();
+        assert_eq!(arg.commit, None);
+
+        // file::justification::commit
+        let arg: RelevantFileArg = "data/config.yaml::Settings used for the run:: 3f2a9c1 "
+            .parse()
+            .unwrap();
+        assert_eq!(arg.file, PathBuf::from("data/config.yaml"));
+        assert_eq!(arg.justification, "Settings used for the run");
+        assert_eq!(arg.commit.as_deref(), Some("3f2a9c1"));
+
+        // Empty commit
+        assert!(
+            "src/main.rs::Entry point::"
+                .parse::<RelevantFileArg>()
+                .is_err()
+        );
+        // Missing justification with a commit
+        assert!("src/main.rs::::abc1234".parse::<RelevantFileArg>().is_err());
+    }
+
+    #[test]
+    fn test_relevant_file_arg_justification_with_separator() {
+        let arg: RelevantFileArg = "R/summary.R::Calls dplyr::mutate".parse().unwrap();
+        assert_eq!(arg.file, PathBuf::from("R/summary.R"));
+        assert_eq!(arg.justification, "Calls dplyr::mutate");
+        assert_eq!(arg.commit, None);
+
+        let arg: RelevantFileArg = "R/summary.R::Calls dplyr::mutate::abc1234".parse().unwrap();
+        assert_eq!(arg.justification, "Calls dplyr::mutate");
+        assert_eq!(arg.commit.as_deref(), Some("abc1234"));
+
+        // A non-hash tail stays part of the justification
+        let arg: RelevantFileArg = "src/main.rs::Entry point::abc def".parse().unwrap();
+        assert_eq!(arg.justification, "Entry point::abc def");
+        assert_eq!(arg.commit, None);
+    }
+
+    fn git_with_files(files: &'static [&'static str]) -> MockGitFileOps {
+        let mut git = MockGitFileOps::new();
+        git.expect_resolve_commit()
+            .returning(|commitish| match commitish {
+                "HEAD" => Ok(ObjectId::from_str(HEAD).unwrap()),
+                "2222222" => Ok(ObjectId::from_str(OLD).unwrap()),
+                other => Err(GitFileOpsError::CommitNotFound(other.to_string())),
+            });
+        git.expect_file_bytes_at_commit()
+            .returning(move |file, commit| {
+                let exists = files.contains(&file.to_str().unwrap());
+                if exists && commit.to_string() == HEAD {
+                    Ok(Vec::new())
+                } else {
+                    Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))
+                }
+            });
+        git.expect_list_tree_entries().returning(move |path| {
+            let mut entries = files
+                .iter()
+                .filter_map(|f| {
+                    let rest = if path.is_empty() {
+                        *f
+                    } else {
+                        f.strip_prefix(path)?.strip_prefix('/')?
+                    };
+                    Some(match rest.split_once('/') {
+                        Some((dir, _)) => (dir.to_string(), true),
+                        None => (rest.to_string(), false),
+                    })
+                })
+                .collect::<Vec<_>>();
+            entries.dedup();
+            Ok(entries)
+        });
+        git
+    }
+
+    const HEAD: &str = "1111111111111111111111111111111111111111";
+    const OLD: &str = "2222222222222222222222222222222222222222";
+
+    #[test]
+    fn test_relevant_file_arg_resolves_head() {
+        let git = git_with_files(&["data/config.yaml", "src/main.rs"]);
+        let arg: RelevantFileArg = "./data/config.yaml::Settings".parse().unwrap();
+
+        let relevant = arg.resolve(&git).unwrap();
+
+        assert_eq!(relevant.file_name, PathBuf::from("data/config.yaml"));
+        match relevant.class {
+            RelevantFileClass::File {
+                justification,
+                commit,
+            } => {
+                assert_eq!(justification, "Settings");
+                assert_eq!(commit, Some(ObjectId::from_str(HEAD).unwrap()));
+            }
+            other => panic!("unexpected class {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_relevant_file_arg_suggests_tracked_file() {
+        let git = git_with_files(&["data/config.yaml", "src/main.rs"]);
+        let arg: RelevantFileArg = "data/confg.yaml::Settings".parse().unwrap();
+
+        let err = arg.resolve(&git).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "File 'data/confg.yaml' does not exist at HEAD. Did you mean 'data/config.yaml'?"
+        );
+    }
+
+    #[test]
+    fn test_relevant_file_arg_checks_the_given_commit() {
+        let git = git_with_files(&["data/config.yaml"]);
+
+        // Exists at HEAD but not at the older commit
+        let arg: RelevantFileArg = "data/config.yaml::Settings::2222222".parse().unwrap();
+        assert_eq!(
+            arg.resolve(&git).unwrap_err().to_string(),
+            "File 'data/config.yaml' does not exist at 2222222"
+        );
+
+        let arg: RelevantFileArg = "data/config.yaml::Settings::dead".parse().unwrap();
+        assert_eq!(
+            arg.resolve(&git).unwrap_err().to_string(),
+            "Commit 'dead' could not be resolved"
+        );
+    }
 }
@@ -0,0 +1,307 @@
+use anyhow::{Result, anyhow};
+use octocrab::models::Milestone;
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    ApprovalWindow, ArchiveFile, Diagnostics, DiskCache, GitCommitOps, GitHelpers, GitHubReader,
+    GitRepository, GitStatusOps, HttpDownloader, IssueInformation, MilestoneSnapshot, SigningKeys,
+    get_milestone_issue_information,
+};
+
+use super::archive::{archive_files_from_threads, check_duplicate_files};
+
+/// Record contents and archive files of a milestone, built from the same issue threads
+#[derive(Debug)]
+pub struct MilestoneFinalization {
+    pub milestone: Milestone,
+    pub issue_information: HashMap<String, Vec<IssueInformation>>,
+    pub archive_files: Vec<ArchiveFile>,
+}
+
+/// Settings of `milestone finalize`
+#[derive(Debug, Clone)]
+pub struct MilestoneFinalizeOptions {
+    /// Title of the milestone to finalize
+    pub milestone: String,
+    /// Archive the files of unapproved issues at their latest commit
+    pub include_unapproved: bool,
+    /// Put every archived file at the archive root
+    pub flatten: bool,
+}
+
+/// Fetch the milestone's issues and comments once, and build both its record and its archive
+///
+/// Unapproved issues are included in the record but only archived with `include_unapproved`,
+/// matching `milestone record` and `milestone archive`.
+pub async fn finalize_milestone(
+    options: &MilestoneFinalizeOptions,
+    milestones: &[Milestone],
+    signing_keys: &SigningKeys,
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitCommitOps + GitStatusOps + GitRepository + GitHelpers),
+    http_downloader: &impl HttpDownloader,
+    staging_dir: &Path,
+) -> Result<MilestoneFinalization> {
+    let milestone = milestones
+        .iter()
+        .find(|m| m.title == options.milestone)
+        .ok_or(anyhow!("Milestone '{}' not found", options.milestone))?;
+
+    let snapshot = MilestoneSnapshot::build(&[milestone], git_info, cache).await?;
+    check_duplicate_files(&snapshot)?;
+    let threads = snapshot.threads(git_info, cache)?;
+
+    let issue_information = get_milestone_issue_information(
+        &threads,
+        cache,
+        signing_keys,
+        git_info,
+        http_downloader,
+        staging_dir,
+        &mut Diagnostics::new(),
+    )
+    .await?;
+    let archive_files = archive_files_from_threads(
+        threads.iter().map(|t| &t.thread),
+        options.include_unapproved,
+        &ApprovalWindow::default(),
+        options.flatten,
+    )?;
+
+    Ok(MilestoneFinalization {
+        milestone: milestone.clone(),
+        issue_information,
+        archive_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Configuration, GitComment, RecordOptions, UreqDownloader,
+        cli::archive::milestone_archive_files,
+        record,
+        test_utils::{FakeGit, create_test_issue, create_test_milestone},
+        utils::MockEnvProvider,
+    };
+    use std::path::PathBuf;
+
+    const INITIAL_COMMIT: &str = "1234567890abcdef1234567890abcdef12345678";
+    const APPROVED_COMMIT: &str = "abcdef1234567890abcdef1234567890abcdef12";
+
+    /// Serves a milestone with an approved and an unapproved issue
+    fn fixture_git() -> FakeGit {
+        let body = format!("git branch: main\ninitial qc commit: {INITIAL_COMMIT}\n");
+        FakeGit::new()
+            .with_issues(vec![
+                create_test_issue("owner", "repo", 1, "src/model.R", &body, Some(1), "closed"),
+                create_test_issue("owner", "repo", 2, "src/plots.R", &body, Some(1), "open"),
+            ])
+            .with_issue_comments(
+                1,
+                vec![GitComment {
+                    body: format!("# QC Approval\n\napproved qc commit: {APPROVED_COMMIT}"),
+                    author_login: "reviewer1".to_string(),
+                    created_at: chrono::Utc::now(),
+                    html: None,
+                }],
+            )
+            .with_user_name("octocat", "The Octocat")
+            .with_head(APPROVED_COMMIT)
+            .with_commits(&[APPROVED_COMMIT, INITIAL_COMMIT])
+            .with_file_commits("src/model.R", &[APPROVED_COMMIT, INITIAL_COMMIT])
+            .with_file_commits("src/plots.R", &[APPROVED_COMMIT, INITIAL_COMMIT])
+            .with_missing_file("src/model.R")
+            .with_missing_file("src/plots.R")
+    }
+
+    fn milestone() -> Milestone {
+        create_test_milestone("owner", "repo", 1, "v1.0", Some("Test milestone"), "open")
+    }
+
+    /// Finalize `milestone` without the files of unapproved issues
+    fn options(milestone: &str, flatten: bool) -> MilestoneFinalizeOptions {
+        MilestoneFinalizeOptions {
+            milestone: milestone.to_string(),
+            include_unapproved: false,
+            flatten,
+        }
+    }
+
+    fn render_record(
+        issue_information: &HashMap<String, Vec<IssueInformation>>,
+        git_info: &FakeGit,
+        staging_dir: &Path,
+    ) -> String {
+        let mut env = MockEnvProvider::new();
+        env.expect_var().returning(|key| match key {
+            "USER" => Ok("tester".to_string()),
+            "GHQC_RECORD_DATE" => Ok("January 01, 2025".to_string()),
+            _ => Err(std::env::VarError::NotPresent),
+        });
+
+        record(
+            &[milestone()],
+            issue_information,
+            &Configuration::default(),
+            git_info,
+            &env,
+            &RecordOptions::default(),
+            staging_dir,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_finalize_matches_independent_record_and_archive() {
+        let staging_dir = tempfile::tempdir().unwrap();
+        let milestone = milestone();
+
+        // `milestone record` followed by `milestone archive`
+        let independent_git = fixture_git();
+        let snapshot = MilestoneSnapshot::build(&[&milestone], &independent_git, None)
+            .await
+            .unwrap();
+        let threads = snapshot.threads(&independent_git, None).unwrap();
+        let record_information = get_milestone_issue_information(
+            &threads,
+            None,
+            &SigningKeys::default(),
+            &independent_git,
+            &UreqDownloader::new(),
+            staging_dir.path(),
+            &mut Diagnostics::new(),
+        )
+        .await
+        .unwrap();
+        let archive_files = milestone_archive_files(
+            &[&milestone],
+            false,
+            &ApprovalWindow::default(),
+            true,
+            false,
+            &independent_git,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let finalize_git = fixture_git();
+        let finalization = finalize_milestone(
+            &options("v1.0", true),
+            std::slice::from_ref(&milestone),
+            &SigningKeys::default(),
+            None,
+            &finalize_git,
+            &UreqDownloader::new(),
+            staging_dir.path(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            render_record(
+                &finalization.issue_information,
+                &finalize_git,
+                staging_dir.path()
+            ),
+            render_record(&record_information, &independent_git, staging_dir.path())
+        );
+        assert_eq!(finalization.issue_information["v1.0"].len(), 2);
+
+        assert_eq!(
+            serde_json::to_value(&finalization.archive_files).unwrap(),
+            serde_json::to_value(&archive_files).unwrap()
+        );
+        // Only the approved issue is archived, at its approved commit
+        assert_eq!(archive_files.len(), 1);
+        assert_eq!(archive_files[0].archive_file, PathBuf::from("model.R"));
+        assert_eq!(archive_files[0].commit.to_string(), APPROVED_COMMIT);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_fetches_comments_once() {
+        let staging_dir = tempfile::tempdir().unwrap();
+        let milestone = milestone();
+
+        let independent_git = fixture_git();
+        let snapshot = MilestoneSnapshot::build(&[&milestone], &independent_git, None)
+            .await
+            .unwrap();
+        snapshot.threads(&independent_git, None).unwrap();
+        milestone_archive_files(
+            &[&milestone],
+            false,
+            &ApprovalWindow::default(),
+            false,
+            false,
+            &independent_git,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(independent_git.comment_fetches(), 4);
+
+        let finalize_git = fixture_git();
+        finalize_milestone(
+            &options("v1.0", false),
+            &[milestone],
+            &SigningKeys::default(),
+            None,
+            &finalize_git,
+            &UreqDownloader::new(),
+            staging_dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(finalize_git.comment_fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_looks_up_each_user_once() {
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        // Neither the author of both issues nor the approver is an assignable user
+        let git_info = fixture_git();
+        let finalization = finalize_milestone(
+            &options("v1.0", false),
+            &[milestone()],
+            &SigningKeys::default(),
+            None,
+            &git_info,
+            &UreqDownloader::new(),
+            staging_dir.path(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(git_info.user_lookups(), vec!["octocat", "reviewer1"]);
+        let issues = &finalization.issue_information["v1.0"];
+        assert_eq!(issues.len(), 2);
+        assert!(
+            issues
+                .iter()
+                .all(|issue| issue.created_by == "The Octocat (octocat)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finalize_unknown_milestone() {
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let err = finalize_milestone(
+            &options("v9.0", false),
+            &[milestone()],
+            &SigningKeys::default(),
+            None,
+            &fixture_git(),
+            &UreqDownloader::new(),
+            staging_dir.path(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "Milestone 'v9.0' not found");
+    }
+}
@@ -1,19 +1,267 @@
 use anyhow::{Result, bail};
 use gix::ObjectId;
 use inquire::{
-    Autocomplete, Confirm, CustomUserError, MultiSelect, Select, Text, validator::Validation,
+    Autocomplete, Confirm, CustomUserError, InquireError, MultiSelect, Select, Text,
+    validator::Validation,
 };
+#[cfg(test)]
+use mockall::automock;
 use octocrab::models::{Milestone, issues::Issue};
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fmt, fs};
 
-use crate::GitHubWriter;
+use crate::message;
 use crate::{
-    Configuration, ContextPosition, QCContext, configuration::Checklist,
-    create::normalize_collaborator_entry, git::RepoUser, issue::IssueThread,
+    ChecklistItem, Configuration, ContextPosition, QCContext,
+    configuration::Checklist,
+    create::normalize_collaborator_entry,
+    git::{FileLastCommit, Permission, RepoUser},
+    issue::IssueThread,
 };
+use crate::{GitHubWriter, MilestoneState};
+
+/// Exit code for a cancelled interactive session, matching an exit by SIGINT
+pub const CANCELLED_EXIT_CODE: u8 = 130;
+
+/// Answer to an interactive prompt. Pressing Esc or Ctrl-C yields `Cancelled` instead of an error
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptOutcome<T> {
+    Value(T),
+    Cancelled,
+}
+
+impl<T> PromptOutcome<T> {
+    /// Convert the result of an inquire prompt, treating Esc and Ctrl-C as a cancellation
+    pub fn from_inquire(result: std::result::Result<T, InquireError>) -> Result<Self> {
+        match result {
+            Ok(value) => Ok(Self::Value(value)),
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                Ok(Self::Cancelled)
+            }
+            Err(e) => Err(anyhow::anyhow!("Prompt failed: {e}")),
+        }
+    }
+
+    /// The answer, or a [`PromptCancelled`] error to propagate out of the interactive flow
+    pub fn value(self) -> std::result::Result<T, PromptCancelled> {
+        match self {
+            Self::Value(value) => Ok(value),
+            Self::Cancelled => Err(PromptCancelled),
+        }
+    }
+}
+
+/// The user cancelled an interactive flow. Nothing has been written to GitHub
+#[derive(Debug, thiserror::Error)]
+#[error("Cancelled")]
+pub struct PromptCancelled;
+
+/// Unwrap an inquire answer within a prompt helper, returning `PromptOutcome::Cancelled` from
+/// the helper when the prompt is cancelled
+macro_rules! answer {
+    ($prompt:expr) => {
+        match PromptOutcome::from_inquire($prompt)? {
+            PromptOutcome::Value(value) => value,
+            PromptOutcome::Cancelled => return Ok(PromptOutcome::Cancelled),
+        }
+    };
+}
+
+/// Unwrap an inquire answer within an interactive flow, failing with [`PromptCancelled`] when
+/// the prompt is cancelled
+pub(crate) fn ask<T>(result: std::result::Result<T, InquireError>) -> Result<T> {
+    Ok(PromptOutcome::from_inquire(result)?.value()?)
+}
+
+/// Restores the terminal when dropped, so a cancelled or panicking prompt never leaves the
+/// terminal in raw mode or with a hidden cursor
+pub struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Only touch the terminal when a prompt left it in raw mode, so piped output stays clean
+        if crossterm::terminal::is_raw_mode_enabled().unwrap_or(false) {
+            let _ = crossterm::terminal::disable_raw_mode();
+            let _ = crossterm::execute!(std::io::stdout(), crossterm::cursor::Show);
+        }
+    }
+}
+
+/// The prompts used by the interactive flows
+///
+/// [`InquirePrompter`] asks the user in the terminal; tests script the answers instead.
+#[cfg_attr(test, automock)]
+pub trait Prompter {
+    fn milestone(&self, milestones: Vec<Milestone>) -> Result<PromptOutcome<MilestoneStatus>>;
+    fn existing_milestone(&self, milestones: &[Milestone]) -> Result<PromptOutcome<Milestone>>;
+    /// Prompt for a file among `files`, each paired with when it was last committed
+    fn file(&self, files: &[FileLastCommit], issues: &[Issue]) -> Result<PromptOutcome<PathBuf>>;
+    fn checklist(&self, configuration: &Configuration) -> Result<PromptOutcome<Checklist>>;
+    /// Prompt for assignees, listing the `suggested` logins first
+    fn assignees(
+        &self,
+        repo_users: &[RepoUser],
+        permissions: &[(String, Permission)],
+        suggested: &[String],
+    ) -> Result<PromptOutcome<Vec<String>>>;
+    fn collaborators(&self, defaults: &[String]) -> Result<PromptOutcome<Vec<String>>>;
+    fn want_relevant_files(&self) -> Result<PromptOutcome<bool>>;
+    fn relevant_file_path(
+        &self,
+        current_dir: &Path,
+        all_issues: &[Issue],
+    ) -> Result<PromptOutcome<PathBuf>>;
+    fn relevant_file_source(
+        &self,
+        file_path: &Path,
+        matching_issues: &[Issue],
+        current_milestone_number: u64,
+    ) -> Result<PromptOutcome<Option<Issue>>>;
+    fn relevant_file_class(&self) -> Result<PromptOutcome<RelevantFileClassType>>;
+    fn include_previous_qc_diff(&self) -> Result<PromptOutcome<bool>>;
+    fn relevant_description(&self, required: bool) -> Result<PromptOutcome<Option<String>>>;
+    fn add_another_relevant_file(&self) -> Result<PromptOutcome<bool>>;
+    fn issue(&self, issues: &[Issue]) -> Result<PromptOutcome<Issue>>;
+    fn commits(
+        &self,
+        issue_thread: &IssueThread,
+    ) -> Result<PromptOutcome<(ObjectId, Option<ObjectId>)>>;
+    fn single_commit(
+        &self,
+        issue_thread: &IssueThread,
+        prompt_text: &str,
+        default_position: usize,
+    ) -> Result<PromptOutcome<ObjectId>>;
+    fn note(&self) -> Result<PromptOutcome<Option<String>>>;
+    fn unapprove_reason(&self) -> Result<PromptOutcome<String>>;
+    /// Prompt for the new assignees of an issue, with its `current` assignees pre-selected
+    fn reassign(
+        &self,
+        repo_users: &[RepoUser],
+        current: &[String],
+    ) -> Result<PromptOutcome<Vec<String>>>;
+    /// Prompt for the checklist items to check, with the currently checked ones pre-selected.
+    /// Returns the indices of the selected items
+    fn checklist_items(&self, items: &[ChecklistItem]) -> Result<PromptOutcome<Vec<usize>>>;
+    fn confirm(&self, message: &str, default: bool) -> Result<PromptOutcome<bool>>;
+}
+
+/// Prompts the user in the terminal
+pub struct InquirePrompter;
+
+impl Prompter for InquirePrompter {
+    fn milestone(&self, milestones: Vec<Milestone>) -> Result<PromptOutcome<MilestoneStatus>> {
+        prompt_milestone(milestones)
+    }
+
+    fn existing_milestone(&self, milestones: &[Milestone]) -> Result<PromptOutcome<Milestone>> {
+        prompt_existing_milestone(milestones)
+    }
+
+    fn file(&self, files: &[FileLastCommit], issues: &[Issue]) -> Result<PromptOutcome<PathBuf>> {
+        prompt_file(files, issues)
+    }
+
+    fn checklist(&self, configuration: &Configuration) -> Result<PromptOutcome<Checklist>> {
+        prompt_checklist(configuration)
+    }
+
+    fn assignees(
+        &self,
+        repo_users: &[RepoUser],
+        permissions: &[(String, Permission)],
+        suggested: &[String],
+    ) -> Result<PromptOutcome<Vec<String>>> {
+        prompt_assignees(repo_users, permissions, suggested)
+    }
+
+    fn collaborators(&self, defaults: &[String]) -> Result<PromptOutcome<Vec<String>>> {
+        prompt_collaborators(defaults)
+    }
+
+    fn want_relevant_files(&self) -> Result<PromptOutcome<bool>> {
+        prompt_want_relevant_files()
+    }
+
+    fn relevant_file_path(
+        &self,
+        current_dir: &Path,
+        all_issues: &[Issue],
+    ) -> Result<PromptOutcome<PathBuf>> {
+        prompt_relevant_file_path(current_dir, all_issues)
+    }
+
+    fn relevant_file_source(
+        &self,
+        file_path: &Path,
+        matching_issues: &[Issue],
+        current_milestone_number: u64,
+    ) -> Result<PromptOutcome<Option<Issue>>> {
+        prompt_relevant_file_source(file_path, matching_issues, current_milestone_number)
+    }
+
+    fn relevant_file_class(&self) -> Result<PromptOutcome<RelevantFileClassType>> {
+        prompt_relevant_file_class()
+    }
+
+    fn include_previous_qc_diff(&self) -> Result<PromptOutcome<bool>> {
+        prompt_include_previous_qc_diff()
+    }
+
+    fn relevant_description(&self, required: bool) -> Result<PromptOutcome<Option<String>>> {
+        prompt_relevant_description(required)
+    }
+
+    fn add_another_relevant_file(&self) -> Result<PromptOutcome<bool>> {
+        prompt_add_another_relevant_file()
+    }
+
+    fn issue(&self, issues: &[Issue]) -> Result<PromptOutcome<Issue>> {
+        prompt_issue(issues)
+    }
+
+    fn commits(
+        &self,
+        issue_thread: &IssueThread,
+    ) -> Result<PromptOutcome<(ObjectId, Option<ObjectId>)>> {
+        prompt_commits(issue_thread)
+    }
+
+    fn single_commit(
+        &self,
+        issue_thread: &IssueThread,
+        prompt_text: &str,
+        default_position: usize,
+    ) -> Result<PromptOutcome<ObjectId>> {
+        prompt_single_commit(issue_thread, prompt_text, default_position)
+    }
+
+    fn note(&self) -> Result<PromptOutcome<Option<String>>> {
+        prompt_note()
+    }
+
+    fn unapprove_reason(&self) -> Result<PromptOutcome<String>> {
+        prompt_unapprove_reason()
+    }
+
+    fn reassign(
+        &self,
+        repo_users: &[RepoUser],
+        current: &[String],
+    ) -> Result<PromptOutcome<Vec<String>>> {
+        prompt_reassign(repo_users, current)
+    }
+
+    fn checklist_items(&self, items: &[ChecklistItem]) -> Result<PromptOutcome<Vec<usize>>> {
+        prompt_checklist_items(items)
+    }
+
+    fn confirm(&self, message: &str, default: bool) -> Result<PromptOutcome<bool>> {
+        prompt_confirm(message, default)
+    }
+}
 
 /// Enum representing the type of relevant file class for interactive selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,7 +310,7 @@ impl MilestoneStatus {
 }
 
 /// Modular milestone selection - allows creation of new milestones
-pub fn prompt_milestone(milestones: Vec<Milestone>) -> Result<MilestoneStatus> {
+pub fn prompt_milestone(milestones: Vec<Milestone>) -> Result<PromptOutcome<MilestoneStatus>> {
     let mut options = vec!["📝 Create new milestone".to_string()];
     let mut open_milestones: Vec<&Milestone> = milestones
         .iter()
@@ -77,40 +325,40 @@ pub fn prompt_milestone(milestones: Vec<Milestone>) -> Result<MilestoneStatus> {
     options.extend(milestone_titles);
 
     if options.len() == 1 {
-        println!("ℹ️  No open milestones found. You'll need to create a new one.");
+        message!("ℹ️  No open milestones found. You'll need to create a new one.");
     }
 
-    let selection = Select::new("Select or create a milestone:", options)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let selection = answer!(searchable_select("Select or create a milestone:", options).prompt());
 
     if selection.starts_with("📝") {
         let existing_names: Vec<String> = milestones.iter().map(|m| m.title.clone()).collect();
 
-        let new_milestone = Text::new("Enter new milestone name:")
-            .with_validator(move |input: &str| {
-                let trimmed = input.trim();
-                if trimmed.is_empty() {
-                    Ok(Validation::Invalid("Milestone name cannot be empty".into()))
-                } else if existing_names.contains(&trimmed.to_string()) {
-                    Ok(Validation::Invalid(
-                        format!(
-                            "Milestone '{}' already exists. Please choose a different name.",
-                            trimmed
-                        )
-                        .into(),
-                    ))
-                } else {
-                    Ok(Validation::Valid)
-                }
-            })
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+        let new_milestone = answer!(
+            Text::new("Enter new milestone name:")
+                .with_validator(move |input: &str| {
+                    let trimmed = input.trim();
+                    if trimmed.is_empty() {
+                        Ok(Validation::Invalid("Milestone name cannot be empty".into()))
+                    } else if existing_names.contains(&trimmed.to_string()) {
+                        Ok(Validation::Invalid(
+                            format!(
+                                "Milestone '{}' already exists. Please choose a different name.",
+                                trimmed
+                            )
+                            .into(),
+                        ))
+                    } else {
+                        Ok(Validation::Valid)
+                    }
+                })
+                .prompt()
+        );
 
-        let description = Text::new("Enter milestone description (optional):")
-            .with_default("")
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+        let description = answer!(
+            Text::new("Enter milestone description (optional):")
+                .with_default("")
+                .prompt()
+        );
 
         let description = if description.trim().is_empty() {
             None
@@ -118,10 +366,10 @@ pub fn prompt_milestone(milestones: Vec<Milestone>) -> Result<MilestoneStatus> {
             Some(description.trim().to_string())
         };
 
-        Ok(MilestoneStatus::New(
+        Ok(PromptOutcome::Value(MilestoneStatus::New(
             new_milestone.trim().to_string(),
             description,
-        ))
+        )))
     } else {
         // Find the selected milestone and return its ID
         let milestone_title = selection.strip_prefix("🎯 ").unwrap_or(&selection);
@@ -129,12 +377,12 @@ pub fn prompt_milestone(milestones: Vec<Milestone>) -> Result<MilestoneStatus> {
             .into_iter()
             .find(|m| m.title == milestone_title)
             .expect("selected milestone to exist");
-        Ok(MilestoneStatus::Existing(milestone))
+        Ok(PromptOutcome::Value(MilestoneStatus::Existing(milestone)))
     }
 }
 
 /// Modular milestone selection - only existing milestones (for comments)
-pub fn prompt_existing_milestone(milestones: &[Milestone]) -> Result<Milestone> {
+pub fn prompt_existing_milestone(milestones: &[Milestone]) -> Result<PromptOutcome<Milestone>> {
     let mut open_milestones: Vec<_> = milestones
         .iter()
         .filter(|m| m.state.as_deref() == Some("open"))
@@ -152,156 +400,121 @@ pub fn prompt_existing_milestone(milestones: &[Milestone]) -> Result<Milestone>
         .map(|m| format!("🎯 {}", m.title))
         .collect();
 
-    let selection = Select::new("Select a milestone:", milestone_titles)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let selection = answer!(select_one(
+        "Select a milestone:",
+        "milestone",
+        milestone_titles
+    )?);
 
     let milestone_title = selection.strip_prefix("🎯 ").unwrap_or(&selection);
     if let Some(milestone) = milestones.iter().find(|m| m.title == milestone_title) {
-        Ok(milestone.clone())
+        Ok(PromptOutcome::Value(milestone.clone()))
     } else {
         Err(anyhow::anyhow!("Selected milestone not found"))
     }
 }
 
-pub fn prompt_file(current_dir: &PathBuf, issues: &[Issue]) -> Result<PathBuf> {
-    // Extract file paths from existing issues to mark as unavailable
-    let existing_issue_files: Vec<String> =
-        issues.iter().map(|issue| issue.title.clone()).collect();
+/// Select a milestone in `state`, most recent first, for `milestone close`, `reopen` or `edit`
+pub fn prompt_milestone_in_state(
+    milestones: &[Milestone],
+    state: MilestoneState,
+) -> Result<PromptOutcome<Milestone>> {
+    let mut candidates: Vec<_> = milestones
+        .iter()
+        .filter(|m| MilestoneState::of(m) == state)
+        .collect();
+    candidates.sort_by_key(|m| std::cmp::Reverse(m.number));
 
-    #[derive(Clone)]
-    struct FileCompleter {
-        current_dir: PathBuf,
-        existing_issue_files: Vec<String>,
+    if candidates.is_empty() {
+        return Err(anyhow::anyhow!("No {} milestones found", state.as_str()));
     }
 
-    impl Autocomplete for FileCompleter {
-        fn get_suggestions(
-            &mut self,
-            input: &str,
-        ) -> std::result::Result<Vec<String>, CustomUserError> {
-            let input = input.trim();
-            let mut suggestions = Vec::new();
-
-            let (base_path, search_term) = if input.contains('/') {
-                let mut parts = input.rsplitn(2, '/');
-                let filename = parts.next().unwrap_or("");
-                let dir_path = parts.next().unwrap_or("");
-                (self.current_dir.join(dir_path), filename)
-            } else {
-                (self.current_dir.clone(), input)
-            };
-
-            if let Ok(entries) = fs::read_dir(&base_path) {
-                let mut files = Vec::new();
-                let mut dirs = Vec::new();
+    let milestone_titles: Vec<String> = candidates.iter().map(|m| m.title.clone()).collect();
+    let selection = answer!(select_one(
+        "Select a milestone:",
+        "milestone",
+        milestone_titles
+    )?);
+
+    candidates
+        .into_iter()
+        .find(|m| m.title == selection)
+        .map(|m| PromptOutcome::Value(m.clone()))
+        .ok_or(anyhow::anyhow!("Selected milestone not found"))
+}
 
-                for entry in entries.flatten() {
-                    if let Ok(name) = entry.file_name().into_string() {
-                        // Skip hidden files/directories
-                        if name.starts_with('.') {
-                            continue;
-                        }
+/// Lists longer than this are searched by typing rather than scrolled
+const SEARCH_THRESHOLD: usize = 20;
 
-                        if name.to_lowercase().starts_with(&search_term.to_lowercase()) {
-                            let relative_path = if input.contains('/') {
-                                let dir_part = input.rsplitn(2, '/').nth(1).unwrap_or("");
-                                format!("{}/{}", dir_part, name)
-                            } else {
-                                name.clone()
-                            };
+/// A select prompt, fuzzy searchable once `options` are too many to scroll through
+fn searchable_select<'a>(message: &'a str, options: Vec<String>) -> Select<'a, String> {
+    if options.len() > SEARCH_THRESHOLD {
+        Select::new(message, options)
+            .with_page_size(15)
+            .with_help_message("type to search, ↑↓ to move, enter to select")
+    } else {
+        Select::new(message, options).without_filtering()
+    }
+}
 
-                            if entry.path().is_file() {
-                                // Check if this file already has an issue
-                                if self.existing_issue_files.contains(&relative_path) {
-                                    // Mark as unavailable with gray styling
-                                    files.push(format!("🚫 {} (already has issue)", relative_path));
-                                } else {
-                                    files.push(relative_path);
-                                }
-                            } else if entry.path().is_dir() {
-                                // Add trailing slash to indicate directory
-                                dirs.push(format!("{}/", relative_path));
-                            }
+/// Select one of `options`, naming them `what` in messages. A single option is confirmed
+/// instead of listed, and declining it cancels the prompt
+fn select_one(
+    message: &str,
+    what: &str,
+    mut options: Vec<String>,
+) -> Result<std::result::Result<String, InquireError>> {
+    match options.len() {
+        0 => bail!("No {what} available to select"),
+        1 => {
+            let option = options.remove(0);
+            Ok(
+                Confirm::new(&format!("Only one {what} is available: {option}. Use it?"))
+                    .with_default(true)
+                    .prompt()
+                    .and_then(|confirmed| {
+                        if confirmed {
+                            Ok(option)
+                        } else {
+                            Err(InquireError::OperationCanceled)
                         }
-                    }
-                }
-
-                // Sort directories and files separately, then combine
-                dirs.sort();
-                files.sort();
-                suggestions.extend(dirs);
-                suggestions.extend(files);
-            }
-
-            Ok(suggestions)
-        }
-
-        fn get_completion(
-            &mut self,
-            _input: &str,
-            highlighted_suggestion: Option<String>,
-        ) -> std::result::Result<inquire::autocompletion::Replacement, CustomUserError> {
-            Ok(match highlighted_suggestion {
-                Some(suggestion) => {
-                    // If the suggestion is marked as unavailable, don't allow completion
-                    if suggestion.starts_with("🚫 ") {
-                        inquire::autocompletion::Replacement::None
-                    } else {
-                        inquire::autocompletion::Replacement::Some(suggestion)
-                    }
-                }
-                None => inquire::autocompletion::Replacement::None,
-            })
+                    }),
+            )
         }
+        _ => Ok(searchable_select(message, options).prompt()),
     }
+}
 
-    let file_completer = FileCompleter {
-        current_dir: current_dir.clone(),
-        existing_issue_files: existing_issue_files.clone(),
-    };
+/// The files that can be picked for a new issue, most recently committed first
+///
+/// Files that already have an issue in the milestone are left out. Files without a known commit
+/// time (before the cut-off of a shallow clone) are listed last, in path order.
+pub(crate) fn file_choices(files: &[FileLastCommit], issues: &[Issue]) -> Vec<String> {
+    let mut choices: Vec<_> = files
+        .iter()
+        .map(|(file, time)| (file.to_string_lossy().replace('\\', "/"), *time))
+        .filter(|(file, _)| !issues.iter().any(|issue| &issue.title == file))
+        .collect();
+    choices.sort_by(|(a_file, a_time), (b_file, b_time)| {
+        b_time.cmp(a_time).then_with(|| a_file.cmp(b_file))
+    });
+    choices.into_iter().map(|(file, _)| file).collect()
+}
 
-    let existing_files_for_validator = existing_issue_files.clone();
-    let validator_dir = current_dir.clone();
-    let file_path =
-        Text::new("📁 Enter file path (Tab for autocomplete, directories shown with /):")
-            .with_autocomplete(file_completer)
-            .with_validator(move |input: &str| {
-                let trimmed = input.trim();
-                // Handle case where user somehow enters the grayed-out format
-                if trimmed.starts_with("🚫 ") {
-                    return Ok(Validation::Invalid(
-                        "This file already has a corresponding issue in the milestone. Please select a different file.".into(),
-                    ));
-                }
-                if trimmed.is_empty() {
-                    Ok(Validation::Invalid("File path cannot be empty".into()))
-                } else if trimmed.ends_with('/') {
-                    Ok(Validation::Invalid(
-                        "Cannot select a directory. Please select a file.".into(),
-                    ))
-                } else {
-                    let path = validator_dir.join(trimmed);
-                    if path.exists() && path.is_dir() {
-                        Ok(Validation::Invalid(
-                            "Path must be a file, not a directory".into(),
-                        ))
-                    } else if existing_files_for_validator.contains(&trimmed.to_string()) {
-                        Ok(Validation::Invalid(
-                            "This file already has a corresponding issue in the milestone. Please select a different file.".into(),
-                        ))
-                    } else {
-                        Ok(Validation::Valid)
-                    }
-                }
-            })
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+pub fn prompt_file(files: &[FileLastCommit], issues: &[Issue]) -> Result<PromptOutcome<PathBuf>> {
+    let choices = file_choices(files, issues);
+    if choices.is_empty() {
+        bail!(
+            "No files are available for a new issue: every file on the branch already has an issue in the milestone"
+        );
+    }
+
+    let file = answer!(select_one("📁 Select a file:", "file", choices)?);
 
-    Ok(PathBuf::from(file_path.trim()))
+    Ok(PromptOutcome::Value(PathBuf::from(file)))
 }
 
-pub fn prompt_checklist(configuration: &Configuration) -> Result<Checklist> {
+pub fn prompt_checklist(configuration: &Configuration) -> Result<PromptOutcome<Checklist>> {
     let mut checklist_names: Vec<String> = configuration.checklists.keys().cloned().collect();
     checklist_names.sort();
 
@@ -314,20 +527,24 @@ pub fn prompt_checklist(configuration: &Configuration) -> Result<Checklist> {
         .map(|name| format!("📋 {}", name))
         .collect();
 
-    let selection = Select::new("Select a checklist:", formatted_options)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let selection = answer!(Select::new("Select a checklist:", formatted_options).prompt());
 
     // Remove the emoji prefix
     let sel = selection.strip_prefix("📋 ").unwrap_or(&selection);
 
-    Ok(configuration.checklists[sel].clone())
+    Ok(PromptOutcome::Value(configuration.checklists[sel].clone()))
 }
 
-pub fn prompt_assignees(repo_users: &[RepoUser]) -> Result<Vec<String>> {
+pub fn prompt_assignees(
+    repo_users: &[RepoUser],
+    permissions: &[(String, Permission)],
+    suggested: &[String],
+) -> Result<PromptOutcome<Vec<String>>> {
     #[derive(Clone)]
     struct UserCompleter {
         users: Vec<RepoUser>,
+        permissions: Vec<(String, Permission)>,
+        suggested: Vec<String>,
     }
 
     impl Autocomplete for UserCompleter {
@@ -348,14 +565,27 @@ pub fn prompt_assignees(repo_users: &[RepoUser]) -> Result<Vec<String>> {
                     .unwrap_or(false);
 
                 if matches_login || matches_name {
-                    suggestions.push(user.to_string());
+                    // Annotate with the permission level so read-only users stand out
+                    let mut suggestion = match self
+                        .permissions
+                        .iter()
+                        .find(|(login, _)| login == &user.login)
+                    {
+                        Some((_, permission)) => format!("{user} [{permission}]"),
+                        None => user.to_string(),
+                    };
+                    let is_suggested = self.suggested.contains(&user.login);
+                    if is_suggested {
+                        suggestion.push_str(" [suggested]");
+                    }
+                    suggestions.push((!is_suggested, suggestion));
                 }
             }
 
-            // Sort suggestions alphabetically
+            // Suggested users first, then alphabetically
             suggestions.sort();
 
-            Ok(suggestions)
+            Ok(suggestions.into_iter().map(|(_, s)| s).collect())
         }
 
         fn get_completion(
@@ -371,11 +601,13 @@ pub fn prompt_assignees(repo_users: &[RepoUser]) -> Result<Vec<String>> {
     }
 
     if repo_users.is_empty() {
-        return Ok(Vec::new());
+        return Ok(PromptOutcome::Value(Vec::new()));
     }
 
     let user_completer = UserCompleter {
         users: repo_users.to_vec(),
+        permissions: permissions.to_vec(),
+        suggested: suggested.to_vec(),
     };
 
     // Create owned copy for validator
@@ -394,30 +626,31 @@ pub fn prompt_assignees(repo_users: &[RepoUser]) -> Result<Vec<String>> {
         };
 
         let valid_logins_for_validator = valid_logins.clone();
-        let input = Text::new(&prompt_text)
-            .with_autocomplete(user_completer.clone())
-            .with_validator(move |input: &str| {
-                if input.trim().is_empty() {
-                    Ok(Validation::Valid) // Empty is valid - means finish
-                } else {
-                    // Validate that the assignee exists and extract login from display format
-                    let login = if let Some(space_pos) = input.find(' ') {
-                        &input[..space_pos]
+        let input = answer!(
+            Text::new(&prompt_text)
+                .with_autocomplete(user_completer.clone())
+                .with_validator(move |input: &str| {
+                    if input.trim().is_empty() {
+                        Ok(Validation::Valid) // Empty is valid - means finish
                     } else {
-                        input.trim()
-                    };
-
-                    if valid_logins_for_validator.iter().any(|u| u == login) {
-                        Ok(Validation::Valid)
-                    } else {
-                        Ok(Validation::Invalid(
-                            format!("User '{}' not found in repository", login).into(),
-                        ))
+                        // Validate that the assignee exists and extract login from display format
+                        let login = if let Some(space_pos) = input.find(' ') {
+                            &input[..space_pos]
+                        } else {
+                            input.trim()
+                        };
+
+                        if valid_logins_for_validator.iter().any(|u| u == login) {
+                            Ok(Validation::Valid)
+                        } else {
+                            Ok(Validation::Invalid(
+                                format!("User '{}' not found in repository", login).into(),
+                            ))
+                        }
                     }
-                }
-            })
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+                })
+                .prompt()
+        );
 
         let trimmed_input = input.trim();
         if trimmed_input.is_empty() {
@@ -437,16 +670,14 @@ pub fn prompt_assignees(repo_users: &[RepoUser]) -> Result<Vec<String>> {
         }
     }
 
-    Ok(assignees)
+    Ok(PromptOutcome::Value(assignees))
 }
 
-pub fn prompt_collaborators(defaults: &[String]) -> Result<Vec<String>> {
+pub fn prompt_collaborators(defaults: &[String]) -> Result<PromptOutcome<Vec<String>>> {
     let mut collaborators = if defaults.is_empty() {
         Vec::new()
     } else {
-        MultiSelect::new("🤝 Select collaborators to keep:", defaults.to_vec())
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?
+        answer!(MultiSelect::new("🤝 Select collaborators to keep:", defaults.to_vec()).prompt())
     };
 
     loop {
@@ -456,18 +687,19 @@ pub fn prompt_collaborators(defaults: &[String]) -> Result<Vec<String>> {
             "🤝 Add another collaborator (Name <email>, Enter to finish):"
         };
 
-        let input = Text::new(prompt)
-            .with_validator(|input: &str| {
-                if input.trim().is_empty() || normalize_collaborator_entry(input).is_some() {
-                    Ok(Validation::Valid)
-                } else {
-                    Ok(Validation::Invalid(
-                        "Collaborator must use the format Name <email>".into(),
-                    ))
-                }
-            })
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+        let input = answer!(
+            Text::new(prompt)
+                .with_validator(|input: &str| {
+                    if input.trim().is_empty() || normalize_collaborator_entry(input).is_some() {
+                        Ok(Validation::Valid)
+                    } else {
+                        Ok(Validation::Invalid(
+                            "Collaborator must use the format Name <email>".into(),
+                        ))
+                    }
+                })
+                .prompt()
+        );
 
         let trimmed = input.trim();
         if trimmed.is_empty() {
@@ -482,81 +714,34 @@ pub fn prompt_collaborators(defaults: &[String]) -> Result<Vec<String>> {
         }
     }
 
-    Ok(collaborators)
+    Ok(PromptOutcome::Value(collaborators))
 }
 
-/// Select an issue from a milestone by title with autocomplete
-pub fn prompt_issue(issues: &[Issue]) -> Result<Issue> {
-    #[derive(Clone)]
-    struct IssueCompleter {
-        issues: Vec<Issue>,
-    }
-
-    impl Autocomplete for IssueCompleter {
-        fn get_suggestions(
-            &mut self,
-            input: &str,
-        ) -> std::result::Result<Vec<String>, CustomUserError> {
-            let input = input.trim();
-            let mut suggestions = Vec::new();
-
-            for issue in &self.issues {
-                // Search by title
-                if issue.title.to_lowercase().contains(&input.to_lowercase()) {
-                    suggestions.push(issue.title.clone());
-                }
-            }
-
-            // Sort suggestions alphabetically by title
-            suggestions.sort();
-
-            Ok(suggestions)
-        }
-
-        fn get_completion(
-            &mut self,
-            _input: &str,
-            highlighted_suggestion: Option<String>,
-        ) -> std::result::Result<inquire::autocompletion::Replacement, CustomUserError> {
-            Ok(match highlighted_suggestion {
-                Some(suggestion) => inquire::autocompletion::Replacement::Some(suggestion),
-                None => inquire::autocompletion::Replacement::None,
-            })
-        }
-    }
+/// Issue titles in the order they are offered, alphabetically
+pub(crate) fn issue_choices(issues: &[Issue]) -> Vec<String> {
+    let mut titles: Vec<String> = issues.iter().map(|i| i.title.clone()).collect();
+    titles.sort();
+    titles
+}
 
+/// Select an issue from a milestone by title
+pub fn prompt_issue(issues: &[Issue]) -> Result<PromptOutcome<Issue>> {
     if issues.is_empty() {
         return Err(anyhow::anyhow!("No issues found in the selected milestone"));
     }
 
-    let issue_completer = IssueCompleter {
-        issues: issues.to_vec(),
-    };
-
-    let issue_input = Text::new("🎫 Enter issue title (use Tab for autocomplete):")
-        .with_autocomplete(issue_completer)
-        .with_validator(move |input: &str| {
-            let trimmed = input.trim();
-            if trimmed.is_empty() {
-                Ok(Validation::Invalid(
-                    "Issue selection cannot be empty".into(),
-                ))
-            } else {
-                Ok(Validation::Valid)
-            }
-        })
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+    let title = answer!(select_one(
+        "🎫 Select an issue:",
+        "issue",
+        issue_choices(issues)
+    )?);
 
-    // Find the issue by title
-    if let Some(issue) = issues.iter().find(|i| i.title == issue_input.trim()) {
-        Ok(issue.clone())
-    } else {
-        Err(anyhow::anyhow!(
-            "Issue with title '{}' not found",
-            issue_input.trim()
-        ))
-    }
+    issues
+        .iter()
+        .find(|i| i.title == title)
+        .cloned()
+        .map(PromptOutcome::Value)
+        .ok_or_else(|| anyhow::anyhow!("Issue with title '{title}' not found"))
 }
 
 /// Helper function to format commit options for display
@@ -603,6 +788,8 @@ fn format_commit_options(issue_thread: &IssueThread, selected: &[usize]) -> Vec<
                 .contains(&crate::issue::CommitStatus::Notification)
             {
                 "💬" // Has comments
+            } else if commit.is_unapproved() {
+                "🚫" // Approval withdrawn at this commit
             } else if commit.hash == issue_thread.latest_commit().hash {
                 "📍" // Latest commit
             } else {
@@ -630,13 +817,15 @@ fn format_commit_options(issue_thread: &IssueThread, selected: &[usize]) -> Vec<
 }
 
 /// Select commits for comparison - returns (current, previous) in chronological order
-pub fn prompt_commits(issue_thread: &IssueThread) -> Result<(ObjectId, Option<ObjectId>)> {
+pub fn prompt_commits(
+    issue_thread: &IssueThread,
+) -> Result<PromptOutcome<(ObjectId, Option<ObjectId>)>> {
     if issue_thread.commits.is_empty() {
         return Err(anyhow::anyhow!("No commits found for this file"));
     }
 
     if issue_thread.commits.len() == 1 {
-        return Ok((issue_thread.commits[0].hash, None));
+        return Ok(PromptOutcome::Value((issue_thread.commits[0].hash, None)));
     }
 
     // Get commits that actually changed the file for smart defaults
@@ -654,26 +843,31 @@ pub fn prompt_commits(issue_thread: &IssueThread) -> Result<(ObjectId, Option<Ob
         0 // Fall back to first commit overall
     };
 
-    println!("📋 Commit Status Legend:");
-    println!("   🌱 Initial commit  💬 Has comments  ✅ Approved  📍 Latest  📝 File changed");
-    println!();
+    message!("📋 Commit Status Legend:");
+    message!(
+        "   🌱 Initial commit  💬 Has comments  ✅ Approved  🚫 Unapproved  📍 Latest  📝 File changed"
+    );
+    message!();
 
     let mut selected_commits: Vec<usize> = Vec::new();
 
     // First selection
-    println!("📝 Select first commit (press Enter for latest file change):");
+    message!("📝 Select first commit (press Enter for latest file change):");
     let options = format_commit_options(issue_thread, &selected_commits);
-    let first_selection = Select::new("Pick commit:", options)
-        .with_starting_cursor(default_cursor)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let first_selection = answer!(
+        Select::new("Pick commit:", options)
+            .with_starting_cursor(default_cursor)
+            .prompt()
+    );
 
     // Extract short hash from selection (remove prefixes and status indicators)
     let cleaned_selection = first_selection
         .trim_start_matches("✓ ")
         .trim_start_matches("  ")
         .chars()
-        .skip_while(|c| c.is_whitespace() || *c == '🌱' || *c == '💬' || *c == '✅' || *c == '📍')
+        .skip_while(|c| {
+            c.is_whitespace() || *c == '🌱' || *c == '💬' || *c == '✅' || *c == '🚫' || *c == '📍'
+        })
         .collect::<String>();
     let first_short_hash = cleaned_selection
         .trim()
@@ -699,17 +893,18 @@ pub fn prompt_commits(issue_thread: &IssueThread) -> Result<(ObjectId, Option<Ob
     };
 
     // Second selection
-    println!("\n📝 Select second commit for comparison (press Enter for second file change):");
+    message!("\n📝 Select second commit for comparison (press Enter for second file change):");
     let mut options_with_skip = format_commit_options(issue_thread, &selected_commits);
     options_with_skip.insert(
         0,
         "  ⏭️  Skip second commit (compare with nothing)".to_string(),
     );
 
-    let second_selection = Select::new("Pick commit:", options_with_skip)
-        .with_starting_cursor(second_default_cursor)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let second_selection = answer!(
+        Select::new("Pick commit:", options_with_skip)
+            .with_starting_cursor(second_default_cursor)
+            .prompt()
+    );
 
     let second_commit = if second_selection.contains("⏭️") {
         None
@@ -724,6 +919,7 @@ pub fn prompt_commits(issue_thread: &IssueThread) -> Result<(ObjectId, Option<Ob
                     || *c == '🌱'
                     || *c == '💬'
                     || *c == '✅'
+                    || *c == '🚫'
                     || *c == '📍'
                     || *c == '📝'
             })
@@ -744,7 +940,10 @@ pub fn prompt_commits(issue_thread: &IssueThread) -> Result<(ObjectId, Option<Ob
         Some(issue_thread.commits[second_index].hash)
     };
 
-    Ok((issue_thread.commits[first_index].hash, second_commit))
+    Ok(PromptOutcome::Value((
+        issue_thread.commits[first_index].hash,
+        second_commit,
+    )))
 }
 
 /// Select a single commit from file commits - returns the selected commit
@@ -752,35 +951,44 @@ pub fn prompt_single_commit(
     issue_thread: &IssueThread,
     prompt_text: &str,
     default_position: usize,
-) -> Result<ObjectId> {
+) -> Result<PromptOutcome<ObjectId>> {
     if issue_thread.commits.is_empty() {
         return Err(anyhow::anyhow!("No commits found for this file"));
     }
 
     if issue_thread.commits.len() == 1 {
         log::info!("Only one commit found for this file. Selecting the commit...");
-        return Ok(issue_thread.commits[0].hash);
+        return Ok(PromptOutcome::Value(issue_thread.commits[0].hash));
     }
 
-    println!("📋 Commit Status Legend:");
-    println!("   🌱 Initial commit  💬 Has comments  ✅ Approved  📍 Latest  📝 File changed");
-    println!();
+    message!("📋 Commit Status Legend:");
+    message!(
+        "   🌱 Initial commit  💬 Has comments  ✅ Approved  🚫 Unapproved  📍 Latest  📝 File changed"
+    );
+    message!();
 
     // Create commit options with status indicators
     let commit_options = format_commit_options(issue_thread, &[]);
 
-    println!("{}", prompt_text);
-    let commit_selection = Select::new("Pick commit:", commit_options)
-        .with_starting_cursor(default_position.min(issue_thread.commits.len() - 1)) // Use provided default position, clamped to valid range
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    message!("{}", prompt_text);
+    let commit_selection = answer!(
+        Select::new("Pick commit:", commit_options)
+            .with_starting_cursor(default_position.min(issue_thread.commits.len() - 1)) // Use provided default position, clamped to valid range
+            .prompt()
+    );
 
     // Extract short hash from selection
     let cleaned_commit_selection = commit_selection
         .trim_start_matches("  ")
         .chars()
         .skip_while(|c| {
-            c.is_whitespace() || *c == '🌱' || *c == '💬' || *c == '✅' || *c == '📍' || *c == '📝'
+            c.is_whitespace()
+                || *c == '🌱'
+                || *c == '💬'
+                || *c == '✅'
+                || *c == '🚫'
+                || *c == '📍'
+                || *c == '📝'
         })
         .collect::<String>();
     let commit_short_hash = cleaned_commit_selection
@@ -796,40 +1004,133 @@ pub fn prompt_single_commit(
         .position(|commit| commit.hash.to_string().starts_with(commit_short_hash))
         .unwrap_or(0);
 
-    Ok(issue_thread.commits[commit_index].hash)
+    Ok(PromptOutcome::Value(
+        issue_thread.commits[commit_index].hash,
+    ))
 }
 
 /// Prompt for optional note for a comment
-pub fn prompt_note() -> Result<Option<String>> {
-    let note_input = Text::new("📝 Enter optional note for this comment (Enter to skip):")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+pub fn prompt_note() -> Result<PromptOutcome<Option<String>>> {
+    let note_input =
+        answer!(Text::new("📝 Enter optional note for this comment (Enter to skip):").prompt());
 
     let trimmed_input = note_input.trim();
     if trimmed_input.is_empty() {
-        Ok(None)
+        Ok(PromptOutcome::Value(None))
     } else {
-        Ok(Some(trimmed_input.to_string()))
+        Ok(PromptOutcome::Value(Some(trimmed_input.to_string())))
     }
 }
 
+/// Prompt for the required reason for an unapproval
+pub fn prompt_unapprove_reason() -> Result<PromptOutcome<String>> {
+    let reason = answer!(
+        Text::new("📝 Enter reason for unapproval:")
+            .with_validator(|input: &str| {
+                if input.trim().is_empty() {
+                    Ok(Validation::Invalid("Reason cannot be empty".into()))
+                } else {
+                    Ok(Validation::Valid)
+                }
+            })
+            .prompt()
+    );
+
+    Ok(PromptOutcome::Value(reason.trim().to_string()))
+}
+
+/// Multi-select of the repository users, with the `current` assignees checked
+pub fn prompt_reassign(
+    repo_users: &[RepoUser],
+    current: &[String],
+) -> Result<PromptOutcome<Vec<String>>> {
+    let mut users = repo_users.to_vec();
+    // Keep assignees who are no longer assignable in the list, so they can be unchecked
+    for login in current {
+        if !users.iter().any(|u| &u.login == login) {
+            users.push(RepoUser {
+                login: login.clone(),
+                name: None,
+            });
+        }
+    }
+    let checked: Vec<usize> = users
+        .iter()
+        .enumerate()
+        .filter(|(_, u)| current.contains(&u.login))
+        .map(|(i, _)| i)
+        .collect();
+
+    let selected = answer!(
+        MultiSelect::new("👥 Select assignees:", users)
+            .with_default(&checked)
+            .prompt()
+    );
+
+    Ok(PromptOutcome::Value(
+        selected.into_iter().map(|u| u.login).collect(),
+    ))
+}
+
+/// Checklist item shown in the checklist multi-select
+struct ChecklistItemOption<'a>(&'a ChecklistItem);
+
+impl fmt::Display for ChecklistItemOption<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}. [{}] {}",
+            self.0.index, self.0.checklist, self.0.text
+        )
+    }
+}
+
+/// Multi-select of an issue's checklist items, with the checked items pre-selected
+pub fn prompt_checklist_items(items: &[ChecklistItem]) -> Result<PromptOutcome<Vec<usize>>> {
+    let checked: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.checked)
+        .map(|(i, _)| i)
+        .collect();
+    let options: Vec<_> = items.iter().map(ChecklistItemOption).collect();
+
+    let selected = answer!(
+        MultiSelect::new("☑️ Select the checked items:", options)
+            .with_default(&checked)
+            .with_page_size(15)
+            .prompt()
+    );
+
+    Ok(PromptOutcome::Value(
+        selected.into_iter().map(|option| option.0.index).collect(),
+    ))
+}
+
+/// Yes/no confirmation
+pub fn prompt_confirm(message: &str, default: bool) -> Result<PromptOutcome<bool>> {
+    PromptOutcome::from_inquire(Confirm::new(message).with_default(default).prompt())
+}
+
+/// Milestones, record file name and whether to render only the tables
+type RecordSelection = (Vec<Milestone>, Option<PathBuf>, bool);
+
 /// Interactive milestone selection for record generation
-pub fn prompt_milestone_record(
-    milestones: &[Milestone],
-) -> Result<(Vec<Milestone>, Option<PathBuf>, bool)> {
-    println!("📄 Welcome to GHQC Milestone Record Mode!");
+pub fn prompt_milestone_record(milestones: &[Milestone]) -> Result<PromptOutcome<RecordSelection>> {
+    message!("📄 Welcome to GHQC Milestone Record Mode!");
 
     if milestones.is_empty() {
         bail!("No milestones found in repository");
     }
 
     // First ask if they want to select all or choose specific ones
-    let choice = Select::new(
-        "📄 How would you like to select milestones for the record?",
-        vec!["📋 Select All Milestones", "🎯 Choose Specific Milestones"],
-    )
-    .prompt()
-    .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let choice = answer!(
+        Select::new(
+            "📄 How would you like to select milestones for the record?",
+            vec!["📋 Select All Milestones", "🎯 Choose Specific Milestones"],
+        )
+        .prompt()
+    );
 
     let selected_milestones: Vec<Milestone> = if choice == "📋 Select All Milestones" {
         milestones.to_vec()
@@ -840,7 +1141,7 @@ pub fn prompt_milestone_record(
             .map(|m| format!("{} ({})", m.title, m.number))
             .collect();
 
-        let selected_strings =
+        let selected_strings = answer!(
             MultiSelect::new("📄 Select milestones for the record:", milestone_options)
                 .with_validator(|selection: &[inquire::list_option::ListOption<&String>]| {
                     if selection.is_empty() {
@@ -852,7 +1153,7 @@ pub fn prompt_milestone_record(
                     }
                 })
                 .prompt()
-                .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+        );
 
         // Filter milestones based on selected strings
         milestones
@@ -870,16 +1171,16 @@ pub fn prompt_milestone_record(
     }
 
     // Prompt for only_tables using y/N format
-    let only_tables = Confirm::new("📋 Generate only tables without detailed issue content?")
-        .with_default(false)
-        .with_help_message("N = include detailed issue content, y = only summary tables")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let only_tables = answer!(
+        Confirm::new("📋 Generate only tables without detailed issue content?")
+            .with_default(false)
+            .with_help_message("N = include detailed issue content, y = only summary tables")
+            .prompt()
+    );
 
     // Prompt for optional record path
-    let record_path_input = Text::new("📁 Enter record file name (Enter for default):")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+    let record_path_input =
+        answer!(Text::new("📁 Enter record file name (Enter for default):").prompt());
 
     let record_path = if record_path_input.trim().is_empty() {
         None
@@ -887,28 +1188,36 @@ pub fn prompt_milestone_record(
         Some(PathBuf::from(record_path_input.trim()))
     };
 
-    Ok((selected_milestones, record_path, only_tables))
+    Ok(PromptOutcome::Value((
+        selected_milestones,
+        record_path,
+        only_tables,
+    )))
 }
 
+/// Milestones, archive file name, and whether to include unapproved issues and flatten paths
+type ArchiveSelection = (Vec<Milestone>, Option<PathBuf>, bool, bool);
+
 /// Interactive milestone selection for archive generation
 pub fn prompt_milestone_archive(
     milestones: &[Milestone],
-) -> Result<(Vec<Milestone>, Option<PathBuf>, bool, bool)> {
+) -> Result<PromptOutcome<ArchiveSelection>> {
     use inquire::Confirm;
 
-    println!("📦 Welcome to GHQC Milestone Archive Mode!");
+    message!("📦 Welcome to GHQC Milestone Archive Mode!");
 
     if milestones.is_empty() {
         bail!("No milestones found in repository");
     }
 
     // First ask if they want to select all or choose specific ones
-    let choice = Select::new(
-        "📦 How would you like to select milestones for the archive?",
-        vec!["📋 Select All Milestones", "🎯 Choose Specific Milestones"],
-    )
-    .prompt()
-    .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let choice = answer!(
+        Select::new(
+            "📦 How would you like to select milestones for the archive?",
+            vec!["📋 Select All Milestones", "🎯 Choose Specific Milestones"],
+        )
+        .prompt()
+    );
 
     let selected_milestones: Vec<Milestone> = if choice == "📋 Select All Milestones" {
         milestones.to_vec()
@@ -919,7 +1228,7 @@ pub fn prompt_milestone_archive(
             .map(|m| format!("{} ({})", m.title, m.number))
             .collect();
 
-        let selected_strings =
+        let selected_strings = answer!(
             MultiSelect::new("📦 Select milestones for the archive:", milestone_options)
                 .with_validator(|selection: &[inquire::list_option::ListOption<&String>]| {
                     if selection.is_empty() {
@@ -931,7 +1240,7 @@ pub fn prompt_milestone_archive(
                     }
                 })
                 .prompt()
-                .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+        );
 
         // Filter milestones based on selected strings
         milestones
@@ -949,23 +1258,24 @@ pub fn prompt_milestone_archive(
     }
 
     // Prompt for include_unapproved using y/N format
-    let include_unapproved = Confirm::new("📋 Include unapproved issues?")
-        .with_default(false)
-        .with_help_message("N = only approved issues, y = all issues")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let include_unapproved = answer!(
+        Confirm::new("📋 Include unapproved issues?")
+            .with_default(false)
+            .with_help_message("N = only approved issues, y = all issues")
+            .prompt()
+    );
 
     // Prompt for flatten using y/N format
-    let flatten = Confirm::new("📁 Flatten archive structure?")
-        .with_default(false)
-        .with_help_message("N = preserve directory structure, y = put all files in root")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let flatten = answer!(
+        Confirm::new("📁 Flatten archive structure?")
+            .with_default(false)
+            .with_help_message("N = preserve directory structure, y = put all files in root")
+            .prompt()
+    );
 
     // Prompt for optional archive path
-    let archive_path_input = Text::new("📁 Enter archive file name (Enter for default):")
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+    let archive_path_input =
+        answer!(Text::new("📁 Enter archive file name (Enter for default):").prompt());
 
     let archive_path = if archive_path_input.trim().is_empty() {
         None
@@ -973,17 +1283,17 @@ pub fn prompt_milestone_archive(
         Some(PathBuf::from(archive_path_input.trim()))
     };
 
-    Ok((
+    Ok(PromptOutcome::Value((
         selected_milestones,
         archive_path,
         include_unapproved,
         flatten,
-    ))
+    )))
 }
 
 /// Interactive context file selection for record generation
 /// Lists PDF files and allows users to select and choose prepend/append position
-pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
+pub fn prompt_context_files(current_dir: &PathBuf) -> Result<PromptOutcome<Vec<QCContext>>> {
     // Find all .pdf files in the directory
     let context_files_available: Vec<PathBuf> = fs::read_dir(current_dir)
         .map_err(|e| anyhow::anyhow!("Failed to read directory: {}", e))?
@@ -1003,8 +1313,8 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
         .collect();
 
     if context_files_available.is_empty() {
-        println!("ℹ️  No PDF context documents found in directory");
-        return Ok(Vec::new());
+        message!("ℹ️  No PDF context documents found in directory");
+        return Ok(PromptOutcome::Value(Vec::new()));
     }
 
     let mut context_files: Vec<QCContext> = Vec::new();
@@ -1012,7 +1322,7 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
 
     loop {
         if available_files.is_empty() {
-            println!("ℹ️  No more context documents available to add");
+            message!("ℹ️  No more context documents available to add");
             break;
         }
 
@@ -1030,9 +1340,7 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
             "📄 Select another document (or Done to finish):"
         };
 
-        let selection = Select::new(prompt_text, options)
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+        let selection = answer!(Select::new(prompt_text, options).prompt());
 
         if selection.starts_with("✅") {
             break;
@@ -1058,12 +1366,13 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
                 "⬇️  Append (after main findings)".to_string(),
             ];
 
-            let position_selection = Select::new(
-                &format!("📍 Where should '{}' appear?", selected_name),
-                position_options,
-            )
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+            let position_selection = answer!(
+                Select::new(
+                    &format!("📍 Where should '{}' appear?", selected_name),
+                    position_options,
+                )
+                .prompt()
+            );
 
             let position = if position_selection.starts_with("⬆️") {
                 ContextPosition::Prepend
@@ -1076,7 +1385,7 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
             // Remove from available files
             available_files.retain(|p| p != &path);
 
-            println!(
+            message!(
                 "✅ Added '{}' to {} context",
                 selected_name,
                 if matches!(position, ContextPosition::Prepend) {
@@ -1090,13 +1399,13 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
 
     // Show summary if any files were selected
     if !context_files.is_empty() {
-        println!("\n📋 Context files summary:");
+        message!("\n📋 Context files summary:");
         for ctx in &context_files {
             let pos = match ctx.position() {
                 ContextPosition::Prepend => "prepend",
                 ContextPosition::Append => "append",
             };
-            println!(
+            message!(
                 "   {} {} ({})",
                 if matches!(ctx.position(), ContextPosition::Prepend) {
                     "⬆️"
@@ -1107,23 +1416,27 @@ pub fn prompt_context_files(current_dir: &PathBuf) -> Result<Vec<QCContext>> {
                 pos
             );
         }
-        println!();
+        message!();
     }
 
-    Ok(context_files)
+    Ok(PromptOutcome::Value(context_files))
 }
 
 /// Asks if user wants to add relevant files
-pub fn prompt_want_relevant_files() -> Result<bool> {
-    Confirm::new("Do you want to add any relevant files?")
-        .with_default(false)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))
+pub fn prompt_want_relevant_files() -> Result<PromptOutcome<bool>> {
+    PromptOutcome::from_inquire(
+        Confirm::new("Do you want to add any relevant files?")
+            .with_default(false)
+            .prompt(),
+    )
 }
 
 /// File selector for relevant files with issue indicators
 /// Shows files with their related issue numbers if they exist
-pub fn prompt_relevant_file_path(current_dir: &PathBuf, all_issues: &[Issue]) -> Result<PathBuf> {
+pub fn prompt_relevant_file_path(
+    current_dir: &Path,
+    all_issues: &[Issue],
+) -> Result<PromptOutcome<PathBuf>> {
     // Build a map of file paths to their issue numbers
     let file_to_issues: HashMap<String, Vec<u64>> = {
         let mut map: HashMap<String, Vec<u64>> = HashMap::new();
@@ -1249,40 +1562,41 @@ pub fn prompt_relevant_file_path(current_dir: &PathBuf, all_issues: &[Issue]) ->
     }
 
     let file_completer = RelevantFileCompleter {
-        current_dir: current_dir.clone(),
+        current_dir: current_dir.to_path_buf(),
         file_to_issues,
     };
 
-    let validator_dir = current_dir.clone();
-    let file_path = Text::new("📁 Select a relevant file:")
-        .with_autocomplete(file_completer)
-        .with_validator(move |input: &str| {
-            // Strip issue indicators if present
-            let trimmed = if let Some(bracket_pos) = input.find(" [") {
-                input[..bracket_pos].trim()
-            } else {
-                input.trim()
-            };
+    let validator_dir = current_dir.to_path_buf();
+    let file_path = answer!(
+        Text::new("📁 Select a relevant file:")
+            .with_autocomplete(file_completer)
+            .with_validator(move |input: &str| {
+                // Strip issue indicators if present
+                let trimmed = if let Some(bracket_pos) = input.find(" [") {
+                    input[..bracket_pos].trim()
+                } else {
+                    input.trim()
+                };
 
-            if trimmed.is_empty() {
-                Ok(Validation::Invalid("File path cannot be empty".into()))
-            } else if trimmed.ends_with('/') {
-                Ok(Validation::Invalid(
-                    "Cannot select a directory. Please select a file.".into(),
-                ))
-            } else {
-                let path = validator_dir.join(trimmed);
-                if path.exists() && path.is_dir() {
+                if trimmed.is_empty() {
+                    Ok(Validation::Invalid("File path cannot be empty".into()))
+                } else if trimmed.ends_with('/') {
                     Ok(Validation::Invalid(
-                        "Path must be a file, not a directory".into(),
+                        "Cannot select a directory. Please select a file.".into(),
                     ))
                 } else {
-                    Ok(Validation::Valid)
+                    let path = validator_dir.join(trimmed);
+                    if path.exists() && path.is_dir() {
+                        Ok(Validation::Invalid(
+                            "Path must be a file, not a directory".into(),
+                        ))
+                    } else {
+                        Ok(Validation::Valid)
+                    }
                 }
-            }
-        })
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+            })
+            .prompt()
+    );
 
     // Strip issue indicators if present and return clean path
     let clean_path = if let Some(bracket_pos) = file_path.find(" [") {
@@ -1291,18 +1605,18 @@ pub fn prompt_relevant_file_path(current_dir: &PathBuf, all_issues: &[Issue]) ->
         file_path.trim().to_string()
     };
 
-    Ok(PathBuf::from(clean_path))
+    Ok(PromptOutcome::Value(PathBuf::from(clean_path)))
 }
 
 /// Select issue or File option for a given file path
 /// Returns Some(issue) if issue selected, None if File selected
-pub fn prompt_relevant_file_source<'a>(
-    file_path: &PathBuf,
-    matching_issues: &'a [&Issue],
+pub fn prompt_relevant_file_source(
+    file_path: &Path,
+    matching_issues: &[Issue],
     current_milestone_number: u64,
-) -> Result<Option<&'a Issue>> {
+) -> Result<PromptOutcome<Option<Issue>>> {
     // Sort issues: current milestone first, then by issue number descending
-    let mut sorted_issues: Vec<&Issue> = matching_issues.to_vec();
+    let mut sorted_issues: Vec<&Issue> = matching_issues.iter().collect();
     sorted_issues.sort_by(|a, b| {
         let a_is_current = a
             .milestone
@@ -1338,16 +1652,17 @@ pub fn prompt_relevant_file_source<'a>(
         .collect();
     options.extend(issue_options);
 
-    let selection = Select::new(
-        &format!("Select the source for '{}':", file_path.display()),
-        options,
-    )
-    .prompt()
-    .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let selection = answer!(
+        Select::new(
+            &format!("Select the source for '{}':", file_path.display()),
+            options,
+        )
+        .prompt()
+    );
 
     // Check if user selected File option
     if selection.starts_with("📄 ") {
-        return Ok(None);
+        return Ok(PromptOutcome::Value(None));
     }
 
     // Extract issue number from selection (format: "🔗 #123 (Milestone Name)")
@@ -1360,44 +1675,43 @@ pub fn prompt_relevant_file_source<'a>(
 
     if let Ok(issue_number) = issue_number_str.parse::<u64>() {
         if let Some(issue) = sorted_issues.iter().find(|i| i.number == issue_number) {
-            return Ok(Some(*issue));
+            return Ok(PromptOutcome::Value(Some((*issue).clone())));
         }
     }
 
-    Ok(None)
+    Ok(PromptOutcome::Value(None))
 }
 
 /// Select relevant file class type (GatingQC, PreviousQC, RelevantQC)
-pub fn prompt_relevant_file_class() -> Result<RelevantFileClassType> {
+pub fn prompt_relevant_file_class() -> Result<PromptOutcome<RelevantFileClassType>> {
     let options = vec![
         "🚦 Gating QC - This issue must be approved before the current issue".to_string(),
         "📜 Previous QC - A previous version of the QC for this file".to_string(),
         "🔗 Relevant QC - Related QC that provides context".to_string(),
     ];
 
-    let selection = Select::new("Select the relationship type:", options)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    let selection = answer!(Select::new("Select the relationship type:", options).prompt());
 
     if selection.starts_with("🚦") {
-        Ok(RelevantFileClassType::GatingQC)
+        Ok(PromptOutcome::Value(RelevantFileClassType::GatingQC))
     } else if selection.starts_with("📜") {
-        Ok(RelevantFileClassType::PreviousQC)
+        Ok(PromptOutcome::Value(RelevantFileClassType::PreviousQC))
     } else {
-        Ok(RelevantFileClassType::RelevantQC)
+        Ok(PromptOutcome::Value(RelevantFileClassType::RelevantQC))
     }
 }
 
 /// Prompt whether to include a diff comment for this Previous QC
-pub fn prompt_include_previous_qc_diff() -> Result<bool> {
-    Confirm::new("Include a diff comment for this Previous QC?")
-        .with_default(true)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))
+pub fn prompt_include_previous_qc_diff() -> Result<PromptOutcome<bool>> {
+    PromptOutcome::from_inquire(
+        Confirm::new("Include a diff comment for this Previous QC?")
+            .with_default(true)
+            .prompt(),
+    )
 }
 
 /// Prompt for description (optional for issues, required for files)
-pub fn prompt_relevant_description(required: bool) -> Result<Option<String>> {
+pub fn prompt_relevant_description(required: bool) -> Result<PromptOutcome<Option<String>>> {
     let prompt_text = if required {
         "📝 Provide a justification for this file:"
     } else {
@@ -1405,38 +1719,38 @@ pub fn prompt_relevant_description(required: bool) -> Result<Option<String>> {
     };
 
     if required {
-        let input = Text::new(prompt_text)
-            .with_validator(|input: &str| {
-                if input.trim().is_empty() {
-                    Ok(Validation::Invalid("Justification cannot be empty".into()))
-                } else {
-                    Ok(Validation::Valid)
-                }
-            })
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+        let input = answer!(
+            Text::new(prompt_text)
+                .with_validator(|input: &str| {
+                    if input.trim().is_empty() {
+                        Ok(Validation::Invalid("Justification cannot be empty".into()))
+                    } else {
+                        Ok(Validation::Valid)
+                    }
+                })
+                .prompt()
+        );
 
-        Ok(Some(input.trim().to_string()))
+        Ok(PromptOutcome::Value(Some(input.trim().to_string())))
     } else {
-        let input = Text::new(prompt_text)
-            .prompt()
-            .map_err(|e| anyhow::anyhow!("Input cancelled: {}", e))?;
+        let input = answer!(Text::new(prompt_text).prompt());
 
         let trimmed = input.trim();
         if trimmed.is_empty() {
-            Ok(None)
+            Ok(PromptOutcome::Value(None))
         } else {
-            Ok(Some(trimmed.to_string()))
+            Ok(PromptOutcome::Value(Some(trimmed.to_string())))
         }
     }
 }
 
 /// Asks if user wants to add another relevant file
-pub fn prompt_add_another_relevant_file() -> Result<bool> {
-    Confirm::new("Add another relevant file?")
-        .with_default(false)
-        .prompt()
-        .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))
+pub fn prompt_add_another_relevant_file() -> Result<PromptOutcome<bool>> {
+    PromptOutcome::from_inquire(
+        Confirm::new("Add another relevant file?")
+            .with_default(false)
+            .prompt(),
+    )
 }
 
 #[cfg(test)]
@@ -1469,4 +1783,76 @@ mod tests {
         // Actual interactive testing would require manual verification
         assert!(config.checklists.len() == 3); // Including the default "Custom" checklist
     }
+
+    #[test]
+    fn test_prompt_outcome_from_inquire() {
+        assert!(matches!(
+            PromptOutcome::from_inquire(Ok(5)).unwrap(),
+            PromptOutcome::Value(5)
+        ));
+        assert!(matches!(
+            PromptOutcome::<u8>::from_inquire(Err(InquireError::OperationCanceled)).unwrap(),
+            PromptOutcome::Cancelled
+        ));
+        assert!(matches!(
+            PromptOutcome::<u8>::from_inquire(Err(InquireError::OperationInterrupted)).unwrap(),
+            PromptOutcome::Cancelled
+        ));
+        assert!(PromptOutcome::<u8>::from_inquire(Err(InquireError::NotTTY)).is_err());
+
+        let err = ask::<u8>(Err(InquireError::OperationCanceled)).unwrap_err();
+        assert!(err.is::<PromptCancelled>());
+    }
+
+    #[test]
+    fn test_file_choices_most_recent_first_without_issues() {
+        use crate::test_utils::create_test_issue;
+
+        let time = |t: &str| Some(chrono::DateTime::parse_from_rfc3339(t).unwrap().to_utc());
+        let files = vec![
+            (PathBuf::from("README.md"), time("2026-01-01T00:00:00Z")),
+            (
+                PathBuf::from("analysis/pk/run301.R"),
+                time("2026-03-01T00:00:00Z"),
+            ),
+            (
+                PathBuf::from("analysis/pk/run300.R"),
+                time("2026-03-01T00:00:00Z"),
+            ),
+            (PathBuf::from("legacy/old.R"), None),
+            (
+                PathBuf::from("analysis/pk/run200.R"),
+                time("2026-02-01T00:00:00Z"),
+            ),
+            (PathBuf::from("data/derived.csv"), None),
+        ];
+        let issues = vec![create_test_issue(
+            "owner",
+            "repo",
+            1,
+            "analysis/pk/run200.R",
+            "",
+            Some(1),
+            "open",
+        )];
+
+        assert_eq!(
+            file_choices(&files, &issues),
+            vec![
+                "analysis/pk/run300.R",
+                "analysis/pk/run301.R",
+                "README.md",
+                "data/derived.csv",
+                "legacy/old.R",
+            ]
+        );
+        assert_eq!(file_choices(&files[..1], &[]), vec!["README.md"]);
+        assert!(file_choices(&[], &issues).is_empty());
+    }
+
+    #[test]
+    fn test_select_one_without_options() {
+        let err = select_one("Select a file:", "file", Vec::new()).unwrap_err();
+        assert_eq!(err.to_string(), "No file available to select");
+    }
 }
@@ -0,0 +1,624 @@
+//! `ghqc issue list`: the QC issues of several milestones, filtered by assignee, state, QC status
+//! and file
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use octocrab::models::{IssueState, Milestone, issues::Issue};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::{
+    CommitIndex, DiskCache, GitCommitOps, GitHubReader, IssueThread, QCStatus,
+    codeowners::pattern_regex, get_issue_comments, get_milestone_issues_cached,
+    utils::repo_path_string,
+};
+
+/// Which issue states `ghqc issue list` shows
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IssueStateFilter {
+    #[default]
+    Open,
+    Closed,
+    All,
+}
+
+impl IssueStateFilter {
+    fn matches(self, issue: &Issue) -> bool {
+        match self {
+            Self::Open => issue.state == IssueState::Open,
+            Self::Closed => issue.state == IssueState::Closed,
+            Self::All => true,
+        }
+    }
+}
+
+/// A QC status to filter on, named as in the JSON status output
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "snake_case")]
+pub enum QCStatusFilter {
+    Approved,
+    ChangesAfterApproval,
+    ApprovalRequired,
+    AwaitingReview,
+    ChangeRequested,
+    InProgress,
+    ChangesToComment,
+    Obsoleted,
+}
+
+impl QCStatusFilter {
+    fn matches(self, status: &QCStatus) -> bool {
+        matches!(
+            (self, status),
+            (Self::Approved, QCStatus::Approved)
+                | (
+                    Self::ChangesAfterApproval,
+                    QCStatus::ChangesAfterApproval(_)
+                )
+                | (Self::ApprovalRequired, QCStatus::ApprovalRequired)
+                | (Self::AwaitingReview, QCStatus::AwaitingReview)
+                | (Self::ChangeRequested, QCStatus::ChangeRequested)
+                | (Self::InProgress, QCStatus::InProgress)
+                | (Self::ChangesToComment, QCStatus::ChangesToComment(_))
+                | (Self::Obsoleted, QCStatus::Obsoleted(_))
+        )
+    }
+}
+
+/// Order of the listed issues
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IssueSort {
+    /// By file path, then milestone
+    #[default]
+    File,
+    /// Most recently updated first
+    Updated,
+}
+
+/// Which issues `ghqc issue list` shows, and whether with their QC status
+#[derive(Debug, Clone, Default)]
+pub struct IssueListOptions {
+    /// Login of an assignee the issues must have
+    pub assignee: Option<String>,
+    pub state: IssueStateFilter,
+    /// QC statuses to keep, any of them. Empty keeps every status
+    pub statuses: Vec<QCStatusFilter>,
+    /// Gitignore-style pattern the file path must match, e.g. `scripts/**/*.R`
+    pub file_glob: Option<String>,
+    /// Determine the QC status of the listed issues even when not filtering on it
+    pub with_status: bool,
+    pub sort: IssueSort,
+}
+
+impl IssueListOptions {
+    /// The QC status is only determined when shown or filtered on, since it needs the comments
+    /// of every issue
+    pub fn needs_status(&self) -> bool {
+        self.with_status || !self.statuses.is_empty()
+    }
+}
+
+/// An issue listed by `ghqc issue list`
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueListEntry {
+    pub file: String,
+    pub issue_number: u64,
+    pub issue_url: String,
+    pub milestone: String,
+    pub assignees: Vec<String>,
+    pub open: bool,
+    pub updated_at: DateTime<Utc>,
+    /// `None` unless the status was requested, or when it could not be determined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qc_status: Option<QCStatus>,
+}
+
+impl IssueListEntry {
+    fn new(issue: &Issue, milestone: &Milestone) -> Self {
+        Self {
+            file: repo_path_string(Path::new(&issue.title)),
+            issue_number: issue.number,
+            issue_url: issue.html_url.to_string(),
+            milestone: milestone.title.clone(),
+            assignees: issue.assignees.iter().map(|a| a.login.clone()).collect(),
+            open: issue.state == IssueState::Open,
+            updated_at: issue.updated_at,
+            qc_status: None,
+        }
+    }
+}
+
+/// The issues of `milestones` matching the options, in the requested order
+///
+/// The assignee, state and file filters are applied first, so comments are only fetched for
+/// the remaining issues, and only when the QC status is needed. Issues whose status cannot be
+/// determined are listed without one, but never match a status filter.
+pub async fn list_issues(
+    milestones: &[Milestone],
+    options: &IssueListOptions,
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitCommitOps),
+) -> Result<Vec<IssueListEntry>> {
+    let file_regex = match options.file_glob.as_deref() {
+        Some(pattern) => match pattern_regex(pattern) {
+            Some(regex) => Some(regex),
+            None => bail!("Unsupported file pattern '{pattern}'"),
+        },
+        None => None,
+    };
+    // Milestones share branches, so walk each branch once for all of them
+    let index = CommitIndex::new();
+
+    let mut entries = Vec::new();
+    for milestone in milestones {
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+        let selected = issues.iter().filter(|issue| {
+            options.state.matches(issue)
+                && options
+                    .assignee
+                    .as_ref()
+                    .is_none_or(|assignee| issue.assignees.iter().any(|a| &a.login == assignee))
+                && file_regex
+                    .as_ref()
+                    .is_none_or(|regex| regex.is_match(&repo_path_string(Path::new(&issue.title))))
+        });
+
+        for issue in selected {
+            let mut entry = IssueListEntry::new(issue, milestone);
+            if options.needs_status() {
+                entry.qc_status = issue_qc_status(issue, cache, git_info, &index).await?;
+                let status_matches = options.statuses.is_empty()
+                    || entry.qc_status.as_ref().is_some_and(|status| {
+                        options.statuses.iter().any(|filter| filter.matches(status))
+                    });
+                if !status_matches {
+                    continue;
+                }
+            }
+            entries.push(entry);
+        }
+    }
+
+    match options.sort {
+        IssueSort::File => entries.sort_by(|a, b| {
+            a.file
+                .cmp(&b.file)
+                .then_with(|| a.milestone.cmp(&b.milestone))
+                .then(a.issue_number.cmp(&b.issue_number))
+        }),
+        IssueSort::Updated => entries.sort_by(|a, b| {
+            b.updated_at
+                .cmp(&a.updated_at)
+                .then(a.issue_number.cmp(&b.issue_number))
+        }),
+    }
+
+    Ok(entries)
+}
+
+/// QC status of an issue, `None` when its commits cannot be determined
+async fn issue_qc_status(
+    issue: &Issue,
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitCommitOps),
+    index: &CommitIndex,
+) -> Result<Option<QCStatus>> {
+    let comments = get_issue_comments(issue, cache, git_info).await?;
+    match IssueThread::from_issue_comments_with_index(issue, &comments, git_info, cache, index) {
+        Ok(thread) => Ok(Some(QCStatus::determine_status(&thread))),
+        Err(e) => {
+            log::debug!(
+                "Could not determine the status of issue #{}: {e}",
+                issue.number
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Table of the listed issues, with a QC Status column when `with_status`
+pub fn format_issue_list_table(entries: &[IssueListEntry], with_status: bool) -> String {
+    if entries.is_empty() {
+        return "No issues match the filters.".to_string();
+    }
+
+    let mut headers = vec!["File", "Issue", "Milestone", "Assignees", "State"];
+    if with_status {
+        headers.push("QC Status");
+    }
+    let rows: Vec<Vec<String>> = entries
+        .iter()
+        .map(|entry| {
+            let mut row = vec![
+                entry.file.clone(),
+                format!("#{}", entry.issue_number),
+                entry.milestone.clone(),
+                entry.assignees.join(", "),
+                if entry.open { "open" } else { "closed" }.to_string(),
+            ];
+            if with_status {
+                row.push(
+                    entry
+                        .qc_status
+                        .as_ref()
+                        .map_or("Unknown".to_string(), QCStatus::to_string),
+                );
+            }
+            row
+        })
+        .collect();
+
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .max()
+                .unwrap_or(0)
+                .max(header.len())
+        })
+        .collect();
+    let format_row = |cells: &[&str]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        format_row(&headers),
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    ];
+    lines.extend(
+        rows.iter()
+            .map(|row| format_row(&row.iter().map(String::as_str).collect::<Vec<_>>())),
+    );
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GitComment,
+        test_utils::{FakeGit, create_test_issue, create_test_milestone},
+    };
+    use serde_json::json;
+
+    const INITIAL_COMMIT: &str = "1234567890abcdef1234567890abcdef12345678";
+    const LATER_COMMIT: &str = "abcdef1234567890abcdef1234567890abcdef12";
+
+    fn issue(
+        number: u64,
+        file: &str,
+        milestone: i64,
+        state: &str,
+        assignees: &[&str],
+        updated_at: &str,
+    ) -> Issue {
+        let body = format!("git branch: main\ninitial qc commit: {INITIAL_COMMIT}\n");
+        let mut value = serde_json::to_value(create_test_issue(
+            "owner",
+            "repo",
+            number,
+            file,
+            &body,
+            Some(milestone),
+            state,
+        ))
+        .unwrap();
+        let user = value["user"].clone();
+        value["assignees"] = assignees
+            .iter()
+            .map(|login| {
+                let mut assignee = user.clone();
+                assignee["login"] = json!(login);
+                assignee
+            })
+            .collect();
+        value["updated_at"] = json!(updated_at);
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// Serves two milestones:
+    ///
+    /// | # | File            | Milestone | State  | Assignees  | QC Status          |
+    /// |---|-----------------|-----------|--------|------------|--------------------|
+    /// | 1 | scripts/model.R | v1.0      | open   | alice      | Awaiting review    |
+    /// | 2 | scripts/plots.R | v1.0      | open   | bob        | Changes to comment |
+    /// | 3 | scripts/model.R | v2.0      | closed | alice      | Approved           |
+    /// | 4 | docs/notes.md   | v2.0      | open   | alice, bob | Awaiting review    |
+    fn fixture_git() -> FakeGit {
+        FakeGit::new()
+            .with_milestones(milestones())
+            .with_issues(vec![
+                issue(
+                    1,
+                    "scripts/model.R",
+                    1,
+                    "open",
+                    &["alice"],
+                    "2026-01-03T00:00:00Z",
+                ),
+                issue(
+                    2,
+                    "scripts/plots.R",
+                    1,
+                    "open",
+                    &["bob"],
+                    "2026-01-05T00:00:00Z",
+                ),
+                issue(
+                    3,
+                    "scripts/model.R",
+                    2,
+                    "closed",
+                    &["alice"],
+                    "2026-01-02T00:00:00Z",
+                ),
+                issue(
+                    4,
+                    "docs/notes.md",
+                    2,
+                    "open",
+                    &["alice", "bob"],
+                    "2026-01-04T00:00:00Z",
+                ),
+            ])
+            .with_issue_comments(
+                3,
+                vec![GitComment {
+                    body: format!("# QC Approval\n\napproved qc commit: {INITIAL_COMMIT}"),
+                    author_login: "alice".to_string(),
+                    created_at: chrono::Utc::now(),
+                    html: None,
+                }],
+            )
+            .with_current_user("alice")
+            .with_commits(&[LATER_COMMIT, INITIAL_COMMIT])
+            // Only the plots have changed since the initial commit
+            .with_file_commits("scripts/plots.R", &[LATER_COMMIT, INITIAL_COMMIT])
+            .with_file_commits("scripts/model.R", &[INITIAL_COMMIT])
+            .with_file_commits("docs/notes.md", &[INITIAL_COMMIT])
+    }
+
+    fn milestones() -> Vec<Milestone> {
+        vec![
+            create_test_milestone("owner", "repo", 1, "v1.0", None, "open"),
+            create_test_milestone("owner", "repo", 2, "v2.0", None, "open"),
+        ]
+    }
+
+    async fn listed(git_info: &FakeGit, options: IssueListOptions) -> Vec<u64> {
+        list_issues(&milestones(), &options, None, git_info)
+            .await
+            .unwrap()
+            .iter()
+            .map(|entry| entry.issue_number)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_open_issues_by_file_without_status() {
+        let git_info = fixture_git();
+        let entries = list_issues(&milestones(), &IssueListOptions::default(), None, &git_info)
+            .await
+            .unwrap();
+
+        let listed: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|entry| (entry.file.as_str(), entry.milestone.as_str()))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("docs/notes.md", "v2.0"),
+                ("scripts/model.R", "v1.0"),
+                ("scripts/plots.R", "v1.0"),
+            ]
+        );
+        assert!(entries.iter().all(|entry| entry.qc_status.is_none()));
+        // Comments are only needed for the QC status
+        assert_eq!(git_info.comment_fetches(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_state() {
+        let git_info = fixture_git();
+        let closed = IssueListOptions {
+            state: IssueStateFilter::Closed,
+            ..Default::default()
+        };
+        let all = IssueListOptions {
+            state: IssueStateFilter::All,
+            ..Default::default()
+        };
+
+        assert_eq!(listed(&git_info, closed).await, vec![3]);
+        assert_eq!(listed(&git_info, all).await, vec![4, 1, 3, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_assignee() {
+        let git_info = fixture_git();
+        let options = IssueListOptions {
+            assignee: Some("alice".to_string()),
+            state: IssueStateFilter::All,
+            ..Default::default()
+        };
+
+        assert_eq!(listed(&git_info, options).await, vec![4, 1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_file_glob() {
+        let git_info = fixture_git();
+        let scripts = IssueListOptions {
+            file_glob: Some("scripts/*.R".to_string()),
+            ..Default::default()
+        };
+        let markdown = IssueListOptions {
+            file_glob: Some("*.md".to_string()),
+            ..Default::default()
+        };
+        let unsupported = IssueListOptions {
+            file_glob: Some("scripts/[a-m]*.R".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(listed(&git_info, scripts).await, vec![1, 2]);
+        assert_eq!(listed(&git_info, markdown).await, vec![4]);
+        let err = list_issues(&milestones(), &unsupported, None, &git_info)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported file pattern"));
+    }
+
+    #[tokio::test]
+    async fn test_list_file_glob_matches_windows_titles() {
+        let git_info = fixture_git().with_issue(issue(
+            5,
+            r".\scripts\\report.R",
+            1,
+            "open",
+            &["bob"],
+            "2026-01-06T00:00:00Z",
+        ));
+        let scripts = IssueListOptions {
+            file_glob: Some("scripts/*.R".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(listed(&git_info, scripts).await, vec![1, 2, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_status() {
+        let git_info = fixture_git();
+        let options = IssueListOptions {
+            state: IssueStateFilter::All,
+            statuses: vec![QCStatusFilter::AwaitingReview, QCStatusFilter::Approved],
+            ..Default::default()
+        };
+
+        let entries = list_issues(&milestones(), &options, None, &git_info)
+            .await
+            .unwrap();
+
+        let statuses: Vec<(u64, String)> = entries
+            .iter()
+            .map(|entry| {
+                let status = entry.qc_status.as_ref().unwrap().to_string();
+                (entry.issue_number, status)
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (4, "Awaiting review".to_string()),
+                (1, "Awaiting review".to_string()),
+                (3, "Approved".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_assignee_and_status() {
+        let git_info = fixture_git();
+        let options = IssueListOptions {
+            assignee: Some("bob".to_string()),
+            statuses: vec![QCStatusFilter::AwaitingReview],
+            ..Default::default()
+        };
+
+        assert_eq!(listed(&git_info, options).await, vec![4]);
+        // The status is only determined for bob's issues
+        assert_eq!(git_info.comment_fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_with_status_keeps_every_status() {
+        let git_info = fixture_git();
+        let options = IssueListOptions {
+            with_status: true,
+            sort: IssueSort::Updated,
+            ..Default::default()
+        };
+
+        let entries = list_issues(&milestones(), &options, None, &git_info)
+            .await
+            .unwrap();
+
+        let statuses: Vec<(u64, String)> = entries
+            .iter()
+            .map(|entry| {
+                let status = entry.qc_status.as_ref().unwrap().to_string();
+                (entry.issue_number, status)
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (2, "Changes to comment".to_string()),
+                (4, "Awaiting review".to_string()),
+                (1, "Awaiting review".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_selected_milestones_only() {
+        let git_info = fixture_git();
+        let entries = list_issues(
+            &milestones()[1..],
+            &IssueListOptions::default(),
+            None,
+            &git_info,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].issue_number, 4);
+    }
+
+    #[tokio::test]
+    async fn test_issue_list_table_and_json() {
+        let git_info = fixture_git();
+        let options = IssueListOptions {
+            file_glob: Some("scripts/**".to_string()),
+            with_status: true,
+            ..Default::default()
+        };
+        let entries = list_issues(&milestones(), &options, None, &git_info)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            format_issue_list_table(&entries, true),
+            "File            | Issue | Milestone | Assignees | State | QC Status\n\
+             ----------------+-------+-----------+-----------+-------+-------------------\n\
+             scripts/model.R | #1    | v1.0      | alice     | open  | Awaiting review\n\
+             scripts/plots.R | #2    | v1.0      | bob       | open  | Changes to comment"
+        );
+        assert_eq!(
+            format_issue_list_table(&[], false),
+            "No issues match the filters."
+        );
+
+        let json = serde_json::to_value(&entries[0]).unwrap();
+        assert_eq!(json["file"], "scripts/model.R");
+        assert_eq!(json["assignees"], json!(["alice"]));
+        assert_eq!(json["open"], true);
+        assert_eq!(json["qc_status"], "awaiting_review");
+    }
+}
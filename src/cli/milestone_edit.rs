@@ -0,0 +1,376 @@
+use anyhow::{Result, anyhow, bail};
+use octocrab::models::{Milestone, issues::Issue};
+use serde::Serialize;
+use std::fmt;
+
+use crate::{GitHubWriter, MilestoneState, MilestoneUpdate};
+
+/// What a `milestone close`, `reopen` or `edit` run did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneAction {
+    Closed,
+    Reopened,
+    Edited,
+}
+
+/// The outcome of a `milestone close`, `reopen` or `edit` run
+#[derive(Debug, Clone, Serialize)]
+pub struct MilestoneEdit {
+    pub action: MilestoneAction,
+    pub milestone_number: u64,
+    pub title: String,
+    pub url: String,
+    pub state: MilestoneState,
+    /// False when the milestone already was in the requested state and GitHub was left untouched
+    pub changed: bool,
+    /// Open issues of a milestone closed with `--force`
+    pub open_issues: Vec<u64>,
+}
+
+impl MilestoneEdit {
+    fn new(action: MilestoneAction, milestone: &Milestone, changed: bool) -> Self {
+        Self {
+            action,
+            milestone_number: milestone.number as u64,
+            title: milestone.title.clone(),
+            url: milestone.html_url.to_string(),
+            state: MilestoneState::of(milestone),
+            changed,
+            open_issues: Vec::new(),
+        }
+    }
+}
+
+/// The milestone titled `name`
+pub fn find_milestone<'a>(milestones: &'a [Milestone], name: &str) -> Result<&'a Milestone> {
+    milestones
+        .iter()
+        .find(|m| m.title == name)
+        .ok_or(anyhow!("Milestone '{name}' not found"))
+}
+
+/// Close a milestone on GitHub
+///
+/// Refuses while `issues` holds open issues unless `force` is set, so a milestone is not closed
+/// with QC still in progress. Closing an already closed milestone changes nothing.
+pub async fn close_milestone(
+    milestone: &Milestone,
+    issues: &[Issue],
+    force: bool,
+    git_info: &impl GitHubWriter,
+) -> Result<MilestoneEdit> {
+    if MilestoneState::of(milestone) == MilestoneState::Closed {
+        log::warn!("Milestone '{}' is already closed", milestone.title);
+        return Ok(MilestoneEdit::new(
+            MilestoneAction::Closed,
+            milestone,
+            false,
+        ));
+    }
+
+    let mut open_issues: Vec<&Issue> = issues
+        .iter()
+        .filter(|issue| issue.state == octocrab::models::IssueState::Open)
+        .collect();
+    open_issues.sort_by_key(|issue| issue.number);
+
+    if !open_issues.is_empty() && !force {
+        let listed = open_issues
+            .iter()
+            .map(|issue| format!("  #{} {}", issue.number, issue.title))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "Milestone '{}' has {} open issue(s):\n{listed}\nClose them first or rerun with --force",
+            milestone.title,
+            open_issues.len()
+        );
+    }
+
+    let closed = git_info.close_milestone(milestone.number as u64).await?;
+    log::debug!("Closed milestone #{} '{}'", closed.number, closed.title);
+
+    let mut edit = MilestoneEdit::new(MilestoneAction::Closed, &closed, true);
+    edit.open_issues = open_issues.iter().map(|issue| issue.number).collect();
+    Ok(edit)
+}
+
+/// Reopen a closed milestone on GitHub. Reopening an open milestone changes nothing
+pub async fn reopen_milestone(
+    milestone: &Milestone,
+    git_info: &impl GitHubWriter,
+) -> Result<MilestoneEdit> {
+    if MilestoneState::of(milestone) == MilestoneState::Open {
+        log::warn!("Milestone '{}' is already open", milestone.title);
+        return Ok(MilestoneEdit::new(
+            MilestoneAction::Reopened,
+            milestone,
+            false,
+        ));
+    }
+
+    let update = MilestoneUpdate {
+        state: Some(MilestoneState::Open),
+        ..Default::default()
+    };
+    let reopened = git_info
+        .update_milestone(milestone.number as u64, &update)
+        .await?;
+    log::debug!(
+        "Reopened milestone #{} '{}'",
+        reopened.number,
+        reopened.title
+    );
+
+    Ok(MilestoneEdit::new(
+        MilestoneAction::Reopened,
+        &reopened,
+        true,
+    ))
+}
+
+/// Change the title, description and/or due date of a milestone on GitHub
+///
+/// Refuses an empty update and a title another milestone already has, ignoring case. Use
+/// `milestone rename` to also repair the cache and issue references to the old title.
+pub async fn edit_milestone(
+    milestone: &Milestone,
+    milestones: &[Milestone],
+    update: &MilestoneUpdate,
+    git_info: &impl GitHubWriter,
+) -> Result<MilestoneEdit> {
+    if update.title.is_none() && update.description.is_none() && update.due_on.is_none() {
+        bail!("Nothing to edit: pass --title, --description or --due-on");
+    }
+
+    if let Some(title) = &update.title {
+        if title.trim().is_empty() {
+            bail!("The new milestone title cannot be empty");
+        }
+        let target = title.to_lowercase();
+        if let Some(existing) = milestones
+            .iter()
+            .find(|m| m.number != milestone.number && m.title.to_lowercase() == target)
+        {
+            bail!(
+                "Milestone '{}' (#{}) already exists",
+                existing.title,
+                existing.number
+            );
+        }
+    }
+
+    let edited = git_info
+        .update_milestone(milestone.number as u64, update)
+        .await?;
+    log::debug!("Edited milestone #{} '{}'", edited.number, edited.title);
+
+    Ok(MilestoneEdit::new(MilestoneAction::Edited, &edited, true))
+}
+
+impl fmt::Display for MilestoneEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.changed {
+            writeln!(
+                f,
+                "⚠️ Milestone #{} '{}' is already {}; nothing changed",
+                self.milestone_number,
+                self.title,
+                self.state.as_str()
+            )?;
+        } else {
+            let action = match self.action {
+                MilestoneAction::Closed => "Closed",
+                MilestoneAction::Reopened => "Reopened",
+                MilestoneAction::Edited => "Edited",
+            };
+            writeln!(
+                f,
+                "✅ {action} milestone #{} '{}' (now {})",
+                self.milestone_number,
+                self.title,
+                self.state.as_str()
+            )?;
+        }
+
+        if !self.open_issues.is_empty() {
+            let issues = self
+                .open_issues
+                .iter()
+                .map(|number| format!("#{number}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "⚠️ Closed with open issues: {issues}")?;
+        }
+
+        write!(f, "{}", self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::MockGitHubWriter;
+    use crate::test_utils::{create_test_issue, create_test_milestone};
+    use chrono::NaiveDate;
+
+    fn milestone(state: &str) -> Milestone {
+        create_test_milestone("owner", "repo", 1, "Sprint 1", None, state)
+    }
+
+    fn issue(number: u64, state: &str) -> Issue {
+        create_test_issue(
+            "owner",
+            "repo",
+            number,
+            &format!("src/file_{number}.R"),
+            "",
+            Some(1),
+            state,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_close_refuses_open_issues_without_force() {
+        let issues = vec![issue(4, "open"), issue(2, "closed"), issue(3, "open")];
+
+        let err = close_milestone(&milestone("open"), &issues, false, &MockGitHubWriter::new())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Milestone 'Sprint 1' has 2 open issue(s):\n  #3 src/file_3.R\n  #4 src/file_4.R\n\
+            Close them first or rerun with --force"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_with_force_reports_open_issues() {
+        let mut writer = MockGitHubWriter::new();
+        writer
+            .expect_close_milestone()
+            .withf(|number| *number == 1)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(milestone("closed")) }));
+
+        let issues = vec![issue(3, "open"), issue(2, "closed")];
+        let edit = close_milestone(&milestone("open"), &issues, true, &writer)
+            .await
+            .unwrap();
+
+        assert!(edit.changed);
+        assert_eq!(edit.state, MilestoneState::Closed);
+        assert_eq!(edit.open_issues, vec![3]);
+        assert!(edit.to_string().contains("Closed with open issues: #3"));
+    }
+
+    #[tokio::test]
+    async fn test_reopen_open_milestone_is_noop() {
+        // No expectations: any write would panic
+        let edit = reopen_milestone(&milestone("open"), &MockGitHubWriter::new())
+            .await
+            .unwrap();
+
+        assert!(!edit.changed);
+        assert_eq!(
+            edit.to_string(),
+            "⚠️ Milestone #1 'Sprint 1' is already open; nothing changed\n\
+            https://github.com/owner/repo/milestone/1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reopen_closed_milestone() {
+        let mut writer = MockGitHubWriter::new();
+        writer
+            .expect_update_milestone()
+            .withf(|number, update| {
+                *number == 1
+                    && *update
+                        == MilestoneUpdate {
+                            state: Some(MilestoneState::Open),
+                            ..Default::default()
+                        }
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(milestone("open")) }));
+
+        let edit = reopen_milestone(&milestone("closed"), &writer)
+            .await
+            .unwrap();
+        assert!(edit.changed);
+        assert_eq!(edit.state, MilestoneState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_edit_description_and_due_date() {
+        let update = MilestoneUpdate {
+            description: Some("QC of the final analysis".to_string()),
+            due_on: NaiveDate::from_ymd_opt(2026, 12, 1),
+            ..Default::default()
+        };
+        let expected = update.clone();
+
+        let mut writer = MockGitHubWriter::new();
+        writer
+            .expect_update_milestone()
+            .withf(move |number, update| *number == 1 && *update == expected)
+            .times(1)
+            .returning(|_, _| {
+                Box::pin(async {
+                    Ok(create_test_milestone(
+                        "owner",
+                        "repo",
+                        1,
+                        "Sprint 1",
+                        Some("QC of the final analysis"),
+                        "open",
+                    ))
+                })
+            });
+
+        let milestones = vec![milestone("open")];
+        let edit = edit_milestone(&milestones[0], &milestones, &update, &writer)
+            .await
+            .unwrap();
+        assert_eq!(edit.action, MilestoneAction::Edited);
+        assert!(edit.changed);
+
+        let json = serde_json::to_value(&edit).unwrap();
+        assert_eq!(json["action"], "edited");
+        assert_eq!(json["state"], "open");
+        assert_eq!(json["url"], "https://github.com/owner/repo/milestone/1");
+    }
+
+    #[tokio::test]
+    async fn test_edit_refuses_empty_update_and_existing_title() {
+        let milestones = vec![
+            milestone("open"),
+            create_test_milestone("owner", "repo", 2, "Sprint 2", None, "closed"),
+        ];
+        let writer = MockGitHubWriter::new();
+
+        let err = edit_milestone(
+            &milestones[0],
+            &milestones,
+            &MilestoneUpdate::default(),
+            &writer,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Nothing to edit: pass --title, --description or --due-on"
+        );
+
+        let update = MilestoneUpdate {
+            title: Some("sprint 2".to_string()),
+            ..Default::default()
+        };
+        let err = edit_milestone(&milestones[0], &milestones, &update, &writer)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Milestone 'Sprint 2' (#2) already exists");
+    }
+}
@@ -0,0 +1,517 @@
+use anyhow::{Result, anyhow, bail};
+use octocrab::models::{Milestone, issues::Issue};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::normalize_repo_path;
+use crate::{
+    DiskCache, GitHubReader, GitHubWriter, QCRelationship, RelevantFileLink,
+    parse_relevant_file_links,
+};
+
+/// Settings of `milestone rename`
+#[derive(Debug, Clone)]
+pub struct MilestoneRenameOptions {
+    /// Current title of the milestone
+    pub from: String,
+    /// New title of the milestone
+    pub to: String,
+    /// Rewrite the old title in the relevant QC descriptions of the milestone's issues
+    pub fix_references: bool,
+}
+
+/// A previous, gating or relevant QC entry of an issue whose description names the old title
+#[derive(Debug, Clone, PartialEq)]
+pub struct MilestoneReference {
+    pub issue_number: u64,
+    /// The file of the issue holding the entry
+    pub file: PathBuf,
+    pub relationship: QCRelationship,
+    /// The file of the QC the entry links to
+    pub linked_file: PathBuf,
+    pub description: String,
+}
+
+/// The outcome of a `milestone rename` run
+#[derive(Debug, Clone)]
+pub struct MilestoneRename {
+    pub milestone_number: u64,
+    pub from: String,
+    pub to: String,
+    /// Cache files removed because they held the old title
+    pub invalidated: Vec<PathBuf>,
+    pub references: Vec<MilestoneReference>,
+    /// Issues whose body was rewritten to name the new title. Empty without `--fix-references`
+    pub fixed_issues: Vec<u64>,
+}
+
+/// Rename a milestone on GitHub and repair what recorded its old title
+///
+/// Refuses when another milestone already has the new title, ignoring case. The cached
+/// milestone listings and the issue events of the milestone are invalidated, and the relevant QC
+/// descriptions of its issues naming the old title are reported, or rewritten when
+/// `fix_references` is set.
+pub async fn rename_milestone(
+    options: &MilestoneRenameOptions,
+    milestones: &[Milestone],
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitHubWriter),
+) -> Result<MilestoneRename> {
+    if options.from == options.to {
+        bail!("Milestone '{}' already has that title", options.from);
+    }
+    if options.to.trim().is_empty() {
+        bail!("The new milestone title cannot be empty");
+    }
+
+    let source = milestones
+        .iter()
+        .find(|m| m.title == options.from)
+        .ok_or(anyhow!("Milestone '{}' not found", options.from))?;
+    let milestone_number = source.number as u64;
+
+    let target = options.to.to_lowercase();
+    if let Some(existing) = milestones
+        .iter()
+        .find(|m| m.number != source.number && m.title.to_lowercase() == target)
+    {
+        bail!(
+            "Milestone '{}' (#{}) already exists",
+            existing.title,
+            existing.number
+        );
+    }
+
+    let mut issues = git_info.get_issues(Some(milestone_number)).await?;
+    issues.sort_by_key(|issue| issue.number);
+
+    git_info
+        .update_milestone_title(milestone_number, &options.to)
+        .await?;
+    log::debug!(
+        "Renamed milestone #{milestone_number} '{}' to '{}'",
+        options.from,
+        options.to
+    );
+
+    let invalidated = cache
+        .map(|cache| invalidate_milestone_cache(cache, milestone_number, &issues))
+        .unwrap_or_default();
+
+    let references: Vec<_> = issues
+        .iter()
+        .flat_map(|issue| milestone_references(issue, &options.from))
+        .collect();
+
+    let mut fixed_issues = Vec::new();
+    if options.fix_references {
+        for issue in &issues {
+            if !references.iter().any(|r| r.issue_number == issue.number) {
+                continue;
+            }
+            let body = issue.body.as_deref().unwrap_or_default();
+            let new_body = replace_milestone_references(body, &options.from, &options.to);
+
+            let updated = match git_info.guard_issue(issue) {
+                Ok(()) => {
+                    git_info
+                        .update_issue(issue.number, None, Some(new_body))
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            match updated {
+                Ok(()) => fixed_issues.push(issue.number),
+                Err(e) => log::warn!(
+                    "Failed to rewrite milestone references of issue #{}: {e}",
+                    issue.number
+                ),
+            }
+        }
+    }
+
+    Ok(MilestoneRename {
+        milestone_number,
+        from: options.from.clone(),
+        to: options.to.clone(),
+        invalidated,
+        references,
+        fixed_issues,
+    })
+}
+
+/// Remove the cache entries holding the milestone's title, returning the files removed
+///
+/// These are the milestone and file completions and the events of the milestone's issues,
+/// whose `milestoned` events name the milestone. Neither is keyed by anything that changes on
+/// a rename, so they would otherwise keep serving the old title.
+fn invalidate_milestone_cache(
+    cache: &DiskCache,
+    milestone_number: u64,
+    issues: &[Issue],
+) -> Vec<PathBuf> {
+    let number = milestone_number.to_string();
+    let issue_keys: Vec<String> = issues
+        .iter()
+        .map(|issue| format!("issue_{}", issue.number))
+        .collect();
+
+    let entries = [
+        (&["completions"][..], "milestones"),
+        (&["completions", "files"][..], number.as_str()),
+    ]
+    .into_iter()
+    .chain(
+        issue_keys
+            .iter()
+            .map(|key| (&["issues", "events"][..], key.as_str())),
+    );
+
+    let mut invalidated = Vec::new();
+    for (path, key) in entries {
+        let file = cache.path(path, key);
+        if !file.exists() {
+            continue;
+        }
+        match cache.invalidate(path, key) {
+            Ok(()) => invalidated.push(file),
+            Err(e) => log::warn!("Failed to invalidate cache entry {}: {e}", file.display()),
+        }
+    }
+    invalidated
+}
+
+/// The previous, gating and relevant QC entries of `issue` whose description names `title`
+pub fn milestone_references(issue: &Issue, title: &str) -> Vec<MilestoneReference> {
+    let body = issue.body.as_deref().unwrap_or_default();
+
+    parse_relevant_file_links(body)
+        .into_iter()
+        .filter_map(|link| match link {
+            RelevantFileLink::Issue {
+                file_name,
+                relationship,
+                description: Some(description),
+                ..
+            } if description.contains(title) => Some(MilestoneReference {
+                issue_number: issue.number,
+                file: normalize_repo_path(Path::new(&issue.title)),
+                relationship,
+                linked_file: file_name,
+                description,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Replace `from` with `to` in the descriptions of the QC entries of the issue body's
+/// "## Relevant Files" section, leaving the rest of the body untouched
+pub fn replace_milestone_references(body: &str, from: &str, to: &str) -> String {
+    let mut in_qc_subsection = false;
+    let mut in_section = false;
+
+    let lines: Vec<String> = body
+        .split('\n')
+        .map(|line| {
+            let trimmed = line.trim_end();
+            if trimmed.starts_with("# ") || trimmed.starts_with("## ") {
+                in_section = trimmed == "## Relevant Files";
+                in_qc_subsection = false;
+                return line.to_string();
+            }
+            if let Some(heading) = trimmed.strip_prefix("### ") {
+                in_qc_subsection = in_section
+                    && matches!(heading.trim(), "Previous QC" | "Gating QC" | "Relevant QC");
+                return line.to_string();
+            }
+            if !in_qc_subsection {
+                return line.to_string();
+            }
+
+            // Only the description, after the link, names the milestone
+            match line.find(") - ") {
+                Some(index) => {
+                    let (link, description) = line.split_at(index);
+                    format!("{link}{}", description.replace(from, to))
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+fn relationship_name(relationship: QCRelationship) -> &'static str {
+    match relationship {
+        QCRelationship::PreviousQC => "previous QC",
+        QCRelationship::GatingQC => "gating QC",
+        QCRelationship::RelevantQC => "relevant QC",
+    }
+}
+
+impl fmt::Display for MilestoneRename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✅ Renamed milestone #{}: '{}' → '{}'",
+            self.milestone_number, self.from, self.to
+        )?;
+
+        writeln!(f, "\nCache entries invalidated: {}", self.invalidated.len())?;
+        for file in &self.invalidated {
+            writeln!(f, "  {}", file.display())?;
+        }
+
+        writeln!(
+            f,
+            "\nReferences to '{}' found: {}",
+            self.from,
+            self.references.len()
+        )?;
+        for reference in &self.references {
+            let state = if self.fixed_issues.contains(&reference.issue_number) {
+                " (fixed)"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "  #{} {}: {} {} - {}{state}",
+                reference.issue_number,
+                reference.file.display(),
+                relationship_name(reference.relationship),
+                reference.linked_file.display(),
+                reference.description
+            )?;
+        }
+
+        let unfixed = self
+            .references
+            .iter()
+            .filter(|r| !self.fixed_issues.contains(&r.issue_number))
+            .count();
+        if unfixed > 0 {
+            write!(
+                f,
+                "\nRun with --fix-references to rewrite the remaining references"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{FakeGit, create_test_issue, create_test_milestone};
+
+    const BODY: &str = "## Metadata\n* initial qc commit: abc1234\n\n## Relevant Files\n\n\
+        ### Previous QC\n\
+        - [src/model.R](https://github.com/owner/repo/issues/3) - Approved in Sprint 1\n\n\
+        ### Gating QC\n\
+        - [src/data.R](https://github.com/owner/repo/issues/4) - Upstream data\n\n\
+        ### Relevant QC\n\
+        - [src/plots.R](https://github.com/owner/repo/issues/5) - Sprint 1 plots\n\n\
+        # Sprint 1 checklist\n- [ ] Review the Sprint 1 outputs";
+
+    /// Serves the issues of milestone `Sprint 1`
+    fn fixture_git() -> FakeGit {
+        FakeGit::new()
+            .with_milestones(milestones())
+            .with_issues(vec![
+                issue(12, "src/report.R", BODY),
+                issue(
+                    11,
+                    "src/summary.R",
+                    "## Metadata\n* initial qc commit: abc1234",
+                ),
+            ])
+    }
+
+    fn issue(number: u64, title: &str, body: &str) -> Issue {
+        create_test_issue("owner", "repo", number, title, body, Some(1), "open")
+    }
+
+    fn milestones() -> Vec<Milestone> {
+        vec![
+            create_test_milestone("owner", "repo", 1, "Sprint 1", None, "open"),
+            create_test_milestone("owner", "repo", 2, "Sprint 2", None, "open"),
+        ]
+    }
+
+    fn options(to: &str, fix_references: bool) -> MilestoneRenameOptions {
+        MilestoneRenameOptions {
+            from: "Sprint 1".to_string(),
+            to: to.to_string(),
+            fix_references,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_refuses_existing_title() {
+        let git = fixture_git();
+
+        let err = rename_milestone(&options("sprint 2", false), &milestones(), None, &git)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Milestone 'Sprint 2' (#2) already exists");
+
+        let err = rename_milestone(&options("Sprint 1", false), &milestones(), None, &git)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Milestone 'Sprint 1' already has that title"
+        );
+
+        assert!(git.writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rename_allows_changing_case() {
+        let git = fixture_git();
+
+        let rename = rename_milestone(&options("SPRINT 1", false), &milestones(), None, &git)
+            .await
+            .unwrap();
+        assert_eq!(rename.milestone_number, 1);
+        assert_eq!(
+            git.write_details(),
+            vec![("update_milestone_title", 1, "SPRINT 1".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_reports_references_without_fixing() {
+        let git = fixture_git();
+
+        let rename = rename_milestone(&options("Sprint 1b", false), &milestones(), None, &git)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            git.write_details(),
+            vec![("update_milestone_title", 1, "Sprint 1b".to_string())]
+        );
+        assert_eq!(
+            rename.references,
+            vec![
+                MilestoneReference {
+                    issue_number: 12,
+                    file: PathBuf::from("src/report.R"),
+                    relationship: QCRelationship::PreviousQC,
+                    linked_file: PathBuf::from("src/model.R"),
+                    description: "Approved in Sprint 1".to_string(),
+                },
+                MilestoneReference {
+                    issue_number: 12,
+                    file: PathBuf::from("src/report.R"),
+                    relationship: QCRelationship::RelevantQC,
+                    linked_file: PathBuf::from("src/plots.R"),
+                    description: "Sprint 1 plots".to_string(),
+                },
+            ]
+        );
+        assert!(rename.fixed_issues.is_empty());
+        assert!(
+            rename
+                .to_string()
+                .contains("Run with --fix-references to rewrite the remaining references")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_fixes_references() {
+        let git = fixture_git();
+
+        let rename = rename_milestone(&options("Sprint 1b", true), &milestones(), None, &git)
+            .await
+            .unwrap();
+        assert_eq!(rename.fixed_issues, vec![12]);
+
+        let writes = git.write_details();
+        assert_eq!(writes.len(), 2);
+        let (write, number, body) = &writes[1];
+        assert_eq!((*write, *number), ("update_issue", 12));
+        assert!(body.contains("issues/3) - Approved in Sprint 1b\n"));
+        assert!(body.contains("issues/5) - Sprint 1b plots\n"));
+        // Text outside the relevant QC descriptions keeps the old title
+        assert!(body.contains("# Sprint 1 checklist\n- [ ] Review the Sprint 1 outputs"));
+    }
+
+    #[tokio::test]
+    async fn test_rename_invalidates_milestone_cache_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::at_root(
+            dir.path().to_path_buf(),
+            "owner".to_string(),
+            "repo".to_string(),
+        );
+        let milestone_list = vec![("Sprint 1".to_string(), 1u64)];
+        cache
+            .write(&["completions"], "milestones", &milestone_list, true)
+            .unwrap();
+        cache
+            .write(&["completions", "files"], "1", &vec!["src/report.R"], true)
+            .unwrap();
+        cache
+            .write(&["completions", "files"], "2", &vec!["src/other.R"], true)
+            .unwrap();
+        cache
+            .write(
+                &["issues", "events"],
+                "issue_12",
+                &Vec::<String>::new(),
+                false,
+            )
+            .unwrap();
+        cache
+            .write(
+                &["issues", "comments"],
+                "issue_12",
+                &Vec::<String>::new(),
+                false,
+            )
+            .unwrap();
+
+        let git = fixture_git();
+        let rename = rename_milestone(
+            &options("Sprint 1b", false),
+            &milestones(),
+            Some(&cache),
+            &git,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            rename.invalidated,
+            vec![
+                cache.path(&["completions"], "milestones"),
+                cache.path(&["completions", "files"], "1"),
+                cache.path(&["issues", "events"], "issue_12"),
+            ]
+        );
+        assert!(rename.invalidated.iter().all(|file| !file.exists()));
+        assert!(cache.path(&["completions", "files"], "2").exists());
+        assert!(cache.path(&["issues", "comments"], "issue_12").exists());
+    }
+
+    #[test]
+    fn test_replace_milestone_references_keeps_links() {
+        let body = "## Relevant Files\n\n### Gating QC\n\
+            - [Sprint 1/data.R](https://github.com/owner/repo/issues/4) - From Sprint 1\n\n\
+            ### Relevant File\n**notes.md** - Sprint 1 notes\n";
+        assert_eq!(
+            replace_milestone_references(body, "Sprint 1", "Sprint 2"),
+            "## Relevant Files\n\n### Gating QC\n\
+            - [Sprint 1/data.R](https://github.com/owner/repo/issues/4) - From Sprint 2\n\n\
+            ### Relevant File\n**notes.md** - Sprint 1 notes\n"
+        );
+    }
+}
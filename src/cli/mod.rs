@@ -1,12 +1,23 @@
 mod archive;
 mod auth;
+mod batch_create;
 pub mod cache;
+mod complete;
 mod context;
+mod copy_plan;
+mod coverage;
 mod file_parser;
+mod finalize;
 mod interactive;
+mod issue_list;
+mod milestone_edit;
+mod milestone_rename;
+mod output;
+mod pager;
 pub mod rename;
 mod sitrep;
 mod status;
+mod time;
 
 use owo_colors::OwoColorize;
 
@@ -25,22 +36,63 @@ pub(crate) fn section_header(title: &str) -> String {
 }
 
 pub use archive::{
-    MilestoneSelectionFilter, generate_archive_name, get_milestone_issue_threads, prompt_archive,
+    ARCHIVE_RECORD_FILE, MilestoneSelectionFilter, archive_files_from_threads, archive_record,
+    check_duplicate_files, get_milestone_issue_threads, milestone_archive_files, optional_record,
+    prompt_archive,
 };
 pub use auth::{gh_auth_login, gh_auth_logout, gh_auth_status, gh_auth_token};
+pub use batch_create::{
+    BatchCreateOptions, BatchCreateReport, BatchFileOutcome, create_issue_batch,
+    expand_file_patterns,
+};
 pub use cache::{CacheCommands, handle_cache};
-pub use context::find_issue;
+pub use complete::{
+    CompleteCommands, CompletionShell, checklist_candidates, dynamic_completions, file_candidates,
+    milestone_candidates,
+};
+pub use context::{
+    ChecklistArg, CreateOptions, IssueDiffRange, PostedReview, ReassignSettings, ReviewBatchReport,
+    ReviewSettings, ReviewTargets, find_issue,
+};
+pub use copy_plan::{
+    CopyPlan, CopyPlanOptions, CopyPlanOutcome, PlannedIssue, SkipReason, SkippedIssue,
+    SourceRelevantFile, copy_milestone_plan, plan_milestone_copy,
+};
+pub use coverage::milestone_coverage;
 pub use file_parser::{
-    FileCommitPair, FileCommitPairParser, IssueUrlArg, IssueUrlArgParser, RelevantFileArg,
-    RelevantFileArgParser,
+    CommitRange, FileCommitPair, FileCommitPairParser, IssueUrlArg, IssueUrlArgParser,
+    RelevantFileArg, RelevantFileArgParser,
 };
+pub use finalize::{MilestoneFinalization, MilestoneFinalizeOptions, finalize_milestone};
 pub use interactive::{
+    CANCELLED_EXIT_CODE, InquirePrompter, PromptCancelled, PromptOutcome, Prompter, TerminalGuard,
     prompt_assignees, prompt_checklist, prompt_collaborators, prompt_context_files,
     prompt_existing_milestone, prompt_file, prompt_issue, prompt_milestone,
-    prompt_milestone_archive, prompt_milestone_record,
+    prompt_milestone_archive, prompt_milestone_in_state, prompt_milestone_record,
+};
+pub use issue_list::{
+    IssueListEntry, IssueListOptions, IssueSort, IssueStateFilter, QCStatusFilter,
+    format_issue_list_table, list_issues,
+};
+pub use milestone_edit::{
+    MilestoneAction, MilestoneEdit, close_milestone, edit_milestone, find_milestone,
+    reopen_milestone,
+};
+pub use milestone_rename::{
+    MilestoneReference, MilestoneRename, MilestoneRenameOptions, milestone_references,
+    rename_milestone, replace_milestone_references,
+};
+pub use output::{
+    ArchiveResult, ChecklistChange, ChecklistItems, ChecklistUpdateResult, CommentDraft,
+    ConfigurationSetupResult, ConfigurationStatusResult, FinalizeResult, OutputFormat,
+    PostedComment, RecordResult, SplitRecordResult, output_format, print_message, print_result,
+    set_output_format,
 };
+pub use pager::page;
 pub use rename::{confirm_rename_noninteractive, interactive_rename};
 pub use sitrep::SitRep;
 pub use status::{
-    interactive_milestone_status, interactive_status, milestone_status, single_issue_status,
+    file_behind_report, interactive_milestone_status, interactive_status, milestone_status,
+    single_issue_status,
 };
+pub use time::{find_time_log_issue, issue_time, milestone_time};
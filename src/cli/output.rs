@@ -0,0 +1,357 @@
+//! Where the output of a command goes
+//!
+//! With `--output json`, a command prints its result as a single JSON object on stdout, while
+//! progress, warnings and summaries go to stderr so they do not mix with the result.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::{
+    CacheHealth, ChecklistItem, Configuration, GitRepository, GitStatusOps, configuration_status,
+};
+
+/// Format of the output of a command
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON object on stdout, with all other messages on stderr
+    Json,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Set the output format of the process, once at startup
+pub fn set_output_format(format: OutputFormat) {
+    if OUTPUT_FORMAT.set(format).is_err() {
+        log::debug!("Output format already set");
+    }
+}
+
+pub fn output_format() -> OutputFormat {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Print a message which is not the result of the command: to stdout for text output, to stderr
+/// for JSON output. Use through [`message!`](crate::message)
+#[doc(hidden)]
+pub fn print_message(args: fmt::Arguments) {
+    match output_format() {
+        OutputFormat::Text => println!("{args}"),
+        OutputFormat::Json => eprintln!("{args}"),
+    }
+}
+
+/// `println!` for progress, prompts summaries and warnings, which go to stderr with
+/// `--output json`
+#[macro_export]
+macro_rules! message {
+    () => {
+        $crate::cli::print_message(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::cli::print_message(format_args!($($arg)*))
+    };
+}
+
+/// Print the result of a command: its text, or its JSON object with `--output json`
+pub fn print_result<T: Serialize + fmt::Display>(result: &T) -> serde_json::Result<()> {
+    match output_format() {
+        OutputFormat::Text => println!("{result}"),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(result)?),
+    }
+    Ok(())
+}
+
+/// A comment posted on an issue
+#[derive(Debug, Clone, Serialize)]
+pub struct PostedComment {
+    /// e.g. `✅ Comment created!`
+    #[serde(skip)]
+    pub headline: String,
+    pub issue_number: u64,
+    pub comment_url: String,
+    /// What happened to the reviewed file's changes after a review
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stash_message: Option<String>,
+}
+
+impl PostedComment {
+    pub fn new(headline: impl Into<String>, issue_number: u64, comment_url: String) -> Self {
+        Self {
+            headline: headline.into(),
+            issue_number,
+            comment_url,
+            stash_message: None,
+        }
+    }
+}
+
+impl fmt::Display for PostedComment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.headline, self.comment_url)?;
+        if let Some(message) = &self.stash_message {
+            write!(f, "\n{message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A comment built with `--draft` instead of being posted
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentDraft {
+    pub issue_url: String,
+    /// Markdown of the comment, when not written to `path`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for CommentDraft {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "📝 Draft for {}", self.issue_url)?;
+        if let Some(path) = &self.path {
+            write!(f, "\n📝 Draft written to {}", path.display())?;
+        }
+        if let Some(draft) = &self.draft {
+            write!(f, "\n\n{draft}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A checklist item checked or unchecked by `ghqc issue checklist`
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistChange {
+    /// 1-based position of the item in the issue body
+    pub index: usize,
+    pub text: String,
+    pub checked: bool,
+}
+
+/// The checklist items of an issue changed by `ghqc issue checklist`
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistUpdateResult {
+    pub issue_number: u64,
+    pub issue_url: String,
+    pub changes: Vec<ChecklistChange>,
+}
+
+impl ChecklistUpdateResult {
+    /// Result of the `changes` made by [`update_checklist`](crate::update_checklist), each item
+    /// with its new state
+    pub fn new(issue_number: u64, issue_url: String, changes: &[(ChecklistItem, bool)]) -> Self {
+        Self {
+            issue_number,
+            issue_url,
+            changes: changes
+                .iter()
+                .map(|(item, checked)| ChecklistChange {
+                    index: item.index,
+                    text: item.text.clone(),
+                    checked: *checked,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ChecklistUpdateResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            writeln!(
+                f,
+                "☑️ Checklist of issue #{} is already up to date",
+                self.issue_number
+            )?;
+        } else {
+            for change in &self.changes {
+                let mark = if change.checked { "x" } else { " " };
+                writeln!(f, "   [{mark}] {}. {}", change.index, change.text)?;
+            }
+            writeln!(f, "☑️ Checklist of issue #{} updated!", self.issue_number)?;
+        }
+        write!(f, "{}", self.issue_url)
+    }
+}
+
+/// The checklist items of an issue, listed by `ghqc issue checklist --list`
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistItems {
+    pub issue_number: u64,
+    pub items: Vec<ChecklistItem>,
+}
+
+impl fmt::Display for ChecklistItems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut checklist = None;
+        let mut lines = Vec::new();
+        for item in &self.items {
+            if checklist != Some(&item.checklist) {
+                lines.push(item.checklist.clone());
+                checklist = Some(&item.checklist);
+            }
+            let mark = if item.checked { "x" } else { " " };
+            lines.push(format!("  {:>3}. [{mark}] {}", item.index, item.text));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// A record or issue preview rendered to a file
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordResult {
+    /// e.g. `✅ Record successfully generated at`
+    #[serde(skip)]
+    pub headline: String,
+    pub path: PathBuf,
+}
+
+impl fmt::Display for RecordResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.headline, self.path.display())
+    }
+}
+
+/// Records rendered one per milestone with `--split-by-milestone`
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitRecordResult {
+    pub records: Vec<PathBuf>,
+    pub index: PathBuf,
+    /// Milestones whose record could not be rendered, with why
+    pub failures: BTreeMap<String, String>,
+}
+
+impl fmt::Display for SplitRecordResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✅ Generated {} of {} milestone records:",
+            self.records.len(),
+            self.records.len() + self.failures.len()
+        )?;
+        for record in &self.records {
+            writeln!(f, "   📄 {}", record.display())?;
+        }
+        for (milestone, e) in &self.failures {
+            writeln!(f, "   ❌ {milestone}: {e}")?;
+        }
+        write!(f, "   📋 Index: {}", self.index.display())
+    }
+}
+
+/// An archive written by `ghqc milestone archive`
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveResult {
+    pub path: PathBuf,
+    pub file_count: usize,
+    /// Where the archive's manifest.json was also written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<PathBuf>,
+}
+
+impl fmt::Display for ArchiveResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "✅ Archive successfully created at {}",
+            self.path.display()
+        )?;
+        if let Some(manifest) = &self.manifest {
+            write!(f, "\n   📋 Manifest: {}", manifest.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The record and archive of a milestone finalized by `ghqc milestone finalize`
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalizeResult {
+    pub record_path: PathBuf,
+    pub archive_path: PathBuf,
+    pub file_count: usize,
+}
+
+impl fmt::Display for FinalizeResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✅ Record successfully generated at {}",
+            self.record_path.display()
+        )?;
+        write!(
+            f,
+            "✅ Archive successfully created at {}",
+            self.archive_path.display()
+        )
+    }
+}
+
+/// The configuration repository as reported by `ghqc configuration status`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigurationStatusResult {
+    pub directory: PathBuf,
+    /// `owner/repo` of the configuration repository, when it is a git repository
+    pub repository: Option<String>,
+    /// Number of items of each checklist
+    pub checklists: BTreeMap<String, usize>,
+    pub warnings: Vec<String>,
+    pub cache: CacheHealth,
+    #[serde(skip)]
+    text: String,
+}
+
+impl ConfigurationStatusResult {
+    pub fn new(
+        configuration: &Configuration,
+        git_info: &Option<impl GitRepository + GitStatusOps>,
+        cache: CacheHealth,
+    ) -> Self {
+        Self {
+            directory: configuration.path.clone(),
+            repository: git_info
+                .as_ref()
+                .map(|git_info| format!("{}/{}", git_info.owner(), git_info.repo())),
+            checklists: configuration
+                .checklists
+                .iter()
+                .map(|(name, checklist)| (name.clone(), checklist.items()))
+                .collect(),
+            warnings: configuration
+                .warnings
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect(),
+            text: format!("{}\n{cache}", configuration_status(configuration, git_info)),
+            cache,
+        }
+    }
+}
+
+impl fmt::Display for ConfigurationStatusResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// The configuration repository cloned by `ghqc configuration setup`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigurationSetupResult {
+    pub directory: PathBuf,
+}
+
+impl fmt::Display for ConfigurationSetupResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "✅ Configuration successfully setup at {}",
+            self.directory.display()
+        )
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+/// Pager used when `$PAGER` is not set. `-F` exits right away when the output fits on one
+/// screen, `-R` keeps colors and `-X` leaves the output on the terminal after quitting.
+const DEFAULT_PAGER: &str = "less -FRX";
+
+#[cfg_attr(test, mockall::automock)]
+pub trait PagerCli {
+    /// Pipe `output` into the pager `command` and wait for the user to quit it
+    fn run(&self, command: &[String], output: &str) -> io::Result<()>;
+}
+
+/// Runs the pager as a child process
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemPager;
+
+impl PagerCli for SystemPager {
+    fn run(&self, command: &[String], output: &str) -> io::Result<()> {
+        run_pager(command, output)
+    }
+}
+
+/// Print `output`, through the user's pager when it would scroll past the terminal
+///
+/// Paging is skipped when `disabled` (`--no-pager`, `--quiet`), when stdout is not a terminal,
+/// and when the output fits on screen. If the pager cannot be started, the output is printed
+/// directly.
+pub fn page(output: &str, disabled: bool) {
+    let stdout_is_tty = io::stdout().is_terminal();
+    let terminal_height = crossterm::terminal::size().ok().map(|(_, rows)| rows);
+    let paged = should_page(output, stdout_is_tty, terminal_height, disabled)
+        && page_with(&SystemPager, std::env::var("PAGER").ok().as_deref(), output);
+
+    if !paged {
+        println!("{output}");
+    }
+}
+
+/// Show `output` through the pager named by `pager_env`. Returns whether it was paged.
+pub(crate) fn page_with(pager: &impl PagerCli, pager_env: Option<&str>, output: &str) -> bool {
+    let Some(command) = pager_command(pager_env) else {
+        return false;
+    };
+
+    match pager.run(&command, output) {
+        Ok(()) => true,
+        Err(e) => {
+            log::debug!("Failed to run pager '{}': {e}", command.join(" "));
+            false
+        }
+    }
+}
+
+/// Whether `output` should be shown through a pager
+pub(crate) fn should_page(
+    output: &str,
+    stdout_is_tty: bool,
+    terminal_height: Option<u16>,
+    disabled: bool,
+) -> bool {
+    if disabled || !stdout_is_tty {
+        return false;
+    }
+
+    match terminal_height {
+        // Keep a line free for the shell prompt
+        Some(height) => output.lines().count() >= height as usize,
+        None => false,
+    }
+}
+
+/// Program and arguments of the pager named by `$PAGER`, falling back to `less -FRX`
+///
+/// `PAGER=cat` (or an empty `PAGER`) turns paging off.
+pub(crate) fn pager_command(pager_env: Option<&str>) -> Option<Vec<String>> {
+    let pager = pager_env.unwrap_or(DEFAULT_PAGER).trim();
+    let command = pager
+        .split_whitespace()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    match command.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(command),
+    }
+}
+
+/// Pipe `output` into the pager and wait for the user to quit it
+pub(crate) fn run_pager(command: &[String], output: &str) -> io::Result<()> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty pager command"))?;
+
+    let mut pager = Command::new(program);
+    pager.args(args).stdin(Stdio::piped());
+    // Like git, make `less` keep colors when the user's PAGER is a bare `less`
+    if std::env::var_os("LESS").is_none() {
+        pager.env("LESS", "FRX");
+    }
+    let mut child = pager.spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        match writeln!(stdin, "{output}") {
+            // The user quit the pager before reading everything
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+            result => result?,
+        }
+    }
+
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(count: usize) -> String {
+        (1..=count)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_should_page_long_output_on_terminal() {
+        assert!(should_page(&lines(60), true, Some(40), false));
+        // Exactly filling the terminal would push the header off screen behind the prompt
+        assert!(should_page(&lines(40), true, Some(40), false));
+    }
+
+    #[test]
+    fn test_should_not_page_output_that_fits() {
+        assert!(!should_page(&lines(39), true, Some(40), false));
+        assert!(!should_page("", true, Some(40), false));
+    }
+
+    #[test]
+    fn test_should_not_page_when_disabled_or_not_a_terminal() {
+        assert!(!should_page(&lines(60), true, Some(40), true));
+        assert!(!should_page(&lines(60), false, Some(40), false));
+        assert!(!should_page(&lines(60), true, None, false));
+    }
+
+    #[test]
+    fn test_page_with_runs_pager_command() {
+        let mut pager = MockPagerCli::new();
+        pager
+            .expect_run()
+            .withf(|command, output| command == ["most", "-s"] && output == "status")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        assert!(page_with(&pager, Some("most -s"), "status"));
+    }
+
+    #[test]
+    fn test_page_with_falls_back_when_pager_fails_or_is_off() {
+        let mut pager = MockPagerCli::new();
+        pager
+            .expect_run()
+            .withf(|command, _| command == ["less", "-FRX"])
+            .times(1)
+            .returning(|_, _| Err(io::Error::from(io::ErrorKind::NotFound)));
+        assert!(!page_with(&pager, None, "status"));
+
+        let mut pager = MockPagerCli::new();
+        pager.expect_run().never();
+        assert!(!page_with(&pager, Some("cat"), "status"));
+    }
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(
+            pager_command(None),
+            Some(vec!["less".to_string(), "-FRX".to_string()])
+        );
+        assert_eq!(
+            pager_command(Some("most -s")),
+            Some(vec!["most".to_string(), "-s".to_string()])
+        );
+        assert_eq!(pager_command(Some("cat")), None);
+        assert_eq!(pager_command(Some("  ")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pager_passes_output_through_intact() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let received = dir.path().join("received.txt");
+        let script = dir.path().join("fake-pager");
+        std::fs::write(
+            &script,
+            format!("#!/bin/sh\ncat > '{}'\n", received.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let output = format!(
+            "\u{1b}[36m── Issue Status ──\u{1b}[0m\n✅ Approved\n{}",
+            lines(100)
+        );
+        run_pager(&[script.display().to_string()], &output).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&received).unwrap(),
+            format!("{output}\n")
+        );
+    }
+
+    #[test]
+    fn test_run_pager_missing_program_errors() {
+        let result = run_pager(&["ghqc-no-such-pager".to_string()], "output");
+        assert!(result.is_err());
+    }
+}
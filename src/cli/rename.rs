@@ -2,14 +2,16 @@ use anyhow::Result;
 use inquire::Confirm;
 use octocrab::models::Milestone;
 use octocrab::models::issues::Issue;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::cli::interactive::prompt_existing_milestone;
+use crate::cli::interactive::{ask, prompt_existing_milestone};
 use crate::comment_system::CommentBody;
 use crate::git::{GitFileOps, GitHelpers, GitHubApiError};
+use crate::message;
+use crate::utils::normalize_repo_path;
 use crate::{
-    FileRenameEvent, GitProvider, detect_renames, file_history_section, head_commit_hash,
-    parse_file_history, splice_file_history,
+    FileRenameEvent, GitProvider, MilestoneStatusReport, detect_renames, file_history_section,
+    head_commit_hash, parse_file_history, splice_file_history,
 };
 
 /// CommentBody for posting a rename confirmation to the issue timeline.
@@ -44,48 +46,62 @@ pub async fn alert_renames<G: GitProvider + 'static>(
     git_info: &G,
     issues: &[Issue],
 ) -> Result<usize> {
-    let repo_path = git_info.path().to_path_buf();
-
-    let open_issues: Vec<&Issue> = issues
+    let open_files = issues
         .iter()
         .filter(|i| matches!(i.state, octocrab::models::IssueState::Open))
+        .map(|i| (i.number, normalize_repo_path(Path::new(&i.title))))
         .collect();
 
-    if open_issues.is_empty() {
-        return Ok(0);
-    }
+    alert_file_renames(git_info, open_files).await
+}
 
-    let issue_paths: Vec<PathBuf> = open_issues
+/// [`alert_renames`] for the open issues of milestone status reports
+pub async fn alert_report_renames<G: GitProvider + 'static>(
+    git_info: &G,
+    reports: &[MilestoneStatusReport],
+) -> Result<usize> {
+    let open_files = reports
         .iter()
-        .map(|i| PathBuf::from(&i.title))
+        .flat_map(|report| &report.issues)
+        .filter(|issue| issue.open)
+        .map(|issue| (issue.issue_number, normalize_repo_path(&issue.file)))
         .collect();
 
-    let renames = tokio::task::spawn_blocking({
-        let repo_path = repo_path.clone();
-        move || detect_renames(&repo_path, &issue_paths)
-    })
-    .await?;
+    alert_file_renames(git_info, open_files).await
+}
+
+/// Warn about renames of the files of open issues, given as issue number and file
+async fn alert_file_renames<G: GitProvider + 'static>(
+    git_info: &G,
+    open_files: Vec<(u64, PathBuf)>,
+) -> Result<usize> {
+    if open_files.is_empty() {
+        return Ok(0);
+    }
+
+    let repo_path = git_info.path().to_path_buf();
+    let issue_paths: Vec<PathBuf> = open_files.iter().map(|(_, file)| file.clone()).collect();
+
+    let renames =
+        tokio::task::spawn_blocking(move || detect_renames(&repo_path, &issue_paths)).await?;
 
     if renames.is_empty() {
         return Ok(0);
     }
 
-    println!();
-    println!("⚠️  Detected {} file rename(s):", renames.len());
+    message!();
+    message!("⚠️  Detected {} file rename(s):", renames.len());
     for (old_path, new_path) in &renames {
-        if let Some(issue) = open_issues
-            .iter()
-            .find(|i| PathBuf::from(&i.title) == *old_path)
-        {
-            println!(
+        if let Some((issue_number, _)) = open_files.iter().find(|(_, file)| file == old_path) {
+            message!(
                 "  `{}` → `{}` (issue #{})",
                 old_path.display(),
                 new_path.display(),
-                issue.number
+                issue_number
             );
         }
     }
-    println!("  Run `ghqc issue rename` to confirm.");
+    message!("  Run `ghqc issue rename` to confirm.");
 
     Ok(renames.len())
 }
@@ -99,12 +115,12 @@ pub async fn interactive_rename<G: GitProvider + 'static>(
     let milestone = if milestones.len() == 1 {
         milestones[0].clone()
     } else {
-        prompt_existing_milestone(milestones)?
+        prompt_existing_milestone(milestones)?.value()?
     };
 
     let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
     if issues.is_empty() {
-        println!("No issues found in milestone '{}'.", milestone.title);
+        message!("No issues found in milestone '{}'.", milestone.title);
         return Ok(());
     }
 
@@ -116,13 +132,13 @@ pub async fn interactive_rename<G: GitProvider + 'static>(
         .collect();
 
     if open_issues.is_empty() {
-        println!("No open issues found in milestone '{}'.", milestone.title);
+        message!("No open issues found in milestone '{}'.", milestone.title);
         return Ok(());
     }
 
     let issue_paths: Vec<PathBuf> = open_issues
         .iter()
-        .map(|i| PathBuf::from(&i.title))
+        .map(|i| normalize_repo_path(Path::new(&i.title)))
         .collect();
 
     let renames = tokio::task::spawn_blocking({
@@ -132,56 +148,56 @@ pub async fn interactive_rename<G: GitProvider + 'static>(
     .await?;
 
     if renames.is_empty() {
-        println!(
+        message!(
             "No file renames detected for open issues in '{}'.",
             milestone.title
         );
         return Ok(());
     }
 
-    println!();
-    println!("⚠️  Detected {} file rename(s):", renames.len());
+    message!();
+    message!("⚠️  Detected {} file rename(s):", renames.len());
 
     let mut confirmed = 0;
     for (old_path, new_path) in &renames {
         let issue = match open_issues
             .iter()
-            .find(|i| PathBuf::from(&i.title) == *old_path)
+            .find(|i| normalize_repo_path(Path::new(&i.title)) == *old_path)
         {
             Some(i) => *i,
             None => continue,
         };
 
-        println!(
+        message!(
             "  `{}` → `{}` (issue #{})",
             old_path.display(),
             new_path.display(),
             issue.number
         );
 
-        let answer = Confirm::new(&format!(
+        let answer = ask(Confirm::new(&format!(
             "Update issue #{} title and record rename in body?",
             issue.number
         ))
         .with_default(true)
-        .prompt()?;
+        .prompt())?;
 
         if !answer {
-            println!("  Skipped.");
+            message!("  Skipped.");
             continue;
         }
 
         if let Err(e) = confirm_rename(git_info, issue, old_path, new_path, &repo_path).await {
             eprintln!("  ✗ Failed to confirm rename: {e}");
         } else {
-            println!("  ✓ Issue #{} updated.", issue.number);
+            message!("  ✓ Issue #{} updated.", issue.number);
             confirmed += 1;
         }
     }
 
     if confirmed > 0 {
-        println!();
-        println!("✅ Confirmed {confirmed} rename(s).");
+        message!();
+        message!("✅ Confirmed {confirmed} rename(s).");
     }
 
     Ok(())
@@ -217,6 +233,8 @@ async fn confirm_rename<G: GitProvider>(
     new_path: &PathBuf,
     repo_path: &std::path::Path,
 ) -> Result<(), GitHubApiError> {
+    git_info.guard_issue(raw_issue)?;
+
     let old_path_str = old_path.to_string_lossy().to_string();
     let new_path_str = new_path.to_string_lossy().to_string();
     let current_body = raw_issue.body.as_deref().unwrap_or("").to_string();
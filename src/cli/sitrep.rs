@@ -8,7 +8,7 @@ use owo_colors::OwoColorize;
 use serde::Serialize;
 
 use crate::{
-    AuthSources, AuthStore, Configuration, GitHubReader, GitInfo, GitRepository,
+    AuthSources, AuthStore, CacheHealth, Configuration, GitHubReader, GitInfo, GitRepository,
     determine_config_dir, extract_host_from_base_url,
     utils::{EnvProvider, StdEnvProvider},
 };
@@ -396,6 +396,7 @@ pub struct SitRep {
     directory: PathBuf,
     repository: Result<RepoSitRep, String>,
     configuration: ConfigSitRep,
+    cache: CacheHealth,
     auth: AuthSitRep,
 }
 
@@ -421,12 +422,18 @@ impl SitRep {
             );
         let config_git_info = GitInfo::from_path(&config_dir, &env, None).ok();
         let configuration = ConfigSitRep::new(&config_dir, config_git_info.as_ref());
+        let cache = CacheHealth::check(
+            &env,
+            configuration.configuration.cache_directory(),
+            repository.as_ref().ok().map(|r| r.path.as_path()),
+        );
 
         Self {
             binary: BinarySitRep::new(),
             directory: directory.as_ref().to_path_buf(),
             repository,
             configuration,
+            cache,
             auth,
         }
     }
@@ -453,13 +460,16 @@ impl fmt::Display for SitRep {
         writeln!(f, "{}", super::section_header("Auth"))?;
         writeln!(f, "{}", self.auth)?;
         writeln!(f, "{}", super::section_header("Configuration"))?;
-        writeln!(f, "{}", self.configuration)
+        writeln!(f, "{}", self.configuration)?;
+        writeln!(f, "{}", super::section_header("Cache"))?;
+        writeln!(f, "{}", self.cache)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::CacheRootSource;
     use serde_json::Value;
     use std::path::PathBuf;
 
@@ -570,6 +580,12 @@ mod tests {
                 path_exists: false,
                 configuration: Configuration::from_path(PathBuf::from("/config/path")),
             },
+            cache: CacheHealth {
+                enabled: true,
+                path: Some(PathBuf::from("/home/user/.cache/ghqc")),
+                source: Some(CacheRootSource::Default),
+                reason: None,
+            },
             auth: AuthSitRep {
                 store_dir: Some(PathBuf::from("/home/user/.local/share/ghqc/auth")),
                 stored_tokens: "none".to_string(),
@@ -616,6 +632,14 @@ mod tests {
             directory: PathBuf::from("/projects/myrepo"),
             repository: Ok(repo),
             configuration: ConfigSitRep::new("src/tests/custom_configuration", None),
+            cache: CacheHealth {
+                enabled: false,
+                path: Some(PathBuf::from("/projects/myrepo/.cache")),
+                source: Some(CacheRootSource::Environment),
+                reason: Some(
+                    "Cache directory /projects/myrepo/.cache is inside the repository working tree /projects/myrepo".to_string(),
+                ),
+            },
             auth: AuthSitRep {
                 store_dir: Some(PathBuf::from("/home/user/.local/share/ghqc/auth")),
                 stored_tokens: "none".to_string(),
@@ -1,28 +1,35 @@
-use std::path::PathBuf;
+use std::fmt::Write;
+use std::path::Path;
 
 use anyhow::{Result, bail};
 use gix::ObjectId;
 use octocrab::models::Milestone;
 
-use crate::cli::interactive::{prompt_existing_milestone, prompt_issue};
-use crate::cli::rename::alert_renames;
+use crate::cli::interactive::{ask, prompt_existing_milestone, prompt_issue};
+use crate::cli::pager::page;
+use crate::cli::rename::{alert_renames, alert_report_renames};
+use crate::message;
 use crate::{
-    BlockingQCStatus, ChecklistSummary, DiskCache, GitHubReader, GitInfo, GitState, IssueThread,
-    QCStatus, analyze_issue_checklists, get_blocking_qc_status, get_git_status,
+    BehindFileReport, BlockingQCStatus, BranchState, ChecklistSummary, DiskCache, GitCommitOps,
+    GitInfo, GitState, GitStatus, IssueStatusReport, IssueThread, MilestoneStatusReport,
+    QCCommitSignature, QCStatus, SigningKeys, analyze_issue_checklists, behind_file_report,
+    get_blocking_qc_status, get_git_status, get_milestone_issues_cached, milestone_status_report,
 };
 
 pub async fn interactive_status(
     milestones: &[Milestone],
     cache: Option<&DiskCache>,
+    signing_keys: &SigningKeys,
     git_info: &GitInfo,
+    no_pager: bool,
 ) -> Result<()> {
-    println!("📊 Welcome to GHQC Status Mode!");
+    message!("📊 Welcome to GHQC Status Mode!");
 
     // Select milestone (existing only)
-    let milestone = prompt_existing_milestone(milestones)?;
+    let milestone = prompt_existing_milestone(milestones)?.value()?;
 
     // Get issues for this milestone
-    let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+    let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
     log::debug!(
         "Found {} total issues in milestone '{}'",
         issues.len(),
@@ -37,7 +44,7 @@ pub async fn interactive_status(
     alert_renames(git_info, &issues).await?;
 
     // Select issue by title
-    let issue = prompt_issue(&issues)?;
+    let issue = prompt_issue(&issues)?.value()?;
     let checklist_summary = analyze_issue_checklists(issue.body.as_deref());
 
     // Create IssueThread from the selected issue
@@ -47,69 +54,124 @@ pub async fn interactive_status(
     // Get git status for the file
     let git_status = get_git_status(git_info)?;
 
-    // Determine QC status
-    let qc_status = QCStatus::determine_status(&issue_thread);
     let blocking_qc_status =
         get_blocking_qc_status(&issue_thread.blocking_qcs, git_info, cache).await;
+    let report = IssueStatusReport::new(
+        &issue_thread,
+        checklist_summary,
+        blocking_qc_status,
+        &git_status.state,
+        &git_status.dirty,
+    );
+    let behind_report = file_behind_report(git_info, &git_status, &issue_thread.file);
+    let commit_signatures = issue_thread.qc_commit_signatures(git_info, signing_keys);
+    let branch_state = issue_thread.branch_state(git_info);
 
     // Display the status
-    println!(
-        "\n{}",
-        single_issue_status(
-            &issue_thread,
+    message!();
+    page(
+        &single_issue_status(
+            &report,
             &git_status.state,
-            &qc_status,
-            &git_status.dirty,
             &file_commits,
-            &checklist_summary,
-            &blocking_qc_status,
-        )
+            behind_report.as_ref(),
+            &commit_signatures,
+            &branch_state,
+        ),
+        no_pager,
     );
 
     Ok(())
 }
 
+/// Determine which of the remote commits missing locally modify `file`.
+///
+/// Failures are logged and treated as unknown, since this only refines the status output.
+pub fn file_behind_report(
+    git_info: &impl GitCommitOps,
+    git_status: &GitStatus,
+    file: &Path,
+) -> Option<BehindFileReport> {
+    match behind_file_report(git_info, git_status, file) {
+        Ok(report) => report,
+        Err(e) => {
+            log::debug!(
+                "Could not determine which remote commits modify {}: {e}",
+                file.display()
+            );
+            None
+        }
+    }
+}
+
+fn behind_details(report: &BehindFileReport) -> String {
+    let mut details = format!("\n  - Remote: {}", report.summary());
+    if report.affects_file() {
+        details.push(':');
+    }
+    for line in report.commit_lines() {
+        details.push_str(&format!("\n    {line}"));
+    }
+    if report.affects_file() {
+        details.push_str(
+            "\n  - ⚠️ Local diffs of this file do not include these commits. Pull before commenting or reviewing",
+        );
+    }
+    details
+}
+
 pub fn single_issue_status(
-    issue_thread: &IssueThread,
+    report: &IssueStatusReport,
     git_status: &GitState,
-    qc_status: &QCStatus,
-    dirty_files: &[PathBuf],
     file_commits: &[&ObjectId],
-    checklist_summaries: &[(String, ChecklistSummary)],
-    blocking_qc_status: &BlockingQCStatus,
+    behind_report: Option<&BehindFileReport>,
+    commit_signatures: &[QCCommitSignature],
+    branch_state: &BranchState,
 ) -> String {
-    let mut res = vec![
-        format!("- File:        {}", issue_thread.file.display()),
-        format!("- Branch:      {}", issue_thread.branch),
-    ];
+    let branch = match branch_state {
+        BranchState::Present => report.branch.clone(),
+        state => format!("{} ({state})", report.branch),
+    };
+    let mut res = vec![format!("- File:        {}", report.file.display())];
+    res.extend(
+        report
+            .renames
+            .iter()
+            .map(|rename| format!("- Renamed:     {rename}")),
+    );
+    res.push(format!("- Branch:      {branch}"));
     res.push(format!(
         "- Issue State: {}",
-        if issue_thread.open { "open" } else { "closed" }
+        if report.open { "open" } else { "closed" }
     ));
 
-    let qc_str = match qc_status {
-        QCStatus::Approved => format!("Approved"),
-        QCStatus::ChangesAfterApproval(_) => {
+    let qc_str = match (&report.qc_status, &report.stale_approval) {
+        (QCStatus::Approved | QCStatus::ChangesAfterApproval(_), Some(stale)) => {
+            format!("Approved. File has changed since approval ({stale})")
+        }
+        (QCStatus::Approved, None) => format!("Approved"),
+        (QCStatus::ChangesAfterApproval(_), None) => {
             format!("Approved. File has changed since approval")
         }
-        QCStatus::AwaitingReview => format!("Awaiting review. Latest commit notified"),
-        QCStatus::ChangeRequested => format!("Changes requested. Latest commit reviewed"),
-        QCStatus::InProgress => format!("Awaiting approval"),
-        QCStatus::ApprovalRequired => format!("Issue closed without approval"),
-        QCStatus::ChangesToComment(commit) => format!(
+        (QCStatus::AwaitingReview, _) => format!("Awaiting review. Latest commit notified"),
+        (QCStatus::ChangeRequested, _) => format!("Changes requested. Latest commit reviewed"),
+        (QCStatus::InProgress, _) => format!("Awaiting approval"),
+        (QCStatus::ApprovalRequired, _) => format!("Issue closed without approval"),
+        (QCStatus::ChangesToComment(commit), _) => format!(
             "File change in '{}' not commented",
             commit.to_string()[..7].to_string()
         ),
+        (QCStatus::Obsoleted(obsoletion), _) => format!("Obsolete: {}", obsoletion.reason),
     };
-    let is_dirty = dirty_files.contains(&issue_thread.file);
+    let is_dirty = report.uncommitted_changes;
 
     let git_str = match git_status {
         GitState::Clean => {
             log::debug!("Repository git status: clean");
             if is_dirty {
-                "File has local, uncommitted changes"
+                "File has local, uncommitted changes".to_string()
             } else {
-                "File is up to date!"
+                "File is up to date!".to_string()
             }
         }
         GitState::Ahead(commits) => {
@@ -120,24 +182,36 @@ pub fn single_issue_status(
                 (false, true) => "File has uncommitted changes",
                 (false, false) => "File is up to date!",
             }
+            .to_string()
         }
         GitState::Behind(commits) => {
             log::debug!("Repository git status: behind");
-            match (file_commits.iter().any(|c| commits.contains(c)), is_dirty) {
+            let is_behind = match behind_report {
+                Some(report) => report.affects_file(),
+                None => file_commits.iter().any(|c| commits.contains(c)),
+            };
+            let status = match (is_behind, is_dirty) {
                 (true, true) => {
                     "File has remote changes that have not been pulled locally and uncommitted changes. Stash the changes and pull"
                 }
                 (true, false) => "File has remote changes that have not been pulled locally",
                 (false, true) => "File has uncommitted changes",
                 (false, false) => "File is up to date!",
-            }
+            };
+            format!(
+                "{status}{}",
+                behind_report.map(behind_details).unwrap_or_default()
+            )
         }
         GitState::Diverged { ahead, behind } => {
             log::debug!("Repository git status: diverged");
             let is_ahead = file_commits.iter().any(|c| ahead.contains(c));
-            let is_behind = file_commits.iter().any(|c| behind.contains(c));
+            let is_behind = match behind_report {
+                Some(report) => report.affects_file(),
+                None => file_commits.iter().any(|c| behind.contains(c)),
+            };
 
-            match (is_ahead, is_behind, is_dirty) {
+            let status = match (is_ahead, is_behind, is_dirty) {
                 (true, true, true) => {
                     "File has diverged and has local, committed and uncommitted changes and remote, unpulled changes"
                 }
@@ -152,22 +226,66 @@ pub fn single_issue_status(
                 (false, true, false) => "File has remote changes that have not been pulled locally",
                 (false, false, true) => "File has uncommitted changes",
                 (false, false, false) => "File is up to date!",
-            }
+            };
+            format!(
+                "{status}{}",
+                behind_report.map(behind_details).unwrap_or_default()
+            )
         }
     };
-    let indiv_checklist = checklist_summaries
+    let indiv_checklist = report
+        .checklists
         .iter()
-        .map(|(name, sum)| format!("{name}: {sum}"))
+        .map(|c| format!("{}: {}", c.name, c.summary))
         .collect::<Vec<_>>();
-    let checklist_sum = ChecklistSummary::sum(checklist_summaries.iter().map(|(_, c)| c));
 
     res.push(format!("- QC Status:   {qc_str}"));
+    if report.qc_round > 0 {
+        let approval = report
+            .rounds_to_approval
+            .map(|round| format!(" (approved in round {round})"))
+            .unwrap_or_default();
+        res.push(format!("- QC Round:    {}{approval}", report.qc_round));
+    }
+    if let Some(unapproval) = &report.unapproval {
+        res.push(format!("- Unapproved:  {unapproval}"));
+    }
     res.push(format!("- Git Status:  {git_str}"));
+    if let Some(caveat) = &report.commit_caveat {
+        res.push(format!("- {caveat}"));
+    }
+    if !commit_signatures.is_empty() {
+        res.push(format!(
+            "- Commit Signatures:\n  - {}",
+            commit_signatures
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join("\n  - ")
+        ));
+    }
+    let inline_checklist = report
+        .inline_checklist
+        .as_ref()
+        .map(|file| format!(" (inline checklist: {file})"))
+        .unwrap_or_default();
     res.push(format!(
-        "- Checklist Summary: {checklist_sum}\n  - {}",
+        "- Checklist Summary: {}{inline_checklist}\n  - {}",
+        report.checklist,
         indiv_checklist.join("\n  - ")
     ));
-    res.push(format!("- {}", blocking_qc_status));
+    res.push(format!("- {}", report.blocking_qcs));
+    if !report.relevant_files.is_empty() {
+        res.push(format!(
+            "- Relevant Files:\n  - {}",
+            report
+                .relevant_files
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join("\n  - ")
+        ));
+    }
 
     res.join("\n")
 }
@@ -179,17 +297,49 @@ pub struct MilestoneStatusRow {
     pub branch: String,
     pub issue_state: String,
     pub qc_status: String,
+    pub qc_round: u32,
     pub git_status: String,
     pub checklist_summary: ChecklistSummary,
     pub blocking_qc_status: BlockingQCStatus,
+    /// Warning when the issue's commit history was resolved through a fallback
+    pub commit_caveat: Option<String>,
+}
+
+impl From<&IssueStatusReport> for MilestoneStatusRow {
+    fn from(report: &IssueStatusReport) -> Self {
+        let mut git_status = report.git_status.to_string();
+        if report.uncommitted_changes {
+            git_status.push_str(" (file has uncommitted local changes)");
+        }
+        Self {
+            file: report.file.display().to_string(),
+            milestone: report.milestone.clone(),
+            branch: if report.branch_deleted {
+                format!("{} (deleted)", report.branch)
+            } else {
+                report.branch.clone()
+            },
+            issue_state: if report.open { "open" } else { "closed" }.to_string(),
+            qc_status: match &report.stale_approval {
+                Some(stale) => format!("{} (stale: {stale})", report.qc_status_detail),
+                None => report.qc_status_detail.clone(),
+            },
+            qc_round: report.qc_round,
+            git_status,
+            checklist_summary: report.checklist.clone(),
+            blocking_qc_status: report.blocking_qcs.clone(),
+            commit_caveat: report.commit_caveat.clone(),
+        }
+    }
 }
 
 pub async fn interactive_milestone_status(
     milestones: &[Milestone],
     cache: Option<&DiskCache>,
     git_info: &GitInfo,
+    no_pager: bool,
 ) -> Result<()> {
-    println!("📊 Welcome to GHQC Milestone Status Mode!");
+    message!("📊 Welcome to GHQC Milestone Status Mode!");
 
     if milestones.is_empty() {
         bail!("No milestones found in repository");
@@ -198,12 +348,11 @@ pub async fn interactive_milestone_status(
     use inquire::{MultiSelect, Select};
 
     // First ask if they want to select all or choose specific ones
-    let choice = Select::new(
+    let choice = ask(Select::new(
         "📊 How would you like to select milestones?",
         vec!["📋 Select All Milestones", "🎯 Choose Specific Milestones"],
     )
-    .prompt()
-    .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+    .prompt())?;
 
     let mut sorted_milestones: Vec<&Milestone> = milestones.iter().collect();
     sorted_milestones.sort_by(|a, b| b.number.cmp(&a.number));
@@ -217,19 +366,20 @@ pub async fn interactive_milestone_status(
             .map(|m| format!("{} ({})", m.title, m.number))
             .collect();
 
-        let selected_strings =
-            MultiSelect::new("📊 Select milestones to check:", milestone_options)
-                .with_validator(|selection: &[inquire::list_option::ListOption<&String>]| {
-                    if selection.is_empty() {
-                        Ok(inquire::validator::Validation::Invalid(
-                            "Please select at least one milestone".into(),
-                        ))
-                    } else {
-                        Ok(inquire::validator::Validation::Valid)
-                    }
-                })
-                .prompt()
-                .map_err(|e| anyhow::anyhow!("Selection cancelled: {}", e))?;
+        let selected_strings = ask(MultiSelect::new(
+            "📊 Select milestones to check:",
+            milestone_options,
+        )
+        .with_validator(|selection: &[inquire::list_option::ListOption<&String>]| {
+            if selection.is_empty() {
+                Ok(inquire::validator::Validation::Invalid(
+                    "Please select at least one milestone".into(),
+                ))
+            } else {
+                Ok(inquire::validator::Validation::Valid)
+            }
+        })
+        .prompt())?;
 
         // Filter milestones based on selected strings
         sorted_milestones
@@ -246,10 +396,15 @@ pub async fn interactive_milestone_status(
     }
 
     // Get status for all selected milestones
-    let status_rows = get_milestone_status_rows(&selected_milestones, cache, git_info).await?;
+    let reports = milestone_status_report(&selected_milestones, cache, git_info).await?;
+    // Alert about any pending file renames (run `ghqc issue rename` to confirm).
+    alert_report_renames(git_info, &reports).await?;
 
     // Display results
-    display_milestone_status_table(&status_rows);
+    page(
+        &format_milestone_status_table(&status_rows(&reports)),
+        no_pager,
+    );
 
     Ok(())
 }
@@ -258,6 +413,8 @@ pub async fn milestone_status(
     milestones: &[Milestone],
     cache: Option<&DiskCache>,
     git_info: &GitInfo,
+    no_pager: bool,
+    json: bool,
 ) -> Result<()> {
     if milestones.is_empty() {
         bail!("No milestones provided");
@@ -266,91 +423,42 @@ pub async fn milestone_status(
     // Convert to &[&Milestone] for the function call
     let milestone_refs: Vec<&Milestone> = milestones.iter().collect();
 
+    if json {
+        // Rename alerts are skipped so stdout stays valid JSON
+        let reports = milestone_status_report(&milestone_refs, cache, git_info).await?;
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+
     // Get status for all milestones
-    let status_rows = get_milestone_status_rows(&milestone_refs, cache, git_info).await?;
+    let reports = milestone_status_report(&milestone_refs, cache, git_info).await?;
+    // Alert about any pending file renames (run `ghqc issue rename` to confirm).
+    alert_report_renames(git_info, &reports).await?;
 
     // Display results
-    display_milestone_status_table(&status_rows);
+    page(
+        &format_milestone_status_table(&status_rows(&reports)),
+        no_pager,
+    );
 
     Ok(())
 }
 
-async fn get_milestone_status_rows(
-    milestones: &[&Milestone],
-    cache: Option<&DiskCache>,
-    git_info: &GitInfo,
-) -> Result<Vec<MilestoneStatusRow>> {
-    let mut rows = Vec::new();
-
-    // Fetch once before processing issues (same result for all issues)
-    let (git_status, dirty_files) = match get_git_status(git_info) {
-        Ok(status) => (status.state, status.dirty),
-        Err(_) => (GitState::Clean, Vec::new()),
-    };
-
-    for milestone in milestones {
-        // Get all issues for this milestone
-        let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
-
-        // Alert about any pending file renames (run `ghqc issue rename` to confirm).
-        alert_renames(git_info, &issues).await?;
-
-        for issue in issues {
-            // Create IssueThread for each issue
-            if let Ok(issue_thread) = IssueThread::from_issue(&issue, cache, git_info).await {
-                let file_commits = issue_thread.file_commits();
-
-                // Determine QC status
-                let qc_status = QCStatus::determine_status(&issue_thread);
-                let checklist_summaries = analyze_issue_checklists(issue.body.as_deref());
-                let checklist_summary =
-                    ChecklistSummary::sum(checklist_summaries.iter().map(|(_, c)| c));
-
-                let mut git_status_str = git_status.format_for_file(&file_commits);
-                if dirty_files.contains(&issue_thread.file) {
-                    git_status_str.push_str(" (file has uncommitted local changes)");
-                }
-
-                let row = MilestoneStatusRow {
-                    file: issue_thread.file.display().to_string(),
-                    milestone: milestone.title.clone(),
-                    branch: issue_thread.branch.clone(),
-                    issue_state: if issue_thread.open {
-                        "open".to_string()
-                    } else {
-                        "closed".to_string()
-                    },
-                    qc_status: qc_status.to_string(),
-                    git_status: git_status_str,
-                    checklist_summary,
-                    blocking_qc_status: get_blocking_qc_status(
-                        &issue_thread.blocking_qcs,
-                        git_info,
-                        cache,
-                    )
-                    .await,
-                };
-                rows.push(row);
-            }
-        }
-    }
-
-    // Sort by milestone name, then by file name
-    rows.sort_by(|a, b| {
-        a.milestone
-            .cmp(&b.milestone)
-            .then_with(|| a.file.cmp(&b.file))
-    });
-
-    Ok(rows)
+/// Table rows of the reports, sorted by milestone name, then by file name
+fn status_rows(reports: &[MilestoneStatusReport]) -> Vec<MilestoneStatusRow> {
+    reports
+        .iter()
+        .flat_map(|report| report.issues.iter().map(MilestoneStatusRow::from))
+        .collect()
 }
 
-fn display_milestone_status_table(rows: &[MilestoneStatusRow]) {
+fn format_milestone_status_table(rows: &[MilestoneStatusRow]) -> String {
     if rows.is_empty() {
-        println!("No issues found in selected milestones.");
-        return;
+        return "No issues found in selected milestones.".to_string();
     }
 
+    let mut table = String::new();
+
     // Calculate column widths
     let file_width = rows.iter().map(|r| r.file.len()).max().unwrap_or(4).max(4);
     let milestone_width = rows
@@ -377,6 +485,12 @@ fn display_milestone_status_table(rows: &[MilestoneStatusRow]) {
         .max()
         .unwrap_or(9)
         .max(9);
+    let qc_round_width = rows
+        .iter()
+        .map(|r| r.qc_round.to_string().len())
+        .max()
+        .unwrap_or(5)
+        .max(5);
     let git_status_width = rows
         .iter()
         .map(|r| r.git_status.len())
@@ -396,15 +510,17 @@ fn display_milestone_status_table(rows: &[MilestoneStatusRow]) {
         .unwrap_or(12)
         .max(12);
 
-    // Print header
-    println!();
-    println!(
-        "{:<file_width$} | {:<milestone_width$} | {:<branch_width$} | {:<issue_state_width$} | {:<qc_status_width$} | {:<git_status_width$} | {:<checklist_width$} | {:<blocking_qc_width$}",
+    // Header
+    writeln!(table).unwrap();
+    writeln!(
+        table,
+        "{:<file_width$} | {:<milestone_width$} | {:<branch_width$} | {:<issue_state_width$} | {:<qc_status_width$} | {:<qc_round_width$} | {:<git_status_width$} | {:<checklist_width$} | {:<blocking_qc_width$}",
         "File",
         "Milestone",
         "Branch",
         "Issue State",
         "QC Status",
+        "Round",
         "Git Status",
         "Checklist",
         "Blocking QCs",
@@ -413,14 +529,18 @@ fn display_milestone_status_table(rows: &[MilestoneStatusRow]) {
         branch_width = branch_width,
         issue_state_width = issue_state_width,
         qc_status_width = qc_status_width,
+        qc_round_width = qc_round_width,
         git_status_width = git_status_width,
         checklist_width = checklist_width,
         blocking_qc_width = blocking_qc_width,
-    );
+    )
+    .unwrap();
 
-    // Print separator
-    println!(
-        "{:-<file_width$}-+-{:-<milestone_width$}-+-{:-<branch_width$}-+-{:-<issue_state_width$}-+-{:-<qc_status_width$}-+-{:-<git_status_width$}-+-{:-<checklist_width$}-+-{:-<blocking_qc_width$}",
+    // Separator
+    writeln!(
+        table,
+        "{:-<file_width$}-+-{:-<milestone_width$}-+-{:-<branch_width$}-+-{:-<issue_state_width$}-+-{:-<qc_status_width$}-+-{:-<qc_round_width$}-+-{:-<git_status_width$}-+-{:-<checklist_width$}-+-{:-<blocking_qc_width$}",
+        "",
         "",
         "",
         "",
@@ -434,22 +554,26 @@ fn display_milestone_status_table(rows: &[MilestoneStatusRow]) {
         branch_width = branch_width,
         issue_state_width = issue_state_width,
         qc_status_width = qc_status_width,
+        qc_round_width = qc_round_width,
         git_status_width = git_status_width,
         checklist_width = checklist_width,
         blocking_qc_width = blocking_qc_width,
-    );
+    )
+    .unwrap();
 
-    // Print rows
+    // Rows
     for row in rows {
         let checklist_str = row.checklist_summary.to_string();
         let blocking_qc_str = row.blocking_qc_status.as_summary_string();
-        println!(
-            "{:<file_width$} | {:<milestone_width$} | {:<branch_width$} | {:<issue_state_width$} | {:<qc_status_width$} | {:<git_status_width$} | {:<checklist_width$} | {:<blocking_qc_width$}",
+        writeln!(
+            table,
+            "{:<file_width$} | {:<milestone_width$} | {:<branch_width$} | {:<issue_state_width$} | {:<qc_status_width$} | {:<qc_round_width$} | {:<git_status_width$} | {:<checklist_width$} | {:<blocking_qc_width$}",
             row.file,
             row.milestone,
             row.branch,
             row.issue_state,
             row.qc_status,
+            row.qc_round,
             row.git_status,
             checklist_str,
             blocking_qc_str,
@@ -458,10 +582,389 @@ fn display_milestone_status_table(rows: &[MilestoneStatusRow]) {
             branch_width = branch_width,
             issue_state_width = issue_state_width,
             qc_status_width = qc_status_width,
+            qc_round_width = qc_round_width,
             git_status_width = git_status_width,
             checklist_width = checklist_width,
             blocking_qc_width = blocking_qc_width,
+        )
+        .unwrap();
+    }
+
+    let caveats: Vec<_> = rows
+        .iter()
+        .filter_map(|row| {
+            row.commit_caveat
+                .as_ref()
+                .map(|caveat| format!("{}: {caveat}", row.file))
+        })
+        .collect();
+    if !caveats.is_empty() {
+        writeln!(table).unwrap();
+        for caveat in caveats {
+            writeln!(table, "{caveat}").unwrap();
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CommitRetrievalInfo, CommitSource, CommitStatus, GitCommit, IssueRelevantFile, QCRounds,
+        RetrievalAttempt, RetrievalFailure, SignatureStatus, Unapproval,
+    };
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    fn oid(s: &str) -> ObjectId {
+        ObjectId::from_str(s).unwrap()
+    }
+
+    fn issue_thread() -> IssueThread {
+        IssueThread {
+            file: PathBuf::from("scripts/analysis.R"),
+            issue_number: 1,
+            issue_url: "https://github.com/owner/repo/issues/1".to_string(),
+            branch: "main".to_string(),
+            branch_deleted: false,
+            open: true,
+            commits: Vec::new(),
+            milestone: "v1.0".to_string(),
+            blocking_qcs: Vec::new(),
+            relevant_files: Vec::new(),
+            approved_at: None,
+            approval_recorded_at: None,
+            approved_by: None,
+            unapproval: None,
+            notified_at: None,
+            obsoleted: None,
+            inline_checklist: None,
+            rounds: QCRounds {
+                current_round: 2,
+                rounds_to_approval: None,
+            },
+            commit_retrieval: Default::default(),
+            renames: Vec::new(),
+        }
+    }
+
+    fn behind_status(touching: &[&str]) -> (GitState, BehindFileReport) {
+        let commits = vec![
+            GitCommit {
+                commit: oid("abc123def456789012345678901234567890abcd"),
+                message: "Update analysis model".to_string(),
+            },
+            GitCommit {
+                commit: oid("def456789abc012345678901234567890123abcd"),
+                message: "Fix README typo".to_string(),
+            },
+            GitCommit {
+                commit: oid("456def789abc012345678901234567890123cdef"),
+                message: "Tweak analysis plot".to_string(),
+            },
+        ];
+        let behind: Vec<ObjectId> = commits.iter().map(|c| c.commit).collect();
+        let touching: HashSet<String> = touching.iter().map(|s| s.to_string()).collect();
+        let report = BehindFileReport::new(
+            Path::new("scripts/analysis.R"),
+            &behind,
+            &touching,
+            &commits,
+        );
+        (GitState::Behind(behind), report)
+    }
+
+    fn issue_report(
+        thread: &IssueThread,
+        qc_status: QCStatus,
+        checklists: Vec<(String, ChecklistSummary)>,
+    ) -> IssueStatusReport {
+        let mut report = IssueStatusReport::new(
+            thread,
+            checklists,
+            BlockingQCStatus::default(),
+            &GitState::Clean,
+            &[],
         );
+        report.qc_status = qc_status;
+        report
+    }
+
+    fn render_status(
+        state: &GitState,
+        report: Option<&BehindFileReport>,
+        signatures: &[QCCommitSignature],
+    ) -> String {
+        let thread = issue_thread();
+        single_issue_status(
+            &issue_report(
+                &thread,
+                QCStatus::AwaitingReview,
+                vec![("Code Review".to_string(), ChecklistSummary::new(2, 4))],
+            ),
+            state,
+            &[],
+            report,
+            signatures,
+            &BranchState::Present,
+        )
+    }
+
+    #[test]
+    fn test_status_behind_touching_file() {
+        let (state, report) = behind_status(&[
+            "abc123def456789012345678901234567890abcd",
+            "456def789abc012345678901234567890123cdef",
+        ]);
+        let status = render_status(&state, Some(&report), &[]);
+        insta::assert_snapshot!(status);
+    }
+
+    #[test]
+    fn test_status_behind_not_touching_file() {
+        let (state, report) = behind_status(&[]);
+        let status = render_status(&state, Some(&report), &[]);
+        insta::assert_snapshot!(status);
+    }
+
+    #[test]
+    fn test_status_behind_without_report() {
+        let (state, _) = behind_status(&[]);
+        let status = render_status(&state, None, &[]);
+        assert!(status.contains("- Git Status:  File is up to date!\n"));
+        assert!(!status.contains("Remote:"));
+    }
+
+    #[test]
+    fn test_status_commit_signatures() {
+        let signatures = vec![
+            QCCommitSignature {
+                role: CommitStatus::Initial,
+                commit: oid("456def789abc012345678901234567890123cdef"),
+                status: SignatureStatus::Signed,
+            },
+            QCCommitSignature {
+                role: CommitStatus::Notification,
+                commit: oid("def456789abc012345678901234567890123abcd"),
+                status: SignatureStatus::Unverified,
+            },
+            QCCommitSignature {
+                role: CommitStatus::Approved,
+                commit: oid("abc123def456789012345678901234567890abcd"),
+                status: SignatureStatus::Unsigned,
+            },
+        ];
+        let status = render_status(&GitState::Clean, None, &signatures);
+        insta::assert_snapshot!(status);
+    }
+
+    #[test]
+    fn test_status_without_commit_signatures() {
+        let status = render_status(&GitState::Clean, None, &[]);
+        assert!(!status.contains("Commit Signatures"));
+    }
+
+    #[test]
+    fn test_status_deleted_branch() {
+        let mut thread = issue_thread();
+        thread.branch = "feature/analysis".to_string();
+        thread.branch_deleted = true;
+
+        let render = |state: &BranchState| {
+            single_issue_status(
+                &issue_report(&thread, QCStatus::Approved, Vec::new()),
+                &GitState::Clean,
+                &[],
+                None,
+                &[],
+                state,
+            )
+        };
+
+        let merged = render(&BranchState::Merged {
+            target: "main".to_string(),
+            merge_commit: oid("def456789abc012345678901234567890123abcd"),
+        });
+        assert!(
+            merged.contains(
+                "- Branch:      feature/analysis (deleted — merged into main at def4567)\n"
+            )
+        );
+
+        let deleted = render(&BranchState::Deleted);
+        assert!(deleted.contains("- Branch:      feature/analysis (deleted)\n"));
+    }
+
+    #[test]
+    fn test_status_qc_round() {
+        let mut thread = issue_thread();
+        thread.rounds.rounds_to_approval = Some(2);
+
+        let status = single_issue_status(
+            &issue_report(&thread, QCStatus::Approved, Vec::new()),
+            &GitState::Clean,
+            &[],
+            None,
+            &[],
+            &BranchState::Present,
+        );
+        assert!(status.contains("- QC Round:    2 (approved in round 2)\n"));
+
+        thread.rounds = QCRounds::default();
+        let status = render_status(&GitState::Clean, None, &[]);
+        assert!(status.contains("- QC Round:    2\n"));
+        let status = single_issue_status(
+            &issue_report(&thread, QCStatus::AwaitingReview, Vec::new()),
+            &GitState::Clean,
+            &[],
+            None,
+            &[],
+            &BranchState::Present,
+        );
+        assert!(!status.contains("QC Round"));
+    }
+
+    #[test]
+    fn test_status_inline_checklist() {
+        let mut thread = issue_thread();
+        thread.inline_checklist = Some("bespoke_model_review.md".to_string());
+
+        let status = single_issue_status(
+            &issue_report(
+                &thread,
+                QCStatus::AwaitingReview,
+                vec![(
+                    "bespoke_model_review".to_string(),
+                    ChecklistSummary::new(1, 3),
+                )],
+            ),
+            &GitState::Clean,
+            &[],
+            None,
+            &[],
+            &BranchState::Present,
+        );
+        assert!(status.contains(
+            "- Checklist Summary: 1/3 (33.3%) (inline checklist: bespoke_model_review.md)\n"
+        ));
+    }
+
+    #[test]
+    fn test_status_relevant_files() {
+        let mut thread = issue_thread();
+        let status = |thread: &IssueThread| {
+            single_issue_status(
+                &issue_report(thread, QCStatus::AwaitingReview, Vec::new()),
+                &GitState::Clean,
+                &[],
+                None,
+                &[],
+                &BranchState::Present,
+            )
+        };
+        assert!(!status(&thread).contains("Relevant Files"));
+
+        thread.relevant_files = vec![
+            IssueRelevantFile {
+                file: PathBuf::from("src/gate.R"),
+                kind: "Gating QC".to_string(),
+                justification: None,
+            },
+            IssueRelevantFile {
+                file: PathBuf::from("data/config.yaml"),
+                kind: "Relevant File".to_string(),
+                justification: Some("Model settings".to_string()),
+            },
+        ];
+        assert!(status(&thread).ends_with(
+            "- Relevant Files:\n  - src/gate.R (Gating QC)\n  - data/config.yaml (Relevant File): Model settings"
+        ));
+    }
+
+    #[test]
+    fn test_status_unapproval() {
+        let mut thread = issue_thread();
+        let status = |thread: &IssueThread| {
+            single_issue_status(
+                &issue_report(thread, QCStatus::InProgress, Vec::new()),
+                &GitState::Clean,
+                &[],
+                None,
+                &[],
+                &BranchState::Present,
+            )
+        };
+        assert!(!status(&thread).contains("Unapproved"));
+
+        thread.unapproval = Some(Unapproval {
+            at: chrono::DateTime::UNIX_EPOCH,
+            by: "reviewer".to_string(),
+            commit: Some("def456789abc012345678901234567890123abcd".to_string()),
+            previously_approved: Some("abc123def456789012345678901234567890abcd".to_string()),
+        });
+        assert!(status(&thread).contains(
+            "- Unapproved:  1970-01-01 by reviewer at def4567 (previously approved abc123d)\n"
+        ));
+    }
+
+    fn fallback_retrieval() -> CommitRetrievalInfo {
+        CommitRetrievalInfo {
+            source: CommitSource::MergedInto("main".to_string()),
+            attempts: vec![RetrievalAttempt {
+                source: CommitSource::IssueBranch,
+                failure: RetrievalFailure::BranchNotFound("feature/x".to_string()),
+            }],
+            may_be_incomplete: false,
+        }
+    }
+
+    #[test]
+    fn test_status_commit_retrieval_caveat() {
+        let status = render_status(&GitState::Clean, None, &[]);
+        assert!(!status.contains("⚠"));
+
+        let mut thread = issue_thread();
+        thread.commit_retrieval = fallback_retrieval();
+        let status = single_issue_status(
+            &issue_report(&thread, QCStatus::AwaitingReview, Vec::new()),
+            &GitState::Clean,
+            &[],
+            None,
+            &[],
+            &BranchState::Present,
+        );
+        assert!(status.contains(
+            "- Git Status:  File is up to date!\n- ⚠ commit history resolved via fallback: branch 'feature/x' not found locally — run git fetch\n"
+        ));
+    }
+
+    #[test]
+    fn test_milestone_status_table_commit_caveats() {
+        let row = |file: &str, commit_caveat: Option<String>| MilestoneStatusRow {
+            file: file.to_string(),
+            milestone: "v1.0".to_string(),
+            branch: "main".to_string(),
+            issue_state: "open".to_string(),
+            qc_status: "Awaiting review".to_string(),
+            qc_round: 1,
+            git_status: "Up to date".to_string(),
+            checklist_summary: ChecklistSummary::new(0, 2),
+            blocking_qc_status: BlockingQCStatus::default(),
+            commit_caveat,
+        };
+
+        let table = format_milestone_status_table(&[row("scripts/a.R", None)]);
+        assert!(!table.contains("⚠"));
+
+        let table = format_milestone_status_table(&[
+            row("scripts/a.R", None),
+            row("scripts/b.R", fallback_retrieval().caveat()),
+        ]);
+        assert!(table.ends_with(
+            "\nscripts/b.R: ⚠ commit history resolved via fallback: branch 'feature/x' not found locally — run git fetch\n"
+        ));
     }
-    println!();
 }
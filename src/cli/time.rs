@@ -0,0 +1,75 @@
+use anyhow::{Result, anyhow};
+use octocrab::models::{IssueState, Milestone, issues::Issue};
+use std::path::Path;
+
+use crate::{
+    DiskCache, GitHubReader, get_issue_comments, get_milestone_issues_cached,
+    time_log::{IssueTime, TimeSummary, time_entries},
+};
+
+/// Find the issue for `file` in the milestone, regardless of its state
+///
+/// Time can be logged against approved (closed) issues, so unlike `find_issue` closed issues
+/// are also considered, preferring an open issue when both exist.
+pub async fn find_time_log_issue(
+    milestone_name: &str,
+    file: impl AsRef<Path>,
+    milestones: &[Milestone],
+    git_info: &impl GitHubReader,
+) -> Result<Issue> {
+    let milestone = milestones
+        .iter()
+        .find(|m| m.title == milestone_name)
+        .ok_or(anyhow!("Milestone '{}' not found", milestone_name))?;
+
+    let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+
+    let file_str = file.as_ref().to_string_lossy();
+    let mut matching: Vec<Issue> = issues
+        .into_iter()
+        .filter(|issue| issue.title == file_str)
+        .collect();
+    matching.sort_by_key(|issue| !matches!(issue.state, IssueState::Open));
+
+    matching.into_iter().next().ok_or(anyhow!(
+        "No issue found for file '{file_str}' in milestone '{milestone_name}'"
+    ))
+}
+
+/// Logged QC time for a single issue
+pub async fn issue_time(
+    issue: &Issue,
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<IssueTime> {
+    let comments = get_issue_comments(issue, cache, git_info).await?;
+
+    Ok(IssueTime {
+        file: issue.title.clone(),
+        number: issue.number,
+        summary: TimeSummary::from_entries(&time_entries(&comments)),
+    })
+}
+
+/// Logged QC time for every issue in the milestone, ordered by issue number
+pub async fn milestone_time(
+    milestone_name: &str,
+    milestones: &[Milestone],
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<IssueTime>> {
+    let milestone = milestones
+        .iter()
+        .find(|m| m.title == milestone_name)
+        .ok_or(anyhow!("Milestone '{}' not found", milestone_name))?;
+
+    let mut issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+    issues.sort_by_key(|issue| issue.number);
+
+    let mut times = Vec::with_capacity(issues.len());
+    for issue in &issues {
+        times.push(issue_time(issue, cache, git_info).await?);
+    }
+
+    Ok(times)
+}
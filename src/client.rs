@@ -0,0 +1,807 @@
+//! High-level entry point for using the toolkit as a library
+//!
+//! [`GhqcClient`] bundles a git provider with the configuration and disk cache the commands need,
+//! so issues can be created, commented on, approved, reported on and archived without going
+//! through the command line. The CLI runs its commands through the same methods. It needs the
+//! `cli` feature, as the non-interactive argument handling is shared with the CLI.
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{NaiveDate, Utc};
+use octocrab::models::Milestone;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{
+    ChecklistArg, CreateOptions, IssueUrlArg, PostedComment, RelevantFileArg, archive_record,
+    find_issue, milestone_archive_files, optional_record,
+};
+use crate::utils::{EnvProvider, StdEnvProvider};
+use crate::{
+    ApprovalResult, ApprovalWindow, ArchiveFile, ArchiveManifest, ArchiveMetadata, AuthStore,
+    Configuration, CreateResult, DiskCache, GeneratedArchiveFile, GitInfo, GitProvider, GitStatus,
+    IssueStatusReport, IssueThread, MilestoneStatusReport, NoDiff, QCApprove, QCComment, QCIssue,
+    RecordFormat, UreqDownloader, analyze_issue_checklists, approve_with_validation, archive,
+    cache_or_warn, configured_cache_directory, create_labels_if_needed, determine_config_dir,
+    generate_archive_name, get_blocking_qc_status, get_git_status, get_milestones_cached,
+    get_repo_users, milestone_status_report, render,
+};
+
+/// A new QC issue for `file` in `milestone`, created if it does not exist yet
+#[derive(Debug, Clone)]
+pub struct CreateIssueRequest {
+    pub milestone: String,
+    pub file: PathBuf,
+    pub checklist: ChecklistArg,
+    /// Reviewers, `None` to use the configured suggestions
+    pub assignees: Option<Vec<String>>,
+    pub add_collaborators: Vec<String>,
+    pub remove_collaborators: Vec<String>,
+    /// Description of the milestone, when it is created
+    pub description: Option<String>,
+    pub previous_qc: Vec<IssueUrlArg>,
+    pub gating_qc: Vec<IssueUrlArg>,
+    pub relevant_qc: Vec<IssueUrlArg>,
+    pub relevant_files: Vec<RelevantFileArg>,
+    /// Only assign users with write access, also required by the configuration option
+    pub require_write_access: bool,
+    /// Link the most recent approved QC of the file as the previous QC
+    pub auto_previous: bool,
+    /// Leave out the Environment section listing the configured `environment_files`
+    pub no_environment: bool,
+    /// Create the issue even when the file already has an issue in the milestone
+    pub allow_duplicate: bool,
+}
+
+impl CreateIssueRequest {
+    pub fn new(
+        milestone: impl Into<String>,
+        file: impl Into<PathBuf>,
+        checklist: ChecklistArg,
+    ) -> Self {
+        Self {
+            milestone: milestone.into(),
+            file: file.into(),
+            checklist,
+            assignees: None,
+            add_collaborators: Vec::new(),
+            remove_collaborators: Vec::new(),
+            description: None,
+            previous_qc: Vec::new(),
+            gating_qc: Vec::new(),
+            relevant_qc: Vec::new(),
+            relevant_files: Vec::new(),
+            require_write_access: false,
+            auto_previous: true,
+            no_environment: false,
+            allow_duplicate: false,
+        }
+    }
+}
+
+/// A QC comment on the issue of `file` in `milestone`
+#[derive(Debug, Clone, Default)]
+pub struct CommentRequest {
+    pub milestone: String,
+    pub file: PathBuf,
+    /// Commit the comment is about, the file's latest commit when `None`
+    pub current_commit: Option<String>,
+    /// Commit to diff against, the previous QC commit when `None`
+    pub previous_commit: Option<String>,
+    pub note: Option<String>,
+    pub no_diff: Option<NoDiff>,
+    /// Include the diff even when the file is too large or binary
+    pub force_diff: bool,
+    /// Refuse commits which did not modify the file
+    pub strict: bool,
+    pub allow_branch_mismatch: bool,
+    /// Leave out the Environment section listing the configured `environment_files`
+    pub no_environment: bool,
+}
+
+impl CommentRequest {
+    pub fn new(milestone: impl Into<String>, file: impl Into<PathBuf>) -> Self {
+        Self {
+            milestone: milestone.into(),
+            file: file.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Approval of the issue of `file` in `milestone`
+#[derive(Debug, Clone, Default)]
+pub struct ApproveRequest {
+    pub milestone: String,
+    pub file: PathBuf,
+    /// Commit to approve, the file's latest commit when `None`
+    pub commit: Option<String>,
+    pub note: Option<String>,
+    /// When the approval actually happened, if it is recorded after the fact
+    pub effective_date: Option<NaiveDate>,
+    /// Leave the summary of the approved QC range out of the comment
+    pub no_scope_summary: bool,
+    /// Approve although blocking QCs are not approved
+    pub force: bool,
+    pub allow_branch_mismatch: bool,
+    /// Approve with unchecked checklist items although the configuration requires them checked
+    pub allow_incomplete_checklist: bool,
+}
+
+impl ApproveRequest {
+    pub fn new(milestone: impl Into<String>, file: impl Into<PathBuf>) -> Self {
+        Self {
+            milestone: milestone.into(),
+            file: file.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// An archive of the QCed files of `milestones`
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveRequest {
+    pub milestones: Vec<String>,
+    pub include_unapproved: bool,
+    pub approval_window: ApprovalWindow,
+    /// Put every file at the archive root
+    pub flatten: bool,
+    pub include_relevant_files: bool,
+    /// Files to archive besides those of the milestones
+    pub additional_files: Vec<ArchiveFile>,
+    /// Where to write the archive, relative to the repository. Defaults to a name generated from
+    /// the milestones in `archive/`
+    pub path: Option<PathBuf>,
+    /// Render the milestones' record into the archive root as `QC_RECORD.pdf`
+    pub include_record: bool,
+    /// Archive without the record, with a warning, when it fails to generate
+    pub record_optional: bool,
+    /// Refuse to write the archive when a file is larger than this many bytes
+    pub max_file_size: Option<u64>,
+}
+
+/// Status of a QC issue, with the data it was determined from
+pub struct IssueStatus {
+    pub thread: IssueThread,
+    pub git_status: GitStatus,
+    pub report: IssueStatusReport,
+}
+
+/// The toolkit's commands on a repository
+///
+/// ```no_run
+/// use ghqctoolkit::{CommentRequest, GhqcClient, utils::StdEnvProvider};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let client = GhqcClient::from_path(".", &StdEnvProvider)?;
+///
+/// let status = client.status("Sprint 1", "scripts/analysis.R").await?;
+/// println!("{}: {}", status.file.display(), status.qc_status);
+///
+/// let mut request = CommentRequest::new("Sprint 1", "scripts/analysis.R");
+/// request.note = Some("Addressed review feedback".to_string());
+/// let posted = client.comment(request).await?;
+/// println!("{}", posted.comment_url);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GhqcClient<G: GitProvider> {
+    git: G,
+    configuration: Configuration,
+    cache: Option<DiskCache>,
+    /// Downloads the images of records
+    downloader: UreqDownloader,
+}
+
+impl GhqcClient<GitInfo> {
+    /// A client for the repository at `dir`, with the configuration repository and cache
+    /// resolved the way the CLI resolves them
+    pub fn from_path(dir: impl AsRef<Path>, env: &impl EnvProvider) -> Result<Self> {
+        let auth_store = AuthStore::new(None::<PathBuf>)
+            .inspect_err(|e| log::warn!("Failed to initialize auth store: {e}"))
+            .map(|mut s| {
+                s.load();
+                s
+            })
+            .ok();
+        let git_info = GitInfo::from_path(dir.as_ref(), env, auth_store.as_ref())?;
+
+        let config_dir = determine_config_dir(None, env)
+            .ok()
+            .filter(|dir| dir.exists());
+        let configuration = match &config_dir {
+            Some(dir) => {
+                let mut configuration = Configuration::from_path(dir);
+                configuration.load_checklists();
+                configuration
+            }
+            None => Configuration::default(),
+        };
+        let cache_dir = config_dir.as_deref().and_then(configured_cache_directory);
+        let cache = cache_or_warn(DiskCache::open(&git_info, cache_dir.as_deref(), env));
+
+        let downloader = UreqDownloader::with_tls(git_info.tls());
+        Ok(Self::new(git_info, configuration, cache).with_downloader(downloader))
+    }
+}
+
+impl<G: GitProvider> GhqcClient<G> {
+    pub fn new(git: G, configuration: Configuration, cache: Option<DiskCache>) -> Self {
+        Self {
+            git,
+            configuration,
+            cache,
+            downloader: UreqDownloader::new(),
+        }
+    }
+
+    /// Download record images with `downloader`, e.g. one trusting an internal certificate
+    /// authority
+    pub fn with_downloader(mut self, downloader: UreqDownloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    pub fn git(&self) -> &G {
+        &self.git
+    }
+
+    pub fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
+
+    pub fn cache(&self) -> Option<&DiskCache> {
+        self.cache.as_ref()
+    }
+
+    pub async fn milestones(&self) -> Result<Vec<Milestone>> {
+        Ok(get_milestones_cached(self.cache(), &self.git).await?)
+    }
+
+    /// Create the QC issue of the request, creating its milestone first if needed
+    pub async fn create_issue(&self, request: CreateIssueRequest) -> Result<CreateResult> {
+        let issue = self.build_issue(request, false).await?;
+        self.post_issue(&issue).await
+    }
+
+    /// The QC issue of the request, without posting it. With `dry_run` a missing milestone is
+    /// not created
+    pub async fn build_issue(&self, request: CreateIssueRequest, dry_run: bool) -> Result<QCIssue> {
+        let milestones = self.milestones().await?;
+        let repo_users = get_repo_users(self.cache(), &self.git).await?;
+        let options = CreateOptions {
+            configuration: &self.configuration,
+            cache: self.cache(),
+            require_write_access: request.require_write_access
+                || self.configuration.require_write_access(),
+            dry_run,
+            auto_previous: request.auto_previous,
+            allow_duplicate: request.allow_duplicate,
+        };
+        let no_environment = request.no_environment;
+
+        let issue =
+            QCIssue::from_args(request, milestones, &repo_users, options, &self.git).await?;
+        if no_environment {
+            Ok(issue)
+        } else {
+            Ok(issue.with_environment(self.configuration.environment_files(), &self.git))
+        }
+    }
+
+    /// Post a QC issue, creating the labels it needs and linking its blocking QCs
+    pub async fn post_issue(&self, issue: &QCIssue) -> Result<CreateResult> {
+        create_labels_if_needed(self.cache(), Some(issue.branch()), &self.git).await?;
+        Ok(issue.post_with_blocking(&self.git).await?)
+    }
+
+    /// Post the QC comment of the request
+    pub async fn comment(&self, request: CommentRequest) -> Result<PostedComment> {
+        let comment = self.build_comment(request).await?;
+        self.post_comment(&comment).await
+    }
+
+    /// The QC comment of the request, without posting it
+    pub async fn build_comment(&self, request: CommentRequest) -> Result<QCComment> {
+        let milestones = self.milestones().await?;
+        let no_environment = request.no_environment;
+        let mut comment =
+            QCComment::from_args(request, &milestones, self.cache(), &self.git).await?;
+        if !no_environment {
+            comment = comment.with_environment(self.configuration.environment_files(), &self.git);
+        }
+        Ok(comment)
+    }
+
+    pub async fn post_comment(&self, comment: &QCComment) -> Result<PostedComment> {
+        let comment_url = self.git.post_comment(comment).await?;
+        Ok(PostedComment::new(
+            "✅ Comment created!",
+            comment.issue.number,
+            comment_url,
+        ))
+    }
+
+    /// Approve the issue of the request and close it
+    pub async fn approve(&self, request: ApproveRequest) -> Result<ApprovalResult> {
+        let force = request.force;
+        let approval = self.build_approval(request).await?;
+        self.post_approval(&approval, force).await
+    }
+
+    /// The approval of the request, without posting it
+    pub async fn build_approval(&self, request: ApproveRequest) -> Result<QCApprove> {
+        let milestones = self.milestones().await?;
+        let require_complete_checklist =
+            !request.allow_incomplete_checklist && self.configuration.require_complete_checklist();
+        let approval = QCApprove::from_args(
+            request,
+            &milestones,
+            require_complete_checklist,
+            self.cache(),
+            &self.git,
+        )
+        .await?;
+
+        approval.check_effective_date(&self.git, Utc::now().date_naive())?;
+        Ok(approval)
+    }
+
+    /// Post an approval and close its issue. Refuses while blocking QCs are unapproved unless
+    /// `force` is set
+    pub async fn post_approval(&self, approval: &QCApprove, force: bool) -> Result<ApprovalResult> {
+        Ok(approve_with_validation(approval, &self.git, self.cache(), force).await?)
+    }
+
+    /// QC status of the issue of `file` in `milestone`
+    pub async fn status(
+        &self,
+        milestone: &str,
+        file: impl AsRef<Path>,
+    ) -> Result<IssueStatusReport> {
+        Ok(self.issue_status(milestone, file).await?.report)
+    }
+
+    /// [`Self::status`] with the issue thread and git status it was determined from
+    pub async fn issue_status(
+        &self,
+        milestone: &str,
+        file: impl AsRef<Path>,
+    ) -> Result<IssueStatus> {
+        let milestones = self.milestones().await?;
+        let issue = find_issue(milestone, file, &milestones, &self.git).await?;
+        let checklists = analyze_issue_checklists(issue.body.as_deref());
+        let thread = IssueThread::from_issue(&issue, self.cache(), &self.git).await?;
+        let git_status = get_git_status(&self.git)?;
+        let blocking_qcs =
+            get_blocking_qc_status(&thread.blocking_qcs, &self.git, self.cache()).await;
+        let report = IssueStatusReport::new(
+            &thread,
+            checklists,
+            blocking_qcs,
+            &git_status.state,
+            &git_status.dirty,
+        );
+
+        Ok(IssueStatus {
+            thread,
+            git_status,
+            report,
+        })
+    }
+
+    /// QC status of the milestones titled `names`, sorted by title
+    pub async fn milestone_report(&self, names: &[String]) -> Result<Vec<MilestoneStatusReport>> {
+        let milestones = self.milestones().await?;
+        let selected = select_milestones(&milestones, names)?;
+        Ok(milestone_status_report(&selected, self.cache(), &self.git).await?)
+    }
+
+    /// Write the archive of the request, returning its path
+    pub async fn archive(&self, request: ArchiveRequest) -> Result<PathBuf> {
+        self.archive_with_renderer(request, |record_str, path, staging_dir| {
+            Ok(render(
+                record_str,
+                path,
+                RecordFormat::Pdf,
+                staging_dir,
+                &[],
+                self.cache(),
+                &self.downloader,
+            )?)
+        })
+        .await
+    }
+
+    /// [`Self::archive`], rendering the record of an `include_record` request with `render_pdf`
+    /// instead of Typst
+    pub async fn archive_with_renderer(
+        &self,
+        request: ArchiveRequest,
+        render_pdf: impl FnOnce(&str, &Path, &Path) -> Result<()>,
+    ) -> Result<PathBuf> {
+        let milestones = self.milestones().await?;
+        let selected = if request.milestones.is_empty() {
+            if request.additional_files.is_empty() {
+                bail!("Must specify milestones and/or file commits to generate an archive");
+            }
+            Vec::new()
+        } else {
+            select_milestones(&milestones, &request.milestones)?
+        };
+
+        let mut files = milestone_archive_files(
+            &selected,
+            request.include_unapproved,
+            &request.approval_window,
+            request.flatten,
+            request.include_relevant_files,
+            &self.git,
+            self.cache(),
+        )
+        .await?;
+        files.extend(request.additional_files);
+
+        let mut generated = Vec::new();
+        if request.include_record {
+            if selected.is_empty() {
+                bail!("--include-record needs milestones to generate the record of");
+            }
+            let record = archive_record(
+                &selected,
+                &self.configuration,
+                &self.git,
+                &StdEnvProvider,
+                self.cache(),
+                &self.downloader,
+                render_pdf,
+            )
+            .await;
+            generated.extend(optional_record(record, request.record_optional)?);
+        }
+
+        let path = request.path.unwrap_or_else(|| {
+            PathBuf::from("archive").join(generate_archive_name(&selected, &self.git))
+        });
+        let path = if path.is_absolute() {
+            path
+        } else {
+            self.git.path().join(path)
+        };
+        self.write_archive(
+            files,
+            generated,
+            request.approval_window,
+            request.max_file_size,
+            &path,
+        )?;
+        Ok(path)
+    }
+
+    /// Write `files` and the `generated` files to an archive at `path`, returning its manifest
+    ///
+    /// Fails before writing anything when a file is larger than `max_file_size` bytes.
+    pub fn write_archive(
+        &self,
+        files: Vec<ArchiveFile>,
+        generated: Vec<GeneratedArchiveFile>,
+        approval_window: ApprovalWindow,
+        max_file_size: Option<u64>,
+        path: &Path,
+    ) -> Result<ArchiveManifest> {
+        let metadata = generated.into_iter().fold(
+            ArchiveMetadata::new(files, &self.git, &StdEnvProvider)?
+                .with_approval_window(approval_window)
+                .with_size_limits(
+                    self.configuration
+                        .options
+                        .archive_size_limits(max_file_size),
+                ),
+            ArchiveMetadata::with_generated_file,
+        );
+        Ok(archive(metadata, &self.git, path)?)
+    }
+}
+
+/// The milestones titled `names`, failing when none of them exist
+fn select_milestones<'a>(
+    milestones: &'a [Milestone],
+    names: &[String],
+) -> Result<Vec<&'a Milestone>> {
+    let selected: Vec<&Milestone> = milestones
+        .iter()
+        .filter(|m| names.contains(&m.title))
+        .collect();
+    if selected.is_empty() {
+        return Err(anyhow!(
+            "No matching milestones found for: {}",
+            names.join(", ")
+        ));
+    }
+    Ok(selected)
+}
+
+#[cfg(all(test, feature = "api"))]
+mod tests {
+    use super::*;
+    use crate::Checklist;
+    use crate::api::tests::helpers::{MockGitInfo, WriteCall};
+    use crate::test_utils::{create_test_issue, create_test_milestone};
+
+    const ISSUE_BODY: &str = "Quality check issue for src/main.rs
+
+## Metadata
+initial qc commit: 456def789abc012345678901234567890123cdef
+git branch: main
+author: Test Author <test@example.com>
+
+# Code Review
+- [x] Code is readable
+- [ ] Tests cover the change
+";
+
+    fn git_with_issue(state: &str) -> MockGitInfo {
+        MockGitInfo::builder()
+            .with_milestone(create_test_milestone(
+                "test-owner",
+                "test-repo",
+                1,
+                "Sprint 1",
+                None,
+                "open",
+            ))
+            .with_issue(
+                1,
+                create_test_issue(
+                    "test-owner",
+                    "test-repo",
+                    1,
+                    "src/main.rs",
+                    ISSUE_BODY,
+                    Some(1),
+                    state,
+                ),
+            )
+            .build()
+    }
+
+    /// A cache already knowing the labels, which the mock cannot create
+    fn cache_with_labels(dir: &Path) -> DiskCache {
+        let cache = DiskCache::at_root(
+            dir.to_path_buf(),
+            "test-owner".to_string(),
+            "test-repo".to_string(),
+        );
+        let labels = vec!["ghqc".to_string(), "main".to_string()];
+        cache.write(&["labels"], "names", &labels, true).unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_creates_missing_milestone() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = MockGitInfo::builder().build();
+        let mut configuration = Configuration::default();
+        configuration.checklists.insert(
+            "Code Review".to_string(),
+            Checklist::new(
+                "Code Review".to_string(),
+                None,
+                "- [ ] Code is readable".to_string(),
+            ),
+        );
+        let client = GhqcClient::new(
+            git.clone(),
+            configuration,
+            Some(cache_with_labels(dir.path())),
+        );
+
+        let mut request = CreateIssueRequest::new(
+            "Sprint 2",
+            "src/main.rs",
+            ChecklistArg::Name("Code Review".to_string()),
+        );
+        request.description = Some("Final analysis".to_string());
+        let created = client.create_issue(request).await.unwrap();
+
+        assert_eq!(created.issue_number, 1);
+        assert!(created.body.contains("- [ ] Code is readable"));
+        assert_eq!(
+            git.write_calls(),
+            vec![
+                WriteCall::CreateMilestone {
+                    name: "Sprint 2".to_string(),
+                    description: Some("Final analysis".to_string()),
+                },
+                WriteCall::PostIssue {
+                    title: "src/main.rs".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_refuses_unknown_checklist() {
+        let git = MockGitInfo::builder().build();
+        let client = GhqcClient::new(git.clone(), Configuration::default(), None);
+
+        let request = CreateIssueRequest::new(
+            "Sprint 1",
+            "src/main.rs",
+            ChecklistArg::Name("Missing".to_string()),
+        );
+        let err = client.create_issue(request).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "No checklist named Missing");
+        assert!(git.write_calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_comment_posts_on_the_file_issue() {
+        let git = git_with_issue("open");
+        let client = GhqcClient::new(git.clone(), Configuration::default(), None);
+
+        let mut request = CommentRequest::new("Sprint 1", "src/main.rs");
+        request.note = Some("Addressed review feedback".to_string());
+        let posted = client.comment(request).await.unwrap();
+
+        assert_eq!(posted.issue_number, 1);
+        assert_eq!(
+            posted.comment_url,
+            "https://github.com/test-owner/test-repo/issues/1#issuecomment-123"
+        );
+        assert_eq!(
+            git.write_calls(),
+            vec![WriteCall::PostComment {
+                comment_type: std::any::type_name::<QCComment>().to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_approve_posts_and_closes_the_issue() {
+        let git = git_with_issue("open");
+        let client = GhqcClient::new(git.clone(), Configuration::default(), None);
+
+        // The default configuration requires a complete checklist
+        let err = client
+            .approve(ApproveRequest::new("Sprint 1", "src/main.rs"))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("1 of 2 checklist items are unchecked")
+        );
+        assert!(git.write_calls().is_empty());
+
+        let mut request = ApproveRequest::new("Sprint 1", "src/main.rs");
+        request.allow_incomplete_checklist = true;
+        let result = client.approve(request).await.unwrap();
+
+        assert!(result.approval_url.ends_with("/issues/1#issuecomment-123"));
+        assert_eq!(
+            git.write_calls(),
+            vec![
+                WriteCall::PostComment {
+                    comment_type: std::any::type_name::<QCApprove>().to_string(),
+                },
+                WriteCall::CloseIssue { issue_number: 1 },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_and_milestone_report() {
+        let client = GhqcClient::new(git_with_issue("open"), Configuration::default(), None);
+
+        let status = client.status("Sprint 1", "src/main.rs").await.unwrap();
+        assert_eq!(status.issue_number, 1);
+        assert_eq!(status.checklist.completed, 1);
+        assert_eq!(status.checklist.total, 2);
+
+        let reports = client
+            .milestone_report(&["Sprint 1".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].milestone, "Sprint 1");
+
+        let err = client
+            .milestone_report(&["Sprint 9".to_string()])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No matching milestones found for: Sprint 9"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archive_writes_to_the_requested_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = GhqcClient::new(git_with_issue("open"), Configuration::default(), None);
+
+        let path = client
+            .archive(ArchiveRequest {
+                milestones: vec!["Sprint 1".to_string()],
+                include_unapproved: true,
+                path: Some(dir.path().join("sprint-1.tar.gz")),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(path, dir.path().join("sprint-1.tar.gz"));
+        assert!(path.exists());
+    }
+
+    /// Names and contents of the files in the archive at `path`
+    fn archive_entries(path: &Path) -> std::collections::HashMap<String, Vec<u8>> {
+        let file = std::fs::File::open(path).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let name = entry.path().unwrap().to_string_lossy().to_string();
+                let mut content = Vec::new();
+                std::io::Read::read_to_end(&mut entry, &mut content).unwrap();
+                (name, content)
+            })
+            .collect()
+    }
+
+    fn record_request(dir: &Path, record_optional: bool) -> ArchiveRequest {
+        ArchiveRequest {
+            milestones: vec!["Sprint 1".to_string()],
+            include_unapproved: true,
+            path: Some(dir.join("sprint-1.tar.gz")),
+            include_record: true,
+            record_optional,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_includes_the_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = GhqcClient::new(git_with_issue("open"), Configuration::default(), None);
+
+        let path = client
+            .archive_with_renderer(record_request(dir.path(), false), |record_str, path, _| {
+                assert!(record_str.contains("Sprint 1"));
+                std::fs::write(path, b"%PDF-1.7 stub")?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let entries = archive_entries(&path);
+        assert_eq!(entries["QC_RECORD.pdf"], b"%PDF-1.7 stub");
+        let manifest = String::from_utf8(entries["manifest.json"].clone()).unwrap();
+        assert!(manifest.contains("\"description\": \"milestone record\""));
+        assert!(crate::verify_archive(&path).unwrap().is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_archive_record_failure_is_fatal_unless_optional() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = GhqcClient::new(git_with_issue("open"), Configuration::default(), None);
+        let failing = |_: &str, _: &Path, _: &Path| Err(anyhow!("typst is unavailable"));
+
+        let err = client
+            .archive_with_renderer(record_request(dir.path(), false), failing)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("typst is unavailable"));
+        assert!(!dir.path().join("sprint-1.tar.gz").exists());
+
+        let path = client
+            .archive_with_renderer(record_request(dir.path(), true), failing)
+            .await
+            .unwrap();
+        let entries = archive_entries(&path);
+        assert!(!entries.contains_key("QC_RECORD.pdf"));
+        assert!(entries.contains_key("src/main.rs"));
+    }
+}
@@ -0,0 +1,475 @@
+//! CODEOWNERS: who owns a path, used to keep a file's owners from QCing it and to suggest their
+//! team-mates instead
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GitHubReader,
+    git::{GitFileOps, GitFileOpsError},
+    utils::repo_path_string,
+};
+
+/// Where GitHub looks for the CODEOWNERS file, in order. The first one found is used.
+pub const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// An owner listed in CODEOWNERS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeOwner {
+    /// `@login`
+    User(String),
+    /// `@org/team-slug`
+    Team { org: String, slug: String },
+    /// `user@example.com`
+    Email(String),
+}
+
+impl CodeOwner {
+    fn parse(owner: &str) -> Option<Self> {
+        match owner.strip_prefix('@') {
+            Some(handle) => match handle.split_once('/') {
+                Some((org, slug)) if !org.is_empty() && !slug.is_empty() && !slug.contains('/') => {
+                    Some(Self::Team {
+                        org: org.to_string(),
+                        slug: slug.to_string(),
+                    })
+                }
+                Some(_) => None,
+                None if !handle.is_empty() => Some(Self::User(handle.to_string())),
+                None => None,
+            },
+            None if owner.contains('@') => Some(Self::Email(owner.to_string())),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for CodeOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(login) => write!(f, "@{login}"),
+            Self::Team { org, slug } => write!(f, "@{org}/{slug}"),
+            Self::Email(email) => write!(f, "{email}"),
+        }
+    }
+}
+
+/// A CODEOWNERS line: a pattern and the owners of the paths it matches
+#[derive(Debug, Clone)]
+struct CodeOwnersRule {
+    regex: Regex,
+    owners: Vec<CodeOwner>,
+}
+
+/// What to do when a selected QCer owns the file in CODEOWNERS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OwnerQCPolicy {
+    /// Owners may QC the file
+    #[default]
+    Off,
+    /// Warn about owners assigned to QC the file
+    Warn,
+    /// Refuse to assign owners to QC the file
+    Block,
+}
+
+/// A parsed CODEOWNERS file
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    /// The file the rules were read from
+    pub path: PathBuf,
+    rules: Vec<CodeOwnersRule>,
+}
+
+impl CodeOwners {
+    /// Parse CODEOWNERS content
+    ///
+    /// Lines GitHub would reject (negated patterns, character ranges) are skipped, as are owners
+    /// that are not a `@login`, `@org/team` or email address.
+    pub fn parse(path: impl Into<PathBuf>, content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                // Comments run from a `#` to the end of the line
+                let line = match line.find(" #") {
+                    Some(pos) => &line[..pos],
+                    None => line,
+                };
+                let mut tokens = line.split_whitespace();
+                let pattern = tokens.next().filter(|p| !p.starts_with('#'))?;
+                let Some(regex) = pattern_regex(pattern) else {
+                    log::debug!("Skipping unsupported CODEOWNERS pattern: {pattern}");
+                    return None;
+                };
+                let owners = tokens
+                    .filter_map(|owner| {
+                        let parsed = CodeOwner::parse(owner);
+                        if parsed.is_none() {
+                            log::debug!("Skipping invalid CODEOWNERS owner: {owner}");
+                        }
+                        parsed
+                    })
+                    .collect();
+                Some(CodeOwnersRule { regex, owners })
+            })
+            .collect();
+
+        Self {
+            path: path.into(),
+            rules,
+        }
+    }
+
+    /// Read the repository's CODEOWNERS from HEAD, if it has one
+    pub fn from_head(git_info: &impl GitFileOps) -> Result<Option<Self>, GitFileOpsError> {
+        let head = git_info.resolve_commit("HEAD")?;
+        for path in CODEOWNERS_PATHS {
+            match git_info.file_bytes_at_commit(Path::new(path), &head) {
+                Ok(bytes) => {
+                    return Ok(Some(Self::parse(path, &String::from_utf8_lossy(&bytes))));
+                }
+                Err(GitFileOpsError::FileNotFoundAtCommit(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Owners of `file`, from the last matching rule
+    pub fn owners(&self, file: &Path) -> &[CodeOwner] {
+        let file = repo_path_string(file);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.regex.is_match(&file))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Translate a gitignore-style CODEOWNERS pattern into a regex matching repo-relative file paths
+///
+/// - A leading `/`, or a `/` within the pattern, anchors it to the repository root
+/// - A trailing `/` matches everything within the directory
+/// - `*` and `?` match within a path segment, `**` across segments
+/// - A pattern naming a directory also matches the files within it, except when its last
+///   segment has a wildcard: `docs/*` matches `docs/a.md` but not `docs/b/c.md`
+pub(crate) fn pattern_regex(pattern: &str) -> Option<Regex> {
+    if pattern.starts_with('!') || pattern.contains('[') || pattern.contains('\\') {
+        return None;
+    }
+
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut rest = trimmed;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            regex.push_str("[^/]*");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('?') {
+            regex.push_str("[^/]");
+            rest = after;
+        } else {
+            let c = rest.chars().next()?;
+            regex.push_str(&regex::escape(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    let last_segment = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    if directory {
+        regex.push_str("/.*$");
+    } else if last_segment.contains(['*', '?']) {
+        regex.push('$');
+    } else {
+        regex.push_str("(?:/.*)?$");
+    }
+
+    Regex::new(&regex).ok()
+}
+
+/// Assignees who own the file as individual owners
+///
+/// Team owners don't make their members owners: team-mates are who should QC the file.
+pub fn owner_assignees<'a>(assignees: &'a [String], owners: &[CodeOwner]) -> Vec<&'a str> {
+    assignees
+        .iter()
+        .filter(|assignee| {
+            owners.iter().any(|owner| match owner {
+                CodeOwner::User(login) => login.eq_ignore_ascii_case(assignee),
+                _ => false,
+            })
+        })
+        .map(|assignee| assignee.as_str())
+        .collect()
+}
+
+/// Check the assignees against the file's owners under `policy`
+///
+/// Returns the assignees who own the file so callers can warn, or an error listing them when the
+/// policy blocks owners.
+pub fn check_owner_assignees(
+    file: &Path,
+    assignees: &[String],
+    owners: &[CodeOwner],
+    policy: OwnerQCPolicy,
+) -> Result<Vec<String>, OwnerAssigneeError> {
+    if policy == OwnerQCPolicy::Off {
+        return Ok(Vec::new());
+    }
+
+    let owning = owner_assignees(assignees, owners)
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if policy == OwnerQCPolicy::Block && !owning.is_empty() {
+        return Err(OwnerAssigneeError {
+            file: file.to_path_buf(),
+            assignees: owning,
+        });
+    }
+
+    Ok(owning)
+}
+
+/// Members of the file's owning teams who don't own it themselves, to suggest as QCers
+///
+/// Teams whose members cannot be fetched (e.g. the token lacks `read:org`) are skipped with a
+/// warning.
+pub async fn suggested_qcers(owners: &[CodeOwner], git_info: &impl GitHubReader) -> Vec<String> {
+    let mut suggested = Vec::new();
+    for owner in owners {
+        let CodeOwner::Team { org, slug } = owner else {
+            continue;
+        };
+        match git_info.get_team_members(org, slug).await {
+            Ok(members) => suggested.extend(members),
+            Err(e) => log::warn!("Could not fetch members of {owner}: {e}"),
+        }
+    }
+
+    let owning = owner_assignees(&suggested, owners)
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let mut seen = std::collections::HashSet::new();
+    suggested.retain(|member| !owning.contains(member) && seen.insert(member.clone()));
+    suggested
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Assignees own {} in CODEOWNERS and cannot QC it: {}", .file.display(), .assignees.join(", "))]
+pub struct OwnerAssigneeError {
+    pub file: PathBuf,
+    pub assignees: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{GitHubApiError, MockGitFileOps, MockGitHubReader};
+    use gix::ObjectId;
+    use std::str::FromStr;
+
+    const HEAD: &str = "1111111111111111111111111111111111111111";
+
+    fn owners_of(content: &str, file: &str) -> Vec<String> {
+        CodeOwners::parse("CODEOWNERS", content)
+            .owners(Path::new(file))
+            .iter()
+            .map(|o| o.to_string())
+            .collect()
+    }
+
+    fn matches(pattern: &str, file: &str) -> bool {
+        !owners_of(&format!("{pattern} @owner"), file).is_empty()
+    }
+
+    #[test]
+    fn test_parse_owners() {
+        let content = "# Default owners\n\
+            * @global user@example.com\n\
+            \n\
+            *.R @a2-ai/statisticians @alice # R code\n\
+            /docs/ @not/valid/ @ invalid\n";
+
+        assert_eq!(
+            owners_of(content, "README.md"),
+            ["@global", "user@example.com"]
+        );
+        assert_eq!(
+            owners_of(content, "scripts/model.R"),
+            ["@a2-ai/statisticians", "@alice"]
+        );
+        assert!(owners_of(content, "docs/index.md").is_empty());
+    }
+
+    #[test]
+    fn test_last_match_wins() {
+        let content = "* @everyone\n/scripts/ @scripters\n/scripts/legacy/\n";
+
+        assert_eq!(owners_of(content, "data/raw.csv"), ["@everyone"]);
+        assert_eq!(owners_of(content, "scripts/model.R"), ["@scripters"]);
+        // A pattern without owners takes ownership away
+        assert!(owners_of(content, "scripts/legacy/old.R").is_empty());
+        assert!(CodeOwners::default().owners(Path::new("any")).is_empty());
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        // Everything
+        assert!(matches("*", "a.txt"));
+        assert!(matches("*", "deep/nested/a.txt"));
+        // Extensions in any directory
+        assert!(matches("*.js", "app.js"));
+        assert!(matches("*.js", "src/web/app.js"));
+        assert!(!matches("*.js", "src/app.json"));
+        // Unanchored directories match at any depth
+        assert!(matches("apps/", "apps/main.R"));
+        assert!(matches("apps/", "src/apps/deep/main.R"));
+        assert!(!matches("apps/", "apps"));
+        // Anchored directories only at the root
+        assert!(matches("/build/logs/", "build/logs/out.txt"));
+        assert!(!matches("/build/logs/", "src/build/logs/out.txt"));
+        assert!(matches("docs/", "docs/a/b.md"));
+        // A name without a trailing slash matches files and directories
+        assert!(matches("logs", "logs"));
+        assert!(matches("logs", "src/logs/today.txt"));
+        assert!(!matches("logs", "src/logs.txt"));
+        // Wildcard last segments match files directly within the directory
+        assert!(matches("docs/*", "docs/getting-started.md"));
+        assert!(!matches("docs/*", "docs/build-app/troubleshooting.md"));
+        assert!(!matches("docs/*", "src/docs/a.md"));
+        // `**` crosses directories
+        assert!(matches("**/logs", "logs/a.txt"));
+        assert!(matches("**/logs", "deep/build/logs/a.txt"));
+        assert!(matches("docs/**", "docs/a/b/c.md"));
+        assert!(matches("/scripts/**/model.R", "scripts/model.R"));
+        assert!(matches("/scripts/**/model.R", "scripts/a/b/model.R"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file10.txt"));
+        // Dots are literal
+        assert!(!matches("a.R", "abR"));
+        // Paths are repo-relative
+        assert!(matches("/scripts/", "./scripts/model.R"));
+    }
+
+    #[test]
+    fn test_unsupported_patterns_are_skipped() {
+        assert!(!matches("!*.R", "model.R"));
+        assert!(!matches("*.[Rr]", "model.R"));
+        assert!(!matches("\\#file", "#file"));
+        assert!(!matches("/", "model.R"));
+    }
+
+    #[test]
+    fn test_from_head_reads_first_location() {
+        let mut git = MockGitFileOps::new();
+        git.expect_resolve_commit()
+            .returning(|_| Ok(ObjectId::from_str(HEAD).unwrap()));
+        git.expect_file_bytes_at_commit()
+            .returning(|file, _| match file.to_str().unwrap() {
+                "CODEOWNERS" => Ok(b"* @root".to_vec()),
+                "docs/CODEOWNERS" => Ok(b"* @docs".to_vec()),
+                other => Err(GitFileOpsError::FileNotFoundAtCommit(PathBuf::from(other))),
+            });
+
+        let codeowners = CodeOwners::from_head(&git).unwrap().unwrap();
+
+        assert_eq!(codeowners.path, PathBuf::from("CODEOWNERS"));
+        assert_eq!(
+            codeowners.owners(Path::new("a.R")),
+            [CodeOwner::User("root".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_from_head_without_codeowners() {
+        let mut git = MockGitFileOps::new();
+        git.expect_resolve_commit()
+            .returning(|_| Ok(ObjectId::from_str(HEAD).unwrap()));
+        git.expect_file_bytes_at_commit()
+            .returning(|file, _| Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf())));
+
+        assert!(CodeOwners::from_head(&git).unwrap().is_none());
+    }
+
+    fn owners() -> Vec<CodeOwner> {
+        vec![
+            CodeOwner::User("Alice".to_string()),
+            CodeOwner::Team {
+                org: "a2-ai".to_string(),
+                slug: "stats".to_string(),
+            },
+            CodeOwner::Team {
+                org: "a2-ai".to_string(),
+                slug: "private".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_check_owner_assignees() {
+        let file = Path::new("scripts/model.R");
+        let assignees = vec!["alice".to_string(), "bob".to_string()];
+
+        assert!(
+            check_owner_assignees(file, &assignees, &owners(), OwnerQCPolicy::Off)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            check_owner_assignees(file, &assignees, &owners(), OwnerQCPolicy::Warn).unwrap(),
+            ["alice"]
+        );
+
+        let err =
+            check_owner_assignees(file, &assignees, &owners(), OwnerQCPolicy::Block).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Assignees own scripts/model.R in CODEOWNERS and cannot QC it: alice"
+        );
+        assert!(
+            check_owner_assignees(file, &assignees[1..], &owners(), OwnerQCPolicy::Block).is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_suggested_qcers_expands_teams_without_owners() {
+        let mut git = MockGitHubReader::new();
+        git.expect_get_team_members()
+            .times(2)
+            .returning(|org, slug| {
+                let members = match (org, slug) {
+                    ("a2-ai", "stats") => Ok(vec![
+                        "alice".to_string(),
+                        "bob".to_string(),
+                        "carol".to_string(),
+                    ]),
+                    _ => Err(GitHubApiError::NoApi),
+                };
+                Box::pin(async move { members })
+            });
+
+        assert_eq!(suggested_qcers(&owners(), &git).await, ["bob", "carol"]);
+    }
+}
@@ -1,11 +1,14 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use gix::ObjectId;
 use octocrab::models::issues::Issue;
 
 use crate::comment_system::CommentBody;
-use crate::diff_utils;
-use crate::git::{GitFileOps, GitHelpers};
+use crate::data_summary::{data_summary_at_commit, data_summary_change};
+use crate::diff_utils::{self, NoDiff};
+use crate::environment::{EnvironmentFile, environment_files, environment_section};
+use crate::git::{GitFileOps, GitHelpers, GitRepository};
+use crate::issue::FileRenameEvent;
 
 #[derive(Debug, Clone)]
 pub struct QCComment {
@@ -14,7 +17,16 @@ pub struct QCComment {
     pub current_commit: ObjectId,
     pub previous_commit: Option<ObjectId>,
     pub note: Option<String>,
-    pub no_diff: bool,
+    /// Omit the diff, keeping an explicit marker and, unless [`NoDiff::Full`], a change summary
+    pub no_diff: Option<NoDiff>,
+    /// Include the diff even when `.gitattributes` suppresses it
+    pub force_diff: bool,
+    /// Highlighted diff workbook of an Excel file, referenced in the body
+    pub excel_diff: Option<PathBuf>,
+    /// Renames of the file after the previous commit up to the current commit, oldest first
+    pub renames: Vec<FileRenameEvent>,
+    /// Environment descriptor files at the current commit, listed after the metadata
+    pub environment: Vec<EnvironmentFile>,
 }
 
 impl CommentBody for QCComment {
@@ -31,6 +43,27 @@ impl CommentBody for QCComment {
             ));
         }
 
+        for rename in &self.renames {
+            metadata.push(format!("file renamed: {rename}"));
+        }
+
+        if let Some(current) = data_summary_at_commit(
+            self.file_at(&self.current_commit),
+            &self.current_commit,
+            git_info,
+        ) {
+            metadata.push(format!("data summary: {current}"));
+            if let Some(previous) = self
+                .previous_commit
+                .and_then(|p_c| data_summary_at_commit(self.file_at(&p_c), &p_c, git_info))
+            {
+                metadata.push(format!(
+                    "data change: {}",
+                    data_summary_change(&previous, &current)
+                ));
+            }
+        }
+
         let assignees = self
             .issue
             .assignees
@@ -49,19 +82,29 @@ impl CommentBody for QCComment {
         }
 
         body.push(metadata.join("\n* "));
-
-        if !self.no_diff {
-            if let Some(previous_commit) = self.previous_commit {
-                if let Some(difference) =
-                    self.file_diff(&previous_commit, &self.current_commit, git_info)
-                {
-                    body.push(format!("## File Difference\n{}", difference));
-                } else {
-                    log::warn!("Could not generate diff for file {:?}", self.file);
+        body.extend(environment_section(&self.environment));
+
+        if let Some(previous_commit) = self.previous_commit {
+            if let Some(summary) = self.binary_summary(&previous_commit, git_info) {
+                body.push(format!("## File Difference\n{summary}"));
+            } else if let Some(no_diff) = self.no_diff {
+                let omitted = diff_utils::omitted_diff(no_diff, &self.file, || {
+                    self.file_versions(&previous_commit, &self.current_commit, git_info)
+                });
+                body.push(format!("## File Difference\n{}", omitted));
+            } else if let Some(difference) =
+                self.file_diff(&previous_commit, &self.current_commit, git_info)
+            {
+                let mut section = format!("## File Difference\n{}", difference);
+                if let Some(path) = &self.excel_diff {
+                    section.push_str(&format!("\n\n{}", diff_utils::excel_diff_note(path)));
                 }
+                body.push(section);
             } else {
-                log::debug!("Previous Commit not specified. Cannot generate diff...");
+                log::warn!("Could not generate diff for file {:?}", self.file);
             }
+        } else {
+            log::debug!("Previous Commit not specified. Cannot generate diff...");
         }
 
         body.join("\n\n")
@@ -77,6 +120,83 @@ impl CommentBody for QCComment {
 }
 
 impl QCComment {
+    /// List the descriptor files named `names` closest to the file, e.g. the `renv.lock` of its
+    /// project, at the current commit
+    pub fn with_environment(
+        mut self,
+        names: &[String],
+        git_info: &(impl GitHelpers + GitFileOps + GitRepository),
+    ) -> Self {
+        self.environment = environment_files(
+            &self.file,
+            &self.current_commit,
+            names,
+            Some(git_info.path()),
+            git_info,
+        );
+        self
+    }
+
+    /// Write the highlighted diff workbook of an Excel file under `dir` of the repository,
+    /// to be referenced in the body
+    ///
+    /// Does nothing unless the diff of an Excel file against a previous commit is included.
+    pub fn write_excel_diff(
+        &mut self,
+        git_info: &impl GitFileOps,
+        repo_root: &Path,
+        dir: &Path,
+    ) -> Result<(), diff_utils::ExcelDiffError> {
+        let Some(previous_commit) = self.previous_commit else {
+            return Ok(());
+        };
+        if self.no_diff.is_some() || !diff_utils::is_excel_file(&self.file) {
+            return Ok(());
+        }
+        let Some((from_bytes, to_bytes)) =
+            self.file_versions(&previous_commit, &self.current_commit, git_info)
+        else {
+            return Ok(());
+        };
+
+        self.excel_diff = Some(diff_utils::write_excel_diff(
+            repo_root,
+            dir,
+            &self.file,
+            &previous_commit.to_hex_with_len(7).to_string(),
+            &self.current_commit.to_hex_with_len(7).to_string(),
+            &from_bytes,
+            &to_bytes,
+        )?);
+        Ok(())
+    }
+
+    /// Summary of a binary file in place of its diff, `None` for text and Excel files and with
+    /// `--no-diff=full`, which does not read the file versions
+    fn binary_summary(
+        &self,
+        previous_commit: &ObjectId,
+        git_info: &(impl GitHelpers + GitFileOps),
+    ) -> Option<String> {
+        if self.no_diff == Some(NoDiff::Full) || diff_utils::is_excel_file(&self.file) {
+            return None;
+        }
+        let (from_bytes, to_bytes) =
+            self.file_versions(previous_commit, &self.current_commit, git_info)?;
+        let settings = diff_utils::diff_settings();
+        if !diff_utils::is_binary(self.file_at(previous_commit), &from_bytes, &settings)
+            && !diff_utils::is_binary(self.file_at(&self.current_commit), &to_bytes, &settings)
+        {
+            return None;
+        }
+
+        Some(diff_utils::binary_summary(
+            &from_bytes,
+            &to_bytes,
+            Some(&git_info.commit_comparison_url(&self.current_commit, previous_commit)),
+        ))
+    }
+
     /// Generate a diff between two commits for this comment's file
     fn file_diff(
         &self,
@@ -84,15 +204,46 @@ impl QCComment {
         to_commit: &ObjectId,
         git_info: &impl GitFileOps,
     ) -> Option<String> {
-        let Ok(from_bytes) = git_info.file_bytes_at_commit(&self.file, from_commit) else {
+        let (from_bytes, to_bytes) = self.file_versions(from_commit, to_commit, git_info)?;
+
+        // Use the shared diff utilities
+        diff_utils::file_diff_with_attributes(
+            from_bytes,
+            to_bytes,
+            &self.file,
+            git_info,
+            self.force_diff,
+        )
+    }
+
+    /// Bytes of this comment's file at both commits
+    fn file_versions(
+        &self,
+        from_commit: &ObjectId,
+        to_commit: &ObjectId,
+        git_info: &impl GitFileOps,
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let Ok(from_bytes) = git_info.file_bytes_at_commit(self.file_at(from_commit), from_commit)
+        else {
             log::debug!("Could not read file at from commit ({from_commit})...");
             return None;
         };
-        // Get bytes from both commits
-        let to_bytes = git_info.file_bytes_at_commit(&self.file, to_commit).ok()?;
+        let to_bytes = git_info
+            .file_bytes_at_commit(self.file_at(to_commit), to_commit)
+            .ok()?;
 
-        // Use the shared diff utilities
-        diff_utils::file_diff(from_bytes, to_bytes, &self.file)
+        Some((from_bytes, to_bytes))
+    }
+
+    /// The file's name at the previous or current commit, following the renames in between
+    fn file_at(&self, commit: &ObjectId) -> &Path {
+        match (self.renames.first(), self.renames.last()) {
+            (Some(first), _) if self.previous_commit.as_ref() == Some(commit) => {
+                Path::new(&first.old_path)
+            }
+            (_, Some(last)) if commit == &self.current_commit => Path::new(&last.new_path),
+            _ => &self.file,
+        }
     }
 }
 
@@ -100,6 +251,7 @@ impl QCComment {
 mod tests {
     use crate::GitAuthor;
     use crate::GitFileOpsError;
+    use crate::PathAttributes;
 
     use super::*;
     use crate::comment_system::CommentBody;
@@ -121,6 +273,14 @@ mod tests {
         previous_commit: Option<String>,
         note: Option<String>,
         no_diff: bool,
+        /// `--no-diff=full`: omit the change summary along with the diff
+        #[serde(default)]
+        no_diff_full: bool,
+        #[serde(default)]
+        force_diff: bool,
+        /// `.gitattributes` of the file, e.g. `-diff` or `linguist-generated`
+        #[serde(default)]
+        attributes: Vec<String>,
         previous_content: Option<ContentSection>,
         current_content: Option<ContentSection>,
     }
@@ -131,18 +291,20 @@ mod tests {
     }
 
     struct MockGitInfo {
-        file_contents: HashMap<(PathBuf, String), String>,
+        file_contents: HashMap<(PathBuf, String), Vec<u8>>,
+        attributes: PathAttributes,
     }
 
     impl MockGitInfo {
         fn new() -> Self {
             Self {
                 file_contents: HashMap::new(),
+                attributes: PathAttributes::default(),
             }
         }
 
-        fn set_file_content(&mut self, file: PathBuf, commit: String, content: String) {
-            self.file_contents.insert((file, commit), content);
+        fn set_file_content(&mut self, file: PathBuf, commit: String, content: impl Into<Vec<u8>>) {
+            self.file_contents.insert((file, commit), content.into());
         }
     }
 
@@ -174,17 +336,27 @@ mod tests {
             commit: &gix::ObjectId,
         ) -> Result<Vec<u8>, GitFileOpsError> {
             let key = (file.to_path_buf(), commit.to_string());
-            Ok(self
-                .file_contents
+            self.file_contents
                 .get(&key)
                 .cloned()
-                .ok_or_else(|| GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))?
-                .into_bytes())
+                .ok_or_else(|| GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))
         }
 
         fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
             Ok(Vec::new())
         }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<PathAttributes, GitFileOpsError> {
+            Ok(self.attributes)
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
     }
 
     fn load_test_config(test_file: &str) -> TestConfig {
@@ -221,10 +393,22 @@ mod tests {
             current_commit,
             previous_commit,
             note: config.note.clone(),
-            no_diff: config.no_diff,
+            no_diff: match (config.no_diff, config.no_diff_full) {
+                (false, _) => None,
+                (true, false) => Some(NoDiff::Stats),
+                (true, true) => Some(NoDiff::Full),
+            },
+            force_diff: config.force_diff,
+            excel_diff: None,
+            renames: Vec::new(),
+            environment: Vec::new(),
         };
 
         let mut git_info = MockGitInfo::new();
+        git_info.attributes = PathAttributes {
+            no_diff: config.attributes.iter().any(|a| a == "-diff"),
+            generated: config.attributes.iter().any(|a| a == "linguist-generated"),
+        };
 
         // Set up file content for current commit
         if let Some(current_content) = &config.current_content {
@@ -317,6 +501,205 @@ mod tests {
         run_comment_test("no_diff_flag.toml");
     }
 
+    #[test]
+    fn test_no_diff_full() {
+        run_comment_test("no_diff_full.toml");
+    }
+
+    #[test]
+    fn test_no_diff_excel_change_summary() {
+        let file = PathBuf::from("data/summary.xlsx");
+        let previous_commit =
+            ObjectId::from_str("0a1b2c3d4e5f60718293a4b5c6d7e8f901234567").unwrap();
+        let current_commit =
+            ObjectId::from_str("9f8e7d6c5b4a39281706f5e4d3c2b1a098765432").unwrap();
+
+        let mut git_info = MockGitInfo::new();
+        git_info.set_file_content(
+            file.clone(),
+            previous_commit.to_string(),
+            std::fs::read("src/tests/data/summary.xlsx").unwrap(),
+        );
+        git_info.set_file_content(
+            file.clone(),
+            current_commit.to_string(),
+            std::fs::read("src/tests/data/summary_v2.xlsx").unwrap(),
+        );
+
+        let mut comment = QCComment {
+            file,
+            issue: load_issue("test_file_issue.json"),
+            current_commit,
+            previous_commit: Some(previous_commit),
+            note: None,
+            no_diff: Some(NoDiff::Stats),
+            force_diff: false,
+            excel_diff: None,
+            renames: Vec::new(),
+            environment: Vec::new(),
+        };
+
+        let body = comment.generate_body(&git_info);
+        assert!(body.contains(
+            "## File Difference\n_Diff omitted (--no-diff)_\n\nchanges: 2 rows added, 1 row modified, 1 sheet added"
+        ));
+        assert!(!body.contains("@@ Sheet"));
+
+        comment.no_diff = Some(NoDiff::Full);
+        let body = comment.generate_body(&git_info);
+        assert!(body.contains("_Diff and change summary omitted (--no-diff=full)_"));
+        assert!(!body.contains("changes:"));
+    }
+
+    #[test]
+    fn test_excel_diff_workbook_referenced() {
+        let file = PathBuf::from("data/summary.xlsx");
+        let previous_commit =
+            ObjectId::from_str("0a1b2c3d4e5f60718293a4b5c6d7e8f901234567").unwrap();
+        let current_commit =
+            ObjectId::from_str("9f8e7d6c5b4a39281706f5e4d3c2b1a098765432").unwrap();
+
+        let mut git_info = MockGitInfo::new();
+        git_info.set_file_content(
+            file.clone(),
+            previous_commit.to_string(),
+            std::fs::read("src/tests/data/summary.xlsx").unwrap(),
+        );
+        git_info.set_file_content(
+            file.clone(),
+            current_commit.to_string(),
+            std::fs::read("src/tests/data/summary_v2.xlsx").unwrap(),
+        );
+
+        let mut comment = QCComment {
+            file,
+            issue: load_issue("test_file_issue.json"),
+            current_commit,
+            previous_commit: Some(previous_commit),
+            note: None,
+            no_diff: None,
+            force_diff: false,
+            excel_diff: None,
+            renames: Vec::new(),
+            environment: Vec::new(),
+        };
+
+        let repo = tempfile::tempdir().unwrap();
+        comment
+            .write_excel_diff(&git_info, repo.path(), Path::new("qc_diffs"))
+            .unwrap();
+
+        let path = PathBuf::from("qc_diffs/data_summary_0a1b2c3_9f8e7d6.xlsx");
+        assert_eq!(comment.excel_diff, Some(path.clone()));
+        assert!(repo.path().join(&path).is_file());
+
+        let body = comment.generate_body(&git_info);
+        assert!(body.contains("@@ Sheet"));
+        assert!(body.contains(
+            "A highlighted workbook of the full diff was generated at `qc_diffs/data_summary_0a1b2c3_9f8e7d6.xlsx`"
+        ));
+    }
+
+    #[test]
+    fn test_binary_file_summarized_instead_of_diffed() {
+        let file = PathBuf::from("figures/plot.png");
+        let previous_commit =
+            ObjectId::from_str("0a1b2c3d4e5f60718293a4b5c6d7e8f901234567").unwrap();
+        let current_commit =
+            ObjectId::from_str("9f8e7d6c5b4a39281706f5e4d3c2b1a098765432").unwrap();
+
+        let mut git_info = MockGitInfo::new();
+        git_info.set_file_content(
+            file.clone(),
+            previous_commit.to_string(),
+            std::fs::read("src/tests/data/plot.png").unwrap(),
+        );
+        git_info.set_file_content(
+            file.clone(),
+            current_commit.to_string(),
+            std::fs::read("src/tests/data/plot_v2.png").unwrap(),
+        );
+
+        let mut comment = QCComment {
+            file,
+            issue: load_issue("test_file_issue.json"),
+            current_commit,
+            previous_commit: Some(previous_commit),
+            note: None,
+            no_diff: None,
+            force_diff: false,
+            excel_diff: None,
+            renames: Vec::new(),
+            environment: Vec::new(),
+        };
+
+        let body = comment.generate_body(&git_info);
+        let section = body.split("## File Difference\n").nth(1).unwrap();
+        assert!(section.starts_with("_Binary file, diff omitted_\n\n* previous: 73 B, sha256 `"));
+        assert!(section.contains("* changed: yes\n* [commit comparison](https://github.com/"));
+        assert!(!body.contains("```diff"));
+        assert!(!body.contains("PNG"));
+        assert!(!body.contains('\u{FFFD}'));
+
+        // Binaries imply --no-diff, without counting lines of their bytes
+        comment.no_diff = Some(NoDiff::Stats);
+        assert_eq!(comment.generate_body(&git_info), body);
+    }
+
+    #[test]
+    fn test_large_diff_truncated_and_split_within_comment_limit() {
+        let file = PathBuf::from("data/pk.csv");
+        let previous_commit =
+            ObjectId::from_str("0a1b2c3d4e5f60718293a4b5c6d7e8f901234567").unwrap();
+        let current_commit =
+            ObjectId::from_str("9f8e7d6c5b4a39281706f5e4d3c2b1a098765432").unwrap();
+
+        // Every 8th of 2,400 lines of 150 characters changes: ~380,000 characters of diff
+        let line = |i: usize, version: &str| format!("{i:>6},{version},{}", "x".repeat(140));
+        let previous = (0..2400).map(|i| line(i, "old")).collect::<Vec<_>>();
+        let current = (0..2400)
+            .map(|i| line(i, if i % 8 == 0 { "new" } else { "old" }))
+            .collect::<Vec<_>>();
+        let mut git_info = MockGitInfo::new();
+        git_info.set_file_content(
+            file.clone(),
+            previous_commit.to_string(),
+            previous.join("\n").into_bytes(),
+        );
+        git_info.set_file_content(
+            file.clone(),
+            current_commit.to_string(),
+            current.join("\n").into_bytes(),
+        );
+
+        let comment = QCComment {
+            file,
+            issue: load_issue("test_file_issue.json"),
+            current_commit,
+            previous_commit: Some(previous_commit),
+            note: None,
+            no_diff: None,
+            force_diff: false,
+            excel_diff: None,
+            renames: Vec::new(),
+            environment: Vec::new(),
+        };
+
+        let body = comment.generate_body(&git_info);
+        assert!(body.len() < 260_000);
+        assert!(body.contains("omitted: the diff exceeds 250000 characters"));
+
+        let parts = crate::body_splitter::split_comment_body(comment.title(), body);
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| part.len() <= 65_536));
+        assert!(
+            parts
+                .last()
+                .unwrap()
+                .contains("omitted: the diff exceeds 250000 characters")
+        );
+    }
+
     #[test]
     fn test_no_previous_commit() {
         run_comment_test("no_previous_commit.toml");
@@ -326,4 +709,24 @@ mod tests {
     fn test_separated_hunks() {
         run_comment_test("separated_hunks.toml");
     }
+
+    #[test]
+    fn test_data_file_change() {
+        run_comment_test("data_file_change.toml");
+    }
+
+    #[test]
+    fn test_quarto_chunks() {
+        run_comment_test("quarto_chunks.toml");
+    }
+
+    #[test]
+    fn test_gitattributes_no_diff() {
+        run_comment_test("gitattributes_no_diff.toml");
+    }
+
+    #[test]
+    fn test_gitattributes_force_diff() {
+        run_comment_test("gitattributes_force_diff.toml");
+    }
 }
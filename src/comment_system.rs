@@ -1,6 +1,6 @@
 use octocrab::models::issues::Issue;
 
-use crate::git::{GitFileOps, GitHelpers};
+use crate::git::{GitFileOps, GitHelpers, RemoteMismatch, guard_comment_body};
 
 /// Trait for generating comment bodies for GitHub issues
 ///
@@ -21,3 +21,20 @@ pub trait CommentBody {
     /// Used when splitting large bodies into multiple parts, e.g. "QC Notification".
     fn title(&self) -> &str;
 }
+
+/// Body `comment` is posted with, built without posting it
+///
+/// This is the body a writer's `post_comment` sends, before splitting it into parts, so a draft
+/// can be reviewed or posted by hand. See [`guard_comment_body`] for `trust_mismatch`.
+pub fn comment_draft(
+    comment: &impl CommentBody,
+    git_info: &(impl GitHelpers + GitFileOps),
+    trust_mismatch: bool,
+) -> Result<String, RemoteMismatch> {
+    guard_comment_body(
+        comment.issue(),
+        git_info,
+        trust_mismatch,
+        comment.generate_body(git_info),
+    )
+}
@@ -8,11 +8,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::git::{GitCli, GitRepository, GitStatusOps, get_git_status};
-use crate::utils::EnvProvider;
+use crate::archive::ArchiveSizeLimits;
+use crate::codeowners::OwnerQCPolicy;
+use crate::diff_utils::DiffSettings;
+use crate::git::{GitCli, GitRepository, GitStatusOps, SigningKeys, get_git_status};
+use crate::issue_template::{ISSUE_TEMPLATE_FILE, IssueTemplate, IssueTemplateError};
+use crate::utils::{EnvProvider, closest_match};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
+#[serde(default, deny_unknown_fields)]
 pub struct ConfigurationOptions {
     // Note to prepend at the top of all checklists
     pub prepended_checklist_note: Option<String>,
@@ -20,12 +24,40 @@ pub struct ConfigurationOptions {
     pub checklist_display_name: String,
     // Whether collaborator metadata should be detected and included. Default: true
     pub include_collaborators: bool,
+    // Whether to block assigning QCers who cannot close/reopen issues. Default: false (warn only)
+    pub require_write_access: bool,
+    // Whether to refuse approving issues with unchecked checklist items. Default: true
+    pub require_complete_checklist: bool,
+    // Whether to warn about or block assigning QCers who own the file in CODEOWNERS. Default: off
+    pub exclude_owners_from_qc: OwnerQCPolicy,
+    // Whether to suggest members of the file's CODEOWNERS teams as QCers. Default: false
+    pub suggest_team_members: bool,
+    // Label applied to issues taken out of QC scope with `issue obsolete`. Default: qc-obsolete
+    pub obsolete_label: String,
     // Path to the logo within the configuration repo. Default: logo
     pub logo_path: PathBuf,
     // Path to the checklist directory within the configuration repo. Default: checklists
     pub checklist_directory: PathBuf,
     // Path to the record template within the configuration repo. Default: record.typ
     pub record_path: PathBuf,
+    // SSH allowed signers file within the configuration repo used to verify commit signatures
+    pub allowed_signers_path: Option<PathBuf>,
+    // GnuPG home directory within the configuration repo holding trusted keys
+    pub gpg_keyring_path: Option<PathBuf>,
+    // Absolute directory for the disk cache. GHQC_CACHE_DIR takes precedence. Default: system cache
+    pub cache_directory: Option<PathBuf>,
+    // Append a local usage event for each command and API write, for `ghqc stats`. Default: false
+    pub usage_stats: bool,
+    // Files larger than this many bytes are not diffed in comments. Default: 10 MiB
+    pub diff_max_file_bytes: u64,
+    // Diffs longer than this many characters keep only their first hunks. Default: 250000
+    pub diff_max_characters: usize,
+    // Extensions of binary files, summarized instead of diffed, on top of the built-in ones
+    pub binary_extensions: Vec<String>,
+    // Environment descriptor files, e.g. renv.lock, listed in issues and comments. Default: none
+    pub environment_files: Vec<String>,
+    // Files larger than this many bytes are archived with a warning. Default: 1 GiB
+    pub archive_warn_file_bytes: u64,
     // UI repo refresh rate in seconds. Falls back to env var/default if not set or invalid
     #[serde(default, deserialize_with = "deserialize_optional_positive_seconds")]
     pub ui_repo_refresh_rate_seconds: Option<u64>,
@@ -37,20 +69,182 @@ impl Default for ConfigurationOptions {
             prepended_checklist_note: None,
             checklist_display_name: "checklists".to_string(),
             include_collaborators: true,
+            require_write_access: false,
+            require_complete_checklist: true,
+            exclude_owners_from_qc: OwnerQCPolicy::Off,
+            suggest_team_members: false,
+            obsolete_label: "qc-obsolete".to_string(),
             logo_path: PathBuf::from("logo.png"),
             checklist_directory: PathBuf::from("checklists"),
             record_path: PathBuf::from("record.typ"),
+            allowed_signers_path: None,
+            gpg_keyring_path: None,
+            cache_directory: None,
+            usage_stats: false,
+            diff_max_file_bytes: DiffSettings::DEFAULT.max_file_bytes,
+            diff_max_characters: DiffSettings::DEFAULT.max_diff_characters,
+            binary_extensions: Vec::new(),
+            environment_files: Vec::new(),
+            archive_warn_file_bytes: ArchiveSizeLimits::DEFAULT_WARN_BYTES,
             ui_repo_refresh_rate_seconds: None,
         }
     }
 }
 
+/// Keys accepted in `options.yaml`, used to suggest the intended key for unknown ones
+const OPTION_KEYS: &[&str] = &[
+    "prepended_checklist_note",
+    "checklist_display_name",
+    "include_collaborators",
+    "require_write_access",
+    "require_complete_checklist",
+    "exclude_owners_from_qc",
+    "suggest_team_members",
+    "obsolete_label",
+    "logo_path",
+    "checklist_directory",
+    "record_path",
+    "allowed_signers_path",
+    "gpg_keyring_path",
+    "cache_directory",
+    "usage_stats",
+    "diff_max_file_bytes",
+    "diff_max_characters",
+    "binary_extensions",
+    "environment_files",
+    "archive_warn_file_bytes",
+    "ui_repo_refresh_rate_seconds",
+];
+
 impl ConfigurationOptions {
-    fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfigurationError> {
+    /// Load and validate the options file at `path`
+    ///
+    /// Unknown keys are reported and ignored. Files that cannot be parsed otherwise (wrong value
+    /// types, invalid YAML) are an error. Values that parse but are invalid are reset to their
+    /// default and reported as warnings.
+    fn from_path(
+        path: impl AsRef<Path>,
+    ) -> Result<(Self, Vec<ConfigurationWarning>), ConfigurationError> {
         let path = path.as_ref();
         let content = fs::read_to_string(path)?;
-        let options = serde_yaml::from_str(&content)?;
-        Ok(options)
+        let (known_content, mut warnings) = without_unknown_keys(path, &content);
+        let mut options: Self = serde_yaml::from_str(&known_content)
+            .map_err(|e| ConfigurationError::InvalidOptions(parse_warning(path, &e)))?;
+        warnings.extend(options.validate().into_iter().map(|(key, message)| {
+            let (line, column) = key_location(&content, key).unzip();
+            ConfigurationWarning {
+                file: path.to_path_buf(),
+                line,
+                column,
+                message,
+            }
+        }));
+        Ok((options, warnings))
+    }
+
+    /// Reset invalid values to their default, returning the key and problem of each. Paths
+    /// outside the configuration repository are reported but kept.
+    fn validate(&mut self) -> Vec<(&'static str, String)> {
+        let defaults = Self::default();
+        let mut problems = Vec::new();
+
+        if self.checklist_display_name.trim().is_empty() {
+            problems.push((
+                "checklist_display_name",
+                format!(
+                    "`checklist_display_name` must not be empty. Using '{}'",
+                    defaults.checklist_display_name
+                ),
+            ));
+            self.checklist_display_name = defaults.checklist_display_name;
+        }
+
+        if self.obsolete_label.trim().is_empty() {
+            problems.push((
+                "obsolete_label",
+                format!(
+                    "`obsolete_label` must not be empty. Using '{}'",
+                    defaults.obsolete_label
+                ),
+            ));
+            self.obsolete_label = defaults.obsolete_label;
+        }
+
+        // Paths outside the configuration repository may be deliberate (e.g. a logo shared by
+        // several configuration repositories), so they are kept and only reported
+        let paths = [
+            ("logo_path", Some(&self.logo_path)),
+            ("checklist_directory", Some(&self.checklist_directory)),
+            ("record_path", Some(&self.record_path)),
+            ("allowed_signers_path", self.allowed_signers_path.as_ref()),
+            ("gpg_keyring_path", self.gpg_keyring_path.as_ref()),
+        ];
+        for (key, path) in paths {
+            if let Some(path) = path.filter(|p| !is_within_repo(p)) {
+                problems.push((key, outside_repo_message(key, path)));
+            }
+        }
+
+        if self.diff_max_file_bytes == 0 {
+            problems.push((
+                "diff_max_file_bytes",
+                format!(
+                    "`diff_max_file_bytes` must be positive. Using {}",
+                    defaults.diff_max_file_bytes
+                ),
+            ));
+            self.diff_max_file_bytes = defaults.diff_max_file_bytes;
+        }
+
+        if self.diff_max_characters == 0 {
+            problems.push((
+                "diff_max_characters",
+                format!(
+                    "`diff_max_characters` must be positive. Using {}",
+                    defaults.diff_max_characters
+                ),
+            ));
+            self.diff_max_characters = defaults.diff_max_characters;
+        }
+
+        if self.archive_warn_file_bytes == 0 {
+            problems.push((
+                "archive_warn_file_bytes",
+                format!(
+                    "`archive_warn_file_bytes` must be positive. Using {}",
+                    defaults.archive_warn_file_bytes
+                ),
+            ));
+            self.archive_warn_file_bytes = defaults.archive_warn_file_bytes;
+        }
+
+        if let Some(cache_directory) = self.cache_directory.take_if(|p| !p.is_absolute()) {
+            problems.push((
+                "cache_directory",
+                format!(
+                    "`cache_directory` must be an absolute path, got '{}'. Using the default cache directory",
+                    cache_directory.display()
+                ),
+            ));
+        }
+
+        problems
+    }
+
+    pub fn diff_settings(&self) -> DiffSettings {
+        DiffSettings {
+            max_file_bytes: self.diff_max_file_bytes,
+            max_diff_characters: self.diff_max_characters,
+            binary_extensions: self.binary_extensions.clone(),
+        }
+    }
+
+    /// Archive size limits with the configured warning size and at most `max_bytes` per file
+    pub fn archive_size_limits(&self, max_bytes: Option<u64>) -> ArchiveSizeLimits {
+        ArchiveSizeLimits {
+            warn_bytes: self.archive_warn_file_bytes,
+            max_bytes,
+        }
     }
 
     pub fn resolved_ui_repo_refresh_rate_seconds(&self, env: &impl EnvProvider) -> u64 {
@@ -68,6 +262,9 @@ impl ConfigurationOptions {
 pub struct Checklist {
     pub name: String,
     pub content: String,
+    /// File name of a one-off local checklist, `None` for checklists from the configuration repo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_file: Option<String>,
 }
 
 impl Checklist {
@@ -76,7 +273,53 @@ impl Checklist {
             "{}{content}",
             note.map(|n| format!("{n}\n\n")).unwrap_or_default()
         );
-        Self { name, content }
+        Self {
+            name,
+            content,
+            inline_file: None,
+        }
+    }
+
+    /// A one-off checklist read from a local markdown file, named after the file stem
+    ///
+    /// The content is used as is, like a `.txt` checklist in the configuration repo, and must
+    /// have at least one task item and no unclosed code fence.
+    pub fn from_inline_file(path: &Path, note: Option<&str>) -> Result<Self, InlineChecklistError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| InlineChecklistError::Read(path.to_path_buf(), e))?;
+        validate_inline_checklist(&content).map_err(|problem| InlineChecklistError::Invalid {
+            path: path.to_path_buf(),
+            problem,
+        })?;
+        let name = extract_title_from_filename(path)
+            .map_err(|_| InlineChecklistError::InvalidFilename(path.to_path_buf()))?;
+
+        Ok(Self {
+            inline_file: path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string()),
+            ..Self::new(name, note, content)
+        })
+    }
+
+    /// Read a checklist file of the configuration repository, `None` for files that are not
+    /// checklists
+    ///
+    /// `.txt` files are used as is and named after the file stem; `.yaml`/`.yml` files hold a
+    /// single key naming the checklist.
+    pub fn from_file(path: &Path, note: Option<&str>) -> Result<Option<Self>, ConfigurationError> {
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            return Ok(None);
+        };
+        let (name, content) = match extension.to_lowercase().as_str() {
+            "txt" => {
+                let content = fs::read_to_string(path)?;
+                (extract_title_from_filename(path)?, content)
+            }
+            "yaml" | "yml" => parse_yaml_checklist(&fs::read_to_string(path)?)?,
+            _ => return Ok(None),
+        };
+        Ok(Some(Self::new(name, note, content)))
     }
 
     pub fn items(&self) -> usize {
@@ -95,7 +338,32 @@ impl Default for Checklist {
         Self {
             name: "Custom".to_string(),
             content: "- [ ] [INSERT]".to_string(),
+            inline_file: None,
+        }
+    }
+}
+
+/// A problem found in the configuration options file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigurationWarning {
+    pub file: PathBuf,
+    /// 1-based line of the problem, when known
+    pub line: Option<usize>,
+    /// 1-based column of the problem, when known
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigurationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
         }
+        write!(f, ": {}", self.message)
     }
 }
 
@@ -105,6 +373,9 @@ pub struct Configuration {
     // checklist name and content
     pub checklists: HashMap<String, Checklist>,
     pub options: ConfigurationOptions,
+    /// Problems found while loading the options file
+    #[serde(default)]
+    pub warnings: Vec<ConfigurationWarning>,
 }
 
 impl Default for Configuration {
@@ -113,6 +384,7 @@ impl Default for Configuration {
             path: PathBuf::default(),
             checklists: HashMap::from([("Custom".to_string(), Checklist::default())]),
             options: ConfigurationOptions::default(),
+            warnings: Vec::new(),
         }
     }
 }
@@ -120,14 +392,30 @@ impl Default for Configuration {
 impl Configuration {
     pub fn from_path(path: impl AsRef<Path>) -> Self {
         let path = path.as_ref();
-        let options = match ConfigurationOptions::from_path(&path.join("options.yaml")) {
-            Ok(o) => o,
+        let options_path = path.join("options.yaml");
+        let (options, warnings) = match ConfigurationOptions::from_path(&options_path) {
+            Ok((options, warnings)) => {
+                for warning in &warnings {
+                    log::warn!("{warning}");
+                }
+                (options, warnings)
+            }
+            Err(ConfigurationError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                log::debug!("No configuration options at {}", options_path.display());
+                (ConfigurationOptions::default(), Vec::new())
+            }
             Err(e) => {
-                log::warn!(
-                    "Could not load configuration options at {} due to: {e}. Using default.",
-                    path.display()
-                );
-                ConfigurationOptions::default()
+                let warning = match e {
+                    ConfigurationError::InvalidOptions(warning) => warning,
+                    e => ConfigurationWarning {
+                        file: options_path.clone(),
+                        line: None,
+                        column: None,
+                        message: e.to_string(),
+                    },
+                };
+                log::warn!("Could not load configuration options: {warning}. Using default.");
+                (ConfigurationOptions::default(), vec![warning])
             }
         };
         log::debug!("checklist note: {:#?}", options.prepended_checklist_note);
@@ -135,6 +423,7 @@ impl Configuration {
         Configuration {
             path: path.to_path_buf(),
             options,
+            warnings,
             ..Default::default()
         }
     }
@@ -165,54 +454,18 @@ impl Configuration {
                 continue;
             }
 
-            let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
-                continue;
-            };
-
-            let Ok(content) = fs::read_to_string(&path) else {
-                log::debug!("Could not read content at {}", path.display());
-                continue;
-            };
-
-            match extension.to_lowercase().as_str() {
-                "txt" => {
-                    match extract_title_from_filename(&path) {
-                        Ok(key) => {
-                            let checklist = Checklist::new(
-                                key.to_string(),
-                                self.options.prepended_checklist_note.as_deref(),
-                                content,
-                            );
-                            self.checklists.insert(key, checklist);
-                        }
-                        Err(e) => {
-                            log::warn!(
-                                "Could not extract title from filename for {} due to: {}. Skipping...",
-                                path.display(),
-                                e
-                            );
-                            continue;
-                        }
-                    };
+            match Checklist::from_file(&path, self.options.prepended_checklist_note.as_deref()) {
+                Ok(Some(checklist)) => {
+                    self.checklists.insert(checklist.name.clone(), checklist);
+                }
+                Ok(None) => continue, // Skip other file types
+                Err(e) => {
+                    log::warn!(
+                        "Could not load checklist {} due to: {}. Skipping...",
+                        path.display(),
+                        e
+                    );
                 }
-                "yaml" | "yml" => match parse_yaml_checklist(&content) {
-                    Ok((title, content)) => {
-                        let checklist = Checklist::new(
-                            title.to_string(),
-                            self.options.prepended_checklist_note.as_deref(),
-                            content,
-                        );
-                        self.checklists.insert(title, checklist);
-                    }
-                    Err(e) => {
-                        log::warn!(
-                            "Could not parse yaml at {} as valid checklist due to: {}",
-                            path.display(),
-                            e
-                        );
-                    }
-                },
-                _ => continue, // Skip other file types
             }
         }
 
@@ -227,6 +480,21 @@ impl Configuration {
         self.path.join(&self.options.record_path)
     }
 
+    pub fn issue_template_path(&self) -> PathBuf {
+        self.path.join(ISSUE_TEMPLATE_FILE)
+    }
+
+    /// The custom layout of new issue bodies, `None` when the configuration repo has no
+    /// `issue_template.md`
+    pub fn issue_template(&self) -> Result<Option<IssueTemplate>, IssueTemplateError> {
+        let path = self.issue_template_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        log::debug!("Using custom issue template from: {}", path.display());
+        IssueTemplate::from_path(path).map(Some)
+    }
+
     pub fn checklist_display_name(&self) -> &str {
         &self.options.checklist_display_name
     }
@@ -242,9 +510,90 @@ impl Configuration {
         self.options.include_collaborators
     }
 
+    pub fn require_write_access(&self) -> bool {
+        self.options.require_write_access
+    }
+
+    pub fn require_complete_checklist(&self) -> bool {
+        self.options.require_complete_checklist
+    }
+
+    pub fn exclude_owners_from_qc(&self) -> OwnerQCPolicy {
+        self.options.exclude_owners_from_qc
+    }
+
+    pub fn suggest_team_members(&self) -> bool {
+        self.options.suggest_team_members
+    }
+
+    pub fn usage_stats(&self) -> bool {
+        self.options.usage_stats
+    }
+
+    pub fn obsolete_label(&self) -> &str {
+        &self.options.obsolete_label
+    }
+
     pub fn ui_repo_refresh_rate_seconds(&self, env: &impl EnvProvider) -> u64 {
         self.options.resolved_ui_repo_refresh_rate_seconds(env)
     }
+
+    pub fn diff_settings(&self) -> DiffSettings {
+        self.options.diff_settings()
+    }
+
+    pub fn cache_directory(&self) -> Option<&Path> {
+        self.options.cache_directory.as_deref()
+    }
+
+    pub fn environment_files(&self) -> &[String] {
+        &self.options.environment_files
+    }
+
+    /// Trusted keys for commit signature verification, resolved within the configuration repo
+    pub fn signing_keys(&self) -> SigningKeys {
+        SigningKeys {
+            allowed_signers: self
+                .options
+                .allowed_signers_path
+                .as_ref()
+                .map(|p| self.path.join(p)),
+            gpg_home: self
+                .options
+                .gpg_keyring_path
+                .as_ref()
+                .map(|p| self.path.join(p)),
+        }
+    }
+}
+
+/// The `cache_directory` option of the configuration at `path`
+///
+/// Read on its own so the cache can be opened before, or without, loading the configuration.
+/// Problems with the options file are left for [`Configuration::from_path`] to report.
+pub fn configured_cache_directory(path: &Path) -> Option<PathBuf> {
+    ConfigurationOptions::from_path(path.join("options.yaml"))
+        .ok()
+        .and_then(|(options, _)| options.cache_directory)
+}
+
+/// The `usage_stats` option of the configuration at `path`
+///
+/// Read on its own, like [`configured_cache_directory`], so usage can be recorded around any
+/// command.
+pub fn configured_usage_stats(path: &Path) -> bool {
+    ConfigurationOptions::from_path(path.join("options.yaml"))
+        .is_ok_and(|(options, _)| options.usage_stats)
+}
+
+/// The diff settings of the configuration at `path`, the defaults without an options file
+///
+/// Read on its own, like [`configured_cache_directory`], as comments are built by commands that
+/// do not otherwise load the configuration.
+pub fn configured_diff_settings(path: &Path) -> DiffSettings {
+    ConfigurationOptions::from_path(path.join("options.yaml"))
+        .map(|(options, _)| options.diff_settings())
+        .unwrap_or_default()
 }
 
 fn deserialize_optional_positive_seconds<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
@@ -277,6 +626,147 @@ fn parse_positive_seconds(value: &str) -> Option<u64> {
         .map(|seconds| seconds as u64)
 }
 
+/// Describe a YAML error in the options file, suggesting the intended key for unknown ones
+fn parse_warning(file: &Path, error: &serde_yaml::Error) -> ConfigurationWarning {
+    let location = error.location();
+    let full_message = error.to_string();
+    // serde_yaml appends the location to the message, which is reported separately
+    let mut message = match full_message.rsplit_once(" at line ") {
+        Some((message, _)) if location.is_some() => message.to_string(),
+        _ => full_message.clone(),
+    };
+
+    let unknown_key = message
+        .strip_prefix("unknown field `")
+        .and_then(|rest| rest.split_once('`'))
+        .map(|(key, _)| key.to_string());
+    if let Some(key) = unknown_key {
+        message = unknown_key_message(&key);
+    }
+
+    ConfigurationWarning {
+        file: file.to_path_buf(),
+        line: location.as_ref().map(|l| l.line()),
+        column: location.as_ref().map(|l| l.column()),
+        message,
+    }
+}
+
+/// Blank out the top-level keys of the options file that are not options, keeping line numbers
+/// so errors in the remaining keys are still reported where they are
+///
+/// Each unknown key is reported with a suggestion for likely typos. Content that is not a YAML
+/// mapping is returned as is, for parsing to report.
+fn without_unknown_keys(file: &Path, content: &str) -> (String, Vec<ConfigurationWarning>) {
+    let Ok(Value::Mapping(mapping)) = serde_yaml::from_str::<Value>(content) else {
+        return (content.to_string(), Vec::new());
+    };
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    let mut warnings = Vec::new();
+    for key in mapping.keys().filter_map(Value::as_str) {
+        if OPTION_KEYS.contains(&key) {
+            continue;
+        }
+        let location = key_location(content, key);
+        if let Some((line, _)) = location {
+            // The value runs until the next top-level line; block sequences may start at the
+            // key's indentation
+            let end = lines[line..]
+                .iter()
+                .position(|l| {
+                    !l.is_empty() && !l.starts_with([' ', '\t', '#']) && !l.starts_with('-')
+                })
+                .map_or(lines.len(), |offset| line + offset);
+            lines[line - 1..end].fill("");
+        }
+        warnings.push(ConfigurationWarning {
+            file: file.to_path_buf(),
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            message: unknown_key_message(key),
+        });
+    }
+
+    (lines.join("\n"), warnings)
+}
+
+fn unknown_key_message(key: &str) -> String {
+    match suggest_key(key) {
+        Some(suggestion) => format!("unknown key `{key}`, did you mean `{suggestion}`?"),
+        None => format!(
+            "unknown key `{key}`. Known keys: {}",
+            OPTION_KEYS.join(", ")
+        ),
+    }
+}
+
+/// The known option key closest to `key`, if it is close enough to be a typo
+fn suggest_key(key: &str) -> Option<&'static str> {
+    closest_match(key, OPTION_KEYS.iter().copied())
+}
+
+/// 1-based line and column of a top-level `key` in the options file
+fn key_location(content: &str, key: &str) -> Option<(usize, usize)> {
+    content
+        .lines()
+        .position(|line| {
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|index| (index + 1, 1))
+}
+
+/// Whether `path` stays inside the configuration repository
+fn is_within_repo(path: &Path) -> bool {
+    path.is_relative()
+        && !path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+}
+
+fn outside_repo_message(key: &str, path: &Path) -> String {
+    format!(
+        "`{key}` points outside the configuration repository: '{}'. Using it as given",
+        path.display()
+    )
+}
+
+/// Check a local checklist would render as one: at least one task item, and every code fence
+/// closed so it does not swallow the rest of the issue body
+fn validate_inline_checklist(content: &str) -> Result<(), InlineChecklistProblem> {
+    let mut open_fence: Option<(usize, &str)> = None;
+    let mut task_items = 0;
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let fence = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+        match (open_fence, fence) {
+            (None, Some(marker)) => open_fence = Some((i + 1, marker)),
+            (Some((_, open)), Some(marker)) if marker == open => open_fence = None,
+            (None, None) if is_task_item(trimmed) => task_items += 1,
+            _ => {}
+        }
+    }
+
+    if let Some((line, _)) = open_fence {
+        return Err(InlineChecklistProblem::UnclosedCodeFence(line));
+    }
+    if task_items == 0 {
+        return Err(InlineChecklistProblem::NoTaskItems);
+    }
+    Ok(())
+}
+
+fn is_task_item(line: &str) -> bool {
+    ["- [", "* [", "+ ["].into_iter().any(|bullet| {
+        line.strip_prefix(bullet)
+            .is_some_and(|rest| rest.starts_with([' ', 'x', 'X']) && rest[1..].starts_with(']'))
+    })
+}
+
 fn extract_title_from_filename(path: &Path) -> Result<String, ConfigurationError> {
     let stem = path
         .file_stem()
@@ -587,6 +1077,12 @@ pub fn configuration_status(
         )
     };
 
+    let issue_template_note = match configuration.issue_template() {
+        Ok(Some(_)) => format!("\n✅ Issue template found at {ISSUE_TEMPLATE_FILE}"),
+        Ok(None) => String::new(),
+        Err(e) => format!("\n⚠️ {e}"),
+    };
+
     let checklist_note = if let Some(note) = &configuration.options.prepended_checklist_note {
         let note = note
             .lines()
@@ -606,19 +1102,440 @@ pub fn configuration_status(
     checklist_vec.sort_by(|a, b| a.cmp(b));
     let checklists_str = checklist_vec.join("\n");
 
+    let warnings_str = if configuration.warnings.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n== Configuration Warnings ==\n{}\n",
+            configuration
+                .warnings
+                .iter()
+                .map(|w| format!("⚠️ {w}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+
     format!(
         "\
 == Directory Information ==
 📁 directory: {}{git_str}
-{checklist_sum}{logo_note}
+{checklist_sum}{logo_note}{issue_template_note}
         
 == {checklist_name} Summary =={checklist_note}
 {checklists_str}
-",
+{warnings_str}",
         configuration.path.display()
     )
 }
 
+/// How serious a problem found by `ghqc configuration validate` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    /// The configuration works, but probably not as intended
+    Warning,
+    /// The configuration does not work as written
+    Error,
+}
+
+/// A problem found in a file of the configuration repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationFinding {
+    pub severity: FindingSeverity,
+    /// 1-based line of the problem, when known
+    pub line: Option<usize>,
+    /// 1-based column of the problem, when known
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ValidationFinding {
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: FindingSeverity::Error,
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: FindingSeverity::Warning,
+            ..Self::error(message)
+        }
+    }
+
+    fn at(mut self, location: Option<(usize, usize)>) -> Self {
+        (self.line, self.column) = location.unzip();
+        self
+    }
+
+    fn from_warning(severity: FindingSeverity, warning: ConfigurationWarning) -> Self {
+        Self {
+            severity,
+            line: warning.line,
+            column: warning.column,
+            message: warning.message,
+        }
+    }
+}
+
+impl fmt::Display for ValidationFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = match self.severity {
+            FindingSeverity::Warning => "⚠️",
+            FindingSeverity::Error => "❌",
+        };
+        write!(f, "{icon} ")?;
+        if let Some(line) = self.line {
+            write!(f, "{line}")?;
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
+            write!(f, ": ")?;
+        }
+        // Keep multi-line messages, e.g. template parse errors with their source, indented
+        write!(f, "{}", self.message.trim_end().replace('\n', "\n     "))
+    }
+}
+
+/// The findings of one file of the configuration repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileValidation {
+    /// Path relative to the configuration repository
+    pub file: PathBuf,
+    /// No error-level findings
+    pub passed: bool,
+    pub findings: Vec<ValidationFinding>,
+}
+
+impl FileValidation {
+    fn new(file: impl Into<PathBuf>, findings: Vec<ValidationFinding>) -> Self {
+        Self {
+            file: file.into(),
+            passed: !findings
+                .iter()
+                .any(|f| f.severity == FindingSeverity::Error),
+            findings,
+        }
+    }
+}
+
+/// Report of `ghqc configuration validate`: the options, logo, checklists and templates of a
+/// configuration repository, file by file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigurationValidation {
+    pub path: PathBuf,
+    /// No file has an error-level finding
+    pub valid: bool,
+    pub files: Vec<FileValidation>,
+}
+
+impl ConfigurationValidation {
+    pub fn errors(&self) -> usize {
+        self.count(FindingSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(FindingSeverity::Warning)
+    }
+
+    fn count(&self, severity: FindingSeverity) -> usize {
+        self.files
+            .iter()
+            .flat_map(|file| &file.findings)
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+}
+
+impl fmt::Display for ConfigurationValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📁 {}", self.path.display())?;
+        for file in &self.files {
+            let status = if !file.passed {
+                "❌ FAIL"
+            } else if file.findings.is_empty() {
+                "✅ PASS"
+            } else {
+                "⚠️ PASS"
+            };
+            writeln!(f, "{status} {}", file.file.display())?;
+            for finding in &file.findings {
+                writeln!(f, "  {finding}")?;
+            }
+        }
+        write!(
+            f,
+            "\n{} error(s), {} warning(s)",
+            self.errors(),
+            self.warnings()
+        )
+    }
+}
+
+/// Image formats the record can embed as the logo, by file extension
+const LOGO_FORMATS: &[(&str, &str)] = &[
+    ("png", "PNG"),
+    ("jpg", "JPEG"),
+    ("jpeg", "JPEG"),
+    ("gif", "GIF"),
+    ("webp", "WebP"),
+    ("svg", "SVG"),
+];
+
+impl Configuration {
+    /// Check the configuration repository at `path` without loading it: the options file, the
+    /// logo, every checklist file and the custom templates
+    ///
+    /// Unlike [`Configuration::from_path`], which falls back to defaults and carries on,
+    /// problems that change what the configuration does are errors.
+    pub fn validate(path: impl AsRef<Path>) -> ConfigurationValidation {
+        let path = path.as_ref();
+        let mut files = Vec::new();
+
+        let options_path = path.join("options.yaml");
+        let options = if options_path.exists() {
+            let (options, findings) = ConfigurationOptions::validate_file(&options_path);
+            files.push(FileValidation::new("options.yaml", findings));
+            options
+        } else {
+            ConfigurationOptions::default()
+        };
+
+        let logo_path = path.join(&options.logo_path);
+        if logo_path.exists() {
+            files.push(FileValidation::new(
+                &options.logo_path,
+                validate_logo(&logo_path).into_iter().collect(),
+            ));
+        } else if options.logo_path != ConfigurationOptions::default().logo_path {
+            files.push(FileValidation::new(
+                &options.logo_path,
+                vec![ValidationFinding::error(
+                    "the logo configured by `logo_path` does not exist",
+                )],
+            ));
+        }
+
+        files.extend(Checklist::validate_directory(
+            path,
+            &options.checklist_directory,
+        ));
+
+        let record_path = path.join(&options.record_path);
+        if let Ok(template) = fs::read_to_string(&record_path) {
+            let findings = crate::record::check_template(&template)
+                .err()
+                .map(template_finding)
+                .into_iter()
+                .collect();
+            files.push(FileValidation::new(&options.record_path, findings));
+        }
+
+        let issue_template_path = path.join(ISSUE_TEMPLATE_FILE);
+        if issue_template_path.exists() {
+            let findings = match IssueTemplate::from_path(&issue_template_path) {
+                Ok(_) => Vec::new(),
+                Err(IssueTemplateError::Parse(_, message)) => vec![template_finding(message)],
+                Err(e) => vec![ValidationFinding::error(e.to_string())],
+            };
+            files.push(FileValidation::new(ISSUE_TEMPLATE_FILE, findings));
+        }
+
+        ConfigurationValidation {
+            path: path.to_path_buf(),
+            valid: files.iter().all(|file| file.passed),
+            files,
+        }
+    }
+}
+
+impl ConfigurationOptions {
+    /// Findings of the options file at `path`, with the options it resolves to
+    ///
+    /// Unknown keys and paths outside the repository are warnings. Files that do not parse, and
+    /// values that are replaced by their default, are errors.
+    fn validate_file(path: &Path) -> (Self, Vec<ValidationFinding>) {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                return (
+                    Self::default(),
+                    vec![ValidationFinding::error(e.to_string())],
+                );
+            }
+        };
+        let (known_content, warnings) = without_unknown_keys(path, &content);
+        let mut findings: Vec<ValidationFinding> = warnings
+            .into_iter()
+            .map(|w| ValidationFinding::from_warning(FindingSeverity::Warning, w))
+            .collect();
+
+        let mut options: Self = match serde_yaml::from_str(&known_content) {
+            Ok(options) => options,
+            Err(e) => {
+                findings.push(ValidationFinding::from_warning(
+                    FindingSeverity::Error,
+                    parse_warning(path, &e),
+                ));
+                return (Self::default(), findings);
+            }
+        };
+
+        let written = serde_yaml::to_value(&options).unwrap_or_default();
+        for (key, message) in options.validate() {
+            let resolved = serde_yaml::to_value(&options).unwrap_or_default();
+            let finding = if written.get(key) == resolved.get(key) {
+                ValidationFinding::warning(message)
+            } else {
+                ValidationFinding::error(message)
+            };
+            findings.push(finding.at(key_location(&content, key)));
+        }
+        (options, findings)
+    }
+}
+
+impl Checklist {
+    /// Findings of every checklist file in `directory` of the configuration repository at
+    /// `path`, in file name order
+    ///
+    /// Files that do not load and checklist names used by an earlier file are errors;
+    /// checklists without items are warnings.
+    fn validate_directory(path: &Path, directory: &Path) -> Vec<FileValidation> {
+        let Ok(read_dir) = fs::read_dir(path.join(directory)) else {
+            return vec![FileValidation::new(
+                directory,
+                vec![ValidationFinding::warning(
+                    "the checklist directory does not exist, so no checklists are available",
+                )],
+            )];
+        };
+        let mut checklist_files: Vec<PathBuf> = read_dir
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect();
+        checklist_files.sort();
+
+        let mut names: HashMap<String, PathBuf> = HashMap::new();
+        let mut files = Vec::new();
+        for checklist_path in checklist_files {
+            let file = directory.join(checklist_path.file_name().unwrap_or_default());
+            let findings = match Checklist::from_file(&checklist_path, None) {
+                Ok(None) => continue,
+                Ok(Some(checklist)) => {
+                    let mut findings = Vec::new();
+                    if checklist.items() == 0 {
+                        findings.push(ValidationFinding::warning(format!(
+                            "checklist '{}' has no items such as `- [ ] Check the model inputs`",
+                            checklist.name
+                        )));
+                    }
+                    if let Some(first) = names.get(&checklist.name) {
+                        findings.push(ValidationFinding::error(format!(
+                            "checklist name '{}' is already used by {}",
+                            checklist.name,
+                            first.display()
+                        )));
+                    } else {
+                        names.insert(checklist.name, file.clone());
+                    }
+                    findings
+                }
+                Err(ConfigurationError::YamlParser(e)) => vec![ValidationFinding::from_warning(
+                    FindingSeverity::Error,
+                    parse_warning(&file, &e),
+                )],
+                Err(e) => vec![ValidationFinding::error(e.to_string())],
+            };
+            files.push(FileValidation::new(file, findings));
+        }
+        files
+    }
+}
+
+/// Check the logo has an extension of an image format the record can embed, and holds an
+/// image of that format
+fn validate_logo(path: &Path) -> Option<ValidationFinding> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let Some((_, format)) = LOGO_FORMATS.iter().find(|(ext, _)| *ext == extension) else {
+        return Some(ValidationFinding::error(format!(
+            "unsupported logo image type '{extension}'. Use one of: {}",
+            LOGO_FORMATS
+                .iter()
+                .map(|(ext, _)| *ext)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    };
+
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => return Some(ValidationFinding::error(e.to_string())),
+    };
+    if image_format(&bytes) != Some(format) {
+        return Some(ValidationFinding::error(format!(
+            "the logo is not a valid {format} image"
+        )));
+    }
+    None
+}
+
+/// Sniff the image format of `bytes` from its signature
+fn image_format(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("PNG")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("JPEG")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("GIF")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("WebP")
+    } else if String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).contains("<svg") {
+        Some("SVG")
+    } else {
+        None
+    }
+}
+
+/// A template parse error, located from the ` --> line:column` marker Tera includes
+fn template_finding(message: String) -> ValidationFinding {
+    let location = message.split_once("--> ").and_then(|(_, rest)| {
+        let (line, rest) = rest.split_once(':')?;
+        let column: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Some((line.trim().parse().ok()?, column.parse().ok()?))
+    });
+    ValidationFinding::error(message).at(location)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InlineChecklistError {
+    #[error("Could not read checklist {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("Invalid checklist file name: {0:?}")]
+    InvalidFilename(PathBuf),
+    #[error("Checklist {path} is not valid: {problem}")]
+    Invalid {
+        path: PathBuf,
+        problem: InlineChecklistProblem,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InlineChecklistProblem {
+    #[error("it has no task items such as `- [ ] Check the model inputs`")]
+    NoTaskItems,
+    #[error("the code fence opened on line {0} is never closed")]
+    UnclosedCodeFence(usize),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigurationError {
     #[error("IO error: {0}")]
@@ -638,12 +1555,14 @@ pub enum ConfigurationError {
     },
     #[error("Git action failed: {0}")]
     GitAction(#[from] crate::git::GitCliError),
+    #[error("Invalid configuration options: {0}")]
+    InvalidOptions(ConfigurationWarning),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::MockEnvProvider;
+    use crate::utils::{MockEnvProvider, edit_distance};
     use mockall::Sequence;
     use tempfile::TempDir;
 
@@ -933,6 +1852,284 @@ mod tests {
         assert!(!options.include_collaborators);
     }
 
+    #[test]
+    fn test_require_write_access_defaults_to_false() {
+        let options = ConfigurationOptions::default();
+        assert!(!options.require_write_access);
+
+        let options: ConfigurationOptions =
+            serde_yaml::from_str("require_write_access: true").unwrap();
+        assert!(options.require_write_access);
+    }
+
+    #[test]
+    fn test_codeowners_options() {
+        let options = ConfigurationOptions::default();
+        assert_eq!(options.exclude_owners_from_qc, OwnerQCPolicy::Off);
+        assert!(!options.suggest_team_members);
+
+        let options: ConfigurationOptions =
+            serde_yaml::from_str("exclude_owners_from_qc: block\nsuggest_team_members: true")
+                .unwrap();
+        assert_eq!(options.exclude_owners_from_qc, OwnerQCPolicy::Block);
+        assert!(options.suggest_team_members);
+
+        assert!(
+            serde_yaml::from_str::<ConfigurationOptions>("exclude_owners_from_qc: sometimes")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_options_unknown_key_suggests_known_key() {
+        let path = PathBuf::from("src/tests/invalid_options/unknown_key.yaml");
+        let (options, warnings) = ConfigurationOptions::from_path(&path).unwrap();
+
+        // The other options are still used
+        assert!(!options.include_collaborators);
+        assert_eq!(options.checklist_display_name, "checklists");
+
+        let [warning] = warnings.as_slice() else {
+            panic!("expected one warning, got {warnings:?}");
+        };
+        assert_eq!(warning.file, path);
+        assert_eq!(warning.line, Some(1));
+        assert_eq!(
+            warning.message,
+            "unknown key `checklist_dispaly_name`, did you mean `checklist_display_name`?"
+        );
+        let display = warning.to_string();
+        assert!(display.starts_with("src/tests/invalid_options/unknown_key.yaml:1:"));
+        assert!(display.ends_with(
+            ": unknown key `checklist_dispaly_name`, did you mean `checklist_display_name`?"
+        ));
+    }
+
+    #[test]
+    fn test_options_unknown_keys_keep_locations_of_other_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("options.yaml");
+        std::fs::write(
+            &path,
+            "theme:\n  colors: [red]\nextra_files:\n- a.R\n- b.R\nlogo_pth: logo.svg\nchecklist_display_name: qc\ninclude_collaborators: sometimes\n",
+        )
+        .unwrap();
+
+        let Err(ConfigurationError::InvalidOptions(warning)) =
+            ConfigurationOptions::from_path(&path)
+        else {
+            panic!("expected invalid options");
+        };
+        assert_eq!(warning.line, Some(8));
+        assert!(warning.message.contains("include_collaborators"));
+
+        std::fs::write(
+            &path,
+            "theme:\n  colors: [red]\nextra_files:\n- a.R\n- b.R\nlogo_pth: logo.svg\ninclude_collaborators: false\n",
+        )
+        .unwrap();
+        let (options, warnings) = ConfigurationOptions::from_path(&path).unwrap();
+        assert!(!options.include_collaborators);
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|w| (w.line, w.message.split('`').nth(1)))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(1), Some("theme")),
+                (Some(3), Some("extra_files")),
+                (Some(6), Some("logo_pth"))
+            ]
+        );
+        assert!(warnings[0].message.ends_with(&OPTION_KEYS.join(", ")));
+        assert!(warnings[2].message.ends_with("did you mean `logo_path`?"));
+    }
+
+    #[test]
+    fn test_options_type_mismatch_reports_location() {
+        let path = PathBuf::from("src/tests/invalid_options/type_mismatch.yaml");
+        let Err(ConfigurationError::InvalidOptions(warning)) =
+            ConfigurationOptions::from_path(&path)
+        else {
+            panic!("expected invalid options");
+        };
+
+        assert_eq!(warning.line, Some(2));
+        assert!(warning.column.is_some());
+        assert!(warning.message.contains("include_collaborators"));
+        assert!(warning.message.contains("expected a boolean"));
+        assert!(!warning.message.contains(" at line "));
+    }
+
+    #[test]
+    fn test_options_invalid_values_reset_with_warnings() {
+        let path = PathBuf::from("src/tests/invalid_options/invalid_values.yaml");
+        let (options, warnings) = ConfigurationOptions::from_path(&path).unwrap();
+
+        assert_eq!(options.checklist_display_name, "checklists");
+        // Paths outside the configuration repository are kept
+        assert_eq!(options.logo_path, PathBuf::from("../shared/logo.png"));
+        assert_eq!(options.record_path, PathBuf::from("templates/record.typ"));
+        assert_eq!(
+            options.diff_max_characters,
+            DiffSettings::DEFAULT.max_diff_characters
+        );
+
+        let locations = warnings
+            .iter()
+            .map(|w| (w.line, w.message.split('`').nth(1)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locations,
+            vec![
+                (Some(1), Some("checklist_display_name")),
+                (Some(2), Some("logo_path")),
+                (Some(4), Some("diff_max_characters"))
+            ]
+        );
+        assert!(warnings[1].message.contains("'../shared/logo.png'"));
+    }
+
+    #[test]
+    fn test_configuration_surfaces_option_warnings() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::copy(
+            "src/tests/invalid_options/unknown_key.yaml",
+            temp_dir.path().join("options.yaml"),
+        )
+        .unwrap();
+
+        let configuration = Configuration::from_path(temp_dir.path());
+        assert_eq!(configuration.options.checklist_display_name, "checklists");
+        assert_eq!(configuration.warnings.len(), 1);
+
+        let status = configuration_status(&configuration, &None::<crate::GitInfo>);
+        assert!(status.contains("== Configuration Warnings =="));
+        assert!(status.contains("did you mean `checklist_display_name`?"));
+
+        // A missing options file is not a problem
+        let configuration = Configuration::from_path(TempDir::new().unwrap().path());
+        assert!(configuration.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_configured_cache_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(configured_cache_directory(temp_dir.path()), None);
+
+        let options_path = temp_dir.path().join("options.yaml");
+        std::fs::write(&options_path, "cache_directory: /scratch/ghqc-cache\n").unwrap();
+        assert_eq!(
+            configured_cache_directory(temp_dir.path()),
+            Some(PathBuf::from("/scratch/ghqc-cache"))
+        );
+
+        // Relative paths would depend on the directory ghqc runs from
+        std::fs::write(&options_path, "cache_directory: cache\n").unwrap();
+        assert_eq!(configured_cache_directory(temp_dir.path()), None);
+        let (_, warnings) = ConfigurationOptions::from_path(&options_path).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, Some(1));
+        assert!(warnings[0].message.contains("must be an absolute path"));
+    }
+
+    #[test]
+    fn test_inline_checklist_from_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("bespoke_model_review.md");
+        std::fs::write(
+            &path,
+            "## Model\n\n- [ ] Priors are justified\n* [x] Inputs match the SAP\n",
+        )
+        .unwrap();
+
+        let checklist = Checklist::from_inline_file(&path, Some("NOTE")).unwrap();
+        assert_eq!(checklist.name, "bespoke_model_review");
+        assert_eq!(
+            checklist.content,
+            "NOTE\n\n## Model\n\n- [ ] Priors are justified\n* [x] Inputs match the SAP\n"
+        );
+        assert_eq!(
+            checklist.inline_file.as_deref(),
+            Some("bespoke_model_review.md")
+        );
+    }
+
+    #[test]
+    fn test_inline_checklist_validation() {
+        assert_eq!(
+            validate_inline_checklist("## Model\n\nCheck everything carefully"),
+            Err(InlineChecklistProblem::NoTaskItems)
+        );
+        // Task items inside a code block are not part of the checklist
+        assert_eq!(
+            validate_inline_checklist("```md\n- [ ] example\n```\n"),
+            Err(InlineChecklistProblem::NoTaskItems)
+        );
+        assert_eq!(
+            validate_inline_checklist("- [ ] Run the model\n\n```r\nfit <- model()\n"),
+            Err(InlineChecklistProblem::UnclosedCodeFence(3))
+        );
+        assert_eq!(
+            validate_inline_checklist("- [ ] Run the model\n~~~\n```\n~~~\n"),
+            Ok(())
+        );
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("empty.md");
+        std::fs::write(&path, "No tasks here").unwrap();
+        let err = Checklist::from_inline_file(&path, None).unwrap_err();
+        assert!(matches!(
+            err,
+            InlineChecklistError::Invalid {
+                problem: InlineChecklistProblem::NoTaskItems,
+                ..
+            }
+        ));
+        assert!(matches!(
+            Checklist::from_inline_file(&temp_dir.path().join("missing.md"), None),
+            Err(InlineChecklistError::Read(..))
+        ));
+    }
+
+    #[test]
+    fn test_configured_usage_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(!configured_usage_stats(temp_dir.path()));
+
+        std::fs::write(temp_dir.path().join("options.yaml"), "usage_stats: true\n").unwrap();
+        assert!(configured_usage_stats(temp_dir.path()));
+    }
+
+    #[test]
+    fn test_suggest_key() {
+        assert_eq!(suggest_key("logo_pth"), Some("logo_path"));
+        assert_eq!(
+            suggest_key("include_colaborators"),
+            Some("include_collaborators")
+        );
+        assert_eq!(suggest_key("theme"), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_signing_keys_resolve_within_configuration_repo() {
+        let configuration = Configuration {
+            path: PathBuf::from("/config"),
+            options: serde_yaml::from_str("allowed_signers_path: keys/allowed_signers").unwrap(),
+            ..Default::default()
+        };
+
+        let keys = configuration.signing_keys();
+        assert_eq!(
+            keys.allowed_signers,
+            Some(PathBuf::from("/config/keys/allowed_signers"))
+        );
+        assert_eq!(keys.gpg_home, None);
+        assert!(keys.is_configured());
+        assert!(!Configuration::default().signing_keys().is_configured());
+    }
+
     #[test]
     fn test_missing_checklist_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -964,6 +2161,122 @@ Second Checklist:
         assert!(result2.is_err());
     }
 
+    #[test]
+    fn test_validate_valid_configuration() {
+        let validation = Configuration::validate("src/tests/default_configuration");
+        assert!(validation.valid, "{validation}");
+        assert_eq!(validation.files.len(), 6);
+        // The plain text fixtures have no task items
+        assert_eq!(validation.warnings(), 2);
+    }
+
+    #[test]
+    fn test_validate_reports_each_failure_mode() {
+        let validation = Configuration::validate("src/tests/invalid_configuration");
+        assert!(!validation.valid);
+
+        let file = |name: &str| {
+            validation
+                .files
+                .iter()
+                .find(|f| f.file == Path::new(name))
+                .unwrap_or_else(|| panic!("{name} was not validated"))
+        };
+
+        // An empty display name is an error; an unknown key is a warning
+        let options = file("options.yaml");
+        assert!(!options.passed);
+        assert_eq!(options.findings.len(), 2);
+        assert!(options.findings.iter().any(|f| {
+            f.severity == FindingSeverity::Warning
+                && f.line == Some(3)
+                && f.message.contains("checklist_colour")
+        }));
+        assert!(options.findings.iter().any(|f| {
+            f.severity == FindingSeverity::Error
+                && f.line == Some(1)
+                && f.message.contains("checklist_display_name")
+        }));
+
+        assert_eq!(
+            file("logo.png").findings,
+            vec![ValidationFinding::error(
+                "the logo is not a valid PNG image"
+            )]
+        );
+
+        let broken = &file("checklists/broken.yaml").findings;
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].severity, FindingSeverity::Error);
+        assert_eq!(broken[0].line, Some(4));
+
+        let empty = &file("checklists/empty.txt");
+        assert!(empty.passed);
+        assert_eq!(empty.findings[0].severity, FindingSeverity::Warning);
+
+        assert!(file("checklists/Simple Tasks.txt").passed);
+        assert_eq!(
+            file("checklists/simple.yaml").findings,
+            vec![ValidationFinding::error(
+                "checklist name 'Simple Tasks' is already used by checklists/Simple Tasks.txt"
+            )]
+        );
+
+        let record = &file("record.typ").findings;
+        assert_eq!(record.len(), 1);
+        assert_eq!(record[0].line, Some(2));
+
+        let issue_template = &file("issue_template.md").findings;
+        assert_eq!(issue_template.len(), 1);
+        assert_eq!(issue_template[0].severity, FindingSeverity::Error);
+
+        assert_eq!(validation.errors(), 6);
+        assert_eq!(validation.warnings(), 2);
+    }
+
+    #[test]
+    fn test_validate_logo_and_checklist_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("options.yaml"),
+            "logo_path: assets/logo.bmp\nchecklist_directory: lists\n",
+        )
+        .unwrap();
+
+        let validation = Configuration::validate(dir.path());
+        assert!(!validation.valid);
+        assert_eq!(validation.files[1].file, PathBuf::from("assets/logo.bmp"));
+        assert_eq!(
+            validation.files[1].findings,
+            vec![ValidationFinding::error(
+                "the logo configured by `logo_path` does not exist"
+            )]
+        );
+        assert_eq!(validation.files[2].file, PathBuf::from("lists"));
+        assert_eq!(
+            validation.files[2].findings[0].severity,
+            FindingSeverity::Warning
+        );
+
+        fs::create_dir(dir.path().join("assets")).unwrap();
+        fs::write(dir.path().join("assets/logo.bmp"), b"BM").unwrap();
+        let logo = validate_logo(&dir.path().join("assets/logo.bmp")).unwrap();
+        assert!(
+            logo.message
+                .starts_with("unsupported logo image type 'bmp'")
+        );
+
+        fs::write(
+            dir.path().join("logo.svg"),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\"/>",
+        )
+        .unwrap();
+        assert_eq!(validate_logo(&dir.path().join("logo.svg")), None);
+        assert_eq!(image_format(b"\x89PNG\r\n\x1a\n..."), Some("PNG"));
+        assert_eq!(image_format(b"GIF89a"), Some("GIF"));
+        assert_eq!(image_format(b"RIFF\0\0\0\0WEBPVP8 "), Some("WebP"));
+    }
+
     #[test]
     fn test_configuration_status() {
         // Create a mock GitInfo
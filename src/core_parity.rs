@@ -0,0 +1,430 @@
+//! Parity of `ghqctoolkit-core` with the implementations it replaced
+//!
+//! The `legacy` module keeps the checklist, comment marker and status logic as it was before it
+//! moved out of this crate, and the tests run both over the fixtures.
+
+use std::{collections::HashSet, path::Path, str::FromStr};
+
+use gix::ObjectId;
+
+use crate::{
+    QCStatus, analyze_issue_checklists,
+    issue::{CommitStatus, IssueCommit},
+    parse_branch_from_body,
+};
+
+mod legacy {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::LazyLock,
+    };
+
+    use regex::Regex;
+
+    use crate::{QCStatus, issue::CommitStatus, issue::IssueCommit};
+
+    static CHECKLIST_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?m)^\s*-\s*\[([xX\s])\]").unwrap());
+    static MARKDOWN_LINK_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\([^)]+\)").unwrap());
+    static HTML_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"<a\s+[^>]*href\s*=\s*["']([^"']+)["'][^>]*>([^<]*)</a>"#).unwrap()
+    });
+
+    /// (section, completed, total) of each section with checklist items
+    pub fn analyze_issue_checklists(body: &str) -> Vec<(String, usize, usize)> {
+        split_body_into_sections(body)
+            .into_iter()
+            .filter_map(|(name, content)| {
+                let (completed, total) = analyze_checklist_in_text(&content);
+                (total > 0).then_some((name, completed, total))
+            })
+            .collect()
+    }
+
+    fn split_body_into_sections(body: &str) -> Vec<(String, String)> {
+        let mut sections = Vec::new();
+        let mut current_section = String::new();
+        let mut current_header: Option<String> = None;
+        let mut found_first_level1_header = false;
+
+        for line in body.lines() {
+            if let Some(header_text) = extract_header_text(line) {
+                let is_level1_header =
+                    line.trim_start().starts_with("# ") && !line.trim_start().starts_with("## ");
+
+                if !found_first_level1_header && !is_level1_header {
+                    continue;
+                }
+
+                if !found_first_level1_header && is_level1_header {
+                    found_first_level1_header = true;
+                }
+
+                if found_first_level1_header {
+                    if let Some(ref header) = current_header {
+                        if !current_section.trim().is_empty() {
+                            sections.push((header.clone(), current_section.clone()));
+                        }
+                    }
+                }
+
+                current_header = Some(header_text);
+                current_section.clear();
+            } else if found_first_level1_header {
+                current_section.push_str(line);
+                current_section.push('\n');
+            }
+        }
+
+        if found_first_level1_header {
+            if let Some(header) = current_header {
+                if !current_section.trim().is_empty() {
+                    sections.push((header, current_section));
+                }
+            }
+        }
+
+        sections
+    }
+
+    fn extract_header_text(line: &str) -> Option<String> {
+        let trimmed = line.trim_start();
+
+        if !trimmed.starts_with('#') {
+            return None;
+        }
+
+        let hash_count = trimmed.chars().take_while(|&c| c == '#').count();
+
+        if hash_count < 1 || hash_count > 6 || trimmed.chars().nth(hash_count) != Some(' ') {
+            return None;
+        }
+
+        let header_text = trimmed
+            .chars()
+            .skip(hash_count + 1)
+            .collect::<String>()
+            .trim()
+            .to_string();
+
+        if header_text.is_empty() {
+            None
+        } else {
+            Some(header_text)
+        }
+    }
+
+    fn analyze_checklist_in_text(text: &str) -> (usize, usize) {
+        let mut total = 0;
+        let mut completed = 0;
+
+        for capture in CHECKLIST_REGEX.captures_iter(text) {
+            total += 1;
+            if let Some(checkbox) = capture.get(1) {
+                if checkbox.as_str().trim().eq_ignore_ascii_case("x") {
+                    completed += 1;
+                }
+            }
+        }
+
+        (completed, total)
+    }
+
+    fn parse_commit_from_pattern<'a>(body: &'a str, pattern: &str) -> Option<&'a str> {
+        let start = body.find(pattern)?;
+        let commit_start = start + pattern.len();
+
+        let remaining = &body[commit_start..];
+        remaining.lines().next()?.split_whitespace().next()
+    }
+
+    pub fn parse_commits_from_comments(bodies: &[String]) -> HashMap<&str, HashSet<CommitStatus>> {
+        let mut commit_statuses = HashMap::new();
+        let mut approved_commit = None;
+        let mut approval_comment_index = None;
+
+        for (index, body) in bodies.iter().enumerate() {
+            if let Some(commit) = parse_commit_from_pattern(body, "current commit: ") {
+                let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+                statuses.insert(CommitStatus::Notification);
+            }
+
+            if let Some(commit) = parse_commit_from_pattern(body, "approved qc commit: ") {
+                for statuses in commit_statuses.values_mut() {
+                    statuses.remove(&CommitStatus::Approved);
+                }
+
+                let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+                statuses.insert(CommitStatus::Approved);
+                approved_commit = Some(commit);
+                approval_comment_index = Some(index);
+            }
+
+            if body.contains("# QC Review") {
+                if let Some(commit) = parse_commit_from_pattern(body, "comparing commit: ") {
+                    let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
+                    statuses.insert(CommitStatus::Reviewed);
+                }
+            }
+
+            if body.contains("# QC Un-Approval") {
+                if let Some(approval_index) = approval_comment_index {
+                    if index > approval_index {
+                        if let Some(commit) = approved_commit {
+                            if let Some(statuses) = commit_statuses.get_mut(commit) {
+                                statuses.remove(&CommitStatus::Approved);
+                            }
+                        }
+                        approved_commit = None;
+                        approval_comment_index = None;
+                    }
+                }
+            }
+        }
+
+        commit_statuses
+    }
+
+    pub fn parse_branch_from_body(body: &str) -> Option<String> {
+        let pattern = "git branch: ";
+        let start = body.find(pattern)?;
+        let branch_start = start + pattern.len();
+        let remaining = &body[branch_start..];
+        let line = remaining.lines().next()?;
+
+        if let Some(md_captures) = MARKDOWN_LINK_REGEX.captures(line) {
+            if let Some(link_text) = md_captures.get(1) {
+                let branch_name = link_text.as_str().trim();
+                if !branch_name.is_empty() {
+                    return Some(branch_name.to_string());
+                }
+            }
+        }
+
+        if let Some(html_captures) = HTML_LINK_REGEX.captures(line) {
+            if let Some(link_text) = html_captures.get(2) {
+                let branch_name = link_text.as_str().trim();
+                if !branch_name.is_empty() {
+                    return Some(branch_name.to_string());
+                }
+            }
+        }
+
+        let branch_name = line.trim();
+        if !branch_name.is_empty() {
+            Some(branch_name.to_string())
+        } else {
+            None
+        }
+    }
+
+    pub fn determine_status(commits: &[IssueCommit], open: bool) -> QCStatus {
+        let approved = commits
+            .iter()
+            .find(|commit| commit.statuses.contains(&CommitStatus::Approved));
+
+        if let Some(approved) = approved {
+            let approved_index = commits
+                .iter()
+                .position(|commit| commit.hash == approved.hash)
+                .expect("Approved commit must be in commits");
+
+            let commits_after_approval = &commits[..approved_index];
+            let file_changes_after_approval = commits_after_approval
+                .iter()
+                .find(|commit| commit.file_changed);
+
+            if let Some(latest_file_change) = file_changes_after_approval {
+                QCStatus::ChangesAfterApproval(latest_file_change.hash)
+            } else {
+                QCStatus::Approved
+            }
+        } else if !open {
+            QCStatus::ApprovalRequired
+        } else {
+            let latest_file_entry = commits.iter().enumerate().find(|(_, c)| c.file_changed);
+
+            match latest_file_entry {
+                Some((file_idx, latest_fc)) => {
+                    let latest_status_entry = commits
+                        .iter()
+                        .enumerate()
+                        .find(|(_, c)| !c.statuses.is_empty());
+
+                    let covered = latest_status_entry
+                        .map(|(si, _)| si <= file_idx)
+                        .unwrap_or(false);
+
+                    if covered {
+                        let status_commit = latest_status_entry.unwrap().1;
+                        if status_commit.statuses.contains(&CommitStatus::Reviewed) {
+                            QCStatus::ChangeRequested
+                        } else {
+                            QCStatus::AwaitingReview
+                        }
+                    } else {
+                        QCStatus::ChangesToComment(latest_fc.hash)
+                    }
+                }
+                None => {
+                    let latest_status_entry = commits.iter().find(|c| !c.statuses.is_empty());
+                    match latest_status_entry {
+                        Some(sc) if sc.statuses.contains(&CommitStatus::Reviewed) => {
+                            QCStatus::ChangeRequested
+                        }
+                        Some(_) => QCStatus::AwaitingReview,
+                        None => QCStatus::InProgress,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bodies of the issues in the fixture directories
+fn fixture_issue_bodies() -> Vec<String> {
+    let mut bodies = vec![include_str!("tests/qc_status/complex_issue_checklist.md").to_string()];
+    for dir in ["src/tests/github_api/issues", "src/tests/issue_threads"] {
+        for value in read_json_fixtures(dir) {
+            if let Some(body) = value["body"].as_str() {
+                bodies.push(body.to_string());
+            }
+        }
+    }
+    bodies
+}
+
+/// Comment bodies of each comment fixture, in order
+fn fixture_comment_threads() -> Vec<Vec<String>> {
+    [
+        "src/tests/github_api/comments",
+        "src/tests/issue_threads/comments",
+    ]
+    .into_iter()
+    .flat_map(read_json_fixtures)
+    .map(|comments| {
+        comments
+            .as_array()
+            .expect("comment fixtures are arrays")
+            .iter()
+            .filter_map(|c| c["body"].as_str().map(str::to_string))
+            .collect()
+    })
+    .collect()
+}
+
+fn read_json_fixtures(dir: impl AsRef<Path>) -> Vec<serde_json::Value> {
+    let mut paths = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_checklist_parity_on_fixtures() {
+    let bodies = fixture_issue_bodies();
+    assert!(bodies.len() > 1);
+
+    for body in &bodies {
+        let summaries = analyze_issue_checklists(Some(body))
+            .into_iter()
+            .map(|(name, summary)| (name, summary.completed, summary.total))
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, legacy::analyze_issue_checklists(body));
+    }
+}
+
+#[test]
+fn test_branch_parity_on_fixtures() {
+    for body in fixture_issue_bodies() {
+        assert_eq!(
+            parse_branch_from_body(&body),
+            legacy::parse_branch_from_body(&body)
+        );
+    }
+}
+
+#[test]
+fn test_marker_parity_on_fixtures() {
+    let mut threads = fixture_comment_threads();
+    // Every prefix of every thread, so approvals that are later withdrawn are covered too
+    let prefixes = threads
+        .iter()
+        .flat_map(|t| (0..t.len()).map(|len| t[..len].to_vec()))
+        .collect::<Vec<_>>();
+    threads.extend(prefixes);
+
+    for bodies in &threads {
+        assert_eq!(
+            ghqctoolkit_core::parse_markers(bodies.iter().map(String::as_str)),
+            legacy::parse_commits_from_comments(bodies)
+        );
+    }
+}
+
+#[test]
+fn test_status_parity_on_all_short_threads() {
+    let all_statuses = [
+        CommitStatus::Initial,
+        CommitStatus::Notification,
+        CommitStatus::Approved,
+        CommitStatus::Reviewed,
+    ];
+    // Every (statuses, file_changed) combination a single commit can have
+    let commit_kinds = (0..32u8)
+        .map(|bits| {
+            let statuses = all_statuses
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| bits & (1 << i) != 0)
+                .map(|(_, s)| s.clone())
+                .collect::<HashSet<_>>();
+            (statuses, bits & 16 != 0)
+        })
+        .collect::<Vec<_>>();
+
+    let mut threads: Vec<Vec<usize>> = vec![vec![]];
+    for _ in 0..3 {
+        let longer = threads
+            .iter()
+            .filter(|t| t.len() == threads.last().unwrap().len())
+            .flat_map(|t| {
+                (0..commit_kinds.len()).map(move |kind| {
+                    let mut t = t.clone();
+                    t.push(kind);
+                    t
+                })
+            })
+            .collect::<Vec<_>>();
+        threads.extend(longer);
+    }
+
+    for thread in threads {
+        let commits = thread
+            .iter()
+            .enumerate()
+            .map(|(i, &kind)| IssueCommit {
+                hash: ObjectId::from_str(&format!("{:040x}", i + 1)).unwrap(),
+                message: String::new(),
+                statuses: commit_kinds[kind].0.clone(),
+                file_changed: commit_kinds[kind].1,
+            })
+            .collect::<Vec<_>>();
+
+        for open in [true, false] {
+            let status: QCStatus = ghqctoolkit_core::determine_status(&commits, open).into();
+            assert_eq!(
+                format!("{status:?}"),
+                format!("{:?}", legacy::determine_status(&commits, open)),
+                "thread {thread:?}, open: {open}"
+            );
+        }
+    }
+}
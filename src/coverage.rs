@@ -0,0 +1,300 @@
+//! QC coverage: how many of the repository's tracked files have been through QC
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    QCStatus, SnapshotThread,
+    git::{GitFileOps, GitFileOpsError},
+    utils::normalize_repo_path,
+};
+
+/// A file with a QC issue, joined against the tracked files
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoveredFile {
+    pub file: PathBuf,
+    /// The file's QC has a standing approval
+    pub approved: bool,
+}
+
+impl CoveredFile {
+    /// Files QCed by the threads, leaving out obsolete issues so they count as uncovered
+    pub fn from_threads(threads: &[SnapshotThread<'_>]) -> Vec<Self> {
+        threads
+            .iter()
+            .filter(|t| !matches!(t.status, QCStatus::Obsoleted(_)))
+            .map(|t| Self {
+                file: t.thread.file.clone(),
+                approved: matches!(
+                    t.status,
+                    QCStatus::Approved | QCStatus::ChangesAfterApproval(_)
+                ),
+            })
+            .collect()
+    }
+}
+
+/// QC coverage of the tracked files in scope
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QCCoverage {
+    /// Directories counted, every tracked file when empty
+    pub scope: Vec<PathBuf>,
+    pub files_in_scope: usize,
+    /// Files with an approved QC issue
+    pub approved: usize,
+    /// Files with QC issues, none of them approved
+    pub in_progress: usize,
+    /// Files without any QC issue
+    pub uncovered: Vec<PathBuf>,
+}
+
+impl QCCoverage {
+    /// Join the files in scope against the files with QC issues
+    ///
+    /// A file with several issues (e.g. one per milestone) counts as approved when any of them
+    /// is approved. Issues for files outside of `files` are ignored.
+    pub fn new(files: &[PathBuf], scope: &[PathBuf], covered: &[CoveredFile]) -> Self {
+        let mut approval_by_file: HashMap<PathBuf, bool> = HashMap::new();
+        for covered_file in covered {
+            let approved = approval_by_file
+                .entry(normalize_repo_path(&covered_file.file))
+                .or_default();
+            *approved |= covered_file.approved;
+        }
+
+        let mut coverage = Self {
+            scope: scope.iter().map(|p| normalize_repo_path(p)).collect(),
+            files_in_scope: files.len(),
+            ..Default::default()
+        };
+        for file in files {
+            match approval_by_file.get(&normalize_repo_path(file)) {
+                Some(true) => coverage.approved += 1,
+                Some(false) => coverage.in_progress += 1,
+                None => coverage.uncovered.push(file.clone()),
+            }
+        }
+
+        coverage
+    }
+
+    /// Percentage of the files in scope with approved QC
+    pub fn percentage(&self) -> f64 {
+        if self.files_in_scope == 0 {
+            return 0.0;
+        }
+        self.approved as f64 / self.files_in_scope as f64 * 100.0
+    }
+
+    /// The scope as shown to users, e.g. `scripts/, models/`
+    pub fn scope_display(&self) -> String {
+        if self.scope.is_empty() {
+            "all tracked files".to_string()
+        } else {
+            self.scope
+                .iter()
+                .map(|p| format!("{}/", p.display()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+}
+
+impl fmt::Display for QCCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "QC coverage of {}", self.scope_display())?;
+        writeln!(f, "- Files in scope:   {}", self.files_in_scope)?;
+        writeln!(
+            f,
+            "- Approved QC:      {} ({:.1}%)",
+            self.approved,
+            self.percentage()
+        )?;
+        writeln!(f, "- QC in progress:   {}", self.in_progress)?;
+        write!(f, "- Without an issue: {}", self.uncovered.len())?;
+        for file in &self.uncovered {
+            write!(f, "\n  - {}", file.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracked files under the `scope` directories, normalized, sorted and without duplicates
+///
+/// Every file is in scope when `scope` is empty.
+pub fn files_in_scope(tracked: &[PathBuf], scope: &[PathBuf]) -> Vec<PathBuf> {
+    let scope = scope
+        .iter()
+        .map(|p| normalize_repo_path(p))
+        .collect::<Vec<_>>();
+
+    let mut files = tracked
+        .iter()
+        .map(|f| normalize_repo_path(f))
+        .filter(|f| scope.is_empty() || scope.iter().any(|dir| f.starts_with(dir)))
+        .collect::<Vec<_>>();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Files tracked at HEAD under the `scope` directories, or in the whole repository
+pub fn tracked_files(
+    git_info: &impl GitFileOps,
+    scope: &[PathBuf],
+) -> Result<Vec<PathBuf>, GitFileOpsError> {
+    let roots = if scope.is_empty() {
+        vec![PathBuf::new()]
+    } else {
+        scope.iter().map(|p| normalize_repo_path(p)).collect()
+    };
+
+    let mut files = Vec::new();
+    for root in roots {
+        collect_tree_files(git_info, &root, &mut files)?;
+    }
+
+    Ok(files_in_scope(&files, scope))
+}
+
+fn collect_tree_files(
+    git_info: &impl GitFileOps,
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), GitFileOpsError> {
+    let tree_path = dir.to_string_lossy().replace('\\', "/");
+    for (name, is_dir) in git_info.list_tree_entries(&tree_path)? {
+        let path = dir.join(name);
+        if is_dir {
+            collect_tree_files(git_info, &path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// QC coverage of the tracked files under `scope` by the issues of `threads`
+pub fn qc_coverage(
+    git_info: &impl GitFileOps,
+    scope: &[PathBuf],
+    threads: &[SnapshotThread<'_>],
+) -> Result<QCCoverage, GitFileOpsError> {
+    let files = tracked_files(git_info, scope)?;
+    Ok(QCCoverage::new(
+        &files,
+        scope,
+        &CoveredFile::from_threads(threads),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::MockGitFileOps;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    fn covered(file: &str, approved: bool) -> CoveredFile {
+        CoveredFile {
+            file: PathBuf::from(file),
+            approved,
+        }
+    }
+
+    #[test]
+    fn test_files_in_scope() {
+        let tracked = paths(&[
+            "README.md",
+            "scripts/model.R",
+            "scripts/sub/plots.R",
+            "models/fit.stan",
+            "scripts_old/model.R",
+        ]);
+
+        assert_eq!(
+            files_in_scope(&tracked, &paths(&["./scripts/", "models"])),
+            paths(&["models/fit.stan", "scripts/model.R", "scripts/sub/plots.R"])
+        );
+        assert_eq!(files_in_scope(&tracked, &[]).len(), 5);
+        // Overlapping scopes count each file once
+        assert_eq!(
+            files_in_scope(&tracked, &paths(&["scripts", "scripts/sub"])),
+            paths(&["scripts/model.R", "scripts/sub/plots.R"])
+        );
+    }
+
+    #[test]
+    fn test_coverage_joins_files_and_issues() {
+        let files = paths(&[
+            "scripts/approved.R",
+            "scripts/in_progress.R",
+            "scripts/approved_later.R",
+            "scripts/uncovered.R",
+        ]);
+        let issues = vec![
+            covered("scripts/approved.R", true),
+            covered("./scripts/in_progress.R", false),
+            // One issue per milestone: approved in the second
+            covered("scripts/approved_later.R", false),
+            covered("scripts/approved_later.R", true),
+            // Outside of the scope
+            covered("README.md", true),
+        ];
+
+        let coverage = QCCoverage::new(&files, &paths(&["scripts"]), &issues);
+
+        assert_eq!(coverage.files_in_scope, 4);
+        assert_eq!(coverage.approved, 2);
+        assert_eq!(coverage.in_progress, 1);
+        assert_eq!(coverage.uncovered, paths(&["scripts/uncovered.R"]));
+        assert_eq!(coverage.percentage(), 50.0);
+        insta::assert_snapshot!(coverage.to_string(), @r"
+        QC coverage of scripts/
+        - Files in scope:   4
+        - Approved QC:      2 (50.0%)
+        - QC in progress:   1
+        - Without an issue: 1
+          - scripts/uncovered.R
+        ");
+    }
+
+    #[test]
+    fn test_coverage_without_files() {
+        let coverage = QCCoverage::new(&[], &[], &[covered("scripts/model.R", true)]);
+        assert_eq!(coverage.files_in_scope, 0);
+        assert_eq!(coverage.percentage(), 0.0);
+        assert_eq!(coverage.scope_display(), "all tracked files");
+    }
+
+    #[test]
+    fn test_tracked_files_walks_scope_directories() {
+        let mut git_info = MockGitFileOps::new();
+        git_info
+            .expect_list_tree_entries()
+            .returning(|path| match path {
+                "scripts" => Ok(vec![
+                    ("sub".to_string(), true),
+                    ("model.R".to_string(), false),
+                ]),
+                "scripts/sub" => Ok(vec![("plots.R".to_string(), false)]),
+                other => Err(GitFileOpsError::DirectoryNotFound(other.to_string())),
+            });
+
+        assert_eq!(
+            tracked_files(&git_info, &paths(&["scripts/"])).unwrap(),
+            paths(&["scripts/model.R", "scripts/sub/plots.R"])
+        );
+        assert!(matches!(
+            tracked_files(&git_info, &paths(&["models"])),
+            Err(GitFileOpsError::DirectoryNotFound(_))
+        ));
+    }
+}
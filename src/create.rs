@@ -8,15 +8,21 @@ use std::{
 use gix::ObjectId;
 
 use crate::{
+    DiskCache,
+    cache::get_user_permissions,
     configuration::Checklist,
+    data_summary::data_summary_at_commit,
+    environment::{EnvironmentFile, environment_files, environment_section},
     git::{
         GitAuthor, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError,
-        GitHubReader, GitHubWriter, GitRepository, GitRepositoryError,
+        GitHubReader, GitHubWriter, GitRepository, GitRepositoryError, Permission,
     },
     issue::IssueThread,
+    issue_template::{IssueTemplate, IssueTemplateError},
     relevant_files::{
         PreviousQCDiffComment, RelevantFile, RelevantFileClass, relevant_files_section,
     },
+    utils::{normalize_repo_path, repo_path_string},
 };
 
 #[derive(Debug, Clone)]
@@ -30,10 +36,47 @@ pub struct QCIssue {
     checklist: Checklist,
     pub(crate) assignees: Vec<String>,
     relevant_files: Vec<RelevantFile>,
+    /// Custom layout of the body from the configuration repo
+    template: Option<IssueTemplate>,
+    /// Environment descriptor files at the initial commit
+    environment: Vec<EnvironmentFile>,
 }
 
 impl QCIssue {
-    pub(crate) fn body(&self, git_info: &impl GitHelpers) -> String {
+    /// Body of the issue, in the custom template's layout when there is one
+    ///
+    /// A template which fails to render falls back to the built-in layout. [`Self::try_body`]
+    /// reports the failure instead, and is checked before the issue is posted.
+    pub(crate) fn body(&self, git_info: &(impl GitHelpers + GitFileOps)) -> String {
+        self.try_body(git_info).unwrap_or_else(|e| {
+            log::error!("{e}. Using the built-in issue layout");
+            self.builtin_body(git_info)
+        })
+    }
+
+    pub(crate) fn try_body(
+        &self,
+        git_info: &(impl GitHelpers + GitFileOps),
+    ) -> Result<String, IssueTemplateError> {
+        match &self.template {
+            Some(template) => {
+                template.render(&self.template_context(git_info), &self.commit, &self.branch)
+            }
+            None => Ok(self.builtin_body(git_info)),
+        }
+    }
+
+    fn builtin_body(&self, git_info: &(impl GitHelpers + GitFileOps)) -> String {
+        let mut body = vec![
+            self.metadata(git_info),
+            relevant_files_section(&self.relevant_files, git_info),
+        ];
+        body.extend(environment_section(&self.environment));
+        body.push(self.checklist.to_string());
+        body.join("\n\n")
+    }
+
+    fn metadata(&self, git_info: &(impl GitHelpers + GitFileOps)) -> String {
         let mut metadata = vec![
             "## Metadata".to_string(),
             format!("initial qc commit: {}", self.commit),
@@ -45,6 +88,17 @@ impl QCIssue {
             metadata.push(format!("collaborators: {}", self.collaborators.join(", ")));
         }
 
+        if let Some(inline_file) = &self.checklist.inline_file {
+            metadata.push(format!("inline checklist: {inline_file}"));
+        }
+
+        if let Some(summary) = ObjectId::from_str(&self.commit)
+            .ok()
+            .and_then(|commit| data_summary_at_commit(&self.title, &commit, git_info))
+        {
+            metadata.push(format!("data summary: {summary}"));
+        }
+
         // Use up to 7 characters for short commit hash, or full length if shorter
         let commit_short = &self.commit[..self.commit.len().min(7)];
         metadata.push(format!(
@@ -52,23 +106,128 @@ impl QCIssue {
             git_info.file_content_url(commit_short, &self.title)
         ));
 
-        let mut body = vec![metadata.join("\n* ")];
+        metadata.join("\n* ")
+    }
 
-        body.push(relevant_files_section(&self.relevant_files, git_info));
+    /// Variables available to a custom issue template
+    fn template_context(&self, git_info: &(impl GitHelpers + GitFileOps)) -> tera::Context {
+        #[derive(serde::Serialize)]
+        struct LinkedQC {
+            issue_number: u64,
+            url: String,
+            file: PathBuf,
+            description: Option<String>,
+        }
 
-        body.push(self.checklist.to_string());
+        // Previous QCs, or gating QCs when `gating`
+        let linked_qcs = |gating: bool| {
+            self.relevant_files
+                .iter()
+                .filter_map(|rf| {
+                    let (issue_number, description) = match &rf.class {
+                        RelevantFileClass::PreviousQC {
+                            issue_number,
+                            description,
+                            ..
+                        } if !gating => (*issue_number, description),
+                        RelevantFileClass::GatingQC {
+                            issue_number,
+                            description,
+                            ..
+                        } if gating => (*issue_number, description),
+                        _ => return None,
+                    };
+                    Some(LinkedQC {
+                        issue_number,
+                        url: git_info.issue_url(issue_number),
+                        file: rf.file_name.clone(),
+                        description: description.clone(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
 
-        body.join("\n\n")
+        let mut context = tera::Context::new();
+        context.insert("metadata", &self.metadata(git_info));
+        context.insert("file", &self.title);
+        context.insert("branch", &self.branch);
+        context.insert("initial_commit", &self.commit);
+        context.insert("author", &self.author);
+        context.insert("collaborators", &self.collaborators);
+        context.insert("checklist", &self.checklist.to_string());
+        context.insert("checklist_name", &self.checklist.name);
+        context.insert(
+            "checklist_items",
+            &self
+                .checklist
+                .content
+                .lines()
+                .filter_map(|line| line.trim_start().strip_prefix("- [ ]"))
+                .map(str::trim)
+                .collect::<Vec<_>>(),
+        );
+        context.insert(
+            "relevant_files",
+            &relevant_files_section(&self.relevant_files, git_info),
+        );
+        context.insert(
+            "environment",
+            &environment_section(&self.environment).unwrap_or_default(),
+        );
+        context.insert("previous_qc", &linked_qcs(false));
+        context.insert("gating_qc", &linked_qcs(true));
+        context
+    }
+
+    /// Lay out the body with `template` instead of the built-in layout
+    pub fn with_template(mut self, template: Option<IssueTemplate>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// List the descriptor files named `names` closest to the file, e.g. the `renv.lock` of its
+    /// project, at the initial commit
+    pub fn with_environment(
+        mut self,
+        names: &[String],
+        git_info: &(impl GitHelpers + GitFileOps + GitRepository),
+    ) -> Self {
+        self.environment = match ObjectId::from_str(&self.commit) {
+            Ok(commit) => {
+                environment_files(&self.title, &commit, names, Some(git_info.path()), git_info)
+            }
+            Err(_) => Vec::new(),
+        };
+        self
     }
 
+    /// The file as the issue title, with `/` separators on every platform
     pub(crate) fn title(&self) -> String {
-        self.title.to_string_lossy().to_string()
+        repo_path_string(&self.title)
     }
 
     pub fn branch(&self) -> &str {
         &self.branch
     }
 
+    /// Labels the issue is posted with
+    pub(crate) fn labels(&self) -> Vec<String> {
+        vec!["ghqc".to_string(), self.branch.clone()]
+    }
+
+    /// The title, body and labels `post_issue` would send, without posting anything
+    pub fn preview(
+        &self,
+        git_info: &(impl GitHelpers + GitFileOps),
+    ) -> Result<IssuePreview, IssueTemplateError> {
+        Ok(IssuePreview {
+            title: self.title(),
+            body: self.try_body(git_info)?,
+            labels: self.labels(),
+            assignees: self.assignees.clone(),
+        })
+    }
+
     /// Construct a QCIssue from pre-resolved fields (no git I/O).
     pub(crate) fn new_without_git(
         file: impl AsRef<Path>,
@@ -83,7 +242,7 @@ impl QCIssue {
     ) -> Self {
         Self {
             milestone_id,
-            title: file.as_ref().to_path_buf(),
+            title: normalize_repo_path(file.as_ref()),
             commit,
             branch,
             author,
@@ -91,6 +250,8 @@ impl QCIssue {
             checklist,
             assignees,
             relevant_files,
+            template: None,
+            environment: Vec::new(),
         }
     }
 
@@ -158,6 +319,8 @@ impl QCIssue {
         &self,
         git_info: &T,
     ) -> Result<CreateResult, QCIssueError> {
+        // A template dropping the markers would leave an issue whose commits cannot be found
+        let body = self.try_body(git_info)?;
         let issue = git_info.post_issue(self).await?;
         let issue_number = issue.number;
         let issue_id = issue.id.0;
@@ -167,6 +330,7 @@ impl QCIssue {
             issue_url,
             issue_number,
             issue_id,
+            body,
             parse_failed: false,
             successful_blocking: Vec::new(),
             blocking_errors: HashMap::new(),
@@ -223,7 +387,8 @@ impl QCIssue {
                     if let Ok(thread) =
                         IssueThread::from_issue(&prev_issue_obj, None, git_info).await
                     {
-                        let prev_commit = thread.latest_commit().hash;
+                        // Diff against the signed-off version of the previous QC
+                        let prev_commit = thread.latest_approved_or_activity().hash;
                         let diff_comment = PreviousQCDiffComment {
                             issue: issue.clone(),
                             prev_file,
@@ -246,6 +411,26 @@ impl QCIssue {
     }
 }
 
+/// An issue as it would be posted to GitHub, for `ghqc issue create --dry-run`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct IssuePreview {
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+}
+
+impl fmt::Display for IssuePreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Title: {}", self.title)?;
+        writeln!(f, "Labels: {}", self.labels.join(", "))?;
+        if !self.assignees.is_empty() {
+            writeln!(f, "Assignees: {}", self.assignees.join(", "))?;
+        }
+        write!(f, "\n{}", self.body)
+    }
+}
+
 pub fn format_git_author(author: &GitAuthor) -> String {
     format!("{} <{}>", author.name.trim(), author.email.trim())
 }
@@ -290,6 +475,7 @@ pub fn clean_git_authors(authors: &[GitAuthor]) -> Vec<String> {
     cleaned
 }
 
+#[cfg(any(feature = "cli", feature = "api"))]
 pub fn normalize_collaborator_entry(entry: &str) -> Option<String> {
     static COLLABORATOR_RE: std::sync::LazyLock<regex::Regex> =
         std::sync::LazyLock::new(|| regex::Regex::new(r"^(.*)<(.*)>$").unwrap());
@@ -305,6 +491,7 @@ pub fn normalize_collaborator_entry(entry: &str) -> Option<String> {
     Some(format!("{name} <{email}>"))
 }
 
+#[cfg(any(feature = "cli", feature = "api"))]
 pub fn normalize_collaborator_entries(entries: &[String]) -> Result<Vec<String>, String> {
     let mut normalized = Vec::new();
     let mut seen = HashSet::new();
@@ -346,6 +533,7 @@ pub fn resolve_issue_people(
     (author, collaborators)
 }
 
+#[cfg(any(feature = "cli", feature = "api"))]
 pub fn collaborator_override_for_policy(
     include_collaborators: bool,
     collaborator_override: Option<Vec<String>>,
@@ -357,15 +545,83 @@ pub fn collaborator_override_for_policy(
     }
 }
 
+/// An assignee whose repository permission is too low to complete a QC
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsufficientAssignee {
+    pub login: String,
+    pub permission: Permission,
+}
+
+impl fmt::Display for InsufficientAssignee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} has {} access and will not be able to approve (close) or unapprove (reopen) the issue",
+            self.login, self.permission
+        )
+    }
+}
+
+/// Check that each assignee can close and reopen issues.
+///
+/// Returns the assignees lacking access so callers can warn, or an error listing them when
+/// `require_write_access` is set.
+pub async fn check_assignee_permissions(
+    assignees: &[String],
+    require_write_access: bool,
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<Vec<InsufficientAssignee>, AssigneePermissionError> {
+    if assignees.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let insufficient = get_user_permissions(cache, git_info, assignees)
+        .await?
+        .into_iter()
+        .filter(|(_, permission)| !permission.can_close_issues())
+        .map(|(login, permission)| InsufficientAssignee { login, permission })
+        .collect::<Vec<_>>();
+
+    if require_write_access && !insufficient.is_empty() {
+        return Err(AssigneePermissionError::InsufficientAccess(insufficient));
+    }
+
+    Ok(insufficient)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AssigneePermissionError {
+    #[error(transparent)]
+    GitHubApiError(#[from] GitHubApiError),
+    #[error("Assignees lack the access required to complete QC:\n  - {}", .0.iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join("\n  - "))]
+    InsufficientAccess(Vec<InsufficientAssignee>),
+}
+
+#[derive(Debug, serde::Serialize)]
 pub struct CreateResult {
     pub issue_url: String,
     pub issue_number: u64,
     pub issue_id: u64,
+    /// Body the issue was posted with
+    #[serde(skip)]
+    pub body: String,
     pub parse_failed: bool,
     pub successful_blocking: Vec<u64>,
+    #[serde(serialize_with = "serialize_blocking_errors")]
     pub blocking_errors: HashMap<u64, GitHubApiError>,
 }
 
+fn serialize_blocking_errors<S: serde::Serializer>(
+    errors: &HashMap<u64, GitHubApiError>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(errors.iter().map(|(number, e)| (number, e.to_string())))
+}
+
 impl fmt::Display for CreateResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.parse_failed {
@@ -446,7 +702,7 @@ pub enum RelevantFileEntry {
 }
 
 /// Type of QC relationship
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QCRelationship {
     PreviousQC,
     GatingQC,
@@ -677,6 +933,7 @@ pub async fn batch_post_qc_entries(
      ),
     milestone_id: u64,
     current_user: Option<&str>,
+    template: Option<&IssueTemplate>,
 ) -> Result<Vec<CreateResult>, QCIssueError> {
     let commit = git_info.commit()?;
     let branch = git_info.branch()?;
@@ -755,6 +1012,7 @@ pub async fn batch_post_qc_entries(
                         file_name: file_path.to_path_buf(),
                         class: RelevantFileClass::File {
                             justification: justification.clone(),
+                            commit: None,
                         },
                     }),
                 }
@@ -773,7 +1031,7 @@ pub async fn batch_post_qc_entries(
         // Create QCIssue
         let qc_issue = QCIssue {
             milestone_id,
-            title: entry.title.clone(),
+            title: normalize_repo_path(&entry.title),
             commit: commit.clone(),
             branch: branch.clone(),
             author,
@@ -781,6 +1039,8 @@ pub async fn batch_post_qc_entries(
             checklist: entry.checklist.clone(),
             assignees: entry.assignees.clone(),
             relevant_files,
+            template: template.cloned(),
+            environment: Vec::new(),
         };
 
         // Post with blocking relationships
@@ -828,6 +1088,8 @@ pub enum QCIssueError {
     GitFileOpsError(#[from] GitFileOpsError),
     #[error(transparent)]
     GitHubApiError(#[from] GitHubApiError),
+    #[error(transparent)]
+    IssueTemplate(#[from] IssueTemplateError),
     #[error("Dependency resolution failed: {errors:?}")]
     DependencyResolution { errors: Vec<DependencyError> },
     #[error("Entry not found for file: {file:?}")]
@@ -843,7 +1105,7 @@ pub enum QCIssueError {
 mod tests {
     use super::*;
     use crate::{
-        GitCommitOps,
+        GitCommitOps, SignatureStatus, SigningKeys,
         git::{GitAuthor, GitFileOps, GitFileOpsError, GitHelpers, GitHubReader, GitHubWriter},
         relevant_files::RelevantFileClass,
     };
@@ -882,9 +1144,11 @@ mod tests {
                 },
                 RelevantFile {
                     file_name: PathBuf::from("file.R"),
-                    class: RelevantFileClass::File { justification: "A required justification".to_string() }
+                    class: RelevantFileClass::File { justification: "A required justification".to_string(), commit: None }
                 }
-            ]
+            ],
+            template: None,
+            environment: Vec::new(),
         }
     }
 
@@ -915,6 +1179,36 @@ mod tests {
         }
     }
 
+    impl GitFileOps for TestGitHelpers {
+        fn authors(&self, _file: &Path) -> Result<Vec<GitAuthor>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
+        fn file_bytes_at_commit(
+            &self,
+            file: &Path,
+            _commit: &ObjectId,
+        ) -> Result<Vec<u8>, GitFileOpsError> {
+            Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))
+        }
+
+        fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<crate::git::PathAttributes, GitFileOpsError> {
+            Ok(Default::default())
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
+    }
+
     #[test]
     fn test_issue_body_snapshot() {
         let issue = create_test_issue();
@@ -924,6 +1218,119 @@ mod tests {
         insta::assert_snapshot!(body);
     }
 
+    #[tokio::test]
+    async fn test_preview_matches_posted_issue() {
+        let issue = create_test_issue();
+        let git = MockGitInfo::new(
+            "https://github.com/owner/repo/issues/42",
+            HashSet::new(),
+            HashMap::new(),
+        );
+
+        let preview = issue.preview(&git).unwrap();
+        let posted = git.post_issue(&issue).await.unwrap();
+
+        assert_eq!(Some(preview.body.as_str()), posted.body.as_deref());
+        assert_eq!(preview.title, posted.title);
+        assert_eq!(preview.labels, ["ghqc", "feature/new-feature"]);
+        assert!(preview.to_string().starts_with(
+            "Title: src/example.rs\nLabels: ghqc, feature/new-feature\n\
+            Assignees: reviewer1, reviewer2\n\n## Metadata\n"
+        ));
+    }
+
+    #[test]
+    fn test_inline_checklist_body_matches_configured_checklist() {
+        use crate::configuration::Checklist;
+
+        let content = "- [ ] Code compiles without warnings\n- [ ] Tests pass";
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("Code Review Checklist.md");
+        std::fs::write(&path, content).unwrap();
+
+        let configured = create_test_issue();
+        let inline = QCIssue {
+            checklist: Checklist::from_inline_file(&path, Some("NOTE")).unwrap(),
+            ..create_test_issue()
+        };
+        let configured = QCIssue {
+            checklist: Checklist::new(
+                "Code Review Checklist".to_string(),
+                Some("NOTE"),
+                content.to_string(),
+            ),
+            ..configured
+        };
+
+        let configured_body = configured.body(&TestGitHelpers);
+        let inline_body = inline.body(&TestGitHelpers);
+        // Only the metadata records where the checklist came from
+        assert_eq!(
+            inline_body.replace("\n* inline checklist: Code Review Checklist.md", ""),
+            configured_body
+        );
+        assert!(
+            inline_body
+                .contains("* collaborators: Jane Smith <jane@example.com>\n* inline checklist: Code Review Checklist.md\n")
+        );
+        assert_eq!(
+            ghqctoolkit_core::parse_inline_checklist(&inline_body).as_deref(),
+            Some("Code Review Checklist.md")
+        );
+    }
+
+    fn test_template(content: &str) -> (tempfile::TempDir, IssueTemplate) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(crate::ISSUE_TEMPLATE_FILE);
+        std::fs::write(&path, content).unwrap();
+        let template = IssueTemplate::from_path(&path).unwrap();
+        (temp_dir, template)
+    }
+
+    #[test]
+    fn test_issue_body_from_template() {
+        let (_dir, template) = test_template(
+            "Study: 101\nQCer must sign off on {{ file }}\n\n{{ metadata }}\n\n\
+            {% for qc in previous_qc %}Previous: #{{ qc.issue_number }}\n{% endfor %}\n\
+            {{ checklist }}",
+        );
+        let issue = create_test_issue().with_template(Some(template));
+
+        let body = issue.try_body(&TestGitHelpers).unwrap();
+        assert!(
+            body.starts_with("Study: 101\nQCer must sign off on src/example.rs\n\n## Metadata\n")
+        );
+        assert!(body.contains("Previous: #1\n"));
+        assert!(body.contains("# Code Review Checklist"));
+        assert_eq!(
+            ghqctoolkit_core::parse_branch_from_body(&body).as_deref(),
+            Some("feature/new-feature")
+        );
+        assert_eq!(issue.body(&TestGitHelpers), body);
+    }
+
+    #[tokio::test]
+    async fn test_template_without_markers_is_not_posted() {
+        let (_dir, template) = test_template("Study: 101\n\n{{ checklist }}");
+        let issue = create_test_issue().with_template(Some(template));
+        let git = MockGitInfo::new(
+            "https://github.com/owner/repo/issues/42",
+            HashSet::new(),
+            HashMap::new(),
+        );
+
+        let result = issue.post_with_blocking(&git).await;
+        assert!(matches!(
+            result,
+            Err(QCIssueError::IssueTemplate(
+                IssueTemplateError::MissingMarker { .. }
+            ))
+        ));
+        assert!(issue.preview(&git).is_err());
+        // The built-in layout stands in wherever the body cannot fail
+        assert_eq!(issue.body(&git), issue.builtin_body(&git));
+    }
+
     #[test]
     fn test_clean_git_authors_filters_bad_entries() {
         let authors = vec![
@@ -958,6 +1365,7 @@ mod tests {
         );
     }
 
+    #[cfg(any(feature = "cli", feature = "api"))]
     #[test]
     fn test_normalize_collaborator_entries() {
         let collaborators = vec![
@@ -1045,6 +1453,7 @@ mod tests {
         );
     }
 
+    #[cfg(any(feature = "cli", feature = "api"))]
     #[test]
     fn test_collaborator_override_for_policy_preserves_override_when_enabled() {
         let override_value = Some(vec!["Jane Doe <jane@example.com>".to_string()]);
@@ -1054,6 +1463,7 @@ mod tests {
         );
     }
 
+    #[cfg(any(feature = "cli", feature = "api"))]
     #[test]
     fn test_collaborator_override_for_policy_forces_empty_when_disabled() {
         assert_eq!(
@@ -1121,6 +1531,8 @@ mod tests {
                     description: None,
                 },
             }],
+            template: None,
+            environment: Vec::new(),
         };
 
         let blocking = issue.blocking_issues();
@@ -1146,6 +1558,7 @@ mod tests {
                     file_name: PathBuf::from("file.R"),
                     class: RelevantFileClass::File {
                         justification: "No QC needed".to_string(),
+                        commit: None,
                     },
                 },
                 RelevantFile {
@@ -1156,6 +1569,8 @@ mod tests {
                     },
                 },
             ],
+            template: None,
+            environment: Vec::new(),
         };
 
         let blocking = issue.blocking_issues();
@@ -1168,6 +1583,7 @@ mod tests {
         fail_blocking_ids: Arc<HashSet<u64>>,
         block_calls: Arc<Mutex<Vec<(u64, u64)>>>,
         issues_by_number: Arc<Mutex<HashMap<u64, octocrab::models::issues::Issue>>>,
+        permissions: Arc<HashMap<String, Permission>>,
     }
 
     impl MockGitInfo {
@@ -1181,8 +1597,19 @@ mod tests {
                 fail_blocking_ids: Arc::new(fail_blocking_ids),
                 block_calls: Arc::new(Mutex::new(Vec::new())),
                 issues_by_number: Arc::new(Mutex::new(issues_by_number)),
+                permissions: Arc::new(HashMap::new()),
             }
         }
+
+        fn with_permissions(mut self, permissions: &[(&str, Permission)]) -> Self {
+            self.permissions = Arc::new(
+                permissions
+                    .iter()
+                    .map(|(login, permission)| (login.to_string(), *permission))
+                    .collect(),
+            );
+            self
+        }
     }
 
     impl GitHelpers for MockGitInfo {
@@ -1213,6 +1640,32 @@ mod tests {
             async move { Err(GitHubApiError::NoApi) }
         }
 
+        fn update_milestone_title(
+            &self,
+            _milestone_number: u64,
+            _new_title: &str,
+        ) -> impl std::future::Future<Output = Result<octocrab::models::Milestone, GitHubApiError>> + Send
+        {
+            async move { Err(GitHubApiError::NoApi) }
+        }
+
+        fn update_milestone(
+            &self,
+            _milestone_number: u64,
+            _update: &crate::MilestoneUpdate,
+        ) -> impl std::future::Future<Output = Result<octocrab::models::Milestone, GitHubApiError>> + Send
+        {
+            async move { Err(GitHubApiError::NoApi) }
+        }
+
+        fn close_milestone(
+            &self,
+            _milestone_number: u64,
+        ) -> impl std::future::Future<Output = Result<octocrab::models::Milestone, GitHubApiError>> + Send
+        {
+            async move { Err(GitHubApiError::NoApi) }
+        }
+
         fn post_issue(
             &self,
             issue: &QCIssue,
@@ -1256,6 +1709,13 @@ mod tests {
             async move { Err(GitHubApiError::NoApi) }
         }
 
+        fn guard_issue(
+            &self,
+            _issue: &octocrab::models::issues::Issue,
+        ) -> Result<(), GitHubApiError> {
+            Err(GitHubApiError::NoApi)
+        }
+
         fn close_issue(
             &self,
             _issue_number: u64,
@@ -1278,6 +1738,30 @@ mod tests {
             async move { Err(GitHubApiError::NoApi) }
         }
 
+        async fn add_labels(
+            &self,
+            _issue_number: u64,
+            _labels: &[String],
+        ) -> Result<(), GitHubApiError> {
+            Err(GitHubApiError::NoApi)
+        }
+
+        async fn remove_label(
+            &self,
+            _issue_number: u64,
+            _label: &str,
+        ) -> Result<(), GitHubApiError> {
+            Err(GitHubApiError::NoApi)
+        }
+
+        async fn set_assignees(
+            &self,
+            _issue_number: u64,
+            _assignees: &[String],
+        ) -> Result<(), GitHubApiError> {
+            Err(GitHubApiError::NoApi)
+        }
+
         fn block_issue(
             &self,
             blocked_issue_number: u64,
@@ -1358,6 +1842,26 @@ mod tests {
             async move { Err(GitHubApiError::NoApi) }
         }
 
+        fn get_user_permission(
+            &self,
+            login: &str,
+        ) -> impl std::future::Future<Output = Result<Permission, GitHubApiError>> + Send {
+            let permission = self
+                .permissions
+                .get(login)
+                .copied()
+                .unwrap_or(Permission::None);
+            async move { Ok(permission) }
+        }
+
+        async fn get_team_members(
+            &self,
+            _org: &str,
+            _team_slug: &str,
+        ) -> Result<Vec<String>, GitHubApiError> {
+            Err(GitHubApiError::NoApi)
+        }
+
         fn get_labels(
             &self,
         ) -> impl std::future::Future<Output = Result<Vec<String>, GitHubApiError>> + Send {
@@ -1418,6 +1922,13 @@ mod tests {
             Ok(std::collections::HashSet::new())
         }
 
+        fn files_by_last_commit(
+            &self,
+            _branch: &Option<String>,
+        ) -> Result<Vec<crate::FileLastCommit>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
         fn get_branches_containing_commit(
             &self,
             _commit: &gix::ObjectId,
@@ -1431,6 +1942,39 @@ mod tests {
         ) -> Result<Option<String>, GitFileOpsError> {
             Ok(None)
         }
+
+        fn find_merge_commit(
+            &self,
+            _target_commit: &gix::ObjectId,
+        ) -> Result<Option<gix::ObjectId>, GitFileOpsError> {
+            Ok(None)
+        }
+
+        fn signature_status(
+            &self,
+            _commit: &ObjectId,
+            _keys: &SigningKeys,
+        ) -> Result<SignatureStatus, GitFileOpsError> {
+            Ok(SignatureStatus::Unsigned)
+        }
+
+        fn commit_time(
+            &self,
+            _commit: &ObjectId,
+        ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+            Ok(chrono::DateTime::UNIX_EPOCH)
+        }
+
+        fn commit_author(&self, _commit: &ObjectId) -> Result<crate::GitAuthor, GitFileOpsError> {
+            Ok(crate::GitAuthor {
+                name: "Test Author".to_string(),
+                email: "test@example.com".to_string(),
+            })
+        }
+
+        fn is_shallow(&self) -> bool {
+            false
+        }
     }
 
     impl GitFileOps for MockGitInfo {
@@ -1449,6 +1993,18 @@ mod tests {
         fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
             Ok(vec![])
         }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<crate::git::PathAttributes, GitFileOpsError> {
+            Ok(Default::default())
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
     }
 
     impl GitRepository for MockGitInfo {
@@ -1532,6 +2088,8 @@ mod tests {
                     },
                 },
             ],
+            template: None,
+            environment: Vec::new(),
         };
 
         let mut fail_ids = HashSet::new();
@@ -1560,6 +2118,17 @@ mod tests {
         assert_eq!(calls.len(), 2);
         assert!(calls.contains(&(42, issue_one.id.0)));
         assert!(calls.contains(&(42, issue_two.id.0)));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(json["issue_url"], "https://github.com/owner/repo/issues/42");
+        assert_eq!(json["issue_number"], 42);
+        assert_eq!(
+            json["successful_blocking"],
+            serde_json::json!([issue_one.number])
+        );
+        assert!(json["blocking_errors"][issue_two.number.to_string()].is_string());
+        assert!(json.get("body").is_none());
     }
 
     fn make_entry(title: &str, relevant_files: Vec<RelevantFileEntry>) -> QCEntry {
@@ -2040,4 +2609,73 @@ mod tests {
         assert!(gating_pos < main_pos);
         assert!(relevant_pos < main_pos);
     }
+
+    #[tokio::test]
+    async fn test_check_assignee_permissions_warns() {
+        let git_info = MockGitInfo::new("", HashSet::new(), HashMap::new()).with_permissions(&[
+            ("writer", Permission::Write),
+            ("triager", Permission::Triage),
+            ("reader", Permission::Read),
+        ]);
+        let assignees = vec![
+            "writer".to_string(),
+            "triager".to_string(),
+            "reader".to_string(),
+            "outsider".to_string(),
+        ];
+
+        let insufficient = check_assignee_permissions(&assignees, false, None, &git_info)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            insufficient,
+            vec![
+                InsufficientAssignee {
+                    login: "reader".to_string(),
+                    permission: Permission::Read,
+                },
+                InsufficientAssignee {
+                    login: "outsider".to_string(),
+                    permission: Permission::None,
+                },
+            ]
+        );
+        assert_eq!(
+            insufficient[0].to_string(),
+            "reader has read access and will not be able to approve (close) or unapprove (reopen) the issue"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_assignee_permissions_blocks_when_required() {
+        let git_info = MockGitInfo::new("", HashSet::new(), HashMap::new())
+            .with_permissions(&[("writer", Permission::Write), ("reader", Permission::Read)]);
+        let assignees = vec!["writer".to_string(), "reader".to_string()];
+
+        let err = check_assignee_permissions(&assignees, true, None, &git_info)
+            .await
+            .unwrap_err();
+
+        match err {
+            AssigneePermissionError::InsufficientAccess(insufficient) => {
+                assert_eq!(insufficient.len(), 1);
+                assert_eq!(insufficient[0].login, "reader");
+            }
+            e => panic!("Expected InsufficientAccess, got {e:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_assignee_permissions_allows_sufficient_access_when_required() {
+        let git_info = MockGitInfo::new("", HashSet::new(), HashMap::new())
+            .with_permissions(&[("writer", Permission::Write), ("admin", Permission::Admin)]);
+        let assignees = vec!["writer".to_string(), "admin".to_string()];
+
+        let insufficient = check_assignee_permissions(&assignees, true, None, &git_info)
+            .await
+            .unwrap();
+
+        assert!(insufficient.is_empty());
+    }
 }
@@ -0,0 +1,375 @@
+use std::{fmt, io::Cursor, path::Path};
+
+use calamine::{Reader, open_workbook_auto_from_rs};
+use gix::ObjectId;
+
+use crate::git::GitFileOps;
+use crate::utils::format_file_size;
+
+/// Tabular data formats that can be summarized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Csv,
+    Tsv,
+    Excel,
+    Parquet,
+}
+
+impl DataFormat {
+    /// Data format of `file`, based on its extension
+    pub fn from_path(file: &Path) -> Option<Self> {
+        let extension = file.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            "xlsx" | "xlsm" | "xlsb" | "xls" => Some(Self::Excel),
+            "parquet" => Some(Self::Parquet),
+            _ => None,
+        }
+    }
+}
+
+/// Number of data rows (excluding the header) and columns in a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableShape {
+    pub rows: usize,
+    pub columns: usize,
+}
+
+/// Size and, when the file could be parsed, shape of a data file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataSummary {
+    pub size: u64,
+    pub shape: Result<TableShape, DataSummaryError>,
+}
+
+impl fmt::Display for DataSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.shape {
+            Ok(shape) => write!(
+                f,
+                "{} rows × {} columns, {}",
+                format_count(shape.rows),
+                format_count(shape.columns),
+                format_file_size(self.size)
+            ),
+            Err(e) => write!(
+                f,
+                "{} (rows and columns unavailable: {e})",
+                format_file_size(self.size)
+            ),
+        }
+    }
+}
+
+/// Summarize the contents of a data file
+///
+/// Failing to parse the file is not an error: the summary still reports the size, along with
+/// why the rows and columns could not be counted.
+pub fn summarize(bytes: &[u8], format: DataFormat) -> DataSummary {
+    let shape = match format {
+        DataFormat::Csv => delimited_shape(bytes, b','),
+        DataFormat::Tsv => delimited_shape(bytes, b'\t'),
+        DataFormat::Excel => excel_shape(bytes),
+        DataFormat::Parquet => parquet_shape(bytes),
+    };
+
+    DataSummary {
+        size: bytes.len() as u64,
+        shape,
+    }
+}
+
+/// Summary of `file` at `commit`, if it is a recognized data file readable at that commit
+pub fn data_summary_at_commit(
+    file: &Path,
+    commit: &ObjectId,
+    git_info: &impl GitFileOps,
+) -> Option<DataSummary> {
+    let format = DataFormat::from_path(file)?;
+    match git_info.file_bytes_at_commit(file, commit) {
+        Ok(bytes) => Some(summarize(&bytes, format)),
+        Err(e) => {
+            log::debug!(
+                "Could not read {} at {commit} for a data summary: {e}",
+                file.display()
+            );
+            None
+        }
+    }
+}
+
+/// Describe how a data file changed between two commits, e.g.
+/// `rows: 12,430 → 12,418 (−12), columns: 8 → 8 (no change), size: 1.20 MB → 1.19 MB`
+///
+/// Rows and columns are only compared when both versions could be parsed.
+pub fn data_summary_change(previous: &DataSummary, current: &DataSummary) -> String {
+    let mut changes = Vec::new();
+
+    if let (Ok(previous_shape), Ok(current_shape)) = (&previous.shape, &current.shape) {
+        changes.push(format!(
+            "rows: {}",
+            count_change(previous_shape.rows, current_shape.rows)
+        ));
+        changes.push(format!(
+            "columns: {}",
+            count_change(previous_shape.columns, current_shape.columns)
+        ));
+    }
+
+    changes.push(format!(
+        "size: {} → {}",
+        format_file_size(previous.size),
+        format_file_size(current.size)
+    ));
+
+    changes.join(", ")
+}
+
+fn count_change(previous: usize, current: usize) -> String {
+    let delta = if current > previous {
+        format!("+{}", format_count(current - previous))
+    } else if current < previous {
+        format!("−{}", format_count(previous - current))
+    } else {
+        "no change".to_string()
+    };
+
+    format!(
+        "{} → {} ({delta})",
+        format_count(previous),
+        format_count(current)
+    )
+}
+
+/// Format a count with thousands separators, e.g. `12430` as `12,430`
+fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+fn delimited_shape(bytes: &[u8], delimiter: u8) -> Result<TableShape, DataSummaryError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(bytes);
+
+    let columns = reader
+        .headers()
+        .map_err(|e| DataSummaryError::Parse(e.to_string()))?
+        .len();
+
+    let mut rows = 0;
+    for record in reader.records() {
+        record.map_err(|e| DataSummaryError::Parse(e.to_string()))?;
+        rows += 1;
+    }
+
+    Ok(TableShape { rows, columns })
+}
+
+/// Shape of the first worksheet, treating its first row as the header
+fn excel_shape(bytes: &[u8]) -> Result<TableShape, DataSummaryError> {
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(bytes))
+        .map_err(|e| DataSummaryError::Parse(e.to_string()))?;
+
+    let sheet_name = workbook
+        .sheet_names()
+        .first()
+        .cloned()
+        .ok_or_else(|| DataSummaryError::Parse("workbook has no worksheets".to_string()))?;
+    let range = workbook
+        .worksheet_range(&sheet_name)
+        .map_err(|e| DataSummaryError::Parse(e.to_string()))?;
+
+    let (height, width) = range.get_size();
+    Ok(TableShape {
+        rows: height.saturating_sub(1),
+        columns: width,
+    })
+}
+
+#[cfg(feature = "parquet")]
+fn parquet_shape(bytes: &[u8]) -> Result<TableShape, DataSummaryError> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let reader = SerializedFileReader::new(bytes::Bytes::copy_from_slice(bytes))
+        .map_err(|e| DataSummaryError::Parse(e.to_string()))?;
+    let metadata = reader.metadata().file_metadata();
+
+    Ok(TableShape {
+        rows: metadata.num_rows().max(0) as usize,
+        columns: metadata.schema_descr().num_columns(),
+    })
+}
+
+#[cfg(not(feature = "parquet"))]
+fn parquet_shape(_bytes: &[u8]) -> Result<TableShape, DataSummaryError> {
+    Err(DataSummaryError::Unsupported("parquet"))
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DataSummaryError {
+    #[error("could not parse file: {0}")]
+    Parse(String),
+    #[error("{0} support is not enabled in this build")]
+    Unsupported(&'static str),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_format_from_path() {
+        assert_eq!(
+            DataFormat::from_path(Path::new("data/adsl.csv")),
+            Some(DataFormat::Csv)
+        );
+        assert_eq!(
+            DataFormat::from_path(Path::new("data/ADSL.TSV")),
+            Some(DataFormat::Tsv)
+        );
+        assert_eq!(
+            DataFormat::from_path(Path::new("data/adsl.xlsx")),
+            Some(DataFormat::Excel)
+        );
+        assert_eq!(
+            DataFormat::from_path(Path::new("data/adsl.parquet")),
+            Some(DataFormat::Parquet)
+        );
+        assert_eq!(DataFormat::from_path(Path::new("scripts/model.R")), None);
+        assert_eq!(DataFormat::from_path(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn test_summarize_csv() {
+        let summary = summarize(b"id,age,arm\n1,34,A\n2,51,B\n3,47,A\n", DataFormat::Csv);
+
+        assert_eq!(summary.size, 32);
+        assert_eq!(
+            summary.shape,
+            Ok(TableShape {
+                rows: 3,
+                columns: 3
+            })
+        );
+        assert_eq!(summary.to_string(), "3 rows × 3 columns, 32 B");
+    }
+
+    #[test]
+    fn test_summarize_tsv() {
+        let summary = summarize(b"id\tage\n1\t34\n2\t51\n", DataFormat::Tsv);
+
+        assert_eq!(
+            summary.shape,
+            Ok(TableShape {
+                rows: 2,
+                columns: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_summarize_malformed_csv_reports_size_only() {
+        let summary = summarize(b"id,age,arm\n1,34,A\n2,51\n", DataFormat::Csv);
+
+        assert_eq!(summary.size, 23);
+        assert!(matches!(summary.shape, Err(DataSummaryError::Parse(_))));
+        assert!(
+            summary
+                .to_string()
+                .starts_with("23 B (rows and columns unavailable: could not parse file:")
+        );
+    }
+
+    #[test]
+    fn test_summarize_excel() {
+        let bytes = std::fs::read("src/tests/data/summary.xlsx").unwrap();
+        let summary = summarize(&bytes, DataFormat::Excel);
+
+        assert_eq!(
+            summary.shape,
+            Ok(TableShape {
+                rows: 3,
+                columns: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_summarize_invalid_excel_reports_size_only() {
+        let summary = summarize(b"not a workbook", DataFormat::Excel);
+
+        assert_eq!(summary.size, 14);
+        assert!(matches!(summary.shape, Err(DataSummaryError::Parse(_))));
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    #[test]
+    fn test_summarize_parquet_without_feature() {
+        let summary = summarize(b"PAR1", DataFormat::Parquet);
+
+        assert_eq!(summary.shape, Err(DataSummaryError::Unsupported("parquet")));
+        assert_eq!(
+            summary.to_string(),
+            "4 B (rows and columns unavailable: parquet support is not enabled in this build)"
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_summarize_invalid_parquet_reports_size_only() {
+        let summary = summarize(b"PAR1", DataFormat::Parquet);
+
+        assert_eq!(summary.size, 4);
+        assert!(matches!(summary.shape, Err(DataSummaryError::Parse(_))));
+    }
+
+    #[test]
+    fn test_data_summary_change() {
+        let previous = DataSummary {
+            size: 1_258_291,
+            shape: Ok(TableShape {
+                rows: 12_430,
+                columns: 8,
+            }),
+        };
+        let current = DataSummary {
+            size: 1_257_000,
+            shape: Ok(TableShape {
+                rows: 12_418,
+                columns: 9,
+            }),
+        };
+
+        assert_eq!(
+            data_summary_change(&previous, &current),
+            "rows: 12,430 → 12,418 (−12), columns: 8 → 9 (+1), size: 1.20 MB → 1.20 MB"
+        );
+
+        let unparsed = DataSummary {
+            size: 512,
+            shape: Err(DataSummaryError::Parse("bad row".to_string())),
+        };
+        assert_eq!(
+            data_summary_change(&unparsed, &current),
+            "size: 512 B → 1.20 MB"
+        );
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1_000), "1,000");
+        assert_eq!(format_count(12_430), "12,430");
+        assert_eq!(format_count(1_234_567), "1,234,567");
+    }
+}
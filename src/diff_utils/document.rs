@@ -0,0 +1,580 @@
+//! Document-aware diffs for Quarto and R Markdown files
+//!
+//! Both versions are split into YAML frontmatter, prose and executable code chunks so that code
+//! changes can be reviewed separately from edits to the surrounding narrative.
+
+use std::{collections::HashMap, path::Path, sync::LazyLock};
+
+use regex::Regex;
+
+use super::diff;
+
+// Executable chunk header, e.g. ```{r setup, include=FALSE} or ```{python}
+static CHUNK_START_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(`{3,})\s*\{\s*([A-Za-z][\w.-]*)([^}]*)\}\s*$")
+        .expect("Invalid chunk start regex")
+});
+
+// Non-executable fenced block, whose contents are prose even if they look like a chunk header
+static FENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(`{3,}|~{3,})").expect("Invalid fence regex"));
+
+// Quarto chunk option label, e.g. `#| label: fig-clearance`
+static LABEL_OPTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^#\|\s*label:\s*["']?([^"'\s]+)["']?\s*$"#).expect("Invalid label option regex")
+});
+
+/// Minimum similarity for two chunks without a common label to be treated as the same chunk
+const CHUNK_SIMILARITY_THRESHOLD: f64 = 0.5;
+
+/// Check if a file is a Quarto or R Markdown document based on its extension
+pub(crate) fn is_literate_document(file: &Path) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "qmd" | "rmd"))
+}
+
+/// An executable code chunk
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CodeChunk {
+    pub language: String,
+    pub label: Option<String>,
+    /// 1-indexed position among the document's chunks
+    pub index: usize,
+    pub code: String,
+}
+
+impl CodeChunk {
+    fn title(&self) -> String {
+        match &self.label {
+            Some(label) => format!("`{label}` ({})", self.language),
+            None => format!("Chunk {} ({})", self.index, self.language),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Frontmatter(String),
+    Prose(String),
+    Chunk(CodeChunk),
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub(crate) enum SegmentError {
+    #[error("YAML frontmatter is not terminated")]
+    UnterminatedFrontmatter,
+    #[error("Code chunk starting on line {0} is not terminated")]
+    UnterminatedChunk(usize),
+}
+
+/// Split a Quarto/R Markdown document into frontmatter, prose blocks and code chunks
+pub(crate) fn segment(content: &str) -> Result<Vec<Segment>, SegmentError> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    if lines.first().is_some_and(|l| l.trim_end() == "---") {
+        let end = lines[1..]
+            .iter()
+            .position(|l| matches!(l.trim_end(), "---" | "..."))
+            .ok_or(SegmentError::UnterminatedFrontmatter)?
+            + 1;
+        segments.push(Segment::Frontmatter(lines[1..end].join("\n")));
+        i = end + 1;
+    }
+
+    let mut prose: Vec<&str> = Vec::new();
+    let mut open_fence: Option<&str> = None;
+    let mut chunk_count = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if let Some(fence) = open_fence {
+            if is_closing_fence(line, fence) {
+                open_fence = None;
+            }
+            prose.push(line);
+            i += 1;
+            continue;
+        }
+
+        if let Some(caps) = CHUNK_START_REGEX.captures(line) {
+            let fence = caps.get(1).map_or("```", |m| m.as_str());
+            let end = lines[i + 1..]
+                .iter()
+                .position(|l| is_closing_fence(l, fence))
+                .ok_or(SegmentError::UnterminatedChunk(i + 1))?
+                + i
+                + 1;
+
+            if !prose.is_empty() {
+                segments.push(Segment::Prose(prose.join("\n")));
+                prose.clear();
+            }
+
+            chunk_count += 1;
+            let body = &lines[i + 1..end];
+            segments.push(Segment::Chunk(CodeChunk {
+                language: caps[2].to_string(),
+                label: chunk_label(&caps[3], body),
+                index: chunk_count,
+                code: body.join("\n"),
+            }));
+            i = end + 1;
+            continue;
+        }
+
+        if let Some(caps) = FENCE_REGEX.captures(line) {
+            open_fence = caps.get(1).map(|m| m.as_str());
+        }
+        prose.push(line);
+        i += 1;
+    }
+
+    if !prose.is_empty() {
+        segments.push(Segment::Prose(prose.join("\n")));
+    }
+
+    Ok(segments)
+}
+
+fn is_closing_fence(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let Some(fence_char) = fence.chars().next() else {
+        return false;
+    };
+    trimmed.len() >= fence.len() && trimmed.chars().all(|c| c == fence_char)
+}
+
+/// Label of a chunk from its header (`{r label}` or `{r label="name"}`) or a `#| label:` option
+fn chunk_label(header_options: &str, body: &[&str]) -> Option<String> {
+    let first = header_options.split(',').next().unwrap_or_default().trim();
+    if !first.is_empty() && !first.contains('=') {
+        return Some(first.to_string());
+    }
+
+    let header_label = header_options.split(',').find_map(|option| {
+        let (key, value) = option.split_once('=')?;
+        (key.trim() == "label").then(|| value.trim().trim_matches(['"', '\'']).to_string())
+    });
+    if let Some(label) = header_label.filter(|l| !l.is_empty()) {
+        return Some(label);
+    }
+
+    body.iter()
+        .take_while(|line| line.trim_start().starts_with("#|"))
+        .find_map(|line| LABEL_OPTION_REGEX.captures(line.trim()))
+        .map(|caps| caps[1].to_string())
+}
+
+/// How a chunk in the previous version corresponds to one in the current version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChunkMatch {
+    Matched { old: usize, new: usize },
+    Added(usize),
+    Removed(usize),
+}
+
+/// Pair up the chunks of two document versions, tolerating reordering
+///
+/// Chunks are matched by label when both versions have it, and otherwise by the similarity of
+/// their code. Matches follow the order of the current version, with removed chunks last.
+pub(crate) fn match_chunks(old: &[CodeChunk], new: &[CodeChunk]) -> Vec<ChunkMatch> {
+    let mut new_to_old: Vec<Option<usize>> = vec![None; new.len()];
+    let mut old_matched = vec![false; old.len()];
+
+    for (o, old_chunk) in old.iter().enumerate() {
+        let Some(label) = &old_chunk.label else {
+            continue;
+        };
+        if let Some(n) = (0..new.len())
+            .find(|&n| new_to_old[n].is_none() && new[n].label.as_ref() == Some(label))
+        {
+            new_to_old[n] = Some(o);
+            old_matched[o] = true;
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (o, old_chunk) in old.iter().enumerate().filter(|(o, _)| !old_matched[*o]) {
+        for (n, new_chunk) in new
+            .iter()
+            .enumerate()
+            .filter(|(n, _)| new_to_old[*n].is_none())
+        {
+            if !old_chunk.language.eq_ignore_ascii_case(&new_chunk.language) {
+                continue;
+            }
+            let similarity = chunk_similarity(&old_chunk.code, &new_chunk.code);
+            if similarity >= CHUNK_SIMILARITY_THRESHOLD {
+                candidates.push((similarity, o, n));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+    for (_, o, n) in candidates {
+        if !old_matched[o] && new_to_old[n].is_none() {
+            new_to_old[n] = Some(o);
+            old_matched[o] = true;
+        }
+    }
+
+    new_to_old
+        .iter()
+        .enumerate()
+        .map(|(n, o)| match o {
+            Some(o) => ChunkMatch::Matched { old: *o, new: n },
+            None => ChunkMatch::Added(n),
+        })
+        .chain(
+            old_matched
+                .iter()
+                .enumerate()
+                .filter(|(_, matched)| !**matched)
+                .map(|(o, _)| ChunkMatch::Removed(o)),
+        )
+        .collect()
+}
+
+/// Similarity of two chunks' code from 0.0 to 1.0: the share of their trimmed, non-blank lines
+/// that appear in both
+fn chunk_similarity(a: &str, b: &str) -> f64 {
+    let a = line_signature(a);
+    let b = line_signature(b);
+    let total: usize = a.values().sum::<usize>() + b.values().sum::<usize>();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let common: usize = a
+        .iter()
+        .map(|(line, count)| (*count).min(b.get(line).copied().unwrap_or(0)))
+        .sum();
+    2.0 * common as f64 / total as f64
+}
+
+fn line_signature(code: &str) -> HashMap<&str, usize> {
+    let mut signature = HashMap::new();
+    for line in code.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        *signature.entry(line).or_insert(0) += 1;
+    }
+    signature
+}
+
+/// Generate a markdown diff of two Quarto/R Markdown versions, grouped into frontmatter, code
+/// chunk and prose changes
+///
+/// Returns `None` if either version cannot be segmented.
+pub(crate) fn diff_documents(old_content: &str, new_content: &str) -> Option<String> {
+    let old = segment(old_content)
+        .inspect_err(|e| log::debug!("Could not segment previous version: {e}"))
+        .ok()?;
+    let new = segment(new_content)
+        .inspect_err(|e| log::debug!("Could not segment current version: {e}"))
+        .ok()?;
+
+    Some(format_document_diff(&old, &new))
+}
+
+fn format_document_diff(old: &[Segment], new: &[Segment]) -> String {
+    let mut sections = Vec::new();
+
+    let old_frontmatter = frontmatter(old);
+    let new_frontmatter = frontmatter(new);
+    if old_frontmatter != new_frontmatter {
+        sections.push(format!(
+            "### Frontmatter changes\n{}",
+            diff(old_frontmatter, new_frontmatter)
+        ));
+    }
+
+    let old_chunks = chunks(old);
+    let new_chunks = chunks(new);
+    let chunk_changes = match_chunks(&old_chunks, &new_chunks)
+        .into_iter()
+        .filter_map(|m| format_chunk_change(m, &old_chunks, &new_chunks))
+        .collect::<Vec<_>>();
+    if !chunk_changes.is_empty() {
+        sections.push(format!(
+            "### Code chunk changes\n{}",
+            chunk_changes.join("\n\n")
+        ));
+    }
+
+    let old_prose = prose(old);
+    let new_prose = prose(new);
+    if old_prose.lines().ne(new_prose.lines()) {
+        sections.push(format!(
+            "### Prose changes\n{}",
+            diff(&old_prose, &new_prose)
+        ));
+    }
+
+    if sections.is_empty() {
+        return "\nNo difference between file versions.\n".to_string();
+    }
+
+    sections.join("\n\n")
+}
+
+fn frontmatter(segments: &[Segment]) -> &str {
+    segments
+        .iter()
+        .find_map(|s| match s {
+            Segment::Frontmatter(frontmatter) => Some(frontmatter.as_str()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn chunks(segments: &[Segment]) -> Vec<CodeChunk> {
+    segments
+        .iter()
+        .filter_map(|s| match s {
+            Segment::Chunk(chunk) => Some(chunk.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn prose(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .filter_map(|s| match s {
+            Segment::Prose(prose) => Some(prose.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_chunk_change(m: ChunkMatch, old: &[CodeChunk], new: &[CodeChunk]) -> Option<String> {
+    match m {
+        ChunkMatch::Matched { old: o, new: n } => {
+            let (old_chunk, new_chunk) = (&old[o], &new[n]);
+            let renamed = old_chunk.label != new_chunk.label;
+            let code_changed = old_chunk.code.lines().ne(new_chunk.code.lines());
+            if !renamed && !code_changed {
+                return None;
+            }
+
+            let mut title = new_chunk.title();
+            if renamed {
+                title.push_str(&format!(" (renamed from {})", old_chunk.title()));
+            }
+            let body = if code_changed {
+                diff(&old_chunk.code, &new_chunk.code)
+            } else {
+                "No code changes.".to_string()
+            };
+            Some(format!("#### {title}\n{body}"))
+        }
+        ChunkMatch::Added(n) => Some(format!(
+            "#### {} (added)\n{}",
+            new[n].title(),
+            whole_chunk_diff(&new[n].code, '+')
+        )),
+        ChunkMatch::Removed(o) => Some(format!(
+            "#### {} (removed)\n{}",
+            old[o].title(),
+            whole_chunk_diff(&old[o].code, '-')
+        )),
+    }
+}
+
+/// Diff block listing every line of an added or removed chunk
+fn whole_chunk_diff(code: &str, marker: char) -> String {
+    let mut result = vec!["```diff".to_string()];
+    result.extend(
+        code.lines()
+            .enumerate()
+            .map(|(i, line)| format!("{marker} {} {line}", i + 1)),
+    );
+    result.push("```".to_string());
+    result.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"---
+title: "PK Analysis"
+format: html
+---
+
+# Introduction
+
+```{r setup, include=FALSE}
+library(dplyr)
+```
+
+Some prose with an example:
+
+```r
+```{r not-a-chunk}
+```
+
+```{python}
+#| label: load-data
+import pandas as pd
+df = pd.read_csv("pk.csv")
+```
+
+```{r}
+summary(df)
+```
+"#;
+
+    fn chunk(label: Option<&str>, index: usize, code: &str) -> CodeChunk {
+        CodeChunk {
+            language: "r".to_string(),
+            label: label.map(String::from),
+            index,
+            code: code.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_literate_document() {
+        assert!(is_literate_document(Path::new("report.qmd")));
+        assert!(is_literate_document(Path::new("analysis/report.Rmd")));
+        assert!(!is_literate_document(Path::new("report.md")));
+        assert!(!is_literate_document(Path::new("script.R")));
+    }
+
+    #[test]
+    fn test_segment_document() {
+        let segments = segment(DOCUMENT).unwrap();
+
+        assert_eq!(
+            segments[0],
+            Segment::Frontmatter("title: \"PK Analysis\"\nformat: html".to_string())
+        );
+
+        let chunks = chunks(&segments);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].label.as_deref(), Some("setup"));
+        assert_eq!(chunks[0].code, "library(dplyr)");
+        assert_eq!(chunks[1].language, "python");
+        assert_eq!(chunks[1].label.as_deref(), Some("load-data"));
+        assert_eq!(chunks[2].label, None);
+        assert_eq!(chunks[2].index, 3);
+
+        // The example inside the plain fenced block stays in the prose
+        assert!(prose(&segments).contains("```{r not-a-chunk}"));
+    }
+
+    #[test]
+    fn test_chunk_label_from_header_option() {
+        assert_eq!(
+            chunk_label(r#", label="fit-model", echo=FALSE"#, &[]),
+            Some("fit-model".to_string())
+        );
+        assert_eq!(chunk_label(", echo=FALSE", &[]), None);
+    }
+
+    #[test]
+    fn test_segment_unterminated() {
+        assert_eq!(
+            segment("---\ntitle: x\n\nbody"),
+            Err(SegmentError::UnterminatedFrontmatter)
+        );
+        assert_eq!(
+            segment("intro\n\n```{r}\nx <- 1\n"),
+            Err(SegmentError::UnterminatedChunk(3))
+        );
+        assert_eq!(diff_documents("```{r}\nx <- 1\n", "x"), None);
+    }
+
+    #[test]
+    fn test_match_chunks_by_label_when_reordered() {
+        let old = vec![
+            chunk(Some("load"), 1, "x <- read()"),
+            chunk(Some("fit"), 2, "fit <- lm(y ~ x)"),
+        ];
+        let new = vec![
+            chunk(Some("fit"), 1, "fit <- glm(y ~ x)"),
+            chunk(Some("load"), 2, "x <- read()"),
+        ];
+
+        assert_eq!(
+            match_chunks(&old, &new),
+            vec![
+                ChunkMatch::Matched { old: 1, new: 0 },
+                ChunkMatch::Matched { old: 0, new: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_renamed_chunk_by_similarity() {
+        let old = vec![chunk(
+            Some("plot"),
+            1,
+            "ggplot(df, aes(x, y)) +\n  geom_point() +\n  theme_bw()",
+        )];
+        let new = vec![chunk(
+            Some("fig-scatter"),
+            1,
+            "ggplot(df, aes(x, y)) +\n  geom_point() +\n  theme_minimal()",
+        )];
+
+        assert_eq!(
+            match_chunks(&old, &new),
+            vec![ChunkMatch::Matched { old: 0, new: 0 }]
+        );
+
+        let diff = format_document_diff(
+            &[Segment::Chunk(old[0].clone())],
+            &[Segment::Chunk(new[0].clone())],
+        );
+        assert!(diff.starts_with(
+            "### Code chunk changes\n#### `fig-scatter` (r) (renamed from `plot` (r))\n```diff"
+        ));
+    }
+
+    #[test]
+    fn test_match_added_and_removed_chunks() {
+        let old = vec![
+            chunk(None, 1, "x <- 1\ny <- 2"),
+            chunk(None, 2, "print('unused')"),
+        ];
+        let new = vec![
+            chunk(None, 1, "x <- 1\ny <- 3"),
+            chunk(None, 2, "z <- x + y\nprint(z)"),
+        ];
+
+        assert_eq!(
+            match_chunks(&old, &new),
+            vec![
+                ChunkMatch::Matched { old: 0, new: 0 },
+                ChunkMatch::Added(1),
+                ChunkMatch::Removed(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_only_change() {
+        let new_document = DOCUMENT.replace("format: html", "format: pdf");
+
+        let diff = diff_documents(DOCUMENT, &new_document).unwrap();
+
+        assert!(diff.starts_with("### Frontmatter changes\n```diff"));
+        assert!(diff.contains("- 2 format: html"));
+        assert!(diff.contains("+ 2 format: pdf"));
+        assert!(!diff.contains("### Code chunk changes"));
+        assert!(!diff.contains("### Prose changes"));
+    }
+
+    #[test]
+    fn test_unchanged_document() {
+        assert_eq!(
+            diff_documents(DOCUMENT, DOCUMENT).unwrap(),
+            "\nNo difference between file versions.\n"
+        );
+    }
+}
@@ -0,0 +1,1049 @@
+use std::{io::Cursor, path::Path, sync::RwLock};
+
+use calamine::{Data, Reader, open_workbook_auto_from_rs};
+use diff::{Result as DiffResult, lines};
+use sha2::{Digest, Sha256};
+
+use crate::git::GitFileOps;
+use crate::utils::format_file_size;
+
+mod document;
+mod workbook;
+
+pub use workbook::{
+    DEFAULT_EXCEL_DIFF_DIR, ExcelDiffError, create_excel_diff, excel_diff_note, write_excel_diff,
+};
+
+/// How file diffs are generated, with limits keeping the diffs of large files readable and
+/// postable
+///
+/// Set from the `diff_max_file_bytes`, `diff_max_characters` and `binary_extensions` options of
+/// the configuration with [`set_diff_settings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSettings {
+    /// Files larger than this in either version are not diffed, only their sizes are given
+    pub max_file_bytes: u64,
+    /// Text diffs longer than this keep their first hunks, followed by what was omitted
+    pub max_diff_characters: usize,
+    /// Extensions of binary files, without the dot, on top of [`BINARY_EXTENSIONS`]
+    pub binary_extensions: Vec<String>,
+}
+
+impl DiffSettings {
+    pub const DEFAULT: Self = Self {
+        max_file_bytes: 10 * 1024 * 1024,
+        max_diff_characters: 250_000,
+        binary_extensions: Vec::new(),
+    };
+}
+
+impl Default for DiffSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static DIFF_SETTINGS: RwLock<DiffSettings> = RwLock::new(DiffSettings::DEFAULT);
+
+/// Set the settings applied by [`file_diff`], whenever the configuration is loaded
+pub fn set_diff_settings(settings: DiffSettings) {
+    *DIFF_SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+pub fn diff_settings() -> DiffSettings {
+    DIFF_SETTINGS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Extensions of files always treated as binary, whatever their content
+pub const BINARY_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "tif", "tiff", "ico", "webp", "pdf", "rds", "rda", "rdata",
+    "feather", "parquet", "sas7bdat", "xpt", "dta", "sav", "zip", "gz", "tgz", "bz2", "xz", "7z",
+    "docx", "pptx", "exe", "dll", "so", "dylib",
+];
+
+/// Whether a version of `file` is binary: a known binary extension, or a NUL byte in its first
+/// 8000 bytes, the check git itself uses
+pub fn is_binary(file: &Path, bytes: &[u8], settings: &DiffSettings) -> bool {
+    let binary_extension = file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            BINARY_EXTENSIONS
+                .iter()
+                .copied()
+                .chain(settings.binary_extensions.iter().map(String::as_str))
+                .any(|binary| binary.trim_start_matches('.').eq_ignore_ascii_case(ext))
+        });
+    binary_extension || bytes.iter().take(8000).any(|&b| b == 0)
+}
+
+/// Sizes and checksums of both versions of a binary file, in place of a content diff, with a
+/// link to the commit comparison when known
+pub fn binary_summary(from_bytes: &[u8], to_bytes: &[u8], comparison_url: Option<&str>) -> String {
+    let mut summary = vec![
+        "_Binary file, diff omitted_".to_string(),
+        String::new(),
+        format!("* previous: {}", describe_version(from_bytes)),
+        format!("* current: {}", describe_version(to_bytes)),
+        format!(
+            "* changed: {}",
+            if from_bytes == to_bytes { "no" } else { "yes" }
+        ),
+    ];
+    if let Some(url) = comparison_url {
+        summary.push(format!("* [commit comparison]({url})"));
+    }
+    summary.join("\n")
+}
+
+/// Size and SHA-256 checksum of a file version
+fn describe_version(bytes: &[u8]) -> String {
+    format!(
+        "{}, sha256 `{:x}`",
+        format_file_size(bytes.len() as u64),
+        Sha256::digest(bytes)
+    )
+}
+
+/// Generate a diff between two file versions
+///
+/// This function handles Excel, Quarto/R Markdown and text files, automatically detecting
+/// the file type and using the appropriate diff engine, with the [`diff_settings`].
+pub fn file_diff(from_bytes: Vec<u8>, to_bytes: Vec<u8>, file: &Path) -> Option<String> {
+    file_diff_with_settings(from_bytes, to_bytes, file, &diff_settings())
+}
+
+/// [`file_diff`] with explicit `settings`
+pub fn file_diff_with_settings(
+    from_bytes: Vec<u8>,
+    to_bytes: Vec<u8>,
+    file: &Path,
+    settings: &DiffSettings,
+) -> Option<String> {
+    let largest = from_bytes.len().max(to_bytes.len()) as u64;
+    if largest > settings.max_file_bytes {
+        log::debug!(
+            "Not diffing {}: {largest} bytes exceeds the limit of {} bytes",
+            file.display(),
+            settings.max_file_bytes
+        );
+        return Some(too_large_to_diff(&from_bytes, &to_bytes, settings));
+    }
+
+    // Try to handle as Excel file first
+    if is_excel_file(file) {
+        if let Some(excel_diff) = diff_excel_files(from_bytes.clone(), to_bytes.clone()) {
+            return Some(excel_diff);
+        }
+        log::debug!("Failed to diff as Excel, falling back to text diff");
+    }
+
+    if is_binary(file, &from_bytes, settings) || is_binary(file, &to_bytes, settings) {
+        log::debug!("Not diffing binary file {}", file.display());
+        return Some(binary_summary(&from_bytes, &to_bytes, None));
+    }
+
+    // Group Quarto/R Markdown changes by frontmatter, code chunks and prose
+    if document::is_literate_document(file) {
+        let from_str = String::from_utf8_lossy(&from_bytes);
+        let to_str = String::from_utf8_lossy(&to_bytes);
+        match document::diff_documents(&from_str, &to_str) {
+            // A plain text diff can be cut between hunks, a grouped one cannot
+            Some(document_diff) if document_diff.len() > settings.max_diff_characters => {
+                log::debug!("Document diff exceeds the character limit, using a text diff")
+            }
+            Some(document_diff) => return Some(document_diff),
+            None => log::debug!("Failed to segment document, falling back to text diff"),
+        }
+    }
+
+    // Fall back to text diff
+    let from_str = String::from_utf8_lossy(&from_bytes);
+    let to_str = String::from_utf8_lossy(&to_bytes);
+    Some(diff_within(
+        &from_str,
+        &to_str,
+        settings.max_diff_characters,
+    ))
+}
+
+/// Sizes of both file versions, in place of the diff of a file over the size limit
+fn too_large_to_diff(from_bytes: &[u8], to_bytes: &[u8], settings: &DiffSettings) -> String {
+    let mut summary = vec![format!(
+        "_File too large to diff: {} → {} (limit {})_",
+        format_file_size(from_bytes.len() as u64),
+        format_file_size(to_bytes.len() as u64),
+        format_file_size(settings.max_file_bytes)
+    )];
+    if from_bytes == to_bytes {
+        summary.push(String::new());
+        summary.push("No differences between file versions.".to_string());
+    }
+    summary.join("\n")
+}
+
+/// Generate a diff between two file versions, unless `.gitattributes` marks the file `-diff`
+/// or `linguist-generated`
+///
+/// A suppressed diff is replaced by the size and checksum of both versions. `force_diff`
+/// ignores the attributes.
+pub fn file_diff_with_attributes(
+    from_bytes: Vec<u8>,
+    to_bytes: Vec<u8>,
+    file: &Path,
+    git_info: &impl GitFileOps,
+    force_diff: bool,
+) -> Option<String> {
+    if !force_diff {
+        match git_info.path_attributes(file) {
+            Ok(attributes) if attributes.suppresses_diff() => {
+                log::debug!("Diff of {} suppressed by .gitattributes", file.display());
+                return Some(suppressed_diff_summary(&from_bytes, &to_bytes));
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Could not read attributes of {}: {e}", file.display()),
+        }
+    }
+
+    file_diff(from_bytes, to_bytes, file)
+}
+
+/// Size and SHA-256 checksum of both file versions, in place of a content diff
+fn suppressed_diff_summary(from_bytes: &[u8], to_bytes: &[u8]) -> String {
+    let mut summary = vec![
+        "_Diff suppressed by .gitattributes_".to_string(),
+        String::new(),
+        format!("* previous: {}", describe_version(from_bytes)),
+        format!("* current: {}", describe_version(to_bytes)),
+    ];
+    if from_bytes == to_bytes {
+        summary.push(String::new());
+        summary.push("No differences between file versions.".to_string());
+    }
+
+    summary.join("\n")
+}
+
+/// What a comment keeps of a file's changes when its diff is omitted with `--no-diff`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NoDiff {
+    /// Keep a summary of the changes: lines added and removed, or Excel rows changed
+    #[default]
+    Stats,
+    /// Omit the change summary as well, for files where even counting the changes is too slow
+    Full,
+}
+
+/// Content of the `File Difference` section when the diff is omitted with `--no-diff`
+///
+/// An explicit marker tells readers the omission was intentional. With [`NoDiff::Stats`], it is
+/// followed by the [`change_summary`] of the two file versions returned by `versions`, which is
+/// not called with [`NoDiff::Full`].
+pub fn omitted_diff(
+    no_diff: NoDiff,
+    file: &Path,
+    versions: impl FnOnce() -> Option<(Vec<u8>, Vec<u8>)>,
+) -> String {
+    match no_diff {
+        NoDiff::Full => "_Diff and change summary omitted (--no-diff=full)_".to_string(),
+        NoDiff::Stats => {
+            let mut section = vec!["_Diff omitted (--no-diff)_".to_string()];
+            match versions() {
+                Some((from_bytes, to_bytes)) => {
+                    section.push(String::new());
+                    section.push(format!(
+                        "changes: {}",
+                        change_summary(&from_bytes, &to_bytes, file)
+                    ));
+                }
+                None => log::warn!("Could not summarize changes of file {:?}", file),
+            }
+            section.join("\n")
+        }
+    }
+}
+
+/// Count the changes between two file versions without rendering a diff
+///
+/// Excel files count the rows added, removed and modified over all sheets, binary files only
+/// tell whether they changed, everything else the lines added and removed.
+pub fn change_summary(from_bytes: &[u8], to_bytes: &[u8], file: &Path) -> String {
+    if is_excel_file(file) {
+        if let Some(summary) = excel_change_summary(from_bytes, to_bytes) {
+            return summary;
+        }
+        log::debug!("Failed to summarize as Excel, falling back to line counts");
+    }
+
+    let settings = diff_settings();
+    if is_binary(file, from_bytes, &settings) || is_binary(file, to_bytes, &settings) {
+        return if from_bytes == to_bytes {
+            "binary file unchanged"
+        } else {
+            "binary file changed"
+        }
+        .to_string();
+    }
+
+    let from_str = String::from_utf8_lossy(from_bytes);
+    let to_str = String::from_utf8_lossy(to_bytes);
+    let (mut added, mut removed) = (0, 0);
+    for change in lines(&from_str, &to_str) {
+        match change {
+            DiffResult::Left(_) => removed += 1,
+            DiffResult::Right(_) => added += 1,
+            DiffResult::Both(_, _) => {}
+        }
+    }
+
+    join_counts(&[(added, "line", "added"), (removed, "line", "removed")])
+}
+
+fn excel_change_summary(from_bytes: &[u8], to_bytes: &[u8]) -> Option<String> {
+    let mut from_workbook = open_workbook_auto_from_rs(Cursor::new(from_bytes.to_vec())).ok()?;
+    let mut to_workbook = open_workbook_auto_from_rs(Cursor::new(to_bytes.to_vec())).ok()?;
+
+    let from_sheets = from_workbook.sheet_names();
+    let to_sheets = to_workbook.sheet_names();
+    let sheets_added = to_sheets
+        .iter()
+        .filter(|s| !from_sheets.contains(s))
+        .count();
+    let sheets_removed = from_sheets
+        .iter()
+        .filter(|s| !to_sheets.contains(s))
+        .count();
+
+    let (mut added, mut removed, mut modified) = (0, 0, 0);
+    for sheet_name in from_sheets.iter().filter(|s| to_sheets.contains(s)) {
+        let from_range = from_workbook.worksheet_range(sheet_name).ok()?;
+        let to_range = to_workbook.worksheet_range(sheet_name).ok()?;
+        let from_rows = from_range.rows().collect::<Vec<_>>();
+        let to_rows = to_range.rows().collect::<Vec<_>>();
+
+        added += to_rows.len().saturating_sub(from_rows.len());
+        removed += from_rows.len().saturating_sub(to_rows.len());
+        modified += from_rows
+            .iter()
+            .zip(&to_rows)
+            .filter(|(from, to)| {
+                (0..from.len().max(to.len())).any(|col| {
+                    from.get(col).unwrap_or(&Data::Empty) != to.get(col).unwrap_or(&Data::Empty)
+                })
+            })
+            .count();
+    }
+
+    Some(join_counts(&[
+        (added, "row", "added"),
+        (removed, "row", "removed"),
+        (modified, "row", "modified"),
+        (sheets_added, "sheet", "added"),
+        (sheets_removed, "sheet", "removed"),
+    ]))
+}
+
+/// `"2 rows added, 1 row removed"`, leaving out zero counts
+fn join_counts(counts: &[(usize, &str, &str)]) -> String {
+    let parts = counts
+        .iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, noun, verb)| {
+            let plural = if *count == 1 { "" } else { "s" };
+            format!("{count} {noun}{plural} {verb}")
+        })
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        "no differences".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Check if a file is an Excel file based on its extension
+pub fn is_excel_file(file: &Path) -> bool {
+    if let Some(ext) = file.extension().and_then(|e| e.to_str()) {
+        matches!(
+            ext.to_lowercase().as_str(),
+            "xlsx" | "xlsm" | "xlsb" | "xls"
+        )
+    } else {
+        false
+    }
+}
+
+/// Generate a diff between two Excel files
+pub fn diff_excel_files(from_bytes: Vec<u8>, to_bytes: Vec<u8>) -> Option<String> {
+    // Use Cursor to provide Read + Seek traits
+    let from_cursor = Cursor::new(from_bytes);
+    let to_cursor = Cursor::new(to_bytes);
+
+    // Try to open both workbooks
+    let mut from_workbook = open_workbook_auto_from_rs(from_cursor).ok()?;
+    let mut to_workbook = open_workbook_auto_from_rs(to_cursor).ok()?;
+
+    let mut diff_lines = Vec::new();
+    diff_lines.push("```diff".to_string());
+
+    // Get worksheet names from both workbooks
+    let from_sheets: std::collections::HashSet<String> =
+        from_workbook.sheet_names().iter().cloned().collect();
+    let to_sheets: std::collections::HashSet<String> =
+        to_workbook.sheet_names().iter().cloned().collect();
+
+    // Check for added/removed sheets
+    for sheet in &from_sheets {
+        if !to_sheets.contains(sheet) {
+            diff_lines.push(format!("- Sheet removed: {}", sheet));
+        }
+    }
+
+    for sheet in &to_sheets {
+        if !from_sheets.contains(sheet) {
+            diff_lines.push(format!("+ Sheet added: {}", sheet));
+        }
+    }
+
+    // Compare common sheets
+    for sheet_name in from_sheets.intersection(&to_sheets) {
+        if let Some(sheet_diff) = diff_excel_sheet(&mut from_workbook, &mut to_workbook, sheet_name)
+        {
+            diff_lines.push(format!("@@ Sheet: {} @@", sheet_name));
+            diff_lines.extend(sheet_diff);
+        }
+    }
+
+    diff_lines.push("```".to_string());
+
+    if diff_lines.len() > 2 {
+        // More than just the ``` markers
+        Some(diff_lines.join("\n"))
+    } else {
+        Some("\nNo differences between Excel file versions.\n".to_string())
+    }
+}
+
+fn diff_excel_sheet<R>(
+    from_workbook: &mut R,
+    to_workbook: &mut R,
+    sheet_name: &str,
+) -> Option<Vec<String>>
+where
+    R: Reader<Cursor<Vec<u8>>>,
+{
+    let from_range = from_workbook.worksheet_range(sheet_name).ok()?;
+    let to_range = to_workbook.worksheet_range(sheet_name).ok()?;
+
+    let mut changes = Vec::new();
+    let mut has_changes = false;
+
+    // Get dimensions
+    let from_dims = from_range.get_size();
+    let to_dims = to_range.get_size();
+
+    if from_dims != to_dims {
+        changes.push(format!(
+            "  Sheet dimensions changed: {}x{} -> {}x{}",
+            from_dims.1, from_dims.0, to_dims.1, to_dims.0
+        ));
+        has_changes = true;
+    }
+
+    // Analyze changes by row for better formatting
+    let row_changes = analyze_row_changes(&from_range, &to_range, from_dims, to_dims);
+
+    if !row_changes.is_empty() {
+        format_row_changes(&mut changes, &row_changes);
+        has_changes = true;
+    }
+
+    if has_changes { Some(changes) } else { None }
+}
+
+#[derive(Debug, Clone)]
+enum RowChange {
+    Added {
+        row_num: usize,
+        values: Vec<String>,
+    },
+    Removed {
+        row_num: usize,
+        values: Vec<String>,
+    },
+    Modified {
+        row_num: usize,
+        changes: Vec<CellChange>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct CellChange {
+    col_letter: char,
+    old_value: String,
+    new_value: String,
+}
+
+/// Analyze changes by row to provide better formatting
+fn analyze_row_changes(
+    from_range: &calamine::Range<Data>,
+    to_range: &calamine::Range<Data>,
+    from_dims: (usize, usize),
+    to_dims: (usize, usize),
+) -> Vec<RowChange> {
+    let mut row_changes = Vec::new();
+    let max_rows = from_dims.0.max(to_dims.0);
+    let max_cols = from_dims.1.max(to_dims.1);
+
+    // Limit the number of rows we analyze to prevent overwhelming output
+    let rows_to_analyze = max_rows.min(20);
+
+    for row in 0..rows_to_analyze {
+        let row_num = row + 1; // 1-indexed for display
+
+        // Check if this is a completely new row (beyond from_dims)
+        if row >= from_dims.0 && row < to_dims.0 {
+            let values = get_row_values(to_range, row, to_dims.1);
+            if !values.iter().all(|v| v.is_empty()) {
+                row_changes.push(RowChange::Added { row_num, values });
+            }
+            continue;
+        }
+
+        // Check if this is a completely removed row (beyond to_dims)
+        if row >= to_dims.0 && row < from_dims.0 {
+            let values = get_row_values(from_range, row, from_dims.1);
+            if !values.iter().all(|v| v.is_empty()) {
+                row_changes.push(RowChange::Removed { row_num, values });
+            }
+            continue;
+        }
+
+        // Compare existing rows cell by cell
+        let mut cell_changes = Vec::new();
+        let cols_to_check = max_cols.min(26); // Limit to A-Z for now
+
+        for col in 0..cols_to_check {
+            let from_cell = from_range.get((row, col)).unwrap_or(&Data::Empty);
+            let to_cell = to_range.get((row, col)).unwrap_or(&Data::Empty);
+
+            if from_cell != to_cell {
+                let col_letter = (b'A' + (col % 26) as u8) as char;
+                cell_changes.push(CellChange {
+                    col_letter,
+                    old_value: format_cell_value(from_cell),
+                    new_value: format_cell_value(to_cell),
+                });
+            }
+        }
+
+        if !cell_changes.is_empty() {
+            row_changes.push(RowChange::Modified {
+                row_num,
+                changes: cell_changes,
+            });
+        }
+
+        // Stop early if we have too many changes to prevent overwhelming output
+        if row_changes.len() >= 10 {
+            break;
+        }
+    }
+
+    row_changes
+}
+
+/// Get all values from a row as strings
+fn get_row_values(range: &calamine::Range<Data>, row: usize, num_cols: usize) -> Vec<String> {
+    let mut values = Vec::new();
+    let cols_to_get = num_cols.min(26); // Limit to A-Z
+
+    for col in 0..cols_to_get {
+        let cell = range.get((row, col)).unwrap_or(&Data::Empty);
+        values.push(format_cell_value(cell));
+    }
+
+    values
+}
+
+/// Format row changes into readable diff output
+fn format_row_changes(changes: &mut Vec<String>, row_changes: &[RowChange]) {
+    let mut change_count = 0;
+
+    for row_change in row_changes {
+        if change_count >= 10 {
+            let remaining = row_changes.len() - change_count;
+            changes.push(format!("  ... and {} more row changes", remaining));
+            break;
+        }
+
+        match row_change {
+            RowChange::Added { row_num, values } => {
+                let row_content = values.join(" | ");
+                changes.push(format!("+ Row {}: {}", row_num, row_content));
+            }
+            RowChange::Removed { row_num, values } => {
+                let row_content = values.join(" | ");
+                changes.push(format!("- Row {}: {}", row_num, row_content));
+            }
+            RowChange::Modified {
+                row_num,
+                changes: cell_changes,
+            } => {
+                if cell_changes.len() == 1 {
+                    // Single cell change - show it concisely
+                    let change = &cell_changes[0];
+                    changes.push(format!(
+                        "  Row {} {}: {} → {}",
+                        row_num, change.col_letter, change.old_value, change.new_value
+                    ));
+                } else if cell_changes.len() <= 3 {
+                    // Few cell changes - show them on one line
+                    let change_strs: Vec<String> = cell_changes
+                        .iter()
+                        .map(|c| format!("{}: {} → {}", c.col_letter, c.old_value, c.new_value))
+                        .collect();
+                    changes.push(format!(
+                        "  Row {} changes: {}",
+                        row_num,
+                        change_strs.join(", ")
+                    ));
+                } else {
+                    // Many cell changes - show summary
+                    changes.push(format!(
+                        "  Row {} has {} cell changes",
+                        row_num,
+                        cell_changes.len()
+                    ));
+                    for change in cell_changes.iter().take(3) {
+                        changes.push(format!(
+                            "    {}: {} → {}",
+                            change.col_letter, change.old_value, change.new_value
+                        ));
+                    }
+                    if cell_changes.len() > 3 {
+                        changes.push(format!("    ... and {} more", cell_changes.len() - 3));
+                    }
+                }
+            }
+        }
+
+        change_count += 1;
+    }
+}
+
+fn format_cell_value(cell: &Data) -> String {
+    match cell {
+        Data::Empty => "".to_string(),
+        Data::String(s) => format!("\"{}\"", s),
+        Data::Float(f) => f.to_string(),
+        Data::Int(i) => i.to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::Error(e) => format!("ERROR({:?})", e),
+        Data::DateTime(dt) => format!("DATE({})", dt),
+        Data::DateTimeIso(dt) => format!("ISO_DATE({})", dt),
+        Data::DurationIso(d) => format!("ISO_DURATION({})", d),
+    }
+}
+
+/// Generate a markdown-formatted diff between two strings showing only changed hunks with context
+pub fn diff(old_content: &str, new_content: &str) -> String {
+    diff_within(old_content, new_content, usize::MAX)
+}
+
+/// [`diff`] keeping the hunks that fit in `max_characters`, followed by a count of the hunks
+/// and changed lines omitted
+fn diff_within(old_content: &str, new_content: &str, max_characters: usize) -> String {
+    let old_lines: Vec<&str> = old_content.lines().collect();
+    let new_lines: Vec<&str> = new_content.lines().collect();
+
+    // Check if files are identical
+    if old_lines == new_lines {
+        return "\nNo difference between file versions.\n".to_string();
+    }
+
+    let changeset = lines(old_content, new_content);
+
+    // Group changes into hunks with context
+    let hunks = create_hunks(&changeset, 3); // 3 lines of context
+
+    if hunks.is_empty() {
+        return "\nNo difference between file versions.\n".to_string();
+    }
+
+    let mut result = vec!["```diff".to_string()];
+    let mut length = result[0].len();
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let formatted = format_hunk(hunk);
+        // Keep at least the first hunk, however long
+        if i > 0 && length + formatted.len() + 1 > max_characters {
+            let omitted = &hunks[i..];
+            let changed_lines: usize = omitted
+                .iter()
+                .map(|hunk| {
+                    hunk.lines
+                        .iter()
+                        .filter(|line| !matches!(line, DiffLine::Context(..)))
+                        .count()
+                })
+                .sum();
+            result.push(format!(
+                "... {} more {} ({} changed {}) omitted: the diff exceeds {} characters",
+                omitted.len(),
+                if omitted.len() == 1 { "hunk" } else { "hunks" },
+                changed_lines,
+                if changed_lines == 1 { "line" } else { "lines" },
+                max_characters
+            ));
+            break;
+        }
+        length += formatted.len() + 1;
+        result.push(formatted);
+    }
+
+    result.push("```".to_string());
+    result.join("\n")
+}
+
+#[derive(Debug, Clone)]
+struct DiffHunk {
+    old_start: usize,
+    old_count: usize,
+    new_start: usize,
+    new_count: usize,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone)]
+enum DiffLine {
+    Context(String, usize, usize), // content, old_line_num, new_line_num
+    Addition(String, usize),       // content, new_line_num
+    Deletion(String, usize),       // content, old_line_num
+}
+
+fn create_hunks(changeset: &[DiffResult<&str>], context_lines: usize) -> Vec<DiffHunk> {
+    let mut hunks = Vec::new();
+    let mut current_hunk_lines = Vec::new();
+    let mut old_line = 1;
+    let mut new_line = 1;
+    let mut last_change_idx = None;
+
+    for (idx, change) in changeset.iter().enumerate() {
+        let is_change = matches!(change, DiffResult::Left(_) | DiffResult::Right(_));
+
+        if is_change {
+            // If this is a change, include context before it if we haven't started a hunk
+            if current_hunk_lines.is_empty() {
+                let context_start = idx.saturating_sub(context_lines);
+                for i in context_start..idx {
+                    if let DiffResult::Both(line, _) = &changeset[i] {
+                        let ctx_old = old_line - (idx - i);
+                        let ctx_new = new_line - (idx - i);
+                        current_hunk_lines.push(DiffLine::Context(
+                            line.to_string(),
+                            ctx_old,
+                            ctx_new,
+                        ));
+                    }
+                }
+            }
+            last_change_idx = Some(idx);
+        }
+
+        // Add the current line to the hunk
+        match change {
+            DiffResult::Left(line) => {
+                current_hunk_lines.push(DiffLine::Deletion(line.to_string(), old_line));
+                old_line += 1;
+            }
+            DiffResult::Right(line) => {
+                current_hunk_lines.push(DiffLine::Addition(line.to_string(), new_line));
+                new_line += 1;
+            }
+            DiffResult::Both(line, _) => {
+                if !current_hunk_lines.is_empty() {
+                    current_hunk_lines.push(DiffLine::Context(
+                        line.to_string(),
+                        old_line,
+                        new_line,
+                    ));
+                }
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+
+        // Check if we should end the current hunk
+        if let Some(last_change) = last_change_idx {
+            let distance_from_last_change = idx - last_change;
+            if distance_from_last_change >= context_lines * 2 && !current_hunk_lines.is_empty() {
+                // Trim to exactly context_lines after the last change
+                let mut lines_to_keep = current_hunk_lines.len();
+                let mut context_after_change = 0;
+
+                // Count backwards from the end to find where to cut off
+                for (i, line) in current_hunk_lines.iter().enumerate().rev() {
+                    if matches!(line, DiffLine::Context(_, _, _)) {
+                        context_after_change += 1;
+                        if context_after_change > context_lines {
+                            lines_to_keep = i + 1;
+                            break;
+                        }
+                    } else {
+                        // Hit a change line, reset counter
+                        context_after_change = 0;
+                    }
+                }
+
+                current_hunk_lines.truncate(lines_to_keep);
+
+                if let Some(hunk) = create_hunk_from_lines(current_hunk_lines.clone()) {
+                    hunks.push(hunk);
+                }
+                current_hunk_lines.clear();
+                last_change_idx = None;
+            }
+        }
+    }
+
+    // Handle remaining hunk
+    if !current_hunk_lines.is_empty() {
+        // Trim final hunk to exactly context_lines after the last change
+        let mut lines_to_keep = current_hunk_lines.len();
+        let mut context_after_change = 0;
+
+        // Count backwards from the end to find where to cut off
+        for (i, line) in current_hunk_lines.iter().enumerate().rev() {
+            if matches!(line, DiffLine::Context(_, _, _)) {
+                context_after_change += 1;
+                if context_after_change > context_lines {
+                    lines_to_keep = i + 1;
+                    break;
+                }
+            } else {
+                // Hit a change line, reset counter
+                context_after_change = 0;
+            }
+        }
+
+        current_hunk_lines.truncate(lines_to_keep);
+
+        if let Some(hunk) = create_hunk_from_lines(current_hunk_lines) {
+            hunks.push(hunk);
+        }
+    }
+
+    hunks
+}
+
+fn create_hunk_from_lines(lines: Vec<DiffLine>) -> Option<DiffHunk> {
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut old_start = usize::MAX;
+    let mut new_start = usize::MAX;
+    let mut old_count = 0;
+    let mut new_count = 0;
+
+    for line in &lines {
+        match line {
+            DiffLine::Context(_, old_num, new_num) => {
+                old_start = old_start.min(*old_num);
+                new_start = new_start.min(*new_num);
+                old_count += 1;
+                new_count += 1;
+            }
+            DiffLine::Addition(_, new_num) => {
+                new_start = new_start.min(*new_num);
+                new_count += 1;
+            }
+            DiffLine::Deletion(_, old_num) => {
+                old_start = old_start.min(*old_num);
+                old_count += 1;
+            }
+        }
+    }
+
+    Some(DiffHunk {
+        old_start,
+        old_count,
+        new_start,
+        new_count,
+        lines,
+    })
+}
+
+fn format_hunk(hunk: &DiffHunk) -> String {
+    let mut result = Vec::new();
+
+    // Add hunk header
+    result.push(format!(
+        "@@ previous script: lines {}-{} @@",
+        hunk.old_start,
+        hunk.old_start + hunk.old_count - 1
+    ));
+    result.push(format!(
+        "@@  current script: lines {}-{} @@",
+        hunk.new_start,
+        hunk.new_start + hunk.new_count - 1
+    ));
+
+    // Add hunk content with line numbers
+    for line in &hunk.lines {
+        match line {
+            DiffLine::Context(content, _, new_num) => {
+                result.push(format!("  {} {}", new_num, content));
+            }
+            DiffLine::Addition(content, new_num) => {
+                result.push(format!("+ {} {}", new_num, content));
+            }
+            DiffLine::Deletion(content, old_num) => {
+                result.push(format!("- {} {}", old_num, content));
+            }
+        }
+    }
+
+    result.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `lines` lines of 150 characters, every 8th one changed in the current version
+    fn versions(lines: usize) -> (Vec<u8>, Vec<u8>) {
+        let line = |i: usize, version: &str| format!("{i:>6},{version},{}", "x".repeat(140));
+        let previous = (0..lines).map(|i| line(i, "old")).collect::<Vec<_>>();
+        let current = (0..lines)
+            .map(|i| line(i, if i % 8 == 0 { "new" } else { "old" }))
+            .collect::<Vec<_>>();
+        (
+            previous.join("\n").into_bytes(),
+            current.join("\n").into_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_diff_truncated_after_last_hunk_that_fits() {
+        let (previous, current) = versions(80);
+        let settings = DiffSettings {
+            max_diff_characters: 3_000,
+            ..DiffSettings::DEFAULT
+        };
+
+        let diff = file_diff_with_settings(previous, current, Path::new("data/pk.csv"), &settings)
+            .unwrap();
+
+        assert!(diff.len() <= settings.max_diff_characters + 200);
+        assert!(diff.starts_with("```diff\n@@ previous script: lines 1-4 @@"));
+        assert!(diff.ends_with(
+            "... 8 more hunks (16 changed lines) omitted: the diff exceeds 3000 characters\n```"
+        ));
+    }
+
+    #[test]
+    fn test_first_hunk_kept_over_character_limit() {
+        let settings = DiffSettings {
+            max_diff_characters: 10,
+            ..DiffSettings::DEFAULT
+        };
+
+        let diff = file_diff_with_settings(
+            b"a\nb".to_vec(),
+            b"a\nc".to_vec(),
+            Path::new("script.R"),
+            &settings,
+        )
+        .unwrap();
+
+        assert_eq!(
+            diff,
+            "```diff\n@@ previous script: lines 1-2 @@\n@@  current script: lines 1-2 @@\n  1 a\n- 2 b\n+ 2 c\n```"
+        );
+    }
+
+    #[test]
+    fn test_file_too_large_to_diff() {
+        let settings = DiffSettings {
+            max_file_bytes: 1024,
+            ..DiffSettings::DEFAULT
+        };
+
+        let diff = file_diff_with_settings(
+            vec![b'a'; 1000],
+            vec![b'b'; 2048],
+            Path::new("data/pk.csv"),
+            &settings,
+        )
+        .unwrap();
+
+        assert_eq!(
+            diff,
+            "_File too large to diff: 1000 B → 2.0 KB (limit 1.0 KB)_"
+        );
+    }
+
+    #[test]
+    fn test_binary_detection() {
+        let settings = DiffSettings {
+            binary_extensions: vec!["sqlite".to_string()],
+            ..DiffSettings::DEFAULT
+        };
+
+        assert!(is_binary(Path::new("model.RDS"), b"text", &settings));
+        assert!(is_binary(
+            Path::new("data/cache.sqlite"),
+            b"text",
+            &settings
+        ));
+        assert!(is_binary(Path::new("data/blob.bin"), b"ab\0cd", &settings));
+        assert!(!is_binary(
+            Path::new("data/pk.csv"),
+            b"id,dv\n1,2\n",
+            &settings
+        ));
+        assert!(!is_binary(
+            Path::new("data/cache.sqlite"),
+            b"text",
+            &DiffSettings::DEFAULT
+        ));
+    }
+
+    #[test]
+    fn test_binary_file_not_diffed() {
+        let diff = file_diff_with_settings(
+            b"ab\0cd".to_vec(),
+            b"ab\0ce".to_vec(),
+            Path::new("data/blob.bin"),
+            &DiffSettings::DEFAULT,
+        )
+        .unwrap();
+
+        assert!(diff.starts_with("_Binary file, diff omitted_\n\n* previous: 5 B, sha256 `"));
+        assert!(diff.ends_with("* changed: yes"));
+        assert_eq!(
+            change_summary(b"ab\0cd", b"ab\0cd", Path::new("data/blob.bin")),
+            "binary file unchanged"
+        );
+    }
+
+    #[test]
+    fn test_long_document_diff_falls_back_to_text_diff() {
+        let (previous, current) = versions(80);
+        let settings = DiffSettings {
+            max_diff_characters: 3_000,
+            ..DiffSettings::DEFAULT
+        };
+
+        let diff =
+            file_diff_with_settings(previous, current, Path::new("report.qmd"), &settings).unwrap();
+
+        assert!(diff.contains("@@ previous script: lines 1-4 @@"));
+        assert!(diff.contains("more hunks"));
+    }
+}
@@ -0,0 +1,467 @@
+//! Highlighted diff workbooks of Excel files
+//!
+//! The text diff of an Excel file only covers the first rows of each sheet. The diff workbook
+//! holds every sheet of both versions: added rows are filled green, removed rows and sheets red
+//! and changed cells yellow, showing `old → new`.
+
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use calamine::{Data, Reader, open_workbook_auto_from_rs};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+/// Directory, relative to the repository, diff workbooks are written to by default
+pub const DEFAULT_EXCEL_DIFF_DIR: &str = "qc_diffs";
+
+const MAIN_NS: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const PACKAGE_REL_NS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExcelDiffError {
+    #[error("Failed to read workbook: {0}")]
+    Read(#[from] calamine::Error),
+    #[error("Failed to write diff workbook: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Failed to write diff workbook to {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Cell styles of the diff workbook, indexing `cellXfs` of [`STYLES`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    None = 0,
+    Added = 1,
+    Changed = 2,
+    Removed = 3,
+}
+
+const STYLES: &str = r#"<fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts><fills count="5"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill><fill><patternFill patternType="solid"><fgColor rgb="FFC6EFCE"/><bgColor indexed="64"/></patternFill></fill><fill><patternFill patternType="solid"><fgColor rgb="FFFFEB9C"/><bgColor indexed="64"/></patternFill></fill><fill><patternFill patternType="solid"><fgColor rgb="FFFFC7CE"/><bgColor indexed="64"/></patternFill></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="4"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="0" fillId="2" borderId="0" xfId="0" applyFill="1"/><xf numFmtId="0" fontId="0" fillId="3" borderId="0" xfId="0" applyFill="1"/><xf numFmtId="0" fontId="0" fillId="4" borderId="0" xfId="0" applyFill="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#;
+
+/// `(column, value, highlight)` of a non-empty cell, by 0-based column number
+type Cell = (u32, CellValue, Highlight);
+
+struct Sheet {
+    name: String,
+    /// Non-empty rows, by 0-based row number
+    rows: Vec<(u32, Vec<Cell>)>,
+}
+
+enum CellValue {
+    Number(String),
+    Bool(bool),
+    Text(String),
+}
+
+/// Build the highlighted diff workbook of two versions of an Excel file, as xlsx bytes
+///
+/// Sheets of the current version come first, followed by the removed sheets.
+pub fn create_excel_diff(from_bytes: &[u8], to_bytes: &[u8]) -> Result<Vec<u8>, ExcelDiffError> {
+    let mut from_workbook = open_workbook_auto_from_rs(Cursor::new(from_bytes.to_vec()))?;
+    let mut to_workbook = open_workbook_auto_from_rs(Cursor::new(to_bytes.to_vec()))?;
+    let from_sheets = from_workbook.sheet_names();
+    let to_sheets = to_workbook.sheet_names();
+
+    let mut sheets = Vec::new();
+    for name in &to_sheets {
+        let to_range = to_workbook.worksheet_range(name)?;
+        let from_range = if from_sheets.contains(name) {
+            Some(from_workbook.worksheet_range(name)?)
+        } else {
+            None
+        };
+        sheets.push(Sheet {
+            name: name.clone(),
+            rows: diff_sheet(from_range.as_ref(), Some(&to_range)),
+        });
+    }
+    for name in from_sheets.iter().filter(|s| !to_sheets.contains(s)) {
+        let from_range = from_workbook.worksheet_range(name)?;
+        sheets.push(Sheet {
+            name: sheet_name(name, "(removed)"),
+            rows: diff_sheet(Some(&from_range), None),
+        });
+    }
+
+    write_workbook(&sheets)
+}
+
+/// Write the diff workbook of `file` under `dir` of the repository, as
+/// `<dir>/<file>_<from>_<to>.xlsx` with the path separators of `file` replaced by `_`
+///
+/// Returns the path of the workbook relative to `repo_root`, or absolute when `dir` is. The
+/// workbook is staged in a temporary file of `dir` and only moved in place once complete, so a
+/// failure leaves neither a partial workbook nor a `dir` created for it behind.
+pub fn write_excel_diff(
+    repo_root: &Path,
+    dir: &Path,
+    file: &Path,
+    from_label: &str,
+    to_label: &str,
+    from_bytes: &[u8],
+    to_bytes: &[u8],
+) -> Result<PathBuf, ExcelDiffError> {
+    let stem = file
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("_");
+    let relative = dir.join(format!("{stem}_{from_label}_{to_label}.xlsx"));
+
+    let workbook = create_excel_diff(from_bytes, to_bytes)?;
+
+    let abs_dir = repo_root.join(dir);
+    let created_dir = !abs_dir.exists();
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| ExcelDiffError::Io { path, source }
+    };
+    let result = std::fs::create_dir_all(&abs_dir)
+        .map_err(io_error(&abs_dir))
+        .and_then(|_| {
+            let target = repo_root.join(&relative);
+            let mut staged =
+                tempfile::NamedTempFile::new_in(&abs_dir).map_err(io_error(&abs_dir))?;
+            staged
+                .write_all(&workbook)
+                .map_err(io_error(staged.path()))?;
+            staged
+                .persist(&target)
+                .map_err(|e| io_error(&target)(e.error))?;
+            Ok(())
+        });
+
+    if let Err(e) = result {
+        if created_dir {
+            // Only removes the directory when the failed write left it empty
+            let _ = std::fs::remove_dir(&abs_dir);
+        }
+        return Err(e);
+    }
+
+    Ok(relative)
+}
+
+/// Comment text pointing QCers to a diff workbook written by [`write_excel_diff`]
+pub fn excel_diff_note(path: &Path) -> String {
+    format!(
+        "_A highlighted workbook of the full diff was generated at `{}`. It is not committed: open it from the working tree it was generated in, or commit it to share it. Added rows are green, removed rows and sheets red and changed cells yellow, showing `old → new`._",
+        path.display()
+    )
+}
+
+/// Cells of one sheet, comparing rows by position as the text diff does
+fn diff_sheet(
+    from: Option<&calamine::Range<Data>>,
+    to: Option<&calamine::Range<Data>>,
+) -> Vec<(u32, Vec<Cell>)> {
+    let end = |range: Option<&calamine::Range<Data>>| {
+        range
+            .and_then(|r| r.end())
+            .map(|(row, col)| (row + 1, col + 1))
+            .unwrap_or((0, 0))
+    };
+    let (from_rows, from_cols) = end(from);
+    let (to_rows, to_cols) = end(to);
+    fn cell(range: Option<&calamine::Range<Data>>, row: u32, col: u32) -> &Data {
+        range
+            .and_then(|r| r.get_value((row, col)))
+            .unwrap_or(&Data::Empty)
+    }
+
+    let mut rows = Vec::new();
+    for row in 0..from_rows.max(to_rows) {
+        let mut cells = Vec::new();
+        for col in 0..from_cols.max(to_cols) {
+            let old = cell(from, row, col);
+            let new = cell(to, row, col);
+            let entry = if row >= from_rows {
+                cell_value(new).map(|v| (v, Highlight::Added))
+            } else if row >= to_rows {
+                cell_value(old).map(|v| (v, Highlight::Removed))
+            } else if old == new {
+                cell_value(new).map(|v| (v, Highlight::None))
+            } else {
+                let text = match old {
+                    Data::Empty => cell_text(new),
+                    _ => format!("{} → {}", cell_text(old), cell_text(new)),
+                };
+                Some((CellValue::Text(text), Highlight::Changed))
+            };
+            if let Some((value, highlight)) = entry {
+                cells.push((col, value, highlight));
+            }
+        }
+        if !cells.is_empty() {
+            rows.push((row, cells));
+        }
+    }
+    rows
+}
+
+fn cell_value(cell: &Data) -> Option<CellValue> {
+    match cell {
+        Data::Empty => None,
+        Data::Float(f) => Some(CellValue::Number(f.to_string())),
+        Data::Int(i) => Some(CellValue::Number(i.to_string())),
+        Data::Bool(b) => Some(CellValue::Bool(*b)),
+        _ => Some(CellValue::Text(cell_text(cell))),
+    }
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) => s.clone(),
+        Data::Error(e) => format!("{e:?}"),
+        Data::DateTime(dt) => dt.to_string(),
+        Data::DateTimeIso(dt) => dt.clone(),
+        Data::DurationIso(d) => d.clone(),
+        _ => cell.to_string(),
+    }
+}
+
+/// Sheet names are limited to 31 characters
+fn sheet_name(name: &str, suffix: &str) -> String {
+    let keep = 31 - suffix.chars().count() - 1;
+    format!("{} {suffix}", name.chars().take(keep).collect::<String>())
+}
+
+/// `A` for column 0, `AA` for column 26
+fn column_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (col % 26) as u8);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).expect("column names are ASCII")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_workbook(sheets: &[Sheet]) -> Result<Vec<u8>, ExcelDiffError> {
+    const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+
+    let mut content_types = format!(
+        r#"{HEADER}<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#
+    );
+    let mut workbook =
+        format!(r#"{HEADER}<workbook xmlns="{MAIN_NS}" xmlns:r="{REL_NS}"><sheets>"#);
+    let mut workbook_rels = format!(r#"{HEADER}<Relationships xmlns="{PACKAGE_REL_NS}">"#);
+    for (i, sheet) in sheets.iter().enumerate() {
+        let n = i + 1;
+        let _ = write!(
+            content_types,
+            r#"<Override PartName="/xl/worksheets/sheet{n}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+        );
+        let _ = write!(
+            workbook,
+            r#"<sheet name="{}" sheetId="{n}" r:id="rId{n}"/>"#,
+            escape(&sheet.name)
+        );
+        let _ = write!(
+            workbook_rels,
+            r#"<Relationship Id="rId{n}" Type="{REL_NS}/worksheet" Target="worksheets/sheet{n}.xml"/>"#
+        );
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    let _ = write!(
+        workbook_rels,
+        r#"<Relationship Id="rId{}" Type="{REL_NS}/styles" Target="styles.xml"/></Relationships>"#,
+        sheets.len() + 1
+    );
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut add = |name: &str, content: &str| -> Result<(), ExcelDiffError> {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(content.as_bytes())
+            .map_err(|source| ExcelDiffError::Io {
+                path: PathBuf::from(name),
+                source,
+            })
+    };
+    add("[Content_Types].xml", &content_types)?;
+    add(
+        "_rels/.rels",
+        &format!(
+            r#"{HEADER}<Relationships xmlns="{PACKAGE_REL_NS}"><Relationship Id="rId1" Type="{REL_NS}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#
+        ),
+    )?;
+    add("xl/workbook.xml", &workbook)?;
+    add("xl/_rels/workbook.xml.rels", &workbook_rels)?;
+    add(
+        "xl/styles.xml",
+        &format!(r#"{HEADER}<styleSheet xmlns="{MAIN_NS}">{STYLES}</styleSheet>"#),
+    )?;
+    for (i, sheet) in sheets.iter().enumerate() {
+        add(
+            &format!("xl/worksheets/sheet{}.xml", i + 1),
+            &worksheet_xml(HEADER, sheet),
+        )?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn worksheet_xml(header: &str, sheet: &Sheet) -> String {
+    let mut xml = format!(r#"{header}<worksheet xmlns="{MAIN_NS}"><sheetData>"#);
+    for (row, cells) in &sheet.rows {
+        let r = row + 1;
+        let _ = write!(xml, r#"<row r="{r}">"#);
+        for (col, value, highlight) in cells {
+            let reference = format!("{}{r}", column_name(*col));
+            let style = *highlight as u8;
+            let _ = match value {
+                CellValue::Number(n) => {
+                    write!(xml, r#"<c r="{reference}" s="{style}"><v>{n}</v></c>"#)
+                }
+                CellValue::Bool(b) => write!(
+                    xml,
+                    r#"<c r="{reference}" s="{style}" t="b"><v>{}</v></c>"#,
+                    u8::from(*b)
+                ),
+                CellValue::Text(t) => write!(
+                    xml,
+                    r#"<c r="{reference}" s="{style}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    escape(t)
+                ),
+            };
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> (Vec<u8>, Vec<u8>) {
+        (
+            std::fs::read("src/tests/data/summary.xlsx").unwrap(),
+            std::fs::read("src/tests/data/summary_v2.xlsx").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn test_create_excel_diff_round_trips() {
+        let (from, to) = fixtures();
+        let diff = create_excel_diff(&from, &to).unwrap();
+
+        let mut workbook = open_workbook_auto_from_rs(Cursor::new(diff)).unwrap();
+        let mut to_workbook = open_workbook_auto_from_rs(Cursor::new(to)).unwrap();
+        assert_eq!(workbook.sheet_names(), to_workbook.sheet_names());
+
+        let sheet = &to_workbook.sheet_names()[0];
+        let diff_range = workbook.worksheet_range(sheet).unwrap();
+        let to_range = to_workbook.worksheet_range(sheet).unwrap();
+        assert_eq!(diff_range.end(), to_range.end());
+        assert!(
+            diff_range
+                .used_cells()
+                .any(|(_, _, cell)| cell.to_string().contains(" → "))
+        );
+    }
+
+    #[test]
+    fn test_create_excel_diff_rejects_non_workbooks() {
+        let (from, _) = fixtures();
+        assert!(matches!(
+            create_excel_diff(&from, b"not a workbook"),
+            Err(ExcelDiffError::Read(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_excel_diff() {
+        let (from, to) = fixtures();
+        let repo = tempfile::tempdir().unwrap();
+
+        let path = write_excel_diff(
+            repo.path(),
+            Path::new(DEFAULT_EXCEL_DIFF_DIR),
+            Path::new("data/summary.xlsx"),
+            "0a1b2c3",
+            "9f8e7d6",
+            &from,
+            &to,
+        )
+        .unwrap();
+
+        assert_eq!(
+            path,
+            PathBuf::from("qc_diffs/data_summary_0a1b2c3_9f8e7d6.xlsx")
+        );
+        assert!(repo.path().join(&path).is_file());
+        assert_eq!(
+            std::fs::read_dir(repo.path().join("qc_diffs"))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_write_excel_diff_failure_cleans_up() {
+        let (from, _) = fixtures();
+        let repo = tempfile::tempdir().unwrap();
+
+        let result = write_excel_diff(
+            repo.path(),
+            Path::new("qc_diffs"),
+            Path::new("summary.xlsx"),
+            "0a1b2c3",
+            "9f8e7d6",
+            &from,
+            b"not a workbook",
+        );
+
+        assert!(matches!(result, Err(ExcelDiffError::Read(_))));
+        assert!(!repo.path().join("qc_diffs").exists());
+
+        // A directory in the way of the workbook fails the final move of the staged file
+        let blocked = repo.path().join("qc_diffs/summary_0a1b2c3_9f8e7d6.xlsx");
+        std::fs::create_dir_all(&blocked).unwrap();
+        let result = write_excel_diff(
+            repo.path(),
+            Path::new("qc_diffs"),
+            Path::new("summary.xlsx"),
+            "0a1b2c3",
+            "9f8e7d6",
+            &from,
+            &from,
+        );
+
+        assert!(matches!(result, Err(ExcelDiffError::Io { .. })));
+        let entries = std::fs::read_dir(repo.path().join("qc_diffs"))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![blocked]);
+    }
+}
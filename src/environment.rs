@@ -0,0 +1,234 @@
+//! Environment descriptor files, e.g. `renv.lock`, listed in issues and comments so QCers know
+//! which package versions the QCed file was run against
+
+use std::path::{Path, PathBuf};
+
+use gix::ObjectId;
+
+use crate::git::{GitFileOps, GitHelpers};
+
+/// An environment descriptor file at the commit an issue or comment is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentFile {
+    /// Path within the repository
+    pub path: PathBuf,
+    /// Git blob hash of the file at the commit
+    pub blob: ObjectId,
+    /// Link to the file at the commit
+    pub url: String,
+    /// The working tree copy differs from the commit's, or is missing
+    pub dirty: bool,
+}
+
+/// The descriptor files named `names` closest to `file` at `commit`
+///
+/// Each name is looked up in the file's directory, then in its parents up to the repository
+/// root. With the repository's `working_tree`, descriptors whose working copy differs from the
+/// commit's are flagged as dirty.
+pub fn environment_files(
+    file: &Path,
+    commit: &ObjectId,
+    names: &[String],
+    working_tree: Option<&Path>,
+    git_info: &(impl GitHelpers + GitFileOps),
+) -> Vec<EnvironmentFile> {
+    let directory = file.parent().unwrap_or(Path::new(""));
+
+    names
+        .iter()
+        .filter_map(|name| {
+            directory.ancestors().find_map(|dir| {
+                let path = dir.join(name);
+                let bytes = git_info.file_bytes_at_commit(&path, commit).ok()?;
+                let blob =
+                    gix::objs::compute_hash(gix::hash::Kind::Sha1, gix::objs::Kind::Blob, &bytes)
+                        .ok()?;
+                let dirty = working_tree.is_some_and(|root| {
+                    std::fs::read(root.join(&path)).map_or(true, |current| current != bytes)
+                });
+                Some(EnvironmentFile {
+                    url: git_info.file_content_url(&commit.to_string(), &path),
+                    path,
+                    blob,
+                    dirty,
+                })
+            })
+        })
+        .collect()
+}
+
+/// The `## Environment` section listing `files`, `None` without any
+pub(crate) fn environment_section(files: &[EnvironmentFile]) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+
+    let mut section = vec!["## Environment".to_string()];
+    for file in files {
+        let mut line = format!(
+            "[{}]({}) blob `{}`",
+            file.path.display(),
+            file.url,
+            file.blob.to_hex_with_len(7)
+        );
+        if file.dirty {
+            line.push_str(" ⚠️ modified in the working tree");
+        }
+        section.push(line);
+    }
+    Some(section.join("\n* "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GitAuthor;
+    use crate::git::{GitFileOpsError, MockGitFileOps, PathAttributes};
+    use std::str::FromStr;
+
+    const LOCKED: &str = "1111111111111111111111111111111111111111";
+    const UNLOCKED: &str = "2222222222222222222222222222222222222222";
+    const RENV_LOCK: &[u8] = b"{\"R\": {\"Version\": \"4.4.1\"}}\n";
+
+    /// Links to GitHub, reading files through the mock
+    struct EnvironmentGit(MockGitFileOps);
+
+    impl GitHelpers for EnvironmentGit {
+        fn file_content_url(&self, git_ref: &str, file: &Path) -> String {
+            format!(
+                "https://github.com/owner/repo/blob/{git_ref}/{}",
+                file.display()
+            )
+        }
+
+        fn commit_comparison_url(&self, current: &ObjectId, previous: &ObjectId) -> String {
+            format!("https://github.com/owner/repo/compare/{previous}..{current}")
+        }
+
+        fn issue_url(&self, issue_number: u64) -> String {
+            format!("https://github.com/owner/repo/issues/{issue_number}")
+        }
+    }
+
+    impl GitFileOps for EnvironmentGit {
+        fn authors(&self, file: &Path) -> Result<Vec<GitAuthor>, GitFileOpsError> {
+            self.0.authors(file)
+        }
+
+        fn file_bytes_at_commit(
+            &self,
+            file: &Path,
+            commit: &ObjectId,
+        ) -> Result<Vec<u8>, GitFileOpsError> {
+            self.0.file_bytes_at_commit(file, commit)
+        }
+
+        fn list_tree_entries(&self, path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
+            self.0.list_tree_entries(path)
+        }
+
+        fn path_attributes(&self, file: &Path) -> Result<PathAttributes, GitFileOpsError> {
+            self.0.path_attributes(file)
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            self.0.resolve_commit(commitish)
+        }
+    }
+
+    /// `analysis/renv.lock` exists at the locked commit only
+    fn git() -> EnvironmentGit {
+        let mut files = MockGitFileOps::new();
+        files
+            .expect_file_bytes_at_commit()
+            .returning(|file, commit| {
+                if file == Path::new("analysis/renv.lock") && commit.to_string() == LOCKED {
+                    Ok(RENV_LOCK.to_vec())
+                } else {
+                    Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))
+                }
+            });
+        EnvironmentGit(files)
+    }
+
+    fn names() -> Vec<String> {
+        vec!["renv.lock".to_string(), "requirements.txt".to_string()]
+    }
+
+    #[test]
+    fn test_environment_file_found_in_parent_directory() {
+        let commit = ObjectId::from_str(LOCKED).unwrap();
+        let files = environment_files(
+            Path::new("analysis/scripts/model.R"),
+            &commit,
+            &names(),
+            None,
+            &git(),
+        );
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, PathBuf::from("analysis/renv.lock"));
+        // `git hash-object` of the lock file
+        assert_eq!(
+            files[0].blob,
+            gix::objs::compute_hash(gix::hash::Kind::Sha1, gix::objs::Kind::Blob, RENV_LOCK)
+                .unwrap()
+        );
+        assert!(!files[0].dirty);
+        assert_eq!(
+            environment_section(&files).unwrap(),
+            format!(
+                "## Environment\n* [analysis/renv.lock](https://github.com/owner/repo/blob/{LOCKED}/analysis/renv.lock) blob `{}`",
+                files[0].blob.to_hex_with_len(7)
+            )
+        );
+    }
+
+    #[test]
+    fn test_environment_file_absent_at_commit() {
+        let commit = ObjectId::from_str(UNLOCKED).unwrap();
+        let files = environment_files(
+            Path::new("analysis/model.R"),
+            &commit,
+            &names(),
+            None,
+            &git(),
+        );
+
+        assert!(files.is_empty());
+        assert_eq!(environment_section(&files), None);
+    }
+
+    #[test]
+    fn test_environment_file_modified_in_working_tree() {
+        let commit = ObjectId::from_str(LOCKED).unwrap();
+        let working_tree = tempfile::tempdir().unwrap();
+        std::fs::create_dir(working_tree.path().join("analysis")).unwrap();
+        let lock_path = working_tree.path().join("analysis/renv.lock");
+
+        std::fs::write(&lock_path, RENV_LOCK).unwrap();
+        let files = environment_files(
+            Path::new("analysis/model.R"),
+            &commit,
+            &names(),
+            Some(working_tree.path()),
+            &git(),
+        );
+        assert!(!files[0].dirty);
+
+        std::fs::write(&lock_path, b"{\"R\": {\"Version\": \"4.5.0\"}}\n").unwrap();
+        let files = environment_files(
+            Path::new("analysis/model.R"),
+            &commit,
+            &names(),
+            Some(working_tree.path()),
+            &git(),
+        );
+        assert!(files[0].dirty);
+        assert!(
+            environment_section(&files)
+                .unwrap()
+                .ends_with("⚠️ modified in the working tree")
+        );
+    }
+}
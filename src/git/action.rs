@@ -1,9 +1,12 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Output;
 
+use chrono::{DateTime, Utc};
 use gix::Url;
 
+use crate::git::signature::SigningKeys;
+
 #[cfg_attr(test, mockall::automock)]
 pub trait GitCli {
     /// Clone a repository from a URL to a local path
@@ -27,18 +30,59 @@ pub trait GitCli {
         file: &Path,
     ) -> Result<HashSet<String>, GitCliError>;
 
+    /// Every rename on `branch` (or HEAD if None) after `since`, oldest first, as
+    /// `(commit, old path, new path)`.
+    /// Uses `git log -M --diff-filter=R --name-status [since..]branch`.
+    fn renames(
+        &self,
+        branch: Option<String>,
+        since: Option<String>,
+    ) -> Result<Vec<(String, String, String)>, GitCliError>;
+
     fn branch_commits<'a>(
         &self,
         branch: Option<&'a str>,
         stop_at: Option<&'a str>,
     ) -> Result<Vec<(String, String)>, GitCliError>;
 
+    /// Every file on `branch` (or HEAD if None) with when it was last committed. Files last
+    /// changed before the cut-off of a shallow clone have no time.
+    /// Uses `git ls-tree -r --name-only` and `git log --format=%x00%ct --name-only`.
+    fn files_last_committed(
+        &self,
+        branch: Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitCliError>;
+
+    /// Whether `commit` carries a GPG or SSH signature (a `gpgsig` header).
+    fn commit_is_signed(&self, commit: &str) -> Result<bool, GitCliError>;
+
+    /// When `commit` was committed (the committer date).
+    fn commit_time(&self, commit: &str) -> Result<DateTime<Utc>, GitCliError>;
+
+    /// Full SHA of the commit a commit-ish (hash, branch, tag, `HEAD~2`, ...) points to.
+    fn rev_parse(&self, commitish: &str) -> Result<String, GitCliError>;
+
+    /// Run `git verify-commit` against the trusted `keys`. Returns whether the signature verifies.
+    fn verify_commit(&self, commit: &str, keys: &SigningKeys) -> Result<bool, GitCliError>;
+
+    /// Values of the named `.gitattributes` attributes for `file`, as reported by
+    /// `git check-attr`: `set`, `unset`, `unspecified` or the attribute's value.
+    fn check_attr(
+        &self,
+        file: &Path,
+        attributes: &[&'static str],
+    ) -> Result<HashMap<String, String>, GitCliError>;
+
     fn path(&self) -> &Path;
 
     /// Construct an instance rooted at `path`.
     fn new(path: &Path) -> Self;
 }
 
+/// A file with when it was last committed, `None` when that commit is missing from a shallow
+/// clone
+pub type FileLastCommit = (PathBuf, Option<DateTime<Utc>>);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StashFileOutcome {
     Stashed,
@@ -86,6 +130,13 @@ impl<T: GitCli + ?Sized> GitCli for &T {
     ) -> Result<HashSet<String>, GitCliError> {
         (**self).file_touching_commits(branch, file)
     }
+    fn renames(
+        &self,
+        branch: Option<String>,
+        since: Option<String>,
+    ) -> Result<Vec<(String, String, String)>, GitCliError> {
+        (**self).renames(branch, since)
+    }
     fn branch_commits(
         &self,
         branch: Option<&str>,
@@ -93,6 +144,31 @@ impl<T: GitCli + ?Sized> GitCli for &T {
     ) -> Result<Vec<(String, String)>, GitCliError> {
         (**self).branch_commits(branch, stop_at)
     }
+    fn files_last_committed(
+        &self,
+        branch: Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitCliError> {
+        (**self).files_last_committed(branch)
+    }
+    fn commit_is_signed(&self, commit: &str) -> Result<bool, GitCliError> {
+        (**self).commit_is_signed(commit)
+    }
+    fn commit_time(&self, commit: &str) -> Result<DateTime<Utc>, GitCliError> {
+        (**self).commit_time(commit)
+    }
+    fn rev_parse(&self, commitish: &str) -> Result<String, GitCliError> {
+        (**self).rev_parse(commitish)
+    }
+    fn verify_commit(&self, commit: &str, keys: &SigningKeys) -> Result<bool, GitCliError> {
+        (**self).verify_commit(commit, keys)
+    }
+    fn check_attr(
+        &self,
+        file: &Path,
+        attributes: &[&'static str],
+    ) -> Result<HashMap<String, String>, GitCliError> {
+        (**self).check_attr(file, attributes)
+    }
     fn path(&self) -> &Path {
         (**self).path()
     }
@@ -217,6 +293,35 @@ impl GitCli for GitCommand {
         Ok(hashes)
     }
 
+    fn renames(
+        &self,
+        branch: Option<String>,
+        since: Option<String>,
+    ) -> Result<Vec<(String, String, String)>, GitCliError> {
+        let branch = branch.as_deref().unwrap_or("HEAD");
+        let range = match since {
+            Some(since) => format!("{since}..{branch}"),
+            None => branch.to_string(),
+        };
+        log::debug!("Finding renames in {} of {}", range, self.path.display());
+
+        let output = self.run_git(&[
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "-M",
+            "--diff-filter=R",
+            "--name-status",
+            "--format=%x00%H",
+            &range,
+        ])?;
+
+        let mut renames = parse_renames(&String::from_utf8_lossy(&output.stdout));
+        // The log is newest first
+        renames.reverse();
+        Ok(renames)
+    }
+
     fn stash_file(&self, file: &Path, message: &str) -> Result<StashFileOutcome, GitCliError> {
         log::debug!(
             "Stashing file {} in {}",
@@ -278,6 +383,119 @@ impl GitCli for GitCommand {
         Ok(commits)
     }
 
+    fn files_last_committed(
+        &self,
+        branch: Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitCliError> {
+        let branch = branch.as_deref().unwrap_or("HEAD");
+        let tree = self.run_git(&[
+            "-c",
+            "core.quotePath=false",
+            "ls-tree",
+            "-r",
+            "--full-tree",
+            "--name-only",
+            branch,
+        ])?;
+        let log = self.run_git(&[
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--format=%x00%ct",
+            "--name-only",
+            branch,
+        ])?;
+
+        Ok(parse_files_last_committed(
+            &String::from_utf8_lossy(&tree.stdout),
+            &String::from_utf8_lossy(&log.stdout),
+        ))
+    }
+
+    fn commit_is_signed(&self, commit: &str) -> Result<bool, GitCliError> {
+        let output = self.run_git(&["cat-file", "commit", commit])?;
+
+        // Commit headers end at the first blank line; the signature is a header
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .take_while(|l| !l.is_empty())
+            .any(|l| l.starts_with("gpgsig")))
+    }
+
+    fn commit_time(&self, commit: &str) -> Result<DateTime<Utc>, GitCliError> {
+        let output = self.run_git(&["show", "-s", "--format=%cI", commit])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        DateTime::parse_from_rfc3339(stdout.trim())
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|e| {
+                GitCliError::GitCommandFailed(format!(
+                    "Invalid commit date '{}' for {commit}: {e}",
+                    stdout.trim()
+                ))
+            })
+    }
+
+    fn rev_parse(&self, commitish: &str) -> Result<String, GitCliError> {
+        let revision = format!("{commitish}^{{commit}}");
+        let output = self.run_git(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            "--end-of-options",
+            &revision,
+        ])?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn verify_commit(&self, commit: &str, keys: &SigningKeys) -> Result<bool, GitCliError> {
+        log::debug!(
+            "Verifying signature of {} in {}",
+            commit,
+            self.path.display()
+        );
+
+        let mut cmd = std::process::Command::new("git");
+        cmd.arg("-C").arg(&self.path);
+        if let Some(allowed_signers) = &keys.allowed_signers {
+            cmd.arg("-c").arg(format!(
+                "gpg.ssh.allowedSignersFile={}",
+                allowed_signers.display()
+            ));
+        }
+        if let Some(gpg_home) = &keys.gpg_home {
+            cmd.env("GNUPGHOME", gpg_home);
+        }
+        cmd.args(["verify-commit", commit]);
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            log::debug!(
+                "Signature of {} did not verify: {}",
+                commit,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(output.status.success())
+    }
+
+    fn check_attr(
+        &self,
+        file: &Path,
+        attributes: &[&str],
+    ) -> Result<HashMap<String, String>, GitCliError> {
+        let file_str = file.to_string_lossy();
+        let mut args = vec!["check-attr", "-z"];
+        args.extend_from_slice(attributes);
+        args.extend(["--", &file_str]);
+        let output = self.run_git(&args)?;
+
+        Ok(parse_check_attr_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
     fn path(&self) -> &Path {
         &self.path
     }
@@ -289,6 +507,63 @@ impl GitCli for GitCommand {
     }
 }
 
+/// Pair each file listed by `git ls-tree` with the time of the newest commit in the
+/// `git log --format=%x00%ct --name-only` output that touched it
+fn parse_files_last_committed(tree: &str, log: &str) -> Vec<FileLastCommit> {
+    let mut last_committed: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    let mut time = None;
+    for line in log.lines() {
+        if let Some(timestamp) = line.strip_prefix('\0') {
+            time = timestamp
+                .trim()
+                .parse()
+                .ok()
+                .and_then(|t| DateTime::from_timestamp(t, 0));
+        } else if !line.is_empty()
+            && let Some(time) = time
+        {
+            // The log is newest first, so the first commit seen for a file is its last
+            last_committed.entry(line).or_insert(time);
+        }
+    }
+
+    tree.lines()
+        .filter(|l| !l.is_empty())
+        .map(|file| (PathBuf::from(file), last_committed.get(file).copied()))
+        .collect()
+}
+
+/// Parse the `git log --format=%x00%H --name-status` output of rename commits into
+/// `(commit, old path, new path)`, in log order
+fn parse_renames(log: &str) -> Vec<(String, String, String)> {
+    let mut renames = Vec::new();
+    let mut commit = None;
+    for line in log.lines() {
+        if let Some(hash) = line.strip_prefix('\0') {
+            commit = Some(hash.trim().to_string());
+        } else if let Some(commit) = &commit
+            && line.starts_with('R')
+        {
+            // Tab-separated: R<score>\t<old>\t<new>
+            let parts: Vec<&str> = line.splitn(3, '\t').collect();
+            if parts.len() == 3 {
+                renames.push((commit.clone(), parts[1].to_string(), parts[2].to_string()));
+            }
+        }
+    }
+    renames
+}
+
+/// Parse `git check-attr -z` output, a sequence of NUL-terminated `path`, `attribute`
+/// and `info` fields, into attribute values
+fn parse_check_attr_output(stdout: &str) -> HashMap<String, String> {
+    let fields = stdout.split('\0').collect::<Vec<_>>();
+    fields
+        .chunks_exact(3)
+        .map(|chunk| (chunk[1].to_string(), chunk[2].to_string()))
+        .collect()
+}
+
 impl GitCommand {
     fn run_git(&self, args: &[&str]) -> Result<Output, GitCliError> {
         let output = std::process::Command::new("git")
@@ -0,0 +1,1374 @@
+//! GitLab backend for the [`GitHubReader`](super::GitHubReader) and
+//! [`GitHubWriter`](super::GitHubWriter) operations of a [`GitInfo`](crate::git::GitInfo) whose
+//! remote is a GitLab project.
+//!
+//! GitLab responses are translated to the octocrab models the rest of the crate works with:
+//! issues are numbered by their project-scoped `iid`, and milestones by their global `id`, which
+//! is what GitLab's milestone endpoints and `milestone_id` parameters expect.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::{Method, StatusCode};
+use octocrab::models::Milestone;
+use octocrab::models::issues::Issue;
+use serde_json::{Value, json};
+
+use super::write::{MilestoneState, MilestoneUpdate};
+use super::{GitComment, GitHubApiError, Permission, RepoUser, RequestTarget};
+use crate::auth::AuthStore;
+use crate::git::helpers::GitRemote;
+use crate::usage;
+use crate::utils::EnvProvider;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Environment variables a GitLab token is read from, in priority order
+pub(crate) const GITLAB_TOKEN_VARS: [&str; 2] = ["GHQC_GITLAB_TOKEN", "GITLAB_TOKEN"];
+
+const PER_PAGE: usize = 100;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// A failed GitLab API request
+#[derive(Debug, thiserror::Error)]
+pub enum GitLabError {
+    #[error("{}", super::response_summary(*status, message))]
+    Status { status: StatusCode, message: String },
+    #[error("{0}")]
+    Transport(String),
+    #[error("Unexpected response from GitLab: {0}")]
+    Decode(String),
+}
+
+impl GitLabError {
+    /// The HTTP status GitLab responded with, when it responded at all
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// The HTTP layer of the GitLab backend
+#[cfg_attr(test, automock)]
+pub trait GitLabTransport: Send + Sync {
+    /// Send a request to the GitLab REST API, `path` being relative to `/api/v4`
+    fn send(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value, GitLabError>;
+}
+
+struct UreqTransport {
+    agent: ureq::Agent,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl GitLabTransport for UreqTransport {
+    fn send(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value, GitLabError> {
+        let mut request = self
+            .agent
+            .request(method.as_str(), &format!("{}{path}", self.api_url));
+        if let Some(token) = &self.token {
+            request = request.set("PRIVATE-TOKEN", token);
+        }
+
+        let response = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+
+        match response {
+            Ok(response) => response
+                .into_json()
+                .map_err(|e| GitLabError::Decode(e.to_string())),
+            Err(ureq::Error::Status(code, response)) => {
+                let status = StatusCode::from_u16(code).unwrap_or(StatusCode::BAD_GATEWAY);
+                let message = response
+                    .into_json::<Value>()
+                    .ok()
+                    .map(|body| error_message(&body))
+                    .unwrap_or_default();
+                Err(GitLabError::Status { status, message })
+            }
+            Err(e) => Err(GitLabError::Transport(e.to_string())),
+        }
+    }
+}
+
+/// The message of a GitLab error response, e.g. `{"message": "404 Project Not Found"}` or
+/// `{"error": "insufficient_scope", "error_description": "..."}`
+fn error_message(body: &Value) -> String {
+    match body.get("message").or_else(|| body.get("error")) {
+        Some(Value::String(message)) => message.clone(),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    }
+}
+
+/// The GitLab token for `base_url`: from `GHQC_GITLAB_TOKEN` or `GITLAB_TOKEN`, falling back to the
+/// ghqc auth store
+fn gitlab_token(
+    base_url: &str,
+    env: &impl EnvProvider,
+    auth_store: Option<&AuthStore>,
+) -> Option<String> {
+    GITLAB_TOKEN_VARS
+        .iter()
+        .find_map(|var| env.var(var).ok().filter(|token| !token.trim().is_empty()))
+        .or_else(|| {
+            let host = base_url.trim_start_matches("https://");
+            auth_store?.token(host).map(str::to_string)
+        })
+}
+
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Client for the GitLab project of a remote
+#[derive(Clone)]
+pub(crate) struct GitLabApi {
+    /// `https://host/api/v4`
+    api_url: String,
+    /// `https://host/group/project`
+    web_url: String,
+    /// The URL-encoded project path, as GitLab expects in `/projects/:id`
+    project_id: String,
+    transport: Arc<dyn GitLabTransport>,
+}
+
+impl std::fmt::Debug for GitLabApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitLabApi")
+            .field("api_url", &self.api_url)
+            .field("project_id", &self.project_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GitLabApi {
+    pub(crate) fn new(
+        remote: &GitRemote,
+        env: &impl EnvProvider,
+        auth_store: Option<&AuthStore>,
+    ) -> Self {
+        let token = gitlab_token(&remote.url, env, auth_store);
+        if token.is_none() {
+            log::warn!(
+                "No GitLab token found in {}. API access will be limited to public projects",
+                GITLAB_TOKEN_VARS.join(" or ")
+            );
+        }
+
+        let transport = UreqTransport {
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(CONNECT_TIMEOUT)
+                .timeout_read(READ_TIMEOUT)
+                .build(),
+            api_url: format!("{}/api/v4", remote.url),
+            token,
+        };
+        Self::with_transport(remote, Arc::new(transport))
+    }
+
+    pub(crate) fn with_transport(remote: &GitRemote, transport: Arc<dyn GitLabTransport>) -> Self {
+        Self {
+            api_url: format!("{}/api/v4", remote.url),
+            web_url: format!("{}/{}/{}", remote.url, remote.owner, remote.repo),
+            project_id: encode(&format!("{}/{}", remote.owner, remote.repo)),
+            transport,
+        }
+    }
+
+    fn project(&self, rest: &str) -> String {
+        format!("/projects/{}{rest}", self.project_id)
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: String,
+        body: Option<Value>,
+    ) -> Result<Value, GitLabError> {
+        let transport = self.transport.clone();
+        tokio::task::spawn_blocking(move || transport.send(method, &path, body))
+            .await
+            .map_err(|e| GitLabError::Transport(e.to_string()))?
+    }
+
+    /// Fetch all pages of a list endpoint
+    async fn get_all(&self, path: &str) -> Result<Vec<Value>, GitLabError> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        for page in 1..=100 {
+            let response = self
+                .request(
+                    Method::GET,
+                    format!("{path}{separator}per_page={PER_PAGE}&page={page}"),
+                    None,
+                )
+                .await?;
+            let Value::Array(page_items) = response else {
+                return Err(GitLabError::Decode(format!("expected a list from {path}")));
+            };
+
+            let count = page_items.len();
+            items.extend(page_items);
+            if count < PER_PAGE {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// The id of the user with `username`, if there is one
+    async fn user_id(&self, username: &str) -> Result<Option<u64>, GitLabError> {
+        let users = self
+            .request(
+                Method::GET,
+                format!("/users?username={}", encode(username)),
+                None,
+            )
+            .await?;
+        Ok(users
+            .get(0)
+            .and_then(|user| user.get("id"))
+            .and_then(Value::as_u64))
+    }
+
+    pub(crate) async fn get_milestones(&self) -> Result<Vec<Milestone>, GitHubApiError> {
+        log::debug!("Fetching GitLab milestones for {}", self.web_url);
+        let ctx = || GitHubApiError::gitlab_with_ctx("get_milestones", None);
+        self.get_all(&self.project("/milestones"))
+            .await
+            .map_err(ctx())?
+            .iter()
+            .map(|milestone| self.milestone(milestone))
+            .collect::<Result<_, _>>()
+            .map_err(ctx())
+    }
+
+    pub(crate) async fn get_issues(
+        &self,
+        milestone: Option<u64>,
+    ) -> Result<Vec<Issue>, GitHubApiError> {
+        log::debug!("Fetching GitLab issues for {}", self.web_url);
+        let ctx = || {
+            GitHubApiError::gitlab_with_ctx("get_issues", milestone.map(RequestTarget::Milestone))
+        };
+        // The project issue list cannot filter by milestone id, the milestone's own list cannot
+        // filter by label
+        let issues = match milestone {
+            Some(id) => self
+                .get_all(&self.project(&format!("/milestones/{id}/issues")))
+                .await
+                .map_err(ctx())?
+                .into_iter()
+                .filter(|issue| has_label(issue, "ghqc"))
+                .collect(),
+            None => self
+                .get_all(&self.project("/issues?labels=ghqc&scope=all"))
+                .await
+                .map_err(ctx())?,
+        };
+
+        issues
+            .iter()
+            .map(|issue| self.issue(issue))
+            .collect::<Result<_, _>>()
+            .map_err(ctx())
+    }
+
+    pub(crate) async fn get_issue(&self, issue_number: u64) -> Result<Issue, GitHubApiError> {
+        let ctx =
+            || GitHubApiError::gitlab_with_ctx("get_issue", RequestTarget::Issue(issue_number));
+        let issue = self
+            .request(
+                Method::GET,
+                self.project(&format!("/issues/{issue_number}")),
+                None,
+            )
+            .await
+            .map_err(ctx())?;
+        self.issue(&issue).map_err(ctx())
+    }
+
+    pub(crate) async fn get_assignees(&self) -> Result<Vec<String>, GitHubApiError> {
+        let members = self
+            .get_all(&self.project("/members/all"))
+            .await
+            .map_err(GitHubApiError::gitlab_with_ctx("get_assignees", None))?;
+        Ok(members.iter().filter_map(username).collect())
+    }
+
+    pub(crate) async fn get_user_details(
+        &self,
+        username: &str,
+    ) -> Result<RepoUser, GitHubApiError> {
+        let name = match self
+            .request(
+                Method::GET,
+                format!("/users?username={}", encode(username)),
+                None,
+            )
+            .await
+        {
+            Ok(users) => users
+                .get(0)
+                .and_then(|user| user.get("name"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            Err(e) => {
+                log::warn!(
+                    "Failed to fetch user details for {}: {}, using login only",
+                    username,
+                    e
+                );
+                None
+            }
+        };
+
+        Ok(RepoUser {
+            login: username.to_string(),
+            name,
+        })
+    }
+
+    pub(crate) async fn get_user_permission(
+        &self,
+        login: &str,
+    ) -> Result<Permission, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_with_ctx(
+                "get_user_permission",
+                RequestTarget::User(login.to_string()),
+            )
+        };
+        let Some(user_id) = self.user_id(login).await.map_err(ctx())? else {
+            return Ok(Permission::None);
+        };
+
+        match self
+            .request(
+                Method::GET,
+                self.project(&format!("/members/all/{user_id}")),
+                None,
+            )
+            .await
+        {
+            Ok(member) => Ok(member
+                .get("access_level")
+                .and_then(Value::as_u64)
+                .map_or(Permission::None, permission_from_access_level)),
+            Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+                log::debug!("{} is not a member of {}", login, self.web_url);
+                Ok(Permission::None)
+            }
+            Err(e) => Err(ctx()(e)),
+        }
+    }
+
+    /// Members of the GitLab subgroup `org/team_slug`
+    pub(crate) async fn get_team_members(
+        &self,
+        org: &str,
+        team_slug: &str,
+    ) -> Result<Vec<String>, GitHubApiError> {
+        let members = self
+            .get_all(&format!(
+                "/groups/{}/members/all",
+                encode(&format!("{org}/{team_slug}"))
+            ))
+            .await
+            .map_err(GitHubApiError::gitlab_with_ctx(
+                "get_team_members",
+                RequestTarget::Team {
+                    org: org.to_string(),
+                    slug: team_slug.to_string(),
+                },
+            ))?;
+        Ok(members.iter().filter_map(username).collect())
+    }
+
+    pub(crate) async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
+        let labels = self
+            .get_all(&self.project("/labels"))
+            .await
+            .map_err(GitHubApiError::gitlab_with_ctx("get_labels", None))?;
+        Ok(labels
+            .iter()
+            .filter_map(|label| label.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// The notes of an issue, leaving out the system notes GitLab records for issue events
+    pub(crate) async fn get_issue_comments(
+        &self,
+        issue_number: u64,
+    ) -> Result<Vec<GitComment>, GitHubApiError> {
+        let notes = self
+            .get_all(&self.project(&format!(
+                "/issues/{issue_number}/notes?sort=asc&order_by=created_at"
+            )))
+            .await
+            .map_err(GitHubApiError::gitlab_with_ctx(
+                "get_issue_comments",
+                RequestTarget::Issue(issue_number),
+            ))?;
+
+        Ok(notes
+            .iter()
+            .filter(|note| !note.get("system").and_then(Value::as_bool).unwrap_or(false))
+            .filter_map(|note| {
+                Some(GitComment {
+                    body: note.get("body")?.as_str()?.to_string(),
+                    author_login: note
+                        .get("author")
+                        .and_then(username)
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    created_at: note
+                        .get("created_at")
+                        .and_then(Value::as_str)
+                        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                        .map(|dt| dt.with_timezone(&chrono::Utc))
+                        .unwrap_or_else(chrono::Utc::now),
+                    html: None,
+                })
+            })
+            .collect())
+    }
+
+    /// The state, label and milestone events of an issue, shaped like GitHub's issue events
+    pub(crate) async fn get_issue_events(
+        &self,
+        issue_number: u64,
+    ) -> Result<Vec<Value>, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_with_ctx("get_issue_events", RequestTarget::Issue(issue_number))
+        };
+        let mut events = Vec::new();
+        for kind in ["state", "label", "milestone"] {
+            let resource_events = self
+                .get_all(&self.project(&format!("/issues/{issue_number}/resource_{kind}_events")))
+                .await
+                .map_err(ctx())?;
+            events.extend(resource_events.iter().filter_map(github_event));
+        }
+
+        events.sort_by(|a, b| {
+            let created_at = |event: &Value| event["created_at"].as_str().map(str::to_string);
+            created_at(a).cmp(&created_at(b))
+        });
+        Ok(events)
+    }
+
+    /// Issues with a `blocks` link from the issue. Linking issues as blocking requires GitLab
+    /// Premium, so a rejected request is reported as [`GitHubApiError::NoApi`].
+    pub(crate) async fn get_blocked_issues(
+        &self,
+        issue_number: u64,
+    ) -> Result<Vec<Issue>, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_with_ctx(
+                "get_blocked_issues",
+                RequestTarget::Issue(issue_number),
+            )
+        };
+        let links = match self
+            .request(
+                Method::GET,
+                self.project(&format!("/issues/{issue_number}/links")),
+                None,
+            )
+            .await
+        {
+            Ok(Value::Array(links)) => links,
+            Ok(_) => {
+                return Err(ctx()(GitLabError::Decode(
+                    "expected a list of links".into(),
+                )));
+            }
+            Err(e)
+                if matches!(
+                    e.status(),
+                    Some(StatusCode::NOT_FOUND | StatusCode::FORBIDDEN)
+                ) =>
+            {
+                return Err(GitHubApiError::NoApi);
+            }
+            Err(e) => return Err(ctx()(e)),
+        };
+
+        let issue_prefix = format!("{}/-/issues/", self.web_url);
+        links
+            .iter()
+            .filter(|link| link.get("link_type").and_then(Value::as_str) == Some("blocks"))
+            .filter(|link| {
+                link.get("web_url")
+                    .and_then(Value::as_str)
+                    .is_some_and(|url| url.starts_with(&issue_prefix))
+            })
+            .map(|link| self.issue(link))
+            .collect::<Result<_, _>>()
+            .map_err(ctx())
+    }
+
+    pub(crate) async fn get_current_user(&self) -> Result<Option<String>, GitHubApiError> {
+        match self.request(Method::GET, "/user".to_string(), None).await {
+            Ok(user) => Ok(username(&user)),
+            Err(e) => {
+                log::debug!("Could not fetch current user (unauthenticated?): {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    pub(crate) async fn create_milestone(
+        &self,
+        milestone_name: &str,
+        description: Option<String>,
+    ) -> Result<Milestone, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_write_with_ctx(
+                "create_milestone",
+                RequestTarget::MilestoneTitle(milestone_name.to_string()),
+            )
+        };
+        let mut request = json!({ "title": milestone_name });
+        if let Some(description) = description {
+            request["description"] = Value::String(description);
+        }
+
+        let milestone = self
+            .request(Method::POST, self.project("/milestones"), Some(request))
+            .await
+            .map_err(ctx())?;
+        usage::count_write("milestones_created");
+        self.milestone(&milestone).map_err(ctx())
+    }
+
+    pub(crate) async fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> Result<Milestone, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_write_with_ctx(
+                "update_milestone_title",
+                RequestTarget::Milestone(milestone_number),
+            )
+        };
+        let milestone = self
+            .request(
+                Method::PUT,
+                self.project(&format!("/milestones/{milestone_number}")),
+                Some(json!({ "title": new_title })),
+            )
+            .await
+            .map_err(ctx())?;
+        usage::count_write("milestones_renamed");
+        self.milestone(&milestone).map_err(ctx())
+    }
+
+    pub(crate) async fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &MilestoneUpdate,
+    ) -> Result<Milestone, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_write_with_ctx(
+                "update_milestone",
+                RequestTarget::Milestone(milestone_number),
+            )
+        };
+        let mut request = serde_json::Map::new();
+        if let Some(title) = &update.title {
+            request.insert("title".to_string(), json!(title));
+        }
+        if let Some(description) = &update.description {
+            request.insert("description".to_string(), json!(description));
+        }
+        if let Some(due_on) = update.due_on {
+            request.insert(
+                "due_date".to_string(),
+                json!(due_on.format("%Y-%m-%d").to_string()),
+            );
+        }
+        if let Some(state) = update.state {
+            let state_event = match state {
+                MilestoneState::Open => "activate",
+                MilestoneState::Closed => "close",
+            };
+            request.insert("state_event".to_string(), json!(state_event));
+        }
+        let milestone = self
+            .request(
+                Method::PUT,
+                self.project(&format!("/milestones/{milestone_number}")),
+                Some(Value::Object(request)),
+            )
+            .await
+            .map_err(ctx())?;
+        usage::count_write("milestones_updated");
+        self.milestone(&milestone).map_err(ctx())
+    }
+
+    pub(crate) async fn post_issue(
+        &self,
+        title: String,
+        body: String,
+        milestone_id: u64,
+        labels: Vec<String>,
+        assignees: Vec<String>,
+    ) -> Result<Issue, GitHubApiError> {
+        let ctx = || {
+            GitHubApiError::gitlab_write_with_ctx(
+                "post_issue",
+                RequestTarget::Milestone(milestone_id),
+            )
+        };
+
+        let mut assignee_ids = Vec::new();
+        for assignee in &assignees {
+            match self.user_id(assignee).await.map_err(ctx())? {
+                Some(id) => assignee_ids.push(id),
+                None => log::warn!("GitLab user {} not found, not assigning them", assignee),
+            }
+        }
+
+        let request = json!({
+            "title": title,
+            "description": body,
+            "milestone_id": milestone_id,
+            "labels": labels.join(","),
+            "assignee_ids": assignee_ids,
+        });
+        let issue = self
+            .request(Method::POST, self.project("/issues"), Some(request))
+            .await
+            .map_err(ctx())?;
+        usage::count_write("issues_created");
+        self.issue(&issue).map_err(ctx())
+    }
+
+    /// Post `body` as a note on the issue, returning the note's URL. GitLab notes hold up to a
+    /// million characters, so the body is not split like on GitHub.
+    pub(crate) async fn post_comment(
+        &self,
+        issue_number: u64,
+        body: String,
+    ) -> Result<String, GitHubApiError> {
+        let note = self
+            .request(
+                Method::POST,
+                self.project(&format!("/issues/{issue_number}/notes")),
+                Some(json!({ "body": body })),
+            )
+            .await
+            .map_err(GitHubApiError::gitlab_write_with_ctx(
+                "post_comment",
+                RequestTarget::Issue(issue_number),
+            ))?;
+        usage::count_write("comments_posted");
+
+        let note_id = note.get("id").and_then(Value::as_u64).unwrap_or_default();
+        Ok(format!(
+            "{}/-/issues/{issue_number}#note_{note_id}",
+            self.web_url
+        ))
+    }
+
+    /// Edit an issue, as `operation` of the [`GitHubWriter`](super::GitHubWriter)
+    async fn edit_issue(
+        &self,
+        operation: &'static str,
+        issue_number: u64,
+        edit: Value,
+    ) -> Result<(), GitHubApiError> {
+        self.request(
+            Method::PUT,
+            self.project(&format!("/issues/{issue_number}")),
+            Some(edit),
+        )
+        .await
+        .map_err(GitHubApiError::gitlab_write_with_ctx(
+            operation,
+            RequestTarget::Issue(issue_number),
+        ))?;
+        Ok(())
+    }
+
+    pub(crate) async fn close_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.edit_issue(
+            "close_issue",
+            issue_number,
+            json!({ "state_event": "close" }),
+        )
+        .await?;
+        usage::count_write("issues_closed");
+        Ok(())
+    }
+
+    pub(crate) async fn open_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.edit_issue(
+            "open_issue",
+            issue_number,
+            json!({ "state_event": "reopen" }),
+        )
+        .await?;
+        usage::count_write("issues_reopened");
+        Ok(())
+    }
+
+    pub(crate) async fn create_label(&self, name: &str, color: &str) -> Result<(), GitHubApiError> {
+        self.request(
+            Method::POST,
+            self.project("/labels"),
+            Some(json!({ "name": name, "color": format!("#{}", color.trim_start_matches('#')) })),
+        )
+        .await
+        .map_err(GitHubApiError::gitlab_write_with_ctx(
+            "create_label",
+            RequestTarget::Label(name.to_string()),
+        ))?;
+        Ok(())
+    }
+
+    pub(crate) async fn add_labels(
+        &self,
+        issue_number: u64,
+        labels: &[String],
+    ) -> Result<(), GitHubApiError> {
+        self.edit_issue(
+            "add_labels",
+            issue_number,
+            json!({ "add_labels": labels.join(",") }),
+        )
+        .await
+    }
+
+    pub(crate) async fn remove_label(
+        &self,
+        issue_number: u64,
+        label: &str,
+    ) -> Result<(), GitHubApiError> {
+        self.edit_issue(
+            "remove_label",
+            issue_number,
+            json!({ "remove_labels": label }),
+        )
+        .await
+    }
+
+    pub(crate) async fn set_assignees(
+        &self,
+        issue_number: u64,
+        assignees: &[String],
+    ) -> Result<(), GitHubApiError> {
+        let mut assignee_ids = Vec::new();
+        for assignee in assignees {
+            match self
+                .user_id(assignee)
+                .await
+                .map_err(GitHubApiError::gitlab_write_with_ctx(
+                    "set_assignees",
+                    RequestTarget::Issue(issue_number),
+                ))? {
+                Some(id) => assignee_ids.push(id),
+                None => log::warn!("GitLab user {} not found, not assigning them", assignee),
+            }
+        }
+        self.edit_issue(
+            "set_assignees",
+            issue_number,
+            json!({ "assignee_ids": assignee_ids }),
+        )
+        .await
+    }
+
+    pub(crate) async fn update_issue(
+        &self,
+        issue_number: u64,
+        new_title: Option<String>,
+        new_body: Option<String>,
+    ) -> Result<(), GitHubApiError> {
+        let mut edit = json!({});
+        if let Some(title) = new_title {
+            edit["title"] = Value::String(title);
+        }
+        if let Some(body) = new_body {
+            edit["description"] = Value::String(body);
+        }
+        self.edit_issue("update_issue", issue_number, edit).await
+    }
+
+    /// A GitLab user as a GitHub author
+    fn author(&self, user: &Value) -> Value {
+        let login = username(user).unwrap_or_else(|| "unknown".to_string());
+        let web_url = user
+            .get("web_url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}/{login}", self.web_url));
+        let avatar_url = user
+            .get("avatar_url")
+            .and_then(Value::as_str)
+            .unwrap_or(&web_url)
+            .to_string();
+
+        json!({
+            "login": login,
+            "id": user.get("id").and_then(Value::as_u64).unwrap_or_default(),
+            "node_id": "",
+            "avatar_url": avatar_url,
+            "gravatar_id": "",
+            "url": web_url,
+            "html_url": web_url,
+            "followers_url": web_url,
+            "following_url": web_url,
+            "gists_url": web_url,
+            "starred_url": web_url,
+            "subscriptions_url": web_url,
+            "organizations_url": web_url,
+            "repos_url": web_url,
+            "events_url": web_url,
+            "received_events_url": web_url,
+            "type": "User",
+            "site_admin": false,
+            "name": user.get("name"),
+            "patch_url": null,
+        })
+    }
+
+    fn milestone_value(&self, milestone: &Value) -> Result<Value, GitLabError> {
+        let id = milestone
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| GitLabError::Decode("milestone without an id".to_string()))?;
+        let state = match milestone.get("state").and_then(Value::as_str) {
+            Some("closed") => "closed",
+            _ => "open",
+        };
+        let html_url = milestone
+            .get("web_url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}/-/milestones/{id}", self.web_url));
+        let due_on = milestone
+            .get("due_date")
+            .and_then(Value::as_str)
+            .map(|date| format!("{date}T00:00:00Z"));
+
+        Ok(json!({
+            "url": format!("{}{}", self.api_url, self.project(&format!("/milestones/{id}"))),
+            "html_url": html_url,
+            "id": id,
+            "node_id": "",
+            "number": id,
+            "state": state,
+            "title": milestone.get("title"),
+            "description": milestone.get("description"),
+            "created_at": milestone.get("created_at"),
+            "updated_at": milestone.get("updated_at"),
+            "due_on": due_on,
+        }))
+    }
+
+    fn milestone(&self, milestone: &Value) -> Result<Milestone, GitLabError> {
+        serde_json::from_value(self.milestone_value(milestone)?)
+            .map_err(|e| GitLabError::Decode(format!("milestone: {e}")))
+    }
+
+    fn issue(&self, issue: &Value) -> Result<Issue, GitLabError> {
+        let iid = issue
+            .get("iid")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| GitLabError::Decode("issue without an iid".to_string()))?;
+        let url = format!(
+            "{}{}",
+            self.api_url,
+            self.project(&format!("/issues/{iid}"))
+        );
+        let html_url = issue
+            .get("web_url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}/-/issues/{iid}", self.web_url));
+        let state = match issue.get("state").and_then(Value::as_str) {
+            Some("closed") => "closed",
+            _ => "open",
+        };
+        let labels: Vec<Value> = issue
+            .get("labels")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(|name| {
+                json!({
+                    "id": 0,
+                    "node_id": "",
+                    "url": format!("{url}/labels"),
+                    "name": name,
+                    "color": "",
+                    "default": false,
+                })
+            })
+            .collect();
+        let assignees: Vec<Value> = issue
+            .get("assignees")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|user| self.author(user))
+            .collect();
+        let milestone = match issue.get("milestone") {
+            Some(milestone) if !milestone.is_null() => Some(self.milestone_value(milestone)?),
+            _ => None,
+        };
+        let user = self.author(issue.get("author").unwrap_or(&Value::Null));
+        let closed_by = issue
+            .get("closed_by")
+            .filter(|user| !user.is_null())
+            .map(|user| self.author(user));
+
+        let github_issue = json!({
+            "id": issue.get("id").and_then(Value::as_u64).unwrap_or_default(),
+            "node_id": "",
+            "url": url,
+            "repository_url": format!("{}{}", self.api_url, self.project("")),
+            "labels_url": format!("{url}/labels"),
+            "comments_url": format!("{url}/notes"),
+            "events_url": format!("{url}/resource_state_events"),
+            "html_url": html_url,
+            "number": iid,
+            "state": state,
+            "state_reason": null,
+            "title": issue.get("title"),
+            "body": issue.get("description"),
+            "user": user,
+            "labels": labels,
+            "assignee": assignees.first(),
+            "assignees": assignees,
+            "milestone": milestone,
+            "locked": issue.get("discussion_locked").and_then(Value::as_bool).unwrap_or(false),
+            "comments": issue.get("user_notes_count").and_then(Value::as_u64).unwrap_or_default(),
+            "closed_at": issue.get("closed_at"),
+            "closed_by": closed_by,
+            "created_at": issue.get("created_at"),
+            "updated_at": issue.get("updated_at"),
+        });
+
+        serde_json::from_value(github_issue)
+            .map_err(|e| GitLabError::Decode(format!("issue #{iid}: {e}")))
+    }
+}
+
+fn username(user: &Value) -> Option<String> {
+    user.get("username")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn has_label(issue: &Value, label: &str) -> bool {
+    issue
+        .get("labels")
+        .and_then(Value::as_array)
+        .is_some_and(|labels| labels.iter().any(|l| l.as_str() == Some(label)))
+}
+
+/// Map a GitLab member access level to the closest GitHub repository role. Reporters can close
+/// issues on GitLab, like GitHub's triage role.
+fn permission_from_access_level(level: u64) -> Permission {
+    match level {
+        50.. => Permission::Admin,
+        40..50 => Permission::Maintain,
+        30..40 => Permission::Write,
+        20..30 => Permission::Triage,
+        10..20 => Permission::Read,
+        _ => Permission::None,
+    }
+}
+
+/// A GitLab resource event as a GitHub issue event, e.g. a `closed` state event or an `add`
+/// label event as `labeled`
+fn github_event(event: &Value) -> Option<Value> {
+    let mut github = json!({
+        "actor": { "login": event.get("user").and_then(username) },
+        "created_at": event.get("created_at"),
+    });
+
+    let action = event.get("action").and_then(Value::as_str);
+    if let Some(state) = event.get("state").and_then(Value::as_str) {
+        github["event"] = json!(state);
+    } else if let Some(label) = event.get("label").filter(|label| !label.is_null()) {
+        github["event"] = json!(if action == Some("remove") {
+            "unlabeled"
+        } else {
+            "labeled"
+        });
+        github["label"] = json!({ "name": label.get("name") });
+    } else if let Some(milestone) = event.get("milestone").filter(|m| !m.is_null()) {
+        github["event"] = json!(if action == Some("remove") {
+            "demilestoned"
+        } else {
+            "milestoned"
+        });
+        github["milestone"] = json!({ "title": milestone.get("title") });
+    } else {
+        return None;
+    }
+    Some(github)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comment_system::CommentBody;
+    use crate::git::helpers::Forge;
+    use crate::git::{
+        AuthSources, GitCommand, GitFileOps, GitHelpers, GitHubReader, GitHubWriter, GitInfo,
+    };
+    use crate::utils::MockEnvProvider;
+    use std::path::PathBuf;
+
+    fn remote() -> GitRemote {
+        GitRemote {
+            owner: "qc/pk".to_string(),
+            repo: "models".to_string(),
+            url: "https://gitlab.example.com".to_string(),
+            forge: Forge::GitLab,
+        }
+    }
+
+    fn git_info(transport: MockGitLabTransport) -> GitInfo {
+        let remote = remote();
+        GitInfo {
+            owner: remote.owner.clone(),
+            repo: remote.repo.clone(),
+            base_url: remote.url.clone(),
+            repository_path: PathBuf::from("."),
+            auth_sources: AuthSources::default(),
+            remote_name: "origin".to_string(),
+            trust_remote_mismatch: false,
+            follow_renames: true,
+            command: GitCommand {
+                path: PathBuf::from("."),
+            },
+            gitlab: Some(GitLabApi::with_transport(&remote, Arc::new(transport))),
+        }
+    }
+
+    fn gitlab_user(username: &str) -> Value {
+        json!({
+            "id": 7,
+            "username": username,
+            "name": "Jane Doe",
+            "avatar_url": null,
+            "web_url": format!("https://gitlab.example.com/{username}"),
+        })
+    }
+
+    fn gitlab_milestone() -> Value {
+        json!({
+            "id": 412,
+            "iid": 2,
+            "title": "Round 1",
+            "description": null,
+            "state": "active",
+            "created_at": "2025-01-06T09:00:00.000Z",
+            "updated_at": "2025-01-06T09:00:00.000Z",
+            "due_date": "2025-02-01",
+            "web_url": "https://gitlab.example.com/qc/pk/models/-/milestones/2",
+        })
+    }
+
+    fn gitlab_issue(iid: u64, labels: &[&str], state: &str) -> Value {
+        json!({
+            "id": 9000 + iid,
+            "iid": iid,
+            "title": "scripts/model.R",
+            "description": "## Metadata\n* author: jane",
+            "state": state,
+            "labels": labels,
+            "author": gitlab_user("jane"),
+            "assignees": [gitlab_user("reviewer")],
+            "milestone": gitlab_milestone(),
+            "user_notes_count": 1,
+            "discussion_locked": null,
+            "closed_at": null,
+            "closed_by": null,
+            "created_at": "2025-01-06T09:00:00.000Z",
+            "updated_at": "2025-01-07T09:00:00.000Z",
+            "web_url": format!("https://gitlab.example.com/qc/pk/models/-/issues/{iid}"),
+        })
+    }
+
+    /// Expect a single request, answering it with `response`
+    fn expect(
+        transport: &mut MockGitLabTransport,
+        method: Method,
+        path: &'static str,
+        body: Option<Value>,
+        response: Value,
+    ) {
+        transport
+            .expect_send()
+            .withf(move |m, p, b| *m == method && p == path && *b == body)
+            .times(1)
+            .returning(move |_, _, _| Ok(response.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_get_milestones_and_issues() {
+        let mut transport = MockGitLabTransport::new();
+        expect(
+            &mut transport,
+            Method::GET,
+            "/projects/qc%2Fpk%2Fmodels/milestones?per_page=100&page=1",
+            None,
+            json!([gitlab_milestone()]),
+        );
+        expect(
+            &mut transport,
+            Method::GET,
+            "/projects/qc%2Fpk%2Fmodels/milestones/412/issues?per_page=100&page=1",
+            None,
+            json!([
+                gitlab_issue(3, &["ghqc"], "opened"),
+                gitlab_issue(4, &["bug"], "opened"),
+                gitlab_issue(5, &["ghqc", "ghqc-approved"], "closed"),
+            ]),
+        );
+        let git_info = git_info(transport);
+
+        let milestones = git_info.get_milestones().await.unwrap();
+        assert_eq!(milestones.len(), 1);
+        assert_eq!(milestones[0].number, 412);
+        assert_eq!(milestones[0].title, "Round 1");
+        assert_eq!(milestones[0].state.as_deref(), Some("open"));
+
+        // Issues without the ghqc label are left out
+        let issues = git_info.get_issues(Some(412)).await.unwrap();
+        let numbers: Vec<u64> = issues.iter().map(|issue| issue.number).collect();
+        assert_eq!(numbers, vec![3, 5]);
+
+        let issue = &issues[0];
+        assert_eq!(issue.id.0, 9003);
+        assert_eq!(issue.title, "scripts/model.R");
+        assert_eq!(issue.body.as_deref(), Some("## Metadata\n* author: jane"));
+        assert_eq!(issue.user.login, "jane");
+        assert_eq!(issue.assignees[0].login, "reviewer");
+        assert_eq!(issue.milestone.as_ref().unwrap().number, 412);
+        assert_eq!(issue.state, octocrab::models::IssueState::Open);
+        assert_eq!(issues[1].state, octocrab::models::IssueState::Closed);
+        assert_eq!(
+            issues[1]
+                .labels
+                .iter()
+                .map(|label| label.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["ghqc", "ghqc-approved"]
+        );
+        // The issue belongs to the remote's repository
+        assert_eq!(issue.html_url.as_str(), git_info.issue_url(3));
+    }
+
+    #[tokio::test]
+    async fn test_get_issue_comments_skips_system_notes() {
+        let mut transport = MockGitLabTransport::new();
+        let note = |body: &str, system: bool| {
+            json!({
+                "id": 1,
+                "body": body,
+                "author": gitlab_user("reviewer"),
+                "system": system,
+                "created_at": "2025-01-08T10:30:00.000Z",
+            })
+        };
+        expect(
+            &mut transport,
+            Method::GET,
+            "/projects/qc%2Fpk%2Fmodels/issues/3/notes?sort=asc&order_by=created_at&per_page=100&page=1",
+            None,
+            json!([note("# QC Notification", false), note("closed", true)]),
+        );
+        let git_info = git_info(transport);
+        let issue = GitLabApi::with_transport(&remote(), Arc::new(MockGitLabTransport::new()))
+            .issue(&gitlab_issue(3, &["ghqc"], "opened"))
+            .unwrap();
+
+        let comments = git_info.get_issue_comments(&issue).await.unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].body, "# QC Notification");
+        assert_eq!(comments[0].author_login, "reviewer");
+        assert_eq!(
+            comments[0].created_at.to_rfc3339(),
+            "2025-01-08T10:30:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_user_permission() {
+        let mut transport = MockGitLabTransport::new();
+        expect(
+            &mut transport,
+            Method::GET,
+            "/users?username=reviewer",
+            None,
+            json!([gitlab_user("reviewer")]),
+        );
+        expect(
+            &mut transport,
+            Method::GET,
+            "/projects/qc%2Fpk%2Fmodels/members/all/7",
+            None,
+            json!({ "id": 7, "username": "reviewer", "access_level": 20 }),
+        );
+        expect(
+            &mut transport,
+            Method::GET,
+            "/users?username=nobody",
+            None,
+            json!([]),
+        );
+        let git_info = git_info(transport);
+
+        assert_eq!(
+            git_info.get_user_permission("reviewer").await.unwrap(),
+            Permission::Triage
+        );
+        assert_eq!(
+            git_info.get_user_permission("nobody").await.unwrap(),
+            Permission::None
+        );
+    }
+
+    struct Note(Issue);
+
+    impl CommentBody for Note {
+        fn generate_body(&self, _: &(impl GitHelpers + GitFileOps)) -> String {
+            "# QC Approval\n\napproved".to_string()
+        }
+
+        fn issue(&self) -> &Issue {
+            &self.0
+        }
+
+        fn title(&self) -> &str {
+            "QC Approval"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_comment_and_close_issue() {
+        let mut transport = MockGitLabTransport::new();
+        expect(
+            &mut transport,
+            Method::POST,
+            "/projects/qc%2Fpk%2Fmodels/issues/3/notes",
+            Some(json!({ "body": "# QC Approval\n\napproved" })),
+            json!({ "id": 1234, "body": "# QC Approval\n\napproved" }),
+        );
+        expect(
+            &mut transport,
+            Method::PUT,
+            "/projects/qc%2Fpk%2Fmodels/issues/3",
+            Some(json!({ "state_event": "close" })),
+            gitlab_issue(3, &["ghqc"], "closed"),
+        );
+        expect(
+            &mut transport,
+            Method::POST,
+            "/projects/qc%2Fpk%2Fmodels/labels",
+            Some(json!({ "name": "ghqc", "color": "#FFCB05" })),
+            json!({ "id": 1, "name": "ghqc" }),
+        );
+        let git_info = git_info(transport);
+        let issue = GitLabApi::with_transport(&remote(), Arc::new(MockGitLabTransport::new()))
+            .issue(&gitlab_issue(3, &["ghqc"], "opened"))
+            .unwrap();
+
+        let url = git_info.post_comment(&Note(issue)).await.unwrap();
+        assert_eq!(
+            url,
+            "https://gitlab.example.com/qc/pk/models/-/issues/3#note_1234"
+        );
+        git_info.close_issue(3).await.unwrap();
+        git_info.create_label("ghqc", "FFCB05").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_errors() {
+        let mut transport = MockGitLabTransport::new();
+        transport.expect_send().returning(|_, _, _| {
+            Err(GitLabError::Status {
+                status: StatusCode::FORBIDDEN,
+                message: "insufficient_scope".to_string(),
+            })
+        });
+        let git_info = git_info(transport);
+
+        let err = git_info.open_issue(3).await.unwrap_err();
+        assert!(err.is_read_only_token(), "{err:?}");
+
+        let err = git_info.get_issue(3).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(
+            err.to_string(),
+            "get_issue(issue #3) failed: 403 Forbidden (insufficient_scope)"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_token() {
+        let mut env = MockEnvProvider::new();
+        env.expect_var().returning(|key| match key {
+            "GITLAB_TOKEN" => Ok("glpat-fallback".to_string()),
+            _ => Err(std::env::VarError::NotPresent),
+        });
+        assert_eq!(
+            gitlab_token("https://gitlab.example.com", &env, None).as_deref(),
+            Some("glpat-fallback")
+        );
+
+        let mut env = MockEnvProvider::new();
+        env.expect_var().returning(|key| match key {
+            "GHQC_GITLAB_TOKEN" => Ok("glpat-ghqc".to_string()),
+            "GITLAB_TOKEN" => Ok("glpat-fallback".to_string()),
+            _ => Err(std::env::VarError::NotPresent),
+        });
+        assert_eq!(
+            gitlab_token("https://gitlab.example.com", &env, None).as_deref(),
+            Some("glpat-ghqc")
+        );
+    }
+
+    #[test]
+    fn test_github_event() {
+        let closed = github_event(&json!({
+            "user": gitlab_user("reviewer"),
+            "state": "closed",
+            "created_at": "2025-01-09T12:00:00.000Z",
+        }))
+        .unwrap();
+        assert_eq!(closed["event"], "closed");
+        assert_eq!(closed["actor"]["login"], "reviewer");
+
+        let labeled = github_event(&json!({
+            "user": gitlab_user("jane"),
+            "action": "add",
+            "label": { "name": "ghqc" },
+            "created_at": "2025-01-06T09:00:00.000Z",
+        }))
+        .unwrap();
+        assert_eq!(labeled["event"], "labeled");
+        assert_eq!(labeled["label"]["name"], "ghqc");
+
+        assert_eq!(permission_from_access_level(30), Permission::Write);
+        assert_eq!(permission_from_access_level(5), Permission::None);
+    }
+}
@@ -1,8 +1,17 @@
+mod gitlab;
 mod read;
+mod retry;
 mod write;
 
+pub(crate) use gitlab::GitLabApi;
+pub use gitlab::GitLabError;
+#[cfg(test)]
+pub use read::MockGitHubReader;
 pub use read::{GitComment, GitHubReader};
-pub use write::GitHubWriter;
+use retry::RetryError;
+#[cfg(test)]
+pub use write::MockGitHubWriter;
+pub use write::{GitHubWriter, MilestoneState, MilestoneUpdate};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RepoUser {
@@ -19,14 +28,459 @@ impl std::fmt::Display for RepoUser {
     }
 }
 
+/// A user's permission level on the repository, from lowest to highest
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    None,
+    Read,
+    Triage,
+    Write,
+    Maintain,
+    Admin,
+}
+
+impl Permission {
+    /// Parse a GitHub role name (`role_name`) or legacy permission (`permission`) value
+    pub fn from_api(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "read" | "pull" => Some(Self::Read),
+            "triage" => Some(Self::Triage),
+            "write" | "push" => Some(Self::Write),
+            "maintain" => Some(Self::Maintain),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether the user can close and reopen issues, which approving and unapproving requires
+    pub fn can_close_issues(&self) -> bool {
+        *self >= Self::Triage
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::None => "none",
+            Self::Read => "read",
+            Self::Triage => "triage",
+            Self::Write => "write",
+            Self::Maintain => "maintain",
+            Self::Admin => "admin",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// What a GitHub request was for, e.g. the issue whose comments were being fetched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestTarget {
+    Issue(u64),
+    Milestone(u64),
+    MilestoneTitle(String),
+    User(String),
+    Team { org: String, slug: String },
+    Label(String),
+}
+
+impl std::fmt::Display for RequestTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Issue(number) => write!(f, "issue #{number}"),
+            Self::Milestone(number) => write!(f, "milestone #{number}"),
+            Self::MilestoneTitle(title) => write!(f, "milestone '{title}'"),
+            Self::User(login) => write!(f, "user {login}"),
+            Self::Team { org, slug } => write!(f, "team {org}/{slug}"),
+            Self::Label(name) => write!(f, "label '{name}'"),
+        }
+    }
+}
+
+/// A failed GitHub request, with the operation and target it failed for
+///
+/// Displayed as e.g. `get_issue_comments(issue #42) failed: 502 Bad Gateway`, so a failure in a
+/// milestone-wide operation can be traced back to the request that caused it.
+#[derive(Debug)]
+pub struct RequestError {
+    /// The logical operation, named after the [`GitHubReader`]/[`GitHubWriter`] method
+    pub operation: &'static str,
+    /// What the request was for; `None` for repository-wide requests
+    pub target: Option<RequestTarget>,
+    /// The HTTP status GitHub responded with, when it responded at all
+    pub status: Option<http::StatusCode>,
+    source: RequestSource,
+}
+
+/// The client error behind a [`RequestError`]
+#[derive(Debug)]
+enum RequestSource {
+    GitHub(octocrab::Error),
+    GitLab(GitLabError),
+}
+
+impl RequestError {
+    pub(crate) fn new(
+        operation: &'static str,
+        target: Option<RequestTarget>,
+        source: octocrab::Error,
+    ) -> Self {
+        let status = match &source {
+            octocrab::Error::GitHub { source, .. } => Some(source.status_code),
+            _ => None,
+        };
+        Self {
+            operation,
+            target,
+            status,
+            source: RequestSource::GitHub(source),
+        }
+    }
+
+    pub(crate) fn gitlab(
+        operation: &'static str,
+        target: Option<RequestTarget>,
+        source: GitLabError,
+    ) -> Self {
+        Self {
+            operation,
+            target,
+            status: source.status(),
+            source: RequestSource::GitLab(source),
+        }
+    }
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if let Some(target) = &self.target {
+            write!(f, "({target})")?;
+        }
+        write!(f, " failed: ")?;
+        match &self.source {
+            RequestSource::GitHub(octocrab::Error::GitHub { source, .. }) => {
+                write!(
+                    f,
+                    "{}",
+                    response_summary(source.status_code, &source.message)
+                )
+            }
+            RequestSource::GitHub(other) => write!(f, "{other}"),
+            RequestSource::GitLab(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// Summarize a GitHub error response as its status, followed by GitHub's message if it adds
+/// anything
+fn response_summary(status: http::StatusCode, message: &str) -> String {
+    if message.is_empty() || status.canonical_reason() == Some(message) {
+        status.to_string()
+    } else {
+        format!("{status} ({message})")
+    }
+}
+
+impl std::error::Error for RequestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            RequestSource::GitHub(err) => Some(err),
+            RequestSource::GitLab(err) => Some(err),
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum GitHubApiError {
     #[error("GitHub API not loaded")]
     NoApi,
-    #[error("GitHub API URL access failed due to: {0}")]
-    APIError(octocrab::Error),
+    #[error("{0}")]
+    APIError(Box<RequestError>),
+    #[error("{error}\nGitHub rate limit exceeded{}", reset_at.map(|at| format!(", resets at {at}")).unwrap_or_default())]
+    RateLimited {
+        /// When the primary rate limit resets; `None` for secondary rate limits
+        reset_at: Option<chrono::DateTime<chrono::Utc>>,
+        error: Box<RequestError>,
+    },
     #[error("Failed to generate comment body: {0}")]
     CommentGenerationError(#[from] crate::git::GitFileOpsError),
     #[error("Failed to create GitHub client: {0}")]
     ClientCreation(#[from] crate::git::AuthError),
+    #[error("The GitHub token is read-only and cannot make changes: {0}")]
+    ReadOnlyToken(String),
+    #[error(transparent)]
+    RemoteMismatch(#[from] crate::git::RemoteMismatch),
+}
+
+impl GitHubApiError {
+    /// Wrap errors from a GitHub read request with the operation and target they failed for, for
+    /// use as `.map_err(GitHubApiError::with_ctx("get_issue", RequestTarget::Issue(number)))`
+    ///
+    /// Requests which exhausted their retries on a rate limit become [`Self::RateLimited`].
+    pub(crate) fn with_ctx<E: Into<RetryError>>(
+        operation: &'static str,
+        target: impl Into<Option<RequestTarget>>,
+    ) -> impl FnOnce(E) -> Self {
+        let target = target.into();
+        move |err| match err.into() {
+            RetryError::RateLimited { reset_at, source } => Self::RateLimited {
+                reset_at,
+                error: Box::new(RequestError::new(operation, target, source)),
+            },
+            RetryError::Failed(err) => {
+                Self::APIError(Box::new(RequestError::new(operation, target, err)))
+            }
+        }
+    }
+
+    /// Like [`Self::with_ctx`], for a GitHub write request
+    ///
+    /// A 403 whose message says the resource is not accessible means the token itself lacks write
+    /// access (e.g. a fine-grained PAT with read-only scopes), as opposed to rate limiting or a
+    /// missing repository role.
+    pub(crate) fn write_with_ctx<E: Into<RetryError>>(
+        operation: &'static str,
+        target: impl Into<Option<RequestTarget>>,
+    ) -> impl FnOnce(E) -> Self {
+        move |err| match err.into() {
+            RetryError::Failed(octocrab::Error::GitHub { source, .. })
+                if is_read_only_rejection(source.status_code, &source.message) =>
+            {
+                Self::ReadOnlyToken(source.message.clone())
+            }
+            err => Self::with_ctx(operation, target)(err),
+        }
+    }
+
+    /// Like [`Self::with_ctx`], for a GitLab read request
+    pub(crate) fn gitlab_with_ctx(
+        operation: &'static str,
+        target: impl Into<Option<RequestTarget>>,
+    ) -> impl FnOnce(GitLabError) -> Self {
+        let target = target.into();
+        move |err| Self::APIError(Box::new(RequestError::gitlab(operation, target, err)))
+    }
+
+    /// Like [`Self::write_with_ctx`], for a GitLab write request
+    ///
+    /// GitLab rejects writes with a token lacking the `api` scope as `insufficient_scope`.
+    pub(crate) fn gitlab_write_with_ctx(
+        operation: &'static str,
+        target: impl Into<Option<RequestTarget>>,
+    ) -> impl FnOnce(GitLabError) -> Self {
+        let target = target.into();
+        move |err| match &err {
+            GitLabError::Status { status, message }
+                if *status == http::StatusCode::FORBIDDEN
+                    && message.contains("insufficient_scope") =>
+            {
+                Self::ReadOnlyToken(message.clone())
+            }
+            _ => Self::APIError(Box::new(RequestError::gitlab(operation, target, err))),
+        }
+    }
+
+    /// The HTTP status GitHub responded with, if the request got a response
+    pub fn status(&self) -> Option<http::StatusCode> {
+        match self {
+            Self::APIError(err) | Self::RateLimited { error: err, .. } => err.status,
+            _ => None,
+        }
+    }
+
+    /// Whether the request failed on a GitHub rate limit, after waiting for it as long as allowed
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::RateLimited { .. })
+    }
+
+    /// Whether GitHub rejected a write because the token is read-only
+    pub fn is_read_only_token(&self) -> bool {
+        matches!(self, Self::ReadOnlyToken(_))
+    }
+}
+
+/// Whether a GitHub error response rejects a write due to the token's scopes
+fn is_read_only_rejection(status: http::StatusCode, message: &str) -> bool {
+    status == http::StatusCode::FORBIDDEN
+        && message
+            .to_lowercase()
+            .contains("resource not accessible by")
+}
+
+/// Whether a classic token's `X-OAuth-Scopes` header grants write access to repositories
+///
+/// Fine-grained PATs and GitHub App tokens do not send the header, so their write access cannot
+/// be determined up front and `None` is returned.
+pub(crate) fn token_scopes_allow_write(scopes: Option<&str>) -> Option<bool> {
+    let scopes = scopes?;
+    Some(
+        scopes
+            .split(',')
+            .map(str::trim)
+            .any(|scope| scope == "repo" || scope == "public_repo"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_from_api() {
+        assert_eq!(Permission::from_api("admin"), Some(Permission::Admin));
+        assert_eq!(Permission::from_api("maintain"), Some(Permission::Maintain));
+        assert_eq!(Permission::from_api("push"), Some(Permission::Write));
+        assert_eq!(Permission::from_api("triage"), Some(Permission::Triage));
+        assert_eq!(Permission::from_api("READ"), Some(Permission::Read));
+        assert_eq!(Permission::from_api("none"), Some(Permission::None));
+        assert_eq!(Permission::from_api("custom-role"), None);
+    }
+
+    #[test]
+    fn test_read_only_rejection_classification() {
+        assert!(is_read_only_rejection(
+            http::StatusCode::FORBIDDEN,
+            "Resource not accessible by personal access token"
+        ));
+        assert!(is_read_only_rejection(
+            http::StatusCode::FORBIDDEN,
+            "Resource not accessible by integration"
+        ));
+        assert!(!is_read_only_rejection(
+            http::StatusCode::FORBIDDEN,
+            "API rate limit exceeded for user ID 1"
+        ));
+        assert!(!is_read_only_rejection(
+            http::StatusCode::NOT_FOUND,
+            "Resource not accessible by personal access token"
+        ));
+    }
+
+    fn io_error(message: &str) -> octocrab::Error {
+        octocrab::Error::Other {
+            source: Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                message.to_string(),
+            )),
+            backtrace: std::backtrace::Backtrace::capture(),
+        }
+    }
+
+    #[test]
+    fn test_request_error_display() {
+        let err = GitHubApiError::with_ctx("get_issue_comments", RequestTarget::Issue(42))(
+            io_error("Last comment missing body"),
+        );
+        let expected = format!(
+            "get_issue_comments(issue #42) failed: {}",
+            io_error("Last comment missing body")
+        );
+        assert_eq!(err.to_string(), expected);
+        assert_eq!(err.status(), None);
+
+        let err = GitHubApiError::write_with_ctx(
+            "create_milestone",
+            RequestTarget::MilestoneTitle("Sprint 3".to_string()),
+        )(io_error("connection reset"));
+        assert!(
+            err.to_string()
+                .starts_with("create_milestone(milestone 'Sprint 3') failed: ")
+        );
+        assert!(!err.is_read_only_token());
+
+        let err = GitHubApiError::with_ctx("get_milestones", None)(io_error("timed out"));
+        assert!(err.to_string().starts_with("get_milestones failed: "));
+    }
+
+    #[test]
+    fn test_request_error_keeps_source() {
+        let err = GitHubApiError::with_ctx(
+            "get_user_permission",
+            RequestTarget::User("alice".into()),
+        )(io_error("timed out"));
+        let GitHubApiError::APIError(request) = &err else {
+            panic!("expected a request error, got {err:?}");
+        };
+        assert_eq!(request.operation, "get_user_permission");
+        assert_eq!(
+            request.target,
+            Some(RequestTarget::User("alice".to_string()))
+        );
+        assert!(std::error::Error::source(request.as_ref()).is_some());
+    }
+
+    #[test]
+    fn test_rate_limited_error() {
+        let reset_at = chrono::DateTime::from_timestamp(1_800_000_000, 0);
+        let err = GitHubApiError::with_ctx("get_issue_events", RequestTarget::Issue(7))(
+            RetryError::RateLimited {
+                reset_at,
+                source: io_error("rate limited"),
+            },
+        );
+        assert!(err.is_rate_limited());
+        assert!(
+            err.to_string()
+                .starts_with("get_issue_events(issue #7) failed: ")
+        );
+        assert!(
+            err.to_string()
+                .ends_with("GitHub rate limit exceeded, resets at 2027-01-15 08:00:00 UTC")
+        );
+    }
+
+    #[test]
+    fn test_response_summary() {
+        assert_eq!(
+            response_summary(http::StatusCode::BAD_GATEWAY, ""),
+            "502 Bad Gateway"
+        );
+        assert_eq!(
+            response_summary(http::StatusCode::NOT_FOUND, "Not Found"),
+            "404 Not Found"
+        );
+        assert_eq!(
+            response_summary(http::StatusCode::FORBIDDEN, "API rate limit exceeded"),
+            "403 Forbidden (API rate limit exceeded)"
+        );
+    }
+
+    #[test]
+    fn test_request_target_display() {
+        assert_eq!(RequestTarget::Issue(7).to_string(), "issue #7");
+        assert_eq!(RequestTarget::Milestone(3).to_string(), "milestone #3");
+        assert_eq!(
+            RequestTarget::Team {
+                org: "a2-ai".to_string(),
+                slug: "qc".to_string()
+            }
+            .to_string(),
+            "team a2-ai/qc"
+        );
+    }
+
+    #[test]
+    fn test_token_scopes_allow_write() {
+        assert_eq!(token_scopes_allow_write(Some("repo, read:org")), Some(true));
+        assert_eq!(token_scopes_allow_write(Some("public_repo")), Some(true));
+        assert_eq!(
+            token_scopes_allow_write(Some("read:org, gist")),
+            Some(false)
+        );
+        assert_eq!(token_scopes_allow_write(Some("")), Some(false));
+        assert_eq!(token_scopes_allow_write(None), None);
+    }
+
+    #[test]
+    fn test_permission_can_close_issues() {
+        assert!(!Permission::None.can_close_issues());
+        assert!(!Permission::Read.can_close_issues());
+        assert!(Permission::Triage.can_close_issues());
+        assert!(Permission::Write.can_close_issues());
+        assert!(Permission::Admin.can_close_issues());
+    }
 }
@@ -4,7 +4,8 @@ use octocrab::models::issues::Issue;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 
-use super::{GitHubApiError, RepoUser};
+use super::retry::{self, RequestKind, RetryError};
+use super::{GitHubApiError, Permission, RepoUser, RequestTarget};
 use crate::git::GitInfo;
 
 /// Git comment data structure
@@ -38,6 +39,21 @@ pub trait GitHubReader {
         &self,
         username: &str,
     ) -> impl Future<Output = Result<RepoUser, GitHubApiError>> + Send;
+    /// Get a user's permission level on the repository
+    ///
+    /// Users who are not collaborators on the repository have [`Permission::None`].
+    fn get_user_permission(
+        &self,
+        login: &str,
+    ) -> impl Future<Output = Result<Permission, GitHubApiError>> + Send;
+    /// Get the logins of the members of an organization team
+    ///
+    /// Requires a token with `read:org` access to the organization.
+    fn get_team_members(
+        &self,
+        org: &str,
+        team_slug: &str,
+    ) -> impl Future<Output = Result<Vec<String>, GitHubApiError>> + Send;
     fn get_labels(&self) -> impl Future<Output = Result<Vec<String>, GitHubApiError>> + Send;
     fn get_issue_comments(
         &self,
@@ -77,7 +93,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_milestones().await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -86,16 +107,16 @@ impl GitHubReader for GitInfo {
             let mut all_milestones: Vec<Milestone> = Vec::new();
             let mut page = 1u32;
             loop {
-                let page_milestones: Vec<Milestone> = octocrab
-                    .get(
-                        format!(
-                            "/repos/{}/{}/milestones?state=all&per_page=100&page={}",
-                            &owner, &repo, page
-                        ),
-                        None::<&()>,
-                    )
+                let url = format!(
+                    "/repos/{}/{}/milestones?state=all&per_page=100&page={}",
+                    &owner, &repo, page
+                );
+                let page_milestones: Vec<Milestone> =
+                    retry::send(&octocrab, RequestKind::Read, || {
+                        octocrab.get(&url, None::<&()>)
+                    })
                     .await
-                    .map_err(GitHubApiError::APIError)?;
+                    .map_err(GitHubApiError::with_ctx("get_milestones", None))?;
 
                 let count = page_milestones.len();
                 all_milestones.extend(page_milestones);
@@ -119,7 +140,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_issues(milestone).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -136,18 +162,24 @@ impl GitHubReader for GitInfo {
             let issues_handler = octocrab.issues(&owner, &repo);
 
             loop {
-                let mut builder = issues_handler
-                    .list()
-                    .state(octocrab::params::State::All)
-                    .labels(&labels)
-                    .per_page(100)
-                    .page(page);
-
-                if let Some(id) = milestone {
-                    builder = builder.milestone(id);
-                }
-
-                let issues = builder.send().await.map_err(GitHubApiError::APIError)?;
+                let issues = retry::send(&octocrab, RequestKind::Read, || {
+                    let mut builder = issues_handler
+                        .list()
+                        .state(octocrab::params::State::All)
+                        .labels(&labels)
+                        .per_page(100)
+                        .page(page);
+
+                    if let Some(id) = milestone {
+                        builder = builder.milestone(id);
+                    }
+                    builder.send()
+                })
+                .await
+                .map_err(GitHubApiError::with_ctx(
+                    "get_issues",
+                    milestone.map(RequestTarget::Milestone),
+                ))?;
 
                 if issues.items.is_empty() {
                     break;
@@ -178,18 +210,26 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_issue(issue_number).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
 
             log::debug!("Fetching issue #{} for {}/{}", issue_number, owner, repo);
 
-            let issue = octocrab
-                .issues(&owner, &repo)
-                .get(issue_number)
+            let issues = octocrab.issues(&owner, &repo);
+
+            let issue = retry::send(&octocrab, RequestKind::Read, || issues.get(issue_number))
                 .await
-                .map_err(GitHubApiError::APIError)?;
+                .map_err(GitHubApiError::with_ctx(
+                    "get_issue",
+                    RequestTarget::Issue(issue_number),
+                ))?;
 
             log::debug!(
                 "Successfully fetched issue #{} (id: {:?})",
@@ -209,7 +249,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_assignees().await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -225,10 +270,12 @@ impl GitHubReader for GitInfo {
                     &owner, &repo, per_page, page
                 );
 
-                let assignees: Vec<serde_json::Value> = octocrab
-                    .get(url, None::<&()>)
+                let assignees: Vec<serde_json::Value> =
+                    retry::send(&octocrab, RequestKind::Read, || {
+                        octocrab.get(&url, None::<&()>)
+                    })
                     .await
-                    .map_err(GitHubApiError::APIError)?;
+                    .map_err(GitHubApiError::with_ctx("get_assignees", None))?;
 
                 if assignees.is_empty() {
                     break; // No more pages
@@ -270,7 +317,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_user_details(&username).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -305,23 +357,138 @@ impl GitHubReader for GitInfo {
         }
     }
 
+    fn get_user_permission(
+        &self,
+        login: &str,
+    ) -> impl Future<Output = Result<Permission, GitHubApiError>> + Send {
+        let login = login.to_string();
+        let owner = self.owner.clone();
+        let repo = self.repo.clone();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_user_permission(&login).await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!("Fetching repository permission for: {}", login);
+
+            let url = format!("/repos/{owner}/{repo}/collaborators/{login}/permission");
+            let result: Result<serde_json::Value, _> =
+                retry::send(&octocrab, RequestKind::Read, || {
+                    octocrab.get(&url, None::<&()>)
+                })
+                .await;
+
+            match result {
+                Ok(data) => {
+                    // `role_name` distinguishes triage/maintain, which `permission` folds into read/write
+                    let permission = ["role_name", "permission"]
+                        .iter()
+                        .filter_map(|key| data.get(key).and_then(|v| v.as_str()))
+                        .find_map(Permission::from_api)
+                        .unwrap_or(Permission::None);
+                    Ok(permission)
+                }
+                Err(RetryError::Failed(octocrab::Error::GitHub { source, .. }))
+                    if source.status_code == http::StatusCode::NOT_FOUND =>
+                {
+                    log::debug!("{} is not a collaborator on {}/{}", login, owner, repo);
+                    Ok(Permission::None)
+                }
+                Err(e) => Err(GitHubApiError::with_ctx(
+                    "get_user_permission",
+                    RequestTarget::User(login),
+                )(e)),
+            }
+        }
+    }
+
+    fn get_team_members(
+        &self,
+        org: &str,
+        team_slug: &str,
+    ) -> impl Future<Output = Result<Vec<String>, GitHubApiError>> + Send {
+        let org = org.to_string();
+        let team_slug = team_slug.to_string();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_team_members(&org, &team_slug).await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!("Fetching members of team {}/{}", org, team_slug);
+
+            let mut logins = Vec::new();
+            let per_page = 100;
+            for page in 1..=100 {
+                let url = format!(
+                    "/orgs/{org}/teams/{team_slug}/members?per_page={per_page}&page={page}"
+                );
+                let members: Vec<serde_json::Value> =
+                    retry::send(&octocrab, RequestKind::Read, || {
+                        octocrab.get(&url, None::<&()>)
+                    })
+                    .await
+                    .map_err(GitHubApiError::with_ctx(
+                        "get_team_members",
+                        RequestTarget::Team {
+                            org: org.clone(),
+                            slug: team_slug.clone(),
+                        },
+                    ))?;
+
+                let count = members.len();
+                logins.extend(members.into_iter().filter_map(|member| {
+                    member
+                        .get("login")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                }));
+
+                if count < per_page {
+                    break;
+                }
+            }
+
+            log::debug!("Team {}/{} has {} members", org, team_slug, logins.len());
+            Ok(logins)
+        }
+    }
+
     fn get_labels(&self) -> impl Future<Output = Result<Vec<String>, GitHubApiError>> + Send {
         let owner = self.owner.clone();
         let repo = self.repo.clone();
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_labels().await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
             log::debug!("Fetching labels for repository {}/{}", owner, repo);
-            let labels = octocrab
-                .issues(&owner, &repo)
-                .list_labels_for_repo()
-                .send()
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            let issues = octocrab.issues(&owner, &repo);
+            let labels = retry::send(&octocrab, RequestKind::Read, || {
+                issues.list_labels_for_repo().send()
+            })
+            .await
+            .map_err(GitHubApiError::with_ctx("get_labels", None))?;
 
             log::debug!("Found {} labels", labels.items.len());
             let names: Vec<String> = labels.items.into_iter().map(|l| l.name).collect();
@@ -339,7 +506,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_issue_comments(issue_number).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -367,10 +539,15 @@ impl GitHubReader for GitInfo {
                 .into_iter()
                 .collect::<HeaderMap<_>>();
 
-                let comments: Vec<serde_json::Value> = octocrab
-                    .get_with_headers(url, None::<&()>, Some(headers))
+                let comments: Vec<serde_json::Value> =
+                    retry::send(&octocrab, RequestKind::Read, || {
+                        octocrab.get_with_headers(&url, None::<&()>, Some(headers.clone()))
+                    })
                     .await
-                    .map_err(GitHubApiError::APIError)?;
+                    .map_err(GitHubApiError::with_ctx(
+                        "get_issue_comments",
+                        RequestTarget::Issue(issue_number),
+                    ))?;
 
                 if comments.is_empty() {
                     break; // No more pages
@@ -413,7 +590,10 @@ impl GitHubReader for GitInfo {
                                 comment_id,
                                 issue_number
                             );
-                            return Err(GitHubApiError::APIError(octocrab::Error::Other {
+                            return Err(GitHubApiError::with_ctx(
+                                "get_issue_comments",
+                                RequestTarget::Issue(issue_number),
+                            )(octocrab::Error::Other {
                                 source: Box::new(std::io::Error::new(
                                     std::io::ErrorKind::InvalidData,
                                     "Last comment missing body",
@@ -484,7 +664,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_issue_events(issue_number).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -505,10 +690,15 @@ impl GitHubReader for GitInfo {
                     &owner, &repo, issue_number, per_page, page
                 );
 
-                let events: Vec<serde_json::Value> = octocrab
-                    .get(url, None::<&()>)
+                let events: Vec<serde_json::Value> =
+                    retry::send(&octocrab, RequestKind::Read, || {
+                        octocrab.get(&url, None::<&()>)
+                    })
                     .await
-                    .map_err(GitHubApiError::APIError)?;
+                    .map_err(GitHubApiError::with_ctx(
+                        "get_issue_events",
+                        RequestTarget::Issue(issue_number),
+                    ))?;
 
                 if events.is_empty() {
                     break; // No more pages
@@ -544,7 +734,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_blocked_issues(issue_number).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -569,7 +764,11 @@ impl GitHubReader for GitInfo {
                     &owner, &repo, issue_number, per_page, page
                 );
 
-                let response: Result<Vec<Issue>, _> = octocrab.get(&url, None::<&()>).await;
+                let response: Result<Vec<Issue>, _> =
+                    retry::send(&octocrab, RequestKind::Read, || {
+                        octocrab.get(&url, None::<&()>)
+                    })
+                    .await;
 
                 match response {
                     Ok(issues) => {
@@ -606,13 +805,16 @@ impl GitHubReader for GitInfo {
                             );
                             // A 404 means the endpoint doesn't exist on this instance.
                             let is_not_found = matches!(&e,
-                                octocrab::Error::GitHub { source, .. }
+                                RetryError::Failed(octocrab::Error::GitHub { source, .. })
                                 if source.status_code == http::StatusCode::NOT_FOUND
                             );
                             if is_not_found {
                                 return Err(GitHubApiError::NoApi);
                             }
-                            return Err(GitHubApiError::APIError(e));
+                            return Err(GitHubApiError::with_ctx(
+                                "get_blocked_issues",
+                                RequestTarget::Issue(issue_number),
+                            )(e));
                         }
                         // If we already have some results, log warning and return what we have
                         log::warn!(
@@ -641,7 +843,12 @@ impl GitHubReader for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.get_current_user().await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -661,3 +868,41 @@ impl GitHubReader for GitInfo {
         }
     }
 }
+
+impl GitInfo {
+    /// Whether the installed token can write to GitHub, probed without making any changes
+    ///
+    /// Classic tokens report their scopes in the `X-OAuth-Scopes` header of any response. Returns
+    /// `None` when this cannot be determined up front (fine-grained PATs, GitHub App tokens, or a
+    /// failed request); writes are then classified on their first rejection instead.
+    pub async fn token_write_access(&self) -> Option<bool> {
+        if self.gitlab.is_some() {
+            return None;
+        }
+        let octocrab = self.auth_sources.client(&self.base_url).ok()?;
+        let response = match octocrab
+            ._get(format!("/repos/{}/{}", self.owner, self.repo))
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                log::debug!("Could not probe token write access: {e}");
+                return None;
+            }
+        };
+
+        if !response.status().is_success() {
+            log::debug!(
+                "Could not probe token write access: GitHub responded with {}",
+                response.status()
+            );
+            return None;
+        }
+
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|value| value.to_str().ok());
+        super::token_scopes_allow_write(scopes)
+    }
+}
@@ -0,0 +1,460 @@
+//! Retrying GitHub requests which fail transiently or hit a rate limit
+
+use chrono::{DateTime, Utc};
+use octocrab::Octocrab;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Retries after the first attempt, unless overridden with `GHQC_API_RETRIES`
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+/// GitHub asks to wait at least a minute after a secondary rate limit without `Retry-After`
+const SECONDARY_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+/// Rate limits resetting later than this are reported instead of waited out
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(15 * 60);
+
+/// How often and how long to retry GitHub requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RetryPolicy {
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub max_rate_limit_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: BASE_DELAY,
+            max_delay: MAX_DELAY,
+            max_rate_limit_wait: MAX_RATE_LIMIT_WAIT,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The default policy, with the retry count from `GHQC_API_RETRIES` if set
+    fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Ok(value) = std::env::var("GHQC_API_RETRIES") {
+            match value.parse() {
+                Ok(retries) => policy.max_retries = retries,
+                Err(_) => log::warn!("Ignoring invalid GHQC_API_RETRIES value '{value}'"),
+            }
+        }
+        policy
+    }
+
+    /// The policy used for every GitHub request of this process
+    pub fn global() -> Self {
+        static POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+        *POLICY.get_or_init(Self::from_env)
+    }
+
+    /// Exponential backoff before retry number `retry` (from 0), with up to 50% jitter
+    fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        delay + delay.mul_f64(jitter() / 2.0)
+    }
+}
+
+/// A pseudo-random fraction in `[0, 1)`, so concurrent requests do not retry in lockstep
+fn jitter() -> f64 {
+    use std::hash::{BuildHasher, RandomState};
+    (RandomState::new().hash_one(std::time::SystemTime::now()) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Whether a request may be sent again without side effects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestKind {
+    /// Retried on server errors, network failures and rate limits
+    Read,
+    /// Retried only when the connection failed, as GitHub may have acted on anything else
+    Write,
+}
+
+/// A request which still failed after its retries
+#[derive(Debug)]
+pub(crate) enum RetryError {
+    RateLimited {
+        reset_at: Option<DateTime<Utc>>,
+        source: octocrab::Error,
+    },
+    Failed(octocrab::Error),
+}
+
+impl From<octocrab::Error> for RetryError {
+    fn from(err: octocrab::Error) -> Self {
+        Self::Failed(err)
+    }
+}
+
+impl std::fmt::Display for RetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RateLimited { source, .. } | Self::Failed(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+/// How a failed attempt should be handled
+#[derive(Debug, PartialEq, Eq)]
+enum Failure {
+    /// Never reached GitHub
+    Connection,
+    /// The connection broke or timed out, or GitHub responded with a 5xx
+    Transient,
+    RateLimited,
+    Permanent,
+}
+
+fn classify(err: &octocrab::Error) -> Failure {
+    match err {
+        octocrab::Error::GitHub { source, .. } => {
+            let status = source.status_code;
+            if status == http::StatusCode::TOO_MANY_REQUESTS
+                || (status == http::StatusCode::FORBIDDEN
+                    && source.message.to_lowercase().contains("rate limit"))
+            {
+                Failure::RateLimited
+            } else if status.is_server_error() {
+                Failure::Transient
+            } else {
+                Failure::Permanent
+            }
+        }
+        octocrab::Error::Service { .. } | octocrab::Error::Hyper { .. } => {
+            if is_connect_error(err) {
+                Failure::Connection
+            } else {
+                Failure::Transient
+            }
+        }
+        _ => Failure::Permanent,
+    }
+}
+
+/// Whether the client failed to connect, e.g. a refused connection or a DNS failure
+///
+/// hyper's client error kinds are private, but a connect failure displays as
+/// `client error (Connect)`.
+fn is_connect_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if err.to_string().starts_with("client error (Connect)") {
+            return true;
+        }
+        current = err.source();
+    }
+    false
+}
+
+/// Send a GitHub request built by `request`, retrying it according to the global policy
+///
+/// On a rate limit, the wait until the limit resets is looked up from `/rate_limit`, as GitHub's
+/// `x-ratelimit-reset` header is not exposed by octocrab's errors.
+pub(crate) async fn send<T, F, Fut>(
+    octocrab: &Octocrab,
+    kind: RequestKind,
+    request: F,
+) -> Result<T, RetryError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, octocrab::Error>>,
+{
+    retry(&RetryPolicy::global(), kind, request, || {
+        rate_limit_reset(octocrab)
+    })
+    .await
+}
+
+/// When the exhausted primary rate limit resets; `None` for a secondary rate limit
+async fn rate_limit_reset(octocrab: &Octocrab) -> Option<DateTime<Utc>> {
+    let limits = octocrab.ratelimit().get().await.ok()?;
+    let core = limits.resources.core;
+    if core.remaining > 0 {
+        return None;
+    }
+    DateTime::from_timestamp(core.reset as i64, 0)
+}
+
+async fn retry<T, F, Fut, R, RFut>(
+    policy: &RetryPolicy,
+    kind: RequestKind,
+    mut request: F,
+    mut reset_at: R,
+) -> Result<T, RetryError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, octocrab::Error>>,
+    R: FnMut() -> RFut,
+    RFut: Future<Output = Option<DateTime<Utc>>>,
+{
+    let mut retries = 0;
+    loop {
+        let err = match request().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        let failure = classify(&err);
+        let retryable = match kind {
+            RequestKind::Read => failure != Failure::Permanent,
+            RequestKind::Write => failure == Failure::Connection,
+        };
+
+        let delay = if failure == Failure::RateLimited && kind == RequestKind::Read {
+            let reset_at = reset_at().await;
+            let wait = match reset_at {
+                Some(reset) => (reset - Utc::now()).to_std().unwrap_or_default() + BASE_DELAY,
+                None => SECONDARY_RATE_LIMIT_WAIT.max(policy.backoff(retries)),
+            };
+            if retries >= policy.max_retries || wait > policy.max_rate_limit_wait {
+                return Err(RetryError::RateLimited {
+                    reset_at,
+                    source: err,
+                });
+            }
+            log::warn!(
+                "GitHub rate limit exceeded, retrying in {}s",
+                wait.as_secs()
+            );
+            wait
+        } else if retryable && retries < policy.max_retries {
+            let delay = policy.backoff(retries);
+            log::warn!("GitHub request failed ({err}), retrying in {delay:?}");
+            delay
+        } else if failure == Failure::RateLimited {
+            return Err(RetryError::RateLimited {
+                reset_at: None,
+                source: err,
+            });
+        } else {
+            return Err(RetryError::Failed(err));
+        };
+
+        tokio::time::sleep(delay).await;
+        retries += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::time::Instant;
+
+    /// The error octocrab returns for a GitHub response with `status` and `message`
+    fn github_error(status: u16, message: &str) -> octocrab::Error {
+        use http_body_util::BodyExt;
+        let body = serde_json::json!({ "message": message }).to_string();
+        let response = http::Response::builder()
+            .status(status)
+            .body(
+                http_body_util::Full::new(bytes::Bytes::from(body))
+                    .map_err(|never| match never {})
+                    .boxed(),
+            )
+            .unwrap();
+        futures::executor::block_on(octocrab::map_github_error(response)).unwrap_err()
+    }
+
+    fn connect_error() -> octocrab::Error {
+        #[derive(Debug, thiserror::Error)]
+        #[error("client error (Connect)")]
+        struct ConnectError;
+        octocrab::Error::Service {
+            source: Box::new(ConnectError),
+            backtrace: std::backtrace::Backtrace::capture(),
+        }
+    }
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_retries: 3,
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(30),
+        max_rate_limit_wait: Duration::from_secs(15 * 60),
+    };
+
+    /// A request failing with `fail` for its first two attempts, then succeeding
+    fn fails_twice(
+        calls: &AtomicU32,
+        fail: fn() -> octocrab::Error,
+    ) -> impl FnMut() -> std::future::Ready<Result<u32, octocrab::Error>> + '_ {
+        move || {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            std::future::ready(if call <= 2 { Err(fail()) } else { Ok(call) })
+        }
+    }
+
+    async fn no_reset() -> Option<DateTime<Utc>> {
+        None
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_read_retries_server_errors_with_backoff() {
+        let calls = AtomicU32::new(0);
+        let start = Instant::now();
+        let result = retry(
+            &POLICY,
+            RequestKind::Read,
+            fails_twice(&calls, || github_error(502, "Bad Gateway")),
+            no_reset,
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        // 1s then 2s, each with up to 50% jitter
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(3), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(4500), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_read_gives_up_after_max_retries() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy {
+            max_retries: 1,
+            ..POLICY
+        };
+        let result = retry(
+            &policy,
+            RequestKind::Read,
+            fails_twice(&calls, || github_error(503, "Service Unavailable")),
+            no_reset,
+        )
+        .await;
+
+        assert!(matches!(result, Err(RetryError::Failed(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_errors_are_not_retried() {
+        let calls = AtomicU32::new(0);
+        let start = Instant::now();
+        let result = retry(
+            &POLICY,
+            RequestKind::Read,
+            fails_twice(&calls, || github_error(404, "Not Found")),
+            no_reset,
+        )
+        .await;
+
+        assert!(matches!(result, Err(RetryError::Failed(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_write_retries_only_connect_errors() {
+        let calls = AtomicU32::new(0);
+        let result = retry(
+            &POLICY,
+            RequestKind::Write,
+            fails_twice(&calls, || github_error(502, "Bad Gateway")),
+            no_reset,
+        )
+        .await;
+        assert!(matches!(result, Err(RetryError::Failed(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let result = retry(
+            &POLICY,
+            RequestKind::Write,
+            fails_twice(&calls, || github_error(429, "Too Many Requests")),
+            no_reset,
+        )
+        .await;
+        assert!(matches!(result, Err(RetryError::RateLimited { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let result = retry(
+            &POLICY,
+            RequestKind::Write,
+            fails_twice(&calls, connect_error),
+            no_reset,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_waits_until_reset() {
+        let calls = AtomicU32::new(0);
+        let reset = Utc::now() + chrono::Duration::seconds(120);
+        let start = Instant::now();
+        let result = retry(
+            &POLICY,
+            RequestKind::Read,
+            fails_twice(&calls, || {
+                github_error(403, "API rate limit exceeded for user ID 1.")
+            }),
+            || async move { Some(reset) },
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        // Two waits of up to two minutes until the reset, plus a second each
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(2 * 119), "{elapsed:?}");
+        assert!(elapsed <= Duration::from_secs(2 * 121), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_secondary_rate_limit_waits_a_minute() {
+        let calls = AtomicU32::new(0);
+        let start = Instant::now();
+        let result = retry(
+            &POLICY,
+            RequestKind::Read,
+            fails_twice(&calls, || {
+                github_error(403, "You have exceeded a secondary rate limit.")
+            }),
+            no_reset,
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(start.elapsed(), Duration::from_secs(120));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_distant_rate_limit_reset_is_reported() {
+        let calls = AtomicU32::new(0);
+        let reset = Utc::now() + chrono::Duration::hours(1);
+        let result = retry(
+            &POLICY,
+            RequestKind::Read,
+            fails_twice(&calls, || github_error(429, "Too Many Requests")),
+            || async move { Some(reset) },
+        )
+        .await;
+
+        match result {
+            Err(RetryError::RateLimited { reset_at, .. }) => assert_eq!(reset_at, Some(reset)),
+            other => panic!("expected a rate limit error, got {other:?}"),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_forbidden_without_rate_limit_is_permanent() {
+        assert_eq!(
+            classify(&github_error(403, "Resource not accessible by integration")),
+            Failure::Permanent
+        );
+        assert_eq!(classify(&connect_error()), Failure::Connection);
+    }
+}
@@ -1,16 +1,76 @@
 use std::future::Future;
 
+use chrono::NaiveDate;
 use octocrab::models::{Milestone, issues::Issue};
 
-use super::GitHubApiError;
+use super::retry::{self, RequestKind};
+use super::{GitHubApiError, RequestTarget};
 use crate::QCIssue;
 use crate::body_splitter;
-use crate::comment_system::CommentBody;
-use crate::git::GitInfo;
+use crate::comment_system::{CommentBody, comment_draft};
+use crate::git::{GitInfo, guard_issue_change};
+use crate::usage;
 
 #[cfg(test)]
 use mockall::automock;
 
+/// State of a milestone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneState {
+    Open,
+    Closed,
+}
+
+impl MilestoneState {
+    /// State of `milestone`, open unless GitHub says it is closed
+    pub fn of(milestone: &Milestone) -> Self {
+        match milestone.state.as_deref() {
+            Some("closed") => Self::Closed,
+            _ => Self::Open,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+/// Changes to a milestone for [`GitHubWriter::update_milestone`]. `None` fields are left unchanged
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MilestoneUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub due_on: Option<NaiveDate>,
+    pub state: Option<MilestoneState>,
+}
+
+impl MilestoneUpdate {
+    /// Body of the GitHub request
+    fn github_request(&self) -> serde_json::Value {
+        let mut request = serde_json::Map::new();
+        if let Some(title) = &self.title {
+            request.insert("title".to_string(), title.clone().into());
+        }
+        if let Some(description) = &self.description {
+            request.insert("description".to_string(), description.clone().into());
+        }
+        if let Some(due_on) = self.due_on {
+            request.insert(
+                "due_on".to_string(),
+                format!("{}T00:00:00Z", due_on.format("%Y-%m-%d")).into(),
+            );
+        }
+        if let Some(state) = self.state {
+            request.insert("state".to_string(), state.as_str().into());
+        }
+        request.into()
+    }
+}
+
 #[cfg_attr(test, automock)]
 pub trait GitHubWriter {
     fn create_milestone(
@@ -18,6 +78,23 @@ pub trait GitHubWriter {
         milestone_name: &str,
         description: &Option<String>,
     ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send;
+    /// Change the title of an existing milestone
+    fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send;
+    /// Change the title, description, due date and/or state of an existing milestone
+    fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &MilestoneUpdate,
+    ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send;
+    /// Close an open milestone
+    fn close_milestone(
+        &self,
+        milestone_number: u64,
+    ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send;
     fn post_issue(
         &self,
         issue: &QCIssue,
@@ -29,6 +106,11 @@ pub trait GitHubWriter {
         comment: &T,
     ) -> impl Future<Output = Result<String, GitHubApiError>> + Send;
 
+    /// Refuse changes to `issue` when it belongs to another repository than the local remote,
+    /// see [`guard_issue_change`](crate::git::guard_issue_change). Comments are guarded by
+    /// `post_comment` itself
+    fn guard_issue(&self, issue: &Issue) -> Result<(), GitHubApiError>;
+
     // Explicit issue state management
     fn close_issue(
         &self,
@@ -46,6 +128,27 @@ pub trait GitHubWriter {
         color: &str,
     ) -> impl Future<Output = Result<(), GitHubApiError>> + Send;
 
+    /// Add labels to an issue, keeping the labels it already has
+    fn add_labels(
+        &self,
+        issue_number: u64,
+        labels: &[String],
+    ) -> impl Future<Output = Result<(), GitHubApiError>> + Send;
+
+    /// Remove a label from an issue
+    fn remove_label(
+        &self,
+        issue_number: u64,
+        label: &str,
+    ) -> impl Future<Output = Result<(), GitHubApiError>> + Send;
+
+    /// Replace the assignees of an issue. An empty list unassigns everyone
+    fn set_assignees(
+        &self,
+        issue_number: u64,
+        assignees: &[String],
+    ) -> impl Future<Output = Result<(), GitHubApiError>> + Send;
+
     /// Add a blocking relationship between issues.
     /// The blocked_issue_number will be "blocked by" the blocking_issue_id.
     /// This uses GitHub's issue dependencies feature which may not be available on all GitHub instances.
@@ -79,7 +182,12 @@ impl GitHubWriter for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.create_milestone(&milestone_name, description).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -99,24 +207,151 @@ impl GitHubWriter for GitInfo {
                 milestone_request["description"] = serde_json::Value::String(desc.clone());
             }
 
-            let milestone: Milestone = octocrab
-                .post(
+            let milestone: Milestone = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.post(
                     format!("/repos/{}/{}/milestones", &owner, &repo),
                     Some(&milestone_request),
                 )
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "create_milestone",
+                RequestTarget::MilestoneTitle(milestone_name.clone()),
+            ))?;
 
             log::debug!(
                 "Successfully created milestone '{}' with ID: {}",
                 milestone_name,
                 milestone.number
             );
+            usage::count_write("milestones_created");
+
+            Ok(milestone)
+        }
+    }
+
+    fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send {
+        let owner = self.owner.clone();
+        let repo = self.repo.clone();
+        let new_title = new_title.to_string();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab
+                    .update_milestone_title(milestone_number, &new_title)
+                    .await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!(
+                "Renaming milestone #{} to '{}' in {}/{}",
+                milestone_number,
+                new_title,
+                owner,
+                repo
+            );
+
+            let update_request = serde_json::json!({
+                "title": new_title,
+            });
+
+            let milestone: Milestone = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.patch(
+                    format!(
+                        "/repos/{}/{}/milestones/{}",
+                        &owner, &repo, milestone_number
+                    ),
+                    Some(&update_request),
+                )
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "update_milestone_title",
+                RequestTarget::Milestone(milestone_number),
+            ))?;
+
+            log::debug!(
+                "Successfully renamed milestone #{} to '{}'",
+                milestone_number,
+                milestone.title
+            );
+            usage::count_write("milestones_renamed");
+
+            Ok(milestone)
+        }
+    }
+
+    fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &MilestoneUpdate,
+    ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send {
+        let owner = self.owner.clone();
+        let repo = self.repo.clone();
+        let update = update.clone();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.update_milestone(milestone_number, &update).await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!(
+                "Updating milestone #{} in {}/{}: {:?}",
+                milestone_number,
+                owner,
+                repo,
+                update
+            );
+
+            let update_request = update.github_request();
+            let milestone: Milestone = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.patch(
+                    format!(
+                        "/repos/{}/{}/milestones/{}",
+                        &owner, &repo, milestone_number
+                    ),
+                    Some(&update_request),
+                )
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "update_milestone",
+                RequestTarget::Milestone(milestone_number),
+            ))?;
+
+            log::debug!("Successfully updated milestone #{}", milestone_number);
+            usage::count_write("milestones_updated");
 
             Ok(milestone)
         }
     }
 
+    fn close_milestone(
+        &self,
+        milestone_number: u64,
+    ) -> impl Future<Output = Result<Milestone, GitHubApiError>> + Send {
+        let update = MilestoneUpdate {
+            state: Some(MilestoneState::Closed),
+            ..Default::default()
+        };
+        async move { self.update_milestone(milestone_number, &update).await }
+    }
+
     fn post_issue(
         &self,
         issue: &QCIssue,
@@ -126,12 +361,19 @@ impl GitHubWriter for GitInfo {
         let title = issue.title();
         let body = issue.body(self);
         let milestone_id = issue.milestone_id;
-        let branch = issue.branch.clone();
+        let labels = issue.labels();
         let assignees = issue.assignees.clone();
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab
+                    .post_issue(title, body, milestone_id, labels, assignees)
+                    .await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -142,14 +384,20 @@ impl GitHubWriter for GitInfo {
             log::debug!("Posting issue '{}' to {}/{}", title, owner, repo);
 
             let handler = octocrab.issues(owner.clone(), repo.clone());
-            let builder = handler
-                .create(title.clone())
-                .body(first_body)
-                .milestone(Some(milestone_id))
-                .labels(vec!["ghqc".to_string(), branch])
-                .assignees(assignees);
-
-            let issue = builder.send().await.map_err(GitHubApiError::APIError)?;
+            let issue = retry::send(&octocrab, RequestKind::Write, || {
+                handler
+                    .create(title.clone())
+                    .body(first_body.clone())
+                    .milestone(Some(milestone_id))
+                    .labels(labels.clone())
+                    .assignees(assignees.clone())
+                    .send()
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "post_issue",
+                RequestTarget::Milestone(milestone_id),
+            ))?;
 
             log::debug!(
                 "Successfully posted issue #{} to {}/{}",
@@ -167,12 +415,17 @@ impl GitHubWriter for GitInfo {
                     owner,
                     repo
                 );
-                octocrab
-                    .issues(&owner, &repo)
-                    .create_comment(issue.number, continuation)
-                    .await
-                    .map_err(GitHubApiError::APIError)?;
+                let issues = octocrab.issues(&owner, &repo);
+                retry::send(&octocrab, RequestKind::Write, || {
+                    issues.create_comment(issue.number, &continuation)
+                })
+                .await
+                .map_err(GitHubApiError::write_with_ctx(
+                    "post_issue",
+                    RequestTarget::Issue(issue.number),
+                ))?;
             }
+            usage::count_write("issues_created");
 
             Ok(issue)
         }
@@ -185,12 +438,18 @@ impl GitHubWriter for GitInfo {
         let owner = self.owner.clone();
         let repo = self.repo.clone();
         let issue_number = comment.issue().number;
-        let body = comment.generate_body(self);
+        let body = comment_draft(comment, self, self.trust_remote_mismatch);
         let title = comment.title().to_string();
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            let body = body?;
+            if let Some(gitlab) = gitlab {
+                return gitlab.post_comment(issue_number, body).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -209,11 +468,16 @@ impl GitHubWriter for GitInfo {
                     repo
                 );
 
-                let posted = octocrab
-                    .issues(&owner, &repo)
-                    .create_comment(issue_number, part)
-                    .await
-                    .map_err(GitHubApiError::APIError)?;
+                let issues = octocrab.issues(&owner, &repo);
+
+                let posted = retry::send(&octocrab, RequestKind::Write, || {
+                    issues.create_comment(issue_number, &part)
+                })
+                .await
+                .map_err(GitHubApiError::write_with_ctx(
+                    "post_comment",
+                    RequestTarget::Issue(issue_number),
+                ))?;
 
                 last_url = posted.html_url.to_string();
             }
@@ -225,11 +489,16 @@ impl GitHubWriter for GitInfo {
                 owner,
                 repo
             );
+            usage::count_write("comments_posted");
 
             Ok(last_url)
         }
     }
 
+    fn guard_issue(&self, issue: &Issue) -> Result<(), GitHubApiError> {
+        Ok(guard_issue_change(issue, self, self.trust_remote_mismatch)?)
+    }
+
     fn close_issue(
         &self,
         issue_number: u64,
@@ -239,7 +508,12 @@ impl GitHubWriter for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.close_issue(issue_number).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -250,13 +524,17 @@ impl GitHubWriter for GitInfo {
                 "state": "closed"
             });
 
-            let _: serde_json::Value = octocrab
-                .patch(
+            let _: serde_json::Value = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.patch(
                     format!("/repos/{}/{}/issues/{}", &owner, &repo, issue_number),
                     Some(&update_request),
                 )
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "close_issue",
+                RequestTarget::Issue(issue_number),
+            ))?;
 
             log::debug!(
                 "Successfully closed issue #{} in {}/{}",
@@ -264,6 +542,7 @@ impl GitHubWriter for GitInfo {
                 owner,
                 repo
             );
+            usage::count_write("issues_closed");
 
             Ok(())
         }
@@ -278,7 +557,12 @@ impl GitHubWriter for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.open_issue(issue_number).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -289,13 +573,17 @@ impl GitHubWriter for GitInfo {
                 "state": "open"
             });
 
-            let _: serde_json::Value = octocrab
-                .patch(
+            let _: serde_json::Value = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.patch(
                     format!("/repos/{}/{}/issues/{}", &owner, &repo, issue_number),
                     Some(&update_request),
                 )
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "open_issue",
+                RequestTarget::Issue(issue_number),
+            ))?;
 
             log::debug!(
                 "Successfully opened issue #{} in {}/{}",
@@ -303,6 +591,7 @@ impl GitHubWriter for GitInfo {
                 owner,
                 repo
             );
+            usage::count_write("issues_reopened");
 
             Ok(())
         }
@@ -320,7 +609,12 @@ impl GitHubWriter for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.create_label(&name, &color).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -331,17 +625,152 @@ impl GitHubWriter for GitInfo {
                 owner,
                 repo
             );
-            octocrab
-                .issues(&owner, &repo)
-                .create_label(&name, &color, "")
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            let issues = octocrab.issues(&owner, &repo);
+            retry::send(&octocrab, RequestKind::Write, || {
+                issues.create_label(&name, &color, "")
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "create_label",
+                RequestTarget::Label(name.clone()),
+            ))?;
 
             log::debug!("Successfully created label '{}'", name);
             Ok(())
         }
     }
 
+    fn add_labels(
+        &self,
+        issue_number: u64,
+        labels: &[String],
+    ) -> impl Future<Output = Result<(), GitHubApiError>> + Send {
+        let owner = self.owner.clone();
+        let repo = self.repo.clone();
+        let labels = labels.to_vec();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.add_labels(issue_number, &labels).await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!(
+                "Adding labels {:?} to issue #{} in {}/{}",
+                labels,
+                issue_number,
+                owner,
+                repo
+            );
+            let issues = octocrab.issues(&owner, &repo);
+            retry::send(&octocrab, RequestKind::Write, || {
+                issues.add_labels(issue_number, &labels)
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "add_labels",
+                RequestTarget::Issue(issue_number),
+            ))?;
+
+            log::debug!("Successfully added labels to issue #{}", issue_number);
+            Ok(())
+        }
+    }
+
+    fn remove_label(
+        &self,
+        issue_number: u64,
+        label: &str,
+    ) -> impl Future<Output = Result<(), GitHubApiError>> + Send {
+        let owner = self.owner.clone();
+        let repo = self.repo.clone();
+        let label = label.to_string();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.remove_label(issue_number, &label).await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!(
+                "Removing label '{}' from issue #{} in {}/{}",
+                label,
+                issue_number,
+                owner,
+                repo
+            );
+            let issues = octocrab.issues(&owner, &repo);
+            retry::send(&octocrab, RequestKind::Write, || {
+                issues.remove_label(issue_number, &label)
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "remove_label",
+                RequestTarget::Issue(issue_number),
+            ))?;
+
+            log::debug!("Successfully removed label '{}'", label);
+            Ok(())
+        }
+    }
+
+    fn set_assignees(
+        &self,
+        issue_number: u64,
+        assignees: &[String],
+    ) -> impl Future<Output = Result<(), GitHubApiError>> + Send {
+        let owner = self.owner.clone();
+        let repo = self.repo.clone();
+        let assignees = assignees.to_vec();
+        let base_url = self.base_url.clone();
+        let auth_sources = self.auth_sources.clone();
+
+        let gitlab = self.gitlab.clone();
+
+        async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.set_assignees(issue_number, &assignees).await;
+            }
+            let octocrab = auth_sources
+                .client(&base_url)
+                .map_err(GitHubApiError::ClientCreation)?;
+            log::debug!(
+                "Setting assignees of issue #{} in {}/{} to {:?}",
+                issue_number,
+                owner,
+                repo,
+                assignees
+            );
+
+            // PATCH replaces the whole list, unlike the add/remove assignee endpoints
+            let update_request = serde_json::json!({ "assignees": assignees });
+            let _: serde_json::Value = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.patch(
+                    format!("/repos/{}/{}/issues/{}", &owner, &repo, issue_number),
+                    Some(&update_request),
+                )
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "set_assignees",
+                RequestTarget::Issue(issue_number),
+            ))?;
+
+            log::debug!("Successfully set assignees of issue #{}", issue_number);
+            Ok(())
+        }
+    }
+
     fn block_issue(
         &self,
         blocked_issue_number: u64,
@@ -352,7 +781,16 @@ impl GitHubWriter for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if gitlab.is_some() {
+                log::debug!(
+                    "Blocking links are not supported on GitLab, not linking issue #{}",
+                    blocked_issue_number
+                );
+                return Err(GitHubApiError::NoApi);
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -377,10 +815,14 @@ impl GitHubWriter for GitInfo {
                 "issue_id": blocking_issue_id
             });
 
-            octocrab
-                .post::<_, serde_json::Value>(url, Some(&body))
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.post::<_, serde_json::Value>(&url, Some(&body))
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "block_issue",
+                RequestTarget::Issue(blocked_issue_number),
+            ))?;
 
             log::info!(
                 "Successfully created blocking relationship: issue #{} is now blocked by issue ID {}",
@@ -403,7 +845,12 @@ impl GitHubWriter for GitInfo {
         let base_url = self.base_url.clone();
         let auth_sources = self.auth_sources.clone();
 
+        let gitlab = self.gitlab.clone();
+
         async move {
+            if let Some(gitlab) = gitlab {
+                return gitlab.update_issue(issue_number, new_title, new_body).await;
+            }
             let octocrab = auth_sources
                 .client(&base_url)
                 .map_err(GitHubApiError::ClientCreation)?;
@@ -424,13 +871,17 @@ impl GitHubWriter for GitInfo {
                 patch["body"] = serde_json::Value::String(body);
             }
 
-            let _: serde_json::Value = octocrab
-                .patch(
+            let _: serde_json::Value = retry::send(&octocrab, RequestKind::Write, || {
+                octocrab.patch(
                     format!("/repos/{}/{}/issues/{}", &owner, &repo, issue_number),
                     Some(&patch),
                 )
-                .await
-                .map_err(GitHubApiError::APIError)?;
+            })
+            .await
+            .map_err(GitHubApiError::write_with_ctx(
+                "update_issue",
+                RequestTarget::Issue(issue_number),
+            ))?;
 
             log::debug!(
                 "Successfully updated issue #{} in {}/{}",
@@ -1,10 +1,16 @@
+use super::tls::TlsSettings;
 use crate::auth::{AuthStore, extract_host_from_base_url, validate_github_token};
 use crate::utils::EnvProvider;
-use octocrab::Octocrab;
+use http::{HeaderValue, Uri, header::USER_AGENT};
+use octocrab::service::middleware::{
+    auth_header::AuthHeaderLayer, base_uri::BaseUriLayer, extra_headers::ExtraHeadersLayer,
+};
+use octocrab::{AuthState, Octocrab, OctocrabBuilder};
 use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::Arc;
 use std::time::Duration;
 
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
@@ -37,8 +43,11 @@ impl fmt::Display for AuthSourceKind {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct AuthSources(HashMap<AuthSourceKind, String>);
+#[derive(Debug, Clone, Default)]
+pub struct AuthSources {
+    tokens: HashMap<AuthSourceKind, String>,
+    tls: TlsSettings,
+}
 
 impl AuthSources {
     pub fn new(base_url: &str, env: &impl EnvProvider, auth_store: Option<&AuthStore>) -> Self {
@@ -71,7 +80,21 @@ impl AuthSources {
             res.insert(AuthSourceKind::Netrc, token);
         }
 
-        AuthSources(res)
+        AuthSources {
+            tokens: res,
+            tls: TlsSettings::default(),
+        }
+    }
+
+    /// Verify the API's certificates with `tls` instead of the platform's certificate
+    /// authorities only
+    pub fn with_tls(mut self, tls: TlsSettings) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn tls(&self) -> &TlsSettings {
+        &self.tls
     }
 
     pub fn token(&self) -> Option<&str> {
@@ -82,12 +105,18 @@ impl AuthSources {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.tokens.is_empty()
     }
 
     pub fn client(&self, base_url: &str) -> Result<Octocrab, AuthError> {
         log::debug!("Creating Octocrab client");
+        if let Some(tls_config) = self.tls.client_config() {
+            return self.client_with_tls(base_url, tls_config);
+        }
+
+        // Requests are retried by `git::api::retry`, which knows which are safe to send again
         let mut builder = Octocrab::builder()
+            .add_retry_config(octocrab::service::middleware::retry::RetryConfig::None)
             .set_connect_timeout(Some(CONNECT_TIMEOUT))
             .set_read_timeout(Some(READ_TIMEOUT));
         if let Some(token) = self.token() {
@@ -108,8 +137,74 @@ impl AuthSources {
         .map_err(AuthError::ClientBuild)
     }
 
+    /// The client of [`Self::client`] on an HTTPS connector verifying certificates with
+    /// `tls_config`, which octocrab's default builder does not take
+    fn client_with_tls(
+        &self,
+        base_url: &str,
+        tls_config: Arc<rustls::ClientConfig>,
+    ) -> Result<Octocrab, AuthError> {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config((*tls_config).clone())
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let mut connector = hyper_timeout::TimeoutConnector::new(connector);
+        connector.set_connect_timeout(Some(CONNECT_TIMEOUT));
+        connector.set_read_timeout(Some(READ_TIMEOUT));
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build(connector);
+
+        let (base_uri, upload_uri) = if base_url == "https://github.com" {
+            (
+                "https://api.github.com".to_string(),
+                "https://uploads.github.com".to_string(),
+            )
+        } else {
+            (
+                format!("{base_url}/api/v3"),
+                format!("{base_url}/api/uploads"),
+            )
+        };
+        let invalid_uri = |e: http::uri::InvalidUri| AuthError::InvalidBaseUrl {
+            url: base_url.to_string(),
+            reason: e.to_string(),
+        };
+        let base_uri = base_uri.parse::<Uri>().map_err(invalid_uri)?;
+        let upload_uri = upload_uri.parse::<Uri>().map_err(invalid_uri)?;
+
+        let auth_header = match self.token() {
+            Some(token) => Some(
+                HeaderValue::from_str(&format!("Bearer {token}"))
+                    .map_err(|_| AuthError::InvalidToken)?,
+            ),
+            None => {
+                log::warn!(
+                    "No authentication found. API access will be limited to public repositories"
+                );
+                None
+            }
+        };
+
+        let octocrab = OctocrabBuilder::new_empty()
+            .with_service(client)
+            .with_layer(&BaseUriLayer::new(base_uri.clone()))
+            .with_layer(&ExtraHeadersLayer::new(Arc::new(vec![(
+                USER_AGENT,
+                HeaderValue::from_static("octocrab"),
+            )])))
+            .with_layer(&AuthHeaderLayer::new(auth_header, base_uri, upload_uri))
+            .with_auth(AuthState::None)
+            .build();
+        match octocrab {
+            Ok(octocrab) => Ok(octocrab),
+            Err(infallible) => match infallible {},
+        }
+    }
+
     pub fn sorted(&self) -> Vec<(&AuthSourceKind, &String)> {
-        let mut v = self.0.iter().collect::<Vec<_>>();
+        let mut v = self.tokens.iter().collect::<Vec<_>>();
         v.sort_by(|(a, _), (b, _)| a.cmp(b));
         v
     }
@@ -127,7 +222,7 @@ impl AuthSources {
         ]
         .into_iter()
         .map(|kind| {
-            let token = self.0.get(&kind).map(|s| s.as_str());
+            let token = self.tokens.get(&kind).map(|s| s.as_str());
             (kind, token)
         })
         .collect()
@@ -362,6 +457,12 @@ pub enum AuthError {
     Json(#[from] serde_json::Error),
     #[error("YAML parsing error: {0}")]
     Yaml(#[from] serde_yaml::Error),
+    #[error("Invalid CA bundle {path} (GHQC_CA_BUNDLE): {reason}")]
+    InvalidCaBundle { path: PathBuf, reason: String },
+    #[error("Invalid API base URL {url}: {reason}")]
+    InvalidBaseUrl { url: String, reason: String },
+    #[error("The authentication token contains characters not allowed in an HTTP header")]
+    InvalidToken,
 }
 
 #[cfg(test)]
@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use gix::ObjectId;
+
+use crate::cache::DiskCache;
+use crate::git::{
+    GitCommit, GitCommitOps, GitFileOpsError, find_commits, find_or_cache_file_changes,
+};
+
+/// Branch histories shared by the issues of milestone-wide operations
+///
+/// Building an issue's thread walks the history of its branch. Archives, statuses and records
+/// build the threads of every issue in a milestone, so they share a `CommitIndex` which walks
+/// each branch once per head and hands out the slice each issue needs. Issues whose branch
+/// cannot be walked, or whose initial commit is missing from the walk, fall back to
+/// [`get_commits_robust`].
+///
+/// [`get_commits_robust`]: crate::git::get_commits_robust
+#[derive(Debug, Default)]
+pub struct CommitIndex {
+    branches: Mutex<HashMap<BranchHead, Option<Arc<BranchCommits>>>>,
+}
+
+/// A branch and its tip, `None` when the tip could not be resolved
+type BranchHead = (String, Option<ObjectId>);
+
+impl CommitIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// History of `branch` at its current head, walking it on first use
+    ///
+    /// `None` when the branch cannot be walked, e.g. after it was deleted.
+    pub fn branch(
+        &self,
+        git_info: &impl GitCommitOps,
+        branch: &str,
+        disk_cache: Option<&DiskCache>,
+    ) -> Option<Arc<BranchCommits>> {
+        let branch_ref = Some(branch.to_string());
+        let head = git_info.branch_tip(&branch_ref).ok();
+        let mut branches = self.branches.lock().unwrap();
+        branches
+            .entry((branch.to_string(), head))
+            .or_insert_with(
+                || match find_commits(git_info, &branch_ref, None, disk_cache) {
+                    Ok(commits) => {
+                        log::debug!("Indexed {} commits of branch {branch}", commits.len());
+                        Some(Arc::new(BranchCommits::new(branch, commits)))
+                    }
+                    Err(e) => {
+                        log::debug!("Could not index the commits of branch {branch}: {e}");
+                        None
+                    }
+                },
+            )
+            .clone()
+    }
+}
+
+/// Full history of a branch, newest commit first
+#[derive(Debug)]
+pub struct BranchCommits {
+    branch: String,
+    commits: Vec<GitCommit>,
+    positions: HashMap<ObjectId, usize>,
+    /// Commits touching each file looked up so far, as full SHAs
+    files: Mutex<HashMap<PathBuf, HashSet<String>>>,
+}
+
+impl BranchCommits {
+    fn new(branch: &str, commits: Vec<GitCommit>) -> Self {
+        let positions = commits
+            .iter()
+            .enumerate()
+            .map(|(position, commit)| (commit.commit, position))
+            .collect();
+        Self {
+            branch: branch.to_string(),
+            commits,
+            positions,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Every commit of the branch, newest first
+    pub fn commits(&self) -> &[GitCommit] {
+        &self.commits
+    }
+
+    pub fn get(&self, commit: &ObjectId) -> Option<&GitCommit> {
+        self.positions
+            .get(commit)
+            .map(|&position| &self.commits[position])
+    }
+
+    /// The commit of a full SHA, or of a short SHA of at least 7 characters matching exactly one
+    /// commit of the branch
+    pub fn find(&self, sha: &str) -> Option<&GitCommit> {
+        if let Ok(commit) = sha.parse::<ObjectId>() {
+            return self.get(&commit);
+        }
+        if sha.len() < 7 {
+            return None;
+        }
+        let mut matches = self
+            .commits
+            .iter()
+            .filter(|commit| commit.commit.to_string().starts_with(sha));
+        match (matches.next(), matches.next()) {
+            (Some(commit), None) => Some(commit),
+            _ => None,
+        }
+    }
+
+    /// Commits from the head down to `stop_at` included, all of them when `stop_at` is `None`
+    ///
+    /// `None` when `stop_at` is not in the indexed history, e.g. when it was cut off by a shallow
+    /// clone or taken from a cached walk which stopped earlier.
+    pub fn since(&self, stop_at: Option<ObjectId>) -> Option<Vec<GitCommit>> {
+        let end = match stop_at {
+            Some(stop_at) => self.positions.get(&stop_at)? + 1,
+            None => self.commits.len(),
+        };
+        Some(self.commits[..end].to_vec())
+    }
+
+    /// Full SHAs of the commits of the branch touching `file`, looked up once per file
+    pub fn file_commits(
+        &self,
+        git_info: &impl GitCommitOps,
+        file: &Path,
+        disk_cache: Option<&DiskCache>,
+    ) -> Result<HashSet<String>, GitFileOpsError> {
+        if let Some(touching) = self.files.lock().unwrap().get(file) {
+            return Ok(touching.clone());
+        }
+        let hashes: Vec<String> = self.commits.iter().map(|c| c.commit.to_string()).collect();
+        let touching = find_or_cache_file_changes(
+            &hashes,
+            git_info,
+            Some(self.branch.clone()),
+            file,
+            disk_cache,
+        )?;
+        self.files
+            .lock()
+            .unwrap()
+            .insert(file.to_path_buf(), touching.clone());
+        Ok(touching)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn branch_commits() -> BranchCommits {
+        let commits = [
+            "cccccccccccccccccccccccccccccccccccccccc",
+            "bbbbbbb111111111111111111111111111111111",
+            "bbbbbbb222222222222222222222222222222222",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        ]
+        .into_iter()
+        .map(|hash| GitCommit {
+            commit: ObjectId::from_str(hash).unwrap(),
+            message: String::new(),
+        })
+        .collect();
+        BranchCommits::new("main", commits)
+    }
+
+    #[test]
+    fn test_find_full_and_short_shas() {
+        let history = branch_commits();
+        let hash = |commit: Option<&GitCommit>| commit.map(|c| c.commit.to_string());
+
+        assert_eq!(
+            hash(history.find("cccccccccccccccccccccccccccccccccccccccc")),
+            Some("cccccccccccccccccccccccccccccccccccccccc".to_string())
+        );
+        assert_eq!(
+            hash(history.find("aaaaaaa")),
+            Some("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string())
+        );
+        // Ambiguous, too short, or not on the branch
+        assert_eq!(hash(history.find("bbbbbbb")), None);
+        assert_eq!(hash(history.find("cccc")), None);
+        assert_eq!(hash(history.find("ddddddd")), None);
+    }
+
+    #[test]
+    fn test_since_stops_at_commit() {
+        let history = branch_commits();
+        let stop_at = ObjectId::from_str("bbbbbbb111111111111111111111111111111111").unwrap();
+        let missing = ObjectId::from_str("dddddddddddddddddddddddddddddddddddddddd").unwrap();
+
+        let commits = history.since(Some(stop_at)).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits.last().unwrap().commit, stop_at);
+        assert_eq!(history.since(None).unwrap().len(), 4);
+        assert!(history.since(Some(missing)).is_none());
+    }
+}
@@ -1,18 +1,25 @@
 use std::{
     collections::HashSet,
     fmt,
+    io::{self, Read},
     path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
     str::FromStr,
 };
 
 use crate::{
-    DiskCache, GitInfo,
+    DiskCache, FileRenameEvent, GitInfo,
     cache::{CachedCommit, FileChangeRecord},
-    git::action::GitCli,
+    git::{
+        action::{FileLastCommit, GitCli},
+        signature::{SignatureStatus, SigningKeys, commit_signature_status},
+    },
 };
+use chrono::{DateTime, Utc};
 use gix::ObjectId;
 #[cfg(test)]
 use mockall::automock;
+use serde::Serialize;
 
 #[derive(Debug, Clone)]
 pub struct GitAuthor {
@@ -64,17 +71,91 @@ pub enum GitFileOpsError {
     #[error("Failed to get HEAD ID: {0}")]
     HeadIdError(gix::reference::head_id::Error),
     #[error("Failed to access repository: {0}")]
-    RepositoryError(#[from] crate::git::GitInfoError),
+    RepositoryError(Box<crate::git::GitInfoError>),
     #[error("Directory not found in git tree: {0}")]
     DirectoryNotFound(String),
     #[error("Path is not a directory: {0}")]
     NotADirectory(String),
     #[error("Failed to parse commit SHA: {0}")]
     ParseError(String),
+    #[error("Commit not found: {0}")]
+    CommitNotFound(String),
     #[error("Git CLI error: {0}")]
     GitCliError(#[from] crate::git::action::GitCliError),
 }
 
+impl From<crate::git::GitInfoError> for GitFileOpsError {
+    fn from(error: crate::git::GitInfoError) -> Self {
+        Self::RepositoryError(Box::new(error))
+    }
+}
+
+/// Reads a blob from the output of `git cat-file`, started on the first read
+///
+/// gix decodes whole objects into memory, which large data files do not fit in.
+struct BlobReader {
+    repository_path: PathBuf,
+    blob: ObjectId,
+    child: Option<Child>,
+}
+
+impl BlobReader {
+    fn new(repository_path: &Path, blob: ObjectId) -> Self {
+        Self {
+            repository_path: repository_path.to_path_buf(),
+            blob,
+            child: None,
+        }
+    }
+}
+
+impl Read for BlobReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let child = match &mut self.child {
+            Some(child) => child,
+            None => self.child.insert(
+                Command::new("git")
+                    .arg("-C")
+                    .arg(&self.repository_path)
+                    .args(["cat-file", "blob", &self.blob.to_string()])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?,
+            ),
+        };
+
+        let read = child
+            .stdout
+            .as_mut()
+            .expect("stdout to be piped")
+            .read(buf)?;
+        if read == 0 && !buf.is_empty() && !child.wait()?.success() {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                pipe.read_to_string(&mut stderr)?;
+            }
+            return Err(io::Error::other(format!(
+                "git cat-file failed to read blob {}: {}",
+                self.blob,
+                stderr.trim()
+            )));
+        }
+        Ok(read)
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        // Stop git when the content is not read to the end
+        if let Some(child) = &mut self.child
+            && child.try_wait().ok().flatten().is_none()
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
 // ──────────────────────────────────────────────────────────────────────────────
 // GitCommitOps — commit history and branch operations
 // ──────────────────────────────────────────────────────────────────────────────
@@ -100,6 +181,28 @@ pub trait GitCommitOps {
         file: &Path,
     ) -> Result<HashSet<String>, GitFileOpsError>;
 
+    /// The renames `file` went through on `branch` (or HEAD if None) after `since`, oldest
+    /// first, following it both to the names it was given and from the names it had.
+    ///
+    /// Implementations without rename detection, and repositories with rename tracking turned
+    /// off, report none.
+    fn file_renames(
+        &self,
+        branch: Option<String>,
+        file: &Path,
+        since: Option<ObjectId>,
+    ) -> Result<Vec<FileRenameEvent>, GitFileOpsError> {
+        let _ = (branch, file, since);
+        Ok(Vec::new())
+    }
+
+    /// Every file on `branch` (or HEAD if None) with when it was last committed, `None` when
+    /// its last commit is missing from a shallow clone.
+    fn files_by_last_commit(
+        &self,
+        branch: &Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitFileOpsError>;
+
     /// Return the names of all local and remote branches that contain `commit`.
     fn get_branches_containing_commit(
         &self,
@@ -115,6 +218,28 @@ pub trait GitCommitOps {
         &self,
         target_commit: &ObjectId,
     ) -> Result<Option<String>, GitFileOpsError>;
+
+    /// Find the merge commit on HEAD that incorporated `target_commit`, if any.
+    fn find_merge_commit(
+        &self,
+        target_commit: &ObjectId,
+    ) -> Result<Option<ObjectId>, GitFileOpsError>;
+
+    /// Whether `commit` is signed and, when trusted `keys` are configured, whether it verifies.
+    fn signature_status(
+        &self,
+        commit: &ObjectId,
+        keys: &SigningKeys,
+    ) -> Result<SignatureStatus, GitFileOpsError>;
+
+    /// When `commit` was committed.
+    fn commit_time(&self, commit: &ObjectId) -> Result<DateTime<Utc>, GitFileOpsError>;
+
+    /// Who authored `commit`.
+    fn commit_author(&self, commit: &ObjectId) -> Result<GitAuthor, GitFileOpsError>;
+
+    /// Whether the repository is a shallow clone, missing history before its cut-off.
+    fn is_shallow(&self) -> bool;
 }
 
 impl GitCommitOps for GitInfo {
@@ -181,6 +306,36 @@ impl GitCommitOps for GitInfo {
             })
     }
 
+    fn file_renames(
+        &self,
+        branch: Option<String>,
+        file: &Path,
+        since: Option<ObjectId>,
+    ) -> Result<Vec<FileRenameEvent>, GitFileOpsError> {
+        if !self.follow_renames {
+            return Ok(Vec::new());
+        }
+        let renames = self
+            .command
+            .renames(branch, since.map(|id| id.to_string()))?
+            .into_iter()
+            .map(|(commit, old_path, new_path)| FileRenameEvent {
+                old_path,
+                new_path,
+                commit,
+            })
+            .collect::<Vec<_>>();
+        Ok(rename_chain(file, &renames))
+    }
+
+    fn files_by_last_commit(
+        &self,
+        branch: &Option<String>,
+    ) -> Result<Vec<FileLastCommit>, GitFileOpsError> {
+        log::debug!("Getting files by last commit for branch: {:?}", branch);
+        Ok(self.command.files_last_committed(branch.clone())?)
+    }
+
     fn get_branches_containing_commit(
         &self,
         commit: &ObjectId,
@@ -235,6 +390,21 @@ impl GitCommitOps for GitInfo {
         &self,
         target_commit: &ObjectId,
     ) -> Result<Option<String>, GitFileOpsError> {
+        let Some(merge_commit) = self.find_merge_commit(target_commit)? else {
+            return Ok(None);
+        };
+
+        // Find which branch contains that merge commit
+        Ok(self
+            .get_branches_containing_commit(&merge_commit)?
+            .into_iter()
+            .next())
+    }
+
+    fn find_merge_commit(
+        &self,
+        target_commit: &ObjectId,
+    ) -> Result<Option<ObjectId>, GitFileOpsError> {
         let repo_str = self.repository_path.to_string_lossy().to_string();
         let target_commit_str = target_commit.to_string();
 
@@ -256,54 +426,48 @@ impl GitCommitOps for GitInfo {
             return Ok(None);
         }
 
-        let merge_sha = String::from_utf8_lossy(&output.stdout)
+        String::from_utf8_lossy(&output.stdout)
             .lines()
             .last()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty());
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| ObjectId::from_str(s).map_err(|_| GitFileOpsError::ParseError(s.to_string())))
+            .transpose()
+    }
 
-        let merge_sha = match merge_sha {
-            Some(s) => s,
-            None => return Ok(None),
-        };
+    fn signature_status(
+        &self,
+        commit: &ObjectId,
+        keys: &SigningKeys,
+    ) -> Result<SignatureStatus, GitFileOpsError> {
+        Ok(commit_signature_status(&self.command, commit, keys)?)
+    }
 
-        // Find which branch contains that merge commit
-        let local = std::process::Command::new("git")
-            .args([
-                "-C",
-                &repo_str,
-                "branch",
-                "--contains",
-                &merge_sha,
-                "--format=%(refname:short)",
-            ])
-            .output()
-            .map_err(|e| GitFileOpsError::BranchLookupFailed(e.to_string()))?;
-        let remote = std::process::Command::new("git")
-            .args([
-                "-C",
-                &repo_str,
-                "branch",
-                "-r",
-                "--contains",
-                &merge_sha,
-                "--format=%(refname:short)",
-            ])
-            .output()
-            .map_err(|e| GitFileOpsError::BranchLookupFailed(e.to_string()))?;
+    fn commit_time(&self, commit: &ObjectId) -> Result<DateTime<Utc>, GitFileOpsError> {
+        Ok(self.command.commit_time(&commit.to_string())?)
+    }
 
-        let branches: Vec<String> = [local.stdout, remote.stdout]
-            .iter()
-            .flat_map(|b| {
-                String::from_utf8_lossy(b)
-                    .lines()
-                    .map(|l| l.trim().to_string())
-                    .filter(|l| !l.is_empty() && l != "HEAD" && !l.ends_with("/HEAD"))
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+    fn commit_author(&self, commit: &ObjectId) -> Result<GitAuthor, GitFileOpsError> {
+        let repo = self.repository()?;
+        let commit_obj = repo
+            .find_object(*commit)
+            .map_err(GitFileOpsError::ObjectError)?
+            .try_into_commit()
+            .map_err(GitFileOpsError::CommitError)?;
+
+        let signature = commit_obj
+            .author()
+            .map_err(GitFileOpsError::SignatureError)?;
+        Ok(GitAuthor {
+            name: signature.name.to_string(),
+            email: signature.email.to_string(),
+        })
+    }
 
-        Ok(branches.into_iter().next())
+    fn is_shallow(&self) -> bool {
+        self.repository()
+            .map(|repo| repo.is_shallow())
+            .unwrap_or(false)
     }
 }
 
@@ -311,6 +475,37 @@ impl GitCommitOps for GitInfo {
 // GitFileOps — file content and metadata operations
 // ──────────────────────────────────────────────────────────────────────────────
 
+/// `.gitattributes` of a path that change how its content is presented
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathAttributes {
+    /// Marked `-diff` (or `binary`): git shows no content diff for the path
+    pub no_diff: bool,
+    /// Marked `linguist-generated`
+    pub generated: bool,
+}
+
+impl PathAttributes {
+    /// Whether content diffs of the path are noise rather than something to review
+    pub fn suppresses_diff(&self) -> bool {
+        self.no_diff || self.generated
+    }
+}
+
+/// Content of a file at a commit, read as it is copied instead of loaded into memory
+pub struct FileReader {
+    /// Size of the content in bytes
+    pub size: u64,
+    pub reader: Box<dyn Read + Send>,
+}
+
+impl fmt::Debug for FileReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileReader")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
 /// File-content and metadata git operations.
 #[cfg_attr(test, automock)]
 pub trait GitFileOps {
@@ -325,11 +520,29 @@ pub trait GitFileOps {
         commit: &ObjectId,
     ) -> Result<Vec<u8>, GitFileOpsError>;
 
+    /// Get a reader of the file's content at a specific commit, for copying large files
+    ///
+    /// `None` when the provider cannot stream content, in which case callers read it with
+    /// [`file_bytes_at_commit`](Self::file_bytes_at_commit) instead.
+    fn file_reader_at_commit(
+        &self,
+        _file: &Path,
+        _commit: &ObjectId,
+    ) -> Result<Option<FileReader>, GitFileOpsError> {
+        Ok(None)
+    }
+
     /// List immediate children of `path` in the HEAD commit tree.
     /// `path` is repo-relative, slash-separated, no leading/trailing slash.
     /// Empty string = repo root.
     /// Returns `(name, is_directory)` pairs sorted: dirs first, then files, alpha within each.
     fn list_tree_entries(&self, path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError>;
+
+    /// `.gitattributes` of `file` relevant to diffing it
+    fn path_attributes(&self, file: &Path) -> Result<PathAttributes, GitFileOpsError>;
+
+    /// Resolve a commit-ish (hash, branch, tag, `HEAD`, ...) to the commit it points to
+    fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError>;
 }
 
 impl GitFileOps for GitInfo {
@@ -420,6 +633,47 @@ impl GitFileOps for GitInfo {
         Ok(blob.data.clone())
     }
 
+    fn file_reader_at_commit(
+        &self,
+        file: &Path,
+        commit: &ObjectId,
+    ) -> Result<Option<FileReader>, GitFileOpsError> {
+        let repo = self.repository()?;
+        let tree = repo
+            .find_object(*commit)
+            .map_err(GitFileOpsError::ObjectError)?
+            .try_into_commit()
+            .map_err(GitFileOpsError::CommitError)?
+            .tree()
+            .map_err(GitFileOpsError::TreeError)?;
+        let entry = tree
+            .lookup_entry_by_path(file)
+            .map_err(|_| GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))?
+            .ok_or_else(|| GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()))?;
+
+        // The header gives the size without decoding the blob
+        let header = repo
+            .find_header(entry.oid())
+            .map_err(GitFileOpsError::ObjectError)?;
+        if header.kind() != gix::object::Kind::Blob {
+            return Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()));
+        }
+
+        log::debug!(
+            "Streaming {} bytes of {:?} at commit {}",
+            header.size(),
+            file,
+            commit
+        );
+        Ok(Some(FileReader {
+            size: header.size(),
+            reader: Box::new(BlobReader::new(
+                &self.repository_path,
+                entry.oid().to_owned(),
+            )),
+        }))
+    }
+
     fn list_tree_entries(&self, path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
         let repo = self.repository()?;
         let head_id = repo.head_id().map_err(GitFileOpsError::HeadIdError)?;
@@ -469,6 +723,27 @@ impl GitFileOps for GitInfo {
 
         Ok(result)
     }
+
+    fn path_attributes(&self, file: &Path) -> Result<PathAttributes, GitFileOpsError> {
+        let attributes = self
+            .command
+            .check_attr(file, &["diff", "linguist-generated"])?;
+
+        Ok(PathAttributes {
+            no_diff: attributes.get("diff").is_some_and(|v| v == "unset"),
+            generated: attributes
+                .get("linguist-generated")
+                .is_some_and(|v| v == "set" || v == "true"),
+        })
+    }
+
+    fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+        let hash = self
+            .command
+            .rev_parse(commitish)
+            .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))?;
+        ObjectId::from_str(&hash).map_err(|e| GitFileOpsError::ParseError(e.to_string()))
+    }
 }
 
 // ──────────────────────────────────────────────────────────────────────────────
@@ -602,28 +877,164 @@ pub fn find_commits(
     }
 }
 
+/// History [`get_commits_robust`] took an issue's commits from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "branch", rename_all = "snake_case")]
+pub enum CommitSource {
+    /// The branch the issue was created on
+    #[default]
+    IssueBranch,
+    /// The branch the issue's commits were merged into
+    MergedInto(String),
+    /// Another branch containing the issue's commits
+    ContainingBranch(String),
+    /// The default branch (HEAD)
+    DefaultBranch,
+}
+
+impl fmt::Display for CommitSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitSource::IssueBranch => write!(f, "issue branch"),
+            CommitSource::MergedInto(branch) => write!(f, "merge target '{branch}'"),
+            CommitSource::ContainingBranch(branch) => write!(f, "branch '{branch}'"),
+            CommitSource::DefaultBranch => write!(f, "default branch"),
+        }
+    }
+}
+
+/// Why a [`CommitSource`] did not provide the issue's commits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum RetrievalFailure {
+    /// The branch is not checked out or tracked locally
+    BranchNotFound(String),
+    /// The history does not contain the issue's reference commit (short SHA)
+    ReferenceCommitMissing(String),
+    /// The history does not contain the reference commit and the clone is shallow
+    ShallowHistory,
+    /// Reading the history failed
+    Failed(String),
+}
+
+impl fmt::Display for RetrievalFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetrievalFailure::BranchNotFound(branch) => {
+                write!(f, "branch '{branch}' not found locally")
+            }
+            RetrievalFailure::ReferenceCommitMissing(commit) => {
+                write!(f, "commit {commit} not found in local history")
+            }
+            RetrievalFailure::ShallowHistory => write!(f, "history is shallow"),
+            RetrievalFailure::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RetrievalAttempt {
+    pub source: CommitSource,
+    pub failure: RetrievalFailure,
+}
+
+/// How [`get_commits_robust`] resolved an issue's commit history
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CommitRetrievalInfo {
+    /// The history the commits were taken from
+    pub source: CommitSource,
+    /// Sources tried before `source`, in order, with why each did not work
+    pub attempts: Vec<RetrievalAttempt>,
+    /// The repository is a shallow clone and the commits may stop short of the issue's history
+    pub may_be_incomplete: bool,
+}
+
+impl CommitRetrievalInfo {
+    /// Whether the commits came from another history than the issue's branch
+    pub fn is_fallback(&self) -> bool {
+        self.source != CommitSource::IssueBranch
+    }
+
+    /// Whether the commits are not known to be the issue branch's complete history
+    pub fn is_degraded(&self) -> bool {
+        self.is_fallback() || self.may_be_incomplete
+    }
+
+    /// One-line warning for status output, `None` when the history is not degraded
+    pub fn caveat(&self) -> Option<String> {
+        if !self.is_fallback() {
+            return self.may_be_incomplete.then(|| {
+                format!(
+                    "⚠ commit history may be incomplete: {} — run git fetch --unshallow",
+                    RetrievalFailure::ShallowHistory
+                )
+            });
+        }
+
+        let reasons = self
+            .attempts
+            .iter()
+            .map(|attempt| attempt.failure.to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        let hint = if self.may_be_incomplete {
+            "run git fetch --unshallow"
+        } else {
+            "run git fetch"
+        };
+        Some(format!(
+            "⚠ commit history resolved via fallback: {reasons} — {hint}"
+        ))
+    }
+
+    fn attempt(&mut self, source: CommitSource, failure: RetrievalFailure) {
+        log::debug!("Could not get commits from {source}: {failure}");
+        self.attempts.push(RetrievalAttempt { source, failure });
+    }
+
+    fn resolved(mut self, source: CommitSource, git_info: &impl GitCommitOps) -> Self {
+        self.may_be_incomplete = git_info.is_shallow();
+        self.source = source;
+        self
+    }
+}
+
 /// Get commits with robust branch handling
 /// 1. Try the specified branch first (via `find_commits` cache)
 /// 2. If commit is provided and branch not found, find merged branch using commit analysis
 /// 3. Fall back to searching all branches containing the commit
+/// 4. Fall back to the default branch (HEAD) history if it contains the commit
+///
+/// Returns, alongside the commits, which of these provided them and why the earlier ones failed.
 pub fn get_commits_robust(
     git_info: &impl GitCommitOps,
     branch: &Option<String>,
     commit: Option<&ObjectId>,
     stop_at: Option<ObjectId>,
     disk_cache: Option<&DiskCache>,
-) -> Result<Vec<GitCommit>, GitFileOpsError> {
+) -> Result<(Vec<GitCommit>, CommitRetrievalInfo), GitFileOpsError> {
+    let mut info = CommitRetrievalInfo::default();
+
     // First, try to get commits from the specified branch
     match find_commits(git_info, branch, stop_at, disk_cache) {
         Ok(commits) => {
             log::debug!("Found {} commits for branch {:?}", commits.len(), branch);
-            return Ok(commits);
+            // A shallow clone can cut the walk off before it reaches the initial commit
+            if let Some(stop_at) = stop_at {
+                info.may_be_incomplete =
+                    !commits.iter().any(|c| c.commit == stop_at) && git_info.is_shallow();
+            }
+            return Ok((commits, info));
         }
-        Err(GitFileOpsError::LocalBranchNotFound(_)) if branch.is_some() => {
+        Err(GitFileOpsError::LocalBranchNotFound(name)) if branch.is_some() => {
             log::debug!(
                 "Branch {:?} not found locally, searching for merged commits",
                 branch
             );
+            info.attempt(
+                CommitSource::IssueBranch,
+                RetrievalFailure::BranchNotFound(branch.clone().unwrap_or(name)),
+            );
         }
         Err(e) => {
             return Err(e);
@@ -650,7 +1061,8 @@ pub fn get_commits_robust(
                         commits.len(),
                         target_branch
                     );
-                    return Ok(commits);
+                    let info = info.resolved(CommitSource::MergedInto(target_branch), git_info);
+                    return Ok((commits, info));
                 }
                 Err(e) => {
                     log::warn!(
@@ -658,6 +1070,10 @@ pub fn get_commits_robust(
                         target_branch,
                         e
                     );
+                    info.attempt(
+                        CommitSource::MergedInto(target_branch),
+                        RetrievalFailure::Failed(e.to_string()),
+                    );
                 }
             }
         }
@@ -682,19 +1098,58 @@ pub fn get_commits_robust(
                             commits.len(),
                             branch_name
                         );
-                        return Ok(commits);
+                        let info =
+                            info.resolved(CommitSource::ContainingBranch(branch_name), git_info);
+                        return Ok((commits, info));
                     }
                     Ok(_) => {
                         log::debug!("Branch {} contains commit but has no commits?", branch_name);
+                        let failure = RetrievalFailure::Failed(format!(
+                            "branch '{branch_name}' has no commits"
+                        ));
+                        info.attempt(CommitSource::ContainingBranch(branch_name), failure);
                     }
                     Err(e) => {
-                        log::debug!("Failed to get commits from branch {}: {}", branch_name, e);
+                        info.attempt(
+                            CommitSource::ContainingBranch(branch_name),
+                            RetrievalFailure::Failed(e.to_string()),
+                        );
                     }
                 }
             }
         }
     }
 
+    // Fall back to the default branch's history when it contains the commit, e.g. after the
+    // branch was merged and deleted
+    if let Some(commit) = commit {
+        match find_commits(git_info, &None, stop_at, disk_cache) {
+            Ok(commits) if commits.iter().any(|c| &c.commit == commit) => {
+                log::debug!(
+                    "Found commit {} in default branch history ({} commits)",
+                    commit,
+                    commits.len()
+                );
+                let info = info.resolved(CommitSource::DefaultBranch, git_info);
+                return Ok((commits, info));
+            }
+            Ok(_) => {
+                let failure = if git_info.is_shallow() {
+                    RetrievalFailure::ShallowHistory
+                } else {
+                    RetrievalFailure::ReferenceCommitMissing(commit.to_string()[..7].to_string())
+                };
+                info.attempt(CommitSource::DefaultBranch, failure);
+            }
+            Err(e) => {
+                info.attempt(
+                    CommitSource::DefaultBranch,
+                    RetrievalFailure::Failed(e.to_string()),
+                );
+            }
+        }
+    }
+
     // Final fallback: return error that branch couldn't be found
     if let Some(branch_name) = branch {
         // We know the branch name — surface it as a structured "not checked
@@ -707,6 +1162,120 @@ pub fn get_commits_robust(
     }
 }
 
+/// State of the branch an issue was created on
+#[derive(Debug, Clone, PartialEq)]
+pub enum BranchState {
+    /// The branch exists as a local or remote-tracking branch
+    Present,
+    /// The branch is gone, but its commits were merged into `target` by `merge_commit`
+    Merged {
+        target: String,
+        merge_commit: ObjectId,
+    },
+    /// The branch is gone and where it was merged could not be determined
+    Deleted,
+}
+
+impl fmt::Display for BranchState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Present => Ok(()),
+            Self::Merged {
+                target,
+                merge_commit,
+            } => write!(
+                f,
+                "deleted — merged into {target} at {}",
+                &merge_commit.to_string()[..7]
+            ),
+            Self::Deleted => write!(f, "deleted"),
+        }
+    }
+}
+
+/// Whether `branch` exists as a local branch, or as a remote-tracking branch containing `commit`
+pub fn branch_exists(
+    git_info: &impl GitCommitOps,
+    branch: &str,
+    commit: Option<&ObjectId>,
+) -> bool {
+    if git_info.branch_tip(&Some(branch.to_string())).is_ok() {
+        return true;
+    }
+
+    let Some(commit) = commit else {
+        return false;
+    };
+
+    match git_info.get_branches_containing_commit(commit) {
+        Ok(branches) => branches.iter().any(|name| {
+            name == branch
+                || name
+                    .split_once('/')
+                    .is_some_and(|(_remote, remote_branch)| remote_branch == branch)
+        }),
+        Err(e) => {
+            log::debug!("Failed to find branches containing {}: {}", commit, e);
+            // Assume the branch exists rather than reporting it deleted on a lookup failure
+            true
+        }
+    }
+}
+
+/// Determine whether `branch` still exists and, if not, where `commit` was merged
+///
+/// `commit` should be a commit made on the branch, such as its latest QC commit.
+pub fn infer_branch_state(
+    git_info: &impl GitCommitOps,
+    branch: &str,
+    commit: &ObjectId,
+) -> Result<BranchState, GitFileOpsError> {
+    if branch_exists(git_info, branch, Some(commit)) {
+        return Ok(BranchState::Present);
+    }
+
+    let Some(merge_commit) = git_info.find_merge_commit(commit)? else {
+        return Ok(BranchState::Deleted);
+    };
+
+    Ok(git_info
+        .get_branches_containing_commit(&merge_commit)?
+        .into_iter()
+        .next()
+        .map(|target| BranchState::Merged {
+            target,
+            merge_commit,
+        })
+        .unwrap_or(BranchState::Deleted))
+}
+
+/// The renames among `renames` (oldest first) that lead to `file` and that `file` went
+/// through afterwards, oldest first
+pub(crate) fn rename_chain(file: &Path, renames: &[FileRenameEvent]) -> Vec<FileRenameEvent> {
+    let file = file.to_string_lossy();
+
+    let mut earlier = Vec::new();
+    let mut name = file.to_string();
+    for rename in renames.iter().rev() {
+        if rename.new_path == name {
+            name = rename.old_path.clone();
+            earlier.push(rename.clone());
+        }
+    }
+    earlier.reverse();
+
+    let mut later = Vec::new();
+    let mut name = file.to_string();
+    for rename in renames {
+        if rename.old_path == name {
+            name = rename.new_path.clone();
+            later.push(rename.clone());
+        }
+    }
+
+    earlier.into_iter().chain(later).collect()
+}
+
 /// For a list of commit hashes, determine which touch `file` on `branch`.
 ///
 /// Checks the disk cache first: if every commit already has a `FileChangeRecord` for `file`,
@@ -809,15 +1378,36 @@ mod tests {
     struct RobustMockGitInfo {
         file_commits_responses:
             HashMap<Option<String>, Result<Vec<(ObjectId, String)>, GitFileOpsError>>,
+        merged_into: Option<String>,
+        containing_branches: Vec<String>,
+        shallow: bool,
     }
 
     impl RobustMockGitInfo {
         fn new() -> Self {
             Self {
                 file_commits_responses: HashMap::new(),
+                merged_into: None,
+                containing_branches: Vec::new(),
+                shallow: false,
             }
         }
 
+        fn with_merged_into(mut self, branch: &str) -> Self {
+            self.merged_into = Some(branch.to_string());
+            self
+        }
+
+        fn with_containing_branches(mut self, branches: &[&str]) -> Self {
+            self.containing_branches = branches.iter().map(|b| b.to_string()).collect();
+            self
+        }
+
+        fn with_shallow(mut self) -> Self {
+            self.shallow = true;
+            self
+        }
+
         fn with_file_commits_result(
             mut self,
             branch: Option<String>,
@@ -869,19 +1459,59 @@ mod tests {
             Ok(HashSet::new())
         }
 
+        fn files_by_last_commit(
+            &self,
+            _branch: &Option<String>,
+        ) -> Result<Vec<FileLastCommit>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
         fn get_branches_containing_commit(
             &self,
             _commit: &ObjectId,
         ) -> Result<Vec<String>, GitFileOpsError> {
-            Ok(Vec::new())
+            Ok(self.containing_branches.clone())
         }
 
         fn find_merged_into_branch(
             &self,
             _target_commit: &ObjectId,
         ) -> Result<Option<String>, GitFileOpsError> {
+            Ok(self.merged_into.clone())
+        }
+
+        fn find_merge_commit(
+            &self,
+            _target_commit: &ObjectId,
+        ) -> Result<Option<ObjectId>, GitFileOpsError> {
             Ok(None)
         }
+
+        fn signature_status(
+            &self,
+            _commit: &ObjectId,
+            _keys: &SigningKeys,
+        ) -> Result<SignatureStatus, GitFileOpsError> {
+            Ok(SignatureStatus::Unsigned)
+        }
+
+        fn commit_time(
+            &self,
+            _commit: &ObjectId,
+        ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+            Ok(chrono::DateTime::UNIX_EPOCH)
+        }
+
+        fn commit_author(&self, _commit: &ObjectId) -> Result<crate::GitAuthor, GitFileOpsError> {
+            Ok(crate::GitAuthor {
+                name: "Test Author".to_string(),
+                email: "test@example.com".to_string(),
+            })
+        }
+
+        fn is_shallow(&self) -> bool {
+            self.shallow
+        }
     }
 
     #[tokio::test]
@@ -894,9 +1524,11 @@ mod tests {
         let git_info = RobustMockGitInfo::new()
             .with_file_commits_result(branch.clone(), Ok(test_commits.clone()));
 
-        let result =
+        let (result, info) =
             get_commits_robust(&git_info, &branch, Some(&initial_commit), None, None).unwrap();
 
+        assert_eq!(info, CommitRetrievalInfo::default());
+        assert_eq!(info.caveat(), None);
         assert_eq!(result.len(), test_commits.len());
         let result_tuples: Vec<(ObjectId, String)> = result
             .iter()
@@ -905,6 +1537,256 @@ mod tests {
         assert_eq!(result_tuples, test_commits);
     }
 
+    #[test]
+    fn test_get_commits_robust_falls_back_to_default_branch() {
+        let test_commits = create_test_commits();
+        let initial_commit = test_commits[2].0;
+        let branch = Some("deleted-branch".to_string());
+
+        let git_info = RobustMockGitInfo::new()
+            .with_file_commits_result(
+                branch.clone(),
+                Err(GitFileOpsError::LocalBranchNotFound(
+                    "deleted-branch".to_string(),
+                )),
+            )
+            .with_file_commits_result(None, Ok(test_commits.clone()));
+
+        let (result, info) =
+            get_commits_robust(&git_info, &branch, Some(&initial_commit), None, None).unwrap();
+        assert_eq!(result.len(), test_commits.len());
+        assert_eq!(info.source, CommitSource::DefaultBranch);
+        assert_eq!(
+            info.attempts,
+            vec![RetrievalAttempt {
+                source: CommitSource::IssueBranch,
+                failure: RetrievalFailure::BranchNotFound("deleted-branch".to_string()),
+            }]
+        );
+        assert!(!info.may_be_incomplete);
+        assert_eq!(
+            info.caveat().unwrap(),
+            "⚠ commit history resolved via fallback: branch 'deleted-branch' not found locally — run git fetch"
+        );
+    }
+
+    fn missing_branch_git_info(branch: &str) -> RobustMockGitInfo {
+        RobustMockGitInfo::new().with_file_commits_result(
+            Some(branch.to_string()),
+            Err(GitFileOpsError::LocalBranchNotFound(branch.to_string())),
+        )
+    }
+
+    #[test]
+    fn test_get_commits_robust_falls_back_to_merge_target() {
+        let test_commits = create_test_commits();
+        let initial_commit = test_commits[2].0;
+        let branch = Some("feature/x".to_string());
+
+        let git_info = missing_branch_git_info("feature/x")
+            .with_merged_into("main")
+            .with_file_commits_result(Some("main".to_string()), Ok(test_commits.clone()));
+
+        let (result, info) =
+            get_commits_robust(&git_info, &branch, Some(&initial_commit), None, None).unwrap();
+        assert_eq!(result.len(), test_commits.len());
+        assert_eq!(info.source, CommitSource::MergedInto("main".to_string()));
+        assert!(info.is_fallback());
+        assert_eq!(
+            info.caveat().unwrap(),
+            "⚠ commit history resolved via fallback: branch 'feature/x' not found locally — run git fetch"
+        );
+    }
+
+    #[test]
+    fn test_get_commits_robust_falls_back_to_containing_branch() {
+        let test_commits = create_test_commits();
+        let initial_commit = test_commits[2].0;
+        let branch = Some("feature/x".to_string());
+
+        let git_info = missing_branch_git_info("feature/x")
+            .with_containing_branches(&["empty", "release"])
+            .with_file_commits_result(Some("release".to_string()), Ok(test_commits.clone()));
+
+        let (_, info) =
+            get_commits_robust(&git_info, &branch, Some(&initial_commit), None, None).unwrap();
+        assert_eq!(
+            info.source,
+            CommitSource::ContainingBranch("release".to_string())
+        );
+        assert_eq!(
+            info.attempts[1],
+            RetrievalAttempt {
+                source: CommitSource::ContainingBranch("empty".to_string()),
+                failure: RetrievalFailure::Failed("branch 'empty' has no commits".to_string()),
+            }
+        );
+        assert_eq!(
+            info.caveat().unwrap(),
+            "⚠ commit history resolved via fallback: branch 'feature/x' not found locally; branch 'empty' has no commits — run git fetch"
+        );
+    }
+
+    #[test]
+    fn test_get_commits_robust_fallback_in_shallow_clone_may_be_incomplete() {
+        let test_commits = create_test_commits();
+        let initial_commit = test_commits[2].0;
+        let branch = Some("feature/x".to_string());
+
+        let git_info = missing_branch_git_info("feature/x")
+            .with_file_commits_result(None, Ok(test_commits.clone()))
+            .with_shallow();
+
+        let (_, info) =
+            get_commits_robust(&git_info, &branch, Some(&initial_commit), None, None).unwrap();
+        assert_eq!(info.source, CommitSource::DefaultBranch);
+        assert!(info.may_be_incomplete);
+        assert_eq!(
+            info.caveat().unwrap(),
+            "⚠ commit history resolved via fallback: branch 'feature/x' not found locally — run git fetch --unshallow"
+        );
+    }
+
+    #[test]
+    fn test_get_commits_robust_shallow_issue_branch_may_be_incomplete() {
+        let test_commits = create_test_commits();
+        let branch = Some("feature/x".to_string());
+        // The walk never reaches the initial commit
+        let stop_at = ObjectId::from_str("0000000000000000000000000000000000000001").unwrap();
+
+        let git_info = RobustMockGitInfo::new()
+            .with_file_commits_result(branch.clone(), Ok(test_commits.clone()))
+            .with_shallow();
+
+        let (_, info) =
+            get_commits_robust(&git_info, &branch, Some(&stop_at), Some(stop_at), None).unwrap();
+        assert_eq!(info.source, CommitSource::IssueBranch);
+        assert!(info.is_degraded());
+        assert_eq!(
+            info.caveat().unwrap(),
+            "⚠ commit history may be incomplete: history is shallow — run git fetch --unshallow"
+        );
+
+        // The initial commit is within the shallow history, so nothing is missing
+        let (_, info) = get_commits_robust(
+            &git_info,
+            &branch,
+            Some(&test_commits[2].0),
+            Some(test_commits[6].0),
+            None,
+        )
+        .unwrap();
+        assert!(!info.is_degraded());
+        assert_eq!(info.caveat(), None);
+    }
+
+    #[test]
+    fn test_retrieval_failure_classification() {
+        let commit = ObjectId::from_str("0000000000000000000000000000000000000001").unwrap();
+        let branch = Some("feature/x".to_string());
+        let git_info = missing_branch_git_info("feature/x")
+            .with_file_commits_result(None, Ok(create_test_commits()));
+
+        // HEAD does not contain the reference commit: every fallback is exhausted
+        let result = get_commits_robust(&git_info, &branch, Some(&commit), None, None);
+        assert!(matches!(
+            result,
+            Err(GitFileOpsError::LocalBranchNotFound(name)) if name == "feature/x"
+        ));
+
+        let info = CommitRetrievalInfo {
+            source: CommitSource::ContainingBranch("release".to_string()),
+            attempts: vec![
+                RetrievalAttempt {
+                    source: CommitSource::IssueBranch,
+                    failure: RetrievalFailure::BranchNotFound("feature/x".to_string()),
+                },
+                RetrievalAttempt {
+                    source: CommitSource::MergedInto("main".to_string()),
+                    failure: RetrievalFailure::ReferenceCommitMissing("0000000".to_string()),
+                },
+                RetrievalAttempt {
+                    source: CommitSource::DefaultBranch,
+                    failure: RetrievalFailure::ShallowHistory,
+                },
+            ],
+            may_be_incomplete: true,
+        };
+        assert_eq!(
+            info.caveat().unwrap(),
+            "⚠ commit history resolved via fallback: branch 'feature/x' not found locally; commit 0000000 not found in local history; history is shallow — run git fetch --unshallow"
+        );
+        assert_eq!(
+            serde_json::to_value(&info.attempts[0]).unwrap(),
+            serde_json::json!({
+                "source": { "kind": "issue_branch" },
+                "failure": { "kind": "branch_not_found", "detail": "feature/x" }
+            })
+        );
+    }
+
+    fn merge_commit() -> ObjectId {
+        ObjectId::from_str("fedcba9876543210fedcba9876543210fedcba98").unwrap()
+    }
+
+    #[test]
+    fn test_infer_branch_state_present() {
+        let commit = create_test_commits()[0].0;
+        let mut git_info = MockGitCommitOps::new();
+        git_info
+            .expect_branch_tip()
+            .returning(|_| Err(GitFileOpsError::LocalBranchNotFound("feature".to_string())));
+        git_info
+            .expect_get_branches_containing_commit()
+            .returning(|_| Ok(vec!["main".to_string(), "origin/feature".to_string()]));
+        git_info.expect_find_merge_commit().never();
+
+        let state = infer_branch_state(&git_info, "feature", &commit).unwrap();
+        assert_eq!(state, BranchState::Present);
+    }
+
+    #[test]
+    fn test_infer_branch_state_merged_and_deleted() {
+        let commit = create_test_commits()[0].0;
+        let mut git_info = MockGitCommitOps::new();
+        git_info
+            .expect_branch_tip()
+            .returning(|_| Err(GitFileOpsError::LocalBranchNotFound("feature".to_string())));
+        git_info
+            .expect_get_branches_containing_commit()
+            .returning(|_| Ok(vec!["main".to_string(), "origin/main".to_string()]));
+        git_info
+            .expect_find_merge_commit()
+            .returning(|_| Ok(Some(merge_commit())));
+
+        let state = infer_branch_state(&git_info, "feature", &commit).unwrap();
+        assert_eq!(
+            state,
+            BranchState::Merged {
+                target: "main".to_string(),
+                merge_commit: merge_commit(),
+            }
+        );
+        assert_eq!(state.to_string(), "deleted — merged into main at fedcba9");
+    }
+
+    #[test]
+    fn test_infer_branch_state_deleted_without_merge() {
+        let commit = create_test_commits()[0].0;
+        let mut git_info = MockGitCommitOps::new();
+        git_info
+            .expect_branch_tip()
+            .returning(|_| Err(GitFileOpsError::LocalBranchNotFound("feature".to_string())));
+        git_info
+            .expect_get_branches_containing_commit()
+            .returning(|_| Ok(Vec::new()));
+        git_info.expect_find_merge_commit().returning(|_| Ok(None));
+
+        let state = infer_branch_state(&git_info, "feature", &commit).unwrap();
+        assert_eq!(state, BranchState::Deleted);
+        assert_eq!(state.to_string(), "deleted");
+    }
+
     #[tokio::test]
     async fn test_get_commits_robust_git_error_propagated() {
         let branch = "test-branch";
@@ -926,14 +1808,41 @@ mod tests {
 
         assert!(matches!(result, Err(GitFileOpsError::AuthorNotFound(_))));
     }
+
+    #[test]
+    fn test_rename_chain_follows_both_directions() {
+        let rename = |old: &str, new: &str, commit: &str| FileRenameEvent {
+            old_path: old.to_string(),
+            new_path: new.to_string(),
+            commit: commit.to_string(),
+        };
+        let renames = vec![
+            rename("a.R", "b.R", "1"),
+            rename("other.R", "elsewhere.R", "2"),
+            rename("b.R", "c.R", "3"),
+            rename("c.R", "d.R", "4"),
+        ];
+
+        assert_eq!(
+            rename_chain(Path::new("c.R"), &renames),
+            vec![
+                rename("a.R", "b.R", "1"),
+                rename("b.R", "c.R", "3"),
+                rename("c.R", "d.R", "4"),
+            ]
+        );
+        assert!(rename_chain(Path::new("untouched.R"), &renames).is_empty());
+    }
 }
 
 #[cfg(test)]
 mod integration_tests {
+    use std::path::Path;
     use std::process::Command;
     use tempfile::TempDir;
 
-    use crate::GitCli;
+    use super::rename_chain;
+    use crate::{FileRenameEvent, GitCli};
 
     fn setup_repo() -> TempDir {
         let dir = tempfile::tempdir().unwrap();
@@ -1072,4 +1981,128 @@ mod integration_tests {
         assert_eq!(hashes[1], sha2.as_str());
         assert_eq!(hashes[2], sha1.as_str());
     }
+
+    #[test]
+    fn test_blob_reader_streams_file_content() {
+        use std::io::Read;
+        use std::str::FromStr;
+
+        use super::BlobReader;
+
+        let dir = setup_repo();
+        let p = dir.path();
+        let content = "id,value\n".to_string() + &"1,2.5\n".repeat(50_000);
+        commit_file(p, "data.csv", &content, "Add data");
+        let blob = Command::new("git")
+            .args(["rev-parse", "HEAD:data.csv"])
+            .current_dir(p)
+            .output()
+            .unwrap();
+        let blob = gix::ObjectId::from_str(String::from_utf8(blob.stdout).unwrap().trim()).unwrap();
+
+        let mut streamed = String::new();
+        BlobReader::new(p, blob)
+            .read_to_string(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, content);
+
+        // Unknown blobs fail instead of reading as empty
+        let missing = gix::ObjectId::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        let err = BlobReader::new(p, missing)
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("git cat-file failed"));
+
+        // Dropping a reader part way through stops git
+        let mut partial = BlobReader::new(p, blob);
+        let mut head = [0; 8];
+        partial.read_exact(&mut head).unwrap();
+        assert_eq!(&head, b"id,value");
+        drop(partial);
+    }
+
+    #[test]
+    fn test_check_attr_reads_gitattributes() {
+        use crate::git::action::GitCommand;
+
+        let dir = setup_repo();
+        let p = dir.path();
+        std::fs::create_dir(p.join("output")).unwrap();
+        commit_file(
+            p,
+            ".gitattributes",
+            "output/*.csv -diff\nrenv.lock linguist-generated\n",
+            "Add gitattributes",
+        );
+
+        let git = GitCommand {
+            path: p.to_path_buf(),
+        };
+        let attributes = ["diff", "linguist-generated"];
+
+        let csv = git
+            .check_attr(std::path::Path::new("output/model fit.csv"), &attributes)
+            .unwrap();
+        assert_eq!(csv["diff"], "unset");
+        assert_eq!(csv["linguist-generated"], "unspecified");
+
+        let lock = git
+            .check_attr(std::path::Path::new("renv.lock"), &attributes)
+            .unwrap();
+        assert_eq!(lock["diff"], "unspecified");
+        assert_eq!(lock["linguist-generated"], "set");
+
+        let script = git
+            .check_attr(std::path::Path::new("scripts/analysis.R"), &attributes)
+            .unwrap();
+        assert_eq!(script["diff"], "unspecified");
+        assert_eq!(script["linguist-generated"], "unspecified");
+    }
+
+    #[test]
+    fn test_renames_follow_file_through_git_mv() {
+        use crate::git::action::GitCommand;
+
+        let dir = setup_repo();
+        let p = dir.path();
+        std::fs::create_dir(p.join("scripts")).unwrap();
+        let content = "library(dplyr)\nfit <- lm(y ~ x, data = df)\nsummary(fit)\n";
+        let initial = commit_file(p, "scripts/run.R", content, "Add run script");
+        commit_file(p, "scripts/plot.R", content, "Add plot script");
+        for (from, to) in [
+            ("scripts/run.R", "scripts/run_final.R"),
+            ("scripts/plot.R", "scripts/figures.R"),
+        ] {
+            Command::new("git")
+                .args(["mv", from, to])
+                .current_dir(p)
+                .output()
+                .unwrap();
+        }
+        let rename = commit_file(p, "README.md", "readme", "Rename scripts");
+
+        let renames = GitCommand {
+            path: p.to_path_buf(),
+        }
+        .renames(None, Some(initial))
+        .unwrap()
+        .into_iter()
+        .map(|(commit, old_path, new_path)| FileRenameEvent {
+            old_path,
+            new_path,
+            commit,
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(renames.len(), 2);
+
+        let chain = rename_chain(Path::new("scripts/run.R"), &renames);
+        assert_eq!(
+            chain,
+            vec![FileRenameEvent {
+                old_path: "scripts/run.R".to_string(),
+                new_path: "scripts/run_final.R".to_string(),
+                commit: rename,
+            }]
+        );
+    }
 }
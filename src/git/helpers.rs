@@ -4,52 +4,197 @@ use gix::ObjectId;
 #[cfg(test)]
 use mockall::automock;
 
+/// The kind of code host a remote points at, which decides the API the issues are managed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Forge {
+    #[default]
+    GitHub,
+    GitLab,
+}
+
+impl Forge {
+    /// Guess the forge from a remote's host; self-hosted GitLab instances are recognized by
+    /// `gitlab` appearing in their host name
+    pub fn detect(host: &str) -> Self {
+        if host.to_lowercase().contains("gitlab") {
+            Self::GitLab
+        } else {
+            Self::GitHub
+        }
+    }
+
+    /// Parse a `GHQC_PROVIDER` value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Forge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GitHub => write!(f, "GitHub"),
+            Self::GitLab => write!(f, "GitLab"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GitRemote {
+    /// The owner of the repository; for GitLab, the full (possibly nested) group path
     pub owner: String,
     pub repo: String,
     pub url: String,
+    pub forge: Forge,
 }
 
 impl GitRemote {
+    /// Parse a remote URL, detecting the forge from its host
     pub fn from_url(url: &str) -> Option<Self> {
+        Self::parse(url, None)
+    }
+
+    /// Parse a remote URL as a repository of `forge`, or of the forge detected from its host when
+    /// `None`
+    pub fn parse(url: &str, forge: Option<Forge>) -> Option<Self> {
         let url = url.strip_suffix(".git").unwrap_or(url);
 
-        if let Some(captures) = url.strip_prefix("https://") {
-            let parts: Vec<&str> = captures.split('/').collect();
-            if parts.len() >= 3 {
-                let host = parts[0];
-                let owner = parts[1];
-                let repo = parts[2];
-                return Some(GitRemote {
-                    owner: owner.to_string(),
-                    repo: repo.to_string(),
-                    url: format!("https://{}", host),
-                });
+        let (host, path) = if let Some(rest) = url.strip_prefix("https://") {
+            rest.split_once('/')?
+        } else if let Some(rest) = url.strip_prefix("git@") {
+            let (host, path) = rest.split_once(':')?;
+            if path.contains(':') {
+                return None;
             }
-        }
+            (host, path)
+        } else {
+            return None;
+        };
 
-        if let Some(captures) = url.strip_prefix("git@") {
-            let host_and_path: Vec<&str> = captures.split(':').collect();
-            if host_and_path.len() == 2 {
-                let host = host_and_path[0];
-                let path_parts: Vec<&str> = host_and_path[1].split('/').collect();
-                if path_parts.len() >= 2 {
-                    let owner = path_parts[0];
-                    let repo = path_parts[1];
-                    return Some(GitRemote {
-                        owner: owner.to_string(),
-                        repo: repo.to_string(),
-                        url: format!("https://{}", host),
-                    });
+        let forge = forge.unwrap_or_else(|| Forge::detect(host));
+        let parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+        let (owner, repo) = match forge {
+            Forge::GitHub => match parts.as_slice() {
+                [owner, repo, ..] => (owner.to_string(), repo.to_string()),
+                _ => return None,
+            },
+            // GitLab projects may live in nested groups; web URLs continue after a `-` segment
+            Forge::GitLab => {
+                let end = parts.iter().position(|part| *part == "-");
+                match &parts[..end.unwrap_or(parts.len())] {
+                    [groups @ .., repo] if !groups.is_empty() => {
+                        (groups.join("/"), repo.to_string())
+                    }
+                    _ => return None,
                 }
             }
+        };
+
+        Some(GitRemote {
+            owner,
+            repo,
+            url: format!("https://{}", host),
+            forge,
+        })
+    }
+}
+
+/// An issue that belongs to a different repository than the one the local remote points at,
+/// e.g. after a project directory was copied and its remote changed
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error(
+    "Issue #{issue_number} belongs to {issue_repository}, but the local repository's remote is {remote_repository}. Refusing to change an issue of another repository; pass --trust-remote-mismatch if the repository was transferred or renamed"
+)]
+pub struct RemoteMismatch {
+    pub issue_number: u64,
+    pub issue_repository: String,
+    pub remote_repository: String,
+}
+
+impl RemoteMismatch {
+    /// Note recording that a comment was posted despite the mismatch
+    pub fn override_note(&self) -> String {
+        format!(
+            "> [!WARNING]\n> Posted with `--trust-remote-mismatch`: this issue belongs to {}, while the local repository's remote is {}.",
+            self.issue_repository, self.remote_repository
+        )
+    }
+}
+
+/// Check that `issue` belongs to the repository of the local remote, comparing the host, owner
+/// and repository of its URL
+pub fn check_issue_remote(
+    issue: &octocrab::models::issues::Issue,
+    git_info: &impl GitHelpers,
+) -> Result<(), RemoteMismatch> {
+    let issue_repository = issue_url_repository(issue.html_url.as_str());
+    let remote_repository = issue_url_repository(&git_info.issue_url(issue.number));
+
+    if issue_repository.eq_ignore_ascii_case(&remote_repository) {
+        Ok(())
+    } else {
+        Err(RemoteMismatch {
+            issue_number: issue.number,
+            issue_repository,
+            remote_repository,
+        })
+    }
+}
+
+/// Whether an issue `url` belongs to the repository of the local remote
+pub fn issue_url_matches_remote(url: &str, issue_number: u64, git_info: &impl GitHelpers) -> bool {
+    issue_url_repository(url)
+        .eq_ignore_ascii_case(&issue_url_repository(&git_info.issue_url(issue_number)))
+}
+
+/// Guard the body of a comment about to be posted on `issue`
+///
+/// Comments on an issue of another repository are refused, unless `trust_mismatch` is set, in
+/// which case the override is recorded at the end of the comment.
+pub fn guard_comment_body(
+    issue: &octocrab::models::issues::Issue,
+    git_info: &impl GitHelpers,
+    trust_mismatch: bool,
+    body: String,
+) -> Result<String, RemoteMismatch> {
+    match check_issue_remote(issue, git_info) {
+        Ok(()) => Ok(body),
+        Err(mismatch) if trust_mismatch => {
+            log::warn!("Posting despite remote mismatch: {mismatch}");
+            Ok(format!("{body}\n\n{}", mismatch.override_note()))
         }
+        Err(mismatch) => Err(mismatch),
+    }
+}
 
-        None
+/// Guard a change other than a comment, e.g. to the assignees or body, about to be made to `issue`
+///
+/// Changes to an issue of another repository are refused, unless `trust_mismatch` is set.
+pub fn guard_issue_change(
+    issue: &octocrab::models::issues::Issue,
+    git_info: &impl GitHelpers,
+    trust_mismatch: bool,
+) -> Result<(), RemoteMismatch> {
+    match check_issue_remote(issue, git_info) {
+        Err(mismatch) if trust_mismatch => {
+            log::warn!("Changing issue despite remote mismatch: {mismatch}");
+            Ok(())
+        }
+        result => result,
     }
 }
 
+/// `host/owner/repo` of an issue URL, e.g. `github.com/owner/repo` for
+/// `https://github.com/owner/repo/issues/1`
+fn issue_url_repository(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let repository = url.rsplit_once("/issues/").map_or(url, |(repo, _)| repo);
+    repository.trim_end_matches('/').to_string()
+}
+
 #[cfg_attr(test, automock)]
 pub trait GitHelpers {
     fn file_content_url(&self, git_ref: &str, file: &Path) -> String;
@@ -63,13 +208,20 @@ pub trait GitHelpers {
 
 use crate::git::GitInfo;
 
+impl GitInfo {
+    /// Prefix of the repository's web pages, e.g. `https://gitlab.com/group/repo/-` on GitLab
+    fn web_prefix(&self) -> String {
+        match self.forge() {
+            Forge::GitHub => format!("{}/{}/{}", self.base_url, self.owner, self.repo),
+            Forge::GitLab => format!("{}/{}/{}/-", self.base_url, self.owner, self.repo),
+        }
+    }
+}
+
 impl GitHelpers for GitInfo {
     fn file_content_url(&self, git_ref: &str, file: &Path) -> String {
         let file = file.to_string_lossy().replace(" ", "%20");
-        format!(
-            "{}/{}/{}/blob/{}/{file}",
-            self.base_url, self.owner, self.repo, &git_ref
-        )
+        format!("{}/blob/{}/{file}", self.web_prefix(), &git_ref)
     }
 
     fn commit_comparison_url(
@@ -77,23 +229,112 @@ impl GitHelpers for GitInfo {
         current_commit: &ObjectId,
         previous_commit: &ObjectId,
     ) -> String {
+        // GitLab only accepts the three-dot form
+        let separator = match self.forge() {
+            Forge::GitHub => "..",
+            Forge::GitLab => "...",
+        };
         format!(
-            "{}/{}/{}/compare/{}..{}",
-            self.base_url, self.owner, self.repo, previous_commit, current_commit,
+            "{}/compare/{}{separator}{}",
+            self.web_prefix(),
+            previous_commit,
+            current_commit,
         )
     }
 
     fn issue_url(&self, issue_number: u64) -> String {
-        format!(
-            "{}/{}/{}/issues/{issue_number}",
-            self.base_url, self.owner, self.repo
-        )
+        format!("{}/issues/{issue_number}", self.web_prefix())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::create_test_issue;
+
+    fn remote(owner: &'static str, repo: &'static str) -> MockGitHelpers {
+        let mut git_info = MockGitHelpers::new();
+        git_info
+            .expect_issue_url()
+            .returning(move |number| format!("https://github.com/{owner}/{repo}/issues/{number}"));
+        git_info
+    }
+
+    #[test]
+    fn test_check_issue_remote() {
+        let issue = create_test_issue("a2-ai", "pk-models", 7, "scripts/model.R", "", None, "open");
+
+        assert_eq!(
+            check_issue_remote(&issue, &remote("a2-ai", "pk-models")),
+            Ok(())
+        );
+        // GitHub owners and repository names are case-insensitive
+        assert_eq!(
+            check_issue_remote(&issue, &remote("A2-AI", "PK-Models")),
+            Ok(())
+        );
+
+        let mismatch = check_issue_remote(&issue, &remote("a2-ai", "pk-models-2")).unwrap_err();
+        assert_eq!(
+            mismatch,
+            RemoteMismatch {
+                issue_number: 7,
+                issue_repository: "github.com/a2-ai/pk-models".to_string(),
+                remote_repository: "github.com/a2-ai/pk-models-2".to_string(),
+            }
+        );
+        assert!(mismatch.to_string().starts_with(
+            "Issue #7 belongs to github.com/a2-ai/pk-models, but the local repository's remote is github.com/a2-ai/pk-models-2."
+        ));
+    }
+
+    #[test]
+    fn test_guard_issue_change() {
+        let issue = create_test_issue("a2-ai", "pk-models", 7, "scripts/model.R", "", None, "open");
+
+        assert_eq!(
+            guard_issue_change(&issue, &remote("a2-ai", "pk-models"), false),
+            Ok(())
+        );
+        let mismatch =
+            guard_issue_change(&issue, &remote("other-org", "pk-models"), false).unwrap_err();
+        assert_eq!(mismatch.issue_repository, "github.com/a2-ai/pk-models");
+        assert_eq!(
+            guard_issue_change(&issue, &remote("other-org", "pk-models"), true),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_guard_comment_body() {
+        let issue = create_test_issue("a2-ai", "pk-models", 7, "scripts/model.R", "", None, "open");
+        let body = "# QC Approval\n\napproved".to_string();
+
+        assert_eq!(
+            guard_comment_body(&issue, &remote("a2-ai", "pk-models"), false, body.clone()),
+            Ok(body.clone())
+        );
+        assert!(
+            guard_comment_body(
+                &issue,
+                &remote("other-org", "pk-models"),
+                false,
+                body.clone()
+            )
+            .is_err()
+        );
+
+        let trusted =
+            guard_comment_body(&issue, &remote("other-org", "pk-models"), true, body).unwrap();
+        insta::assert_snapshot!(trusted, @r"
+        # QC Approval
+
+        approved
+
+        > [!WARNING]
+        > Posted with `--trust-remote-mismatch`: this issue belongs to github.com/a2-ai/pk-models, while the local repository's remote is github.com/other-org/pk-models.
+        ");
+    }
 
     #[test]
     fn test_parse_github_url_matrix() {
@@ -168,6 +409,7 @@ mod tests {
                             owner: exp_owner.to_string(),
                             repo: exp_repo.to_string(),
                             url: exp_base_url.to_string(),
+                            forge: Forge::GitHub,
                         },
                         "Failed for input: {}",
                         input
@@ -183,4 +425,59 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_parse_gitlab_url_matrix() {
+        let test_cases = [
+            (
+                "https://gitlab.com/group/repo.git",
+                None,
+                Some(("group", "repo", "https://gitlab.com")),
+            ),
+            (
+                "git@gitlab.com:group/subgroup/repo.git",
+                None,
+                Some(("group/subgroup", "repo", "https://gitlab.com")),
+            ),
+            (
+                "https://gitlab.company.internal/group/subgroup/repo/-/tree/main",
+                None,
+                Some(("group/subgroup", "repo", "https://gitlab.company.internal")),
+            ),
+            // Self-hosted instances without `gitlab` in the host need GHQC_PROVIDER
+            (
+                "https://code.company.internal/group/subgroup/repo",
+                Some(Forge::GitLab),
+                Some(("group/subgroup", "repo", "https://code.company.internal")),
+            ),
+            ("https://gitlab.com/repo", None, None),
+        ];
+
+        for (input, forge, expected) in test_cases {
+            let result = GitRemote::parse(input, forge);
+            match expected {
+                Some((owner, repo, url)) => assert_eq!(
+                    result,
+                    Some(GitRemote {
+                        owner: owner.to_string(),
+                        repo: repo.to_string(),
+                        url: url.to_string(),
+                        forge: Forge::GitLab,
+                    }),
+                    "Failed for input: {input}"
+                ),
+                None => assert_eq!(result, None, "Expected None for input: {input}"),
+            }
+        }
+
+        // An explicit provider overrides detection
+        assert_eq!(
+            GitRemote::parse("https://gitlab.com/group/repo", Some(Forge::GitHub))
+                .unwrap()
+                .forge,
+            Forge::GitHub
+        );
+        assert_eq!(Forge::from_name("GitLab"), Some(Forge::GitLab));
+        assert_eq!(Forge::from_name("bitbucket"), None);
+    }
 }
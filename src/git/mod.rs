@@ -4,31 +4,46 @@ use std::path::{Path, PathBuf};
 mod action;
 mod api;
 mod auth;
+mod commit_index;
 mod file_ops;
 mod helpers;
 mod provider;
 mod repository;
+mod signature;
 mod status;
+mod tls;
 
-pub use action::{GitCli, GitCliError, GitCommand};
-pub use api::{GitComment, GitHubApiError, GitHubReader, GitHubWriter, RepoUser};
+pub use action::{FileLastCommit, GitCli, GitCliError, GitCommand};
+pub use api::{
+    GitComment, GitHubApiError, GitHubReader, GitHubWriter, GitLabError, MilestoneState,
+    MilestoneUpdate, Permission, RepoUser, RequestError, RequestTarget,
+};
 pub use auth::{AuthError, AuthSourceKind, AuthSources};
+pub use commit_index::{BranchCommits, CommitIndex};
 pub use file_ops::{
-    GitAuthor, GitCommit, GitCommitOps, GitFileOps, GitFileOpsError, find_commits,
-    find_or_cache_file_changes, get_commits_robust,
+    BranchState, CommitRetrievalInfo, CommitSource, FileReader, GitAuthor, GitCommit, GitCommitOps,
+    GitFileOps, GitFileOpsError, PathAttributes, RetrievalAttempt, RetrievalFailure, branch_exists,
+    find_commits, find_or_cache_file_changes, get_commits_robust, infer_branch_state,
 };
 
 #[cfg(test)]
 pub use action::MockGitCli;
 #[cfg(test)]
-pub use file_ops::MockGitFileOps;
-pub use helpers::GitHelpers;
+pub use api::{MockGitHubReader, MockGitHubWriter};
+#[cfg(test)]
+pub use file_ops::{MockGitCommitOps, MockGitFileOps};
+pub use helpers::{
+    Forge, GitHelpers, RemoteMismatch, check_issue_remote, guard_comment_body, guard_issue_change,
+    issue_url_matches_remote,
+};
 pub use provider::GitProvider;
 pub use repository::{FileStashOutcome, GitRepository, GitRepositoryError};
+pub use signature::{SignatureStatus, SigningKeys, commit_signature_status};
 pub use status::{
-    GitState, GitStatus, GitStatusError, GitStatusOps, detect_renames, get_git_status,
-    head_commit_hash,
+    BehindFileReport, FileGitState, GitState, GitStatus, GitStatusError, GitStatusOps,
+    behind_file_report, detect_renames, get_git_status, head_commit_hash,
 };
+pub use tls::TlsSettings;
 
 use crate::auth::AuthStore;
 use crate::utils::EnvProvider;
@@ -45,6 +60,8 @@ pub enum GitInfoError {
     NoRemoteUrl,
     #[error("Invalid GitHub URL")]
     InvalidGitHubUrl,
+    #[error("Unknown GHQC_PROVIDER '{0}': expected 'github' or 'gitlab'")]
+    UnknownProvider(String),
     #[error("Failed to build API: {0}")]
     ApiBuildError(#[from] octocrab::Error),
     #[error("Authentication error: {0}")]
@@ -63,7 +80,13 @@ pub struct GitInfo {
     /// and forks-of-forks workflows. Captured once at GitInfo construction so
     /// callers don't have to reach for the gix Remote each time.
     pub(crate) remote_name: String,
+    /// Post comments on issues of another repository than the remote's, noting the override
+    pub(crate) trust_remote_mismatch: bool,
+    /// Follow renames of QCed files through the git history
+    pub(crate) follow_renames: bool,
     command: GitCommand,
+    /// Client for the GitLab project, when the remote is hosted on GitLab
+    pub(crate) gitlab: Option<api::GitLabApi>,
 }
 
 impl GitInfo {
@@ -94,17 +117,31 @@ impl GitInfo {
             .to_string();
         log::debug!("Found remote URL: {}", remote_url);
 
-        let remote_info =
-            helpers::GitRemote::from_url(&remote_url).ok_or(GitInfoError::InvalidGitHubUrl)?;
+        // GHQC_PROVIDER overrides the forge detected from the remote's host
+        let forge = match env.var("GHQC_PROVIDER") {
+            Ok(name) if !name.trim().is_empty() => {
+                Some(Forge::from_name(&name).ok_or(GitInfoError::UnknownProvider(name))?)
+            }
+            _ => None,
+        };
+        let remote_info = match forge {
+            Some(forge) => helpers::GitRemote::parse(&remote_url, Some(forge)),
+            None => helpers::GitRemote::from_url(&remote_url),
+        }
+        .ok_or(GitInfoError::InvalidGitHubUrl)?;
         log::debug!(
-            "Parsed GitHub info - Owner: {}, Repo: {}, Base URL: {}",
+            "Parsed {} info - Owner: {}, Repo: {}, Base URL: {}",
+            remote_info.forge,
             remote_info.owner,
             remote_info.repo,
             remote_info.url
         );
+        let gitlab = (remote_info.forge == Forge::GitLab)
+            .then(|| api::GitLabApi::new(&remote_info, env, auth_store));
 
         // Get auth token but don't create Octocrab client yet
-        let auth_sources = AuthSources::new(&remote_info.url, env, auth_store);
+        let auth_sources = AuthSources::new(&remote_info.url, env, auth_store)
+            .with_tls(TlsSettings::from_env(env)?);
         if auth_sources.is_empty() {
             log::debug!("Found authentication token");
         } else {
@@ -124,16 +161,48 @@ impl GitInfo {
             repository_path: path.to_path_buf(),
             auth_sources,
             remote_name,
+            trust_remote_mismatch: false,
+            follow_renames: true,
             command: GitCommand {
                 path: path.to_path_buf(),
             },
+            gitlab,
         })
     }
 
+    /// The forge hosting the remote, whose API issues are managed with
+    pub fn forge(&self) -> Forge {
+        if self.gitlab.is_some() {
+            Forge::GitLab
+        } else {
+            Forge::GitHub
+        }
+    }
+
     pub fn remote_name(&self) -> &str {
         &self.remote_name
     }
 
+    /// How the certificates of the API and of record images are verified, from
+    /// `GHQC_CA_BUNDLE` and `GHQC_INSECURE_SKIP_VERIFY`
+    pub fn tls(&self) -> &TlsSettings {
+        self.auth_sources.tls()
+    }
+
+    /// Allow commenting on issues whose URL points at another repository than the remote, e.g.
+    /// after the repository was transferred. Each such comment records the override.
+    pub fn with_trust_remote_mismatch(mut self, trust: bool) -> Self {
+        self.trust_remote_mismatch = trust;
+        self
+    }
+
+    /// Follow renames of QCed files through the git history. Turning it off saves a history
+    /// walk with rename detection per issue in large repositories.
+    pub fn with_follow_renames(mut self, follow: bool) -> Self {
+        self.follow_renames = follow;
+        self
+    }
+
     /// Get a repository instance (recreated for thread safety)
     pub fn repository(&self) -> Result<Repository, GitInfoError> {
         gix::open(&self.repository_path).map_err(GitInfoError::RepoOpen)
@@ -0,0 +1,149 @@
+use std::{fmt, path::PathBuf};
+
+use gix::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::git::action::{GitCli, GitCliError};
+
+/// Signature state of a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureStatus {
+    /// Signed and verified against the configured trusted keys
+    Signed,
+    /// No GPG or SSH signature present
+    Unsigned,
+    /// Signed, but no trusted keys are configured or the signature does not verify against them
+    Unverified,
+}
+
+impl SignatureStatus {
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Self::Signed => "✔",
+            Self::Unsigned => "✘",
+            Self::Unverified => "?",
+        }
+    }
+}
+
+impl fmt::Display for SignatureStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::Signed => "signed",
+            Self::Unsigned => "unsigned",
+            Self::Unverified => "unverified",
+        };
+        write!(f, "{status} {}", self.marker())
+    }
+}
+
+/// Trusted keys used to verify commit signatures
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SigningKeys {
+    /// SSH allowed signers file, passed as `gpg.ssh.allowedSignersFile`
+    pub allowed_signers: Option<PathBuf>,
+    /// GnuPG home directory holding the trusted keyring, passed as `GNUPGHOME`
+    pub gpg_home: Option<PathBuf>,
+}
+
+impl SigningKeys {
+    pub fn is_configured(&self) -> bool {
+        self.allowed_signers.is_some() || self.gpg_home.is_some()
+    }
+}
+
+/// Determine whether `commit` is signed and, if trusted keys are configured, whether it verifies
+pub fn commit_signature_status(
+    git_cli: &impl GitCli,
+    commit: &ObjectId,
+    keys: &SigningKeys,
+) -> Result<SignatureStatus, GitCliError> {
+    let commit = commit.to_string();
+
+    if !git_cli.commit_is_signed(&commit)? {
+        return Ok(SignatureStatus::Unsigned);
+    }
+
+    if !keys.is_configured() {
+        log::debug!("No signing keys configured. Cannot verify signature of {commit}");
+        return Ok(SignatureStatus::Unverified);
+    }
+
+    if git_cli.verify_commit(&commit, keys)? {
+        Ok(SignatureStatus::Signed)
+    } else {
+        Ok(SignatureStatus::Unverified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::MockGitCli;
+    use std::str::FromStr;
+
+    fn commit() -> ObjectId {
+        ObjectId::from_str("abc123def456789012345678901234567890abcd").unwrap()
+    }
+
+    fn keys() -> SigningKeys {
+        SigningKeys {
+            allowed_signers: Some(PathBuf::from("/config/allowed_signers")),
+            gpg_home: None,
+        }
+    }
+
+    #[test]
+    fn test_unsigned_commit_skips_verification() {
+        let mut git_cli = MockGitCli::default();
+        git_cli.expect_commit_is_signed().returning(|_| Ok(false));
+        git_cli.expect_verify_commit().never();
+
+        let status = commit_signature_status(&git_cli, &commit(), &keys()).unwrap();
+        assert_eq!(status, SignatureStatus::Unsigned);
+    }
+
+    #[test]
+    fn test_signed_commit_without_keys_is_unverified() {
+        let mut git_cli = MockGitCli::default();
+        git_cli.expect_commit_is_signed().returning(|_| Ok(true));
+        git_cli.expect_verify_commit().never();
+
+        let status = commit_signature_status(&git_cli, &commit(), &SigningKeys::default()).unwrap();
+        assert_eq!(status, SignatureStatus::Unverified);
+    }
+
+    #[test]
+    fn test_signed_commit_verifies() {
+        let mut git_cli = MockGitCli::default();
+        git_cli
+            .expect_commit_is_signed()
+            .withf(|c| c == "abc123def456789012345678901234567890abcd")
+            .returning(|_| Ok(true));
+        git_cli
+            .expect_verify_commit()
+            .withf(|_, keys| keys.allowed_signers == Some(PathBuf::from("/config/allowed_signers")))
+            .returning(|_, _| Ok(true));
+
+        let status = commit_signature_status(&git_cli, &commit(), &keys()).unwrap();
+        assert_eq!(status, SignatureStatus::Signed);
+    }
+
+    #[test]
+    fn test_signed_commit_fails_verification() {
+        let mut git_cli = MockGitCli::default();
+        git_cli.expect_commit_is_signed().returning(|_| Ok(true));
+        git_cli.expect_verify_commit().returning(|_, _| Ok(false));
+
+        let status = commit_signature_status(&git_cli, &commit(), &keys()).unwrap();
+        assert_eq!(status, SignatureStatus::Unverified);
+    }
+
+    #[test]
+    fn test_signature_status_display() {
+        assert_eq!(SignatureStatus::Signed.to_string(), "signed ✔");
+        assert_eq!(SignatureStatus::Unsigned.to_string(), "unsigned ✘");
+        assert_eq!(SignatureStatus::Unverified.to_string(), "unverified ?");
+    }
+}
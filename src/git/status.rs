@@ -1,10 +1,17 @@
-use std::{fmt, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use crate::GitInfo;
+use crate::git::file_ops::{GitCommit, GitCommitOps, GitFileOpsError};
 use crate::git::repository::{GitRepository, GitRepositoryError};
+use crate::utils::contains_repo_path;
 use gix::ObjectId;
 #[cfg(test)]
 use mockall::automock;
+use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum GitState {
@@ -33,39 +40,226 @@ impl fmt::Display for GitState {
     }
 }
 
+/// Whether a file has commits that are not pushed or not pulled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileGitState {
+    UpToDate,
+    /// Local commits to the file are not pushed
+    LocalCommits,
+    /// Remote commits to the file are not pulled
+    RemoteChanges,
+    /// Both local and remote commits to the file are missing from the other side
+    Diverged,
+}
+
+impl fmt::Display for FileGitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UpToDate => write!(f, "Up to date"),
+            Self::LocalCommits => write!(f, "Local commits"),
+            Self::RemoteChanges => write!(f, "Remote changes"),
+            Self::Diverged => write!(f, "Diverged"),
+        }
+    }
+}
+
 impl GitState {
+    /// How the repository's unpushed and unpulled commits relate to the commits of a file
+    pub fn file_state(&self, file_commits: &[&ObjectId]) -> FileGitState {
+        let touches = |commits: &[ObjectId]| file_commits.iter().any(|c| commits.contains(c));
+        match self {
+            GitState::Clean => FileGitState::UpToDate,
+            GitState::Ahead(commits) if touches(commits) => FileGitState::LocalCommits,
+            GitState::Behind(commits) if touches(commits) => FileGitState::RemoteChanges,
+            GitState::Ahead(_) | GitState::Behind(_) => FileGitState::UpToDate,
+            GitState::Diverged { ahead, behind } => match (touches(ahead), touches(behind)) {
+                (true, true) => FileGitState::Diverged,
+                (true, false) => FileGitState::LocalCommits,
+                (false, true) => FileGitState::RemoteChanges,
+                (false, false) => FileGitState::UpToDate,
+            },
+        }
+    }
+
     /// Format git status for a specific file and issue thread
     pub fn format_for_file(&self, file_commits: &[&ObjectId]) -> String {
-        match self {
-            GitState::Clean => "Up to date".to_string(),
-            GitState::Ahead(commits) => {
-                if file_commits.iter().any(|c| commits.contains(c)) {
-                    "Local commits".to_string()
-                } else {
-                    "Up to date".to_string()
-                }
-            }
-            GitState::Behind(commits) => {
-                if file_commits.iter().any(|c| commits.contains(c)) {
-                    "Remote changes".to_string()
-                } else {
-                    "Up to date".to_string()
-                }
+        self.file_state(file_commits).to_string()
+    }
+
+    /// [`format_for_file`](Self::format_for_file), but counting the remote commits missing
+    /// locally which touch the file when `behind` is known, e.g. "Behind remote by 4 commits,
+    /// 2 touch this file"
+    pub fn format_for_file_behind(
+        &self,
+        file_commits: &[&ObjectId],
+        behind: Option<&BehindFileReport>,
+    ) -> String {
+        let Some(behind) = behind else {
+            return self.format_for_file(file_commits);
+        };
+        let local_commits = match self {
+            GitState::Ahead(ahead) | GitState::Diverged { ahead, .. } => {
+                file_commits.iter().any(|c| ahead.contains(c))
             }
-            GitState::Diverged { ahead, behind } => {
-                let is_ahead = file_commits.iter().any(|c| ahead.contains(c));
-                let is_behind = file_commits.iter().any(|c| behind.contains(c));
-                match (is_ahead, is_behind) {
-                    (true, true) => "Diverged".to_string(),
-                    (true, false) => "Local commits".to_string(),
-                    (false, true) => "Remote changes".to_string(),
-                    (false, false) => "Up to date".to_string(),
-                }
+            GitState::Clean | GitState::Behind(_) => false,
+        };
+        if local_commits {
+            format!("{}. {}", FileGitState::LocalCommits, behind.summary())
+        } else {
+            behind.summary()
+        }
+    }
+
+    /// Remote commits which are not present locally (empty unless behind or diverged)
+    pub fn behind(&self) -> &[ObjectId] {
+        match self {
+            GitState::Behind(commits) => commits,
+            GitState::Diverged { behind, .. } => behind,
+            GitState::Clean | GitState::Ahead(_) => &[],
+        }
+    }
+}
+
+/// The commits a local checkout is missing from its remote, narrowed to those
+/// which modify a specific QC file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BehindFileReport {
+    pub file: PathBuf,
+    /// Total number of remote commits missing locally
+    pub behind: usize,
+    /// Missing commits which modify `file`, in the same order as the behind list
+    pub file_commits: Vec<GitCommit>,
+}
+
+impl BehindFileReport {
+    /// Intersect the behind-commit list with the set of commits touching `file`.
+    ///
+    /// `messages` supplies commit subjects; commits without a known message are
+    /// reported with an empty one.
+    pub fn new(
+        file: &Path,
+        behind: &[ObjectId],
+        file_touching: &HashSet<String>,
+        messages: &[GitCommit],
+    ) -> Self {
+        let file_commits = behind
+            .iter()
+            .filter(|c| file_touching.contains(&c.to_string()))
+            .map(|c| GitCommit {
+                commit: *c,
+                message: messages
+                    .iter()
+                    .find(|m| m.commit == *c)
+                    .map(|m| m.message.clone())
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            file: file.to_path_buf(),
+            behind: behind.len(),
+            file_commits,
+        }
+    }
+
+    /// Whether any of the missing commits modify the file
+    pub fn affects_file(&self) -> bool {
+        !self.file_commits.is_empty()
+    }
+
+    /// One line count of the missing commits, e.g. "Behind remote by 4 commits, 2 touch this file"
+    pub fn summary(&self) -> String {
+        let plural = if self.behind == 1 { "" } else { "s" };
+        let touching = match self.file_commits.len() {
+            0 => "none touch".to_string(),
+            1 => "1 touches".to_string(),
+            n => format!("{n} touch"),
+        };
+        format!(
+            "Behind remote by {} commit{plural}, {touching} this file",
+            self.behind
+        )
+    }
+
+    /// The missing commits which modify the file, one "sha message" line each
+    pub fn commit_lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.file_commits.iter().map(|commit| {
+            let sha = &commit.commit.to_string()[..7];
+            if commit.message.is_empty() {
+                sha.to_string()
+            } else {
+                format!("{sha} {}", commit.message)
             }
+        })
+    }
+}
+
+impl fmt::Display for BehindFileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.behind == 1 { "" } else { "s" };
+        if !self.affects_file() {
+            return write!(
+                f,
+                "behind by {} commit{plural}, none of which modify {}",
+                self.behind,
+                self.file.display()
+            );
+        }
+
+        let which = if self.file_commits.len() == 1 && self.behind == 1 {
+            "which modifies".to_string()
+        } else {
+            format!("{} of which modify", self.file_commits.len())
+        };
+        write!(
+            f,
+            "behind by {} commit{plural}, {which} {}:",
+            self.behind,
+            self.file.display()
+        )?;
+        for line in self.commit_lines() {
+            write!(f, "\n    {line}")?;
         }
+        Ok(())
     }
 }
 
+/// Build a [`BehindFileReport`] for `file` from an already-fetched status.
+///
+/// Returns `Ok(None)` when the local checkout is not behind its remote.
+pub fn behind_file_report(
+    git_info: &impl GitCommitOps,
+    status: &GitStatus,
+    file: &Path,
+) -> Result<Option<BehindFileReport>, GitFileOpsError> {
+    let behind = status.state.behind();
+    if behind.is_empty() {
+        return Ok(None);
+    }
+
+    let file_touching = status
+        .remote_commits_touching(git_info, file)?
+        .iter()
+        .map(ObjectId::to_string)
+        .collect();
+    let remote = Some(status.remote_commit.to_string());
+    let messages = match git_info.commits(&remote, behind.last().copied()) {
+        Ok(commits) => commits,
+        Err(e) => {
+            log::debug!("Could not read messages for remote commits: {e}");
+            Vec::new()
+        }
+    };
+
+    Ok(Some(BehindFileReport::new(
+        file,
+        behind,
+        &file_touching,
+        &messages,
+    )))
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GitStatus {
     pub remote_commit: ObjectId,
@@ -73,6 +267,36 @@ pub struct GitStatus {
     pub dirty: Vec<PathBuf>,
 }
 
+impl GitStatus {
+    /// Whether `file` has uncommitted changes, whichever separators its path uses
+    pub fn is_dirty(&self, file: &Path) -> bool {
+        contains_repo_path(&self.dirty, file)
+    }
+
+    /// Remote commits missing locally which modify `file`, in the order of
+    /// [`GitState::behind`]. Empty when the checkout is not behind, e.g. without an upstream.
+    ///
+    /// Reads the remote tracking ref already in the local object store; nothing is fetched.
+    pub fn remote_commits_touching(
+        &self,
+        git_info: &impl GitCommitOps,
+        file: &Path,
+    ) -> Result<Vec<ObjectId>, GitFileOpsError> {
+        let behind = self.state.behind();
+        if behind.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let touching =
+            git_info.file_touching_commits(Some(self.remote_commit.to_string()), file)?;
+        Ok(behind
+            .iter()
+            .filter(|c| touching.contains(&c.to_string()))
+            .copied()
+            .collect())
+    }
+}
+
 impl fmt::Display for GitStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.state)
@@ -530,3 +754,215 @@ where
         dirty,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::file_ops::MockGitCommitOps;
+    use std::str::FromStr;
+
+    fn oid(s: &str) -> ObjectId {
+        ObjectId::from_str(s).unwrap()
+    }
+
+    fn behind_commits() -> Vec<GitCommit> {
+        vec![
+            GitCommit {
+                commit: oid("abc123def456789012345678901234567890abcd"),
+                message: "Update analysis model".to_string(),
+            },
+            GitCommit {
+                commit: oid("def456789abc012345678901234567890123abcd"),
+                message: "Fix README typo".to_string(),
+            },
+            GitCommit {
+                commit: oid("456def789abc012345678901234567890123cdef"),
+                message: "Tweak analysis plot".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_behind_file_report_touching_file() {
+        let commits = behind_commits();
+        let behind: Vec<ObjectId> = commits.iter().map(|c| c.commit).collect();
+        let touching: HashSet<String> = [
+            "abc123def456789012345678901234567890abcd".to_string(),
+            "456def789abc012345678901234567890123cdef".to_string(),
+            // commit on the remote branch which the local checkout already has
+            "789abc12def345678901234567890123456789ef".to_string(),
+        ]
+        .into_iter()
+        .collect();
+
+        let report = BehindFileReport::new(
+            Path::new("scripts/analysis.R"),
+            &behind,
+            &touching,
+            &commits,
+        );
+
+        assert!(report.affects_file());
+        assert_eq!(report.behind, 3);
+        assert_eq!(
+            report.file_commits,
+            vec![commits[0].clone(), commits[2].clone()]
+        );
+        assert_eq!(
+            report.to_string(),
+            "behind by 3 commits, 2 of which modify scripts/analysis.R:\n    abc123d Update analysis model\n    456def7 Tweak analysis plot"
+        );
+    }
+
+    #[test]
+    fn test_behind_file_report_not_touching_file() {
+        let commits = behind_commits();
+        let behind: Vec<ObjectId> = commits.iter().map(|c| c.commit).collect();
+        let touching: HashSet<String> = ["789abc12def345678901234567890123456789ef".to_string()]
+            .into_iter()
+            .collect();
+
+        let report = BehindFileReport::new(
+            Path::new("scripts/analysis.R"),
+            &behind,
+            &touching,
+            &commits,
+        );
+
+        assert!(!report.affects_file());
+        assert_eq!(
+            report.to_string(),
+            "behind by 3 commits, none of which modify scripts/analysis.R"
+        );
+    }
+
+    #[test]
+    fn test_behind_file_report_missing_message() {
+        let behind = vec![oid("abc123def456789012345678901234567890abcd")];
+        let touching: HashSet<String> = ["abc123def456789012345678901234567890abcd".to_string()]
+            .into_iter()
+            .collect();
+
+        let report = BehindFileReport::new(Path::new("a.R"), &behind, &touching, &[]);
+
+        assert_eq!(
+            report.to_string(),
+            "behind by 1 commit, which modifies a.R:\n    abc123d"
+        );
+    }
+
+    fn behind_status(behind: &[&str]) -> GitStatus {
+        GitStatus {
+            remote_commit: oid("abc123def456789012345678901234567890abcd"),
+            state: GitState::Behind(behind.iter().map(|c| oid(c)).collect()),
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Remote history where only `abc123d` and the already-pulled `789abc1` touch the file
+    fn remote_history() -> MockGitCommitOps {
+        let mut git_info = MockGitCommitOps::new();
+        git_info
+            .expect_file_touching_commits()
+            .withf(|branch, file| {
+                branch.as_deref() == Some("abc123def456789012345678901234567890abcd")
+                    && file == Path::new("scripts/analysis.R")
+            })
+            .returning(|_, _| {
+                Ok([
+                    "abc123def456789012345678901234567890abcd".to_string(),
+                    "789abc12def345678901234567890123456789ef".to_string(),
+                ]
+                .into_iter()
+                .collect())
+            });
+        git_info
+    }
+
+    #[test]
+    fn test_remote_commits_touching() {
+        let file = Path::new("scripts/analysis.R");
+        let status = behind_status(&[
+            "abc123def456789012345678901234567890abcd",
+            "def456789abc012345678901234567890123abcd",
+        ]);
+        assert_eq!(
+            status
+                .remote_commits_touching(&remote_history(), file)
+                .unwrap(),
+            vec![oid("abc123def456789012345678901234567890abcd")]
+        );
+
+        let untouched = behind_status(&["def456789abc012345678901234567890123abcd"]);
+        assert!(
+            untouched
+                .remote_commits_touching(&remote_history(), file)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Not behind, e.g. without an upstream: the remote history is not read
+        let ahead = GitStatus {
+            state: GitState::Ahead(vec![oid("def456789abc012345678901234567890123abcd")]),
+            ..untouched
+        };
+        assert!(
+            ahead
+                .remote_commits_touching(&MockGitCommitOps::new(), file)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_format_for_file_behind() {
+        let commits = behind_commits();
+        let behind: Vec<ObjectId> = commits.iter().map(|c| c.commit).collect();
+        let touching: HashSet<String> = ["abc123def456789012345678901234567890abcd".to_string()]
+            .into_iter()
+            .collect();
+        let report = BehindFileReport::new(
+            Path::new("scripts/analysis.R"),
+            &behind,
+            &touching,
+            &commits,
+        );
+        let local = oid("0123456789abcdef0123456789abcdef01234567");
+        let diverged = GitState::Diverged {
+            ahead: vec![local],
+            behind: behind.clone(),
+        };
+
+        assert_eq!(
+            GitState::Behind(behind.clone()).format_for_file_behind(&[], Some(&report)),
+            "Behind remote by 3 commits, 1 touches this file"
+        );
+        assert_eq!(
+            diverged.format_for_file_behind(&[&local], Some(&report)),
+            "Local commits. Behind remote by 3 commits, 1 touches this file"
+        );
+        assert_eq!(
+            diverged.format_for_file_behind(&[&local], None),
+            "Local commits"
+        );
+    }
+
+    #[test]
+    fn test_git_state_behind() {
+        let commits = vec![oid("abc123def456789012345678901234567890abcd")];
+        assert!(GitState::Clean.behind().is_empty());
+        assert!(GitState::Ahead(commits.clone()).behind().is_empty());
+        assert_eq!(
+            GitState::Behind(commits.clone()).behind(),
+            commits.as_slice()
+        );
+        assert_eq!(
+            GitState::Diverged {
+                ahead: Vec::new(),
+                behind: commits.clone()
+            }
+            .behind(),
+            commits.as_slice()
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! TLS trust settings for GitHub Enterprise servers whose certificates are signed by an internal
+//! certificate authority
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, ring};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
+
+use super::auth::AuthError;
+use crate::utils::EnvProvider;
+
+/// PEM file of certificate authorities to trust on top of the platform's
+const CA_BUNDLE_ENV: &str = "GHQC_CA_BUNDLE";
+/// Set to `1` or `true` to skip certificate verification altogether
+const INSECURE_SKIP_VERIFY_ENV: &str = "GHQC_INSECURE_SKIP_VERIFY";
+
+/// How the certificates of the GitHub API and of record images are verified
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TlsSettings {
+    /// The platform's trusted certificate authorities
+    #[default]
+    Native,
+    /// The platform's trusted certificate authorities and those of a PEM bundle
+    CaBundle {
+        path: PathBuf,
+        certificates: Vec<CertificateDer<'static>>,
+    },
+    /// No certificate verification
+    InsecureSkipVerify,
+}
+
+impl TlsSettings {
+    /// Settings from `GHQC_CA_BUNDLE` and `GHQC_INSECURE_SKIP_VERIFY`, failing when the bundle
+    /// does not hold valid PEM certificates
+    pub fn from_env(env: &impl EnvProvider) -> Result<Self, AuthError> {
+        if env
+            .var(INSECURE_SKIP_VERIFY_ENV)
+            .is_ok_and(|value| matches!(value.trim(), "1" | "true"))
+        {
+            log::warn!(
+                "⚠️  {INSECURE_SKIP_VERIFY_ENV} is set: TLS certificates are NOT verified. Anyone on the network can intercept the GitHub token and API traffic. Use {CA_BUNDLE_ENV} with the server's certificate authority instead"
+            );
+            return Ok(Self::InsecureSkipVerify);
+        }
+
+        match env.var(CA_BUNDLE_ENV) {
+            Ok(path) if !path.trim().is_empty() => Self::from_ca_bundle(Path::new(path.trim())),
+            _ => Ok(Self::Native),
+        }
+    }
+
+    /// Trust the certificate authorities of the PEM file at `path` on top of the platform's
+    pub fn from_ca_bundle(path: &Path) -> Result<Self, AuthError> {
+        let invalid = |reason: String| AuthError::InvalidCaBundle {
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+        let certificates = CertificateDer::pem_slice_iter(&pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| invalid(e.to_string()))?;
+        if certificates.is_empty() {
+            return Err(invalid("no PEM certificates found".to_string()));
+        }
+        let mut roots = RootCertStore::empty();
+        for certificate in &certificates {
+            roots
+                .add(certificate.clone())
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+
+        log::debug!(
+            "Trusting {} certificates from {}",
+            certificates.len(),
+            path.display()
+        );
+        Ok(Self::CaBundle {
+            path: path.to_path_buf(),
+            certificates,
+        })
+    }
+
+    /// The rustls configuration for these settings, `None` to keep the HTTP clients' own
+    pub fn client_config(&self) -> Option<Arc<ClientConfig>> {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .expect("the ring provider supports the default protocol versions");
+
+        let config = match self {
+            Self::Native => return None,
+            Self::CaBundle { certificates, .. } => {
+                let mut roots = RootCertStore::empty();
+                let native = rustls_native_certs::load_native_certs();
+                for error in &native.errors {
+                    log::debug!("Failed to load a platform certificate: {error}");
+                }
+                roots.add_parsable_certificates(native.certs);
+                roots.add_parsable_certificates(certificates.iter().cloned());
+                builder.with_root_certificates(roots).with_no_client_auth()
+            }
+            Self::InsecureSkipVerify => builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+                .with_no_client_auth(),
+        };
+        Some(Arc::new(config))
+    }
+}
+
+/// Accepts any server certificate, for `GHQC_INSECURE_SKIP_VERIFY`
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthSources;
+    use crate::utils::MockEnvProvider;
+    use openssl::{
+        asn1::Asn1Time,
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{X509, X509NameBuilder},
+    };
+    use tempfile::TempDir;
+
+    /// A self-signed certificate authority, as an internal GitHub Enterprise CA would be
+    fn ca_pem() -> Vec<u8> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "Internal CA").unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        builder.build().to_pem().unwrap()
+    }
+
+    fn env_with(vars: &[(&'static str, String)]) -> MockEnvProvider {
+        let vars = vars.to_vec();
+        let mut env = MockEnvProvider::new();
+        env.expect_var().returning(move |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.clone())
+                .ok_or(std::env::VarError::NotPresent)
+        });
+        env
+    }
+
+    fn write_bundle(dir: &TempDir, content: &[u8]) -> PathBuf {
+        let path = dir.path().join("ca.pem");
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_tls_settings_default_to_native_roots() {
+        let tls = TlsSettings::from_env(&env_with(&[])).unwrap();
+
+        assert_eq!(tls, TlsSettings::Native);
+        assert!(tls.client_config().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tls_settings_ca_bundle() {
+        let dir = TempDir::new().unwrap();
+        let path = write_bundle(&dir, &ca_pem());
+
+        let tls = TlsSettings::from_env(&env_with(&[(CA_BUNDLE_ENV, path.display().to_string())]))
+            .unwrap();
+
+        match &tls {
+            TlsSettings::CaBundle {
+                path: bundle,
+                certificates,
+            } => {
+                assert_eq!(bundle, &path);
+                assert_eq!(certificates.len(), 1);
+            }
+            other => panic!("expected a CA bundle, got {other:?}"),
+        }
+        assert!(tls.client_config().is_some());
+        AuthSources::default()
+            .with_tls(tls.clone())
+            .client("https://ghe.example.com")
+            .unwrap();
+        crate::UreqDownloader::with_tls(&tls);
+    }
+
+    #[test]
+    fn test_tls_settings_invalid_ca_bundle() {
+        let dir = TempDir::new().unwrap();
+        let cases = [
+            (
+                b"not a certificate\n".to_vec(),
+                "no PEM certificates found",
+            ),
+            (
+                b"-----BEGIN CERTIFICATE-----\nbm90IGEgY2VydGlmaWNhdGU=\n-----END CERTIFICATE-----\n"
+                    .to_vec(),
+                "BadEncoding",
+            ),
+        ];
+
+        for (content, reason) in cases {
+            let path = write_bundle(&dir, &content);
+            let err =
+                TlsSettings::from_env(&env_with(&[(CA_BUNDLE_ENV, path.display().to_string())]))
+                    .unwrap_err();
+            match err {
+                AuthError::InvalidCaBundle {
+                    path: bundle,
+                    reason: actual,
+                } => {
+                    assert_eq!(bundle, path);
+                    assert!(actual.contains(reason), "{actual}");
+                }
+                other => panic!("expected an invalid CA bundle, got {other:?}"),
+            }
+        }
+
+        let missing = dir.path().join("missing.pem");
+        let err = TlsSettings::from_ca_bundle(&missing).unwrap_err();
+        assert!(matches!(err, AuthError::InvalidCaBundle { .. }));
+        assert!(err.to_string().starts_with(&format!(
+            "Invalid CA bundle {} (GHQC_CA_BUNDLE)",
+            missing.display()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_tls_settings_insecure_skip_verify_takes_precedence() {
+        let tls = TlsSettings::from_env(&env_with(&[
+            (INSECURE_SKIP_VERIFY_ENV, "1".to_string()),
+            (CA_BUNDLE_ENV, "/does/not/exist.pem".to_string()),
+        ]))
+        .unwrap();
+
+        assert_eq!(tls, TlsSettings::InsecureSkipVerify);
+        assert!(tls.client_config().is_some());
+        AuthSources::default()
+            .with_tls(tls)
+            .client("https://github.com")
+            .unwrap();
+
+        let tls = TlsSettings::from_env(&env_with(&[(INSECURE_SKIP_VERIFY_ENV, "0".to_string())]))
+            .unwrap();
+        assert_eq!(tls, TlsSettings::Native);
+    }
+}
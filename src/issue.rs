@@ -1,51 +1,56 @@
-use std::{collections::HashSet, fmt, path::PathBuf, str::FromStr, sync::LazyLock};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+};
 
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use ghqctoolkit_core::{
+    StatusCommit, parse_commit_from_pattern, parse_inline_checklist, parse_markers,
+    parse_obsoletion,
+};
 use gix::ObjectId;
 use octocrab::models::{IssueState, issues::Issue};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+pub use ghqctoolkit_core::{CommitStatus, Obsoletion, parse_branch_from_body};
+
 use crate::{
     cache::{DiskCache, get_issue_comments},
+    create::QCRelationship,
     git::{
-        GitComment, GitCommitOps, GitFileOpsError, GitHubApiError, GitHubReader,
-        find_or_cache_file_changes, get_commits_robust,
+        BranchState, CommitIndex, CommitRetrievalInfo, GitComment, GitCommitOps, GitFileOpsError,
+        GitHubApiError, GitHubReader, SignatureStatus, SigningKeys, branch_exists,
+        find_or_cache_file_changes, get_commits_robust, infer_branch_state,
     },
+    utils::normalize_repo_path,
 };
 
-static MARKDOWN_LINK_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\([^)]+\)").unwrap());
+/// Regex for a QC issue entry of the "## Relevant Files" section
+/// Pattern: - [file_name](url/issues/123) - description, the description being optional
+/// Works with any host (github.com, GHE, GitLab's `/-/issues/`, etc.)
+static RELEVANT_ISSUE_ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*-\s+\[([^\]]+)\]\(([^)\s]*/issues/(\d+)[^)\s]*)\)(?:\s+-\s+(.*\S))?\s*$")
+        .unwrap()
+});
 
-/// Regex to extract file name and issue number from markdown links to issues
-/// Pattern: [file_name](url/issues/123) - captures link text and issue number
-/// Works with any host (github.com, GHE, etc.)
-static BLOCKING_QC_LINK_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\([^)]*\/issues\/(\d+)[^)]*\)").unwrap());
+/// Regex for a relevant file without a QC issue in the "## Relevant Files" section
+/// Pattern: - **file_name** ([abc1234](url)) - justification, the commit link and justification
+/// being optional
+static RELEVANT_FILE_ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*-\s+\*\*([^*]+)\*\*(?:\s+\(\[([0-9a-fA-F]+)\]\(([^)\s]+)\)\))?(?:\s+-\s+(.*\S))?\s*$",
+    )
+    .unwrap()
+});
 
 pub(crate) static HTML_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"<a\s+[^>]*href\s*=\s*["']([^"']+)["'][^>]*>([^<]*)</a>"#).unwrap()
 });
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum CommitStatus {
-    Initial,
-    Notification,
-    Approved,
-    Reviewed,
-}
-
-impl fmt::Display for CommitStatus {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let self_str = match self {
-            Self::Initial => "initial",
-            Self::Notification => "notification",
-            Self::Approved => "approved",
-            Self::Reviewed => "reviewed",
-        };
-        write!(f, "{self_str}")
-    }
-}
-
 /// Relationship type for blocking QC issues
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub enum BlockingRelationship {
@@ -77,6 +82,137 @@ pub struct BlockingQC {
     pub file_name: PathBuf,
     /// The relationship type (GatingQC or PreviousQC)
     pub relationship: BlockingRelationship,
+    /// URL of the issue as linked in the body, which may be of another repository
+    pub url: String,
+}
+
+/// An entry of the "## Relevant Files" section of an issue body, as written when the issue is
+/// created
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelevantFileLink {
+    /// A QC issue listed under `### Previous QC`, `### Gating QC` or `### Relevant QC`
+    Issue {
+        /// The link text
+        file_name: PathBuf,
+        url: String,
+        issue_number: u64,
+        relationship: QCRelationship,
+        description: Option<String>,
+    },
+    /// A file without a QC issue listed under `### Relevant File`
+    File {
+        file_name: PathBuf,
+        /// Short hash of the commit the file is linked at, if any
+        commit: Option<String>,
+        /// URL of the file at `commit`, if any
+        file_url: Option<String>,
+        justification: Option<String>,
+    },
+}
+
+/// A file of the "## Relevant Files" section of an issue body, with why it is relevant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueRelevantFile {
+    pub file: PathBuf,
+    /// Subsection listing the file: `Previous QC`, `Gating QC`, `Relevant QC` or `Relevant File`
+    pub kind: String,
+    /// Description of the linked QC issue, or justification of a file without one
+    pub justification: Option<String>,
+}
+
+impl From<RelevantFileLink> for IssueRelevantFile {
+    fn from(link: RelevantFileLink) -> Self {
+        match link {
+            RelevantFileLink::Issue {
+                file_name,
+                relationship,
+                description,
+                ..
+            } => Self {
+                file: file_name,
+                kind: match relationship {
+                    QCRelationship::PreviousQC => "Previous QC",
+                    QCRelationship::GatingQC => "Gating QC",
+                    QCRelationship::RelevantQC => "Relevant QC",
+                }
+                .to_string(),
+                justification: description,
+            },
+            RelevantFileLink::File {
+                file_name,
+                justification,
+                ..
+            } => Self {
+                file: file_name,
+                kind: "Relevant File".to_string(),
+                justification,
+            },
+        }
+    }
+}
+
+impl fmt::Display for IssueRelevantFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.file.display(), self.kind)?;
+        if let Some(justification) = &self.justification {
+            write!(f, ": {justification}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A QC step posted on an issue, in posting order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QCEvent {
+    /// The issue was created for its initial QC commit
+    Initial,
+    Notification,
+    Review,
+    Approval,
+    Unapproval,
+}
+
+/// QC rounds of an issue, where each notification → review exchange is a round
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QCRounds {
+    /// Round the issue is in, `0` when nothing has been posted for QC
+    pub current_round: u32,
+    /// Round in which the standing approval was given
+    pub rounds_to_approval: Option<u32>,
+}
+
+impl QCRounds {
+    /// Count rounds over the QC steps of an issue, oldest first
+    ///
+    /// A notification following a review or approval starts a new round, so several
+    /// notifications before a review count as one round. A review without a preceding
+    /// notification starts round 1, and an un-approval keeps the count going rather than
+    /// resetting it.
+    pub fn from_events(events: impl IntoIterator<Item = QCEvent>) -> Self {
+        let mut rounds = Self::default();
+        let mut reviewed = false;
+
+        for event in events {
+            match event {
+                QCEvent::Initial | QCEvent::Notification => {
+                    if rounds.current_round == 0 || reviewed {
+                        rounds.current_round += 1;
+                        reviewed = false;
+                    }
+                }
+                QCEvent::Review | QCEvent::Approval => {
+                    rounds.current_round = rounds.current_round.max(1);
+                    reviewed = true;
+                    if event == QCEvent::Approval {
+                        rounds.rounds_to_approval = Some(rounds.current_round);
+                    }
+                }
+                QCEvent::Unapproval => rounds.rounds_to_approval = None,
+            }
+        }
+
+        rounds
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -87,16 +223,107 @@ pub struct IssueCommit {
     pub file_changed: bool,
 }
 
+impl IssueCommit {
+    /// Whether QC comments record activity on the commit, see [`CommitStatus::is_activity`]
+    pub fn has_activity(&self) -> bool {
+        self.statuses.iter().any(CommitStatus::is_activity)
+    }
+
+    /// The file was at this commit when an approval was withdrawn
+    pub fn is_unapproved(&self) -> bool {
+        self.statuses.contains(&CommitStatus::Unapproved)
+    }
+}
+
+impl StatusCommit for IssueCommit {
+    type Hash = ObjectId;
+
+    fn hash(&self) -> &ObjectId {
+        &self.hash
+    }
+
+    fn file_changed(&self) -> bool {
+        self.file_changed
+    }
+
+    fn statuses(&self) -> &HashSet<CommitStatus> {
+        &self.statuses
+    }
+}
+
+/// A QC commit with its signature state, as surfaced in status output and the record
+#[derive(Debug, Clone, PartialEq)]
+pub struct QCCommitSignature {
+    /// Which QC event the commit belongs to: initial, notified or approved
+    pub role: CommitStatus,
+    pub commit: ObjectId,
+    pub status: SignatureStatus,
+}
+
+impl QCCommitSignature {
+    pub fn label(&self) -> &'static str {
+        match self.role {
+            CommitStatus::Initial => "Initial",
+            CommitStatus::Notification => "Notified",
+            CommitStatus::Approved => "Approved",
+            CommitStatus::Reviewed => "Reviewed",
+            CommitStatus::Unapproved => "Unapproved",
+        }
+    }
+}
+
+impl fmt::Display for QCCommitSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {}",
+            self.label(),
+            &self.commit.to_string()[..7],
+            self.status
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct IssueThread {
     pub file: PathBuf,
+    /// Number of the QC issue
+    pub issue_number: u64,
+    pub issue_url: String,
     pub branch: String,
+    /// The branch no longer exists locally or on the remote, e.g. after being merged and deleted
+    pub branch_deleted: bool,
     pub(crate) open: bool,
     pub commits: Vec<IssueCommit>,
     pub milestone: String,
     /// Blocking QC issues parsed from issue body
     /// Includes both Gating QC and Previous QC sections
     pub blocking_qcs: Vec<BlockingQC>,
+    /// Every file of the issue body's relevant files, with its justification
+    pub relevant_files: Vec<IssueRelevantFile>,
+    /// When the standing approval took effect, `None` if the issue is not approved
+    ///
+    /// This is the approval's effective date (at midnight UTC) for approvals recorded after the
+    /// fact, and otherwise when the approval was posted.
+    pub approved_at: Option<DateTime<Utc>>,
+    /// When a backfilled approval was posted, `None` unless the approval has an effective date
+    pub approval_recorded_at: Option<DateTime<Utc>>,
+    /// Login of who posted the standing approval, `None` if the issue is not approved
+    pub approved_by: Option<String>,
+    /// The latest unapproval, `None` if the issue was never unapproved or was approved again since
+    pub unapproval: Option<Unapproval>,
+    /// When the latest QC notification was posted
+    pub notified_at: Option<DateTime<Utc>>,
+    /// Why the issue was taken out of QC scope, `None` unless it is currently obsolete
+    pub obsoleted: Option<Obsoletion>,
+    /// File name of the one-off checklist the issue was created with, `None` for configured
+    /// checklists
+    pub inline_checklist: Option<String>,
+    pub rounds: QCRounds,
+    /// How the commits were found, flagging histories resolved through a fallback
+    pub commit_retrieval: CommitRetrievalInfo,
+    /// Renames of the file on the branch since the initial commit, oldest first
+    pub renames: Vec<FileRenameEvent>,
 }
 
 impl IssueThread {
@@ -107,7 +334,30 @@ impl IssueThread {
         git_info: &impl GitCommitOps,
         disk_cache: Option<&DiskCache>,
     ) -> Result<Self, IssueError> {
-        let file = PathBuf::from(&issue.title);
+        Self::build(issue, comments, git_info, disk_cache, None)
+    }
+
+    /// Create IssueThread from issue and pre-fetched comments, taking the branch history from
+    /// `index` instead of walking it for this issue alone
+    pub fn from_issue_comments_with_index(
+        issue: &Issue,
+        comments: &[GitComment],
+        git_info: &impl GitCommitOps,
+        disk_cache: Option<&DiskCache>,
+        index: &CommitIndex,
+    ) -> Result<Self, IssueError> {
+        Self::build(issue, comments, git_info, disk_cache, Some(index))
+    }
+
+    fn build(
+        issue: &Issue,
+        comments: &[GitComment],
+        git_info: &impl GitCommitOps,
+        disk_cache: Option<&DiskCache>,
+        index: Option<&CommitIndex>,
+    ) -> Result<Self, IssueError> {
+        // Titles of issues created on Windows may use `\` separators
+        let file = normalize_repo_path(Path::new(&issue.title));
         let issue_is_open = matches!(issue.state, IssueState::Open);
         let milestone = if let Some(m) = &issue.milestone {
             m.title.to_string()
@@ -161,45 +411,78 @@ impl IssueThread {
         // Use the initial commit as a stop point so the walk terminates early on large repos.
         let stop_at = ObjectId::from_str(&initial_commit_str).ok();
 
-        let all_commits = get_commits_robust(
-            git_info,
-            &Some(branch.clone()),
-            reference_commit.as_ref(),
-            stop_at,
-            disk_cache,
-        )?;
+        let indexed = index.and_then(|index| index.branch(git_info, &branch, disk_cache));
+        let indexed_commits = indexed.as_ref().and_then(|indexed| indexed.since(stop_at));
+        let indexed = indexed.filter(|_| indexed_commits.is_some());
+        let (all_commits, commit_retrieval) = match indexed_commits {
+            Some(commits) => (commits, CommitRetrievalInfo::default()),
+            None => get_commits_robust(
+                git_info,
+                &Some(branch.clone()),
+                reference_commit.as_ref(),
+                stop_at,
+                disk_cache,
+            )?,
+        };
+        // The commits only fall back to another history when the branch tip could not be
+        // resolved, so only then look for the branch on the remotes
+        let branch_deleted = commit_retrieval.is_fallback()
+            && !branch_exists(git_info, &branch, reference_commit.as_ref());
+        if branch_deleted {
+            log::debug!(
+                "Branch {} no longer exists locally or on the remote",
+                branch
+            );
+        }
 
-        // Pre-compute which commits touch this issue's file (one subprocess call).
+        // Pre-compute which commits touch this issue's file (one subprocess call, shared by the
+        // issues of the branch when indexed).
         let commit_hashes: Vec<String> = all_commits.iter().map(|c| c.commit.to_string()).collect();
-        let mut file_touching = find_or_cache_file_changes(
-            &commit_hashes,
-            git_info,
-            Some(branch.clone()),
-            &file,
-            disk_cache,
-        )?;
-
-        // Also mark commits that touched any previously-known file names from ## File History.
-        // This ensures commits made against the old filename are still flagged as file-changing.
-        let old_paths: Vec<PathBuf> = issue
-            .body
-            .as_deref()
-            .map(|body| {
-                parse_file_history(body)
-                    .into_iter()
-                    .map(|e| PathBuf::from(e.old_path))
-                    .collect()
-            })
-            .unwrap_or_default();
-        for old_path in &old_paths {
-            let old_touching = find_or_cache_file_changes(
+        let touching_commits = |path: &Path| match &indexed {
+            Some(indexed) => indexed.file_commits(git_info, path, disk_cache),
+            None => find_or_cache_file_changes(
                 &commit_hashes,
                 git_info,
                 Some(branch.clone()),
-                old_path,
+                path,
                 disk_cache,
+            ),
+        };
+        let mut file_touching = touching_commits(&file)?;
+
+        // Follow renames of the file since the initial commit. Failing to detect them only
+        // loses the commits made under other names, so it does not fail the thread.
+        let renames = git_info
+            .file_renames(Some(branch.clone()), &file, stop_at)
+            .unwrap_or_else(|e| {
+                log::debug!("Could not detect renames of {}: {}", file.display(), e);
+                Vec::new()
+            });
+
+        // Also mark commits that touched any other name of the file, from ## File History or
+        // the detected renames, so commits made against those names are still file-changing.
+        let mut old_paths: Vec<PathBuf> = Vec::new();
+        let history = issue
+            .body
+            .as_deref()
+            .map(parse_file_history)
+            .unwrap_or_default();
+        for path in history
+            .into_iter()
+            .map(|e| e.old_path)
+            .chain(
+                renames
+                    .iter()
+                    .flat_map(|e| [e.old_path.clone(), e.new_path.clone()]),
             )
-            .map_err(IssueError::GitFileOpsError)?;
+            .map(PathBuf::from)
+        {
+            if path != file && !old_paths.contains(&path) {
+                old_paths.push(path);
+            }
+        }
+        for old_path in &old_paths {
+            let old_touching = touching_commits(old_path).map_err(IssueError::GitFileOpsError)?;
             file_touching.extend(old_touching);
         }
 
@@ -209,22 +492,36 @@ impl IssueThread {
         // all_commits is latest commit first in the vec.
         // We want to iter rev to "look" from the bottom for the first qc notification to kick-off recording commits.
         // Typically the first qc notification will be initial, but flexible enough to accept any
+        // With the branch indexed, resolve the commits of the thread up front rather than
+        // comparing each of them to every commit of the branch
+        let resolved_commits = indexed.as_ref().map(|indexed| {
+            let mut resolved: HashMap<ObjectId, HashSet<CommitStatus>> = HashMap::new();
+            for (issue_commit_str, statuses) in &issue_thread_commits {
+                if let Some(commit) = indexed.find(issue_commit_str) {
+                    resolved
+                        .entry(commit.commit)
+                        .or_default()
+                        .extend(statuses.iter().cloned());
+                }
+            }
+            resolved
+        });
         for commit in all_commits.into_iter().rev() {
-            let statuses = issue_thread_commits
-                .iter()
-                .find_map(|(issue_commit_str, statuses)| {
-                    let full_sha = commit.commit.to_string();
-                    // Handle both exact matches and short SHA matches
-                    if **issue_commit_str == full_sha
-                        || (issue_commit_str.len() >= 7 && full_sha.starts_with(issue_commit_str))
-                    {
-                        qc_notif_found = true;
-                        Some(statuses.clone())
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or_else(HashSet::new);
+            let statuses = match &resolved_commits {
+                Some(resolved) => resolved.get(&commit.commit).cloned(),
+                None => issue_thread_commits
+                    .iter()
+                    .find_map(|(issue_commit_str, statuses)| {
+                        let full_sha = commit.commit.to_string();
+                        // Handle both exact matches and short SHA matches
+                        (**issue_commit_str == full_sha
+                            || (issue_commit_str.len() >= 7
+                                && full_sha.starts_with(issue_commit_str)))
+                        .then(|| statuses.clone())
+                    }),
+            };
+            qc_notif_found |= statuses.is_some();
+            let statuses = statuses.unwrap_or_else(HashSet::new);
             let file_changed = file_touching.contains(&commit.commit.to_string());
 
             if qc_notif_found {
@@ -253,13 +550,53 @@ impl IssueThread {
             .map(|body| parse_blocking_qcs(body))
             .unwrap_or_default();
 
+        let relevant_files = issue
+            .body
+            .as_deref()
+            .map(parse_relevant_files)
+            .unwrap_or_default();
+
+        // 8. Timestamps of the standing approval and latest notification
+        let QCCommentTimes {
+            approved_at,
+            approval_recorded_at,
+            notified_at,
+        } = parse_qc_comment_times(comments);
+        let approved_by = parse_approver(comments);
+        let unapproval = parse_unapproval(comments);
+
+        // 9. Whether the issue has been obsoleted and not since restored
+        let obsoleted = parse_obsoletion(comments.iter().map(|comment| comment.body.as_str()));
+
+        // 10. Local checklist file the issue was created with, if any
+        let inline_checklist = issue.body.as_deref().and_then(parse_inline_checklist);
+
+        // 11. QC rounds, counted from the initial commit onwards
+        let rounds = QCRounds::from_events(
+            std::iter::once(QCEvent::Initial).chain(parse_qc_events(comments)),
+        );
+
         Ok(IssueThread {
             file,
+            issue_number: issue.number,
+            issue_url: issue.html_url.to_string(),
             branch,
+            branch_deleted,
             open: issue_is_open,
             commits: issue_commits,
             milestone,
             blocking_qcs,
+            relevant_files,
+            approved_at,
+            approval_recorded_at,
+            approved_by,
+            unapproval,
+            notified_at,
+            obsoleted,
+            inline_checklist,
+            rounds,
+            commit_retrieval,
+            renames,
         })
     }
 
@@ -273,30 +610,52 @@ impl IssueThread {
         Self::from_issue_comments(issue, &comments, git_info, disk_cache)
     }
 
+    /// Whether the issue's branch still exists and, if not, where it was merged
+    pub fn branch_state(&self, git_info: &impl GitCommitOps) -> BranchState {
+        if !self.branch_deleted {
+            return BranchState::Present;
+        }
+
+        infer_branch_state(git_info, &self.branch, &self.latest_commit().hash).unwrap_or_else(|e| {
+            log::debug!(
+                "Failed to determine where {} was merged: {}",
+                self.branch,
+                e
+            );
+            BranchState::Deleted
+        })
+    }
+
+    /// The most recent commit carrying any QC status
+    ///
+    /// A commit can hold several statuses at once, so an approval only wins over a
+    /// notification or review when both are on the same commit. Use
+    /// [`IssueThread::latest_approved_or_activity`] when the approved commit should win.
     pub fn latest_commit(&self) -> &IssueCommit {
-        // Find the latest commit with the highest priority status
-        // Priority: Approved > (Notification | Initial | Reviewed) - with tie-break to most recent
-        let mut latest_commentable = None; // Notification, Initial, or Reviewed
-
-        // Iterate in forward order (newest first) to find most recent commits first
-        for commit in &self.commits {
-            // Return immediately on first approved commit (highest priority)
-            if commit.statuses.contains(&CommitStatus::Approved) {
-                return &commit;
-            }
+        // commits are ordered newest first
+        self.commits
+            .iter()
+            .find(|commit| commit.has_activity())
+            .expect("IssueThread must have at least one commit with Initial status")
+    }
 
-            // Track first significant commit we find as fallback
-            if latest_commentable.is_none() {
-                if commit.statuses.contains(&CommitStatus::Notification)
-                    || commit.statuses.contains(&CommitStatus::Initial)
-                    || commit.statuses.contains(&CommitStatus::Reviewed)
-                {
-                    latest_commentable = Some(commit);
-                }
-            }
-        }
+    /// The approved commit if the approval still stands, otherwise [`IssueThread::latest_commit`]
+    pub fn latest_approved_or_activity(&self) -> &IssueCommit {
+        self.approved_commit()
+            .unwrap_or_else(|| self.latest_commit())
+    }
 
-        latest_commentable.expect("IssueThread must have at least one commit with Initial status")
+    /// Default commit to diff `current` against: the latest QC activity before `current`,
+    /// or the commit immediately preceding it when there is no earlier activity
+    pub fn previous_qc_commit(&self, current: &ObjectId) -> Option<ObjectId> {
+        let current_position = self.commits.iter().position(|c| &c.hash == current)?;
+        let older = &self.commits[current_position + 1..];
+
+        older
+            .iter()
+            .find(|commit| commit.has_activity())
+            .or_else(|| older.first())
+            .map(|commit| commit.hash)
     }
 
     pub fn approved_commit(&self) -> Option<&IssueCommit> {
@@ -305,6 +664,48 @@ impl IssueThread {
             .find(|commit| commit.statuses.contains(&CommitStatus::Approved))
     }
 
+    /// The names the file had since the initial commit, oldest first
+    pub fn file_paths(&self) -> Vec<PathBuf> {
+        match self.renames.first() {
+            Some(first) => std::iter::once(&first.old_path)
+                .chain(self.renames.iter().map(|rename| &rename.new_path))
+                .map(PathBuf::from)
+                .collect(),
+            None => vec![self.file.clone()],
+        }
+    }
+
+    /// The name of the file at the latest commit of the thread
+    pub fn current_file(&self) -> PathBuf {
+        self.renames
+            .last()
+            .map(|rename| PathBuf::from(&rename.new_path))
+            .unwrap_or_else(|| self.file.clone())
+    }
+
+    /// Renames made after `from` up to and including `to`, oldest first
+    pub fn renames_between(&self, from: &ObjectId, to: &ObjectId) -> Vec<FileRenameEvent> {
+        let position = |hash: &str| {
+            self.commits
+                .iter()
+                .position(|commit| commit.hash.to_string().starts_with(hash))
+        };
+        let (Some(from), Some(to)) = (position(&from.to_string()), position(&to.to_string()))
+        else {
+            return Vec::new();
+        };
+
+        // commits are ordered newest first
+        self.renames
+            .iter()
+            .filter(|rename| {
+                !rename.commit.is_empty()
+                    && position(&rename.commit).is_some_and(|p| to <= p && p < from)
+            })
+            .cloned()
+            .collect()
+    }
+
     pub fn file_commits(&self) -> Vec<&ObjectId> {
         self.commits
             .iter()
@@ -321,190 +722,315 @@ impl IssueThread {
             .expect("IssueThread must have exactly one commit with Initial status")
             .hash
     }
+
+    /// Signature state of the initial, latest notified and approved commits.
+    ///
+    /// Commits whose signature cannot be checked are reported as unverified.
+    pub fn qc_commit_signatures(
+        &self,
+        git_info: &impl GitCommitOps,
+        keys: &SigningKeys,
+    ) -> Vec<QCCommitSignature> {
+        let notified = self
+            .commits
+            .iter()
+            .find(|c| c.statuses.contains(&CommitStatus::Notification))
+            .map(|c| c.hash);
+        let approved = self.approved_commit().map(|c| c.hash);
+
+        [
+            (CommitStatus::Initial, Some(*self.initial_commit())),
+            (CommitStatus::Notification, notified),
+            (CommitStatus::Approved, approved),
+        ]
+        .into_iter()
+        .filter_map(|(role, commit)| commit.map(|commit| (role, commit)))
+        .map(|(role, commit)| {
+            let status = git_info
+                .signature_status(&commit, keys)
+                .unwrap_or_else(|e| {
+                    log::debug!("Could not determine signature of {commit}: {e}");
+                    SignatureStatus::Unverified
+                });
+            QCCommitSignature {
+                role,
+                commit,
+                status,
+            }
+        })
+        .collect()
+    }
 }
 
 /// Parse notification and approval commits from comment bodies
 /// Returns a HashMap of commit strings to their accumulated status sets
-/// Uses accumulative approach - commits can hold multiple statuses simultaneously
-fn parse_commits_from_comments<'a>(
-    comments: &'a [GitComment],
-) -> std::collections::HashMap<&'a str, HashSet<CommitStatus>> {
-    let mut commit_statuses = std::collections::HashMap::new();
-    let mut approved_commit = None;
-    let mut approval_comment_index = None;
-
-    // Parse all comments in order
-    for (index, comment) in comments.iter().enumerate() {
-        // Check for notification commit: "current commit: {hash}"
-        if let Some(commit) = parse_commit_from_pattern(&comment.body, "current commit: ") {
-            // Add notification status (accumulative approach)
-            let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
-            statuses.insert(CommitStatus::Notification);
-        }
-
-        // Check for approval commit: "approved qc commit: {hash}"
-        if let Some(commit) = parse_commit_from_pattern(&comment.body, "approved qc commit: ") {
-            // Remove Approved status from all other commits (only one approval allowed)
-            for statuses in commit_statuses.values_mut() {
-                statuses.remove(&CommitStatus::Approved);
-            }
+fn parse_commits_from_comments(comments: &[GitComment]) -> HashMap<&str, HashSet<CommitStatus>> {
+    parse_markers(comments.iter().map(|comment| comment.body.as_str()))
+}
 
-            // Add approved status to this commit
-            let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
-            statuses.insert(CommitStatus::Approved);
-            approved_commit = Some(commit);
-            approval_comment_index = Some(index);
+/// When the standing approval took effect and the latest notification was posted
+#[derive(Debug, Default, PartialEq)]
+struct QCCommentTimes {
+    approved_at: Option<DateTime<Utc>>,
+    approval_recorded_at: Option<DateTime<Utc>>,
+    notified_at: Option<DateTime<Utc>>,
+}
+
+/// Parse when the standing approval took effect and when the latest notification was posted
+///
+/// An approval followed by an un-approval no longer stands. An approval's effective date takes
+/// precedence over when its comment was posted.
+fn parse_qc_comment_times(comments: &[GitComment]) -> QCCommentTimes {
+    let mut times = QCCommentTimes::default();
+
+    for comment in comments {
+        if parse_commit_from_pattern(&comment.body, "current commit: ").is_some() {
+            times.notified_at = Some(comment.created_at);
         }
 
-        // Check for review commit: "comparing commit: {hash}" in "# QC Review" comments
-        if comment.body.contains("# QC Review") {
-            if let Some(commit) = parse_commit_from_pattern(&comment.body, "comparing commit: ") {
-                // Add reviewed status (accumulative approach)
-                let statuses = commit_statuses.entry(commit).or_insert_with(HashSet::new);
-                statuses.insert(CommitStatus::Reviewed);
+        if parse_commit_from_pattern(&comment.body, "approved qc commit: ").is_some() {
+            match parse_effective_date(&comment.body) {
+                Some(date) => {
+                    times.approved_at = Some(date.and_time(NaiveTime::MIN).and_utc());
+                    times.approval_recorded_at = Some(comment.created_at);
+                }
+                None => {
+                    times.approved_at = Some(comment.created_at);
+                    times.approval_recorded_at = None;
+                }
             }
         }
 
-        // Check for unapproval: "# QC Un-Approval"
         if comment.body.contains("# QC Un-Approval") {
-            // If this unapproval comes after an approval, remove the approval status
-            if let Some(approval_index) = approval_comment_index {
-                if index > approval_index {
-                    if let Some(commit) = approved_commit {
-                        if let Some(statuses) = commit_statuses.get_mut(commit) {
-                            statuses.remove(&CommitStatus::Approved);
-                        }
-                    }
-                    approved_commit = None;
-                    approval_comment_index = None;
-                }
-            }
+            times.approved_at = None;
+            times.approval_recorded_at = None;
         }
     }
 
-    commit_statuses
+    times
 }
 
-/// Parse a commit from a body using the given pattern
-/// Supports both full and short SHAs with minimum 7 character length
-fn parse_commit_from_pattern<'a>(body: &'a str, pattern: &str) -> Option<&'a str> {
-    let start = body.find(pattern)?;
-    let commit_start = start + pattern.len();
-
-    let remaining = &body[commit_start..];
-    remaining.lines().next()?.split_whitespace().next()
+/// When, by whom and at which commit an approval was withdrawn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unapproval {
+    /// The unapproval's effective date (at midnight UTC) when recorded after the fact, otherwise
+    /// when it was posted
+    pub at: DateTime<Utc>,
+    /// Login of who posted the unapproval
+    pub by: String,
+    /// Commit the file was at, `None` for unapprovals posted before it was recorded
+    pub commit: Option<String>,
+    /// Commit of the approval withdrawn, `None` for unapprovals posted before it was recorded
+    pub previously_approved: Option<String>,
 }
 
-/// Parse branch name from issue body
-/// Only looks for the "git branch: <branch-name>" pattern
-/// Branch name can be plain text, markdown link text, or HTML link text
-pub fn parse_branch_from_body(body: &str) -> Option<String> {
-    let pattern = "git branch: ";
-    let start = body.find(pattern)?;
-    let branch_start = start + pattern.len();
-    let remaining = &body[branch_start..];
-    let line = remaining.lines().next()?;
-
-    // Check if the branch name is a markdown link [name](url)
-    if let Some(md_captures) = MARKDOWN_LINK_REGEX.captures(line) {
-        if let Some(link_text) = md_captures.get(1) {
-            let branch_name = link_text.as_str().trim();
-            if !branch_name.is_empty() {
-                return Some(branch_name.to_string());
-            }
+impl fmt::Display for Unapproval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} by {}", self.at.format("%Y-%m-%d"), self.by)?;
+        if let Some(commit) = &self.commit {
+            write!(f, " at {}", short_sha(commit))?;
+        }
+        if let Some(approved) = &self.previously_approved {
+            write!(f, " (previously approved {})", short_sha(approved))?;
         }
+        Ok(())
     }
+}
 
-    // Check if the branch name is an HTML link <a href="url">text</a>
-    if let Some(html_captures) = HTML_LINK_REGEX.captures(line) {
-        if let Some(link_text) = html_captures.get(2) {
-            let branch_name = link_text.as_str().trim();
-            if !branch_name.is_empty() {
-                return Some(branch_name.to_string());
-            }
+fn short_sha(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
+
+/// Parse the latest unapproval, unless an approval was posted after it
+///
+/// Follows [`parse_qc_comment_times`] in telling approvals and unapprovals apart.
+fn parse_unapproval(comments: &[GitComment]) -> Option<Unapproval> {
+    let mut unapproval = None;
+    for comment in comments {
+        if parse_commit_from_pattern(&comment.body, "approved qc commit: ").is_some() {
+            unapproval = None;
+        }
+        if comment.body.contains("# QC Un-Approval") {
+            let at = parse_effective_date(&comment.body)
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+                .unwrap_or(comment.created_at);
+            let commit =
+                |pattern| parse_commit_from_pattern(&comment.body, pattern).map(str::to_string);
+            unapproval = Some(Unapproval {
+                at,
+                by: comment.author_login.clone(),
+                commit: commit("unapproved at commit: "),
+                previously_approved: commit("previously approved commit: "),
+            });
         }
     }
+    unapproval
+}
 
-    // Fall back to plain text branch name
-    let branch_name = line.trim();
-    if !branch_name.is_empty() {
-        Some(branch_name.to_string())
-    } else {
-        None
+/// Login of who posted the standing approval
+///
+/// Follows [`parse_qc_comment_times`]: an approval followed by an un-approval no longer stands.
+fn parse_approver(comments: &[GitComment]) -> Option<String> {
+    let mut approver = None;
+    for comment in comments {
+        if parse_commit_from_pattern(&comment.body, "approved qc commit: ").is_some() {
+            approver = Some(comment.author_login.clone());
+        }
+        if comment.body.contains("# QC Un-Approval") {
+            approver = None;
+        }
     }
+    approver
 }
 
-/// Parse blocking QC issues from issue body
-///
-/// Looks for the "## Relevant Files" section and extracts:
-/// - `### Gating QC` subsection → `BlockingRelationship::GatingQC`
-/// - `### Previous QC` subsection → `BlockingRelationship::PreviousQC`
+/// Parse the `effective date: YYYY-MM-DD` of an approval or unapproval recorded after the fact
+pub fn parse_effective_date(body: &str) -> Option<NaiveDate> {
+    let pattern = "effective date: ";
+    let start = body.find(pattern)?;
+    let date = body[start + pattern.len()..].split_whitespace().next()?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// QC steps posted in the comments, in posting order
 ///
-/// Extracts file name (link text) and issue number from markdown links.
-pub fn parse_blocking_qcs(body: &str) -> Vec<BlockingQC> {
-    let mut blocking_qcs = Vec::new();
+/// Mirrors how [`parse_commits_from_comments`] recognizes each kind of comment.
+fn parse_qc_events(comments: &[GitComment]) -> Vec<QCEvent> {
+    let mut events = Vec::new();
 
-    // Find the start of "## Relevant Files" section
-    let relevant_files_start = match body.find("## Relevant Files") {
-        Some(pos) => pos,
-        None => return blocking_qcs,
-    };
+    for comment in comments {
+        if parse_commit_from_pattern(&comment.body, "current commit: ").is_some() {
+            events.push(QCEvent::Notification);
+        }
 
-    let relevant_section = &body[relevant_files_start..];
+        if parse_commit_from_pattern(&comment.body, "approved qc commit: ").is_some() {
+            events.push(QCEvent::Approval);
+        }
 
-    // Find the end of the relevant files section (next level 2 header or end of body)
-    let section_end = relevant_section[17..] // Skip "## Relevant Files"
-        .find("\n## ")
-        .map(|pos| pos + 17)
-        .unwrap_or(relevant_section.len());
-
-    let relevant_section = &relevant_section[..section_end];
-
-    // Parse Gating QC section
-    if let Some(gating_start) = relevant_section.find("### Gating QC") {
-        let gating_section = &relevant_section[gating_start..];
-        let gating_end = gating_section[13..] // Skip "### Gating QC"
-            .find("\n### ")
-            .map(|pos| pos + 13)
-            .unwrap_or(gating_section.len());
-        let gating_section = &gating_section[..gating_end];
-
-        for capture in BLOCKING_QC_LINK_REGEX.captures_iter(gating_section) {
-            if let (Some(file_name), Some(issue_number)) = (capture.get(1), capture.get(2)) {
-                if let Ok(issue_num) = issue_number.as_str().parse::<u64>() {
-                    blocking_qcs.push(BlockingQC {
-                        issue_number: issue_num,
-                        file_name: PathBuf::from(file_name.as_str()),
-                        relationship: BlockingRelationship::GatingQC,
-                    });
-                }
-            }
+        if comment.body.contains("# QC Review")
+            && parse_commit_from_pattern(&comment.body, "comparing commit: ").is_some()
+        {
+            events.push(QCEvent::Review);
+        }
+
+        if comment.body.contains("# QC Un-Approval") {
+            events.push(QCEvent::Unapproval);
         }
     }
 
-    // Parse Previous QC section
-    if let Some(previous_start) = relevant_section.find("### Previous QC") {
-        let previous_section = &relevant_section[previous_start..];
-        let previous_end = previous_section[15..] // Skip "### Previous QC"
-            .find("\n### ")
-            .map(|pos| pos + 15)
-            .unwrap_or(previous_section.len());
-        let previous_section = &previous_section[..previous_end];
-
-        for capture in BLOCKING_QC_LINK_REGEX.captures_iter(previous_section) {
-            if let (Some(file_name), Some(issue_number)) = (capture.get(1), capture.get(2)) {
-                if let Ok(issue_num) = issue_number.as_str().parse::<u64>() {
-                    blocking_qcs.push(BlockingQC {
-                        issue_number: issue_num,
-                        file_name: PathBuf::from(file_name.as_str()),
-                        relationship: BlockingRelationship::PreviousQC,
-                    });
-                }
-            }
+    events
+}
+
+/// Parse the entries of the "## Relevant Files" section of the issue body
+///
+/// This is the one reader of the section: callers pick the entries they need. Lines that are
+/// not entries, e.g. free text or entries of unknown subsections, are skipped.
+pub fn parse_relevant_file_links(body: &str) -> Vec<RelevantFileLink> {
+    let mut links = Vec::new();
+    let mut in_section = false;
+    // `None` within the section for `### Relevant File`, or a subsection that is not read
+    let mut subsection: Option<Option<QCRelationship>> = None;
+
+    for line in body.lines() {
+        let line = line.trim_end();
+
+        if line.starts_with("# ") || line.starts_with("## ") {
+            in_section = line == "## Relevant Files";
+            subsection = None;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some(heading) = line.strip_prefix("### ") {
+            subsection = match heading.trim() {
+                "Previous QC" => Some(Some(QCRelationship::PreviousQC)),
+                "Gating QC" => Some(Some(QCRelationship::GatingQC)),
+                "Relevant QC" => Some(Some(QCRelationship::RelevantQC)),
+                "Relevant File" => Some(None),
+                _ => None,
+            };
+            continue;
         }
+
+        let link = match subsection {
+            Some(Some(relationship)) => parse_issue_entry(line, relationship),
+            Some(None) => parse_file_entry(line),
+            None => None,
+        };
+        links.extend(link);
     }
 
-    blocking_qcs
+    links
+}
+
+fn parse_issue_entry(line: &str, relationship: QCRelationship) -> Option<RelevantFileLink> {
+    let capture = RELEVANT_ISSUE_ENTRY_REGEX.captures(line)?;
+    let Ok(issue_number) = capture[3].parse() else {
+        log::debug!("Skipping relevant file entry with an invalid issue number: {line}");
+        return None;
+    };
+
+    Some(RelevantFileLink::Issue {
+        file_name: normalize_repo_path(Path::new(capture[1].trim())),
+        url: capture[2].to_string(),
+        issue_number,
+        relationship,
+        description: capture.get(4).map(|d| d.as_str().to_string()),
+    })
+}
+
+fn parse_file_entry(line: &str) -> Option<RelevantFileLink> {
+    let capture = RELEVANT_FILE_ENTRY_REGEX.captures(line)?;
+
+    Some(RelevantFileLink::File {
+        file_name: normalize_repo_path(Path::new(capture[1].trim())),
+        commit: capture.get(2).map(|c| c.as_str().to_string()),
+        file_url: capture.get(3).map(|u| u.as_str().to_string()),
+        justification: capture.get(4).map(|j| j.as_str().to_string()),
+    })
+}
+
+/// Parse the files of the "## Relevant Files" section of the issue body with their
+/// justifications
+///
+/// Entries which cannot be parsed are skipped, as in [`parse_relevant_file_links`].
+pub fn parse_relevant_files(body: &str) -> Vec<IssueRelevantFile> {
+    parse_relevant_file_links(body)
+        .into_iter()
+        .map(IssueRelevantFile::from)
+        .collect()
+}
+
+/// Parse blocking QC issues from issue body
+///
+/// Reads the `### Gating QC` and `### Previous QC` entries of [`parse_relevant_file_links`],
+/// as `BlockingRelationship::GatingQC` and `BlockingRelationship::PreviousQC`.
+pub fn parse_blocking_qcs(body: &str) -> Vec<BlockingQC> {
+    let links = parse_relevant_file_links(body);
+
+    [
+        (QCRelationship::GatingQC, BlockingRelationship::GatingQC),
+        (QCRelationship::PreviousQC, BlockingRelationship::PreviousQC),
+    ]
+    .into_iter()
+    .flat_map(|(wanted, blocking)| {
+        links.iter().filter_map(move |link| match link {
+            RelevantFileLink::Issue {
+                file_name,
+                url,
+                issue_number,
+                relationship,
+                ..
+            } if *relationship == wanted => Some(BlockingQC {
+                issue_number: *issue_number,
+                file_name: file_name.clone(),
+                relationship: blocking.clone(),
+                url: url.clone(),
+            }),
+            _ => None,
+        })
+    })
+    .collect()
 }
 
 /// Determine the relationship type from a child's body by finding where the parent issue appears
@@ -537,6 +1063,17 @@ pub struct FileRenameEvent {
     pub commit: String,
 }
 
+impl fmt::Display for FileRenameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let commit = &self.commit[..self.commit.len().min(7)];
+        write!(
+            f,
+            "{} \u{2192} {} at commit {commit}",
+            self.old_path, self.new_path
+        )
+    }
+}
+
 /// Parse file rename events from the "## File History" section of an issue body.
 ///
 /// Each line has the form: `* \`old_path\` → \`new_path\` (commit: abc1234)`
@@ -695,6 +1232,7 @@ mod tests {
     use crate::git::{
         GitComment, GitCommit, GitCommitOps, GitFileOps, GitFileOpsError, GitHubReader,
     };
+    use crate::test_utils::{RENOTIFIED_THREAD_COMMITS, create_renotified_after_approval_thread};
     use octocrab::models::issues::Issue;
     use std::path::PathBuf;
     use std::str::FromStr;
@@ -767,6 +1305,11 @@ mod tests {
     struct SimpleMockGitInfo {
         commits: Vec<(ObjectId, String)>,
         comments: Vec<GitComment>,
+        /// Commits touching each file, every commit for files not listed
+        file_changes: std::collections::HashMap<PathBuf, std::collections::HashSet<String>>,
+        renames: Vec<FileRenameEvent>,
+        /// Number of times the commit history was walked
+        commit_walks: std::sync::atomic::AtomicUsize,
     }
 
     impl SimpleMockGitInfo {
@@ -774,9 +1317,25 @@ mod tests {
             Self {
                 commits: Vec::new(),
                 comments: Vec::new(),
+                file_changes: std::collections::HashMap::new(),
+                renames: Vec::new(),
+                commit_walks: std::sync::atomic::AtomicUsize::new(0),
             }
         }
 
+        fn with_file_changes(mut self, file: &str, commits: &[&str]) -> Self {
+            self.file_changes.insert(
+                PathBuf::from(file),
+                commits.iter().map(|c| c.to_string()).collect(),
+            );
+            self
+        }
+
+        fn with_renames(mut self, renames: Vec<FileRenameEvent>) -> Self {
+            self.renames = renames;
+            self
+        }
+
         fn with_commits(mut self, commits: Vec<(ObjectId, String)>) -> Self {
             self.commits = commits;
             self
@@ -794,6 +1353,8 @@ mod tests {
             _branch: &Option<String>,
             _stop_at: Option<ObjectId>,
         ) -> Result<Vec<GitCommit>, GitFileOpsError> {
+            self.commit_walks
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
             Ok(self
                 .commits
                 .iter()
@@ -811,12 +1372,31 @@ mod tests {
         fn file_touching_commits(
             &self,
             _branch: Option<String>,
-            _file: &std::path::Path,
+            file: &std::path::Path,
         ) -> Result<std::collections::HashSet<String>, GitFileOpsError> {
+            if let Some(changes) = self.file_changes.get(file) {
+                return Ok(changes.clone());
+            }
             // Return all commit hashes as "touching" since tests use a single file
             Ok(self.commits.iter().map(|(id, _)| id.to_string()).collect())
         }
 
+        fn file_renames(
+            &self,
+            _branch: Option<String>,
+            _file: &std::path::Path,
+            _since: Option<ObjectId>,
+        ) -> Result<Vec<FileRenameEvent>, GitFileOpsError> {
+            Ok(self.renames.clone())
+        }
+
+        fn files_by_last_commit(
+            &self,
+            _branch: &Option<String>,
+        ) -> Result<Vec<crate::FileLastCommit>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
         fn get_branches_containing_commit(
             &self,
             _commit: &ObjectId,
@@ -824,11 +1404,44 @@ mod tests {
             Ok(Vec::new())
         }
 
-        fn find_merged_into_branch(
-            &self,
-            _target_commit: &ObjectId,
-        ) -> Result<Option<String>, GitFileOpsError> {
-            Ok(None)
+        fn find_merged_into_branch(
+            &self,
+            _target_commit: &ObjectId,
+        ) -> Result<Option<String>, GitFileOpsError> {
+            Ok(None)
+        }
+
+        fn find_merge_commit(
+            &self,
+            _target_commit: &ObjectId,
+        ) -> Result<Option<ObjectId>, GitFileOpsError> {
+            Ok(None)
+        }
+
+        fn signature_status(
+            &self,
+            _commit: &ObjectId,
+            _keys: &SigningKeys,
+        ) -> Result<SignatureStatus, GitFileOpsError> {
+            Ok(SignatureStatus::Unsigned)
+        }
+
+        fn commit_time(
+            &self,
+            _commit: &ObjectId,
+        ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+            Ok(chrono::DateTime::UNIX_EPOCH)
+        }
+
+        fn commit_author(&self, _commit: &ObjectId) -> Result<crate::GitAuthor, GitFileOpsError> {
+            Ok(crate::GitAuthor {
+                name: "Test Author".to_string(),
+                email: "test@example.com".to_string(),
+            })
+        }
+
+        fn is_shallow(&self) -> bool {
+            false
         }
     }
 
@@ -851,6 +1464,18 @@ mod tests {
         fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
             Ok(Vec::new())
         }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<crate::git::PathAttributes, GitFileOpsError> {
+            Ok(Default::default())
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
     }
 
     impl GitHubReader for SimpleMockGitInfo {
@@ -885,6 +1510,21 @@ mod tests {
             })
         }
 
+        async fn get_user_permission(
+            &self,
+            _login: &str,
+        ) -> Result<crate::git::Permission, crate::git::GitHubApiError> {
+            Ok(crate::git::Permission::Write)
+        }
+
+        async fn get_team_members(
+            &self,
+            _org: &str,
+            _team_slug: &str,
+        ) -> Result<Vec<String>, crate::git::GitHubApiError> {
+            Ok(Vec::new())
+        }
+
         async fn get_labels(&self) -> Result<Vec<String>, crate::git::GitHubApiError> {
             Ok(Vec::new())
         }
@@ -977,6 +1617,169 @@ mod tests {
         assert_eq!(result.branch, "feature/new-feature");
     }
 
+    #[tokio::test]
+    async fn test_from_issue_normalizes_windows_title() {
+        let mut issue = load_issue("open_issue_with_notifications.json");
+        issue.title = r"src\main.rs".to_string();
+
+        let git_info = SimpleMockGitInfo::new().with_commits(create_test_commits());
+
+        let result = IssueThread::from_issue(&issue, None, &git_info)
+            .await
+            .unwrap();
+
+        assert_eq!(result.file, PathBuf::from("src/main.rs"));
+    }
+
+    #[test]
+    fn test_commit_index_walks_branch_once_per_milestone() {
+        const INITIAL: &str = "1111000000000000000000000000000000000000";
+        const NOTIFIED: &str = "2222000000000000000000000000000000000000";
+        const LATEST: &str = "3333000000000000000000000000000000000000";
+
+        let issues: Vec<Issue> = ["scripts/a.R", "scripts/b.R", "scripts/c.R"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, file)| {
+                crate::test_utils::create_test_issue(
+                    "owner",
+                    "repo",
+                    i as u64 + 1,
+                    file,
+                    &format!("## Metadata\ninitial qc commit: {INITIAL}\ngit branch: main\n"),
+                    Some(1),
+                    "open",
+                )
+            })
+            .collect();
+        // A short SHA is resolved through the index too
+        let comments = vec![GitComment {
+            body: format!(
+                "# QC Notification\n\ncurrent commit: {}\nprevious commit: {INITIAL}",
+                &NOTIFIED[..7]
+            ),
+            author_login: "author".to_string(),
+            created_at: chrono::Utc::now(),
+            html: None,
+        }];
+        let commits = [
+            (LATEST, "Latest"),
+            (NOTIFIED, "Notified"),
+            (INITIAL, "Initial"),
+        ]
+        .into_iter()
+        .map(|(hash, message)| (ObjectId::from_str(hash).unwrap(), message.to_string()))
+        .collect();
+        let git_info = SimpleMockGitInfo::new().with_commits(commits);
+        let walks = || {
+            git_info
+                .commit_walks
+                .swap(0, std::sync::atomic::Ordering::SeqCst)
+        };
+
+        let unindexed: Vec<IssueThread> = issues
+            .iter()
+            .map(|issue| IssueThread::from_issue_comments(issue, &comments, &git_info, None))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(walks(), 3);
+
+        let index = CommitIndex::new();
+        let indexed: Vec<IssueThread> = issues
+            .iter()
+            .map(|issue| {
+                IssueThread::from_issue_comments_with_index(
+                    issue, &comments, &git_info, None, &index,
+                )
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(walks(), 1);
+        assert_eq!(indexed, unindexed);
+        assert!(
+            indexed[0].commits[1]
+                .statuses
+                .contains(&CommitStatus::Notification)
+        );
+
+        let snapshot = crate::MilestoneSnapshot {
+            milestones: Vec::new(),
+            issues: issues
+                .into_iter()
+                .map(|issue| crate::IssueSnapshot {
+                    milestone: "v1.0".to_string(),
+                    issue,
+                    comments: comments.clone(),
+                    events: Vec::new(),
+                })
+                .collect(),
+        };
+        assert_eq!(snapshot.threads(&git_info, None).unwrap().len(), 3);
+        assert_eq!(walks(), 1);
+    }
+
+    #[test]
+    fn test_from_issue_comments_follows_renames() {
+        const INITIAL: &str = "aaaa000000000000000000000000000000000001";
+        const UNRELATED: &str = "aaaa000000000000000000000000000000000002";
+        const RENAME: &str = "aaaa000000000000000000000000000000000003";
+        const EDIT: &str = "aaaa000000000000000000000000000000000004";
+
+        let issue = crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            1,
+            "scripts/run.R",
+            &format!("## Metadata\ninitial qc commit: {INITIAL}\ngit branch: main\n"),
+            Some(1),
+            "open",
+        );
+        // newest first
+        let commits = [
+            (EDIT, "Tweak final run"),
+            (RENAME, "Rename run script"),
+            (UNRELATED, "Update README"),
+            (INITIAL, "Add run script"),
+        ]
+        .into_iter()
+        .map(|(hash, message)| (ObjectId::from_str(hash).unwrap(), message.to_string()))
+        .collect();
+        let git_info = SimpleMockGitInfo::new()
+            .with_commits(commits)
+            .with_file_changes("scripts/run.R", &[INITIAL, RENAME])
+            .with_file_changes("scripts/run_final.R", &[RENAME, EDIT])
+            .with_renames(vec![FileRenameEvent {
+                old_path: "scripts/run.R".to_string(),
+                new_path: "scripts/run_final.R".to_string(),
+                commit: RENAME.to_string(),
+            }]);
+
+        let thread = IssueThread::from_issue_comments(&issue, &[], &git_info, None).unwrap();
+
+        let oid = |hash: &str| ObjectId::from_str(hash).unwrap();
+        assert_eq!(
+            thread.file_commits(),
+            vec![&oid(EDIT), &oid(RENAME), &oid(INITIAL)]
+        );
+        assert_eq!(
+            thread.file_paths(),
+            vec![
+                PathBuf::from("scripts/run.R"),
+                PathBuf::from("scripts/run_final.R")
+            ]
+        );
+        assert_eq!(thread.current_file(), PathBuf::from("scripts/run_final.R"));
+        assert_eq!(
+            thread.renames_between(&oid(INITIAL), &oid(EDIT)),
+            thread.renames
+        );
+        assert!(thread.renames_between(&oid(RENAME), &oid(EDIT)).is_empty());
+        assert_eq!(
+            thread.renames[0].to_string(),
+            "scripts/run.R \u{2192} scripts/run_final.R at commit aaaa000"
+        );
+    }
+
     #[tokio::test]
     async fn test_from_issue_closed_with_approval() {
         // Comment sequence:
@@ -1107,6 +1910,12 @@ mod tests {
         assert_eq!(result.approved_commit(), None);
         assert_eq!(result.file, PathBuf::from("src/utils.rs"));
         assert_eq!(result.branch, "feature/utils-refactor");
+
+        // The unapproval predates recording its commits
+        let unapproval = result.unapproval.expect("unapproval to be parsed");
+        assert_eq!(unapproval.commit, None);
+        assert_eq!(unapproval.previously_approved, None);
+        assert!(!result.commits.iter().any(IssueCommit::is_unapproved));
     }
 
     #[tokio::test]
@@ -1198,6 +2007,115 @@ mod tests {
         assert_eq!(result.open, true);
     }
 
+    fn renotified_commit(index: usize) -> ObjectId {
+        ObjectId::from_str(RENOTIFIED_THREAD_COMMITS[index]).unwrap()
+    }
+
+    #[test]
+    fn test_latest_commit_prefers_notification_after_approval() {
+        let thread = create_renotified_after_approval_thread();
+
+        assert_eq!(thread.latest_commit().hash, renotified_commit(1));
+        assert_eq!(
+            thread.approved_commit().map(|c| c.hash),
+            Some(renotified_commit(2))
+        );
+        assert_eq!(
+            thread.latest_approved_or_activity().hash,
+            renotified_commit(2)
+        );
+    }
+
+    #[test]
+    fn test_latest_commit_approval_wins_tie_at_same_commit() {
+        let mut thread = create_renotified_after_approval_thread();
+        // Approve the re-notified commit; approval moves off the older commit
+        thread.commits[2].statuses.remove(&CommitStatus::Approved);
+        thread.commits[1].statuses.insert(CommitStatus::Approved);
+
+        let latest = thread.latest_commit();
+        assert_eq!(latest.hash, renotified_commit(1));
+        assert!(latest.statuses.contains(&CommitStatus::Approved));
+        assert_eq!(
+            thread.latest_approved_or_activity().hash,
+            renotified_commit(1)
+        );
+    }
+
+    #[test]
+    fn test_latest_approved_or_activity_without_approval() {
+        let mut thread = create_renotified_after_approval_thread();
+        thread.commits[2].statuses.remove(&CommitStatus::Approved);
+
+        assert_eq!(thread.approved_commit(), None);
+        assert_eq!(
+            thread.latest_approved_or_activity().hash,
+            thread.latest_commit().hash
+        );
+    }
+
+    #[test]
+    fn test_previous_qc_commit() {
+        let thread = create_renotified_after_approval_thread();
+
+        // A new file change diffs against the latest notification, not the approval
+        assert_eq!(
+            thread.previous_qc_commit(&renotified_commit(0)),
+            Some(renotified_commit(1))
+        );
+        assert_eq!(
+            thread.previous_qc_commit(&renotified_commit(1)),
+            Some(renotified_commit(2))
+        );
+        assert_eq!(
+            thread.previous_qc_commit(&renotified_commit(2)),
+            Some(renotified_commit(3))
+        );
+        // Nothing precedes the initial commit
+        assert_eq!(thread.previous_qc_commit(&renotified_commit(3)), None);
+    }
+
+    #[test]
+    fn test_qc_commit_signatures() {
+        let thread = create_renotified_after_approval_thread();
+        let mut git_info = crate::git::MockGitCommitOps::new();
+        git_info.expect_signature_status().returning(|commit, _| {
+            match commit.to_string().as_str() {
+                "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa" => Ok(SignatureStatus::Unsigned),
+                "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb" => Ok(SignatureStatus::Signed),
+                _ => Err(GitFileOpsError::ParseError(
+                    "verify-commit failed".to_string(),
+                )),
+            }
+        });
+
+        let signatures = thread.qc_commit_signatures(&git_info, &SigningKeys::default());
+        let rendered: Vec<String> = signatures.iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(
+            rendered,
+            vec![
+                "Initial: aaaaaaa unsigned ✘",
+                "Notified: ccccccc unverified ?",
+                "Approved: bbbbbbb signed ✔",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_previous_qc_commit_falls_back_to_preceding_commit() {
+        let mut thread = create_renotified_after_approval_thread();
+        for commit in thread.commits.iter_mut().skip(1) {
+            commit.statuses.clear();
+        }
+
+        assert_eq!(
+            thread.previous_qc_commit(&renotified_commit(0)),
+            Some(renotified_commit(1))
+        );
+        assert_eq!(thread.previous_qc_commit(&create_test_commits()[0].0), None);
+    }
+
     #[test]
     fn test_parse_commit_from_pattern_full_sha() {
         let body = "approved qc commit: abc123def456789012345678901234567890abcd";
@@ -1349,6 +2267,324 @@ mod tests {
         assert!(!def_statuses.contains(&CommitStatus::Notification)); // No notification status for this commit
     }
 
+    #[test]
+    fn test_parse_qc_comment_times() {
+        let time = |day: u32| {
+            use chrono::TimeZone;
+            Utc.with_ymd_and_hms(2026, 6, day, 12, 0, 0).unwrap()
+        };
+        let comment = |body: &str, day: u32| GitComment {
+            body: body.to_string(),
+            author_login: "test-user".to_string(),
+            created_at: time(day),
+            html: None,
+        };
+
+        let comments = vec![
+            comment(
+                "current commit: abc123def456789012345678901234567890abcd",
+                1,
+            ),
+            comment(
+                "approved qc commit: abc123def456789012345678901234567890abcd",
+                2,
+            ),
+            comment("# QC Un-Approval\n\nunapproved", 3),
+            comment(
+                "current commit: def456789abc012345678901234567890123abcd",
+                4,
+            ),
+            comment(
+                "approved qc commit: def456789abc012345678901234567890123abcd",
+                5,
+            ),
+        ];
+        assert_eq!(
+            parse_qc_comment_times(&comments),
+            QCCommentTimes {
+                approved_at: Some(time(5)),
+                approval_recorded_at: None,
+                notified_at: Some(time(4)),
+            }
+        );
+
+        // The approval no longer stands after an un-approval
+        assert_eq!(
+            parse_qc_comment_times(&comments[..3]),
+            QCCommentTimes {
+                approved_at: None,
+                approval_recorded_at: None,
+                notified_at: Some(time(1)),
+            }
+        );
+        assert_eq!(parse_qc_comment_times(&[]), QCCommentTimes::default());
+    }
+
+    #[test]
+    fn test_parse_unapproval_records_commits() {
+        use chrono::TimeZone;
+
+        let posted = Utc.with_ymd_and_hms(2024, 5, 6, 15, 30, 0).unwrap();
+        let comment = |body: &str| GitComment {
+            body: body.to_string(),
+            author_login: "reviewer".to_string(),
+            created_at: posted,
+            html: None,
+        };
+        let approval = comment("approved qc commit: abc123def456789012345678901234567890abcd");
+        let unapproval = comment(
+            "# QC Un-Approval\n\nInputs changed\n\n## Metadata\n* issue: #1\n* unapproval reason: Inputs changed\n* unapproved at commit: def456789abc012345678901234567890123abcd\n* previously approved commit: abc123def456789012345678901234567890abcd\n* effective date: 2024-05-02",
+        );
+        let comments = vec![approval.clone(), unapproval];
+
+        assert_eq!(
+            parse_unapproval(&comments),
+            Some(Unapproval {
+                at: Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap(),
+                by: "reviewer".to_string(),
+                commit: Some("def456789abc012345678901234567890123abcd".to_string()),
+                previously_approved: Some("abc123def456789012345678901234567890abcd".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_unapproval(&comments).unwrap().to_string(),
+            "2024-05-02 by reviewer at def4567 (previously approved abc123d)"
+        );
+
+        let statuses = parse_commits_from_comments(&comments);
+        assert_eq!(
+            statuses["def456789abc012345678901234567890123abcd"],
+            HashSet::from([CommitStatus::Unapproved])
+        );
+        assert!(statuses["abc123def456789012345678901234567890abcd"].is_empty());
+
+        // Approving again clears the unapproval
+        let reapproved = vec![comments[0].clone(), comments[1].clone(), approval];
+        assert_eq!(parse_unapproval(&reapproved), None);
+    }
+
+    #[test]
+    fn test_parse_unapproval_legacy_body() {
+        let comment = GitComment {
+            body: "# QC Un-Approval\n\nWrong commit\n\n## Metadata\n* issue: #1\n* unapproval reason: Wrong commit".to_string(),
+            author_login: "reviewer".to_string(),
+            created_at: chrono::DateTime::UNIX_EPOCH,
+            html: None,
+        };
+
+        let unapproval = parse_unapproval(std::slice::from_ref(&comment)).unwrap();
+        assert_eq!(unapproval.commit, None);
+        assert_eq!(unapproval.previously_approved, None);
+        assert_eq!(unapproval.to_string(), "1970-01-01 by reviewer");
+        assert!(parse_commits_from_comments(&[comment]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_approver() {
+        let comment = |body: &str, author: &str| GitComment {
+            body: body.to_string(),
+            author_login: author.to_string(),
+            created_at: Utc::now(),
+            html: None,
+        };
+
+        let comments = vec![
+            comment(
+                "current commit: abc123def456789012345678901234567890abcd",
+                "author",
+            ),
+            comment(
+                "approved qc commit: abc123def456789012345678901234567890abcd",
+                "first-reviewer",
+            ),
+            comment("# QC Un-Approval\n\nunapproved", "author"),
+            comment(
+                "approved qc commit: abc123def456789012345678901234567890abcd",
+                "second-reviewer",
+            ),
+        ];
+        assert_eq!(
+            parse_approver(&comments),
+            Some("second-reviewer".to_string())
+        );
+        assert_eq!(parse_approver(&comments[..3]), None);
+        assert_eq!(parse_approver(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_qc_comment_times_prefers_effective_date() {
+        use chrono::TimeZone;
+
+        let comment = |body: &str, created_at: DateTime<Utc>| GitComment {
+            body: body.to_string(),
+            author_login: "test-user".to_string(),
+            created_at,
+            html: None,
+        };
+        let recorded = Utc.with_ymd_and_hms(2024, 5, 6, 15, 30, 0).unwrap();
+        let backfilled = comment(
+            "# QC Approved\n\n## Metadata\n* approved qc commit: abc123def456789012345678901234567890abcd\n* effective date: 2024-05-02",
+            recorded,
+        );
+
+        assert_eq!(
+            parse_qc_comment_times(std::slice::from_ref(&backfilled)),
+            QCCommentTimes {
+                approved_at: Some(Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap()),
+                approval_recorded_at: Some(recorded),
+                notified_at: None,
+            }
+        );
+
+        // A later approval without an effective date replaces the backfilled one
+        let reapproved_at = Utc.with_ymd_and_hms(2024, 6, 1, 9, 0, 0).unwrap();
+        let comments = vec![
+            backfilled,
+            comment("# QC Un-Approval\n\nunapproved", reapproved_at),
+            comment(
+                "approved qc commit: def456789abc012345678901234567890123abcd",
+                reapproved_at,
+            ),
+        ];
+        assert_eq!(
+            parse_qc_comment_times(&comments),
+            QCCommentTimes {
+                approved_at: Some(reapproved_at),
+                approval_recorded_at: None,
+                notified_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_effective_date() {
+        assert_eq!(
+            parse_effective_date("* approved qc commit: abc123\n* effective date: 2024-05-02\n"),
+            NaiveDate::from_ymd_opt(2024, 5, 2)
+        );
+        assert_eq!(parse_effective_date("* approved qc commit: abc123"), None);
+        assert_eq!(parse_effective_date("* effective date: 2024-13-02"), None);
+    }
+
+    #[test]
+    fn test_qc_rounds_from_events() {
+        use QCEvent::*;
+
+        let cases: &[(&str, &[QCEvent], QCRounds)] = &[
+            (
+                "nothing posted",
+                &[],
+                QCRounds {
+                    current_round: 0,
+                    rounds_to_approval: None,
+                },
+            ),
+            (
+                "initial awaiting review",
+                &[Initial],
+                QCRounds {
+                    current_round: 1,
+                    rounds_to_approval: None,
+                },
+            ),
+            (
+                "approved in the first round",
+                &[Initial, Approval],
+                QCRounds {
+                    current_round: 1,
+                    rounds_to_approval: Some(1),
+                },
+            ),
+            (
+                "notification after a review starts a round",
+                &[
+                    Initial,
+                    Review,
+                    Notification,
+                    Review,
+                    Notification,
+                    Approval,
+                ],
+                QCRounds {
+                    current_round: 3,
+                    rounds_to_approval: Some(3),
+                },
+            ),
+            (
+                "notifications before any review are one round",
+                &[Initial, Notification, Notification, Review],
+                QCRounds {
+                    current_round: 1,
+                    rounds_to_approval: None,
+                },
+            ),
+            (
+                "review first starts round 1",
+                &[Review, Notification, Approval],
+                QCRounds {
+                    current_round: 2,
+                    rounds_to_approval: Some(2),
+                },
+            ),
+            (
+                "unapproval keeps counting",
+                &[Initial, Approval, Unapproval, Notification, Review],
+                QCRounds {
+                    current_round: 2,
+                    rounds_to_approval: None,
+                },
+            ),
+            (
+                "re-approval after unapproval",
+                &[Initial, Approval, Unapproval, Notification, Approval],
+                QCRounds {
+                    current_round: 2,
+                    rounds_to_approval: Some(2),
+                },
+            ),
+        ];
+
+        for (name, events, expected) in cases {
+            assert_eq!(
+                QCRounds::from_events(events.iter().copied()),
+                *expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_qc_events() {
+        let comment = |body: &str| GitComment {
+            body: body.to_string(),
+            author_login: "test-user".to_string(),
+            created_at: Utc::now(),
+            html: None,
+        };
+
+        let comments = vec![
+            comment(
+                "# QC Notification\n\ncurrent commit: abc123def456789012345678901234567890abcd",
+            ),
+            comment("# QC Review\n\ncomparing commit: abc123def456789012345678901234567890abcd"),
+            comment("a plain comment"),
+            comment(
+                "# QC Approval\n\napproved qc commit: abc123def456789012345678901234567890abcd",
+            ),
+            comment("# QC Un-Approval\n\nunapproved"),
+        ];
+
+        assert_eq!(
+            parse_qc_events(&comments),
+            vec![
+                QCEvent::Notification,
+                QCEvent::Review,
+                QCEvent::Approval,
+                QCEvent::Unapproval
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_commits_from_comments_with_review() {
         let comments = vec![
@@ -1597,6 +2833,114 @@ author: test"#;
         assert_eq!(result, None); // Should ignore links with spaces in text
     }
 
+    #[test]
+    fn test_parse_relevant_file_links() {
+        let body = r#"## Metadata
+* git branch: main
+
+## Relevant Files
+
+### Previous QC
+- [previous.R](https://github.com/owner/repo/issues/123) - Previous version of this file
+- [overflow.R](https://github.com/owner/repo/issues/99999999999999999999999)
+
+### Gating QC
+- [upstream.R](https://gitlab.example.com/group/project/-/issues/7) - Calls dplyr::mutate - twice
+
+### Relevant QC
+Free text is not an entry
+- [related.R](https://github.com/owner/repo/issues/300)
+
+### Relevant File
+- **data/config.yaml** ([abc1234](https://github.com/owner/repo/blob/abc1234def/data/config.yaml)) - Inputs
+- **R/utils.R**
+
+### Unknown
+- [unknown.R](https://github.com/owner/repo/issues/1)
+
+# Code Review Checklist
+- [ ] **Inputs** - checked"#;
+
+        assert_eq!(
+            parse_relevant_file_links(body),
+            vec![
+                RelevantFileLink::Issue {
+                    file_name: PathBuf::from("previous.R"),
+                    url: "https://github.com/owner/repo/issues/123".to_string(),
+                    issue_number: 123,
+                    relationship: QCRelationship::PreviousQC,
+                    description: Some("Previous version of this file".to_string()),
+                },
+                RelevantFileLink::Issue {
+                    file_name: PathBuf::from("upstream.R"),
+                    url: "https://gitlab.example.com/group/project/-/issues/7".to_string(),
+                    issue_number: 7,
+                    relationship: QCRelationship::GatingQC,
+                    description: Some("Calls dplyr::mutate - twice".to_string()),
+                },
+                RelevantFileLink::Issue {
+                    file_name: PathBuf::from("related.R"),
+                    url: "https://github.com/owner/repo/issues/300".to_string(),
+                    issue_number: 300,
+                    relationship: QCRelationship::RelevantQC,
+                    description: None,
+                },
+                RelevantFileLink::File {
+                    file_name: PathBuf::from("data/config.yaml"),
+                    commit: Some("abc1234".to_string()),
+                    file_url: Some(
+                        "https://github.com/owner/repo/blob/abc1234def/data/config.yaml"
+                            .to_string()
+                    ),
+                    justification: Some("Inputs".to_string()),
+                },
+                RelevantFileLink::File {
+                    file_name: PathBuf::from("R/utils.R"),
+                    commit: None,
+                    file_url: None,
+                    justification: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_relevant_files_skips_malformed_entries() {
+        let body = r#"## Relevant Files
+
+### Relevant File
+- **data/config.yaml** - Inputs
+- **unterminated.R - missing closing bold
+- data/plain.csv - not bold
+- **R/utils.R** ([abc1234](https://github.com/owner/repo/blob/abc1234/R/utils.R)) - Helpers
+
+### Gating QC
+- [upstream.R](https://github.com/owner/repo/pull/7) - not an issue link
+- upstream.R - not a link
+
+# Code Review Checklist
+- **checklist.R** - outside the section"#;
+
+        let files = parse_relevant_files(body);
+
+        assert_eq!(
+            files,
+            vec![
+                IssueRelevantFile {
+                    file: PathBuf::from("data/config.yaml"),
+                    kind: "Relevant File".to_string(),
+                    justification: Some("Inputs".to_string()),
+                },
+                IssueRelevantFile {
+                    file: PathBuf::from("R/utils.R"),
+                    kind: "Relevant File".to_string(),
+                    justification: Some("Helpers".to_string()),
+                },
+            ]
+        );
+        assert_eq!(files[1].to_string(), "R/utils.R (Relevant File): Helpers");
+    }
+
     // Tests for parse_blocking_qcs
 
     #[test]
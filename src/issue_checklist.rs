@@ -0,0 +1,329 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use octocrab::models::issues::Issue;
+
+use crate::git::{GitHubApiError, GitHubWriter};
+use ghqctoolkit_core::{ChecklistItem, checklist_items, set_checklist_items};
+
+/// A checklist item given on the command line: its 1-based index, or a substring of its text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecklistItemRef {
+    Index(usize),
+    Text(String),
+}
+
+impl FromStr for ChecklistItemRef {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().parse::<usize>() {
+            Ok(index) => Self::Index(index),
+            Err(_) => Self::Text(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ChecklistItemRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{index}"),
+            Self::Text(text) => write!(f, "'{text}'"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChecklistUpdateError {
+    #[error("Issue #{0} has no checklist items")]
+    NoItems(u64),
+    #[error("Checklist item {index} is out of range: the issue has {count} items")]
+    OutOfRange { index: usize, count: usize },
+    #[error("No checklist item matches {0}")]
+    NotFound(String),
+    #[error("{text} matches checklist items {indices:?}; refer to the item by its index instead")]
+    Ambiguous { text: String, indices: Vec<usize> },
+    #[error("Checklist item {0} is both checked and unchecked")]
+    Conflict(usize),
+    #[error("GitHub API error: {0}")]
+    GitHubApiError(#[from] GitHubApiError),
+}
+
+/// Check and uncheck checklist items of an issue's body
+#[derive(Debug, Clone)]
+pub struct ChecklistUpdate {
+    pub issue: Issue,
+    pub items: Vec<ChecklistItem>,
+    /// Indices of the items to check
+    pub check: Vec<usize>,
+    /// Indices of the items to uncheck
+    pub uncheck: Vec<usize>,
+}
+
+impl ChecklistUpdate {
+    /// Resolve the items referenced by `check` and `uncheck` against the issue's body
+    pub fn new(
+        issue: Issue,
+        check: &[ChecklistItemRef],
+        uncheck: &[ChecklistItemRef],
+    ) -> Result<Self, ChecklistUpdateError> {
+        let items = issue_checklist_items(&issue)?;
+        let resolve = |refs: &[ChecklistItemRef]| {
+            refs.iter()
+                .map(|item_ref| resolve_item(&items, item_ref))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let check = resolve(check)?;
+        let uncheck = resolve(uncheck)?;
+        if let Some(index) = check.iter().find(|index| uncheck.contains(index)) {
+            return Err(ChecklistUpdateError::Conflict(*index));
+        }
+
+        Ok(Self {
+            issue,
+            items,
+            check,
+            uncheck,
+        })
+    }
+
+    /// Check exactly the items at `checked`, unchecking every other item
+    pub fn from_selection(issue: Issue, checked: &[usize]) -> Result<Self, ChecklistUpdateError> {
+        let items = issue_checklist_items(&issue)?;
+        let (check, uncheck) = items
+            .iter()
+            .map(|item| item.index)
+            .partition(|index| checked.contains(index));
+
+        Ok(Self {
+            issue,
+            items,
+            check,
+            uncheck,
+        })
+    }
+
+    /// Items whose state changes, with their new state
+    pub fn changes(&self) -> Vec<(&ChecklistItem, bool)> {
+        self.items
+            .iter()
+            .filter_map(|item| {
+                let checked = if self.check.contains(&item.index) {
+                    true
+                } else if self.uncheck.contains(&item.index) {
+                    false
+                } else {
+                    return None;
+                };
+                (item.checked != checked).then_some((item, checked))
+            })
+            .collect()
+    }
+
+    /// The issue's body with the changed markers flipped
+    pub fn updated_body(&self) -> String {
+        let changes: Vec<(usize, bool)> = self
+            .changes()
+            .into_iter()
+            .map(|(item, checked)| (item.line, checked))
+            .collect();
+        set_checklist_items(self.issue.body.as_deref().unwrap_or_default(), &changes)
+    }
+}
+
+/// Checklist items of the issue's body, failing when it has none
+pub fn issue_checklist_items(issue: &Issue) -> Result<Vec<ChecklistItem>, ChecklistUpdateError> {
+    let items = checklist_items(issue.body.as_deref());
+    if items.is_empty() {
+        return Err(ChecklistUpdateError::NoItems(issue.number));
+    }
+    Ok(items)
+}
+
+/// Index of the item `item_ref` refers to
+///
+/// Text is matched case-insensitively, preferring items whose whole text matches over items
+/// merely containing it, and must match a single item.
+fn resolve_item(
+    items: &[ChecklistItem],
+    item_ref: &ChecklistItemRef,
+) -> Result<usize, ChecklistUpdateError> {
+    let text = match item_ref {
+        ChecklistItemRef::Index(index) => {
+            return items
+                .iter()
+                .any(|item| item.index == *index)
+                .then_some(*index)
+                .ok_or(ChecklistUpdateError::OutOfRange {
+                    index: *index,
+                    count: items.len(),
+                });
+        }
+        ChecklistItemRef::Text(text) => text.to_lowercase(),
+    };
+
+    let matching = |exact: bool| -> Vec<usize> {
+        items
+            .iter()
+            .filter(|item| {
+                let item_text = item.text.to_lowercase();
+                if exact {
+                    item_text == text
+                } else {
+                    item_text.contains(&text)
+                }
+            })
+            .map(|item| item.index)
+            .collect()
+    };
+    let mut indices = matching(true);
+    if indices.is_empty() {
+        indices = matching(false);
+    }
+
+    match indices.as_slice() {
+        [] => Err(ChecklistUpdateError::NotFound(item_ref.to_string())),
+        [index] => Ok(*index),
+        _ => Err(ChecklistUpdateError::Ambiguous {
+            text: item_ref.to_string(),
+            indices,
+        }),
+    }
+}
+
+/// Write the updated checklist to the issue's body
+///
+/// Returns the items whose state changed; nothing is written when no item changes.
+pub async fn update_checklist(
+    update: &ChecklistUpdate,
+    git_info: &impl GitHubWriter,
+) -> Result<Vec<(ChecklistItem, bool)>, ChecklistUpdateError> {
+    let changes: Vec<(ChecklistItem, bool)> = update
+        .changes()
+        .into_iter()
+        .map(|(item, checked)| (item.clone(), checked))
+        .collect();
+    if !changes.is_empty() {
+        git_info.guard_issue(&update.issue)?;
+        git_info
+            .update_issue(update.issue.number, None, Some(update.updated_body()))
+            .await?;
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{MockGitHubWriter, RemoteMismatch};
+    use crate::test_utils::create_test_issue;
+
+    const BODY: &str = "## Metadata\n* initial commit: abc123\n\n# Code Review\n- [ ] Inputs validated\n- [x] Outputs checked\n```r\n- [ ] Inputs validated\n```\n\n## Output\n- [ ] Outputs checked\n  - [ ] Plots labelled\n";
+
+    fn issue() -> Issue {
+        create_test_issue("owner", "repo", 3, "src/analysis.R", BODY, Some(1), "open")
+    }
+
+    fn refs(refs: &[&str]) -> Vec<ChecklistItemRef> {
+        refs.iter().map(|r| r.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_fenced_items_are_not_toggled() {
+        let update = ChecklistUpdate::new(issue(), &refs(&["inputs validated"]), &[]).unwrap();
+
+        assert_eq!(update.items.len(), 4);
+        assert_eq!(update.check, vec![1]);
+        assert_eq!(
+            update.updated_body(),
+            BODY.replacen("- [ ] Inputs validated", "- [x] Inputs validated", 1)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_item_texts_are_ambiguous() {
+        let err = ChecklistUpdate::new(issue(), &refs(&["outputs checked"]), &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            ChecklistUpdateError::Ambiguous { ref indices, .. } if indices == &[2, 3]
+        ));
+
+        // Indices tell duplicates apart
+        let update = ChecklistUpdate::new(issue(), &refs(&["3"]), &refs(&["2"])).unwrap();
+        let changes: Vec<_> = update
+            .changes()
+            .into_iter()
+            .map(|(item, checked)| (item.index, checked))
+            .collect();
+        assert_eq!(changes, vec![(2, false), (3, true)]);
+    }
+
+    #[test]
+    fn test_out_of_range_and_unknown_items_are_rejected() {
+        assert!(matches!(
+            ChecklistUpdate::new(issue(), &refs(&["5"]), &[]),
+            Err(ChecklistUpdateError::OutOfRange { index: 5, count: 4 })
+        ));
+        assert!(matches!(
+            ChecklistUpdate::new(issue(), &[], &refs(&["0"])),
+            Err(ChecklistUpdateError::OutOfRange { index: 0, count: 4 })
+        ));
+        assert!(matches!(
+            ChecklistUpdate::new(issue(), &refs(&["missing"]), &[]),
+            Err(ChecklistUpdateError::NotFound(_))
+        ));
+        assert!(matches!(
+            ChecklistUpdate::new(issue(), &refs(&["plots"]), &refs(&["4"])),
+            Err(ChecklistUpdateError::Conflict(4))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_update_writes_body_only_when_items_change() {
+        let update = ChecklistUpdate::from_selection(issue(), &[2, 4]).unwrap();
+        let expected = update.updated_body();
+        let mut writer = MockGitHubWriter::new();
+        writer.expect_guard_issue().times(1).returning(|_| Ok(()));
+        writer
+            .expect_update_issue()
+            .withf(move |number, title, body| {
+                *number == 3 && title.is_none() && body.as_deref() == Some(expected.as_str())
+            })
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+
+        let changes = update_checklist(&update, &writer).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0.text, "Plots labelled");
+
+        let unchanged = ChecklistUpdate::from_selection(issue(), &[2]).unwrap();
+        assert!(
+            update_checklist(&unchanged, &MockGitHubWriter::new())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_refuses_issue_of_another_repository() {
+        let update = ChecklistUpdate::from_selection(issue(), &[2, 4]).unwrap();
+        let mut writer = MockGitHubWriter::new();
+        writer.expect_guard_issue().times(1).returning(|issue| {
+            Err(RemoteMismatch {
+                issue_number: issue.number,
+                issue_repository: "github.com/owner/repo".to_string(),
+                remote_repository: "github.com/other-org/repo".to_string(),
+            }
+            .into())
+        });
+
+        // No expectation for `update_issue`: writing the body fails the test
+        let err = update_checklist(&update, &writer).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ChecklistUpdateError::GitHubApiError(GitHubApiError::RemoteMismatch(_))
+        ));
+    }
+}
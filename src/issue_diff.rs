@@ -0,0 +1,255 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use gix::ObjectId;
+
+use crate::diff_utils;
+use crate::git::{GitFileOps, GitFileOpsError};
+
+/// Version of the file a diff is taken to
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffTarget {
+    Commit(ObjectId),
+    /// Uncommitted file in the working tree of the repository at this path
+    WorkingTree(PathBuf),
+}
+
+impl fmt::Display for DiffTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffTarget::Commit(commit) => write!(f, "{}", &commit.to_string()[..7]),
+            DiffTarget::WorkingTree(_) => write!(f, "the working tree"),
+        }
+    }
+}
+
+/// Diff of a QCed file between two of its versions, for inspection without posting a comment
+#[derive(Debug, Clone)]
+pub struct IssueDiff {
+    pub file: PathBuf,
+    pub from: ObjectId,
+    pub to: DiffTarget,
+}
+
+impl IssueDiff {
+    /// Markdown diff of the file, as it appears in QC comments
+    ///
+    /// Excel files are diffed sheet by sheet. Fails when both versions are identical.
+    pub fn render(&self, git_info: &impl GitFileOps) -> Result<String, IssueDiffError> {
+        let (from_bytes, to_bytes) = self.changed_versions(git_info)?;
+        diff_utils::file_diff(from_bytes, to_bytes, &self.file)
+            .ok_or_else(|| IssueDiffError::NoDiff(self.file.clone()))
+    }
+
+    /// Write the diff to `path`
+    ///
+    /// An Excel file written to an `.xlsx` path gets the highlighted diff workbook of
+    /// [`create_excel_diff`](diff_utils::create_excel_diff), any other the markdown diff of
+    /// [`render`](Self::render).
+    pub fn write(&self, git_info: &impl GitFileOps, path: &Path) -> Result<(), IssueDiffError> {
+        let is_workbook = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx"));
+        let content = if diff_utils::is_excel_file(&self.file) && is_workbook {
+            let (from_bytes, to_bytes) = self.changed_versions(git_info)?;
+            diff_utils::create_excel_diff(&from_bytes, &to_bytes)?
+        } else {
+            self.render(git_info)?.into_bytes()
+        };
+
+        std::fs::write(path, content).map_err(|e| IssueDiffError::Write(path.to_path_buf(), e))
+    }
+
+    /// Both versions of the file, failing when they are identical
+    fn changed_versions(
+        &self,
+        git_info: &impl GitFileOps,
+    ) -> Result<(Vec<u8>, Vec<u8>), IssueDiffError> {
+        let (from_bytes, to_bytes) = self.file_versions(git_info)?;
+        if from_bytes == to_bytes {
+            return Err(IssueDiffError::Unchanged {
+                file: self.file.clone(),
+                from: self.from,
+                to: self.to.clone(),
+            });
+        }
+        Ok((from_bytes, to_bytes))
+    }
+
+    fn file_versions(
+        &self,
+        git_info: &impl GitFileOps,
+    ) -> Result<(Vec<u8>, Vec<u8>), IssueDiffError> {
+        let at_commit = |commit: &ObjectId| {
+            git_info
+                .file_bytes_at_commit(&self.file, commit)
+                .map_err(|e| IssueDiffError::ReadCommit {
+                    file: self.file.clone(),
+                    commit: *commit,
+                    source: e,
+                })
+        };
+
+        let from_bytes = at_commit(&self.from)?;
+        let to_bytes = match &self.to {
+            DiffTarget::Commit(commit) => at_commit(commit)?,
+            DiffTarget::WorkingTree(dir) => read_working_tree(dir, &self.file)?,
+        };
+        Ok((from_bytes, to_bytes))
+    }
+}
+
+fn read_working_tree(dir: &Path, file: &Path) -> Result<Vec<u8>, IssueDiffError> {
+    std::fs::read(dir.join(file))
+        .map_err(|e| IssueDiffError::ReadWorkingTree(file.to_path_buf(), e))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IssueDiffError {
+    #[error("Could not read {file} at commit {commit}: {source}")]
+    ReadCommit {
+        file: PathBuf,
+        commit: ObjectId,
+        source: GitFileOpsError,
+    },
+    #[error("Could not read {0} in the working tree: {1}")]
+    ReadWorkingTree(PathBuf, std::io::Error),
+    #[error("{file} is identical at {} and {to}", &from.to_string()[..7])]
+    Unchanged {
+        file: PathBuf,
+        from: ObjectId,
+        to: DiffTarget,
+    },
+    #[error("Could not generate a diff for {0}")]
+    NoDiff(PathBuf),
+    #[error(transparent)]
+    Workbook(#[from] diff_utils::ExcelDiffError),
+    #[error("Failed to write diff to {0}: {1}")]
+    Write(PathBuf, std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::MockGitFileOps;
+    use std::str::FromStr;
+
+    const FROM: &str = "0a1b2c3d4e5f60718293a4b5c6d7e8f901234567";
+    const TO: &str = "9f8e7d6c5b4a39281706f5e4d3c2b1a098765432";
+
+    fn git_info(from_bytes: Vec<u8>, to_bytes: Vec<u8>) -> MockGitFileOps {
+        let mut git_info = MockGitFileOps::new();
+        git_info
+            .expect_file_bytes_at_commit()
+            .returning(move |_, commit| {
+                Ok(if commit.to_string() == FROM {
+                    from_bytes.clone()
+                } else {
+                    to_bytes.clone()
+                })
+            });
+        git_info
+    }
+
+    fn diff(file: &str) -> IssueDiff {
+        IssueDiff {
+            file: PathBuf::from(file),
+            from: ObjectId::from_str(FROM).unwrap(),
+            to: DiffTarget::Commit(ObjectId::from_str(TO).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_text_diff() {
+        let git_info = git_info(b"a <- 1\nb <- 2\n".to_vec(), b"a <- 1\nb <- 3\n".to_vec());
+
+        let rendered = diff("src/analysis.R").render(&git_info).unwrap();
+        assert_eq!(
+            rendered,
+            "```diff\n\
+            @@ previous script: lines 1-3 @@\n\
+            @@  current script: lines 1-3 @@\n  \
+            1 a <- 1\n\
+            - 2 b <- 2\n\
+            + 2 b <- 3\n  \
+            3 \n\
+            ```"
+        );
+    }
+
+    #[test]
+    fn test_excel_diff() {
+        let git_info = git_info(
+            std::fs::read("src/tests/data/summary.xlsx").unwrap(),
+            std::fs::read("src/tests/data/summary_v2.xlsx").unwrap(),
+        );
+
+        let rendered = diff("data/summary.xlsx").render(&git_info).unwrap();
+        assert!(rendered.contains("@@ Sheet"), "{rendered}");
+    }
+
+    #[test]
+    fn test_excel_diff_written_as_workbook() {
+        use calamine::{Reader, open_workbook_auto};
+
+        let to_bytes = std::fs::read("src/tests/data/summary_v2.xlsx").unwrap();
+        let git_info = git_info(
+            std::fs::read("src/tests/data/summary.xlsx").unwrap(),
+            to_bytes.clone(),
+        );
+        let dir = tempfile::TempDir::new().unwrap();
+        let issue_diff = diff("data/summary.xlsx");
+
+        let workbook_path = dir.path().join("out.xlsx");
+        issue_diff.write(&git_info, &workbook_path).unwrap();
+        let mut workbook = open_workbook_auto(&workbook_path).unwrap();
+        let to_workbook =
+            calamine::open_workbook_auto_from_rs(std::io::Cursor::new(to_bytes)).unwrap();
+        assert_eq!(workbook.sheet_names(), to_workbook.sheet_names());
+        let sheet = &workbook.sheet_names()[0];
+        assert!(
+            workbook
+                .worksheet_range(sheet)
+                .unwrap()
+                .used_cells()
+                .any(|(_, _, cell)| cell.to_string().contains(" → "))
+        );
+
+        // Other extensions get the markdown diff
+        let markdown_path = dir.path().join("out.md");
+        issue_diff.write(&git_info, &markdown_path).unwrap();
+        let markdown = std::fs::read_to_string(&markdown_path).unwrap();
+        assert!(markdown.contains("@@ Sheet"), "{markdown}");
+    }
+
+    #[test]
+    fn test_identical_versions() {
+        let git_info = git_info(b"a <- 1\n".to_vec(), b"a <- 1\n".to_vec());
+
+        let err = diff("src/analysis.R").render(&git_info).unwrap_err();
+        assert!(matches!(err, IssueDiffError::Unchanged { .. }));
+        assert_eq!(
+            err.to_string(),
+            "src/analysis.R is identical at 0a1b2c3 and 9f8e7d6"
+        );
+    }
+
+    #[test]
+    fn test_working_tree_diff() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/analysis.R"), "a <- 1\n").unwrap();
+        let git_info = git_info(b"a <- 1\n".to_vec(), Vec::new());
+
+        let mut issue_diff = diff("src/analysis.R");
+        issue_diff.to = DiffTarget::WorkingTree(dir.path().to_path_buf());
+        let err = issue_diff.render(&git_info).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "src/analysis.R is identical at 0a1b2c3 and the working tree"
+        );
+
+        std::fs::write(dir.path().join("src/analysis.R"), "a <- 2\n").unwrap();
+        assert!(issue_diff.render(&git_info).unwrap().contains("+ 1 a <- 2"));
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use ghqctoolkit_core::{parse_branch_from_body, parse_commit_from_pattern};
+use tera::{Context, Tera};
+
+/// File name of the issue body template in the configuration repository
+pub const ISSUE_TEMPLATE_FILE: &str = "issue_template.md";
+
+const TEMPLATE_NAME: &str = "issue_template.md";
+
+/// Custom layout of new issue bodies, a Tera template from the configuration repository
+///
+/// The rendered body must keep the `initial qc commit:` and `git branch:` markers, which are
+/// read back from every issue to find its commits.
+#[derive(Debug, Clone)]
+pub struct IssueTemplate {
+    path: PathBuf,
+    tera: Tera,
+}
+
+impl IssueTemplate {
+    /// Read and parse the template at `path`
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, IssueTemplateError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| IssueTemplateError::Read(path.to_path_buf(), e))?;
+        Self::from_str(path, &content)
+    }
+
+    fn from_str(path: &Path, content: &str) -> Result<Self, IssueTemplateError> {
+        let mut tera = Tera::default();
+        tera.add_raw_template(TEMPLATE_NAME, content)
+            .map_err(|e| IssueTemplateError::Parse(path.to_path_buf(), error_chain(&e)))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            tera,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Render the body of an issue on `commit` and `branch`, checking both can be read back
+    pub(crate) fn render(
+        &self,
+        context: &Context,
+        commit: &str,
+        branch: &str,
+    ) -> Result<String, IssueTemplateError> {
+        let body = self
+            .tera
+            .render(TEMPLATE_NAME, context)
+            .map_err(|e| IssueTemplateError::Render(self.path.clone(), error_chain(&e)))?;
+
+        if parse_commit_from_pattern(&body, "initial qc commit: ") != Some(commit) {
+            return Err(self.missing_marker("initial qc commit:"));
+        }
+        if parse_branch_from_body(&body).as_deref() != Some(branch) {
+            return Err(self.missing_marker("git branch:"));
+        }
+        Ok(body)
+    }
+
+    fn missing_marker(&self, marker: &'static str) -> IssueTemplateError {
+        IssueTemplateError::MissingMarker {
+            path: self.path.clone(),
+            marker,
+        }
+    }
+}
+
+/// Tera nests the cause of parse and render failures, e.g. the undefined variable
+pub(crate) fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IssueTemplateError {
+    #[error("Could not read issue template {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("Issue template {0} does not parse: {1}")]
+    Parse(PathBuf, String),
+    #[error("Could not render issue template {0}: {1}")]
+    Render(PathBuf, String),
+    #[error(
+        "Issue template {path} must render the `{marker}` line of the built-in metadata, e.g. with {{{{ metadata }}}}"
+    )]
+    MissingMarker { path: PathBuf, marker: &'static str },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(content: &str) -> IssueTemplate {
+        IssueTemplate::from_str(Path::new("issue_template.md"), content).unwrap()
+    }
+
+    fn context() -> Context {
+        let mut context = Context::new();
+        context.insert("initial_commit", "abc1234");
+        context.insert("branch", "qc/model");
+        context
+    }
+
+    #[test]
+    fn test_render_checks_markers() {
+        let complete = template(
+            "study: 101\n* initial qc commit: {{ initial_commit }}\n* git branch: {{ branch }}",
+        );
+        assert!(complete.render(&context(), "abc1234", "qc/model").is_ok());
+
+        let no_branch = template("study: 101\n* initial qc commit: {{ initial_commit }}");
+        assert!(matches!(
+            no_branch.render(&context(), "abc1234", "qc/model"),
+            Err(IssueTemplateError::MissingMarker {
+                marker: "git branch:",
+                ..
+            })
+        ));
+
+        let no_commit = template("* git branch: {{ branch }}");
+        assert!(matches!(
+            no_commit.render(&context(), "abc1234", "qc/model"),
+            Err(IssueTemplateError::MissingMarker {
+                marker: "initial qc commit:",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let err = IssueTemplate::from_str(Path::new("issue_template.md"), "{% if %}").unwrap_err();
+        assert!(matches!(err, IssueTemplateError::Parse(..)));
+
+        let err = template("{{ undefined_variable }}")
+            .render(&context(), "abc1234", "qc/model")
+            .unwrap_err();
+        assert!(err.to_string().contains("undefined_variable"), "{err}");
+    }
+}
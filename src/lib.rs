@@ -1,26 +1,43 @@
 mod approve;
 mod archive;
+mod audit;
 mod auth;
 mod body_splitter;
 mod cache;
+mod codeowners;
 mod comment;
 mod comment_system;
 mod configuration;
+mod coverage;
 mod create;
+mod data_summary;
 mod diff_utils;
+mod environment;
 mod git;
 mod issue;
+mod issue_checklist;
+mod issue_diff;
+mod issue_template;
+mod milestone_snapshot;
+mod obsolete;
 mod qc_status;
+mod reassign;
 mod record;
 mod relevant_files;
 mod review;
+mod time_log;
+mod usage;
 pub mod utils;
 
+#[cfg(test)]
+mod core_parity;
 #[cfg(test)]
 pub mod test_utils;
 
 #[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "cli")]
+mod client;
 
 #[cfg(feature = "api")]
 pub mod api;
@@ -29,48 +46,115 @@ pub mod api;
 pub mod ui;
 
 pub use approve::{
-    ApprovalError, ApprovalResult, BlockingQCCheckResult, ImpactNode, ImpactedIssues, QCApprove,
-    QCUnapprove, UnapprovalResult, approve_with_validation, get_unapproved_blocking_qcs,
-    unapprove_with_impact,
+    ApprovalError, ApprovalResult, BlockingQCCheckResult, ImpactNode, ImpactedIssues,
+    IncompleteChecklist, QCApprove, QCScope, QCUnapprove, UnapprovalResult,
+    approve_with_validation, check_approval_blocking_qcs, check_checklist_completion,
+    get_unapproved_blocking_qcs, unapprove_with_impact, validate_effective_date,
+    verify_signed_approval,
+};
+pub use archive::{
+    ApprovalWindow, ArchiveError, ArchiveFile, ArchiveManifest, ArchiveManifestFile,
+    ArchiveManifestGeneratedFile, ArchiveMetadata, ArchiveQC, ArchiveRepository, ArchiveSizeLimits,
+    ChecksumMismatch, GeneratedArchiveFile, VerificationReport, archive, generate_archive_name,
+    verify_archive,
+};
+pub use audit::{
+    AuditEntry, AuditLog, AuditOperation, AuditedGit, audit_log_path, read_audit_entries,
 };
-pub use archive::{ArchiveError, ArchiveFile, ArchiveMetadata, ArchiveQC, archive};
 pub use auth::{
     AuthStore, AuthStoreError, AuthToken, canonicalize_base_url, extract_host_from_base_url,
     token_page_url, validate_github_token,
 };
 pub use cache::DiskCache;
 pub use cache::{
-    CachedCommit, CachedEvents, FileChangeRecord, cache_root, create_labels_if_needed,
-    get_issue_comments, get_issue_events, get_repo_users,
+    CACHE_SCHEMA_VERSION, CacheError, CacheHealth, CacheImportSummary, CacheManifest,
+    CacheRootSource, CacheSnapshotError, CachedCommit, CachedEvents, CachedIssues,
+    FileChangeRecord, ListingInvalidation, UserDirectory, cache_or_warn, cache_root,
+    create_labels_if_needed, get_all_issues_cached, get_issue_comments, get_issue_events,
+    get_milestone_issues_cached, get_milestones_cached, get_repo_users, get_user_permissions,
+    resolve_cache_root,
+};
+#[cfg(feature = "cli")]
+pub use client::{
+    ApproveRequest, ArchiveRequest, CommentRequest, CreateIssueRequest, GhqcClient, IssueStatus,
+};
+pub use codeowners::{
+    CODEOWNERS_PATHS, CodeOwner, CodeOwners, OwnerAssigneeError, OwnerQCPolicy,
+    check_owner_assignees, owner_assignees, suggested_qcers,
 };
 pub use comment::QCComment;
-pub use comment_system::CommentBody;
+pub use comment_system::{CommentBody, comment_draft};
 pub use configuration::{
-    Checklist, Configuration, ConfigurationOptions, configuration_status, determine_config_dir,
-    setup_configuration,
+    Checklist, Configuration, ConfigurationOptions, ConfigurationValidation, ConfigurationWarning,
+    FileValidation, FindingSeverity, InlineChecklistError, InlineChecklistProblem,
+    ValidationFinding, configuration_status, configured_cache_directory, configured_diff_settings,
+    configured_usage_stats, determine_config_dir, setup_configuration,
 };
-pub use create::{QCEntry, QCIssue, QCRelationship, RelevantFileEntry, batch_post_qc_entries};
+pub use coverage::{CoveredFile, QCCoverage, files_in_scope, qc_coverage, tracked_files};
+pub use create::{
+    AssigneePermissionError, CreateResult, InsufficientAssignee, IssuePreview, QCEntry, QCIssue,
+    QCRelationship, RelevantFileEntry, batch_post_qc_entries, check_assignee_permissions,
+};
+pub use data_summary::{
+    DataFormat, DataSummary, DataSummaryError, TableShape, data_summary_at_commit,
+    data_summary_change, summarize,
+};
+pub use diff_utils::{
+    DEFAULT_EXCEL_DIFF_DIR, DiffSettings, ExcelDiffError, NoDiff, diff_settings, set_diff_settings,
+};
+pub use environment::{EnvironmentFile, environment_files};
 pub use git::{
-    AuthError, AuthSourceKind, AuthSources, FileStashOutcome, GitAuthor, GitCli, GitCliError,
-    GitCommand, GitComment, GitCommit, GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers,
-    GitHubApiError, GitHubReader, GitHubWriter, GitInfo, GitInfoError, GitProvider, GitRepository,
-    GitRepositoryError, GitState, GitStatus, GitStatusError, GitStatusOps, RepoUser,
-    detect_renames, find_commits, find_or_cache_file_changes, get_commits_robust, get_git_status,
-    head_commit_hash,
+    AuthError, AuthSourceKind, AuthSources, BehindFileReport, BranchCommits, BranchState,
+    CommitIndex, CommitRetrievalInfo, CommitSource, FileGitState, FileLastCommit, FileReader,
+    FileStashOutcome, Forge, GitAuthor, GitCli, GitCliError, GitCommand, GitComment, GitCommit,
+    GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError, GitHubReader,
+    GitHubWriter, GitInfo, GitInfoError, GitLabError, GitProvider, GitRepository,
+    GitRepositoryError, GitState, GitStatus, GitStatusError, GitStatusOps, MilestoneState,
+    MilestoneUpdate, PathAttributes, Permission, RemoteMismatch, RepoUser, RequestError,
+    RequestTarget, RetrievalAttempt, RetrievalFailure, SignatureStatus, SigningKeys, TlsSettings,
+    behind_file_report, branch_exists, check_issue_remote, commit_signature_status, detect_renames,
+    find_commits, find_or_cache_file_changes, get_commits_robust, get_git_status,
+    guard_comment_body, guard_issue_change, head_commit_hash, infer_branch_state,
+    issue_url_matches_remote,
 };
 pub use issue::{
     BlockingQC, BlockingRelationship, CommitStatus, FileRenameEvent, IssueCommit, IssueError,
-    IssueThread, determine_relationship_from_body, file_history_section, find_checklist_start,
-    parse_blocking_qcs, parse_branch_from_body, parse_file_history, splice_file_history,
+    IssueRelevantFile, IssueThread, Obsoletion, QCCommitSignature, QCEvent, QCRounds,
+    RelevantFileLink, Unapproval, determine_relationship_from_body, file_history_section,
+    find_checklist_start, parse_blocking_qcs, parse_branch_from_body, parse_effective_date,
+    parse_file_history, parse_relevant_file_links, parse_relevant_files, splice_file_history,
 };
+pub use issue_checklist::{
+    ChecklistItemRef, ChecklistUpdate, ChecklistUpdateError, issue_checklist_items,
+    update_checklist,
+};
+pub use issue_diff::{DiffTarget, IssueDiff, IssueDiffError};
+pub use issue_template::{ISSUE_TEMPLATE_FILE, IssueTemplate, IssueTemplateError};
+pub use milestone_snapshot::{IssueSnapshot, MilestoneSnapshot, SnapshotThread};
+pub use obsolete::{ObsoleteError, QCObsolete, QCUnobsolete, obsolete_issue, unobsolete_issue};
 pub use qc_status::{
-    BlockingQCStatus, ChecklistSummary, QCStatus, QCStatusError, analyze_issue_checklists,
-    get_blocking_qc_status,
+    BlockingQCStatus, ChecklistItem, ChecklistSummary, IssueStatusReport, MilestoneStatusReport,
+    NamedChecklistSummary, QCStatus, QCStatusError, StaleApproval, analyze_issue_checklists,
+    format_section_summaries, get_blocking_qc_status, milestone_status_report, status_git_state,
+    unchecked_checklist_items,
 };
+pub use reassign::{QCReassign, ReassignError, current_assignees, reassign_issue};
 pub use record::{
-    BUILTIN_TEMPLATE, ContextPosition, HttpDownloader, IssueInformation, QCContext, UreqDownloader,
-    create_staging_dir, fetch_milestone_issues, get_milestone_issue_information, load_template,
-    record, render,
+    AttachmentInfo, BUILTIN_TEMPLATE, CommitSignatureInfo, ContextPosition, Diagnostics,
+    HttpDownloader, ISSUE_DETAIL_PARTIAL, ImageCache, IssueInformation, MilestoneRecord, QCContext,
+    RecordFormat, RecordIndexEntry, RecordOptions, RecordRelevantFile, StrictViolation,
+    StrictViolations, UreqDownloader, create_staging_dir, get_issue_information,
+    get_milestone_issue_information, issue_preview_file_name, issue_record_preview, load_template,
+    milestone_record_file_name, pdf_attachment_contexts, record, record_by_milestone, record_date,
+    record_index, render, render_each,
 };
 pub use relevant_files::{RelevantFile, RelevantFileClass};
 pub use review::{QCReview, ReviewStashResult, ReviewStashStatus, stash_review_file};
+pub use time_log::{
+    IssueTime, QC_TIME_MARKER, QCTimeLog, TimeEntry, TimeLogError, TimeSummary, format_hours,
+    parse_duration, parse_logged_minutes, time_entries, time_report, time_report_csv,
+};
+pub use usage::{
+    CommandStats, UsageEvent, UsageLog, UsageStats, count_writes, read_usage_events,
+    usage_log_path, usage_stats, usage_stats_csv,
+};
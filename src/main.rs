@@ -1,29 +1,68 @@
 use anyhow::{Result, anyhow, bail};
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 use octocrab::models::Milestone;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
 
 use ghqctoolkit::AuthStore;
 use ghqctoolkit::cli::{
-    CacheCommands, FileCommitPair, FileCommitPairParser, IssueUrlArg, IssueUrlArgParser,
-    MilestoneSelectionFilter, RelevantFileArg, RelevantFileArgParser,
-    confirm_rename_noninteractive, find_issue, generate_archive_name, get_milestone_issue_threads,
-    gh_auth_login, gh_auth_logout, gh_auth_status, gh_auth_token, handle_cache,
-    interactive_milestone_status, interactive_rename, interactive_status, milestone_status,
-    prompt_archive, prompt_context_files, prompt_milestone_record, single_issue_status,
+    ArchiveResult, ChecklistItems, ChecklistUpdateResult, ConfigurationSetupResult,
+    ConfigurationStatusResult, FinalizeResult, OutputFormat, PostedComment, RecordResult,
+    SplitRecordResult, print_result, set_output_format,
+};
+use ghqctoolkit::cli::{
+    BatchCreateOptions, CANCELLED_EXIT_CODE, CacheCommands, ChecklistArg, CommentDraft,
+    CommitRange, CompleteCommands, CompletionShell, CopyPlanOptions, CreateOptions, FileCommitPair,
+    FileCommitPairParser, InquirePrompter, IssueDiffRange, IssueUrlArg, IssueUrlArgParser,
+    MilestoneFinalizeOptions, MilestoneRenameOptions, MilestoneSelectionFilter, PromptCancelled,
+    ReassignSettings, RelevantFileArg, RelevantFileArgParser, ReviewSettings, ReviewTargets,
+    TerminalGuard, checklist_candidates, confirm_rename_noninteractive, copy_milestone_plan,
+    create_issue_batch, dynamic_completions, expand_file_patterns, file_behind_report,
+    file_candidates, finalize_milestone, find_issue, find_time_log_issue, gh_auth_login,
+    gh_auth_logout, gh_auth_status, gh_auth_token, handle_cache, interactive_milestone_status,
+    interactive_rename, interactive_status, issue_time, milestone_archive_files,
+    milestone_candidates, milestone_coverage, milestone_status, milestone_time, page,
+    prompt_archive, prompt_context_files, prompt_milestone_record, rename_milestone,
+    single_issue_status,
+};
+use ghqctoolkit::cli::{
+    IssueListOptions, IssueSort, IssueStateFilter, QCStatusFilter, archive_record, close_milestone,
+    edit_milestone, find_milestone, format_issue_list_table, list_issues, optional_record,
+    prompt_milestone_in_state, reopen_milestone,
+};
+use ghqctoolkit::message;
+use ghqctoolkit::utils::{
+    IgnoreRules, StdEnvProvider, parse_date_time, parse_file_size, same_repo_path,
+};
+use ghqctoolkit::{
+    ApprovalWindow, ApproveRequest, ArchiveMetadata, CacheHealth, CommentBody, Configuration,
+    ConfigurationOptions, ContextPosition, Diagnostics, DiskCache, GitCommand, GitCommitOps,
+    GitHubApiError, GitHubReader, GitHubWriter, GitInfo, GitRepository, GitStatusOps, IssueDiff,
+    IssueSnapshot, ListingInvalidation, MilestoneSnapshot, MilestoneState, MilestoneUpdate, NoDiff,
+    QCContext, RecordFormat, RecordIndexEntry, RecordOptions, UreqDownloader, archive,
+    cache_or_warn, check_approval_blocking_qcs, comment_draft, configured_cache_directory,
+    configured_diff_settings, configured_usage_stats, create_staging_dir, determine_config_dir,
+    generate_archive_name, get_issue_information, get_milestone_issue_information,
+    get_milestone_issues_cached, get_milestones_cached, get_repo_users, issue_preview_file_name,
+    issue_record_preview, milestone_record_file_name, pdf_attachment_contexts, qc_coverage, record,
+    record_by_milestone, record_date, record_index, render, render_each, set_diff_settings,
+    setup_configuration, stash_review_file, unapprove_with_impact, verify_archive,
+    verify_signed_approval,
+};
+use ghqctoolkit::{AuditLog, AuditedGit, audit_log_path, read_audit_entries};
+use ghqctoolkit::{ChecklistItemRef, ChecklistUpdate, issue_checklist_items, update_checklist};
+use ghqctoolkit::{CommentRequest, CreateIssueRequest, GhqcClient, IssueStatus};
+use ghqctoolkit::{
+    QCApprove, QCComment, QCIssue, QCObsolete, QCReassign, QCReview, QCTimeLog, QCUnapprove,
+    QCUnobsolete, obsolete_issue, reassign_issue, unobsolete_issue,
 };
-use ghqctoolkit::utils::StdEnvProvider;
 use ghqctoolkit::{
-    ArchiveFile, ArchiveMetadata, Configuration, ContextPosition, DiskCache, GitCommand,
-    GitCommitOps, GitHubReader, GitHubWriter, GitInfo, GitRepository, IssueThread, QCContext,
-    QCStatus, UreqDownloader, analyze_issue_checklists, approve_with_validation, archive,
-    configuration_status, create_labels_if_needed, create_staging_dir, determine_config_dir,
-    fetch_milestone_issues, get_blocking_qc_status, get_git_status,
-    get_milestone_issue_information, get_repo_users, record, render, setup_configuration,
-    stash_review_file, unapprove_with_impact,
+    UsageLog, count_writes, read_usage_events, usage_log_path, usage_stats, usage_stats_csv,
 };
-use ghqctoolkit::{QCApprove, QCComment, QCIssue, QCReview, QCUnapprove};
+use ghqctoolkit::{format_hours, parse_duration, time_report, time_report_csv};
 
 #[derive(Parser)]
 #[command(name = "ghqc", author, version, about, long_about = None)]
@@ -39,6 +78,32 @@ struct Cli {
     #[arg(long, global = true)]
     config_dir: Option<PathBuf>,
 
+    /// Print long output directly instead of through the pager
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Format of the command's result. `json` prints the result as a single JSON object on
+    /// stdout, and progress and other messages on stderr
+    #[arg(long, value_enum, global = true, default_value_t = OutputFormat::Text)]
+    output_format: OutputFormat,
+
+    /// Fetch milestones, issues and other GitHub data again instead of reading the disk cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Follow renames of QCed files through the git history. `--follow-renames=false` skips the
+    /// rename detection, which walks the history of each issue, in large repositories
+    #[arg(
+        long,
+        global = true,
+        default_value_t = true,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        action = clap::ArgAction::Set
+    )]
+    follow_renames: bool,
+
     #[command(flatten)]
     verbose: Verbosity<InfoLevel>,
 }
@@ -49,11 +114,21 @@ enum Commands {
     Issue {
         #[command(subcommand)]
         issue_command: IssueCommands,
+
+        /// Change and comment on issues whose URL points at another repository than the local
+        /// remote, e.g. after the repository was transferred. The override is noted in each comment
+        #[arg(long, global = true)]
+        trust_remote_mismatch: bool,
     },
     /// Milestone status commands
     Milestone {
         #[command(subcommand)]
         milestone_command: MilestoneCommands,
+
+        /// Change and comment on issues whose URL points at another repository than the local
+        /// remote, e.g. after the repository was transferred. The override is noted in each comment
+        #[arg(long, global = true)]
+        trust_remote_mismatch: bool,
     },
     /// Configuration management commands
     Configuration {
@@ -65,6 +140,11 @@ enum Commands {
         #[command(subcommand)]
         cache_command: CacheCommands,
     },
+    /// Local audit log of the changes ghqc made on GitHub
+    Audit {
+        #[command(subcommand)]
+        audit_command: AuditCommands,
+    },
     /// Authentication management commands
     Auth {
         /// GitHub host to use, e.g. github.com or https://ghe.example.com
@@ -79,6 +159,28 @@ enum Commands {
         #[arg(long)]
         json: bool,
     },
+    /// Usage statistics recorded locally while the `usage_stats` option is on
+    Stats {
+        /// Only include usage on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: StatsFormat,
+    },
+    /// Print a shell completion script, e.g. `source <(ghqc completions bash)`
+    Completions {
+        /// Shell to complete for
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+    /// Completion candidates for the shell completion scripts, one per line
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[command(subcommand)]
+        complete_command: CompleteCommands,
+    },
     #[cfg(all(feature = "api", not(feature = "ui")))]
     /// Start the API server
     Serve {
@@ -108,6 +210,21 @@ enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command has a JSON form of its result for `--output-format json`
+    fn has_json_output(&self) -> bool {
+        match self {
+            Self::Issue { issue_command, .. } => issue_command.has_json_output(),
+            Self::Milestone {
+                milestone_command, ..
+            } => milestone_command.has_json_output(),
+            Self::Configuration { .. } | Self::Audit { .. } | Self::Sitrep { .. } => true,
+            Self::Stats { format, .. } => *format != StatsFormat::Csv,
+            _ => false,
+        }
+    }
+}
+
 #[cfg(feature = "ui")]
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 enum UiAction {
@@ -130,6 +247,11 @@ enum IssueCommands {
         #[arg(short, long)]
         checklist_name: Option<String>,
 
+        /// Local markdown file to use as a one-off checklist instead of one from the
+        /// configuration repository. The checklist is named after the file stem
+        #[arg(long, conflicts_with = "checklist_name")]
+        checklist_inline: Option<PathBuf>,
+
         /// Assignees for the issue (usernames)
         #[arg(short, long)]
         assignees: Option<Vec<String>>,
@@ -170,10 +292,84 @@ enum IssueCommands {
         relevant_qc: Vec<IssueUrlArg>,
 
         /// Relevant files (files relevant to the QC but don't require QC themselves)
-        /// Format: file_path::justification (justification is required)
-        /// Example: data/config.yaml::Configuration used by this script
+        /// Format: file_path::justification[::commit] (justification is required)
+        /// The file must exist at the commit (HEAD by default)
+        /// Example: data/config.yaml::Configuration used by this script::v1.2
         #[arg(long, value_parser = RelevantFileArgParser)]
         relevant_file: Vec<RelevantFileArg>,
+
+        /// Fail instead of warning when an assignee cannot close or reopen issues
+        /// (read-only access). Can also be enabled with `require_write_access` in options.yaml
+        #[arg(long)]
+        require_write_access: bool,
+
+        /// Print the title and body the issue would be posted with, without creating the
+        /// issue, its milestone or labels
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write the body of the dry run to this file instead of printing it
+        #[arg(short, long, requires = "dry_run")]
+        output: Option<PathBuf>,
+
+        /// Do not link the closed issues of the file in other milestones as previous QCs. By
+        /// default they are linked, after confirmation in interactive mode
+        #[arg(long)]
+        no_auto_previous: bool,
+
+        /// Leave out the Environment section listing the `environment_files` of the configuration
+        #[arg(long)]
+        no_environment: bool,
+
+        /// Offer the files matched by .ghqcignore in the file prompt
+        #[arg(long)]
+        no_ignore: bool,
+
+        /// Create the issue even when the file already has an open issue in the milestone, or a
+        /// closed one which is not linked as a previous QC
+        #[arg(long)]
+        allow_duplicate: bool,
+    },
+    /// Create one issue per file in the same milestone, with the same checklist and assignees
+    CreateBatch {
+        /// Milestone for the issues (created if it does not exist)
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File to create an issue for (repeatable)
+        #[arg(short, long = "file", required_unless_present = "glob")]
+        files: Vec<PathBuf>,
+
+        /// Create an issue for every file tracked at HEAD matching this CODEOWNERS-style
+        /// pattern, e.g. `scripts/*.R` (repeatable). Files matched by .ghqcignore are skipped
+        #[arg(long)]
+        glob: Vec<String>,
+
+        /// Include the files matched by .ghqcignore in --glob matches
+        #[arg(long)]
+        no_ignore: bool,
+
+        /// Name of the checklist to use
+        #[arg(short, long, required_unless_present = "checklist_inline")]
+        checklist_name: Option<String>,
+
+        /// Local markdown file to use as a one-off checklist instead of one from the
+        /// configuration repository. The checklist is named after the file stem
+        #[arg(long, conflicts_with = "checklist_name")]
+        checklist_inline: Option<PathBuf>,
+
+        /// Assignees for the issues (usernames)
+        #[arg(short, long)]
+        assignees: Vec<String>,
+
+        /// Description for the milestone (only used when creating a new milestone)
+        #[arg(short = 'D', long)]
+        description: Option<String>,
+
+        /// Fail instead of warning when an assignee cannot close or reopen issues
+        /// (read-only access). Can also be enabled with `require_write_access` in options.yaml
+        #[arg(long)]
+        require_write_access: bool,
     },
     /// Comment on an existing issue, providing updated context
     Comment {
@@ -193,13 +389,53 @@ enum IssueCommands {
         #[arg(short, long)]
         previous_commit: Option<String>,
 
+        /// Previous and current commits as `previous..current`, instead of --previous-commit and
+        /// --current-commit
+        #[arg(long, conflicts_with_all = ["current_commit", "previous_commit"])]
+        range: Option<CommitRange>,
+
+        /// Fail instead of warning when a given commit did not modify the file
+        #[arg(long)]
+        strict: bool,
+
         /// Optional note to include in the comment
         #[arg(short, long)]
         note: Option<String>,
 
-        /// Do not include commit diff between files even if possible. No effect in interactive mode
+        /// Do not include commit diff between files even if possible. No effect in interactive mode.
+        /// The comment still summarizes the changes (lines or Excel rows); use --no-diff=full to
+        /// omit the summary too
+        #[arg(
+            long,
+            value_enum,
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "stats"
+        )]
+        no_diff: Option<NoDiffArg>,
+
+        /// Include the diff even if .gitattributes marks the file -diff or linguist-generated
+        #[arg(long, conflicts_with = "no_diff")]
+        force_diff: bool,
+
+        /// Proceed when the checked-out branch is not the issue's QC branch. The commit must still
+        /// be on the QC branch
+        #[arg(long)]
+        allow_branch_mismatch: bool,
+
+        /// Directory, relative to the repository, to write the highlighted diff workbook of an
+        /// Excel file to. The comment references the workbook
+        #[arg(long, default_value = ghqctoolkit::DEFAULT_EXCEL_DIFF_DIR)]
+        excel_diff_dir: PathBuf,
+
+        /// Leave out the Environment section listing the `environment_files` of the configuration
         #[arg(long)]
-        no_diff: bool,
+        no_environment: bool,
+
+        /// Build the comment without posting it, writing its markdown to stdout, or to PATH
+        /// with --draft=PATH. Validation still runs and fails the command
+        #[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+        draft: Option<Option<PathBuf>>,
     },
     /// Approve and close an existing issue
     Approve {
@@ -220,8 +456,35 @@ enum IssueCommands {
         note: Option<String>,
 
         /// Force approval even if Blocking QCs are not approved
-        #[arg(long)]
+        #[arg(long, visible_alias = "force-gating")]
         force: bool,
+
+        /// Refuse to approve a commit that is unsigned, or whose signature does not verify against the configured trusted keys
+        #[arg(long)]
+        require_signed_approval: bool,
+
+        /// Date the approval actually happened, e.g. when recording a signed paper approval after the fact
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        effective_date: Option<NaiveDate>,
+
+        /// Leave the "Scope of QC" summary out of the approval, e.g. for files too large to diff
+        #[arg(long)]
+        no_scope_summary: bool,
+
+        /// Proceed when the checked-out branch is not the issue's QC branch. The commit must still
+        /// be on the QC branch
+        #[arg(long)]
+        allow_branch_mismatch: bool,
+
+        /// Approve even if the issue's checklist has unchecked items. The approval comment records
+        /// how many were unchecked. Not needed when `require_complete_checklist` is false in options.yaml
+        #[arg(long)]
+        allow_incomplete_checklist: bool,
+
+        /// Build the approval comment without posting it, writing its markdown to stdout, or to PATH
+        /// with --draft=PATH. Validation still runs and fails the command
+        #[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+        draft: Option<Option<PathBuf>>,
     },
     /// Unapprove a closed issue
     Unapprove {
@@ -236,6 +499,92 @@ enum IssueCommands {
         /// Reason to re-open issue (will prompt if not provided)
         #[arg(short, long)]
         reason: Option<String>,
+
+        /// Date the unapproval actually happened, when recording it after the fact
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        effective_date: Option<NaiveDate>,
+
+        /// Build the unapproval comment without posting it, writing its markdown to stdout, or to PATH
+        /// with --draft=PATH. Validation still runs and fails the command
+        #[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+        draft: Option<Option<PathBuf>>,
+    },
+    /// Close an issue whose file was dropped from QC scope, marking it obsolete
+    Obsolete {
+        /// Milestone of the issue
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File path of the issue to mark obsolete
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Why the file is no longer in scope
+        #[arg(short, long)]
+        reason: String,
+    },
+    /// Re-open an obsolete issue, bringing it back into QC scope
+    UnObsolete {
+        /// Milestone of the issue
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File path of the obsolete issue
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Why the file is back in scope
+        #[arg(short, long)]
+        reason: String,
+    },
+    /// Change who QCs an open issue, documenting the change in a comment
+    Reassign {
+        /// Milestone of the issue (will prompt if not provided)
+        #[arg(short, long)]
+        milestone: Option<String>,
+
+        /// File path of the issue to reassign (will prompt if not provided)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Username to assign the issue to (repeatable)
+        #[arg(long)]
+        add_assignee: Vec<String>,
+
+        /// Username to unassign from the issue (repeatable)
+        #[arg(long)]
+        remove_assignee: Vec<String>,
+
+        /// Reason for the reassignment, included in the comment
+        #[arg(short, long)]
+        note: Option<String>,
+
+        /// Fail instead of warning when an added assignee cannot close or reopen issues
+        /// (read-only access). Can also be enabled with `require_write_access` in options.yaml
+        #[arg(long)]
+        require_write_access: bool,
+    },
+    /// Check or uncheck checklist items of an issue
+    Checklist {
+        /// Milestone of the issue (will prompt if not provided)
+        #[arg(short, long)]
+        milestone: Option<String>,
+
+        /// File path of the issue (will prompt if not provided)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Item to check, by index or by a unique part of its text (repeatable)
+        #[arg(long, value_name = "ITEM")]
+        check: Vec<ChecklistItemRef>,
+
+        /// Item to uncheck, by index or by a unique part of its text (repeatable)
+        #[arg(long, value_name = "ITEM")]
+        uncheck: Vec<ChecklistItemRef>,
+
+        /// List the checklist items with their index and state
+        #[arg(long, conflicts_with_all = ["check", "uncheck"])]
+        list: bool,
     },
     /// Review current working directory changes against a commit
     Review {
@@ -243,9 +592,13 @@ enum IssueCommands {
         #[arg(short, long)]
         milestone: Option<String>,
 
-        /// File path to review (will prompt if not provided)
+        /// File path to review (will prompt if not provided). Repeat to review several files
         #[arg(short, long)]
-        file: Option<PathBuf>,
+        file: Vec<PathBuf>,
+
+        /// Review every file with uncommitted changes that has an open issue in the milestone
+        #[arg(long, requires = "milestone", conflicts_with = "file")]
+        all_dirty: bool,
 
         /// Commit to compare against (defaults to HEAD if not specified)
         #[arg(short, long)]
@@ -255,13 +608,66 @@ enum IssueCommands {
         #[arg(short, long)]
         note: Option<String>,
 
-        /// Do not include diff between commit and working directory
-        #[arg(long)]
-        no_diff: bool,
+        /// Do not include diff between commit and working directory. The review still summarizes
+        /// the changes (lines or Excel rows); use --no-diff=full to omit the summary too
+        #[arg(
+            long,
+            value_enum,
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "stats"
+        )]
+        no_diff: Option<NoDiffArg>,
+
+        /// Include the diff even if .gitattributes marks the file -diff or linguist-generated
+        #[arg(long, conflicts_with = "no_diff")]
+        force_diff: bool,
 
         /// Do not stash the reviewed file after a successful review post
         #[arg(long)]
         no_stash_after_review: bool,
+
+        /// Proceed when the checked-out branch is not the issue's QC branch. The commit must still
+        /// be on the QC branch
+        #[arg(long)]
+        allow_branch_mismatch: bool,
+
+        /// Directory, relative to the repository, to write the highlighted diff workbook of an
+        /// Excel file to. The comment references the workbook
+        #[arg(long, default_value = ghqctoolkit::DEFAULT_EXCEL_DIFF_DIR)]
+        excel_diff_dir: PathBuf,
+
+        /// Build the review comment of a single file without posting it, writing its markdown to stdout, or to PATH
+        /// with --draft=PATH. Validation still runs and fails the command
+        #[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true, conflicts_with = "all_dirty")]
+        draft: Option<Option<PathBuf>>,
+    },
+    /// Show the diff of an issue's file between two of its commits without posting it
+    Diff {
+        /// Milestone of the issue
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File path of the issue
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Commit to diff from, full or short SHA (defaults to the approved or latest notified commit)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Commit to diff to, full or short SHA (defaults to the latest commit modifying the file)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Diff to the uncommitted file in the working tree
+        #[arg(long, conflicts_with = "to")]
+        working_tree: bool,
+
+        /// Write the diff to this path instead of stdout. Excel files written to an `.xlsx` path
+        /// get a highlighted diff workbook
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
     /// detailed status of the ongoing qc issue
     Status {
@@ -272,6 +678,45 @@ enum IssueCommands {
         /// File path of issue to check status for (will prompt if not provided)
         #[arg(short, long)]
         file: Option<PathBuf>,
+
+        /// Print the status as JSON instead of text
+        #[arg(long, requires_all = ["milestone", "file"])]
+        json: bool,
+    },
+    /// List the QC issues of one or more milestones, filtered by assignee, state, QC status and
+    /// file
+    List {
+        /// Milestones to list issues from (repeatable). Defaults to all milestones
+        #[arg(short, long)]
+        milestone: Vec<String>,
+
+        /// Only issues assigned to this user. `@me` is the authenticated user
+        #[arg(short, long)]
+        assignee: Option<String>,
+
+        /// Only issues in this state
+        #[arg(long, value_enum, default_value_t)]
+        state: IssueStateFilter,
+
+        /// Only issues with one of these QC statuses (repeatable). Implies --with-status
+        #[arg(long, value_enum)]
+        status: Vec<QCStatusFilter>,
+
+        /// Only issues whose file matches this gitignore-style pattern, e.g. `scripts/**/*.R`
+        #[arg(long)]
+        file_glob: Option<String>,
+
+        /// Show the QC status of each issue. Fetches the comments of every listed issue
+        #[arg(long)]
+        with_status: bool,
+
+        /// Order of the issues
+        #[arg(long, value_enum, default_value_t)]
+        sort: IssueSort,
+
+        /// Print the issues as JSON instead of a table
+        #[arg(long)]
+        json: bool,
     },
     /// Confirm detected file renames and update issue titles
     Rename {
@@ -284,6 +729,82 @@ enum IssueCommands {
         #[arg(short, long, requires = "milestone")]
         file: Option<PathBuf>,
     },
+    /// Log time spent performing QC on an issue
+    LogTime {
+        /// Milestone of the issue
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File path of the issue to log time against
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Time spent, e.g. 90m, 1h30m or 1.5h
+        #[arg(long)]
+        duration: String,
+
+        /// Optional note describing the QC effort
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+    /// Total QC time logged on an issue, per QCer
+    Time {
+        /// Milestone of the issue
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File path of the issue
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Render an issue's section of the milestone record on its own, for a quick check
+    PreviewRecord {
+        /// Milestone of the issue
+        #[arg(short, long)]
+        milestone: String,
+
+        /// File path of the issue
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// File to save the preview pdf as. Defaults to <repo>_issue-<number>-preview.pdf
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Print the Typst source of the preview instead of rendering a pdf
+        #[arg(long, conflicts_with = "output")]
+        typ: bool,
+    },
+}
+
+impl IssueCommands {
+    /// Whether the subcommand makes changes on GitHub
+    fn writes_to_github(&self) -> bool {
+        !matches!(
+            self,
+            Self::Status { .. }
+                | Self::List { .. }
+                | Self::Time { .. }
+                | Self::PreviewRecord { .. }
+                | Self::Checklist { list: true, .. }
+                | Self::Create { dry_run: true, .. }
+                | Self::Comment { draft: Some(_), .. }
+                | Self::Approve { draft: Some(_), .. }
+                | Self::Unapprove { draft: Some(_), .. }
+                | Self::Review { draft: Some(_), .. }
+        )
+    }
+
+    /// Whether the subcommand has a JSON form of its result for `--output-format json`
+    fn has_json_output(&self) -> bool {
+        !matches!(
+            self,
+            Self::Diff { .. }
+                | Self::Rename { .. }
+                | Self::Time { .. }
+                | Self::PreviewRecord { typ: true, .. }
+        )
+    }
 }
 
 #[derive(Subcommand)]
@@ -296,6 +817,23 @@ enum MilestoneCommands {
         /// Check status for all milestones
         #[arg(long)]
         all_milestones: bool,
+
+        /// Print the status as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Share of the repository's tracked files with approved QC in the milestone(s)
+    Coverage {
+        /// Milestone names to count QC issues from
+        milestones: Vec<String>,
+
+        /// Count QC issues from all milestones
+        #[arg(long)]
+        all_milestones: bool,
+
+        /// Directories to check coverage of. Defaults to every tracked file
+        #[arg(long, num_args = 1..)]
+        paths: Vec<PathBuf>,
     },
     /// Generate a record for the milestones within the repository
     Record {
@@ -306,14 +844,41 @@ enum MilestoneCommands {
         #[arg(long)]
         all_milestones: bool,
 
-        /// File name to save the record pdf as. Will default to <repo>_<milestone names>.pdf
+        /// File name to save the record as. Will default to <repo>_<milestone names>.<format>
         #[arg(short, long)]
         record_path: Option<PathBuf>,
 
+        /// Output format of the record. HTML records cannot include context files
+        #[arg(long, value_enum, default_value = "pdf")]
+        format: RecordFormatArg,
+
         /// Only include tables and skip detailed issue content
         #[arg(long)]
         only_tables: bool,
 
+        /// Include the QC coverage of the repository's tracked files, with the files lacking QC
+        /// listed in an appendix
+        #[arg(long, conflicts_with = "split_by_milestone")]
+        coverage: bool,
+
+        /// Directories to report QC coverage of. Defaults to every tracked file
+        #[arg(long, num_args = 1.., requires = "coverage")]
+        coverage_paths: Vec<PathBuf>,
+
+        /// End the record with an appendix of each file's approved commit, a link to the file at
+        /// that commit and the approval date, per milestone
+        #[arg(long)]
+        appendix: bool,
+
+        /// Render one record per milestone, plus an index listing each record and its SHA-256 checksum.
+        /// A milestone failing to render does not stop the others
+        #[arg(long, alias = "split", conflicts_with = "record_path")]
+        split_by_milestone: bool,
+
+        /// Directory to save split records and their index in. Defaults to the current directory
+        #[arg(long, requires = "split_by_milestone")]
+        output_dir: Option<PathBuf>,
+
         /// PDF documents to prepend before the main findings.
         /// Files are rendered in the order listed.
         #[arg(long)]
@@ -323,6 +888,19 @@ enum MilestoneCommands {
         /// Files are rendered in the order listed.
         #[arg(long)]
         appended_context: Vec<PathBuf>,
+
+        /// Append PDF files attached to issues and comments after the record
+        #[arg(long)]
+        embed_attachments: bool,
+
+        /// Fail without writing a record when gathering its content raises any warning,
+        /// e.g. an empty milestone, an unresolved user name or a skipped timeline event
+        #[arg(long)]
+        strict: bool,
+
+        /// Download issue images again instead of reusing the ones cached by earlier records
+        #[arg(long)]
+        refresh_images: bool,
     },
     /// Create an archive of files from milestones
     Archive {
@@ -341,10 +919,25 @@ enum MilestoneCommands {
         #[arg(long)]
         include_unapproved: bool,
 
+        /// Only archive issues approved before this date (YYYY-MM-DD or RFC 3339 timestamp).
+        /// With --include-unapproved, unapproved issues are filtered by their latest notification
+        #[arg(long, value_parser = parse_date_time)]
+        approved_before: Option<DateTime<Utc>>,
+
+        /// Only archive issues approved at or after this date (YYYY-MM-DD or RFC 3339 timestamp).
+        /// With --include-unapproved, unapproved issues are filtered by their latest notification
+        #[arg(long, value_parser = parse_date_time)]
+        approved_after: Option<DateTime<Utc>>,
+
         /// Flatten archive structure (put all files in root directory)
         #[arg(long)]
         flatten: bool,
 
+        /// Also archive the files listed as relevant to each issue, at the issue's archived
+        /// commit, under relevant/<issue number>/
+        #[arg(long)]
+        include_relevant_files: bool,
+
         /// File name to save the archive as. Will default to <repo>_<milestone names>.tar.gz
         #[arg(short, long)]
         archive_path: Option<PathBuf>,
@@ -352,56 +945,534 @@ enum MilestoneCommands {
         /// Additional files to include with specific commits (format: file:commit)
         #[arg(long, value_parser = FileCommitPairParser)]
         additional_file: Vec<FileCommitPair>,
-    },
-}
 
-#[derive(Subcommand)]
-enum ConfigurationCommands {
-    /// Set-up the custom configuration to be used by the tool
-    Setup {
-        /// git repository url to be cloned
-        git: Option<String>,
+        /// Also write the archive's manifest.json to this path, next to the archive
+        #[arg(long)]
+        manifest_path: Option<PathBuf>,
+
+        /// Render the record of the archived milestones and place it at the archive root as
+        /// QC_RECORD.pdf
+        #[arg(long)]
+        include_record: bool,
+
+        /// Archive without the record, with a warning, when it fails to generate
+        #[arg(long, requires = "include_record")]
+        record_optional: bool,
+
+        /// Fail before writing the archive when a file is larger than this size, in bytes or
+        /// with a K, M or G suffix (e.g. 500M)
+        #[arg(long, value_parser = parse_file_size)]
+        max_file_size: Option<u64>,
     },
-    /// Status of the configuration repository
-    Status,
-}
+    /// Check the files of an archive against the checksums recorded when it was created
+    #[command(hide = true)]
+    VerifyArchive {
+        /// Path to the archive tar.gz
+        path: PathBuf,
+    },
+    /// Generate the record and archive of a milestone in one run, fetching its issues only once
+    Finalize {
+        /// Milestone name to finalize
+        milestone: String,
 
-#[derive(Subcommand)]
-enum AuthCommands {
-    /// Log in to a GitHub host and optionally store a token in ghqc
-    Login {
-        /// Token to store directly instead of launching an interactive flow
-        token: Option<String>,
+        /// File name to save the record pdf as. Will default to <repo>-<milestone name>.pdf
+        #[arg(short, long)]
+        record_path: Option<PathBuf>,
 
-        /// Skip using gh if found
+        /// File name to save the archive as. Will default to archive/<repo>-<milestone name>.tar.gz
+        #[arg(short, long)]
+        archive_path: Option<PathBuf>,
+
+        /// Include unapproved issues in archive
         #[arg(long)]
-        skip_gh: bool,
+        include_unapproved: bool,
 
-        /// Skip importing into the ghqc auth store after successful gh auth login
+        /// Flatten archive structure (put all files in root directory)
         #[arg(long)]
-        no_store: bool,
+        flatten: bool,
     },
-    /// Remove the ghqc-stored token for a host
-    Logout,
-    /// Show ghqc auth storage status
-    Status,
-    /// Print the resolved token
-    Token,
-}
+    /// Recreate the QC issues of a milestone in another milestone, with fresh initial commits
+    CopyPlan {
+        /// Milestone to copy the QC issues from
+        #[arg(long)]
+        from: String,
 
-#[cfg(feature = "cli")]
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+        /// Milestone to create the copies in. Created if it does not exist
+        #[arg(long)]
+        to: String,
 
-    let log_level = cli.verbose.log_level_filter();
-    env_logger::Builder::new()
-        .filter_level(log::LevelFilter::Off) // Turn off all logs by default
-        .filter(Some("ghqctoolkit"), log_level) // Allow logs from your crate
-        .filter(Some("octocrab"), log_level) // Allow logs from octocrab
+        /// Assign the copies to the assignees of the source issues (default)
+        #[arg(long, conflicts_with = "unassigned")]
+        assignees_from_source: bool,
+
+        /// Create the copies without assignees
+        #[arg(long)]
+        unassigned: bool,
+
+        /// Print the plan without creating anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rename a milestone, repairing cached listings and issue references to its old title
+    Rename {
+        /// Current milestone name
+        #[arg(long)]
+        from: String,
+
+        /// New milestone name
+        #[arg(long)]
+        to: String,
+
+        /// Rewrite the old name in the previous/gating/relevant QC descriptions of the
+        /// milestone's issues. Without it, the references are only reported
+        #[arg(long)]
+        fix_references: bool,
+    },
+    /// Close a milestone. Refuses while it has open issues unless --force is passed
+    Close {
+        /// Milestone name. Prompts for an open milestone when omitted
+        milestone: Option<String>,
+
+        /// Close the milestone even though some of its issues are still open
+        #[arg(long)]
+        force: bool,
+    },
+    /// Reopen a closed milestone
+    Reopen {
+        /// Milestone name. Prompts for a closed milestone when omitted
+        milestone: Option<String>,
+    },
+    /// Change the title, description or due date of a milestone
+    Edit {
+        /// Milestone name. Prompts for an open milestone when omitted
+        milestone: Option<String>,
+
+        /// New title. Use `milestone rename` to also fix references to the old title
+        #[arg(long)]
+        title: Option<String>,
+
+        /// New description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// New due date (YYYY-MM-DD)
+        #[arg(long)]
+        due_on: Option<NaiveDate>,
+    },
+    /// Total QC time logged on the issues within a milestone, per issue and per QCer
+    Time {
+        /// Milestone name to report time for
+        milestone: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: TimeFormat,
+    },
+}
+
+impl MilestoneCommands {
+    /// Whether the subcommand makes changes on GitHub
+    fn writes_to_github(&self) -> bool {
+        matches!(
+            self,
+            Self::CopyPlan { dry_run: false, .. }
+                | Self::Rename { .. }
+                | Self::Close { .. }
+                | Self::Reopen { .. }
+                | Self::Edit { .. }
+        )
+    }
+
+    /// Whether the subcommand has a JSON form of its result for `--output-format json`
+    fn has_json_output(&self) -> bool {
+        matches!(
+            self,
+            Self::Status { .. }
+                | Self::Record { .. }
+                | Self::Archive { .. }
+                | Self::Finalize { .. }
+                | Self::Close { .. }
+                | Self::Reopen { .. }
+                | Self::Edit { .. }
+        )
+    }
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Print the audit log of the repository, oldest change first
+    Show {
+        /// Only include changes made on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Print the entries as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// How much `--no-diff` leaves out
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum NoDiffArg {
+    /// Omit the diff but keep a summary of the changes
+    Stats,
+    /// Omit the diff and the change summary
+    Full,
+}
+
+impl From<NoDiffArg> for NoDiff {
+    fn from(arg: NoDiffArg) -> Self {
+        match arg {
+            NoDiffArg::Stats => NoDiff::Stats,
+            NoDiffArg::Full => NoDiff::Full,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum RecordFormatArg {
+    Pdf,
+    Html,
+}
+
+impl From<RecordFormatArg> for RecordFormat {
+    fn from(arg: RecordFormatArg) -> Self {
+        match arg {
+            RecordFormatArg::Pdf => RecordFormat::Pdf,
+            RecordFormatArg::Html => RecordFormat::Html,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum TimeFormat {
+    Text,
+    Csv,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum StatsFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum ConfigurationCommands {
+    /// Set-up the custom configuration to be used by the tool
+    Setup {
+        /// git repository url to be cloned
+        git: Option<String>,
+    },
+    /// Status of the configuration repository
+    Status,
+    /// Check the options, logo, checklists and templates of the configuration repository,
+    /// failing when any of them has an error
+    Validate {
+        /// Print the report as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Log in to a GitHub host and optionally store a token in ghqc
+    Login {
+        /// Token to store directly instead of launching an interactive flow
+        token: Option<String>,
+
+        /// Skip using gh if found
+        #[arg(long)]
+        skip_gh: bool,
+
+        /// Skip importing into the ghqc auth store after successful gh auth login
+        #[arg(long)]
+        no_store: bool,
+    },
+    /// Remove the ghqc-stored token for a host
+    Logout,
+    /// Show ghqc auth storage status
+    Status,
+    /// Print the resolved token
+    Token,
+}
+
+#[cfg(feature = "cli")]
+const READ_ONLY_TOKEN_HINT: &str = "Log in with a token that has write access to the repository (e.g. the `repo` scope) using `ghqc auth login`";
+
+/// Fail early when the GitHub token is known to be read-only, before prompting for anything
+#[cfg(feature = "cli")]
+async fn ensure_write_access(git_info: &GitInfo) -> Result<()> {
+    if git_info.token_write_access().await == Some(false) {
+        bail!(
+            "The GitHub token is read-only, so this command cannot make changes on GitHub.\n{READ_ONLY_TOKEN_HINT}"
+        );
+    }
+    Ok(())
+}
+
+/// Open the repository's disk cache, warning once when the command has to run without it
+#[cfg(feature = "cli")]
+/// Open the repository's disk cache; with `--no-cache` it is only written to, so data is fetched
+/// from GitHub again and refreshes the cache
+fn open_cache(
+    git_info: &GitInfo,
+    configured_root: Option<&Path>,
+    no_cache: bool,
+) -> Option<DiskCache> {
+    let cache = cache_or_warn(DiskCache::open(git_info, configured_root, &StdEnvProvider))?;
+    Some(if no_cache { cache.refreshing() } else { cache })
+}
+
+/// The repository's audit log, warning when its location cannot be resolved
+#[cfg(feature = "cli")]
+fn audit_log(
+    git_info: &GitInfo,
+    configured_root: Option<&Path>,
+    actor: Option<String>,
+) -> Option<AuditLog> {
+    audit_log_path(
+        &StdEnvProvider,
+        configured_root,
+        git_info.owner(),
+        git_info.repo(),
+    )
+    .inspect_err(|e| {
+        message!("⚠️  Changes made on GitHub will not be recorded in the audit log: {e}")
+    })
+    .ok()
+    .map(|path| AuditLog::new(path, actor))
+}
+
+/// The authenticated user, whom the audit log attributes changes to
+#[cfg(feature = "cli")]
+async fn audit_actor(git_info: &GitInfo) -> Option<String> {
+    git_info
+        .get_current_user()
+        .await
+        .inspect_err(|e| log::debug!("Could not determine the user for the audit log: {e}"))
+        .ok()
+        .flatten()
+}
+
+/// Record the GitHub writes of a command that `writes` in the repository's audit log
+#[cfg(feature = "cli")]
+async fn audited(
+    git_info: GitInfo,
+    configured_root: Option<&Path>,
+    writes: bool,
+) -> AuditedGit<GitInfo> {
+    if !writes {
+        return AuditedGit::new(git_info, None);
+    }
+    let actor = audit_actor(&git_info).await;
+    let log = audit_log(&git_info, configured_root, actor);
+    AuditedGit::new(git_info, log)
+}
+
+/// Label for obsolete issues, the default when no configuration repository is set up
+#[cfg(feature = "cli")]
+fn obsolete_label(config_dir: Option<PathBuf>, env: &StdEnvProvider) -> String {
+    determine_config_dir(config_dir, env)
+        .ok()
+        .filter(|dir| dir.exists())
+        .map(|dir| Configuration::from_path(&dir).obsolete_label().to_string())
+        .unwrap_or_else(|| ConfigurationOptions::default().obsolete_label)
+}
+
+/// The configuration repository's configuration, the defaults when it is not set up
+#[cfg(feature = "cli")]
+fn configuration_or_default(config_dir: Option<PathBuf>, env: &StdEnvProvider) -> Configuration {
+    determine_config_dir(config_dir, env)
+        .ok()
+        .filter(|dir| dir.exists())
+        .map(Configuration::from_path)
+        .unwrap_or_default()
+}
+
+/// Print the draft of `comment` with the issue it would be posted on, or write it to `path`
+#[cfg(feature = "cli")]
+fn print_draft(
+    comment: &impl CommentBody,
+    git_info: &AuditedGit<GitInfo>,
+    trust_remote_mismatch: bool,
+    path: Option<PathBuf>,
+) -> Result<()> {
+    let draft = comment_draft(comment, git_info, trust_remote_mismatch)?;
+    let result = match path {
+        Some(path) => {
+            std::fs::write(&path, &draft)
+                .map_err(|e| anyhow!("Failed to write draft to {}: {e}", path.display()))?;
+            CommentDraft {
+                issue_url: comment.issue().html_url.to_string(),
+                draft: None,
+                path: Some(path),
+            }
+        }
+        None => CommentDraft {
+            issue_url: comment.issue().html_url.to_string(),
+            draft: Some(draft),
+            path: None,
+        },
+    };
+    print_result(&result)?;
+    Ok(())
+}
+
+/// Print the completion candidates of `command`, one per line
+///
+/// Never prompts: any error, e.g. a missing remote or no network, prints nothing.
+#[cfg(feature = "cli")]
+async fn print_completions(cli: &Cli, command: &CompleteCommands) {
+    let env = StdEnvProvider;
+    let config_dir = determine_config_dir(cli.config_dir.clone(), &env).ok();
+
+    let candidates = match command {
+        CompleteCommands::Checklists => config_dir
+            .filter(|dir| dir.exists())
+            .map(|dir| {
+                let mut configuration = Configuration::from_path(&dir);
+                configuration.load_checklists();
+                checklist_candidates(&configuration)
+            })
+            .unwrap_or_default(),
+        CompleteCommands::Milestones => match completion_repository(cli, config_dir) {
+            Some((git_info, cache)) => milestone_candidates(&git_info, cache.as_ref()).await,
+            None => Vec::new(),
+        },
+        CompleteCommands::Files { milestone } => match completion_repository(cli, config_dir) {
+            Some((git_info, cache)) => file_candidates(&git_info, cache.as_ref(), milestone).await,
+            None => Vec::new(),
+        },
+    };
+
+    for candidate in candidates {
+        println!("{candidate}");
+    }
+}
+
+/// The repository and its cache for completion, `None` when the repository cannot be opened
+#[cfg(feature = "cli")]
+fn completion_repository(
+    cli: &Cli,
+    config_dir: Option<PathBuf>,
+) -> Option<(GitInfo, Option<DiskCache>)> {
+    let env = StdEnvProvider;
+    let auth_store = AuthStore::new(None::<PathBuf>).ok().map(|mut s| {
+        s.load();
+        s
+    });
+    let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref()).ok()?;
+    let cache_dir = config_dir.and_then(|dir| configured_cache_directory(&dir));
+    let cache = DiskCache::open(&git_info, cache_dir.as_deref(), &env).ok();
+    Some((git_info, cache))
+}
+
+/// Subcommand path of the invocation, e.g. `issue approve`, naming its usage event
+#[cfg(feature = "cli")]
+fn usage_command(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub_matches)) = current.subcommand() {
+        names.push(name);
+        current = sub_matches;
+    }
+    names.join(" ")
+}
+
+/// The usage log, when the configuration turns the `usage_stats` option on
+#[cfg(feature = "cli")]
+fn open_usage_log(cli: &Cli, env: &StdEnvProvider) -> Option<UsageLog> {
+    let config_dir = determine_config_dir(cli.config_dir.clone(), env).ok()?;
+    if !configured_usage_stats(&config_dir) {
+        return None;
+    }
+    UsageLog::open(
+        env,
+        configured_cache_directory(&config_dir).as_deref(),
+        &cli.directory,
+    )
+    .inspect_err(|e| log::debug!("Not recording usage: {e}"))
+    .ok()
+}
+
+#[cfg(feature = "cli")]
+#[tokio::main]
+async fn main() -> ExitCode {
+    let _terminal = TerminalGuard;
+
+    let matches = Cli::command().get_matches();
+    let command = usage_command(&matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // Completion runs on every <TAB>: no logging, no usage event, never a failing exit code
+    if let Commands::Complete { complete_command } = &cli.command {
+        print_completions(&cli, complete_command).await;
+        return ExitCode::SUCCESS;
+    }
+
+    if cli.output_format == OutputFormat::Json && !cli.command.has_json_output() {
+        eprintln!("Error: `ghqc {command}` does not support `--output-format json`");
+        return ExitCode::FAILURE;
+    }
+    set_output_format(cli.output_format);
+
+    let log_level = cli.verbose.log_level_filter();
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Off) // Turn off all logs by default
+        .filter(Some("ghqctoolkit"), log_level) // Allow logs from your crate
+        .filter(Some("octocrab"), log_level) // Allow logs from octocrab
         .init();
 
+    let usage_log = open_usage_log(&cli, &StdEnvProvider);
+    let start = Instant::now();
+    let result = match usage_log {
+        Some(usage_log) => {
+            let (result, counts) = count_writes(run(cli, Some(usage_log.clone()))).await;
+            usage_log.record(&command, start.elapsed(), result.is_ok(), counts);
+            result
+        }
+        None => run(cli, None).await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.is::<PromptCancelled>() => {
+            eprintln!("Cancelled, nothing was posted");
+            ExitCode::from(CANCELLED_EXIT_CODE)
+        }
+        Err(e)
+            if e.downcast_ref::<GitHubApiError>()
+                .is_some_and(GitHubApiError::is_read_only_token) =>
+        {
+            eprintln!("Error: {e}\n{READ_ONLY_TOKEN_HINT}");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+async fn run(cli: Cli, usage_log: Option<UsageLog>) -> Result<()> {
+    let log_level = cli.verbose.log_level_filter();
+    // `--quiet` also means no pager
+    let no_pager = cli.no_pager || log_level < log::LevelFilter::Info;
+    let no_cache = cli.no_cache;
+    let output_format = cli.output_format;
+
     let env = StdEnvProvider;
+    // `cache_directory` and the diff settings are read up front as commands take ownership of
+    // `cli.config_dir`
+    let config_dir = determine_config_dir(cli.config_dir.clone(), &env).ok();
+    let cache_dir = config_dir.as_deref().and_then(configured_cache_directory);
+    if let Some(dir) = &config_dir {
+        set_diff_settings(configured_diff_settings(dir));
+    }
 
     let auth_store = AuthStore::new(None::<std::path::PathBuf>)
         .inspect_err(|e| log::warn!("Failed to initialize auth store: {e}"))
@@ -412,14 +1483,36 @@ async fn main() -> Result<()> {
         .ok();
 
     match cli.command {
-        Commands::Issue { issue_command } => {
-            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?;
+        Commands::Issue {
+            issue_command,
+            trust_remote_mismatch,
+        } => {
+            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?
+                .with_trust_remote_mismatch(trust_remote_mismatch)
+                .with_follow_renames(cli.follow_renames);
+            if issue_command.writes_to_github() {
+                ensure_write_access(&git_info).await?;
+            }
+            let git_info = audited(
+                git_info,
+                cache_dir.as_deref(),
+                issue_command.writes_to_github(),
+            )
+            .await;
+            // Issues created or changed by the command make the cached listings stale
+            let _listing_invalidation = ListingInvalidation::new(
+                issue_command
+                    .writes_to_github()
+                    .then(|| open_cache(&git_info, cache_dir.as_deref(), no_cache))
+                    .flatten(),
+            );
 
             match issue_command {
                 IssueCommands::Create {
                     milestone,
                     file,
                     checklist_name,
+                    checklist_inline,
                     assignees,
                     add_collaborator,
                     remove_collaborator,
@@ -428,92 +1521,221 @@ async fn main() -> Result<()> {
                     gating_qc,
                     relevant_qc,
                     relevant_file,
+                    require_write_access,
+                    dry_run,
+                    output,
+                    no_ignore,
+                    no_auto_previous,
+                    no_environment,
+                    allow_duplicate,
                 } => {
                     let config_dir = determine_config_dir(cli.config_dir, &env)?;
                     let mut configuration = Configuration::from_path(&config_dir);
                     configuration.load_checklists();
+                    let client = GhqcClient::new(
+                        git_info.clone(),
+                        configuration,
+                        open_cache(&git_info, cache_dir.as_deref(), no_cache),
+                    );
 
-                    // Fetch milestones first
-                    let milestones = git_info.get_milestones().await?;
-                    let cache = DiskCache::from_git_info(&git_info).ok();
-                    let repo_users = get_repo_users(cache.as_ref(), &git_info).await?;
-
-                    let qc_issue = match (milestone, file, checklist_name) {
-                        (Some(milestone_name), Some(file), Some(checklist_name)) => {
-                            QCIssue::from_args(
-                                milestone_name,
-                                file,
-                                checklist_name,
+                    let checklist = checklist_name
+                        .map(ChecklistArg::Name)
+                        .or(checklist_inline.map(ChecklistArg::Inline));
+                    let qc_issue = match (milestone, file, checklist) {
+                        (Some(milestone_name), Some(file), Some(checklist)) => {
+                            let request = CreateIssueRequest {
                                 assignees,
-                                add_collaborator,
-                                remove_collaborator,
+                                add_collaborators: add_collaborator,
+                                remove_collaborators: remove_collaborator,
                                 description,
                                 previous_qc,
                                 gating_qc,
                                 relevant_qc,
-                                relevant_file,
-                                milestones,
-                                &repo_users,
-                                configuration,
-                                &git_info,
-                            )
-                            .await?
+                                relevant_files: relevant_file,
+                                require_write_access,
+                                auto_previous: !no_auto_previous,
+                                no_environment,
+                                allow_duplicate,
+                                ..CreateIssueRequest::new(milestone_name, file, checklist)
+                            };
+                            client.build_issue(request, dry_run).await?
                         }
                         (None, None, None) => {
-                            QCIssue::from_interactive(
+                            let ignore = if no_ignore {
+                                IgnoreRules::default()
+                            } else {
+                                IgnoreRules::load(git_info.path())?
+                            };
+                            let milestones = client.milestones().await?;
+                            let repo_users = get_repo_users(client.cache(), &git_info).await?;
+                            let require_write_access = require_write_access
+                                || client.configuration().require_write_access();
+                            let options = CreateOptions {
+                                configuration: client.configuration(),
+                                cache: client.cache(),
+                                require_write_access,
+                                dry_run,
+                                auto_previous: !no_auto_previous,
+                                allow_duplicate,
+                            };
+                            let qc_issue = QCIssue::from_interactive(
+                                &InquirePrompter,
                                 &cli.directory,
+                                &ignore,
                                 milestones,
-                                configuration,
-                                &git_info,
                                 &repo_users,
+                                options,
+                                &git_info,
                             )
-                            .await?
+                            .await?;
+                            if no_environment {
+                                qc_issue
+                            } else {
+                                qc_issue.with_environment(
+                                    client.configuration().environment_files(),
+                                    &git_info,
+                                )
+                            }
                         }
                         _ => {
                             bail!(
-                                "Either provide all three arguments (--milestone, --file, --checklist-name) or none to enter interactive mode"
+                                "Either provide all three arguments (--milestone, --file, --checklist-name or --checklist-inline) or none to enter interactive mode"
                             );
                         }
                     };
 
-                    create_labels_if_needed(cache.as_ref(), Some(qc_issue.branch()), &git_info)
-                        .await?;
+                    if dry_run {
+                        let preview = qc_issue.preview(&git_info)?;
+                        match output {
+                            Some(path) => {
+                                std::fs::write(&path, &preview.body)?;
+                                message!("Title: {}", preview.title);
+                                message!("Labels: {}", preview.labels.join(", "));
+                                message!("📝 Issue body written to {}", path.display());
+                            }
+                            None => print_result(&preview)?,
+                        }
+                    } else {
+                        let create_result = client.post_issue(&qc_issue).await?;
+                        print_result(&create_result)?;
+                    }
+                }
+                IssueCommands::CreateBatch {
+                    milestone,
+                    files,
+                    glob,
+                    no_ignore,
+                    checklist_name,
+                    checklist_inline,
+                    assignees,
+                    description,
+                    require_write_access,
+                } => {
+                    let config_dir = determine_config_dir(cli.config_dir, &env)?;
+                    let mut configuration = Configuration::from_path(&config_dir);
+                    configuration.load_checklists();
+                    let require_write_access =
+                        require_write_access || configuration.require_write_access();
 
-                    let create_result = qc_issue.post_with_blocking(&git_info).await?;
-                    println!("{create_result}");
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let repo_users = get_repo_users(cache.as_ref(), &git_info).await?;
+
+                    let checklist = checklist_name
+                        .map(ChecklistArg::Name)
+                        .or(checklist_inline.map(ChecklistArg::Inline))
+                        .ok_or(anyhow!("Provide --checklist-name or --checklist-inline"))?;
+                    let ignore = if no_ignore {
+                        IgnoreRules::default()
+                    } else {
+                        IgnoreRules::load(git_info.path())?
+                    };
+                    let options = BatchCreateOptions {
+                        milestone,
+                        description,
+                        files: expand_file_patterns(&files, &glob, &ignore, &git_info)?,
+                        checklist,
+                        assignees,
+                        require_write_access,
+                    };
+
+                    let report = create_issue_batch(
+                        &options,
+                        &milestones,
+                        &configuration,
+                        &repo_users,
+                        cache.as_ref(),
+                        &git_info,
+                    )
+                    .await?;
+                    print_result(&report)?;
+                    if report.failures() > 0 {
+                        bail!(
+                            "{} of {} issues could not be created",
+                            report.failures(),
+                            report.files.len()
+                        );
+                    }
                 }
                 IssueCommands::Comment {
                     milestone,
                     file,
                     current_commit,
                     previous_commit,
+                    range,
+                    strict,
                     note,
                     no_diff,
+                    force_diff,
+                    allow_branch_mismatch,
+                    excel_diff_dir,
+                    draft,
+                    no_environment,
                 } => {
-                    // Fetch milestones first
-                    let milestones = git_info.get_milestones().await?;
-                    let cache = DiskCache::from_git_info(&git_info).ok();
+                    let (current_commit, previous_commit) = match range {
+                        Some(range) => (Some(range.current), Some(range.previous)),
+                        None => (current_commit, previous_commit),
+                    };
+                    let client = GhqcClient::new(
+                        git_info.clone(),
+                        configuration_or_default(cli.config_dir, &env),
+                        open_cache(&git_info, cache_dir.as_deref(), no_cache),
+                    );
 
-                    let comment = match (milestone, file) {
+                    let mut comment = match (milestone, file) {
                         (None, None) => {
                             // Interactive mode
-                            QCComment::from_interactive(&milestones, cache.as_ref(), &git_info)
-                                .await?
-                        }
-                        (Some(milestone), Some(file)) => {
-                            // Non-interactive mode
-                            QCComment::from_args(
-                                milestone,
-                                file,
-                                current_commit,
-                                previous_commit,
-                                note,
+                            let milestones = client.milestones().await?;
+                            let comment = QCComment::from_interactive(
+                                &InquirePrompter,
                                 &milestones,
-                                cache.as_ref(),
+                                client.cache(),
                                 &git_info,
-                                no_diff,
                             )
-                            .await?
+                            .await?;
+                            if no_environment {
+                                comment
+                            } else {
+                                comment.with_environment(
+                                    client.configuration().environment_files(),
+                                    &git_info,
+                                )
+                            }
+                        }
+                        (Some(milestone), Some(file)) => {
+                            // Non-interactive mode
+                            client
+                                .build_comment(CommentRequest {
+                                    current_commit,
+                                    previous_commit,
+                                    note,
+                                    no_diff: no_diff.map(NoDiff::from),
+                                    strict,
+                                    allow_branch_mismatch,
+                                    no_environment,
+                                    ..CommentRequest::new(milestone, file)
+                                })
+                                .await?
                         }
                         _ => {
                             bail!(
@@ -521,11 +1743,18 @@ async fn main() -> Result<()> {
                             )
                         }
                     };
+                    comment.force_diff = force_diff;
+                    if let Err(e) =
+                        comment.write_excel_diff(&git_info, git_info.path(), &excel_diff_dir)
+                    {
+                        message!("⚠️  Could not write the Excel diff workbook: {e}");
+                    }
 
-                    let comment_url = git_info.post_comment(&comment).await?;
+                    if let Some(path) = draft {
+                        return print_draft(&comment, &git_info, trust_remote_mismatch, path);
+                    }
 
-                    println!("✅ Comment created!");
-                    println!("{}", comment_url);
+                    print_result(&client.post_comment(&comment).await?)?;
                 }
                 IssueCommands::Approve {
                     milestone,
@@ -533,26 +1762,54 @@ async fn main() -> Result<()> {
                     approved_commit,
                     note,
                     force,
+                    require_signed_approval,
+                    effective_date,
+                    no_scope_summary,
+                    allow_branch_mismatch,
+                    allow_incomplete_checklist,
+                    draft,
                 } => {
-                    let milestones = git_info.get_milestones().await?;
-                    let cache = DiskCache::from_git_info(&git_info).ok();
+                    let configuration = configuration_or_default(cli.config_dir, &env);
+                    let signing_keys = configuration.signing_keys();
+                    let client = GhqcClient::new(
+                        git_info.clone(),
+                        configuration,
+                        open_cache(&git_info, cache_dir.as_deref(), no_cache),
+                    );
                     let approval = match (milestone, file, &note) {
                         (None, None, None) => {
                             // Interactive Mode
-                            QCApprove::from_interactive(&milestones, cache.as_ref(), &git_info)
-                                .await?
-                        }
-                        (Some(milestone), Some(file), _) => {
-                            QCApprove::from_args(
-                                milestone.clone(),
-                                file.clone(),
-                                approved_commit,
-                                note,
+                            let require_complete_checklist = !allow_incomplete_checklist
+                                && client.configuration().require_complete_checklist();
+                            let milestones = client.milestones().await?;
+                            let mut approval = QCApprove::from_interactive(
+                                &InquirePrompter,
                                 &milestones,
-                                cache.as_ref(),
+                                client.cache(),
                                 &git_info,
+                                require_complete_checklist,
                             )
-                            .await?
+                            .await?;
+                            approval.effective_date = effective_date;
+                            if no_scope_summary {
+                                approval.scope = None;
+                            }
+                            approval.check_effective_date(&git_info, Utc::now().date_naive())?;
+                            approval
+                        }
+                        (Some(milestone), Some(file), _) => {
+                            client
+                                .build_approval(ApproveRequest {
+                                    commit: approved_commit,
+                                    note,
+                                    effective_date,
+                                    no_scope_summary,
+                                    force,
+                                    allow_branch_mismatch,
+                                    allow_incomplete_checklist,
+                                    ..ApproveRequest::new(milestone.clone(), file.clone())
+                                })
+                                .await?
                         }
                         _ => {
                             bail!(
@@ -561,23 +1818,49 @@ async fn main() -> Result<()> {
                         }
                     };
 
-                    // Use approval with validation
-                    let result =
-                        approve_with_validation(&approval, &git_info, cache.as_ref(), force)
-                            .await?;
+                    if require_signed_approval {
+                        let status = verify_signed_approval(&approval, &git_info, &signing_keys)?;
+                        message!("🔏 Approved commit is {status}");
+                    }
 
-                    println!("{}", result);
+                    if let Some(path) = draft {
+                        let check_result = check_approval_blocking_qcs(
+                            &approval,
+                            &git_info,
+                            client.cache(),
+                            force,
+                        )
+                        .await?;
+                        if !check_result.all_approved() {
+                            message!(
+                                "⚠️  --force bypasses the blocking QC checks:\n{check_result}"
+                            );
+                        }
+                        return print_draft(&approval, &git_info, trust_remote_mismatch, path);
+                    }
+
+                    let result = client.post_approval(&approval, force).await?;
+                    print_result(&result)?;
                 }
                 IssueCommands::Unapprove {
                     milestone,
                     file,
                     reason,
+                    effective_date,
+                    draft,
                 } => {
-                    let milestones = git_info.get_milestones().await?;
-                    let unapproval = match (milestone, file, &reason) {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let mut unapproval = match (milestone, file, &reason) {
                         (None, None, None) => {
                             // Interactive Mode
-                            QCUnapprove::from_interactive(&milestones, &git_info).await?
+                            QCUnapprove::from_interactive(
+                                &InquirePrompter,
+                                &milestones,
+                                cache.as_ref(),
+                                &git_info,
+                            )
+                            .await?
                         }
                         (Some(milestone), Some(file), Some(reason)) => {
                             QCUnapprove::from_args(
@@ -585,6 +1868,7 @@ async fn main() -> Result<()> {
                                 file,
                                 reason.clone(),
                                 &milestones,
+                                cache.as_ref(),
                                 &git_info,
                             )
                             .await?
@@ -596,87 +1880,395 @@ async fn main() -> Result<()> {
                         }
                     };
 
+                    unapproval.effective_date = effective_date;
+                    unapproval.check_effective_date(Utc::now().date_naive())?;
+
+                    if let Some(path) = draft {
+                        return print_draft(&unapproval, &git_info, trust_remote_mismatch, path);
+                    }
+
                     // Use unapproval with impact tree display
                     let result = unapprove_with_impact(&unapproval, &git_info).await?;
 
-                    println!("{}", result);
+                    print_result(&result)?;
                 }
-                IssueCommands::Review {
+                IssueCommands::Obsolete {
                     milestone,
                     file,
-                    commit,
+                    reason,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let issue =
+                        find_time_log_issue(&milestone, &file, &milestones, &git_info).await?;
+                    let obsolete = QCObsolete {
+                        issue,
+                        reason,
+                        actor: git_info.get_current_user().await?,
+                        date: Utc::now().date_naive(),
+                    };
+                    let label = obsolete_label(cli.config_dir, &env);
+                    let comment_url =
+                        obsolete_issue(&obsolete, &label, cache.as_ref(), &git_info).await?;
+
+                    print_result(&PostedComment::new(
+                        format!("🗑️ Issue #{} marked obsolete!", obsolete.issue.number),
+                        obsolete.issue.number,
+                        comment_url,
+                    ))?;
+                }
+                IssueCommands::UnObsolete {
+                    milestone,
+                    file,
+                    reason,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let issue =
+                        find_time_log_issue(&milestone, &file, &milestones, &git_info).await?;
+                    let unobsolete = QCUnobsolete { issue, reason };
+                    let label = obsolete_label(cli.config_dir, &env);
+                    let comment_url =
+                        unobsolete_issue(&unobsolete, &label, cache.as_ref(), &git_info).await?;
+
+                    print_result(&PostedComment::new(
+                        format!("♻️ Issue #{} is back in QC scope!", unobsolete.issue.number),
+                        unobsolete.issue.number,
+                        comment_url,
+                    ))?;
+                }
+                IssueCommands::Reassign {
+                    milestone,
+                    file,
+                    add_assignee,
+                    remove_assignee,
                     note,
-                    no_diff,
-                    no_stash_after_review,
+                    require_write_access,
                 } => {
-                    let milestones = git_info.get_milestones().await?;
-                    let cache = DiskCache::from_git_info(&git_info).ok();
-
-                    let review = match (milestone, file) {
-                        (None, None) => {
-                            QCReview::from_interactive(milestones, cache.as_ref(), &git_info)
-                                .await?
+                    let config_dir = determine_config_dir(cli.config_dir, &env)?;
+                    let require_write_access = require_write_access
+                        || Configuration::from_path(&config_dir).require_write_access();
+
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let interactive = milestone.is_none()
+                        && file.is_none()
+                        && add_assignee.is_empty()
+                        && remove_assignee.is_empty()
+                        && note.is_none();
+                    let reassign = match (milestone, file) {
+                        _ if interactive => {
+                            QCReassign::from_interactive(
+                                &InquirePrompter,
+                                &milestones,
+                                require_write_access,
+                                cache.as_ref(),
+                                &git_info,
+                            )
+                            .await?
                         }
-                        (Some(m), Some(f)) => {
-                            QCReview::from_args(
-                                m,
-                                f,
-                                commit,
+                        (Some(milestone), Some(file)) => {
+                            if add_assignee.is_empty() && remove_assignee.is_empty() {
+                                bail!("Must provide --add-assignee or --remove-assignee")
+                            }
+                            let settings = ReassignSettings {
+                                milestone,
+                                file,
+                                add_assignee,
+                                remove_assignee,
                                 note,
+                            };
+                            QCReassign::from_args(
+                                settings,
                                 &milestones,
+                                require_write_access,
                                 cache.as_ref(),
                                 &git_info,
-                                no_diff,
-                                !no_stash_after_review,
                             )
                             .await?
                         }
                         _ => {
                             bail!(
-                                "Must provide both milestone and file arguments, or neither to enter interactive mode"
+                                "Must provide both --milestone and --file arguments or no arguments to enter interactive mode"
                             )
                         }
                     };
 
-                    // Post the review comment
-                    let review_url = git_info.post_comment(&review).await?;
-                    let stash = stash_review_file(
-                        &git_info,
-                        review.issue.number,
-                        &review.file,
-                        review.stash_after_review,
-                    );
+                    if reassign.leaves_unassigned() {
+                        message!(
+                            "⚠️  Removing the last assignee leaves issue #{} without a QCer",
+                            reassign.issue.number
+                        );
+                    }
+                    let comment_url = reassign_issue(&reassign, &git_info).await?;
+
+                    print_result(&PostedComment::new(
+                        format!("👥 Issue #{} reassigned!", reassign.issue.number),
+                        reassign.issue.number,
+                        comment_url,
+                    ))?;
+                }
+                IssueCommands::Checklist {
+                    milestone,
+                    file,
+                    check,
+                    uncheck,
+                    list,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    if list {
+                        let (Some(milestone), Some(file)) = (milestone, file) else {
+                            bail!("--list requires both --milestone and --file arguments")
+                        };
+                        let issue = find_issue(&milestone, &file, &milestones, &git_info).await?;
+                        print_result(&ChecklistItems {
+                            issue_number: issue.number,
+                            items: issue_checklist_items(&issue)?,
+                        })?;
+                    } else {
+                        let interactive = milestone.is_none()
+                            && file.is_none()
+                            && check.is_empty()
+                            && uncheck.is_empty();
+                        let update = match (milestone, file) {
+                            _ if interactive => {
+                                ChecklistUpdate::from_interactive(
+                                    &InquirePrompter,
+                                    &milestones,
+                                    cache.as_ref(),
+                                    &git_info,
+                                )
+                                .await?
+                            }
+                            (Some(milestone), Some(file)) => {
+                                if check.is_empty() && uncheck.is_empty() {
+                                    bail!("Must provide --check, --uncheck or --list")
+                                }
+                                ChecklistUpdate::from_args(
+                                    milestone,
+                                    file,
+                                    check,
+                                    uncheck,
+                                    &milestones,
+                                    &git_info,
+                                )
+                                .await?
+                            }
+                            _ => {
+                                bail!(
+                                    "Must provide both --milestone and --file arguments or no arguments to enter interactive mode"
+                                )
+                            }
+                        };
+
+                        let changes = update_checklist(&update, &git_info).await?;
+                        print_result(&ChecklistUpdateResult::new(
+                            update.issue.number,
+                            update.issue.html_url.to_string(),
+                            &changes,
+                        ))?;
+                    }
+                }
+                IssueCommands::Review {
+                    milestone,
+                    mut file,
+                    all_dirty,
+                    commit,
+                    note,
+                    no_diff,
+                    force_diff,
+                    no_stash_after_review,
+                    allow_branch_mismatch,
+                    excel_diff_dir,
+                    draft,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+
+                    if draft.is_some() && file.len() > 1 {
+                        bail!("--draft reviews a single file")
+                    }
+                    let settings = ReviewSettings {
+                        commit,
+                        note,
+                        no_diff: no_diff.map(NoDiff::from),
+                        force_diff,
+                        stash_after_review: !no_stash_after_review,
+                        allow_branch_mismatch,
+                        excel_diff_dir: Some(excel_diff_dir.clone()),
+                    };
+                    if all_dirty || file.len() > 1 {
+                        let Some(milestone) = milestone else {
+                            bail!("Must provide a milestone to review multiple files")
+                        };
+                        let files = if all_dirty { git_info.dirty()? } else { file };
+                        let targets = ReviewTargets::find(
+                            &milestone,
+                            files,
+                            &milestones,
+                            cache.as_ref(),
+                            &git_info,
+                        )
+                        .await?;
+                        let report = targets.post(&settings, cache.as_ref(), &git_info).await;
+
+                        if output_format == OutputFormat::Json {
+                            for (failed, e) in &report.failed {
+                                eprintln!("❌ {}: {:#}", failed.display(), e);
+                            }
+                            println!("{}", serde_json::to_string_pretty(&report)?);
+                            if !report.failed.is_empty() {
+                                bail!("Failed to review {} file(s)", report.failed.len());
+                            }
+                            return Ok(());
+                        }
+                        for posted in &report.posted {
+                            message!("📝 {}: {}", posted.file.display(), posted.url);
+                            if let Some(message) = &posted.stash_message {
+                                message!("   {}", message);
+                            }
+                        }
+                        for skipped in &report.skipped {
+                            message!(
+                                "⏭️  {}: no open issue in milestone '{}'",
+                                skipped.display(),
+                                milestone
+                            );
+                        }
+                        for (failed, e) in &report.failed {
+                            eprintln!("❌ {}: {:#}", failed.display(), e);
+                        }
+                        message!(
+                            "\n📝 Posted {} review comment(s), skipped {} file(s), {} failed",
+                            report.posted.len(),
+                            report.skipped.len(),
+                            report.failed.len()
+                        );
+                        if !report.failed.is_empty() {
+                            bail!("Failed to review {} file(s)", report.failed.len());
+                        }
+                    } else {
+                        let mut review = match (milestone, file.pop()) {
+                            (None, None) => {
+                                QCReview::from_interactive(
+                                    &InquirePrompter,
+                                    milestones,
+                                    cache.as_ref(),
+                                    &git_info,
+                                )
+                                .await?
+                            }
+                            (Some(m), Some(f)) => {
+                                QCReview::from_args(
+                                    m,
+                                    f,
+                                    &settings,
+                                    &milestones,
+                                    cache.as_ref(),
+                                    &git_info,
+                                )
+                                .await?
+                            }
+                            _ => {
+                                bail!(
+                                    "Must provide both milestone and file arguments, or neither to enter interactive mode"
+                                )
+                            }
+                        };
+                        review.force_diff = force_diff;
+                        if let Err(e) = review.write_excel_diff(&git_info, &excel_diff_dir) {
+                            message!("⚠️  Could not write the Excel diff workbook: {e}");
+                        }
+
+                        if let Some(path) = draft {
+                            return print_draft(&review, &git_info, trust_remote_mismatch, path);
+                        }
+
+                        // Post the review comment
+                        let review_url = git_info.post_comment(&review).await?;
+                        let stash = stash_review_file(
+                            &git_info,
+                            review.issue.number,
+                            &review.file,
+                            review.stash_after_review,
+                        );
 
-                    println!("📝 Review comment created!");
-                    println!("{}", review_url);
-                    if let Some(message) = stash.message {
-                        println!("{}", message);
+                        print_result(&PostedComment {
+                            stash_message: stash.message,
+                            ..PostedComment::new(
+                                "📝 Review comment created!",
+                                review.issue.number,
+                                review_url,
+                            )
+                        })?;
+                    }
+                }
+                IssueCommands::Diff {
+                    milestone,
+                    file,
+                    from,
+                    to,
+                    working_tree,
+                    output,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let range = IssueDiffRange {
+                        from,
+                        to,
+                        working_tree,
+                    };
+                    let issue_diff = IssueDiff::from_args(
+                        milestone,
+                        file,
+                        range,
+                        &milestones,
+                        cache.as_ref(),
+                        &git_info,
+                    )
+                    .await?;
+                    match output {
+                        Some(path) => {
+                            issue_diff.write(&git_info, &path)?;
+                            message!("✅ Diff written to {}", path.display());
+                        }
+                        None => println!("{}", issue_diff.render(&git_info)?),
                     }
                 }
                 IssueCommands::Rename { milestone, file } => {
                     match (milestone, file) {
                         (Some(milestone_name), Some(old_file)) => {
                             // Non-interactive: auto-detect the new path for the specified file.
-                            let all_milestones = git_info.get_milestones().await?;
+                            let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                            let all_milestones =
+                                get_milestones_cached(cache.as_ref(), &git_info).await?;
                             let milestone = all_milestones
                                 .iter()
                                 .find(|m| m.title == milestone_name)
                                 .ok_or_else(|| {
                                     anyhow!("No milestone found with name '{milestone_name}'")
                                 })?;
-                            let issues = git_info.get_issues(Some(milestone.number as u64)).await?;
+                            let issues = get_milestone_issues_cached(
+                                cache.as_ref(),
+                                &git_info,
+                                milestone.number as u64,
+                            )
+                            .await?;
                             let issue = issues
                                 .iter()
-                                .find(|i| PathBuf::from(&i.title) == old_file)
+                                .find(|i| same_repo_path(Path::new(&i.title), &old_file))
                                 .ok_or_else(|| {
                                     anyhow!("No open issue found for file '{}'", old_file.display())
                                 })?;
                             confirm_rename_noninteractive(&git_info, issue, &old_file).await?;
-                            println!("✅ Issue #{} renamed.", issue.number);
+                            message!("✅ Issue #{} renamed.", issue.number);
                         }
                         (milestone_opt, None) => {
                             // Interactive mode: prompt for milestone (or use provided), detect, confirm each.
-                            let all_milestones = git_info.get_milestones().await?;
+                            let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                            let all_milestones =
+                                get_milestones_cached(cache.as_ref(), &git_info).await?;
                             let milestones = if let Some(name) = milestone_opt {
                                 let filtered: Vec<Milestone> = all_milestones
                                     .into_iter()
@@ -694,77 +2286,295 @@ async fn main() -> Result<()> {
                         _ => unreachable!("clap requires = constraints prevent partial args"),
                     }
                 }
-                IssueCommands::Status { milestone, file } => {
-                    let milestones = git_info.get_milestones().await?;
-                    let cache = DiskCache::from_git_info(&git_info).ok();
+                IssueCommands::Status {
+                    milestone,
+                    file,
+                    json,
+                } => {
+                    let configuration = configuration_or_default(cli.config_dir, &env);
+                    let signing_keys = configuration.signing_keys();
+                    let client = GhqcClient::new(
+                        git_info.clone(),
+                        configuration,
+                        open_cache(&git_info, cache_dir.as_deref(), no_cache),
+                    );
                     match (milestone, file) {
                         (Some(milestone), Some(file)) => {
-                            let issue =
-                                find_issue(&milestone, &file, &milestones, &git_info).await?;
-                            let checklist_summaries =
-                                analyze_issue_checklists(issue.body.as_deref());
-                            let issue_thread =
-                                IssueThread::from_issue(&issue, cache.as_ref(), &git_info).await?;
-                            let git_status = get_git_status(&git_info)?;
-                            let qc_status = QCStatus::determine_status(&issue_thread);
-                            let file_commits = issue_thread.file_commits();
-                            let blocking_qc_status = get_blocking_qc_status(
-                                &issue_thread.blocking_qcs,
+                            let IssueStatus {
+                                thread: issue_thread,
+                                git_status,
+                                report,
+                            } = client.issue_status(&milestone, &file).await?;
+                            if json || output_format == OutputFormat::Json {
+                                println!("{}", serde_json::to_string_pretty(&report)?);
+                            } else {
+                                page(
+                                    &single_issue_status(
+                                        &report,
+                                        &git_status.state,
+                                        &issue_thread.file_commits(),
+                                        file_behind_report(
+                                            &git_info,
+                                            &git_status,
+                                            &issue_thread.file,
+                                        )
+                                        .as_ref(),
+                                        &issue_thread
+                                            .qc_commit_signatures(&git_info, &signing_keys),
+                                        &issue_thread.branch_state(&git_info),
+                                    ),
+                                    no_pager,
+                                );
+                            }
+                        }
+                        (None, None) => {
+                            // Interactive mode
+                            interactive_status(
+                                &client.milestones().await?,
+                                client.cache(),
+                                &signing_keys,
                                 &git_info,
-                                cache.as_ref(),
+                                no_pager,
                             )
-                            .await;
-                            println!(
-                                "{}",
-                                single_issue_status(
-                                    &issue_thread,
-                                    &git_status.state,
-                                    &qc_status,
-                                    &git_status.dirty,
-                                    &file_commits,
-                                    &checklist_summaries,
-                                    &blocking_qc_status
-                                )
+                            .await?;
+                        }
+                        _ => {
+                            bail!(
+                                "Must provide both --milestone and --file arguments or neither to enter interactive mode"
+                            )
+                        }
+                    }
+                }
+                IssueCommands::List {
+                    milestone,
+                    assignee,
+                    state,
+                    status,
+                    file_glob,
+                    with_status,
+                    sort,
+                    json,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let all_milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let selected_milestones: Vec<Milestone> = if milestone.is_empty() {
+                        all_milestones
+                    } else {
+                        let selected: Vec<Milestone> = all_milestones
+                            .into_iter()
+                            .filter(|m| milestone.contains(&m.title))
+                            .collect();
+                        if selected.is_empty() {
+                            bail!("No matching milestones found for: {}", milestone.join(", "));
+                        }
+                        selected
+                    };
+                    let assignee = match assignee.as_deref() {
+                        Some("@me") => Some(
+                            git_info
+                                .get_current_user()
+                                .await?
+                                .ok_or(anyhow!("Could not determine the authenticated user"))?,
+                        ),
+                        _ => assignee,
+                    };
+
+                    let options = IssueListOptions {
+                        assignee,
+                        state,
+                        statuses: status,
+                        file_glob,
+                        with_status,
+                        sort,
+                    };
+                    let entries =
+                        list_issues(&selected_milestones, &options, cache.as_ref(), &git_info)
+                            .await?;
+                    if json || output_format == OutputFormat::Json {
+                        println!("{}", serde_json::to_string_pretty(&entries)?);
+                    } else {
+                        page(
+                            &format_issue_list_table(&entries, options.needs_status()),
+                            no_pager,
+                        );
+                    }
+                }
+                IssueCommands::LogTime {
+                    milestone,
+                    file,
+                    duration,
+                    note,
+                } => {
+                    let minutes = parse_duration(&duration)?;
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let issue =
+                        find_time_log_issue(&milestone, &file, &milestones, &git_info).await?;
+                    let author = git_info.get_current_user().await?;
+
+                    let time_log = QCTimeLog {
+                        file,
+                        issue,
+                        minutes,
+                        note,
+                        author,
+                    };
+                    let comment_url = git_info.post_comment(&time_log).await?;
+
+                    print_result(&PostedComment::new(
+                        format!("✅ Logged {} of QC time!", format_hours(minutes)),
+                        time_log.issue.number,
+                        comment_url,
+                    ))?;
+                }
+                IssueCommands::Time { milestone, file } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let issue =
+                        find_time_log_issue(&milestone, &file, &milestones, &git_info).await?;
+                    let time = issue_time(&issue, cache.as_ref(), &git_info).await?;
+
+                    message!("{}", time_report(&[time]));
+                }
+                IssueCommands::PreviewRecord {
+                    milestone,
+                    file,
+                    output,
+                    typ,
+                } => {
+                    let config_dir = determine_config_dir(cli.config_dir, &env)?;
+                    let configuration = Configuration::from_path(&config_dir);
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+
+                    let issue =
+                        find_time_log_issue(&milestone, &file, &milestones, &git_info).await?;
+                    let issue_number = issue.number;
+                    let snapshot =
+                        IssueSnapshot::fetch(issue, &milestone, cache.as_ref(), &git_info).await?;
+                    let thread = snapshot.thread(&git_info, cache.as_ref())?;
+
+                    let staging_dir = create_staging_dir()?;
+                    let http_downloader = UreqDownloader::with_tls(git_info.tls());
+                    let issue_information = get_issue_information(
+                        &thread,
+                        cache.as_ref(),
+                        &configuration.signing_keys(),
+                        &git_info,
+                        &http_downloader,
+                        &staging_dir,
+                    )
+                    .await?;
+                    let preview = issue_record_preview(
+                        &issue_information,
+                        &configuration,
+                        &git_info,
+                        &env,
+                        &staging_dir,
+                    )?;
+
+                    if typ {
+                        if let Err(e) = std::fs::remove_dir_all(&staging_dir) {
+                            log::debug!(
+                                "Failed to clean up staging directory {}: {e}",
+                                staging_dir.display()
                             );
                         }
-                        (None, None) => {
-                            // Interactive mode
-                            interactive_status(&milestones, cache.as_ref(), &git_info).await?;
-                        }
-                        _ => {
-                            bail!(
-                                "Must provide both --milestone and --file arguments or neither to enter interactive mode"
-                            )
-                        }
+                        message!("{preview}");
+                    } else {
+                        let output = output.unwrap_or_else(|| {
+                            PathBuf::from(issue_preview_file_name(git_info.repo(), issue_number))
+                        });
+                        let output = if output.is_relative() {
+                            cli.directory.join(output)
+                        } else {
+                            output
+                        };
+
+                        render(
+                            &preview,
+                            &output,
+                            RecordFormat::Pdf,
+                            &staging_dir,
+                            &[],
+                            cache.as_ref(),
+                            &http_downloader,
+                        )?;
+
+                        print_result(&RecordResult {
+                            headline: format!("✅ Preview of issue #{issue_number} generated at"),
+                            path: output,
+                        })?;
                     }
                 }
             }
         }
-        Commands::Milestone { milestone_command } => {
-            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?;
+        Commands::Milestone {
+            milestone_command: MilestoneCommands::VerifyArchive { path },
+            ..
+        } => {
+            let report = verify_archive(&path)?;
+            message!("{report}");
+            if !report.is_valid() {
+                bail!("Archive {} failed verification", path.display());
+            }
+        }
+        Commands::Milestone {
+            milestone_command,
+            trust_remote_mismatch,
+        } => {
+            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?
+                .with_trust_remote_mismatch(trust_remote_mismatch)
+                .with_follow_renames(cli.follow_renames);
+            let git_info = audited(
+                git_info,
+                cache_dir.as_deref(),
+                milestone_command.writes_to_github(),
+            )
+            .await;
 
             match milestone_command {
                 MilestoneCommands::Status {
                     milestones,
                     all_milestones,
+                    json,
                 } => {
-                    let cache = DiskCache::from_git_info(&git_info).ok();
-                    let all_milestones_data = git_info.get_milestones().await?;
+                    let json = json || output_format == OutputFormat::Json;
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let all_milestones_data =
+                        get_milestones_cached(cache.as_ref(), &git_info).await?;
 
                     match (milestones.is_empty(), all_milestones) {
                         (true, false) => {
+                            if json {
+                                bail!("--json requires milestone names or --all-milestones");
+                            }
                             // Interactive mode - no milestones specified and not all_milestones
                             interactive_milestone_status(
                                 &all_milestones_data,
                                 cache.as_ref(),
                                 &git_info,
+                                no_pager,
                             )
                             .await?;
                         }
                         (true, true) => {
                             // All milestones requested
-                            milestone_status(&all_milestones_data, cache.as_ref(), &git_info)
-                                .await?;
+                            milestone_status(
+                                &all_milestones_data,
+                                cache.as_ref(),
+                                &git_info,
+                                no_pager,
+                                json,
+                            )
+                            .await?;
+                        }
+                        (false, false) if json => {
+                            // Rename alerts are skipped so stdout stays valid JSON
+                            let client =
+                                GhqcClient::new(git_info.clone(), Configuration::default(), cache);
+                            let reports = client.milestone_report(&milestones).await?;
+                            println!("{}", serde_json::to_string_pretty(&reports)?);
                         }
                         (false, false) => {
                             // Specific milestones provided - filter by name
@@ -780,46 +2590,126 @@ async fn main() -> Result<()> {
                                 );
                             }
 
-                            milestone_status(&selected_milestones, cache.as_ref(), &git_info)
-                                .await?;
+                            milestone_status(
+                                &selected_milestones,
+                                cache.as_ref(),
+                                &git_info,
+                                no_pager,
+                                json,
+                            )
+                            .await?;
                         }
                         (false, true) => {
                             bail!("Cannot specify both milestone names and --all-milestones flag");
                         }
                     }
                 }
+                MilestoneCommands::Coverage {
+                    milestones,
+                    all_milestones,
+                    paths,
+                } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let all_milestones_data =
+                        get_milestones_cached(cache.as_ref(), &git_info).await?;
+
+                    let selected_milestones: Vec<Milestone> =
+                        match (milestones.is_empty(), all_milestones) {
+                            (true, true) => all_milestones_data,
+                            (false, false) => all_milestones_data
+                                .into_iter()
+                                .filter(|m| milestones.contains(&m.title))
+                                .collect(),
+                            (true, false) => {
+                                bail!("Please specify milestone names or use --all-milestones");
+                            }
+                            (false, true) => {
+                                bail!(
+                                    "Cannot specify both milestone names and --all-milestones flag"
+                                );
+                            }
+                        };
+
+                    if selected_milestones.is_empty() {
+                        bail!(
+                            "No matching milestones found for: {}",
+                            milestones.join(", ")
+                        );
+                    }
+
+                    milestone_coverage(
+                        &selected_milestones,
+                        &paths,
+                        cache.as_ref(),
+                        &git_info,
+                        no_pager,
+                    )
+                    .await?;
+                }
                 MilestoneCommands::Record {
                     milestones,
                     all_milestones,
                     record_path,
+                    format,
                     only_tables,
+                    coverage,
+                    coverage_paths,
+                    appendix,
+                    split_by_milestone,
+                    output_dir,
                     prepended_context,
                     appended_context,
+                    embed_attachments,
+                    strict,
+                    refresh_images,
                 } => {
+                    if format == RecordFormatArg::Html
+                        && (!prepended_context.is_empty()
+                            || !appended_context.is_empty()
+                            || embed_attachments)
+                    {
+                        bail!(
+                            "Context files and embedded attachments can only be merged into PDF records"
+                        );
+                    }
                     let config_dir = determine_config_dir(cli.config_dir, &env)?;
                     let configuration = Configuration::from_path(&config_dir);
 
-                    let cache = DiskCache::from_git_info(&git_info).ok();
+                    let cache =
+                        open_cache(&git_info, cache_dir.as_deref(), no_cache).map(|cache| {
+                            if refresh_images {
+                                cache.refreshing_images()
+                            } else {
+                                cache
+                            }
+                        });
 
-                    let milestones_data = git_info.get_milestones().await?;
+                    let milestones_data = get_milestones_cached(cache.as_ref(), &git_info).await?;
 
                     // Determine if we're in interactive mode (no CLI args provided)
                     let is_interactive_mode = milestones.is_empty()
                         && !all_milestones
                         && record_path.is_none()
+                        && !split_by_milestone
+                        && !coverage
+                        && !appendix
                         && prepended_context.is_empty()
-                        && appended_context.is_empty();
+                        && appended_context.is_empty()
+                        && !embed_attachments
+                        && !strict
+                        && format == RecordFormatArg::Pdf;
+                    let format = RecordFormat::from(format);
 
                     let (selected_milestones, interactive_record_path, interactive_only_tables) =
                         match (milestones.is_empty(), all_milestones, record_path.is_none()) {
                             (true, false, true) if is_interactive_mode => {
                                 // Interactive mode - no milestones specified, not all_milestones, and no record_path
-                                prompt_milestone_record(&milestones_data)?
+                                prompt_milestone_record(&milestones_data)?.value()?
                             }
                             (true, false, true) => {
                                 // Context files provided but no milestones - need milestones
                                 bail!(
-                                    "Please specify milestone names or use --all-milestones when using context files."
+                                    "Please specify milestone names or use --all-milestones when using context files or --split-by-milestone."
                                 );
                             }
                             (true, true, _) => {
@@ -857,7 +2747,7 @@ async fn main() -> Result<()> {
                     // Build context files from CLI args or interactive prompt
                     let context_files: Vec<QCContext> = if is_interactive_mode {
                         // Interactive mode - prompt for context files
-                        prompt_context_files(&cli.directory)?
+                        prompt_context_files(&cli.directory)?.value()?
                     } else {
                         // CLI mode - build from prepended_context and appended_context args
                         let mut contexts = Vec::new();
@@ -870,76 +2760,217 @@ async fn main() -> Result<()> {
                         contexts
                     };
 
-                    let issues = fetch_milestone_issues(&selected_milestones, &git_info).await?;
+                    let milestone_refs = selected_milestones.iter().collect::<Vec<_>>();
+                    let snapshot =
+                        MilestoneSnapshot::build(&milestone_refs, &git_info, cache.as_ref())
+                            .await?;
+                    let threads = snapshot.threads(&git_info, cache.as_ref())?;
+                    let mut diagnostics = Diagnostics::new();
+                    diagnostics.check_snapshot(&snapshot);
+                    let coverage = if coverage {
+                        Some(qc_coverage(&git_info, &coverage_paths, &threads)?)
+                    } else {
+                        None
+                    };
 
                     // Create staging directory for images, logo, and template
                     let staging_dir = create_staging_dir()?;
 
-                    let http_downloader = UreqDownloader::new();
+                    let http_downloader = UreqDownloader::with_tls(git_info.tls());
                     let issue_information = get_milestone_issue_information(
-                        &issues,
+                        &threads,
                         cache.as_ref(),
+                        &configuration.signing_keys(),
                         &git_info,
                         &http_downloader,
                         &staging_dir,
+                        &mut diagnostics,
                     )
                     .await?;
 
-                    let record_str = record(
-                        &selected_milestones,
-                        &issue_information,
-                        &configuration,
-                        &git_info,
-                        &env,
-                        interactive_only_tables,
-                        &staging_dir,
-                    )?;
-                    let final_record_path = interactive_record_path.or(record_path);
-                    let record_path = if let Some(mut record_path) = final_record_path {
-                        record_path.set_extension(".pdf");
-                        // Make path relative to the directory argument
-                        if record_path.is_relative() {
-                            cli.directory.join(record_path)
-                        } else {
-                            record_path
+                    if strict {
+                        diagnostics.ensure_strict()?;
+                    }
+
+                    if split_by_milestone {
+                        let output_dir = match output_dir {
+                            Some(dir) if dir.is_relative() => cli.directory.join(dir),
+                            Some(dir) => dir,
+                            None => cli.directory.clone(),
+                        };
+                        std::fs::create_dir_all(&output_dir)?;
+
+                        let milestone_records = record_by_milestone(
+                            &selected_milestones,
+                            &issue_information,
+                            &configuration,
+                            &git_info,
+                            &env,
+                            &RecordOptions {
+                                only_tables: interactive_only_tables,
+                                format,
+                                appendix,
+                                ..Default::default()
+                            },
+                            &staging_dir,
+                        )?;
+                        let renders = milestone_records
+                            .iter()
+                            .map(|r| {
+                                let mut contexts = context_files.clone();
+                                if embed_attachments {
+                                    let milestone = selected_milestones
+                                        .iter()
+                                        .filter(|m| m.title == r.milestone)
+                                        .cloned()
+                                        .collect::<Vec<_>>();
+                                    contexts.extend(pdf_attachment_contexts(
+                                        &milestone,
+                                        &issue_information,
+                                        &staging_dir,
+                                    ));
+                                }
+                                (
+                                    r.record.clone(),
+                                    output_dir.join(milestone_record_file_name(
+                                        git_info.repo(),
+                                        &r.milestone,
+                                        format,
+                                    )),
+                                    contexts,
+                                )
+                            })
+                            .collect::<Vec<_>>();
+
+                        let results = render_each(
+                            &renders,
+                            format,
+                            &staging_dir,
+                            cache.as_ref(),
+                            &http_downloader,
+                        );
+
+                        // A milestone failing to render keeps the others and their index
+                        let mut entries = Vec::new();
+                        let mut failures = Vec::new();
+                        for ((r, (_, path, _)), result) in
+                            milestone_records.iter().zip(&renders).zip(results)
+                        {
+                            match result {
+                                Ok(()) => entries.push(RecordIndexEntry::from_file(
+                                    &r.milestone,
+                                    r.issue_count,
+                                    path,
+                                )?),
+                                Err(e) => failures.push((r.milestone.clone(), e)),
+                            }
+                        }
+                        let index_path = output_dir.join(format!("{}_index.md", git_info.repo()));
+                        std::fs::write(
+                            &index_path,
+                            record_index(git_info.repo(), &record_date(&env), &entries),
+                        )?;
+
+                        print_result(&SplitRecordResult {
+                            records: entries.iter().map(|entry| entry.path.clone()).collect(),
+                            index: index_path,
+                            failures: failures
+                                .iter()
+                                .map(|(milestone, e)| (milestone.clone(), e.to_string()))
+                                .collect(),
+                        })?;
+                        if !failures.is_empty() {
+                            bail!(
+                                "{} of {} milestone records could not be rendered",
+                                failures.len(),
+                                milestone_records.len()
+                            );
                         }
                     } else {
-                        // Default record path in the directory argument location
-                        cli.directory.join(format!(
-                            "{}-{}.pdf",
-                            git_info.repo(),
-                            issues
-                                .keys()
-                                .map(|s| s.as_str())
-                                .collect::<Vec<_>>()
-                                .join("-")
-                                .replace(" ", "-")
-                        ))
-                    };
+                        let record_str = record(
+                            &selected_milestones,
+                            &issue_information,
+                            &configuration,
+                            &git_info,
+                            &env,
+                            &RecordOptions {
+                                only_tables: interactive_only_tables,
+                                coverage,
+                                format,
+                                appendix,
+                            },
+                            &staging_dir,
+                        )?;
+                        let final_record_path = interactive_record_path.or(record_path);
+                        let record_path = if let Some(mut record_path) = final_record_path {
+                            record_path.set_extension(format.extension());
+                            // Make path relative to the directory argument
+                            if record_path.is_relative() {
+                                cli.directory.join(record_path)
+                            } else {
+                                record_path
+                            }
+                        } else {
+                            // Default record path in the directory argument location
+                            cli.directory.join(format!(
+                                "{}-{}.{}",
+                                git_info.repo(),
+                                issue_information
+                                    .keys()
+                                    .map(|s| s.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join("-")
+                                    .replace(" ", "-"),
+                                format.extension()
+                            ))
+                        };
 
-                    render(
-                        &record_str,
-                        &record_path,
-                        &staging_dir,
-                        &context_files,
-                        cache.as_ref(),
-                        &http_downloader,
-                    )?;
+                        let mut context_files = context_files;
+                        if embed_attachments {
+                            context_files.extend(pdf_attachment_contexts(
+                                &selected_milestones,
+                                &issue_information,
+                                &staging_dir,
+                            ));
+                        }
 
-                    println!(
-                        "✅ Record successfully generated at {}",
-                        record_path.display()
-                    );
+                        render(
+                            &record_str,
+                            &record_path,
+                            format,
+                            &staging_dir,
+                            &context_files,
+                            cache.as_ref(),
+                            &http_downloader,
+                        )?;
+
+                        print_result(&RecordResult {
+                            headline: "✅ Record successfully generated at".to_string(),
+                            path: record_path,
+                        })?;
+                    }
                 }
                 MilestoneCommands::Archive {
                     milestones,
                     all_closed_milestones,
                     all_milestones,
                     include_unapproved,
+                    approved_before,
+                    approved_after,
                     flatten,
+                    include_relevant_files,
                     archive_path,
                     additional_file,
+                    manifest_path,
+                    include_record,
+                    record_optional,
+                    max_file_size,
                 } => {
+                    let approval_window = ApprovalWindow {
+                        approved_after,
+                        approved_before,
+                    };
+
                     let selected_archive_files = if !additional_file.is_empty() {
                         let commits = git_info.commits(&None, None)?;
                         additional_file
@@ -950,27 +2981,35 @@ async fn main() -> Result<()> {
                         Vec::new()
                     };
 
-                    let cache = DiskCache::from_git_info(&git_info).ok();
-
-                    let milestones_data = git_info.get_milestones().await?;
+                    let client = GhqcClient::new(
+                        git_info.clone(),
+                        configuration_or_default(cli.config_dir, &env),
+                        open_cache(&git_info, cache_dir.as_deref(), no_cache),
+                    );
+                    let milestones_data = client.milestones().await?;
 
                     // Determine milestone selection first
-                    let (mut archive_files, archive_path) = match (
+                    let (mut archive_files, archive_path, selected_milestones) = match (
                         milestones.is_empty(),
                         all_closed_milestones,
                         all_milestones,
                     ) {
                         (true, false, false)
-                            if archive_path.is_none() && additional_file.is_empty() =>
+                            if archive_path.is_none()
+                                && additional_file.is_empty()
+                                && approval_window.is_unbounded()
+                                && !include_relevant_files
+                                && !include_record =>
                         {
                             // Interactive mode - no milestones, no archive_path, no file_commit
-                            prompt_archive(
+                            let (archive_files, archive_path) = prompt_archive(
                                 &milestones_data,
                                 &cli.directory,
                                 &git_info,
-                                cache.as_ref(),
+                                client.cache(),
                             )
-                            .await?
+                            .await?;
+                            (archive_files, archive_path, Vec::new())
                         }
                         (true, false, false) => {
                             if additional_file.is_empty() {
@@ -983,28 +3022,28 @@ async fn main() -> Result<()> {
                                 PathBuf::from("archive")
                                     .join(generate_archive_name(&[], &git_info)),
                             );
-                            (Vec::new(), archive_path)
+                            (Vec::new(), archive_path, Vec::new())
                         }
                         (true, false, true) => {
                             // All milestones requested
                             let selected_milestones =
                                 MilestoneSelectionFilter::All.filter_milestones(&milestones_data);
-                            let artifact_files = get_milestone_issue_threads(
+                            let artifact_files = milestone_archive_files(
                                 &selected_milestones,
+                                include_unapproved,
+                                &approval_window,
+                                flatten,
+                                include_relevant_files,
                                 &git_info,
-                                cache.as_ref(),
+                                client.cache(),
                             )
-                            .await?
-                            .into_iter()
-                            .filter(|i| include_unapproved || i.approved_commit().is_some())
-                            .map(|i| ArchiveFile::from_issue_thread(&i, flatten))
-                            .collect::<std::result::Result<Vec<ArchiveFile>, _>>()?;
+                            .await?;
 
                             let archive_path = archive_path.unwrap_or(
                                 PathBuf::from("archive")
                                     .join(generate_archive_name(&selected_milestones, &git_info)),
                             );
-                            (artifact_files, archive_path)
+                            (artifact_files, archive_path, selected_milestones)
                         }
                         (true, true, false) => {
                             // All closed milestones requested
@@ -1015,22 +3054,22 @@ async fn main() -> Result<()> {
                                 bail!("No closed milestones found in repository");
                             }
 
-                            let artifact_files = get_milestone_issue_threads(
+                            let artifact_files = milestone_archive_files(
                                 &selected_milestones,
+                                include_unapproved,
+                                &approval_window,
+                                flatten,
+                                include_relevant_files,
                                 &git_info,
-                                cache.as_ref(),
+                                client.cache(),
                             )
-                            .await?
-                            .into_iter()
-                            .filter(|i| include_unapproved || i.approved_commit().is_some())
-                            .map(|i| ArchiveFile::from_issue_thread(&i, flatten))
-                            .collect::<std::result::Result<Vec<ArchiveFile>, _>>()?;
+                            .await?;
 
                             let archive_path = archive_path.unwrap_or(
                                 PathBuf::from("archive")
                                     .join(generate_archive_name(&selected_milestones, &git_info)),
                             );
-                            (artifact_files, archive_path)
+                            (artifact_files, archive_path, selected_milestones)
                         }
                         (false, false, false) => {
                             // Specific milestones provided
@@ -1046,22 +3085,22 @@ async fn main() -> Result<()> {
                                 );
                             }
 
-                            let artifact_files = get_milestone_issue_threads(
+                            let artifact_files = milestone_archive_files(
                                 &selected_milestones,
+                                include_unapproved,
+                                &approval_window,
+                                flatten,
+                                include_relevant_files,
                                 &git_info,
-                                cache.as_ref(),
+                                client.cache(),
                             )
-                            .await?
-                            .into_iter()
-                            .filter(|i| include_unapproved || i.approved_commit().is_some())
-                            .map(|i| ArchiveFile::from_issue_thread(&i, flatten))
-                            .collect::<std::result::Result<Vec<ArchiveFile>, _>>()?;
+                            .await?;
 
                             let archive_path = archive_path.unwrap_or(
                                 PathBuf::from("archive")
                                     .join(generate_archive_name(&selected_milestones, &git_info)),
                             );
-                            (artifact_files, archive_path)
+                            (artifact_files, archive_path, selected_milestones)
                         }
                         (false, true, true) => {
                             bail!("Cannot specify both milestone names and --all-milestones flag");
@@ -1082,6 +3121,35 @@ async fn main() -> Result<()> {
                     };
 
                     archive_files.extend(selected_archive_files);
+
+                    let mut generated = Vec::new();
+                    if include_record {
+                        if selected_milestones.is_empty() {
+                            bail!("--include-record needs milestones to generate the record of");
+                        }
+                        let http_downloader = UreqDownloader::with_tls(git_info.tls());
+                        let record = archive_record(
+                            &selected_milestones,
+                            client.configuration(),
+                            &git_info,
+                            &env,
+                            client.cache(),
+                            &http_downloader,
+                            |record_str, path, staging_dir| {
+                                Ok(render(
+                                    record_str,
+                                    path,
+                                    RecordFormat::Pdf,
+                                    staging_dir,
+                                    &[],
+                                    client.cache(),
+                                    &http_downloader,
+                                )?)
+                            },
+                        )
+                        .await;
+                        generated.extend(optional_record(record, record_optional)?);
+                    }
                     let archive_path = if archive_path.is_absolute() {
                         archive_path
                     } else {
@@ -1089,13 +3157,255 @@ async fn main() -> Result<()> {
                     };
 
                     // Create the actual archive using ArchiveFile approach
-                    let metadata = ArchiveMetadata::new(archive_files, &env)?;
-                    archive(metadata, &git_info, &archive_path)?;
+                    let manifest = client.write_archive(
+                        archive_files,
+                        generated,
+                        approval_window,
+                        max_file_size,
+                        &archive_path,
+                    )?;
 
-                    println!(
-                        "✅ Archive successfully created at {}",
-                        archive_path.display()
-                    );
+                    let manifest_path = match manifest_path {
+                        Some(manifest_path) => {
+                            let manifest_path = if manifest_path.is_absolute() {
+                                manifest_path
+                            } else {
+                                cli.directory.join(manifest_path)
+                            };
+                            std::fs::write(
+                                &manifest_path,
+                                serde_json::to_string_pretty(&manifest)?,
+                            )?;
+                            Some(manifest_path)
+                        }
+                        None => None,
+                    };
+                    print_result(&ArchiveResult {
+                        path: archive_path,
+                        file_count: manifest.files.len(),
+                        manifest: manifest_path,
+                    })?;
+                }
+                MilestoneCommands::Finalize {
+                    milestone,
+                    record_path,
+                    archive_path,
+                    include_unapproved,
+                    flatten,
+                } => {
+                    let config_dir = determine_config_dir(cli.config_dir, &env)?;
+                    let configuration = Configuration::from_path(&config_dir);
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones_data = get_milestones_cached(cache.as_ref(), &git_info).await?;
+
+                    let staging_dir = create_staging_dir()?;
+                    let http_downloader = UreqDownloader::with_tls(git_info.tls());
+                    let options = MilestoneFinalizeOptions {
+                        milestone,
+                        include_unapproved,
+                        flatten,
+                    };
+                    let finalization = finalize_milestone(
+                        &options,
+                        &milestones_data,
+                        &configuration.signing_keys(),
+                        cache.as_ref(),
+                        &git_info,
+                        &http_downloader,
+                        &staging_dir,
+                    )
+                    .await?;
+
+                    let record_str = record(
+                        std::slice::from_ref(&finalization.milestone),
+                        &finalization.issue_information,
+                        &configuration,
+                        &git_info,
+                        &env,
+                        &RecordOptions::default(),
+                        &staging_dir,
+                    )?;
+                    let record_path = match record_path {
+                        Some(mut record_path) => {
+                            record_path.set_extension("pdf");
+                            if record_path.is_relative() {
+                                cli.directory.join(record_path)
+                            } else {
+                                record_path
+                            }
+                        }
+                        None => cli.directory.join(format!(
+                            "{}-{}.pdf",
+                            git_info.repo(),
+                            options.milestone.replace(" ", "-")
+                        )),
+                    };
+                    render(
+                        &record_str,
+                        &record_path,
+                        RecordFormat::Pdf,
+                        &staging_dir,
+                        &[],
+                        cache.as_ref(),
+                        &http_downloader,
+                    )?;
+
+                    let archive_path = archive_path.unwrap_or_else(|| {
+                        PathBuf::from("archive")
+                            .join(generate_archive_name(&[&finalization.milestone], &git_info))
+                    });
+                    let archive_path = if archive_path.is_absolute() {
+                        archive_path
+                    } else {
+                        cli.directory.join(&archive_path)
+                    };
+                    let metadata =
+                        ArchiveMetadata::new(finalization.archive_files, &git_info, &env)?
+                            .with_size_limits(configuration.options.archive_size_limits(None));
+                    let manifest = archive(metadata, &git_info, &archive_path)?;
+
+                    print_result(&FinalizeResult {
+                        record_path,
+                        archive_path,
+                        file_count: manifest.files.len(),
+                    })?;
+                }
+                MilestoneCommands::CopyPlan {
+                    from,
+                    to,
+                    assignees_from_source: _,
+                    unassigned,
+                    dry_run,
+                } => {
+                    if !dry_run {
+                        ensure_write_access(&git_info).await?;
+                    }
+                    let config_dir = determine_config_dir(cli.config_dir, &env)?;
+                    let mut configuration = Configuration::from_path(&config_dir);
+                    configuration.load_checklists();
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let _listing_invalidation =
+                        ListingInvalidation::new(cache.clone().filter(|_| !dry_run));
+
+                    let options = CopyPlanOptions {
+                        from,
+                        to,
+                        assignees_from_source: !unassigned,
+                        dry_run,
+                    };
+                    let outcome = copy_milestone_plan(
+                        &options,
+                        &milestones,
+                        &configuration,
+                        cache.as_ref(),
+                        &git_info,
+                    )
+                    .await?;
+
+                    message!("{}", outcome.plan);
+                    if dry_run {
+                        message!("Dry run: no milestone or issues were created");
+                    } else {
+                        for create_result in &outcome.created {
+                            message!("{create_result}");
+                        }
+                    }
+                }
+                MilestoneCommands::Rename {
+                    from,
+                    to,
+                    fix_references,
+                } => {
+                    ensure_write_access(&git_info).await?;
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let _listing_invalidation = ListingInvalidation::new(cache.clone());
+
+                    let options = MilestoneRenameOptions {
+                        from,
+                        to,
+                        fix_references,
+                    };
+                    let rename =
+                        rename_milestone(&options, &milestones, cache.as_ref(), &git_info).await?;
+
+                    message!("{rename}");
+                }
+                MilestoneCommands::Close { milestone, force } => {
+                    ensure_write_access(&git_info).await?;
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let milestone = match milestone {
+                        Some(name) => find_milestone(&milestones, &name)?.clone(),
+                        None => {
+                            prompt_milestone_in_state(&milestones, MilestoneState::Open)?.value()?
+                        }
+                    };
+                    let issues = get_milestone_issues_cached(
+                        cache.as_ref(),
+                        &git_info,
+                        milestone.number as u64,
+                    )
+                    .await?;
+                    let _listing_invalidation = ListingInvalidation::new(cache.clone());
+
+                    let edit = close_milestone(&milestone, &issues, force, &git_info).await?;
+                    print_result(&edit)?;
+                }
+                MilestoneCommands::Reopen { milestone } => {
+                    ensure_write_access(&git_info).await?;
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let milestone = match milestone {
+                        Some(name) => find_milestone(&milestones, &name)?.clone(),
+                        None => prompt_milestone_in_state(&milestones, MilestoneState::Closed)?
+                            .value()?,
+                    };
+                    let _listing_invalidation = ListingInvalidation::new(cache.clone());
+
+                    let edit = reopen_milestone(&milestone, &git_info).await?;
+                    print_result(&edit)?;
+                }
+                MilestoneCommands::Edit {
+                    milestone,
+                    title,
+                    description,
+                    due_on,
+                } => {
+                    ensure_write_access(&git_info).await?;
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let milestone = match milestone {
+                        Some(name) => find_milestone(&milestones, &name)?.clone(),
+                        None => {
+                            prompt_milestone_in_state(&milestones, MilestoneState::Open)?.value()?
+                        }
+                    };
+                    let _listing_invalidation = ListingInvalidation::new(cache.clone());
+
+                    let update = MilestoneUpdate {
+                        title,
+                        description,
+                        due_on,
+                        state: None,
+                    };
+                    let edit = edit_milestone(&milestone, &milestones, &update, &git_info).await?;
+                    print_result(&edit)?;
+                }
+                MilestoneCommands::Time { milestone, format } => {
+                    let cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+                    let milestones = get_milestones_cached(cache.as_ref(), &git_info).await?;
+                    let times =
+                        milestone_time(&milestone, &milestones, cache.as_ref(), &git_info).await?;
+
+                    match format {
+                        TimeFormat::Text => println!("{}", time_report(&times)),
+                        TimeFormat::Csv => print!("{}", time_report_csv(&times)),
+                    }
+                }
+                MilestoneCommands::VerifyArchive { .. } => {
+                    unreachable!("archives are verified without opening the repository")
                 }
             }
         }
@@ -1128,10 +3438,9 @@ async fn main() -> Result<()> {
                     .await
                     .map_err(|e| anyhow!("{e}"))?;
 
-                println!(
-                    "✅ Configuration successfully setup at {}",
-                    config_dir.display()
-                );
+                print_result(&ConfigurationSetupResult {
+                    directory: config_dir,
+                })?;
             }
             ConfigurationCommands::Status => {
                 let env = StdEnvProvider;
@@ -1140,11 +3449,31 @@ async fn main() -> Result<()> {
                 configuration.load_checklists();
                 let git_info = GitInfo::from_path(&config_dir, &env, None).ok();
 
-                println!("{}", configuration_status(&configuration, &git_info))
+                print_result(&ConfigurationStatusResult::new(
+                    &configuration,
+                    &git_info,
+                    CacheHealth::check(&env, configuration.cache_directory(), None),
+                ))?;
+            }
+            ConfigurationCommands::Validate { json } => {
+                let config_dir = determine_config_dir(cli.config_dir, &StdEnvProvider)?;
+                let validation = Configuration::validate(&config_dir);
+                if json || output_format == OutputFormat::Json {
+                    println!("{}", serde_json::to_string_pretty(&validation)?);
+                } else {
+                    message!("{validation}");
+                }
+                if !validation.valid {
+                    bail!(
+                        "Configuration at {} has {} error(s)",
+                        config_dir.display(),
+                        validation.errors()
+                    );
+                }
             }
         },
         Commands::Cache { cache_command } => {
-            handle_cache(cache_command, &cli.directory)?;
+            handle_cache(cache_command, &cli.directory, cache_dir.as_deref())?;
         }
         Commands::Auth { host, auth_command } => {
             let store = auth_store
@@ -1181,18 +3510,82 @@ async fn main() -> Result<()> {
 
             let sit_rep =
                 SitRep::new(&cli.directory, cli.config_dir.as_ref(), auth_store.as_ref()).await;
-            if json {
+            if json || output_format == OutputFormat::Json {
                 println!(
                     "{}",
                     serde_json::to_string_pretty(&sit_rep).expect("valid json")
                 );
             } else {
-                println!("{}", sit_rep);
+                page(&sit_rep.to_string(), no_pager);
+            }
+        }
+        Commands::Audit {
+            audit_command: AuditCommands::Show { since, json },
+        } => {
+            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?;
+            let path = audit_log_path(
+                &env,
+                cache_dir.as_deref(),
+                git_info.owner(),
+                git_info.repo(),
+            )?;
+            let entries = read_audit_entries(&path, since)?;
+
+            if json || output_format == OutputFormat::Json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&entries).expect("valid json")
+                );
+            } else if entries.is_empty() {
+                message!("No changes recorded in {}", path.display());
+            } else {
+                let lines: Vec<String> = entries.iter().map(|e| e.to_string()).collect();
+                page(&lines.join("\n"), no_pager);
+            }
+        }
+        Commands::Stats { since, format } => {
+            let events = read_usage_events(&usage_log_path(&env, cache_dir.as_deref())?)?;
+            let stats = usage_stats(&events, since);
+            let format = match format {
+                StatsFormat::Text if output_format == OutputFormat::Json => StatsFormat::Json,
+                format => format,
+            };
+
+            match format {
+                StatsFormat::Text => {
+                    if usage_log.is_none() {
+                        message!(
+                            "Usage statistics are off. Set `usage_stats: true` in the configuration's options.yaml to record them"
+                        );
+                    }
+                    message!("{stats}");
+                }
+                StatsFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&stats).expect("valid json")
+                ),
+                StatsFormat::Csv => print!("{}", usage_stats_csv(&stats)),
             }
         }
+        Commands::Completions { shell } => {
+            let mut script = Vec::new();
+            clap_complete::generate(
+                clap_complete::Shell::from(shell),
+                &mut Cli::command(),
+                "ghqc",
+                &mut script,
+            );
+            print!("{}", String::from_utf8_lossy(&script));
+            print!("{}", dynamic_completions(shell));
+        }
+        Commands::Complete { .. } => unreachable!("completion candidates are printed in main"),
         #[cfg(all(feature = "api", not(feature = "ui")))]
         Commands::Serve { port, ipv4_only } => {
-            use ghqctoolkit::api::{AppState, bind_local_server, create_router, local_server_url};
+            use ghqctoolkit::api::{
+                ApiMetrics, AppState, MeteredGit, bind_local_server, create_router,
+                local_server_url,
+            };
+            use std::sync::Arc;
 
             let config_dir = determine_config_dir(cli.config_dir, &env)?;
             let mut configuration = Configuration::from_path(&config_dir);
@@ -1207,18 +3600,52 @@ async fn main() -> Result<()> {
                 }
             };
 
-            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?;
-            let disk_cache = DiskCache::from_git_info(&git_info).ok();
+            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?
+                .with_follow_renames(cli.follow_renames);
+            let disk_cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+            let cache_health =
+                CacheHealth::check(&env, cache_dir.as_deref(), Some(git_info.path()));
+            let write_enabled = git_info.token_write_access().await != Some(false);
+            if !write_enabled {
+                message!(
+                    "⚠️  The GitHub token is read-only. Commenting, approving and other write actions are disabled"
+                );
+            }
 
+            let actor = if write_enabled {
+                audit_actor(&git_info).await
+            } else {
+                None
+            };
+            let audit = write_enabled
+                .then(|| audit_log(&git_info, cache_dir.as_deref(), actor.clone()))
+                .flatten();
+
+            let metrics = Arc::new(ApiMetrics::default());
+            let git_info = MeteredGit::new(AuditedGit::new(git_info, audit), metrics.clone());
+            let configuration_git_info = configuration_git_info
+                .map(|g| MeteredGit::new(AuditedGit::new(g, None), metrics.clone()));
             let store_clone = auth_store.clone();
+            let creator_metrics = metrics.clone();
             let state = AppState::new(git_info, configuration, configuration_git_info, disk_cache)
                 .with_creator(move |path| {
-                    GitInfo::from_path(path, &StdEnvProvider, store_clone.as_ref()).ok()
-                });
-            let app = create_router::<GitInfo, GitCommand>(state);
+                    GitInfo::from_path(path, &StdEnvProvider, store_clone.as_ref())
+                        .ok()
+                        .map(|g| {
+                            let audit = write_enabled
+                                .then(|| audit_log(&g, cache_dir.as_deref(), actor.clone()))
+                                .flatten();
+                            MeteredGit::new(AuditedGit::new(g, audit), creator_metrics.clone())
+                        })
+                })
+                .with_write_enabled(write_enabled)
+                .with_cache_health(cache_health)
+                .with_metrics(metrics)
+                .with_usage_log(usage_log);
+            let app = create_router::<MeteredGit<AuditedGit<GitInfo>>, GitCommand>(state);
 
             let listener = bind_local_server(port, ipv4_only).await?;
-            println!("Starting API server on {}", local_server_url(&listener));
+            message!("Starting API server on {}", local_server_url(&listener));
             axum::serve(listener, app).await?;
         }
         #[cfg(feature = "ui")]
@@ -1228,11 +3655,12 @@ async fn main() -> Result<()> {
             no_open,
             ipv4_only,
         } => {
-            use ghqctoolkit::api::{AppState, bind_local_server_with_url};
+            use ghqctoolkit::api::{ApiMetrics, AppState, MeteredGit, bind_local_server_with_url};
+            use std::sync::Arc;
 
             if action == Some(UiAction::Url) {
                 let (_listener, url) = bind_local_server_with_url(port, ipv4_only).await?;
-                println!("{url}");
+                message!("{url}");
                 return Ok(());
             }
 
@@ -1249,15 +3677,52 @@ async fn main() -> Result<()> {
                 }
             };
 
-            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?;
-            let disk_cache = DiskCache::from_git_info(&git_info).ok();
+            let git_info = GitInfo::from_path(&cli.directory, &env, auth_store.as_ref())?
+                .with_follow_renames(cli.follow_renames);
+            let disk_cache = open_cache(&git_info, cache_dir.as_deref(), no_cache);
+            let cache_health =
+                CacheHealth::check(&env, cache_dir.as_deref(), Some(git_info.path()));
+            let write_enabled = git_info.token_write_access().await != Some(false);
+            if !write_enabled {
+                message!(
+                    "⚠️  The GitHub token is read-only. Commenting, approving and other write actions are disabled"
+                );
+            }
 
+            let actor = if write_enabled {
+                audit_actor(&git_info).await
+            } else {
+                None
+            };
+            let audit = write_enabled
+                .then(|| audit_log(&git_info, cache_dir.as_deref(), actor.clone()))
+                .flatten();
+
+            let metrics = Arc::new(ApiMetrics::default());
+            let git_info = MeteredGit::new(AuditedGit::new(git_info, audit), metrics.clone());
+            let configuration_git_info = configuration_git_info
+                .map(|g| MeteredGit::new(AuditedGit::new(g, None), metrics.clone()));
             let store_clone = auth_store.clone();
+            let creator_metrics = metrics.clone();
             let state = AppState::new(git_info, configuration, configuration_git_info, disk_cache)
                 .with_creator(move |path| {
-                    GitInfo::from_path(path, &StdEnvProvider, store_clone.as_ref()).ok()
-                });
-            ghqctoolkit::ui::run::<GitInfo, GitCommand>(port, state, no_open, ipv4_only).await?;
+                    GitInfo::from_path(path, &StdEnvProvider, store_clone.as_ref())
+                        .ok()
+                        .map(|g| {
+                            let audit = write_enabled
+                                .then(|| audit_log(&g, cache_dir.as_deref(), actor.clone()))
+                                .flatten();
+                            MeteredGit::new(AuditedGit::new(g, audit), creator_metrics.clone())
+                        })
+                })
+                .with_write_enabled(write_enabled)
+                .with_cache_health(cache_health)
+                .with_metrics(metrics)
+                .with_usage_log(usage_log);
+            ghqctoolkit::ui::run::<MeteredGit<AuditedGit<GitInfo>>, GitCommand>(
+                port, state, no_open, ipv4_only,
+            )
+            .await?;
         }
     }
 
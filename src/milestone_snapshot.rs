@@ -0,0 +1,190 @@
+//! Milestone issues and their threads, fetched once and shared by records, archives and the API
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use futures::future;
+use octocrab::models::{Milestone, issues::Issue};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CommitIndex, DiskCache, GitCommitOps, GitHubReader, IssueThread, QCStatus,
+    cache::{get_issue_comments, get_issue_events},
+    git::{GitComment, GitHubApiError},
+    issue::IssueError,
+    record::needs_html_for_jwt_urls,
+};
+
+/// Everything fetched from GitHub for a set of milestones
+///
+/// Serializable so that it can be stored and reused without fetching again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneSnapshot {
+    pub milestones: Vec<Milestone>,
+    pub issues: Vec<IssueSnapshot>,
+}
+
+/// An issue with its comments and timeline events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueSnapshot {
+    /// Title of the milestone the issue was fetched for
+    pub milestone: String,
+    pub issue: Issue,
+    pub comments: Vec<GitComment>,
+    pub events: Vec<serde_json::Value>,
+}
+
+/// An issue of a snapshot resolved against the local repository
+#[derive(Debug, Clone)]
+pub struct SnapshotThread<'a> {
+    pub snapshot: &'a IssueSnapshot,
+    pub thread: IssueThread,
+    pub status: QCStatus,
+}
+
+impl MilestoneSnapshot {
+    /// Fetch the issues of `milestones`, along with each issue's comments and events
+    ///
+    /// Comments and events are read from the disk cache when it is up to date with the issue.
+    pub async fn build(
+        milestones: &[&Milestone],
+        git_info: &impl GitHubReader,
+        cache: Option<&DiskCache>,
+    ) -> Result<Self, GitHubApiError> {
+        Self::build_with_progress(milestones, git_info, cache, |_, _| {}).await
+    }
+
+    /// [`MilestoneSnapshot::build`], calling `on_fetched(done, total)` each time the comments and
+    /// events of an issue have been fetched
+    pub async fn build_with_progress(
+        milestones: &[&Milestone],
+        git_info: &impl GitHubReader,
+        cache: Option<&DiskCache>,
+        on_fetched: impl Fn(usize, usize),
+    ) -> Result<Self, GitHubApiError> {
+        let issue_futures = milestones
+            .iter()
+            .map(|&m| async move { git_info.get_issues(Some(m.number as u64)).await })
+            .collect::<Vec<_>>();
+        let milestone_issues = future::try_join_all(issue_futures).await?;
+
+        for (milestone, issues) in milestones.iter().zip(&milestone_issues) {
+            if issues.is_empty() {
+                log::warn!("Milestone '{}' has no ghqc issues", milestone.title);
+            }
+        }
+
+        let total = milestone_issues.iter().map(Vec::len).sum();
+        let done = AtomicUsize::new(0);
+        let (done, on_fetched) = (&done, &on_fetched);
+        let snapshot_futures = milestones
+            .iter()
+            .zip(milestone_issues)
+            .flat_map(|(milestone, issues)| {
+                let title = milestone.title.as_str();
+                issues.into_iter().map(move |issue| async move {
+                    let snapshot = IssueSnapshot::fetch(issue, title, cache, git_info).await?;
+                    on_fetched(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                    Ok::<_, GitHubApiError>(snapshot)
+                })
+            })
+            .collect::<Vec<_>>();
+        let issues = future::try_join_all(snapshot_futures).await?;
+
+        Ok(Self {
+            milestones: milestones.iter().map(|&m| m.clone()).collect(),
+            issues,
+        })
+    }
+
+    /// Issue threads and QC statuses of every issue in the snapshot, in snapshot order
+    pub fn threads(
+        &self,
+        git_info: &impl GitCommitOps,
+        cache: Option<&DiskCache>,
+    ) -> Result<Vec<SnapshotThread<'_>>, IssueError> {
+        // Issues share branches, so walk each branch once for all of them
+        let index = CommitIndex::new();
+        self.issues
+            .iter()
+            .map(|issue| issue.indexed_thread(git_info, cache, Some(&index)))
+            .collect()
+    }
+}
+
+impl IssueSnapshot {
+    /// Fetch the comments and events of `issue`
+    ///
+    /// Cached comments without HTML are re-fetched when they contain images, since the HTML
+    /// holds the signed URLs needed to download them.
+    pub async fn fetch(
+        issue: Issue,
+        milestone: &str,
+        cache: Option<&DiskCache>,
+        git_info: &impl GitHubReader,
+    ) -> Result<Self, GitHubApiError> {
+        let mut comments = get_issue_comments(&issue, cache, git_info).await?;
+
+        if needs_html_for_jwt_urls(&comments) {
+            log::info!(
+                "Issue #{} contains images but cached comments lack HTML - re-fetching with HTML",
+                issue.number
+            );
+
+            if let Some(cache) = cache {
+                let cache_key = format!("issue_{}", issue.number);
+                if let Err(e) = cache.invalidate(&["issues", "comments"], &cache_key) {
+                    log::warn!(
+                        "Failed to invalidate cache for issue #{}: {}",
+                        issue.number,
+                        e
+                    );
+                }
+            }
+
+            comments = get_issue_comments(&issue, cache, git_info).await?;
+        }
+
+        let events = get_issue_events(&issue, cache, git_info).await?;
+
+        Ok(Self {
+            milestone: milestone.to_string(),
+            issue,
+            comments,
+            events,
+        })
+    }
+
+    /// Resolve the issue's thread and QC status against the local repository
+    pub fn thread(
+        &self,
+        git_info: &impl GitCommitOps,
+        cache: Option<&DiskCache>,
+    ) -> Result<SnapshotThread<'_>, IssueError> {
+        self.indexed_thread(git_info, cache, None)
+    }
+
+    fn indexed_thread(
+        &self,
+        git_info: &impl GitCommitOps,
+        cache: Option<&DiskCache>,
+        index: Option<&CommitIndex>,
+    ) -> Result<SnapshotThread<'_>, IssueError> {
+        let thread = match index {
+            Some(index) => IssueThread::from_issue_comments_with_index(
+                &self.issue,
+                &self.comments,
+                git_info,
+                cache,
+                index,
+            )?,
+            None => IssueThread::from_issue_comments(&self.issue, &self.comments, git_info, cache)?,
+        };
+        let status = QCStatus::determine_status(&thread);
+
+        Ok(SnapshotThread {
+            snapshot: self,
+            thread,
+            status,
+        })
+    }
+}
@@ -0,0 +1,239 @@
+use chrono::NaiveDate;
+use ghqctoolkit_core::parse_obsoletion;
+use octocrab::models::issues::Issue;
+
+use crate::cache::{DiskCache, get_issue_comments};
+use crate::comment_system::CommentBody;
+use crate::git::{GitFileOps, GitHelpers, GitHubApiError, GitHubReader, GitHubWriter};
+
+/// Color of the obsolete label when it has to be created
+const OBSOLETE_LABEL_COLOR: &str = "BFBFBF";
+
+/// Take an issue out of QC scope, e.g. after its file was dropped from the analysis
+#[derive(Debug, Clone)]
+pub struct QCObsolete {
+    pub issue: Issue,
+    pub reason: String,
+    /// Login of whoever obsoletes the issue, when known
+    pub actor: Option<String>,
+    pub date: NaiveDate,
+}
+
+impl QCObsolete {
+    fn body(&self) -> String {
+        let mut metadata = vec![
+            "## Metadata".to_string(),
+            format!("issue: #{}", self.issue.number),
+            // The marker is parsed a line at a time
+            format!("obsolete reason: {}", single_line(&self.reason)),
+        ];
+        if let Some(actor) = &self.actor {
+            metadata.push(format!("obsoleted by: {actor}"));
+        }
+        metadata.push(format!("obsoleted on: {}", self.date));
+
+        let body = [
+            "# QC Obsoleted".to_string(),
+            self.reason.clone(),
+            metadata.join("\n* "),
+        ];
+        body.join("\n\n")
+    }
+}
+
+impl CommentBody for QCObsolete {
+    fn title(&self) -> &str {
+        "QC Obsoleted"
+    }
+
+    fn generate_body(&self, _git_info: &(impl GitHelpers + GitFileOps)) -> String {
+        self.body()
+    }
+
+    fn issue(&self) -> &Issue {
+        &self.issue
+    }
+}
+
+/// Bring an obsolete issue back into QC scope
+#[derive(Debug, Clone)]
+pub struct QCUnobsolete {
+    pub issue: Issue,
+    pub reason: String,
+}
+
+impl QCUnobsolete {
+    fn body(&self) -> String {
+        let metadata = [
+            "## Metadata".to_string(),
+            format!("issue: #{}", self.issue.number),
+            format!("un-obsolete reason: {}", single_line(&self.reason)),
+        ];
+
+        let body = [
+            "# QC Un-Obsoleted".to_string(),
+            self.reason.clone(),
+            metadata.join("\n* "),
+        ];
+        body.join("\n\n")
+    }
+}
+
+impl CommentBody for QCUnobsolete {
+    fn title(&self) -> &str {
+        "QC Un-Obsoleted"
+    }
+
+    fn generate_body(&self, _git_info: &(impl GitHelpers + GitFileOps)) -> String {
+        self.body()
+    }
+
+    fn issue(&self) -> &Issue {
+        &self.issue
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ObsoleteError {
+    #[error("Issue #{0} is already obsolete")]
+    AlreadyObsolete(u64),
+    #[error("Issue #{0} is not obsolete")]
+    NotObsolete(u64),
+    #[error("GitHub API error: {0}")]
+    GitHubApiError(#[from] GitHubApiError),
+}
+
+/// Post the obsolete comment, close the issue and label it with `label`
+///
+/// Returns the URL of the posted comment.
+pub async fn obsolete_issue(
+    obsolete: &QCObsolete,
+    label: &str,
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitHubWriter),
+) -> Result<String, ObsoleteError> {
+    let number = obsolete.issue.number;
+    if is_obsolete(&obsolete.issue, cache, git_info).await? {
+        return Err(ObsoleteError::AlreadyObsolete(number));
+    }
+
+    let comment_url = git_info.post_comment(obsolete).await?;
+    git_info.close_issue(number).await?;
+
+    if !git_info
+        .get_labels()
+        .await?
+        .iter()
+        .any(|name| name == label)
+    {
+        log::debug!("{label} label does not exist. Creating...");
+        git_info.create_label(label, OBSOLETE_LABEL_COLOR).await?;
+    }
+    git_info.add_labels(number, &[label.to_string()]).await?;
+
+    Ok(comment_url)
+}
+
+/// Post the un-obsolete comment, reopen the issue and remove `label` from it
+///
+/// Returns the URL of the posted comment.
+pub async fn unobsolete_issue(
+    unobsolete: &QCUnobsolete,
+    label: &str,
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitHubWriter),
+) -> Result<String, ObsoleteError> {
+    let number = unobsolete.issue.number;
+    if !is_obsolete(&unobsolete.issue, cache, git_info).await? {
+        return Err(ObsoleteError::NotObsolete(number));
+    }
+
+    let comment_url = git_info.post_comment(unobsolete).await?;
+    git_info.open_issue(number).await?;
+
+    if unobsolete.issue.labels.iter().any(|l| l.name == label) {
+        git_info.remove_label(number, label).await?;
+    }
+
+    Ok(comment_url)
+}
+
+async fn is_obsolete(
+    issue: &Issue,
+    cache: Option<&DiskCache>,
+    git_info: &impl GitHubReader,
+) -> Result<bool, GitHubApiError> {
+    let comments = get_issue_comments(issue, cache, git_info).await?;
+    Ok(parse_obsoletion(comments.iter().map(|c| c.body.as_str())).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issue::Obsoletion;
+    use crate::test_utils::create_test_issue;
+
+    fn issue() -> Issue {
+        create_test_issue("owner", "repo", 3, "src/analysis.R", "", Some(1), "open")
+    }
+
+    #[test]
+    fn test_qc_obsolete_body() {
+        let obsolete = QCObsolete {
+            issue: issue(),
+            reason: "Dropped from the analysis plan.\nSee the SAP amendment.".to_string(),
+            actor: Some("reviewer".to_string()),
+            date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+        };
+
+        insta::assert_snapshot!(obsolete.body(), @r"
+        # QC Obsoleted
+
+        Dropped from the analysis plan.
+        See the SAP amendment.
+
+        ## Metadata
+        * issue: #3
+        * obsolete reason: Dropped from the analysis plan. See the SAP amendment.
+        * obsoleted by: reviewer
+        * obsoleted on: 2026-03-01
+        ");
+        assert_eq!(
+            parse_obsoletion([obsolete.body().as_str()]),
+            Some(Obsoletion {
+                reason: "Dropped from the analysis plan. See the SAP amendment.".to_string(),
+                actor: Some("reviewer".to_string()),
+                date: Some("2026-03-01".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_qc_unobsolete_clears_obsoletion() {
+        let obsolete = QCObsolete {
+            issue: issue(),
+            reason: "Out of scope".to_string(),
+            actor: None,
+            date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+        }
+        .body();
+        let unobsolete = QCUnobsolete {
+            issue: issue(),
+            reason: "Back in the analysis plan".to_string(),
+        }
+        .body();
+
+        assert_eq!(
+            parse_obsoletion([obsolete.as_str()]).map(|o| o.actor),
+            Some(None)
+        );
+        assert_eq!(
+            parse_obsoletion([obsolete.as_str(), unobsolete.as_str()]),
+            None
+        );
+    }
+}
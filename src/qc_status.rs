@@ -1,19 +1,28 @@
+use ghqctoolkit_core::ThreadStatus;
 use gix::ObjectId;
-use regex::Regex;
-use serde::{Deserialize, Serialize};
+use octocrab::models::Milestone;
+use octocrab::models::issues::Issue;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
-use std::sync::LazyLock;
 
 use crate::GitCommitOps;
-use crate::cache::DiskCache;
-use crate::git::{GitHubApiError, GitHubReader};
-use crate::issue::{BlockingQC, IssueError, IssueThread};
-
-static CHECKLIST_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?m)^\s*-\s*\[([xX\s])\]").expect("Failed to compile checklist regex")
-});
+use crate::cache::{DiskCache, get_issue_comments, get_milestone_issues_cached};
+use crate::git::{
+    CommitIndex, FileGitState, GitHubApiError, GitHubReader, GitRepository, GitState, GitStatusOps,
+    get_git_status,
+};
+use crate::issue::{
+    BlockingQC, CommitStatus, FileRenameEvent, IssueError, IssueRelevantFile, IssueThread,
+    Obsoletion, Unapproval,
+};
+use crate::utils::contains_repo_path;
+
+pub use ghqctoolkit_core::{
+    ChecklistItem, ChecklistSummary, analyze_issue_checklists, format_section_summaries,
+    unchecked_checklist_items,
+};
 
 #[derive(Debug, Clone)]
 pub enum QCStatus {
@@ -27,6 +36,8 @@ pub enum QCStatus {
     ChangeRequested,
     InProgress,
     ChangesToComment(ObjectId),
+    // closed as out of QC scope
+    Obsoleted(Obsoletion),
 }
 
 impl std::fmt::Display for QCStatus {
@@ -39,91 +50,49 @@ impl std::fmt::Display for QCStatus {
             QCStatus::ApprovalRequired => "Approval required",
             QCStatus::ChangeRequested => "Changes requested",
             QCStatus::ChangesToComment(_) => "Changes to comment",
+            QCStatus::Obsoleted(_) => "Obsolete",
         };
         write!(f, "{}", status_text)
     }
 }
 
-impl QCStatus {
-    pub fn determine_status(issue_thread: &IssueThread) -> Self {
-        let commits = &issue_thread.commits;
-
-        let status = if let Some(approved) = issue_thread.approved_commit() {
-            // Find the approved commit index in the chronological sequence
-            let approved_index = commits
-                .iter()
-                .position(|commit| commit.hash == approved.hash)
-                .expect("Approved commit must be in commits");
-
-            // Check if there are any commits after the approved commit that touch the file
-            // (commits are ordered chronologically, newer commits have lower indices)
-            let commits_after_approval = &commits[..approved_index];
-            let file_changes_after_approval = commits_after_approval
-                .iter()
-                .find(|commit| commit.file_changed);
+/// Serialized as the status name in snake case, e.g. `"changes_after_approval"`
+impl Serialize for QCStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            QCStatus::Approved => "approved",
+            QCStatus::ChangesAfterApproval(_) => "changes_after_approval",
+            QCStatus::ApprovalRequired => "approval_required",
+            QCStatus::AwaitingReview => "awaiting_review",
+            QCStatus::ChangeRequested => "change_requested",
+            QCStatus::InProgress => "in_progress",
+            QCStatus::ChangesToComment(_) => "changes_to_comment",
+            QCStatus::Obsoleted(_) => "obsoleted",
+        })
+    }
+}
 
-            if let Some(latest_file_change) = file_changes_after_approval {
-                Self::ChangesAfterApproval(latest_file_change.hash)
-            } else {
-                Self::Approved
-            }
-        } else {
-            // if not approved and closed
-            if !issue_thread.open {
-                Self::ApprovalRequired
-            } else {
-                // Find the newest (lowest index) file-changing commit.
-                // Commits are stored newest-first, so lower index = more recent.
-                let latest_file_entry = commits.iter().enumerate().find(|(_, c)| c.file_changed);
-
-                match latest_file_entry {
-                    Some((file_idx, latest_fc)) => {
-                        // Find the newest commit that carries any status.
-                        // A status commit "covers" the file change if it is at the same
-                        // position or newer (index ≤ file_idx).
-                        let latest_status_entry = commits
-                            .iter()
-                            .enumerate()
-                            .find(|(_, c)| !c.statuses.is_empty());
-
-                        let covered = latest_status_entry
-                            .map(|(si, _)| si <= file_idx)
-                            .unwrap_or(false);
-
-                        if covered {
-                            let status_commit = latest_status_entry.unwrap().1;
-                            if status_commit
-                                .statuses
-                                .contains(&crate::issue::CommitStatus::Reviewed)
-                            {
-                                Self::ChangeRequested
-                            } else {
-                                Self::AwaitingReview
-                            }
-                        } else {
-                            Self::ChangesToComment(latest_fc.hash)
-                        }
-                    }
-                    None => {
-                        // No file-changing commit found, but the issue has been posted
-                        // (an Initial/Notification commit exists). Treat like a covered
-                        // status commit: awaiting review unless already reviewed.
-                        let latest_status_entry = commits.iter().find(|c| !c.statuses.is_empty());
-                        match latest_status_entry {
-                            Some(sc)
-                                if sc.statuses.contains(&crate::issue::CommitStatus::Reviewed) =>
-                            {
-                                Self::ChangeRequested
-                            }
-                            Some(_) => Self::AwaitingReview,
-                            None => Self::InProgress,
-                        }
-                    }
-                }
-            }
-        };
+impl From<ThreadStatus<ObjectId>> for QCStatus {
+    fn from(status: ThreadStatus<ObjectId>) -> Self {
+        match status {
+            ThreadStatus::Approved => Self::Approved,
+            ThreadStatus::ChangesAfterApproval(commit) => Self::ChangesAfterApproval(commit),
+            ThreadStatus::ApprovalRequired => Self::ApprovalRequired,
+            ThreadStatus::AwaitingReview => Self::AwaitingReview,
+            ThreadStatus::ChangeRequested => Self::ChangeRequested,
+            ThreadStatus::InProgress => Self::InProgress,
+            ThreadStatus::ChangesToComment(commit) => Self::ChangesToComment(commit),
+        }
+    }
+}
 
-        status
+impl QCStatus {
+    pub fn determine_status(issue_thread: &IssueThread) -> Self {
+        // An obsolete issue is out of scope whatever its QC history
+        if let Some(obsoletion) = &issue_thread.obsoleted {
+            return Self::Obsoleted(obsoletion.clone());
+        }
+        ghqctoolkit_core::determine_status(&issue_thread.commits, issue_thread.open).into()
     }
 
     /// Returns true if this status represents an approved issue
@@ -144,190 +113,51 @@ impl QCStatus {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ChecklistSummary {
-    pub completed: usize,
-    pub total: usize,
+/// How far the file has moved on from a standing approval
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StaleApproval {
+    /// The file has uncommitted changes in the working tree
+    pub dirty: bool,
+    /// Commits on the issue branch that modified the file after the approved commit
+    pub commits_after_approval: usize,
 }
 
-impl ChecklistSummary {
-    pub fn new(completed: usize, total: usize) -> Self {
-        Self { completed, total }
-    }
-
-    pub fn completion_percentage(&self) -> f64 {
-        if self.total == 0 {
-            100.0
-        } else {
-            (self.completed as f64 / self.total as f64) * 100.0
-        }
-    }
-
-    pub fn is_complete(&self) -> bool {
-        self.completed == self.total && self.total > 0
-    }
-
-    pub fn sum<'a, I>(summaries: I) -> Self
-    where
-        I: IntoIterator<Item = &'a Self>,
-    {
-        let mut total_completed = 0;
-        let mut total_items = 0;
-
-        for summary in summaries {
-            total_completed += summary.completed;
-            total_items += summary.total;
-        }
-
-        Self::new(total_completed, total_items)
-    }
-}
-
-impl std::fmt::Display for ChecklistSummary {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}/{} ({:.1}%)",
-            self.completed,
-            self.total,
-            self.completion_percentage()
-        )
-    }
-}
-
-/// Analyze checklists within an issue's body
-/// Returns a vector of (checklist_name, summary) tuples
-pub fn analyze_issue_checklists(issue_body: Option<&str>) -> Vec<(String, ChecklistSummary)> {
-    let body = match issue_body {
-        Some(body) => body,
-        None => return vec![],
-    };
-
-    let mut checklists = Vec::new();
-
-    // Split body into sections by headers (any level # to ######)
-    let sections = split_body_into_sections(body);
-
-    for (section_name, section_content) in sections {
-        let summary = analyze_checklist_in_text(&section_content);
-
-        // Only include sections that have checklist items
-        if summary.total > 0 {
-            checklists.push((section_name, summary));
+impl StaleApproval {
+    /// `None` unless the issue is approved and the file changed since, in later commits or in
+    /// `dirty_files`
+    pub fn from_thread(issue_thread: &IssueThread, dirty_files: &[PathBuf]) -> Option<Self> {
+        if !QCStatus::determine_status(issue_thread).is_approved() {
+            return None;
         }
+        // commits are ordered newest first
+        let approved_position = issue_thread
+            .commits
+            .iter()
+            .position(|c| c.statuses.contains(&CommitStatus::Approved))?;
+        let stale = Self {
+            dirty: contains_repo_path(dirty_files, &issue_thread.current_file()),
+            commits_after_approval: issue_thread.commits[..approved_position]
+                .iter()
+                .filter(|c| c.file_changed)
+                .count(),
+        };
+        (stale.dirty || stale.commits_after_approval > 0).then_some(stale)
     }
-
-    checklists
 }
 
-/// Split the issue body into sections based on markdown headers
-/// Only processes content starting from the first level 1 header (ignoring Metadata section)
-fn split_body_into_sections(body: &str) -> Vec<(String, String)> {
-    let mut sections = Vec::new();
-    let mut current_section = String::new();
-    let mut current_header: Option<String> = None;
-    let mut found_first_level1_header = false;
-
-    for line in body.lines() {
-        if let Some(header_text) = extract_header_text(line) {
-            let is_level1_header =
-                line.trim_start().starts_with("# ") && !line.trim_start().starts_with("## ");
-
-            // Only start processing after we find the first level 1 header
-            if !found_first_level1_header && !is_level1_header {
-                continue; // Skip non-level-1 headers before the first level 1 header
-            }
-
-            if !found_first_level1_header && is_level1_header {
-                found_first_level1_header = true;
-            }
-
-            // Save the previous section if it has content and a header
-            if found_first_level1_header {
-                if let Some(ref header) = current_header {
-                    if !current_section.trim().is_empty() {
-                        sections.push((header.clone(), current_section.clone()));
-                    }
-                }
-            }
-
-            // Start new section
-            current_header = Some(header_text);
-            current_section.clear();
-        } else if found_first_level1_header {
-            // Only collect content after we've found the first level 1 header
-            current_section.push_str(line);
-            current_section.push('\n');
-        }
-        // Ignore everything before the first level 1 header (like Metadata section)
-    }
-
-    // Don't forget the last section
-    if found_first_level1_header {
-        if let Some(header) = current_header {
-            if !current_section.trim().is_empty() {
-                sections.push((header, current_section));
-            }
+impl fmt::Display for StaleApproval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        match self.commits_after_approval {
+            0 => {}
+            1 => changes.push("1 commit after approval".to_string()),
+            n => changes.push(format!("{n} commits after approval")),
         }
-    }
-
-    sections
-}
-
-/// Extract header text from a line if it's a markdown header (# to ######)
-/// Returns None if the line is not a valid header
-fn extract_header_text(line: &str) -> Option<String> {
-    let trimmed = line.trim_start();
-
-    if !trimmed.starts_with('#') {
-        return None;
-    }
-
-    // Count the number of # symbols at the start
-    let hash_count = trimmed.chars().take_while(|&c| c == '#').count();
-
-    // Must be 1-6 # symbols followed by a space
-    if hash_count < 1 || hash_count > 6 || trimmed.chars().nth(hash_count) != Some(' ') {
-        return None;
-    }
-
-    // Extract the text after the # symbols and space
-    let header_text = trimmed
-        .chars()
-        .skip(hash_count + 1)
-        .collect::<String>()
-        .trim()
-        .to_string();
-
-    if header_text.is_empty() {
-        None
-    } else {
-        Some(header_text)
-    }
-}
-
-/// Analyze checklist items in a text block
-/// Recognizes patterns like:
-/// - [ ] Unchecked item
-/// - [x] Checked item
-/// - [X] Checked item
-fn analyze_checklist_in_text(text: &str) -> ChecklistSummary {
-    let mut total = 0;
-    let mut completed = 0;
-
-    for capture in CHECKLIST_REGEX.captures_iter(text) {
-        total += 1;
-
-        // Check if the item is marked as complete
-        if let Some(checkbox) = capture.get(1) {
-            let checkbox_content = checkbox.as_str().trim();
-            if checkbox_content.eq_ignore_ascii_case("x") {
-                completed += 1;
-            }
+        if self.dirty {
+            changes.push("uncommitted changes".to_string());
         }
+        write!(f, "{}", changes.join(" and "))
     }
-
-    ChecklistSummary::new(completed, total)
 }
 
 /// Status of blocking QC issues for a given issue
@@ -427,6 +257,67 @@ impl fmt::Display for BlockingQCStatus {
     }
 }
 
+/// Serialized as lists sorted by issue number, so the output is stable
+impl Serialize for BlockingQCStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct Entry<'a> {
+            issue_number: u64,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            file: Option<&'a PathBuf>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            qc_status: Option<&'a QCStatus>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<&'a str>,
+        }
+
+        fn sorted(mut entries: Vec<Entry<'_>>) -> Vec<Entry<'_>> {
+            entries.sort_by_key(|e| e.issue_number);
+            entries
+        }
+
+        let approved = sorted(
+            self.approved
+                .iter()
+                .map(|(&issue_number, file)| Entry {
+                    issue_number,
+                    file: Some(file),
+                    qc_status: None,
+                    error: None,
+                })
+                .collect(),
+        );
+        let not_approved = sorted(
+            self.not_approved
+                .iter()
+                .map(|(&issue_number, (file, status))| Entry {
+                    issue_number,
+                    file: Some(file),
+                    qc_status: Some(status),
+                    error: None,
+                })
+                .collect(),
+        );
+        let errors = sorted(
+            self.errors
+                .iter()
+                .map(|(&issue_number, error)| Entry {
+                    issue_number,
+                    file: None,
+                    qc_status: None,
+                    error: Some(error),
+                })
+                .collect(),
+        );
+
+        let mut state = serializer.serialize_struct("BlockingQCStatus", 3)?;
+        state.serialize_field("approved", &approved)?;
+        state.serialize_field("not_approved", &not_approved)?;
+        state.serialize_field("errors", &errors)?;
+        state.end()
+    }
+}
+
 /// Get the approval status for a list of blocking QCs.
 ///
 /// This function works directly with a slice of `BlockingQC` without requiring an `IssueThread`,
@@ -470,6 +361,229 @@ pub async fn get_blocking_qc_status(
     status
 }
 
+/// Checklist completion of one checklist in an issue body
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NamedChecklistSummary {
+    pub name: String,
+    #[serde(flatten)]
+    pub summary: ChecklistSummary,
+}
+
+/// Structured QC status of a single issue
+///
+/// This is what `ghqc issue status --json` and `ghqc milestone status --json` print, and what the
+/// status tables are rendered from.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IssueStatusReport {
+    pub issue_number: u64,
+    pub issue_url: String,
+    pub file: PathBuf,
+    pub milestone: String,
+    pub branch: String,
+    /// The branch no longer exists locally or on the remote
+    pub branch_deleted: bool,
+    pub open: bool,
+    pub qc_status: QCStatus,
+    /// Human-readable form of `qc_status`
+    pub qc_status_detail: String,
+    pub qc_round: u32,
+    /// Round in which the standing approval was given
+    pub rounds_to_approval: Option<u32>,
+    /// Commit of the standing approval, `None` if the issue is not approved
+    pub approved_commit: Option<String>,
+    /// Latest withdrawal of approval, `None` if never unapproved or approved again since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unapproval: Option<Unapproval>,
+    /// Newest commit on the issue branch which modified the file
+    pub latest_commit: Option<String>,
+    /// Completion over all checklists in the issue body
+    pub checklist: ChecklistSummary,
+    pub checklists: Vec<NamedChecklistSummary>,
+    /// File name of the one-off checklist the issue was created with
+    pub inline_checklist: Option<String>,
+    /// Whether the file's commits are behind or ahead of the remote
+    pub git_status: FileGitState,
+    /// The file has local, uncommitted changes
+    pub uncommitted_changes: bool,
+    /// The file changed since the standing approval, `None` if it did not or the issue is not
+    /// approved
+    pub stale_approval: Option<StaleApproval>,
+    pub blocking_qcs: BlockingQCStatus,
+    /// Files listed as relevant in the issue body, with their justification
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub relevant_files: Vec<IssueRelevantFile>,
+    /// Warning when the issue's commit history was resolved through a fallback
+    pub commit_caveat: Option<String>,
+    /// Renames of the file on the branch since the initial commit, oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub renames: Vec<FileRenameEvent>,
+}
+
+impl IssueStatusReport {
+    pub fn new(
+        issue_thread: &IssueThread,
+        checklists: Vec<(String, ChecklistSummary)>,
+        blocking_qcs: BlockingQCStatus,
+        git_state: &GitState,
+        dirty_files: &[PathBuf],
+    ) -> Self {
+        let qc_status = QCStatus::determine_status(issue_thread);
+        let checklist = ChecklistSummary::sum(checklists.iter().map(|(_, c)| c));
+        Self {
+            issue_number: issue_thread.issue_number,
+            issue_url: issue_thread.issue_url.clone(),
+            file: issue_thread.file.clone(),
+            milestone: issue_thread.milestone.clone(),
+            branch: issue_thread.branch.clone(),
+            branch_deleted: issue_thread.branch_deleted,
+            open: issue_thread.open,
+            qc_status_detail: qc_status.to_string(),
+            qc_status,
+            qc_round: issue_thread.rounds.current_round,
+            rounds_to_approval: issue_thread.rounds.rounds_to_approval,
+            approved_commit: issue_thread
+                .approved_commit()
+                .map(|commit| commit.hash.to_string()),
+            unapproval: issue_thread.unapproval.clone(),
+            latest_commit: issue_thread
+                .file_commits()
+                .first()
+                .map(|hash| hash.to_string()),
+            checklist,
+            checklists: checklists
+                .into_iter()
+                .map(|(name, summary)| NamedChecklistSummary { name, summary })
+                .collect(),
+            inline_checklist: issue_thread.inline_checklist.clone(),
+            git_status: git_state.file_state(&issue_thread.file_commits()),
+            uncommitted_changes: contains_repo_path(dirty_files, &issue_thread.current_file()),
+            stale_approval: StaleApproval::from_thread(issue_thread, dirty_files),
+            blocking_qcs,
+            relevant_files: issue_thread.relevant_files.clone(),
+            commit_caveat: issue_thread.commit_retrieval.caveat(),
+            renames: issue_thread.renames.clone(),
+        }
+    }
+
+    /// Build the report of an issue, fetching its comments and blocking QCs
+    ///
+    /// The issue's commits are taken from `index`, shared with the other issues of the report.
+    pub async fn from_issue(
+        issue: &Issue,
+        git_state: &GitState,
+        dirty_files: &[PathBuf],
+        cache: Option<&DiskCache>,
+        git_info: &(impl GitHubReader + GitCommitOps),
+        index: &CommitIndex,
+    ) -> Result<Self, QCStatusError> {
+        let comments = get_issue_comments(issue, cache, git_info).await?;
+        let issue_thread =
+            IssueThread::from_issue_comments_with_index(issue, &comments, git_info, cache, index)?;
+        let checklists = analyze_issue_checklists(issue.body.as_deref());
+        let blocking_qcs =
+            get_blocking_qc_status(&issue_thread.blocking_qcs, git_info, cache).await;
+        Ok(Self::new(
+            &issue_thread,
+            checklists,
+            blocking_qcs,
+            git_state,
+            dirty_files,
+        ))
+    }
+}
+
+/// Structured QC status of every issue in a milestone, sorted by file
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MilestoneStatusReport {
+    pub milestone: String,
+    pub issues: Vec<IssueStatusReport>,
+}
+
+impl MilestoneStatusReport {
+    /// Build the report of a milestone from its issues
+    ///
+    /// Issues whose QC history cannot be determined are skipped.
+    pub async fn from_issues(
+        milestone: &Milestone,
+        issues: &[Issue],
+        git_state: &GitState,
+        dirty_files: &[PathBuf],
+        cache: Option<&DiskCache>,
+        git_info: &(impl GitHubReader + GitCommitOps),
+        index: &CommitIndex,
+    ) -> Self {
+        let mut reports = Vec::new();
+        for issue in issues {
+            match IssueStatusReport::from_issue(
+                issue,
+                git_state,
+                dirty_files,
+                cache,
+                git_info,
+                index,
+            )
+            .await
+            {
+                Ok(report) => reports.push(report),
+                Err(e) => log::debug!("Skipping issue #{} in status report: {e}", issue.number),
+            }
+        }
+        reports.sort_by(|a, b| a.file.cmp(&b.file));
+
+        Self {
+            milestone: milestone.title.clone(),
+            issues: reports,
+        }
+    }
+}
+
+/// Local git state used for status reports
+///
+/// Failing to fetch or inspect the repository is logged and treated as a clean repository.
+pub fn status_git_state(
+    git_info: &(impl GitRepository + GitStatusOps),
+) -> (GitState, Vec<PathBuf>) {
+    match get_git_status(git_info) {
+        Ok(status) => (status.state, status.dirty),
+        Err(e) => {
+            log::debug!("Could not determine git status, treating as clean: {e}");
+            (GitState::Clean, Vec::new())
+        }
+    }
+}
+
+/// Get the structured QC status of every issue in the given milestones
+pub async fn milestone_status_report(
+    milestones: &[&Milestone],
+    cache: Option<&DiskCache>,
+    git_info: &(impl GitHubReader + GitCommitOps + GitRepository + GitStatusOps),
+) -> Result<Vec<MilestoneStatusReport>, QCStatusError> {
+    // Fetch once before processing issues (same result for all issues)
+    let (git_state, dirty_files) = status_git_state(git_info);
+    // Milestones share branches, so walk each branch once for all of them
+    let index = CommitIndex::new();
+
+    let mut reports = Vec::new();
+    for milestone in milestones {
+        let issues = get_milestone_issues_cached(cache, git_info, milestone.number as u64).await?;
+        reports.push(
+            MilestoneStatusReport::from_issues(
+                milestone,
+                &issues,
+                &git_state,
+                &dirty_files,
+                cache,
+                git_info,
+                &index,
+            )
+            .await,
+        );
+    }
+    reports.sort_by(|a, b| a.milestone.cmp(&b.milestone));
+
+    Ok(reports)
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum QCStatusError {
     #[error("Failed to determine commits for issue due to: {0}")]
@@ -481,6 +595,7 @@ pub enum QCStatusError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::issue::CommitStatus;
     use std::str::FromStr;
 
     #[test]
@@ -490,6 +605,29 @@ mod tests {
         insta::assert_debug_snapshot!(result);
     }
 
+    #[test]
+    fn test_checklist_template_examples_are_not_counted() {
+        let issue_body = include_str!("tests/qc_status/checklist_template_with_examples.md");
+        let checklists = analyze_issue_checklists(Some(issue_body));
+
+        // The quoted tip and the fenced and indented examples hold 8 checkboxes between them
+        assert_eq!(
+            checklists,
+            vec![
+                ("Functionality".to_string(), ChecklistSummary::new(3, 4)),
+                ("Testing".to_string(), ChecklistSummary::new(2, 5)),
+            ]
+        );
+        assert_eq!(
+            ChecklistSummary::sum(checklists.iter().map(|(_, summary)| summary)),
+            ChecklistSummary::new(5, 9)
+        );
+        assert_eq!(
+            crate::format_section_summaries(&checklists),
+            "Functionality 3/4, Testing 2/5"
+        );
+    }
+
     #[test]
     fn test_change_requested_status_matrix() {
         use crate::issue::{CommitStatus, IssueCommit, IssueThread};
@@ -662,11 +800,25 @@ mod tests {
 
             let issue_thread = IssueThread {
                 file: PathBuf::from("test.rs"),
+                issue_number: 1,
+                issue_url: "https://github.com/owner/repo/issues/1".to_string(),
                 branch: "main".to_string(),
+                branch_deleted: false,
                 open: issue_open,
                 commits,
                 milestone: "milestone".to_string(),
                 blocking_qcs: vec![],
+                relevant_files: Vec::new(),
+                approved_at: None,
+                approval_recorded_at: None,
+                approved_by: None,
+                unapproval: None,
+                notified_at: None,
+                obsoleted: None,
+                inline_checklist: None,
+                rounds: Default::default(),
+                commit_retrieval: Default::default(),
+                renames: Vec::new(),
             };
 
             let status = QCStatus::determine_status(&issue_thread);
@@ -678,6 +830,7 @@ mod tests {
                 QCStatus::InProgress => "InProgress",
                 QCStatus::ApprovalRequired => "ApprovalRequired",
                 QCStatus::ChangesToComment(_) => "ChangesToComment",
+                QCStatus::Obsoleted(_) => "Obsoleted",
             };
 
             assert_eq!(
@@ -783,4 +936,236 @@ mod tests {
             .is_approved()
         );
     }
+
+    #[test]
+    fn test_obsoleted_thread_is_terminal() {
+        let mut issue_thread = crate::test_utils::create_renotified_after_approval_thread();
+        assert!(QCStatus::determine_status(&issue_thread).is_approved());
+
+        issue_thread.open = false;
+        issue_thread.obsoleted = Some(Obsoletion {
+            reason: "Dropped from the analysis plan".to_string(),
+            actor: Some("reviewer".to_string()),
+            date: Some("2026-03-01".to_string()),
+        });
+
+        let status = QCStatus::determine_status(&issue_thread);
+        assert!(
+            matches!(&status, QCStatus::Obsoleted(o) if o.reason == "Dropped from the analysis plan")
+        );
+        assert!(!status.is_approved());
+        assert_eq!(status.to_string(), "Obsolete");
+    }
+
+    fn report_thread(commits: Vec<(&[CommitStatus], bool)>, open: bool) -> IssueThread {
+        use crate::issue::IssueCommit;
+
+        let commits = commits
+            .into_iter()
+            .enumerate()
+            .map(|(i, (statuses, file_changed))| IssueCommit {
+                hash: ObjectId::from_str(&format!("{:040x}", i + 1)).unwrap(),
+                message: format!("Commit {}", i + 1),
+                statuses: statuses.iter().cloned().collect(),
+                file_changed,
+            })
+            .rev()
+            .collect();
+        IssueThread {
+            file: PathBuf::from("scripts/model.R"),
+            issue_number: 7,
+            issue_url: "https://github.com/owner/repo/issues/7".to_string(),
+            branch: "qc/model".to_string(),
+            branch_deleted: false,
+            open,
+            commits,
+            milestone: "v1.0".to_string(),
+            blocking_qcs: vec![],
+            relevant_files: Vec::new(),
+            approved_at: None,
+            approval_recorded_at: None,
+            approved_by: None,
+            unapproval: None,
+            notified_at: None,
+            obsoleted: None,
+            inline_checklist: None,
+            rounds: Default::default(),
+            commit_retrieval: Default::default(),
+            renames: Vec::new(),
+        }
+    }
+
+    fn report_json(issue_thread: &IssueThread, git_state: &GitState) -> serde_json::Value {
+        let report = IssueStatusReport::new(
+            issue_thread,
+            vec![("Code Review".to_string(), ChecklistSummary::new(1, 4))],
+            BlockingQCStatus::default(),
+            git_state,
+            &[PathBuf::from("scripts/model.R")],
+        );
+        serde_json::to_value(&report).unwrap()
+    }
+
+    const FIRST: &str = "0000000000000000000000000000000000000001";
+    const SECOND: &str = "0000000000000000000000000000000000000002";
+
+    #[test]
+    fn test_issue_status_report_json_open() {
+        let thread = report_thread(
+            vec![
+                (&[CommitStatus::Initial], true),
+                (&[CommitStatus::Notification], true),
+            ],
+            true,
+        );
+        let ahead = GitState::Ahead(vec![ObjectId::from_str(SECOND).unwrap()]);
+
+        assert_eq!(
+            report_json(&thread, &ahead),
+            serde_json::json!({
+                "issue_number": 7,
+                "issue_url": "https://github.com/owner/repo/issues/7",
+                "file": "scripts/model.R",
+                "milestone": "v1.0",
+                "branch": "qc/model",
+                "branch_deleted": false,
+                "open": true,
+                "qc_status": "awaiting_review",
+                "qc_status_detail": "Awaiting review",
+                "qc_round": 0,
+                "rounds_to_approval": null,
+                "approved_commit": null,
+                "latest_commit": SECOND,
+                "checklist": {"completed": 1, "total": 4},
+                "checklists": [{"name": "Code Review", "completed": 1, "total": 4}],
+                "inline_checklist": null,
+                "git_status": "local_commits",
+                "uncommitted_changes": true,
+                "stale_approval": null,
+                "blocking_qcs": {"approved": [], "not_approved": [], "errors": []},
+                "commit_caveat": null,
+            })
+        );
+    }
+
+    #[test]
+    fn test_issue_status_report_json_approved() {
+        let thread = report_thread(
+            vec![(&[CommitStatus::Initial, CommitStatus::Approved], true)],
+            false,
+        );
+        let json = report_json(&thread, &GitState::Clean);
+
+        assert_eq!(json["open"], false);
+        assert_eq!(json["qc_status"], "approved");
+        assert_eq!(json["approved_commit"], FIRST);
+        assert_eq!(json["latest_commit"], FIRST);
+        assert_eq!(json["git_status"], "up_to_date");
+        assert_eq!(
+            json["stale_approval"],
+            serde_json::json!({"dirty": true, "commits_after_approval": 0})
+        );
+    }
+
+    #[test]
+    fn test_stale_approval() {
+        let dirty = [PathBuf::from("scripts/model.R")];
+
+        // The approved commit is the latest change of the file
+        let latest = report_thread(
+            vec![
+                (&[CommitStatus::Initial], true),
+                (&[CommitStatus::Approved], true),
+            ],
+            false,
+        );
+        assert_eq!(StaleApproval::from_thread(&latest, &[]), None);
+
+        // Later commits touched the file, and one commit did not
+        let later = report_thread(
+            vec![
+                (&[CommitStatus::Initial, CommitStatus::Approved], true),
+                (&[], true),
+                (&[], false),
+                (&[], true),
+            ],
+            false,
+        );
+        let stale = StaleApproval::from_thread(&later, &[]).unwrap();
+        assert_eq!(
+            stale,
+            StaleApproval {
+                dirty: false,
+                commits_after_approval: 2,
+            }
+        );
+        assert_eq!(stale.to_string(), "2 commits after approval");
+
+        // The working tree has changes to the approved file
+        let stale = StaleApproval::from_thread(&latest, &dirty).unwrap();
+        assert_eq!(
+            stale,
+            StaleApproval {
+                dirty: true,
+                commits_after_approval: 0,
+            }
+        );
+        assert_eq!(stale.to_string(), "uncommitted changes");
+        assert_eq!(
+            StaleApproval::from_thread(&later, &dirty)
+                .unwrap()
+                .to_string(),
+            "2 commits after approval and uncommitted changes"
+        );
+
+        // Changes to an unapproved file are not a stale approval
+        let open = report_thread(vec![(&[CommitStatus::Initial], true)], true);
+        assert_eq!(StaleApproval::from_thread(&open, &dirty), None);
+    }
+
+    #[test]
+    fn test_issue_status_report_json_unapproved_reopened() {
+        // The approval was revoked and the issue reopened, leaving no standing approval
+        let thread = report_thread(
+            vec![
+                (&[CommitStatus::Initial], true),
+                (&[CommitStatus::Notification], true),
+            ],
+            true,
+        );
+        let behind = GitState::Diverged {
+            ahead: vec![ObjectId::from_str(SECOND).unwrap()],
+            behind: vec![ObjectId::from_str(FIRST).unwrap()],
+        };
+        let json = report_json(&thread, &behind);
+
+        assert_eq!(json["open"], true);
+        assert_eq!(json["qc_status"], "awaiting_review");
+        assert_eq!(json["approved_commit"], serde_json::Value::Null);
+        assert_eq!(json["latest_commit"], SECOND);
+        assert_eq!(json["git_status"], "diverged");
+    }
+
+    #[test]
+    fn test_blocking_qc_status_json_sorted() {
+        let mut status = BlockingQCStatus::default();
+        status.approved.insert(3, PathBuf::from("c.R"));
+        status.approved.insert(1, PathBuf::from("a.R"));
+        status
+            .not_approved
+            .insert(2, (PathBuf::from("b.R"), QCStatus::InProgress));
+        status.errors.insert(4, "not found".to_string());
+
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            serde_json::json!({
+                "approved": [
+                    {"issue_number": 1, "file": "a.R"},
+                    {"issue_number": 3, "file": "c.R"},
+                ],
+                "not_approved": [{"issue_number": 2, "file": "b.R", "qc_status": "in_progress"}],
+                "errors": [{"issue_number": 4, "error": "not found"}],
+            })
+        );
+    }
 }
@@ -0,0 +1,254 @@
+use octocrab::models::issues::Issue;
+
+use crate::comment_system::CommentBody;
+use crate::git::{GitFileOps, GitHelpers, GitHubApiError, GitHubWriter};
+
+/// Move QC responsibility for an issue to other people
+#[derive(Debug, Clone)]
+pub struct QCReassign {
+    pub issue: Issue,
+    /// Logins assigned before the change
+    pub old_assignees: Vec<String>,
+    /// Logins assigned after the change
+    pub new_assignees: Vec<String>,
+    /// Why the issue is reassigned
+    pub note: Option<String>,
+}
+
+impl QCReassign {
+    /// Add `add` to and remove `remove` from the issue's current assignees
+    pub fn new(issue: Issue, add: &[String], remove: &[String], note: Option<String>) -> Self {
+        let old_assignees = current_assignees(&issue);
+        for login in remove {
+            if !old_assignees.contains(login) {
+                log::warn!("{login} is not assigned to issue #{}", issue.number);
+            }
+        }
+
+        let mut new_assignees: Vec<String> = old_assignees
+            .iter()
+            .filter(|login| !remove.contains(login))
+            .cloned()
+            .collect();
+        for login in add {
+            if !new_assignees.contains(login) {
+                new_assignees.push(login.clone());
+            }
+        }
+
+        Self {
+            issue,
+            old_assignees,
+            new_assignees,
+            note,
+        }
+    }
+
+    /// Whether the assignees are the same after the change, ignoring their order
+    pub fn is_noop(&self) -> bool {
+        self.old_assignees.len() == self.new_assignees.len()
+            && self
+                .old_assignees
+                .iter()
+                .all(|login| self.new_assignees.contains(login))
+    }
+
+    /// Whether the change removes the last assignee, leaving nobody to QC the issue
+    pub fn leaves_unassigned(&self) -> bool {
+        !self.old_assignees.is_empty() && self.new_assignees.is_empty()
+    }
+
+    fn body(&self) -> String {
+        let metadata = [
+            "## Metadata".to_string(),
+            format!("issue: #{}", self.issue.number),
+            format!("previous assignees: {}", logins(&self.old_assignees)),
+            format!("new assignees: {}", logins(&self.new_assignees)),
+        ];
+
+        let mut body = vec!["# QC Reassigned".to_string()];
+        if let Some(note) = &self.note {
+            body.push(note.clone());
+        }
+        body.push(metadata.join("\n* "));
+        body.join("\n\n")
+    }
+}
+
+impl CommentBody for QCReassign {
+    fn title(&self) -> &str {
+        "QC Reassigned"
+    }
+
+    fn generate_body(&self, _git_info: &(impl GitHelpers + GitFileOps)) -> String {
+        self.body()
+    }
+
+    fn issue(&self) -> &Issue {
+        &self.issue
+    }
+}
+
+/// Logins currently assigned to `issue`
+pub fn current_assignees(issue: &Issue) -> Vec<String> {
+    issue.assignees.iter().map(|a| a.login.clone()).collect()
+}
+
+fn logins(assignees: &[String]) -> String {
+    if assignees.is_empty() {
+        "none".to_string()
+    } else {
+        assignees.join(", ")
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReassignError {
+    #[error("Issue #{0} already has these assignees")]
+    NoChange(u64),
+    #[error("GitHub API error: {0}")]
+    GitHubApiError(#[from] GitHubApiError),
+}
+
+/// Replace the issue's assignees and post a comment documenting the change
+///
+/// Returns the URL of the posted comment.
+pub async fn reassign_issue(
+    reassign: &QCReassign,
+    git_info: &impl GitHubWriter,
+) -> Result<String, ReassignError> {
+    let number = reassign.issue.number;
+    if reassign.is_noop() {
+        return Err(ReassignError::NoChange(number));
+    }
+    if reassign.leaves_unassigned() {
+        log::warn!("Issue #{number} will have no assignees");
+    }
+
+    git_info.guard_issue(&reassign.issue)?;
+    git_info
+        .set_assignees(number, &reassign.new_assignees)
+        .await?;
+    Ok(git_info.post_comment(reassign).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{MockGitHubWriter, RemoteMismatch};
+    use crate::test_utils::create_test_issue;
+
+    fn issue(assignees: &[&str]) -> Issue {
+        let mut issue =
+            create_test_issue("owner", "repo", 3, "src/analysis.R", "", Some(1), "open");
+        issue.assignees = assignees
+            .iter()
+            .map(|login| {
+                let mut user = issue.user.clone();
+                user.login = login.to_string();
+                user
+            })
+            .collect();
+        issue
+    }
+
+    fn strings(logins: &[&str]) -> Vec<String> {
+        logins.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn writer(expected: &[&str]) -> MockGitHubWriter {
+        let expected = strings(expected);
+        let mut writer = MockGitHubWriter::new();
+        writer.expect_guard_issue().times(1).returning(|_| Ok(()));
+        writer
+            .expect_set_assignees()
+            .withf(move |number, assignees| *number == 3 && assignees == expected.as_slice())
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        writer
+            .expect_post_comment::<QCReassign>()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async {
+                    Ok("https://github.com/owner/repo/issues/3#issuecomment-1".to_string())
+                })
+            });
+        writer
+    }
+
+    #[tokio::test]
+    async fn test_reassign_adds_to_unassigned_issue() {
+        let reassign = QCReassign::new(issue(&[]), &strings(&["bob"]), &[], None);
+        assert!(!reassign.leaves_unassigned());
+
+        let url = reassign_issue(&reassign, &writer(&["bob"])).await.unwrap();
+        assert_eq!(url, "https://github.com/owner/repo/issues/3#issuecomment-1");
+    }
+
+    #[tokio::test]
+    async fn test_reassign_removing_last_assignee_leaves_unassigned() {
+        let reassign = QCReassign::new(issue(&["alice"]), &[], &strings(&["alice"]), None);
+        assert!(reassign.leaves_unassigned());
+
+        reassign_issue(&reassign, &writer(&[])).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reassign_without_change_is_rejected() {
+        let reassign = QCReassign::new(
+            issue(&["alice", "bob"]),
+            &strings(&["bob"]),
+            &strings(&["carol"]),
+            None,
+        );
+        assert!(reassign.is_noop());
+
+        // No expectations: any write fails the test
+        let err = reassign_issue(&reassign, &MockGitHubWriter::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ReassignError::NoChange(3)));
+    }
+
+    #[tokio::test]
+    async fn test_reassign_refuses_issue_of_another_repository() {
+        let reassign = QCReassign::new(issue(&[]), &strings(&["bob"]), &[], None);
+        let mut writer = MockGitHubWriter::new();
+        writer.expect_guard_issue().times(1).returning(|issue| {
+            Err(RemoteMismatch {
+                issue_number: issue.number,
+                issue_repository: "github.com/owner/repo".to_string(),
+                remote_repository: "github.com/owner/repo-2".to_string(),
+            }
+            .into())
+        });
+
+        // Neither the assignees nor a comment are written
+        let err = reassign_issue(&reassign, &writer).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ReassignError::GitHubApiError(GitHubApiError::RemoteMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_qc_reassign_body() {
+        let reassign = QCReassign::new(
+            issue(&["alice", "bob"]),
+            &strings(&["carol"]),
+            &strings(&["alice"]),
+            Some("Alice moved to the PK study".to_string()),
+        );
+
+        insta::assert_snapshot!(reassign.body(), @r"
+        # QC Reassigned
+
+        Alice moved to the PK study
+
+        ## Metadata
+        * issue: #3
+        * previous assignees: alice, bob
+        * new assignees: bob, carol
+        ");
+    }
+}
@@ -0,0 +1,215 @@
+//! Warnings raised while gathering the content of a record
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{MilestoneSnapshot, record::RecordError};
+
+/// Timeline events that carry no QC information and are left out of the record on purpose
+const UNRECORDED_EVENTS: &[&str] = &["mentioned", "subscribed", "unsubscribed"];
+
+/// Something the record leaves out or shows degraded, which `--strict` does not tolerate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrictViolation {
+    /// A selected milestone has no ghqc issues, so it is missing from the record
+    EmptyMilestone { milestone: String },
+    /// A user has no display name in the repository, so only their login is shown
+    UnresolvedUser { issue_number: u64, login: String },
+    /// A timeline event of a type the record cannot show was skipped
+    SkippedEvent { issue_number: u64, event: String },
+    /// The file commits of an issue were read from a fallback source
+    CommitHistoryFallback { issue_number: u64, caveat: String },
+}
+
+impl StrictViolation {
+    /// The issue the violation was raised for, `None` for milestone-wide violations
+    pub fn issue_number(&self) -> Option<u64> {
+        match self {
+            Self::EmptyMilestone { .. } => None,
+            Self::UnresolvedUser { issue_number, .. }
+            | Self::SkippedEvent { issue_number, .. }
+            | Self::CommitHistoryFallback { issue_number, .. } => Some(*issue_number),
+        }
+    }
+}
+
+impl fmt::Display for StrictViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyMilestone { milestone } => {
+                write!(f, "milestone '{milestone}' has no ghqc issues")
+            }
+            Self::UnresolvedUser { login, .. } => {
+                write!(f, "no display name found for user '{login}'")
+            }
+            Self::SkippedEvent { event, .. } => {
+                write!(f, "skipped '{event}' event in the timeline")
+            }
+            Self::CommitHistoryFallback { caveat, .. } => {
+                write!(f, "{}", caveat.trim_start_matches("⚠ "))
+            }
+        }
+    }
+}
+
+/// Sink for the warnings raised while gathering the content of a record
+///
+/// Every warning is collected, once, so that strict mode can refuse to produce a record with
+/// any of them.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    violations: Vec<StrictViolation>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a warning, ignoring repeats of one already recorded
+    pub fn warn(&mut self, violation: StrictViolation) {
+        if !self.violations.contains(&violation) {
+            log::debug!("Record warning: {violation}");
+            self.violations.push(violation);
+        }
+    }
+
+    /// Record the milestones of `snapshot` without any issues
+    pub fn check_snapshot(&mut self, snapshot: &MilestoneSnapshot) {
+        for milestone in &snapshot.milestones {
+            if !snapshot
+                .issues
+                .iter()
+                .any(|i| i.milestone == milestone.title)
+            {
+                self.warn(StrictViolation::EmptyMilestone {
+                    milestone: milestone.title.clone(),
+                });
+            }
+        }
+    }
+
+    /// Record a timeline event skipped by the record, unless it is never recorded
+    pub(crate) fn skipped_event(&mut self, issue_number: u64, event: &str) {
+        if !UNRECORDED_EVENTS.contains(&event) {
+            self.warn(StrictViolation::SkippedEvent {
+                issue_number,
+                event: event.to_string(),
+            });
+        }
+    }
+
+    pub fn violations(&self) -> &[StrictViolation] {
+        &self.violations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Fail with every collected warning, for records generated in strict mode
+    pub fn ensure_strict(&self) -> Result<(), RecordError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(RecordError::Strict(StrictViolations(
+                self.violations.clone(),
+            )))
+        }
+    }
+}
+
+/// The warnings that stopped a strict record from being generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrictViolations(pub Vec<StrictViolation>);
+
+impl fmt::Display for StrictViolations {
+    /// Milestone-wide violations first, then the violations of each issue in issue order
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut by_issue: BTreeMap<Option<u64>, Vec<&StrictViolation>> = BTreeMap::new();
+        for violation in &self.0 {
+            by_issue
+                .entry(violation.issue_number())
+                .or_default()
+                .push(violation);
+        }
+
+        write!(
+            f,
+            "Strict mode: {} warning(s) found, no record was generated",
+            self.0.len()
+        )?;
+        for (issue_number, violations) in by_issue {
+            match issue_number {
+                Some(number) => write!(f, "\n  Issue #{number}:")?,
+                None => write!(f, "\n  Milestones:")?,
+            }
+            for violation in violations {
+                write!(f, "\n    - {violation}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for StrictViolations {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_ignore_repeats_and_unrecorded_events() {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.skipped_event(3, "subscribed");
+        diagnostics.skipped_event(3, "renamed");
+        diagnostics.skipped_event(3, "renamed");
+        diagnostics.warn(StrictViolation::UnresolvedUser {
+            issue_number: 3,
+            login: "octocat".to_string(),
+        });
+
+        assert_eq!(
+            diagnostics.violations(),
+            &[
+                StrictViolation::SkippedEvent {
+                    issue_number: 3,
+                    event: "renamed".to_string()
+                },
+                StrictViolation::UnresolvedUser {
+                    issue_number: 3,
+                    login: "octocat".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strict_violations_grouped_by_issue() {
+        let mut diagnostics = Diagnostics::new();
+        assert!(diagnostics.ensure_strict().is_ok());
+
+        diagnostics.warn(StrictViolation::UnresolvedUser {
+            issue_number: 12,
+            login: "octocat".to_string(),
+        });
+        diagnostics.warn(StrictViolation::CommitHistoryFallback {
+            issue_number: 4,
+            caveat: "⚠ commits read from the default branch".to_string(),
+        });
+        diagnostics.warn(StrictViolation::EmptyMilestone {
+            milestone: "v2.0".to_string(),
+        });
+        diagnostics.skipped_event(12, "renamed");
+
+        let err = diagnostics.ensure_strict().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Strict mode: 4 warning(s) found, no record was generated\n  \
+            Milestones:\n    - milestone 'v2.0' has no ghqc issues\n  \
+            Issue #4:\n    - commits read from the default branch\n  \
+            Issue #12:\n    \
+            - no display name found for user 'octocat'\n    \
+            - skipped 'renamed' event in the timeline"
+        );
+    }
+}
@@ -1,13 +1,18 @@
 use regex::Regex;
 use scraper::{Html, Selector};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+use crate::TlsSettings;
+use crate::utils::{format_file_size, sanitize_file_name};
+
 // Markdown image regex
 static MD_IMG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"!\[([^\]]*)\]\(([^)]+)\)").expect("Invalid markdown image regex")
@@ -18,9 +23,35 @@ static HTML_IMG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"<img[^>]+src=["']([^"']+)["'][^>]*/?>"#).expect("Invalid HTML image regex")
 });
 
+// Markdown link regex - also matches images, which are skipped by checking for a leading `!`
+static MD_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[([^\]]*)\]\(([^)\s]+)\)").expect("Invalid markdown link regex")
+});
+
+// GitHub file attachments:
+// - github.com/user-attachments/files/<id>/<name>
+// - <host>/<owner>/<repo>/files/<id>/<name> (legacy github.com and GHES)
+// - <host>/storage/user/<id>/files/<uuid> (GHES storage)
+static ATTACHMENT_URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^https?://[^/]+/(?:user-attachments/files/\d+/[^/?#]+|storage/user/\d+/files/[^?#]+|[^/]+/[^/]+/files/\d+/[^/?#]+)",
+    )
+    .expect("Invalid attachment url regex")
+});
+
+// GitHub image uploads, which are served without a file extension
+static IMAGE_URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^https?://(?:private-user-images\.githubusercontent\.com/|[^/]+/user-attachments/assets/)")
+        .expect("Invalid image url regex")
+});
+
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "svg", "webp", "bmp"];
+
 // Scraper selectors for HTML parsing
 static IMG_SELECTOR: LazyLock<Selector> =
     LazyLock::new(|| Selector::parse("img").expect("Invalid img selector"));
+static LINK_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("a[href]").expect("Invalid link selector"));
 
 /// Maximum download size (50 MB)
 const MAX_DOWNLOAD_SIZE: usize = 50 * 1024 * 1024;
@@ -49,18 +80,27 @@ pub trait HttpDownloader: Send + Sync {
 /// - 30 second connection timeout
 /// - 60 second read timeout
 /// - 50 MB maximum download size
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct UreqDownloader {
     agent: ureq::Agent,
 }
 
 impl UreqDownloader {
     pub fn new() -> Self {
+        Self::with_tls(&TlsSettings::default())
+    }
+
+    /// A downloader verifying certificates with `tls`, e.g. to download images from a GitHub
+    /// Enterprise server with an internal certificate authority
+    pub fn with_tls(tls: &TlsSettings) -> Self {
+        let mut builder = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(30))
+            .timeout_read(Duration::from_secs(60));
+        if let Some(tls_config) = tls.client_config() {
+            builder = builder.tls_config(tls_config);
+        }
         Self {
-            agent: ureq::AgentBuilder::new()
-                .timeout_connect(Duration::from_secs(30))
-                .timeout_read(Duration::from_secs(60))
-                .build(),
+            agent: builder.build(),
         }
     }
 }
@@ -107,6 +147,49 @@ impl HttpDownloader for UreqDownloader {
     }
 }
 
+/// Maximum number of downloads in flight at once while building a record
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 8;
+
+/// Run `download` for each of `items` with at most `limit` in flight, returning the results in
+/// the order of `items`
+///
+/// Downloads block on HTTP round trips, so they run on scoped threads. Every item is attempted,
+/// so all failures can be reported together.
+pub fn download_concurrently<T: Sync, R: Send>(
+    items: &[T],
+    limit: usize,
+    download: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let workers = limit.clamp(1, items.len().max(1));
+
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, download(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("download worker panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 #[cfg(test)]
 use mockall::automock;
 
@@ -136,6 +219,104 @@ impl IssueImage {
         );
         downloader.download(&self.html, &self.path)
     }
+
+    /// Download this image, reusing the copy in `cache` when there is one
+    ///
+    /// Downloaded images are stored in the cache; anything that is not an image, e.g. an error
+    /// page served for an expired signed URL, is not.
+    pub fn download_cached(
+        &self,
+        downloader: &impl HttpDownloader,
+        cache: Option<&ImageCache>,
+    ) -> Result<(), DownloadError> {
+        let Some(cache) = cache else {
+            return self.download(downloader);
+        };
+
+        let entry = cache.entry(&self.text);
+        if !cache.refresh && is_cached_image(&entry) {
+            log::debug!("Using cached image {} for {}", entry.display(), self.text);
+            std::fs::copy(&entry, &self.path)?;
+            return Ok(());
+        }
+
+        self.download(downloader)?;
+        if let Err(e) = cache.store(&entry, &self.path) {
+            log::warn!("Failed to cache image {}: {}", self.text, e);
+        }
+        Ok(())
+    }
+}
+
+/// Persistent cache of the issue images downloaded for records
+///
+/// Images are keyed by the SHA-256 of their markdown URL: the signed URL an image is downloaded
+/// from changes on every fetch, while the uploaded asset behind the markdown URL does not.
+#[derive(Debug, Clone)]
+pub struct ImageCache {
+    dir: PathBuf,
+    refresh: bool,
+}
+
+impl ImageCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            refresh: false,
+        }
+    }
+
+    /// Download every image again, replacing the cached copies
+    pub fn refreshing(mut self) -> Self {
+        self.refresh = true;
+        self
+    }
+
+    /// Cache file of the image with markdown URL `text_url`, e.g. `<dir>/<sha256>.png`
+    fn entry(&self, text_url: &str) -> PathBuf {
+        let hash = format!("{:x}", Sha256::digest(text_url.as_bytes()));
+        let extension = Path::new(text_url.split(['?', '#']).next().unwrap_or(text_url))
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .filter(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+            .unwrap_or_else(|| "img".to_string());
+        self.dir.join(format!("{hash}.{extension}"))
+    }
+
+    /// Copy the downloaded image at `path` to the cache `entry`, if it is an image
+    fn store(&self, entry: &Path, path: &Path) -> std::io::Result<()> {
+        let bytes = std::fs::read(path)?;
+        if !is_image(&bytes) {
+            log::debug!("Not caching {}: not an image", path.display());
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(entry, bytes)
+    }
+}
+
+/// Whether `entry` holds a cached image, removing it when it does not look like one
+fn is_cached_image(entry: &Path) -> bool {
+    match std::fs::read(entry) {
+        Ok(bytes) if is_image(&bytes) => true,
+        Ok(_) => {
+            log::debug!("Discarding invalid cached image {}", entry.display());
+            let _ = std::fs::remove_file(entry);
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether `bytes` start like one of the supported image formats
+fn is_image(bytes: &[u8]) -> bool {
+    let text_start = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]).to_lowercase();
+    bytes.starts_with(b"\x89PNG\r\n\x1a\n")
+        || bytes.starts_with(&[0xFF, 0xD8, 0xFF])
+        || bytes.starts_with(b"GIF8")
+        || (bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP"))
+        || bytes.starts_with(b"BM")
+        || (text_start.trim_start().starts_with('<') && text_start.contains("<svg"))
 }
 
 /// Create IssueImage structs from markdown text and HTML content
@@ -306,10 +487,223 @@ pub fn replace_images_with_typst(
     result
 }
 
+/// What a link in an issue or comment points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// An image, embedded in the record with `#image()`
+    Image,
+    /// A non-image file uploaded to GitHub, downloaded and listed with its checksum
+    Attachment,
+    /// Any other link, left as is
+    External,
+}
+
+/// Classify a link URL as an image, a GitHub file attachment, or an external link
+pub fn classify_link(url: &str) -> LinkKind {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let has_image_extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false);
+
+    if has_image_extension || IMAGE_URL_REGEX.is_match(url) {
+        LinkKind::Image
+    } else if ATTACHMENT_URL_REGEX.is_match(url) {
+        LinkKind::Attachment
+    } else {
+        LinkKind::External
+    }
+}
+
+/// Represents a non-image file attached to an issue or comment
+///
+/// Like [`IssueImage`], the text URL is used for lookups and the HTML URL for downloading.
+#[derive(Debug, Clone)]
+pub struct IssueAttachment {
+    /// The link text, or the file name from the URL when the link has no text
+    pub name: String,
+    /// The URL as it appears in the markdown text
+    pub text: String,
+    /// The URL from the HTML. Used for downloading since it may contain auth
+    pub html: String,
+    /// The local path where the attachment should be downloaded
+    pub path: PathBuf,
+}
+
+impl IssueAttachment {
+    /// Download this attachment and checksum the downloaded file
+    pub fn download(
+        &self,
+        downloader: &impl HttpDownloader,
+    ) -> Result<DownloadedAttachment, DownloadError> {
+        log::debug!(
+            "Downloading attachment {} to {}...",
+            self.html,
+            self.path.display()
+        );
+        downloader.download(&self.html, &self.path)?;
+        let bytes = std::fs::read(&self.path)?;
+
+        // Attachment URLs (e.g. GHES `storage/user/<id>/files/<uuid>`) need not end in the file's
+        // extension, so recognize PDFs by their magic bytes and give them a `.pdf` extension to
+        // load them as context files
+        let is_pdf = bytes.starts_with(b"%PDF-");
+        let mut path = self.path.clone();
+        if is_pdf && !has_pdf_extension(&path) {
+            let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+            file_name.push(".pdf");
+            path.set_file_name(file_name);
+            std::fs::rename(&self.path, &path)?;
+        }
+
+        Ok(DownloadedAttachment {
+            name: self.name.clone(),
+            path,
+            size: bytes.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+            is_pdf,
+        })
+    }
+}
+
+/// An attachment downloaded to the staging directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadedAttachment {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+    /// Whether the downloaded file starts with the PDF magic bytes
+    pub is_pdf: bool,
+}
+
+fn has_pdf_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case("pdf"))
+}
+
+impl DownloadedAttachment {
+    /// Human readable file size
+    pub fn display_size(&self) -> String {
+        format_file_size(self.size)
+    }
+}
+
+/// Create IssueAttachment structs from markdown text and HTML content
+///
+/// Maps markdown attachment links to HTML links by position, as [`create_issue_images`] does.
+pub fn create_issue_attachments(
+    markdown: &str,
+    html: Option<&str>,
+    base_download_dir: &Path,
+) -> Vec<IssueAttachment> {
+    let text_links = extract_attachment_links_from_markdown(markdown);
+    let html_urls = html
+        .map(extract_attachment_urls_from_html)
+        .unwrap_or_default();
+
+    text_links
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, text_url))| {
+            let html_url = html_urls
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| text_url.clone());
+
+            let url_file_name = text_url
+                .split(['?', '#'])
+                .next()
+                .and_then(|path| path.rsplit('/').find(|segment| !segment.is_empty()))
+                .unwrap_or("attachment")
+                .to_string();
+
+            // Prefix with a hash of the text URL so attachments with the same name don't collide
+            let mut hasher = DefaultHasher::new();
+            text_url.hash(&mut hasher);
+            let hash = hasher.finish();
+            let filename = format!(
+                "attachment_{:x}_{}",
+                hash,
+                sanitize_file_name(&url_file_name)
+            );
+
+            IssueAttachment {
+                name: if name.trim().is_empty() {
+                    url_file_name
+                } else {
+                    name.trim().to_string()
+                },
+                text: text_url,
+                html: html_url,
+                path: base_download_dir.join(filename),
+            }
+        })
+        .collect()
+}
+
+/// Extract attachment links from markdown content in order of appearance
+///
+/// Returns `(name, url)` pairs for markdown links (`[name](url)`) and HTML anchors
+/// (`<a href="url">name</a>`) classified as [`LinkKind::Attachment`].
+pub fn extract_attachment_links_from_markdown(markdown: &str) -> Vec<(String, String)> {
+    let mut links_with_positions = Vec::new();
+
+    for captures in MD_LINK_REGEX.captures_iter(markdown) {
+        let is_image = captures
+            .get(0)
+            .map(|m| markdown[..m.start()].ends_with('!'))
+            .unwrap_or(false);
+        if is_image {
+            continue;
+        }
+        if let (Some(name), Some(url)) = (captures.get(1), captures.get(2)) {
+            links_with_positions.push((
+                url.start(),
+                name.as_str().to_string(),
+                url.as_str().to_string(),
+            ));
+        }
+    }
+
+    let document = Html::parse_fragment(markdown);
+    for element in document.select(&LINK_SELECTOR) {
+        if let Some(href) = element.value().attr("href") {
+            let pos = markdown
+                .find(&format!("href=\"{}\"", href))
+                .unwrap_or(markdown.len());
+            let name = element.text().collect::<String>();
+            links_with_positions.push((pos, name, href.to_string()));
+        }
+    }
+
+    links_with_positions.sort_by_key(|(pos, _, _)| *pos);
+
+    links_with_positions
+        .into_iter()
+        .filter(|(_, _, url)| classify_link(url) == LinkKind::Attachment)
+        .map(|(_, name, url)| (name, url))
+        .collect()
+}
+
+/// Extract attachment URLs from HTML content in order of appearance
+pub fn extract_attachment_urls_from_html(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let urls = document
+        .select(&LINK_SELECTOR)
+        .filter_map(|element| element.value().attr("href"))
+        .filter(|href| classify_link(href) == LinkKind::Attachment)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    log::debug!("Extracted {} attachment URLs from HTML", urls.len());
+    urls
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum DownloadError {
     #[error("HTTP request failed: {0}")]
-    Ureq(#[from] ureq::Error),
+    Ureq(Box<ureq::Error>),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("File too large: {url} is {size} bytes (max: {max_size})")]
@@ -320,6 +714,12 @@ pub enum DownloadError {
     },
 }
 
+impl From<ureq::Error> for DownloadError {
+    fn from(error: ureq::Error) -> Self {
+        Self::Ureq(Box::new(error))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -550,4 +950,283 @@ And finally an HTML image without dimensions:
         // Snapshot test the normalized results
         insta::assert_debug_snapshot!(normalized_images);
     }
+
+    #[test]
+    fn test_classify_link() {
+        assert_eq!(
+            classify_link(
+                "https://github.com/user-attachments/assets/6df1bc0a-d30d-4b21-b4ac-51c297e43741"
+            ),
+            LinkKind::Image
+        );
+        assert_eq!(
+            classify_link(
+                "https://private-user-images.githubusercontent.com/12345/plot.png?jwt=abc"
+            ),
+            LinkKind::Image
+        );
+        assert_eq!(
+            classify_link("https://example.com/figure.PNG"),
+            LinkKind::Image
+        );
+        assert_eq!(
+            classify_link("https://github.com/user-attachments/files/18234567/results.csv"),
+            LinkKind::Attachment
+        );
+        assert_eq!(
+            classify_link("https://github.com/owner/repo/files/12345/report.pdf"),
+            LinkKind::Attachment
+        );
+        assert_eq!(
+            classify_link(
+                "https://ghes.example.com/storage/user/42/files/7a1c2d3e-4f56-7890-abcd-ef0123456789"
+            ),
+            LinkKind::Attachment
+        );
+        assert_eq!(
+            classify_link("https://github.com/owner/repo/blob/main/files/data.csv"),
+            LinkKind::External
+        );
+        assert_eq!(
+            classify_link("https://example.com/docs/spec.pdf"),
+            LinkKind::External
+        );
+    }
+
+    #[test]
+    fn test_extract_attachment_links_markdown() {
+        let markdown = r#"
+Results attached: [results.csv](https://github.com/user-attachments/files/18234567/results.csv)
+![Plot](https://github.com/user-attachments/assets/6df1bc0a-d30d-4b21-b4ac-51c297e43741)
+See the [spec](https://example.com/docs/spec.pdf) for details.
+[report.pdf](https://ghes.example.com/storage/user/42/files/7a1c2d3e-4f56-7890-abcd-ef0123456789)[](https://github.com/owner/repo/files/12345/model.rds)
+"#;
+
+        let links = extract_attachment_links_from_markdown(markdown);
+        assert_eq!(
+            links,
+            vec![
+                (
+                    "results.csv".to_string(),
+                    "https://github.com/user-attachments/files/18234567/results.csv".to_string()
+                ),
+                (
+                    "report.pdf".to_string(),
+                    "https://ghes.example.com/storage/user/42/files/7a1c2d3e-4f56-7890-abcd-ef0123456789"
+                        .to_string()
+                ),
+                (
+                    String::new(),
+                    "https://github.com/owner/repo/files/12345/model.rds".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_attachment_links_html_anchor() {
+        let markdown = r#"
+<img src="https://github.com/user-attachments/assets/6df1bc0a-d30d-4b21-b4ac-51c297e43741" />
+<a href="https://github.com/user-attachments/files/18234567/results.csv">results.csv</a>
+<a href="https://example.com">Example</a>
+"#;
+
+        let links = extract_attachment_links_from_markdown(markdown);
+        assert_eq!(
+            links,
+            vec![(
+                "results.csv".to_string(),
+                "https://github.com/user-attachments/files/18234567/results.csv".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_extract_attachment_urls_html() {
+        let html = r#"
+<p>Results attached: <a href="https://github.com/user-attachments/files/18234567/results.csv">results.csv</a></p>
+<p><a href="https://private-user-images.githubusercontent.com/12345/plot.png?jwt=abc"><img src="https://private-user-images.githubusercontent.com/12345/plot.png?jwt=abc" /></a></p>
+<p>See the <a href="https://example.com/docs/spec.pdf" rel="nofollow">spec</a></p>
+<p><a href="https://ghes.example.com/storage/user/42/files/7a1c2d3e-4f56-7890-abcd-ef0123456789">report.pdf</a></p>
+"#;
+
+        let urls = extract_attachment_urls_from_html(html);
+        assert_eq!(
+            urls,
+            vec![
+                "https://github.com/user-attachments/files/18234567/results.csv",
+                "https://ghes.example.com/storage/user/42/files/7a1c2d3e-4f56-7890-abcd-ef0123456789",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_issue_attachments() {
+        let markdown = "[Final report](https://github.com/owner/repo/files/12345/report%20final.pdf) and [](https://github.com/user-attachments/files/18234567/results.csv)";
+        let html = r#"<p><a href="https://github.com/owner/repo/files/12345/report%20final.pdf?token=abc">Final report</a></p>"#;
+
+        let attachments = create_issue_attachments(markdown, Some(html), Path::new("/tmp/staging"));
+        assert_eq!(attachments.len(), 2);
+
+        assert_eq!(attachments[0].name, "Final report");
+        assert_eq!(
+            attachments[0].html,
+            "https://github.com/owner/repo/files/12345/report%20final.pdf?token=abc"
+        );
+        let file_name = attachments[0].path.file_name().unwrap().to_string_lossy();
+        assert!(file_name.starts_with("attachment_"));
+        assert!(file_name.ends_with("_report%20final.pdf"));
+
+        // No link text or HTML: falls back to the file name and text URL
+        assert_eq!(attachments[1].name, "results.csv");
+        assert_eq!(attachments[1].html, attachments[1].text);
+    }
+
+    #[test]
+    fn test_issue_attachment_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let attachment = IssueAttachment {
+            name: "results.csv".to_string(),
+            text: "https://github.com/user-attachments/files/18234567/results.csv".to_string(),
+            html: "https://github.com/user-attachments/files/18234567/results.csv".to_string(),
+            path: dir.path().join("attachment_1_results.csv"),
+        };
+
+        let mut downloader = MockHttpDownloader::new();
+        downloader
+            .expect_download()
+            .returning(|_, path| Ok(std::fs::write(path, b"abc")?));
+
+        let downloaded = attachment.download(&downloader).unwrap();
+        assert_eq!(downloaded.size, 3);
+        assert_eq!(
+            downloaded.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(downloaded.display_size(), "3 B");
+        assert!(!downloaded.is_pdf);
+        assert_eq!(downloaded.path, attachment.path);
+    }
+
+    #[test]
+    fn test_issue_attachment_download_detects_extensionless_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let url =
+            "https://ghes.example.com/storage/user/42/files/7a1c2d3e-5f60-4b8a-9c1d-2e3f4a5b6c7d";
+        let attachment = IssueAttachment {
+            name: "report".to_string(),
+            text: url.to_string(),
+            html: url.to_string(),
+            path: dir
+                .path()
+                .join("attachment_1_7a1c2d3e-5f60-4b8a-9c1d-2e3f4a5b6c7d"),
+        };
+
+        let mut downloader = MockHttpDownloader::new();
+        downloader
+            .expect_download()
+            .returning(|_, path| Ok(std::fs::write(path, b"%PDF-1.7\n")?));
+
+        let downloaded = attachment.download(&downloader).unwrap();
+        assert!(downloaded.is_pdf);
+        assert_eq!(
+            downloaded.path,
+            dir.path()
+                .join("attachment_1_7a1c2d3e-5f60-4b8a-9c1d-2e3f4a5b6c7d.pdf")
+        );
+        assert!(downloaded.path.exists());
+        assert!(!attachment.path.exists());
+    }
+
+    /// Downloader that tracks how many downloads are in flight at once
+    #[derive(Default)]
+    struct InFlightDownloader {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        calls: AtomicUsize,
+    }
+
+    impl HttpDownloader for InFlightDownloader {
+        fn download(&self, url: &str, _path: &Path) -> Result<(), DownloadError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if url.ends_with("broken.png") {
+                Err(DownloadError::Io(std::io::Error::other("connection reset")))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_download_concurrently_respects_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let images: Vec<IssueImage> = (0..30)
+            .map(|i| {
+                let name = if i % 10 == 3 { "broken" } else { "ok" };
+                IssueImage {
+                    text: format!("https://example.com/{i}/{name}.png"),
+                    html: format!("https://example.com/{i}/{name}.png"),
+                    path: dir.path().join(format!("image_{i}.png")),
+                }
+            })
+            .collect();
+        let downloader = InFlightDownloader::default();
+
+        let results = download_concurrently(&images, 4, |image| image.download(&downloader));
+
+        assert_eq!(downloader.calls.load(Ordering::SeqCst), 30);
+        let max_in_flight = downloader.max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight <= 4, "{max_in_flight} downloads in flight");
+        assert!(max_in_flight > 1, "downloads ran sequentially");
+
+        // Results keep the order of the images, and every failure is reported
+        let failed: Vec<usize> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.is_err())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(failed, vec![3, 13, 23]);
+    }
+
+    #[test]
+    fn test_image_cache_skips_non_images() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let staging_dir = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(cache_dir.path());
+        let image = IssueImage {
+            text: "https://github.com/user-attachments/assets/1a2b".to_string(),
+            html: "https://private-user-images.githubusercontent.com/1.png?jwt=abc".to_string(),
+            path: staging_dir.path().join("image_1.png"),
+        };
+
+        // An error page served for an expired signed URL is not cached
+        let mut downloader = MockHttpDownloader::new();
+        downloader
+            .expect_download()
+            .times(2)
+            .returning(|_, path| Ok(std::fs::write(path, b"<html>expired</html>")?));
+        image.download_cached(&downloader, Some(&cache)).unwrap();
+        image.download_cached(&downloader, Some(&cache)).unwrap();
+
+        let mut downloader = MockHttpDownloader::new();
+        downloader
+            .expect_download()
+            .times(1)
+            .returning(|_, path| Ok(std::fs::write(path, b"GIF89a...")?));
+        image.download_cached(&downloader, Some(&cache)).unwrap();
+        image.download_cached(&downloader, Some(&cache)).unwrap();
+
+        let entries: Vec<_> = std::fs::read_dir(cache_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].ends_with(".img"), "{entries:?}");
+    }
 }
@@ -1,34 +1,49 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf, absolute},
 };
 
-use chrono;
+use chrono::{self, DateTime, Utc};
 use lazy_static::lazy_static;
-use octocrab::models::{Milestone, issues::Issue};
+use octocrab::models::Milestone;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::{
-    ChecklistSummary, Configuration, DiskCache, GitCommitOps, GitHubReader, GitRepository,
-    GitStatusOps, RepoUser, get_git_status, get_issue_comments, get_issue_events, get_repo_users,
-    git::{GitComment, GitState},
+    ChecklistSummary, Configuration, DiskCache, GitCommitOps, GitHelpers, GitHubReader,
+    GitRepository, GitStatusOps, QCCoverage, QCStatus, StaleApproval, UserDirectory,
+    get_git_status,
+    git::{GitComment, GitStatus, SigningKeys, behind_file_report},
     issue::IssueThread,
-    qc_status::{QCStatus, analyze_issue_checklists},
+    issue_template::error_chain,
+    milestone_snapshot::{IssueSnapshot, SnapshotThread},
+    qc_status::analyze_issue_checklists,
+    time_log::{TimeSummary, format_hours, time_entries},
     utils::EnvProvider,
 };
 
 // Re-export submodules
+mod diagnostics;
 mod images;
+mod preview;
 mod render;
+mod split;
 mod tables;
 mod typst;
 
 // Re-export public items from submodules
+pub use diagnostics::{Diagnostics, StrictViolation, StrictViolations};
 pub use typst::{escape_typst, format_markdown};
 // Template functions - used by tera templates, not directly by Rust code
-pub use images::{HttpDownloader, UreqDownloader};
-pub use render::{ContextPosition, QCContext, create_staging_dir, render};
+pub use images::{HttpDownloader, ImageCache, UreqDownloader};
+pub use preview::{get_issue_information, issue_preview_file_name, issue_record_preview};
+pub use render::{
+    ContextPosition, QCContext, RecordFormat, create_staging_dir, render, render_each,
+};
+pub use split::{
+    MilestoneRecord, RecordIndexEntry, milestone_record_file_name, record_by_milestone,
+    record_index,
+};
 #[allow(unused_imports)]
 pub use tables::{
     create_milestone_df, insert_breaks, render_issue_summary_table_rows,
@@ -38,6 +53,12 @@ pub use tables::{
 /// Built-in Typst template embedded at compile time
 pub const BUILTIN_TEMPLATE: &str = include_str!("../templates/record.typ");
 
+/// Record section of a single issue, included by the built-in templates as `issue_detail.typ`
+pub const ISSUE_DETAIL_PARTIAL: &str = include_str!("../templates/issue_detail.typ");
+
+/// Standalone document previewing the record section of a single issue
+const ISSUE_PREVIEW_TEMPLATE: &str = include_str!("../templates/issue_preview.typ");
+
 /// Load template from configuration or fall back to built-in
 ///
 /// Checks if a custom template exists at the configuration's record_path.
@@ -57,12 +78,29 @@ pub fn load_template(configuration: &Configuration) -> Result<String, RecordErro
     }
 }
 
+/// Parse a record template with the built-in partials and functions, returning why it cannot
+/// be used
+pub(crate) fn check_template(template: &str) -> Result<(), String> {
+    match create_tera_with_template(template) {
+        Ok(_) => Ok(()),
+        Err(RecordError::Template(e)) => Err(error_chain(&e)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 /// Create a Tera instance with the given template
+///
+/// The built-in partials are registered alongside it, so custom templates can
+/// `{% include "issue_detail.typ" %}` as well.
 fn create_tera_with_template(template: &str) -> Result<Tera, RecordError> {
     let mut tera = Tera::default();
 
-    tera.add_raw_template("record.typ", template)
-        .map_err(RecordError::Template)?;
+    tera.add_raw_templates([
+        ("issue_detail.typ", ISSUE_DETAIL_PARTIAL),
+        ("issue_preview.typ", ISSUE_PREVIEW_TEMPLATE),
+        ("record.typ", template),
+    ])
+    .map_err(RecordError::Template)?;
 
     // Register custom functions from tables module
     tera.register_function(
@@ -73,6 +111,10 @@ fn create_tera_with_template(template: &str) -> Result<Tera, RecordError> {
         "render_issue_summary_table_rows",
         tables::render_issue_summary_table_rows,
     );
+    tera.register_function(
+        "render_file_versions_table_rows",
+        tables::render_file_versions_table_rows,
+    );
 
     Ok(tera)
 }
@@ -83,45 +125,39 @@ lazy_static! {
     };
 }
 
+/// Date shown on generated records, overridable with `GHQC_RECORD_DATE`
+pub fn record_date(env: &impl EnvProvider) -> String {
+    env.var("GHQC_RECORD_DATE")
+        .unwrap_or_else(|_| chrono::Local::now().format("%B %d, %Y").to_string())
+}
+
+/// Optional content of a record
+#[derive(Debug, Clone, Default)]
+pub struct RecordOptions {
+    /// Only include tables and skip detailed issue content
+    pub only_tables: bool,
+    /// QC coverage of the repository's tracked files, summarized after the milestone tables
+    pub coverage: Option<QCCoverage>,
+    /// Format the record is rendered to, available to templates as `format`
+    pub format: RecordFormat,
+    /// End the record with an appendix of the approved version of each file, per milestone
+    pub appendix: bool,
+}
+
 pub fn record(
     milestones: &[Milestone],
     issues: &HashMap<String, Vec<IssueInformation>>,
     configuration: &Configuration,
     git_info: &impl GitRepository,
     env: &impl EnvProvider,
-    only_tables: bool,
+    options: &RecordOptions,
     staging_dir: impl AsRef<Path>,
 ) -> Result<String, RecordError> {
-    let staging_dir = staging_dir.as_ref();
-    let mut context = Context::new();
-
-    context.insert("repository_name", &escape_typst(git_info.repo()));
-    context.insert(
-        "checklist_name",
-        &escape_typst(&configuration.options.checklist_display_name),
-    );
-
-    if let Ok(author) = env.var("USER") {
-        context.insert("author", &escape_typst(&author));
-    }
-
-    let date = if let Ok(custom_date) = env.var("GHQC_RECORD_DATE") {
-        escape_typst(&custom_date)
-    } else {
-        escape_typst(&chrono::Local::now().format("%B %d, %Y").to_string())
-    };
-    context.insert("date", &date);
-
-    // Copy logo to staging directory and use relative path
-    let logo_path = absolute(configuration.logo_path())?;
-    if logo_path.exists() {
-        if let Some(filename) = logo_path.file_name() {
-            let staging_logo_path = staging_dir.join(filename);
-            std::fs::copy(&logo_path, &staging_logo_path)?;
-            // Use just the filename since Typst runs from staging_dir
-            context.insert("logo_path", &PathBuf::from(filename));
-        }
-    }
+    let issues = issues
+        .iter()
+        .map(|(milestone, issue_list)| (milestone.clone(), disambiguate_titles(issue_list)))
+        .collect::<HashMap<_, _>>();
+    let mut context = document_context(configuration, git_info, env, staging_dir)?;
 
     // Generate milestone dataframe
     let milestone_data = create_milestone_df(milestones, &issues)?;
@@ -137,6 +173,7 @@ pub fn record(
                 .map(|issue_list| MilestoneSection {
                     name: milestone.title.clone(),
                     issues: issue_list.clone(),
+                    has_qc_effort: issue_list.iter().any(|i| i.qc_effort.is_some()),
                 })
         })
         .collect::<Vec<_>>();
@@ -151,7 +188,12 @@ pub fn record(
         &escape_typst(&milestone_names.join(", ")),
     );
 
-    context.insert("only_tables", &only_tables);
+    context.insert("only_tables", &options.only_tables);
+    context.insert("format", options.format.extension());
+    context.insert("appendix", &options.appendix);
+    if let Some(coverage) = &options.coverage {
+        context.insert("coverage", &CoverageSection::new(coverage));
+    }
 
     // Load template from configuration or use built-in
     let template = load_template(configuration)?;
@@ -162,73 +204,159 @@ pub fn record(
         .map_err(RecordError::Template)?)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MilestoneRow {
-    pub name: String,
-    pub description: String,
-    pub status: String,
-    pub issues: String,
+/// Context shared by every record document: repository, author, date and logo
+fn document_context(
+    configuration: &Configuration,
+    git_info: &impl GitRepository,
+    env: &impl EnvProvider,
+    staging_dir: impl AsRef<Path>,
+) -> Result<Context, RecordError> {
+    let staging_dir = staging_dir.as_ref();
+    let mut context = Context::new();
+
+    context.insert("repository_name", &escape_typst(git_info.repo()));
+    context.insert(
+        "checklist_name",
+        &escape_typst(&configuration.options.checklist_display_name),
+    );
+
+    if let Ok(author) = env.var("USER") {
+        context.insert("author", &escape_typst(&author));
+    }
+
+    context.insert("date", &escape_typst(&record_date(env)));
+
+    // Copy logo to staging directory and use relative path
+    let logo_path = absolute(configuration.logo_path())?;
+    if logo_path.exists() {
+        if let Some(filename) = logo_path.file_name() {
+            let staging_logo_path = staging_dir.join(filename);
+            std::fs::copy(&logo_path, &staging_logo_path)?;
+            // Use just the filename since Typst runs from staging_dir
+            context.insert("logo_path", &PathBuf::from(filename));
+        }
+    }
+
+    Ok(context)
 }
 
-/// Fetch all issues for milestones and return as HashMap
-pub async fn fetch_milestone_issues(
-    milestones: &[Milestone],
-    git_info: &(impl GitHubReader + GitRepository),
-) -> Result<HashMap<String, Vec<Issue>>, RecordError> {
-    let mut issue_map = HashMap::new();
+/// Tell apart the issues of a milestone that share a title, e.g. an old closed issue and the
+/// issue that replaced it
+///
+/// Shared titles get the issue number appended, and each of those issues is marked as
+/// superseded by the next one created after it.
+fn disambiguate_titles(issues: &[IssueInformation]) -> Vec<IssueInformation> {
+    let mut by_title: HashMap<&str, Vec<&IssueInformation>> = HashMap::new();
+    for issue in issues {
+        by_title
+            .entry(issue.title.as_str())
+            .or_default()
+            .push(issue);
+    }
 
-    for milestone in milestones {
-        let issues = git_info
-            .get_issues(Some(milestone.number as u64))
-            .await
-            .map_err(RecordError::GitHubApi)?;
-        if issues.is_empty() {
-            log::warn!(
-                "Milestone '{}' has no ghqc issues, omitting from record",
-                milestone.title
-            );
-        } else {
-            issue_map.insert(milestone.title.clone(), issues);
+    let mut duplicated = HashSet::new();
+    let mut superseded_by = HashMap::new();
+    for group in by_title.values_mut().filter(|group| group.len() > 1) {
+        group.sort_by(|a, b| (&a.created_at, a.number).cmp(&(&b.created_at, b.number)));
+        for pair in group.windows(2) {
+            if pair[1].created_at > pair[0].created_at {
+                superseded_by.insert(pair[0].number, pair[1].number);
+            }
+        }
+        duplicated.extend(group.iter().map(|issue| issue.number));
+    }
+
+    issues
+        .iter()
+        .cloned()
+        .map(|mut issue| {
+            if duplicated.contains(&issue.number) {
+                issue.title = format!("{} (\\#{})", issue.title, issue.number);
+                issue.superseded_by = superseded_by.get(&issue.number).copied();
+            }
+            issue
+        })
+        .collect()
+}
+
+/// QC coverage as rendered in the record, with paths escaped for Typst
+#[derive(Debug, Clone, Serialize)]
+struct CoverageSection {
+    scope: String,
+    files_in_scope: usize,
+    approved: usize,
+    in_progress: usize,
+    percentage: String,
+    uncovered: Vec<String>,
+}
+
+impl CoverageSection {
+    fn new(coverage: &QCCoverage) -> Self {
+        Self {
+            scope: escape_typst(&coverage.scope_display()),
+            files_in_scope: coverage.files_in_scope,
+            approved: coverage.approved,
+            in_progress: coverage.in_progress,
+            percentage: format!("{:.1}", coverage.percentage()),
+            uncovered: coverage
+                .uncovered
+                .iter()
+                .map(|f| escape_typst(&f.display().to_string()))
+                .collect(),
         }
     }
+}
 
-    Ok(issue_map)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneRow {
+    pub name: String,
+    pub description: String,
+    pub status: String,
+    pub issues: String,
 }
 
+/// Build detailed issue information for the record, grouped by milestone name
+///
+/// Milestones without any issues in `threads` are omitted from the record. Anything left out
+/// of or degraded in the issue information is reported to `diagnostics`.
 pub async fn get_milestone_issue_information(
-    milestone_issues: &HashMap<String, Vec<Issue>>,
+    threads: &[SnapshotThread<'_>],
     cache: Option<&DiskCache>,
-    git_info: &(impl GitHubReader + GitCommitOps + GitStatusOps + GitRepository),
+    signing_keys: &SigningKeys,
+    git_info: &(impl GitHubReader + GitCommitOps + GitStatusOps + GitRepository + GitHelpers),
     http_downloader: &impl images::HttpDownloader,
     staging_dir: impl AsRef<Path>,
+    diagnostics: &mut Diagnostics,
 ) -> Result<HashMap<String, Vec<IssueInformation>>, RecordError> {
     let staging_dir = staging_dir.as_ref();
-    let repo_users = get_repo_users(cache, git_info).await?;
+    let logins: Vec<&str> = threads
+        .iter()
+        .flat_map(|t| snapshot_logins(t.snapshot))
+        .collect();
+    let mut users = UserDirectory::load(cache, git_info).await?;
+    users.resolve(logins, cache, git_info).await;
     let git_status = get_git_status(git_info)?;
-    let git_state = git_status.state.clone();
-    let dirty_files = git_status.dirty.clone();
-
-    let mut res = HashMap::new();
-    for (milestone_name, issues) in milestone_issues {
-        let mut issue_information = Vec::new();
-
-        for issue in issues {
-            let info = create_issue_information(
-                issue,
-                milestone_name,
-                &repo_users,
-                &git_state,
-                &dirty_files,
-                cache,
-                git_info,
-                http_downloader,
-                staging_dir,
-            )
-            .await?;
-            issue_information.push(info);
-        }
+    let image_cache = cache.map(DiskCache::image_cache);
+    let context = IssueInformationContext {
+        users: &users,
+        git_status: &git_status,
+        signing_keys,
+        image_cache: image_cache.as_ref(),
+        staging_dir,
+    };
 
-        res.insert(milestone_name.to_string(), issue_information);
+    let mut res: HashMap<String, Vec<IssueInformation>> = HashMap::new();
+    for issue_thread in threads {
+        let info = create_issue_information(
+            issue_thread,
+            &context,
+            git_info,
+            http_downloader,
+            diagnostics,
+        )?;
+        res.entry(issue_thread.snapshot.milestone.clone())
+            .or_default()
+            .push(info);
     }
 
     Ok(res)
@@ -239,7 +367,7 @@ pub async fn get_milestone_issue_information(
 /// Returns true if any comment has images in the body but no HTML content,
 /// indicating we need to re-fetch from the API to get HTML with JWT URLs.
 /// Note: Issue HTML is handled separately since issues are fetched differently than comments.
-fn needs_html_for_jwt_urls(comments: &[GitComment]) -> bool {
+pub(crate) fn needs_html_for_jwt_urls(comments: &[GitComment]) -> bool {
     comments.iter().any(|comment| {
         let has_images = !images::extract_image_urls_from_markdown(&comment.body).is_empty();
         let lacks_html = comment.html.is_none();
@@ -256,62 +384,62 @@ fn needs_html_for_jwt_urls(comments: &[GitComment]) -> bool {
     })
 }
 
-/// Create detailed issue information from an issue
-pub async fn create_issue_information(
-    issue: &Issue,
-    milestone_name: &str,
-    repo_users: &[RepoUser],
-    git_status: &GitState,
-    dirty_files: &[PathBuf],
-    cache: Option<&DiskCache>,
-    git_info: &(impl GitHubReader + GitCommitOps),
+/// What the issues of a record are detailed with, shared by all of them
+pub struct IssueInformationContext<'a> {
+    pub users: &'a UserDirectory,
+    pub git_status: &'a GitStatus,
+    pub signing_keys: &'a SigningKeys,
+    pub image_cache: Option<&'a ImageCache>,
+    /// Directory the images of the issues are downloaded to
+    pub staging_dir: &'a Path,
+}
+
+/// Create detailed issue information from an issue of a milestone snapshot
+pub fn create_issue_information(
+    snapshot_thread: &SnapshotThread<'_>,
+    context: &IssueInformationContext<'_>,
+    git_info: &(impl GitCommitOps + GitHelpers),
     http_downloader: &impl images::HttpDownloader,
-    staging_dir: &Path,
+    diagnostics: &mut Diagnostics,
 ) -> Result<IssueInformation, RecordError> {
-    // Get comments and check if we need HTML for JWT URLs
-    let mut comments = get_issue_comments(issue, cache, git_info).await?;
-
-    // Check if we need HTML for JWT URLs
-    if needs_html_for_jwt_urls(&comments) {
-        log::info!(
-            "Issue #{} contains images but cached comments lack HTML - re-fetching with HTML",
-            issue.number
-        );
-
-        // Invalidate cache for this issue to force fresh fetch with HTML
-        if let Some(cache) = cache {
-            let cache_key = format!("issue_{}", issue.number);
-            if let Err(e) = cache.invalidate(&["issues", "comments"], &cache_key) {
-                log::warn!(
-                    "Failed to invalidate cache for issue #{}: {}",
-                    issue.number,
-                    e
-                );
-            }
-        }
-
-        // Re-fetch comments (will now include HTML since cache is invalidated)
-        comments = git_info.get_issue_comments(issue).await?;
-
-        // Verify we got HTML content for JWT URLs
-        if needs_html_for_jwt_urls(&comments) {
-            return Err(RecordError::HtmlRequiredForJwtUrls {
-                issue_number: issue.number,
-            });
-        }
-
-        log::debug!(
-            "Re-fetched {} comments with HTML for issue #{}",
-            comments.len(),
-            issue.number
-        );
+    let IssueInformationContext {
+        users,
+        git_status,
+        signing_keys,
+        image_cache,
+        staging_dir,
+    } = *context;
+    let IssueSnapshot {
+        milestone: milestone_name,
+        issue,
+        comments,
+        events,
+    } = snapshot_thread.snapshot;
+
+    // Comments with images need HTML for the JWT URLs used to download them
+    if needs_html_for_jwt_urls(comments) {
+        return Err(RecordError::HtmlRequiredForJwtUrls {
+            issue_number: issue.number,
+        });
     }
 
-    let issue_thread = IssueThread::from_issue_comments(issue, &comments, git_info, cache)?;
+    let issue_thread = &snapshot_thread.thread;
     let is_closed = matches!(issue.state, octocrab::models::IssueState::Closed);
 
-    // QC Status
-    let qc_status = QCStatus::determine_status(&issue_thread).to_string();
+    // QC Status, with the reason an obsolete issue was taken out of scope
+    let qc_status = match &snapshot_thread.status {
+        QCStatus::Obsoleted(obsoletion) => {
+            format!("{}: {}", snapshot_thread.status, obsoletion.reason)
+        }
+        status => match StaleApproval::from_thread(issue_thread, &git_status.dirty) {
+            Some(stale) => format!("{status} (stale: {stale})"),
+            None => status.to_string(),
+        },
+    };
+
+    // QC time logged in the comments
+    let time_summary = TimeSummary::from_entries(&time_entries(comments));
+    let qc_effort = (!time_summary.is_empty()).then(|| format_hours(time_summary.total_minutes));
 
     // Checklist Summary
     let checklist_summaries = analyze_issue_checklists(issue.body.as_deref());
@@ -320,18 +448,36 @@ pub async fn create_issue_information(
 
     // Git Status for this specific file
     let file_commits = issue_thread.file_commits();
-    let mut git_status_str = git_status.format_for_file(&file_commits);
-    if dirty_files.contains(&issue_thread.file) {
+    let behind = match behind_file_report(git_info, git_status, &issue_thread.current_file()) {
+        Ok(behind) => behind,
+        Err(e) => {
+            log::debug!("Could not determine which remote commits modify the file: {e}");
+            None
+        }
+    };
+    let mut git_status_str = git_status
+        .state
+        .format_for_file_behind(&file_commits, behind.as_ref());
+    if git_status.is_dirty(&issue_thread.file) {
         git_status_str.push_str(" (file has uncommitted local changes)");
     }
+    if let Some(caveat) = issue_thread.commit_retrieval.caveat() {
+        // The warning sign is left out of the PDF, where the record font may not have it
+        git_status_str.push_str(&format!(" ({})", caveat.trim_start_matches("⚠ ")));
+        diagnostics.warn(StrictViolation::CommitHistoryFallback {
+            issue_number: issue.number,
+            caveat,
+        });
+    }
+
+    let mut users = UserNames {
+        directory: users,
+        issue_number: issue.number,
+        diagnostics,
+    };
 
     // Created by (with name lookup)
-    let created_by = repo_users
-        .iter()
-        .find(|user| user.login == issue.user.login)
-        .and_then(|user| user.name.as_ref())
-        .map(|name| format!("{} ({})", name, issue.user.login))
-        .unwrap_or_else(|| issue.user.login.clone());
+    let created_by = users.display(&issue.user.login);
 
     // QCers (with name lookup)
     let qcer = if issue.assignees.is_empty() {
@@ -340,34 +486,13 @@ pub async fn create_issue_information(
         issue
             .assignees
             .iter()
-            .map(|assignee| {
-                repo_users
-                    .iter()
-                    .find(|user| user.login == assignee.login)
-                    .and_then(|user| user.name.as_ref())
-                    .map(|name| format!("{} ({})", name, assignee.login))
-                    .unwrap_or_else(|| assignee.login.clone())
-            })
+            .map(|assignee| users.display(&assignee.login))
             .collect()
     };
 
-    // Get issue events (used for both closer detection and event timeline)
-    let events = get_issue_events(issue, cache, git_info).await?;
-
     // Issue closer (with name lookup)
     let closed_by = if is_closed {
-        match get_issue_closer_username(&events) {
-            Some(closer_login) => {
-                let closer_display = repo_users
-                    .iter()
-                    .find(|user| user.login == closer_login)
-                    .and_then(|user| user.name.as_ref())
-                    .map(|name| format!("{} ({})", name, closer_login))
-                    .unwrap_or(closer_login);
-                Some(closer_display)
-            }
-            None => None,
-        }
+        get_issue_closer_username(events).map(|closer_login| users.display(&closer_login))
     } else {
         None
     };
@@ -377,10 +502,25 @@ pub async fn create_issue_information(
     let closed_at = issue
         .closed_at
         .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string());
+    let approved_at =
+        format_approval_date(issue_thread.approved_at, issue_thread.approval_recorded_at);
+    let approved_commit = issue_thread.approved_commit().map(|commit| commit.hash);
+    let approved_file_url = approved_commit
+        .map(|commit| git_info.file_content_url(&commit.to_string(), Path::new(&issue.title)));
 
     // Commit information
     let initial_qc_commit = issue_thread.initial_commit().to_string();
+    // Latest QC activity, even after an approval; qc_status reports whether the approval stands
     let latest_qc_commit = issue_thread.latest_commit().hash.to_string();
+    let commit_signatures = issue_thread
+        .qc_commit_signatures(git_info, signing_keys)
+        .into_iter()
+        .map(|s| CommitSignatureInfo {
+            role: escape_typst(s.label()),
+            commit: escape_typst(&s.commit.to_string()),
+            signature_status: escape_typst(&s.status.to_string()),
+        })
+        .collect();
 
     // Create IssueImage structs for all images in the issue and comments
     // Images are downloaded to staging_dir for use during Typst rendering
@@ -394,32 +534,34 @@ pub async fn create_issue_information(
     }
 
     // Create IssueImages from each comment
-    for comment in &comments {
+    for comment in comments {
         let comment_images =
             images::create_issue_images(&comment.body, comment.html.as_deref(), staging_dir);
         all_issue_images.extend(comment_images);
     }
 
+    // The same image can appear in several comments, e.g. when quoted in a reply
+    let mut seen = HashSet::new();
+    all_issue_images.retain(|issue_image| seen.insert(issue_image.text.clone()));
+
     log::debug!(
         "Created {} IssueImages for issue #{}",
         all_issue_images.len(),
         issue.number
     );
 
-    // Download all images sequentially
-    let download_results: Vec<_> = all_issue_images
-        .iter()
-        .map(|issue_image| {
-            let result = issue_image.download(http_downloader);
-            (issue_image.clone(), result)
-        })
-        .collect();
+    // Download all images, a bounded number at a time
+    let download_results = images::download_concurrently(
+        &all_issue_images,
+        images::MAX_CONCURRENT_DOWNLOADS,
+        |issue_image| issue_image.download_cached(http_downloader, image_cache),
+    );
 
     // Build URL-to-path map from successful downloads and collect failures
     let mut image_url_map = HashMap::new();
     let mut failed_downloads = Vec::new();
 
-    for (issue_image, result) in download_results {
+    for (issue_image, result) in all_issue_images.into_iter().zip(download_results) {
         match result {
             Ok(_) => {
                 // Map text URL to filename only (Typst runs from staging_dir)
@@ -434,7 +576,49 @@ pub async fn create_issue_information(
         }
     }
 
-    // Fail loudly if any image downloads failed
+    // Non-image attachments are downloaded alongside the images so they can be checksummed
+    let mut all_issue_attachments = Vec::new();
+    if let Some(body_text) = &issue.body {
+        all_issue_attachments.extend(images::create_issue_attachments(
+            body_text,
+            issue.body_html.as_deref(),
+            staging_dir,
+        ));
+    }
+    for comment in comments {
+        all_issue_attachments.extend(images::create_issue_attachments(
+            &comment.body,
+            comment.html.as_deref(),
+            staging_dir,
+        ));
+    }
+    let mut seen = HashSet::new();
+    all_issue_attachments.retain(|issue_attachment| seen.insert(issue_attachment.text.clone()));
+
+    let attachment_results = images::download_concurrently(
+        &all_issue_attachments,
+        images::MAX_CONCURRENT_DOWNLOADS,
+        |issue_attachment| issue_attachment.download(http_downloader),
+    );
+
+    let mut attachment_map = HashMap::new();
+    for (issue_attachment, result) in all_issue_attachments.into_iter().zip(attachment_results) {
+        match result {
+            Ok(downloaded) => {
+                attachment_map.insert(issue_attachment.text, downloaded);
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to download attachment {}: {}",
+                    issue_attachment.html,
+                    e
+                );
+                failed_downloads.push(format!("{}: {}", issue_attachment.html, e));
+            }
+        }
+    }
+
+    // Fail loudly if any image or attachment downloads failed
     if !failed_downloads.is_empty() {
         return Err(RecordError::MultipleImageDownloadsFailed {
             failures: failed_downloads,
@@ -445,17 +629,37 @@ pub async fn create_issue_information(
     let body = issue
         .body
         .as_ref()
-        .map(|b| format_markdown(b, 4, &image_url_map))
+        .map(|b| append_attachment_list(format_markdown(b, 4, &image_url_map), b, &attachment_map))
         .unwrap_or_else(|| "No description provided.".to_string());
 
     // Format comments as header-body pairs
-    let formatted_comments = format_comments(&comments, repo_users, &image_url_map);
+    let formatted_comments = format_comments(comments, &mut users, &image_url_map, &attachment_map);
+
+    let mut attachments = attachment_map
+        .into_values()
+        .map(|a| AttachmentInfo {
+            name: escape_typst(&a.name),
+            file_name: a
+                .path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: a.size,
+            sha256: a.sha256,
+            is_pdf: a.is_pdf,
+        })
+        .collect::<Vec<_>>();
+    attachments.sort_by(|a, b| a.file_name.cmp(&b.file_name));
 
     // Format events timeline
-    let formatted_events = format_events(&events, repo_users);
+    let formatted_events = format_events(events, &mut users);
 
-    // Create combined timeline from formatted events and comment headers
-    let timeline = create_combined_timeline(&formatted_events, &formatted_comments);
+    // Commits that changed the file during QC
+    let formatted_commits = format_file_commits(issue_thread, git_info);
+
+    // Create combined timeline from formatted events, comment headers and file commits
+    let timeline =
+        create_combined_timeline(&formatted_events, &formatted_comments, &formatted_commits);
 
     Ok(IssueInformation {
         title: escape_typst(&issue.title),
@@ -466,10 +670,36 @@ pub async fn create_issue_information(
         qcer: qcer.into_iter().map(|q| escape_typst(&q)).collect(),
         qc_status: escape_typst(&qc_status),
         checklist_summary: escape_typst(&checklist_summary),
+        inline_checklist: issue_thread
+            .inline_checklist
+            .as_ref()
+            .map(|file| escape_typst(file)),
+        relevant_files: issue_thread
+            .relevant_files
+            .iter()
+            .map(|relevant| RecordRelevantFile {
+                file: escape_typst(&relevant.file.display().to_string()),
+                kind: escape_typst(&relevant.kind),
+                justification: relevant.justification.as_deref().map(escape_typst),
+            })
+            .collect(),
         git_status: escape_typst(&git_status_str),
         initial_qc_commit: escape_typst(&initial_qc_commit),
         latest_qc_commit: escape_typst(&latest_qc_commit),
+        commit_signatures,
+        attachments,
+        qc_effort,
+        qc_round: issue_thread.rounds.current_round,
+        rounds_to_approval: issue_thread.rounds.rounds_to_approval,
+        approved_at: approved_at.map(|a| escape_typst(&a)),
+        unapproved: issue_thread
+            .unapproval
+            .as_ref()
+            .map(|unapproval| escape_typst(&unapproval.to_string())),
+        approved_commit_sha: approved_commit.map(|commit| commit.to_string()),
+        approved_file_url,
         issue_url: escape_typst(&issue.html_url.to_string()),
+        superseded_by: None,
         state: escape_typst(&if is_closed { "Closed" } else { "Open" }.to_string()),
         closed_by: closed_by.map(|c| escape_typst(&c)),
         closed_at: closed_at.map(|c| escape_typst(&c)),
@@ -479,7 +709,24 @@ pub async fn create_issue_information(
             .into_iter()
             .map(|e| escape_typst(&e))
             .collect(),
-        timeline: timeline.into_iter().map(|t| escape_typst(&t)).collect(),
+        timeline,
+    })
+}
+
+/// When an approval took effect, e.g. `2024-05-02 (recorded 2024-05-06)` for an approval
+/// recorded after the fact with an effective date
+fn format_approval_date(
+    approved_at: Option<DateTime<Utc>>,
+    recorded_at: Option<DateTime<Utc>>,
+) -> Option<String> {
+    let approved_at = approved_at?;
+    Some(match recorded_at {
+        Some(recorded_at) => format!(
+            "{} (recorded {})",
+            approved_at.format("%Y-%m-%d"),
+            recorded_at.format("%Y-%m-%d")
+        ),
+        None => approved_at.format("%Y-%m-%d %H:%M:%S").to_string(),
     })
 }
 
@@ -493,10 +740,34 @@ pub struct IssueInformation {
     pub qcer: Vec<String>,
     pub qc_status: String,
     pub checklist_summary: String,
+    /// File name of the one-off checklist the issue was created with
+    pub inline_checklist: Option<String>,
+    /// Files listed as relevant in the issue body
+    #[serde(default)]
+    pub relevant_files: Vec<RecordRelevantFile>,
     pub git_status: String,
     pub initial_qc_commit: String,
     pub latest_qc_commit: String,
+    pub commit_signatures: Vec<CommitSignatureInfo>,
+    pub attachments: Vec<AttachmentInfo>,
+    /// Total QC time logged on the issue, e.g. `1.5h`
+    pub qc_effort: Option<String>,
+    /// QC round the issue is in
+    pub qc_round: u32,
+    /// Round in which the standing approval was given
+    pub rounds_to_approval: Option<u32>,
+    /// When the standing approval took effect, and when it was recorded if that was later
+    pub approved_at: Option<String>,
+    /// Latest withdrawal of approval, with the commits it was made at
+    #[serde(default)]
+    pub unapproved: Option<String>,
+    /// Full SHA of the commit the standing approval was given on
+    pub approved_commit_sha: Option<String>,
+    /// Link to the file's contents at the approved commit
+    pub approved_file_url: Option<String>,
     pub issue_url: String,
+    /// Number of the issue created later in the milestone for the same file
+    pub superseded_by: Option<u64>,
     pub state: String,
     pub closed_by: Option<String>,
     pub closed_at: Option<String>,
@@ -506,39 +777,151 @@ pub struct IssueInformation {
     pub timeline: Vec<String>,
 }
 
+/// Signature state of one of an issue's QC commits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSignatureInfo {
+    pub role: String,
+    pub commit: String,
+    pub signature_status: String,
+}
+
+/// A file listed as relevant in an issue body, with why it is relevant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordRelevantFile {
+    pub file: String,
+    /// e.g. `Gating QC` or `Relevant File`
+    pub kind: String,
+    pub justification: Option<String>,
+}
+
+/// A non-image file attached to an issue or one of its comments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub name: String,
+    /// File name of the downloaded attachment within the staging directory
+    pub file_name: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Whether the attachment is a PDF, from its contents rather than its URL
+    pub is_pdf: bool,
+}
+
+/// PDF attachments of the milestones' issues, as context files to append after the record
+///
+/// Attachments are ordered by milestone, then issue, matching the order of the record.
+pub fn pdf_attachment_contexts(
+    milestones: &[Milestone],
+    issues: &HashMap<String, Vec<IssueInformation>>,
+    staging_dir: impl AsRef<Path>,
+) -> Vec<QCContext> {
+    let staging_dir = staging_dir.as_ref();
+
+    milestones
+        .iter()
+        .filter_map(|milestone| issues.get(&milestone.title))
+        .flatten()
+        .flat_map(|issue| &issue.attachments)
+        .filter(|attachment| attachment.is_pdf)
+        .map(|attachment| {
+            QCContext::new(
+                staging_dir.join(&attachment.file_name),
+                ContextPosition::Append,
+            )
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MilestoneSection {
     pub name: String,
     pub issues: Vec<IssueInformation>,
+    /// Whether any issue in the milestone has logged QC time
+    pub has_qc_effort: bool,
 }
 
-/// Create combined timeline from formatted events and comment headers, sorted chronologically
+/// Create combined timeline from formatted events, comment headers and file commits, sorted
+/// chronologically
+///
+/// Items are sorted by the date and time they start with (`YYYY-MM-DD HH:MM:SS`, UTC), with
+/// items of unknown time last. The items are escaped for Typst, and commits are set in italics
+/// to stand apart from the issue's activity.
 pub(crate) fn create_combined_timeline(
     formatted_events: &[String],
     formatted_comments: &[(String, String)],
+    formatted_commits: &[String],
 ) -> Vec<String> {
     let mut timeline_items = Vec::new();
 
     // Add formatted events (they already have timestamp and description)
-    timeline_items.extend(formatted_events.iter().cloned());
+    timeline_items.extend(
+        formatted_events
+            .iter()
+            .map(|event| (event.clone(), escape_typst(event))),
+    );
 
     // Add comment headers (the .0 elements which have timestamp and author)
     // Lowercase "Comment" to "comment" for timeline consistency
+    timeline_items.extend(formatted_comments.iter().map(|(header, _)| {
+        let header = header.replace(" - Comment by ", " - commented by ");
+        let item = escape_typst(&header);
+        (header, item)
+    }));
+
     timeline_items.extend(
-        formatted_comments
+        formatted_commits
             .iter()
-            .map(|(header, _)| header.replace(" - Comment by ", " - commented by ")),
+            .map(|commit| (commit.clone(), format!("_{}_", escape_typst(commit)))),
     );
 
-    // Sort by the timestamp at the beginning of each string (YYYY-MM-DD HH:MM:SS format)
-    timeline_items.sort_by(|a, b| {
-        // Extract timestamp from the beginning of each string
-        let timestamp_a = a.split(" - ").next().unwrap_or("");
-        let timestamp_b = b.split(" - ").next().unwrap_or("");
-        timestamp_a.cmp(timestamp_b)
+    // Sort by the timestamp at the beginning of each item; the sort is stable, so items of the
+    // same second keep the order events, comments, commits
+    timeline_items.sort_by_key(|(text, _)| {
+        let timestamp = text
+            .split(" - ")
+            .next()
+            .and_then(|t| chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%d %H:%M:%S").ok());
+        (timestamp.is_none(), timestamp)
     });
 
-    timeline_items
+    timeline_items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Format the commits of the issue that changed its file as timeline items, e.g.
+/// `2024-04-12 09:31:02 - commit abc1234 modified scripts/analysis.R: 'fix covariate handling' by Jane Doe`
+///
+/// Commits whose time cannot be read are left out.
+pub(crate) fn format_file_commits(
+    issue_thread: &IssueThread,
+    git_info: &impl GitCommitOps,
+) -> Vec<String> {
+    issue_thread
+        .commits
+        .iter()
+        .filter(|commit| commit.file_changed)
+        .filter_map(|commit| {
+            let committed_at = match git_info.commit_time(&commit.hash) {
+                Ok(time) => time,
+                Err(e) => {
+                    log::debug!("Could not read the time of commit {}: {e}", commit.hash);
+                    return None;
+                }
+            };
+            let author = git_info
+                .commit_author(&commit.hash)
+                .map(|author| author.name)
+                .unwrap_or_else(|_| "Unknown author".to_string());
+            let hash = commit.hash.to_string();
+
+            Some(format!(
+                "{} - commit {} modified {}: '{}' by {}",
+                committed_at.format("%Y-%m-%d %H:%M:%S"),
+                &hash[..7],
+                issue_thread.file.display(),
+                commit.message.lines().next().unwrap_or_default().trim(),
+                author
+            ))
+        })
+        .collect()
 }
 
 /// Extract the username of who closed the issue from pre-fetched events
@@ -563,8 +946,51 @@ pub(crate) fn get_issue_closer_username(events: &[serde_json::Value]) -> Option<
         })
 }
 
+/// Logins of the users shown in an issue's record section: its author, assignees, commenters and
+/// the actors of its events
+pub(crate) fn snapshot_logins(snapshot: &IssueSnapshot) -> impl Iterator<Item = &str> {
+    let event_logins = snapshot.events.iter().flat_map(|event| {
+        ["actor", "assignee", "assigner"]
+            .into_iter()
+            .filter_map(|field| {
+                event
+                    .get(field)
+                    .and_then(|user| user.get("login"))
+                    .and_then(|login| login.as_str())
+            })
+    });
+
+    std::iter::once(snapshot.issue.user.login.as_str())
+        .chain(snapshot.issue.assignees.iter().map(|a| a.login.as_str()))
+        .chain(snapshot.comments.iter().map(|c| c.author_login.as_str()))
+        .chain(event_logins)
+}
+
+/// Display names of the users of an issue, reporting users without one to the diagnostics
+pub(crate) struct UserNames<'a> {
+    pub(crate) directory: &'a UserDirectory,
+    pub(crate) issue_number: u64,
+    pub(crate) diagnostics: &'a mut Diagnostics,
+}
+
+impl UserNames<'_> {
+    /// `Name (login)` for a user with a display name, otherwise the login
+    pub(crate) fn display(&mut self, login: &str) -> String {
+        match self.directory.display_name(login) {
+            Some(display) => display,
+            None => {
+                self.diagnostics.warn(StrictViolation::UnresolvedUser {
+                    issue_number: self.issue_number,
+                    login: login.to_string(),
+                });
+                login.to_string()
+            }
+        }
+    }
+}
+
 /// Format events timeline as bullet points
-pub(crate) fn format_events(events: &[serde_json::Value], repo_users: &[RepoUser]) -> Vec<String> {
+pub(crate) fn format_events(events: &[serde_json::Value], users: &mut UserNames) -> Vec<String> {
     let mut formatted_events = Vec::new();
 
     for event in events {
@@ -583,16 +1009,9 @@ pub(crate) fn format_events(events: &[serde_json::Value], repo_users: &[RepoUser
             .and_then(|login| login.as_str())
             .unwrap_or("Unknown user");
 
-        // Look up display name for actor
-        let actor_display = repo_users
-            .iter()
-            .find(|user| user.login == actor_login)
-            .and_then(|user| user.name.as_ref())
-            .map(|name| format!("{} ({})", name, actor_login))
-            .unwrap_or_else(|| actor_login.to_string());
-
         let formatted_event = match event_type {
             "milestoned" => {
+                let actor_display = users.display(actor_login);
                 let milestone_title = event
                     .get("milestone")
                     .and_then(|m| m.get("title"))
@@ -617,20 +1036,8 @@ pub(crate) fn format_events(events: &[serde_json::Value], repo_users: &[RepoUser
                     .and_then(|l| l.as_str())
                     .unwrap_or(actor_login); // Fallback to actor if assigner is not available
 
-                let assignee_display = repo_users
-                    .iter()
-                    .find(|user| user.login == assignee_login)
-                    .and_then(|user| user.name.as_ref())
-                    .map(|name| format!("{} ({})", name, assignee_login))
-                    .unwrap_or_else(|| assignee_login.to_string());
-
-                // Look up display name for assigner
-                let assigner_display = repo_users
-                    .iter()
-                    .find(|user| user.login == assigner_login)
-                    .and_then(|user| user.name.as_ref())
-                    .map(|name| format!("{} ({})", name, assigner_login))
-                    .unwrap_or_else(|| assigner_login.to_string());
+                let assignee_display = users.display(assignee_login);
+                let assigner_display = users.display(assigner_login);
 
                 let formatted_message = format!(
                     "{} - {} assigned by {}",
@@ -647,16 +1054,28 @@ pub(crate) fn format_events(events: &[serde_json::Value], repo_users: &[RepoUser
                     .unwrap_or("Unknown label");
                 format!(
                     "{} - added label '{}' by {}",
-                    created_at, label_name, actor_display
+                    created_at,
+                    label_name,
+                    users.display(actor_login)
                 )
             }
             "closed" => {
-                format!("{} - closed by {}", created_at, actor_display)
+                format!("{} - closed by {}", created_at, users.display(actor_login))
             }
             "reopened" => {
-                format!("{} - reopened by {}", created_at, actor_display)
+                format!(
+                    "{} - reopened by {}",
+                    created_at,
+                    users.display(actor_login)
+                )
+            }
+            _ => {
+                // Skip other event types
+                users
+                    .diagnostics
+                    .skipped_event(users.issue_number, event_type);
+                continue;
             }
-            _ => continue, // Skip other event types
         };
 
         formatted_events.push(formatted_event);
@@ -668,19 +1087,15 @@ pub(crate) fn format_events(events: &[serde_json::Value], repo_users: &[RepoUser
 /// Format comments as header-body pairs
 pub(crate) fn format_comments(
     comments: &[GitComment],
-    repo_users: &[RepoUser],
+    users: &mut UserNames,
     image_url_map: &HashMap<String, PathBuf>,
+    attachment_map: &HashMap<String, images::DownloadedAttachment>,
 ) -> Vec<(String, String)> {
     let mut formatted_comments = Vec::new();
 
     for comment in comments {
         // Look up display name
-        let author_display = repo_users
-            .iter()
-            .find(|user| user.login == comment.author_login)
-            .and_then(|user| user.name.as_ref())
-            .map(|name| format!("{} ({})", name, comment.author_login))
-            .unwrap_or_else(|| comment.author_login.clone());
+        let author_display = users.display(&comment.author_login);
 
         // Format timestamp and header
         let created_at = comment.created_at.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -691,7 +1106,11 @@ pub(crate) fn format_comments(
         );
 
         // Format comment body (min level 4 since it will be under #### header in template)
-        let body = format_markdown(&comment.body, 4, image_url_map);
+        let body = append_attachment_list(
+            format_markdown(&comment.body, 4, image_url_map),
+            &comment.body,
+            attachment_map,
+        );
 
         formatted_comments.push((header, body));
     }
@@ -699,6 +1118,35 @@ pub(crate) fn format_comments(
     formatted_comments
 }
 
+/// Append a list of the attachments linked in `markdown`, with their size and SHA-256, to the
+/// formatted Typst content
+fn append_attachment_list(
+    formatted: String,
+    markdown: &str,
+    attachment_map: &HashMap<String, images::DownloadedAttachment>,
+) -> String {
+    let mut seen = HashSet::new();
+    let items = images::extract_attachment_links_from_markdown(markdown)
+        .into_iter()
+        .filter(|(_, url)| seen.insert(url.clone()))
+        .filter_map(|(_, url)| attachment_map.get(&url))
+        .map(|a| {
+            format!(
+                "- {} ({}, SHA-256: `{}`)",
+                escape_typst(&a.name),
+                a.display_size(),
+                a.sha256
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if items.is_empty() {
+        formatted
+    } else {
+        format!("{formatted}\n\n*Attachments:*\n{}", items.join("\n"))
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum RecordError {
     #[error("IO Error: {0}")]
@@ -712,7 +1160,7 @@ pub enum RecordError {
     #[error("QC Status Error: {0}")]
     QCStatus(#[from] crate::qc_status::QCStatusError),
     #[error("Git Status Error: {0}")]
-    GitStatus(#[from] crate::git::GitStatusError),
+    GitStatus(Box<crate::git::GitStatusError>),
     #[error("Render Error: {0}")]
     Render(#[from] render::RenderError),
     #[error("Image download failed for URL {url}: {error}")]
@@ -725,6 +1173,14 @@ pub enum RecordError {
         "Unable to fetch HTML content for JWT URL extraction in issue #{issue_number}. Images detected but GitHub API did not provide body_html field."
     )]
     HtmlRequiredForJwtUrls { issue_number: u64 },
+    #[error(transparent)]
+    Strict(#[from] StrictViolations),
+}
+
+impl From<crate::git::GitStatusError> for RecordError {
+    fn from(error: crate::git::GitStatusError) -> Self {
+        Self::GitStatus(Box::new(error))
+    }
 }
 
 #[cfg(test)]
@@ -732,11 +1188,15 @@ mod tests {
     use super::*;
     use crate::{
         GitCommitOps,
-        git::{GitComment, GitCommit, GitFileOpsError, GitHubApiError},
+        git::{
+            GitComment, GitCommit, GitFileOpsError, GitHubApiError, GitState, RepoUser,
+            SignatureStatus,
+        },
         record::images::DownloadError,
         test_utils::create_test_issue,
     };
     use gix::ObjectId;
+    use octocrab::models::issues::Issue;
     use std::{path::Path, str::FromStr};
 
     struct TestGitInfo {
@@ -767,6 +1227,13 @@ mod tests {
             Ok(self.commits.iter().map(|c| c.commit.to_string()).collect())
         }
 
+        fn files_by_last_commit(
+            &self,
+            _branch: &Option<String>,
+        ) -> Result<Vec<crate::FileLastCommit>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
         fn get_branches_containing_commit(
             &self,
             _commit: &ObjectId,
@@ -780,32 +1247,97 @@ mod tests {
         ) -> Result<Option<String>, GitFileOpsError> {
             Ok(None)
         }
-    }
 
-    impl GitHubReader for TestGitInfo {
-        async fn get_milestones(&self) -> Result<Vec<Milestone>, GitHubApiError> {
-            Ok(Vec::new())
+        fn find_merge_commit(
+            &self,
+            _target_commit: &ObjectId,
+        ) -> Result<Option<ObjectId>, GitFileOpsError> {
+            Ok(None)
         }
 
-        async fn get_issues(&self, _milestone: Option<u64>) -> Result<Vec<Issue>, GitHubApiError> {
-            Ok(Vec::new())
+        fn signature_status(
+            &self,
+            _commit: &ObjectId,
+            _keys: &SigningKeys,
+        ) -> Result<SignatureStatus, GitFileOpsError> {
+            Ok(SignatureStatus::Unsigned)
         }
 
-        async fn get_issue(&self, _issue_number: u64) -> Result<Issue, GitHubApiError> {
-            Err(GitHubApiError::NoApi)
+        fn commit_time(
+            &self,
+            _commit: &ObjectId,
+        ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+            Ok(chrono::DateTime::UNIX_EPOCH)
         }
 
-        async fn get_assignees(&self) -> Result<Vec<String>, GitHubApiError> {
-            Ok(Vec::new())
+        fn commit_author(&self, _commit: &ObjectId) -> Result<crate::GitAuthor, GitFileOpsError> {
+            Ok(crate::GitAuthor {
+                name: "Test Author".to_string(),
+                email: "test@example.com".to_string(),
+            })
         }
 
-        async fn get_user_details(&self, username: &str) -> Result<RepoUser, GitHubApiError> {
-            Ok(RepoUser {
+        fn is_shallow(&self) -> bool {
+            false
+        }
+    }
+
+    impl GitHelpers for TestGitInfo {
+        fn file_content_url(&self, git_ref: &str, file: &Path) -> String {
+            format!(
+                "https://github.com/owner/repo/blob/{git_ref}/{}",
+                file.display()
+            )
+        }
+
+        fn commit_comparison_url(&self, current: &ObjectId, previous: &ObjectId) -> String {
+            format!("https://github.com/owner/repo/compare/{previous}..{current}")
+        }
+
+        fn issue_url(&self, issue_number: u64) -> String {
+            format!("https://github.com/owner/repo/issues/{issue_number}")
+        }
+    }
+
+    impl GitHubReader for TestGitInfo {
+        async fn get_milestones(&self) -> Result<Vec<Milestone>, GitHubApiError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_issues(&self, _milestone: Option<u64>) -> Result<Vec<Issue>, GitHubApiError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_issue(&self, _issue_number: u64) -> Result<Issue, GitHubApiError> {
+            Err(GitHubApiError::NoApi)
+        }
+
+        async fn get_assignees(&self) -> Result<Vec<String>, GitHubApiError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_user_details(&self, username: &str) -> Result<RepoUser, GitHubApiError> {
+            Ok(RepoUser {
                 login: username.to_string(),
                 name: None,
             })
         }
 
+        async fn get_user_permission(
+            &self,
+            _login: &str,
+        ) -> Result<crate::git::Permission, GitHubApiError> {
+            Ok(crate::git::Permission::Write)
+        }
+
+        async fn get_team_members(
+            &self,
+            _org: &str,
+            _team_slug: &str,
+        ) -> Result<Vec<String>, GitHubApiError> {
+            Ok(Vec::new())
+        }
+
         async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
             Ok(Vec::new())
         }
@@ -836,6 +1368,14 @@ mod tests {
         }
     }
 
+    fn clean_git_status() -> GitStatus {
+        GitStatus {
+            remote_commit: ObjectId::null(gix::hash::Kind::Sha1),
+            state: GitState::Clean,
+            dirty: Vec::new(),
+        }
+    }
+
     struct TestDownloader;
 
     impl images::HttpDownloader for TestDownloader {
@@ -888,18 +1428,22 @@ mod tests {
         ];
 
         let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
         let issue_info = create_issue_information(
-            &issue,
-            "v1.0",
-            &repo_users,
-            &GitState::Clean,
-            &[],
-            None,
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::new(repo_users),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
             &git_info,
             &TestDownloader,
-            staging_dir.path(),
+            &mut Diagnostics::new(),
         )
-        .await
         .unwrap();
 
         assert_eq!(
@@ -907,4 +1451,932 @@ mod tests {
             Some("Alice Reviewer (reviewer1)")
         );
     }
+
+    #[tokio::test]
+    async fn create_issue_information_counts_remote_commits_touching_file() {
+        let initial_commit = "1234567890abcdef1234567890abcdef12345678";
+        let remote_touching = "abc123def456789012345678901234567890abcd";
+        let remote_other = "def456789abc012345678901234567890123abcd";
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            3,
+            "src/analysis.R",
+            &format!("git branch: main\ninitial qc commit: {initial_commit}\n"),
+            Some(1),
+            "open",
+        );
+        // Only the commits listed here touch the file
+        let git_info = TestGitInfo {
+            comments: Vec::new(),
+            events: Vec::new(),
+            commits: vec![
+                GitCommit {
+                    commit: ObjectId::from_str(remote_touching).unwrap(),
+                    message: "Update analysis".to_string(),
+                },
+                GitCommit {
+                    commit: ObjectId::from_str(initial_commit).unwrap(),
+                    message: "Initial commit".to_string(),
+                },
+            ],
+        };
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let thread = snapshot.thread(&git_info, None).unwrap();
+        let staging_dir = tempfile::tempdir().unwrap();
+        let git_status_for = |state: GitState| {
+            create_issue_information(
+                &thread,
+                &IssueInformationContext {
+                    users: &UserDirectory::default(),
+                    git_status: &GitStatus {
+                        remote_commit: ObjectId::from_str(remote_touching).unwrap(),
+                        state,
+                        dirty: Vec::new(),
+                    },
+                    signing_keys: &SigningKeys::default(),
+                    image_cache: None,
+                    staging_dir: staging_dir.path(),
+                },
+                &git_info,
+                &TestDownloader,
+                &mut Diagnostics::new(),
+            )
+            .unwrap()
+            .git_status
+        };
+
+        let behind = vec![
+            ObjectId::from_str(remote_other).unwrap(),
+            ObjectId::from_str(remote_touching).unwrap(),
+        ];
+        assert_eq!(
+            git_status_for(GitState::Behind(behind)),
+            "Behind remote by 2 commits, 1 touches this file"
+        );
+        assert_eq!(
+            git_status_for(GitState::Behind(vec![
+                ObjectId::from_str(remote_other).unwrap()
+            ])),
+            "Behind remote by 1 commit, none touch this file"
+        );
+        assert_eq!(git_status_for(GitState::Clean), "Up to date");
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_lists_comment_attachments() {
+        struct WritingDownloader;
+
+        impl images::HttpDownloader for WritingDownloader {
+            fn download(&self, url: &str, path: &Path) -> Result<(), DownloadError> {
+                if url.contains("/storage/user/") {
+                    Ok(std::fs::write(path, b"%PDF-1.7\n")?)
+                } else {
+                    Ok(std::fs::write(path, b"abc")?)
+                }
+            }
+        }
+
+        let initial_commit = "1234567890abcdef1234567890abcdef12345678";
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            4,
+            "src/analysis.R",
+            &format!("git branch: main\ninitial qc commit: {}\n", initial_commit),
+            Some(1),
+            "open",
+        );
+
+        let git_info = TestGitInfo {
+            comments: vec![GitComment {
+                body: "Outputs: [results.csv](https://github.com/user-attachments/files/18234567/results.csv) and [report.pdf](https://ghes.example.com/storage/user/42/files/7a1c2d3e-5f60-4b8a-9c1d-2e3f4a5b6c7d)".to_string(),
+                author_login: "reviewer1".to_string(),
+                created_at: chrono::Utc::now(),
+                html: None,
+            }],
+            events: Vec::new(),
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial_commit).unwrap(),
+                message: "Initial commit".to_string(),
+            }],
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &WritingDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        let comment_body = &issue_info.comments[0].1;
+        assert!(comment_body.contains("*Attachments:*"));
+        assert!(comment_body.contains(
+            "- results.csv (3 B, SHA-256: `ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad`)"
+        ));
+        assert_eq!(issue_info.attachments.len(), 2);
+
+        let milestone: Milestone = serde_json::from_str(
+            &std::fs::read_to_string("src/tests/github_api/milestones/v1.0.json").unwrap(),
+        )
+        .unwrap();
+        let issues = HashMap::from([("v1.0".to_string(), vec![issue_info])]);
+        let contexts = pdf_attachment_contexts(&[milestone], &issues, staging_dir.path());
+        assert_eq!(contexts.len(), 1);
+        assert!(
+            contexts[0]
+                .file()
+                .to_string_lossy()
+                .ends_with("_7a1c2d3e-5f60-4b8a-9c1d-2e3f4a5b6c7d.pdf")
+        );
+        assert!(matches!(contexts[0].position(), ContextPosition::Append));
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_downloads_repeated_images_once() {
+        /// Records the URLs it is asked to download, failing those of broken images
+        #[derive(Default)]
+        struct RecordingDownloader(std::sync::Mutex<Vec<String>>);
+
+        impl images::HttpDownloader for RecordingDownloader {
+            fn download(&self, url: &str, _path: &Path) -> Result<(), DownloadError> {
+                self.0.lock().unwrap().push(url.to_string());
+                if url.contains("broken") {
+                    Err(DownloadError::Io(std::io::Error::other("connection reset")))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let initial_commit = "1234567890abcdef1234567890abcdef12345678";
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            5,
+            "src/plots.R",
+            &format!("git branch: main\ninitial qc commit: {}\n", initial_commit),
+            Some(1),
+            "open",
+        );
+        let comment = |body: &str| GitComment {
+            body: body.to_string(),
+            author_login: "reviewer1".to_string(),
+            created_at: chrono::Utc::now(),
+            html: Some(String::new()),
+        };
+        let git_info = TestGitInfo {
+            comments: vec![
+                comment("![plot](https://example.com/plot.png)"),
+                comment("> ![plot](https://example.com/plot.png)\n\nStill off"),
+                comment(
+                    "![a](https://example.com/broken-a.png) ![b](https://example.com/broken-b.png)",
+                ),
+            ],
+            events: Vec::new(),
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial_commit).unwrap(),
+                message: "Initial commit".to_string(),
+            }],
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let downloader = RecordingDownloader::default();
+        let err = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &downloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap_err();
+
+        let mut downloaded = downloader.0.into_inner().unwrap();
+        downloaded.sort();
+        assert_eq!(
+            downloaded,
+            vec![
+                "https://example.com/broken-a.png",
+                "https://example.com/broken-b.png",
+                "https://example.com/plot.png",
+            ]
+        );
+        // Every failed download is reported, not just the first
+        let RecordError::MultipleImageDownloadsFailed { failures } = err else {
+            panic!("expected failed downloads, got {err:?}");
+        };
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].starts_with("https://example.com/broken-a.png: "));
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_reuses_cached_images() {
+        /// Counts downloads, serving a PNG from a signed URL that differs per fetch
+        #[derive(Default)]
+        struct CountingDownloader(std::sync::atomic::AtomicUsize);
+
+        impl images::HttpDownloader for CountingDownloader {
+            fn download(&self, _url: &str, path: &Path) -> Result<(), DownloadError> {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(std::fs::write(path, b"\x89PNG\r\n\x1a\nimage data")?)
+            }
+        }
+
+        let initial_commit = "1234567890abcdef1234567890abcdef12345678";
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            6,
+            "src/plots.R",
+            &format!("git branch: main\ninitial qc commit: {}\n", initial_commit),
+            Some(1),
+            "closed",
+        );
+        let git_info = |jwt: &str| {
+            TestGitInfo {
+            comments: vec![GitComment {
+                body: "![plot](https://github.com/user-attachments/assets/1a2b) ![fit](https://github.com/user-attachments/assets/3c4d)".to_string(),
+                author_login: "reviewer1".to_string(),
+                created_at: chrono::Utc::now(),
+                html: Some(format!(
+                    r#"<img src="https://private-user-images.githubusercontent.com/1.png?jwt={jwt}"><img src="https://private-user-images.githubusercontent.com/2.png?jwt={jwt}">"#
+                )),
+            }],
+            events: Vec::new(),
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial_commit).unwrap(),
+                message: "Initial commit".to_string(),
+            }],
+        }
+        };
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let downloader = CountingDownloader::default();
+        let record_run = async |jwt: &str, image_cache: ImageCache| {
+            let git_info = git_info(jwt);
+            let staging_dir = tempfile::tempdir().unwrap();
+            let snapshot = IssueSnapshot::fetch(issue.clone(), "v1.0", None, &git_info)
+                .await
+                .unwrap();
+            let issue_info = create_issue_information(
+                &snapshot.thread(&git_info, None).unwrap(),
+                &IssueInformationContext {
+                    users: &UserDirectory::default(),
+                    git_status: &clean_git_status(),
+                    signing_keys: &SigningKeys::default(),
+                    image_cache: Some(&image_cache),
+                    staging_dir: staging_dir.path(),
+                },
+                &git_info,
+                &downloader,
+                &mut Diagnostics::new(),
+            )
+            .unwrap();
+            // The staged images are in place for rendering either way
+            let staged = std::fs::read_dir(staging_dir.path()).unwrap().count();
+            (issue_info, staged)
+        };
+        let downloads = || downloader.0.load(std::sync::atomic::Ordering::SeqCst);
+
+        let (_, staged) = record_run("first", ImageCache::new(cache_dir.path())).await;
+        assert_eq!(downloads(), 2);
+        assert_eq!(staged, 2);
+
+        // The signed URLs changed, but the images are cached by their markdown URL
+        let (issue_info, staged) = record_run("second", ImageCache::new(cache_dir.path())).await;
+        assert_eq!(downloads(), 2);
+        assert_eq!(staged, 2);
+        assert!(issue_info.comments[0].1.contains("#image("));
+
+        record_run("third", ImageCache::new(cache_dir.path()).refreshing()).await;
+        assert_eq!(downloads(), 4);
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_latest_qc_commit_follows_notification_after_approval() {
+        let [_, notified, approved, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            3,
+            "src/analysis.R",
+            &format!("git branch: main\ninitial qc commit: {}\n", initial),
+            Some(1),
+            "open",
+        );
+
+        let comment = |body: String| GitComment {
+            body,
+            author_login: "reviewer1".to_string(),
+            created_at: chrono::Utc::now(),
+            html: None,
+        };
+        let git_info = TestGitInfo {
+            comments: vec![
+                comment(format!("# QC Notification\n\ncurrent commit: {}", approved)),
+                comment(format!("# QC Approval\n\napproved qc commit: {}", approved)),
+                comment(format!("# QC Notification\n\ncurrent commit: {}", notified)),
+            ],
+            events: Vec::new(),
+            commits: [notified, approved, initial]
+                .iter()
+                .map(|hash| GitCommit {
+                    commit: ObjectId::from_str(hash).unwrap(),
+                    message: "Update analysis".to_string(),
+                })
+                .collect(),
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        assert_eq!(issue_info.initial_qc_commit, initial);
+        assert_eq!(issue_info.latest_qc_commit, notified);
+
+        let signatures: Vec<(&str, &str, &str)> = issue_info
+            .commit_signatures
+            .iter()
+            .map(|s| {
+                (
+                    s.role.as_str(),
+                    s.commit.as_str(),
+                    s.signature_status.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                ("Initial", initial, "unsigned ✘"),
+                ("Notified", notified, "unsigned ✘"),
+                ("Approved", approved, "unsigned ✘"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_shows_effective_and_recorded_approval_dates() {
+        use chrono::TimeZone;
+
+        let [_, _, approved, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            4,
+            "src/analysis.R",
+            &format!("git branch: main\ninitial qc commit: {}\n", initial),
+            Some(1),
+            "closed",
+        );
+
+        let git_info = TestGitInfo {
+            comments: vec![GitComment {
+                body: format!(
+                    "# QC Approved\n\n## Metadata\n* approved qc commit: {}\n* effective date: 2024-05-02",
+                    approved
+                ),
+                author_login: "reviewer1".to_string(),
+                created_at: Utc.with_ymd_and_hms(2024, 5, 6, 14, 0, 0).unwrap(),
+                html: None,
+            }],
+            events: Vec::new(),
+            commits: [approved, initial]
+                .iter()
+                .map(|hash| GitCommit {
+                    commit: ObjectId::from_str(hash).unwrap(),
+                    message: "Update analysis".to_string(),
+                })
+                .collect(),
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            issue_info.approved_at.as_deref(),
+            Some("2024-05-02 (recorded 2024-05-06)")
+        );
+        assert_eq!(issue_info.approved_commit_sha.as_deref(), Some(approved));
+        assert_eq!(
+            issue_info.approved_file_url,
+            Some(format!(
+                "https://github.com/owner/repo/blob/{approved}/src/analysis.R"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_shows_obsolete_reason() {
+        let [_, _, approved, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            5,
+            "src/analysis.R",
+            &format!("git branch: main\ninitial qc commit: {}\n", initial),
+            Some(1),
+            "closed",
+        );
+
+        let comment = |body: String| GitComment {
+            body,
+            author_login: "reviewer1".to_string(),
+            created_at: chrono::Utc::now(),
+            html: None,
+        };
+        let git_info = TestGitInfo {
+            comments: vec![
+                comment(format!("# QC Approval\n\napproved qc commit: {}", approved)),
+                comment(
+                    "# QC Obsoleted\n\nDropped from the analysis plan\n\n## Metadata\n* issue: #5\n* obsolete reason: Dropped from the analysis plan\n* obsoleted by: reviewer1\n* obsoleted on: 2026-03-01".to_string(),
+                ),
+            ],
+            events: Vec::new(),
+            commits: [approved, initial]
+                .iter()
+                .map(|hash| GitCommit {
+                    commit: ObjectId::from_str(hash).unwrap(),
+                    message: "Update analysis".to_string(),
+                })
+                .collect(),
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            issue_info.qc_status,
+            "Obsolete: Dropped from the analysis plan"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_shows_inline_checklist() {
+        let [_, _, _, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            6,
+            "src/model.R",
+            &format!(
+                "## Metadata\n* initial qc commit: {initial}\n* git branch: main\n* inline checklist: bespoke_model_review.md\n\n# bespoke_model_review\n\n- [ ] Check the priors"
+            ),
+            Some(1),
+            "open",
+        );
+        let git_info = TestGitInfo {
+            comments: Vec::new(),
+            events: Vec::new(),
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial).unwrap(),
+                message: "Add model".to_string(),
+            }],
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            issue_info.inline_checklist.as_deref(),
+            Some("bespoke\\_model\\_review.md")
+        );
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_lists_relevant_files() {
+        let [_, _, _, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            6,
+            "src/model.R",
+            &format!(
+                "## Metadata\n* initial qc commit: {initial}\n* git branch: main\n\n## Relevant Files\n\n### Gating QC\n- [src/data_prep.R](https://github.com/owner/repo/issues/2) - Builds the model_input dataset\n\n### Relevant File\n- **data/priors.csv** - Priors from the #protocol\n\n# Code Review\n\n- [ ] Check the priors"
+            ),
+            Some(1),
+            "open",
+        );
+        let git_info = TestGitInfo {
+            comments: Vec::new(),
+            events: Vec::new(),
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial).unwrap(),
+                message: "Add model".to_string(),
+            }],
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        let relevant: Vec<_> = issue_info
+            .relevant_files
+            .iter()
+            .map(|r| (r.file.as_str(), r.kind.as_str(), r.justification.as_deref()))
+            .collect();
+        assert_eq!(
+            relevant,
+            vec![
+                (
+                    "src/data\\_prep.R",
+                    "Gating QC",
+                    Some("Builds the model\\_input dataset")
+                ),
+                (
+                    "data/priors.csv",
+                    "Relevant File",
+                    Some("Priors from the \\#protocol")
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_lists_file_commits_in_timeline() {
+        let [_, _, _, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            7,
+            "src/model.R",
+            &format!("## Metadata\n* initial qc commit: {initial}\n* git branch: main\n"),
+            Some(1),
+            "open",
+        );
+        let git_info = TestGitInfo {
+            comments: Vec::new(),
+            events: vec![serde_json::json!({
+                "event": "assigned",
+                "created_at": "2025-11-01T12:00:00Z",
+                "actor": {"login": "octocat"},
+                "assignee": {"login": "reviewer1"}
+            })],
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial).unwrap(),
+                message: "Add model\n\nWith covariates".to_string(),
+            }],
+        };
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let issue_info = create_issue_information(
+            &snapshot.thread(&git_info, None).unwrap(),
+            &IssueInformationContext {
+                users: &UserDirectory::default(),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut Diagnostics::new(),
+        )
+        .unwrap();
+
+        assert_eq!(issue_info.timeline.len(), 2);
+        assert_eq!(
+            issue_info.timeline[0],
+            format!(
+                "_1970-01-01 00:00:00 - commit {} modified src/model.R: 'Add model' by Test Author_",
+                &initial[..7]
+            )
+        );
+        assert!(issue_info.timeline[1].starts_with("2025-11-01 12:00:00 - "));
+    }
+
+    #[tokio::test]
+    async fn create_issue_information_collects_diagnostics() {
+        let [_, _, _, initial] = crate::test_utils::RENOTIFIED_THREAD_COMMITS;
+        let issue = create_test_issue(
+            "owner",
+            "repo",
+            7,
+            "src/model.R",
+            &format!("## Metadata\n* initial qc commit: {initial}\n* git branch: main\n"),
+            Some(1),
+            "open",
+        );
+        let git_info = TestGitInfo {
+            comments: Vec::new(),
+            events: vec![
+                serde_json::json!({
+                    "event": "assigned",
+                    "created_at": "2025-11-01T12:00:00Z",
+                    "actor": {"login": "octocat"},
+                    "assignee": {"login": "reviewer1"}
+                }),
+                serde_json::json!({
+                    "event": "subscribed",
+                    "created_at": "2025-11-01T12:00:00Z",
+                    "actor": {"login": "octocat"}
+                }),
+                serde_json::json!({
+                    "event": "renamed",
+                    "created_at": "2025-11-02T12:00:00Z",
+                    "actor": {"login": "octocat"}
+                }),
+            ],
+            commits: vec![GitCommit {
+                commit: ObjectId::from_str(initial).unwrap(),
+                message: "Add model".to_string(),
+            }],
+        };
+        let repo_users = vec![RepoUser {
+            login: "octocat".to_string(),
+            name: Some("The Octocat".to_string()),
+        }];
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let snapshot = IssueSnapshot::fetch(issue, "v1.0", None, &git_info)
+            .await
+            .unwrap();
+        let thread = snapshot.thread(&git_info, None).unwrap();
+        let mut diagnostics = Diagnostics::new();
+        create_issue_information(
+            &thread,
+            &IssueInformationContext {
+                users: &UserDirectory::new(repo_users),
+                git_status: &clean_git_status(),
+                signing_keys: &SigningKeys::default(),
+                image_cache: None,
+                staging_dir: staging_dir.path(),
+            },
+            &git_info,
+            &TestDownloader,
+            &mut diagnostics,
+        )
+        .unwrap();
+
+        assert_eq!(
+            diagnostics.violations(),
+            &[
+                StrictViolation::UnresolvedUser {
+                    issue_number: 7,
+                    login: "reviewer1".to_string()
+                },
+                StrictViolation::SkippedEvent {
+                    issue_number: 7,
+                    event: "renamed".to_string()
+                },
+            ]
+        );
+        assert!(matches!(
+            diagnostics.ensure_strict(),
+            Err(RecordError::Strict(StrictViolations(v))) if v.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_create_combined_timeline_interleaves_commits() {
+        let events = vec![
+            "Unknown time - labeled by octocat".to_string(),
+            "2024-04-12 11:00:00 - closed by reviewer1".to_string(),
+        ];
+        let comments = vec![
+            (
+                "2024-04-12 08:15:00 - Comment by octocat".to_string(),
+                "QC notification".to_string(),
+            ),
+            (
+                "2024-04-12 10:02:45 - Comment by reviewer1".to_string(),
+                "QC review".to_string(),
+            ),
+        ];
+        let commits = vec![
+            "2024-04-12 09:31:02 - commit abc1234 modified scripts/analysis.R: 'fix covariate handling' by Jane Doe"
+                .to_string(),
+        ];
+
+        assert_eq!(
+            create_combined_timeline(&events, &comments, &commits),
+            vec![
+                "2024-04-12 08:15:00 - commented by octocat",
+                "_2024-04-12 09:31:02 - commit abc1234 modified scripts/analysis.R: 'fix covariate handling' by Jane Doe_",
+                "2024-04-12 10:02:45 - commented by reviewer1",
+                "2024-04-12 11:00:00 - closed by reviewer1",
+                "Unknown time - labeled by octocat",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_approval_date() {
+        use chrono::TimeZone;
+
+        let posted = Utc.with_ymd_and_hms(2024, 5, 6, 14, 0, 0).unwrap();
+        assert_eq!(
+            format_approval_date(Some(posted), None).as_deref(),
+            Some("2024-05-06 14:00:00")
+        );
+        assert_eq!(
+            format_approval_date(
+                Some(Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap()),
+                Some(posted)
+            )
+            .as_deref(),
+            Some("2024-05-02 (recorded 2024-05-06)")
+        );
+        assert_eq!(format_approval_date(None, None), None);
+    }
+
+    fn issue_information(number: u64, title: &str, created_at: &str) -> IssueInformation {
+        IssueInformation {
+            title: title.to_string(),
+            number,
+            milestone: "v1.0".to_string(),
+            created_by: "author".to_string(),
+            created_at: created_at.to_string(),
+            qcer: vec!["qcer1".to_string()],
+            qc_status: "Approved".to_string(),
+            checklist_summary: "1/1 (100.0%)".to_string(),
+            inline_checklist: None,
+            relevant_files: Vec::new(),
+            git_status: "Clean".to_string(),
+            initial_qc_commit: "abc123".to_string(),
+            latest_qc_commit: "def456".to_string(),
+            commit_signatures: Vec::new(),
+            attachments: Vec::new(),
+            qc_effort: None,
+            qc_round: 1,
+            rounds_to_approval: None,
+            approved_at: None,
+            unapproved: None,
+            approved_commit_sha: None,
+            approved_file_url: None,
+            issue_url: format!("https://github.com/owner/repo/issues/{number}"),
+            superseded_by: None,
+            state: "Closed".to_string(),
+            closed_by: None,
+            closed_at: None,
+            body: "Issue body".to_string(),
+            comments: Vec::new(),
+            events: Vec::new(),
+            timeline: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_disambiguate_titles_appends_numbers_to_shared_titles() {
+        let issues = vec![
+            issue_information(42, "scripts/analysis.R", "2025-03-01 09:00:00"),
+            issue_information(7, "scripts/analysis.R", "2024-11-15 16:30:00"),
+            issue_information(8, "scripts/plots.R", "2024-11-15 16:31:00"),
+        ];
+
+        let disambiguated = disambiguate_titles(&issues);
+
+        let titles = disambiguated
+            .iter()
+            .map(|i| (i.title.as_str(), i.superseded_by))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                ("scripts/analysis.R (\\#42)", None),
+                ("scripts/analysis.R (\\#7)", Some(42)),
+                ("scripts/plots.R", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disambiguate_titles_chains_supersession() {
+        let issues = vec![
+            issue_information(1, "scripts/analysis.R", "2024-01-01 00:00:00"),
+            issue_information(2, "scripts/analysis.R", "2024-06-01 00:00:00"),
+            issue_information(3, "scripts/analysis.R", "2025-01-01 00:00:00"),
+        ];
+
+        let superseded_by = disambiguate_titles(&issues)
+            .iter()
+            .map(|i| i.superseded_by)
+            .collect::<Vec<_>>();
+        assert_eq!(superseded_by, vec![Some(2), Some(3), None]);
+    }
+
+    #[test]
+    fn test_disambiguate_titles_without_later_issue() {
+        // Issues created at the same time are told apart, but neither supersedes the other
+        let issues = vec![
+            issue_information(5, "scripts/analysis.R", "2024-01-01 00:00:00"),
+            issue_information(6, "scripts/analysis.R", "2024-01-01 00:00:00"),
+        ];
+
+        let disambiguated = disambiguate_titles(&issues);
+        assert_eq!(disambiguated[0].title, "scripts/analysis.R (\\#5)");
+        assert_eq!(disambiguated[1].title, "scripts/analysis.R (\\#6)");
+        assert!(disambiguated.iter().all(|i| i.superseded_by.is_none()));
+
+        let unique = vec![issue_information(
+            9,
+            "scripts/model.R",
+            "2024-01-01 00:00:00",
+        )];
+        assert_eq!(disambiguate_titles(&unique)[0].title, "scripts/model.R");
+    }
 }
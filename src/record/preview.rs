@@ -0,0 +1,267 @@
+use std::path::Path;
+
+use crate::{
+    Configuration, DiskCache, GitCommitOps, GitHelpers, GitHubReader, GitRepository, GitStatusOps,
+    UserDirectory, get_git_status,
+    git::SigningKeys,
+    milestone_snapshot::SnapshotThread,
+    record::{
+        Diagnostics, IssueInformation, IssueInformationContext, RecordError, TEMPLATES,
+        create_issue_information, document_context, images::HttpDownloader,
+    },
+    utils::{EnvProvider, sanitize_file_name},
+};
+
+/// Build the record information of a single issue, downloading its images to `staging_dir`
+///
+/// The single-issue counterpart of [`super::get_milestone_issue_information`].
+pub async fn get_issue_information(
+    thread: &SnapshotThread<'_>,
+    cache: Option<&DiskCache>,
+    signing_keys: &SigningKeys,
+    git_info: &(impl GitHubReader + GitCommitOps + GitStatusOps + GitRepository + GitHelpers),
+    http_downloader: &impl HttpDownloader,
+    staging_dir: impl AsRef<Path>,
+) -> Result<IssueInformation, RecordError> {
+    let mut users = UserDirectory::load(cache, git_info).await?;
+    users
+        .resolve(super::snapshot_logins(thread.snapshot), cache, git_info)
+        .await;
+    let git_status = get_git_status(git_info)?;
+
+    create_issue_information(
+        thread,
+        &IssueInformationContext {
+            users: &users,
+            git_status: &git_status,
+            signing_keys,
+            image_cache: cache.map(DiskCache::image_cache).as_ref(),
+            staging_dir: staging_dir.as_ref(),
+        },
+        git_info,
+        http_downloader,
+        &mut Diagnostics::new(),
+    )
+}
+
+/// Standalone Typst document with the record section of a single issue
+///
+/// The section is rendered from the same `issue_detail.typ` partial as the built-in milestone
+/// record, so an issue's body, comments and images can be checked without generating the whole
+/// record. A custom record template does not change the preview.
+pub fn issue_record_preview(
+    issue: &IssueInformation,
+    configuration: &Configuration,
+    git_info: &impl GitRepository,
+    env: &impl EnvProvider,
+    staging_dir: impl AsRef<Path>,
+) -> Result<String, RecordError> {
+    let mut context = document_context(configuration, git_info, env, staging_dir)?;
+    context.insert("issue", issue);
+
+    TEMPLATES
+        .render("issue_preview.typ", &context)
+        .map_err(RecordError::Template)
+}
+
+/// File name for an issue's record preview: `<repo>_issue-<number>-preview.pdf`
+pub fn issue_preview_file_name(repo: &str, issue_number: u64) -> String {
+    format!(
+        "{}_issue-{issue_number}-preview.pdf",
+        sanitize_file_name(repo)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{
+        FileStashOutcome, GitAuthor, GitRepositoryError,
+        record::{RecordOptions, RecordRelevantFile, record},
+        test_utils::create_test_milestone,
+        utils::MockEnvProvider,
+    };
+
+    struct TestRepo;
+
+    impl GitRepository for TestRepo {
+        fn commit(&self) -> Result<String, GitRepositoryError> {
+            Ok(String::new())
+        }
+        fn branch(&self) -> Result<String, GitRepositoryError> {
+            Ok("main".to_string())
+        }
+        fn owner(&self) -> &str {
+            "owner"
+        }
+        fn repo(&self) -> &str {
+            "repo"
+        }
+        fn remote_name(&self) -> &str {
+            "origin"
+        }
+        fn path(&self) -> &Path {
+            Path::new(".")
+        }
+        fn fetch(&self) -> Result<bool, GitRepositoryError> {
+            Ok(false)
+        }
+        fn stash_file(
+            &self,
+            _file: &Path,
+            _message: &str,
+        ) -> Result<FileStashOutcome, GitRepositoryError> {
+            Ok(FileStashOutcome::NoChanges)
+        }
+        fn configured_author(&self) -> Option<GitAuthor> {
+            None
+        }
+    }
+
+    fn mock_env() -> MockEnvProvider {
+        let mut env = MockEnvProvider::new();
+        env.expect_var().returning(|key| match key {
+            "USER" => Ok("tester".to_string()),
+            "GHQC_RECORD_DATE" => Ok("January 01, 2025".to_string()),
+            _ => Err(std::env::VarError::NotPresent),
+        });
+        env
+    }
+
+    fn issue_information() -> IssueInformation {
+        IssueInformation {
+            title: "scripts/analysis.R".to_string(),
+            number: 12,
+            milestone: "v1.0".to_string(),
+            created_by: "octocat".to_string(),
+            created_at: "2025-01-01 12:00:00".to_string(),
+            qcer: vec!["reviewer1".to_string()],
+            qc_status: "Approved".to_string(),
+            checklist_summary: "2/2 (100.0%)".to_string(),
+            inline_checklist: None,
+            relevant_files: Vec::new(),
+            git_status: "Up to date".to_string(),
+            initial_qc_commit: "abc123".to_string(),
+            latest_qc_commit: "def456".to_string(),
+            commit_signatures: Vec::new(),
+            attachments: Vec::new(),
+            qc_effort: None,
+            qc_round: 1,
+            rounds_to_approval: Some(1),
+            approved_at: Some("2025-01-02 09:00:00".to_string()),
+            unapproved: None,
+            approved_commit_sha: Some("def4567890abcdef1234567890abcdef12345678".to_string()),
+            approved_file_url: Some(
+                "https://github.com/owner/repo/blob/def4567890abcdef1234567890abcdef12345678/scripts/analysis.R"
+                    .to_string(),
+            ),
+            issue_url: "https://github.com/owner/repo/issues/12".to_string(),
+            superseded_by: None,
+            state: "Closed".to_string(),
+            closed_by: Some("reviewer1".to_string()),
+            closed_at: Some("2025-01-02 09:00:00".to_string()),
+            body: "Issue body".to_string(),
+            comments: vec![(
+                "reviewer1 - 2025-01-02 08:00:00".to_string(),
+                "#figure(image(\"images/plot.png\"))".to_string(),
+            )],
+            events: vec!["2025-01-02 09:00:00 - closed by reviewer1".to_string()],
+            timeline: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_issue_record_preview() {
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let preview = issue_record_preview(
+            &issue_information(),
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        insta::assert_snapshot!(preview);
+    }
+
+    #[test]
+    fn test_issue_record_preview_lists_relevant_files() {
+        let mut issue = issue_information();
+        let staging_dir = tempfile::tempdir().unwrap();
+        let preview = |issue: &IssueInformation| {
+            issue_record_preview(
+                issue,
+                &Configuration::default(),
+                &TestRepo,
+                &mock_env(),
+                staging_dir.path(),
+            )
+            .unwrap()
+        };
+        assert!(!preview(&issue).contains("=== Relevant Files"));
+
+        issue.relevant_files = vec![
+            RecordRelevantFile {
+                file: "scripts/gate.R".to_string(),
+                kind: "Gating QC".to_string(),
+                justification: None,
+            },
+            RecordRelevantFile {
+                file: "data/config.yaml".to_string(),
+                kind: "Relevant File".to_string(),
+                justification: Some("Model settings".to_string()),
+            },
+        ];
+        let rendered = preview(&issue);
+        assert!(rendered.contains("=== Relevant Files\n"));
+        assert!(rendered.contains("- scripts/gate.R (Gating QC)\n"));
+        assert!(rendered.contains("- data/config.yaml (Relevant File): Model settings\n"));
+    }
+
+    #[test]
+    fn test_issue_record_preview_matches_record_section() {
+        let issue = issue_information();
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let preview = issue_record_preview(
+            &issue,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            staging_dir.path(),
+        )
+        .unwrap();
+        let full = record(
+            &[create_test_milestone(
+                "owner", "repo", 1, "v1.0", None, "open",
+            )],
+            &HashMap::from([("v1.0".to_string(), vec![issue])]),
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions::default(),
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        let section = |document: &str| {
+            let start = document.find("== scripts/analysis.R <issue-12>").unwrap();
+            let end = document[start..].find("No timeline items found.").unwrap();
+            document[start..start + end].to_string()
+        };
+        assert_eq!(section(&preview), section(&full));
+        assert!(!preview.contains("= Milestone Summary"));
+    }
+
+    #[test]
+    fn test_issue_preview_file_name() {
+        assert_eq!(
+            issue_preview_file_name("my repo", 42),
+            "my-repo_issue-42-preview.pdf"
+        );
+    }
+}
@@ -12,6 +12,7 @@ use typst::{
     diag::{Severity, SourceDiagnostic},
     ecow::EcoVec,
 };
+use typst_html::HtmlDocument;
 use typst_pdf::PdfOptions;
 
 use crate::{DiskCache, record::images::HttpDownloader, record::typst::TypstWorld};
@@ -66,11 +67,31 @@ pub enum ContextPosition {
     Append,
 }
 
-/// Render a Typst document to PDF using the typst library
+/// Output format of a rendered record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordFormat {
+    #[default]
+    Pdf,
+    /// A single self-contained HTML page, with images embedded and issue links kept clickable
+    Html,
+}
+
+impl RecordFormat {
+    /// File extension of records rendered in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// Render a Typst document to PDF or HTML using the typst library
 ///
 /// # Arguments
 /// * `record_str` - The Typst document content to render
-/// * `path` - The output path for the rendered PDF
+/// * `path` - The output path for the rendered record
+/// * `format` - The output format. Context files can only be merged into PDF records
 /// * `staging_dir` - The staging directory containing the template and assets (images, logo)
 /// * `qc_context` - Optional context files to prepend/append to the PDF
 /// * `cache` - Optional disk cache for typst package caching
@@ -83,15 +104,16 @@ pub enum ContextPosition {
 /// # Example
 /// ```no_run
 /// use std::path::Path;
-/// use ghqctoolkit::{render, QCContext, ContextPosition, create_staging_dir, UreqDownloader};
+/// use ghqctoolkit::{render, QCContext, ContextPosition, RecordFormat, create_staging_dir, UreqDownloader};
 ///
 /// let report = "#set document(title: \"My Report\")\n= Hello World";
 /// let staging_dir = create_staging_dir().unwrap();
-/// render(report, Path::new("output/my-report.pdf"), &staging_dir, &[], None, &UreqDownloader::new()).unwrap();
+/// render(report, Path::new("output/my-report.pdf"), RecordFormat::Pdf, &staging_dir, &[], None, &UreqDownloader::new()).unwrap();
 /// ```
 pub fn render(
     record_str: &str,
     path: impl AsRef<Path>,
+    format: RecordFormat,
     staging_dir: impl AsRef<Path>,
     qc_context: &[QCContext],
     cache: Option<&DiskCache>,
@@ -103,6 +125,7 @@ pub fn render(
     let result = render_inner(
         record_str,
         path.as_ref(),
+        format,
         staging_dir,
         qc_context,
         cache,
@@ -121,14 +144,66 @@ pub fn render(
     result
 }
 
+/// Render several Typst documents that share one staging directory
+///
+/// Each `(record_str, path, qc_context)` entry is rendered as with [`render`], returning one
+/// result per entry: a document failing to render does not stop the others. The staging
+/// directory is cleaned up once, after all documents are rendered.
+pub fn render_each(
+    records: &[(String, PathBuf, Vec<QCContext>)],
+    format: RecordFormat,
+    staging_dir: impl AsRef<Path>,
+    cache: Option<&DiskCache>,
+    http: &(impl HttpDownloader + Clone + 'static),
+) -> Vec<Result<(), RenderError>> {
+    let staging_dir = staging_dir.as_ref();
+
+    let results = records
+        .iter()
+        .map(|(record_str, path, qc_context)| {
+            render_inner(
+                record_str,
+                path,
+                format,
+                staging_dir,
+                qc_context,
+                cache,
+                http,
+            )
+        })
+        .collect();
+
+    if let Err(e) = std::fs::remove_dir_all(staging_dir) {
+        log::warn!(
+            "Failed to cleanup staging directory {}: {}",
+            staging_dir.display(),
+            e
+        );
+    }
+
+    results
+}
+
 fn render_inner(
     record_str: &str,
     output_path: &Path,
+    format: RecordFormat,
     staging_dir: &Path,
     qc_context: &[QCContext],
     cache: Option<&DiskCache>,
     http: &(impl HttpDownloader + Clone + 'static),
 ) -> Result<(), RenderError> {
+    if format == RecordFormat::Html {
+        if let Some(context) = qc_context.first() {
+            return Err(RenderError::HtmlContextFile {
+                file: context.file.clone(),
+            });
+        }
+        let html = render_html_in_staging(staging_dir, record_str, cache, http)?;
+        fs::write(output_path, html)?;
+        return Ok(());
+    }
+
     let findings_doc =
         render_typst_in_staging(staging_dir, record_str, cache, http).and_then(|file| {
             Document::load(&file).map_err(|error| RenderError::PdfReadError { file, error })
@@ -338,33 +413,20 @@ fn render_typst_in_staging(
     let cache_dir = cache
         .map(|c| c.root.to_path_buf())
         .unwrap_or(tempdir().map_err(RenderError::Io)?.path().to_path_buf());
-    let world = TypstWorld::new(staging_dir, report.to_string(), &cache_dir, http.clone());
+    let world = TypstWorld::new(
+        staging_dir,
+        report.to_string(),
+        &cache_dir,
+        http.clone(),
+        RecordFormat::Pdf,
+    );
     log::debug!("Rendering pdf record from typst...");
-    let generate_compile_error_message = |v: EcoVec<SourceDiagnostic>| -> RenderError {
-        let err = v
-            .iter()
-            .map(|s| {
-                format!(
-                    "{}: {}",
-                    match s.severity {
-                        Severity::Error => "ERROR",
-                        Severity::Warning => "WARNING",
-                    },
-                    s.message
-                )
-            })
-            .collect::<Vec<_>>()
-            .join("\n\t");
-
-        RenderError::TypstCompile(err)
-    };
-
     let document = typst::compile(&world)
         .output
-        .map_err(generate_compile_error_message)?;
+        .map_err(|diagnostics| classify_compile_failure(&diagnostics))?;
 
     let pdf = typst_pdf::pdf(&document, &PdfOptions::default())
-        .map_err(generate_compile_error_message)?;
+        .map_err(|diagnostics| classify_compile_failure(&diagnostics))?;
 
     let staging_pdf_path = staging_dir.join("record.pdf");
 
@@ -373,6 +435,79 @@ fn render_typst_in_staging(
     Ok(staging_pdf_path)
 }
 
+/// Compile a Typst document to a standalone HTML page
+///
+/// Images referenced from the staging directory are embedded in the page, so the result is a
+/// single file.
+fn render_html_in_staging(
+    staging_dir: &Path,
+    report: &str,
+    cache: Option<&DiskCache>,
+    http: &(impl HttpDownloader + Clone + 'static),
+) -> Result<String, RenderError> {
+    let cache_dir = cache
+        .map(|c| c.root.to_path_buf())
+        .unwrap_or(tempdir().map_err(RenderError::Io)?.path().to_path_buf());
+    let world = TypstWorld::new(
+        staging_dir,
+        report.to_string(),
+        &cache_dir,
+        http.clone(),
+        RecordFormat::Html,
+    );
+    log::debug!("Rendering html record from typst...");
+    let document = typst::compile::<HtmlDocument>(&world)
+        .output
+        .map_err(|diagnostics| classify_compile_failure(&diagnostics))?;
+
+    typst_html::html(&document).map_err(|diagnostics| classify_compile_failure(&diagnostics))
+}
+
+/// Turn Typst diagnostics into a render error
+///
+/// Failures with a known environmental cause (an unreachable package registry, a file missing
+/// from the staging directory) get their own error with remediation. Anything else is reported
+/// as [`RenderError::TypstCompile`] with every diagnostic listed.
+fn classify_compile_failure(diagnostics: &EcoVec<SourceDiagnostic>) -> RenderError {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| d.message.as_str());
+
+    for message in errors {
+        if let Some(reason) = message.strip_prefix("failed to download package") {
+            let reason = reason.trim();
+            let reason = reason
+                .strip_prefix('(')
+                .and_then(|r| r.strip_suffix(')'))
+                .unwrap_or(reason);
+            return RenderError::TypstPackageDownload(reason.to_string());
+        }
+        if let Some(path) = message.strip_prefix("file not found (searched at ") {
+            return RenderError::TypstFileNotFound(PathBuf::from(
+                path.strip_suffix(')').unwrap_or(path),
+            ));
+        }
+    }
+
+    let err = diagnostics
+        .iter()
+        .map(|s| {
+            format!(
+                "{}: {}",
+                match s.severity {
+                    Severity::Error => "ERROR",
+                    Severity::Warning => "WARNING",
+                },
+                s.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\t");
+
+    RenderError::TypstCompile(err)
+}
+
 /// Load a context file as a PDF Document.
 /// Only PDF files are supported - users must convert Word documents to PDF first.
 fn load_context_file(file: impl AsRef<Path>) -> Result<Document, RenderError> {
@@ -401,6 +536,14 @@ fn load_context_file(file: impl AsRef<Path>) -> Result<Document, RenderError> {
 pub enum RenderError {
     #[error("Typst Compile Failed: {0}")]
     TypstCompile(String),
+    #[error(
+        "Typst could not download a package used by the record template: {0}. Check that https://packages.typst.org is reachable from this machine; packages are cached after the first successful download."
+    )]
+    TypstPackageDownload(String),
+    #[error(
+        "Typst could not find {0}. The record references a file missing from the staging directory, such as an image that failed to download or a logo path that does not exist."
+    )]
+    TypstFileNotFound(PathBuf),
     #[error("IO Error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to read pdf at {file}: {error}")]
@@ -417,6 +560,10 @@ pub enum RenderError {
         "Unsupported file format for {file}: only PDF files are supported. Please convert the file to PDF first."
     )]
     UnsupportedFileFormat { file: PathBuf },
+    #[error(
+        "Cannot add {file} to an HTML record: context files can only be merged into PDF records."
+    )]
+    HtmlContextFile { file: PathBuf },
 }
 
 #[cfg(test)]
@@ -473,6 +620,39 @@ mod tests {
         doc
     }
 
+    #[test]
+    fn test_render_each_continues_after_failure() {
+        let staging = TempDir::new().unwrap();
+        let output = TempDir::new().unwrap();
+        let records = vec![
+            (
+                "#nonexistent_function()".to_string(),
+                output.path().join("broken.pdf"),
+                Vec::new(),
+            ),
+            (
+                "= Milestone 2".to_string(),
+                output.path().join("ok.pdf"),
+                Vec::new(),
+            ),
+        ];
+
+        let results = render_each(
+            &records,
+            RecordFormat::Pdf,
+            staging.path(),
+            None,
+            &crate::UreqDownloader::new(),
+        );
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+        assert!(!output.path().join("broken.pdf").exists());
+        assert!(output.path().join("ok.pdf").exists());
+        assert!(!staging.path().exists());
+    }
+
     // ===================
     // QCContext tests
     // ===================
@@ -647,4 +827,71 @@ mod tests {
         let reloaded = Document::load(&output_path);
         assert!(reloaded.is_ok());
     }
+
+    // ===================
+    // classify_compile_failure tests
+    // ===================
+
+    fn diagnostics(errors: &[&str], warnings: &[&str]) -> EcoVec<SourceDiagnostic> {
+        use typst::syntax::Span;
+
+        warnings
+            .iter()
+            .map(|m| SourceDiagnostic::warning(Span::detached(), *m))
+            .chain(
+                errors
+                    .iter()
+                    .map(|m| SourceDiagnostic::error(Span::detached(), *m)),
+            )
+            .collect()
+    }
+
+    #[test]
+    fn test_classify_package_download_failure() {
+        let diagnostics = diagnostics(
+            &["failed to download package (Connection refused (os error 111))"],
+            &[],
+        );
+
+        match classify_compile_failure(&diagnostics) {
+            RenderError::TypstPackageDownload(reason) => {
+                assert_eq!(reason, "Connection refused (os error 111)");
+            }
+            e => panic!("Expected TypstPackageDownload, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_classify_missing_file() {
+        let diagnostics = diagnostics(
+            &["file not found (searched at /tmp/ghqc-render-1a2b/images/plot.png)"],
+            &["unknown font family: inter"],
+        );
+
+        match classify_compile_failure(&diagnostics) {
+            RenderError::TypstFileNotFound(path) => {
+                assert_eq!(path, PathBuf::from("/tmp/ghqc-render-1a2b/images/plot.png"));
+            }
+            e => panic!("Expected TypstFileNotFound, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_classify_warning_does_not_match() {
+        // A warning mentioning a missing file must not hide the actual error
+        let diagnostics = diagnostics(
+            &["unknown variable: milestone"],
+            &["file not found (searched at /tmp/logo.png)"],
+        );
+
+        match classify_compile_failure(&diagnostics) {
+            RenderError::TypstCompile(message) => {
+                assert_eq!(
+                    message,
+                    "WARNING: file not found (searched at /tmp/logo.png)\n\tERROR: unknown variable: milestone"
+                );
+            }
+            e => panic!("Expected TypstCompile, got {:?}", e),
+        }
+    }
 }
@@ -0,0 +1,577 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use octocrab::models::Milestone;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    Configuration, GitRepository,
+    record::{IssueInformation, RecordError, RecordFormat, RecordOptions, record},
+    utils::{EnvProvider, sanitize_file_name},
+};
+
+/// A standalone record for a single milestone
+#[derive(Debug, Clone)]
+pub struct MilestoneRecord {
+    pub milestone: String,
+    pub issue_count: usize,
+    pub record: String,
+}
+
+/// Generate one standalone record per milestone from a single fetched issue map
+///
+/// Each record is rendered with the regular template and `options`, scoped to only its
+/// milestone. Milestones without any issue information are skipped, matching [`record`].
+pub fn record_by_milestone(
+    milestones: &[Milestone],
+    issues: &HashMap<String, Vec<IssueInformation>>,
+    configuration: &Configuration,
+    git_info: &impl GitRepository,
+    env: &impl EnvProvider,
+    options: &RecordOptions,
+    staging_dir: impl AsRef<Path>,
+) -> Result<Vec<MilestoneRecord>, RecordError> {
+    let staging_dir = staging_dir.as_ref();
+
+    milestones
+        .iter()
+        .filter_map(|milestone| {
+            issues
+                .get(&milestone.title)
+                .map(|issue_list| (milestone, issue_list))
+        })
+        .map(|(milestone, issue_list)| {
+            let milestone_issues = HashMap::from([(milestone.title.clone(), issue_list.clone())]);
+            let record = record(
+                std::slice::from_ref(milestone),
+                &milestone_issues,
+                configuration,
+                git_info,
+                env,
+                options,
+                staging_dir,
+            )?;
+
+            Ok(MilestoneRecord {
+                milestone: milestone.title.clone(),
+                issue_count: issue_list.len(),
+                record,
+            })
+        })
+        .collect()
+}
+
+/// File name for a single milestone's record: `<repo>_<milestone>.<format>`
+pub fn milestone_record_file_name(repo: &str, milestone: &str, format: RecordFormat) -> String {
+    format!(
+        "{repo}_{}.{}",
+        sanitize_file_name(milestone),
+        format.extension()
+    )
+}
+
+/// A rendered milestone record, as listed in the record index
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordIndexEntry {
+    pub milestone: String,
+    pub issue_count: usize,
+    pub path: PathBuf,
+    pub sha256: String,
+}
+
+impl RecordIndexEntry {
+    /// Create an entry for a rendered record, checksumming the file at `path`
+    pub fn from_file(
+        milestone: impl Into<String>,
+        issue_count: usize,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, RecordError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+
+        Ok(Self {
+            milestone: milestone.into(),
+            issue_count,
+            path: path.to_path_buf(),
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+        })
+    }
+}
+
+/// Markdown index listing each milestone record with its issue count and checksum
+pub fn record_index(repo: &str, date: &str, entries: &[RecordIndexEntry]) -> String {
+    let mut index = vec![
+        format!("# QC Record Index: {repo}"),
+        String::new(),
+        format!("Generated: {date}"),
+        String::new(),
+        "| Milestone | Issues | File | SHA-256 |".to_string(),
+        "| --- | --- | --- | --- |".to_string(),
+    ];
+
+    for entry in entries {
+        let file_name = entry
+            .path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| entry.path.display().to_string());
+        index.push(format!(
+            "| {} | {} | {} | `{}` |",
+            entry.milestone.replace('|', "\\|"),
+            entry.issue_count,
+            file_name,
+            entry.sha256
+        ));
+    }
+
+    let total: usize = entries.iter().map(|e| e.issue_count).sum();
+    index.push(String::new());
+    index.push(format!("{} records, {} issues total", entries.len(), total));
+
+    index.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CoveredFile, FileStashOutcome, GitAuthor, GitRepositoryError, QCCoverage,
+        utils::MockEnvProvider,
+    };
+
+    struct TestRepo;
+
+    impl GitRepository for TestRepo {
+        fn commit(&self) -> Result<String, GitRepositoryError> {
+            Ok(String::new())
+        }
+        fn branch(&self) -> Result<String, GitRepositoryError> {
+            Ok("main".to_string())
+        }
+        fn owner(&self) -> &str {
+            "owner"
+        }
+        fn repo(&self) -> &str {
+            "repo"
+        }
+        fn remote_name(&self) -> &str {
+            "origin"
+        }
+        fn path(&self) -> &Path {
+            Path::new(".")
+        }
+        fn fetch(&self) -> Result<bool, GitRepositoryError> {
+            Ok(false)
+        }
+        fn stash_file(
+            &self,
+            _file: &Path,
+            _message: &str,
+        ) -> Result<FileStashOutcome, GitRepositoryError> {
+            Ok(FileStashOutcome::NoChanges)
+        }
+        fn configured_author(&self) -> Option<GitAuthor> {
+            None
+        }
+    }
+
+    fn load_milestone(name: &str) -> Milestone {
+        let text = std::fs::read_to_string(format!("src/tests/github_api/milestones/{name}.json"))
+            .unwrap_or_else(|_| panic!("Failed to load milestone fixture: {name}"));
+        serde_json::from_str(&text).expect("Failed to parse milestone fixture")
+    }
+
+    fn issue_information(number: u64, title: &str, milestone: &str) -> IssueInformation {
+        IssueInformation {
+            title: title.to_string(),
+            number,
+            milestone: milestone.to_string(),
+            created_by: "octocat".to_string(),
+            created_at: "2025-01-01 12:00:00".to_string(),
+            qcer: vec!["reviewer1".to_string()],
+            qc_status: "Approved".to_string(),
+            checklist_summary: "1/1 (100.0%)".to_string(),
+            git_status: "Up to date".to_string(),
+            initial_qc_commit: "abc123".to_string(),
+            latest_qc_commit: "def456".to_string(),
+            commit_signatures: Vec::new(),
+            attachments: Vec::new(),
+            qc_effort: None,
+            qc_round: 1,
+            rounds_to_approval: None,
+            approved_at: None,
+            unapproved: None,
+            approved_commit_sha: None,
+            approved_file_url: None,
+            issue_url: format!("https://github.com/owner/repo/issues/{number}"),
+            superseded_by: None,
+            inline_checklist: None,
+            relevant_files: Vec::new(),
+            state: "Closed".to_string(),
+            closed_by: None,
+            closed_at: None,
+            body: format!("Body of {title}"),
+            comments: Vec::new(),
+            events: Vec::new(),
+            timeline: Vec::new(),
+        }
+    }
+
+    fn mock_env() -> MockEnvProvider {
+        let mut env = MockEnvProvider::new();
+        env.expect_var().returning(|key| match key {
+            "USER" => Ok("tester".to_string()),
+            "GHQC_RECORD_DATE" => Ok("January 01, 2025".to_string()),
+            _ => Err(std::env::VarError::NotPresent),
+        });
+        env
+    }
+
+    #[test]
+    fn test_record_by_milestone_isolates_milestones() {
+        let milestones = vec![load_milestone("v1.0"), load_milestone("v2.0")];
+        let issues = HashMap::from([
+            (
+                "v1.0".to_string(),
+                vec![
+                    issue_information(1, "scripts/alpha_model.R", "v1.0"),
+                    issue_information(2, "scripts/alpha_plots.R", "v1.0"),
+                ],
+            ),
+            (
+                "v2.0".to_string(),
+                vec![issue_information(3, "scripts/beta_report.R", "v2.0")],
+            ),
+        ]);
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let records = record_by_milestone(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions::default(),
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].milestone, "v1.0");
+        assert_eq!(records[0].issue_count, 2);
+        assert_eq!(records[1].milestone, "v2.0");
+        assert_eq!(records[1].issue_count, 1);
+
+        assert!(records[0].record.contains("scripts/alpha_model.R"));
+        assert!(records[0].record.contains("scripts/alpha_plots.R"));
+        assert!(!records[0].record.contains("scripts/beta_report.R"));
+
+        assert!(records[1].record.contains("scripts/beta_report.R"));
+        assert!(!records[1].record.contains("scripts/alpha_model.R"));
+        assert!(!records[1].record.contains("scripts/alpha_plots.R"));
+    }
+
+    #[test]
+    fn test_record_by_milestone_skips_milestones_without_issues() {
+        let milestones = vec![load_milestone("v1.0"), load_milestone("v2.0")];
+        let issues = HashMap::from([(
+            "v2.0".to_string(),
+            vec![issue_information(3, "scripts/beta_report.R", "v2.0")],
+        )]);
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let records = record_by_milestone(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions {
+                only_tables: true,
+                ..Default::default()
+            },
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].milestone, "v2.0");
+    }
+
+    #[test]
+    fn test_record_disambiguates_duplicate_titles() {
+        let milestones = vec![load_milestone("v1.0")];
+        let older = IssueInformation {
+            created_at: "2024-11-15 16:30:00".to_string(),
+            ..issue_information(7, "scripts/analysis.R", "v1.0")
+        };
+        let issues = HashMap::from([(
+            "v1.0".to_string(),
+            vec![older, issue_information(42, "scripts/analysis.R", "v1.0")],
+        )]);
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let rendered = record(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions::default(),
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        assert!(rendered.contains("== scripts/analysis.R (\\#7) <issue-7>"));
+        assert!(rendered.contains("== scripts/analysis.R (\\#42) <issue-42>"));
+        assert_eq!(rendered.matches("- *Superseded by:*").count(), 1);
+        assert!(rendered.contains("- *Superseded by:* Issue \\#42"));
+    }
+
+    #[test]
+    fn test_record_with_coverage() {
+        let milestones = vec![load_milestone("v1.0")];
+        let issues = HashMap::from([(
+            "v1.0".to_string(),
+            vec![issue_information(1, "scripts/alpha_model.R", "v1.0")],
+        )]);
+        let coverage = QCCoverage::new(
+            &[
+                PathBuf::from("scripts/alpha_model.R"),
+                PathBuf::from("scripts/data_prep.R"),
+            ],
+            &[PathBuf::from("scripts")],
+            &[CoveredFile {
+                file: PathBuf::from("scripts/alpha_model.R"),
+                approved: true,
+            }],
+        );
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let with_coverage = record(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions {
+                only_tables: true,
+                coverage: Some(coverage),
+                ..Default::default()
+            },
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        assert!(with_coverage.contains("= QC Coverage"));
+        assert!(with_coverage.contains("- *Files in Scope:* 2"));
+        assert!(with_coverage.contains("- *Approved QC:* 1 (50.0%)"));
+        assert!(with_coverage.contains("= Appendix: Files Without QC"));
+        assert!(with_coverage.contains("[scripts/data\\_prep.R]"));
+
+        let without_coverage = record(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions::default(),
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        assert!(!without_coverage.contains("QC Coverage"));
+        assert!(!without_coverage.contains("Files Without QC"));
+    }
+
+    #[test]
+    fn test_record_file_versions_appendix() {
+        use crate::record::{images::MockHttpDownloader, typst::TypstWorld};
+        use typst::layout::PagedDocument;
+
+        let milestones = vec![load_milestone("v1.0")];
+        let approved = IssueInformation {
+            approved_at: Some("2025-01-03 10:00:00".to_string()),
+            unapproved: None,
+            approved_commit_sha: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+            approved_file_url: Some(
+                "https://github.com/owner/repo/blob/0123456789abcdef0123456789abcdef01234567/scripts/model.R"
+                    .to_string(),
+            ),
+            ..issue_information(1, "scripts/model.R", "v1.0")
+        };
+        let unapproved = IssueInformation {
+            qc_status: "Awaiting review".to_string(),
+            ..issue_information(2, "scripts/plots.R", "v1.0")
+        };
+        let issues = HashMap::from([("v1.0".to_string(), vec![approved, unapproved])]);
+        let staging_dir = tempfile::tempdir().unwrap();
+        let render = |appendix| {
+            record(
+                &milestones,
+                &issues,
+                &Configuration::default(),
+                &TestRepo,
+                &mock_env(),
+                &RecordOptions {
+                    only_tables: true,
+                    appendix,
+                    ..Default::default()
+                },
+                staging_dir.path(),
+            )
+            .unwrap()
+        };
+
+        let with_appendix = render(true);
+        let without_appendix = render(false);
+        insta::assert_snapshot!("record_with_file_versions_appendix", with_appendix);
+        insta::assert_snapshot!("record_without_file_versions_appendix", without_appendix);
+        assert!(!without_appendix.contains("Approved File Versions"));
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let mut http = MockHttpDownloader::new();
+        http.expect_download().times(0);
+        let world = TypstWorld::new(
+            staging_dir.path(),
+            with_appendix,
+            cache_dir.path(),
+            http,
+            RecordFormat::Pdf,
+        );
+        let paged = typst::compile::<PagedDocument>(&world).output;
+        assert!(
+            paged.is_ok(),
+            "record with appendix failed to compile: {paged:?}"
+        );
+    }
+
+    #[test]
+    fn test_record_shows_effective_and_recorded_approval_dates() {
+        let milestones = vec![load_milestone("v1.0")];
+        let mut backfilled = issue_information(1, "scripts/alpha_model.R", "v1.0");
+        backfilled.approved_at = Some("2024-05-02 (recorded 2024-05-06)".to_string());
+        let issues = HashMap::from([(
+            "v1.0".to_string(),
+            vec![
+                backfilled,
+                issue_information(2, "scripts/alpha_plots.R", "v1.0"),
+            ],
+        )]);
+        let staging_dir = tempfile::tempdir().unwrap();
+
+        let record = record(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions::default(),
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        assert!(record.contains("- *Approved:* 2024-05-02 (recorded 2024-05-06)"));
+        assert_eq!(record.matches("- *Approved:*").count(), 1);
+    }
+
+    #[test]
+    fn test_record_formats_render_from_same_issues() {
+        use crate::record::{images::MockHttpDownloader, typst::TypstWorld};
+        use typst::layout::PagedDocument;
+        use typst_html::HtmlDocument;
+
+        let milestones = vec![load_milestone("v1.0")];
+        let issues = HashMap::from([(
+            "v1.0".to_string(),
+            vec![issue_information(1, "scripts/model.R", "v1.0")],
+        )]);
+        let staging_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let world = |source: &str, format| {
+            let mut http = MockHttpDownloader::new();
+            http.expect_download().times(0);
+            TypstWorld::new(
+                staging_dir.path(),
+                source.to_string(),
+                cache_dir.path(),
+                http,
+                format,
+            )
+        };
+
+        let pdf = record(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions::default(),
+            staging_dir.path(),
+        )
+        .unwrap();
+        let html = record(
+            &milestones,
+            &issues,
+            &Configuration::default(),
+            &TestRepo,
+            &mock_env(),
+            &RecordOptions {
+                format: RecordFormat::Html,
+                ..Default::default()
+            },
+            staging_dir.path(),
+        )
+        .unwrap();
+
+        insta::assert_snapshot!("record_pdf_source", pdf);
+        insta::assert_snapshot!("record_html_source", html);
+
+        let paged = typst::compile::<PagedDocument>(&world(&pdf, RecordFormat::Pdf)).output;
+        assert!(paged.is_ok(), "PDF record failed to compile: {paged:?}");
+
+        let document = typst::compile::<HtmlDocument>(&world(&html, RecordFormat::Html))
+            .output
+            .expect("HTML record failed to compile");
+        let page = typst_html::html(&document).unwrap();
+        assert!(page.contains("<h1>QC Record: v1.0</h1>"));
+        assert!(page.contains(
+            r#"<a href="https://github.com/owner/repo/issues/1">https://github.com/owner/repo/issues/1</a>"#
+        ));
+    }
+
+    #[test]
+    fn test_milestone_record_file_name() {
+        assert_eq!(
+            milestone_record_file_name("repo", "Sprint 1: Data/Models", RecordFormat::Pdf),
+            "repo_Sprint-1-Data-Models.pdf"
+        );
+        assert_eq!(
+            milestone_record_file_name("repo", "v1.0", RecordFormat::Html),
+            "repo_v1.0.html"
+        );
+    }
+
+    #[test]
+    fn test_record_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let v1_path = dir.path().join("repo_v1.0.pdf");
+        let v2_path = dir.path().join("repo_v2.0.pdf");
+        std::fs::write(&v1_path, b"abc").unwrap();
+        std::fs::write(&v2_path, b"").unwrap();
+
+        let entries = vec![
+            RecordIndexEntry::from_file("v1.0", 2, &v1_path).unwrap(),
+            RecordIndexEntry::from_file("v2.0", 1, &v2_path).unwrap(),
+        ];
+
+        assert_eq!(
+            entries[0].sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        insta::assert_snapshot!(record_index("repo", "January 01, 2025", &entries));
+    }
+}
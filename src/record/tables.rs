@@ -2,7 +2,7 @@ use octocrab::models::Milestone;
 use std::collections::HashMap;
 use tera::{Result as TeraResult, Value};
 
-use super::typst::escape_typst;
+use super::typst::{escape_typst, escape_typst_string};
 use super::{IssueInformation, MilestoneRow};
 
 /// Create milestone dataframe equivalent to R function
@@ -22,8 +22,11 @@ pub fn create_milestone_df(
             .map(|issue| {
                 let mut issue_name = insert_breaks(&issue.title, 42);
 
+                // O = Obsolete: issue was taken out of QC scope
                 // U = Unapproved: status is not "Approved" or "Approved; subsequent file changes"
-                if !issue.qc_status.contains("Approved") {
+                if issue.qc_status.starts_with("Obsolete") {
+                    issue_name = format!("{} #text(fill: gray)[O]", issue_name);
+                } else if !issue.qc_status.contains("Approved") {
                     issue_name = format!("{} #text(fill: red)[U]", issue_name);
                 }
 
@@ -178,6 +181,12 @@ pub fn render_issue_summary_table_rows(args: &HashMap<String, Value>) -> TeraRes
         return Ok(Value::String(String::new()));
     }
 
+    // Optional QC effort column, shown when any issue in the milestone has logged time
+    let qc_effort = args
+        .get("qc_effort")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
     let mut table_rows = Vec::new();
 
     // Add data rows as Typst table cells
@@ -200,19 +209,66 @@ pub fn render_issue_summary_table_rows(args: &HashMap<String, Value>) -> TeraRes
             .map(|closer| closer.split(" (").next().unwrap_or(closer))
             .unwrap_or("NA");
 
-        table_rows.push(format!(
-            "[{}], [{}], [{}], [{}], [{}],",
+        let rounds_to_approval = row
+            .rounds_to_approval
+            .map(|round| round.to_string())
+            .unwrap_or_else(|| "NA".to_string());
+
+        let mut table_row = format!(
+            "[{}], [{}], [{}], [{}], [{}], [{}],",
             insert_breaks(&row.title, 18),
             insert_breaks(&row.qc_status, 14),
+            rounds_to_approval,
             insert_breaks(author_display, 14),
             insert_breaks(&qcer_display, 14),
             insert_breaks(closer_display, 14)
-        ));
+        );
+        if qc_effort {
+            table_row.push_str(&format!(" [{}],", row.qc_effort.as_deref().unwrap_or("NA")));
+        }
+        table_rows.push(table_row);
     }
 
     Ok(Value::String(table_rows.join("\n")))
 }
 
+/// Tera function to render the rows of the approved file versions appendix (Typst format)
+///
+/// Issues without a standing approval show `N/A` for the commit, link and date.
+pub fn render_file_versions_table_rows(args: &HashMap<String, Value>) -> TeraResult<Value> {
+    let data = args
+        .get("data")
+        .ok_or_else(|| tera::Error::msg("Missing 'data' argument for file versions table"))?;
+
+    let rows: Vec<IssueInformation> = serde_json::from_value(data.clone())
+        .map_err(|e| tera::Error::msg(format!("Failed to parse file versions data: {}", e)))?;
+
+    let table_rows = rows
+        .iter()
+        .map(|row| {
+            let commit = row
+                .approved_commit_sha
+                .as_deref()
+                .map(|sha| sha.chars().take(7).collect::<String>())
+                .unwrap_or_else(|| "N/A".to_string());
+            let link = row
+                .approved_file_url
+                .as_deref()
+                .map(|url| format!("#link(\"{}\")[View file]", escape_typst_string(url)))
+                .unwrap_or_else(|| "N/A".to_string());
+            format!(
+                "[{}], [{}], [{}], [{}],",
+                insert_breaks(&row.title, 30),
+                commit,
+                link,
+                row.approved_at.as_deref().unwrap_or("N/A")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Value::String(table_rows.join("\n")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,7 +301,19 @@ mod tests {
             git_status: "Clean".to_string(),
             initial_qc_commit: "abc123".to_string(),
             latest_qc_commit: "def456".to_string(),
+            commit_signatures: Vec::new(),
+            attachments: Vec::new(),
+            qc_effort: None,
+            qc_round: 1,
+            rounds_to_approval: None,
+            approved_at: None,
+            unapproved: None,
+            approved_commit_sha: None,
+            approved_file_url: None,
             issue_url: "https://github.com/owner/repo/issues/1".to_string(),
+            superseded_by: None,
+            inline_checklist: None,
+            relevant_files: Vec::new(),
             state: "Open".to_string(),
             closed_by: None,
             closed_at: None,
@@ -351,6 +419,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_milestone_df_marks_obsolete_issues() {
+        let milestones = vec![load_test_milestone("v1.0.json")];
+        let issues = HashMap::from([(
+            "v1.0".to_string(),
+            vec![create_test_issue_information(
+                "Dropped Issue",
+                "100.0%",
+                "Obsolete: Dropped from the analysis plan",
+            )],
+        )]);
+
+        let result = create_milestone_df(&milestones, &issues).unwrap();
+        insta::assert_snapshot!(result[0].issues, @"Dropped Issue #text(fill: gray)[O]");
+
+        let mut args = HashMap::new();
+        args.insert(
+            "data".to_string(),
+            serde_json::to_value(&issues["v1.0"]).unwrap(),
+        );
+        let rows = render_issue_summary_table_rows(&args).unwrap();
+        insta::assert_snapshot!(rows.as_str().unwrap(), @r"
+        [Dropped Issue], [Obsolete:
+        Dropped from
+        the analysis
+        plan], [NA], [author], [qcer1], [NA],
+        ");
+    }
+
+    #[test]
+    fn test_create_milestone_df_disambiguates_duplicate_titles() {
+        let milestones = vec![load_test_milestone("v1.0.json")];
+        let older = IssueInformation {
+            number: 7,
+            created_at: "2024-11-15 16:30:00".to_string(),
+            ..create_test_issue_information("scripts/analysis.R", "100.0%", "Approved")
+        };
+        let newer = IssueInformation {
+            number: 42,
+            created_at: "2025-03-01 09:00:00".to_string(),
+            ..create_test_issue_information("scripts/analysis.R", "100.0%", "In Progress")
+        };
+        let issues = HashMap::from([(
+            "v1.0".to_string(),
+            super::super::disambiguate_titles(&[older, newer]),
+        )]);
+
+        let result = create_milestone_df(&milestones, &issues).unwrap();
+        insta::assert_snapshot!(result[0].issues, @r"
+        scripts/analysis.R (\#7)
+
+        scripts/analysis.R (\#42) #text(fill: red)[U]
+        ");
+
+        let mut args = HashMap::new();
+        args.insert(
+            "data".to_string(),
+            serde_json::to_value(&issues["v1.0"]).unwrap(),
+        );
+        let rows = render_issue_summary_table_rows(&args).unwrap();
+        insta::assert_snapshot!(rows.as_str().unwrap(), @r"
+        [scripts/analysis.R
+        (\#7)], [Approved], [NA], [author], [qcer1], [NA],
+        [scripts/analysis.R
+        (\#42)], [In Progress], [NA], [author], [qcer1], [NA],
+        ");
+    }
+
     #[test]
     fn test_create_milestone_df_empty_issues() {
         let milestone = load_test_milestone("v1.0.json");
@@ -405,8 +541,10 @@ mod tests {
         let result_str = result.as_str().unwrap();
 
         // Should contain Typst table cells
-        assert!(result_str.contains("[Test Issue 1], [In Progress], [author], [qcer1], [NA],"));
-        assert!(result_str.contains("[Test Issue 2], [Approved], [author], [qcer1], [NA],"));
+        assert!(
+            result_str.contains("[Test Issue 1], [In Progress], [NA], [author], [qcer1], [NA],")
+        );
+        assert!(result_str.contains("[Test Issue 2], [Approved], [NA], [author], [qcer1], [NA],"));
     }
 
     #[test]
@@ -429,6 +567,55 @@ mod tests {
         assert!(result_str.contains("Awaiting\nreview"));
     }
 
+    #[test]
+    fn test_render_issue_summary_table_rows_qc_effort() {
+        let mut logged = create_test_issue_information("Test Issue 1", "50.0%", "In Progress");
+        logged.qc_effort = Some("1.5h".to_string());
+        let rows = vec![
+            logged,
+            create_test_issue_information("Test Issue 2", "100.0%", "Approved"),
+        ];
+
+        let mut args = HashMap::new();
+        args.insert("data".to_string(), serde_json::to_value(&rows).unwrap());
+
+        let without_column = render_issue_summary_table_rows(&args).unwrap();
+        assert!(!without_column.as_str().unwrap().contains("1.5h"));
+
+        args.insert("qc_effort".to_string(), Value::Bool(true));
+        let result = render_issue_summary_table_rows(&args).unwrap();
+        let result_str = result.as_str().unwrap();
+
+        assert!(
+            result_str
+                .contains("[Test Issue 1], [In Progress], [NA], [author], [qcer1], [NA], [1.5h],")
+        );
+        assert!(
+            result_str.contains("[Test Issue 2], [Approved], [NA], [author], [qcer1], [NA], [NA],")
+        );
+    }
+
+    #[test]
+    fn test_render_issue_summary_table_rows_rounds_to_approval() {
+        let mut approved = create_test_issue_information("Test Issue 1", "100.0%", "Approved");
+        approved.qc_round = 3;
+        approved.rounds_to_approval = Some(3);
+
+        let mut args = HashMap::new();
+        args.insert(
+            "data".to_string(),
+            serde_json::to_value(vec![approved]).unwrap(),
+        );
+
+        let result = render_issue_summary_table_rows(&args).unwrap();
+        assert!(
+            result
+                .as_str()
+                .unwrap()
+                .contains("[Test Issue 1], [Approved], [3], [author], [qcer1], [NA],")
+        );
+    }
+
     #[test]
     fn test_render_issue_summary_table_rows_empty() {
         let rows: Vec<IssueInformation> = vec![];
@@ -1,4 +1,5 @@
 use super::images::{HttpDownloader, replace_images_with_typst};
+use super::render::RecordFormat;
 /// Typst formatting utilities for the record generation system.
 /// This module handles markdown processing and Typst escaping.
 use crate::issue::HTML_LINK_REGEX;
@@ -14,7 +15,7 @@ use typst::syntax::package::PackageSpec;
 use typst::syntax::{FileId, Source};
 use typst::text::FontBook;
 use typst::utils::LazyHash;
-use typst::{Library, LibraryExt};
+use typst::{Feature, Library, LibraryExt};
 use typst_kit::fonts::{FontSearcher, FontSlot};
 
 // Regex for markdown bold **text**
@@ -30,10 +31,6 @@ static MARKDOWN_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").expect("Invalid markdown link regex")
 });
 
-// Regex for inline markdown code `code`
-static INLINE_CODE_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"`([^`\n]+)`").expect("Invalid inline code regex"));
-
 // Regex for bare URLs (http/https not already inside a markdown/HTML link)
 static BARE_URL_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r#"https?://[^\s\[\]<>()""]+"#).expect("Invalid bare URL regex"));
@@ -137,12 +134,10 @@ fn convert_inline_markdown(text: &str) -> String {
         )
     });
 
-    // Step 3: Convert inline markdown code to Typst raw text
-    let with_inline_code =
-        INLINE_CODE_REGEX.replace_all(&with_bare_urls, |caps: &regex::Captures| {
-            let code = &caps[1];
-            protect_code(format!("`{code}`"), &mut protected_code)
-        });
+    // Step 3: Convert inline markdown code spans to Typst raw text
+    let with_inline_code = replace_code_spans(&with_bare_urls, |code| {
+        protect_code(typst_raw(code), &mut protected_code)
+    });
 
     // Step 4: Convert **bold** to Typst strong text via placeholders.
     let with_bold_placeholder =
@@ -197,6 +192,68 @@ fn convert_inline_markdown(text: &str) -> String {
         })
 }
 
+/// Replace the CommonMark code spans of a line with `replace(code)`
+///
+/// A span opens with a run of backticks and closes at the next run of the same length, so
+/// ``` ``a ` b`` ``` holds a backtick. Runs without a closing run are left as literal backticks.
+fn replace_code_spans(line: &str, mut replace: impl FnMut(&str) -> String) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find('`') {
+        result.push_str(&rest[..start]);
+        let run = backtick_run(&rest[start..]);
+        let after = &rest[start + run..];
+
+        match find_backtick_run(after, run) {
+            Some(end) => {
+                let code = &after[..end];
+                // One space of padding on both sides is stripped, unless the span is all spaces
+                let code = match code.strip_prefix(' ').and_then(|c| c.strip_suffix(' ')) {
+                    Some(stripped) if !code.trim().is_empty() => stripped,
+                    _ => code,
+                };
+                result.push_str(&replace(code));
+                rest = &after[end + run..];
+            }
+            None => {
+                result.push_str(&rest[start..start + run]);
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn backtick_run(text: &str) -> usize {
+    text.bytes().take_while(|&b| b == b'`').count()
+}
+
+/// Byte offset of the first run of exactly `len` backticks in `text`
+fn find_backtick_run(text: &str, len: usize) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(pos) = text[offset..].find('`') {
+        let start = offset + pos;
+        let run = backtick_run(&text[start..]);
+        if run == len {
+            return Some(start);
+        }
+        offset = start + run;
+    }
+    None
+}
+
+/// Typst inline raw text for a code span
+fn typst_raw(code: &str) -> String {
+    if code.contains('`') {
+        format!("#raw(\"{}\")", escape_typst_string(code))
+    } else {
+        format!("`{code}`")
+    }
+}
+
 fn escape_typst_inline_text(text: &str) -> String {
     text.replace('\\', "\\\\")
         .replace('#', "\\#")
@@ -211,7 +268,7 @@ fn escape_typst_inline_text(text: &str) -> String {
         .replace(']', "\\]")
 }
 
-fn escape_typst_string(text: &str) -> String {
+pub(crate) fn escape_typst_string(text: &str) -> String {
     text.replace('\\', "\\\\").replace('"', "\\\"")
 }
 
@@ -233,52 +290,40 @@ pub fn format_markdown(
         let line = lines[i];
         let trimmed = line.trim_start();
 
-        if trimmed.starts_with("```") {
-            let fence_len = trimmed.chars().take_while(|&c| c == '`').count();
-            let lang = trimmed[fence_len..].trim();
-            let is_diff_block = lang == "diff";
-            let mut block_lines = Vec::new();
+        if let Some((fence_char, fence_len, lang)) = code_fence(trimmed) {
             let mut j = i + 1;
-
-            while j < lines.len() {
-                let candidate = lines[j];
-                let candidate_trimmed = candidate.trim_start();
-                let closing_fence_len = candidate_trimmed.chars().take_while(|&c| c == '`').count();
-                if closing_fence_len >= fence_len
-                    && candidate_trimmed[closing_fence_len..].trim().is_empty()
-                {
-                    break;
-                }
-
-                if is_diff_block && (candidate.starts_with('+') || candidate.starts_with('-')) {
-                    block_lines.extend(wrap_diff_line(candidate, 80));
-                } else if candidate.len() > 75 {
-                    block_lines.extend(simple_wrap_line(candidate, 75));
-                } else {
-                    block_lines.push(candidate.to_string());
-                }
+            while j < lines.len() && !closes_fence(lines[j], fence_char, fence_len) {
                 j += 1;
             }
 
-            let delimiter_len = std::cmp::max(
-                3,
-                std::cmp::max(
-                    fence_len,
-                    max_backtick_run(block_lines.iter().map(|s| s.as_str())) + 1,
-                ),
-            );
-            let delimiter = "`".repeat(delimiter_len);
-            let opening = if lang.is_empty() {
-                delimiter.clone()
-            } else {
-                format!("{delimiter}{lang}")
-            };
+            // Typst only has backtick raw blocks, at least as long as the original fence
+            let min_delimiter = if fence_char == '`' { fence_len } else { 3 };
+            push_code_block(&mut result, lang, &lines[i + 1..j], min_delimiter);
+            i = if j < lines.len() { j + 1 } else { j };
+            continue;
+        }
 
-            result.push(opening);
-            result.extend(block_lines);
-            result.push(delimiter);
+        // Indented code blocks start after a blank line, outside of list items
+        if starts_indented_code(&lines, i) {
+            let mut j = i;
+            while j < lines.len() && (is_indented_code(lines[j]) || lines[j].trim().is_empty()) {
+                j += 1;
+            }
+            // Trailing blank lines are not part of the block
+            while lines[j - 1].trim().is_empty() {
+                j -= 1;
+            }
 
-            i = if j < lines.len() { j + 1 } else { j };
+            let block_lines = lines[i..j]
+                .iter()
+                .map(|line| {
+                    line.strip_prefix("    ")
+                        .or_else(|| line.strip_prefix('\t'))
+                        .unwrap_or(line.trim_start())
+                })
+                .collect::<Vec<_>>();
+            push_code_block(&mut result, "", &block_lines, 3);
+            i = j;
             continue;
         }
 
@@ -340,6 +385,82 @@ pub fn format_markdown(
     result.join("\n")
 }
 
+/// An opening code fence of ``` or ~~~: its character, length and info string
+fn code_fence(trimmed: &str) -> Option<(char, usize, &str)> {
+    let fence_char = trimmed.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let fence_len = trimmed.chars().take_while(|&c| c == fence_char).count();
+    let info = trimmed[fence_len..].trim();
+    // Backtick fences cannot have backticks in their info string, or they'd be inline code
+    if fence_len < 3 || (fence_char == '`' && info.contains('`')) {
+        return None;
+    }
+    Some((fence_char, fence_len, info))
+}
+
+/// Whether `line` closes a fence of `fence_len` `fence_char`s: at least as long, with nothing after
+fn closes_fence(line: &str, fence_char: char, fence_len: usize) -> bool {
+    let trimmed = line.trim_start();
+    let len = trimmed.chars().take_while(|&c| c == fence_char).count();
+    len >= fence_len && trimmed[len..].trim().is_empty()
+}
+
+fn is_indented_code(line: &str) -> bool {
+    (line.starts_with("    ") || line.starts_with('\t')) && !line.trim().is_empty()
+}
+
+fn is_list_item(trimmed: &str) -> bool {
+    let after_number = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+    ["- ", "* ", "+ "].iter().any(|b| trimmed.starts_with(b))
+        || (after_number.len() < trimmed.len()
+            && (after_number.starts_with(". ") || after_number.starts_with(") ")))
+}
+
+/// Whether an indented code block starts at `lines[i]`
+///
+/// It cannot interrupt a paragraph, and indented text after a list item (or its indented
+/// continuation) belongs to the list.
+fn starts_indented_code(lines: &[&str], i: usize) -> bool {
+    if !is_indented_code(lines[i]) || (i > 0 && !lines[i - 1].trim().is_empty()) {
+        return false;
+    }
+    match lines[..i].iter().rev().find(|line| !line.trim().is_empty()) {
+        Some(previous) => {
+            !is_list_item(previous.trim_start()) && previous.trim_start().len() == previous.len()
+        }
+        None => true,
+    }
+}
+
+/// Push a code block as a Typst raw block, wrapping long lines
+fn push_code_block(result: &mut Vec<String>, lang: &str, lines: &[&str], min_delimiter: usize) {
+    let is_diff_block = lang == "diff";
+    let mut block_lines = Vec::new();
+    for &line in lines {
+        if is_diff_block && (line.starts_with('+') || line.starts_with('-')) {
+            block_lines.extend(wrap_diff_line(line, 80));
+        } else if line.len() > 75 {
+            block_lines.extend(simple_wrap_line(line, 75));
+        } else {
+            block_lines.push(line.to_string());
+        }
+    }
+
+    let delimiter_len = std::cmp::max(
+        min_delimiter,
+        max_backtick_run(block_lines.iter().map(|s| s.as_str())) + 1,
+    );
+    let delimiter = "`".repeat(delimiter_len);
+    let opening = if lang.is_empty() {
+        delimiter.clone()
+    } else {
+        format!("{delimiter}{lang}")
+    };
+
+    result.push(opening);
+    result.extend(block_lines);
+    result.push(delimiter);
+}
+
 fn max_backtick_run<'a>(lines: impl IntoIterator<Item = &'a str>) -> usize {
     lines
         .into_iter()
@@ -487,11 +608,19 @@ impl TypstWorld {
         source: String,
         cache_root: impl AsRef<Path>,
         http: impl HttpDownloader + 'static,
+        format: RecordFormat,
     ) -> Self {
         let root = root.as_ref().to_path_buf();
         let fonts = FontSearcher::new().include_system_fonts(true).search();
+        // HTML export is still gated behind a feature flag in Typst
+        let library = match format {
+            RecordFormat::Pdf => Library::default(),
+            RecordFormat::Html => Library::builder()
+                .with_features([Feature::Html].into_iter().collect())
+                .build(),
+        };
         Self {
-            library: LazyHash::new(Library::default()),
+            library: LazyHash::new(library),
             book: LazyHash::new(fonts.book),
             root,
             fonts: fonts.fonts,
@@ -1210,6 +1339,112 @@ Heading with * stray delimiter
         );
     }
 
+    #[test]
+    fn test_tilde_fences_are_code_blocks() {
+        let empty_image_map = HashMap::new();
+
+        let result = format_markdown("~~~r\n**x** <- @y\n~~~\nAfter *text*", 4, &empty_image_map);
+        assert_eq!(result, "```r\n**x** <- @y\n```\nAfter #emph[text]");
+
+        // A fence only closes with its own character, so ``` inside ~~~ is content
+        let result = format_markdown("~~~~\n```\n~~~\n~~~~", 4, &empty_image_map);
+        assert_eq!(result, "````\n```\n~~~\n````");
+
+        // And ~~~ inside ``` is content too
+        let result = format_markdown("```\n~~~\n*a*\n```", 4, &empty_image_map);
+        assert_eq!(result, "```\n~~~\n*a*\n```");
+    }
+
+    #[test]
+    fn test_fence_info_strings() {
+        let empty_image_map = HashMap::new();
+
+        let result = format_markdown("```python title=x\n*a*\n```", 4, &empty_image_map);
+        assert_eq!(result, "```python title=x\n*a*\n```");
+
+        // Backticks in the info string make it inline code, not a fence
+        let result = format_markdown("```a`b``` *c*", 4, &empty_image_map);
+        assert_eq!(result, "#raw(\"a`b\") #emph[c]");
+
+        // A closing fence cannot have an info string
+        let result = format_markdown("```\n```r\n```", 4, &empty_image_map);
+        assert_eq!(result, "````\n```r\n````");
+    }
+
+    #[test]
+    fn test_indented_code_blocks() {
+        let empty_image_map = HashMap::new();
+
+        let result = format_markdown(
+            "Text\n\n    x <- **1**\n\n    @y\n\nAfter",
+            4,
+            &empty_image_map,
+        );
+        assert_eq!(result, "Text\n\n```\nx <- **1**\n\n@y\n```\n\nAfter");
+
+        let result = format_markdown("\tcode *a*", 4, &empty_image_map);
+        assert_eq!(result, "```\ncode *a*\n```");
+
+        // Indented lines cannot interrupt a paragraph
+        let result = format_markdown("Text\n    *more*", 4, &empty_image_map);
+        assert_eq!(result, "Text\n    #emph[more]");
+
+        // Indented text after a list item continues the item
+        let result = format_markdown("- item\n\n    *more*", 4, &empty_image_map);
+        assert_eq!(result, "- item\n\n    #emph[more]");
+        let result = format_markdown("1. item\n    - sub\n\n    *more*", 4, &empty_image_map);
+        assert_eq!(result, "1. item\n    - sub\n\n    #emph[more]");
+    }
+
+    #[test]
+    fn test_code_spans() {
+        let empty_image_map = HashMap::new();
+
+        // An unpaired backtick does not swallow the following lines
+        let result = format_markdown("a ` *b*\n`c` *d*", 4, &empty_image_map);
+        assert_eq!(result, "a \\` #emph[b]\n`c` #emph[d]");
+
+        // Spans can hold backticks when delimited by longer runs
+        let result = format_markdown("Use ``a ` b`` and `` `x` ``", 4, &empty_image_map);
+        assert_eq!(result, "Use #raw(\"a ` b\") and #raw(\"`x`\")");
+
+        // A span closes only at a run of the same length
+        let result = format_markdown("``a`", 4, &empty_image_map);
+        assert_eq!(result, "\\`\\`a\\`");
+        let result = format_markdown("``a`b``", 4, &empty_image_map);
+        assert_eq!(result, "#raw(\"a`b\")");
+
+        // One space of padding is stripped from both sides, but not from all-space spans
+        let result = format_markdown("` a ` and ` `", 4, &empty_image_map);
+        assert_eq!(result, "`a` and ` `");
+
+        // Formatting characters in code stay literal
+        let result = format_markdown("`*a* @b` *c*", 4, &empty_image_map);
+        assert_eq!(result, "`*a* @b` #emph[c]");
+    }
+
+    #[test]
+    fn test_code_blocks_and_spans_compile() {
+        let markdown = "Paragraph with ``a ` b`` and a stray ` backtick\n\n\
+            ~~~python\n#not a header @ref <label>\n~~~\n\n\
+            \x20   indented = \"#code\" $math$\n\n\
+            - item with `code`\n";
+
+        let empty_image_map = HashMap::new();
+        let formatted = format_markdown(markdown, 4, &empty_image_map);
+        let source = format!("= Test\n\n{}", formatted);
+        let (world, _staging, _cache) = create_test_world(&source);
+
+        let result = typst::compile::<PagedDocument>(&world);
+
+        assert!(
+            result.output.is_ok(),
+            "Compilation failed: {:?}\nFormatted source:\n{}",
+            result.output.err(),
+            source
+        );
+    }
+
     // ===================
     // TypstWorld and compilation tests
     // ===================
@@ -1232,6 +1467,7 @@ Heading with * stray delimiter
             source.to_string(),
             cache_dir.path(),
             mock_downloader,
+            RecordFormat::Pdf,
         );
 
         (world, staging_dir, cache_dir)
@@ -1392,6 +1628,7 @@ Local file says: #content
             source.to_string(),
             cache_dir.path(),
             mock_downloader,
+            RecordFormat::Pdf,
         );
 
         let result = typst::compile::<PagedDocument>(&world);
@@ -1480,6 +1717,7 @@ Today's date: #today.display()
             source.to_string(),
             cache_dir.path(),
             mock_downloader,
+            RecordFormat::Pdf,
         );
 
         // Attempt to compile - this should trigger the package download
@@ -1536,6 +1774,7 @@ entrypoint = "lib.typ"
             source.to_string(),
             cache_dir.path(),
             mock_downloader,
+            RecordFormat::Pdf,
         );
 
         let result = typst::compile::<PagedDocument>(&world);
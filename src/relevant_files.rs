@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use gix::ObjectId;
 use octocrab::models::issues::Issue;
 
+#[cfg(feature = "cli")]
+use crate::utils::{normalize_repo_path, same_repo_path};
 use crate::{
     comment_system::CommentBody,
     diff_utils,
@@ -37,7 +39,11 @@ pub enum RelevantFileClass {
     },
     /// A file which has no associated issue that is relevant to the current QC.
     /// A justification for the lack of QC is required
-    File { justification: String },
+    File {
+        justification: String,
+        /// The version of the file that is relevant, linked from the issue body
+        commit: Option<ObjectId>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +52,63 @@ pub struct RelevantFile {
     pub(crate) class: RelevantFileClass,
 }
 
+/// Closed QC issues of `file` in milestones other than `milestone_number`, newest first
+///
+/// These are the earlier QCs of a file re-QCed in a new milestone, to be linked as previous QCs.
+#[cfg(feature = "cli")]
+pub(crate) fn prior_qc_issues<'a>(
+    file: &Path,
+    issues: &'a [Issue],
+    milestone_number: Option<u64>,
+) -> Vec<&'a Issue> {
+    let mut priors = issues
+        .iter()
+        .filter(|i| same_repo_path(Path::new(&i.title), file))
+        .filter(|i| matches!(i.state, octocrab::models::IssueState::Closed))
+        .filter(|i| {
+            milestone_number.is_none()
+                || i.milestone.as_ref().map(|m| m.number as u64) != milestone_number
+        })
+        .collect::<Vec<_>>();
+    priors.sort_by(|a, b| {
+        b.closed_at
+            .unwrap_or(b.created_at)
+            .cmp(&a.closed_at.unwrap_or(a.created_at))
+            .then(b.number.cmp(&a.number))
+    });
+    priors
+}
+
+impl RelevantFile {
+    /// Previous QC entry for an issue found by [`prior_qc_issues`], with its diff comment
+    #[cfg(feature = "cli")]
+    pub(crate) fn detected_previous_qc(issue: &Issue) -> Self {
+        Self {
+            file_name: normalize_repo_path(Path::new(&issue.title)),
+            class: RelevantFileClass::PreviousQC {
+                issue_number: issue.number,
+                issue_id: Some(issue.id.0),
+                description: issue
+                    .milestone
+                    .as_ref()
+                    .map(|m| format!("QCed in milestone {}", m.title)),
+                include_diff: true,
+            },
+        }
+    }
+
+    /// Issue number of a QC entry, `None` for a plain file
+    #[cfg(feature = "cli")]
+    pub(crate) fn issue_number(&self) -> Option<u64> {
+        match &self.class {
+            RelevantFileClass::PreviousQC { issue_number, .. }
+            | RelevantFileClass::GatingQC { issue_number, .. }
+            | RelevantFileClass::RelevantQC { issue_number, .. } => Some(*issue_number),
+            RelevantFileClass::File { .. } => None,
+        }
+    }
+}
+
 pub(crate) fn relevant_files_section(
     relevant_files: &[RelevantFile],
     git_info: &impl GitHelpers,
@@ -102,9 +165,22 @@ pub(crate) fn relevant_files_section(
                     &file.file_name,
                 ));
             }
-            RelevantFileClass::File { justification } => {
+            RelevantFileClass::File {
+                justification,
+                commit,
+            } => {
+                let commit_link = commit
+                    .map(|commit| {
+                        let commit = commit.to_string();
+                        format!(
+                            " ([{}]({}))",
+                            &commit[..7],
+                            git_info.file_content_url(&commit, &file.file_name)
+                        )
+                    })
+                    .unwrap_or_default();
                 rel_file.push(format!(
-                    "**{}** - {justification}",
+                    "**{}**{commit_link} - {justification}",
                     file.file_name.display()
                 ));
             }
@@ -211,7 +287,13 @@ impl PreviousQCDiffComment {
                 }
             };
 
-        match diff_utils::file_diff(prev_bytes, curr_bytes, &self.current_file) {
+        match diff_utils::file_diff_with_attributes(
+            prev_bytes,
+            curr_bytes,
+            &self.current_file,
+            git_info,
+            false,
+        ) {
             Some(diff) => format!(
                 "<details>\n<summary>View diff</summary>\n\n{}\n\n</details>",
                 diff
@@ -293,6 +375,18 @@ mod tests {
         fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
             Ok(Vec::new())
         }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<crate::git::PathAttributes, GitFileOpsError> {
+            Ok(Default::default())
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
     }
 
     fn load_issue() -> octocrab::models::issues::Issue {
@@ -393,6 +487,7 @@ mod tests {
             file_name: PathBuf::from("data/raw.csv"),
             class: RelevantFileClass::File {
                 justification: "No QC required for raw input".to_string(),
+                commit: None,
             },
         }];
         let result = relevant_files_section(&files, &git());
@@ -401,6 +496,33 @@ mod tests {
         assert!(result.contains("No QC required for raw input"));
     }
 
+    #[test]
+    fn test_section_file_with_commit() {
+        let files = vec![
+            RelevantFile {
+                file_name: PathBuf::from("data/config.yaml"),
+                class: RelevantFileClass::File {
+                    justification: "Model settings at the time of the run".to_string(),
+                    commit: Some(ObjectId::from_str(PREV_COMMIT).unwrap()),
+                },
+            },
+            RelevantFile {
+                file_name: PathBuf::from("data/raw.csv"),
+                class: RelevantFileClass::File {
+                    justification: "raw input".to_string(),
+                    commit: None,
+                },
+            },
+        ];
+        insta::assert_snapshot!(relevant_files_section(&files, &git()), @r"
+        ## Relevant Files
+
+        ### Relevant File
+        - **data/config.yaml** ([aaaaaaa](https://github.com/owner/repo/blob/aaaaaaa/data/config.yaml)) - Model settings at the time of the run
+        - **data/raw.csv** - raw input
+        ");
+    }
+
     #[test]
     fn test_section_all_types_present() {
         let files = vec![
@@ -432,6 +554,7 @@ mod tests {
                 file_name: PathBuf::from("data/raw.csv"),
                 class: RelevantFileClass::File {
                     justification: "raw input".to_string(),
+                    commit: None,
                 },
             },
         ];
@@ -457,6 +580,82 @@ mod tests {
         assert!(result.starts_with("## Relevant Files"));
     }
 
+    #[test]
+    fn test_section_round_trips_through_parse_relevant_files() {
+        use crate::issue::{IssueRelevantFile, parse_relevant_files};
+
+        let parse = |files: &[RelevantFile]| {
+            parse_relevant_files(&format!(
+                "## Metadata\n* git branch: main\n\n{}\n\n# Code Review\n- [ ] **Inputs** - checked",
+                relevant_files_section(files, &git())
+            ))
+        };
+        let entry = |file: &str, kind: &str, justification: Option<&str>| IssueRelevantFile {
+            file: PathBuf::from(file),
+            kind: kind.to_string(),
+            justification: justification.map(str::to_string),
+        };
+
+        assert!(parse(&[]).is_empty());
+
+        let config = RelevantFile {
+            file_name: PathBuf::from("data/config.yaml"),
+            class: RelevantFileClass::File {
+                justification: "Model settings - [v2](https://example.com)".to_string(),
+                commit: Some(ObjectId::from_str(PREV_COMMIT).unwrap()),
+            },
+        };
+        assert_eq!(
+            parse(std::slice::from_ref(&config)),
+            vec![entry(
+                "data/config.yaml",
+                "Relevant File",
+                Some("Model settings - [v2](https://example.com)")
+            )]
+        );
+
+        let files = vec![
+            RelevantFile {
+                file_name: PathBuf::from("src/old.R"),
+                class: RelevantFileClass::PreviousQC {
+                    issue_number: 1,
+                    issue_id: None,
+                    description: Some("Previous version".to_string()),
+                    include_diff: true,
+                },
+            },
+            RelevantFile {
+                file_name: PathBuf::from("src/gate.R"),
+                class: RelevantFileClass::GatingQC {
+                    issue_number: 2,
+                    issue_id: None,
+                    description: None,
+                },
+            },
+            RelevantFile {
+                file_name: PathBuf::from("src/ctx.R"),
+                class: RelevantFileClass::RelevantQC {
+                    issue_number: 3,
+                    description: Some("Shared helpers".to_string()),
+                },
+            },
+            config,
+        ];
+        assert_eq!(
+            parse(&files),
+            vec![
+                entry("src/old.R", "Previous QC", Some("Previous version")),
+                entry("src/gate.R", "Gating QC", None),
+                entry("src/ctx.R", "Relevant QC", Some("Shared helpers")),
+                entry(
+                    "data/config.yaml",
+                    "Relevant File",
+                    Some("Model settings - [v2](https://example.com)")
+                ),
+            ]
+        );
+    }
+
     // ─── PreviousQCDiffComment::generate_body ─────────────────────────────────
 
     #[test]
@@ -545,4 +744,72 @@ mod tests {
         let diff = comment.build_diff(&git_info);
         assert!(diff.is_empty());
     }
+
+    #[cfg(feature = "cli")]
+    fn qc_issue(number: u64, title: &str, milestone: i64, state: &str, closed: i64) -> Issue {
+        let mut issue = crate::test_utils::create_test_issue(
+            "owner",
+            "repo",
+            number,
+            title,
+            "",
+            Some(milestone),
+            state,
+        );
+        issue.closed_at = chrono::DateTime::from_timestamp(1_700_000_000 + closed, 0);
+        issue
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_prior_qc_issues_none() {
+        let issues = vec![
+            qc_issue(1, "src/other.R", 1, "closed", 0),
+            // Still being QCed
+            qc_issue(2, "src/a.R", 1, "open", 0),
+        ];
+
+        assert!(prior_qc_issues(Path::new("src/a.R"), &issues, Some(2)).is_empty());
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_prior_qc_issues_one_closed() {
+        let issues = vec![
+            qc_issue(1, "src/a.R", 1, "closed", 0),
+            qc_issue(2, "src/b.R", 1, "closed", 0),
+        ];
+
+        let priors = prior_qc_issues(Path::new("src/a.R"), &issues, Some(2));
+        assert_eq!(priors.len(), 1);
+        assert_eq!(priors[0].number, 1);
+
+        let relevant = RelevantFile::detected_previous_qc(priors[0]);
+        assert_eq!(relevant.file_name, PathBuf::from("src/a.R"));
+        assert!(matches!(
+            relevant.class,
+            RelevantFileClass::PreviousQC {
+                issue_number: 1,
+                issue_id: Some(1000),
+                include_diff: true,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_prior_qc_issues_newest_first() {
+        let issues = vec![
+            qc_issue(3, "src/a.R", 1, "closed", 10),
+            qc_issue(7, "src/a.R", 3, "closed", 30),
+            qc_issue(5, "src/a.R", 2, "closed", 20),
+            // In the milestone of the issue being created
+            qc_issue(9, "src/a.R", 4, "closed", 40),
+        ];
+
+        let priors = prior_qc_issues(Path::new("src/a.R"), &issues, Some(4));
+        let numbers = priors.iter().map(|i| i.number).collect::<Vec<_>>();
+        assert_eq!(numbers, [7, 5, 3]);
+    }
 }
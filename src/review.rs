@@ -1,11 +1,11 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use gix::ObjectId;
 use octocrab::models::issues::Issue;
 use serde::{Deserialize, Serialize};
 
 use crate::comment_system::CommentBody;
-use crate::diff_utils;
+use crate::diff_utils::{self, NoDiff};
 use crate::git::{GitFileOps, GitHelpers};
 use crate::{FileStashOutcome, GitRepository};
 
@@ -15,9 +15,14 @@ pub struct QCReview {
     pub issue: Issue,
     pub commit: ObjectId, // Commit to compare against (defaults to HEAD)
     pub note: Option<String>,
-    pub no_diff: bool,
+    /// Omit the diff, keeping an explicit marker and, unless [`NoDiff::Full`], a change summary
+    pub no_diff: Option<NoDiff>,
+    /// Include the diff even when `.gitattributes` suppresses it
+    pub force_diff: bool,
     pub stash_after_review: bool,
     pub working_dir: PathBuf, // Working directory path for reading local files
+    /// Highlighted diff workbook of an Excel file, referenced in the body
+    pub excel_diff: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,12 +63,18 @@ impl CommentBody for QCReview {
 
         body.push(metadata.join("\n* "));
 
-        if !self.no_diff {
-            if let Some(difference) = self.file_diff_to_local(git_info) {
-                body.push(format!("## File Difference\n{}", difference));
-            } else {
-                log::warn!("Could not generate diff for file {:?}", self.file);
+        if let Some(no_diff) = self.no_diff {
+            let omitted =
+                diff_utils::omitted_diff(no_diff, &self.file, || self.file_versions(git_info));
+            body.push(format!("## File Difference\n{}", omitted));
+        } else if let Some(difference) = self.file_diff_to_local(git_info) {
+            let mut section = format!("## File Difference\n{}", difference);
+            if let Some(path) = &self.excel_diff {
+                section.push_str(&format!("\n\n{}", diff_utils::excel_diff_note(path)));
             }
+            body.push(section);
+        } else {
+            log::warn!("Could not generate diff for file {:?}", self.file);
         }
 
         body.join("\n\n")
@@ -79,8 +90,50 @@ impl CommentBody for QCReview {
 }
 
 impl QCReview {
+    /// Write the highlighted diff workbook of an Excel file under `dir` of the working
+    /// directory, to be referenced in the body
+    ///
+    /// Does nothing unless the diff of an Excel file is included.
+    pub fn write_excel_diff(
+        &mut self,
+        git_info: &impl GitFileOps,
+        dir: &Path,
+    ) -> Result<(), diff_utils::ExcelDiffError> {
+        if self.no_diff.is_some() || !diff_utils::is_excel_file(&self.file) {
+            return Ok(());
+        }
+        let Some((commit_bytes, local_bytes)) = self.file_versions(git_info) else {
+            return Ok(());
+        };
+
+        self.excel_diff = Some(diff_utils::write_excel_diff(
+            &self.working_dir,
+            dir,
+            &self.file,
+            &self.commit.to_hex_with_len(7).to_string(),
+            "working",
+            &commit_bytes,
+            &local_bytes,
+        )?);
+        Ok(())
+    }
+
     /// Generate a diff between a commit and the current working directory
     fn file_diff_to_local(&self, git_info: &impl GitFileOps) -> Option<String> {
+        let (commit_bytes, local_bytes) = self.file_versions(git_info)?;
+
+        // Use the shared diff utilities
+        diff_utils::file_diff_with_attributes(
+            commit_bytes,
+            local_bytes,
+            &self.file,
+            git_info,
+            self.force_diff,
+        )
+    }
+
+    /// Bytes of the file at the compared commit and in the working directory
+    fn file_versions(&self, git_info: &impl GitFileOps) -> Option<(Vec<u8>, Vec<u8>)> {
         // Get file bytes from the commit
         let commit_bytes = match git_info.file_bytes_at_commit(&self.file, &self.commit) {
             Ok(bytes) => bytes,
@@ -110,8 +163,7 @@ impl QCReview {
             }
         };
 
-        // Use the shared diff utilities
-        diff_utils::file_diff(commit_bytes, local_bytes, &self.file)
+        Some((commit_bytes, local_bytes))
     }
 }
 
@@ -152,7 +204,10 @@ pub fn stash_review_file(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{GitAuthor, GitCommitOps, GitFileOpsError, git::GitCommit};
+    use crate::{
+        GitAuthor, GitCommitOps, GitFileOpsError, PathAttributes, SignatureStatus, SigningKeys,
+        git::GitCommit,
+    };
     use gix::ObjectId;
     use octocrab::models::issues::Issue;
     use std::collections::HashMap;
@@ -210,12 +265,14 @@ mod tests {
 
     struct MockGitInfo {
         file_contents: HashMap<(PathBuf, String), String>,
+        attributes: PathAttributes,
     }
 
     impl MockGitInfo {
         fn new() -> Self {
             Self {
                 file_contents: HashMap::new(),
+                attributes: PathAttributes::default(),
             }
         }
 
@@ -267,6 +324,13 @@ mod tests {
             Ok(std::collections::HashSet::new())
         }
 
+        fn files_by_last_commit(
+            &self,
+            _branch: &Option<String>,
+        ) -> Result<Vec<crate::FileLastCommit>, GitFileOpsError> {
+            Ok(Vec::new())
+        }
+
         fn get_branches_containing_commit(
             &self,
             _commit: &gix::ObjectId,
@@ -280,6 +344,39 @@ mod tests {
         ) -> Result<Option<String>, GitFileOpsError> {
             Ok(None)
         }
+
+        fn find_merge_commit(
+            &self,
+            _target_commit: &gix::ObjectId,
+        ) -> Result<Option<gix::ObjectId>, GitFileOpsError> {
+            Ok(None)
+        }
+
+        fn signature_status(
+            &self,
+            _commit: &ObjectId,
+            _keys: &SigningKeys,
+        ) -> Result<SignatureStatus, GitFileOpsError> {
+            Ok(SignatureStatus::Unsigned)
+        }
+
+        fn commit_time(
+            &self,
+            _commit: &ObjectId,
+        ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+            Ok(chrono::DateTime::UNIX_EPOCH)
+        }
+
+        fn commit_author(&self, _commit: &ObjectId) -> Result<crate::GitAuthor, GitFileOpsError> {
+            Ok(crate::GitAuthor {
+                name: "Test Author".to_string(),
+                email: "test@example.com".to_string(),
+            })
+        }
+
+        fn is_shallow(&self) -> bool {
+            false
+        }
     }
 
     impl GitFileOps for MockGitInfo {
@@ -304,6 +401,18 @@ mod tests {
         fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
             Ok(Vec::new())
         }
+
+        fn path_attributes(
+            &self,
+            _file: &std::path::Path,
+        ) -> Result<PathAttributes, GitFileOpsError> {
+            Ok(self.attributes)
+        }
+
+        fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+            ObjectId::from_str(commitish)
+                .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+        }
     }
 
     #[test]
@@ -333,9 +442,11 @@ mod tests {
             issue,
             commit,
             note: Some("Testing commit-to-local diff".to_string()),
-            no_diff: true, // Skip diff for this test
+            no_diff: Some(NoDiff::Full), // Skip diff for this test
+            force_diff: false,
             stash_after_review: true,
             working_dir: PathBuf::from("/tmp/test-repo"), // Test working directory
+            excel_diff: None,
         };
 
         let body = review.generate_body(&git_info);
@@ -348,6 +459,105 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_review_diff_suppressed_by_gitattributes() {
+        let working_dir = tempfile::tempdir().unwrap();
+        let commit = ObjectId::from_str("1234567890abcdef1234567890abcdef12345678").unwrap();
+        let file_path = PathBuf::from("output/fit.json");
+
+        std::fs::create_dir(working_dir.path().join("output")).unwrap();
+        std::fs::write(working_dir.path().join(&file_path), "{\"estimate\": 2}\n").unwrap();
+
+        let mut git_info = MockGitInfo::new();
+        git_info.set_file_content(
+            file_path.clone(),
+            commit.to_string(),
+            "{\"estimate\": 1}\n".to_string(),
+        );
+        git_info.attributes = PathAttributes {
+            no_diff: true,
+            generated: false,
+        };
+
+        let json_str =
+            std::fs::read_to_string("src/tests/github_api/issues/test_file_issue.json").unwrap();
+        let issue: Issue = serde_json::from_str(&json_str).unwrap();
+
+        let mut review = QCReview {
+            file: file_path,
+            issue,
+            commit,
+            note: None,
+            no_diff: None,
+            force_diff: false,
+            stash_after_review: false,
+            working_dir: working_dir.path().to_path_buf(),
+            excel_diff: None,
+        };
+
+        let body = review.generate_body(&git_info);
+        assert!(body.contains("## File Difference\n_Diff suppressed by .gitattributes_"));
+        assert!(body.contains("* previous: 16 B, sha256 `"));
+        assert!(!body.contains("```diff"));
+
+        review.force_diff = true;
+        let body = review.generate_body(&git_info);
+        assert!(!body.contains("Diff suppressed"));
+        assert!(body.contains("```diff"));
+        assert!(body.contains("+ 1 {\"estimate\": 2}"));
+    }
+
+    #[test]
+    fn test_review_no_diff_change_summary() {
+        let working_dir = tempfile::tempdir().unwrap();
+        let commit = ObjectId::from_str("1234567890abcdef1234567890abcdef12345678").unwrap();
+        let file_path = PathBuf::from("src/example.rs");
+
+        std::fs::create_dir(working_dir.path().join("src")).unwrap();
+        std::fs::write(
+            working_dir.path().join(&file_path),
+            "fn new_function() {\n    println!(\"new\");\n}\n",
+        )
+        .unwrap();
+
+        let mut git_info = MockGitInfo::new();
+        git_info.set_file_content(
+            file_path.clone(),
+            commit.to_string(),
+            "fn old_function() {\n    println!(\"old\");\n}\n".to_string(),
+        );
+
+        let json_str =
+            std::fs::read_to_string("src/tests/github_api/issues/test_file_issue.json").unwrap();
+        let issue: Issue = serde_json::from_str(&json_str).unwrap();
+
+        let mut review = QCReview {
+            file: file_path,
+            issue,
+            commit,
+            note: None,
+            no_diff: Some(NoDiff::Stats),
+            force_diff: false,
+            stash_after_review: false,
+            working_dir: working_dir.path().to_path_buf(),
+            excel_diff: None,
+        };
+
+        let body = review.generate_body(&git_info);
+        assert!(body.ends_with(
+            "## File Difference\n_Diff omitted (--no-diff)_\n\nchanges: 2 lines added, 2 lines removed"
+        ));
+        assert!(!body.contains("```diff"));
+
+        review.no_diff = Some(NoDiff::Full);
+        let body = review.generate_body(&git_info);
+        assert!(
+            body.ends_with(
+                "## File Difference\n_Diff and change summary omitted (--no-diff=full)_"
+            )
+        );
+    }
+
     #[test]
     fn test_stash_review_file_skipped_when_disabled() {
         let git = MockGitRepo {
@@ -0,0 +1,79 @@
+== {{ issue.title }} <issue-{{ issue.number }}>
+
+=== Issue Information
+
+- *Issue Number:* {{ issue.number }}
+{% if issue.superseded_by %}
+- *Superseded by:* Issue \#{{ issue.superseded_by }}, created later for the same file
+{% endif %}
+- *Milestone:* `{{ issue.milestone }}`
+- *Created by:* {{ issue.created_by }}
+- *Created at:* {{ issue.created_at }}
+- *QCer:* {{ issue.qcer | join(sep=", ") }}
+- *QC Status:* {{ issue.qc_status }}
+{% if issue.approved_at %}
+- *Approved:* {{ issue.approved_at }}
+{% endif %}{% if issue.unapproved %}
+- *Unapproved:* {{ issue.unapproved }}
+{% endif %}
+- *QC Round:* {{ issue.qc_round }}{% if issue.rounds_to_approval %} (approved in round {{ issue.rounds_to_approval }}){% endif %}
+- *{{ checklist_name | title }} Summary:* {{ issue.checklist_summary }}{% if issue.inline_checklist %} (inline checklist: {{ issue.inline_checklist }}){% endif %}
+- *Git Status:* {{ issue.git_status }}
+- *Initial QC Commit:* {{ issue.initial_qc_commit }}
+- *Latest QC Commit:* {{ issue.latest_qc_commit }}
+{% if issue.commit_signatures %}
+- *Commit Signatures:*
+{% for signature in issue.commit_signatures %}
+  - {{ signature.role }}: {{ signature.commit }} ({{ signature.signature_status }})
+{% endfor %}
+{% endif %}
+- *Issue URL:* {{ issue.issue_url }}
+- *Issue state:* {{ issue.state }}
+{% if issue.closed_by %}
+- *Closed by:* {{ issue.closed_by }}
+- *Closed at:* {{ issue.closed_at }}
+{% endif %}{% if issue.relevant_files %}
+
+=== Relevant Files
+
+{% for relevant in issue.relevant_files %}
+- {{ relevant.file }} ({{ relevant.kind }}){% if relevant.justification %}: {{ relevant.justification }}{% endif %}
+{% endfor %}
+{% endif %}
+
+=== Issue Body
+
+{{ issue.body }}
+
+=== Comments
+
+{% if issue.comments %}
+{% for comment in issue.comments %}
+==== {{ comment.0 }}
+
+{{ comment.1 }}
+
+{% endfor %}
+{% else %}
+No comments found.
+{% endif %}
+
+=== Events
+
+{% if issue.events %}
+{% for event in issue.events %}
+- {{ event }}
+{% endfor %}
+{% else %}
+No events found.
+{% endif %}
+
+=== Detailed Timeline
+
+{% if issue.timeline %}
+{% for item in issue.timeline %}
+- {{ item }}
+{% endfor %}
+{% else %}
+No timeline items found.
+{% endif %}
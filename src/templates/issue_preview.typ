@@ -0,0 +1,42 @@
+// Document settings
+#set document(
+  title: "QC Record Preview: {{ issue.title }}",
+  {% if author %}author: "{{ author }}",{% endif %}
+  date: auto,
+)
+
+#set page(
+  paper: "us-letter",
+  margin: (x: 1in, y: 1in),
+  {% if logo_path %}
+  header: align(right)[#image("{{ logo_path }}", height: 0.7in)],
+  header-ascent: 25%,
+  {% endif %}
+  footer: context [
+    #align(center)[Page #counter(page).display() of #counter(page).final().first()]
+  ],
+)
+
+#set text(
+  font: "Times New Roman",
+  size: 12pt,
+)
+
+#set heading(numbering: none)
+
+#align(center)[
+  #text(size: 18pt, weight: "bold")[QC Record Preview]
+
+  #v(0.5em)
+
+  #text(size: 12pt)[Git repository: {{ repository_name }}]
+
+  {% if author %}#text(size: 12pt)[Author: {{ author }}]
+
+  {% endif %}
+  #text(size: 12pt)[Date: {{ date }}]
+]
+
+#v(1em)
+
+{% include "issue_detail.typ" %}
@@ -5,6 +5,11 @@
   date: auto,
 )
 
+{% if format == "html" %}
+{% if logo_path %}
+#image("{{ logo_path }}", height: 0.7in)
+{% endif %}
+{% else %}
 #set page(
   paper: "us-letter",
   margin: (x: 1in, y: 1in),
@@ -16,6 +21,7 @@
     #align(center)[Page #counter(page).display() of #counter(page).final().first()]
   ],
 )
+{% endif %}
 
 #set text(
   font: "Times New Roman",
@@ -25,6 +31,14 @@
 #set heading(numbering: none)
 
 // Title page
+{% if format == "html" %}
+#title[QC Record: {{ milestone_names }}]
+
+- *Git repository:* {{ repository_name }}
+{% if author %}- *Author:* {{ author }}
+{% endif %}- *Date:* {{ date }}
+
+{% else %}
 #align(center)[
   #text(size: 24pt, weight: "bold")[QC Record: {{ milestone_names }}]
 
@@ -41,6 +55,7 @@
 ]
 
 #v(2em)
+{% endif %}
 
 #outline(title: "Table of Contents", depth: 2)
 
@@ -64,8 +79,20 @@
 
 #v(1em)
 #text(fill: red)[U] Unapproved Issue \
+#text(fill: gray)[O] Obsolete Issue \
 #text(fill: red)[C] Issue with unchecked items
 
+{% if coverage %}
+= QC Coverage
+
+QC coverage of {{ coverage.scope }} at the time of this record:
+
+- *Files in Scope:* {{ coverage.files_in_scope }}
+- *Approved QC:* {{ coverage.approved }} ({{ coverage.percentage }}%)
+- *QC in Progress:* {{ coverage.in_progress }}
+- *Without a QC Issue:* {{ coverage.uncovered | length }}{% if coverage.uncovered %} (see Appendix: Files Without QC){% endif %}
+
+{% endif %}
 {% for section in milestone_sections %}
 #pagebreak()
 
@@ -74,16 +101,16 @@
 == Issue Summary
 
 #table(
-  columns: (1.7fr, 1.2fr, 1fr, 1fr, 1.1fr),
+  columns: (1.7fr, 1.2fr, 0.8fr, 1fr, 1fr, 1.1fr{% if section.has_qc_effort %}, 0.8fr{% endif %}),
   stroke: none,
   inset: 8pt,
-  align: (left, left, left, left, left),
+  align: (left, left, left, left, left, left{% if section.has_qc_effort %}, left{% endif %}),
   table.hline(),
   table.header(
-    [*File Path*], [*QC Status*], [*Author*], [*QCer*], [*Issue Closer*],
+    [*File Path*], [*QC Status*], [*QC Rounds*], [*Author*], [*QCer*], [*Issue Closer*],{% if section.has_qc_effort %} [*QC Effort*],{% endif %}
   ),
   table.hline(),
-  {{ render_issue_summary_table_rows(data=section.issues) }}
+  {{ render_issue_summary_table_rows(data=section.issues, qc_effort=section.has_qc_effort) }}
   table.hline(),
 )
 
@@ -91,65 +118,52 @@
 
 {% if not only_tables %}
 {% for issue in section.issues %}
-== {{ issue.title }}
-
-=== Issue Information
-
-- *Issue Number:* {{ issue.number }}
-- *Milestone:* `{{ issue.milestone }}`
-- *Created by:* {{ issue.created_by }}
-- *Created at:* {{ issue.created_at }}
-- *QCer:* {{ issue.qcer | join(sep=", ") }}
-- *QC Status:* {{ issue.qc_status }}
-- *{{ checklist_name | title }} Summary:* {{ issue.checklist_summary }}
-- *Git Status:* {{ issue.git_status }}
-- *Initial QC Commit:* {{ issue.initial_qc_commit }}
-- *Latest QC Commit:* {{ issue.latest_qc_commit }}
-- *Issue URL:* {{ issue.issue_url }}
-- *Issue state:* {{ issue.state }}
-{% if issue.closed_by %}
-- *Closed by:* {{ issue.closed_by }}
-- *Closed at:* {{ issue.closed_at }}
-{% endif %}
-
-=== Issue Body
-
-{{ issue.body }}
-
-=== Comments
-
-{% if issue.comments %}
-{% for comment in issue.comments %}
-==== {{ comment.0 }}
-
-{{ comment.1 }}
-
+{% include "issue_detail.typ" %}
+{% if not loop.last %}#pagebreak(){% endif %}
 {% endfor %}
-{% else %}
-No comments found.
 {% endif %}
+{% endfor %}
+{% if coverage and coverage.uncovered %}
+#pagebreak()
 
-=== Events
+= Appendix: Files Without QC
 
-{% if issue.events %}
-{% for event in issue.events %}
-- {{ event }}
+#table(
+  columns: (1fr),
+  stroke: none,
+  inset: 8pt,
+  align: (left),
+  table.hline(),
+  table.header(
+    [*File Path*],
+  ),
+  table.hline(),
+{% for file in coverage.uncovered %}
+  [{{ file }}],
 {% endfor %}
-{% else %}
-No events found.
+  table.hline(),
+)
 {% endif %}
+{% if appendix %}
+#pagebreak()
 
-=== Detailed Timeline
+= Appendix: Approved File Versions
+{% for section in milestone_sections %}
 
-{% if issue.timeline %}
-{% for item in issue.timeline %}
-- {{ item }}
-{% endfor %}
-{% else %}
-No timeline items found.
-{% endif %}
+== {{ section.name }}
 
-{% if not loop.last %}#pagebreak(){% endif %}
+#table(
+  columns: (1.6fr, 0.8fr, 0.8fr, 1.2fr),
+  stroke: none,
+  inset: 8pt,
+  align: (left, left, left, left),
+  table.hline(),
+  table.header(
+    [*File Path*], [*Approved Commit*], [*File Contents*], [*Approval Date*],
+  ),
+  table.hline(),
+  {{ render_file_versions_table_rows(data=section.issues) }}
+  table.hline(),
+)
 {% endfor %}
 {% endif %}
-{% endfor %}
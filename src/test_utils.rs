@@ -1,8 +1,27 @@
 //! Shared test utilities for creating mock GitHub objects
 
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use gix::ObjectId;
 use octocrab::models::{Milestone, issues::Issue};
 use serde_json::json;
 
+use crate::{
+    CommentBody, CommitStatus, Configuration, FileStashOutcome, GitAuthor, GitComment, GitCommit,
+    GitCommitOps, GitFileOps, GitFileOpsError, GitHelpers, GitHubApiError, GitHubReader,
+    GitHubWriter, GitRepository, GitRepositoryError, GitState, GitStatusError, GitStatusOps,
+    IssueCommit, IssueThread, MilestoneUpdate, PathAttributes, Permission, QCIssue, RepoUser,
+    SignatureStatus, SigningKeys, guard_issue_change,
+};
+
 /// Creates a mock Issue object for testing with configurable parameters
 pub fn create_test_issue(
     owner: &str,
@@ -206,3 +225,683 @@ pub fn create_test_milestone(
 
     serde_json::from_value(milestone_json).expect("Failed to create test milestone")
 }
+
+/// Commits of [`create_renotified_after_approval_thread`], newest first
+pub const RENOTIFIED_THREAD_COMMITS: [&str; 4] = [
+    "dddddddddddddddddddddddddddddddddddddddd",
+    "cccccccccccccccccccccccccccccccccccccccc",
+    "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+];
+
+/// Creates an IssueThread where a notification follows an approval without an unapproval:
+/// `aaaa` is the initial commit, `bbbb` is notified and approved, `cccc` is notified
+/// afterwards and `dddd` is a later file change with no QC activity
+pub fn create_renotified_after_approval_thread() -> IssueThread {
+    let statuses: [&[CommitStatus]; 4] = [
+        &[],
+        &[CommitStatus::Notification],
+        &[CommitStatus::Notification, CommitStatus::Approved],
+        &[CommitStatus::Initial],
+    ];
+
+    IssueThread {
+        file: PathBuf::from("src/analysis.R"),
+        issue_number: 1,
+        issue_url: "https://github.com/owner/repo/issues/1".to_string(),
+        branch: "main".to_string(),
+        branch_deleted: false,
+        open: true,
+        commits: RENOTIFIED_THREAD_COMMITS
+            .iter()
+            .zip(statuses)
+            .map(|(hash, statuses)| IssueCommit {
+                hash: ObjectId::from_str(hash).unwrap(),
+                message: format!("Commit {}", &hash[..4]),
+                statuses: statuses.iter().cloned().collect::<HashSet<_>>(),
+                file_changed: true,
+            })
+            .collect(),
+        milestone: "v1.0".to_string(),
+        blocking_qcs: Vec::new(),
+        relevant_files: Vec::new(),
+        approved_at: None,
+        approval_recorded_at: None,
+        approved_by: None,
+        unapproval: None,
+        notified_at: None,
+        obsoleted: None,
+        inline_checklist: None,
+        rounds: Default::default(),
+        commit_retrieval: Default::default(),
+        renames: Vec::new(),
+    }
+}
+
+/// The default configuration with its checklists loaded
+pub fn test_configuration() -> Configuration {
+    let mut configuration = Configuration::from_path("src/tests/default_configuration");
+    configuration.load_checklists();
+    configuration
+}
+
+/// HEAD of a [`FakeGit`] unless set with [`FakeGit::with_head`]
+pub const FAKE_HEAD_COMMIT: &str = "456def789abc012345678901234567890123cdef";
+
+/// In-memory repository `owner/repo` and its GitHub remote, for testing functions that need
+/// several of the git traits at once.
+///
+/// Reads are served from the fixtures set with the `with_*` methods and every write is recorded.
+/// Writes succeed unless [`FakeGit::with_failing_writes`] is set.
+#[derive(Default)]
+pub struct FakeGit {
+    milestones: Vec<Milestone>,
+    issues: Vec<Issue>,
+    comments: HashMap<u64, Vec<GitComment>>,
+    user_names: HashMap<String, String>,
+    team_members: Vec<String>,
+    permissions: HashMap<String, Permission>,
+    current_user: Option<String>,
+    head: Option<String>,
+    branch: Option<String>,
+    commits: Vec<GitCommit>,
+    file_commits: HashMap<PathBuf, Vec<String>>,
+    files: HashMap<PathBuf, Vec<u8>>,
+    missing_files: HashSet<PathBuf>,
+    unreadable_files: HashSet<PathBuf>,
+    tracked_files: Vec<PathBuf>,
+    containing_branches: Vec<String>,
+    failing_writes: bool,
+    /// Call, issue or milestone number and value of each write
+    writes: Mutex<Vec<(&'static str, u64, String)>>,
+    /// Title and milestone of each posted issue
+    posted_issues: Mutex<Vec<(String, u64)>>,
+    /// Body of each posted comment
+    posted_comments: Mutex<Vec<String>>,
+    comment_fetches: AtomicUsize,
+    user_lookups: Mutex<Vec<String>>,
+}
+
+impl FakeGit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_milestones(mut self, milestones: Vec<Milestone>) -> Self {
+        self.milestones = milestones;
+        self
+    }
+
+    pub fn with_issues(mut self, issues: Vec<Issue>) -> Self {
+        self.issues = issues;
+        self
+    }
+
+    pub fn with_issue(mut self, issue: Issue) -> Self {
+        self.issues.push(issue);
+        self
+    }
+
+    pub fn with_issue_comments(mut self, issue_number: u64, comments: Vec<GitComment>) -> Self {
+        self.comments.insert(issue_number, comments);
+        self
+    }
+
+    /// Display name returned for `login` by `get_user_details`
+    pub fn with_user_name(mut self, login: &str, name: &str) -> Self {
+        self.user_names.insert(login.to_string(), name.to_string());
+        self
+    }
+
+    pub fn with_team_members(mut self, members: &[&str]) -> Self {
+        self.team_members = members.iter().map(|member| member.to_string()).collect();
+        self
+    }
+
+    /// Permissions of the given users, the others having write access
+    pub fn with_permissions(mut self, permissions: &[(&str, Permission)]) -> Self {
+        self.permissions = permissions
+            .iter()
+            .map(|(login, permission)| (login.to_string(), *permission))
+            .collect();
+        self
+    }
+
+    pub fn with_current_user(mut self, login: &str) -> Self {
+        self.current_user = Some(login.to_string());
+        self
+    }
+
+    pub fn with_head(mut self, commit: &str) -> Self {
+        self.head = Some(commit.to_string());
+        self
+    }
+
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = Some(branch.to_string());
+        self
+    }
+
+    /// Commits of the branch, newest first
+    pub fn with_commits(mut self, commits: &[&str]) -> Self {
+        self.commits = commits
+            .iter()
+            .map(|hash| GitCommit {
+                commit: ObjectId::from_str(hash).unwrap(),
+                message: "Update".to_string(),
+            })
+            .collect();
+        self
+    }
+
+    /// Commits touching `file`, which no commit touches otherwise
+    pub fn with_file_commits(mut self, file: &str, commits: &[&str]) -> Self {
+        self.file_commits.insert(
+            PathBuf::from(file),
+            commits.iter().map(|hash| hash.to_string()).collect(),
+        );
+        self
+    }
+
+    /// Content of `file` at every commit, empty for files without content
+    pub fn with_file(mut self, file: &str, content: &str) -> Self {
+        self.files
+            .insert(PathBuf::from(file), content.as_bytes().to_vec());
+        self
+    }
+
+    /// A file not found at any commit
+    pub fn with_missing_file(mut self, file: &str) -> Self {
+        self.missing_files.insert(PathBuf::from(file));
+        self
+    }
+
+    /// A file whose content cannot be read for another reason than being missing
+    pub fn with_unreadable_file(mut self, file: &str) -> Self {
+        self.unreadable_files.insert(PathBuf::from(file));
+        self
+    }
+
+    /// A file listed by `files_by_last_commit`
+    pub fn with_tracked_file(mut self, file: &str) -> Self {
+        self.tracked_files.push(PathBuf::from(file));
+        self
+    }
+
+    pub fn with_containing_branches(mut self, branches: &[&str]) -> Self {
+        self.containing_branches = branches.iter().map(|branch| branch.to_string()).collect();
+        self
+    }
+
+    /// Fail every write with [`GitHubApiError::NoApi`], after recording it
+    pub fn with_failing_writes(mut self) -> Self {
+        self.failing_writes = true;
+        self
+    }
+
+    /// Calls of the recorded writes, in order
+    pub fn writes(&self) -> Vec<&'static str> {
+        self.writes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(write, _, _)| *write)
+            .collect()
+    }
+
+    /// Call, issue or milestone number and value of the recorded writes, in order
+    pub fn write_details(&self) -> Vec<(&'static str, u64, String)> {
+        self.writes.lock().unwrap().clone()
+    }
+
+    /// Title and milestone of the posted issues, in order
+    pub fn posted_issues(&self) -> Vec<(String, u64)> {
+        self.posted_issues.lock().unwrap().clone()
+    }
+
+    /// Bodies of the posted comments, in order
+    pub fn posted_comments(&self) -> Vec<String> {
+        self.posted_comments.lock().unwrap().clone()
+    }
+
+    pub fn comment_fetches(&self) -> usize {
+        self.comment_fetches.load(Ordering::SeqCst)
+    }
+
+    /// Logins looked up with `get_user_details`, sorted
+    pub fn user_lookups(&self) -> Vec<String> {
+        let mut lookups = self.user_lookups.lock().unwrap().clone();
+        lookups.sort();
+        lookups
+    }
+
+    fn record(
+        &self,
+        write: &'static str,
+        number: u64,
+        value: String,
+    ) -> Result<(), GitHubApiError> {
+        self.writes.lock().unwrap().push((write, number, value));
+        if self.failing_writes {
+            Err(GitHubApiError::NoApi)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn milestone(&self, number: u64, title: Option<&str>, state: &str) -> Milestone {
+        let existing = self.milestones.iter().find(|m| m.number as u64 == number);
+        let title = title
+            .or(existing.map(|m| m.title.as_str()))
+            .unwrap_or_default();
+        create_test_milestone("owner", "repo", number as i64, title, None, state)
+    }
+
+    fn head_commit(&self) -> &str {
+        self.head.as_deref().unwrap_or(FAKE_HEAD_COMMIT)
+    }
+}
+
+impl GitHubReader for FakeGit {
+    async fn get_milestones(&self) -> Result<Vec<Milestone>, GitHubApiError> {
+        Ok(self.milestones.clone())
+    }
+
+    async fn get_issues(&self, milestone: Option<u64>) -> Result<Vec<Issue>, GitHubApiError> {
+        Ok(self
+            .issues
+            .iter()
+            .filter(|issue| {
+                milestone.is_none_or(|number| {
+                    issue.milestone.as_ref().map(|m| m.number as u64) == Some(number)
+                })
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn get_issue(&self, issue_number: u64) -> Result<Issue, GitHubApiError> {
+        self.issues
+            .iter()
+            .find(|issue| issue.number == issue_number)
+            .cloned()
+            .ok_or(GitHubApiError::NoApi)
+    }
+
+    async fn get_assignees(&self) -> Result<Vec<String>, GitHubApiError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_user_details(&self, username: &str) -> Result<RepoUser, GitHubApiError> {
+        self.user_lookups.lock().unwrap().push(username.to_string());
+        Ok(RepoUser {
+            login: username.to_string(),
+            name: self.user_names.get(username).cloned(),
+        })
+    }
+
+    async fn get_user_permission(&self, login: &str) -> Result<Permission, GitHubApiError> {
+        Ok(self
+            .permissions
+            .get(login)
+            .copied()
+            .unwrap_or(Permission::Write))
+    }
+
+    async fn get_team_members(
+        &self,
+        _org: &str,
+        _team_slug: &str,
+    ) -> Result<Vec<String>, GitHubApiError> {
+        Ok(self.team_members.clone())
+    }
+
+    async fn get_labels(&self) -> Result<Vec<String>, GitHubApiError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_issue_comments(&self, issue: &Issue) -> Result<Vec<GitComment>, GitHubApiError> {
+        self.comment_fetches.fetch_add(1, Ordering::SeqCst);
+        Ok(self
+            .comments
+            .get(&issue.number)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_issue_events(
+        &self,
+        _issue: &Issue,
+    ) -> Result<Vec<serde_json::Value>, GitHubApiError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_blocked_issues(&self, _issue_number: u64) -> Result<Vec<Issue>, GitHubApiError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_current_user(&self) -> Result<Option<String>, GitHubApiError> {
+        Ok(self.current_user.clone())
+    }
+}
+
+impl GitHubWriter for FakeGit {
+    async fn create_milestone(
+        &self,
+        milestone_name: &str,
+        _description: &Option<String>,
+    ) -> Result<Milestone, GitHubApiError> {
+        self.record("create_milestone", 0, milestone_name.to_string())?;
+        let number = self.milestones.iter().map(|m| m.number).max().unwrap_or(0) + 1;
+        Ok(create_test_milestone(
+            "owner",
+            "repo",
+            number,
+            milestone_name,
+            None,
+            "open",
+        ))
+    }
+
+    async fn update_milestone_title(
+        &self,
+        milestone_number: u64,
+        new_title: &str,
+    ) -> Result<Milestone, GitHubApiError> {
+        self.record(
+            "update_milestone_title",
+            milestone_number,
+            new_title.to_string(),
+        )?;
+        Ok(self.milestone(milestone_number, Some(new_title), "open"))
+    }
+
+    async fn update_milestone(
+        &self,
+        milestone_number: u64,
+        update: &MilestoneUpdate,
+    ) -> Result<Milestone, GitHubApiError> {
+        self.record("update_milestone", milestone_number, String::new())?;
+        Ok(self.milestone(milestone_number, update.title.as_deref(), "open"))
+    }
+
+    async fn close_milestone(&self, milestone_number: u64) -> Result<Milestone, GitHubApiError> {
+        self.record("close_milestone", milestone_number, String::new())?;
+        Ok(self.milestone(milestone_number, None, "closed"))
+    }
+
+    async fn post_issue(&self, issue: &QCIssue) -> Result<Issue, GitHubApiError> {
+        self.record("post_issue", issue.milestone_id, issue.title())?;
+        let mut posted = self.posted_issues.lock().unwrap();
+        posted.push((issue.title(), issue.milestone_id));
+        Ok(create_test_issue(
+            "owner",
+            "repo",
+            100 + posted.len() as u64,
+            &issue.title(),
+            &issue.body(self),
+            Some(issue.milestone_id as i64),
+            "open",
+        ))
+    }
+
+    async fn post_comment<T: CommentBody + Sync + 'static>(
+        &self,
+        comment: &T,
+    ) -> Result<String, GitHubApiError> {
+        let issue_number = comment.issue().number;
+        self.record("post_comment", issue_number, String::new())?;
+        let mut posted = self.posted_comments.lock().unwrap();
+        posted.push(comment.generate_body(self));
+        Ok(format!(
+            "{}#issuecomment-{}",
+            self.issue_url(issue_number),
+            posted.len()
+        ))
+    }
+
+    fn guard_issue(&self, issue: &Issue) -> Result<(), GitHubApiError> {
+        Ok(guard_issue_change(issue, self, false)?)
+    }
+
+    async fn close_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.record("close_issue", issue_number, String::new())
+    }
+
+    async fn open_issue(&self, issue_number: u64) -> Result<(), GitHubApiError> {
+        self.record("open_issue", issue_number, String::new())
+    }
+
+    async fn create_label(&self, name: &str, _color: &str) -> Result<(), GitHubApiError> {
+        self.record("create_label", 0, name.to_string())
+    }
+
+    async fn add_labels(&self, issue_number: u64, labels: &[String]) -> Result<(), GitHubApiError> {
+        self.record("add_labels", issue_number, labels.join(","))
+    }
+
+    async fn remove_label(&self, issue_number: u64, label: &str) -> Result<(), GitHubApiError> {
+        self.record("remove_label", issue_number, label.to_string())
+    }
+
+    async fn set_assignees(
+        &self,
+        issue_number: u64,
+        assignees: &[String],
+    ) -> Result<(), GitHubApiError> {
+        self.record("set_assignees", issue_number, assignees.join(","))
+    }
+
+    async fn block_issue(
+        &self,
+        blocked_issue_number: u64,
+        _blocking_issue_id: u64,
+    ) -> Result<(), GitHubApiError> {
+        self.record("block_issue", blocked_issue_number, String::new())
+    }
+
+    async fn update_issue(
+        &self,
+        issue_number: u64,
+        _new_title: Option<String>,
+        new_body: Option<String>,
+    ) -> Result<(), GitHubApiError> {
+        self.record("update_issue", issue_number, new_body.unwrap_or_default())
+    }
+}
+
+impl GitHelpers for FakeGit {
+    fn file_content_url(&self, git_ref: &str, file: &Path) -> String {
+        format!(
+            "https://github.com/owner/repo/blob/{git_ref}/{}",
+            file.display()
+        )
+    }
+
+    fn commit_comparison_url(
+        &self,
+        current_commit: &ObjectId,
+        previous_commit: &ObjectId,
+    ) -> String {
+        format!("https://github.com/owner/repo/compare/{previous_commit}..{current_commit}")
+    }
+
+    fn issue_url(&self, issue_number: u64) -> String {
+        format!("https://github.com/owner/repo/issues/{issue_number}")
+    }
+}
+
+impl GitFileOps for FakeGit {
+    fn authors(&self, _file: &Path) -> Result<Vec<GitAuthor>, GitFileOpsError> {
+        Ok(Vec::new())
+    }
+
+    fn file_bytes_at_commit(
+        &self,
+        file: &Path,
+        _commit: &ObjectId,
+    ) -> Result<Vec<u8>, GitFileOpsError> {
+        if self.missing_files.contains(file) {
+            return Err(GitFileOpsError::FileNotFoundAtCommit(file.to_path_buf()));
+        }
+        if self.unreadable_files.contains(file) {
+            return Err(GitFileOpsError::EncodingError(file.to_path_buf()));
+        }
+        Ok(self.files.get(file).cloned().unwrap_or_default())
+    }
+
+    fn list_tree_entries(&self, _path: &str) -> Result<Vec<(String, bool)>, GitFileOpsError> {
+        Ok(Vec::new())
+    }
+
+    fn path_attributes(&self, _file: &Path) -> Result<PathAttributes, GitFileOpsError> {
+        Ok(PathAttributes::default())
+    }
+
+    fn resolve_commit(&self, commitish: &str) -> Result<ObjectId, GitFileOpsError> {
+        let commitish = if commitish == "HEAD" {
+            self.head_commit()
+        } else {
+            commitish
+        };
+        ObjectId::from_str(commitish)
+            .map_err(|_| GitFileOpsError::CommitNotFound(commitish.to_string()))
+    }
+}
+
+impl GitCommitOps for FakeGit {
+    fn commits(
+        &self,
+        _branch: &Option<String>,
+        _stop_at: Option<ObjectId>,
+    ) -> Result<Vec<GitCommit>, GitFileOpsError> {
+        Ok(self.commits.clone())
+    }
+
+    fn branch_tip(&self, _branch: &Option<String>) -> Result<ObjectId, GitFileOpsError> {
+        Err(GitFileOpsError::LocalBranchNotFound("mock".to_string()))
+    }
+
+    fn file_touching_commits(
+        &self,
+        _branch: Option<String>,
+        file: &Path,
+    ) -> Result<HashSet<String>, GitFileOpsError> {
+        Ok(self
+            .file_commits
+            .get(file)
+            .map(|commits| commits.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn files_by_last_commit(
+        &self,
+        _branch: &Option<String>,
+    ) -> Result<Vec<crate::FileLastCommit>, GitFileOpsError> {
+        Ok(self
+            .tracked_files
+            .iter()
+            .map(|file| (file.clone(), None))
+            .collect())
+    }
+
+    fn get_branches_containing_commit(
+        &self,
+        _commit: &ObjectId,
+    ) -> Result<Vec<String>, GitFileOpsError> {
+        Ok(self.containing_branches.clone())
+    }
+
+    fn find_merged_into_branch(
+        &self,
+        _target_commit: &ObjectId,
+    ) -> Result<Option<String>, GitFileOpsError> {
+        Ok(None)
+    }
+
+    fn find_merge_commit(
+        &self,
+        _target_commit: &ObjectId,
+    ) -> Result<Option<ObjectId>, GitFileOpsError> {
+        Ok(None)
+    }
+
+    fn signature_status(
+        &self,
+        _commit: &ObjectId,
+        _keys: &SigningKeys,
+    ) -> Result<SignatureStatus, GitFileOpsError> {
+        Ok(SignatureStatus::Unsigned)
+    }
+
+    fn commit_time(
+        &self,
+        _commit: &ObjectId,
+    ) -> Result<chrono::DateTime<chrono::Utc>, GitFileOpsError> {
+        Ok(chrono::DateTime::UNIX_EPOCH)
+    }
+
+    fn commit_author(&self, _commit: &ObjectId) -> Result<GitAuthor, GitFileOpsError> {
+        Ok(GitAuthor {
+            name: "Test Author".to_string(),
+            email: "test@example.com".to_string(),
+        })
+    }
+
+    fn is_shallow(&self) -> bool {
+        false
+    }
+}
+
+impl GitRepository for FakeGit {
+    fn commit(&self) -> Result<String, GitRepositoryError> {
+        Ok(self.head_commit().to_string())
+    }
+
+    fn branch(&self) -> Result<String, GitRepositoryError> {
+        Ok(self.branch.as_deref().unwrap_or("main").to_string())
+    }
+
+    fn owner(&self) -> &str {
+        "owner"
+    }
+
+    fn repo(&self) -> &str {
+        "repo"
+    }
+
+    fn remote_name(&self) -> &str {
+        "origin"
+    }
+
+    fn path(&self) -> &Path {
+        Path::new(".")
+    }
+
+    fn fetch(&self) -> Result<bool, GitRepositoryError> {
+        Ok(false)
+    }
+
+    fn stash_file(
+        &self,
+        _file: &Path,
+        _message: &str,
+    ) -> Result<FileStashOutcome, GitRepositoryError> {
+        Ok(FileStashOutcome::NoChanges)
+    }
+
+    fn configured_author(&self) -> Option<GitAuthor> {
+        None
+    }
+}
+
+impl GitStatusOps for FakeGit {
+    fn state(&self) -> Result<(ObjectId, GitState), GitStatusError> {
+        let head = ObjectId::from_str(self.head_commit()).unwrap();
+        Ok((head, GitState::Clean))
+    }
+
+    fn dirty(&self) -> Result<Vec<PathBuf>, GitStatusError> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,3 @@
+#heading[QC Record]
+{% if %}
+{% endif %}
@@ -0,0 +1,407 @@
+use std::{collections::BTreeMap, fmt, path::PathBuf, sync::LazyLock};
+
+use octocrab::models::issues::Issue;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::comment_system::CommentBody;
+use crate::git::{GitComment, GitFileOps, GitHelpers};
+
+/// Machine-readable marker recording the minutes logged by a time log comment
+pub const QC_TIME_MARKER: &str = "qc time minutes: ";
+
+static DURATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(\d+(?:\.\d+)?)h)?(?:(\d+)m)?$").expect("Invalid duration regex")
+});
+
+#[derive(Debug, Clone)]
+pub struct QCTimeLog {
+    pub file: PathBuf,
+    pub issue: Issue,
+    pub minutes: u32,
+    pub note: Option<String>,
+    /// Login of the QCer logging the time, when known
+    pub author: Option<String>,
+}
+
+impl CommentBody for QCTimeLog {
+    fn generate_body(&self, _git_info: &(impl GitHelpers + GitFileOps)) -> String {
+        let mut logged = format!("QC time logged: {}", format_hours(self.minutes));
+        if let Some(author) = &self.author {
+            logged.push_str(&format!(" by {author}"));
+        }
+
+        let mut body = vec!["# QC Time Log".to_string(), logged];
+
+        if let Some(note) = &self.note {
+            body.push(note.clone());
+        }
+
+        let metadata = [
+            "## Metadata".to_string(),
+            format!("{QC_TIME_MARKER}{}", self.minutes),
+        ];
+        body.push(metadata.join("\n* "));
+
+        body.join("\n\n")
+    }
+
+    fn issue(&self) -> &Issue {
+        &self.issue
+    }
+
+    fn title(&self) -> &str {
+        "QC Time Log"
+    }
+}
+
+/// Parse a duration such as `90m`, `1h30m`, or `1.5h` into minutes
+pub fn parse_duration(input: &str) -> Result<u32, TimeLogError> {
+    let normalized = input.trim().to_lowercase().replace(' ', "");
+    let invalid = || TimeLogError::InvalidDuration(input.to_string());
+
+    let captures = DURATION_REGEX.captures(&normalized).ok_or_else(invalid)?;
+    let hours = captures.get(1).map(|h| h.as_str());
+    let minutes = captures.get(2).map(|m| m.as_str());
+    if hours.is_none() && minutes.is_none() {
+        return Err(invalid());
+    }
+
+    let hour_minutes = match hours {
+        Some(h) => {
+            let (whole, fraction) = h.split_once('.').unwrap_or((h, "0"));
+            let fraction_minutes = (format!("0.{fraction}")
+                .parse::<f64>()
+                .map_err(|_| invalid())?
+                * 60.0)
+                .round() as u32;
+            whole
+                .parse::<u32>()
+                .ok()
+                .and_then(|whole| whole.checked_mul(60))
+                .and_then(|minutes| minutes.checked_add(fraction_minutes))
+                .ok_or_else(invalid)?
+        }
+        None => 0,
+    };
+    let minutes = match minutes {
+        Some(m) => m.parse::<u32>().map_err(|_| invalid())?,
+        None => 0,
+    };
+
+    match hour_minutes.checked_add(minutes).ok_or_else(invalid)? {
+        0 => Err(invalid()),
+        total => Ok(total),
+    }
+}
+
+/// Format minutes as hours, e.g. `90` as `1.5h`
+pub fn format_hours(minutes: u32) -> String {
+    let hours = format!("{:.2}", minutes as f64 / 60.0);
+    format!("{}h", hours.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Minutes recorded by a time log marker in `body`
+///
+/// Markers inside fenced or indented code blocks and block quotes are ignored, so quoting
+/// a time log does not count it twice.
+pub fn parse_logged_minutes(body: &str) -> Option<u32> {
+    let mut in_fence = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+
+        let indent = line.len() - trimmed.len();
+        if in_fence || indent >= 4 || trimmed.starts_with('>') {
+            continue;
+        }
+
+        let item = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+            .unwrap_or(trimmed);
+        if let Some(minutes) = item.strip_prefix(QC_TIME_MARKER) {
+            return minutes.trim().parse().ok();
+        }
+    }
+
+    None
+}
+
+/// Time logged by a single comment
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub qcer: String,
+    pub minutes: u32,
+}
+
+/// Time entries logged in an issue's comments, in comment order
+pub fn time_entries(comments: &[GitComment]) -> Vec<TimeEntry> {
+    comments
+        .iter()
+        .filter_map(|comment| {
+            parse_logged_minutes(&comment.body).map(|minutes| TimeEntry {
+                qcer: comment.author_login.clone(),
+                minutes,
+            })
+        })
+        .collect()
+}
+
+/// Logged QC time, in total and per QCer
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimeSummary {
+    pub total_minutes: u32,
+    pub by_qcer: BTreeMap<String, u32>,
+}
+
+impl TimeSummary {
+    pub fn from_entries(entries: &[TimeEntry]) -> Self {
+        let mut summary = Self::default();
+        for entry in entries {
+            summary.add(&entry.qcer, entry.minutes);
+        }
+        summary
+    }
+
+    fn add(&mut self, qcer: &str, minutes: u32) {
+        // Logged durations come from comments anyone can post, so clamp instead of overflowing
+        self.total_minutes = self.total_minutes.saturating_add(minutes);
+        let qcer_minutes = self.by_qcer.entry(qcer.to_string()).or_default();
+        *qcer_minutes = qcer_minutes.saturating_add(minutes);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_minutes == 0
+    }
+}
+
+impl fmt::Display for TimeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_hours(self.total_minutes))?;
+        for (qcer, minutes) in &self.by_qcer {
+            write!(f, "\n  - {qcer}: {}", format_hours(*minutes))?;
+        }
+        Ok(())
+    }
+}
+
+/// Logged QC time for one issue
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IssueTime {
+    pub file: String,
+    pub number: u64,
+    pub summary: TimeSummary,
+}
+
+/// Human readable time report: each issue with its per-QCer breakdown, then the milestone totals
+pub fn time_report(issues: &[IssueTime]) -> String {
+    let mut total = TimeSummary::default();
+    let mut lines = Vec::new();
+
+    for issue in issues.iter().filter(|i| !i.summary.is_empty()) {
+        lines.push(format!(
+            "{} (#{}): {}",
+            issue.file, issue.number, issue.summary
+        ));
+        for (qcer, minutes) in &issue.summary.by_qcer {
+            total.add(qcer, *minutes);
+        }
+    }
+
+    if lines.is_empty() {
+        return "No QC time logged".to_string();
+    }
+
+    lines.push(format!("Total: {total}"));
+    lines.join("\n")
+}
+
+/// CSV time report with one row per issue and QCer
+pub fn time_report_csv(issues: &[IssueTime]) -> String {
+    let mut rows = vec!["file,issue,qcer,minutes,hours".to_string()];
+    for issue in issues {
+        for (qcer, minutes) in &issue.summary.by_qcer {
+            rows.push(format!(
+                "{},{},{},{},{}",
+                csv_field(&issue.file),
+                issue.number,
+                csv_field(qcer),
+                minutes,
+                format_hours(*minutes).trim_end_matches('h')
+            ));
+        }
+    }
+    rows.join("\n") + "\n"
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TimeLogError {
+    #[error("Invalid duration '{0}'. Use a format like 90m, 1h30m or 1.5h")]
+    InvalidDuration(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(author: &str, body: &str) -> GitComment {
+        GitComment {
+            body: body.to_string(),
+            author_login: author.to_string(),
+            created_at: chrono::Utc::now(),
+            html: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90m").unwrap(), 90);
+        assert_eq!(parse_duration("1h30m").unwrap(), 90);
+        assert_eq!(parse_duration("1.5h").unwrap(), 90);
+        assert_eq!(parse_duration("2h").unwrap(), 120);
+        assert_eq!(parse_duration(" 1H 15M ").unwrap(), 75);
+        assert_eq!(parse_duration("0.25h").unwrap(), 15);
+
+        for invalid in [
+            "",
+            "90",
+            "h",
+            "0m",
+            "1.5m",
+            "30m1h",
+            "abc",
+            "71582789h",
+            "71582788h60m",
+        ] {
+            assert!(
+                matches!(
+                    parse_duration(invalid),
+                    Err(TimeLogError::InvalidDuration(_))
+                ),
+                "{invalid} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_hours() {
+        assert_eq!(format_hours(90), "1.5h");
+        assert_eq!(format_hours(60), "1h");
+        assert_eq!(format_hours(600), "10h");
+        assert_eq!(format_hours(100), "1.67h");
+        assert_eq!(format_hours(0), "0h");
+    }
+
+    #[test]
+    fn test_parse_logged_minutes() {
+        let body =
+            "# QC Time Log\n\nQC time logged: 1.5h by alice\n\n## Metadata\n* qc time minutes: 90";
+        assert_eq!(parse_logged_minutes(body), Some(90));
+        assert_eq!(parse_logged_minutes("qc time minutes: 15"), Some(15));
+        assert_eq!(parse_logged_minutes("# QC Notification"), None);
+    }
+
+    #[test]
+    fn test_parse_logged_minutes_ignores_quotes_and_code() {
+        let quoted = "Thanks!\n\n> ## Metadata\n> * qc time minutes: 90";
+        assert_eq!(parse_logged_minutes(quoted), None);
+
+        let fenced = "Marker format:\n```\n* qc time minutes: 90\n```";
+        assert_eq!(parse_logged_minutes(fenced), None);
+
+        let indented = "Marker format:\n\n    qc time minutes: 90";
+        assert_eq!(parse_logged_minutes(indented), None);
+
+        let after_fence = "```\nqc time minutes: 90\n```\n* qc time minutes: 30";
+        assert_eq!(parse_logged_minutes(after_fence), Some(30));
+    }
+
+    #[test]
+    fn test_time_summary_aggregates_per_qcer() {
+        let comments = vec![
+            comment("alice", "* qc time minutes: 90"),
+            comment("bob", "# QC Notification\ncurrent commit: abc1234"),
+            comment("bob", "* qc time minutes: 60"),
+            comment("alice", "* qc time minutes: 30"),
+            comment("carol", "> * qc time minutes: 600"),
+        ];
+
+        let entries = time_entries(&comments);
+        assert_eq!(entries.len(), 3);
+
+        let summary = TimeSummary::from_entries(&entries);
+        assert_eq!(summary.total_minutes, 180);
+        assert_eq!(summary.by_qcer["alice"], 120);
+        assert_eq!(summary.by_qcer["bob"], 60);
+        assert!(!summary.by_qcer.contains_key("carol"));
+        assert_eq!(summary.to_string(), "3h\n  - alice: 2h\n  - bob: 1h");
+    }
+
+    #[test]
+    fn test_time_summary_saturates() {
+        let entry = TimeEntry {
+            qcer: "alice".to_string(),
+            minutes: u32::MAX,
+        };
+
+        let summary = TimeSummary::from_entries(&[entry.clone(), entry]);
+        assert_eq!(summary.total_minutes, u32::MAX);
+        assert_eq!(summary.by_qcer["alice"], u32::MAX);
+    }
+
+    fn issue_times() -> Vec<IssueTime> {
+        vec![
+            IssueTime {
+                file: "scripts/model.R".to_string(),
+                number: 3,
+                summary: TimeSummary::from_entries(&[
+                    TimeEntry {
+                        qcer: "alice".to_string(),
+                        minutes: 90,
+                    },
+                    TimeEntry {
+                        qcer: "bob".to_string(),
+                        minutes: 45,
+                    },
+                ]),
+            },
+            IssueTime {
+                file: "scripts/plots, final.R".to_string(),
+                number: 4,
+                summary: TimeSummary::from_entries(&[TimeEntry {
+                    qcer: "alice".to_string(),
+                    minutes: 30,
+                }]),
+            },
+            IssueTime {
+                file: "scripts/untracked.R".to_string(),
+                number: 5,
+                summary: TimeSummary::default(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_time_report() {
+        insta::assert_snapshot!(time_report(&issue_times()));
+        assert_eq!(time_report(&[]), "No QC time logged");
+    }
+
+    #[test]
+    fn test_time_report_csv() {
+        insta::assert_snapshot!(time_report_csv(&issue_times()));
+    }
+}
@@ -8,8 +8,9 @@ use axum::{
 };
 use rust_embed::Embed;
 
+/// The built client, or a placeholder page when it was never built (see `build.rs`)
 #[derive(Embed)]
-#[folder = "ui/dist/client/"]
+#[folder = "$GHQC_UI_DIST"]
 struct UiAssets;
 
 /// Serve an embedded static file, falling back to index.html for SPA routing.
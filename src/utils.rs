@@ -1,5 +1,11 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 #[cfg(test)]
 use mockall::automock;
+use regex::Regex;
+
+use crate::codeowners::pattern_regex;
 
 // Trait for environment variable access (mockable in tests)
 #[cfg_attr(test, automock)]
@@ -23,3 +29,376 @@ impl EnvProvider for StdEnvProvider {
         unsafe { std::env::set_var(key, value) }
     }
 }
+
+/// Sanitize a name (e.g. a milestone title) for use in a file name
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            // Replace problematic characters with dashes
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | ' ' => '-',
+            c => c,
+        })
+        .collect::<String>()
+        // Remove consecutive dashes
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Repository-relative path with `/` separators and without `.` components, so that paths
+/// from issue titles, the command line and git trees compare equal
+///
+/// `\\` is read as a separator on every platform, since issues created on Windows may have
+/// backslashes in their titles.
+pub fn normalize_repo_path(path: &Path) -> PathBuf {
+    PathBuf::from(repo_path_string(path))
+}
+
+/// [`normalize_repo_path`] as a string, e.g. for issue titles and archive entry names
+pub fn repo_path_string(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>()
+        .join("/");
+    if path.starts_with('/') {
+        format!("/{segments}")
+    } else {
+        segments
+    }
+}
+
+/// Whether `a` and `b` are the same repository path once normalized
+pub fn same_repo_path(a: &Path, b: &Path) -> bool {
+    repo_path_string(a) == repo_path_string(b)
+}
+
+/// Whether `paths` has `file`, comparing normalized repository paths
+pub fn contains_repo_path(paths: &[PathBuf], file: &Path) -> bool {
+    paths.iter().any(|path| same_repo_path(path, file))
+}
+
+/// Human readable file size, e.g. `512 B`, `1.5 KB`, `1.20 MB` or `2.00 GB`
+pub fn format_file_size(size: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+    if size >= GB {
+        format!("{:.2} GB", size as f64 / GB as f64)
+    } else if size >= MB {
+        format!("{:.2} MB", size as f64 / MB as f64)
+    } else if size >= KB {
+        format!("{:.1} KB", size as f64 / KB as f64)
+    } else {
+        format!("{size} B")
+    }
+}
+
+/// Parse a date given on the command line, either `YYYY-MM-DD` (midnight UTC) or an RFC 3339
+/// timestamp with an offset, e.g. `2026-06-01T00:00:00-04:00`
+pub fn parse_date_time(input: &str) -> Result<DateTime<Utc>, DateParseError> {
+    let input = input.trim();
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(date_time.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .map_err(|_| DateParseError(input.to_string()))
+}
+
+/// Parse a file size given on the command line, in bytes or with a `K`, `M` or `G` suffix
+/// (powers of 1024, with an optional `B`), e.g. `500M` or `2GB`
+pub fn parse_file_size(input: &str) -> Result<u64, FileSizeParseError> {
+    let error = || FileSizeParseError(input.to_string());
+    let upper = input.trim().to_ascii_uppercase();
+    let number = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, multiplier) = match number.char_indices().last() {
+        Some((i, 'K')) => (&number[..i], 1 << 10),
+        Some((i, 'M')) => (&number[..i], 1 << 20),
+        Some((i, 'G')) => (&number[..i], 1 << 30),
+        _ => (number, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|&size| size > 0)
+        .ok_or_else(error)
+}
+
+/// The candidate closest to `target`, if it is close enough to be a typo of it
+pub fn closest_match<'a>(
+    target: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (target.len() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|candidate| (candidate, edit_distance(target, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Levenshtein distance between two strings
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// File at the repository root listing paths left out of file selection
+pub const IGNORE_FILE: &str = ".ghqcignore";
+
+/// Paths excluded from the files offered for new QC issues, read from [`IGNORE_FILE`]
+///
+/// Patterns use the gitignore syntax: `renv/` matches everything in the directory, `*.csv`
+/// matches at any depth, `/outputs` only at the root and `!keep-this.R` includes a path again.
+/// The last matching pattern wins, so a negation can also include a file back from an ignored
+/// directory.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    /// Patterns in file order, `true` for negations
+    rules: Vec<(Regex, bool)>,
+}
+
+impl IgnoreRules {
+    /// Read the rules of the repository at `repo_dir`, none when it has no ignore file
+    pub fn load(repo_dir: &Path) -> Result<Self, IgnoreRulesError> {
+        let path = repo_dir.join(IGNORE_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(IgnoreRulesError(path, e)),
+        }
+    }
+
+    /// Parse ignore file content
+    ///
+    /// Blank lines and `#` comments are skipped, as are patterns with character ranges or
+    /// escapes, which are not supported.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (pattern, negated) = match line.strip_prefix('!') {
+                    Some(pattern) => (pattern, true),
+                    None => (line, false),
+                };
+                let Some(regex) = pattern_regex(pattern) else {
+                    log::warn!("Skipping unsupported {IGNORE_FILE} pattern: {line}");
+                    return None;
+                };
+                Some((regex, negated))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether the repository-relative `path` is ignored. Directories are ignored by patterns
+    /// matching the files within them, e.g. `renv/`.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut path = repo_path_string(path);
+        if is_dir {
+            path.push('/');
+        }
+        self.rules
+            .iter()
+            .rev()
+            .find(|(regex, _)| regex.is_match(&path))
+            .is_some_and(|(_, negated)| !negated)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Could not read {0}: {1}")]
+pub struct IgnoreRulesError(PathBuf, std::io::Error);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error(
+    "Invalid date '{0}': expected YYYY-MM-DD or an RFC 3339 timestamp such as 2026-06-01T00:00:00-04:00"
+)]
+pub struct DateParseError(pub String);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error(
+    "Invalid file size '{0}': expected a positive number of bytes, optionally with a K, M or G suffix such as 500M"
+)]
+pub struct FileSizeParseError(pub String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_repo_path() {
+        assert_eq!(
+            normalize_repo_path(Path::new("./scripts/model.R")),
+            PathBuf::from("scripts/model.R")
+        );
+        assert_eq!(
+            normalize_repo_path(Path::new("scripts\\sub\\model.R")),
+            PathBuf::from("scripts/sub/model.R")
+        );
+        assert_eq!(
+            normalize_repo_path(Path::new("scripts/")),
+            PathBuf::from("scripts")
+        );
+        assert_eq!(
+            normalize_repo_path(Path::new(".\\scripts/sub\\./model.R")),
+            PathBuf::from("scripts/sub/model.R")
+        );
+        assert_eq!(
+            normalize_repo_path(Path::new("/scripts\\model.R")),
+            PathBuf::from("/scripts/model.R")
+        );
+    }
+
+    #[test]
+    fn test_repo_path_string() {
+        assert_eq!(
+            repo_path_string(Path::new("scripts\\run.R")),
+            "scripts/run.R"
+        );
+        assert_eq!(
+            repo_path_string(Path::new("analysis\\pk model/fit\\run 1.R")),
+            "analysis/pk model/fit/run 1.R"
+        );
+        assert_eq!(
+            repo_path_string(Path::new("data\\\\raw//claims.csv")),
+            "data/raw/claims.csv"
+        );
+        assert_eq!(
+            repo_path_string(&Path::new("scripts").join("run.R")),
+            "scripts/run.R"
+        );
+
+        assert!(same_repo_path(
+            Path::new("my scripts\\run.R"),
+            Path::new("./my scripts/run.R")
+        ));
+        assert!(!same_repo_path(
+            Path::new("scripts\\run.R"),
+            Path::new("scripts/run.r")
+        ));
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        assert_eq!(
+            parse_date_time("2026-06-01").unwrap().to_rfc3339(),
+            "2026-06-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date_time("2026-06-01T00:00:00-04:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2026-06-01T04:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date_time(" 2026-06-01T09:30:00Z ")
+                .unwrap()
+                .to_rfc3339(),
+            "2026-06-01T09:30:00+00:00"
+        );
+        assert_eq!(
+            parse_date_time("June 1"),
+            Err(DateParseError("June 1".to_string()))
+        );
+        assert!(parse_date_time("2026-13-01").is_err());
+    }
+
+    #[test]
+    fn test_parse_file_size() {
+        assert_eq!(parse_file_size("1048576"), Ok(1 << 20));
+        assert_eq!(parse_file_size("500M"), Ok(500 << 20));
+        assert_eq!(parse_file_size("2GB"), Ok(2 << 30));
+        assert_eq!(parse_file_size(" 64 kb "), Ok(64 << 10));
+        assert_eq!(parse_file_size("12B"), Ok(12));
+        for invalid in ["", "0", "G", "1.5G", "-1M", "10T"] {
+            assert_eq!(
+                parse_file_size(invalid),
+                Err(FileSizeParseError(invalid.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let rules = IgnoreRules::parse(
+            "# generated\n\
+            renv/\n\
+            .Rproj.user\n\
+            outputs/*.csv\n\
+            !outputs/summary.csv\n\
+            \n\
+            data/**/cache/\n",
+        );
+        let ignored = |path: &str| rules.is_ignored(Path::new(path), false);
+
+        assert!(ignored("renv/activate.R"));
+        assert!(ignored("renv/library/R-4.3/pkg/DESCRIPTION"));
+        assert!(ignored("analysis/.Rproj.user/state"));
+        assert!(ignored("outputs/table1.csv"));
+        assert!(ignored("data/raw/cache/a.rds"));
+        assert!(ignored("data/cache/b.rds"));
+        assert!(!ignored("outputs/summary.csv"));
+        assert!(!ignored("outputs/nested/table1.csv"));
+        assert!(!ignored("scripts/renv.R"));
+
+        assert!(rules.is_ignored(Path::new("renv"), true));
+        assert!(rules.is_ignored(Path::new("data/raw/cache"), true));
+        assert!(!rules.is_ignored(Path::new("outputs"), true));
+    }
+
+    #[test]
+    fn test_ignore_negation_includes_back_from_directory() {
+        let rules = IgnoreRules::parse("renv/\n!renv/activate.R\n[abc].R\n");
+        assert!(!rules.is_ignored(Path::new("renv/activate.R"), false));
+        assert!(rules.is_ignored(Path::new("renv/settings.json"), false));
+        // Character ranges are skipped rather than matching literally
+        assert!(!rules.is_ignored(Path::new("[abc].R"), false));
+    }
+
+    #[test]
+    fn test_ignore_rules_without_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let rules = IgnoreRules::load(dir.path()).unwrap();
+        assert!(rules.is_empty());
+        assert!(!rules.is_ignored(Path::new("renv/activate.R"), false));
+
+        std::fs::write(dir.path().join(IGNORE_FILE), "renv/\n").unwrap();
+        let rules = IgnoreRules::load(dir.path()).unwrap();
+        assert!(rules.is_ignored(Path::new("renv/activate.R"), false));
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let files = ["data/config.yaml", "data/raw.csv", "scripts/model.R"];
+        assert_eq!(
+            closest_match("data/confg.yaml", files),
+            Some("data/config.yaml")
+        );
+        assert_eq!(closest_match("README.md", files), None);
+    }
+}
@@ -0,0 +1,48 @@
+//! Feature permutations the crate must compile under on its own, warning free.
+//!
+//! Each permutation runs a full `cargo check`, so the test is ignored by default and run by CI with
+//! `cargo test --test feature_permutations -- --ignored`.
+
+use std::{path::Path, process::Command};
+
+const PERMUTATIONS: &[&str] = &["", "cli", "api", "ui", "parquet"];
+
+#[test]
+#[ignore = "runs cargo check once per feature permutation"]
+fn test_feature_permutations_compile() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut failures = Vec::new();
+
+    for features in PERMUTATIONS {
+        let output = Command::new(env!("CARGO"))
+            .current_dir(manifest_dir)
+            .args([
+                "check",
+                "--all-targets",
+                "--no-default-features",
+                "--features",
+            ])
+            .arg(features)
+            // Separate target directory so the -D warnings build does not invalidate the test build
+            .env(
+                "CARGO_TARGET_DIR",
+                manifest_dir.join("target/feature-permutations"),
+            )
+            .env("RUSTFLAGS", "-D warnings")
+            .output()
+            .expect("failed to run cargo check");
+
+        if !output.status.success() {
+            failures.push(format!(
+                "--features \"{features}\":\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Feature permutations failed to compile:\n\n{}",
+        failures.join("\n\n")
+    );
+}
@@ -17,6 +17,18 @@ export interface ArchiveGenerateResponse {
   output_path: string
 }
 
+export interface ArchiveDownloadRequest {
+  issues: number[]
+  flatten?: boolean
+  include_unapproved?: boolean
+  additional_files?: ArchiveFileRequest[]
+}
+
+export interface ArchiveDownload {
+  blob: Blob
+  fileName: string
+}
+
 export async function generateArchive(
   request: ArchiveGenerateRequest,
 ): Promise<ArchiveGenerateResponse> {
@@ -31,3 +43,20 @@ export async function generateArchive(
   }
   return res.json()
 }
+
+export async function downloadArchive(
+  request: ArchiveDownloadRequest,
+): Promise<ArchiveDownload> {
+  const res = await fetch(`${API_BASE}/archive`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(request),
+  })
+  if (!res.ok) {
+    const data = await res.json().catch(() => null)
+    throw new Error(data?.error ?? `Failed to download archive: ${res.status}`)
+  }
+  const disposition = res.headers.get('Content-Disposition') ?? ''
+  const fileName = /filename="([^"]+)"/.exec(disposition)?.[1] ?? 'archive.tar.gz'
+  return { blob: await res.blob(), fileName }
+}
@@ -21,6 +21,13 @@ export interface ConfigurationOptions {
   ui_repo_refresh_rate_seconds: number
 }
 
+export interface ConfigurationWarning {
+  file: string
+  line: number | null
+  column: number | null
+  message: string
+}
+
 export interface ConfigurationStatus {
   directory: string
   exists: boolean
@@ -28,6 +35,7 @@ export interface ConfigurationStatus {
   options: ConfigurationOptions
   checklists: Checklist[]
   config_repo_env: string | null
+  warnings: ConfigurationWarning[]
 }
 
 async function fetchConfigurationStatus(): Promise<ConfigurationStatus> {
@@ -32,6 +32,24 @@ export interface CreateIssueRequest {
   relevant_files?: RelevantFileInput[]
 }
 
+interface RelatedIssueUrl {
+  url: string
+  description?: string | null
+  include_diff?: boolean
+}
+
+/** Single issue with a configured checklist, for `POST /api/issues`. */
+export interface NewIssueRequest {
+  milestone: { name: string; create?: boolean; description?: string | null }
+  file: string
+  checklist_name: string
+  assignees?: string[]
+  gating_qc?: RelatedIssueUrl[]
+  previous_qc?: RelatedIssueUrl[]
+  relevant_qc?: RelatedIssueUrl[]
+  relevant_files?: RelevantFileInput[]
+}
+
 
 // ── Response types ────────────────────────────────────────────────────────────
 
@@ -47,6 +65,16 @@ export interface CreateIssueResponse {
   blocking_errors: BlockingQCError[]
 }
 
+export interface NewIssueResponse {
+  issue_number: number
+  issue_url: string
+  /** Body the issue was posted with */
+  body: string
+  milestone_created: boolean
+  blocking_created: number[]
+  blocking_errors: BlockingQCError[]
+}
+
 // ── Conversion ────────────────────────────────────────────────────────────────
 
 function toRelevantIssue(rf: RelevantFileDraft, batchFiles: Set<string>): RelevantIssue | null {
@@ -141,3 +169,13 @@ export async function postCreateIssues(
   if (!res.ok) throw new Error(await parseError(res, 'Failed to create issues'))
   return res.json()
 }
+
+export async function postCreateIssue(request: NewIssueRequest): Promise<NewIssueResponse> {
+  const res = await fetch(`${API_BASE}/issues`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(request),
+  })
+  if (!res.ok) throw new Error(await parseError(res, 'Failed to create issue'))
+  return res.json()
+}
@@ -18,8 +18,9 @@ export interface FileCollaboratorsResponse {
   collaborators: string[]
 }
 
-export async function fetchFileTree(path: string): Promise<FileTreeResponse> {
-  const res = await fetch(`${API_BASE}/files/tree?path=${encodeURIComponent(path)}`)
+export async function fetchFileTree(path: string, noIgnore = false): Promise<FileTreeResponse> {
+  const query = `path=${encodeURIComponent(path)}${noIgnore ? '&no_ignore=true' : ''}`
+  const res = await fetch(`${API_BASE}/files/tree?${query}`)
   if (!res.ok) throw new Error(`Failed to fetch file tree: ${res.status}`)
   return res.json()
 }
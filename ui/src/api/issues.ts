@@ -7,6 +7,8 @@ export interface RelevantFileInfo {
   file_name: string
   kind: RelevantFileKind
   issue_url: string | null
+  commit: string | null
+  file_url: string | null
 }
 
 export interface FileRenameEvent {
@@ -36,7 +38,7 @@ export interface Issue {
 export interface IssueCommit {
   hash: string
   message: string
-  statuses: ('initial' | 'notification' | 'approved' | 'reviewed')[]
+  statuses: ('initial' | 'notification' | 'approved' | 'reviewed' | 'unapproved')[]
   file_changed: boolean
 }
 
@@ -55,6 +57,7 @@ export interface QCStatus {
     | 'in_progress'
     | 'approval_required'
     | 'changes_to_comment'
+    | 'obsoleted'
   status_detail: string
   approved_commit: string | null
   initial_commit: string
@@ -95,10 +98,34 @@ export interface IssueStatusResponse {
   issue: Issue
   qc_status: QCStatus
   dirty: boolean
+  stale_approval?: StaleApproval
   branch: string
   commits: IssueCommit[]
   checklist_summary: ChecklistSummary
   blocking_qc_status?: BlockingQCStatus
+  commit_retrieval?: CommitRetrievalInfo
+}
+
+export interface StaleApproval {
+  dirty: boolean
+  commits_after_approval: number
+}
+
+export interface CommitSource {
+  kind: 'issue_branch' | 'merged_into' | 'containing_branch' | 'default_branch'
+  branch?: string
+}
+
+export interface CommitRetrievalInfo {
+  source: CommitSource
+  attempts: {
+    source: CommitSource
+    failure: {
+      kind: 'branch_not_found' | 'reference_commit_missing' | 'shallow_history' | 'failed'
+      detail?: string
+    }
+  }[]
+  may_be_incomplete: boolean
 }
 
 export interface CreateCommentRequest {
@@ -118,6 +145,8 @@ export interface ReviewRequest {
 export interface ApproveRequest {
   commit: string
   note: string | null
+  effective_date?: string | null
+  allow_incomplete_checklist?: boolean
 }
 
 export interface ApprovalResponse {
@@ -129,6 +158,7 @@ export interface ApprovalResponse {
 
 export interface UnapproveRequest {
   reason: string
+  effective_date?: string | null
 }
 
 export interface UnapprovalResponse {
@@ -178,6 +208,24 @@ export interface BatchIssueStatusResponse {
   errors: IssueStatusError[]
 }
 
+export interface IssueListResponse {
+  /** Issues matching the filters, across all pages */
+  total: number
+  page: number
+  per_page: number
+  results: IssueStatusResponse[]
+  errors: IssueStatusError[]
+}
+
+export interface IssueListParams {
+  page?: number
+  /** At most 100 */
+  per_page?: number
+  state?: 'open' | 'closed' | 'all'
+  milestone?: number
+  sort?: 'updated' | 'title' | 'number'
+}
+
 // Returned by each per-issue query. Backend application-level errors come back
 // as { ok: false } so React Query doesn't treat them as retryable failures.
 export type IssueStatusResult =
@@ -206,6 +254,18 @@ async function fetchIssueStatuses(issueNumbers: number[]): Promise<BatchIssueSta
   throw new Error(`Failed to fetch issue statuses: ${res.status}`)
 }
 
+export async function fetchIssuePage(params: IssueListParams = {}): Promise<IssueListResponse> {
+  const query = new URLSearchParams(
+    Object.entries(params)
+      .filter(([, value]) => value !== undefined)
+      .map(([key, value]) => [key, String(value)]),
+  )
+  const res = await fetch(`${API_BASE}/issues?${query}`)
+  const data = await res.json()
+  if ('results' in data && 'errors' in data) return data as IssueListResponse
+  throw new Error(data?.error ?? `Failed to fetch issues: ${res.status}`)
+}
+
 export async function postReview(issueNumber: number, request: ReviewRequest): Promise<ReviewResponse> {
   const res = await fetch(`${API_BASE}/issues/${issueNumber}/review`, {
     method: 'POST',
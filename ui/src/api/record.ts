@@ -10,8 +10,18 @@ export interface RecordRequest {
   tables_only: boolean
   output_path: string
   context_files: RecordContextFileRequest[]
+  coverage_paths?: string[] | null
+  appendix?: boolean
 }
 
+export type RecordProgress =
+  | { stage: 'fetching_issues' }
+  | { stage: 'fetching_comments'; issue: number; total: number }
+  | { stage: 'downloading_images' }
+  | { stage: 'rendering_pdf' }
+  | { stage: 'complete'; output_path: string }
+  | { stage: 'error'; message: string }
+
 export async function uploadContextFile(file: File): Promise<{ temp_path: string }> {
   const form = new FormData()
   form.append('file', file)
@@ -47,3 +57,30 @@ export async function generateRecord(req: RecordRequest): Promise<void> {
     throw new Error(`Generate failed: ${text}`)
   }
 }
+
+export async function startRecord(req: RecordRequest): Promise<{ job_id: string }> {
+  const res = await fetch(`${API_BASE}/record`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(req),
+  })
+  if (!res.ok) {
+    const text = await res.text()
+    throw new Error(`Generate failed: ${text}`)
+  }
+  return res.json()
+}
+
+/** Follow a background record job, calling `onProgress` for each stage until it completes or fails. */
+export function watchRecordProgress(
+  jobId: string,
+  onProgress: (progress: RecordProgress) => void,
+): EventSource {
+  const source = new EventSource(`${API_BASE}/record/progress/${encodeURIComponent(jobId)}`)
+  source.onmessage = (event) => {
+    const progress: RecordProgress = JSON.parse(event.data)
+    onProgress(progress)
+    if (progress.stage === 'complete' || progress.stage === 'error') source.close()
+  }
+  return source
+}
@@ -17,6 +17,8 @@ export interface RepoInfo {
   git_status_detail: string
   dirty_files: string[]
   current_user: string | null
+  /** Whether the GitHub token is read-only; write actions return 403 `read_only_token`. */
+  read_only: boolean
 }
 
 async function fetchRepoInfo(): Promise<RepoInfo> {
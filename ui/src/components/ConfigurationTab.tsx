@@ -272,6 +272,14 @@ function OptionsSection({ configStatus }: { configStatus: ConfigurationStatus })
 
   return (
     <div style={{ display: 'flex', flexDirection: 'column', gap: 8 }}>
+      {configStatus.warnings.map((warning) => {
+        const location = [warning.file, warning.line, warning.column].filter((p) => p !== null).join(':')
+        return (
+          <Text key={`${location}-${warning.message}`} c="orange" size="sm">
+            ⚠ {location}: {warning.message}
+          </Text>
+        )
+      })}
       {rows.map(({ label, value }) => (
         <div key={label} style={{ display: 'flex', alignItems: 'baseline', gap: 8 }}>
           <Text c="dimmed" size="sm" style={{ minWidth: 160, flexShrink: 0 }}>
@@ -85,6 +85,10 @@ function RelevantFileLine({ file }: { file: RelevantFileInfo }) {
         <Anchor href={file.issue_url} target="_blank" size="xs" style={{ wordBreak: 'break-all' }}>
           {file.file_name}
         </Anchor>
+      ) : file.file_url ? (
+        <Anchor href={file.file_url} target="_blank" size="xs" style={{ wordBreak: 'break-all' }}>
+          {file.file_name} @ {file.commit}
+        </Anchor>
       ) : (
         <Text size="xs" c="dimmed">{file.file_name}</Text>
       )}
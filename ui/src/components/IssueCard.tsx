@@ -23,6 +23,7 @@ export function IssueCard({ status, currentBranch, remoteCommit, postApprovalCom
   switch (qc_status.status) {
     case 'awaiting_review':
     case 'approval_required':
+    case 'obsoleted':
       commitRows = <CommitRow label="Latest" hash={qc_status.latest_commit} />
       break
     case 'change_requested':
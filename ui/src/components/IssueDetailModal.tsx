@@ -36,8 +36,9 @@ const STATUS_DOT_COLORS: Record<string, string> = {
   notification: '#ffd43b', // yellow
   approved:     '#51cf66', // green
   reviewed:     '#ff922b', // orange
+  unapproved:   '#fa5252', // red
 }
-const STATUS_ORDER = ['initial', 'notification', 'approved', 'reviewed'] as const
+const STATUS_ORDER = ['initial', 'notification', 'approved', 'reviewed', 'unapproved'] as const
 
 interface Props {
   status: IssueStatusResponse | null
@@ -78,6 +79,8 @@ function defaultTab(status: IssueStatusResponse): string {
     case 'approved':
     case 'changes_after_approval':
       return 'unapprove'
+    case 'obsoleted':
+      return 'notify'
   }
 }
 
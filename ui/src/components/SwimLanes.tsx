@@ -10,6 +10,7 @@ const LANES: { id: string; title: string; headerColor: string }[] = [
   { id: 'findings-to-address', title: 'Findings to Address', headerColor: '#fee2e2' },
   { id: 'changes-to-notify',   title: 'Changes to Notify',  headerColor: '#fef9c3' },
   { id: 'approved',            title: 'Approved',            headerColor: '#dcfce7' },
+  { id: 'obsolete',            title: 'Obsolete',            headerColor: '#e5e7eb' },
 ]
 
 function getLaneId(status: QCStatus['status']): string {
@@ -25,6 +26,8 @@ function getLaneId(status: QCStatus['status']): string {
     case 'in_progress':
     case 'changes_to_comment':
       return 'changes-to-notify'
+    case 'obsoleted':
+      return 'obsolete'
   }
 }
 
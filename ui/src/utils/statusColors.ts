@@ -9,4 +9,5 @@ export const STATUS_LANE_COLOR: Record<QCStatus['status'], string> = {
   change_requested:       '#fee2e2',
   in_progress:            '#fef9c3',
   changes_to_comment:     '#fef9c3',
+  obsoleted:              '#e5e7eb',
 }
@@ -29,6 +29,7 @@ const notConfigured = {
   options: defaultOptions,
   checklists: [],
   config_repo_env: null,
+  warnings: [],
 }
 
 const configured = {
@@ -38,6 +39,7 @@ const configured = {
   options: defaultOptions,
   checklists: twoChecklists,
   config_repo_env: null,
+  warnings: [],
 }
 
 const localDirectoryConfig = {
@@ -47,6 +49,7 @@ const localDirectoryConfig = {
   options: defaultOptions,
   checklists: twoChecklists,
   config_repo_env: null,
+  warnings: [],
 }
 
 // ---------------------------------------------------------------------------
@@ -23,6 +23,7 @@ export const defaultRepoInfo: RepoInfo = {
   git_status_detail: 'Up to date',
   dirty_files: [],
   current_user: 'test-user',
+  read_only: false,
 }
 
 export const openMilestone: Milestone = {
@@ -202,6 +202,7 @@ export async function setupRoutes(page: Page, overrides: Partial<RouteOverrides>
       },
       checklists: cfg.checklists,
       config_repo_env: null,
+      warnings: [],
     }
     route.fulfill({
       status: 200,